@@ -0,0 +1,81 @@
+use ledger::proofs::verifiers::{BlockVerifier, TransactionVerifier};
+use mina_core::{constants::PROTOCOL_VERSION, NetworkConfig};
+use mina_p2p_messages::v2;
+use node::BuildEnv;
+use serde_json::json;
+
+/// Print the constants compiled into this build for the selected network,
+/// in JSON.
+///
+/// Includes the constraint constants, the digests of the circuits and of the
+/// verifier indices, and the protocol versions of the blocks the node
+/// accepts. Builds or networks which differ in any of them can't verify
+/// each other's proofs.
+///
+/// # Examples
+///
+/// ```bash
+/// mina advanced compile-time-constants --network mainnet
+/// ```
+#[derive(Debug, clap::Args)]
+pub struct CompileTimeConstants;
+
+impl CompileTimeConstants {
+    pub fn run(self) -> anyhow::Result<()> {
+        let build_env = BuildEnv::get();
+        let network = NetworkConfig::global();
+        let constants = network.constraint_constants;
+        let [transaction_merge, transaction_base, blockchain_step] =
+            network.constraint_system_digests;
+
+        let protocol_version = format!(
+            "{}.{}.{}",
+            PROTOCOL_VERSION.transaction.as_u64(),
+            PROTOCOL_VERSION.network.as_u64(),
+            PROTOCOL_VERSION.patch.as_u64()
+        );
+        let supported_protocol_versions = format!(
+            "{}.{}.*",
+            PROTOCOL_VERSION.transaction.as_u64(),
+            PROTOCOL_VERSION.network.as_u64()
+        );
+
+        let output = json!({
+            "network": network.name,
+            "version": build_env.version,
+            "commit": build_env.git.commit_hash,
+            "protocol_version": protocol_version,
+            "supported_protocol_versions": supported_protocol_versions,
+            "constraint_constants": {
+                "sub_windows_per_window": constants.sub_windows_per_window,
+                "ledger_depth": constants.ledger_depth,
+                "work_delay": constants.work_delay,
+                "block_window_duration_ms": constants.block_window_duration_ms,
+                "transaction_capacity_log_2": constants.transaction_capacity_log_2,
+                "pending_coinbase_depth": constants.pending_coinbase_depth,
+                "coinbase_amount": constants.coinbase_amount,
+                "supercharged_coinbase_factor": constants.supercharged_coinbase_factor,
+                "account_creation_fee": constants.account_creation_fee,
+                "minimum_user_command_fee": constants.minimum_user_command_fee,
+                "fork": constants.fork.as_ref().map(|fork| json!({
+                    "state_hash": v2::StateHash::from_fp(fork.state_hash).to_string(),
+                    "blockchain_length": fork.blockchain_length,
+                    "global_slot_since_genesis": fork.global_slot_since_genesis,
+                })),
+            },
+            "constraint_constants_digest": hex::encode(constants.digest()),
+            "constraint_system_digests": {
+                "transaction_merge": hex::encode(transaction_merge),
+                "transaction_base": hex::encode(transaction_base),
+                "blockchain_step": hex::encode(blockchain_step),
+            },
+            "verifier_index_digests": {
+                "block_verifier_index": hex::encode(BlockVerifier::source_digest()),
+                "transaction_verifier_index": hex::encode(TransactionVerifier::source_digest()),
+            },
+        });
+
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        Ok(())
+    }
+}
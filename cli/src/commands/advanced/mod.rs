@@ -0,0 +1,26 @@
+pub mod compile_time_constants;
+pub mod rotate_p2p_key;
+
+#[derive(Debug, clap::Args)]
+pub struct Advanced {
+    #[command(subcommand)]
+    pub command: AdvancedCommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum AdvancedCommand {
+    /// Replace the node's libp2p key with a new one.
+    RotateP2pKey(rotate_p2p_key::RotateP2pKey),
+    /// Print the constants, circuit digests and protocol versions compiled
+    /// into this build, in JSON.
+    CompileTimeConstants(compile_time_constants::CompileTimeConstants),
+}
+
+impl Advanced {
+    pub fn run(self) -> anyhow::Result<()> {
+        match self.command {
+            AdvancedCommand::RotateP2pKey(v) => v.run(),
+            AdvancedCommand::CompileTimeConstants(v) => v.run(),
+        }
+    }
+}
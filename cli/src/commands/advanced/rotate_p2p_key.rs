@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use node::p2p::identity::SecretKey;
+
+/// Generate a new libp2p key and replace the one in the node's keypair file.
+///
+/// The previous key file is kept next to the new one, suffixed with its peer
+/// id. The new key is used once the node is restarted. Peers keep
+/// advertising the old peer id for a while, pass the old key file to the
+/// node with `--libp2p-previous-keypair` during that period so that the old
+/// peer id is recognized as the node itself and webrtc dials to it are
+/// still answered.
+///
+/// # Examples
+///
+/// ```bash
+/// MINA_LIBP2P_PASS=mypassword mina advanced rotate-p2p-key --libp2p-keypair ~/.mina/keys/libp2p-key
+/// ```
+#[derive(Debug, clap::Args)]
+pub struct RotateP2pKey {
+    /// libp2p keypair file used by the node, in the OCaml node format.
+    #[arg(long)]
+    pub libp2p_keypair: PathBuf,
+
+    /// Password of the keypair file, the new key is encrypted with it too.
+    #[arg(env = "MINA_LIBP2P_PASS")]
+    pub libp2p_password: String,
+
+    /// Secret key to rotate to. If not provided, a new random key is
+    /// generated.
+    #[arg(long)]
+    pub new_secret_key: Option<SecretKey>,
+}
+
+impl RotateP2pKey {
+    pub fn run(self) -> anyhow::Result<()> {
+        let path = &self.libp2p_keypair;
+        let old_key = SecretKey::from_encrypted_file(path, &self.libp2p_password)
+            .with_context(|| format!("reading keypair file {path:?}"))?;
+        let new_key = self.new_secret_key.unwrap_or_else(SecretKey::rand);
+        if new_key.to_bytes() == old_key.to_bytes() {
+            anyhow::bail!("new key is the same as the current one");
+        }
+
+        let old_peer_id = old_key.public_key().peer_id().to_libp2p_string();
+        let new_peer_id = new_key.public_key().peer_id().to_libp2p_string();
+        let backup_path = with_suffix(path, &old_peer_id);
+        if backup_path.exists() {
+            anyhow::bail!("backup file {backup_path:?} already exists");
+        }
+
+        // Write the new key next to the current one first, so that a failure
+        // leaves the current key file untouched.
+        let new_path = with_suffix(path, "new");
+        new_key
+            .to_encrypted_file(&self.libp2p_password, &new_path)
+            .with_context(|| format!("writing new keypair file {new_path:?}"))?;
+        std::fs::rename(path, &backup_path)
+            .with_context(|| format!("moving current keypair file to {backup_path:?}"))?;
+        std::fs::rename(&new_path, path)
+            .with_context(|| format!("moving new keypair file to {path:?}"))?;
+
+        println!("old peer_id:  {old_peer_id}");
+        println!("new peer_id:  {new_peer_id}");
+        println!("old key file: {}", backup_path.display());
+        println!("new key file: {}", path.display());
+        println!();
+        println!("Restart the node to use the new key. Until peers stop advertising");
+        println!("the old identity, also pass:");
+        println!("  --libp2p-previous-keypair {}", backup_path.display());
+
+        Ok(())
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(suffix);
+    path.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rotate_p2p_key_keeps_previous_key() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("libp2p-key");
+        let password = "test_password";
+
+        let old_key = SecretKey::rand();
+        old_key.to_encrypted_file(password, &path).unwrap();
+        let new_key = SecretKey::rand();
+
+        let cmd = RotateP2pKey {
+            libp2p_keypair: path.clone(),
+            libp2p_password: password.to_string(),
+            new_secret_key: Some(new_key.clone()),
+        };
+        cmd.run().unwrap();
+
+        let loaded = SecretKey::from_encrypted_file(&path, password).unwrap();
+        assert_eq!(loaded.to_bytes(), new_key.to_bytes());
+
+        let old_peer_id = old_key.public_key().peer_id().to_libp2p_string();
+        let backup = SecretKey::from_encrypted_file(with_suffix(&path, &old_peer_id), password);
+        assert_eq!(backup.unwrap().to_bytes(), old_key.to_bytes());
+        assert!(!with_suffix(&path, "new").exists());
+    }
+}
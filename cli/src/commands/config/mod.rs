@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use clap::{Args, FromArgMatches};
+
+use super::node::{ConfigFileFormat, Node, NodeConfigFile};
+
+#[derive(Debug, clap::Args)]
+pub struct Config {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+#[allow(clippy::large_enum_variant)]
+pub enum ConfigCommand {
+    /// Check that a node config file (`mina.toml` or `mina.yaml`) is valid.
+    Validate(Validate),
+    /// Print the node configuration resulting from the config file, the
+    /// environment variables and the flags, in the config file format.
+    PrintEffective(PrintEffective),
+}
+
+impl Config {
+    pub fn load_node_config(&mut self, matches: &clap::ArgMatches) -> anyhow::Result<()> {
+        match &mut self.command {
+            ConfigCommand::Validate(_) => Ok(()),
+            ConfigCommand::PrintEffective(v) => match matches.subcommand_matches("print-effective")
+            {
+                Some(matches) => v.node.load_node_config(matches),
+                None => Ok(()),
+            },
+        }
+    }
+
+    pub fn run(self) -> anyhow::Result<()> {
+        match self.command {
+            ConfigCommand::Validate(v) => v.run(),
+            ConfigCommand::PrintEffective(v) => v.run(),
+        }
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Validate {
+    /// Node config file to check.
+    ///
+    /// Options set with environment variables are taken into account, the
+    /// same way as when the node is started with this file.
+    pub file: PathBuf,
+}
+
+impl Validate {
+    pub fn run(self) -> anyhow::Result<()> {
+        let matches =
+            Node::augment_args(clap::Command::new("node")).try_get_matches_from(["node"])?;
+        let mut node = Node::from_arg_matches(&matches)?;
+        NodeConfigFile::load(&self.file)?.apply(&mut node, &matches)?;
+
+        println!("{} is valid", self.file.display());
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct PrintEffective {
+    /// Format to print the configuration in.
+    #[arg(long, value_enum, default_value = "toml")]
+    pub format: ConfigFileFormat,
+
+    #[command(flatten)]
+    pub node: Node,
+}
+
+impl PrintEffective {
+    pub fn run(self) -> anyhow::Result<()> {
+        let effective = NodeConfigFile::effective(&self.node);
+        print!("{}", effective.to_string(self.format)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use ledger::ondisk::Database;
+
+#[derive(Debug, clap::Args)]
+/// Check the integrity of an ondisk key-value database, without loading it.
+///
+/// The database must not be in use by a running node.
+pub struct FsckOndisk {
+    /// Directory of the database.
+    pub dir: PathBuf,
+
+    /// Truncate a partially written entry left at the end of the database
+    /// by a crash. This is also done when the database is loaded.
+    #[arg(long)]
+    pub repair: bool,
+}
+
+impl FsckOndisk {
+    pub fn run(self) -> anyhow::Result<()> {
+        let report = Database::fsck(&self.dir, self.repair)
+            .with_context(|| format!("checking database {:?}", self.dir))?;
+
+        println!("File length:   {}", report.file_length);
+        println!("Valid length:  {}", report.valid_length);
+        println!("Entries:       {}", report.entries);
+        println!("Live keys:     {}", report.live_keys);
+        println!("Stale entries: {}", report.stale_entries);
+
+        if let Some(offset) = report.corrupted_at {
+            anyhow::bail!("corrupted entry at offset {offset}, the database can't be repaired");
+        }
+
+        match report.torn_tail_length() {
+            0 => println!("No errors found"),
+            length if self.repair => println!("Truncated torn tail of {length} bytes"),
+            length => println!("Torn tail of {length} bytes, run with --repair to truncate it"),
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use anyhow::{anyhow, Result};
-use serde::{Deserialize, Serialize};
+use mina_graphql_client::{blocking::GraphQLClient, ClientConfig};
 use std::io::{self, Read};
 
 #[derive(Debug, clap::Args)]
@@ -20,24 +20,6 @@ pub struct Run {
     pub node: String,
 }
 
-#[derive(Debug, Serialize)]
-struct GraphQLRequest {
-    query: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    variables: Option<serde_json::Value>,
-}
-
-#[derive(Debug, Deserialize)]
-struct GraphQLResponse {
-    data: Option<serde_json::Value>,
-    errors: Option<Vec<GraphQLError>>,
-}
-
-#[derive(Debug, Deserialize)]
-struct GraphQLError {
-    message: String,
-}
-
 impl Run {
     pub fn run(self) -> Result<()> {
         // Get the query from various sources
@@ -64,38 +46,20 @@ impl Run {
             None
         };
 
-        // Build GraphQL request
-        let request = GraphQLRequest { query, variables };
-
         // Execute the query
-        let client = reqwest::blocking::Client::new();
-        let response = client
-            .post(&self.node)
-            .json(&request)
-            .send()
-            .map_err(|e| anyhow!("Failed to connect to GraphQL server: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!(
-                "GraphQL server returned error: {}",
-                response.status()
-            ));
-        }
-
-        let graphql_response: GraphQLResponse = response
-            .json()
-            .map_err(|e| anyhow!("Failed to parse GraphQL response: {}", e))?;
+        let client = GraphQLClient::new(ClientConfig::new(&self.node))?;
+        let graphql_response = client
+            .execute_raw(&query, variables)
+            .map_err(|e| anyhow!("GraphQL request failed: {}", e))?;
 
         // Display the response
-        if let Some(errors) = &graphql_response.errors {
-            if !errors.is_empty() {
-                eprintln!("Errors:");
-                for error in errors {
-                    eprintln!("  - {}", error.message);
-                }
-                if graphql_response.data.is_none() {
-                    return Err(anyhow!("GraphQL query failed with errors"));
-                }
+        if !graphql_response.errors.is_empty() {
+            eprintln!("Errors:");
+            for error in &graphql_response.errors {
+                eprintln!("  - {}", error.message);
+            }
+            if graphql_response.data.is_none() {
+                return Err(anyhow!("GraphQL query failed with errors"));
             }
         }
 
@@ -0,0 +1,269 @@
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context;
+use mina_node_native::{
+    archive::config::ArchiveStorageOptions, rpc::RpcSender, tracing, NodeBuilder,
+};
+use mina_p2p_messages::v2;
+use node::{
+    rpc::{
+        RpcBlockInjectError, RpcBlockInjectResponse, RpcNodeStatusTransitionFrontierBlockSummary,
+        RpcRequest, RpcStatusGetResponse,
+    },
+    transition_frontier::genesis::GenesisConfig,
+};
+
+#[derive(Debug, clap::Args)]
+/// Replay a directory of precomputed blocks into a fresh node, without
+/// networking, to reconstruct a historical chain.
+pub struct ImportBlocks {
+    /// Directory with the precomputed blocks, in JSON format.
+    ///
+    /// Files are imported in the order of their block height.
+    pub dir: PathBuf,
+
+    /// Config JSON file of the chain the blocks belong to.
+    #[arg(short = 'c', long, env)]
+    pub config: Option<PathBuf>,
+
+    #[arg(long, short = 'd', default_value = "~/.mina", env = "MINA_HOME")]
+    pub work_dir: String,
+
+    /// Send the imported blocks to the archiver process, to rebuild the
+    /// archive database.
+    ///
+    /// This requires the following environment variables to be set:
+    /// - MINA_ARCHIVE_ADDRESS
+    #[arg(long, env)]
+    pub archive_archiver_process: bool,
+
+    /// Max time to wait for a block to be applied, in seconds.
+    #[arg(long, default_value_t = 600)]
+    pub block_timeout: u64,
+
+    /// Verbosity level
+    #[arg(long, short, default_value = "info")]
+    pub verbosity: tracing::Level,
+}
+
+impl ImportBlocks {
+    pub fn run(self) -> anyhow::Result<()> {
+        tracing::initialize(self.verbosity);
+
+        let work_dir = shellexpand::full(&self.work_dir)?.into_owned();
+        let files = precomputed_block_files(&self.dir)?;
+        if files.is_empty() {
+            anyhow::bail!("no precomputed blocks found in {:?}", self.dir);
+        }
+
+        let (daemon_conf, genesis_conf) = match &self.config {
+            Some(config) => {
+                let reader =
+                    File::open(config).with_context(|| format!("config file {config:?}"))?;
+                let config: node::daemon_json::DaemonJson = serde_json::from_reader(reader)
+                    .with_context(|| format!("config file {config:?}"))?;
+                (
+                    config
+                        .daemon
+                        .clone()
+                        .unwrap_or(node::daemon_json::Daemon::DEFAULT),
+                    Arc::new(GenesisConfig::DaemonJson(Box::new(config))),
+                )
+            }
+            None => (
+                node::daemon_json::Daemon::DEFAULT,
+                node::config::DEVNET_CONFIG.clone(),
+            ),
+        };
+
+        let mut node_builder = NodeBuilder::new(None, daemon_conf, genesis_conf);
+        node_builder
+            .p2p_seed_node()
+            .p2p_no_discovery()
+            .p2p_max_peers(0)
+            .allow_historical_blocks();
+
+        if self.archive_archiver_process {
+            let options = ArchiveStorageOptions::ARCHIVER_PROCESS;
+            options
+                .validate_env_vars()
+                .map_err(|e| anyhow::anyhow!(e))?;
+            node_builder.archive(options, work_dir.clone());
+        }
+
+        mina_core::set_work_dir(work_dir.into());
+
+        let mut node = node_builder.build().context("node build failed!")?;
+        let rpc = node.rpc();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .thread_stack_size(64 * 1024 * 1024)
+            .build()
+            .unwrap();
+
+        let timeout = Duration::from_secs(self.block_timeout);
+        runtime.block_on(async {
+            tokio::select! {
+                _ = node.run_forever() => unreachable!(),
+                res = import(&rpc, &files, timeout) => res,
+            }
+        })
+    }
+}
+
+async fn import(
+    rpc: &RpcSender,
+    files: &[(u32, PathBuf)],
+    timeout: Duration,
+) -> anyhow::Result<()> {
+    // Wait for the genesis block, blocks can't be validated before it.
+    wait_for_best_tip(rpc, 0, timeout).await?;
+
+    for (height, path) in files {
+        let block = read_precomputed_block(path)?;
+        let res: Option<RpcBlockInjectResponse> = rpc
+            .oneshot_request(RpcRequest::BlockInject(Arc::new(block)))
+            .await;
+        let hash = match res.context("response channel dropped")? {
+            Ok(hash) => hash,
+            Err(RpcBlockInjectError::AlreadyKnown) => continue,
+            Err(err) => anyhow::bail!("block {path:?} rejected: {err}"),
+        };
+
+        // Blocks at the same height may be forks, so it is enough that the
+        // best tip reaches the height, even if it is through another block.
+        let best_tip = wait_for_best_tip(rpc, *height, timeout)
+            .await
+            .with_context(|| {
+                format!("block {hash} at height {height} wasn't applied, is its parent missing?")
+            })?;
+        if best_tip.hash == hash {
+            node::core::info!(
+                node::core::log::system_time();
+                summary = "block imported",
+                height = height,
+                hash = hash.to_string(),
+            );
+        } else {
+            node::core::warn!(
+                node::core::log::system_time();
+                summary = "block imported, but not on the best chain",
+                height = height,
+                hash = hash.to_string(),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn wait_for_best_tip(
+    rpc: &RpcSender,
+    height: u32,
+    timeout: Duration,
+) -> anyhow::Result<RpcNodeStatusTransitionFrontierBlockSummary> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let status: Option<RpcStatusGetResponse> = rpc.oneshot_request(RpcRequest::StatusGet).await;
+        let best_tip = status
+            .flatten()
+            .and_then(|status| status.transition_frontier.best_tip)
+            .filter(|best_tip| best_tip.height >= height);
+        if let Some(best_tip) = best_tip {
+            return Ok(best_tip);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            anyhow::bail!("timed out waiting for the best tip to reach height {height}");
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+/// Precomputed block files in `dir`, sorted by block height.
+///
+/// The height is taken from the file name when it follows the
+/// `<network>-<height>-<state_hash>.json` naming of the block dumps,
+/// otherwise the file is parsed. The `<...>-vrf.json` files dumped next
+/// to the blocks aren't blocks, and are skipped.
+fn precomputed_block_files(dir: &Path) -> anyhow::Result<Vec<(u32, PathBuf)>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("reading {dir:?}"))? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let stem = path.file_stem().and_then(|stem| stem.to_str());
+        if stem.is_some_and(|stem| stem.ends_with("-vrf")) {
+            continue;
+        }
+        let height = stem
+            .and_then(|stem| stem.rsplit('-').nth(1))
+            .and_then(|height| height.parse().ok());
+        let height = match height {
+            Some(height) => height,
+            None => read_precomputed_block(&path)?
+                .header
+                .protocol_state
+                .body
+                .consensus_state
+                .blockchain_length
+                .as_u32(),
+        };
+        files.push((height, path));
+    }
+    files.sort();
+    Ok(files)
+}
+
+pub fn read_precomputed_block(path: &Path) -> anyhow::Result<v2::MinaBlockBlockStableV2> {
+    let contents = std::fs::read(path).with_context(|| format!("reading {path:?}"))?;
+    // Blocks dumped by the OCaml node are wrapped with their version.
+    let block = serde_json::from_slice::<v2::PrecomputedBlockData>(&contents)
+        .map(|block| block.data)
+        .or_else(|_| serde_json::from_slice::<v2::PrecomputedBlock>(&contents))
+        .with_context(|| format!("parsing precomputed block {path:?}"))?;
+    Ok(block.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const STATE_HASH: &str = "3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ";
+
+    #[test]
+    fn test_precomputed_block_files_skips_vrf_outputs() {
+        let dir = TempDir::new().unwrap();
+        // Heights come from the names, so the blocks' contents aren't read,
+        // while reading any other file would fail.
+        for name in [
+            format!("mainnet-3-{STATE_HASH}.json"),
+            format!("mainnet-2-{STATE_HASH}.json"),
+            format!("mainnet-2-{STATE_HASH}-vrf.json"),
+            format!("mainnet-3-{STATE_HASH}-vrf.json"),
+            "README.md".to_owned(),
+        ] {
+            std::fs::write(dir.path().join(name), "not a block").unwrap();
+        }
+
+        let files = precomputed_block_files(dir.path()).unwrap();
+        let files = files
+            .iter()
+            .map(|(height, path)| (*height, path.file_name().unwrap().to_str().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            files,
+            [
+                (2, format!("mainnet-2-{STATE_HASH}.json").as_str()),
+                (3, format!("mainnet-3-{STATE_HASH}.json").as_str()),
+            ]
+        );
+    }
+}
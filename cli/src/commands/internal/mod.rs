@@ -1,4 +1,6 @@
+pub mod fsck_ondisk;
 pub mod graphql;
+pub mod import_blocks;
 
 #[derive(Debug, clap::Args)]
 pub struct Internal {
@@ -8,14 +10,20 @@ pub struct Internal {
 
 #[derive(Debug, clap::Subcommand)]
 pub enum InternalCommand {
+    /// Check the integrity of an ondisk key-value database.
+    FsckOndisk(fsck_ondisk::FsckOndisk),
     /// GraphQL endpoint introspection and management.
     Graphql(graphql::Graphql),
+    /// Import precomputed blocks into a fresh node, without networking.
+    ImportBlocks(import_blocks::ImportBlocks),
 }
 
 impl Internal {
     pub fn run(self) -> anyhow::Result<()> {
         match self.command {
+            InternalCommand::FsckOndisk(v) => v.run(),
             InternalCommand::Graphql(v) => v.run(),
+            InternalCommand::ImportBlocks(v) => v.run(),
         }
     }
 }
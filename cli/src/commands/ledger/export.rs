@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use ledger::BaseLedger;
+use node::ledger::export::{read_snapshot, write_snapshot};
+
+use super::verify::{build_ledger, read_ledger_file, LedgerFileFormat};
+
+/// Write the accounts of a ledger file in the genesis ledger format of
+/// `daemon.json`, to start a fork from this ledger or to audit it.
+#[derive(Debug, clap::Args)]
+pub struct Export {
+    /// Ledger file.
+    pub file: PathBuf,
+
+    /// Format of the ledger file.
+    #[arg(long, value_enum, default_value_t = LedgerFileFormat::Snapshot)]
+    pub format: LedgerFileFormat,
+
+    /// Where to write the ledger, in JSON.
+    #[arg(long, short)]
+    pub output: PathBuf,
+}
+
+impl Export {
+    pub fn run(self) -> anyhow::Result<()> {
+        let (accounts, _) = read_ledger_file(&self.file, &self.format)?;
+        let mut mask = build_ledger(&accounts)?;
+        write_snapshot(&mut mask, &self.output)
+            .with_context(|| format!("writing {:?}", self.output))?;
+
+        // Check that the snapshot reads back to the same ledger
+        let imported = read_snapshot(&self.output)
+            .with_context(|| format!("reading back {:?}", self.output))?;
+
+        println!("Accounts: {}", imported.num_accounts());
+        println!("Written:  {:?}", self.output);
+        Ok(())
+    }
+}
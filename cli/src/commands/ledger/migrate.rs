@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use node::{
+    daemon_json::DaemonJson,
+    ledger::{export::migrate_ledger, ledger_depth},
+};
+
+/// Move the accounts of a ledger exported with `mina ledger export` to a
+/// ledger of another depth, for custom networks.
+#[derive(Debug, clap::Args)]
+pub struct Migrate {
+    /// Ledger in the genesis ledger format of `daemon.json`.
+    pub file: PathBuf,
+
+    /// Depth of the ledger in the file, its hash is checked against it.
+    /// Defaults to the ledger depth of the network.
+    #[arg(long)]
+    pub from_depth: Option<usize>,
+
+    /// Depth of the migrated ledger.
+    #[arg(long)]
+    pub to_depth: usize,
+
+    /// Where to write the migrated ledger, in JSON.
+    #[arg(long, short)]
+    pub output: PathBuf,
+}
+
+impl Migrate {
+    pub fn run(self) -> anyhow::Result<()> {
+        let file =
+            std::fs::File::open(&self.file).with_context(|| format!("opening {:?}", self.file))?;
+        let config: DaemonJson = serde_json::from_reader(std::io::BufReader::new(file))
+            .with_context(|| format!("reading {:?}", self.file))?;
+        let ledger = config
+            .ledger
+            .as_ref()
+            .with_context(|| format!("no ledger in {:?}", self.file))?;
+
+        let migrated = migrate_ledger(
+            ledger,
+            self.from_depth.unwrap_or_else(ledger_depth),
+            self.to_depth,
+        )?;
+        let num_accounts = migrated.num_accounts.unwrap_or_default();
+        let hash = migrated.hash.clone().unwrap_or_default();
+
+        let output = DaemonJson {
+            ledger: Some(migrated),
+            ..config
+        };
+        let file = std::fs::File::create(&self.output)
+            .with_context(|| format!("creating {:?}", self.output))?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &output)
+            .with_context(|| format!("writing {:?}", self.output))?;
+
+        println!("Accounts: {num_accounts}");
+        println!("Hash:     {hash}");
+        println!("Written:  {:?}", self.output);
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+pub mod export;
+pub mod migrate;
+pub mod verify;
+
+#[derive(Debug, clap::Args)]
+pub struct Ledger {
+    #[command(subcommand)]
+    pub command: LedgerCommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum LedgerCommand {
+    /// Recompute the Merkle root of a ledger file and compare it with the
+    /// expected hash.
+    Verify(verify::Verify),
+    /// Write the accounts of a ledger file in the genesis ledger format of
+    /// `daemon.json`.
+    Export(export::Export),
+    /// Move the accounts of an exported ledger to a ledger of another
+    /// depth.
+    Migrate(migrate::Migrate),
+}
+
+impl Ledger {
+    pub fn run(self) -> anyhow::Result<()> {
+        match self.command {
+            LedgerCommand::Verify(v) => v.run(),
+            LedgerCommand::Export(v) => v.run(),
+            LedgerCommand::Migrate(v) => v.run(),
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, Read},
+    mem::size_of,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context;
+use ledger::{ondisk::AccountStore, BaseLedger, Database, Mask};
+use mina_core::block::ArcBlockWithHash;
+use mina_p2p_messages::{
+    binprot::BinProtRead,
+    v2::{LedgerHash, MinaBaseAccountBinableArgStableV2},
+};
+use node::transition_frontier::genesis::LedgerAccountsWithHash;
+
+use crate::commands::internal::import_blocks::read_precomputed_block;
+
+/// Recompute the Merkle root of a ledger from its accounts, and compare it
+/// with the expected ledger hash.
+///
+/// Meant for integrity audits, for example after disk issues. No hash
+/// stored in the file is trusted.
+#[derive(Debug, clap::Args)]
+pub struct Verify {
+    /// Ledger file.
+    pub file: PathBuf,
+
+    /// Format of the ledger file.
+    #[arg(long, value_enum, default_value_t = LedgerFileFormat::Snapshot)]
+    pub format: LedgerFileFormat,
+
+    /// Expected ledger hash.
+    #[arg(long, required_unless_present = "block", conflicts_with = "block")]
+    pub expected_hash: Option<String>,
+
+    /// Precomputed block, in JSON format, whose protocol state holds the
+    /// expected ledger hash.
+    #[arg(long)]
+    pub block: Option<PathBuf>,
+
+    /// Ledger of the block's protocol state to compare with, with `--block`.
+    #[arg(long, value_enum, default_value_t = ProtocolStateLedger::Snarked)]
+    pub ledger: ProtocolStateLedger,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub enum LedgerFileFormat {
+    /// Snarked ledger snapshot, as served by a node at
+    /// `/bootstrap/snarked-ledger/<hash>`.
+    Snapshot,
+    /// Ledger cached by the node in its `ledgers` cache directory.
+    Cache,
+    /// Ledger persisted on disk by the node, in a directory named after
+    /// its hash. The ledger must not be in use by a running node.
+    Ondisk,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub enum ProtocolStateLedger {
+    Snarked,
+    Staged,
+    StakingEpoch,
+    NextEpoch,
+}
+
+impl Verify {
+    pub fn run(self) -> anyhow::Result<()> {
+        let expected_hash = self.expected_hash()?;
+
+        let (accounts, cached) = read_ledger_file(&self.file, &self.format)?;
+        let depth = mina_core::constants::constraint_constants().ledger_depth as u8;
+        let mut mask = build_ledger(&accounts)?;
+        let computed_hash = LedgerHash::from_fp(mask.merkle_root());
+
+        println!("Accounts:      {}", accounts.len());
+        println!("Depth:         {depth}");
+        println!("Computed hash: {computed_hash}");
+        println!("Expected hash: {expected_hash}");
+
+        let mut errors = Vec::new();
+        if let Some((cached_hash, cached_hashes)) = cached {
+            println!("Stored hash:   {cached_hash}");
+            if cached_hash != computed_hash {
+                errors
+                    .push("the hash stored with the ledger doesn't match the accounts".to_owned());
+            }
+            let mismatched = mask
+                .get_raw_inner_hashes()
+                .into_iter()
+                .filter(|(index, hash)| {
+                    cached_hashes
+                        .get(index)
+                        .is_some_and(|cached| cached.to_field().ok() != Some(*hash))
+                })
+                .count();
+            if mismatched > 0 {
+                errors.push(format!(
+                    "{mismatched} inner hashes stored in the cache don't match the accounts"
+                ));
+            }
+        }
+        if computed_hash != expected_hash {
+            errors.push("the computed hash doesn't match the expected hash".to_owned());
+        }
+
+        if !errors.is_empty() {
+            anyhow::bail!("ledger verification failed: {}", errors.join(", "));
+        }
+        println!("Ledger is valid");
+        Ok(())
+    }
+
+    fn expected_hash(&self) -> anyhow::Result<LedgerHash> {
+        if let Some(hash) = &self.expected_hash {
+            return hash
+                .parse()
+                .map_err(|err| anyhow::anyhow!("invalid ledger hash {hash:?}: {err}"));
+        }
+        let path = self.block.as_ref().context("no expected hash")?;
+        let block = ArcBlockWithHash::try_new(Arc::new(read_precomputed_block(path)?))
+            .map_err(|err| anyhow::anyhow!("hashing block {path:?}: {err:?}"))?;
+        Ok(match self.ledger {
+            ProtocolStateLedger::Snarked => block.snarked_ledger_hash(),
+            ProtocolStateLedger::Staged => block.merkle_root_hash(),
+            ProtocolStateLedger::StakingEpoch => block.staking_epoch_ledger_hash(),
+            ProtocolStateLedger::NextEpoch => block.next_epoch_ledger_hash(),
+        }
+        .clone())
+    }
+}
+
+type CachedHashes = (LedgerHash, BTreeMap<u64, LedgerHash>);
+
+/// Reads the accounts of a ledger file, with the hashes stored along them
+/// for cached ledgers, and the hash in the directory name for ledgers
+/// persisted on disk.
+pub(super) fn read_ledger_file(
+    path: &Path,
+    format: &LedgerFileFormat,
+) -> anyhow::Result<(Vec<MinaBaseAccountBinableArgStableV2>, Option<CachedHashes>)> {
+    let open = || -> anyhow::Result<_> {
+        let file = File::open(path).with_context(|| format!("opening {path:?}"))?;
+        Ok(BufReader::new(file))
+    };
+    match format {
+        LedgerFileFormat::Snapshot => {
+            let mut reader = open()?;
+            // Snapshots are prefixed with their length.
+            reader
+                .read_exact(&mut [0; size_of::<u64>()])
+                .with_context(|| format!("reading {path:?}"))?;
+            let accounts = Vec::<MinaBaseAccountBinableArgStableV2>::binprot_read(&mut reader)
+                .with_context(|| format!("reading {path:?}"))?;
+            Ok((accounts, None))
+        }
+        LedgerFileFormat::Cache => {
+            let cached = LedgerAccountsWithHash::read(open()?)
+                .with_context(|| format!("reading {path:?}"))?;
+            let hashes = cached.hashes.into_iter().collect::<BTreeMap<_, _>>();
+            Ok((cached.accounts, Some((cached.ledger_hash, hashes))))
+        }
+        LedgerFileFormat::Ondisk => read_ondisk_ledger(path),
+    }
+}
+
+fn read_ondisk_ledger(
+    directory: &Path,
+) -> anyhow::Result<(Vec<MinaBaseAccountBinableArgStableV2>, Option<CachedHashes>)> {
+    // Opening a missing ledger would create an empty one.
+    anyhow::ensure!(directory.is_dir(), "{directory:?} is not a directory");
+    let stored_hash = directory
+        .file_name()
+        .and_then(|name| name.to_str()?.parse::<LedgerHash>().ok())
+        .with_context(|| format!("{directory:?} isn't named after a ledger hash"))?;
+
+    let depth = mina_core::constants::constraint_constants().ledger_depth as u8;
+    let store =
+        AccountStore::open(directory, depth).with_context(|| format!("opening {directory:?}"))?;
+    let accounts = store.fold_until(Ok(Vec::new()), |accounts, index, account| {
+        let Ok(mut accounts) = accounts else {
+            return ControlFlow::Break(accounts);
+        };
+        if index != accounts.len() {
+            return ControlFlow::Break(Err(anyhow::anyhow!(
+                "missing account at index {}",
+                accounts.len()
+            )));
+        }
+        accounts.push(MinaBaseAccountBinableArgStableV2::from(&account));
+        ControlFlow::Continue(Ok(accounts))
+    })?;
+    Ok((accounts, Some((stored_hash, BTreeMap::new()))))
+}
+
+/// Builds a ledger from accounts, in order of their index.
+pub(super) fn build_ledger(accounts: &[MinaBaseAccountBinableArgStableV2]) -> anyhow::Result<Mask> {
+    let depth = mina_core::constants::constraint_constants().ledger_depth as u8;
+    let mut mask = Mask::new_root(Database::create(depth));
+    for (index, account) in accounts.iter().enumerate() {
+        let account = ledger::Account::try_from(account)
+            .map_err(|err| anyhow::anyhow!("invalid account at index {index}: {err:?}"))?;
+        mask.get_or_create_account(account.id(), account)
+            .map_err(|err| anyhow::anyhow!("adding account at index {index}: {err:?}"))?;
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::{Account, AccountIndex, Address};
+    use mina_p2p_messages::binprot::BinProtWrite;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn accounts() -> Vec<MinaBaseAccountBinableArgStableV2> {
+        (0..5).map(|_| (&Account::rand()).into()).collect()
+    }
+
+    fn ledger_hash(accounts: &[MinaBaseAccountBinableArgStableV2]) -> LedgerHash {
+        LedgerHash::from_fp(build_ledger(accounts).unwrap().merkle_root())
+    }
+
+    fn verify(file: &Path, format: LedgerFileFormat, expected: &LedgerHash) -> anyhow::Result<()> {
+        Verify {
+            file: file.to_owned(),
+            format,
+            expected_hash: Some(expected.to_string()),
+            block: None,
+            ledger: ProtocolStateLedger::Snarked,
+        }
+        .run()
+    }
+
+    #[test]
+    fn test_verify_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ledger.bin");
+        let accounts = accounts();
+        let mut bytes = Vec::new();
+        accounts.binprot_write(&mut bytes).unwrap();
+        let mut snapshot = (bytes.len() as u64).to_le_bytes().to_vec();
+        snapshot.extend(bytes);
+        std::fs::write(&path, snapshot).unwrap();
+
+        verify(&path, LedgerFileFormat::Snapshot, &ledger_hash(&accounts)).unwrap();
+        let error = verify(
+            &path,
+            LedgerFileFormat::Snapshot,
+            &ledger_hash(&accounts[1..]),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("doesn't match the expected hash"), "{error}");
+    }
+
+    #[test]
+    fn test_verify_cache_checks_stored_hashes() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ledger.bin");
+        let accounts = accounts();
+        let ledger_hash = ledger_hash(&accounts);
+        let mut mask = build_ledger(&accounts).unwrap();
+        // The hashes are computed along the root.
+        mask.merkle_root();
+        let mut hashes = mask
+            .get_raw_inner_hashes()
+            .into_iter()
+            .map(|(index, hash)| (index, LedgerHash::from_fp(hash)))
+            .collect::<Vec<_>>();
+        let write = |hashes: &Vec<(u64, LedgerHash)>| {
+            let cached = LedgerAccountsWithHash {
+                ledger_hash: ledger_hash.clone(),
+                accounts: accounts.clone(),
+                hashes: hashes.clone(),
+            };
+            cached
+                .binprot_write(&mut File::create(&path).unwrap())
+                .unwrap();
+        };
+
+        write(&hashes);
+        verify(&path, LedgerFileFormat::Cache, &ledger_hash).unwrap();
+
+        hashes[0].1 = self::ledger_hash(&accounts[1..]);
+        write(&hashes);
+        let error = verify(&path, LedgerFileFormat::Cache, &ledger_hash)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("1 inner hashes"), "{error}");
+    }
+
+    #[test]
+    fn test_verify_ondisk_ledger() {
+        let temp_dir = TempDir::new().unwrap();
+        let accounts = accounts();
+        let ledger_hash = ledger_hash(&accounts);
+        let path = temp_dir.path().join(ledger_hash.to_string());
+        {
+            // Stored the way the node persists its ledgers.
+            let depth = mina_core::constants::constraint_constants().ledger_depth as u8;
+            let mut db = Database::create_ondisk(depth, path.clone()).unwrap();
+            let accounts = accounts
+                .iter()
+                .enumerate()
+                .map(|(index, account)| {
+                    let addr = Address::from_index(AccountIndex(index as u64), depth as usize);
+                    (addr, Box::new(Account::try_from(account).unwrap()))
+                })
+                .collect::<Vec<_>>();
+            db.set_batch_with_hashes(&accounts);
+        }
+
+        verify(&path, LedgerFileFormat::Ondisk, &ledger_hash).unwrap();
+
+        // Stored under the hash of another ledger.
+        let other_path = temp_dir
+            .path()
+            .join(self::ledger_hash(&accounts[1..]).to_string());
+        std::fs::rename(&path, &other_path).unwrap();
+        let error = verify(&other_path, LedgerFileFormat::Ondisk, &ledger_hash)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("stored with the ledger"), "{error}");
+
+        // A missing ledger isn't created.
+        assert!(verify(&path, LedgerFileFormat::Ondisk, &ledger_hash).is_err());
+        assert!(!path.exists());
+    }
+}
@@ -1,9 +1,13 @@
+pub mod advanced;
 pub mod build_info;
+pub mod config;
 pub mod internal;
+pub mod ledger;
 pub mod misc;
 pub mod node;
 pub mod replay;
 pub mod snark;
+pub mod status;
 pub mod wallet;
 
 #[derive(Debug, clap::Parser)]
@@ -39,13 +43,55 @@ pub enum Command {
     Misc(misc::Misc),
     Replay(replay::Replay),
     BuildInfo(build_info::Command),
+    /// Node configuration file utilities.
+    Config(config::Config),
     /// Wallet operations for managing accounts and sending transactions.
     Wallet(wallet::Wallet),
     /// Internal utilities for debugging and introspection.
     Internal(internal::Internal),
+    /// Node operator utilities, like p2p key rotation.
+    Advanced(advanced::Advanced),
+    /// Ledger utilities, like integrity checks of ledger files.
+    Ledger(ledger::Ledger),
+    /// Status of a running node, refreshed live with `--watch`.
+    Status(status::Status),
+}
+
+impl Network {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Devnet => "devnet",
+            Self::Mainnet => "mainnet",
+        }
+    }
 }
 
 impl Command {
+    /// Constraint constants overriding the network's built-in ones, if the
+    /// command is configured with any.
+    pub fn constraint_constants(
+        &self,
+        network: &Network,
+    ) -> anyhow::Result<Option<mina_core::constants::ConstraintConstants>> {
+        match self {
+            Self::Node(v) => v.constraint_constants(network.name()),
+            _ => Ok(None),
+        }
+    }
+
+    /// Loads the node configuration file, if the command is configured
+    /// with one. `matches` are the matches of the whole cli.
+    pub fn load_node_config(&mut self, matches: &clap::ArgMatches) -> anyhow::Result<()> {
+        let Some((_, matches)) = matches.subcommand() else {
+            return Ok(());
+        };
+        match self {
+            Self::Node(v) => v.load_node_config(matches),
+            Self::Config(v) => v.load_node_config(matches),
+            _ => Ok(()),
+        }
+    }
+
     pub fn run(self, network: Network) -> anyhow::Result<()> {
         match self {
             Self::Snark(v) => v.run(),
@@ -53,8 +99,12 @@ impl Command {
             Self::Misc(v) => v.run(),
             Self::Replay(v) => v.run(),
             Self::BuildInfo(v) => v.run(),
+            Self::Config(v) => v.run(),
             Self::Wallet(v) => v.run(network),
             Self::Internal(v) => v.run(),
+            Self::Advanced(v) => v.run(),
+            Self::Ledger(v) => v.run(),
+            Self::Status(v) => v.run(),
         }
     }
 }
@@ -0,0 +1,848 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeSet,
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::Context;
+use clap::{parser::ValueSource, ArgMatches};
+use ledger::transaction_pool::ZkappRelayPolicy;
+use mina_node_native::EventShedPolicy;
+use node::{snark::SnarkVerifyCacheEviction, SnarkerStrategy};
+use serde::{Deserialize, Serialize};
+
+use super::Node;
+
+const REDACTED: &str = "<redacted>";
+
+/// Node configuration file (`mina.toml`, or `mina.yaml`), covering the
+/// `mina node` flags.
+///
+/// Values set with flags or environment variables take precedence over the
+/// ones from the file, which take precedence over the flag defaults.
+///
+/// ```toml
+/// work_dir = "~/.mina"
+///
+/// [p2p]
+/// libp2p_port = 8302
+/// peers = ["/dns4/seed.example.com/tcp/8302/p2p/12D3KooW..."]
+///
+/// [producer]
+/// key = "/keys/producer-key"
+///
+/// [logging]
+/// verbosity = "debug"
+/// ```
+///
+/// The same file in YAML, which is used for files ending in `.yaml` or
+/// `.yml`:
+///
+/// ```yaml
+/// work_dir: ~/.mina
+/// p2p:
+///   libp2p_port: 8302
+///   peers: ["/dns4/seed.example.com/tcp/8302/p2p/12D3KooW..."]
+/// producer:
+///   key: /keys/producer-key
+/// logging:
+///   verbosity: debug
+/// ```
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NodeConfigFile {
+    pub work_dir: Option<String>,
+    /// HTTP server port.
+    pub port: Option<u16>,
+    /// Daemon JSON config file (`--config`).
+    pub daemon_config: Option<PathBuf>,
+    pub record: Option<String>,
+    pub rng_seed: Option<String>,
+    /// Trusted node to download the root ledgers from (`--bootstrap-from`).
+    pub bootstrap_from: Option<String>,
+    /// Peer id of that node (`--bootstrap-peer-id`).
+    pub bootstrap_peer_id: Option<String>,
+    /// Token for downloading ledgers from `/bootstrap`
+    /// (`--bootstrap-token`).
+    pub bootstrap_token: Option<String>,
+    /// Bucket to fetch blocks from during catchup
+    /// (`--precomputed-blocks-url`).
+    pub precomputed_blocks_url: Option<String>,
+    /// Run a local development chain (`--dev-local`).
+    pub dev_local: Option<bool>,
+    /// Number of finalized blocks to keep a summary of
+    /// (`--root-history-size`).
+    pub root_history_size: Option<usize>,
+    /// Speed of the time of the node, on test networks
+    /// (`--time-acceleration`).
+    pub time_acceleration: Option<u32>,
+    /// Unix timestamp in milliseconds since which the time is accelerated
+    /// (`--time-acceleration-origin`).
+    pub time_acceleration_origin: Option<u64>,
+    #[serde(default)]
+    pub p2p: P2pSection,
+    #[serde(default)]
+    pub event_queue: EventQueueSection,
+    #[serde(default)]
+    pub verifier: VerifierSection,
+    #[serde(default)]
+    pub tx_pool: TxPoolSection,
+    #[serde(default)]
+    pub logging: LoggingSection,
+    #[serde(default)]
+    pub producer: ProducerSection,
+    #[serde(default)]
+    pub snarker: SnarkerSection,
+    #[serde(default)]
+    pub archive: ArchiveSection,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct P2pSection {
+    pub secret_key: Option<String>,
+    pub libp2p_keypair: Option<String>,
+    pub libp2p_password: Option<String>,
+    pub libp2p_port: Option<u16>,
+    pub previous_keypairs: Option<Vec<String>>,
+    pub external_ips: Option<Vec<String>>,
+    pub peers: Option<Vec<String>>,
+    pub peer_list_file: Option<PathBuf>,
+    pub peer_list_url: Option<String>,
+    /// In seconds.
+    pub peer_list_refresh_interval: Option<u64>,
+    pub max_peers: Option<usize>,
+    pub snarks_per_message: Option<usize>,
+    pub seed: Option<bool>,
+    pub peer_discovery: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct EventQueueSection {
+    pub capacity: Option<usize>,
+    pub shed_policy: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct VerifierSection {
+    pub work_threads: Option<usize>,
+    pub transaction_threads: Option<usize>,
+    /// Max number of zkApp proofs of a batch verified in parallel.
+    pub zkapp_concurrency: Option<usize>,
+    /// Number of verification results to keep, per kind of proof.
+    pub cache_size: Option<usize>,
+    pub cache_eviction: Option<String>,
+    pub skip_digest_check: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TxPoolSection {
+    /// In seconds, `0` for unlimited.
+    pub max_lifetime: Option<u64>,
+    pub zkapp_relay_policy: Option<String>,
+    /// In seconds.
+    pub zkapp_relay_budget: Option<u64>,
+    /// In nanomina.
+    pub replace_fee: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct LoggingSection {
+    pub verbosity: Option<String>,
+    /// Whether logs are also written to files.
+    pub filesystem: Option<bool>,
+    pub path: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ProducerSection {
+    pub key: Option<PathBuf>,
+    pub key_password: Option<String>,
+    pub coinbase_receiver: Option<String>,
+    pub max_snark_fee_per_work: Option<u64>,
+    pub max_snark_fee_per_block: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SnarkerSection {
+    pub key: Option<String>,
+    pub fee: Option<u64>,
+    pub strategy: Option<String>,
+    /// Nodes to submit the produced work to (`--snarker-coordinator`).
+    pub coordinators: Option<Vec<String>>,
+    /// Token for submitting work (`--snarker-work-token`), which doesn't
+    /// need the snarker to be enabled.
+    pub work_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ArchiveSection {
+    pub local_storage: Option<bool>,
+    pub archiver_process: Option<bool>,
+    pub gcp_storage: Option<bool>,
+    pub aws_storage: Option<bool>,
+    /// Keep zkApp actions and events (`--archive-zkapp-actions-events`).
+    pub zkapp_actions_events: Option<bool>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConfigFileFormat {
+    #[default]
+    Toml,
+    Yaml,
+}
+
+impl ConfigFileFormat {
+    /// Format of the file at `path`, YAML for the `.yaml` and `.yml`
+    /// extensions, TOML otherwise.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => Self::Yaml,
+            _ => Self::Toml,
+        }
+    }
+}
+
+impl NodeConfigFile {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents =
+            std::fs::read_to_string(path).with_context(|| format!("reading {path:?}"))?;
+        Self::parse(&contents, ConfigFileFormat::from_path(path))
+            .with_context(|| format!("parsing {path:?}"))
+    }
+
+    pub fn parse(contents: &str, format: ConfigFileFormat) -> anyhow::Result<Self> {
+        Ok(match format {
+            ConfigFileFormat::Toml => toml::from_str(contents)?,
+            ConfigFileFormat::Yaml => serde_yaml::from_str(contents)?,
+        })
+    }
+
+    pub fn to_string(&self, format: ConfigFileFormat) -> anyhow::Result<String> {
+        Ok(match format {
+            ConfigFileFormat::Toml => toml::to_string(self)?,
+            ConfigFileFormat::Yaml => serde_yaml::to_string(self)?,
+        })
+    }
+
+    /// Sets the values of `node` that weren't set with a flag or an
+    /// environment variable, according to `matches`.
+    pub fn apply(self, node: &mut Node, matches: &ArgMatches) -> anyhow::Result<()> {
+        self.apply_with(node, &Merge::new(matches))
+    }
+
+    fn apply_with(self, node: &mut Node, merge: &Merge<'_>) -> anyhow::Result<()> {
+        merge.set("work_dir", &mut node.work_dir, self.work_dir);
+        merge.set("port", &mut node.port, self.port);
+        merge.set("config", &mut node.config, self.daemon_config.map(Some));
+        merge.set("record", &mut node.record, self.record);
+        merge.set("rng_seed", &mut node.rng_seed, self.rng_seed.map(Some));
+        let bootstrap_from = self
+            .bootstrap_from
+            .as_deref()
+            .map(|v| parse("bootstrap_from", v));
+        merge.set(
+            "bootstrap_from",
+            &mut node.bootstrap_from,
+            bootstrap_from.transpose()?.map(Some),
+        );
+        let bootstrap_peer_id = self
+            .bootstrap_peer_id
+            .as_deref()
+            .map(|v| parse("bootstrap_peer_id", v));
+        merge.set(
+            "bootstrap_peer_id",
+            &mut node.bootstrap_peer_id,
+            bootstrap_peer_id.transpose()?.map(Some),
+        );
+        merge.set(
+            "bootstrap_token",
+            &mut node.bootstrap_token,
+            self.bootstrap_token.map(Some),
+        );
+        let precomputed_blocks_url = self
+            .precomputed_blocks_url
+            .as_deref()
+            .map(|v| parse("precomputed_blocks_url", v));
+        merge.set(
+            "precomputed_blocks_url",
+            &mut node.precomputed_blocks_url,
+            precomputed_blocks_url.transpose()?.map(Some),
+        );
+        merge.set("dev_local", &mut node.dev_local, self.dev_local);
+        merge.set(
+            "root_history_size",
+            &mut node.root_history_size,
+            self.root_history_size.map(Some),
+        );
+        merge.set(
+            "time_acceleration",
+            &mut node.time_acceleration,
+            self.time_acceleration,
+        );
+        merge.set(
+            "time_acceleration_origin",
+            &mut node.time_acceleration_origin,
+            self.time_acceleration_origin.map(Some),
+        );
+
+        let p2p = self.p2p;
+        let secret_key = p2p
+            .secret_key
+            .as_deref()
+            .map(|v| parse("p2p.secret_key", v));
+        merge.set(
+            "p2p_secret_key",
+            &mut node.p2p_secret_key,
+            secret_key.transpose()?.map(Some),
+        );
+        merge.set(
+            "libp2p_keypair",
+            &mut node.libp2p_keypair,
+            p2p.libp2p_keypair.map(Some),
+        );
+        merge.set(
+            "libp2p_password",
+            &mut node.libp2p_password,
+            p2p.libp2p_password.map(Some),
+        );
+        merge.set("libp2p_port", &mut node.libp2p_port, p2p.libp2p_port);
+        merge.set(
+            "libp2p_previous_keypair",
+            &mut node.libp2p_previous_keypair,
+            p2p.previous_keypairs,
+        );
+        merge.set(
+            "libp2p_external_ip",
+            &mut node.libp2p_external_ip,
+            p2p.external_ips,
+        );
+        let peers = p2p.peers.map(|v| parse_all("p2p.peers", &v));
+        merge.set("peers", &mut node.peers, peers.transpose()?);
+        merge.set(
+            "peer_list_file",
+            &mut node.peer_list_file,
+            p2p.peer_list_file.map(Some),
+        );
+        let peer_list_url = p2p
+            .peer_list_url
+            .as_deref()
+            .map(|v| parse("p2p.peer_list_url", v));
+        merge.set(
+            "peer_list_url",
+            &mut node.peer_list_url,
+            peer_list_url.transpose()?.map(Some),
+        );
+        merge.set(
+            "peer_list_refresh_interval",
+            &mut node.peer_list_refresh_interval,
+            p2p.peer_list_refresh_interval.map(Some),
+        );
+        merge.set("max_peers", &mut node.max_peers, p2p.max_peers);
+        merge.set(
+            "snarks_per_message",
+            &mut node.snarks_per_message,
+            p2p.snarks_per_message,
+        );
+        merge.set("seed", &mut node.seed, p2p.seed);
+        merge.set(
+            "no_peers_discovery",
+            &mut node.no_peers_discovery,
+            p2p.peer_discovery.map(|v| !v),
+        );
+
+        let event_queue = self.event_queue;
+        merge.set(
+            "event_queue_capacity",
+            &mut node.event_queue_capacity,
+            event_queue.capacity,
+        );
+        let shed_policy = event_queue
+            .shed_policy
+            .as_deref()
+            .map(|v| parse("event_queue.shed_policy", v));
+        merge.set(
+            "event_queue_shed_policy",
+            &mut node.event_queue_shed_policy,
+            shed_policy.transpose()?,
+        );
+
+        let verifier = self.verifier;
+        merge.set(
+            "work_verify_threads",
+            &mut node.work_verify_threads,
+            verifier.work_threads.map(Some),
+        );
+        merge.set(
+            "transaction_verify_threads",
+            &mut node.transaction_verify_threads,
+            verifier.transaction_threads.map(Some),
+        );
+        merge.set(
+            "zkapp_verify_concurrency",
+            &mut node.zkapp_verify_concurrency,
+            verifier.zkapp_concurrency,
+        );
+        merge.set(
+            "snark_verify_cache_size",
+            &mut node.snark_verify_cache_size,
+            verifier.cache_size,
+        );
+        let cache_eviction = verifier
+            .cache_eviction
+            .as_deref()
+            .map(|v| parse("verifier.cache_eviction", v));
+        merge.set(
+            "snark_verify_cache_eviction",
+            &mut node.snark_verify_cache_eviction,
+            cache_eviction.transpose()?,
+        );
+        merge.set(
+            "skip_verifier_digest_check",
+            &mut node.skip_verifier_digest_check,
+            verifier.skip_digest_check,
+        );
+
+        let tx_pool = self.tx_pool;
+        merge.set(
+            "tx_pool_max_lifetime",
+            &mut node.tx_pool_max_lifetime,
+            tx_pool.max_lifetime.map(Some),
+        );
+        let zkapp_relay_policy = tx_pool
+            .zkapp_relay_policy
+            .as_deref()
+            .map(|v| parse("tx_pool.zkapp_relay_policy", v));
+        merge.set(
+            "zkapp_relay_policy",
+            &mut node.zkapp_relay_policy,
+            zkapp_relay_policy.transpose()?,
+        );
+        merge.set(
+            "zkapp_relay_budget",
+            &mut node.zkapp_relay_budget,
+            tx_pool.zkapp_relay_budget,
+        );
+        merge.set(
+            "tx_pool_replace_fee",
+            &mut node.tx_pool_replace_fee,
+            tx_pool.replace_fee,
+        );
+
+        let logging = self.logging;
+        let verbosity = logging
+            .verbosity
+            .as_deref()
+            .map(|v| parse("logging.verbosity", v));
+        merge.set("verbosity", &mut node.verbosity, verbosity.transpose()?);
+        merge.set(
+            "disable_filesystem_logging",
+            &mut node.disable_filesystem_logging,
+            logging.filesystem.map(|v| !v),
+        );
+        merge.set("log_path", &mut node.log_path, logging.path);
+
+        let producer = self.producer;
+        let producer_options_set = producer.coinbase_receiver.is_some()
+            || producer.max_snark_fee_per_work.is_some()
+            || producer.max_snark_fee_per_block.is_some();
+        merge.set(
+            "producer_key",
+            &mut node.producer_key,
+            producer.key.map(Some),
+        );
+        merge.set(
+            "producer_key_password",
+            &mut node.producer_key_password,
+            producer.key_password,
+        );
+        let coinbase_receiver = producer
+            .coinbase_receiver
+            .as_deref()
+            .map(|v| parse("producer.coinbase_receiver", v));
+        merge.set(
+            "coinbase_receiver",
+            &mut node.coinbase_receiver,
+            coinbase_receiver.transpose()?.map(Some),
+        );
+        merge.set(
+            "max_snark_fee_per_work",
+            &mut node.max_snark_fee_per_work,
+            producer.max_snark_fee_per_work.map(Some),
+        );
+        merge.set(
+            "max_snark_fee_per_block",
+            &mut node.max_snark_fee_per_block,
+            producer.max_snark_fee_per_block.map(Some),
+        );
+        if producer_options_set && node.producer_key.is_none() {
+            anyhow::bail!("[producer] options are set, but the producer key is missing");
+        }
+
+        let snarker = self.snarker;
+        let snarker_options_set =
+            snarker.fee.is_some() || snarker.strategy.is_some() || snarker.coordinators.is_some();
+        let snarker_key = snarker.key.as_deref().map(|v| parse("snarker.key", v));
+        merge.set(
+            "run_snarker",
+            &mut node.run_snarker,
+            snarker_key.transpose()?.map(Some),
+        );
+        merge.set("snarker_fee", &mut node.snarker_fee, snarker.fee);
+        let strategy = snarker
+            .strategy
+            .as_deref()
+            .map(|v| parse("snarker.strategy", v));
+        merge.set(
+            "snarker_strategy",
+            &mut node.snarker_strategy,
+            strategy.transpose()?,
+        );
+        let coordinators = snarker
+            .coordinators
+            .map(|v| parse_all("snarker.coordinators", &v));
+        merge.set(
+            "snarker_coordinators",
+            &mut node.snarker_coordinators,
+            coordinators.transpose()?,
+        );
+        merge.set(
+            "snarker_work_token",
+            &mut node.snarker_work_token,
+            snarker.work_token.map(Some),
+        );
+        if snarker_options_set && node.run_snarker.is_none() {
+            anyhow::bail!("[snarker] options are set, but the snarker key is missing");
+        }
+
+        let archive = self.archive;
+        merge.set(
+            "archive_local_storage",
+            &mut node.archive_local_storage,
+            archive.local_storage,
+        );
+        merge.set(
+            "archive_archiver_process",
+            &mut node.archive_archiver_process,
+            archive.archiver_process,
+        );
+        merge.set(
+            "archive_gcp_storage",
+            &mut node.archive_gcp_storage,
+            archive.gcp_storage,
+        );
+        merge.set(
+            "archive_aws_storage",
+            &mut node.archive_aws_storage,
+            archive.aws_storage,
+        );
+        merge.set(
+            "archive_zkapp_actions_events",
+            &mut node.archive_zkapp_actions_events,
+            archive.zkapp_actions_events,
+        );
+
+        Ok(())
+    }
+
+    /// Effective configuration of `node`, with secrets redacted.
+    pub fn effective(node: &Node) -> Self {
+        let redacted = |v: bool| v.then(|| REDACTED.to_owned());
+        Self {
+            work_dir: Some(node.work_dir.clone()),
+            port: Some(node.port),
+            daemon_config: node.config.clone(),
+            record: Some(node.record.clone()),
+            rng_seed: node.rng_seed.clone(),
+            bootstrap_from: node.bootstrap_from.as_ref().map(ToString::to_string),
+            bootstrap_peer_id: node.bootstrap_peer_id.as_ref().map(ToString::to_string),
+            bootstrap_token: redacted(node.bootstrap_token.is_some()),
+            precomputed_blocks_url: node
+                .precomputed_blocks_url
+                .as_ref()
+                .map(ToString::to_string),
+            dev_local: Some(node.dev_local),
+            root_history_size: node.root_history_size,
+            time_acceleration: Some(node.time_acceleration),
+            time_acceleration_origin: node.time_acceleration_origin,
+            p2p: P2pSection {
+                secret_key: redacted(node.p2p_secret_key.is_some()),
+                libp2p_keypair: node.libp2p_keypair.clone(),
+                libp2p_password: redacted(node.libp2p_password.is_some()),
+                libp2p_port: Some(node.libp2p_port),
+                previous_keypairs: Some(node.libp2p_previous_keypair.clone()),
+                external_ips: Some(node.libp2p_external_ip.clone()),
+                peers: Some(to_strings(&node.peers)),
+                peer_list_file: node.peer_list_file.clone(),
+                peer_list_url: node.peer_list_url.as_ref().map(ToString::to_string),
+                peer_list_refresh_interval: node.peer_list_refresh_interval,
+                max_peers: Some(node.max_peers),
+                snarks_per_message: Some(node.snarks_per_message),
+                seed: Some(node.seed),
+                peer_discovery: Some(!node.no_peers_discovery),
+            },
+            event_queue: EventQueueSection {
+                capacity: Some(node.event_queue_capacity),
+                shed_policy: Some(
+                    match node.event_queue_shed_policy {
+                        EventShedPolicy::DropOldest => "drop-oldest",
+                        EventShedPolicy::DropNewest => "drop-newest",
+                    }
+                    .to_owned(),
+                ),
+            },
+            verifier: VerifierSection {
+                work_threads: node.work_verify_threads,
+                transaction_threads: node.transaction_verify_threads,
+                zkapp_concurrency: Some(node.zkapp_verify_concurrency),
+                cache_size: Some(node.snark_verify_cache_size),
+                cache_eviction: Some(
+                    match node.snark_verify_cache_eviction {
+                        SnarkVerifyCacheEviction::Lru => "lru",
+                        SnarkVerifyCacheEviction::Fifo => "fifo",
+                    }
+                    .to_owned(),
+                ),
+                skip_digest_check: Some(node.skip_verifier_digest_check),
+            },
+            tx_pool: TxPoolSection {
+                max_lifetime: node.tx_pool_max_lifetime,
+                zkapp_relay_policy: Some(
+                    match node.zkapp_relay_policy {
+                        ZkappRelayPolicy::Drop => "drop",
+                        ZkappRelayPolicy::Delay => "delay",
+                        ZkappRelayPolicy::ForwardUnverified => "forward-unverified",
+                    }
+                    .to_owned(),
+                ),
+                zkapp_relay_budget: Some(node.zkapp_relay_budget),
+                replace_fee: Some(node.tx_pool_replace_fee),
+            },
+            logging: LoggingSection {
+                verbosity: Some(node.verbosity.to_string().to_lowercase()),
+                filesystem: Some(!node.disable_filesystem_logging),
+                path: Some(node.log_path.clone()),
+            },
+            producer: ProducerSection {
+                key: node.producer_key.clone(),
+                key_password: redacted(!node.producer_key_password.is_empty()),
+                coinbase_receiver: node.coinbase_receiver.as_ref().map(ToString::to_string),
+                max_snark_fee_per_work: node.max_snark_fee_per_work,
+                max_snark_fee_per_block: node.max_snark_fee_per_block,
+            },
+            snarker: SnarkerSection {
+                key: redacted(node.run_snarker.is_some()),
+                fee: Some(node.snarker_fee),
+                strategy: Some(
+                    match node.snarker_strategy {
+                        SnarkerStrategy::Sequential => "seq",
+                        SnarkerStrategy::Random => "rand",
+                    }
+                    .to_owned(),
+                ),
+                coordinators: Some(to_strings(&node.snarker_coordinators)),
+                work_token: redacted(node.snarker_work_token.is_some()),
+            },
+            archive: ArchiveSection {
+                local_storage: Some(node.archive_local_storage),
+                archiver_process: Some(node.archive_archiver_process),
+                gcp_storage: Some(node.archive_gcp_storage),
+                aws_storage: Some(node.archive_aws_storage),
+                zkapp_actions_events: Some(node.archive_zkapp_actions_events),
+            },
+        }
+    }
+}
+
+struct Merge<'a> {
+    matches: &'a ArgMatches,
+    /// Ids of the flags the config file has a key for.
+    ids: RefCell<BTreeSet<&'static str>>,
+}
+
+impl<'a> Merge<'a> {
+    fn new(matches: &'a ArgMatches) -> Self {
+        Self {
+            matches,
+            ids: Default::default(),
+        }
+    }
+
+    fn set<T>(&self, id: &'static str, field: &mut T, value: Option<T>) {
+        self.ids.borrow_mut().insert(id);
+        let is_explicit = matches!(
+            self.matches.value_source(id),
+            Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+        );
+        if let Some(value) = value.filter(|_| !is_explicit) {
+            *field = value;
+        }
+    }
+}
+
+fn parse<T>(key: &str, value: &str) -> anyhow::Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|err| anyhow::anyhow!("invalid value for `{key}` ({value}): {err}"))
+}
+
+fn parse_all<T>(key: &str, values: &[String]) -> anyhow::Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    values.iter().map(|v| parse(key, v)).collect()
+}
+
+fn to_strings<T: ToString>(values: &[T]) -> Vec<String> {
+    values.iter().map(ToString::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{Args, FromArgMatches};
+
+    use super::*;
+
+    fn node_with_config(args: &[&str], config: &str) -> anyhow::Result<Node> {
+        node_with_config_format(args, config, ConfigFileFormat::Toml)
+    }
+
+    fn node_with_config_format(
+        args: &[&str],
+        config: &str,
+        format: ConfigFileFormat,
+    ) -> anyhow::Result<Node> {
+        let matches = Node::augment_args(clap::Command::new("node"))
+            .try_get_matches_from(std::iter::once("node").chain(args.iter().copied()))?;
+        let mut node = Node::from_arg_matches(&matches)?;
+        NodeConfigFile::parse(config, format)?.apply(&mut node, &matches)?;
+        Ok(node)
+    }
+
+    #[test]
+    fn flags_take_precedence_over_config_file() {
+        let config = r#"
+            [p2p]
+            max_peers = 20
+            seed = true
+
+            [archive]
+            local_storage = true
+
+            [verifier]
+            transaction_threads = 2
+
+            [tx_pool]
+            max_lifetime = 3600
+        "#;
+
+        let node = node_with_config(&[], config).unwrap();
+        assert_eq!(node.max_peers, 20);
+        assert!(node.seed);
+        assert!(node.archive_local_storage);
+        assert_eq!(node.transaction_verify_threads, Some(2));
+        assert_eq!(node.work_verify_threads, None);
+        assert_eq!(node.tx_pool_max_lifetime, Some(3600));
+
+        let node = node_with_config(&["--max-peers", "50"], config).unwrap();
+        assert_eq!(node.max_peers, 50);
+        assert!(node.seed);
+    }
+
+    #[test]
+    fn config_file_errors() {
+        let unknown_field = "[p2p]\nmax_peer = 20";
+        assert!(node_with_config(&[], unknown_field).is_err());
+
+        let invalid_value = "[snarker]\nkey = \"not a key\"";
+        assert!(node_with_config(&[], invalid_value).is_err());
+
+        let missing_producer_key = "[producer]\nmax_snark_fee_per_work = 10";
+        assert!(node_with_config(&[], missing_producer_key).is_err());
+    }
+
+    #[test]
+    fn yaml_config_file() {
+        let config = r#"
+            dev_local: true
+            root_history_size: 1000
+            time_acceleration: 10
+            p2p:
+              max_peers: 20
+            tx_pool:
+              max_lifetime: 3600
+        "#;
+
+        let node = node_with_config_format(&[], config, ConfigFileFormat::Yaml).unwrap();
+        assert!(node.dev_local);
+        assert_eq!(node.root_history_size, Some(1000));
+        assert_eq!(node.time_acceleration, 10);
+        assert_eq!(node.max_peers, 20);
+        assert_eq!(node.tx_pool_max_lifetime, Some(3600));
+
+        let unknown_field = "p2p:\n  max_peer: 20";
+        assert!(node_with_config_format(&[], unknown_field, ConfigFileFormat::Yaml).is_err());
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("mina.yml");
+        std::fs::write(&path, config).unwrap();
+        assert_eq!(ConfigFileFormat::from_path(&path), ConfigFileFormat::Yaml);
+        let loaded = NodeConfigFile::load(&path).unwrap();
+        assert_eq!(loaded.p2p.max_peers, Some(20));
+        assert_eq!(
+            ConfigFileFormat::from_path(Path::new("mina.toml")),
+            ConfigFileFormat::Toml
+        );
+    }
+
+    #[test]
+    fn effective_config_round_trip() {
+        let node =
+            node_with_config(&["--max-peers", "50", "--time-acceleration", "4"], "").unwrap();
+        let effective = NodeConfigFile::effective(&node);
+        for format in [ConfigFileFormat::Toml, ConfigFileFormat::Yaml] {
+            let contents = effective.to_string(format).unwrap();
+            let parsed = NodeConfigFile::parse(&contents, format).unwrap();
+            assert_eq!(parsed.p2p.max_peers, Some(50), "{format:?}");
+            assert_eq!(parsed.time_acceleration, Some(4), "{format:?}");
+        }
+    }
+
+    #[test]
+    fn every_flag_has_a_config_key() {
+        let command = Node::augment_args(clap::Command::new("node"));
+        let matches = command.clone().try_get_matches_from(["node"]).unwrap();
+        let mut node = Node::from_arg_matches(&matches).unwrap();
+        let merge = Merge::new(&matches);
+        NodeConfigFile::default()
+            .apply_with(&mut node, &merge)
+            .unwrap();
+
+        let ids = merge.ids.into_inner();
+        let missing = command
+            .get_arguments()
+            .map(|arg| arg.get_id().as_str())
+            // The config file can't point to another one.
+            .filter(|id| !["node_config", "help", "version"].contains(id))
+            .filter(|id| !ids.contains(id))
+            .collect::<Vec<_>>();
+        assert!(
+            missing.is_empty(),
+            "flags without a config key: {missing:?}"
+        );
+    }
+}
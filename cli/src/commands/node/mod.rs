@@ -1,18 +1,38 @@
 use anyhow::Context;
-use ledger::proofs::provers::BlockProver;
+use ledger::{
+    proofs::provers::BlockProver,
+    scan_state::currency::Fee,
+    transaction_pool::{ZkappRelayConfig, ZkappRelayPolicy, DEFAULT_REPLACE_FEE},
+};
+use mina_core::{constants::ConstraintConstants, NetworkConfig, TimeSource};
 use mina_node_account::AccountPublicKey;
-use mina_node_native::{archive::config::ArchiveStorageOptions, tracing, NodeBuilder};
+use mina_node_native::{
+    archive::config::ArchiveStorageOptions, tracing, EventQueueConfig, EventShedPolicy,
+    NodeBuilder, SnarkVerifyPoolsConfig,
+};
 use node::{
     account::AccountSecretKey,
+    block_producer::BlockProducerWorkFeePolicy,
     core::log::inner::Level,
-    p2p::{connection::outgoing::P2pConnectionOutgoingInitOpts, identity::SecretKey},
+    p2p::{connection::outgoing::P2pConnectionOutgoingPeerAddr, identity::SecretKey, PeerId},
     service::Recorder,
-    snark::{BlockVerifier, TransactionVerifier},
+    snark::{BlockVerifier, SnarkVerifyCacheConfig, SnarkVerifyCacheEviction, TransactionVerifier},
     transition_frontier::genesis::GenesisConfig,
     SnarkerStrategy,
 };
 use reqwest::Url;
-use std::{fs::File, path::PathBuf, sync::Arc};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+mod config_file;
+pub use config_file::{ConfigFileFormat, NodeConfigFile};
+
+mod startup;
+use startup::WorkDirLock;
 
 /// Mina node configuration and runtime options
 ///
@@ -37,6 +57,13 @@ use std::{fs::File, path::PathBuf, sync::Arc};
 /// mina node --network devnet --producer-key /path/to/key --coinbase-receiver B62q...
 /// ```
 ///
+/// # Local Development Chain
+///
+/// ```bash
+/// # Run a single node chain, producing a block on every slot
+/// mina node --dev-local
+/// ```
+///
 /// # Archive Node Mode
 ///
 /// ```bash
@@ -69,6 +96,17 @@ pub struct Node {
     #[arg(env = "MINA_LIBP2P_PASS")]
     pub libp2p_password: Option<String>,
 
+    /// Keypair file this node used before its key was rotated with
+    /// `mina advanced rotate-p2p-key`, decrypted with `MINA_LIBP2P_PASS`.
+    /// Can be repeated.
+    ///
+    /// Peers keep advertising the old peer id for a while after the
+    /// rotation. It is recognized as this node instead of being dialed as
+    /// another peer, and webrtc peers which still dial it are answered with
+    /// the old key. Drop it once the grace period is over.
+    #[arg(long)]
+    pub libp2p_previous_keypair: Vec<String>,
+
     /// List of external addresses at which this node is accessible
     #[arg(long)]
     pub libp2p_external_ip: Vec<String>,
@@ -130,13 +168,25 @@ pub struct Node {
     /// /dns4/node.example.com/tcp/8302/p2p/12D3KooWABCDEF1234567890abcdef...
     /// ```
     ///
+    /// **DNSADDR Example:**
+    /// ```
+    /// /dnsaddr/seeds.example.com
+    /// ```
+    ///
     /// Where:
-    /// - `ip4/ip6/dns4` specifies the address type
+    /// - `ip4/ip6/dns/dns4/dns6` specifies the address type
     /// - IP address or hostname
     /// - `tcp` protocol with port number (typically 8302 for Mina)
     /// - `p2p` protocol with the peer's public key identifier
+    ///
+    /// Hostnames are resolved when connecting, and resolved again when a
+    /// connection to the peer fails. Internationalized names are converted
+    /// to punycode. A `/dnsaddr` address is expanded into the peers listed
+    /// in the `TXT` records of `_dnsaddr.<domain>`, optionally restricted to
+    /// one peer with a trailing `/p2p/<peer_id>`. The domain is resolved
+    /// again when its records expire or a connection to its peers fails.
     #[arg(long, short = 'P', alias = "peer")]
-    pub peers: Vec<P2pConnectionOutgoingInitOpts>,
+    pub peers: Vec<P2pConnectionOutgoingPeerAddr>,
 
     /// File containing initial peers to connect to
     ///
@@ -147,6 +197,7 @@ pub struct Node {
     /// /ip4/192.168.1.100/tcp/8302/p2p/12D3KooWABCDEF1234567890abcdef...
     /// /ip4/10.0.0.50/tcp/8302/p2p/12D3KooWXYZ9876543210fedcba...
     /// /dns4/bootstrap.example.com/tcp/8302/p2p/12D3KooW123ABC...
+    /// /dnsaddr/seeds.example.com
     /// ```
     ///
     /// Empty lines and lines starting with `#` are ignored.
@@ -167,6 +218,59 @@ pub struct Node {
     #[arg(long, env)]
     pub peer_list_url: Option<Url>,
 
+    /// Interval in seconds for refetching the peer list from `peer_list_url`
+    ///
+    /// Peers added to the list get dialed, peers removed from it are no
+    /// longer dialed. Without it, the list is only refetched when requested
+    /// with the `refreshPeers` GraphQL mutation or the
+    /// `/admin/refresh-peers` endpoint.
+    #[arg(long, env)]
+    pub peer_list_refresh_interval: Option<u64>,
+
+    /// URL of a trusted node's HTTP server to bootstrap from
+    ///
+    /// The snarked ledger and the staged ledger parts of the root block are
+    /// downloaded from it instead of being synced from peers, which takes
+    /// much less time. Downloaded ledgers are only used if they're signed
+    /// by `--bootstrap-peer-id` and their hashes match the best tip received
+    /// from peers. If the download fails, the ledgers are synced from peers.
+    #[arg(long, env, requires = "bootstrap_peer_id")]
+    pub bootstrap_from: Option<Url>,
+
+    /// Peer id of the `--bootstrap-from` node, in libp2p format
+    ///
+    /// Downloaded ledgers must be signed with its p2p key.
+    #[arg(long, env, requires = "bootstrap_from")]
+    pub bootstrap_peer_id: Option<libp2p_identity::PeerId>,
+
+    /// Token for downloading ledgers from `/bootstrap`
+    ///
+    /// Nodes on other hosts must send it as `Authorization: Bearer <token>`
+    /// to bootstrap from this node, without it only nodes on this host
+    /// can. It's also sent when downloading ledgers from the
+    /// `--bootstrap-from` node.
+    #[arg(long, env)]
+    pub bootstrap_token: Option<String>,
+
+    /// URL of a bucket with precomputed blocks to use during catchup
+    ///
+    /// Blocks that peers can't serve are downloaded from it, which avoids
+    /// relying on peers that keep old blocks. Blocks must be named as
+    /// archive nodes upload them, `<network>-<height>-<state_hash>.json`.
+    /// Supports public `gs://<bucket>/<path>` and `s3://<bucket>/<path>`
+    /// buckets, and `http(s)://` URLs. Downloaded blocks are only used if
+    /// their hash matches the chain received from peers.
+    #[arg(long, env)]
+    pub precomputed_blocks_url: Option<Url>,
+
+    /// Number of blocks to keep a summary of after they leave the frontier
+    ///
+    /// Hashes, heights and ledger hashes of recently finalized blocks stay
+    /// available from the `blockSummary` GraphQL query, without an archive.
+    /// Default: 290
+    #[arg(long, env)]
+    pub root_history_size: Option<usize>,
+
     /// Maximum number of peer connections to maintain
     ///
     /// The node will attempt to maintain up to this many connections
@@ -174,6 +278,104 @@ pub struct Node {
     #[arg(long, default_value = "100")]
     pub max_peers: usize,
 
+    /// Maximum number of snarks sent to a WebRTC peer in one message
+    ///
+    /// Batching snarks reduces the per-message overhead when propagating
+    /// a lot of snark work. Peers running older versions can't decode
+    /// batches and disconnect, so only raise it once they've upgraded.
+    /// Default: 1
+    #[arg(long, env, default_value = "1")]
+    pub snarks_per_message: usize,
+
+    /// Maximum number of events buffered before low priority ones get dropped
+    ///
+    /// Under load, blocks, snarks and transactions received on WebRTC
+    /// propagation channels are dropped (transactions first, then snarks,
+    /// then blocks). The channel then requests the next ones, so the peer
+    /// stays connected. Other events, including libp2p data, are never
+    /// dropped.
+    #[arg(long, env, default_value_t = EventQueueConfig::default().capacity)]
+    pub event_queue_capacity: usize,
+
+    /// Which low priority event to drop when the event queue is full
+    ///
+    /// Available policies: `drop-oldest` (default), `drop-newest`
+    #[arg(long, env, default_value = "drop-oldest")]
+    pub event_queue_shed_policy: EventShedPolicy,
+
+    /// Number of threads verifying snark works received from peers
+    ///
+    /// Works and transactions are verified on separate pools, so that a
+    /// flood of one can't delay the other. Default: half of the cores
+    #[arg(long, env)]
+    pub work_verify_threads: Option<usize>,
+
+    /// Number of threads verifying transactions received from peers
+    ///
+    /// Default: a quarter of the cores
+    #[arg(long, env)]
+    pub transaction_verify_threads: Option<usize>,
+
+    /// Max number of zkApp proofs of a batch of transactions verified in
+    /// parallel
+    ///
+    /// The proofs get their own threads, on top of the transaction
+    /// verification threads. `1` verifies them one after another
+    #[arg(long, env, default_value_t = 1)]
+    pub zkapp_verify_concurrency: usize,
+
+    /// Number of verification results to keep, per kind of proof
+    ///
+    /// Blocks, transactions and snark works received again aren't verified
+    /// again while their result is kept. `0` disables the cache
+    #[arg(long, env, default_value_t = SnarkVerifyCacheConfig::default().capacity)]
+    pub snark_verify_cache_size: usize,
+
+    /// Which verification result to evict when the cache is full
+    ///
+    /// Available policies: `lru` (default), `fifo`
+    #[arg(long, env, default_value = "lru")]
+    pub snark_verify_cache_eviction: SnarkVerifyCacheEviction,
+
+    /// Start even if the SRS or the verifier indices don't match the
+    /// known-good ones of the network
+    ///
+    /// The mismatch is logged as a warning instead. The node may then fail
+    /// to verify valid proofs, or accept invalid ones
+    #[arg(long, env)]
+    pub skip_verifier_digest_check: bool,
+
+    /// Maximum time in seconds a transaction can stay in the pool
+    ///
+    /// Transactions still in the pool after this long are removed, even if
+    /// they didn't reach their `valid_until` slot. `0` disables the limit.
+    /// Default: 12 hours on devnet, unlimited on mainnet
+    #[arg(long, env)]
+    pub tx_pool_max_lifetime: Option<u64>,
+
+    /// What to do with zkApp commands from the gossip network which can't
+    /// be verified in time
+    ///
+    /// `drop` doesn't relay them, `delay` (default) relays them once
+    /// verified however long it takes, and `forward-unverified` relays them
+    /// without waiting, logging them as forwarded unverified
+    #[arg(long, env, default_value = "delay")]
+    pub zkapp_relay_policy: ZkappRelayPolicy,
+
+    /// Time in seconds allowed to verify zkApp commands from the gossip
+    /// network, before `--zkapp-relay-policy` applies
+    #[arg(long, env, default_value_t = ZkappRelayConfig::default().budget.as_secs())]
+    pub zkapp_relay_budget: u64,
+
+    /// Fee increase, in nanomina, required to replace a transaction in the
+    /// pool with one from the same sender and with the same nonce
+    ///
+    /// The new transaction must pay at least this much more than the
+    /// replaced one, plus the fees of the following transactions of the
+    /// sender it invalidates.
+    #[arg(long, env, default_value_t = DEFAULT_REPLACE_FEE.as_u64())]
+    pub tx_pool_replace_fee: u64,
+
     /// Run the node in seed mode. No default peers will be added.
     #[arg(long, env)]
     pub seed: bool,
@@ -191,6 +393,24 @@ pub struct Node {
     #[arg(long, env, default_value = "seq", requires = "snarker")]
     pub snarker_strategy: SnarkerStrategy,
 
+    /// HTTP server of another node to fetch snark jobs from
+    ///
+    /// The idle worker works on the job paying best among the jobs of these
+    /// nodes, and submits the work only to the node which offered it. Jobs
+    /// of our own snark pool are worked on when none of them has a job
+    /// for our fee. Can be given several times.
+    #[arg(long = "snarker-coordinator", requires = "snarker")]
+    pub snarker_coordinators: Vec<Url>,
+
+    /// Token for submitting snark work to `/snarker/work`
+    ///
+    /// Snarkers on other hosts must send it as `Authorization: Bearer
+    /// <token>` to submit work to this node, without it only snarkers on
+    /// this host can. It's also sent with the work submitted to the
+    /// `--snarker-coordinator` nodes.
+    #[arg(long, env)]
+    pub snarker_work_token: Option<String>,
+
     /// Enable block producer with this key file
     ///
     /// MINA_PRIVKEY_PASS must be set to decrypt the keyfile if it is password-protected
@@ -206,10 +426,55 @@ pub struct Node {
     /// of a block.
     ///
     /// Warning: If the key is from a zkApp account, the account's
-    /// receive permission must be None.
+    /// receive permission must be None. The node checks it in the best tip
+    /// ledger at startup and once per epoch, and logs a
+    /// `CoinbaseReceiverCannotReceive` error otherwise.
     #[arg(long, requires = "producer")]
     pub coinbase_receiver: Option<AccountPublicKey>,
 
+    /// Max fee, in nanomina, the block producer pays for a single completed
+    /// snark work included in its blocks.
+    #[arg(long, env, requires = "producer")]
+    pub max_snark_fee_per_work: Option<u64>,
+
+    /// Max sum of the fees, in nanomina, the block producer pays for the
+    /// completed snark works included in a single block.
+    #[arg(long, env, requires = "producer")]
+    pub max_snark_fee_per_block: Option<u64>,
+
+    /// Run a local development chain, without connecting to any network
+    ///
+    /// The node starts a new chain whose genesis ledger funds a single
+    /// built-in account, printed at startup, and produces a block with it on
+    /// every slot. P2P is disabled and neither blocks nor snark work are
+    /// proven, while the GraphQL and HTTP APIs are available as usual, which
+    /// makes it suitable for developing and testing zkApps and tools against
+    /// the node.
+    ///
+    /// The node does the snark work its blocks need itself, with the
+    /// built-in account and no fee.
+    ///
+    /// # Example
+    ///
+    /// ```bash
+    /// mina node --dev-local --work-dir /tmp/mina-dev
+    /// ```
+    #[arg(
+        long,
+        env = "MINA_DEV_LOCAL",
+        conflicts_with_all = [
+            "producer",
+            "run_snarker",
+            "peers",
+            "peer_list_file",
+            "peer_list_url",
+            "seed",
+            "bootstrap_from",
+            "config",
+        ],
+    )]
+    pub dev_local: bool,
+
     /// Enable recording of node state and actions for debugging and replay
     ///
     /// Recording captures the node's state transitions and input actions,
@@ -235,6 +500,22 @@ pub struct Node {
     #[arg(long, default_value = "none", env)]
     pub record: String,
 
+    /// Make the time of the node go by this many times faster than the wall
+    /// clock, on test networks
+    ///
+    /// Slots, transaction pool expiry and block production all follow the
+    /// accelerated time. All nodes of the network must use the same speed
+    /// and the same `--time-acceleration-origin`. Default: 1, real time
+    #[arg(long, env, default_value = "1")]
+    pub time_acceleration: u32,
+
+    /// Unix timestamp in milliseconds since which the time is accelerated
+    ///
+    /// Default: the genesis timestamp with `--dev-local`, the start of the
+    /// node otherwise
+    #[arg(long, env)]
+    pub time_acceleration_origin: Option<u64>,
+
     /// Do not use peers discovery.
     #[arg(long)]
     pub no_peers_discovery: bool,
@@ -244,6 +525,14 @@ pub struct Node {
     #[arg(short = 'c', long, env)]
     pub config: Option<PathBuf>,
 
+    /// Node configuration file (`mina.toml`, or `mina.yaml`) with the values
+    /// of these options.
+    ///
+    /// Options set with flags or environment variables take precedence over
+    /// the ones set in the file. See `mina config print-effective`.
+    #[arg(long, env = "MINA_NODE_CONFIG")]
+    pub node_config: Option<PathBuf>,
+
     /// Enable local precomputed storage.
     ///
     /// This option requires the following environment variables to be set:
@@ -278,11 +567,68 @@ pub struct Node {
     #[arg(long, env)]
     pub archive_aws_storage: bool,
 
+    /// Keep the actions and events emitted by zkApp commands, to serve them
+    /// with the `actions` and `events` GraphQL queries.
+    ///
+    /// They are saved to `zkapp-actions-events.json` in the working
+    /// directory, for about two epochs of finalized blocks.
+    #[arg(long, env)]
+    pub archive_zkapp_actions_events: bool,
+
     #[arg(long, env)]
     pub rng_seed: Option<String>,
 }
 
 impl Node {
+    /// Loads the values from the `--node-config` file, unless they were set
+    /// with flags or environment variables according to `matches`.
+    pub fn load_node_config(&mut self, matches: &clap::ArgMatches) -> anyhow::Result<()> {
+        let Some(path) = self.node_config.clone() else {
+            return Ok(());
+        };
+        NodeConfigFile::load(&path)?
+            .apply(self, matches)
+            .with_context(|| format!("node config file {path:?}"))
+    }
+
+    /// Constants of the network `network_name`, which the `proof` section of
+    /// the config file must match, with the minimum user command fee from
+    /// its `daemon` section.
+    pub fn constraint_constants(
+        &self,
+        network_name: &str,
+    ) -> anyhow::Result<Option<ConstraintConstants>> {
+        #[derive(serde::Deserialize)]
+        struct Sections {
+            daemon: Option<node::daemon_json::Daemon>,
+            proof: Option<node::daemon_json::Proof>,
+        }
+
+        let Some(config) = &self.config else {
+            return Ok(None);
+        };
+        let reader = File::open(config).with_context(|| format!("config file {config:?}"))?;
+        let Sections { daemon, proof } =
+            serde_json::from_reader(reader).with_context(|| format!("config file {config:?}"))?;
+        let minimum_user_command_fee = match &daemon {
+            Some(daemon) => daemon.minimum_user_command_fee()?,
+            None => None,
+        };
+        if proof.is_none() && minimum_user_command_fee.is_none() {
+            return Ok(None);
+        }
+
+        let base = NetworkConfig::by_name(network_name).map_err(anyhow::Error::msg)?;
+        let mut constants = match proof {
+            Some(proof) => proof.constraint_constants(base.constraint_constants)?,
+            None => base.constraint_constants.clone(),
+        };
+        if let Some(fee) = minimum_user_command_fee {
+            constants.minimum_user_command_fee = fee;
+        }
+        Ok(Some(constants))
+    }
+
     pub fn run(self) -> anyhow::Result<()> {
         let work_dir = shellexpand::full(&self.work_dir).unwrap().into_owned();
 
@@ -307,7 +653,34 @@ impl Node {
             .build_global()
             .context("failed to initialize threadpool")?;
 
+        let _work_dir_lock = WorkDirLock::acquire(Path::new(&work_dir))?;
+
+        if self.dev_local
+            && (self.producer_key.is_some() || self.config.is_some() || self.run_snarker.is_some())
+        {
+            anyhow::bail!(
+                "`--dev-local` can't be used with `--producer-key`, `--config` or `--run-snarker`"
+            );
+        }
+
+        let mut time_acceleration_origin_ms = self.time_acceleration_origin;
         let (daemon_conf, genesis_conf) = match self.config {
+            _ if self.dev_local => {
+                // Genesis is one slot in the past, so that the first block is
+                // produced right away.
+                let now_ms = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .context("system time before unix epoch")?
+                    .as_millis() as u64;
+                let genesis_timestamp_ms = now_ms.saturating_sub(
+                    mina_core::constants::constraint_constants().block_window_duration_ms,
+                );
+                time_acceleration_origin_ms.get_or_insert(genesis_timestamp_ms);
+                (
+                    node::daemon_json::Daemon::DEFAULT,
+                    Arc::new(GenesisConfig::dev_local(genesis_timestamp_ms)),
+                )
+            }
             Some(config) => {
                 let reader = File::open(config).context("config file {config:?}")?;
                 let config: node::daemon_json::DaemonJson =
@@ -347,6 +720,17 @@ impl Node {
         let mut node_builder: NodeBuilder =
             NodeBuilder::new(custom_rng_seed, daemon_conf, genesis_conf);
 
+        if self.time_acceleration > 1 {
+            let time_source = match time_acceleration_origin_ms {
+                Some(origin_ms) => TimeSource::accelerated_since(
+                    redux::Timestamp::new(origin_ms.saturating_mul(1_000_000)),
+                    self.time_acceleration,
+                ),
+                None => TimeSource::accelerated(self.time_acceleration),
+            };
+            node_builder.time_source(time_source);
+        }
+
         // let genesis_config = match self.config {
         //     Some(config_path) => GenesisConfig::DaemonJsonFile(config_path).into(),
         //     None => node::config::DEVNET_CONFIG.clone(),
@@ -388,6 +772,21 @@ impl Node {
             return Err(anyhow::anyhow!(error));
         }
 
+        if !self.libp2p_previous_keypair.is_empty() {
+            let Some(password) = &self.libp2p_password else {
+                anyhow::bail!("previous keyfiles are specified, but `MINA_LIBP2P_PASS` is not set");
+            };
+            let previous_keys = self
+                .libp2p_previous_keypair
+                .iter()
+                .map(|key_file| {
+                    SecretKey::from_encrypted_file(key_file, password)
+                        .with_context(|| format!("reading previous keypair file {key_file}"))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            node_builder.p2p_previous_sec_keys(previous_keys);
+        }
+
         node_builder.p2p_libp2p_port(self.libp2p_port);
 
         node_builder.external_addrs(
@@ -397,17 +796,47 @@ impl Node {
         );
 
         node_builder.p2p_max_peers(self.max_peers);
+        node_builder.p2p_snarks_per_message(self.snarks_per_message);
         self.seed.then(|| node_builder.p2p_seed_node());
         self.no_peers_discovery
             .then(|| node_builder.p2p_no_discovery());
 
-        node_builder.initial_peers(self.peers);
+        node_builder.initial_peer_addrs(self.peers);
         if let Some(path) = self.peer_list_file {
             node_builder.initial_peers_from_file(path)?;
         }
         if let Some(url) = self.peer_list_url {
             node_builder.initial_peers_from_url(url)?;
+            if let Some(interval) = self.peer_list_refresh_interval {
+                node_builder.peer_list_refresh_interval(Duration::from_secs(interval));
+            }
+        } else if self.peer_list_refresh_interval.is_some() {
+            anyhow::bail!("`--peer-list-refresh-interval` requires `--peer-list-url`");
+        }
+        match (self.bootstrap_from, self.bootstrap_peer_id) {
+            (Some(url), Some(peer_id)) => {
+                let peer_id = PeerId::try_from(peer_id)
+                    .with_context(|| format!("invalid bootstrap peer id: {peer_id}"))?;
+                node_builder.bootstrap_from(url, peer_id);
+            }
+            (None, None) => {}
+            _ => anyhow::bail!("`--bootstrap-from` and `--bootstrap-peer-id` must be set together"),
+        }
+        if let Some(url) = self.precomputed_blocks_url {
+            node_builder.precomputed_blocks_url(url)?;
         }
+        if let Some(size) = self.root_history_size {
+            node_builder.root_history_size(size);
+        }
+        if let Some(secs) = self.tx_pool_max_lifetime {
+            node_builder
+                .tx_pool_max_lifetime(Some(Duration::from_secs(secs)).filter(|d| !d.is_zero()));
+        }
+        node_builder.tx_pool_zkapp_relay(ZkappRelayConfig {
+            policy: self.zkapp_relay_policy,
+            budget: Duration::from_secs(self.zkapp_relay_budget),
+        });
+        node_builder.tx_pool_replace_fee(Fee::from_u64(self.tx_pool_replace_fee));
 
         let block_verifier_index = BlockVerifier::make();
         let work_verifier_index = TransactionVerifier::make();
@@ -415,6 +844,18 @@ impl Node {
             .block_verifier_index(block_verifier_index.clone())
             .work_verifier_index(work_verifier_index.clone());
 
+        if self.dev_local {
+            let key = GenesisConfig::dev_local_key();
+            node::core::info!(
+                node::core::log::system_time();
+                summary = "running a local development chain, p2p is disabled",
+                account = key.public_key().to_string(),
+                private_key = key.to_string(),
+                balance_mina = GenesisConfig::DEV_LOCAL_BALANCE,
+            );
+            node_builder.dev_local(key);
+        }
+
         if let Some(producer_key_path) = self.producer_key {
             let password = &self.producer_key_password;
             mina_core::thread::spawn(|| {
@@ -429,6 +870,12 @@ impl Node {
                     .custom_coinbase_receiver(pub_key.into())
                     .unwrap();
             }
+            node_builder
+                .block_producer_work_fee_policy(BlockProducerWorkFeePolicy {
+                    max_fee_per_work: self.max_snark_fee_per_work,
+                    max_fee_per_block: self.max_snark_fee_per_block,
+                })
+                .unwrap();
         }
 
         let archive_storage_options = ArchiveStorageOptions::from_iter(
@@ -470,16 +917,50 @@ impl Node {
 
             node_builder.archive(archive_storage_options, work_dir.clone());
         }
+        if self.archive_zkapp_actions_events {
+            node_builder
+                .zkapp_actions_events(Path::new(&work_dir).join("zkapp-actions-events.json"));
+        }
 
         if let Some(sec_key) = self.run_snarker {
             node_builder.snarker(sec_key, self.snarker_fee, self.snarker_strategy);
+            node_builder.snarker_coordinators(self.snarker_coordinators)?;
+        }
+
+        if let Some(token) = self.snarker_work_token {
+            node_builder.snarker_work_token(token);
+        }
+        if let Some(token) = self.bootstrap_token {
+            node_builder.bootstrap_token(token);
         }
 
         mina_core::set_work_dir(work_dir.clone().into());
 
+        let http_port = startup::available_http_port(self.port, &[self.libp2p_port])?;
         node_builder
-            .http_server(self.port)
+            .http_server(http_port)
             .gather_stats()
+            .sync_history(Path::new(&work_dir).join("sync-history.json"))
+            .crash_bundle_dir(work_dir.clone().into())
+            .event_queue_config(EventQueueConfig {
+                capacity: self.event_queue_capacity,
+                shed_policy: self.event_queue_shed_policy,
+            })
+            .snark_verify_pools_config({
+                let default = SnarkVerifyPoolsConfig::default();
+                SnarkVerifyPoolsConfig {
+                    work_threads: self.work_verify_threads.unwrap_or(default.work_threads),
+                    user_command_threads: self
+                        .transaction_verify_threads
+                        .unwrap_or(default.user_command_threads),
+                }
+            })
+            .snark_verify_cache_config(SnarkVerifyCacheConfig {
+                capacity: self.snark_verify_cache_size,
+                eviction: self.snark_verify_cache_eviction,
+            })
+            .snark_verify_concurrency(self.zkapp_verify_concurrency)
+            .skip_verifier_digest_check(self.skip_verifier_digest_check)
             .record(match self.record.trim() {
                 "none" => Recorder::None,
                 "state-with-input-actions" => Recorder::only_input_actions(work_dir),
@@ -0,0 +1,131 @@
+//! Checks run before the node starts, to recover from a previous instance
+//! which didn't shut down cleanly.
+
+use std::{
+    io::{ErrorKind, Write},
+    net::{Ipv4Addr, TcpListener},
+    path::Path,
+};
+
+use anyhow::Context;
+use ledger::ondisk::LockedFile;
+
+/// Name of the lock file in the working directory, containing the pid of
+/// the node using it.
+pub const LOCK_FILE_NAME: &str = "mina.lock";
+
+/// Number of ports tried after the configured HTTP port, if it's taken.
+const HTTP_PORT_FALLBACK_ATTEMPTS: u16 = 10;
+
+/// Lock on the working directory, an exclusive `flock` on its lock file.
+/// The system releases it when the process exits, so a node which didn't
+/// shut down cleanly doesn't leave it behind.
+pub struct WorkDirLock {
+    _file: LockedFile,
+}
+
+impl WorkDirLock {
+    /// Locks `work_dir` for this process, fails if another process holds
+    /// the lock. The pid written to the lock file is only used to tell
+    /// which process that is.
+    pub fn acquire(work_dir: &Path) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(work_dir)
+            .with_context(|| format!("creating working directory {work_dir:?}"))?;
+        let path = work_dir.join(LOCK_FILE_NAME);
+
+        let mut options = std::fs::OpenOptions::new();
+        options.read(true).write(true).create(true).truncate(false);
+        let mut file = match LockedFile::try_open_exclusively(&path, &options) {
+            Ok(file) => file,
+            Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                let pid = std::fs::read_to_string(&path)
+                    .ok()
+                    .map(|pid| pid.trim().to_owned())
+                    .filter(|pid| !pid.is_empty())
+                    .map(|pid| format!(" (pid {pid})"))
+                    .unwrap_or_default();
+                anyhow::bail!("working directory {work_dir:?} is used by another node{pid}");
+            }
+            Err(err) => return Err(err).with_context(|| format!("locking {path:?}")),
+        };
+
+        file.set_len(0)
+            .and_then(|()| write!(file, "{}", std::process::id()))
+            .and_then(|()| file.flush())
+            .with_context(|| format!("writing lock {path:?}"))?;
+        Ok(Self { _file: file })
+    }
+}
+
+/// First port, starting from `port`, on which the HTTP server can listen.
+///
+/// Ports in `reserved` are skipped, as they are going to be bound by the
+/// node itself.
+pub fn available_http_port(port: u16, reserved: &[u16]) -> anyhow::Result<u16> {
+    let candidates = (port..=port.saturating_add(HTTP_PORT_FALLBACK_ATTEMPTS))
+        .filter(|candidate| !reserved.contains(candidate));
+    for candidate in candidates {
+        match TcpListener::bind((Ipv4Addr::UNSPECIFIED, candidate)) {
+            Ok(_) if candidate == port => return Ok(port),
+            Ok(_) => {
+                node::core::warn!(
+                    node::core::log::system_time();
+                    summary = format!("HTTP port {port} is taken, using port {candidate} instead"),
+                    configured_port = port,
+                    port = candidate,
+                );
+                return Ok(candidate);
+            }
+            Err(err) if err.kind() == ErrorKind::AddrInUse => continue,
+            Err(err) => return Err(err).with_context(|| format!("binding HTTP port {candidate}")),
+        }
+    }
+    anyhow::bail!(
+        "HTTP port {port} and the next {HTTP_PORT_FALLBACK_ATTEMPTS} ports are taken, \
+         is another node running? Use `--port` to choose another one"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_is_exclusive() {
+        let work_dir = tempfile::tempdir().unwrap();
+        let lock = WorkDirLock::acquire(work_dir.path()).unwrap();
+        let contents = std::fs::read_to_string(work_dir.path().join(LOCK_FILE_NAME)).unwrap();
+        assert_eq!(contents, std::process::id().to_string());
+
+        let err = WorkDirLock::acquire(work_dir.path()).err().unwrap();
+        assert!(
+            err.to_string()
+                .contains(&format!("(pid {})", std::process::id())),
+            "{err}"
+        );
+
+        drop(lock);
+        assert!(WorkDirLock::acquire(work_dir.path()).is_ok());
+    }
+
+    #[test]
+    fn unlocked_lock_file_is_reused() {
+        let work_dir = tempfile::tempdir().unwrap();
+        // Left by a node which didn't shut down cleanly, with a longer pid.
+        std::fs::write(work_dir.path().join(LOCK_FILE_NAME), "123456789").unwrap();
+
+        let _lock = WorkDirLock::acquire(work_dir.path()).unwrap();
+        let contents = std::fs::read_to_string(work_dir.path().join(LOCK_FILE_NAME)).unwrap();
+        assert_eq!(contents, std::process::id().to_string());
+    }
+
+    #[test]
+    fn taken_http_port_falls_back() {
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let fallback = available_http_port(port, &[]).unwrap();
+        assert_ne!(fallback, port);
+        assert!(fallback > port);
+    }
+}
@@ -1,6 +1,9 @@
 pub mod precalculate_block_verifier_index_and_srs;
 pub use precalculate_block_verifier_index_and_srs::PrecalculateBlockVerifierIndexAndSrs;
 
+pub mod verify_dry_run;
+pub use verify_dry_run::VerifyDryRun;
+
 #[derive(Debug, clap::Args)]
 pub struct Snark {
     #[command(subcommand)]
@@ -10,12 +13,14 @@ pub struct Snark {
 #[derive(Debug, clap::Subcommand)]
 pub enum SnarkCommand {
     PrecalculateBlockVerifierIndexAndSrs(PrecalculateBlockVerifierIndexAndSrs),
+    VerifyDryRun(VerifyDryRun),
 }
 
 impl Snark {
     pub fn run(self) -> anyhow::Result<()> {
         match self.command {
             SnarkCommand::PrecalculateBlockVerifierIndexAndSrs(v) => v.run(),
+            SnarkCommand::VerifyDryRun(v) => v.run(),
         }
     }
 }
@@ -0,0 +1,75 @@
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::Context;
+use console::style;
+use node::rpc::RpcSnarkVerifyDryRunResponse;
+
+/// Verifies a block, snark works or a user command with a running node,
+/// without the node acting on the result, and prints each check performed.
+#[derive(Debug, clap::Args)]
+pub struct VerifyDryRun {
+    /// HTTP server of the node. Only local requests are served.
+    #[arg(long, default_value = "http://localhost:3000", env = "MINA_NODE_URL")]
+    pub node: String,
+
+    /// What the input file contains.
+    #[arg(long, value_enum)]
+    pub kind: VerifyDryRunKind,
+
+    /// JSON file with a block, a list of snark works, or an object with the
+    /// user `command` and the `verification_keys` of its zkApp accounts.
+    pub input: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum VerifyDryRunKind {
+    Block,
+    Work,
+    UserCommand,
+}
+
+impl VerifyDryRunKind {
+    fn tag(self) -> &'static str {
+        match self {
+            Self::Block => "block",
+            Self::Work => "work",
+            Self::UserCommand => "user_command",
+        }
+    }
+}
+
+impl VerifyDryRun {
+    pub fn run(self) -> anyhow::Result<()> {
+        let file = std::fs::File::open(&self.input)
+            .with_context(|| format!("failed to open {}", self.input.display()))?;
+        let value: serde_json::Value = serde_json::from_reader(std::io::BufReader::new(file))
+            .with_context(|| format!("failed to parse {}", self.input.display()))?;
+        let input = serde_json::Value::Object([(self.kind.tag().to_owned(), value)].into());
+
+        // Verifying a block or a batch of works takes a few seconds.
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()?;
+        let url = format!("{}/snark/verify/dry-run", self.node.trim_end_matches('/'));
+        let report: RpcSnarkVerifyDryRunResponse = client
+            .post(&url)
+            .json(&input)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json())
+            .with_context(|| format!("failed to run the verification with {url}"))?;
+
+        for check in &report.checks {
+            match &check.error {
+                None => println!("{} {}", style("pass").green(), check.name),
+                Some(error) => println!("{} {}: {error}", style("FAIL").red(), check.name),
+            }
+        }
+        println!("took {:?}", report.duration);
+
+        if !report.is_valid() {
+            anyhow::bail!("verification failed");
+        }
+        Ok(())
+    }
+}
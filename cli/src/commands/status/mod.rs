@@ -0,0 +1,175 @@
+use std::{fmt::Write, time::Duration};
+
+use anyhow::Context;
+use console::{style, Term};
+use node::rpc::{RpcNodeOverview, RpcNodeOverviewGetResponse};
+
+/// Shows the sync status, peers, slot, pools and recent blocks of a running
+/// node.
+#[derive(Debug, clap::Args)]
+pub struct Status {
+    /// HTTP server of the node.
+    #[arg(long, default_value = "http://localhost:3000", env = "MINA_NODE_URL")]
+    pub node: String,
+
+    /// Keep refreshing the status until interrupted.
+    #[arg(long, short)]
+    pub watch: bool,
+
+    /// Refresh interval in seconds, with `--watch`.
+    #[arg(long, default_value_t = 2)]
+    pub interval: u64,
+}
+
+impl Status {
+    pub fn run(self) -> anyhow::Result<()> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        let url = format!("{}/overview", self.node.trim_end_matches('/'));
+        let fetch = || -> anyhow::Result<RpcNodeOverviewGetResponse> {
+            client
+                .get(&url)
+                .send()
+                .and_then(|response| response.error_for_status())
+                .and_then(|response| response.json())
+                .with_context(|| format!("failed to get the overview from {url}"))
+        };
+
+        if !self.watch {
+            print!("{}", render(&fetch()?));
+            return Ok(());
+        }
+
+        let term = Term::stdout();
+        let interval = Duration::from_secs(self.interval.max(1));
+        loop {
+            // Errors are shown instead of the status, the node may be
+            // restarting.
+            let screen = match fetch() {
+                Ok(overview) => render(&overview),
+                Err(err) => format!("{}\n", style(format!("{err:#}")).red()),
+            };
+            term.clear_screen()?;
+            term.write_str(&screen)?;
+            term.write_line(&format!(
+                "\n{}",
+                style(format!(
+                    "{url}, refreshed every {}s, Ctrl+C to exit",
+                    interval.as_secs()
+                ))
+                .dim()
+            ))?;
+            std::thread::sleep(interval);
+        }
+    }
+}
+
+fn render(overview: &RpcNodeOverview) -> String {
+    let mut out = String::new();
+    let ago = |time: redux::Timestamp| {
+        overview
+            .time
+            .checked_sub(time)
+            .map_or_else(|| "now".to_owned(), |age| format!("{}s ago", age.as_secs()))
+    };
+
+    let sync = if overview.synced {
+        style("synced".to_owned()).green()
+    } else {
+        style(format!(
+            "{} ({})",
+            overview.sync.status, overview.sync.phase
+        ))
+        .yellow()
+    };
+    let _ = writeln!(out, "{}  {sync}", style("Sync").bold());
+    if let Some(target) = overview.sync.target.as_ref().filter(|_| !overview.synced) {
+        let _ = writeln!(
+            out,
+            "      target {} at height {}",
+            target.hash, target.height
+        );
+    }
+
+    // Shown right after the sync status, a diverging ledger is likely
+    // to end in a fork.
+    for divergence in &overview.ledger_hash_divergences {
+        let _ = writeln!(
+            out,
+            "{}",
+            style(format!("Ledger divergence: {divergence}"))
+                .red()
+                .bold()
+        );
+    }
+
+    let _ = write!(out, "{}  ", style("Slot").bold());
+    match (overview.current_global_slot, &overview.best_tip) {
+        (Some(current), Some(best_tip)) => {
+            let _ = writeln!(
+                out,
+                "{current}, best tip at {} ({} behind)",
+                best_tip.global_slot,
+                current.saturating_sub(best_tip.global_slot)
+            );
+        }
+        (current, _) => {
+            let _ = writeln!(
+                out,
+                "{}",
+                current.map_or_else(|| "unknown".to_owned(), |slot| slot.to_string())
+            );
+        }
+    }
+
+    let peers = &overview.peers;
+    let _ = writeln!(
+        out,
+        "{} {} connected, {} connecting, {} disconnected, highest best tip {}",
+        style("Peers").bold(),
+        peers.connected,
+        peers.connecting,
+        peers.disconnected,
+        peers
+            .best_tip_height
+            .map_or_else(|| "unknown".to_owned(), |height| height.to_string())
+    );
+
+    let _ = writeln!(
+        out,
+        "{} {} transactions ({} candidates), {} snarks for {} jobs",
+        style("Pools").bold(),
+        overview.transaction_pool.transactions,
+        overview.transaction_pool.transaction_candidates,
+        overview.snark_pool.snarks,
+        overview.snark_pool.total_jobs
+    );
+
+    if let Some(producer) = &overview.block_producer {
+        let _ = writeln!(
+            out,
+            "{} {}, {} won slots ahead",
+            style("Producer").bold(),
+            producer.public_key,
+            producer.future_won_slots.len()
+        );
+    }
+
+    let _ = writeln!(out, "\n{}", style("Recent blocks").bold());
+    if overview.recent_blocks.is_empty() {
+        let _ = writeln!(out, "  none yet");
+    }
+    for block in &overview.recent_blocks {
+        let _ = writeln!(
+            out,
+            "  {:>8}  slot {:>8}  {}  {:>8}  by {}",
+            block.height,
+            block.global_slot,
+            block.hash,
+            ago(block.timestamp),
+            block.producer
+        );
+    }
+    out
+}
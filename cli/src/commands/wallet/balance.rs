@@ -1,6 +1,7 @@
 use anyhow::Context;
+use mina_graphql_client::{blocking::GraphQLClient, queries, ClientConfig};
 use mina_node_account::AccountSecretKey;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use std::path::PathBuf;
 
 #[derive(Debug, clap::Args)]
@@ -40,49 +41,6 @@ pub enum OutputFormat {
     Json,
 }
 
-#[derive(Serialize)]
-struct GraphQLRequest {
-    query: String,
-    variables: serde_json::Value,
-}
-
-#[derive(Deserialize, Debug)]
-struct GraphQLResponse {
-    data: Option<DataResponse>,
-    errors: Option<Vec<GraphQLError>>,
-}
-
-#[derive(Deserialize, Debug)]
-struct DataResponse {
-    account: Option<AccountResponse>,
-}
-
-#[derive(Deserialize, Debug)]
-struct AccountResponse {
-    balance: BalanceResponse,
-    nonce: String,
-    #[serde(rename = "delegateAccount")]
-    delegate_account: Option<DelegateAccount>,
-}
-
-#[derive(Deserialize, Debug)]
-struct BalanceResponse {
-    total: String,
-    liquid: Option<String>,
-    locked: Option<String>,
-}
-
-#[derive(Deserialize, Debug)]
-struct DelegateAccount {
-    #[serde(rename = "publicKey")]
-    public_key: String,
-}
-
-#[derive(Deserialize, Debug)]
-struct GraphQLError {
-    message: String,
-}
-
 #[derive(Serialize, Debug)]
 struct BalanceOutput {
     account: String,
@@ -119,58 +77,10 @@ impl Balance {
             anyhow::bail!("Either --address or --from must be provided to specify the account");
         };
 
-        // GraphQL query
-        let query = r#"
-            query GetBalance($publicKey: String!) {
-                account(publicKey: $publicKey) {
-                    balance {
-                        total
-                        liquid
-                        locked
-                    }
-                    nonce
-                    delegateAccount {
-                        publicKey
-                    }
-                }
-            }
-        "#;
-
-        let variables = serde_json::json!({
-            "publicKey": public_key
-        });
-
-        let request = GraphQLRequest {
-            query: query.to_string(),
-            variables,
-        };
-
-        // Make the GraphQL request
-        let client = reqwest::blocking::Client::new();
-        let response = client
-            .post(&self.endpoint)
-            .json(&request)
-            .send()
-            .with_context(|| format!("Failed to connect to GraphQL endpoint: {}", self.endpoint))?;
-
-        if !response.status().is_success() {
-            anyhow::bail!("GraphQL request failed with status: {}", response.status());
-        }
-
-        let graphql_response: GraphQLResponse = response
-            .json()
-            .context("Failed to parse GraphQL response")?;
-
-        // Check for GraphQL errors
-        if let Some(errors) = graphql_response.errors {
-            let error_messages: Vec<String> = errors.iter().map(|e| e.message.clone()).collect();
-            anyhow::bail!("GraphQL errors: {}", error_messages.join(", "));
-        }
-
-        // Extract account data
-        let account = graphql_response
-            .data
-            .and_then(|d| d.account)
+        let client = GraphQLClient::new(ClientConfig::new(&self.endpoint))?;
+        let account = client
+            .execute(&queries::Account::new(&public_key))?
+            .account
             .with_context(|| format!("Account not found: {}", public_key))?;
 
         // Create output structure
@@ -3,6 +3,7 @@ pub mod balance;
 pub mod generate;
 pub mod send;
 pub mod status;
+pub mod verify_receipt;
 
 use super::Network;
 use crate::exit_with_error;
@@ -25,6 +26,8 @@ pub enum WalletCommand {
     Send(send::Send),
     /// Check transaction status
     Status(status::Status),
+    /// Verify a payment proof against the receipt chain hash of the account
+    VerifyReceipt(verify_receipt::VerifyReceipt),
 }
 
 impl Wallet {
@@ -35,6 +38,7 @@ impl Wallet {
             WalletCommand::Generate(cmd) => cmd.run(),
             WalletCommand::Send(cmd) => cmd.run(network),
             WalletCommand::Status(cmd) => cmd.run(),
+            WalletCommand::VerifyReceipt(cmd) => cmd.run(),
         };
 
         // Handle errors without backtraces for wallet commands
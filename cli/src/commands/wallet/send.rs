@@ -1,4 +1,4 @@
-use std::{path::PathBuf, str::FromStr};
+use std::{path::PathBuf, str::FromStr, time::Duration};
 
 use anyhow::{Context, Result};
 use ledger::scan_state::{
@@ -9,6 +9,11 @@ use ledger::scan_state::{
         Memo,
     },
 };
+use mina_graphql_client::{
+    blocking::GraphQLClient,
+    mutations::{SendPayment, SendPaymentInput, UserCommandSignature},
+    queries, ClientConfig,
+};
 use mina_node_account::{AccountPublicKey, AccountSecretKey};
 use mina_p2p_messages::v2::MinaBaseSignedCommandStableV2;
 use mina_signer::{CompressedPubKey, Keypair, Signer};
@@ -152,46 +157,19 @@ impl Send {
         Ok(())
     }
 
+    fn graphql_client(&self, timeout: Duration) -> Result<GraphQLClient> {
+        let config = ClientConfig::new(format!("{}/graphql", self.node)).with_timeout(timeout);
+        Ok(GraphQLClient::new(config)?)
+    }
+
     fn check_node_status(&self, network: &Network) -> Result<()> {
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .context("Failed to create HTTP client")?;
-        let url = format!("{}/graphql", self.node);
-
-        // GraphQL query to check sync status and network ID
-        let query = serde_json::json!({
-            "query": r#"query {
-                syncStatus
-                networkID
-            }"#
-        });
-
-        let response = client
-            .post(&url)
-            .json(&query)
-            .send()
+        let status = self
+            .graphql_client(Duration::from_secs(30))?
+            .execute(&queries::NodeStatus)
             .context("Failed to query node status")?;
 
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to connect to node: HTTP {}", response.status());
-        }
-
-        let response_json: serde_json::Value = response
-            .json()
-            .context("Failed to parse GraphQL response")?;
-
-        // Check for GraphQL errors
-        if let Some(errors) = response_json.get("errors") {
-            let error_msg = serde_json::to_string_pretty(errors)
-                .unwrap_or_else(|_| "Unknown GraphQL error".to_string());
-            anyhow::bail!("GraphQL error: {}", error_msg);
-        }
-
         // Check sync status
-        let sync_status = response_json["data"]["syncStatus"]
-            .as_str()
-            .context("Sync status not found in GraphQL response")?;
+        let sync_status = &status.sync_status;
 
         if sync_status != "SYNCED" {
             anyhow::bail!(
@@ -203,9 +181,7 @@ impl Send {
         println!("Node is synced: {}", sync_status);
 
         // Check network ID
-        let network_id = response_json["data"]["networkID"]
-            .as_str()
-            .context("Network ID not found in GraphQL response")?;
+        let network_id = &status.network_id;
 
         // Expected network ID based on selected network
         let expected_network = match network {
@@ -227,44 +203,16 @@ impl Send {
     }
 
     fn fetch_nonce(&self, sender_pk: &CompressedPubKey) -> Result<u32> {
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .context("Failed to create HTTP client")?;
-        let url = format!("{}/graphql", self.node);
-
-        // GraphQL query to fetch account information
-        let query = serde_json::json!({
-            "query": format!(
-                r#"query {{
-                    account(publicKey: "{}") {{
-                        nonce
-                    }}
-                }}"#,
-                AccountPublicKey::from(sender_pk.clone()).to_string()
-            )
-        });
-
-        let response = client
-            .post(&url)
-            .json(&query)
-            .send()
-            .context("Failed to query account from node")?;
-
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to fetch account: HTTP {}", response.status());
-        }
-
-        let response_json: serde_json::Value = response
-            .json()
-            .context("Failed to parse GraphQL response")?;
-
-        // Extract nonce from GraphQL response
-        let nonce_str = response_json["data"]["account"]["nonce"]
-            .as_str()
-            .context("Nonce not found in GraphQL response")?;
-
-        let nonce = nonce_str
+        let public_key = AccountPublicKey::from(sender_pk.clone()).to_string();
+        let account = self
+            .graphql_client(Duration::from_secs(30))?
+            .execute(&queries::Account::new(&public_key))
+            .context("Failed to query account from node")?
+            .account
+            .with_context(|| format!("Account not found: {}", public_key))?;
+
+        let nonce = account
+            .nonce
             .parse::<u32>()
             .context("Failed to parse nonce as u32")?;
 
@@ -294,20 +242,16 @@ impl Send {
     }
 
     fn submit_transaction(&self, signed_command: SignedCommand) -> Result<String> {
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(120))
-            .build()
-            .context("Failed to create HTTP client")?;
-        let url = format!("{}/graphql", self.node);
-
         // Convert to v2 types for easier field extraction
         let signed_cmd_v2: MinaBaseSignedCommandStableV2 = (&signed_command).into();
 
         // Convert signature to GraphQL format (field and scalar as decimal strings)
-        let sig_field =
-            mina_p2p_messages::bigint::BigInt::from(signed_command.signature.rx).to_decimal();
-        let sig_scalar =
-            mina_p2p_messages::bigint::BigInt::from(signed_command.signature.s).to_decimal();
+        let signature = UserCommandSignature {
+            field: mina_p2p_messages::bigint::BigInt::from(signed_command.signature.rx)
+                .to_decimal(),
+            scalar: mina_p2p_messages::bigint::BigInt::from(signed_command.signature.s)
+                .to_decimal(),
+        };
 
         // Extract payment details from signed command
         let (receiver_pk, amount) = match &signed_cmd_v2.payload.body {
@@ -317,89 +261,23 @@ impl Send {
             _ => anyhow::bail!("Expected payment body in signed command"),
         };
 
-        let fee_payer_pk = signed_cmd_v2.payload.common.fee_payer_pk.to_string();
-
-        // Build memo field - omit if empty
-        let memo_field = if self.memo.is_empty() {
-            String::new()
-        } else {
-            format!(r#"memo: "{}""#, self.memo)
+        let common = &signed_cmd_v2.payload.common;
+        let input = SendPaymentInput {
+            from: common.fee_payer_pk.to_string(),
+            to: receiver_pk,
+            amount,
+            fee: (***common.fee).to_string(),
+            // Omit the memo if empty
+            memo: (!self.memo.is_empty()).then(|| self.memo.clone()),
+            nonce: Some((**common.nonce).to_string()),
+            valid_until: Some(common.valid_until.as_u32().to_string()),
         };
 
-        // Build GraphQL mutation
-        let mutation = format!(
-            r#"mutation {{
-                sendPayment(
-                    input: {{
-                        from: "{}"
-                        to: "{}"
-                        amount: "{}"
-                        fee: "{}"
-                        {}
-                        nonce: "{}"
-                        validUntil: "{}"
-                    }}
-                    signature: {{
-                        field: "{}"
-                        scalar: "{}"
-                    }}
-                ) {{
-                    payment {{
-                        hash
-                        id
-                    }}
-                }}
-            }}"#,
-            fee_payer_pk,
-            receiver_pk,
-            amount,
-            ***signed_cmd_v2.payload.common.fee,
-            memo_field,
-            **signed_cmd_v2.payload.common.nonce,
-            signed_cmd_v2.payload.common.valid_until.as_u32(),
-            sig_field,
-            sig_scalar,
-        );
-
-        let query = serde_json::json!({
-            "query": mutation
-        });
-
-        let response = client
-            .post(&url)
-            .json(&query)
-            .send()
+        let response = self
+            .graphql_client(Duration::from_secs(120))?
+            .execute(&SendPayment { input, signature })
             .context("Failed to submit transaction to node")?;
 
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            anyhow::bail!(
-                "Failed to submit transaction: HTTP {} - {}",
-                status,
-                error_text
-            );
-        }
-
-        let response_json: serde_json::Value = response
-            .json()
-            .context("Failed to parse GraphQL response")?;
-
-        // Check for GraphQL errors
-        if let Some(errors) = response_json.get("errors") {
-            let error_msg = serde_json::to_string_pretty(errors)
-                .unwrap_or_else(|_| "Unknown GraphQL error".to_string());
-            anyhow::bail!("GraphQL error: {}", error_msg);
-        }
-
-        // Extract transaction hash from response
-        let hash = response_json["data"]["sendPayment"]["payment"]["hash"]
-            .as_str()
-            .context("Transaction hash not found in GraphQL response")?
-            .to_string();
-
-        Ok(hash)
+        Ok(response.send_payment.payment.hash)
     }
 }
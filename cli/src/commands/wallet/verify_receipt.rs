@@ -0,0 +1,144 @@
+use anyhow::Context;
+use ledger::{
+    scan_state::transaction_logic::{receipt::verify_receipt_chain, UserCommand, WithStatus},
+    AccountId, ReceiptChainHash, TokenId,
+};
+use mina_graphql_client::{blocking::GraphQLClient, queries, ClientConfig};
+use mina_p2p_messages::v2;
+use std::{path::PathBuf, str::FromStr};
+
+#[derive(Debug, clap::Args)]
+pub struct VerifyReceipt {
+    /// Payment proof: JSON file with the receipt chain hash to start from
+    /// (`init`, the empty hash if missing) and the commands of the account
+    /// applied since (`commands`, each with its `data` and `status`), or a
+    /// proof file of the OCaml `mina advanced verify-receipt`
+    pub proof: PathBuf,
+
+    /// JSON file with a command which must be part of the proof, as the
+    /// `--payment-path` of the OCaml `mina advanced verify-receipt`
+    #[arg(long)]
+    pub payment: Option<PathBuf>,
+
+    /// Public key of the account
+    #[arg(long)]
+    pub address: String,
+
+    /// Token id of the account, the default token if not provided
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// GraphQL endpoint URL
+    #[arg(
+        long,
+        default_value = "http://localhost:3000/graphql",
+        help = "GraphQL endpoint URL"
+    )]
+    pub endpoint: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum PaymentProof {
+    WithStatus {
+        init: Option<String>,
+        commands: Vec<WithStatus<UserCommand>>,
+    },
+    /// Format of the OCaml node, the receipt chain hash followed by the
+    /// commands, without their status.
+    OCaml(String, Vec<UserCommand>),
+}
+
+impl PaymentProof {
+    fn into_parts(self) -> (Option<String>, Vec<WithStatus<UserCommand>>) {
+        match self {
+            Self::WithStatus { init, commands } => (init, commands),
+            // Like the OCaml node, commands are taken as applied
+            Self::OCaml(init, commands) => (
+                Some(init),
+                commands.into_iter().map(WithStatus::applied).collect(),
+            ),
+        }
+    }
+}
+
+impl VerifyReceipt {
+    pub fn run(self) -> anyhow::Result<()> {
+        let proof = std::fs::read(&self.proof)
+            .with_context(|| format!("Failed to read proof file: {}", self.proof.display()))?;
+        let proof: PaymentProof =
+            serde_json::from_slice(&proof).context("Failed to parse proof file")?;
+        let (init, commands) = proof.into_parts();
+
+        if let Some(payment) = &self.payment {
+            let payment = std::fs::read(payment)
+                .with_context(|| format!("Failed to read payment file: {}", payment.display()))?;
+            let payment: UserCommand =
+                serde_json::from_slice(&payment).context("Failed to parse payment file")?;
+            if !commands.iter().any(|command| command.data == payment) {
+                anyhow::bail!("The payment isn't part of the proof");
+            }
+        }
+
+        let public_key = v2::NonZeroCurvePoint::from_str(&self.address)
+            .map_err(|_| anyhow::anyhow!("Invalid public key: {}", self.address))?;
+        let token_id = match &self.token {
+            None => TokenId::default(),
+            Some(token) => v2::TokenIdKeyHash::from_str(token)
+                .map_err(|_| anyhow::anyhow!("Invalid token id: {token}"))?
+                .into(),
+        };
+        let account_id = AccountId::new((&public_key).try_into()?, token_id);
+
+        let client = GraphQLClient::new(ClientConfig::new(&self.endpoint))?;
+        let on_ledger = client
+            .execute(&queries::ReceiptChainHash::new(
+                &self.address,
+                self.token.clone(),
+            ))?
+            .account
+            .with_context(|| format!("Account not found: {}", self.address))?
+            .receipt_chain_hash;
+
+        let expected = parse_receipt_chain_hash(&on_ledger)?;
+        let init = match &init {
+            None => ReceiptChainHash::empty(),
+            Some(init) => parse_receipt_chain_hash(init)?,
+        };
+
+        verify_receipt_chain(&account_id, &expected, init, &commands)?;
+
+        println!(
+            "✓ {} command(s) applied to {}, receipt chain hash: {on_ledger}",
+            commands.len(),
+            self.address
+        );
+
+        Ok(())
+    }
+}
+
+fn parse_receipt_chain_hash(s: &str) -> anyhow::Result<ReceiptChainHash> {
+    let hash = v2::ReceiptChainHash::from_str(s)
+        .map_err(|_| anyhow::anyhow!("Invalid receipt chain hash: {s}"))?;
+    Ok(ReceiptChainHash(hash.into_inner().0.to_field()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payment_proof_formats() {
+        let hash = "2mzbV7WevxLuchs2dAMY4vQBS6XttnCUF8Hvks4XNBQ5qiSGGBQe";
+
+        let proof: PaymentProof = serde_json::from_str(r#"{ "commands": [] }"#).unwrap();
+        assert!(matches!(proof.into_parts(), (None, commands) if commands.is_empty()));
+
+        let proof: PaymentProof = serde_json::from_str(&format!(r#"["{hash}", []]"#)).unwrap();
+        let (init, commands) = proof.into_parts();
+        assert_eq!(init.as_deref(), Some(hash));
+        assert!(commands.is_empty());
+        assert!(parse_receipt_chain_hash(hash).is_ok());
+    }
+}
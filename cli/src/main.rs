@@ -9,7 +9,7 @@ use tikv_jemallocator::Jemalloc;
 static GLOBAL: Jemalloc = Jemalloc;
 
 pub mod commands;
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
 
 mod exit_with_error;
 pub use exit_with_error::exit_with_error;
@@ -94,6 +94,17 @@ fn new_hook(info: &PanicHookInfo<'_>) {
 
     eprintln!("{}", String::from_utf8_lossy(&s));
 
+    match mina_node_native::crash_bundle::write(
+        name,
+        location.to_string(),
+        msg,
+        format!("{backtrace:?}"),
+    ) {
+        Some(Ok(path)) => eprintln!("Crash bundle written to {}", path.display()),
+        Some(Err(err)) => eprintln!("Failed to write the crash bundle: {err}"),
+        None => {}
+    }
+
     if name != "main" {
         let Ok(mut previous) = PREVIOUS_PANICS.lock() else {
             return;
@@ -127,12 +138,15 @@ fn main() -> anyhow::Result<()> {
 
     #[cfg(feature = "unsafe-signal-handlers")]
     unsafe_signal_handlers::setup();
-    let app = commands::MinaCli::parse();
-
-    let network_init_result = match app.network {
-        commands::Network::Devnet => mina_core::NetworkConfig::init("devnet"),
-        commands::Network::Mainnet => mina_core::NetworkConfig::init("mainnet"),
-    };
+    let matches = commands::MinaCli::command().get_matches();
+    let mut app = commands::MinaCli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    app.command.load_node_config(&matches)?;
+
+    let constraint_constants = app.command.constraint_constants(&app.network)?;
+    let network_init_result = mina_core::NetworkConfig::init_with_constraint_constants(
+        app.network.name(),
+        constraint_constants,
+    );
 
     network_init_result.expect("Failed to initialize network configuration");
 
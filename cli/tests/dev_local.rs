@@ -0,0 +1,117 @@
+use clap::Parser;
+use cli::commands::Network;
+use std::{
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Runs a local development chain until it produced blocks with snark work
+/// in them, and then some more, so that block production goes on once the
+/// scan state is full.
+#[test]
+fn dev_local() -> anyhow::Result<()> {
+    if std::env::var(FORK_VAR).is_ok() {
+        run_node()?;
+        unreachable!();
+    }
+    let work_dir = tempfile::tempdir()?;
+    let mut child = node_command(work_dir.path().to_str().unwrap()).spawn()?;
+    let result = monitor_node(&mut child);
+    child.kill()?;
+    result
+}
+
+const FORK_VAR: &str = "MINA_DEV_LOCAL_FORK";
+const HTTP_PORT: u16 = 49996;
+/// Slots of 2 seconds.
+const TIME_ACCELERATION: u32 = 90;
+/// Blocks to produce after the first one including snark work.
+const BLOCKS_AFTER_WORK: u32 = 10;
+
+const PAUSE: Duration = Duration::from_secs(2);
+const HEALTHY: Duration = Duration::from_secs(60);
+const PRODUCING: Duration = Duration::from_secs(30 * 60);
+
+fn run_node() -> anyhow::Result<()> {
+    let args = [std::env::args().next().unwrap(), String::from("node")];
+    if let Err(e) = cli::commands::MinaCli::parse_from(args)
+        .command
+        .run(Network::Devnet)
+    {
+        anyhow::bail!(format!("{e:#}"));
+    }
+    Ok(())
+}
+
+fn node_command(work_dir: &str) -> Command {
+    let mut command = Command::new(std::env::args().next().unwrap());
+    command
+        .env(FORK_VAR, "true")
+        .env("MINA_DEV_LOCAL", "true")
+        .env("MINA_HOME", work_dir)
+        .env("PORT", HTTP_PORT.to_string())
+        .env("TIME_ACCELERATION", TIME_ACCELERATION.to_string())
+        .env("RUST_MIN_STACK", "10000000")
+        .arg("--nocapture")
+        .stdout(Stdio::null())
+        .stderr(Stdio::inherit());
+    command
+}
+
+fn is_healthy() -> bool {
+    reqwest::blocking::get(format!("http://localhost:{HTTP_PORT}/healthz"))
+        .is_ok_and(|res| res.status().is_success())
+}
+
+/// Height of the best tip and whether it includes snark work.
+fn best_tip() -> anyhow::Result<Option<(u32, bool)>> {
+    let summary: serde_json::Value =
+        reqwest::blocking::get(format!("http://localhost:{HTTP_PORT}/scan-state/summary"))?
+            .json()?;
+    let Some(block) = summary.get("block") else {
+        return Ok(None);
+    };
+    let height = block["height"]
+        .as_u64()
+        .ok_or(anyhow::anyhow!("no best tip height"))?;
+    let has_work = block["completed_works"]
+        .as_array()
+        .is_some_and(|works| !works.is_empty());
+    Ok(Some((height as u32, has_work)))
+}
+
+fn wait_for(
+    child: &mut Child,
+    time: Duration,
+    mut f: impl FnMut() -> anyhow::Result<bool>,
+) -> anyhow::Result<()> {
+    let timeout = Instant::now() + time;
+    while Instant::now() < timeout {
+        if child.try_wait()?.is_some() {
+            anyhow::bail!("node finished unexpectedly");
+        }
+        if f()? {
+            return Ok(());
+        }
+        thread::sleep(PAUSE);
+    }
+    Err(anyhow::anyhow!("node did not get there within {time:?}"))
+}
+
+fn monitor_node(child: &mut Child) -> anyhow::Result<()> {
+    wait_for(child, HEALTHY, || Ok(is_healthy()))?;
+
+    let mut first_work_height = None;
+    wait_for(child, PRODUCING, || {
+        // Not available until the genesis block is loaded.
+        let Ok(Some((height, has_work))) = best_tip() else {
+            return Ok(false);
+        };
+        if has_work && first_work_height.is_none() {
+            println!("first block with snark work at height {height}");
+            first_work_height = Some(height);
+        }
+        Ok(first_work_height.is_some_and(|first| height >= first + BLOCKS_AFTER_WORK))
+    })
+}
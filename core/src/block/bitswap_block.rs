@@ -0,0 +1,425 @@
+//! Bitswap blocks of a block body. The serialized body is split into blocks
+//! forming a tree of blake2 hashes, whose root is the body reference found
+//! in the block header, so each block can be checked on its own as soon as
+//! it's received.
+//!
+//! <https://github.com/MinaProtocol/mina/blob/850309dad6293c3b7b15ef682d38e1e26c1d2e13/src/lib/staged_ledger_diff/bitswap_block.ml>
+
+use std::collections::{BTreeMap, VecDeque};
+
+use binprot::{BinProtRead, BinProtWrite};
+use mina_p2p_messages::v2::{ConsensusBodyReferenceStableV1, StagedLedgerDiffDiffStableV2};
+use serde::{Deserialize, Serialize};
+
+const BODY_TAG: u8 = 0;
+pub const MAX_BLOCK_SIZE: usize = 262144;
+const LINK_SIZE: usize = 32;
+const ABSOLUTE_MAX_LINKS_PER_BLOCK: usize = u16::MAX as usize;
+
+type Link = Box<[u8; LINK_SIZE]>;
+
+#[derive(thiserror::Error, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum BitswapBlockError {
+    #[error("invalid bitswap block produced")]
+    InvalidBlockProduced,
+    #[error("invalid state while producing bitswap blocks")]
+    InvalidState,
+    #[error("bitswap block hash mismatch, expected: {expected}, got: {got}")]
+    HashMismatch { expected: String, got: String },
+    #[error("unexpected bitswap block, the body is already complete")]
+    Unexpected,
+    #[error("malformed bitswap block")]
+    Malformed,
+    #[error("body is larger than {0} bytes")]
+    TooLarge(usize),
+    #[error("body is incomplete")]
+    Incomplete,
+    #[error("failed to decode body: {0}")]
+    Decode(String),
+}
+
+/// Computes the body reference of the body, its root bitswap block hash.
+pub fn body_reference(
+    body: &StagedLedgerDiffDiffStableV2,
+) -> Result<ConsensusBodyReferenceStableV1, BitswapBlockError> {
+    let bytes = serialize_with_len_and_tag(body);
+    blocks_of_data(MAX_BLOCK_SIZE, &bytes)
+        .map(|(_, hash)| hash)
+        .map(|hash| hash.as_slice().into())
+        .map(ConsensusBodyReferenceStableV1)
+}
+
+/// Splits the body into its bitswap blocks, in the order [`BodyBlocksReader`]
+/// expects them, breadth first from the root block.
+pub fn body_blocks(body: &StagedLedgerDiffDiffStableV2) -> Result<Vec<Vec<u8>>, BitswapBlockError> {
+    let bytes = serialize_with_len_and_tag(body);
+    let (blocks, root) = blocks_of_data(MAX_BLOCK_SIZE, &bytes)?;
+
+    let mut queue = VecDeque::from([root]);
+    let mut ordered = Vec::with_capacity(blocks.len());
+    while let Some(link) = queue.pop_front() {
+        // Blocks with the same contents are stored once, but linked from
+        // each place they're at.
+        let block = blocks.get(&link).ok_or(BitswapBlockError::InvalidState)?;
+        let (links, _) = parse_block(block)?;
+        queue.extend(links);
+        ordered.push(block.clone());
+    }
+    Ok(ordered)
+}
+
+/// Checks the bitswap blocks of a body as they're received, in the order of
+/// [`body_blocks`], and reassembles the body out of them.
+///
+/// Each block must hash to the link the header or an earlier block has for
+/// it, so a corrupted transfer is detected at its first invalid block,
+/// without waiting for the rest of the body.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BodyBlocksReader {
+    /// Hashes of the blocks still to receive, in the order they're sent.
+    pending: VecDeque<[u8; LINK_SIZE]>,
+    data: Vec<u8>,
+    received: usize,
+    max_size: usize,
+}
+
+impl BodyBlocksReader {
+    /// Reader of the body whose reference is `body_reference`, failing once
+    /// more than `max_size` bytes of blocks are received.
+    pub fn new(
+        body_reference: &ConsensusBodyReferenceStableV1,
+        max_size: usize,
+    ) -> Result<Self, BitswapBlockError> {
+        let root = body_reference
+            .0
+            .as_ref()
+            .try_into()
+            .map_err(|_| BitswapBlockError::Malformed)?;
+        Ok(Self {
+            pending: VecDeque::from([root]),
+            data: Vec::new(),
+            received: 0,
+            max_size,
+        })
+    }
+
+    pub fn push(&mut self, block: &[u8]) -> Result<(), BitswapBlockError> {
+        let expected = self
+            .pending
+            .pop_front()
+            .ok_or(BitswapBlockError::Unexpected)?;
+        self.received = self.received.saturating_add(block.len());
+        if self.received > self.max_size {
+            return Err(BitswapBlockError::TooLarge(self.max_size));
+        }
+
+        let hash = blake2(block);
+        if *hash != expected {
+            return Err(BitswapBlockError::HashMismatch {
+                expected: hex::encode(expected),
+                got: hex::encode(*hash),
+            });
+        }
+
+        let (links, data) = parse_block(block)?;
+        self.pending.extend(links.into_iter().map(|link| *link));
+        self.data.extend_from_slice(data);
+        Ok(())
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Number of bytes of blocks received so far.
+    pub fn received(&self) -> usize {
+        self.received
+    }
+
+    pub fn finish(&self) -> Result<StagedLedgerDiffDiffStableV2, BitswapBlockError> {
+        if !self.is_done() {
+            return Err(BitswapBlockError::Incomplete);
+        }
+        let (len, rest) = self
+            .data
+            .split_first_chunk::<4>()
+            .ok_or(BitswapBlockError::Malformed)?;
+        let (tag, mut body) = rest.split_first().ok_or(BitswapBlockError::Malformed)?;
+        if u32::from_le_bytes(*len) as usize != rest.len() || *tag != BODY_TAG {
+            return Err(BitswapBlockError::Malformed);
+        }
+
+        let diff = StagedLedgerDiffDiffStableV2::binprot_read(&mut body)
+            .map_err(|error| BitswapBlockError::Decode(error.to_string()))?;
+        if !body.is_empty() {
+            return Err(BitswapBlockError::Malformed);
+        }
+        Ok(diff)
+    }
+}
+
+impl std::fmt::Debug for BodyBlocksReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BodyBlocksReader")
+            .field("pending", &self.pending.len())
+            .field("received", &self.received)
+            .field("max_size", &self.max_size)
+            .finish()
+    }
+}
+
+fn serialize_with_len_and_tag(block: &StagedLedgerDiffDiffStableV2) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(32 * 1024);
+    block.binprot_write(&mut bytes).unwrap();
+    let len = bytes.len();
+
+    let mut bytes_with_header = Vec::with_capacity(len + 5);
+    bytes_with_header.extend(((len + 1) as u32).to_le_bytes());
+    bytes_with_header.extend(BODY_TAG.to_ne_bytes());
+    bytes_with_header.append(&mut bytes);
+    bytes_with_header
+}
+
+/// Splits a block into its links and its chunk of data.
+fn parse_block(block: &[u8]) -> Result<(Vec<Link>, &[u8]), BitswapBlockError> {
+    let (num_links, rest) = block
+        .split_first_chunk::<2>()
+        .ok_or(BitswapBlockError::Malformed)?;
+    let links_size = u16::from_le_bytes(*num_links) as usize * LINK_SIZE;
+    if rest.len() < links_size {
+        return Err(BitswapBlockError::Malformed);
+    }
+    let (links, data) = rest.split_at(links_size);
+    let links = links
+        .chunks_exact(LINK_SIZE)
+        .map(|link| Box::new(link.try_into().unwrap()))
+        .collect();
+    Ok((links, data))
+}
+
+fn blake2(data: &[u8]) -> Link {
+    use blake2::{
+        digest::{Update, VariableOutput},
+        Blake2bVar,
+    };
+
+    let mut hasher = Blake2bVar::new(LINK_SIZE).unwrap();
+    hasher.update(data);
+    hasher.finalize_boxed().try_into().unwrap()
+}
+
+/// <https://github.com/MinaProtocol/mina/blob/850309dad6293c3b7b15ef682d38e1e26c1d2e13/src/lib/staged_ledger_diff/bitswap_block.ml#L78>
+fn blocks_of_data(
+    max_block_size: usize,
+    data: &[u8],
+) -> Result<(BTreeMap<Link, Vec<u8>>, Link), BitswapBlockError> {
+    if max_block_size <= 2 + LINK_SIZE {
+        panic!("Max block size too small");
+    }
+    let max_data_chunk_size = max_block_size - 2;
+    let data_length = data.len();
+    let schema = create_schema(max_block_size, data_length);
+
+    let mut remaining_data = data_length;
+    let mut blocks = BTreeMap::<Link, Vec<u8>>::default();
+    let mut link_queue = VecDeque::<Link>::with_capacity(128);
+
+    let mut dequeue_chunk = |chunk_size: usize| {
+        assert!(!remaining_data >= chunk_size);
+        let pos = remaining_data - chunk_size;
+        let chunk = data.get(pos..pos + chunk_size).unwrap();
+        remaining_data -= chunk_size;
+        chunk
+    };
+
+    let dequeue_links = |num_links: usize, link_queue: &mut VecDeque<Link>| {
+        assert!(link_queue.len() >= num_links);
+        let mut links = Vec::with_capacity(num_links);
+        for _ in 1..=num_links {
+            let front = link_queue.pop_front().unwrap();
+            links.push(front);
+        }
+        links.reverse();
+        links
+    };
+
+    let mut create_block =
+        |links: Vec<Link>, chunk_size: usize, link_queue: &mut VecDeque<Link>| {
+            let chunk = dequeue_chunk(chunk_size);
+            let num_links = links.len();
+            let size = 2 + (num_links * LINK_SIZE) + chunk_size;
+            if num_links > ABSOLUTE_MAX_LINKS_PER_BLOCK || size > max_block_size {
+                return Err(BitswapBlockError::InvalidBlockProduced);
+            }
+
+            let mut block = Vec::with_capacity(size);
+            block.extend((num_links as u16).to_le_bytes());
+            for link in links.iter() {
+                let link: &[u8; LINK_SIZE] = link;
+                block.extend(link);
+            }
+            block.extend(chunk);
+
+            let hash = blake2(&block);
+            blocks.insert(hash.clone(), block);
+            link_queue.push_back(hash);
+            Ok(())
+        };
+
+    // create the last block
+    create_block(vec![], schema.last_leaf_block_data_size, &mut link_queue)?;
+
+    if schema.num_total_blocks > 1 {
+        // create the data-only blocks
+        let num_data_only_blocks = schema.num_total_blocks
+            - schema.num_full_branch_blocks
+            - 1
+            - if schema.num_links_in_partial_branch_block > 0 {
+                1
+            } else {
+                0
+            };
+        for _ in 1..=num_data_only_blocks {
+            create_block(vec![], max_data_chunk_size, &mut link_queue)?;
+        }
+        // create the non max link block, if there is one
+        if schema.num_links_in_partial_branch_block > 0 {
+            let chunk_size =
+                max_block_size - 2 - (schema.num_links_in_partial_branch_block * LINK_SIZE);
+            let link = dequeue_links(schema.num_links_in_partial_branch_block, &mut link_queue);
+            create_block(link, chunk_size, &mut link_queue)?;
+        }
+
+        // create the max link blocks
+        let full_link_chunk_size = max_block_size - 2 - (schema.max_links_per_block * LINK_SIZE);
+
+        for _ in 1..=schema.num_full_branch_blocks {
+            create_block(
+                dequeue_links(schema.max_links_per_block, &mut link_queue),
+                full_link_chunk_size,
+                &mut link_queue,
+            )?;
+        }
+    }
+    if remaining_data != 0 {
+        return Err(BitswapBlockError::InvalidState);
+    }
+    if link_queue.len() != 1 {
+        return Err(BitswapBlockError::InvalidState);
+    }
+
+    Ok((blocks, link_queue.pop_back().unwrap()))
+}
+
+fn required_bitswap_block_count(max_block_size: usize, data_length: usize) -> usize {
+    if data_length <= max_block_size - 2 {
+        1
+    } else {
+        let n1 = data_length - LINK_SIZE;
+        let n2 = max_block_size - LINK_SIZE - 2;
+        // (n1 + n2 - 1) / n2
+        n1.div_ceil(n2)
+    }
+}
+
+fn max_links_per_block(max_block_size: usize) -> usize {
+    let links_per_block = (max_block_size - 2) / LINK_SIZE;
+    links_per_block.min(ABSOLUTE_MAX_LINKS_PER_BLOCK)
+}
+
+#[derive(Debug)]
+struct Schema {
+    num_total_blocks: usize,
+    num_full_branch_blocks: usize,
+    last_leaf_block_data_size: usize,
+    num_links_in_partial_branch_block: usize,
+    max_block_data_size: usize,
+    max_links_per_block: usize,
+}
+
+fn create_schema(max_block_size: usize, data_length: usize) -> Schema {
+    let num_total_blocks = required_bitswap_block_count(max_block_size, data_length);
+    let last_leaf_block_data_size =
+        data_length - ((max_block_size - LINK_SIZE - 2) * (num_total_blocks - 1));
+    let max_links_per_block = max_links_per_block(max_block_size);
+    let num_full_branch_blocks = (num_total_blocks - 1) / max_links_per_block;
+    let num_links_in_partial_branch_block =
+        num_total_blocks - 1 - (num_full_branch_blocks * max_links_per_block);
+
+    Schema {
+        num_total_blocks,
+        num_full_branch_blocks,
+        last_leaf_block_data_size,
+        num_links_in_partial_branch_block,
+        max_block_data_size: max_block_size,
+        max_links_per_block,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_body() -> StagedLedgerDiffDiffStableV2 {
+        // No works, commands, coinbase nor statuses, and no second pre-diff.
+        StagedLedgerDiffDiffStableV2::binprot_read(&mut &[0u8; 5][..]).unwrap()
+    }
+
+    /// Reassembles the data of blocks created by [`blocks_of_data`].
+    fn read_data(max_block_size: usize, data: &[u8]) -> Result<Vec<u8>, BitswapBlockError> {
+        let (blocks, root) = blocks_of_data(max_block_size, data)?;
+        let mut reader = BodyBlocksReader {
+            pending: VecDeque::from([*root]),
+            data: Vec::new(),
+            received: 0,
+            max_size: usize::MAX,
+        };
+        while let Some(link) = reader.pending.front() {
+            let block = blocks[&Box::new(*link)].clone();
+            reader.push(&block)?;
+        }
+        Ok(reader.data)
+    }
+
+    #[test]
+    fn blocks_read_back_in_order() {
+        // Small enough blocks for the data to need a few levels of links.
+        let max_block_size = 2 + 4 * LINK_SIZE;
+        for len in [0, 1, 100, 1000, 10_000] {
+            let data = (0..len).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+            assert_eq!(read_data(max_block_size, &data).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn body_blocks_match_body_reference() {
+        let body = empty_body();
+        let reference = body_reference(&body).unwrap();
+        let blocks = body_blocks(&body).unwrap();
+
+        let mut reader = BodyBlocksReader::new(&reference, MAX_BLOCK_SIZE).unwrap();
+        for block in &blocks {
+            reader.push(block).unwrap();
+        }
+        assert!(reader.is_done());
+        assert_eq!(reader.finish().unwrap(), body);
+        assert_eq!(reader.push(&blocks[0]), Err(BitswapBlockError::Unexpected));
+    }
+
+    #[test]
+    fn corrupted_block_is_rejected() {
+        let body = empty_body();
+        let reference = body_reference(&body).unwrap();
+        let mut block = body_blocks(&body).unwrap().remove(0);
+        *block.last_mut().unwrap() ^= 1;
+
+        let mut reader = BodyBlocksReader::new(&reference, MAX_BLOCK_SIZE).unwrap();
+        assert!(matches!(
+            reader.push(&block),
+            Err(BitswapBlockError::HashMismatch { .. })
+        ));
+
+        let mut reader = BodyBlocksReader::new(&reference, 1).unwrap();
+        assert_eq!(reader.push(&block), Err(BitswapBlockError::TooLarge(1)));
+    }
+}
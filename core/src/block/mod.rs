@@ -8,6 +8,8 @@ pub mod prevalidate;
 
 pub mod genesis;
 
+pub mod bitswap_block;
+
 use std::sync::Arc;
 
 pub use mina_p2p_messages::v2::{
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::ArcBlockWithHash;
-use crate::constants::PROTOCOL_VERSION;
+use crate::constants::is_protocol_version_compatible;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum BlockPrevalidationError {
@@ -80,9 +80,7 @@ pub fn validate_protocol_versions(block: &ArcBlockWithHash) -> Result<(), BlockP
         return Err(BlockPrevalidationError::InvalidProtocolVersion);
     }
 
-    let compatible =
-        v.transaction == PROTOCOL_VERSION.transaction && v.network == PROTOCOL_VERSION.network;
-    if !compatible {
+    if !is_protocol_version_compatible(v) {
         return Err(BlockPrevalidationError::MismatchedProtocolVersion);
     }
 
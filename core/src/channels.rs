@@ -67,6 +67,10 @@ pub mod mpsc {
         pub fn try_send(&self, message: T) -> Result<(), TrySendError<T>> {
             self.0.try_send(message)
         }
+
+        pub fn is_disconnected(&self) -> bool {
+            self.0.is_disconnected()
+        }
     }
 
     impl<T> Receiver<T> {
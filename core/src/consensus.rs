@@ -200,6 +200,70 @@ pub fn consensus_take(
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ConsensusForkDecisionReason {
+    ShortRange(ConsensusShortRangeForkDecisionReason),
+    LongRange(ConsensusLongRangeForkDecisionReason),
+}
+
+/// Values compared by the consensus rules when choosing between the
+/// current best tip and a candidate block, along with the outcome.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConsensusForkComparison {
+    pub tip_hash: StateHash,
+    pub candidate_hash: StateHash,
+    pub tip_length: u32,
+    pub candidate_length: u32,
+    /// Relative min window density of the tip. Only compared for long
+    /// range forks, so `None` for short range ones.
+    pub tip_density: Option<u32>,
+    pub candidate_density: Option<u32>,
+    /// Hex encoded blake2b hash of the tip's last VRF output, used as a
+    /// tiebreak.
+    pub tip_vrf_hash: String,
+    pub candidate_vrf_hash: String,
+    /// Whether the candidate should be taken as the new best tip.
+    pub take: bool,
+    pub reason: ConsensusForkDecisionReason,
+}
+
+/// Same as [`consensus_take`], but returns all the compared values.
+pub fn consensus_compare(
+    tip_cs: &MinaConsensusState,
+    candidate_cs: &MinaConsensusState,
+    tip_hash: &StateHash,
+    candidate_hash: &StateHash,
+) -> ConsensusForkComparison {
+    let (take, reason, densities) = if is_short_range_fork(tip_cs, candidate_cs) {
+        let (take, reason) = short_range_fork_take(tip_cs, candidate_cs, tip_hash, candidate_hash);
+        (take, ConsensusForkDecisionReason::ShortRange(reason), None)
+    } else {
+        let (take, reason) = long_range_fork_take(tip_cs, candidate_cs, tip_hash, candidate_hash);
+        let densities = (
+            relative_min_window_density(tip_cs, candidate_cs),
+            relative_min_window_density(candidate_cs, tip_cs),
+        );
+        (
+            take,
+            ConsensusForkDecisionReason::LongRange(reason),
+            Some(densities),
+        )
+    };
+
+    ConsensusForkComparison {
+        tip_hash: tip_hash.clone(),
+        candidate_hash: candidate_hash.clone(),
+        tip_length: tip_cs.blockchain_length.as_u32(),
+        candidate_length: candidate_cs.blockchain_length.as_u32(),
+        tip_density: densities.map(|(tip, _)| tip),
+        candidate_density: densities.map(|(_, candidate)| candidate),
+        tip_vrf_hash: hex::encode(tip_cs.last_vrf_output.blake2b()),
+        candidate_vrf_hash: hex::encode(candidate_cs.last_vrf_output.blake2b()),
+        take,
+        reason,
+    }
+}
+
 pub fn in_seed_update_range(
     slot: u32,
     constants: &v2::MinaBaseProtocolConstantsCheckedValueStableV1,
@@ -325,7 +389,10 @@ impl ConsensusConstants {
 
 #[cfg(test)]
 mod tests {
-    use super::{long_range_fork_take, short_range_fork_take};
+    use super::{
+        consensus_compare, consensus_take, long_range_fork_take, short_range_fork_take,
+        ConsensusForkDecisionReason,
+    };
     use mina_p2p_messages::v2::{MinaStateProtocolStateValueStableV2, StateHash};
     macro_rules! fork_file {
         ($prefix:expr, $tip:expr, $cnd:expr, $suffix:expr) => {
@@ -438,4 +505,53 @@ mod tests {
                 "3NKLEnUBTAhC95XEdJpLvJPqAUuvkC176tFKyLDcXUcofXXgQUvY"
         );
     }
+
+    #[test]
+    fn consensus_compare_matches_take() {
+        macro_rules! compare_test {
+            ($prefix:expr, $tip:expr, $cnd:expr) => {{
+                let tip_str = include_str!(fork_file!($prefix, $tip, $cnd, "tip"));
+                let cnd_str = include_str!(fork_file!($prefix, $tip, $cnd, "cnd"));
+                let tip_hash = $tip.parse::<StateHash>().unwrap();
+                let cnd_hash = $cnd.parse::<StateHash>().unwrap();
+                let tip =
+                    serde_json::from_str::<MinaStateProtocolStateValueStableV2>(tip_str).unwrap();
+                let cnd =
+                    serde_json::from_str::<MinaStateProtocolStateValueStableV2>(cnd_str).unwrap();
+                let (tip_cs, cnd_cs) = (&tip.body.consensus_state, &cnd.body.consensus_state);
+
+                let comparison = consensus_compare(tip_cs, cnd_cs, &tip_hash, &cnd_hash);
+                assert_eq!(
+                    comparison.take,
+                    consensus_take(tip_cs, cnd_cs, &tip_hash, &cnd_hash)
+                );
+                assert_eq!(comparison.tip_length, tip_cs.blockchain_length.as_u32());
+                assert_eq!(
+                    comparison.candidate_length,
+                    cnd_cs.blockchain_length.as_u32()
+                );
+                comparison
+            }};
+        }
+
+        let comparisons = [
+            compare_test!(
+                "long-take-density-92-97",
+                "3NLESd9gzU52bDWSXL5uUAYbCojHXSVdeBX4sCMF3V8Ns9D1Sriy",
+                "3NLQfKJ4kBagLgmiwyiVw9zbi53tiNy8TNu2ua1jmCyEecgbBJoN"
+            ),
+            compare_test!(
+                "short-keep-vrf-117-117",
+                "3NLWvDBFYJ2NXZ1EKMZXHB52zcbVtosHPArn4cGj8pDKkYsTHNnC",
+                "3NKLEnUBTAhC95XEdJpLvJPqAUuvkC176tFKyLDcXUcofXXgQUvY"
+            ),
+        ];
+        for comparison in comparisons {
+            // Densities are only part of the decision for long range forks.
+            let is_long_range =
+                matches!(comparison.reason, ConsensusForkDecisionReason::LongRange(_));
+            assert_eq!(comparison.tip_density.is_some(), is_long_range);
+            assert_eq!(comparison.candidate_density.is_some(), is_long_range);
+        }
+    }
 }
@@ -10,6 +10,13 @@ pub const PROTOCOL_VERSION: v2::ProtocolVersionStableV2 = v2::ProtocolVersionSta
     patch: number::Number(0),
 };
 
+/// Whether blocks of the protocol version can be applied by this node,
+/// which is the case if only their patch version differs.
+pub fn is_protocol_version_compatible(version: &v2::ProtocolVersionStableV2) -> bool {
+    version.transaction == PROTOCOL_VERSION.transaction
+        && version.network == PROTOCOL_VERSION.network
+}
+
 pub fn constraint_constants() -> &'static ConstraintConstants {
     NetworkConfig::global().constraint_constants
 }
@@ -197,6 +204,16 @@ pub struct ConstraintConstants {
     /// **Usage**: Account creation, transaction validation, fee calculations
     pub account_creation_fee: u64,
 
+    /// Minimum fee of user commands in nanomina.
+    ///
+    /// Commands paying less are not well formed, so they are rejected by the
+    /// transaction pool. Unlike the other constants, it doesn't affect the
+    /// circuits, and isn't part of [`ConstraintConstantsUnversioned`].
+    ///
+    /// **Value**: 1,000,000 nanomina (0.001 MINA)
+    /// **Usage**: Transaction pool admission, see `UserCommand::check_well_formedness`
+    pub minimum_user_command_fee: u64,
+
     /// Optional fork constants defining a protocol upgrade point.
     ///
     /// When present, these constants specify the blockchain state at which a protocol
@@ -261,6 +278,21 @@ impl binprot::BinProtWrite for ConstraintConstants {
     }
 }
 
+impl ConstraintConstants {
+    /// Digest of the constants. They are compiled into the circuits, so
+    /// verifier indices generated for some constants can't verify proofs
+    /// created with others.
+    pub fn digest(&self) -> [u8; 32] {
+        use binprot::BinProtWrite;
+        use sha2::{Digest, Sha256};
+
+        let mut bytes = Vec::new();
+        self.binprot_write(&mut bytes)
+            .expect("writing to a vec can't fail");
+        Sha256::digest(&bytes).into()
+    }
+}
+
 pub fn slots_per_window(constants: &v2::MinaBaseProtocolConstantsCheckedValueStableV1) -> u32 {
     constants.slots_per_sub_window.as_u32() * (constraint_constants().sub_windows_per_window as u32)
 }
@@ -288,3 +320,20 @@ pub const TX_POOL_MAX_SIZE: u32 = 3000;
 pub use v2::PROTOCOL_CONSTANTS;
 
 use crate::NetworkConfig;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constraint_constants_digest_changes_with_constants() {
+        let mainnet = &crate::network::mainnet::CONSTRAINT_CONSTANTS;
+        assert_eq!(mainnet.digest(), mainnet.clone().digest());
+
+        let custom = ConstraintConstants {
+            account_creation_fee: mainnet.account_creation_fee + 1,
+            ..mainnet.clone()
+        };
+        assert_ne!(mainnet.digest(), custom.digest());
+    }
+}
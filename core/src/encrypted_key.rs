@@ -283,12 +283,23 @@ pub trait EncryptedSecretKey {
     /// - Default Argon2i parameters: 128MB memory cost, 6 iterations
     /// - Each encryption produces unique salt and nonce for security
     fn try_encrypt(key: &[u8], password: &str) -> Result<EncryptedSecretKeyFile, EncryptionError> {
-        let argon2 = setup_argon(Self::PW_DIFF)?;
-
         // add the prefix byte to the key
         let mut key_prefixed = vec![Self::SECRET_KEY_PREFIX_BYTE];
         key_prefixed.extend(key);
 
+        Self::try_encrypt_unprefixed(&key_prefixed, password)
+    }
+
+    /// Encrypts `data` as is, without the format version prefix byte.
+    ///
+    /// Used for secret files whose content isn't a raw secret key, like the
+    /// libp2p keypair files of the OCaml Mina node.
+    fn try_encrypt_unprefixed(
+        data: &[u8],
+        password: &str,
+    ) -> Result<EncryptedSecretKeyFile, EncryptionError> {
+        let argon2 = setup_argon(Self::PW_DIFF)?;
+
         let salt = SaltString::generate(&mut OsRng);
         let password_hash = argon2
             .hash_password(password.as_bytes(), &salt)?
@@ -298,7 +309,7 @@ pub trait EncryptedSecretKey {
         let nonce = XSalsa20Poly1305::generate_nonce(&mut OsRng);
         let cipher = XSalsa20Poly1305::new_from_slice(password_hash.as_bytes())?;
 
-        let ciphertext = cipher.encrypt(&nonce, key_prefixed.as_slice())?;
+        let ciphertext = cipher.encrypt(&nonce, data)?;
 
         // Same reason as in decrypt, we need to decode the SaltString from
         // base64 then encode it to base58 below
@@ -0,0 +1,177 @@
+//! Stable codes of the errors returned by the node's APIs, so that clients
+//! can tell errors apart without matching on their messages, which aren't
+//! guaranteed to stay the same.
+//!
+//! Codes are grouped by the range of their numeric code:
+//! - `1xxx`: a transaction rejected by the transaction pool,
+//! - `2xxx`: proofs or signatures failing verification,
+//! - `3xxx`: requests to the RPC or GraphQL APIs which can't be served,
+//! - `4xxx`: errors while syncing with the network.
+//!
+//! Codes are never reused nor renumbered once released. The string codes of
+//! the transaction pool rejections are the names the OCaml node reports for
+//! them.
+
+use serde::{Deserialize, Serialize};
+
+/// Error having a code in the [`ErrorCode`] catalog.
+pub trait HasErrorCode {
+    fn error_code(&self) -> ErrorCode;
+}
+
+macro_rules! error_codes {
+    ($($(#[$meta:meta])* $variant:ident = $code:literal => $name:literal,)*) => {
+        #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ErrorCode {
+            $(
+                $(#[$meta])*
+                #[serde(rename = $name)]
+                $variant,
+            )*
+        }
+
+        impl ErrorCode {
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)*];
+
+            pub fn code(self) -> u16 {
+                match self {
+                    $(ErrorCode::$variant => $code,)*
+                }
+            }
+
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $name,)*
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    InsufficientReplaceFee = 1000 => "insufficient_replace_fee",
+    Duplicate = 1001 => "duplicate",
+    InvalidNonce = 1002 => "invalid_nonce",
+    InsufficientFunds = 1003 => "insufficient_funds",
+    Overflow = 1004 => "overflow",
+    BadToken = 1005 => "bad_token",
+    UnwantedFeeToken = 1006 => "unwanted_fee_token",
+    Expired = 1007 => "expired",
+    Overloaded = 1008 => "overloaded",
+    FeePayerAccountNotFound = 1009 => "fee_payer_account_not_found",
+    FeePayerNotPermittedToSend = 1010 => "fee_payer_not_permitted_to_send",
+    AfterSlotTxEnd = 1011 => "after_slot_tx_end",
+    BacktrackNonceMismatch = 1012 => "backtrack_nonce_mismatch",
+    InvalidCurrencyConsumed = 1013 => "invalid_currency_consumed",
+    /// A zkApp command with an account precondition which can never be
+    /// satisfied.
+    AccountPreconditionUnsatisfied = 1014 => "account_precondition_unsatisfied",
+    /// A payment to an account which doesn't accept receiving without
+    /// authorization, or a delegation of an account which doesn't accept
+    /// setting its delegate with a signature.
+    AccountNotPermitted = 1015 => "account_not_permitted",
+    /// Rejected by the pool for another reason.
+    PoolRejected = 1099 => "pool_rejected",
+
+    InvalidProof = 2000 => "invalid_proof",
+    /// The accumulator of a block proof is invalid.
+    AccumulatorCheckFailed = 2001 => "accumulator_check_failed",
+    /// A transaction which isn't well formed, or with an invalid signature
+    /// or proof.
+    InvalidTransaction = 2002 => "invalid_transaction",
+    /// The verifier failed, which says nothing about the proofs.
+    VerifierCrashed = 2003 => "verifier_crashed",
+    VerifierTimeout = 2004 => "verifier_timeout",
+    /// The statement a proof is verified against can't be decoded.
+    InvalidStatement = 2005 => "invalid_statement",
+
+    Internal = 3000 => "internal",
+    InvalidInput = 3001 => "invalid_input",
+    PermissionDenied = 3002 => "permission_denied",
+    /// The state machine dropped the request without answering it.
+    ResponseChannelDropped = 3003 => "response_channel_dropped",
+    InvalidFilter = 3004 => "invalid_filter",
+    NotFound = 3005 => "not_found",
+    /// The data isn't available yet, e.g. while the node is syncing.
+    Unavailable = 3006 => "unavailable",
+    InvalidBlock = 3007 => "invalid_block",
+    BlockAlreadyKnown = 3008 => "block_already_known",
+    BlockPrevalidationFailed = 3009 => "block_prevalidation_failed",
+    /// A page cursor whose anchor isn't known anymore, paging must restart
+    /// from the first page.
+    CursorExpired = 3010 => "cursor_expired",
+
+    BlockApplyFailed = 4000 => "block_apply_failed",
+    PeerTimeout = 4001 => "peer_timeout",
+    PeerDisconnected = 4002 => "peer_disconnected",
+    PeerDataUnavailable = 4003 => "peer_data_unavailable",
+}
+
+/// JSON body of an error returned by the HTTP APIs.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ErrorCodeBody {
+    pub error: String,
+    pub code: ErrorCode,
+    pub numeric_code: u16,
+}
+
+impl ErrorCode {
+    pub fn body(self, error: impl std::fmt::Display) -> ErrorCodeBody {
+        ErrorCodeBody {
+            error: error.to_string(),
+            code: self,
+            numeric_code: self.code(),
+        }
+    }
+}
+
+impl ErrorCodeBody {
+    pub fn new<E: HasErrorCode + std::fmt::Display>(error: &E) -> Self {
+        error.error_code().body(error)
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn codes_are_unique() {
+        let codes = ErrorCode::ALL
+            .iter()
+            .map(|c| c.code())
+            .collect::<BTreeSet<_>>();
+        let names = ErrorCode::ALL
+            .iter()
+            .map(|c| c.as_str())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(codes.len(), ErrorCode::ALL.len());
+        assert_eq!(names.len(), ErrorCode::ALL.len());
+    }
+
+    #[test]
+    fn serialized_as_string_code() {
+        for code in ErrorCode::ALL {
+            let json = serde_json::to_value(code).unwrap();
+            assert_eq!(json, code.as_str());
+            assert_eq!(serde_json::from_value::<ErrorCode>(json).unwrap(), *code);
+        }
+        let body = ErrorCode::InvalidNonce.body("invalid nonce");
+        assert_eq!(
+            serde_json::to_value(body).unwrap(),
+            serde_json::json!({
+                "error": "invalid nonce",
+                "code": "invalid_nonce",
+                "numeric_code": 1002,
+            })
+        );
+    }
+}
@@ -23,6 +23,9 @@ pub mod transaction;
 
 pub mod consensus;
 
+mod time_source;
+pub use time_source::TimeSource;
+
 mod substate;
 
 pub use substate::{Substate, SubstateAccess, SubstateResult};
@@ -36,6 +39,9 @@ pub use chain_id::*;
 pub mod encrypted_key;
 pub use encrypted_key::*;
 
+pub mod error_code;
+pub use error_code::{ErrorCode, HasErrorCode};
+
 mod work_dir {
     use once_cell::sync::OnceCell;
     use std::path::PathBuf;
@@ -4,6 +4,8 @@ use poseidon::hash::{
     params::{CODA_SIGNATURE, MAINNET_ZKAPP_BODY, MINA_SIGNATURE_MAINNET, TESTNET_ZKAPP_BODY},
 };
 
+use std::time::Duration;
+
 use crate::constants::ConstraintConstants;
 
 // From mina-signer, to avoid dependency
@@ -24,9 +26,13 @@ pub struct NetworkConfig {
     pub legacy_signature_prefix: &'static poseidon::hash::LazyParam,
     pub account_update_hash_param: &'static poseidon::hash::LazyParam,
     pub constraint_system_digests: &'static [[u8; 16]; 3],
+    pub verifier_index_digests: &'static VerifierIndexDigests,
     pub default_peers: Vec<&'static str>,
     pub circuits_config: &'static CircuitsConfig,
     pub constraint_constants: &'static ConstraintConstants,
+    /// Default for how long a command can stay in the transaction pool,
+    /// independently of its `valid_until`. Unlimited if `None`.
+    pub tx_pool_max_lifetime: Option<Duration>,
 }
 
 #[derive(Debug)]
@@ -43,6 +49,15 @@ pub struct CircuitsConfig {
     pub step_transaction_proved_gates: &'static str,
 }
 
+/// Known-good sha256 digests, hex encoded, of the json the verifier indices
+/// of the network are built from. The loaded indices must be built from, or
+/// cached for, json matching them.
+#[derive(Debug)]
+pub struct VerifierIndexDigests {
+    pub block_verifier: &'static str,
+    pub transaction_verifier: &'static str,
+}
+
 static CONFIG: OnceCell<NetworkConfig> = OnceCell::new();
 
 impl NetworkConfig {
@@ -60,11 +75,20 @@ impl NetworkConfig {
     }
 
     pub fn init(network_name: &str) -> Result<(), String> {
-        let config = match network_name {
-            "devnet" => Self::devnet_config(),
-            "mainnet" => Self::mainnet_config(),
-            other => Err(format!("Unknown network {other}"))?,
-        };
+        Self::init_with_constraint_constants(network_name, None)
+    }
+
+    /// Same as [`Self::init`], but replaces the network's built-in constraint
+    /// constants with `constraint_constants` (e.g. loaded from daemon.json).
+    pub fn init_with_constraint_constants(
+        network_name: &str,
+        constraint_constants: Option<ConstraintConstants>,
+    ) -> Result<(), String> {
+        let mut config = Self::by_name(network_name)?;
+        if let Some(constraint_constants) = constraint_constants {
+            // Leaked on purpose, the global config lives until the process exits.
+            config.constraint_constants = Box::leak(Box::new(constraint_constants));
+        }
 
         CONFIG
             .set(config)
@@ -73,6 +97,26 @@ impl NetworkConfig {
         Ok(())
     }
 
+    /// Built-in constraint constants of the network, which its circuits and
+    /// verifier indices are generated for. They differ from
+    /// [`Self::constraint_constants`] if those were loaded from daemon.json.
+    pub fn circuits_constraint_constants(&self) -> &'static ConstraintConstants {
+        match self.name {
+            mainnet::NAME => &mainnet::CONSTRAINT_CONSTANTS,
+            devnet::NAME => &devnet::CONSTRAINT_CONSTANTS,
+            _ => self.constraint_constants,
+        }
+    }
+
+    /// Returns the built-in config of the network named `network_name`.
+    pub fn by_name(network_name: &str) -> Result<Self, String> {
+        match network_name {
+            "devnet" => Ok(Self::devnet_config()),
+            "mainnet" => Ok(Self::mainnet_config()),
+            other => Err(format!("Unknown network {other}")),
+        }
+    }
+
     fn default_config() -> Self {
         Self::devnet_config()
     }
@@ -85,9 +129,11 @@ impl NetworkConfig {
             legacy_signature_prefix: &legacy::params::MINA_SIGNATURE_MAINNET,
             account_update_hash_param: &MAINNET_ZKAPP_BODY,
             constraint_system_digests: &mainnet::CONSTRAINT_SYSTEM_DIGESTS,
+            verifier_index_digests: &mainnet::VERIFIER_INDEX_DIGESTS,
             default_peers: mainnet::default_peers(),
             circuits_config: &mainnet::CIRCUITS_CONFIG,
             constraint_constants: &mainnet::CONSTRAINT_CONSTANTS,
+            tx_pool_max_lifetime: None,
         }
     }
 
@@ -99,9 +145,11 @@ impl NetworkConfig {
             legacy_signature_prefix: &legacy::params::CODA_SIGNATURE,
             account_update_hash_param: &TESTNET_ZKAPP_BODY,
             constraint_system_digests: &devnet::CONSTRAINT_SYSTEM_DIGESTS,
+            verifier_index_digests: &devnet::VERIFIER_INDEX_DIGESTS,
             default_peers: devnet::default_peers(),
             circuits_config: &devnet::CIRCUITS_CONFIG,
             constraint_constants: &devnet::CONSTRAINT_CONSTANTS,
+            tx_pool_max_lifetime: Some(devnet::TX_POOL_MAX_LIFETIME),
         }
     }
 }
@@ -109,13 +157,18 @@ impl NetworkConfig {
 // Network constants
 
 pub mod devnet {
-    use super::{CircuitsConfig, NetworkId};
+    use std::time::Duration;
+
+    use super::{CircuitsConfig, NetworkId, VerifierIndexDigests};
     use crate::constants::{ConstraintConstants, ForkConstants};
 
     pub const NETWORK_ID: NetworkId = NetworkId::TESTNET;
     pub const NAME: &str = "devnet";
     pub const SIGNATURE_PREFIX: &str = "CodaSignature";
     pub const ACCOUNT_UPDATE_HASH_PARAM: &str = "TestnetZkappBody";
+    /// Devnet can go without blocks for long periods, during which commands
+    /// would otherwise accumulate in the pool.
+    pub const TX_POOL_MAX_LIFETIME: Duration = Duration::from_secs(12 * 60 * 60);
 
     pub const CONSTRAINT_SYSTEM_DIGESTS: [[u8; 16]; 3] = [
         // transaction-merge
@@ -145,6 +198,7 @@ pub mod devnet {
         coinbase_amount: 720000000000,
         supercharged_coinbase_factor: 1,
         account_creation_fee: 1000000000,
+        minimum_user_command_fee: 1000000,
         // TODO(tizoc): This should come from the config file, but
         // it affects the circuits. Since we cannot produce the circuits
         // ourselves right now, we cannot react to changes in this value,
@@ -158,6 +212,11 @@ pub mod devnet {
         }),
     };
 
+    pub const VERIFIER_INDEX_DIGESTS: VerifierIndexDigests = VerifierIndexDigests {
+        block_verifier: "062b7183c4af80ab74cca9c9d0dd6f6031654d22ae94d6ce7310e66b72cdf626",
+        transaction_verifier: "a61a861a471f631ff176ef290921885400001be11e3ea4d49af0a0292ef5549f",
+    };
+
     pub const CIRCUITS_CONFIG: CircuitsConfig = CircuitsConfig {
         directory_name: "berkeley-devnet",
 
@@ -187,7 +246,7 @@ pub mod devnet {
 }
 
 pub mod mainnet {
-    use super::{CircuitsConfig, NetworkId};
+    use super::{CircuitsConfig, NetworkId, VerifierIndexDigests};
     use crate::constants::{ConstraintConstants, ForkConstants};
 
     pub const NETWORK_ID: NetworkId = NetworkId::MAINNET;
@@ -223,6 +282,7 @@ pub mod mainnet {
         coinbase_amount: 720000000000,
         supercharged_coinbase_factor: 1,
         account_creation_fee: 1000000000,
+        minimum_user_command_fee: 1000000,
         // TODO(tizoc): This should come from the config file, but
         // it affects the circuits. Since we cannot produce the circuits
         // ourselves right now, we cannot react to changes in this value,
@@ -236,6 +296,11 @@ pub mod mainnet {
         }),
     };
 
+    pub const VERIFIER_INDEX_DIGESTS: VerifierIndexDigests = VerifierIndexDigests {
+        block_verifier: "c2714d66cc9e5fde240032c8da23493bd6ec8820d47a37e7eaf736a341bda7f5",
+        transaction_verifier: "4191c63348b3735dd8b130d09446b199293524bfaaf437a52a2584392790ba7c",
+    };
+
     pub const CIRCUITS_CONFIG: CircuitsConfig = CircuitsConfig {
         directory_name: "3.0.0mainnet",
 
@@ -8,6 +8,9 @@ pub use request_id::{RequestId, RequestIdType};
 mod rpc_id;
 pub use rpc_id::{RpcId, RpcIdType};
 
+mod retry_policy;
+pub use retry_policy::RequestRetryPolicy;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct PendingRequest<Request> {
     counter: usize,
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Timeout and retries of an effectful request sent to a service.
+///
+/// A service may never answer, e.g. when the thread handling the request
+/// died. Instead of waiting forever, a request which isn't answered within
+/// `timeout` is sent again, at most `max_retries` times, after which it
+/// fails with a timeout error.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestRetryPolicy {
+    pub timeout: Duration,
+    pub max_retries: u8,
+}
+
+impl RequestRetryPolicy {
+    pub const fn new(timeout: Duration, max_retries: u8) -> Self {
+        Self {
+            timeout,
+            max_retries,
+        }
+    }
+
+    /// Whether the request, sent at `since`, is timed out at `now`.
+    pub fn is_timed_out(&self, since: redux::Timestamp, now: redux::Timestamp) -> bool {
+        now.checked_sub(since)
+            .is_some_and(|elapsed| elapsed >= self.timeout)
+    }
+
+    /// Whether a timed out request which was already retried `retries`
+    /// times can be retried again.
+    pub fn can_retry(&self, retries: u8) -> bool {
+        retries < self.max_retries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_policy() {
+        let policy = RequestRetryPolicy::new(Duration::from_secs(10), 2);
+        let since = redux::Timestamp::ZERO + Duration::from_secs(5);
+        assert!(!policy.is_timed_out(since, since + Duration::from_secs(9)));
+        assert!(policy.is_timed_out(since, since + Duration::from_secs(10)));
+        // The clock going backwards isn't a timeout.
+        assert!(!policy.is_timed_out(since, redux::Timestamp::ZERO));
+
+        assert!(policy.can_retry(0));
+        assert!(policy.can_retry(1));
+        assert!(!policy.can_retry(2));
+    }
+}
@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+/// Source of the monotonic time driving the state machine.
+///
+/// Everything time based in the node (slot of the consensus, transaction pool
+/// expiry, block producer scheduling, timeouts) is computed from the time of
+/// the actions, so accelerating this source makes slots go by faster, in the
+/// same way for all of them.
+///
+/// Copies of a source share its origin, so nodes running in the same
+/// process must be given copies of one source to stay in sync. Separate
+/// processes stay in sync by using [`TimeSource::accelerated_since`] with
+/// the same origin.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeSource {
+    origin: redux::Instant,
+    speed: u32,
+}
+
+impl TimeSource {
+    /// Time going by at the speed of the wall clock.
+    pub fn real() -> Self {
+        Self::accelerated(1)
+    }
+
+    /// Time going by `speed` times faster than the wall clock, starting now.
+    ///
+    /// A speed of 0 is treated as 1.
+    pub fn accelerated(speed: u32) -> Self {
+        Self {
+            origin: redux::Instant::now(),
+            speed: speed.max(1),
+        }
+    }
+
+    /// Time going by `speed` times faster than the wall clock, as if it
+    /// was accelerated since the wall clock time `origin`, such as the
+    /// genesis timestamp. An origin in the future is treated as now.
+    pub fn accelerated_since(origin: redux::Timestamp, speed: u32) -> Self {
+        let now = redux::Instant::now();
+        let elapsed = redux::Timestamp::global_now()
+            .checked_sub(origin)
+            .unwrap_or_default();
+        Self {
+            origin: now.checked_sub(elapsed).unwrap_or(now),
+            speed: speed.max(1),
+        }
+    }
+
+    pub fn speed(&self) -> u32 {
+        self.speed
+    }
+
+    pub fn is_accelerated(&self) -> bool {
+        self.speed > 1
+    }
+
+    /// Duration in the time of this source of `real` wall clock time.
+    pub fn scale(&self, real: Duration) -> Duration {
+        real.checked_mul(self.speed).unwrap_or(Duration::MAX)
+    }
+
+    pub fn now(&self) -> redux::Instant {
+        self.at(redux::Instant::now())
+    }
+
+    /// Time of this source at the `real` wall clock instant.
+    pub fn at(&self, real: redux::Instant) -> redux::Instant {
+        if !self.is_accelerated() {
+            return real;
+        }
+        let elapsed = real.saturating_duration_since(self.origin);
+        self.origin + self.scale(elapsed)
+    }
+
+    /// Wall clock time of this source, to use as the initial time of the
+    /// node, so that its timestamps follow the accelerated time too.
+    pub fn global_now(&self) -> redux::Timestamp {
+        let real = redux::Instant::now();
+        let ahead = self.at(real).saturating_duration_since(real);
+        redux::Timestamp::global_now() + ahead
+    }
+}
+
+impl Default for TimeSource {
+    fn default() -> Self {
+        Self::real()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale() {
+        let real = TimeSource::real();
+        assert!(!real.is_accelerated());
+        assert_eq!(real.scale(Duration::from_secs(3)), Duration::from_secs(3));
+
+        let fast = TimeSource::accelerated(100);
+        assert!(fast.is_accelerated());
+        assert_eq!(fast.scale(Duration::from_secs(3)), Duration::from_secs(300));
+        assert_eq!(fast.scale(Duration::MAX), Duration::MAX);
+
+        assert_eq!(TimeSource::accelerated(0).speed(), 1);
+    }
+
+    #[test]
+    fn test_accelerated_now() {
+        let fast = TimeSource::accelerated(1_000);
+        let start = redux::Instant::now();
+        std::thread::sleep(Duration::from_millis(10));
+        let elapsed = fast.now().duration_since(start);
+        assert!(elapsed >= Duration::from_secs(9), "{elapsed:?}");
+    }
+
+    #[test]
+    fn test_copies_share_origin() {
+        let source = TimeSource::accelerated(1_000);
+        let copy = source;
+        std::thread::sleep(Duration::from_millis(10));
+        let later = TimeSource::accelerated(1_000);
+
+        let real = redux::Instant::now();
+        assert_eq!(source.at(real), copy.at(real));
+        // A source created later is behind by the acceleration of the
+        // time in between, which is why nodes must share one source.
+        let behind = source.at(real).duration_since(later.at(real));
+        assert!(behind >= Duration::from_secs(9), "{behind:?}");
+    }
+
+    #[test]
+    fn test_accelerated_since() {
+        let origin = redux::Timestamp::global_now();
+        std::thread::sleep(Duration::from_millis(10));
+        let first = TimeSource::accelerated_since(origin, 1_000);
+        std::thread::sleep(Duration::from_millis(10));
+        let second = TimeSource::accelerated_since(origin, 1_000);
+
+        // Created at different times, but from the same origin.
+        let real = redux::Instant::now();
+        let (a, b) = (first.at(real), second.at(real));
+        let drift = a.max(b).duration_since(a.min(b));
+        assert!(drift < Duration::from_secs(1), "{drift:?}");
+
+        let ahead = first
+            .global_now()
+            .checked_sub(redux::Timestamp::global_now())
+            .unwrap();
+        assert!(ahead >= Duration::from_secs(9), "{ahead:?}");
+
+        let future = redux::Timestamp::global_now() + Duration::from_secs(60);
+        let source = TimeSource::accelerated_since(future, 1_000);
+        let real = redux::Instant::now();
+        assert!(source.at(real).duration_since(real) < Duration::from_secs(1));
+    }
+}
@@ -71,9 +71,65 @@ fn benchmark_merkle_root_computation(c: &mut Criterion) {
     group.finish();
 }
 
+/// Updates a batch of accounts in a mask over a ledger, as when applying a
+/// staged ledger diff, and computes the new merkle root
+fn benchmark_batched_updates(c: &mut Criterion) {
+    const DEPTH: usize = 35;
+
+    let mut group = c.benchmark_group("batched_updates");
+    group.sample_size(10);
+
+    let mut db = Database::<V2>::create(DEPTH as u8);
+    for index in 0..10_000u64 {
+        let mut account = Account::rand();
+        account.token_id = TokenId::from(index);
+        db.get_or_create_account(account.id(), account).unwrap();
+    }
+    let root = Mask::new_root(db);
+
+    for naccounts in [128u64, 1_024] {
+        let list = (0..naccounts)
+            .map(|index| {
+                (
+                    Address::from_index(AccountIndex(index * 7), DEPTH),
+                    Box::new(Account::rand()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut mask = root.make_child();
+        mask.merkle_root();
+        group.bench_with_input(
+            BenchmarkId::new("set_batch", naccounts),
+            &list,
+            |b, list| {
+                b.iter(|| {
+                    mask.set_batch(list);
+                    black_box(mask.merkle_root())
+                });
+            },
+        );
+        mask.unregister_mask(UnregisterBehavior::Check);
+
+        let mut mask = root.make_child();
+        mask.merkle_root();
+        group.bench_with_input(
+            BenchmarkId::new("set_batch_with_hashes", naccounts),
+            &list,
+            |b, list| {
+                b.iter(|| black_box(mask.set_batch_with_hashes(list)));
+            },
+        );
+        mask.unregister_mask(UnregisterBehavior::Check);
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     benchmark_account_generation,
-    benchmark_merkle_root_computation
+    benchmark_merkle_root_computation,
+    benchmark_batched_updates
 );
 criterion_main!(benches);
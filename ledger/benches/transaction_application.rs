@@ -0,0 +1,222 @@
+//! Transaction application benchmarks
+//!
+//! Applies the zkApp commands of a block to a mask over a ledger in both
+//! passes, hashing the mask after each transaction, as the staged ledger
+//! does when applying a diff. The accounts of each zkApp command are
+//! committed to the mask at once, see `Mask::commit_with_hashes`.
+//!
+//! Run with:
+//! ```sh
+//! cargo bench --bench transaction_application
+//! ```
+//!
+//! Or from the workspace root:
+//! ```sh
+//! cd ledger && cargo bench --bench transaction_application
+//! ```
+
+use ark_ff::Zero;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use mina_curves::pasta::Fp;
+use mina_signer::{CompressedPubKey, Signature};
+use mina_tree::{
+    scan_state::{
+        currency::{Amount, Balance, Fee, Length, Magnitude, Nonce, Sgn, Signed, Slot},
+        transaction_logic::{
+            apply_transaction_first_pass, apply_transaction_second_pass,
+            protocol_state::{EpochData, EpochLedger, ProtocolStateView},
+            zkapp_command::{
+                self, AccountPreconditions, AccountUpdate, Actions, AuthorizationKind, CallForest,
+                Control, Events, FeePayer, FeePayerBody, MayUseToken, Numeric, Preconditions,
+                Update, ZkAppCommand, ZkAppPreconditions,
+            },
+            Memo, Transaction, UserCommand,
+        },
+    },
+    *,
+};
+
+const DEPTH: usize = 35;
+/// Accounts of the ledger the block is applied to.
+const LEDGER_ACCOUNTS: usize = 10_000;
+/// zkApp commands included in the block.
+const BLOCK_COMMANDS: usize = 24;
+const FEE: u64 = 10_000_000;
+const AMOUNT: u64 = 1_000_000_000;
+
+fn state_view() -> ProtocolStateView {
+    let epoch_data = || EpochData {
+        ledger: EpochLedger {
+            hash: Fp::zero(),
+            total_currency: Amount::zero(),
+        },
+        seed: Fp::zero(),
+        start_checkpoint: Fp::zero(),
+        lock_checkpoint: Fp::zero(),
+        epoch_length: Length::from_u32(1),
+    };
+    ProtocolStateView {
+        snarked_ledger_hash: Fp::zero(),
+        blockchain_length: Length::from_u32(1),
+        min_window_density: Length::zero(),
+        total_currency: Amount::zero(),
+        global_slot_since_genesis: Slot::zero(),
+        staking_epoch_data: epoch_data(),
+        next_epoch_data: epoch_data(),
+    }
+}
+
+fn account_update(public_key: &CompressedPubKey, balance_change: Signed<Amount>) -> AccountUpdate {
+    let signed = balance_change.sgn == Sgn::Neg;
+    AccountUpdate {
+        body: zkapp_command::Body {
+            public_key: public_key.clone(),
+            token_id: TokenId::default(),
+            update: Update::noop(),
+            balance_change,
+            increment_nonce: false,
+            events: Events::empty(),
+            actions: Actions::empty(),
+            call_data: Fp::zero(),
+            preconditions: Preconditions {
+                network: ZkAppPreconditions::accept(),
+                account: AccountPreconditions(zkapp_command::Account::accept()),
+                valid_while: Numeric::Ignore,
+            },
+            use_full_commitment: signed,
+            implicit_account_creation_fee: false,
+            may_use_token: MayUseToken::No,
+            authorization_kind: if signed {
+                AuthorizationKind::Signature
+            } else {
+                AuthorizationKind::NoneGiven
+            },
+        },
+        authorization: if signed {
+            Control::Signature(Signature::dummy())
+        } else {
+            Control::NoneGiven
+        },
+    }
+}
+
+/// zkApp command of `sender` paying each of `receivers`.
+fn zkapp_command(sender: &CompressedPubKey, receivers: &[CompressedPubKey]) -> Transaction {
+    let total = Amount::from_u64(AMOUNT * receivers.len() as u64);
+    let account_updates = receivers
+        .iter()
+        .rev()
+        .fold(CallForest::new(), |forest, receiver| {
+            let update = account_update(receiver, Signed::of_unsigned(Amount::from_u64(AMOUNT)));
+            forest.cons(None, update)
+        })
+        .cons(
+            None,
+            account_update(sender, Signed::create(total, Sgn::Neg)),
+        );
+
+    let command = ZkAppCommand {
+        fee_payer: FeePayer {
+            body: FeePayerBody {
+                public_key: sender.clone(),
+                fee: Fee::from_u64(FEE),
+                valid_until: None,
+                nonce: Nonce::zero(),
+            },
+            authorization: Signature::dummy(),
+        },
+        account_updates,
+        memo: Memo::empty(),
+    };
+    Transaction::Command(UserCommand::ZkAppCommand(Box::new(command)))
+}
+
+/// A ledger, and a block of zkApp commands each paying `receivers`
+/// accounts of it.
+fn setup(receivers: usize) -> (Mask, Vec<Transaction>) {
+    let keys = (0..LEDGER_ACCOUNTS)
+        .map(|_| gen_compressed())
+        .collect::<Vec<_>>();
+    let mut db = Database::<V2>::create(DEPTH as u8);
+    for key in &keys {
+        let id = AccountId::new(key.clone(), TokenId::default());
+        let account = Account::create_with(id.clone(), Balance::from_u64(1_000_000_000_000));
+        db.get_or_create_account(id, account).unwrap();
+    }
+    let mut root = Mask::new_root(db);
+    root.merkle_root();
+
+    let (senders, receivers_keys) = keys.split_at(BLOCK_COMMANDS);
+    let transactions = senders
+        .iter()
+        .enumerate()
+        .map(|(index, sender)| {
+            let receivers = receivers_keys
+                .iter()
+                .skip(index * receivers)
+                .take(receivers)
+                .cloned()
+                .collect::<Vec<_>>();
+            zkapp_command(sender, &receivers)
+        })
+        .collect();
+
+    (root, transactions)
+}
+
+fn benchmark_zkapp_diff_application(c: &mut Criterion) {
+    let constraint_constants = mina_core::constants::constraint_constants();
+    let state_view = state_view();
+
+    let mut group = c.benchmark_group("zkapp_diff_application");
+    group.sample_size(10);
+
+    for receivers in [4, 32] {
+        let (root, transactions) = setup(receivers);
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(receivers),
+            &transactions,
+            |b, transactions| {
+                b.iter_batched(
+                    || root.make_child(),
+                    |mut mask| {
+                        let partially_applied = transactions
+                            .iter()
+                            .map(|transaction| {
+                                let applied = apply_transaction_first_pass(
+                                    constraint_constants,
+                                    Slot::zero(),
+                                    &state_view,
+                                    &mut mask,
+                                    transaction,
+                                )
+                                .unwrap();
+                                black_box(mask.merkle_root());
+                                applied
+                            })
+                            .collect::<Vec<_>>();
+
+                        for partially_applied in partially_applied {
+                            let applied = apply_transaction_second_pass(
+                                constraint_constants,
+                                &mut mask,
+                                partially_applied,
+                            )
+                            .unwrap();
+                            assert!(applied.transaction_status().is_applied());
+                            black_box(mask.merkle_root());
+                        }
+                        mask.unregister_mask(UnregisterBehavior::Check);
+                    },
+                    BatchSize::PerIteration,
+                );
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_zkapp_diff_application);
+criterion_main!(benches);
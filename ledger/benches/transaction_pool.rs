@@ -0,0 +1,222 @@
+//! Transaction pool benchmarks
+//!
+//! Compares revalidating the entire pool on a new best tip with
+//! revalidating only the commands whose accounts are referenced by the
+//! applied block.
+//!
+//! Run with:
+//! ```sh
+//! cargo bench --bench transaction_pool
+//! ```
+//!
+//! Or from the workspace root:
+//! ```sh
+//! cd ledger && cargo bench --bench transaction_pool
+//! ```
+
+use std::collections::BTreeMap;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use mina_core::consensus::ConsensusConstants;
+use mina_p2p_messages::v2;
+use mina_signer::{CompressedPubKey, Signature};
+use mina_tree::{
+    scan_state::{
+        currency::{Amount, Balance, Fee, Nonce, Slot},
+        transaction_logic::{
+            signed_command::{Body, PaymentPayload, SignedCommand, SignedCommandPayload},
+            valid, Memo, TransactionStatus, WithStatus,
+        },
+    },
+    transaction_pool::{
+        diff::{BestTipDiff, DiffVerified},
+        transaction_hash::hash_command,
+        Config, TransactionPool, DEFAULT_REPLACE_FEE,
+    },
+    *,
+};
+
+/// Commands queued per sender.
+const COMMANDS_PER_SENDER: usize = 3;
+/// Commands included in the applied block.
+const BLOCK_COMMANDS: usize = 128;
+
+struct Setup {
+    pool: TransactionPool,
+    accounts: BTreeMap<AccountId, Account>,
+    diff: BestTipDiff,
+    diff_accounts: BTreeMap<AccountId, Account>,
+}
+
+fn consensus_constants() -> ConsensusConstants {
+    let protocol_constants = v2::MinaBaseProtocolConstantsCheckedValueStableV1 {
+        k: 290.into(),
+        slots_per_epoch: 7140.into(),
+        slots_per_sub_window: 7.into(),
+        grace_period_slots: 2160.into(),
+        delta: 0.into(),
+        genesis_state_timestamp: v2::BlockTimeTimeStableV1(
+            v2::UnsignedExtendedUInt64Int64ForVersionTagsStableV1(0u64.into()),
+        ),
+    };
+    ConsensusConstants::create(
+        mina_core::constants::constraint_constants(),
+        &protocol_constants,
+    )
+}
+
+fn payment(
+    sender: &CompressedPubKey,
+    receiver: &CompressedPubKey,
+    nonce: u32,
+) -> valid::UserCommand {
+    let payload = SignedCommandPayload::create(
+        Fee::from_u64(10_000_000),
+        sender.clone(),
+        Nonce::from_u32(nonce),
+        None,
+        Memo::empty(),
+        Body::Payment(PaymentPayload {
+            receiver_pk: receiver.clone(),
+            amount: Amount::from_u64(1_000_000_000),
+        }),
+    );
+    valid::UserCommand::SignedCommand(Box::new(SignedCommand {
+        payload,
+        signer: sender.clone(),
+        signature: Signature::dummy(),
+    }))
+}
+
+/// A pool with `pool_size` commands, and a block including the first
+/// command of `BLOCK_COMMANDS` of its senders.
+fn setup(pool_size: usize) -> Setup {
+    let receiver = gen_compressed();
+    let senders = (0..pool_size / COMMANDS_PER_SENDER)
+        .map(|_| gen_compressed())
+        .collect::<Vec<_>>();
+
+    let accounts = senders
+        .iter()
+        .map(|pk| {
+            let id = AccountId::new(pk.clone(), TokenId::default());
+            let account = Account::create_with(id.clone(), Balance::from_u64(1_000_000_000_000));
+            (id, account)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let list = senders
+        .iter()
+        .flat_map(|pk| {
+            (0..COMMANDS_PER_SENDER as u32).map(|nonce| hash_command(payment(pk, &receiver, nonce)))
+        })
+        .collect::<Vec<_>>();
+
+    let mut pool = TransactionPool::new(
+        Config {
+            trust_system: (),
+            pool_max_size: pool_size,
+            slot_tx_end: None,
+            max_lifetime: None,
+            zkapp_relay: Default::default(),
+            replace_fee: DEFAULT_REPLACE_FEE,
+        },
+        &consensus_constants(),
+    );
+    pool.unsafe_apply(
+        redux::Timestamp::ZERO,
+        Slot::zero(),
+        Slot::zero(),
+        &DiffVerified { list },
+        &accounts,
+        false,
+    )
+    .unwrap();
+    assert_eq!(pool.size(), pool_size);
+
+    let new_commands = senders
+        .iter()
+        .take(BLOCK_COMMANDS)
+        .map(|pk| WithStatus {
+            data: payment(pk, &receiver, 0),
+            status: TransactionStatus::Applied,
+        })
+        .collect::<Vec<_>>();
+    let diff = BestTipDiff {
+        new_commands,
+        removed_commands: Vec::new(),
+        reorg_best_tip: false,
+    };
+
+    let (account_ids, _uncommitted) = pool.get_accounts_to_handle_transition_diff(&diff);
+    let diff_accounts = account_ids
+        .iter()
+        .filter_map(|id| {
+            let mut account = accounts.get(id)?.clone();
+            account.nonce = account.nonce.incr();
+            Some((id.clone(), account))
+        })
+        .collect();
+
+    Setup {
+        pool,
+        accounts,
+        diff,
+        diff_accounts,
+    }
+}
+
+fn benchmark_revalidation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("transaction_pool_revalidation");
+    group.sample_size(10);
+
+    for pool_size in [300, 3_000] {
+        let setup = setup(pool_size);
+
+        group.bench_with_input(
+            BenchmarkId::new("entire_pool", pool_size),
+            &setup,
+            |b, setup| {
+                b.iter_batched(
+                    || setup.pool.clone(),
+                    |mut pool| {
+                        let dropped = pool.on_new_best_tip(Slot::zero(), &setup.accounts).unwrap();
+                        black_box((pool, dropped))
+                    },
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("applied_block", pool_size),
+            &setup,
+            |b, setup| {
+                let (account_ids, _uncommitted) = setup
+                    .pool
+                    .get_accounts_to_handle_transition_diff(&setup.diff);
+                b.iter_batched(
+                    || setup.pool.clone(),
+                    |mut pool| {
+                        pool.handle_transition_frontier_diff(
+                            Slot::zero(),
+                            Slot::zero(),
+                            &setup.diff,
+                            &account_ids,
+                            &setup.diff_accounts,
+                            &BTreeMap::new(),
+                        )
+                        .unwrap();
+                        black_box(pool)
+                    },
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_revalidation);
+criterion_main!(benches);
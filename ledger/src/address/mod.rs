@@ -10,7 +10,11 @@ const fn compute_nbytes(nbits: usize) -> usize {
 
 /// Devnet uses trees of depth 35, which requires addresses of 35 bits
 const NBITS: usize = 35;
-const NBYTES: usize = compute_nbytes(NBITS);
+
+/// Deepest ledger supported, for custom networks which need more accounts
+/// than the 2^35 of devnet and mainnet
+pub const MAX_LEDGER_DEPTH: usize = 48;
+const NBYTES: usize = compute_nbytes(MAX_LEDGER_DEPTH);
 
 pub use raw::Direction;
 pub type Address = raw::Address<NBYTES>;
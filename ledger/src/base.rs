@@ -13,7 +13,10 @@ use crate::{
     account::{Account, AccountId, TokenId},
     address::Address,
     database::DatabaseError,
-    scan_state::transaction_logic::AccountState,
+    scan_state::{
+        currency::{Amount, Magnitude},
+        transaction_logic::AccountState,
+    },
     sparse_ledger::LedgerIntf,
     Mask,
 };
@@ -89,6 +92,38 @@ pub trait BaseLedger {
     /// Get all of the tokens for which a public key has accounts.
     fn tokens(&self, public_key: CompressedPubKey) -> HashSet<TokenId>;
 
+    /// Get at most `limit` accounts of `token_id`, which are its holders,
+    /// skipping the first `offset` of them in the order of their index.
+    fn accounts_for_token(&self, token_id: &TokenId, offset: usize, limit: usize) -> Vec<Account> {
+        let mut skipped = 0;
+        self.fold_until(Vec::new(), |mut accounts, account| {
+            if &account.token_id == token_id {
+                if skipped < offset {
+                    skipped += 1;
+                } else {
+                    accounts.push(account.clone());
+                }
+            }
+            if accounts.len() < limit {
+                ControlFlow::Continue(accounts)
+            } else {
+                ControlFlow::Break(accounts)
+            }
+        })
+    }
+
+    /// Total supply of `token_id`: the sum of the balances of its accounts.
+    /// `None` if it doesn't fit in an amount, which the protocol doesn't
+    /// prevent for custom tokens.
+    fn token_supply(&self, token_id: &TokenId) -> Option<Amount> {
+        self.fold(Some(Amount::zero()), |supply, account| {
+            if &account.token_id != token_id {
+                return supply;
+            }
+            supply?.checked_add(&account.balance.to_amount())
+        })
+    }
+
     fn location_of_account(&self, account_id: &AccountId) -> Option<Address>;
 
     fn location_of_account_batch(
@@ -280,10 +315,6 @@ impl LedgerIntf for Mask {
         Ok(())
     }
 
-    fn remove_accounts_exn(&mut self, account_ids: &[AccountId]) {
-        BaseLedger::remove_accounts(self, account_ids)
-    }
-
     fn merkle_root(&mut self) -> Fp {
         BaseLedger::merkle_root(self)
     }
@@ -316,7 +347,9 @@ impl LedgerIntf for Mask {
     fn apply_mask(&mut self, mask: Self) {
         // ignore `self` here:
         // <https://github.com/MinaProtocol/mina/blob/f6756507ff7380a691516ce02a3cf7d9d32915ae/src/lib/mina_ledger/ledger.ml#L236-L246>
-        mask.commit()
+        // The accounts of a zkApp command are committed at once, the ledger is
+        // hashed right after its application anyway.
+        mask.commit_with_hashes();
     }
 
     fn account_locations(&self) -> Vec<Self::Location> {
@@ -11,6 +11,7 @@ use crate::{
     account::{Account, AccountId, TokenId},
     address::Address,
     base::{AccountIndex, BaseLedger, GetOrCreated, MerklePath, Uuid},
+    ondisk::IoFaults,
     // tree::{Database, DatabaseError},
     tree_version::V2,
     TreeVersion,
@@ -67,6 +68,35 @@ impl Database<V2> {
         db
     }
 
+    /// Creates a ledger whose accounts are stored in `directory` instead of
+    /// in memory, or opens the one already stored there. It can be the root
+    /// of masks like any other database.
+    pub fn create_ondisk(depth: u8, directory: PathBuf) -> std::io::Result<Self> {
+        let db = DatabaseImpl::<V2>::create_ondisk(depth, directory)?;
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(db)),
+        })
+    }
+
+    pub fn is_ondisk(&self) -> bool {
+        self.with(|this| this.is_ondisk())
+    }
+
+    /// Injects latency and failures into the I/O of an on-disk ledger, and
+    /// of its copies. Only meant for tests.
+    pub fn set_io_faults(&mut self, faults: IoFaults) {
+        self.with(|this| this.set_io_faults(faults))
+    }
+
+    pub fn set_batch_with_hashes(&mut self, list: &[(Address, Box<Account>)]) -> Fp {
+        self.with(|this| this.set_batch_with_hashes(list))
+    }
+
+    pub fn recompute_hashes_parallel(&mut self) -> Fp {
+        self.with(|this| this.recompute_hashes_parallel())
+    }
+
     pub fn set_token_owners(&mut self) {
         self.with(|this| this.set_token_owners());
     }
@@ -602,6 +632,96 @@ export function performance_now() {
         assert_eq!(account_empty_hash.to_hex(), hexs[0]);
     }
 
+    /// Ledgers deeper than the built-in depth of 35 hash like a ledger of
+    /// depth 35 completed with empty subtrees
+    #[test]
+    fn test_depth_beyond_built_in() {
+        const DEPTH: u8 = 40;
+        let mut db = Database::<V2>::create(DEPTH);
+        assert_eq!(db.merkle_root(), V2::empty_hash_at_height(DEPTH as usize));
+
+        let mut shallow = Database::<V2>::create(35);
+        for _ in 0..20 {
+            let account = Account::rand();
+            db.get_or_create_account(account.id(), account.clone())
+                .unwrap();
+            shallow
+                .get_or_create_account(account.id(), account)
+                .unwrap();
+        }
+
+        let root = (35..DEPTH as usize).fold(shallow.merkle_root(), |hash, height| {
+            V2::hash_node(height, hash, V2::empty_hash_at_height(height))
+        });
+        assert_eq!(db.merkle_root(), root);
+    }
+
+    /// Recomputing all the hashes in parallel gives the same root as hashing
+    /// the tree path by path
+    #[test]
+    fn test_recompute_hashes_parallel() {
+        const DEPTH: u8 = 10;
+
+        for naccounts in [0, 1, 300, 1 << DEPTH] {
+            let mut db = Database::<V2>::create(DEPTH);
+            for _ in 0..naccounts {
+                let account = Account::rand();
+                db.get_or_create_account(account.id(), account).unwrap();
+            }
+            let mut other = db.clone_db(PathBuf::from("/tmp/other"));
+
+            let merkle_root = other.recompute_hashes_parallel();
+
+            assert_eq!(db.merkle_root(), merkle_root, "naccounts={naccounts}");
+            assert_eq!(other.merkle_root(), merkle_root, "naccounts={naccounts}");
+            assert_eq!(
+                db.merkle_path_at_index(AccountIndex(0)),
+                other.merkle_path_at_index(AccountIndex(0))
+            );
+        }
+    }
+
+    #[test]
+    fn test_accounts_for_token() {
+        use crate::{
+            gen_compressed,
+            scan_state::currency::{Amount, Balance, Magnitude},
+        };
+
+        let mut db = Database::<V2>::create(10);
+        let token_id = TokenId::from(42);
+
+        for index in 0..20u64 {
+            let token = if index % 4 == 0 {
+                token_id.clone()
+            } else {
+                TokenId::default()
+            };
+            let account_id = AccountId::new(gen_compressed(), token);
+            let account = Account::create_with(account_id.clone(), Balance::from_u64(index));
+            db.get_or_create_account(account_id, account).unwrap();
+        }
+
+        let holders = db.accounts_for_token(&token_id, 0, usize::MAX);
+        assert_eq!(holders.len(), 5);
+        assert!(holders.iter().all(|account| account.token_id == token_id));
+
+        let page = db.accounts_for_token(&token_id, 1, 2);
+        assert_eq!(page, holders[1..3]);
+        assert_eq!(db.accounts_for_token(&token_id, 4, 2), holders[4..]);
+        assert!(db.accounts_for_token(&token_id, 5, 2).is_empty());
+        assert_eq!(
+            db.token_supply(&token_id),
+            Some(Amount::from_u64(4 + 8 + 12 + 16))
+        );
+        assert_eq!(db.token_supply(&TokenId::from(43)), Some(Amount::zero()));
+
+        let account_id = AccountId::new(gen_compressed(), token_id.clone());
+        let account = Account::create_with(account_id.clone(), Balance::max());
+        db.get_or_create_account(account_id, account).unwrap();
+        assert_eq!(db.token_supply(&token_id), None);
+    }
+
     // /// An empty tree produces the same hash than a tree full of empty accounts
     // #[test]
     // fn test_root_hash_v2() {
@@ -671,6 +791,41 @@ export function performance_now() {
         assert_eq!(root_hash_1, root_hash_3);
     }
 
+    /// Batched updates give the same hashes as updating accounts one by one
+    #[test]
+    fn test_set_batch_with_hashes() {
+        const DEPTH: u8 = 10;
+        let mut db = Database::<V2>::create(DEPTH);
+
+        let accounts = (0..300).map(|_| Account::rand()).collect::<Vec<_>>();
+        for account in &accounts {
+            db.get_or_create_account(account.id(), account.clone())
+                .unwrap();
+        }
+        db.merkle_root();
+        let mut other = db.clone_db(PathBuf::from("/tmp/other"));
+
+        let list = (0..100)
+            .map(|index| index * 3)
+            .chain(300..310)
+            .map(|index| {
+                (
+                    Address::from_index(AccountIndex(index), DEPTH as usize),
+                    Box::new(Account::rand()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for (addr, account) in &list {
+            db.set(addr.clone(), account.clone());
+        }
+        let merkle_root = other.set_batch_with_hashes(&list);
+
+        assert_eq!(db.merkle_root(), merkle_root);
+        assert_eq!(other.merkle_root(), merkle_root);
+        assert_eq!(db.num_accounts(), other.num_accounts());
+    }
+
     // /// An empty tree produces the same hash than a tree full of empty accounts
     // #[test]
     // fn test_root_hash_legacy() {
@@ -705,6 +860,100 @@ export function performance_now() {
     //         "2db7d27130b6fe46b95541a70bc69ac51d9ea02825f7a7ab41ec4c414989421e"
     //     );
     // }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn ondisk_directory() -> PathBuf {
+        std::env::temp_dir().join(format!("minadb-ondisk-test-{}", crate::next_uuid()))
+    }
+
+    /// On-disk ledgers have the same hashes as in-memory ledgers, and keep
+    /// their accounts once reopened
+    #[cfg(not(target_family = "wasm"))]
+    #[test]
+    fn test_ondisk_same_as_in_memory() {
+        const DEPTH: u8 = 10;
+        let directory = ondisk_directory();
+
+        let mut db = Database::<V2>::create(DEPTH);
+        let mut ondisk = Database::<V2>::create_ondisk(DEPTH, directory.clone()).unwrap();
+        assert!(ondisk.is_ondisk());
+
+        let accounts = (0..100).map(|_| Account::rand()).collect::<Vec<_>>();
+        for account in &accounts {
+            db.get_or_create_account(account.id(), account.clone())
+                .unwrap();
+            ondisk
+                .get_or_create_account(account.id(), account.clone())
+                .unwrap();
+        }
+        let updated = (0..10)
+            .map(|index| {
+                (
+                    Address::from_index(AccountIndex(index), DEPTH as usize),
+                    Box::new(Account::rand()),
+                )
+            })
+            .collect::<Vec<_>>();
+        db.set_batch(&updated);
+        ondisk.set_batch(&updated);
+        let removed = [accounts[99].id(), accounts[50].id()];
+        db.remove_accounts(&removed);
+        ondisk.remove_accounts(&removed);
+
+        assert_eq!(db.merkle_root(), ondisk.merkle_root());
+        assert_eq!(db.to_list(), ondisk.to_list());
+        assert_eq!(db.num_accounts(), ondisk.num_accounts());
+        assert_eq!(db.last_filled(), ondisk.last_filled());
+
+        let merkle_root = ondisk.merkle_root();
+        drop(ondisk);
+        let mut ondisk = Database::<V2>::create_ondisk(DEPTH, directory.clone()).unwrap();
+
+        assert_eq!(ondisk.merkle_root(), merkle_root);
+        assert_eq!(db.to_list(), ondisk.to_list());
+        assert_eq!(db.last_filled(), ondisk.last_filled());
+        for account in &updated {
+            assert_eq!(
+                ondisk.location_of_account(&account.1.id()),
+                Some(account.0.clone())
+            );
+        }
+        assert!(ondisk.location_of_account(&accounts[50].id()).is_none());
+
+        drop(ondisk);
+        assert!(Database::<V2>::create_ondisk(DEPTH + 1, directory.clone()).is_err());
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    /// Masks commit their accounts to an on-disk root
+    #[cfg(not(target_family = "wasm"))]
+    #[test]
+    fn test_ondisk_mask_root() {
+        const DEPTH: u8 = 10;
+        let directory = ondisk_directory();
+
+        let db = Database::<V2>::create_ondisk(DEPTH, directory.clone()).unwrap();
+        let root = crate::Mask::new_root(db);
+        let mut mask = root.make_child();
+
+        for _ in 0..20 {
+            let account = Account::rand();
+            mask.get_or_create_account(account.id(), account).unwrap();
+        }
+        let merkle_root = mask.merkle_root();
+        mask.commit();
+        assert_eq!(root.clone().merkle_root(), merkle_root);
+
+        mask.unregister_mask(crate::UnregisterBehavior::Check);
+        drop(mask);
+        drop(root);
+        let mut db = Database::<V2>::create_ondisk(DEPTH, directory.clone()).unwrap();
+        assert_eq!(db.merkle_root(), merkle_root);
+        assert_eq!(db.num_accounts(), 20);
+
+        drop(db);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
 }
 
 #[cfg(test)]
@@ -1,4 +1,5 @@
 use std::{
+    borrow::Cow,
     collections::{HashMap, HashSet},
     ops::ControlFlow,
     path::PathBuf,
@@ -8,16 +9,103 @@ use mina_curves::pasta::Fp;
 use mina_signer::CompressedPubKey;
 
 use crate::{
-    next_uuid, Account, AccountId, AccountIndex, AccountLegacy, Address, AddressIterator,
-    BaseLedger, Direction, GetOrCreated, HashesMatrix, MerklePath, TokenId, TreeVersion, Uuid, V1,
-    V2,
+    next_uuid,
+    ondisk::{AccountStore, IoFaults},
+    tree::{hash_accounts, hash_dirty_nodes, recompute_tree_parallel},
+    Account, AccountId, AccountIndex, AccountLegacy, Address, AddressIterator, BaseLedger,
+    Direction, GetOrCreated, HashesMatrix, MerklePath, TokenId, TreeVersion, Uuid,
+    MAX_LEDGER_DEPTH, V1, V2,
 };
 
 use super::DatabaseError;
 
-#[derive(Clone)]
+/// Where the accounts of a ledger are stored
+enum Accounts<A> {
+    InMemory(Vec<Option<A>>),
+    OnDisk(AccountStore),
+}
+
+impl<A> Accounts<A> {
+    fn len(&self) -> usize {
+        match self {
+            Self::InMemory(accounts) => accounts.len(),
+            Self::OnDisk(store) => store.len(),
+        }
+    }
+}
+
+impl Accounts<Account> {
+    fn get(&self, index: usize) -> Option<Cow<'_, Account>> {
+        match self {
+            Self::InMemory(accounts) => accounts.get(index)?.as_ref().map(Cow::Borrowed),
+            Self::OnDisk(store) => store.get(index).map(Cow::Owned),
+        }
+    }
+
+    fn take(&mut self, index: usize) -> Option<Account> {
+        match self {
+            Self::InMemory(accounts) => accounts.get_mut(index)?.take(),
+            Self::OnDisk(store) => store.take(index),
+        }
+    }
+
+    fn set(&mut self, index: usize, account: Account) {
+        match self {
+            Self::InMemory(accounts) => {
+                if accounts.len() <= index {
+                    accounts.resize(index + 1, None);
+                }
+                accounts[index] = Some(account);
+            }
+            Self::OnDisk(store) => store.set(index, account),
+        }
+    }
+
+    /// Writes the changes to disk, for on-disk ledgers
+    fn flush(&mut self) {
+        match self {
+            Self::InMemory(_) => {}
+            Self::OnDisk(store) => store.flush(),
+        }
+    }
+
+    /// Folds the accounts in order of their index, until `fun` breaks
+    fn fold_until<B, F>(&self, init: B, mut fun: F) -> B
+    where
+        F: FnMut(B, usize, &Account) -> ControlFlow<B, B>,
+    {
+        match self {
+            Self::InMemory(accounts) => {
+                let mut accum = init;
+                for (index, account) in accounts.iter().enumerate() {
+                    let Some(account) = account else {
+                        continue;
+                    };
+                    match fun(accum, index, account) {
+                        ControlFlow::Continue(v) => accum = v,
+                        ControlFlow::Break(v) => return v,
+                    }
+                }
+                accum
+            }
+            Self::OnDisk(store) => {
+                store.fold_until(init, |accum, index, account| fun(accum, index, &account))
+            }
+        }
+    }
+
+    fn fold<B, F>(&self, init: B, mut fun: F) -> B
+    where
+        F: FnMut(B, usize, &Account) -> B,
+    {
+        self.fold_until(init, |accum, index, account| {
+            ControlFlow::Continue(fun(accum, index, account))
+        })
+    }
+}
+
 pub struct DatabaseImpl<T: TreeVersion> {
-    accounts: Vec<Option<T::Account>>,
+    accounts: Accounts<T::Account>,
     pub hashes_matrix: HashesMatrix,
     id_to_addr: HashMap<AccountId, Address>,
     token_owners: Option<HashMap<T::TokenId, AccountId>>,
@@ -46,15 +134,34 @@ impl<T: TreeVersion> std::fmt::Debug for DatabaseImpl<T> {
 
 impl DatabaseImpl<V2> {
     pub fn clone_db(&self, new_directory: PathBuf) -> Self {
+        let uuid = next_uuid();
+
+        let (accounts, directory) = match &self.accounts {
+            Accounts::InMemory(accounts) => (Accounts::InMemory(accounts.clone()), new_directory),
+            Accounts::OnDisk(store) => {
+                // Two stores can't share a directory, masks copy their root
+                // database into the same directory
+                let directory = if new_directory == self.directory {
+                    self.directory.with_file_name(format!("minadb-{uuid}"))
+                } else {
+                    new_directory
+                };
+                let store = store
+                    .copy(&directory)
+                    .expect("failed to copy ledger accounts");
+                (Accounts::OnDisk(store), directory)
+            }
+        };
+
         Self {
-            accounts: self.accounts.clone(),
+            accounts,
             id_to_addr: self.id_to_addr.clone(),
             token_owners: self.token_owners.clone(),
             depth: self.depth,
             last_location: self.last_location.clone(),
             naccounts: self.naccounts,
-            uuid: next_uuid(),
-            directory: new_directory,
+            uuid,
+            directory,
             hashes_matrix: self.hashes_matrix.clone(),
         }
     }
@@ -63,11 +170,7 @@ impl DatabaseImpl<V2> {
         let index = addr.to_index();
         let index: usize = index.0 as usize;
 
-        if let Some(account) = self.accounts.get_mut(index) {
-            return account.take();
-        }
-
-        None
+        self.accounts.take(index)
     }
 
     fn create_account(
@@ -86,7 +189,7 @@ impl DatabaseImpl<V2> {
         };
 
         assert_eq!(location.to_index(), self.accounts.len());
-        self.accounts.push(Some(account));
+        self.accounts.set(self.accounts.len(), account);
 
         self.last_location = Some(location.clone());
         self.naccounts += 1;
@@ -101,21 +204,104 @@ impl DatabaseImpl<V2> {
         Ok(GetOrCreated::Added(location))
     }
 
+    /// Sets the account without writing it to disk yet, for on-disk ledgers
+    fn set_account(&mut self, addr: Address, account: Box<Account>) {
+        let index = addr.to_index();
+
+        self.hashes_matrix.invalidate_hashes(index);
+
+        let index: usize = index.0 as usize;
+
+        let id = account.id();
+
+        // Remove account at the address and it's index
+        if let Some(account) = self.get(addr.clone()) {
+            let id = account.id();
+            self.id_to_addr.remove(&id);
+            if !id.token_id.is_default() {
+                if let Some(token_owners) = self.token_owners.as_mut() {
+                    token_owners.remove(&id.derive_token_id());
+                }
+            }
+        } else {
+            self.naccounts += 1;
+        }
+
+        if !account.token_id.is_default() {
+            if let Some(token_owners) = self.token_owners.as_mut() {
+                token_owners.insert(account.id().derive_token_id(), id.clone());
+            }
+        }
+        self.id_to_addr.insert(id, addr.clone());
+        self.accounts.set(index, *account);
+
+        if self
+            .last_location
+            .as_ref()
+            .map(|l| l.to_index() < addr.to_index())
+            .unwrap_or(true)
+        {
+            self.last_location = Some(addr);
+        }
+    }
+
+    /// Like `set_batch`, but hashes the accounts in parallel and the nodes
+    /// above them once each, instead of leaving `merkle_root` to recompute
+    /// them path by path. Returns the new merkle root.
+    pub fn set_batch_with_hashes(&mut self, list: &[(Address, Box<Account>)]) -> Fp {
+        let depth = self.depth as usize;
+
+        for (addr, account) in list {
+            assert_eq!(addr.length(), depth, "addr={:?}", addr);
+            self.set_account(addr.clone(), account.clone());
+        }
+        self.accounts.flush();
+
+        if list.is_empty() {
+            return self.merkle_root();
+        }
+
+        let leaves = hash_accounts(list);
+        let hashes = hash_dirty_nodes(depth, leaves, |addr| self.emulate_tree_to_get_hash_at(addr));
+
+        for (addr, hash) in &hashes {
+            self.hashes_matrix.set(addr, *hash);
+        }
+
+        let (_, root) = hashes.last().expect("the root is hashed last");
+        *root
+    }
+
+    /// Drops all the hashes and recomputes them from the accounts, hashing
+    /// each level of the tree in parallel. Faster than `merkle_root` on a
+    /// freshly loaded ledger. Returns the merkle root.
+    pub fn recompute_hashes_parallel(&mut self) -> Fp {
+        let depth = self.depth as usize;
+
+        let mut accounts = Vec::with_capacity(self.num_accounts());
+        self.iter_with_addr(|addr, account| accounts.push((addr, Box::new(account.clone()))));
+
+        let hashes = recompute_tree_parallel(depth, &accounts);
+
+        self.hashes_matrix.clear();
+        for (addr, hash) in &hashes {
+            self.hashes_matrix.set(addr, *hash);
+        }
+
+        let (_, root) = hashes.last().expect("the root is hashed last");
+        *root
+    }
+
     pub fn iter_with_addr<F>(&self, mut fun: F)
     where
         F: FnMut(Address, &Account),
     {
         let depth = self.depth as usize;
 
-        for (index, account) in self.accounts.iter().enumerate() {
-            let account = match account {
-                Some(account) => account,
-                None => continue,
-            };
-
+        self.accounts.fold((), |(), index, account| {
             let addr = Address::from_index(index.into(), depth);
             fun(addr, account);
-        }
+        });
     }
 
     fn emulate_tree_to_get_hash_at(&mut self, addr: Address) -> Fp {
@@ -269,8 +455,11 @@ impl DatabaseImpl<V1> {
             None => Address::first(self.depth as usize),
         };
 
-        assert_eq!(location.to_index(), self.accounts.len());
-        self.accounts.push(Some(account));
+        let Accounts::InMemory(accounts) = &mut self.accounts else {
+            unreachable!("legacy ledgers are only kept in memory")
+        };
+        assert_eq!(location.to_index(), accounts.len());
+        accounts.push(Some(account));
 
         self.last_location = Some(location.clone());
         self.naccounts += 1;
@@ -284,7 +473,10 @@ impl DatabaseImpl<V2> {
     const NTOKENS: usize = 10;
 
     pub fn create_with_dir(depth: u8, dir_name: Option<PathBuf>) -> Self {
-        assert!((1..0xfe).contains(&depth));
+        assert!(
+            (1..=MAX_LEDGER_DEPTH).contains(&(depth as usize)),
+            "unsupported ledger depth: {depth}"
+        );
 
         let uuid = next_uuid();
 
@@ -301,7 +493,7 @@ impl DatabaseImpl<V2> {
 
         Self {
             depth,
-            accounts: Vec::with_capacity(Self::NACCOUNTS),
+            accounts: Accounts::InMemory(Vec::with_capacity(Self::NACCOUNTS)),
             last_location: None,
             naccounts: 0,
             id_to_addr: HashMap::with_capacity(Self::NACCOUNTS),
@@ -322,9 +514,54 @@ impl DatabaseImpl<V2> {
         db
     }
 
+    /// Creates a ledger whose accounts are stored in `directory` instead of
+    /// in memory, or opens the one already stored there.
+    ///
+    /// Only the accounts are stored, their index and the hashes are
+    /// recomputed when the ledger is opened.
+    pub fn create_ondisk(depth: u8, directory: PathBuf) -> std::io::Result<Self> {
+        let store = AccountStore::open(&directory, depth)?;
+
+        let mut db = Self::create_with_dir(depth, Some(directory));
+        db.accounts = Accounts::OnDisk(store);
+
+        let ids = db.accounts.fold(Vec::new(), |mut ids, index, account| {
+            ids.push((index, account.id()));
+            ids
+        });
+        for (index, id) in ids {
+            let addr = Address::from_index(index.into(), depth as usize);
+            db.id_to_addr.insert(id, addr.clone());
+            db.last_location = Some(addr);
+            db.naccounts += 1;
+        }
+
+        Ok(db)
+    }
+
+    pub fn is_ondisk(&self) -> bool {
+        matches!(self.accounts, Accounts::OnDisk(_))
+    }
+
+    /// See [`AccountStore::set_io_faults`]. Does nothing for in-memory
+    /// ledgers.
+    pub fn set_io_faults(&mut self, faults: IoFaults) {
+        if let Accounts::OnDisk(store) = &mut self.accounts {
+            store.set_io_faults(faults);
+        }
+    }
+
     pub fn set_token_owners(&mut self) {
         if self.token_owners.is_none() {
-            self.token_owners = Some(HashMap::with_capacity(Self::NTOKENS));
+            let mut token_owners = HashMap::with_capacity(Self::NTOKENS);
+            // Accounts of a ledger opened from disk
+            self.accounts.fold((), |(), _, account| {
+                if !account.token_id.is_default() {
+                    let id = account.id();
+                    token_owners.insert(id.derive_token_id(), id);
+                }
+            });
+            self.token_owners = Some(token_owners);
         }
     }
 
@@ -338,45 +575,38 @@ impl DatabaseImpl<V2> {
 
     // Do not use
     pub fn naccounts(&self) -> usize {
-        self.accounts.iter().filter_map(Option::as_ref).count()
+        self.accounts.fold(0, |count, _, _| count + 1)
     }
 
-    fn get_account_ref(&self, addr: Address) -> Option<&Account> {
+    fn get_account_ref(&self, addr: Address) -> Option<Cow<'_, Account>> {
         let index = addr.to_index();
         let index: usize = index.0 as usize;
 
-        self.accounts.get(index)?.as_ref()
+        self.accounts.get(index)
     }
 }
 
 impl BaseLedger for DatabaseImpl<V2> {
     fn to_list(&self) -> Vec<Account> {
-        self.accounts
-            .iter()
-            .filter_map(Option::as_ref)
-            .cloned()
-            .collect()
+        self.accounts.fold(Vec::new(), |mut list, _, account| {
+            list.push(account.clone());
+            list
+        })
     }
 
-    fn iter<F>(&self, fun: F)
+    fn iter<F>(&self, mut fun: F)
     where
         F: FnMut(&Account),
     {
-        self.accounts
-            .iter()
-            .filter_map(Option::as_ref)
-            .for_each(fun);
+        self.accounts.fold((), |(), _, account| fun(account));
     }
 
     fn fold<B, F>(&self, init: B, mut fun: F) -> B
     where
         F: FnMut(B, &Account) -> B,
     {
-        let mut accum = init;
-        for account in self.accounts.iter().filter_map(Option::as_ref) {
-            accum = fun(accum, account);
-        }
-        accum
+        self.accounts
+            .fold(init, |accum, _, account| fun(accum, account))
     }
 
     fn fold_with_ignored_accounts<B, F>(
@@ -388,34 +618,21 @@ impl BaseLedger for DatabaseImpl<V2> {
     where
         F: FnMut(B, &Account) -> B,
     {
-        let mut accum = init;
-        for account in self.accounts.iter().filter_map(Option::as_ref) {
-            let account_id = account.id();
-
-            if !ignoreds.contains(&account_id) {
-                accum = fun(accum, account);
+        self.accounts.fold(init, |accum, _, account| {
+            if ignoreds.contains(&account.id()) {
+                accum
+            } else {
+                fun(accum, account)
             }
-        }
-        accum
+        })
     }
 
     fn fold_until<B, F>(&self, init: B, mut fun: F) -> B
     where
         F: FnMut(B, &Account) -> ControlFlow<B, B>,
     {
-        let mut accum = init;
-        for account in self.accounts.iter().filter_map(Option::as_ref) {
-            match fun(accum, account) {
-                ControlFlow::Continue(v) => {
-                    accum = v;
-                }
-                ControlFlow::Break(v) => {
-                    accum = v;
-                    break;
-                }
-            }
-        }
-        accum
+        self.accounts
+            .fold_until(init, |accum, _, account| fun(accum, account))
     }
 
     fn accounts(&self) -> HashSet<AccountId> {
@@ -431,11 +648,11 @@ impl BaseLedger for DatabaseImpl<V2> {
     fn tokens(&self, public_key: CompressedPubKey) -> HashSet<TokenId> {
         let mut set = HashSet::with_capacity(100);
 
-        for account in self.accounts.iter().filter_map(Option::as_ref) {
+        self.accounts.fold((), |(), _, account| {
             if account.public_key == public_key {
                 set.insert(account.token_id.clone());
             }
-        }
+        });
         set
     }
 
@@ -475,6 +692,7 @@ impl BaseLedger for DatabaseImpl<V2> {
         if let Ok(GetOrCreated::Added(addr)) = result.as_ref() {
             let account_index = addr.to_index();
             self.hashes_matrix.invalidate_hashes(account_index);
+            self.accounts.flush();
         };
 
         result
@@ -519,7 +737,8 @@ impl BaseLedger for DatabaseImpl<V2> {
 
     #[inline(never)]
     fn get(&self, addr: Address) -> Option<Box<Account>> {
-        self.get_account_ref(addr).cloned().map(Box::new)
+        self.get_account_ref(addr)
+            .map(|account| Box::new(account.into_owned()))
     }
 
     fn get_batch(&self, addr: &[Address]) -> Vec<(Address, Option<Box<Account>>)> {
@@ -534,55 +753,17 @@ impl BaseLedger for DatabaseImpl<V2> {
     }
 
     fn set(&mut self, addr: Address, account: Box<Account>) {
-        let index = addr.to_index();
-
-        self.hashes_matrix.invalidate_hashes(index);
-
-        let index: usize = index.0 as usize;
-
-        if self.accounts.len() <= index {
-            self.accounts.resize(index + 1, None);
-        }
-
-        let id = account.id();
-
-        // Remove account at the address and it's index
-        if let Some(account) = self.get(addr.clone()) {
-            let id = account.id();
-            self.id_to_addr.remove(&id);
-            if !id.token_id.is_default() {
-                if let Some(token_owners) = self.token_owners.as_mut() {
-                    token_owners.remove(&id.derive_token_id());
-                }
-            }
-        } else {
-            self.naccounts += 1;
-        }
-
-        if !account.token_id.is_default() {
-            if let Some(token_owners) = self.token_owners.as_mut() {
-                token_owners.insert(account.id().derive_token_id(), id.clone());
-            }
-        }
-        self.id_to_addr.insert(id, addr.clone());
-        self.accounts[index] = Some(*account);
-
-        if self
-            .last_location
-            .as_ref()
-            .map(|l| l.to_index() < addr.to_index())
-            .unwrap_or(true)
-        {
-            self.last_location = Some(addr);
-        }
+        self.set_account(addr, account);
+        self.accounts.flush();
     }
 
     fn set_batch(&mut self, list: &[(Address, Box<Account>)]) {
         elog!("SET_BATCH {:?}", list.len());
         for (addr, account) in list {
             assert_eq!(addr.length(), self.depth as usize, "addr={:?}", addr);
-            self.set(addr.clone(), account.clone());
+            self.set_account(addr.clone(), account.clone());
         }
+        self.accounts.flush();
     }
 
     fn get_at_index(&self, index: AccountIndex) -> Option<Box<Account>> {
@@ -664,6 +845,7 @@ impl BaseLedger for DatabaseImpl<V2> {
                 self.last_location = addr.prev();
             }
         }
+        self.accounts.flush();
     }
 
     fn detached_signal(&mut self) {
@@ -705,8 +887,9 @@ impl BaseLedger for DatabaseImpl<V2> {
         }
 
         for (child_addr, account) in addr.iter_children(self.depth as usize).zip(accounts) {
-            self.set(child_addr, account.clone());
+            self.set_account(child_addr, account.clone());
         }
+        self.accounts.flush();
 
         Ok(())
     }
@@ -64,6 +64,24 @@ impl Drop for Mask {
     }
 }
 
+/// Accounts which differ between two ledgers, see [`Mask::diff_against`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LedgerDiff {
+    /// Accounts which are only in the mask.
+    pub added: Vec<Account>,
+    /// Accounts which are in both ledgers, with their contents in `other`
+    /// and in the mask.
+    pub modified: Vec<(Account, Account)>,
+    /// Accounts which are only in `other`.
+    pub removed: Vec<Account>,
+}
+
+impl LedgerDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
 #[derive(Debug)]
 pub enum UnregisterBehavior {
     Check,
@@ -232,6 +250,12 @@ impl Mask {
         self.with(|this| this.commit())
     }
 
+    /// Like [`Self::commit`], but hashes the committed accounts in the parent
+    /// at once, returning its merkle root.
+    pub fn commit_with_hashes(&self) -> Fp {
+        self.with(|this| this.commit_with_hashes())
+    }
+
     /// commit all the masks from this mask all the way upto the root
     /// and return root mask while also detaching all intermediary masks.
     pub fn commit_and_reparent_to_root(&mut self) -> Option<Mask> {
@@ -266,6 +290,14 @@ impl Mask {
         self.with(|this| this.set_impl(addr, account, ignore))
     }
 
+    pub(super) fn set_batch_with_hashes_impl(
+        &mut self,
+        list: &[(Address, Box<Account>)],
+        ignore: Option<Uuid>,
+    ) -> Fp {
+        self.with(|this| this.set_batch_with_hashes_impl(list, ignore))
+    }
+
     pub(super) fn transfert_hashes(&mut self, hashes: HashesMatrix) {
         self.with(|this| this.transfert_hashes(hashes))
     }
@@ -296,6 +328,19 @@ impl Mask {
     pub fn set_raw_inner_hashes(&self, hashes: Vec<(u64, Fp)>) {
         self.with(|this| this.set_raw_inner_hashes(hashes))
     }
+
+    /// Sets the accounts and recomputes the hashes above them at once,
+    /// returning the new merkle root.
+    pub fn set_batch_with_hashes(&mut self, list: &[(Address, Box<Account>)]) -> Fp {
+        self.with(|this| this.set_batch_with_hashes(list))
+    }
+
+    /// Recomputes all the hashes of a root mask in parallel, returning the
+    /// merkle root. Meant for ledgers which were just loaded.
+    pub fn recompute_hashes_parallel(&mut self) -> Fp {
+        self.with(|this| this.recompute_hashes_parallel())
+    }
+
     pub fn has_token_owners(&self) -> bool {
         self.with(|this| this.has_token_owners())
     }
@@ -306,6 +351,86 @@ impl Mask {
         self.with(|this| this.test_is_in_mask(addr))
     }
 
+    /// Accounts which differ between `other` and this mask, as the changes
+    /// to apply to `other` to get this mask.
+    ///
+    /// When `other` is an ancestor of this mask, as the mask of a previous
+    /// block, only the accounts set in the masks between them are compared.
+    /// Otherwise, all the accounts of both ledgers are.
+    pub fn diff_against(&self, other: &Mask) -> LedgerDiff {
+        let Some(ids) = self.account_ids_set_since(other) else {
+            return self.diff_all_accounts(other);
+        };
+
+        let mut diff = LedgerDiff::default();
+        let mut changes: Vec<(AccountIndex, Account, Option<Box<Account>>)> = ids
+            .into_iter()
+            .filter_map(|id| {
+                let addr = self.location_of_account(&id)?;
+                let account = self.get(addr.clone())?;
+                let previous = other
+                    .location_of_account(&id)
+                    .and_then(|addr| other.get(addr));
+                Some((addr.to_index(), *account, previous))
+            })
+            .collect();
+        changes.sort_by_key(|(index, ..)| *index);
+
+        for (_, account, previous) in changes {
+            match previous {
+                None => diff.added.push(account),
+                Some(previous) if *previous != account => diff.modified.push((*previous, account)),
+                Some(_) => {}
+            }
+        }
+        diff
+    }
+
+    /// Ids of the accounts set in this mask and its parents, up to
+    /// `ancestor`. `None` if `ancestor` isn't one of the parents of this
+    /// mask.
+    ///
+    /// Accounts removed from a mask are removed from its parents too, so
+    /// `ancestor` can't have accounts that this mask doesn't have.
+    fn account_ids_set_since(&self, ancestor: &Mask) -> Option<HashSet<AccountId>> {
+        let ancestor_uuid = ancestor.get_uuid();
+        let mut ids = HashSet::new();
+        let mut mask = self.clone();
+
+        while mask.get_uuid() != ancestor_uuid {
+            let (own, parent) = mask.with(|this| (this.own_account_ids(), this.get_parent()));
+            ids.extend(own?);
+            mask = parent?;
+        }
+        Some(ids)
+    }
+
+    fn diff_all_accounts(&self, other: &Mask) -> LedgerDiff {
+        let mut previous: HashMap<AccountId, Account> = other.fold(HashMap::new(), |mut acc, a| {
+            acc.insert(a.id(), a.clone());
+            acc
+        });
+
+        let mut diff = self.fold(LedgerDiff::default(), |mut diff, account| {
+            match previous.remove(&account.id()) {
+                None => diff.added.push(account.clone()),
+                Some(previous) if &previous != account => {
+                    diff.modified.push((previous, account.clone()))
+                }
+                Some(_) => {}
+            }
+            diff
+        });
+
+        diff.removed = other.fold(Vec::new(), |mut removed, account| {
+            if previous.contains_key(&account.id()) {
+                removed.push(account.clone());
+            }
+            removed
+        });
+        diff
+    }
+
     /// For tests only
     #[cfg(test)]
     fn test_matrix(&self) -> HashesMatrix {
@@ -683,6 +808,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_diff_against() {
+        let (mut root, layer1, mut layer2) = new_chain(DEPTH);
+
+        let accounts = (0..4).map(|_| Account::rand()).collect::<Vec<_>>();
+        for account in &accounts {
+            root.get_or_create_account(account.id(), account.clone())
+                .unwrap();
+        }
+        assert!(layer2.diff_against(&root).is_empty());
+
+        // Set in layer2 without changes, modified, and created
+        let addr = |index| Address::from_index(AccountIndex(index), DEPTH);
+        layer2.set(addr(0), Box::new(accounts[0].clone()));
+        let mut modified = accounts[2].clone();
+        modified.nonce = modified.nonce.incr();
+        layer2.set(addr(2), Box::new(modified.clone()));
+        let added = Account::rand();
+        layer2
+            .get_or_create_account(added.id(), added.clone())
+            .unwrap();
+
+        let expected = LedgerDiff {
+            added: vec![added],
+            modified: vec![(accounts[2].clone(), modified)],
+            removed: vec![],
+        };
+        assert_eq!(layer2.diff_against(&root), expected);
+        assert_eq!(layer2.diff_against(&layer1), expected);
+        assert!(layer2.diff_against(&layer2).is_empty());
+
+        // Not an ancestor, all the accounts are compared
+        let mut other = Mask::new_root(Database::create(DEPTH as u8));
+        for account in &accounts[1..] {
+            other
+                .get_or_create_account(account.id(), account.clone())
+                .unwrap();
+        }
+        let diff = layer1.diff_against(&other);
+        assert_eq!(diff.added, vec![accounts[0].clone()]);
+        assert!(diff.modified.is_empty());
+        assert!(diff.removed.is_empty());
+
+        let diff = other.diff_against(&layer2);
+        assert_eq!(diff.removed.len(), 2);
+        assert_eq!(diff.modified.len(), 1);
+        assert!(diff.added.is_empty());
+    }
+
     #[test]
     fn test_cached_merkle_path() {
         let (mut root, mask) = new_instances(DEPTH);
@@ -1369,6 +1543,93 @@ mod tests_mask_ocaml {
         }
     }
 
+    /// Batched updates give the same hashes as updating accounts one by one
+    #[test]
+    fn test_set_batch_with_hashes() {
+        const DEPTH: usize = 10;
+        let accounts = (0..300).map(|_| Account::rand()).collect::<Vec<_>>();
+        let (root, layer1, layer2) = new_chain(DEPTH);
+        let (other_root, other_layer1, other_layer2) = new_chain(DEPTH);
+
+        for (mut mask, mut other) in [
+            (root, other_root),
+            (layer1, other_layer1),
+            (layer2, other_layer2),
+        ] {
+            for account in &accounts {
+                mask.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+                other
+                    .get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
+            mask.merkle_root();
+            other.merkle_root();
+
+            // Updated, appended and repeated accounts
+            let list = (0..100)
+                .map(|index| index * 3)
+                .chain(300..310)
+                .chain([0, 3])
+                .map(|index| {
+                    (
+                        Address::from_index(AccountIndex(index), DEPTH),
+                        Box::new(Account::rand()),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            mask.set_batch(&list);
+            let merkle_root = other.set_batch_with_hashes(&list);
+
+            assert_eq!(mask.merkle_root(), merkle_root);
+            assert_eq!(other.merkle_root(), merkle_root);
+            other.validate_inner_hashes().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_commit_with_hashes() {
+        const DEPTH: usize = 10;
+        let accounts = (0..100).map(|_| Account::rand()).collect::<Vec<_>>();
+        let (root, layer1, layer2) = new_chain(DEPTH);
+        let (other_root, other_layer1, other_layer2) = new_chain(DEPTH);
+
+        for (mut mask, mut other) in [
+            (root, other_root),
+            (layer1, other_layer1),
+            (layer2, other_layer2),
+        ] {
+            for account in &accounts {
+                mask.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+                other
+                    .get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
+            mask.merkle_root();
+            other.merkle_root();
+
+            // Unregistered masks, as transaction logic uses
+            let child = Mask::new_unattached(DEPTH).set_parent(mask.clone(), None);
+            let other_child = Mask::new_unattached(DEPTH).set_parent(other.clone(), None);
+            for index in (0..50).map(|index| index * 2).chain(100..105) {
+                let addr = Address::from_index(AccountIndex(index), DEPTH);
+                let account = Box::new(Account::rand());
+                child.clone().set(addr.clone(), account.clone());
+                other_child.clone().set(addr, account);
+            }
+
+            child.commit();
+            let merkle_root = other_child.commit_with_hashes();
+
+            assert_eq!(mask.merkle_root(), merkle_root);
+            assert_eq!(other.merkle_root(), merkle_root);
+            assert_eq!(other.to_list(), mask.to_list());
+            other.validate_inner_hashes().unwrap();
+        }
+    }
+
     #[test]
     fn test_nmasks_to_root() {
         let (root, layer1, layer2) = new_chain(DEPTH);
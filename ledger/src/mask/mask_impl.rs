@@ -13,6 +13,7 @@ use crate::{
     database::{Database, DatabaseError},
     mask::UnregisterBehavior,
     next_uuid,
+    tree::{hash_accounts, hash_dirty_nodes},
     tree_version::{TreeVersion, V2},
     HashesMatrix,
 };
@@ -376,6 +377,17 @@ impl MaskImpl {
         }
     }
 
+    /// Ids of the accounts set in this mask, `None` on a root mask, whose
+    /// accounts are all its own.
+    pub(super) fn own_account_ids(&self) -> Option<Vec<AccountId>> {
+        match self {
+            Root { .. } => None,
+            Attached { id_to_addr, .. } | Unattached { id_to_addr, .. } => {
+                Some(id_to_addr.keys().cloned().collect())
+            }
+        }
+    }
+
     pub fn unset_parent(&mut self, trigger_detach_signal: bool) {
         let parent = self.remove_parent();
 
@@ -443,6 +455,45 @@ impl MaskImpl {
         }
     }
 
+    /// Like [`Self::commit`], but sets the accounts in the parent with
+    /// [`Self::set_batch_with_hashes`], hashing them and the nodes above them
+    /// once. Meant for masks which don't hash their accounts themselves, like
+    /// the ones transaction logic applies zkApp commands to. Returns the
+    /// merkle root of the parent.
+    pub fn commit_with_hashes(&mut self) -> Fp {
+        let depth = self.depth() as usize;
+        let self_uuid = self.uuid();
+
+        match self {
+            Root { .. } => panic!("commit on a root"),
+            Unattached { .. } => panic!("commit on a unattached mask"),
+            Attached {
+                parent,
+                owning_account,
+                token_owners,
+                id_to_addr,
+                hashes,
+                ..
+            } => {
+                assert_ne!(parent.get_uuid(), self_uuid);
+
+                if let Some(to) = token_owners.as_mut() {
+                    to.clear();
+                }
+                id_to_addr.clear();
+                hashes.take();
+
+                let mut list = std::mem::take(owning_account)
+                    .into_iter()
+                    .map(|(index, account)| (Address::from_index(index, depth), Box::new(account)))
+                    .collect::<Vec<_>>();
+                list.sort_by_key(|(addr, _)| addr.to_index());
+
+                parent.set_batch_with_hashes_impl(&list, Some(self_uuid))
+            }
+        }
+    }
+
     pub fn commit_and_reparent(&mut self) -> Option<Mask> {
         self.commit();
         self.remove_and_reparent()
@@ -926,6 +977,93 @@ impl MaskImpl {
         }
     }
 
+    /// Like `set_batch`, but hashes the accounts in parallel and the nodes
+    /// above them once each. Returns the new merkle root.
+    pub fn set_batch_with_hashes(&mut self, list: &[(Address, Box<Account>)]) -> Fp {
+        self.set_batch_with_hashes_impl(list, None)
+    }
+
+    pub(super) fn set_batch_with_hashes_impl(
+        &mut self,
+        list: &[(Address, Box<Account>)],
+        child_to_ignore: Option<Uuid>,
+    ) -> Fp {
+        if let Root { database, childs } = self {
+            for (addr, account) in list {
+                for (uuid, child) in childs.iter() {
+                    if Some(uuid) == child_to_ignore.as_ref() {
+                        continue;
+                    }
+                    child.parent_set_notify(addr.to_index(), account);
+                }
+            }
+            return database.set_batch_with_hashes(list);
+        }
+
+        for (addr, account) in list {
+            self.set_impl(addr.clone(), account.clone(), child_to_ignore.clone());
+        }
+
+        if list.is_empty() {
+            return self.merkle_root();
+        }
+
+        let depth = self.depth() as usize;
+        let last_account = self.last_filled().unwrap_or_else(|| Address::first(depth));
+
+        // Sorted indexes of the accounts of this mask, to find the nodes
+        // having some below them without scanning all of them
+        let own: Vec<u64> = match self {
+            Root { .. } => unreachable!(),
+            Attached { id_to_addr, .. } | Unattached { id_to_addr, .. } => {
+                let mut own: Vec<u64> = id_to_addr.values().map(|a| a.to_index().0).collect();
+                own.sort_unstable();
+                own
+            }
+        };
+
+        let leaves = hash_accounts(list);
+        let hashes = hash_dirty_nodes(depth, leaves, |addr| {
+            if let Some(hash) = self.get_cached_hash(&addr) {
+                return hash;
+            }
+            if !addr.is_before(&last_account) {
+                return self.empty_hash_at_height(depth - addr.length());
+            }
+
+            let height = depth - addr.length();
+            let first = addr.to_index().0 << height;
+            let next = own.partition_point(|index| *index < first);
+            let has_own = own
+                .get(next)
+                .is_some_and(|index| *index < first + (1 << height));
+
+            match self {
+                Attached { parent, .. } if !has_own => {
+                    parent.with(|parent| parent.compute_hash_or_parent(addr, &last_account))
+                }
+                _ => self.compute_hash_or_parent(addr, &last_account),
+            }
+        });
+
+        for (addr, hash) in &hashes {
+            self.set_cached_hash(addr, *hash);
+        }
+
+        let (_, root) = hashes.last().expect("the root is hashed last");
+        *root
+    }
+
+    /// On a root mask, drops all the hashes of the database and recomputes
+    /// them in parallel. Other masks only hash what they are missing, as
+    /// `merkle_root` does. Returns the merkle root.
+    pub fn recompute_hashes_parallel(&mut self) -> Fp {
+        match self {
+            Root { database, .. } => database.recompute_hashes_parallel(),
+            Attached { .. } | Unattached { .. } => self.merkle_root(),
+        }
+    }
+
     pub(super) fn transfert_hashes(&mut self, new_hashes: HashesMatrix) {
         match self {
             Root { database, .. } => database.transfert_hashes(new_hashes),
@@ -0,0 +1,255 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
+
+use crate::Account;
+
+use super::{
+    database::{Key, Value},
+    Database, IoFaults,
+};
+
+const DEPTH_KEY: &[u8] = b"depth";
+
+/// Accounts of a ledger, stored by index in a [`Database`] instead of in
+/// memory, see [`crate::Database::create_ondisk`]
+///
+/// Writes are buffered until [`AccountStore::flush`], so that a batch of
+/// accounts is written to the file at once. A batch that fails to be
+/// written stays buffered, and is written again by the next flush. Failing
+/// to read the file panics, as the ledger can't be trusted anymore.
+pub struct AccountStore {
+    /// `Database::get` requires `&mut self` to seek in the file
+    database: RefCell<Database>,
+    directory: PathBuf,
+    /// Accounts set (`Some`) or removed (`None`) since the last flush
+    pending: BTreeMap<usize, Option<Account>>,
+    /// Index after the last account ever stored
+    len: usize,
+    /// Copies are removed from the disk once dropped
+    remove_on_drop: bool,
+}
+
+impl std::fmt::Debug for AccountStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AccountStore")
+            .field("directory", &self.directory)
+            .field("pending", &self.pending.len())
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+fn encode_index(index: usize) -> Key {
+    Box::from((index as u64).to_be_bytes())
+}
+
+fn decode_index(key: &[u8]) -> Option<usize> {
+    let bytes = <[u8; 8]>::try_from(key).ok()?;
+    Some(u64::from_be_bytes(bytes) as usize)
+}
+
+impl AccountStore {
+    /// Opens the accounts of a ledger of `depth` stored in `directory`, or
+    /// creates an empty store there.
+    pub fn open(directory: impl AsRef<Path>, depth: u8) -> std::io::Result<Self> {
+        let directory = directory.as_ref().to_owned();
+        let mut database = Database::create(&directory)?;
+
+        match database.get(DEPTH_KEY)? {
+            Some(stored) if *stored != [depth] => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("ledger in {directory:?} has depth {stored:?}, expected {depth}"),
+                ));
+            }
+            Some(_) => {}
+            None => {
+                // Only checked when the store is opened again, after which
+                // writing it is retried.
+                if let Err(e) = database.set(Box::from(DEPTH_KEY), Box::from([depth])) {
+                    mina_core::warn!(
+                        mina_core::log::system_time();
+                        kind = "ledger", message = "Failed to write ledger depth",
+                        error = e.to_string());
+                }
+            }
+        }
+
+        let len = database
+            .keys()
+            .iter()
+            .filter_map(|key| decode_index(key))
+            .max()
+            .map_or(0, |index| index + 1);
+
+        Ok(Self {
+            database: RefCell::new(database),
+            directory,
+            pending: BTreeMap::new(),
+            len,
+            remove_on_drop: false,
+        })
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<Account> {
+        if let Some(account) = self.pending.get(&index) {
+            return account.clone();
+        }
+
+        let value = self
+            .database
+            .borrow_mut()
+            .get(&encode_index(index))
+            .expect("failed to read ledger account");
+
+        value.map(|bytes| Account::deserialize(&bytes))
+    }
+
+    pub fn set(&mut self, index: usize, account: Account) {
+        self.len = self.len.max(index + 1);
+        self.pending.insert(index, Some(account));
+    }
+
+    pub fn take(&mut self, index: usize) -> Option<Account> {
+        let account = self.get(index)?;
+        self.pending.insert(index, None);
+        Some(account)
+    }
+
+    /// Writes the accounts set or removed since the last flush. On failure
+    /// they are kept, to be written again by the next flush.
+    pub fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        let mut accounts: Vec<(Key, Value)> = Vec::with_capacity(self.pending.len());
+        let mut removed: Vec<Key> = Vec::new();
+
+        for (index, account) in &self.pending {
+            match account {
+                Some(account) => {
+                    accounts.push((encode_index(*index), account.serialize().into_boxed_slice()))
+                }
+                None => removed.push(encode_index(*index)),
+            }
+        }
+
+        match self.database.get_mut().set_batch(accounts, removed) {
+            Ok(()) => self.pending.clear(),
+            Err(e) => {
+                mina_core::warn!(
+                    mina_core::log::system_time();
+                    kind = "ledger", message = "Failed to write ledger accounts, retrying on next flush",
+                    accounts = self.pending.len(), error = e.to_string());
+            }
+        }
+    }
+
+    /// Folds the accounts in order of their index, until `fun` breaks
+    pub fn fold_until<B, F>(&self, init: B, mut fun: F) -> B
+    where
+        F: FnMut(B, usize, Account) -> ControlFlow<B, B>,
+    {
+        let mut accum = init;
+        for index in 0..self.len {
+            let Some(account) = self.get(index) else {
+                continue;
+            };
+            match fun(accum, index, account) {
+                ControlFlow::Continue(v) => accum = v,
+                ControlFlow::Break(v) => return v,
+            }
+        }
+        accum
+    }
+
+    /// Injects latency and failures into the I/O of the store, and of its
+    /// copies. Only meant for tests.
+    pub fn set_io_faults(&mut self, faults: IoFaults) {
+        self.database.get_mut().set_io_faults(faults);
+    }
+
+    /// Copies the accounts to `directory`, including the ones not flushed
+    /// yet. The copy is removed from the disk once dropped, so it isn't
+    /// synced.
+    pub fn copy(&self, directory: impl AsRef<Path>) -> std::io::Result<Self> {
+        let database = self
+            .database
+            .borrow_mut()
+            .copy_unsynced(directory.as_ref())?;
+
+        Ok(Self {
+            database: RefCell::new(database),
+            directory: directory.as_ref().to_owned(),
+            pending: self.pending.clone(),
+            len: self.len,
+            remove_on_drop: true,
+        })
+    }
+}
+
+impl Drop for AccountStore {
+    fn drop(&mut self) {
+        if !self.remove_on_drop {
+            return;
+        }
+        if let Err(e) = std::fs::remove_dir_all(&self.directory) {
+            elog!("failed to remove ledger copy {:?}: {:?}", self.directory, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        scan_state::currency::{Balance, Magnitude},
+        AccountId, TokenId,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_failed_flush_is_retried() {
+        let directory =
+            std::env::temp_dir().join(format!("mina-account-store-test-{}", std::process::id()));
+        let mut store = AccountStore::open(&directory, 10).unwrap();
+        store.remove_on_drop = true;
+        store.set_io_faults(IoFaults {
+            sync_failure_probability: 1.0,
+            ..IoFaults::default()
+        });
+
+        let id = AccountId::new(crate::gen_compressed(), TokenId::default());
+        let account = Account::create_with(id, Balance::from_u64(1_000_000_000));
+        store.set(0, account.clone());
+        store.flush();
+        assert_eq!(store.pending.len(), 1);
+        assert_eq!(store.get(0), Some(account.clone()));
+
+        // Copies aren't synced, so they don't fail.
+        let copy = store.copy(directory.with_extension("copy")).unwrap();
+        assert_eq!(copy.get(0), Some(account.clone()));
+
+        store.set_io_faults(IoFaults::default());
+        store.flush();
+        assert!(store.pending.is_empty());
+        assert_eq!(store.get(0), Some(account));
+    }
+}
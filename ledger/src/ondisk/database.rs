@@ -1,8 +1,9 @@
 use std::{
     collections::HashMap,
     fs::{File, OpenOptions},
-    io::{BufReader, BufWriter, Seek, SeekFrom, Write},
+    io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
+    thread::JoinHandle,
 };
 
 use std::io::ErrorKind::{InvalidData, Other, UnexpectedEof};
@@ -10,6 +11,7 @@ use std::io::ErrorKind::{InvalidData, Other, UnexpectedEof};
 use super::{
     batch::Batch,
     compression::{compress, decompress, MaybeCompressed},
+    io::IoFaults,
     lock::LockedFile,
 };
 
@@ -25,9 +27,17 @@ const IS_REMOVED_BIT: u8 = 1 << 2;
 
 const BUFFER_DEFAULT_CAPACITY: usize = 4096;
 
+/// Longest partially written entry a crash is assumed to leave at the end
+/// of the file. A longer invalid tail is reported as corrupted.
+const TORN_TAIL_MAX_NBYTES: u64 = 4 * 1024 * 1024;
+
 const DATABASE_VERSION: u64 = 1;
 const DATABASE_VERSION_NBYTES: usize = 8;
 
+/// Background compaction starts once there are at least this many stale
+/// entries, and they outnumber the live ones
+const COMPACTION_MIN_STALE_ENTRIES: usize = 100_000;
+
 pub struct Database {
     uuid: Uuid,
     /// Index of keys to their values offset
@@ -39,6 +49,44 @@ pub struct Database {
     buffer: Vec<u8>,
     /// Filename of the inner file
     filename: PathBuf,
+    /// Number of entries in the file that were overwritten or removed
+    stale_entries: usize,
+    /// Number of stale entries required to start a compaction
+    compaction_threshold: usize,
+    /// Compaction running in the background
+    compaction: Option<Compaction>,
+}
+
+struct Compaction {
+    /// Entries appended from this offset are missing from the compacted file
+    snapshot_offset: Offset,
+    handle: JoinHandle<std::io::Result<Database>>,
+}
+
+/// Result of checking a database file, see [`Database::fsck`]
+#[derive(Debug)]
+pub struct FsckReport {
+    pub file_length: u64,
+    /// Offset following the last valid entry
+    pub valid_length: u64,
+    pub entries: usize,
+    pub live_keys: usize,
+    /// Overwritten or removed entries, which compaction would reclaim
+    pub stale_entries: usize,
+    /// Offset of an invalid entry followed by more data. Such a database
+    /// can't be recovered by truncating it.
+    pub corrupted_at: Option<Offset>,
+}
+
+impl FsckReport {
+    /// Length of the partially written entry left at the end of the file
+    /// by a crash, which is truncated when the database is loaded
+    pub fn torn_tail_length(&self) -> u64 {
+        match self.corrupted_at {
+            Some(_) => 0,
+            None => self.file_length - self.valid_length,
+        }
+    }
 }
 
 /// Compute crc32 of an entry
@@ -86,11 +134,12 @@ impl EntryHeader {
             .ok_or_else(|| std::io::Error::from(InvalidData))
     }
 
-    /// Returns the value offset of this entry
-    fn compute_value_offset(&self, header_offset: Offset) -> Option<Offset> {
-        header_offset
-            .checked_add(self.key_length as u64)?
-            .checked_add(EntryHeader::NBYTES as u64)
+    /// Returns the number of bytes this entry occupies on disk, including
+    /// its header
+    fn nbytes(&self) -> std::io::Result<u64> {
+        self.entry_length()?
+            .checked_add(Self::NBYTES as u64)
+            .ok_or_else(|| std::io::Error::from(InvalidData))
     }
 
     /// Convert this header to bytes
@@ -234,12 +283,227 @@ fn read_exact_at(file: &mut File, buffer: &mut [u8], offset: Offset) -> std::io:
 
 #[cfg(not(unix))]
 fn read_exact_at(file: &mut File, buffer: &mut [u8], offset: Offset) -> std::io::Result<()> {
-    use std::io::Read;
-
     file.seek(SeekFrom::Start(offset))?;
     file.read_exact(buffer)
 }
 
+/// Reads the entry at `header_offset` into `buffer`, and verifies its
+/// checksum
+///
+/// Returns the header and the length of the key and value
+fn read_entry_at(
+    file: &mut File,
+    buffer: &mut Vec<u8>,
+    header_offset: Offset,
+) -> std::io::Result<(EntryHeader, usize)> {
+    ensure_buffer_length(buffer, EntryHeader::NBYTES);
+    read_exact_at(file, &mut buffer[..EntryHeader::NBYTES], header_offset)?;
+
+    let header = EntryHeader::read(buffer)?;
+    let entry_length = header.entry_length()? as usize;
+    let entry_offset = header_offset + EntryHeader::NBYTES as u64;
+
+    ensure_buffer_length(buffer, entry_length);
+    read_exact_at(file, &mut buffer[..entry_length], entry_offset)?;
+
+    let (key_bytes, value_bytes) = buffer[..entry_length].split_at(header.key_length as usize);
+    header.verify_checksum(key_bytes, value_bytes)?;
+
+    Ok((header, entry_length))
+}
+
+/// Reads the next entry of `reader` into `buffer`, and verifies its
+/// checksum
+///
+/// Returns an `UnexpectedEof` error when the entry is longer than
+/// `remaining` bytes
+fn read_next_entry(
+    reader: &mut impl Read,
+    buffer: &mut Vec<u8>,
+    remaining: u64,
+) -> std::io::Result<(EntryHeader, usize)> {
+    if remaining < EntryHeader::NBYTES as u64 {
+        return Err(UnexpectedEof.into());
+    }
+
+    ensure_buffer_length(buffer, EntryHeader::NBYTES);
+    reader.read_exact(&mut buffer[..EntryHeader::NBYTES])?;
+
+    let header = EntryHeader::read(buffer)?;
+    if header.nbytes()? > remaining {
+        return Err(UnexpectedEof.into());
+    }
+    let entry_length = header.entry_length()? as usize;
+
+    ensure_buffer_length(buffer, entry_length);
+    reader.read_exact(&mut buffer[..entry_length])?;
+
+    let (key_bytes, value_bytes) = buffer[..entry_length].split_at(header.key_length as usize);
+    header.verify_checksum(key_bytes, value_bytes)?;
+
+    Ok((header, entry_length))
+}
+
+/// Returns true when all the remaining bytes of `reader` are zeros
+fn is_zeroed(reader: &mut impl Read) -> std::io::Result<bool> {
+    let mut buffer = [0; BUFFER_DEFAULT_CAPACITY];
+
+    loop {
+        match reader.read(&mut buffer)? {
+            0 => return Ok(true),
+            n if buffer[..n].iter().any(|byte| *byte != 0) => return Ok(false),
+            _ => {}
+        }
+    }
+}
+
+fn read_version(reader: &mut impl Read) -> std::io::Result<()> {
+    let mut bytes = [0; DATABASE_VERSION_NBYTES];
+    reader.read_exact(&mut bytes)?;
+
+    if u64::from_le_bytes(bytes) != DATABASE_VERSION {
+        return Err(std::io::Error::new(Other, "Incompatible database"));
+    }
+
+    Ok(())
+}
+
+/// Returns true when the entry whose header was just read from `reader`,
+/// and whose length runs past the end of the file, is a torn tail
+///
+/// Such an entry is either the last one, partially written, or one whose
+/// length field is corrupted. Only in the latter case do valid entries
+/// follow it.
+fn is_torn_tail(reader: &mut impl Read, remaining: u64) -> std::io::Result<bool> {
+    let Some(tail_length) = remaining.checked_sub(EntryHeader::NBYTES as u64) else {
+        return Ok(true);
+    };
+    if tail_length > TORN_TAIL_MAX_NBYTES {
+        return Ok(false);
+    }
+
+    let mut tail = vec![0; tail_length as usize];
+    reader.read_exact(&mut tail)?;
+
+    let is_entry_at = |start: usize| {
+        let Ok(header) = EntryHeader::read(&tail[start..]) else {
+            return false;
+        };
+        let entry = &tail[start + EntryHeader::NBYTES..];
+        match header.entry_length() {
+            Ok(length) if length <= entry.len() as u64 => {
+                let (key_bytes, value_bytes) =
+                    entry[..length as usize].split_at(header.key_length as usize);
+                header.verify_checksum(key_bytes, value_bytes).is_ok()
+            }
+            _ => false,
+        }
+    };
+
+    Ok(!(0..tail.len()).any(is_entry_at))
+}
+
+/// Points `key` to the entry at `header_offset`, or removes it from the
+/// index
+///
+/// Returns the number of entries that became stale
+fn update_index(
+    index: &mut HashMap<Key, Offset>,
+    key: Key,
+    is_removed: bool,
+    header_offset: Offset,
+) -> usize {
+    let previous = if is_removed {
+        index.remove(&key)
+    } else {
+        index.insert(key, header_offset)
+    };
+
+    // A removal entry is stale as soon as it's written
+    usize::from(previous.is_some()) + usize::from(is_removed)
+}
+
+/// Entries of a database file, up to the first invalid one
+struct Scan {
+    index: HashMap<Key, Offset>,
+    entries: usize,
+    stale_entries: usize,
+    /// Offset following the last valid entry
+    valid_length: u64,
+    /// Offset of the first invalid entry, when it isn't a torn tail
+    corrupted_at: Option<Offset>,
+}
+
+impl Scan {
+    /// Reads the entries of `reader`, positioned after the database version
+    fn read(reader: &mut impl Read, eof: u64) -> std::io::Result<Self> {
+        let mut current_offset = DATABASE_VERSION_NBYTES as u64;
+        let mut bytes = vec![0; BUFFER_DEFAULT_CAPACITY];
+
+        let mut scan = Self {
+            index: HashMap::with_capacity(256),
+            entries: 0,
+            stale_entries: 0,
+            valid_length: current_offset,
+            corrupted_at: None,
+        };
+
+        while current_offset < eof {
+            let header_offset = current_offset;
+
+            // Entries are only appended, so a crash can only leave a partially
+            // written entry at the end of the file, possibly followed by zeros
+            let (header, entry_length) =
+                match read_next_entry(reader, &mut bytes, eof - header_offset) {
+                    Ok(entry) => entry,
+                    Err(e) if e.kind() == UnexpectedEof => {
+                        if !is_torn_tail(reader, eof - header_offset)? {
+                            scan.corrupted_at = Some(header_offset);
+                        }
+                        break;
+                    }
+                    Err(e) if e.kind() == InvalidData => {
+                        if !is_zeroed(reader)? {
+                            scan.corrupted_at = Some(header_offset);
+                        }
+                        break;
+                    }
+                    Err(e) => return Err(e),
+                };
+
+            let key_bytes = &bytes[..header.key_length as usize];
+            let key = decompress(key_bytes, header.key_is_compressed)?;
+
+            scan.stale_entries +=
+                update_index(&mut scan.index, key, header.is_removed, header_offset);
+            scan.entries += 1;
+
+            current_offset += (EntryHeader::NBYTES + entry_length) as u64;
+            scan.valid_length = current_offset;
+        }
+
+        Ok(scan)
+    }
+}
+
+/// Writes the entries at `offsets` of the database file `filename` to a
+/// new temporary database
+fn compact(filename: PathBuf, offsets: Vec<Offset>) -> std::io::Result<Database> {
+    let directory = filename.parent().unwrap();
+    let mut file = File::open(&filename)?;
+    let mut compacted = Database::create_impl(directory, CreateMode::Temporary)?;
+    let mut buffer = Vec::with_capacity(BUFFER_DEFAULT_CAPACITY);
+
+    for header_offset in offsets {
+        let (header, entry_length) = read_entry_at(&mut file, &mut buffer, header_offset)?;
+        compacted.copy_entry(&header, &buffer[..entry_length])?;
+    }
+
+    compacted.flush()?;
+
+    Ok(compacted)
+}
+
 enum CreateMode {
     Regular,
     Temporary,
@@ -309,13 +573,17 @@ impl Database {
             file: BufWriter::with_capacity(4 * 1024 * 1024, file), // 4 MB
             buffer: Vec::with_capacity(BUFFER_DEFAULT_CAPACITY),
             filename,
+            stale_entries: 0,
+            compaction_threshold: COMPACTION_MIN_STALE_ENTRIES,
+            compaction: None,
         })
     }
 
     /// Reload the database at the specified path
+    ///
+    /// A partially written entry left at the end of the file by a crash is
+    /// truncated. Any other invalid entry is an error.
     fn reload(filename: PathBuf) -> std::io::Result<Self> {
-        use std::io::Read;
-
         let mut file = LockedFile::try_open_exclusively(
             &filename,
             OpenOptions::new()
@@ -325,67 +593,87 @@ impl Database {
                 .create_new(false),
         )?;
 
-        let mut current_offset = 0;
         let eof = file.seek(SeekFrom::End(0))?;
-
         file.seek(SeekFrom::Start(0))?;
 
         let mut reader = BufReader::with_capacity(4 * 1024 * 1024, file); // 4 MB
-        let mut bytes = vec![0; BUFFER_DEFAULT_CAPACITY];
 
-        // Check if the database is the same version
-        {
-            reader.read_exact(&mut bytes[..DATABASE_VERSION_NBYTES])?;
-            let database_version = read_u64(&bytes)?;
-            if database_version != DATABASE_VERSION {
-                return Err(std::io::Error::new(Other, "Incompatible database"));
-            }
-            current_offset += DATABASE_VERSION_NBYTES as u64;
+        read_version(&mut reader)?;
+        let scan = Scan::read(&mut reader, eof)?;
+
+        if let Some(offset) = scan.corrupted_at {
+            return Err(std::io::Error::new(
+                InvalidData,
+                format!("Corrupted entry at offset {offset}"),
+            ));
         }
 
-        let mut index = HashMap::with_capacity(256);
+        let file = reader.into_inner();
 
-        while current_offset < eof {
-            let header_offset = current_offset;
-
-            ensure_buffer_length(&mut bytes, EntryHeader::NBYTES);
-            reader.read_exact(&mut bytes[..EntryHeader::NBYTES])?;
+        // New entries are appended after the last valid one
+        if scan.valid_length < eof {
+            file.set_len(scan.valid_length)?;
+            file.sync_all()?;
+        }
 
-            let header = EntryHeader::read(&bytes)?;
-            let entry_length = header.entry_length()? as usize;
-            let key_length = header.key_length as usize;
+        Ok(Self {
+            uuid: next_uuid(),
+            index: scan.index,
+            current_file_offset: scan.valid_length,
+            file: BufWriter::with_capacity(4 * 1024 * 1024, file), // 4 MB
+            buffer: Vec::with_capacity(BUFFER_DEFAULT_CAPACITY),
+            filename,
+            stale_entries: scan.stale_entries,
+            compaction_threshold: COMPACTION_MIN_STALE_ENTRIES,
+            compaction: None,
+        })
+    }
 
-            ensure_buffer_length(&mut bytes, entry_length);
-            reader.read_exact(&mut bytes[..entry_length])?;
+    /// Checks the integrity of the database at the specified directory,
+    /// without loading it.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The path of the database.
+    /// * `repair` - Truncate a partially written entry left at the end of
+    ///   the file by a crash, as done when the database is loaded.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<FsckReport>` - Returns the result of the check, or an error
+    ///   if the database can't be read or is in use.
+    pub fn fsck(directory: impl AsRef<Path>, repair: bool) -> std::io::Result<FsckReport> {
+        let filename = directory.as_ref().join("db");
 
-            ensure_buffer_length(&mut bytes, entry_length);
-            let (key_bytes, value_bytes) = bytes[..entry_length].split_at(key_length);
+        let mut file = LockedFile::try_open_exclusively(
+            &filename,
+            OpenOptions::new().read(true).write(repair),
+        )?;
 
-            header.verify_checksum(key_bytes, value_bytes)?;
+        let file_length = file.seek(SeekFrom::End(0))?;
+        file.seek(SeekFrom::Start(0))?;
 
-            let key = decompress(key_bytes, header.key_is_compressed)?;
+        let mut reader = BufReader::with_capacity(4 * 1024 * 1024, file); // 4 MB
 
-            if header.is_removed {
-                index.remove(&key);
-            } else {
-                index.insert(key, header_offset);
-            }
+        read_version(&mut reader)?;
+        let scan = Scan::read(&mut reader, file_length)?;
 
-            current_offset += (EntryHeader::NBYTES + entry_length) as u64;
-        }
+        let report = FsckReport {
+            file_length,
+            valid_length: scan.valid_length,
+            entries: scan.entries,
+            live_keys: scan.index.len(),
+            stale_entries: scan.stale_entries,
+            corrupted_at: scan.corrupted_at,
+        };
 
-        if eof != current_offset {
-            return Err(UnexpectedEof.into());
+        if repair && report.torn_tail_length() > 0 {
+            let file = reader.into_inner();
+            file.set_len(report.valid_length)?;
+            file.sync_all()?;
         }
 
-        Ok(Self {
-            uuid: next_uuid(),
-            index,
-            current_file_offset: eof,
-            file: BufWriter::with_capacity(4 * 1024 * 1024, reader.into_inner()), // 4 MB
-            buffer: Vec::with_capacity(BUFFER_DEFAULT_CAPACITY),
-            filename,
-        })
+        Ok(report)
     }
 
     /// Retrieves the UUID of the current database instance.
@@ -404,24 +692,6 @@ impl Database {
         // NOTE: `close` is actually implemented at the ffi level, where `Self` is dropped
     }
 
-    fn read_header(&mut self, header_offset: Offset) -> std::io::Result<EntryHeader> {
-        ensure_buffer_length(&mut self.buffer, EntryHeader::NBYTES);
-        read_exact_at(
-            self.file.get_mut(),
-            &mut self.buffer[..EntryHeader::NBYTES],
-            header_offset,
-        )?;
-
-        EntryHeader::read(&self.buffer)
-    }
-
-    fn read_value(&mut self, offset: Offset, length: usize) -> std::io::Result<&[u8]> {
-        ensure_buffer_length(&mut self.buffer, length);
-        read_exact_at(self.file.get_mut(), &mut self.buffer[..length], offset)?;
-
-        Ok(&self.buffer[..length])
-    }
-
     /// Retrieves the value associated with a given key.
     ///
     /// # Arguments
@@ -431,7 +701,8 @@ impl Database {
     /// # Returns
     ///
     /// * `Result<Option<Box<[u8]>>>` - Returns an optional values if the key exists;
-    ///    otherwise, None. Returns an error if something goes wrong.
+    ///    otherwise, None. Returns an error if something goes wrong, or if the
+    ///    entry is corrupted.
     pub fn get(&mut self, key: &[u8]) -> std::io::Result<Option<Value>> {
         // Note: `&mut self` is required for `File::seek`
 
@@ -440,18 +711,44 @@ impl Database {
             None => return Ok(None),
         };
 
-        let header = self.read_header(header_offset)?;
+        let (header, entry_length) =
+            read_entry_at(self.file.get_mut(), &mut self.buffer, header_offset)?;
 
-        let value_offset = header
-            .compute_value_offset(header_offset)
-            .ok_or_else(|| std::io::Error::from(InvalidData))?;
-        let value_length = header.value_length as usize;
-
-        let value = self.read_value(value_offset, value_length)?;
+        let value = &self.buffer[header.key_length as usize..entry_length];
 
         decompress(value, header.value_is_compressed).map(Some)
     }
 
+    /// Appends an entry, whose key and value are compressed as described by
+    /// `header`, and returns its offset
+    fn append_entry(
+        &mut self,
+        header: &EntryHeader,
+        key_bytes: &[u8],
+        value_bytes: &[u8],
+    ) -> std::io::Result<Offset> {
+        let header_offset = self.current_file_offset;
+
+        self.file.write_all(&header.to_bytes()?)?;
+        self.file.write_all(key_bytes)?;
+        self.file.write_all(value_bytes)?;
+
+        self.current_file_offset += header.nbytes()?;
+
+        Ok(header_offset)
+    }
+
+    /// Appends an entry read from another database file
+    fn copy_entry(&mut self, header: &EntryHeader, entry: &[u8]) -> std::io::Result<()> {
+        let (key_bytes, value_bytes) = entry.split_at(header.key_length as usize);
+        let header_offset = self.append_entry(header, key_bytes, value_bytes)?;
+
+        let key = decompress(key_bytes, header.key_is_compressed)?;
+        self.stale_entries += update_index(&mut self.index, key, header.is_removed, header_offset);
+
+        Ok(())
+    }
+
     fn set_impl(&mut self, key: Key, value: Option<Value>) -> std::io::Result<()> {
         let is_removed = value.is_none();
 
@@ -462,23 +759,13 @@ impl Database {
         };
 
         let header = EntryHeader::make(&compressed_key, &compressed_value)?;
-        let header_offset = self.current_file_offset;
-
-        self.file.write_all(&header.to_bytes()?)?;
-        self.file.write_all(compressed_key.as_ref())?;
-        if let Some(value) = compressed_value.as_ref() {
-            self.file.write_all(value.as_ref())?;
-        };
-
-        let buffer_len = EntryHeader::NBYTES as u64 + header.entry_length()?;
-        self.current_file_offset += buffer_len;
+        let header_offset = self.append_entry(
+            &header,
+            compressed_key.as_ref(),
+            compressed_value.as_ref().map(AsRef::as_ref).unwrap_or(&[]),
+        )?;
 
-        // Update index
-        if is_removed {
-            self.index.remove(&key);
-        } else {
-            self.index.insert(key, header_offset);
-        }
+        self.stale_entries += update_index(&mut self.index, key, is_removed, header_offset);
 
         Ok(())
     }
@@ -495,7 +782,7 @@ impl Database {
     /// * `Result<()>` - Returns () if successful, otherwise returns an error.
     pub fn set(&mut self, key: Key, value: Value) -> std::io::Result<()> {
         self.set_impl(key, Some(value))?;
-        self.flush()?;
+        self.commit()?;
         Ok(())
     }
 
@@ -523,7 +810,7 @@ impl Database {
             self.set_impl(key, None)? // empty value
         }
 
-        self.flush()?;
+        self.commit()?;
 
         Ok(())
     }
@@ -572,18 +859,38 @@ impl Database {
     /// * `Result<Self>` - Returns a new instance of the database if successful,
     ///   otherwise returns an error.
     pub fn create_checkpoint(&mut self, directory: impl AsRef<Path>) -> std::io::Result<Self> {
-        let mut checkpoint = Self::create(directory.as_ref())?;
+        let mut checkpoint = self.copy_unsynced(directory)?;
+        checkpoint.flush()?;
+
+        Ok(checkpoint)
+    }
+
+    /// Like [`Self::create_checkpoint`], without syncing the new database
+    /// file, for copies that aren't meant to survive a crash.
+    pub fn copy_unsynced(&mut self, directory: impl AsRef<Path>) -> std::io::Result<Self> {
+        let mut copy = Self::create(directory.as_ref())?;
+        copy.set_io_faults(self.file.get_ref().faults().clone());
 
         let keys: Vec<Key> = self.index.keys().cloned().collect();
 
         for key in keys {
             let value = self.get(&key)?;
-            checkpoint.set_impl(key, value)?;
+            copy.set_impl(key, value)?;
         }
 
-        checkpoint.flush()?;
+        copy.file.flush()?;
 
-        Ok(checkpoint)
+        Ok(copy)
+    }
+
+    /// Injects latency and failures into the I/O of the database file.
+    /// Only meant for tests.
+    ///
+    /// # Arguments
+    ///
+    /// * `faults` - The faults to inject, replacing the previous ones.
+    pub fn set_io_faults(&mut self, faults: IoFaults) {
+        self.file.get_mut().set_faults(faults);
     }
 
     /// Flush writes buffer to fs and call `fsync`
@@ -592,6 +899,95 @@ impl Database {
         self.file.get_ref().sync_all()
     }
 
+    /// Flush writes, and start or complete a background compaction
+    fn commit(&mut self) -> std::io::Result<()> {
+        self.flush()?;
+
+        let is_finished = self
+            .compaction
+            .as_ref()
+            .map(|compaction| compaction.handle.is_finished());
+
+        match is_finished {
+            Some(true) => {
+                if let Err(e) = self.finish_compaction() {
+                    elog!("ondisk compaction of {:?} failed: {:?}", self.filename, e);
+                    self.compaction_threshold = self.stale_entries.saturating_mul(2);
+                }
+            }
+            Some(false) => {}
+            None => {
+                if self.stale_entries >= self.compaction_threshold
+                    && self.stale_entries > self.index.len()
+                {
+                    self.start_compaction();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the live entries to a new file in a background thread, while
+    /// new entries keep being appended to the current one
+    fn start_compaction(&mut self) {
+        let filename = self.filename.clone();
+        let offsets: Vec<Offset> = self.index.values().copied().collect();
+
+        let handle = std::thread::Builder::new()
+            .name("ondisk-compaction".to_owned())
+            .spawn(move || compact(filename, offsets));
+
+        match handle {
+            Ok(handle) => {
+                self.compaction = Some(Compaction {
+                    snapshot_offset: self.current_file_offset,
+                    handle,
+                })
+            }
+            Err(e) => elog!("failed to start ondisk compaction: {:?}", e),
+        }
+    }
+
+    /// Waits for the running compaction, appends the entries written since
+    /// it started to the compacted file, and replaces the current file with
+    /// it
+    fn finish_compaction(&mut self) -> std::io::Result<()> {
+        let Some(Compaction {
+            snapshot_offset,
+            handle,
+        }) = self.compaction.take()
+        else {
+            return Ok(());
+        };
+
+        let mut compacted = handle
+            .join()
+            .map_err(|_| std::io::Error::new(Other, "Compaction thread panicked"))??;
+
+        self.file.flush()?;
+
+        let mut header_offset = snapshot_offset;
+        while header_offset < self.current_file_offset {
+            let (header, entry_length) =
+                read_entry_at(self.file.get_mut(), &mut self.buffer, header_offset)?;
+            compacted.copy_entry(&header, &self.buffer[..entry_length])?;
+            header_offset += header.nbytes()?;
+        }
+
+        compacted.flush()?;
+
+        exchange_file_atomically(&self.filename, &compacted.filename)?;
+
+        compacted.filename.clone_from(&self.filename);
+        compacted.uuid.clone_from(&self.uuid);
+        compacted.set_io_faults(self.file.get_ref().faults().clone());
+
+        *self = compacted;
+
+        Ok(())
+    }
+
     fn remove_impl(&mut self, key: Key) -> std::io::Result<()> {
         self.set_impl(key, None) // empty value
     }
@@ -608,7 +1004,7 @@ impl Database {
     ///   otherwise returns an error.
     pub fn remove(&mut self, key: Key) -> std::io::Result<()> {
         self.remove_impl(key)?;
-        self.flush()
+        self.commit()
     }
 
     /// Retrieves all entries (key-value pairs) from the database.
@@ -631,6 +1027,16 @@ impl Database {
             .collect()
     }
 
+    /// Retrieves all keys of the database, without reading their values.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Box<[u8]>>` - Returns a vector containing all keys, in no
+    ///   particular order.
+    pub fn keys(&self) -> Vec<Key> {
+        self.index.keys().cloned().collect()
+    }
+
     /// Processes a pre-built batch of operations, effectively running the batch on the database.
     ///
     /// # Arguments
@@ -651,7 +1057,7 @@ impl Database {
             }
         }
 
-        self.flush()
+        self.commit()
     }
 
     /// Triggers garbage collection for the database, cleaning up obsolete
@@ -662,6 +1068,11 @@ impl Database {
     /// * `Result<()>` - Returns () if garbage collection is successful,
     ///   otherwise returns an error.
     pub fn gc(&mut self) -> std::io::Result<()> {
+        // The running compaction uses the same temporary file
+        if let Some(compaction) = self.compaction.take() {
+            let _ = compaction.handle.join();
+        }
+
         let directory = self.filename.parent().unwrap();
         let mut new_db = Self::create_impl(directory, CreateMode::Temporary)?;
 
@@ -939,6 +1350,153 @@ mod tests {
         assert_eq!(db.get(&key("a")).unwrap().unwrap(), value("b"));
     }
 
+    #[test]
+    fn test_torn_tail() {
+        let db_dir = TempDir::new();
+        let filename = db_dir.as_path().join("db");
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        db.set(key("a"), value("abc")).unwrap();
+        db.set(key("b"), value("def")).unwrap();
+        let valid_length = db.current_file_offset;
+        drop(db);
+
+        // Crash in the middle of writing an entry
+        let mut bytes = std::fs::read(&filename).unwrap();
+        let entry = bytes[DATABASE_VERSION_NBYTES..].to_vec();
+        bytes.extend_from_slice(&entry[..EntryHeader::NBYTES + 1]);
+        bytes.extend_from_slice(&[0; 64]);
+        std::fs::write(&filename, &bytes).unwrap();
+
+        let report = Database::fsck(db_dir.as_path(), false).unwrap();
+        assert_eq!(report.entries, 2);
+        assert_eq!(report.corrupted_at, None);
+        assert_eq!(report.torn_tail_length(), bytes.len() as u64 - valid_length);
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        assert_eq!(db.current_file_offset, valid_length);
+        assert_eq!(std::fs::metadata(&filename).unwrap().len(), valid_length);
+        assert_eq!(db.get(&key("a")).unwrap().unwrap(), value("abc"));
+        assert_eq!(db.get(&key("b")).unwrap().unwrap(), value("def"));
+
+        db.set(key("c"), value("ghi")).unwrap();
+        drop(db);
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        assert_eq!(db.get(&key("c")).unwrap().unwrap(), value("ghi"));
+    }
+
+    #[test]
+    fn test_corrupted_entry() {
+        let db_dir = TempDir::new();
+        let filename = db_dir.as_path().join("db");
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        db.set(key("a"), value("abc")).unwrap();
+        db.set(key("b"), value("def")).unwrap();
+        db.set(key("c"), value("ghi")).unwrap();
+
+        let offset = db.index[&key("b")];
+        let mut bytes = std::fs::read(&filename).unwrap();
+        bytes[offset as usize + EntryHeader::NBYTES + 1] ^= 0xff;
+        std::fs::write(&filename, &bytes).unwrap();
+
+        assert_eq!(db.get(&key("b")).unwrap_err().kind(), InvalidData);
+        assert_eq!(db.get(&key("c")).unwrap().unwrap(), value("ghi"));
+        drop(db);
+
+        let report = Database::fsck(db_dir.as_path(), true).unwrap();
+        assert_eq!(report.corrupted_at, Some(offset));
+        assert_eq!(report.torn_tail_length(), 0);
+        assert_eq!(
+            std::fs::metadata(&filename).unwrap().len(),
+            bytes.len() as u64
+        );
+
+        let error = Database::create(db_dir.as_path()).err().unwrap();
+        assert_eq!(error.kind(), InvalidData);
+    }
+
+    #[test]
+    fn test_corrupted_length() {
+        let db_dir = TempDir::new();
+        let filename = db_dir.as_path().join("db");
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        db.set(key("a"), value("abc")).unwrap();
+        db.set(key("b"), value("def")).unwrap();
+        db.set(key("c"), value("ghi")).unwrap();
+        let offset = db.index[&key("b")];
+        drop(db);
+
+        // The value length of "b" now runs past the end of the file
+        let mut bytes = std::fs::read(&filename).unwrap();
+        let value_length = offset as usize + 4..offset as usize + 12;
+        bytes[value_length].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        std::fs::write(&filename, &bytes).unwrap();
+
+        let report = Database::fsck(db_dir.as_path(), true).unwrap();
+        assert_eq!(report.entries, 1);
+        assert_eq!(report.corrupted_at, Some(offset));
+        assert_eq!(report.torn_tail_length(), 0);
+
+        let error = Database::create(db_dir.as_path()).err().unwrap();
+        assert_eq!(error.kind(), InvalidData);
+        assert_eq!(
+            std::fs::metadata(&filename).unwrap().len(),
+            bytes.len() as u64
+        );
+    }
+
+    #[test]
+    fn test_background_compaction() {
+        let db_dir = TempDir::new();
+
+        let nkeys = COMPACTION_MIN_STALE_ENTRIES;
+        let keys: Vec<Key> = (0..nkeys).map(|i| key(&i.to_string())).collect();
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        db.set_batch(keys.iter().map(|k| (k.clone(), value("a"))), [])
+            .unwrap();
+        assert!(db.compaction.is_none());
+
+        db.set_batch(
+            keys.iter().map(|k| (k.clone(), value("b"))),
+            [keys[0].clone()],
+        )
+        .unwrap();
+        assert_eq!(db.stale_entries, nkeys + 2);
+        assert!(db.compaction.is_some());
+
+        let offset = db.current_file_offset;
+
+        // Written after the compaction started
+        db.set(keys[1].clone(), value("c")).unwrap();
+        db.remove(keys[2].clone()).unwrap();
+
+        let uuid = db.get_uuid().clone();
+
+        db.finish_compaction().unwrap();
+        assert!(db.compaction.is_none());
+        assert!(db.current_file_offset < offset);
+        assert_eq!(db.get_uuid(), &uuid);
+        assert_eq!(db.index.len(), nkeys - 2);
+
+        let check = |db: &mut Database| {
+            assert_eq!(db.get(&keys[0]).unwrap(), None);
+            assert_eq!(db.get(&keys[1]).unwrap().unwrap(), value("c"));
+            assert_eq!(db.get(&keys[2]).unwrap(), None);
+            assert_eq!(db.get(&keys[3]).unwrap().unwrap(), value("b"));
+        };
+
+        check(&mut db);
+        drop(db);
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        check(&mut db);
+        assert_eq!(db.index.len(), nkeys - 2);
+    }
+
     #[test]
     fn test_to_alist() {
         let db_dir = TempDir::new();
@@ -996,4 +1554,72 @@ mod tests {
         assert_eq!(db_sorted, db_alist);
         assert_eq!(cp_sorted, cp_alist);
     }
+
+    #[test]
+    fn test_slow_disk() {
+        let db_dir = TempDir::new();
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        let sync_latency = std::time::Duration::from_millis(200);
+        db.set_io_faults(IoFaults {
+            write_latency: std::time::Duration::from_millis(1),
+            sync_latency,
+            sync_failure_probability: 0.0,
+        });
+
+        let key_values = make_random_key_values(100);
+        db.set_batch(key_values.clone(), []).unwrap();
+
+        // Reads don't wait for the disk
+        let start = std::time::Instant::now();
+        for (key, value) in &key_values {
+            assert_eq!(&db.get(key).unwrap().unwrap(), value);
+        }
+        assert!(start.elapsed() < sync_latency);
+        drop(db);
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        assert_eq!(sorted_vec(db.to_alist().unwrap()), sorted_vec(key_values));
+    }
+
+    #[test]
+    fn test_failing_sync() {
+        let db_dir = TempDir::new();
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        db.set_io_faults(IoFaults {
+            sync_failure_probability: 1.0,
+            ..IoFaults::default()
+        });
+
+        let error = db.set(key("a"), value("abc")).unwrap_err();
+        assert_eq!(error.kind(), Other);
+        // The entry was written, only its sync failed
+        assert_eq!(db.get(&key("a")).unwrap().unwrap(), value("abc"));
+
+        db.set_io_faults(IoFaults {
+            sync_failure_probability: 0.5,
+            ..IoFaults::default()
+        });
+
+        let key_values = make_random_key_values(200);
+        let mut synced = HashMap::new();
+        for (key, value) in key_values {
+            if db.set(key.clone(), value.clone()).is_ok() {
+                synced.insert(key, value);
+            }
+        }
+
+        // Once a sync succeeds, the entries whose sync failed are synced too
+        db.set_io_faults(IoFaults::default());
+        db.set(key("b"), value("def")).unwrap();
+        drop(db);
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        assert_eq!(db.get(&key("a")).unwrap().unwrap(), value("abc"));
+        assert_eq!(db.get(&key("b")).unwrap().unwrap(), value("def"));
+        for (key, value) in synced {
+            assert_eq!(db.get(&key).unwrap().unwrap(), value);
+        }
+    }
 }
@@ -0,0 +1,39 @@
+use std::{io::ErrorKind::Other, time::Duration};
+
+use rand::Rng;
+
+/// Faults injected into the I/O of a database file, to test its behaviour
+/// on slow or failing disks.
+///
+/// No faults are injected by default.
+#[derive(Debug, Clone, Default)]
+pub struct IoFaults {
+    /// Latency added to each write to the file
+    pub write_latency: Duration,
+    /// Latency added to each `fsync`
+    pub sync_latency: Duration,
+    /// Probability, between 0 and 1, of an `fsync` to fail. A failed
+    /// `fsync` doesn't sync the file.
+    pub sync_failure_probability: f64,
+}
+
+impl IoFaults {
+    pub(super) fn before_write(&self) {
+        if !self.write_latency.is_zero() {
+            std::thread::sleep(self.write_latency);
+        }
+    }
+
+    pub(super) fn before_sync(&self) -> std::io::Result<()> {
+        if !self.sync_latency.is_zero() {
+            std::thread::sleep(self.sync_latency);
+        }
+
+        let probability = self.sync_failure_probability.clamp(0.0, 1.0);
+        if probability > 0.0 && rand::thread_rng().gen_bool(probability) {
+            return Err(std::io::Error::new(Other, "Injected fsync failure"));
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
 use std::{fs::File, path::Path};
 
+use super::io::IoFaults;
+
 pub struct LockedFile {
     file: File,
+    faults: IoFaults,
 }
 
 impl Drop for LockedFile {
@@ -26,6 +29,7 @@ impl std::ops::DerefMut for LockedFile {
 
 impl std::io::Write for LockedFile {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.faults.before_write();
         self.file.write(buf)
     }
 
@@ -54,7 +58,24 @@ impl LockedFile {
         let file = open_options.open(filename)?;
         sys::try_lock_exclusive(&file)?;
 
-        Ok(Self { file })
+        Ok(Self {
+            file,
+            faults: IoFaults::default(),
+        })
+    }
+
+    /// Same as [`File::sync_all`], with the injected faults
+    pub fn sync_all(&self) -> std::io::Result<()> {
+        self.faults.before_sync()?;
+        self.file.sync_all()
+    }
+
+    pub fn faults(&self) -> &IoFaults {
+        &self.faults
+    }
+
+    pub fn set_faults(&mut self, faults: IoFaults) {
+        self.faults = faults;
     }
 }
 
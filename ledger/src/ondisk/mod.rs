@@ -33,6 +33,32 @@
 //! - `KEY`: The key data
 //! - `VALUE`: The value data
 //!
+//! ## Integrity
+//!
+//! The checksum of an entry is verified whenever it's read. When the database
+//! is loaded, a partially written entry at the end of the file, left by a crash,
+//! is truncated. Any other invalid entry makes loading fail.
+//! [`Database::fsck`] checks a database without loading it.
+//!
+//! ## Compaction
+//!
+//! Overwritten and removed entries stay in the file. Once they outnumber the
+//! live entries, the live entries are copied to a new file in a background thread.
+//! Entries written in the meantime are then appended to it, and it atomically
+//! replaces the current file.
+//!
+//! ## Fault Injection
+//!
+//! [`Database::set_io_faults`] adds latency to writes and `fsync`s of the
+//! database file, or makes `fsync`s fail, to test behaviour on slow or
+//! failing disks.
+//!
+//! ## Ledgers
+//!
+//! [`AccountStore`] keeps the accounts of a ledger in a database, keyed by
+//! their index, so that large ledgers don't have to fit in memory. See
+//! [`crate::Database::create_ondisk`].
+//!
 //! ## Example Usage
 //!
 //! Create an instance of MyDatabase:
@@ -54,10 +80,15 @@
 //! # }
 //! ```
 
+mod accounts;
 pub mod batch;
 mod compression;
 mod database;
+mod io;
 mod lock;
 
+pub use accounts::AccountStore;
 pub use batch::Batch;
 pub use database::*;
+pub use io::IoFaults;
+pub use lock::LockedFile;
@@ -26,6 +26,7 @@ use poly_commitment::{
     commitment::CommitmentCurve, hash_map_cache::HashMapCache, ipa::SRS, PolyComm,
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use super::VerifierIndex;
 
@@ -533,6 +534,29 @@ where
     (&srs).into()
 }
 
+/// Digest of the points of `srs`.
+///
+/// The lagrange bases are left out, as they are derived from the points and
+/// computed lazily, depending on the domains used so far.
+pub fn srs_digest<G>(srs: &SRS<G>) -> [u8; 32]
+where
+    G: CommitmentCurve,
+    GroupAffineCached: for<'y> From<&'y G>,
+{
+    let g: Vec<GroupAffineCached> = into(&srs.g);
+    let h = GroupAffineCached::from(&srs.h);
+    Sha256::digest(postcard::to_stdvec(&(g, h)).unwrap()).into()
+}
+
+/// Digest of `verifier`, which only depends on its content, unlike the bytes
+/// returned by [`verifier_index_to_bytes`] which include the lagrange bases
+/// computed so far by its SRS.
+pub fn verifier_index_digest(verifier: &VerifierIndex<Fq>) -> [u8; 32] {
+    let mut verifier: VerifierIndexCached = verifier.into();
+    verifier.srs.lagrange_bases.clear();
+    Sha256::digest(postcard::to_stdvec(&verifier).unwrap()).into()
+}
+
 pub fn mina_cache_path<P: AsRef<Path>>(path: P) -> Option<PathBuf> {
     std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache/mina").join(path))
 }
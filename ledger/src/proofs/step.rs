@@ -15,7 +15,7 @@ use crate::{
             COMMON_MAX_DEGREE_WRAP_LOG2,
         },
     },
-    verifier::{get_srs, get_srs_mut},
+    verifier::get_srs,
 };
 use anyhow::Context;
 use ark_ff::{BigInteger256, One, Zero};
@@ -1128,13 +1128,13 @@ pub mod step_verifier {
 
     fn public_input_commitment_dynamic(
         which: &[Boolean],
-        srs: &mut poly_commitment::ipa::SRS<Pallas>,
+        srs: &poly_commitment::ipa::SRS<Pallas>,
         domains: Vec<Domains>,
         public_input: Vec<Packed>,
         w: &mut Witness<Fp>,
     ) -> GroupAffine<Fp> {
         let lagrange_commitment =
-            |d: &Domains, i: usize, srs: &mut poly_commitment::ipa::SRS<Pallas>| {
+            |d: &Domains, i: usize, srs: &poly_commitment::ipa::SRS<Pallas>| {
                 let d = 2u64.pow(d.h.log2_size() as u32);
                 let elems = wrap_verifier::lagrange_commitment::<Fp>(srs, d, i).chunks;
                 assert_eq!(elems.len(), 1);
@@ -1144,11 +1144,11 @@ pub mod step_verifier {
         fn select_curve_points(
             domains: &[Domains],
             which: &[Boolean],
-            srs: &mut poly_commitment::ipa::SRS<Pallas>,
+            srs: &poly_commitment::ipa::SRS<Pallas>,
             w: &mut Witness<Fp>,
             points_for_domain: impl Fn(
                 &Domains,
-                &mut poly_commitment::ipa::SRS<Pallas>,
+                &poly_commitment::ipa::SRS<Pallas>,
             ) -> Vec<InnerCurve<Fp>>,
         ) -> Vec<InnerCurve<Fp>> {
             let (d, ds) = domains.split_first().unwrap();
@@ -1166,19 +1166,18 @@ pub mod step_verifier {
             }
         }
 
-        let lagrange =
-            |i: usize, srs: &mut poly_commitment::ipa::SRS<Pallas>, w: &mut Witness<Fp>| {
-                let vec = select_curve_points(&domains, which, srs, w, |d, srs| {
-                    vec![lagrange_commitment(d, i, srs)]
-                });
-                vec[0].clone()
-            };
+        let lagrange = |i: usize, srs: &poly_commitment::ipa::SRS<Pallas>, w: &mut Witness<Fp>| {
+            let vec = select_curve_points(&domains, which, srs, w, |d, srs| {
+                vec![lagrange_commitment(d, i, srs)]
+            });
+            vec[0].clone()
+        };
 
         let pow2pow = |x: InnerCurve<Fp>, n: usize| (0..n).fold(x, |acc, _| acc.clone() + acc);
 
         let lagrange_with_correction = |input_length: usize,
                                         i: usize,
-                                        srs: &mut poly_commitment::ipa::SRS<Pallas>,
+                                        srs: &poly_commitment::ipa::SRS<Pallas>,
                                         w: &mut Witness<Fp>|
          -> Vec<InnerCurve<Fp>> {
             let actual_shift = OPS_BITS_PER_CHUNK * chunks_needed(input_length);
@@ -1335,7 +1334,7 @@ pub mod step_verifier {
     }
 
     fn lagrange_commitment<F: FieldWitness>(
-        srs: &mut SRS<GroupAffine<F>>,
+        srs: &SRS<GroupAffine<F>>,
         domain: &Domain,
         i: usize,
     ) -> InnerCurve<F> {
@@ -1533,7 +1532,7 @@ pub mod step_verifier {
 
     struct IncrementallyVerifyProofParams<'a> {
         pub proofs_verified: usize,
-        pub srs: &'a mut poly_commitment::ipa::SRS<Pallas>,
+        pub srs: &'a poly_commitment::ipa::SRS<Pallas>,
         pub wrap_domain: &'a ForStepKind<Domain, Box<[Boolean]>>,
         pub sponge: Sponge<Fp>,
         pub sponge_after_index: Sponge<Fp>,
@@ -1691,7 +1690,7 @@ pub mod step_verifier {
     }
 
     pub(super) struct VerifyParams<'a> {
-        pub(super) srs: &'a mut poly_commitment::ipa::SRS<Pallas>,
+        pub(super) srs: &'a poly_commitment::ipa::SRS<Pallas>,
         pub(super) feature_flags: &'a FeatureFlags<OptFlag>,
         pub(super) lookup_parameters: (),
         pub(super) proofs_verified: usize,
@@ -1788,7 +1787,7 @@ pub mod step_verifier {
 }
 
 struct VerifyOneParams<'a> {
-    srs: &'a mut poly_commitment::ipa::SRS<Pallas>,
+    srs: &'a poly_commitment::ipa::SRS<Pallas>,
     proof: &'a PerProofWitness,
     data: &'a ForStep,
     messages_for_next_wrap_proof: Fp,
@@ -2808,8 +2807,7 @@ pub fn step<C: ProofConstants, const N_PREVIOUS: usize>(
         .try_into()
         .unwrap();
 
-    let srs = get_srs_mut::<Fq>();
-    let mut srs = srs.lock().unwrap();
+    let srs = get_srs::<Fq>();
 
     let bulletproof_challenges = prevs
         .iter()
@@ -2837,7 +2835,7 @@ pub fn step<C: ProofConstants, const N_PREVIOUS: usize>(
                 }
                 let (chals, _verified) = verify_one(
                     VerifyOneParams {
-                        srs: &mut srs,
+                        srs: &srs,
                         proof,
                         data,
                         messages_for_next_wrap_proof: msg_for_next_wrap_proof,
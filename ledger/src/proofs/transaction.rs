@@ -45,7 +45,7 @@ use crate::{
         scan_state::transaction_snark::{Registers, SokDigest, SokMessage, Statement},
         transaction_logic::{local_state::LocalState, transaction_union_payload},
     },
-    verifier::get_srs_mut,
+    verifier::get_srs,
     Account, AppendToInputs, MyCow, ReceiptChainHash, TimingAsRecord, TokenId, TokenSymbol,
 };
 
@@ -3903,15 +3903,11 @@ pub fn make_prover_index<C: ProofConstants, F: FieldWitness>(
 
     let (endo_q, _endo_r) = endos::<F>();
 
-    // TODO: `proof-systems` needs to change how the SRS is used
-    let srs: poly_commitment::ipa::SRS<F::OtherCurve> = {
-        let srs = get_srs_mut::<F>();
-        let srs = srs.lock().unwrap().clone();
-        srs.get_lagrange_basis(cs.domain.d1);
-        srs
-    };
+    // The SRS is shared with the verifiers and the other prover indexes
+    let srs = get_srs::<F>();
+    srs.get_lagrange_basis(cs.domain.d1);
 
-    let mut index = ProverIndex::<F>::create(cs, endo_q, Arc::new(srs), false);
+    let mut index = ProverIndex::<F>::create(cs, endo_q, srs, false);
     index.verifier_index = verifier_index.map(|i| i.as_ref().clone());
 
     // Compute and cache the verifier index digest
@@ -719,11 +719,68 @@ pub struct VK<'a> {
     pub data: (), // Unused in proof verification
 }
 
+/// Why a block proof failed verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum BlockProofError {
+    /// The protocol state, whose hash is the statement of the proof,
+    /// contains an invalid field element.
+    #[error("protocol state contains an invalid field")]
+    InvalidProtocolState,
+    #[error("accumulator check failed")]
+    AccumulatorCheckFailed,
+    #[error("proof verification failed")]
+    VerificationFailed,
+}
+
 pub fn verify_block(
     header: &MinaBlockHeaderStableV2,
     verifier_index: &VerifierIndex<Fq>,
     srs: &SRS<Vesta>,
 ) -> bool {
+    check_block(header, verifier_index, srs).is_ok()
+}
+
+/// Like [`verify_block`], telling why the proof is invalid.
+pub fn check_block(
+    header: &MinaBlockHeaderStableV2,
+    verifier_index: &VerifierIndex<Fq>,
+    srs: &SRS<Vesta>,
+) -> Result<(), BlockProofError> {
+    let checks = block_checks(header, verifier_index, srs).inspect_err(|_| {
+        mina_core::warn!(message = format!("verify_block: Protocol state contains invalid field"));
+    })?;
+    let ok = checks.accumulator_check && checks.verified;
+
+    mina_core::info!(message = format!("verify_block OK={ok:?}"));
+
+    if !ok {
+        on_fail::dump_block_verification(header);
+    }
+
+    if !checks.accumulator_check {
+        Err(BlockProofError::AccumulatorCheckFailed)
+    } else if !checks.verified {
+        Err(BlockProofError::VerificationFailed)
+    } else {
+        Ok(())
+    }
+}
+
+/// Outcome of each check of a block proof. Both checks run, even if the
+/// first one fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockProofChecks {
+    pub accumulator_check: bool,
+    pub verified: bool,
+}
+
+/// Runs the checks of a block proof, failing only if the protocol state
+/// can't be decoded.
+pub fn block_checks(
+    header: &MinaBlockHeaderStableV2,
+    verifier_index: &VerifierIndex<Fq>,
+    srs: &SRS<Vesta>,
+) -> Result<BlockProofChecks, BlockProofError> {
     let MinaBlockHeaderStableV2 {
         protocol_state,
         protocol_state_proof,
@@ -736,24 +793,18 @@ pub fn verify_block(
         data: (),
     };
 
-    let Ok(protocol_state) = ProtocolState::try_from(protocol_state) else {
-        mina_core::warn!(message = format!("verify_block: Protocol state contains invalid field"));
-        return false; // invalid bigint
-    };
+    let protocol_state = ProtocolState::try_from(protocol_state)
+        .map_err(|_| BlockProofError::InvalidProtocolState)?;
     let protocol_state_hash = MinaHash::hash(&protocol_state);
 
-    let accum_check =
+    let accumulator_check =
         accumulator_check::accumulator_check(srs, &[protocol_state_proof]).unwrap_or(false);
     let verified = verify_impl(&protocol_state_hash, protocol_state_proof, &vk).unwrap_or(false);
-    let ok = accum_check && verified;
 
-    mina_core::info!(message = format!("verify_block OK={ok:?}"));
-
-    if !ok {
-        on_fail::dump_block_verification(header);
-    }
-
-    ok
+    Ok(BlockProofChecks {
+        accumulator_check,
+        verified,
+    })
 }
 
 pub fn verify_transaction<'a>(
@@ -97,6 +97,8 @@ macro_rules! read_cache {
     }};
 }
 
+/// Reads the verifier index from the cache file, which must be keyed by the
+/// source `digest`.
 #[cfg(not(target_family = "wasm"))]
 fn read_cache(kind: Kind, digest: &[u8]) -> anyhow::Result<VerifierIndex<Fq>> {
     read_cache!(kind, digest)
@@ -129,12 +131,14 @@ fn write_cache(kind: Kind, index: &VerifierIndex<Fq>, digest: &[u8]) -> anyhow::
     Ok(())
 }
 
+fn source_digest(data: &str) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
 macro_rules! make_with_ext_cache {
     ($kind: expr, $data: expr) => {{
         let verifier_index: VerifierIndex<Fq> = serde_json::from_str($data).unwrap();
-        let mut hasher = Sha256::new();
-        hasher.update($data);
-        let src_index_digest = hasher.finalize();
+        let src_index_digest = source_digest($data);
 
         #[cfg(not(target_family = "wasm"))]
         let cache = read_cache($kind, &src_index_digest);
@@ -185,6 +189,29 @@ impl BlockVerifier {
         Kind::BlockVerifier
     }
 
+    /// Digest of the json this verifier index was built from, which its
+    /// cache file is keyed by. `None` if it wasn't returned by
+    /// [`Self::make`], e.g. an index provided by the user.
+    pub fn loaded_source_digest(&self) -> Option<[u8; 32]> {
+        BLOCK_VERIFIER
+            .get()
+            .filter(|v| Arc::ptr_eq(&v.0, &self.0))
+            .map(|_| Self::source_digest())
+    }
+
+    /// Digest of the loaded verifier index, see
+    /// [`super::caching::verifier_index_digest`].
+    pub fn digest(&self) -> [u8; 32] {
+        super::caching::verifier_index_digest(&self.0)
+    }
+
+    /// Digest of the json of the verifier index compiled into the node for
+    /// the current network. [`Self::make`] only loads the cache file keyed
+    /// by it.
+    pub fn source_digest() -> [u8; 32] {
+        source_digest(Self::src_json())
+    }
+
     fn src_json() -> &'static str {
         let network_name = mina_core::NetworkConfig::global().name;
         match network_name {
@@ -200,6 +227,29 @@ impl TransactionVerifier {
         Kind::TransactionVerifier
     }
 
+    /// Digest of the json this verifier index was built from, which its
+    /// cache file is keyed by. `None` if it wasn't returned by
+    /// [`Self::make`], e.g. an index provided by the user.
+    pub fn loaded_source_digest(&self) -> Option<[u8; 32]> {
+        TX_VERIFIER
+            .get()
+            .filter(|v| Arc::ptr_eq(&v.0, &self.0))
+            .map(|_| Self::source_digest())
+    }
+
+    /// Digest of the loaded verifier index, see
+    /// [`super::caching::verifier_index_digest`].
+    pub fn digest(&self) -> [u8; 32] {
+        super::caching::verifier_index_digest(&self.0)
+    }
+
+    /// Digest of the json of the verifier index compiled into the node for
+    /// the current network. [`Self::make`] only loads the cache file keyed
+    /// by it.
+    pub fn source_digest() -> [u8; 32] {
+        source_digest(Self::src_json())
+    }
+
     fn src_json() -> &'static str {
         let network_name = mina_core::NetworkConfig::global().name;
         match network_name {
@@ -467,3 +517,37 @@ pub fn make_zkapp_verifier_index(vk: &VerificationKey) -> VerifierIndex<Fq> {
         zk_rows,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mina_core::network::{devnet, mainnet};
+
+    use super::*;
+
+    /// Pins the json of the verifier indices compiled into the node to the
+    /// known-good digests the node checks them against at startup.
+    #[test]
+    fn test_verifier_index_source_digests() {
+        let sources = [
+            (
+                include_str!("data/mainnet_blockchain_verifier_index.json"),
+                mainnet::VERIFIER_INDEX_DIGESTS.block_verifier,
+            ),
+            (
+                include_str!("data/mainnet_transaction_verifier_index.json"),
+                mainnet::VERIFIER_INDEX_DIGESTS.transaction_verifier,
+            ),
+            (
+                include_str!("data/devnet_blockchain_verifier_index.json"),
+                devnet::VERIFIER_INDEX_DIGESTS.block_verifier,
+            ),
+            (
+                include_str!("data/devnet_transaction_verifier_index.json"),
+                devnet::VERIFIER_INDEX_DIGESTS.transaction_verifier,
+            ),
+        ];
+        for (json, expected) in sources {
+            assert_eq!(hex::encode(source_digest(json)), expected);
+        }
+    }
+}
@@ -1821,7 +1821,7 @@ pub mod wrap_verifier {
         lagrange_bases[i].clone()
     }
 
-    fn lagrange(domain: (&[Boolean], &[Domains]), srs: &mut SRS<Vesta>, i: usize) -> (Fq, Fq) {
+    fn lagrange(domain: (&[Boolean], &[Domains]), srs: &SRS<Vesta>, i: usize) -> (Fq, Fq) {
         let (which_branch, domains) = domain;
         assert_eq!(which_branch.len(), domains.len());
 
@@ -1854,7 +1854,7 @@ pub mod wrap_verifier {
     fn lagrange_with_correction(
         input_length: usize,
         domain: (&[Boolean], &[Domains]),
-        srs: &mut SRS<Vesta>,
+        srs: &SRS<Vesta>,
         i: usize,
         w: &mut Witness<Fq>,
     ) -> (CircuitVar<InnerCurve<Fq>>, InnerCurve<Fq>) {
@@ -2276,7 +2276,7 @@ pub mod wrap_verifier {
                 sponge.absorb((*b, y));
             };
 
-        let mut srs = (*srs).clone();
+        let srs: &SRS<Vesta> = &srs;
         let sg_old = actual_proofs_verified_mask
             .iter()
             .map(|b| CircuitVar::Var(*b))
@@ -2320,7 +2320,7 @@ pub mod wrap_verifier {
                         Packed::Field((CircuitVar::Constant(c), _)) => Left(if c.is_zero() {
                             None
                         } else if c.is_one() {
-                            Some(lagrange(domain, &mut srs, i))
+                            Some(lagrange(domain, srs, i))
                         } else {
                             todo!()
                         }),
@@ -2343,11 +2343,11 @@ pub mod wrap_verifier {
                 .map(|(i, x)| match x {
                     (b, 1) => CondOrAdd::CondAdd(
                         Boolean::of_field(b.as_field()),
-                        lagrange(domain, &mut srs, i),
+                        lagrange(domain, srs, i),
                     ),
                     (x, n) => CondOrAdd::AddWithCorrection(
                         (x, n),
-                        lagrange_with_correction(n, domain, &mut srs, i, w),
+                        lagrange_with_correction(n, domain, srs, i, w),
                     ),
                 })
                 .collect::<Vec<_>>();
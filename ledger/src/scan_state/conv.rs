@@ -1789,6 +1789,14 @@ impl From<&SignedCommand> for MinaBaseSignedCommandStableV2 {
     }
 }
 
+impl TryFrom<MinaBaseZkappCommandTStableV1WireStableV1> for zkapp_command::ZkAppCommand {
+    type Error = InvalidBigInt;
+
+    fn try_from(value: MinaBaseZkappCommandTStableV1WireStableV1) -> Result<Self, Self::Error> {
+        (&value).try_into()
+    }
+}
+
 impl TryFrom<&MinaBaseZkappCommandTStableV1WireStableV1> for zkapp_command::ZkAppCommand {
     type Error = InvalidBigInt;
 
@@ -1835,6 +1843,12 @@ impl From<verifiable::ZkAppCommand> for v2::MinaBaseZkappCommandVerifiableStable
     }
 }
 
+impl From<zkapp_command::ZkAppCommand> for MinaBaseZkappCommandTStableV1WireStableV1 {
+    fn from(value: zkapp_command::ZkAppCommand) -> Self {
+        (&value).into()
+    }
+}
+
 impl From<&zkapp_command::ZkAppCommand> for MinaBaseZkappCommandTStableV1WireStableV1 {
     fn from(cmd: &zkapp_command::ZkAppCommand) -> Self {
         Self {
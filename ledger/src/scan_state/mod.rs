@@ -11,6 +11,8 @@ pub mod snark_work;
 pub mod transaction_logic;
 pub use parallel_scan::SpacePartition;
 
+use once_cell::sync::OnceCell;
+
 pub struct GenesisConstant {
     pub protocol: (),
     pub txpool_max_size: usize,
@@ -37,3 +39,48 @@ pub const GENESIS_CONSTANT: GenesisConstant = GenesisConstant {
     max_action_elements: 100,
     zkapp_cmd_limit_hardcap: 128,
 };
+
+/// Caps on the events and actions of a zkApp command, checked by
+/// [`transaction_logic::zkapp_command::ZkAppCommand::valid_size`].
+///
+/// Defaults to the values of [`GENESIS_CONSTANT`]. Private networks can
+/// change them with [`ZkAppCommandLimits::init`], before any command is
+/// checked. All nodes of a network must use the same limits, otherwise they
+/// won't agree on which blocks are valid.
+///
+/// <https://github.com/MinaProtocol/mina/blob/2ff0292b637684ce0372e7b8e23ec85404dc5091/src/lib/mina_base/zkapp_command.ml#L1436>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZkAppCommandLimits {
+    /// Maximum number of field elements of all the events of a command
+    pub max_event_elements: usize,
+    /// Maximum number of field elements of all the actions of a command
+    pub max_action_elements: usize,
+}
+
+static ZKAPP_COMMAND_LIMITS: OnceCell<ZkAppCommandLimits> = OnceCell::new();
+
+impl Default for ZkAppCommandLimits {
+    fn default() -> Self {
+        Self {
+            max_event_elements: GENESIS_CONSTANT.max_event_elements,
+            max_action_elements: GENESIS_CONSTANT.max_action_elements,
+        }
+    }
+}
+
+impl ZkAppCommandLimits {
+    pub fn global() -> &'static Self {
+        ZKAPP_COMMAND_LIMITS.get_or_init(Self::default)
+    }
+
+    /// Fails if different limits were already initialized or used
+    pub fn init(self) -> Result<(), String> {
+        match ZKAPP_COMMAND_LIMITS.try_insert(self) {
+            Ok(_) => Ok(()),
+            Err((current, _)) if *current == self => Ok(()),
+            Err((current, _)) => Err(format!(
+                "zkApp command limits already initialized to {current:?}"
+            )),
+        }
+    }
+}
@@ -520,6 +520,21 @@ impl PendingCoinbase {
         self.tree.merkle_root()
     }
 
+    /// Merkle root computed from the stacks only, ignoring the hashes cached
+    /// in the tree.
+    pub fn recompute_merkle_root(&self) -> Fp {
+        let mut tree = self.tree.clone();
+        tree.hashes_matrix = crate::HashesMatrix::new(tree.depth);
+        tree.merkle_root()
+    }
+
+    /// Stacks which weren't popped yet, oldest first.
+    pub fn stacks(&self) -> impl Iterator<Item = (StackId, &Stack)> {
+        self.pos_list
+            .iter()
+            .map(|id| (*id, self.get_stack(self.find_index(*id))))
+    }
+
     fn get_stack(&self, addr: Address) -> &Stack {
         self.tree.get_exn(addr)
     }
@@ -43,6 +43,7 @@
 //!
 //! - [`local_state`]: Local state management during zkApp application
 //! - [`protocol_state`]: Protocol state views for transaction application
+//! - [`receipt`]: Receipt chain verification, for payment proofs
 //! - [`signed_command`]: Payment and stake delegation logic
 //! - [`transaction_applied`]: Final transaction application results
 //! - [`transaction_partially_applied`]: Two-phase transaction application
@@ -95,6 +96,7 @@ use std::{
 
 pub mod local_state;
 pub mod protocol_state;
+pub mod receipt;
 pub mod signed_command;
 pub mod transaction_applied;
 pub mod transaction_partially_applied;
@@ -580,7 +582,8 @@ impl std::fmt::Display for Memo {
             return Err(std::fmt::Error);
         }
 
-        let length = self.0[1] as usize;
+        // The length byte comes from untrusted input, don't read past the memo
+        let length = std::cmp::min(self.0[1] as usize, Self::DIGEST_LENGTH);
         let memo_slice = &self.0[2..2 + length];
         let memo_str = String::from_utf8_lossy(memo_slice).to_string();
         let trimmed = memo_str.trim_end_matches('\0').to_string();
@@ -697,12 +700,22 @@ impl Memo {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// Serialized to JSON like OCaml's `User_command.to_yojson`, through
+/// [`MinaBaseUserCommandStableV2`].
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(into = "MinaBaseUserCommandStableV2")]
+#[serde(try_from = "MinaBaseUserCommandStableV2")]
 pub enum UserCommand {
     SignedCommand(Box<signed_command::SignedCommand>),
     ZkAppCommand(Box<zkapp_command::ZkAppCommand>),
 }
 
+impl From<UserCommand> for MinaBaseUserCommandStableV2 {
+    fn from(user_command: UserCommand) -> Self {
+        (&user_command).into()
+    }
+}
+
 impl From<&UserCommand> for MinaBaseUserCommandStableV2 {
     fn from(user_command: &UserCommand) -> Self {
         match user_command {
@@ -716,6 +729,14 @@ impl From<&UserCommand> for MinaBaseUserCommandStableV2 {
     }
 }
 
+impl TryFrom<MinaBaseUserCommandStableV2> for UserCommand {
+    type Error = InvalidBigInt;
+
+    fn try_from(user_command: MinaBaseUserCommandStableV2) -> Result<Self, Self::Error> {
+        (&user_command).try_into()
+    }
+}
+
 impl TryFrom<&MinaBaseUserCommandStableV2> for UserCommand {
     type Error = InvalidBigInt;
 
@@ -943,10 +964,8 @@ impl UserCommand {
             .collect()
     }
 
-    fn has_insufficient_fee(&self) -> bool {
-        /// `minimum_user_command_fee`
-        const MINIMUM_USER_COMMAND_FEE: Fee = Fee::from_u64(1000000);
-        self.fee() < MINIMUM_USER_COMMAND_FEE
+    fn has_insufficient_fee(&self, constraint_constants: &ConstraintConstants) -> bool {
+        self.fee() < Fee::from_u64(constraint_constants.minimum_user_command_fee)
     }
 
     fn has_zero_vesting_period(&self) -> bool {
@@ -977,27 +996,30 @@ impl UserCommand {
         }
     }
 
-    pub fn check_well_formedness(&self) -> Result<(), Vec<WellFormednessError>> {
+    pub fn check_well_formedness(
+        &self,
+        constraint_constants: &ConstraintConstants,
+    ) -> Result<(), Vec<WellFormednessError>> {
         let mut errors: Vec<_> = [
             (
-                Self::has_insufficient_fee as fn(_) -> _,
+                self.has_insufficient_fee(constraint_constants),
                 WellFormednessError::InsufficientFee,
             ),
             (
-                Self::has_zero_vesting_period,
+                self.has_zero_vesting_period(),
                 WellFormednessError::ZeroVestingPeriod,
             ),
             (
-                Self::is_incompatible_version,
+                self.is_incompatible_version(),
                 WellFormednessError::IncompatibleVersion,
             ),
             (
-                Self::is_disabled,
+                self.is_disabled(),
                 WellFormednessError::TransactionTypeDisabled,
             ),
         ]
-        .iter()
-        .filter_map(|(fun, e)| if fun(self) { Some(e.clone()) } else { None })
+        .into_iter()
+        .filter_map(|(failed, e)| failed.then_some(e))
         .collect();
 
         if let Err(e) = self.valid_size() {
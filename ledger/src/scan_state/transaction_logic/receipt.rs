@@ -0,0 +1,305 @@
+//! Receipt chain verification
+//!
+//! Each account commits to the user commands it authorized through its
+//! [`ReceiptChainHash`]: every such command is consed onto the previous hash
+//! when the command is applied. Given a starting hash and the commands of an
+//! account, [`receipt_chain_hashes`] replays that sequence, and
+//! [`verify_receipt_chain`] checks that it ends on the hash in the ledger,
+//! proving that the commands were applied to the account, in that order.
+//!
+//! This is what the payment proofs of the OCaml CLI (`verify-receipt`) are
+//! built on.
+
+use crate::{
+    scan_state::currency::Index, zkapps::zkapp_logic::ZkAppCommandElt, AccountId, ReceiptChainHash,
+};
+
+use super::{
+    cons_signed_command_payload, cons_zkapp_command_commitment, zkapp_command::Control,
+    UserCommand, WithStatus,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ReceiptChainError {
+    #[error("command {index} does not change the receipt chain hash of the account")]
+    UnrelatedCommand { index: usize },
+    #[error("receipt chain hash mismatch: expected {expected:?}, computed {computed:?}")]
+    Mismatch {
+        expected: ReceiptChainHash,
+        computed: ReceiptChainHash,
+    },
+}
+
+/// Receipt chain hash of the account after `command` was applied, when it was
+/// `last` before it. `None` when the command doesn't change it, as the account
+/// didn't authorize any part of it.
+///
+/// - Signed commands change the hash of their fee payer only, even when they
+///   failed.
+/// - zkApp commands change the hash of their fee payer, and of the accounts
+///   of the account updates authorized by a signature or a proof, unless the
+///   command failed.
+pub fn cons_user_command(
+    account_id: &AccountId,
+    command: &WithStatus<UserCommand>,
+    last: &ReceiptChainHash,
+) -> Option<ReceiptChainHash> {
+    let WithStatus { data, status } = command;
+
+    match data {
+        UserCommand::SignedCommand(cmd) => (&cmd.fee_payer() == account_id)
+            .then(|| cons_signed_command_payload(&cmd.payload, last.clone())),
+        UserCommand::ZkAppCommand(cmd) => {
+            let full_commitment = cmd.full_commitment();
+            let elt = ZkAppCommandElt::ZkAppCommandCommitment(ReceiptChainHash(full_commitment.0));
+
+            let fee_payer = (&cmd.fee_payer() == account_id).then_some(Index::zero());
+            let account_updates = cmd
+                .account_updates
+                .to_account_updates()
+                .into_iter()
+                .enumerate()
+                .filter(|_| status.is_applied())
+                .filter(|(_, account_update)| match account_update.authorization {
+                    Control::Proof(_) | Control::Signature(_) => true,
+                    Control::NoneGiven => false,
+                })
+                .filter(|(_, account_update)| &account_update.account_id() == account_id)
+                .map(|(index, _)| Index::from_u32(index as u32 + 1));
+
+            fee_payer.into_iter().chain(account_updates).fold(
+                None,
+                |hash: Option<ReceiptChainHash>, index| {
+                    let last = hash.as_ref().unwrap_or(last);
+                    Some(cons_zkapp_command_commitment(index, elt.clone(), last))
+                },
+            )
+        }
+    }
+}
+
+/// Receipt chain hashes of the account after each of `commands`, starting
+/// from `init`. Fails if one of them doesn't change the hash, as it can't be
+/// part of the chain then.
+pub fn receipt_chain_hashes(
+    account_id: &AccountId,
+    init: ReceiptChainHash,
+    commands: &[WithStatus<UserCommand>],
+) -> Result<Vec<ReceiptChainHash>, ReceiptChainError> {
+    let mut hashes = Vec::with_capacity(commands.len());
+    let mut last = init;
+
+    for (index, command) in commands.iter().enumerate() {
+        last = cons_user_command(account_id, command, &last)
+            .ok_or(ReceiptChainError::UnrelatedCommand { index })?;
+        hashes.push(last.clone());
+    }
+
+    Ok(hashes)
+}
+
+/// Checks that applying `commands`, in order, to an account whose receipt
+/// chain hash was `init` leads to `expected`, the current receipt chain hash
+/// of the account in the ledger. Returns the intermediate hashes.
+pub fn verify_receipt_chain(
+    account_id: &AccountId,
+    expected: &ReceiptChainHash,
+    init: ReceiptChainHash,
+    commands: &[WithStatus<UserCommand>],
+) -> Result<Vec<ReceiptChainHash>, ReceiptChainError> {
+    let hashes = receipt_chain_hashes(account_id, init.clone(), commands)?;
+    let computed = hashes.last().cloned().unwrap_or(init);
+
+    if &computed != expected {
+        return Err(ReceiptChainError::Mismatch {
+            expected: expected.clone(),
+            computed,
+        });
+    }
+
+    Ok(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_signer::{CompressedPubKey, Signature};
+
+    use crate::{
+        gen_compressed,
+        scan_state::{
+            currency::{Amount, Fee, Nonce, Slot},
+            transaction_logic::{
+                signed_command::{
+                    Body, Common, PaymentPayload, SignedCommand, SignedCommandPayload,
+                },
+                zkapp_command::{AccountUpdate, CallForest, FeePayer, FeePayerBody, ZkAppCommand},
+                Memo, TransactionFailure,
+            },
+        },
+        TokenId,
+    };
+
+    use super::*;
+
+    #[cfg(target_family = "wasm")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    fn payment(from: &CompressedPubKey, to: &CompressedPubKey, nonce: u32) -> UserCommand {
+        UserCommand::SignedCommand(Box::new(SignedCommand {
+            payload: SignedCommandPayload {
+                common: Common {
+                    fee: Fee::from_u64(10_000_000),
+                    fee_payer_pk: from.clone(),
+                    nonce: Nonce::from_u32(nonce),
+                    valid_until: Slot::max(),
+                    memo: Memo::empty(),
+                },
+                body: Body::Payment(PaymentPayload {
+                    receiver_pk: to.clone(),
+                    amount: Amount::from_u64(1_000_000_000),
+                }),
+            },
+            signer: from.clone(),
+            signature: Signature::dummy(),
+        }))
+    }
+
+    fn fee_payer(public_key: &CompressedPubKey) -> FeePayer {
+        FeePayer {
+            body: FeePayerBody {
+                public_key: public_key.clone(),
+                fee: Fee::from_u64(10_000_000),
+                valid_until: None,
+                nonce: Nonce::zero(),
+            },
+            authorization: Signature::dummy(),
+        }
+    }
+
+    fn account_update(public_key: &CompressedPubKey, authorization: Control) -> AccountUpdate {
+        AccountUpdate {
+            authorization,
+            ..AccountUpdate::of_fee_payer(fee_payer(public_key))
+        }
+    }
+
+    fn zkapp_command(
+        fee_payer_pk: &CompressedPubKey,
+        account_updates: Vec<AccountUpdate>,
+    ) -> UserCommand {
+        let account_updates = account_updates
+            .into_iter()
+            .rev()
+            .fold(CallForest::new(), |forest, account_update| {
+                forest.cons(None, account_update)
+            });
+        UserCommand::ZkAppCommand(Box::new(ZkAppCommand {
+            fee_payer: fee_payer(fee_payer_pk),
+            account_updates,
+            memo: Memo::empty(),
+        }))
+    }
+
+    #[test]
+    fn test_cons_zkapp_command() {
+        let (a, b, c) = (gen_compressed(), gen_compressed(), gen_compressed());
+        let account_id = |pk: &CompressedPubKey| AccountId::new(pk.clone(), TokenId::default());
+
+        let command = zkapp_command(
+            &a,
+            vec![
+                account_update(&a, Control::Signature(Signature::dummy())),
+                account_update(&b, Control::Signature(Signature::dummy())),
+                account_update(&a, Control::NoneGiven),
+                account_update(&a, Control::Signature(Signature::dummy())),
+            ],
+        );
+        let UserCommand::ZkAppCommand(zkapp) = &command else {
+            unreachable!()
+        };
+        let elt =
+            ZkAppCommandElt::ZkAppCommandCommitment(ReceiptChainHash(zkapp.full_commitment().0));
+        let consed = |indexes: &[u32]| {
+            indexes
+                .iter()
+                .fold(ReceiptChainHash::empty(), |hash, index| {
+                    cons_zkapp_command_commitment(Index::from_u32(*index), elt.clone(), &hash)
+                })
+        };
+        let empty = ReceiptChainHash::empty();
+
+        // The fee payer is index 0, the account updates authorized by the
+        // account follow it
+        let applied = WithStatus::applied(command.clone());
+        assert_eq!(
+            cons_user_command(&account_id(&a), &applied, &empty),
+            Some(consed(&[0, 1, 4]))
+        );
+        assert_eq!(
+            cons_user_command(&account_id(&b), &applied, &empty),
+            Some(consed(&[2]))
+        );
+        assert_eq!(cons_user_command(&account_id(&c), &applied, &empty), None);
+
+        // Only the fee payer is charged when the command failed
+        let failed = WithStatus::failed(command, vec![vec![], vec![TransactionFailure::Overflow]]);
+        assert_eq!(
+            cons_user_command(&account_id(&a), &failed, &empty),
+            Some(consed(&[0]))
+        );
+        assert_eq!(cons_user_command(&account_id(&b), &failed, &empty), None);
+    }
+
+    #[test]
+    fn test_verify_receipt_chain() {
+        let (sender, receiver) = (gen_compressed(), gen_compressed());
+        let account_id = AccountId::new(sender.clone(), TokenId::default());
+
+        let commands = vec![
+            WithStatus::applied(payment(&sender, &receiver, 0)),
+            // Failed payments still pay the fee, and are part of the chain
+            WithStatus::failed(
+                payment(&sender, &receiver, 1),
+                vec![vec![TransactionFailure::SourceInsufficientBalance]],
+            ),
+            WithStatus::applied(payment(&sender, &receiver, 2)),
+        ];
+
+        let expected = commands
+            .iter()
+            .fold(ReceiptChainHash::empty(), |hash, cmd| {
+                let UserCommand::SignedCommand(cmd) = &cmd.data else {
+                    unreachable!()
+                };
+                cons_signed_command_payload(&cmd.payload, hash)
+            });
+
+        let hashes =
+            verify_receipt_chain(&account_id, &expected, ReceiptChainHash::empty(), &commands)
+                .unwrap();
+        assert_eq!(hashes.len(), commands.len());
+        assert_eq!(hashes.last(), Some(&expected));
+
+        // Starting from the middle of the chain
+        let hashes =
+            verify_receipt_chain(&account_id, &expected, hashes[0].clone(), &commands[1..])
+                .unwrap();
+        assert_eq!(hashes.len(), 2);
+
+        // Commands out of order
+        let mut swapped = commands.clone();
+        swapped.swap(0, 2);
+        assert!(matches!(
+            verify_receipt_chain(&account_id, &expected, ReceiptChainHash::empty(), &swapped),
+            Err(ReceiptChainError::Mismatch { .. })
+        ));
+
+        // The receiver doesn't sign payments, they aren't in its chain
+        let mut unrelated = commands.clone();
+        unrelated.insert(1, WithStatus::applied(payment(&receiver, &sender, 0)));
+        assert_eq!(
+            receipt_chain_hashes(&account_id, ReceiptChainHash::empty(), &unrelated),
+            Err(ReceiptChainError::UnrelatedCommand { index: 1 })
+        );
+    }
+}
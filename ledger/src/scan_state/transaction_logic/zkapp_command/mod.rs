@@ -17,7 +17,7 @@ use crate::{
             Amount, Balance, Fee, Length, Magnitude, MinMax, Nonce, Sgn, Signed, Slot, SlotSpan,
         },
         fee_excess::FeeExcess,
-        GenesisConstant, GENESIS_CONSTANT,
+        GenesisConstant, ZkAppCommandLimits, GENESIS_CONSTANT,
     },
     zkapps::checks::{ZkappCheck, ZkappCheckOps},
     AccountId, AuthRequired, ControlTag, MutableFp, MyCow, Permissions, SetVerificationKey,
@@ -27,7 +27,10 @@ use crate::{
 use ark_ff::{UniformRand, Zero};
 use itertools::Itertools;
 use mina_curves::pasta::Fp;
-use mina_p2p_messages::v2::MinaBaseZkappCommandTStableV1WireStableV1AccountUpdatesA;
+use mina_p2p_messages::v2::{
+    MinaBaseZkappCommandTStableV1WireStableV1,
+    MinaBaseZkappCommandTStableV1WireStableV1AccountUpdatesA,
+};
 use mina_signer::{CompressedPubKey, Signature};
 use poseidon::hash::{
     hash_noinputs, hash_with_kimchi,
@@ -2704,7 +2707,12 @@ pub struct FeePayer {
 }
 
 /// <https://github.com/MinaProtocol/mina/blob/2ee6e004ba8c6a0541056076aab22ea162f7eb3a/src/lib/mina_base/zkapp_command.ml#L959>
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Serialized to JSON like OCaml's `Zkapp_command.to_yojson`, through
+/// [`MinaBaseZkappCommandTStableV1WireStableV1`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(into = "MinaBaseZkappCommandTStableV1WireStableV1")]
+#[serde(try_from = "MinaBaseZkappCommandTStableV1WireStableV1")]
 pub struct ZkAppCommand {
     pub fee_payer: FeePayer,
     pub account_updates: CallForest<AccountUpdate>,
@@ -2807,6 +2815,12 @@ impl ZkAppCommand {
     /// - when adding to the transaction pool
     /// - in incoming blocks
     pub fn valid_size(&self) -> Result<(), String> {
+        self.valid_size_with_limits(ZkAppCommandLimits::global())
+    }
+
+    /// [`Self::valid_size`] with the given limits on events and actions,
+    /// instead of the ones of the network.
+    pub fn valid_size_with_limits(&self, limits: &ZkAppCommandLimits) -> Result<(), String> {
         use crate::proofs::zkapp::group::{SegmentBasic, ZkappCommandIntermediateState};
 
         let Self {
@@ -2819,10 +2833,18 @@ impl ZkAppCommand {
 
         let mut n_account_updates = 0;
         let (mut num_event_elements, mut num_action_elements) = (0, 0);
+        // Index of the account update which makes the command exceed the limit
+        let (mut events_exceeded_at, mut actions_exceeded_at) = (None, None);
 
         account_updates.fold((), |_, account_update| {
             num_event_elements += events_elements(account_update.body.events.events());
             num_action_elements += events_elements(account_update.body.actions.events());
+            if num_event_elements > limits.max_event_elements {
+                events_exceeded_at.get_or_insert(n_account_updates);
+            }
+            if num_action_elements > limits.max_action_elements {
+                actions_exceeded_at.get_or_insert(n_account_updates);
+            }
             n_account_updates += 1;
         });
 
@@ -2847,32 +2869,35 @@ impl ZkAppCommand {
 
         let GenesisConstant {
             zkapp_transaction_cost_limit: cost_limit,
-            max_event_elements,
-            max_action_elements,
             ..
         } = GENESIS_CONSTANT;
 
         let zkapp_cost_within_limit =
             Self::zkapp_cost(proof_segments, signed_single_segments, signed_pair_segments)
                 < cost_limit;
-        let valid_event_elements = num_event_elements <= max_event_elements;
-        let valid_action_elements = num_action_elements <= max_action_elements;
 
-        if zkapp_cost_within_limit && valid_event_elements && valid_action_elements {
-            return Ok(());
+        let mut errors = Vec::new();
+        if !zkapp_cost_within_limit {
+            errors.push("zkapp transaction too expensive".to_string());
+        }
+        if let Some(index) = events_exceeded_at {
+            errors.push(format!(
+                "too many event elements ({num_event_elements}, max {}), limit exceeded at account update {index}",
+                limits.max_event_elements
+            ));
+        }
+        if let Some(index) = actions_exceeded_at {
+            errors.push(format!(
+                "too many action elements ({num_action_elements}, max {}), limit exceeded at account update {index}",
+                limits.max_action_elements
+            ));
         }
 
-        let err = [
-            (zkapp_cost_within_limit, "zkapp transaction too expensive"),
-            (valid_event_elements, "too many event elements"),
-            (valid_action_elements, "too many action elements"),
-        ]
-        .iter()
-        .filter(|(b, _s)| !b)
-        .map(|(_b, s)| s)
-        .join(";");
-
-        Err(err)
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join(";"))
+        }
     }
 
     /// <https://github.com/MinaProtocol/mina/blob/2ff0292b637684ce0372e7b8e23ec85404dc5091/src/lib/mina_base/zkapp_command.ml#L997>
@@ -2960,6 +2985,14 @@ impl ZkAppCommand {
         let account_updates_hash = self.account_updates_hash();
         TransactionCommitment::create(account_updates_hash)
     }
+
+    /// Commitment to the account updates, the memo and the fee payer, signed
+    /// by the fee payer and by the account updates using the full commitment.
+    pub fn full_commitment(&self) -> TransactionCommitment {
+        let memo_hash = self.memo.hash();
+        let fee_payer_hash = AccountUpdate::of_fee_payer(self.fee_payer.clone()).digest();
+        self.commitment().create_complete(memo_hash, fee_payer_hash)
+    }
 }
 
 pub struct MaybeWithStatus<T> {
@@ -11,20 +11,40 @@ use crate::{
 
 /// Trait used in transaction logic, on the ledger witness (`SparseLedger`), or on mask
 ///
+/// This is the ledger backend of the transaction logic: any type implementing
+/// it and [`LedgerNonSnarkBackend`] can have transactions applied to it, for
+/// example the ledger of a rollup reusing Mina's transaction logic.
+///
+/// The transaction logic relies on the following:
+///
+/// - Clones are handles to the same ledger: a change made through a clone is
+///   visible through the original. The ledger passed to the transaction
+///   logic is cloned into its global and local states, and the changes made
+///   there are expected in the caller's ledger.
+/// - [`LedgerIntf::create_masked`] returns a ledger with the same accounts,
+///   whose changes are only visible in `self` once it is passed to
+///   [`LedgerIntf::apply_mask`]. Discarding the mask discards its changes.
+/// - [`LedgerIntf::empty`] is called with a depth of 0 to create placeholder
+///   ledgers, so it should be cheap.
+///
 /// <https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/mina_base/ledger_intf.ml>
 /// <https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/mina_base/sparse_ledger_base.ml>
+///
+/// [`LedgerNonSnarkBackend`]: crate::zkapps::non_snark::LedgerNonSnarkBackend
 pub trait LedgerIntf {
     type Location: Clone + std::fmt::Debug;
 
     fn get(&self, addr: &Self::Location) -> Option<Box<Account>>;
     fn location_of_account(&self, account_id: &AccountId) -> Option<Self::Location>;
     fn set(&mut self, addr: &Self::Location, account: Box<Account>);
+    /// Returns the account, creating it with the given id if it doesn't exist
     fn get_or_create(
         &mut self,
         account_id: &AccountId,
     ) -> Result<(AccountState, Box<Account>, Self::Location), String>;
+    /// Fails if the account already exists
     fn create_new_account(&mut self, account_id: AccountId, account: Account) -> Result<(), ()>;
-    fn remove_accounts_exn(&mut self, account_ids: &[AccountId]);
+    /// Root hash, recorded as the hash of the ledger before each transaction
     fn merkle_root(&mut self) -> Fp;
     fn empty(depth: usize) -> Self;
     fn create_masked(&self) -> Self;
@@ -58,9 +78,6 @@ impl LedgerIntf for LedgerWithHash {
     fn create_new_account(&mut self, account_id: AccountId, account: Account) -> Result<(), ()> {
         todo!()
     }
-    fn remove_accounts_exn(&mut self, account_ids: &[AccountId]) {
-        todo!()
-    }
     fn merkle_root(&mut self) -> Fp {
         todo!()
     }
@@ -267,11 +267,6 @@ impl LedgerIntf for SparseLedger {
         self.with(|this| this.create_new_account(account_id, to_set))
     }
 
-    /// <https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/mina_base/sparse_ledger_base.ml#L112>
-    fn remove_accounts_exn(&mut self, _account_ids: &[AccountId]) {
-        unimplemented!("remove_accounts_exn: not implemented")
-    }
-
     /// <https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/mina_base/sparse_ledger_base.ml#L115>
     fn merkle_root(&mut self) -> Fp {
         self.merkle_root()
@@ -0,0 +1,443 @@
+//! Raw actions and events of zkApp accounts
+//!
+//! Accounts only keep a hash of the actions and events emitted by their
+//! account updates, the values themselves are dropped once applied. The
+//! [`ActionEventStore`] keeps the ones of the zkApp commands included in each
+//! applied block, so that they can be served the way the `actions` and
+//! `events` queries of the OCaml archive node do.
+//!
+//! Blocks of the frontier are all kept, as a fork can become the best chain
+//! again, but only the ones on the best chain are served. Once the root moves
+//! past a block, it is dropped if it isn't on the best chain, and finalized
+//! blocks are kept for a limited number of slots.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    ops::RangeBounds,
+    path::Path,
+};
+
+use mina_p2p_messages::v2::StateHash;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    scan_state::{
+        currency::Slot,
+        transaction_logic::{zkapp_command::Event, TransactionStatus, UserCommand},
+    },
+    AccountId,
+};
+
+/// Actions and events emitted by one account update.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ActionsEvents {
+    pub account_id: AccountId,
+    /// Global slot of the block which included the zkApp command.
+    pub global_slot: Slot,
+    /// State hash of the block which included the zkApp command.
+    pub state_hash: StateHash,
+    /// Index of the zkApp command among the commands of the block.
+    pub transaction_index: usize,
+    /// Index of the account update in the zkApp command, not counting the
+    /// fee payer.
+    pub account_update_index: usize,
+    #[serde(with = "fields")]
+    pub actions: Vec<Event>,
+    #[serde(with = "fields")]
+    pub events: Vec<Event>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct StoredBlock {
+    global_slot: Slot,
+    parent_hash: StateHash,
+    entries: Vec<ActionsEvents>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActionEventStore {
+    /// Number of slots finalized blocks are kept for.
+    history_slots: u32,
+    blocks: BTreeMap<StateHash, StoredBlock>,
+    /// Blocks of the best chain, as of the last [`Self::commit`].
+    best_chain: BTreeSet<StateHash>,
+    /// Blocks with actions or events of each account.
+    #[serde(skip)]
+    accounts: BTreeMap<AccountId, BTreeSet<(Slot, StateHash)>>,
+}
+
+impl ActionEventStore {
+    /// Default number of slots finalized blocks are kept for, about two
+    /// epochs.
+    pub const DEFAULT_HISTORY_SLOTS: u32 = 2 * 7140;
+
+    pub fn new(history_slots: u32) -> Self {
+        Self {
+            history_slots,
+            blocks: Default::default(),
+            best_chain: Default::default(),
+            accounts: Default::default(),
+        }
+    }
+
+    /// Reads the store saved at `path` by [`Self::save`], or creates an empty
+    /// one if there is none.
+    pub fn load(path: &Path, history_slots: u32) -> io::Result<Self> {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(Self::new(history_slots))
+            }
+            Err(err) => return Err(err),
+        };
+        let mut store: Self = serde_json::from_slice(&bytes)?;
+        store.history_slots = history_slots;
+        store.accounts = Default::default();
+        let blocks = store
+            .blocks
+            .iter()
+            .map(|(hash, block)| (hash.clone(), block.entries.clone()))
+            .collect::<Vec<_>>();
+        for (hash, entries) in blocks {
+            store.index(&hash, &entries);
+        }
+        Ok(store)
+    }
+
+    /// Writes the store to `path`, replacing the previous file only once the
+    /// new one is complete.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        let bytes = serde_json::to_vec(self)?;
+        std::fs::write(&tmp_path, bytes)?;
+        std::fs::rename(&tmp_path, path)
+    }
+
+    /// Keeps the actions and events of the zkApp commands included in a
+    /// block, `commands` being the commands of the block with their status.
+    /// Failed commands are skipped, their actions and events weren't applied
+    /// to the accounts. Applying the same block again replaces what was kept
+    /// for it.
+    pub fn add_block(
+        &mut self,
+        state_hash: StateHash,
+        parent_hash: StateHash,
+        global_slot: Slot,
+        commands: impl IntoIterator<Item = (UserCommand, TransactionStatus)>,
+    ) {
+        let entries = commands
+            .into_iter()
+            .enumerate()
+            .filter_map(|(transaction_index, (command, status))| match command {
+                UserCommand::ZkAppCommand(command) if status.is_applied() => {
+                    Some((transaction_index, command))
+                }
+                _ => None,
+            })
+            .flat_map(|(transaction_index, command)| {
+                let state_hash = state_hash.clone();
+                command
+                    .account_updates
+                    .to_account_updates()
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, account_update)| {
+                        !account_update.body.actions.0.is_empty()
+                            || !account_update.body.events.0.is_empty()
+                    })
+                    .map(
+                        move |(account_update_index, account_update)| ActionsEvents {
+                            account_id: account_update.account_id(),
+                            global_slot,
+                            state_hash: state_hash.clone(),
+                            transaction_index,
+                            account_update_index,
+                            actions: account_update.body.actions.0,
+                            events: account_update.body.events.0,
+                        },
+                    )
+            })
+            .collect();
+
+        self.insert(
+            state_hash,
+            StoredBlock {
+                global_slot,
+                parent_hash,
+                entries,
+            },
+        );
+    }
+
+    fn insert(&mut self, state_hash: StateHash, block: StoredBlock) {
+        self.remove_block(&state_hash);
+        self.index(&state_hash, &block.entries);
+        self.blocks.insert(state_hash, block);
+    }
+
+    fn index(&mut self, state_hash: &StateHash, entries: &[ActionsEvents]) {
+        for entry in entries {
+            self.accounts
+                .entry(entry.account_id.clone())
+                .or_default()
+                .insert((entry.global_slot, state_hash.clone()));
+        }
+    }
+
+    /// Updates the best chain after a new best tip or a new root. Blocks
+    /// before the root which aren't on the best chain are dropped, as well as
+    /// finalized blocks older than the history, or without any entry.
+    pub fn commit(&mut self, root_slot: Slot, best_tip: &StateHash) {
+        let mut best_chain = BTreeSet::new();
+        let mut hash = best_tip;
+        while let Some(block) = self.blocks.get(hash) {
+            best_chain.insert(hash.clone());
+            hash = &block.parent_hash;
+        }
+
+        let min_slot = Slot::from_u32(root_slot.as_u32().saturating_sub(self.history_slots));
+        let dropped = self
+            .blocks
+            .iter()
+            .filter(|(hash, block)| {
+                block.global_slot <= root_slot
+                    && (!best_chain.contains(*hash)
+                        || block.global_slot < min_slot
+                        || block.entries.is_empty())
+            })
+            .map(|(hash, _)| hash.clone())
+            .collect::<Vec<_>>();
+        for hash in dropped {
+            self.remove_block(&hash);
+            best_chain.remove(&hash);
+        }
+        self.best_chain = best_chain;
+    }
+
+    /// Actions and events of the account emitted in blocks of the best chain
+    /// within `slots`, oldest first.
+    pub fn get(&self, account_id: &AccountId, slots: impl RangeBounds<Slot>) -> Vec<ActionsEvents> {
+        let Some(blocks) = self.accounts.get(account_id) else {
+            return Vec::new();
+        };
+
+        blocks
+            .iter()
+            .filter(|(slot, hash)| slots.contains(slot) && self.best_chain.contains(hash))
+            .filter_map(|(_, hash)| self.blocks.get(hash))
+            .flat_map(|block| &block.entries)
+            .filter(|entry| &entry.account_id == account_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Account updates of the account which emitted actions, within `slots`.
+    pub fn actions(
+        &self,
+        account_id: &AccountId,
+        slots: impl RangeBounds<Slot>,
+    ) -> Vec<ActionsEvents> {
+        let mut entries = self.get(account_id, slots);
+        entries.retain(|entry| !entry.actions.is_empty());
+        entries
+    }
+
+    /// Account updates of the account which emitted events, within `slots`.
+    pub fn events(
+        &self,
+        account_id: &AccountId,
+        slots: impl RangeBounds<Slot>,
+    ) -> Vec<ActionsEvents> {
+        let mut entries = self.get(account_id, slots);
+        entries.retain(|entry| !entry.events.is_empty());
+        entries
+    }
+
+    fn remove_block(&mut self, state_hash: &StateHash) {
+        let Some(block) = self.blocks.remove(state_hash) else {
+            return;
+        };
+        for entry in block.entries {
+            let Some(blocks) = self.accounts.get_mut(&entry.account_id) else {
+                continue;
+            };
+            blocks.remove(&(block.global_slot, state_hash.clone()));
+            if blocks.is_empty() {
+                self.accounts.remove(&entry.account_id);
+            }
+        }
+    }
+}
+
+impl Default for ActionEventStore {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_HISTORY_SLOTS)
+    }
+}
+
+/// Events as lists of field elements, in the decimal representation used by
+/// GraphQL.
+mod fields {
+    use std::str::FromStr;
+
+    use mina_curves::pasta::Fp;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Event;
+    use crate::FpExt;
+
+    pub fn serialize<S: Serializer>(events: &[Event], serializer: S) -> Result<S::Ok, S::Error> {
+        events
+            .iter()
+            .map(|event| event.0.iter().map(|f| f.to_decimal()).collect())
+            .collect::<Vec<Vec<String>>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Event>, D::Error> {
+        Vec::<Vec<String>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|event| {
+                event
+                    .iter()
+                    .map(|f| Fp::from_str(f).ok())
+                    .collect::<Option<_>>()
+                    .map(Event)
+                    .ok_or_else(|| serde::de::Error::custom("invalid field element"))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_curves::pasta::Fp;
+
+    use crate::{gen_compressed, TokenId};
+
+    use super::*;
+
+    #[cfg(target_family = "wasm")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    fn hash(n: u64) -> StateHash {
+        StateHash::from_fp(Fp::from(n))
+    }
+
+    fn entry(account_id: &AccountId, block: u64, actions: usize, events: usize) -> ActionsEvents {
+        ActionsEvents {
+            account_id: account_id.clone(),
+            global_slot: Slot::from_u32(block as u32),
+            state_hash: hash(block),
+            transaction_index: 0,
+            account_update_index: 0,
+            actions: vec![Event(vec![Fp::from(1u64)]); actions],
+            events: vec![Event(vec![Fp::from(2u64)]); events],
+        }
+    }
+
+    /// Block `n` at slot `n`, its parent being block `parent`.
+    fn block(store: &mut ActionEventStore, n: u64, parent: u64, entries: Vec<ActionsEvents>) {
+        store.insert(
+            hash(n),
+            StoredBlock {
+                global_slot: Slot::from_u32(n as u32),
+                parent_hash: hash(parent),
+                entries,
+            },
+        );
+    }
+
+    #[test]
+    fn test_action_event_store() {
+        let mut store = ActionEventStore::new(10);
+        let a = AccountId::new(gen_compressed(), TokenId::default());
+        let b = AccountId::new(gen_compressed(), TokenId::default());
+
+        block(
+            &mut store,
+            1,
+            0,
+            vec![entry(&a, 1, 1, 0), entry(&b, 1, 0, 1)],
+        );
+        block(&mut store, 2, 1, vec![entry(&a, 2, 0, 2)]);
+        block(&mut store, 3, 2, vec![entry(&a, 3, 3, 0)]);
+        // Not served before being on the best chain.
+        assert!(store.get(&a, ..).is_empty());
+
+        store.commit(Slot::from_u32(1), &hash(3));
+        assert_eq!(store.get(&a, ..).len(), 3);
+        assert_eq!(store.actions(&a, ..).len(), 2);
+        assert_eq!(store.events(&a, ..).len(), 1);
+        assert_eq!(store.events(&b, ..).len(), 1);
+        assert_eq!(
+            store
+                .get(&a, Slot::from_u32(2)..)
+                .iter()
+                .map(|e| e.global_slot)
+                .collect::<Vec<_>>(),
+            [Slot::from_u32(2), Slot::from_u32(3)]
+        );
+
+        // Applying a block again replaces its entries
+        block(&mut store, 3, 2, vec![entry(&b, 3, 1, 0)]);
+        assert_eq!(store.actions(&a, ..).len(), 1);
+        assert_eq!(store.actions(&b, ..).len(), 1);
+    }
+
+    #[test]
+    fn test_action_event_store_reorg() {
+        let mut store = ActionEventStore::new(10);
+        let a = AccountId::new(gen_compressed(), TokenId::default());
+
+        block(&mut store, 1, 0, vec![entry(&a, 1, 1, 0)]);
+        block(&mut store, 2, 1, vec![entry(&a, 2, 1, 0)]);
+        // Sibling of block 2, on another fork.
+        block(&mut store, 3, 1, vec![entry(&a, 3, 1, 0)]);
+
+        store.commit(Slot::from_u32(1), &hash(2));
+        let slots = |store: &ActionEventStore| {
+            store
+                .get(&a, ..)
+                .iter()
+                .map(|e| e.global_slot.as_u32())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(slots(&store), [1, 2]);
+
+        // The fork becomes the best chain.
+        store.commit(Slot::from_u32(1), &hash(3));
+        assert_eq!(slots(&store), [1, 3]);
+
+        // Once the root passes them, blocks of other forks are dropped.
+        store.commit(Slot::from_u32(3), &hash(3));
+        assert!(!store.blocks.contains_key(&hash(2)));
+        assert_eq!(slots(&store), [1, 3]);
+
+        // As well as finalized blocks older than the history.
+        store.commit(Slot::from_u32(12), &hash(3));
+        assert_eq!(slots(&store), [3]);
+    }
+
+    #[test]
+    fn test_action_event_store_persisted() {
+        let dir = std::env::temp_dir().join(format!("actions-events-{}", crate::next_uuid()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("actions-events.json");
+        let mut store = ActionEventStore::new(10);
+        let a = AccountId::new(gen_compressed(), TokenId::default());
+
+        block(&mut store, 1, 0, vec![entry(&a, 1, 2, 1)]);
+        store.commit(Slot::from_u32(1), &hash(1));
+        store.save(&path).unwrap();
+
+        let loaded = ActionEventStore::load(&path, 10).unwrap();
+        assert_eq!(loaded.get(&a, ..), store.get(&a, ..));
+        assert!(ActionEventStore::load(&dir.join("missing.json"), 10)
+            .unwrap()
+            .get(&a, ..)
+            .is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
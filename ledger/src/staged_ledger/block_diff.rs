@@ -0,0 +1,278 @@
+//! Validation of the staged ledger diff of a block, as done by the node when
+//! applying a block, usable without the rest of the node.
+//!
+//! ```ignore
+//! let mut staged_ledger = parent_staged_ledger.clone();
+//! let result = validate_block_diff(
+//!     &mut staged_ledger,
+//!     &constraint_constants,
+//!     &Verifier,
+//!     None,
+//!     &block,
+//!     &parent_block.header.protocol_state,
+//! )?;
+//! ```
+
+use mina_core::constants::ConstraintConstants;
+use mina_p2p_messages::{
+    bigint::InvalidBigInt,
+    v2::{
+        MinaBaseStagedLedgerHashStableV1, MinaBlockBlockStableV2,
+        MinaStateProtocolStateValueStableV2,
+    },
+};
+use mina_signer::CompressedPubKey;
+
+use crate::{
+    proofs::block::ProtocolState,
+    scan_state::{currency::Slot, transaction_logic::protocol_state::protocol_state_view},
+    verifier::Verifier,
+};
+
+use super::{
+    diff::Diff,
+    staged_ledger::{DiffResult, SkipVerification, StagedLedger, StagedLedgerError},
+    validate_block::{validate_block, BlockBodyValidationError},
+};
+
+#[derive(Debug, derive_more::From)]
+pub enum BlockDiffError {
+    /// The block contains an invalid field element or public key
+    #[from]
+    InvalidBigInt(InvalidBigInt),
+    /// The body doesn't match the body reference of the header
+    #[from]
+    Body(BlockBodyValidationError),
+    /// The diff couldn't be applied: invalid commands, proofs or works,
+    /// insufficient work, invalid coinbase...
+    #[from]
+    StagedLedger(StagedLedgerError),
+    /// The diff was applied, but doesn't result in the staged ledger hash of
+    /// the header
+    HashMismatch {
+        expected: Box<MinaBaseStagedLedgerHashStableV1>,
+        got: Box<MinaBaseStagedLedgerHashStableV1>,
+    },
+}
+
+/// Applies the staged ledger diff of `block` to `staged_ledger`, which must
+/// be the staged ledger of the parent block, whose protocol state is
+/// `pred_protocol_state`.
+///
+/// Commands, completed works and coinbase are verified, unless skipped with
+/// `skip_verification`. The resulting staged ledger hash is not compared to
+/// the one of the block header, see [`validate_block_diff`] for that.
+///
+/// `staged_ledger` is left in an unspecified state on error, so callers
+/// wanting to keep the parent staged ledger should pass a clone of it.
+pub fn apply_block_diff(
+    staged_ledger: &mut StagedLedger,
+    constraint_constants: &ConstraintConstants,
+    verifier: &Verifier,
+    skip_verification: Option<SkipVerification>,
+    block: &MinaBlockBlockStableV2,
+    pred_protocol_state: &MinaStateProtocolStateValueStableV2,
+) -> Result<DiffResult, BlockDiffError> {
+    let consensus_state = &block.header.protocol_state.body.consensus_state;
+    let global_slot = Slot::from_u32(consensus_state.global_slot_since_genesis.as_u32());
+    let coinbase_receiver: CompressedPubKey = (&consensus_state.coinbase_receiver).try_into()?;
+    let supercharge_coinbase = consensus_state.supercharge_coinbase;
+
+    let prev_state_view = protocol_state_view(pred_protocol_state)?;
+    let prev_protocol_state: ProtocolState = pred_protocol_state.try_into()?;
+    let diff: Diff = (&block.body.staged_ledger_diff).try_into()?;
+
+    let result = staged_ledger.apply(
+        skip_verification,
+        constraint_constants,
+        global_slot,
+        diff,
+        (),
+        verifier,
+        &prev_state_view,
+        prev_protocol_state.hashes(),
+        coinbase_receiver,
+        supercharge_coinbase,
+    )?;
+
+    Ok(result)
+}
+
+/// Fully validates the staged ledger diff of `block` against the staged
+/// ledger of its parent. This is what the node runs to apply a block.
+///
+/// On top of [`apply_block_diff`], checks that the body matches the body
+/// reference of the header and that the resulting staged ledger hash is the
+/// one of the header. This is the same logic the node uses to accept a block,
+/// minus the verification of the blockchain proof and of the consensus state.
+pub fn validate_block_diff(
+    staged_ledger: &mut StagedLedger,
+    constraint_constants: &ConstraintConstants,
+    verifier: &Verifier,
+    skip_verification: Option<SkipVerification>,
+    block: &MinaBlockBlockStableV2,
+    pred_protocol_state: &MinaStateProtocolStateValueStableV2,
+) -> Result<DiffResult, BlockDiffError> {
+    validate_block(block)?;
+
+    let result = apply_block_diff(
+        staged_ledger,
+        constraint_constants,
+        verifier,
+        skip_verification,
+        block,
+        pred_protocol_state,
+    )?;
+
+    let got = MinaBaseStagedLedgerHashStableV1::from(&result.hash_after_applying);
+    let expected = &block
+        .header
+        .protocol_state
+        .body
+        .blockchain_state
+        .staged_ledger_hash;
+    if &got != expected {
+        return Err(BlockDiffError::HashMismatch {
+            expected: Box::new(expected.clone()),
+            got: Box::new(got),
+        });
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_core::constants::{constraint_constants, PROTOCOL_VERSION};
+    use mina_curves::pasta::Fp;
+    use mina_p2p_messages::v2::{
+        LedgerHash, MinaBlockHeaderStableV2, StagedLedgerDiffBodyStableV1, StateHash,
+    };
+
+    use crate::{
+        dummy::{dummy_blockchain_proof, for_tests::dummy_protocol_state},
+        staged_ledger::{
+            diff::{with_valid_signatures_and_proofs, AtMostTwo},
+            validate_block::block_body_hash,
+        },
+        Database, Mask,
+    };
+
+    use super::*;
+
+    fn staged_ledger() -> StagedLedger {
+        let depth = constraint_constants().ledger_depth as u8;
+        let ledger = Mask::new_root(Database::create(depth));
+        StagedLedger::create_exn(constraint_constants().clone(), ledger).unwrap()
+    }
+
+    /// Child of the dummy protocol state, with an empty diff and a valid
+    /// body reference.
+    fn block() -> MinaBlockBlockStableV2 {
+        let diff = (&with_valid_signatures_and_proofs::Diff::empty()).into();
+        let mut protocol_state = dummy_protocol_state();
+        protocol_state.body.blockchain_state.body_reference = block_body_hash(&diff).unwrap();
+        MinaBlockBlockStableV2 {
+            header: MinaBlockHeaderStableV2 {
+                protocol_state,
+                protocol_state_proof: dummy_blockchain_proof(),
+                delta_block_chain_proof: (StateHash::from_fp(Fp::from(1)), Default::default()),
+                current_protocol_version: PROTOCOL_VERSION.clone(),
+                proposed_protocol_version_opt: None,
+            },
+            body: StagedLedgerDiffBodyStableV1 {
+                staged_ledger_diff: diff,
+            },
+        }
+    }
+
+    fn validate(block: &MinaBlockBlockStableV2) -> Result<DiffResult, BlockDiffError> {
+        validate_block_diff(
+            &mut staged_ledger(),
+            constraint_constants(),
+            &Verifier,
+            Some(SkipVerification::All),
+            block,
+            &dummy_protocol_state(),
+        )
+    }
+
+    /// Block whose header has the staged ledger hash resulting from its diff.
+    fn valid_block() -> MinaBlockBlockStableV2 {
+        let mut block = block();
+        let result = apply_block_diff(
+            &mut staged_ledger(),
+            constraint_constants(),
+            &Verifier,
+            Some(SkipVerification::All),
+            &block,
+            &dummy_protocol_state(),
+        )
+        .unwrap();
+        block
+            .header
+            .protocol_state
+            .body
+            .blockchain_state
+            .staged_ledger_hash = (&result.hash_after_applying).into();
+        block
+    }
+
+    #[test]
+    fn test_accepts_valid_block() {
+        let block = valid_block();
+        let result = validate(&block).unwrap();
+        assert_eq!(
+            MinaBaseStagedLedgerHashStableV1::from(&result.hash_after_applying),
+            block
+                .header
+                .protocol_state
+                .body
+                .blockchain_state
+                .staged_ledger_hash
+        );
+    }
+
+    #[test]
+    fn test_rejects_body_mismatch() {
+        let mut block = valid_block();
+        // The body has a coinbase, the body reference of the header is the
+        // one of the empty body.
+        let mut diff = with_valid_signatures_and_proofs::Diff::empty();
+        diff.diff.0.coinbase = AtMostTwo::One(None);
+        block.body.staged_ledger_diff = (&diff).into();
+
+        let err = validate(&block).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                BlockDiffError::Body(BlockBodyValidationError::HashMismatch { .. })
+            ),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn test_rejects_staged_ledger_hash_mismatch() {
+        let mut block = valid_block();
+        let staged_ledger_hash = &mut block
+            .header
+            .protocol_state
+            .body
+            .blockchain_state
+            .staged_ledger_hash;
+        let expected_hash = staged_ledger_hash.clone();
+        staged_ledger_hash.non_snark.ledger_hash = LedgerHash::from_fp(Fp::from(7));
+
+        match validate(&block).unwrap_err() {
+            BlockDiffError::HashMismatch { expected, got } => {
+                assert_eq!(*got, expected_hash);
+                assert_eq!(
+                    expected.non_snark.ledger_hash,
+                    LedgerHash::from_fp(Fp::from(7))
+                );
+            }
+            err => panic!("unexpected error: {err:?}"),
+        }
+    }
+}
@@ -9,6 +9,8 @@
 /// Diff creation logs:
 /// <https://github.com/MinaProtocol/mina/pull/4463>
 ///
+pub mod action_event_store;
+pub mod block_diff;
 pub mod diff;
 pub mod diff_creation_log;
 pub mod hash;
@@ -2067,6 +2067,7 @@ mod tests_ocaml {
         coinbase_amount: 720000000000,
         supercharged_coinbase_factor: 2,
         account_creation_fee: 1000000000,
+        minimum_user_command_fee: 1000000,
         fork: None,
     };
 
@@ -1,29 +1,34 @@
 use crate::{
+    check_permission,
     scan_state::{
         currency::{Amount, Balance, BlockTime, Fee, Magnitude, Nonce, Slot},
         fee_rate::FeeRate,
         transaction_logic::{
-            valid, verifiable,
+            signed_command, valid, verifiable,
             zkapp_command::{
+                self,
                 from_unapplied_sequence::{self, FromUnappliedSequence},
-                MaybeWithStatus, WithHash,
+                AccountUpdate, ClosedInterval, MaybeWithStatus, OrIgnore, WithHash,
             },
             TransactionStatus::Applied,
             UserCommand, WellFormednessError, WithStatus,
         },
     },
     verifier::{Verifier, VerifierError},
-    Account, AccountId, BaseLedger, Mask, TokenId, VerificationKey, VerificationKeyWire,
+    Account, AccountId, AuthRequired, BaseLedger, ControlTag, Mask, TokenId, VerificationKey,
+    VerificationKeyWire,
 };
 use backtrace::Backtrace;
 use itertools::Itertools;
-use mina_core::{bug_condition, consensus::ConsensusConstants};
+use mina_core::{bug_condition, consensus::ConsensusConstants, constants::ConstraintConstants};
 use mina_curves::pasta::Fp;
 use mina_p2p_messages::{bigint::BigInt, v2};
 use serde::{Deserialize, Serialize};
 use std::{
     borrow::{Borrow, Cow},
     collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    str::FromStr,
+    time::Duration,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -192,8 +197,12 @@ mod consensus {
     }
 }
 
-/// Fee increase required to replace a transaction.
-const REPLACE_FEE: Fee = Fee::of_nanomina_int_exn(1);
+/// Default of [`Config::replace_fee`].
+pub const DEFAULT_REPLACE_FEE: Fee = Fee::of_nanomina_int_exn(1);
+
+fn default_replace_fee() -> Fee {
+    DEFAULT_REPLACE_FEE
+}
 
 pub type ValidCommandWithHash = WithHash<valid::UserCommand, v2::TransactionHash>;
 
@@ -216,6 +225,14 @@ pub mod diff {
         AfterSlotTxEnd,
         BacktrackNonceMismatch,
         InvalidCurrencyConsumed,
+        #[strum(to_string = "AccountPreconditionUnsatisfied: {mismatch}")]
+        AccountPreconditionUnsatisfied {
+            mismatch: AccountPreconditionMismatch,
+        },
+        #[strum(to_string = "AccountNotPermitted: {denied}")]
+        AccountNotPermitted {
+            denied: PermissionDenied,
+        },
         Custom,
     }
 
@@ -232,6 +249,8 @@ pub mod diff {
                 | Error::FeePayerNotPermittedToSend
                 | Error::AfterSlotTxEnd
                 | Error::InvalidCurrencyConsumed
+                | Error::AccountPreconditionUnsatisfied { .. }
+                | Error::AccountNotPermitted { .. }
                 | Error::Custom
                 | Error::BacktrackNonceMismatch => false,
                 Error::Overflow | Error::BadToken | Error::UnwantedFeeToken => true,
@@ -239,6 +258,81 @@ pub mod diff {
         }
     }
 
+    impl mina_core::HasErrorCode for Error {
+        fn error_code(&self) -> mina_core::ErrorCode {
+            use mina_core::ErrorCode;
+            match self {
+                Error::InsufficientReplaceFee => ErrorCode::InsufficientReplaceFee,
+                Error::Duplicate => ErrorCode::Duplicate,
+                Error::InvalidNonce => ErrorCode::InvalidNonce,
+                Error::InsufficientFunds => ErrorCode::InsufficientFunds,
+                Error::Overflow => ErrorCode::Overflow,
+                Error::BadToken => ErrorCode::BadToken,
+                Error::UnwantedFeeToken => ErrorCode::UnwantedFeeToken,
+                Error::Expired => ErrorCode::Expired,
+                Error::Overloaded => ErrorCode::Overloaded,
+                Error::FeePayerAccountNotFound => ErrorCode::FeePayerAccountNotFound,
+                Error::FeePayerNotPermittedToSend => ErrorCode::FeePayerNotPermittedToSend,
+                Error::AfterSlotTxEnd => ErrorCode::AfterSlotTxEnd,
+                Error::BacktrackNonceMismatch => ErrorCode::BacktrackNonceMismatch,
+                Error::InvalidCurrencyConsumed => ErrorCode::InvalidCurrencyConsumed,
+                Error::AccountPreconditionUnsatisfied { .. } => {
+                    ErrorCode::AccountPreconditionUnsatisfied
+                }
+                Error::AccountNotPermitted { .. } => ErrorCode::AccountNotPermitted,
+                Error::Custom => ErrorCode::PoolRejected,
+            }
+        }
+    }
+
+    /// Account precondition of a zkApp command that can never be satisfied,
+    /// along with the value found in the ledger.
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct AccountPreconditionMismatch {
+        pub public_key: String,
+        pub field: String,
+        pub required: String,
+        pub actual: String,
+    }
+
+    impl std::fmt::Display for AccountPreconditionMismatch {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let Self {
+                public_key,
+                field,
+                required,
+                actual,
+            } = self;
+            write!(
+                f,
+                "{field} precondition {required} but account {field} is {actual} ({public_key})"
+            )
+        }
+    }
+
+    /// Permission of an account which a signed command needs, and which
+    /// requires an authorization the command doesn't provide.
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct PermissionDenied {
+        pub public_key: String,
+        pub permission: String,
+        pub required: String,
+    }
+
+    impl std::fmt::Display for PermissionDenied {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let Self {
+                public_key,
+                permission,
+                required,
+            } = self;
+            write!(
+                f,
+                "{permission} permission of {public_key} requires {required} authorization"
+            )
+        }
+    }
+
     #[derive(Debug)]
     pub struct Diff {
         pub list: Vec<UserCommand>,
@@ -280,6 +374,72 @@ pub struct Config {
     pub trust_system: (),
     pub pool_max_size: usize,
     pub slot_tx_end: Option<Slot>,
+    /// Commands staying in the pool for longer than this are removed, even
+    /// if they are still valid, see [`TransactionPool::remove_stale`].
+    #[serde(default)]
+    pub max_lifetime: Option<Duration>,
+    #[serde(default)]
+    pub zkapp_relay: ZkappRelayConfig,
+    /// Fee increase required to replace commands in the pool with a command
+    /// from the same fee payer and with the same nonce. The new command must
+    /// pay at least this much more than the replaced command, and the
+    /// commands following it which can't be applied after it anymore.
+    #[serde(default = "default_replace_fee")]
+    pub replace_fee: Fee,
+}
+
+/// How zkApp commands received from the gossip network are relayed when
+/// they can't be verified in time.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ZkappRelayConfig {
+    pub policy: ZkappRelayPolicy,
+    /// Time allowed to verify the commands of a gossip message, from the
+    /// moment their verification starts, before `policy` applies.
+    pub budget: Duration,
+}
+
+/// What to do with a gossip message whose zkApp commands couldn't be
+/// verified within [`ZkappRelayConfig::budget`], or at all because the
+/// verification key of an account isn't available yet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ZkappRelayPolicy {
+    /// Don't relay the message. Its commands are still added to the pool
+    /// once verified.
+    Drop,
+    /// Relay the message once its commands are verified, however long it
+    /// takes.
+    #[default]
+    Delay,
+    /// Relay the message without waiting for the verification, which is
+    /// logged as a `TransactionPoolZkappForwardedUnverified` warning.
+    ForwardUnverified,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("invalid zkApp relay policy: {0}! expected one of: drop/delay/forward-unverified")]
+pub struct ZkappRelayPolicyParseError(String);
+
+impl Default for ZkappRelayConfig {
+    fn default() -> Self {
+        Self {
+            policy: Default::default(),
+            budget: Duration::from_secs(10),
+        }
+    }
+}
+
+impl FromStr for ZkappRelayPolicy {
+    type Err = ZkappRelayPolicyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "drop" => Self::Drop,
+            "delay" => Self::Delay,
+            "forward-unverified" => Self::ForwardUnverified,
+            other => return Err(ZkappRelayPolicyParseError(other.to_owned())),
+        })
+    }
 }
 
 /// Used to be able to de/serialize our `TransactionPool` in the state machine
@@ -585,6 +745,8 @@ impl From<CommandError> for diff::Error {
 pub struct IndexedPoolConfig {
     pub consensus_constants: consensus::Constants,
     slot_tx_end: Option<Slot>,
+    #[serde(default = "default_replace_fee")]
+    replace_fee: Fee,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -632,8 +794,41 @@ pub enum RevalidateKind<'a> {
     Subset(&'a BTreeSet<AccountId>),
 }
 
+/// Pending commands of a fee payer, in the order they can be included.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SenderQueue {
+    pub sender: AccountId,
+    /// Currency the commands spend from the account, fees included.
+    pub currency_reserved: Amount,
+    pub commands: Vec<SenderQueueCommand>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SenderQueueCommand {
+    pub hash: v2::TransactionHash,
+    /// Nonce the account must have for the command to be applied.
+    pub nonce: Nonce,
+    pub fee: Fee,
+    pub valid_until: Slot,
+    pub status: SenderQueueCommandStatus,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SenderQueueCommandStatus {
+    /// Applicable to the best tip ledger, can be included in the next block.
+    Includable,
+    /// Can only be included after the command of the queue with this nonce.
+    WaitingForNonce(Nonce),
+    /// The global slot since genesis is past its `valid_until`, it will be
+    /// removed from the pool.
+    Expired,
+    /// The current global slot is at or after `slot_tx_end`, no command can
+    /// be included anymore.
+    AfterSlotTxEnd,
+}
+
 impl IndexedPool {
-    fn new(constants: &ConsensusConstants) -> Self {
+    fn new(constants: &ConsensusConstants, replace_fee: Fee) -> Self {
         Self {
             applicable_by_fee: HashMap::new(),
             all_by_sender: HashMap::new(),
@@ -644,6 +839,7 @@ impl IndexedPool {
             config: IndexedPoolConfig {
                 consensus_constants: consensus::Constants::create(constants),
                 slot_tx_end: None,
+                replace_fee,
             },
         }
     }
@@ -1192,9 +1388,10 @@ impl IndexedPool {
                         let _ = drop_tail.next();
                     }
 
-                    if increment < REPLACE_FEE {
+                    let replace_fee = self.config.replace_fee;
+                    if increment < replace_fee {
                         return Err(CommandError::InsufficientReplaceFee {
-                            replace_fee: REPLACE_FEE,
+                            replace_fee,
                             fee: increment,
                         });
                     }
@@ -1229,15 +1426,25 @@ impl IndexedPool {
     fn remove_expired(
         &mut self,
         global_slot_since_genesis: Slot,
-    ) -> Result<Vec<ValidCommandWithHash>, CommandError> {
-        let mut dropped = Vec::with_capacity(128);
+    ) -> Result<Vec<Eviction>, CommandError> {
+        let mut evicted = Vec::with_capacity(128);
         for cmd in self.expired(global_slot_since_genesis) {
             if self.member(&cmd) {
                 let removed = self.remove_with_dependents_exn(&cmd)?;
-                dropped.extend(removed);
+                evicted.extend(removed.into_iter().map(|command| {
+                    let valid_until = command.data.forget_check().valid_until();
+                    let reason = if valid_until < global_slot_since_genesis {
+                        EvictionReason::Expired { valid_until }
+                    } else {
+                        EvictionReason::PredecessorExpired {
+                            predecessor: cmd.hash.clone(),
+                        }
+                    };
+                    Eviction { command, reason }
+                }));
             }
         }
-        Ok(dropped)
+        Ok(evicted)
     }
 
     fn remove_lowest_fee(&mut self) -> Result<VecDeque<ValidCommandWithHash>, CommandError> {
@@ -1289,17 +1496,25 @@ impl IndexedPool {
     where
         F: Fn(&AccountId) -> Option<Account>,
     {
-        let requires_revalidation = |account_id: &AccountId| match kind {
-            RevalidateKind::EntirePool => true,
-            RevalidateKind::Subset(set) => set.contains(account_id),
+        // Only the queues of the senders being revalidated are cloned, so that
+        // revalidating a subset doesn't cost as much as the entire pool.
+        let senders: Vec<AccountId> = match kind {
+            RevalidateKind::EntirePool => self.all_by_sender.keys().cloned().collect(),
+            RevalidateKind::Subset(set) => set
+                .iter()
+                .filter(|account_id| self.all_by_sender.contains_key(account_id))
+                .cloned()
+                .collect(),
         };
 
         let mut dropped = Vec::new();
 
-        for (sender, (mut queue, mut currency_reserved)) in self.all_by_sender.clone() {
-            if !requires_revalidation(&sender) {
+        for sender in senders {
+            // Revalidating a sender only modifies its own queue
+            let Some((mut queue, mut currency_reserved)) = self.all_by_sender.get(&sender).cloned()
+            else {
                 continue;
-            }
+            };
             let account: Account = get_account(&sender)
                 .ok_or(CommandError::Custom(Cow::Borrowed("Account not find")))?;
             let current_balance = account
@@ -1519,6 +1734,60 @@ impl IndexedPool {
             .map(|(acc_id, (cmds, amount))| (acc_id, (cmds.back().unwrap().data.nonce(), amount)))
             .collect()
     }
+
+    fn sender_queue(
+        &self,
+        sender: &AccountId,
+        global_slot_since_genesis: Slot,
+        after_slot_tx_end: bool,
+    ) -> Option<SenderQueue> {
+        let (queue, currency_reserved) = self.all_by_sender.get(sender)?;
+
+        let mut previous_nonce = None;
+        let commands = queue
+            .iter()
+            .map(|cmd| {
+                let data = cmd.data.forget_check();
+                let nonce = data.applicable_at_nonce();
+                let valid_until = data.valid_until();
+                // Only the head of the queue is in `applicable_by_fee`.
+                let status = match previous_nonce.replace(nonce) {
+                    _ if after_slot_tx_end => SenderQueueCommandStatus::AfterSlotTxEnd,
+                    _ if valid_until < global_slot_since_genesis => {
+                        SenderQueueCommandStatus::Expired
+                    }
+                    None => SenderQueueCommandStatus::Includable,
+                    Some(nonce) => SenderQueueCommandStatus::WaitingForNonce(nonce),
+                };
+                SenderQueueCommand {
+                    hash: cmd.hash.clone(),
+                    nonce,
+                    fee: data.fee(),
+                    valid_until,
+                    status,
+                }
+            })
+            .collect();
+
+        Some(SenderQueue {
+            sender: sender.clone(),
+            currency_reserved: *currency_reserved,
+            commands,
+        })
+    }
+
+    fn sender_queues(
+        &self,
+        global_slot_since_genesis: Slot,
+        after_slot_tx_end: bool,
+    ) -> Vec<SenderQueue> {
+        self.all_by_sender
+            .keys()
+            .filter_map(|sender| {
+                self.sender_queue(sender, global_slot_since_genesis, after_slot_tx_end)
+            })
+            .collect()
+    }
 }
 
 fn currency_consumed(cmd: &UserCommand) -> Result<Amount, CommandError> {
@@ -1571,6 +1840,73 @@ pub enum ApplyDecision {
     Reject,
 }
 
+/// Command of the pool replaced by a command with the same fee payer and
+/// nonce, paying a higher fee, see [`Config::replace_fee`].
+#[derive(Clone, Debug)]
+pub struct Replacement {
+    pub replaced: ValidCommandWithHash,
+    pub replacement: ValidCommandWithHash,
+    /// Commands following the replaced one, removed along with it.
+    pub dropped_dependents: usize,
+}
+
+/// Command removed from the pool without being included in a block.
+#[derive(Clone, Debug)]
+pub struct Eviction {
+    pub command: ValidCommandWithHash,
+    pub reason: EvictionReason,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EvictionReason {
+    /// The global slot since genesis is past the `valid_until` of the
+    /// command.
+    Expired { valid_until: Slot },
+    /// The command follows an expired command of the same fee payer, so it
+    /// can't be included anymore.
+    PredecessorExpired { predecessor: v2::TransactionHash },
+    /// An account precondition of the command can't be satisfied anymore by
+    /// the best tip ledger.
+    AccountPreconditionUnsatisfied {
+        mismatch: diff::AccountPreconditionMismatch,
+    },
+    /// The command follows a command of the same fee payer that was evicted
+    /// for an unsatisfied account precondition.
+    PredecessorPreconditionUnsatisfied { predecessor: v2::TransactionHash },
+}
+
+impl std::fmt::Display for EvictionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Expired { valid_until } => {
+                write!(f, "expired at slot {}", valid_until.as_u32())
+            }
+            Self::PredecessorExpired { predecessor } => {
+                write!(f, "follows expired command {predecessor}")
+            }
+            Self::AccountPreconditionUnsatisfied { mismatch } => {
+                write!(f, "unsatisfied account precondition: {mismatch}")
+            }
+            Self::PredecessorPreconditionUnsatisfied { predecessor } => {
+                write!(
+                    f,
+                    "follows command {predecessor} with unsatisfied account precondition"
+                )
+            }
+        }
+    }
+}
+
+/// Decision, accepted and rejected commands, hashes of the commands removed
+/// from the pool, and replacements.
+pub type ApplyResult = (
+    ApplyDecision,
+    Vec<ValidCommandWithHash>,
+    Vec<(ValidCommandWithHash, diff::Error)>,
+    HashSet<v2::TransactionHash>,
+    Vec<Replacement>,
+);
+
 const MAX_PER_15_SECONDS: usize = 10;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -1578,6 +1914,10 @@ pub struct TransactionPool {
     pub pool: IndexedPool,
     locally_generated_uncommitted: HashMap<ValidCommandWithHash, (redux::Timestamp, Batch)>,
     locally_generated_committed: HashMap<ValidCommandWithHash, (redux::Timestamp, Batch)>,
+    /// Time at which the commands were added to the pool, only tracked with
+    /// a [`Config::max_lifetime`].
+    #[serde(default)]
+    added_at: HashMap<v2::TransactionHash, redux::Timestamp>,
     current_batch: usize,
     remaining_in_batch: usize,
     pub config: Config,
@@ -1589,9 +1929,10 @@ pub struct TransactionPool {
 impl TransactionPool {
     pub fn new(config: Config, consensus_constants: &ConsensusConstants) -> Self {
         Self {
-            pool: IndexedPool::new(consensus_constants),
+            pool: IndexedPool::new(consensus_constants, config.replace_fee),
             locally_generated_uncommitted: Default::default(),
             locally_generated_committed: Default::default(),
+            added_at: Default::default(),
             current_batch: 0,
             remaining_in_batch: 0,
             config,
@@ -1613,6 +1954,39 @@ impl TransactionPool {
         self.pool.get_pending_amount_and_nonce()
     }
 
+    /// Pending commands of `sender`, ordered by nonce, with whether they can
+    /// be included in the next block at these slots. `None` if it has none.
+    pub fn sender_queue(
+        &self,
+        sender: &AccountId,
+        global_slot_since_genesis: Slot,
+        current_global_slot: Slot,
+    ) -> Option<SenderQueue> {
+        self.pool.sender_queue(
+            sender,
+            global_slot_since_genesis,
+            self.is_after_slot_tx_end(current_global_slot),
+        )
+    }
+
+    /// Same as [`Self::sender_queue`], for all the fee payers of the pool.
+    pub fn sender_queues(
+        &self,
+        global_slot_since_genesis: Slot,
+        current_global_slot: Slot,
+    ) -> Vec<SenderQueue> {
+        self.pool.sender_queues(
+            global_slot_since_genesis,
+            self.is_after_slot_tx_end(current_global_slot),
+        )
+    }
+
+    fn is_after_slot_tx_end(&self, current_global_slot: Slot) -> bool {
+        self.config
+            .slot_tx_end
+            .is_some_and(|slot_tx_end| current_global_slot >= slot_tx_end)
+    }
+
     pub fn transactions(&mut self, limit: usize) -> Vec<ValidCommandWithHash> {
         self.pool.transactions(limit)
     }
@@ -1621,8 +1995,20 @@ impl TransactionPool {
         self.pool.list_includable_transactions(limit)
     }
 
+    /// Senders of the commands, and the accounts of zkApp account updates
+    /// with preconditions checked in [`stale_account_precondition`].
     pub fn get_accounts_to_revalidate_on_new_best_tip(&self) -> BTreeSet<AccountId> {
-        self.pool.all_by_sender.keys().cloned().collect()
+        let precondition_accounts = self
+            .pool
+            .all_by_hash
+            .values()
+            .flat_map(|cmd| precondition_accounts(&cmd.data));
+        self.pool
+            .all_by_sender
+            .keys()
+            .cloned()
+            .chain(precondition_accounts)
+            .collect()
     }
 
     pub fn on_new_best_tip(
@@ -1642,6 +2028,12 @@ impl TransactionPool {
                 )
             },
         )?;
+        let mut dropped = dropped;
+        dropped.extend(
+            self.remove_unsatisfied_preconditions(accounts)?
+                .into_iter()
+                .map(|Eviction { command, .. }| command),
+        );
 
         let dropped_locally_generated = dropped
             .iter()
@@ -1884,18 +2276,147 @@ impl TransactionPool {
             }
         }
 
-        let expired_commands = self.pool.remove_expired(global_slot_since_genesis)?;
-        for cmd in &expired_commands {
+        self.remove_unsatisfied_preconditions(accounts)?;
+        self.remove_expired(global_slot_since_genesis)?;
+
+        Ok(())
+    }
+
+    /// Removes the commands whose `valid_until` is before
+    /// `global_slot_since_genesis`, along with the commands depending on
+    /// them. Returns the removed commands, with why they were removed.
+    pub fn remove_expired(
+        &mut self,
+        global_slot_since_genesis: Slot,
+    ) -> Result<Vec<Eviction>, CommandError> {
+        let evicted = self.pool.remove_expired(global_slot_since_genesis)?;
+        for Eviction { command, .. } in &evicted {
+            self.verification_key_table.decrement_hashed([command]);
+            self.locally_generated_uncommitted.remove(command);
+            self.added_at.remove(&command.hash);
+        }
+        Ok(evicted)
+    }
+
+    /// Removes the commands which have been in the pool for longer than
+    /// [`Config::max_lifetime`], along with the commands depending on them,
+    /// whatever their `valid_until`. Returns the removed commands.
+    ///
+    /// Commands added back to the pool by a reorg are considered added at
+    /// the first call following it.
+    pub fn remove_stale(
+        &mut self,
+        now: redux::Timestamp,
+    ) -> Result<Vec<ValidCommandWithHash>, CommandError> {
+        let Some(max_lifetime) = self.config.max_lifetime else {
+            return Ok(Vec::new());
+        };
+
+        let all_by_hash = &self.pool.all_by_hash;
+        self.added_at
+            .retain(|hash, _| all_by_hash.contains_key(hash));
+        for hash in all_by_hash.keys() {
+            self.added_at.entry(hash.clone()).or_insert(now);
+        }
+
+        let stale = self
+            .added_at
+            .iter()
+            .filter(|(_, added_at)| {
+                now.checked_sub(**added_at)
+                    .is_some_and(|dur| dur >= max_lifetime)
+            })
+            .map(|(hash, _)| hash.clone())
+            .collect::<Vec<_>>();
+
+        let mut dropped = Vec::with_capacity(stale.len());
+        for hash in stale {
+            // Might already be removed as a dependent of another one.
+            let Some(cmd) = self.pool.get(&hash).cloned() else {
+                continue;
+            };
+            dropped.extend(self.pool.remove_with_dependents_exn(&cmd)?);
+        }
+
+        for cmd in &dropped {
             self.verification_key_table.decrement_hashed([cmd]);
             self.locally_generated_uncommitted.remove(cmd);
+            self.added_at.remove(&cmd.hash);
         }
 
-        Ok(())
+        Ok(dropped)
     }
 
+    /// Removes the zkApp commands with account preconditions that `accounts`
+    /// make impossible to satisfy, along with the commands depending on
+    /// them.
+    /// Returns the removed commands, with why they were removed.
+    pub fn remove_unsatisfied_preconditions(
+        &mut self,
+        accounts: &BTreeMap<AccountId, Account>,
+    ) -> Result<Vec<Eviction>, CommandError> {
+        let unsatisfied = self
+            .pool
+            .all_by_hash
+            .values()
+            .filter_map(|cmd| {
+                let mismatch = stale_account_precondition(&cmd.data, accounts)?;
+                Some((cmd.clone(), mismatch))
+            })
+            .collect::<Vec<_>>();
+
+        let mut evicted = Vec::with_capacity(unsatisfied.len());
+        for (cmd, mismatch) in unsatisfied {
+            // Might already be removed as a dependent of another one.
+            if self.pool.get(&cmd.hash).is_none() {
+                continue;
+            }
+            let removed = self.pool.remove_with_dependents_exn(&cmd)?;
+            evicted.extend(removed.into_iter().map(|command| {
+                let reason = if command.hash == cmd.hash {
+                    EvictionReason::AccountPreconditionUnsatisfied {
+                        mismatch: mismatch.clone(),
+                    }
+                } else {
+                    EvictionReason::PredecessorPreconditionUnsatisfied {
+                        predecessor: cmd.hash.clone(),
+                    }
+                };
+                Eviction { command, reason }
+            }));
+        }
+
+        for Eviction { command, .. } in &evicted {
+            self.verification_key_table.decrement_hashed([command]);
+            self.locally_generated_uncommitted.remove(command);
+            self.added_at.remove(&command.hash);
+        }
+        Ok(evicted)
+    }
+
+    /// Fee payers of the commands, receivers of payments, whose permissions
+    /// are checked in [`permission_denied`], and the accounts of zkApp
+    /// account updates with preconditions checked in
+    /// [`stale_account_precondition`].
     pub fn get_accounts_to_apply_diff(&self, diff: &diff::DiffVerified) -> BTreeSet<AccountId> {
         let fee_payer = |cmd: &ValidCommandWithHash| cmd.data.fee_payer();
-        diff.list.iter().map(fee_payer).collect()
+        let payment_receiver = |cmd: &ValidCommandWithHash| match &cmd.data {
+            valid::UserCommand::SignedCommand(cmd) => match &cmd.payload.body {
+                signed_command::Body::Payment(_) => Some(cmd.receiver()),
+                signed_command::Body::StakeDelegation(_) => None,
+            },
+            valid::UserCommand::ZkAppCommand(_) => None,
+        };
+        diff.list
+            .iter()
+            .map(fee_payer)
+            .chain(diff.list.iter().filter_map(payment_receiver))
+            .chain(
+                diff.list
+                    .iter()
+                    .flat_map(|cmd| precondition_accounts(&cmd.data)),
+            )
+            .collect()
     }
 
     fn apply(
@@ -1906,15 +2427,7 @@ impl TransactionPool {
         diff: &diff::DiffVerified,
         accounts: &BTreeMap<AccountId, Account>,
         is_sender_local: bool,
-    ) -> Result<
-        (
-            ApplyDecision,
-            Vec<ValidCommandWithHash>,
-            Vec<(ValidCommandWithHash, diff::Error)>,
-            HashSet<v2::TransactionHash>,
-        ),
-        String,
-    > {
+    ) -> Result<ApplyResult, String> {
         let fee_payer = |cmd: &ValidCommandWithHash| cmd.data.fee_payer();
         let fee_payer_accounts = accounts;
 
@@ -1947,6 +2460,12 @@ impl TransactionPool {
 
                 let result: Result<_, diff::Error> = (|| {
                     check_command(&self.pool, cmd)?;
+                    if let Some(denied) = permission_denied(&cmd.data, accounts) {
+                        return Err(diff::Error::AccountNotPermitted { denied });
+                    }
+                    if let Some(mismatch) = stale_account_precondition(&cmd.data, accounts) {
+                        return Err(diff::Error::AccountPreconditionUnsatisfied { mismatch });
+                    }
 
                     match self.pool.add_from_gossip_exn(
                         global_slot_since_genesis,
@@ -2024,15 +2543,28 @@ impl TransactionPool {
 
         let mut accepted = Vec::with_capacity(128);
         let mut rejected = Vec::with_capacity(128);
+        let mut replacements = Vec::new();
 
         // TODO: Re-work this to avoid cloning ?
         for result in &add_results {
             match result {
-                Ok((cmd, _dropped)) => {
+                Ok((cmd, dropped)) => {
                     if all_dropped_cmd_hashes.contains(&cmd.hash) {
                         // ignored (dropped)
                     } else {
+                        if self.config.max_lifetime.is_some() {
+                            self.added_at.insert(cmd.hash.clone(), time);
+                        }
                         accepted.push(cmd.clone());
+                        // Commands are only dropped when adding one if it
+                        // replaces the head of `dropped`.
+                        if let Some(replaced) = dropped.front() {
+                            replacements.push(Replacement {
+                                replaced: replaced.clone(),
+                                replacement: cmd.clone(),
+                                dropped_dependents: dropped.len() - 1,
+                            });
+                        }
                     }
                 }
                 Err((cmd, error)) => {
@@ -2050,7 +2582,13 @@ impl TransactionPool {
             ApplyDecision::Accept
         };
 
-        Ok((decision, accepted, rejected, all_dropped_cmd_hashes))
+        Ok((
+            decision,
+            accepted,
+            rejected,
+            all_dropped_cmd_hashes,
+            replacements,
+        ))
     }
 
     pub fn unsafe_apply(
@@ -2061,31 +2599,22 @@ impl TransactionPool {
         diff: &diff::DiffVerified,
         accounts: &BTreeMap<AccountId, Account>,
         is_sender_local: bool,
-    ) -> Result<
-        (
-            ApplyDecision,
-            Vec<ValidCommandWithHash>,
-            Vec<(ValidCommandWithHash, diff::Error)>,
-            HashSet<v2::TransactionHash>,
-        ),
-        String,
-    > {
-        let (decision, accepted, rejected, dropped) = self.apply(
+    ) -> Result<ApplyResult, String> {
+        self.apply(
             time,
             global_slot_since_genesis,
             current_global_slot,
             diff,
             accounts,
             is_sender_local,
-        )?;
-        Ok((decision, accepted, rejected, dropped))
+        )
     }
 
     fn register_locally_generated(&mut self, time: redux::Timestamp, cmd: &ValidCommandWithHash) {
         match self.locally_generated_uncommitted.entry(cmd.clone()) {
             Entry::Occupied(mut entry) => {
                 let (entry_time, _batch_num) = entry.get_mut();
-                *entry_time = redux::Timestamp::global_now();
+                *entry_time = time;
             }
             Entry::Vacant(entry) => {
                 let batch_num = if self.remaining_in_batch > 0 {
@@ -2101,14 +2630,20 @@ impl TransactionPool {
         }
     }
 
-    pub fn prevalidate(&self, diff: diff::Diff) -> Result<diff::Diff, TransactionPoolErrors> {
+    pub fn prevalidate(
+        &self,
+        diff: diff::Diff,
+        constraint_constants: &ConstraintConstants,
+    ) -> Result<diff::Diff, TransactionPoolErrors> {
         let well_formedness_errors: HashSet<_> = diff
             .list
             .iter()
-            .flat_map(|cmd| match cmd.check_well_formedness() {
-                Ok(()) => Vec::new(),
-                Err(errors) => errors,
-            })
+            .flat_map(
+                |cmd| match cmd.check_well_formedness(constraint_constants) {
+                    Ok(()) => Vec::new(),
+                    Err(errors) => errors,
+                },
+            )
             .collect();
 
         if !well_formedness_errors.is_empty() {
@@ -2210,13 +2745,33 @@ impl TransactionPool {
         }
     }
 
-    fn get_rebroadcastable<F>(&mut self, has_timed_out: F) -> Vec<Vec<UserCommand>>
+    /// Locally generated commands, with the time they were added to the pool
+    /// and whether they were included in the best chain.
+    pub fn locally_generated(
+        &self,
+    ) -> impl Iterator<Item = (&ValidCommandWithHash, redux::Timestamp, bool)> {
+        let uncommitted = self
+            .locally_generated_uncommitted
+            .iter()
+            .map(|(cmd, (time, _batch))| (cmd, *time, false));
+        let committed = self
+            .locally_generated_committed
+            .iter()
+            .map(|(cmd, (time, _batch))| (cmd, *time, true));
+        uncommitted.chain(committed)
+    }
+
+    /// Forgets the locally generated commands whose time `has_timed_out`, and
+    /// returns the remaining uncommitted ones to rebroadcast, by batch.
+    pub fn get_rebroadcastable<F>(&mut self, has_timed_out: F) -> Vec<Vec<UserCommand>>
     where
         F: Fn(&redux::Timestamp) -> bool,
     {
         let log = |has_timed_out: bool, s: &str, cmd: &ValidCommandWithHash| -> bool {
             if has_timed_out {
-                eprintln!("{}: {:?}", s, cmd);
+                mina_core::debug!(
+                    mina_core::log::system_time();
+                    kind = "transaction pool", message = s, hash = cmd.hash.to_string());
                 false
             } else {
                 true
@@ -2275,6 +2830,139 @@ impl TransactionPool {
     }
 }
 
+/// Accounts of the zkApp account updates with account preconditions.
+fn precondition_accounts(cmd: &valid::UserCommand) -> Vec<AccountId> {
+    let valid::UserCommand::ZkAppCommand(cmd) = cmd else {
+        return Vec::new();
+    };
+    cmd.zkapp_command
+        .account_updates
+        .fold(Vec::new(), |mut acc, account_update: &AccountUpdate| {
+            let precondition = &account_update.body.preconditions.account.0;
+            if matches!(precondition.nonce, OrIgnore::Check(_))
+                || matches!(precondition.is_new, OrIgnore::Check(true))
+            {
+                acc.push(account_update.account_id());
+            }
+            acc
+        })
+}
+
+/// Finds an account precondition of a zkApp command that can't be satisfied
+/// anymore, whatever gets applied before the command. The fee payer is
+/// checked separately by the pool.
+///
+/// Only preconditions that are monotonic on the ledger are checked: nonces
+/// never decrease and accounts are never removed. Balance, delegate, state
+/// and other preconditions might still be satisfied by commands pending in
+/// the pool before this one, so they are left to the transaction
+/// application.
+///
+/// Commands are checked when they are added to the pool, and again on new
+/// best tips, see [`TransactionPool::remove_unsatisfied_preconditions`].
+/// Accounts missing from `accounts` are not checked.
+fn stale_account_precondition(
+    cmd: &valid::UserCommand,
+    accounts: &BTreeMap<AccountId, Account>,
+) -> Option<diff::AccountPreconditionMismatch> {
+    let valid::UserCommand::ZkAppCommand(cmd) = cmd else {
+        return None;
+    };
+    cmd.zkapp_command
+        .account_updates
+        .fold(None, |found, account_update| {
+            found.or_else(|| {
+                let account = accounts.get(&account_update.account_id())?;
+                account_precondition_mismatch(&account_update.body.preconditions.account.0, account)
+            })
+        })
+}
+
+fn account_precondition_mismatch(
+    precondition: &zkapp_command::Account,
+    account: &Account,
+) -> Option<diff::AccountPreconditionMismatch> {
+    let mismatch = |field: &str, required: String, actual: String| {
+        Some(diff::AccountPreconditionMismatch {
+            public_key: account.public_key.into_address(),
+            field: field.to_owned(),
+            required,
+            actual,
+        })
+    };
+
+    if let OrIgnore::Check(ClosedInterval { lower, upper }) = &precondition.nonce {
+        if account.nonce > *upper {
+            return mismatch(
+                "nonce",
+                format!("{}..{}", lower.as_u32(), upper.as_u32()),
+                account.nonce.as_u32().to_string(),
+            );
+        }
+    }
+    // Accounts are never removed, so an account in the ledger can't be new.
+    if let OrIgnore::Check(true) = precondition.is_new {
+        return mismatch("is_new", true.to_string(), false.to_string());
+    }
+    None
+}
+
+/// Finds a permission that a signed command needs besides the ones of its
+/// fee payer, and that its account doesn't grant to the command: payments
+/// need the receiver to accept receiving without authorization, delegations
+/// need the delegator to accept setting its delegate with a signature.
+/// Such commands would be included in blocks as failed, and still charge
+/// their fee.
+///
+/// The fee payers of all commands, zkApp ones included, are checked
+/// separately. Accounts missing from `accounts` are created by the command
+/// with the default permissions, which grant everything.
+fn permission_denied(
+    cmd: &valid::UserCommand,
+    accounts: &BTreeMap<AccountId, Account>,
+) -> Option<diff::PermissionDenied> {
+    let valid::UserCommand::SignedCommand(cmd) = cmd else {
+        return None;
+    };
+    let (account, control, permissions) = match &cmd.payload.body {
+        signed_command::Body::Payment(_) => {
+            let receiver = accounts.get(&cmd.receiver())?;
+            let permissions = &receiver.permissions;
+            (
+                receiver,
+                ControlTag::NoneGiven,
+                [
+                    ("access", permissions.access),
+                    ("receive", permissions.receive),
+                ],
+            )
+        }
+        signed_command::Body::StakeDelegation(_) => {
+            let delegator = accounts.get(&cmd.fee_payer())?;
+            let permissions = &delegator.permissions;
+            (
+                delegator,
+                ControlTag::Signature,
+                [
+                    ("access", permissions.access),
+                    ("set_delegate", permissions.set_delegate),
+                ],
+            )
+        }
+    };
+    permissions
+        .into_iter()
+        // `Both` only exists in legacy ledgers, and is never satisfied.
+        .find(|(_, required)| {
+            *required == AuthRequired::Both || !check_permission(*required, control)
+        })
+        .map(|(permission, required)| diff::PermissionDenied {
+            public_key: account.public_key.into_address(),
+            permission: permission.to_owned(),
+            required: required.to_string(),
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2315,4 +3003,20 @@ mod tests {
 
         dbg!(merged);
     }
+
+    #[test]
+    fn test_account_precondition_rejection_message() {
+        let mismatch = diff::AccountPreconditionMismatch {
+            public_key: "B62qpk".to_owned(),
+            field: "nonce".to_owned(),
+            required: "250..250".to_owned(),
+            actual: "252".to_owned(),
+        };
+        let error = diff::Error::AccountPreconditionUnsatisfied { mismatch };
+        assert!(!error.grounds_for_diff_rejection());
+        assert_eq!(
+            error.to_string(),
+            "AccountPreconditionUnsatisfied: nonce precondition 250..250 but account nonce is 252 (B62qpk)"
+        );
+    }
 }
@@ -110,8 +110,7 @@ impl HashesMatrix {
             return *hash;
         };
 
-        // If `depth` is out of bound, see `HASH_EMPTIES`
-        let hash = HASH_EMPTIES.lock().unwrap()[height];
+        let hash = empty_hash(height);
         self.empty_hashes[height] = Some(hash);
 
         hash
@@ -154,9 +153,122 @@ impl HashesMatrix {
     }
 }
 
+/// Empty hashes by height, precomputed for the depth of the built-in ledgers
+/// and extended on demand for deeper ones.
 static HASH_EMPTIES: Lazy<Mutex<Vec<Fp>>> = Lazy::new(|| {
-    /// This value needs to be changed when the tree's height change
     const RANGE_HEIGHT: std::ops::Range<usize> = 0..36;
 
     Mutex::new((RANGE_HEIGHT).map(V2::empty_hash_at_height).collect())
 });
+
+fn empty_hash(height: usize) -> Fp {
+    let mut empties = HASH_EMPTIES.lock().unwrap();
+    // Deeper than the built-in ledgers
+    while empties.len() <= height {
+        let below = *empties.last().unwrap();
+        let below_height = empties.len() - 1;
+        empties.push(V2::hash_node(below_height, below, below));
+    }
+    empties[height]
+}
+
+/// Hashes the accounts in parallel, by index. When an address is set more
+/// than once, the last account wins, as in `set_batch`.
+pub(crate) fn hash_accounts(list: &[(Address, Box<crate::Account>)]) -> BTreeMap<u64, Fp> {
+    use rayon::prelude::*;
+
+    let accounts: BTreeMap<u64, &crate::Account> = list
+        .iter()
+        .map(|(addr, account)| (addr.to_index().0, &**account))
+        .collect();
+
+    accounts
+        .into_par_iter()
+        .map(|(index, account)| (index, account.hash()))
+        .collect()
+}
+
+/// Computes the hashes of the nodes above `leaves` (account index to account
+/// hash), level by level, so that each of them is hashed only once, however
+/// many of the leaves are below it. The nodes of a level are hashed in
+/// parallel.
+///
+/// `get_hash` returns the hash of a node which isn't above any of the leaves.
+/// Returns the hashes of the leaves and of the nodes above them, the last one
+/// being the root.
+pub(crate) fn hash_dirty_nodes<F>(
+    ledger_depth: usize,
+    leaves: BTreeMap<u64, Fp>,
+    mut get_hash: F,
+) -> Vec<(Address, Fp)>
+where
+    F: FnMut(Address) -> Fp,
+{
+    use rayon::prelude::*;
+
+    let mut hashes: Vec<(Address, Fp)> = leaves
+        .iter()
+        .map(|(index, hash)| {
+            (
+                Address::from_index(AccountIndex(*index), ledger_depth),
+                *hash,
+            )
+        })
+        .collect();
+
+    let mut level = leaves;
+
+    for height in 0..ledger_depth {
+        let length = ledger_depth - height;
+        let parents: Vec<u64> = {
+            let mut parents: Vec<u64> = level.keys().map(|index| index / 2).collect();
+            parents.dedup();
+            parents
+        };
+
+        let mut child_hash = |index: u64| match level.get(&index) {
+            Some(hash) => *hash,
+            None => get_hash(Address::from_index(AccountIndex(index), length)),
+        };
+
+        let children: Vec<(u64, Fp, Fp)> = parents
+            .into_iter()
+            .map(|parent| (parent, child_hash(parent * 2), child_hash(parent * 2 + 1)))
+            .collect();
+
+        level = children
+            .into_par_iter()
+            .map(|(parent, left, right)| (parent, V2::hash_node(height, left, right)))
+            .collect();
+
+        hashes.extend(
+            level.iter().map(|(index, hash)| {
+                (Address::from_index(AccountIndex(*index), length - 1), *hash)
+            }),
+        );
+    }
+
+    hashes
+}
+
+/// Computes all the hashes of a tree holding `accounts` and nothing else,
+/// hashing the accounts and then each level of the tree in parallel.
+/// Subtrees without accounts get the empty hash of their height, without
+/// hashing them.
+///
+/// Returns the hashes of the accounts and of the nodes above them, the last
+/// one being the root.
+pub fn recompute_tree_parallel(
+    ledger_depth: usize,
+    accounts: &[(Address, Box<crate::Account>)],
+) -> Vec<(Address, Fp)> {
+    if accounts.is_empty() {
+        let root = Address::root();
+        return vec![(root, empty_hash(ledger_depth))];
+    }
+
+    let leaves = hash_accounts(accounts);
+    hash_dirty_nodes(ledger_depth, leaves, |addr| {
+        empty_hash(ledger_depth - addr.length())
+    })
+}
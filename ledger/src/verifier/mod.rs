@@ -1,4 +1,7 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    any::Any,
+    sync::{Arc, Mutex},
+};
 
 use crate::{
     proofs::{
@@ -26,6 +29,7 @@ use mina_p2p_messages::v2::{
 use mina_signer::CompressedPubKey;
 use once_cell::sync::Lazy;
 use poly_commitment::{ipa::SRS, SRS as _};
+use rayon::prelude::*;
 
 // TODO: Move this into `Verifier` struct above
 pub static VERIFIER_INDEX: Lazy<Arc<VerifierIndex<Fq>>> = Lazy::new(|| {
@@ -34,26 +38,26 @@ pub static VERIFIER_INDEX: Lazy<Arc<VerifierIndex<Fq>>> = Lazy::new(|| {
         .into()
 });
 
-/// Returns the SRS on the other curve (immutable version for verifiers)
+/// Returns the SRS on the other curve
+///
+/// The SRS is created once per process, and shared by all the threads, the
+/// verifiers and the provers alike.
 pub fn get_srs<F: FieldWitness>() -> Arc<SRS<F::OtherCurve>> {
-    cache! {
-        Arc<SRS<F::OtherCurve>>,
-        {
-            let srs = SRS::<F::OtherCurve>::create(<F as proofs::field::FieldWitness>::Scalar::SRS_DEPTH);
-            Arc::new(srs)
-        }
-    }
-}
+    type Srs<F> = Arc<SRS<<F as FieldWitness>::OtherCurve>>;
 
-/// Returns the SRS on the other curve (Mutex-wrapped version for prover)
-pub fn get_srs_mut<F: FieldWitness>() -> Arc<Mutex<SRS<F::OtherCurve>>> {
-    cache! {
-        Arc<Mutex<SRS<F::OtherCurve>>>,
-        {
-            let srs = SRS::<F::OtherCurve>::create(<F as proofs::field::FieldWitness>::Scalar::SRS_DEPTH);
-            Arc::new(Mutex::new(srs))
-        }
+    static SRS_CACHE: Lazy<Mutex<Vec<Box<dyn Any + Send + Sync>>>> =
+        Lazy::new(|| Mutex::new(Vec::with_capacity(2)));
+
+    // Held during the creation, so that an SRS is never created twice
+    let mut cache = SRS_CACHE.lock().unwrap();
+    if let Some(srs) = cache.iter().find_map(|srs| srs.downcast_ref::<Srs<F>>()) {
+        return srs.clone();
     }
+    let srs: Srs<F> = Arc::new(SRS::<F::OtherCurve>::create(
+        <F as proofs::field::FieldWitness>::Scalar::SRS_DEPTH,
+    ));
+    cache.push(Box::new(srs.clone()));
+    srs
 }
 
 /// <https://github.com/MinaProtocol/mina/blob/bfd1009abdbee78979ff0343cc73a3480e862f58/src/lib/transaction_snark/transaction_snark.ml#L3492>
@@ -173,7 +177,21 @@ impl Verifier {
         cmds: Vec<WithStatus<verifiable::UserCommand>>,
         skip_verification: Option<SkipVerification>,
     ) -> Vec<VerifyCommandsResult> {
-        let cs: Vec<_> = cmds.into_iter().map(common::check).collect();
+        self.verify_commands_in(cmds, skip_verification, None)
+    }
+
+    /// Same as [`Self::verify_commands`], with the signatures checked and
+    /// the zkApp proofs verified in parallel on `pool`, if any.
+    pub fn verify_commands_in(
+        &self,
+        cmds: Vec<WithStatus<verifiable::UserCommand>>,
+        skip_verification: Option<SkipVerification>,
+        pool: Option<&rayon::ThreadPool>,
+    ) -> Vec<VerifyCommandsResult> {
+        let cs: Vec<_> = match pool {
+            Some(pool) => pool.install(|| cmds.into_par_iter().map(common::check).collect()),
+            None => cmds.into_iter().map(common::check).collect(),
+        };
 
         let mut to_verify = cs
             .iter()
@@ -188,11 +206,25 @@ impl Verifier {
             true
         } else {
             let srs = get_srs::<Fp>();
-
-            to_verify.all(|(vk, zkapp_statement, proof)| {
+            let verify = |(vk, zkapp_statement, proof): &(
+                VerificationKey,
+                ZkappStatement,
+                Arc<PicklesProofProofsVerifiedMaxStableV2>,
+            )| {
                 let proof: PicklesProofProofsVerified2ReprStableV2 = (&**proof).into();
                 verification::verify_zkapp(vk, zkapp_statement, &proof, &srs)
-            })
+            };
+
+            match pool {
+                // Stops at the first invalid proof, like the sequential
+                // verification, though the proofs already being verified
+                // finish first.
+                Some(pool) => {
+                    let to_verify = to_verify.collect::<Vec<_>>();
+                    pool.install(|| to_verify.into_par_iter().all(verify))
+                }
+                None => to_verify.all(verify),
+            }
         };
 
         cs.into_iter()
@@ -922,48 +922,25 @@ mod ledger {
     }
 }
 
-impl LedgerInterface for Mask {
+/// Ledger backend of the transaction logic outside of the snark.
+///
+/// Implementing [`LedgerIntf`] and this trait is enough to apply transactions
+/// with [`apply_transactions`] and the other functions of the transaction
+/// logic. See [`LedgerIntf`] for what the transaction logic expects from the
+/// ledger, and `ledger/tests/test_custom_ledger.rs` for an example backend.
+///
+/// [`apply_transactions`]: crate::scan_state::transaction_logic::transaction_partially_applied::apply_transactions
+pub trait LedgerNonSnarkBackend: LedgerIntf + Clone {}
+
+impl LedgerNonSnarkBackend for Mask {}
+impl LedgerNonSnarkBackend for SparseLedger {}
+
+impl<L: LedgerNonSnarkBackend> LedgerInterface for L {
     type W = ();
     type AccountUpdate = AccountUpdate;
     type Account = Account;
     type Bool = bool;
-    type InclusionProof = ExistingOrNew<<Mask as LedgerIntf>::Location>;
-
-    fn empty(depth: usize) -> Self {
-        <Self as LedgerIntf>::empty(depth)
-    }
-    fn get_account(
-        &self,
-        account_update: &Self::AccountUpdate,
-        _w: &mut Self::W,
-    ) -> Result<(Self::Account, Self::InclusionProof), String> {
-        ledger::get_account(self, account_update)
-    }
-    fn set_account(
-        &mut self,
-        account: (Self::Account, Self::InclusionProof),
-        _w: &mut Self::W,
-    ) -> Result<(), String> {
-        ledger::set_account(self, account)
-    }
-    fn check_inclusion(&self, _account: &(Self::Account, Self::InclusionProof), _w: &mut Self::W) {
-        // Nothing
-    }
-    fn check_account(
-        public_key: &CompressedPubKey,
-        token_id: &TokenId,
-        account: (&Self::Account, &Self::InclusionProof),
-        _w: &mut Self::W,
-    ) -> Result<Self::Bool, String> {
-        ledger::check_account::<Self>(public_key, token_id, account)
-    }
-}
-impl LedgerInterface for SparseLedger {
-    type W = ();
-    type AccountUpdate = AccountUpdate;
-    type Account = Account;
-    type Bool = bool;
-    type InclusionProof = ExistingOrNew<<Mask as LedgerIntf>::Location>;
+    type InclusionProof = ExistingOrNew<<L as LedgerIntf>::Location>;
 
     fn empty(depth: usize) -> Self {
         <Self as LedgerIntf>::empty(depth)
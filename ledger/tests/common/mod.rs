@@ -0,0 +1,185 @@
+//! Fixtures shared by the transaction pool tests.
+
+// Each test crate only uses some of them.
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+
+use mina_core::consensus::ConsensusConstants;
+use mina_p2p_messages::v2;
+use mina_signer::{CompressedPubKey, Signature};
+use mina_tree::{
+    scan_state::{
+        currency::{Amount, Balance, Fee, Nonce, Slot},
+        transaction_logic::{
+            signed_command::{Body, PaymentPayload, SignedCommand, SignedCommandPayload},
+            valid,
+            zkapp_command::{
+                self, AccountUpdate, CallForest, FeePayer, FeePayerBody, ZkAppCommand,
+            },
+            Memo,
+        },
+    },
+    transaction_pool::{
+        diff::DiffVerified, transaction_hash::hash_command, ApplyResult, Config, TransactionPool,
+        DEFAULT_REPLACE_FEE,
+    },
+    Account, AccountId, TokenId,
+};
+
+pub const FEE: u64 = 10_000_000;
+pub const AMOUNT: u64 = 1_000_000_000;
+
+pub fn consensus_constants() -> ConsensusConstants {
+    let protocol_constants = v2::MinaBaseProtocolConstantsCheckedValueStableV1 {
+        k: 290.into(),
+        slots_per_epoch: 7140.into(),
+        slots_per_sub_window: 7.into(),
+        grace_period_slots: 2160.into(),
+        delta: 0.into(),
+        genesis_state_timestamp: v2::BlockTimeTimeStableV1(
+            v2::UnsignedExtendedUInt64Int64ForVersionTagsStableV1(0u64.into()),
+        ),
+    };
+    ConsensusConstants::create(
+        mina_core::constants::constraint_constants(),
+        &protocol_constants,
+    )
+}
+
+/// Config of the pool returned by [`pool`].
+pub fn config() -> Config {
+    Config {
+        trust_system: (),
+        pool_max_size: 3000,
+        slot_tx_end: None,
+        max_lifetime: None,
+        zkapp_relay: Default::default(),
+        replace_fee: DEFAULT_REPLACE_FEE,
+    }
+}
+
+pub fn pool() -> TransactionPool {
+    pool_with(config())
+}
+
+pub fn pool_with(config: Config) -> TransactionPool {
+    TransactionPool::new(config, &consensus_constants())
+}
+
+pub fn account(pk: &CompressedPubKey) -> (AccountId, Account) {
+    let id = AccountId::new(pk.clone(), TokenId::default());
+    let account = Account::create_with(id.clone(), Balance::from_u64(1_000_000_000_000));
+    (id, account)
+}
+
+pub fn signed_command(
+    sender: &CompressedPubKey,
+    nonce: u32,
+    fee: u64,
+    valid_until: Option<u32>,
+    body: Body,
+) -> valid::UserCommand {
+    let payload = SignedCommandPayload::create(
+        Fee::from_u64(fee),
+        sender.clone(),
+        Nonce::from_u32(nonce),
+        valid_until.map(Slot::from_u32),
+        Memo::empty(),
+        body,
+    );
+    valid::UserCommand::SignedCommand(Box::new(SignedCommand {
+        payload,
+        signer: sender.clone(),
+        signature: Signature::dummy(),
+    }))
+}
+
+pub fn payment_body(receiver: &CompressedPubKey) -> Body {
+    Body::Payment(PaymentPayload {
+        receiver_pk: receiver.clone(),
+        amount: Amount::from_u64(AMOUNT),
+    })
+}
+
+/// Payment of [`AMOUNT`], paying [`FEE`] and valid forever.
+pub fn payment(
+    sender: &CompressedPubKey,
+    receiver: &CompressedPubKey,
+    nonce: u32,
+) -> valid::UserCommand {
+    signed_command(sender, nonce, FEE, None, payment_body(receiver))
+}
+
+/// zkApp command paying [`FEE`], with a dummy signature.
+pub fn zkapp_command(
+    fee_payer: &CompressedPubKey,
+    nonce: u32,
+    account_updates: CallForest<AccountUpdate>,
+) -> valid::UserCommand {
+    let zkapp_command = ZkAppCommand {
+        fee_payer: FeePayer {
+            body: FeePayerBody {
+                public_key: fee_payer.clone(),
+                fee: Fee::from_u64(FEE),
+                valid_until: None,
+                nonce: Nonce::from_u32(nonce),
+            },
+            authorization: Signature::dummy(),
+        },
+        account_updates,
+        memo: Memo::empty(),
+    };
+    valid::UserCommand::ZkAppCommand(Box::new(zkapp_command::valid::ZkAppCommand {
+        zkapp_command,
+    }))
+}
+
+/// Applies `commands` to the pool as received from peers at `time`, in
+/// the genesis slot.
+pub fn apply_at(
+    pool: &mut TransactionPool,
+    time: redux::Timestamp,
+    commands: Vec<valid::UserCommand>,
+    accounts: &BTreeMap<AccountId, Account>,
+) -> ApplyResult {
+    let list = commands.into_iter().map(hash_command).collect();
+    pool.unsafe_apply(
+        time,
+        Slot::zero(),
+        Slot::zero(),
+        &DiffVerified { list },
+        accounts,
+        false,
+    )
+    .unwrap()
+}
+
+pub fn apply(
+    pool: &mut TransactionPool,
+    commands: Vec<valid::UserCommand>,
+    accounts: &BTreeMap<AccountId, Account>,
+) -> ApplyResult {
+    apply_at(pool, redux::Timestamp::ZERO, commands, accounts)
+}
+
+/// Same as [`apply_at`], all the commands must be accepted.
+pub fn add_at(
+    pool: &mut TransactionPool,
+    time: redux::Timestamp,
+    commands: Vec<valid::UserCommand>,
+    accounts: &BTreeMap<AccountId, Account>,
+) {
+    let count = commands.len();
+    let (_, accepted, rejected, _, _) = apply_at(pool, time, commands, accounts);
+    assert!(rejected.is_empty(), "{rejected:?}");
+    assert_eq!(accepted.len(), count);
+}
+
+pub fn add(
+    pool: &mut TransactionPool,
+    commands: Vec<valid::UserCommand>,
+    accounts: &BTreeMap<AccountId, Account>,
+) {
+    add_at(pool, redux::Timestamp::ZERO, commands, accounts)
+}
@@ -0,0 +1,287 @@
+//! Tests the JSON representation of commands against the one of the OCaml
+//! node (`Signed_command.to_yojson`, `Zkapp_command.to_yojson`), used by the
+//! GraphQL API and by tools submitting transactions
+//!
+//! Run with: cargo test --test test_command_json
+//!
+//! The zkApp commands are binprot fixtures produced by the OCaml node, in
+//! `tests/files/zkapps`. The signed commands in `tests/files/commands` are
+//! payments included in a Berkeley testnet block (see
+//! `p2p/tests/files/rpc`), in the layout of `User_command.to_yojson`. Their
+//! signature is checked, so a field decoded differently than the OCaml node
+//! signed it fails the test.
+//!
+//! The goldens in `tests/files/commands/ocaml` are the same commands as
+//! serialized by the OCaml node itself, written by `generate.sh` there.
+
+use mina_p2p_messages::{
+    binprot::{BinProtRead, BinProtWrite},
+    v2::{MinaBaseZkappCommandTStableV1WireStableV1, PicklesProofProofsVerifiedMaxStableV2},
+};
+use mina_tree::scan_state::{
+    currency::{Amount, Fee, Magnitude, Nonce, Slot},
+    transaction_logic::{
+        signed_command::{self, SignedCommand},
+        verifiable::check_only_for_signature,
+        zkapp_command::{Control, ZkAppCommand},
+        Memo, UserCommand,
+    },
+};
+
+fn fixture_path(dir: &str, name: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/files")
+        .join(dir)
+        .join(name)
+}
+
+fn read_fixture(dir: &str, name: &str) -> Vec<u8> {
+    let path = fixture_path(dir, name);
+    std::fs::read(&path).unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()))
+}
+
+fn read_zkapp_command(name: &str) -> (Vec<u8>, ZkAppCommand) {
+    let bytes = read_fixture("zkapps", name);
+    let wire =
+        MinaBaseZkappCommandTStableV1WireStableV1::binprot_read(&mut bytes.as_slice()).unwrap();
+    (bytes, ZkAppCommand::try_from(&wire).unwrap())
+}
+
+/// Position of `"key":` in `json`, panics if missing
+fn key_position(json: &str, key: &str) -> usize {
+    json.find(&format!("\"{key}\":"))
+        .unwrap_or_else(|| panic!("missing key {key:?} in {json}"))
+}
+
+fn assert_key_order(json: &str, keys: &[&str]) {
+    let positions: Vec<_> = keys.iter().map(|key| key_position(json, key)).collect();
+    assert!(
+        positions.windows(2).all(|w| w[0] < w[1]),
+        "keys not in order {keys:?}: {json}"
+    );
+}
+
+/// Binprot -> `ZkAppCommand` -> JSON -> `ZkAppCommand` -> binprot must give
+/// back the bytes produced by the OCaml node
+fn check_zkapp_command_roundtrip(name: &str) -> (ZkAppCommand, String) {
+    let (bytes, cmd) = read_zkapp_command(name);
+
+    let json = serde_json::to_string(&cmd).unwrap();
+    let decoded: ZkAppCommand = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, cmd, "{name}");
+    assert_eq!(serde_json::to_string(&decoded).unwrap(), json, "{name}");
+
+    let mut encoded = Vec::new();
+    MinaBaseZkappCommandTStableV1WireStableV1::from(&decoded)
+        .binprot_write(&mut encoded)
+        .unwrap();
+    assert_eq!(encoded, bytes, "{name}");
+
+    assert_key_order(&json, &["fee_payer", "account_updates", "memo"]);
+    assert_key_order(
+        &json,
+        &[
+            "body",
+            "public_key",
+            "fee",
+            "valid_until",
+            "nonce",
+            "authorization",
+        ],
+    );
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let fee_payer = &value["fee_payer"];
+    assert!(fee_payer["body"]["public_key"]
+        .as_str()
+        .unwrap()
+        .starts_with("B62"));
+    assert!(fee_payer["authorization"]
+        .as_str()
+        .unwrap()
+        .starts_with("7mX"));
+    assert!(value["memo"].as_str().unwrap().starts_with("E4"));
+
+    (cmd, json)
+}
+
+#[test]
+fn test_valid_zkapp_json_roundtrip() {
+    check_zkapp_command_roundtrip("valid_zkapp.bin");
+}
+
+#[test]
+fn test_signature_auth_zkapp_json_roundtrip() {
+    check_zkapp_command_roundtrip("with_sig_auth.bin");
+}
+
+#[test]
+fn test_proof_auth_zkapp_json_roundtrip() {
+    let (cmd, json) = check_zkapp_command_roundtrip("with_proof_auth.bin");
+
+    let has_proof = cmd
+        .all_account_updates_list()
+        .iter()
+        .any(|update| matches!(update.authorization, Control::Proof(_)));
+    assert!(has_proof);
+
+    // Proofs are the URL-safe base64 of their s-expression, like OCaml's
+    // `Proof.to_base64`
+    let proofs: Vec<_> = json
+        .split(r#"["Proof",""#)
+        .skip(1)
+        .map(|rest| &rest[..rest.find('"').unwrap()])
+        .collect();
+    assert!(!proofs.is_empty(), "{json}");
+    for proof in proofs {
+        assert!(proof
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '=')));
+    }
+}
+
+#[test]
+fn test_user_command_json_roundtrip() {
+    let (_, cmd) = read_zkapp_command("with_sig_auth.bin");
+    let cmd = UserCommand::ZkAppCommand(Box::new(cmd));
+
+    let json = serde_json::to_string(&cmd).unwrap();
+    assert!(
+        json.starts_with(r#"["Zkapp_command",{"fee_payer":"#),
+        "{json}"
+    );
+
+    let decoded: UserCommand = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, cmd);
+}
+
+#[test]
+fn test_ocaml_proof_string() {
+    // Proof as sent by the OCaml node in GraphQL responses
+    let proof = include_str!("../../tests/files/zkapps/proof_string.txt").trim();
+    let json = serde_json::to_string(proof).unwrap();
+
+    let decoded: PicklesProofProofsVerifiedMaxStableV2 = serde_json::from_str(&json).unwrap();
+    let encoded = serde_json::to_string(&decoded).unwrap();
+    let decoded_again: PicklesProofProofsVerifiedMaxStableV2 =
+        serde_json::from_str(&encoded).unwrap();
+    assert_eq!(decoded_again, decoded);
+}
+
+/// JSON -> `UserCommand` -> JSON must give back the fixture, and the
+/// signature must verify against the decoded payload
+fn check_signed_command_roundtrip(name: &str) -> SignedCommand {
+    let bytes = read_fixture("commands", name);
+    let json = std::str::from_utf8(&bytes).unwrap().trim_end();
+
+    let cmd: UserCommand = serde_json::from_str(json).unwrap();
+    assert_eq!(serde_json::to_string(&cmd).unwrap(), json, "{name}");
+
+    let UserCommand::SignedCommand(cmd) = cmd else {
+        panic!("{name}: not a signed command");
+    };
+    assert_key_order(
+        json,
+        &[
+            "fee",
+            "fee_payer_pk",
+            "nonce",
+            "valid_until",
+            "memo",
+            "body",
+        ],
+    );
+    assert_key_order(json, &["payload", "signer", "signature"]);
+
+    let value: serde_json::Value = serde_json::from_str(json).unwrap();
+    let signed_command: SignedCommand = serde_json::from_value(value[1].clone()).unwrap();
+    assert_eq!(signed_command, *cmd, "{name}");
+
+    check_only_for_signature(cmd.clone()).unwrap_or_else(|_| panic!("{name}: invalid signature"));
+    *cmd
+}
+
+#[test]
+fn test_signed_command_json_roundtrip() {
+    let cmd = check_signed_command_roundtrip("payment.json");
+
+    let common = &cmd.payload.common;
+    assert_eq!(common.fee, Fee::from_u64(10_100_000));
+    assert_eq!(common.nonce, Nonce::from_u32(49));
+    assert_eq!(common.valid_until, Slot::max());
+    assert_eq!(common.memo, Memo::empty());
+    assert_eq!(common.fee_payer_pk, cmd.signer);
+    match &cmd.payload.body {
+        signed_command::Body::Payment(payment) => {
+            assert_eq!(payment.amount, Amount::from_u64(1_000_000_000));
+        }
+        body => panic!("unexpected body {body:?}"),
+    }
+}
+
+#[test]
+fn test_signed_command_with_memo_json_roundtrip() {
+    let cmd = check_signed_command_roundtrip("payment_with_memo.json");
+
+    let common = &cmd.payload.common;
+    assert_eq!(common.fee, Fee::from_u64(94_475_217));
+    assert_eq!(common.nonce, Nonce::from_u32(122_581));
+    assert_ne!(common.memo, Memo::empty());
+    match &cmd.payload.body {
+        signed_command::Body::Payment(payment) => {
+            assert_eq!(payment.amount, Amount::from_u64(1_500_000));
+        }
+        body => panic!("unexpected body {body:?}"),
+    }
+}
+
+#[test]
+fn test_tampered_signed_command_is_rejected() {
+    let bytes = read_fixture("commands", "payment.json");
+    let json = std::str::from_utf8(&bytes).unwrap();
+    // 0.0101 -> 0.0102 MINA
+    let tampered = json.replace(r#""fee":"0.0101""#, r#""fee":"0.0102""#);
+    assert_ne!(tampered, json);
+
+    let UserCommand::SignedCommand(cmd) = serde_json::from_str(&tampered).unwrap() else {
+        panic!("not a signed command");
+    };
+    assert!(check_only_for_signature(cmd).is_err());
+}
+
+/// JSON written by the OCaml node must decode to the same command, which must
+/// serialize back to the same JSON. zkApp goldens must also match their
+/// binprot fixture, and signed ones must have a valid signature.
+#[test]
+#[ignore = "no golden recorded from an OCaml node yet"]
+fn test_ocaml_json_goldens() {
+    let dir = fixture_path("commands", "ocaml");
+    let mut goldens = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    goldens.sort();
+    assert!(!goldens.is_empty(), "no goldens in {}", dir.display());
+
+    for path in goldens {
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let golden: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+
+        let cmd: UserCommand = serde_json::from_value(golden.clone())
+            .unwrap_or_else(|e| panic!("{name}: failed to decode: {e}"));
+        assert_eq!(serde_json::to_value(&cmd).unwrap(), golden, "{name}");
+
+        match cmd {
+            UserCommand::ZkAppCommand(cmd) => {
+                let (_, expected) = read_zkapp_command(&format!("{name}.bin"));
+                assert_eq!(*cmd, expected, "{name}");
+            }
+            UserCommand::SignedCommand(cmd) => {
+                check_only_for_signature(cmd)
+                    .unwrap_or_else(|_| panic!("{name}: invalid signature"));
+            }
+        }
+    }
+}
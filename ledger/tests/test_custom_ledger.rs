@@ -0,0 +1,439 @@
+//! Tests applying transactions to a ledger backend defined outside of the
+//! crate
+//!
+//! Run with: cargo test --test test_custom_ledger
+//!
+//! `MemoryLedger` is a minimal ledger keeping its accounts in a vector, as a
+//! rollup embedding the transaction logic could. The same transactions are
+//! applied to it and to a `Mask`, and both must end up with the same
+//! accounts and the same merkle root.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use ark_ff::Zero;
+use mina_core::constants::ConstraintConstants;
+use mina_curves::pasta::Fp;
+use mina_signer::{CompressedPubKey, Signature};
+use mina_tree::{
+    scan_state::{
+        currency::{Amount, Balance, Fee, Length, Magnitude, Nonce, Sgn, Signed, Slot},
+        transaction_logic::{
+            protocol_state::{EpochData, EpochLedger, ProtocolStateView},
+            signed_command::{self, PaymentPayload, SignedCommand, SignedCommandPayload},
+            transaction_partially_applied::{apply_transactions, AccountState},
+            zkapp_command::{
+                self, AccountPreconditions, AccountUpdate, Actions, AuthorizationKind, CallForest,
+                Control, Events, FeePayer, FeePayerBody, MayUseToken, Numeric, Preconditions, Tree,
+                Update, WithStackHash, ZkAppCommand, ZkAppPreconditions,
+            },
+            Memo, Transaction, TransactionStatus, UserCommand,
+        },
+    },
+    sparse_ledger::LedgerIntf,
+    zkapps::non_snark::LedgerNonSnarkBackend,
+    Account, AccountId, BaseLedger, Database, Mask, MutableFp, TokenId, TreeVersion, V2,
+};
+
+const DEPTH: usize = 15;
+
+const ALICE: &str = "B62qmnY6m4c6bdgSPnQGZriSaj9vuSjsfh6qkveGTsFX3yGA5ywRaja";
+const BOB: &str = "B62qjVQLxt9nYMWGn45mkgwYfcz8e8jvjNCBo11VKJb7vxDNwv5QLPS";
+const CAROL: &str = "B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV";
+
+#[derive(Clone)]
+struct Inner {
+    depth: usize,
+    accounts: Vec<Account>,
+    locations: HashMap<AccountId, usize>,
+}
+
+/// Accounts are stored in insertion order, like in `Mask`, so both ledgers
+/// have the same merkle root.
+///
+/// Clones share the same accounts, as required by [`LedgerIntf`].
+#[derive(Clone)]
+struct MemoryLedger {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl MemoryLedger {
+    fn with<F, R>(&self, fun: F) -> R
+    where
+        F: FnOnce(&mut Inner) -> R,
+    {
+        let mut inner = self.inner.lock().unwrap();
+        fun(&mut inner)
+    }
+
+    fn add(&mut self, account: Account) {
+        self.create_new_account(account.id(), account).unwrap();
+    }
+
+    fn accounts(&self) -> Vec<Account> {
+        self.with(|this| this.accounts.clone())
+    }
+}
+
+impl LedgerIntf for MemoryLedger {
+    type Location = usize;
+
+    fn get(&self, addr: &Self::Location) -> Option<Box<Account>> {
+        self.with(|this| this.accounts.get(*addr).cloned().map(Box::new))
+    }
+
+    fn location_of_account(&self, account_id: &AccountId) -> Option<Self::Location> {
+        self.with(|this| this.locations.get(account_id).copied())
+    }
+
+    fn set(&mut self, addr: &Self::Location, account: Box<Account>) {
+        self.with(|this| {
+            let slot = this.accounts.get_mut(*addr).expect("invalid location");
+            *slot = *account;
+        })
+    }
+
+    fn get_or_create(
+        &mut self,
+        account_id: &AccountId,
+    ) -> Result<(AccountState, Box<Account>, Self::Location), String> {
+        if let Some(addr) = self.location_of_account(account_id) {
+            let account = self.get(&addr).unwrap();
+            return Ok((AccountState::Existed, account, addr));
+        }
+        let account = Account::initialize(account_id);
+        self.create_new_account(account_id.clone(), account.clone())
+            .map_err(|_| "failed to create account".to_string())?;
+        let addr = self.location_of_account(account_id).unwrap();
+        Ok((AccountState::Added, Box::new(account), addr))
+    }
+
+    fn create_new_account(&mut self, account_id: AccountId, account: Account) -> Result<(), ()> {
+        self.with(|this| {
+            if this.locations.contains_key(&account_id) {
+                return Err(());
+            }
+            this.locations.insert(account_id, this.accounts.len());
+            this.accounts.push(account);
+            Ok(())
+        })
+    }
+
+    fn merkle_root(&mut self) -> Fp {
+        self.with(|this| {
+            if this.accounts.is_empty() {
+                return V2::empty_hash_at_height(this.depth);
+            }
+            let mut hashes: Vec<Fp> = this.accounts.iter().map(Account::hash).collect();
+            for height in 0..this.depth {
+                hashes = hashes
+                    .chunks(2)
+                    .map(|pair| match pair {
+                        [left, right] => V2::hash_node(height, *left, *right),
+                        [left] => V2::hash_node(height, *left, V2::empty_hash_at_height(height)),
+                        _ => unreachable!(),
+                    })
+                    .collect();
+            }
+            hashes[0]
+        })
+    }
+
+    fn empty(depth: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                depth,
+                accounts: Vec::new(),
+                locations: HashMap::new(),
+            })),
+        }
+    }
+
+    fn create_masked(&self) -> Self {
+        let inner = self.with(|this| this.clone());
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+        }
+    }
+
+    fn apply_mask(&mut self, mask: Self) {
+        let mask_inner = mask.with(|this| this.clone());
+        self.with(|this| *this = mask_inner);
+    }
+
+    fn account_locations(&self) -> Vec<Self::Location> {
+        self.with(|this| (0..this.accounts.len()).collect())
+    }
+}
+
+impl LedgerNonSnarkBackend for MemoryLedger {}
+
+fn dummy_epoch_data() -> EpochData<Fp> {
+    EpochData {
+        ledger: EpochLedger {
+            hash: Fp::zero(),
+            total_currency: Amount::zero(),
+        },
+        seed: Fp::zero(),
+        start_checkpoint: Fp::zero(),
+        lock_checkpoint: Fp::zero(),
+        epoch_length: Length::from_u32(0),
+    }
+}
+
+fn test_constraint_constants() -> ConstraintConstants {
+    ConstraintConstants {
+        sub_windows_per_window: 11,
+        ledger_depth: DEPTH as u64,
+        work_delay: 2,
+        block_window_duration_ms: 180_000,
+        transaction_capacity_log_2: 7,
+        pending_coinbase_depth: 5,
+        coinbase_amount: 720_000_000_000,
+        supercharged_coinbase_factor: 2,
+        account_creation_fee: 1_000_000_000,
+        minimum_user_command_fee: 1_000_000,
+        fork: None,
+    }
+}
+
+fn state_view() -> ProtocolStateView {
+    ProtocolStateView {
+        snarked_ledger_hash: Fp::zero(),
+        blockchain_length: Length::from_u32(0),
+        min_window_density: Length::from_u32(0),
+        total_currency: Amount::zero(),
+        global_slot_since_genesis: Slot::from_u32(0),
+        staking_epoch_data: dummy_epoch_data(),
+        next_epoch_data: dummy_epoch_data(),
+    }
+}
+
+fn pk(address: &str) -> CompressedPubKey {
+    mina_signer::PubKey::from_address(address)
+        .unwrap()
+        .into_compressed()
+}
+
+fn account_id(address: &str) -> AccountId {
+    AccountId::new(pk(address), TokenId::default())
+}
+
+fn initial_accounts() -> Vec<Account> {
+    vec![
+        Account::create_with(account_id(ALICE), Balance::from_u64(10_000_000_000)),
+        Account::create_with(account_id(BOB), Balance::from_u64(500_000_000)),
+    ]
+}
+
+/// Creates a `Mask` and a `MemoryLedger` with the same accounts
+fn create_ledgers() -> (Mask, MemoryLedger) {
+    let mut mask = Mask::new_root(Database::create(DEPTH as u8));
+    let mut memory = <MemoryLedger as LedgerIntf>::empty(DEPTH);
+
+    for account in initial_accounts() {
+        BaseLedger::get_or_create_account(&mut mask, account.id(), account.clone()).unwrap();
+        memory.add(account);
+    }
+
+    (mask, memory)
+}
+
+fn create_payment(from: &str, to: &str, amount: u64, fee: u64, nonce: u32) -> Transaction {
+    let payload = SignedCommandPayload {
+        common: signed_command::Common {
+            fee: Fee::from_u64(fee),
+            fee_payer_pk: pk(from),
+            nonce: Nonce::from_u32(nonce),
+            valid_until: Slot::max(),
+            memo: Memo::empty(),
+        },
+        body: signed_command::Body::Payment(PaymentPayload {
+            receiver_pk: pk(to),
+            amount: Amount::from_u64(amount),
+        }),
+    };
+
+    Transaction::Command(UserCommand::SignedCommand(Box::new(SignedCommand {
+        payload,
+        signer: pk(from),
+        signature: Signature::dummy(),
+    })))
+}
+
+/// zkApp command with a single account update, without balance change
+fn create_zkapp_command(fee_payer: &str, account: &str, fee: u64, nonce: u32) -> Transaction {
+    let fee_payer = FeePayer {
+        body: FeePayerBody {
+            public_key: pk(fee_payer),
+            fee: Fee::from_u64(fee),
+            valid_until: Some(Slot::max()),
+            nonce: Nonce::from_u32(nonce),
+        },
+        authorization: Signature::dummy(),
+    };
+
+    let account_update = AccountUpdate {
+        body: zkapp_command::Body {
+            public_key: pk(account),
+            token_id: TokenId::default(),
+            update: Update::noop(),
+            balance_change: Signed {
+                magnitude: Amount::zero(),
+                sgn: Sgn::Pos,
+            },
+            increment_nonce: false,
+            events: Events::empty(),
+            actions: Actions::empty(),
+            call_data: Fp::zero(),
+            preconditions: Preconditions {
+                network: ZkAppPreconditions::accept(),
+                account: AccountPreconditions(zkapp_command::Account::accept()),
+                valid_while: Numeric::Ignore,
+            },
+            use_full_commitment: false,
+            implicit_account_creation_fee: false,
+            may_use_token: MayUseToken::No,
+            authorization_kind: AuthorizationKind::NoneGiven,
+        },
+        authorization: Control::NoneGiven,
+    };
+
+    let call_forest = CallForest(vec![WithStackHash {
+        elt: Tree {
+            account_update,
+            account_update_digest: MutableFp::new(Fp::zero()),
+            calls: CallForest::new(),
+        },
+        stack_hash: MutableFp::new(Fp::zero()),
+    }]);
+    call_forest.ensure_hashed();
+
+    Transaction::Command(UserCommand::ZkAppCommand(Box::new(ZkAppCommand {
+        fee_payer,
+        account_updates: call_forest,
+        memo: Memo::empty(),
+    })))
+}
+
+/// Applies the transactions to both ledgers, checks that they end up in the
+/// same state and returns the statuses of the transactions.
+fn apply_to_both(txns: &[Transaction]) -> (MemoryLedger, Vec<TransactionStatus>) {
+    let (mut mask, mut memory) = create_ledgers();
+    let constraint_constants = test_constraint_constants();
+    let state_view = state_view();
+
+    assert_eq!(
+        BaseLedger::merkle_root(&mut mask),
+        LedgerIntf::merkle_root(&mut memory),
+        "initial roots differ"
+    );
+
+    let mask_applied = apply_transactions(
+        &constraint_constants,
+        Slot::from_u32(0),
+        &state_view,
+        &mut mask,
+        txns,
+    )
+    .unwrap();
+    let memory_applied = apply_transactions(
+        &constraint_constants,
+        Slot::from_u32(0),
+        &state_view,
+        &mut memory,
+        txns,
+    )
+    .unwrap();
+
+    let mask_statuses: Vec<_> = mask_applied
+        .iter()
+        .map(|applied| applied.transaction_status().clone())
+        .collect();
+    let memory_statuses: Vec<_> = memory_applied
+        .iter()
+        .map(|applied| applied.transaction_status().clone())
+        .collect();
+    assert_eq!(mask_statuses, memory_statuses);
+
+    for account in memory.accounts() {
+        let addr = BaseLedger::location_of_account(&mask, &account.id()).unwrap();
+        assert_eq!(*BaseLedger::get(&mask, addr).unwrap(), account);
+    }
+    assert_eq!(BaseLedger::num_accounts(&mask), memory.accounts().len());
+    assert_eq!(
+        BaseLedger::merkle_root(&mut mask),
+        LedgerIntf::merkle_root(&mut memory),
+        "final roots differ"
+    );
+
+    (memory, memory_statuses)
+}
+
+fn balance(ledger: &MemoryLedger, address: &str) -> Balance {
+    let addr = ledger.location_of_account(&account_id(address)).unwrap();
+    ledger.get(&addr).unwrap().balance
+}
+
+#[test]
+fn test_clones_share_accounts() {
+    let (_, memory) = create_ledgers();
+    let mut clone = memory.clone();
+    let masked = memory.create_masked();
+
+    clone.add(Account::create_with(account_id(CAROL), Balance::zero()));
+
+    assert!(memory.location_of_account(&account_id(CAROL)).is_some());
+    assert!(masked.location_of_account(&account_id(CAROL)).is_none());
+}
+
+#[test]
+fn test_payment() {
+    let (ledger, statuses) = apply_to_both(&[create_payment(ALICE, BOB, 1_000_000, 10_000_000, 0)]);
+
+    assert_eq!(statuses, vec![TransactionStatus::Applied]);
+    assert_eq!(balance(&ledger, ALICE), Balance::from_u64(9_989_000_000));
+    assert_eq!(balance(&ledger, BOB), Balance::from_u64(501_000_000));
+}
+
+#[test]
+fn test_payment_creates_receiver() {
+    let (ledger, statuses) =
+        apply_to_both(&[create_payment(ALICE, CAROL, 2_000_000_000, 10_000_000, 0)]);
+
+    assert_eq!(statuses, vec![TransactionStatus::Applied]);
+    // The account creation fee is taken from the amount received
+    assert_eq!(balance(&ledger, CAROL), Balance::from_u64(1_000_000_000));
+}
+
+#[test]
+fn test_payment_insufficient_balance() {
+    let (ledger, statuses) =
+        apply_to_both(&[create_payment(BOB, ALICE, 1_000_000_000, 10_000_000, 0)]);
+
+    assert!(matches!(statuses[..], [TransactionStatus::Failed(_)]));
+    // Only the fee is charged
+    assert_eq!(balance(&ledger, BOB), Balance::from_u64(490_000_000));
+}
+
+#[test]
+fn test_sequence_of_transactions() {
+    let (_, statuses) = apply_to_both(&[
+        create_payment(ALICE, BOB, 1_000_000, 10_000_000, 0),
+        create_zkapp_command(ALICE, BOB, 10_000_000, 1),
+        create_payment(BOB, CAROL, 100_000_000, 10_000_000, 0),
+        create_zkapp_command(BOB, ALICE, 10_000_000, 1),
+    ]);
+
+    assert_eq!(statuses.len(), 4);
+}
+
+#[test]
+fn test_zkapp_command() {
+    let (ledger, statuses) = apply_to_both(&[create_zkapp_command(ALICE, BOB, 10_000_000, 0)]);
+
+    assert_eq!(statuses, vec![TransactionStatus::Applied]);
+    assert_eq!(balance(&ledger, ALICE), Balance::from_u64(9_990_000_000));
+}
@@ -0,0 +1,334 @@
+//! Checks the hashing of the ledger against test vectors of the OCaml node:
+//! accounts, verification keys of zkApp accounts, token ids and nodes of the
+//! Merkle tree
+//!
+//! Run with: cargo test --test test_hashing_vectors
+//!
+//! The vectors are in `tests/files/hashing/ledger_hashes.json`. Accounts are
+//! referred to by name and built in `account_by_name`, hashes are hex encoded
+//! field elements (`to_hex`) or base58check strings, as printed by the OCaml
+//! node. A regression in any of these hashes makes the node compute ledger
+//! hashes that OCaml nodes reject.
+//!
+//! Derived token ids were computed by `Token_id.derive` of the OCaml
+//! implementation, as compiled to JavaScript in o1js. Merkle paths, with the
+//! zkApp accounts at their leaves, come from the sparse ledger witnesses of a
+//! Berkeley block (see
+//! `p2p/tests/files/rpc/staged_ledger_aux_and_pending_coinbases_at_block_response.json`):
+//! every parent hash was computed by the OCaml node. Verification key hashes
+//! of these accounts are the ones that authorized their applied zkApp
+//! commands.
+
+use mina_curves::pasta::Fp;
+use mina_p2p_messages::{
+    binprot::BinProtRead,
+    v2::{LedgerHash, MinaBaseAccountBinableArgStableV2, TokenIdKeyHash},
+};
+use mina_signer::CompressedPubKey;
+use mina_tree::{
+    scan_state::currency::{Balance, Nonce, TxnVersion},
+    Account, AccountId, AuthRequired, Permissions, ReceiptChainHash, SetVerificationKey, Timing,
+    TokenId, TokenSymbol, TreeVersion, VerificationKey, VotingFor, V2,
+};
+use o1_utils::FieldHelpers;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Vectors {
+    accounts: Vec<AccountVector>,
+    verification_keys: Vec<HashVector>,
+    token_ids: Vec<TokenIdVector>,
+    derived_token_ids: Vec<DerivedTokenIdVector>,
+    empty_merkle_nodes: Vec<EmptyMerkleNodeVector>,
+    merkle_paths: Vec<MerklePathVector>,
+}
+
+#[derive(Deserialize)]
+struct AccountVector {
+    name: String,
+    /// Hash of the account, hex encoded
+    hash: Option<String>,
+    /// Hash of the account, as a base58check `LedgerHash`
+    ledger_hash: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HashVector {
+    name: String,
+    hash: String,
+}
+
+#[derive(Deserialize)]
+struct TokenIdVector {
+    name: String,
+    field: String,
+    token_id: String,
+}
+
+/// `Account_id.derive_token_id` of the account of `owner` for `parent`
+#[derive(Deserialize)]
+struct DerivedTokenIdVector {
+    owner: String,
+    parent: String,
+    token_id: String,
+}
+
+#[derive(Deserialize)]
+struct EmptyMerkleNodeVector {
+    height: usize,
+    hash: String,
+}
+
+/// Path from an account of a ledger of depth `ledger_depth` up to its root
+#[derive(Deserialize)]
+struct MerklePathVector {
+    public_key: String,
+    /// Binprot encoded `MinaBaseAccountBinableArgStableV2`, hex encoded
+    account: String,
+    /// Hash of the verification key of the zkApp account, hex encoded
+    verification_key_hash: Option<String>,
+    /// Index of the account in the ledger
+    index: u64,
+    /// Nodes from the leaf up, `path[height]` hashes children of `height`
+    path: Vec<MerklePathNode>,
+}
+
+#[derive(Deserialize)]
+struct MerklePathNode {
+    /// Hash of the sibling, as a base58check `LedgerHash`
+    sibling: String,
+    /// Hash of the parent, as a base58check `LedgerHash`
+    parent: String,
+}
+
+fn vectors() -> Vectors {
+    let path = format!(
+        "{}/../tests/files/hashing/ledger_hashes.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let file = std::fs::read(&path).unwrap_or_else(|e| panic!("failed to read {path}: {e}"));
+    serde_json::from_slice(&file).unwrap_or_else(|e| panic!("failed to parse {path}: {e}"))
+}
+
+fn pk(address: &str) -> CompressedPubKey {
+    CompressedPubKey::from_address(address).unwrap()
+}
+
+fn token_id(b58: &str) -> TokenId {
+    b58.parse::<TokenIdKeyHash>().unwrap().into()
+}
+
+fn account_by_name(name: &str) -> Account {
+    match name {
+        "empty" => Account::empty(),
+        "create" => Account::create(),
+        "token_symbol" => Account {
+            public_key: pk("B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV"),
+            token_id: TokenId::default(),
+            token_symbol: TokenSymbol::from("seb".to_string().into_bytes()),
+            balance: Balance::from_u64(10101),
+            nonce: Nonce::from_u32(62772),
+            receipt_chain_hash: ReceiptChainHash::empty(),
+            delegate: None,
+            voting_for: VotingFor::dummy(),
+            timing: Timing::Untimed,
+            permissions: Permissions::user_default(),
+            zkapp: None,
+        },
+        "genesis_winner" => {
+            let public_key = pk("B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg");
+            Account {
+                public_key: public_key.clone(),
+                token_id: TokenId::default(),
+                token_symbol: TokenSymbol::default(),
+                balance: Balance::from_u64(20000001000),
+                nonce: Nonce::from_u32(0),
+                receipt_chain_hash: ReceiptChainHash::parse_str(
+                    "2mzbV7WevxLuchs2dAMY4vQBS6XttnCUF8Hvks4XNBQ5qiSGGBQe",
+                )
+                .unwrap(),
+                delegate: Some(public_key),
+                voting_for: VotingFor::parse_str(
+                    "3NK2tkzqqK5spR2sZ7tujjqPksL45M3UUrcA4WhCkeiPtnugyE2x",
+                )
+                .unwrap(),
+                timing: Timing::Untimed,
+                permissions: Permissions {
+                    edit_state: AuthRequired::Signature,
+                    access: AuthRequired::None,
+                    send: AuthRequired::Signature,
+                    receive: AuthRequired::None,
+                    set_delegate: AuthRequired::Signature,
+                    set_permissions: AuthRequired::Signature,
+                    set_verification_key: SetVerificationKey {
+                        auth: AuthRequired::Signature,
+                        txn_version: TxnVersion::from_u32(2),
+                    },
+                    set_zkapp_uri: AuthRequired::Signature,
+                    edit_action_state: AuthRequired::Signature,
+                    set_token_symbol: AuthRequired::Signature,
+                    increment_nonce: AuthRequired::Signature,
+                    set_voting_for: AuthRequired::Signature,
+                    set_timing: AuthRequired::Signature,
+                },
+                zkapp: None,
+            }
+        }
+        name => panic!("unknown account {name:?} in the test vectors"),
+    }
+}
+
+fn verification_key_by_name(name: &str) -> VerificationKey {
+    match name {
+        "dummy" => VerificationKey::dummy(),
+        name => panic!("unknown verification key {name:?} in the test vectors"),
+    }
+}
+
+fn account_of_binprot(hex: &str) -> Account {
+    let bytes = hex::decode(hex).unwrap();
+    let account = MinaBaseAccountBinableArgStableV2::binprot_read(&mut bytes.as_slice()).unwrap();
+    (&account).try_into().unwrap()
+}
+
+fn ledger_depth() -> usize {
+    mina_core::constants::constraint_constants().ledger_depth as usize
+}
+
+#[test]
+fn test_account_hashes() {
+    let vectors = vectors();
+    assert!(!vectors.accounts.is_empty());
+
+    for vector in vectors.accounts {
+        let hash = account_by_name(&vector.name).hash();
+        assert!(
+            vector.hash.is_some() || vector.ledger_hash.is_some(),
+            "no hash for account {:?}",
+            vector.name
+        );
+        if let Some(expected) = &vector.hash {
+            assert_eq!(&hash.to_hex(), expected, "account {:?}", vector.name);
+        }
+        if let Some(expected) = &vector.ledger_hash {
+            assert_eq!(
+                &LedgerHash::from_fp(hash).to_string(),
+                expected,
+                "account {:?}",
+                vector.name
+            );
+        }
+    }
+}
+
+#[test]
+fn test_zkapp_verification_key_hashes() {
+    let vectors = vectors();
+    assert!(!vectors.verification_keys.is_empty());
+
+    for vector in vectors.verification_keys {
+        let vk = verification_key_by_name(&vector.name);
+        assert_eq!(vk.hash().to_hex(), vector.hash, "vk {:?}", vector.name);
+    }
+}
+
+#[test]
+fn test_token_ids() {
+    let vectors = vectors();
+    assert!(!vectors.token_ids.is_empty());
+
+    for vector in vectors.token_ids {
+        let field = Fp::from_hex(&vector.field).unwrap();
+        assert_eq!(token_id(&vector.token_id).0, field, "{:?}", vector.name);
+        assert_eq!(
+            TokenIdKeyHash::from(TokenId(field)).to_string(),
+            vector.token_id,
+            "{:?}",
+            vector.name
+        );
+    }
+
+    assert!(!vectors.derived_token_ids.is_empty());
+    for vector in vectors.derived_token_ids {
+        let owner = AccountId::new(pk(&vector.owner), token_id(&vector.parent));
+        assert_eq!(
+            TokenIdKeyHash::from(owner.derive_token_id()).to_string(),
+            vector.token_id,
+            "owner {} parent {}",
+            vector.owner,
+            vector.parent
+        );
+    }
+}
+
+#[test]
+fn test_empty_merkle_nodes() {
+    let vectors = vectors();
+    assert!(!vectors.empty_merkle_nodes.is_empty());
+
+    for vector in &vectors.empty_merkle_nodes {
+        assert_eq!(
+            V2::empty_hash_at_height(vector.height).to_hex(),
+            vector.hash,
+            "height {}",
+            vector.height
+        );
+    }
+
+    // Every other height derives from the vectors, one node at a time
+    let mut empty = Account::empty().hash();
+    for height in 0..ledger_depth() {
+        assert_eq!(V2::empty_hash_at_height(height), empty, "height {height}");
+        empty = V2::hash_node(height, empty, empty);
+    }
+    assert_eq!(V2::empty_hash_at_height(ledger_depth()), empty);
+}
+
+/// Hashes of zkApp and regular accounts, and of every node up to the root
+/// of the ledger, against the nodes computed by the OCaml node
+#[test]
+fn test_merkle_paths() {
+    let vectors = vectors();
+    assert!(vectors
+        .merkle_paths
+        .iter()
+        .any(|vector| vector.verification_key_hash.is_some()));
+
+    for vector in vectors.merkle_paths {
+        let name = &vector.public_key;
+        let account = account_of_binprot(&vector.account);
+        assert_eq!(account.public_key, pk(name));
+
+        let vk = account
+            .zkapp
+            .as_ref()
+            .and_then(|zkapp| zkapp.verification_key.as_ref());
+        match (vk, &vector.verification_key_hash) {
+            (Some(vk), Some(expected)) => {
+                assert_eq!(&vk.vk().hash().to_hex(), expected, "vk of {name}")
+            }
+            (None, None) => {}
+            (vk, _) => panic!("unexpected verification key {:?} of {name}", vk.is_some()),
+        }
+
+        assert_eq!(vector.path.len(), ledger_depth(), "{name}");
+        let mut hash = account.hash();
+        for (height, node) in vector.path.iter().enumerate() {
+            let sibling = node
+                .sibling
+                .parse::<LedgerHash>()
+                .unwrap()
+                .to_field()
+                .unwrap();
+            hash = match (vector.index >> height) & 1 {
+                0 => V2::hash_node(height, hash, sibling),
+                _ => V2::hash_node(height, sibling, hash),
+            };
+            assert_eq!(
+                LedgerHash::from_fp(hash).to_string(),
+                node.parent,
+                "{name} at height {height}"
+            );
+        }
+    }
+}
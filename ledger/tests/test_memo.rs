@@ -0,0 +1,48 @@
+// Run this test with:
+// cargo test --package mina-tree --test test_memo
+
+#![cfg(not(target_family = "wasm"))]
+
+use std::{fmt::Write, str::FromStr};
+
+use mina_tree::scan_state::transaction_logic::Memo;
+use proptest::prelude::*;
+
+/// Strings which fit in a memo. At most 8 chars of at most 4 bytes each, or
+/// 32 ASCII chars.
+fn memo_string() -> impl Strategy<Value = String> {
+    prop_oneof!["[^\0]{0,8}", "[ -~]{0,32}"]
+}
+
+proptest! {
+    #[test]
+    fn roundtrip(s in memo_string()) {
+        let memo = Memo::from_str(&s).unwrap();
+        prop_assert_eq!(memo.to_string(), s);
+    }
+
+    #[test]
+    fn long_strings_truncated(s in any::<String>()) {
+        let memo = Memo::from_str(&s).unwrap();
+        let bytes = memo.as_slice();
+        let length = s.len().min(32);
+
+        prop_assert_eq!(bytes.len(), 34);
+        prop_assert_eq!(bytes[0], 1);
+        prop_assert_eq!(bytes[1] as usize, length);
+        prop_assert_eq!(&bytes[2..2 + length], &s.as_bytes()[..length]);
+        prop_assert!(bytes[2 + length..].iter().all(|b| *b == 0));
+    }
+
+    /// Memos of commands received from peers can have any content.
+    #[test]
+    fn arbitrary_bytes(bytes in any::<[u8; 34]>()) {
+        let memo = Memo(bytes);
+        let mut s = String::new();
+        let res = write!(s, "{memo}");
+        // Only byte memos can be displayed, digests can't
+        prop_assert_eq!(res.is_ok(), bytes[0] == 1);
+        prop_assert!(s.len() <= 32 * 3);
+        let _ = format!("{memo:?}");
+    }
+}
@@ -0,0 +1,38 @@
+// Run this test with:
+// cargo test --package mina-tree --test test_srs_sharing
+
+use std::sync::Arc;
+
+use mina_curves::pasta::Fp;
+use mina_tree::verifier::get_srs;
+
+/// Resident set size of the process, in bytes.
+#[cfg(target_os = "linux")]
+fn rss() -> usize {
+    let statm = std::fs::read_to_string("/proc/self/statm").unwrap();
+    let resident_pages: usize = statm.split_whitespace().nth(1).unwrap().parse().unwrap();
+    resident_pages * 4096
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_srs_is_shared_across_threads() {
+    let before = rss();
+    let srs = get_srs::<Fp>();
+    let srs_size = rss().saturating_sub(before);
+
+    // Verifier and prover services run on their own threads
+    let after_first = rss();
+    let handles = (0..4)
+        .map(|_| std::thread::spawn(get_srs::<Fp>))
+        .collect::<Vec<_>>();
+    for handle in handles {
+        assert!(Arc::ptr_eq(&srs, &handle.join().unwrap()));
+    }
+    let growth = rss().saturating_sub(after_first);
+
+    assert!(
+        growth < srs_size / 2,
+        "RSS grew by {growth} bytes when using the SRS from other threads, the SRS takes {srs_size} bytes"
+    );
+}
@@ -49,6 +49,7 @@ fn test_constraint_constants() -> ConstraintConstants {
         coinbase_amount: 720_000_000_000,
         supercharged_coinbase_factor: 2,
         account_creation_fee: 1_000_000_000,
+        minimum_user_command_fee: 1_000_000,
         fork: None,
     }
 }
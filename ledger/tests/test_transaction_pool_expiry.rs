@@ -0,0 +1,73 @@
+// Run this test with:
+// cargo test --package mina-tree --test test_transaction_pool_expiry
+
+use std::collections::BTreeMap;
+
+use mina_signer::CompressedPubKey;
+use mina_tree::{
+    scan_state::{currency::Slot, transaction_logic::valid},
+    transaction_pool::EvictionReason,
+    *,
+};
+
+mod common;
+use common::*;
+
+fn payment_until(
+    sender: &CompressedPubKey,
+    receiver: &CompressedPubKey,
+    nonce: u32,
+    valid_until: Option<u32>,
+) -> valid::UserCommand {
+    signed_command(sender, nonce, FEE, valid_until, payment_body(receiver))
+}
+
+#[test]
+fn test_expired_commands_are_evicted() {
+    let receiver = gen_compressed();
+    let (expiring_sender, other_sender) = (gen_compressed(), gen_compressed());
+    let accounts = [account(&expiring_sender), account(&other_sender)]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+
+    let mut pool = pool();
+    add(
+        &mut pool,
+        vec![
+            payment_until(&expiring_sender, &receiver, 0, Some(10)),
+            // Valid for longer, but can't be included without the first one.
+            payment(&expiring_sender, &receiver, 1),
+            payment_until(&other_sender, &receiver, 0, Some(20)),
+        ],
+        &accounts,
+    );
+    assert_eq!(pool.size(), 3);
+
+    // Still valid at its `valid_until` slot.
+    assert!(pool.remove_expired(Slot::from_u32(10)).unwrap().is_empty());
+    assert_eq!(pool.size(), 3);
+
+    let evicted = pool.remove_expired(Slot::from_u32(11)).unwrap();
+    assert_eq!(evicted.len(), 2);
+    assert!(evicted
+        .iter()
+        .all(|eviction| eviction.command.data.fee_payer().public_key == expiring_sender));
+    let expired = evicted
+        .iter()
+        .find(|eviction| {
+            eviction.reason
+                == EvictionReason::Expired {
+                    valid_until: Slot::from_u32(10),
+                }
+        })
+        .unwrap();
+    assert!(evicted.iter().any(|eviction| eviction.reason
+        == EvictionReason::PredecessorExpired {
+            predecessor: expired.command.hash.clone(),
+        }));
+    assert_eq!(pool.size(), 1);
+
+    let evicted = pool.remove_expired(Slot::from_u32(21)).unwrap();
+    assert_eq!(evicted.len(), 1);
+    assert_eq!(pool.size(), 0);
+}
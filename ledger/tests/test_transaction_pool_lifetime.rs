@@ -0,0 +1,80 @@
+// Run this test with:
+// cargo test --package mina-tree --test test_transaction_pool_lifetime
+
+use std::{collections::BTreeMap, time::Duration};
+
+use mina_tree::{transaction_pool::Config, *};
+
+mod common;
+use common::*;
+
+const MAX_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
+fn time(secs: u64) -> redux::Timestamp {
+    redux::Timestamp::ZERO + Duration::from_secs(secs)
+}
+
+#[test]
+fn test_stale_commands_are_removed() {
+    let receiver = gen_compressed();
+    let (old_sender, new_sender) = (gen_compressed(), gen_compressed());
+    let accounts = [account(&old_sender), account(&new_sender)]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+
+    let mut pool = pool_with(Config {
+        max_lifetime: Some(MAX_LIFETIME),
+        ..config()
+    });
+    add_at(
+        &mut pool,
+        time(0),
+        vec![payment(&old_sender, &receiver, 0)],
+        &accounts,
+    );
+    add_at(
+        &mut pool,
+        time(30 * 60),
+        vec![
+            payment(&new_sender, &receiver, 0),
+            // Depends on the first command, removed along with it.
+            payment(&old_sender, &receiver, 1),
+        ],
+        &accounts,
+    );
+    assert_eq!(pool.size(), 3);
+
+    assert!(pool.remove_stale(time(59 * 60)).unwrap().is_empty());
+    assert_eq!(pool.size(), 3);
+
+    let removed = pool.remove_stale(time(60 * 60)).unwrap();
+    assert_eq!(removed.len(), 2);
+    assert!(removed
+        .iter()
+        .all(|cmd| cmd.data.fee_payer().public_key == old_sender));
+    assert_eq!(pool.size(), 1);
+
+    let removed = pool.remove_stale(time(90 * 60)).unwrap();
+    assert_eq!(removed.len(), 1);
+    assert_eq!(pool.size(), 0);
+}
+
+#[test]
+fn test_unlimited_lifetime() {
+    let (sender, receiver) = (gen_compressed(), gen_compressed());
+    let accounts = [account(&sender)].into_iter().collect::<BTreeMap<_, _>>();
+
+    let mut pool = pool();
+    add_at(
+        &mut pool,
+        time(0),
+        vec![payment(&sender, &receiver, 0)],
+        &accounts,
+    );
+
+    assert!(pool
+        .remove_stale(time(365 * 24 * 60 * 60))
+        .unwrap()
+        .is_empty());
+    assert_eq!(pool.size(), 1);
+}
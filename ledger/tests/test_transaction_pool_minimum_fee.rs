@@ -0,0 +1,76 @@
+// Run this test with:
+// cargo test --package mina-tree --test test_transaction_pool_minimum_fee
+
+use mina_core::constants::ConstraintConstants;
+use mina_tree::{
+    scan_state::transaction_logic::WellFormednessError,
+    transaction_pool::{diff, TransactionError, TransactionPool, TransactionPoolErrors},
+    *,
+};
+
+mod common;
+use common::*;
+
+fn constants(minimum_user_command_fee: u64) -> ConstraintConstants {
+    ConstraintConstants {
+        minimum_user_command_fee,
+        ..mina_core::constants::constraint_constants().clone()
+    }
+}
+
+fn prevalidate(
+    pool: &TransactionPool,
+    fee: u64,
+    constants: &ConstraintConstants,
+) -> Result<diff::Diff, TransactionPoolErrors> {
+    let command = signed_command(
+        &gen_compressed(),
+        0,
+        fee,
+        None,
+        payment_body(&gen_compressed()),
+    );
+    let list = vec![command.forget_check()];
+    pool.prevalidate(diff::Diff { list }, constants)
+}
+
+fn is_insufficient_fee(result: Result<diff::Diff, TransactionPoolErrors>) -> bool {
+    match result {
+        Err(TransactionPoolErrors::BatchedErrors(errors)) => matches!(
+            errors[..],
+            [TransactionError::WellFormedness(
+                WellFormednessError::InsufficientFee
+            )]
+        ),
+        _ => false,
+    }
+}
+
+#[test]
+fn test_fee_below_minimum_is_rejected() {
+    let pool = pool();
+    let default_minimum = mina_core::constants::constraint_constants().minimum_user_command_fee;
+    assert_eq!(default_minimum, 1_000_000);
+
+    assert!(prevalidate(&pool, default_minimum, &constants(default_minimum)).is_ok());
+    assert!(is_insufficient_fee(prevalidate(
+        &pool,
+        default_minimum - 1,
+        &constants(default_minimum)
+    )));
+}
+
+#[test]
+fn test_configured_minimum_fee() {
+    let pool = pool();
+
+    // A network with a higher fee floor rejects the default minimum fee.
+    let higher = constants(2_000_000);
+    assert!(is_insufficient_fee(prevalidate(&pool, 1_000_000, &higher)));
+    assert!(prevalidate(&pool, 2_000_000, &higher).is_ok());
+
+    // And one with a lower floor accepts lower fees.
+    let lower = constants(1_000);
+    assert!(prevalidate(&pool, 1_000, &lower).is_ok());
+    assert!(is_insufficient_fee(prevalidate(&pool, 999, &lower)));
+}
@@ -0,0 +1,147 @@
+// Run this test with:
+// cargo test --package mina-tree --test test_transaction_pool_permissions
+
+use std::collections::BTreeMap;
+
+use mina_signer::CompressedPubKey;
+use mina_tree::{
+    scan_state::transaction_logic::{
+        signed_command::{Body, StakeDelegationPayload},
+        valid,
+        zkapp_command::CallForest,
+    },
+    transaction_pool::{
+        diff::{self, DiffVerified},
+        transaction_hash::hash_command,
+        ApplyResult, TransactionPool,
+    },
+    *,
+};
+
+mod common;
+use common::*;
+
+fn delegation(sender: &CompressedPubKey, delegate: &CompressedPubKey) -> valid::UserCommand {
+    let body = Body::StakeDelegation(StakeDelegationPayload::SetDelegate {
+        new_delegate: delegate.clone(),
+    });
+    signed_command(sender, 0, FEE, None, body)
+}
+
+/// Applies `command`, giving the pool the accounts it asks for when they
+/// exist.
+fn apply(
+    pool: &mut TransactionPool,
+    command: valid::UserCommand,
+    accounts: &BTreeMap<AccountId, Account>,
+) -> ApplyResult {
+    let diff = DiffVerified {
+        list: vec![hash_command(command.clone())],
+    };
+    let accounts = pool
+        .get_accounts_to_apply_diff(&diff)
+        .into_iter()
+        .filter_map(|id| Some((id.clone(), accounts.get(&id)?.clone())))
+        .collect();
+    common::apply(pool, vec![command], &accounts)
+}
+
+fn rejection(result: &ApplyResult) -> Option<&diff::Error> {
+    let (_, accepted, rejected, _, _) = result;
+    match &rejected[..] {
+        [] => {
+            assert_eq!(accepted.len(), 1);
+            None
+        }
+        [(_, error)] => {
+            assert!(accepted.is_empty());
+            Some(error)
+        }
+        rejected => panic!("unexpected rejections: {rejected:?}"),
+    }
+}
+
+#[test]
+fn test_payment_to_account_not_accepting_payments_is_rejected() {
+    let (sender, receiver) = (gen_compressed(), gen_compressed());
+    let (receiver_id, mut receiver_account) = account(&receiver);
+    receiver_account.permissions.receive = AuthRequired::Proof;
+    let accounts = [account(&sender), (receiver_id, receiver_account)]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+    let mut pool = pool();
+
+    let result = apply(&mut pool, payment(&sender, &receiver, 0), &accounts);
+    match rejection(&result) {
+        Some(diff::Error::AccountNotPermitted { denied }) => {
+            assert_eq!(denied.permission, "receive");
+            assert_eq!(denied.public_key, receiver.into_address());
+            assert_eq!(denied.required, AuthRequired::Proof.to_string());
+        }
+        error => panic!("unexpected rejection: {error:?}"),
+    }
+    assert_eq!(pool.size(), 0);
+}
+
+#[test]
+fn test_payment_to_new_or_default_account_is_accepted() {
+    let (sender, receiver) = (gen_compressed(), gen_compressed());
+    let mut accounts = [account(&sender)].into_iter().collect::<BTreeMap<_, _>>();
+
+    // The receiver is created by the payment.
+    let result = apply(&mut pool(), payment(&sender, &receiver, 0), &accounts);
+    assert!(rejection(&result).is_none());
+
+    accounts.extend([account(&receiver)]);
+    let result = apply(&mut pool(), payment(&sender, &receiver, 0), &accounts);
+    assert!(rejection(&result).is_none());
+}
+
+#[test]
+fn test_delegation_of_account_not_accepting_delegations_is_rejected() {
+    let (sender, delegate) = (gen_compressed(), gen_compressed());
+    let (sender_id, mut sender_account) = account(&sender);
+    let mut accounts = [
+        (sender_id.clone(), sender_account.clone()),
+        account(&delegate),
+    ]
+    .into_iter()
+    .collect::<BTreeMap<_, _>>();
+
+    let result = apply(&mut pool(), delegation(&sender, &delegate), &accounts);
+    assert!(rejection(&result).is_none());
+
+    sender_account.permissions.set_delegate = AuthRequired::Impossible;
+    accounts.insert(sender_id, sender_account);
+    let result = apply(&mut pool(), delegation(&sender, &delegate), &accounts);
+    match rejection(&result) {
+        Some(diff::Error::AccountNotPermitted { denied }) => {
+            assert_eq!(denied.permission, "set_delegate");
+        }
+        error => panic!("unexpected rejection: {error:?}"),
+    }
+}
+
+#[test]
+fn test_fee_payer_not_permitted_to_send_is_rejected() {
+    let fee_payer = gen_compressed();
+    let (fee_payer_id, mut fee_payer_account) = account(&fee_payer);
+    fee_payer_account.permissions.send = AuthRequired::Proof;
+    let accounts = [(fee_payer_id, fee_payer_account)]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+
+    for command in [
+        payment(&fee_payer, &gen_compressed(), 0),
+        delegation(&fee_payer, &gen_compressed()),
+        // Without account updates, only paying its fee.
+        zkapp_command(&fee_payer, 0, CallForest::new()),
+    ] {
+        let result = apply(&mut pool(), command, &accounts);
+        let error = rejection(&result);
+        assert!(
+            matches!(error, Some(diff::Error::FeePayerNotPermittedToSend)),
+            "{error:?}"
+        );
+    }
+}
@@ -0,0 +1,286 @@
+// Run this test with:
+// cargo test --package mina-tree --test test_transaction_pool_preconditions
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use ark_ff::Zero;
+use mina_curves::pasta::Fp;
+use mina_signer::CompressedPubKey;
+use mina_tree::{
+    scan_state::{
+        currency::{Amount, Balance, Magnitude, Nonce, Sgn, Signed, Slot},
+        transaction_logic::zkapp_command::{
+            self, AccountPreconditions, AccountUpdate, Actions, AuthorizationKind, Body,
+            CallForest, ClosedInterval, Control, Events, MayUseToken, Numeric, OrIgnore,
+            Preconditions, Tree, Update, WithStackHash, ZkAppPreconditions,
+        },
+    },
+    transaction_pool::{
+        diff::{self, BestTipDiff},
+        transaction_hash::hash_command,
+        ApplyResult, EvictionReason,
+    },
+    *,
+};
+
+mod common;
+use common::*;
+
+/// zkApp account with `state` as its first app state field.
+fn zkapp_account(
+    pk: &CompressedPubKey,
+    balance: u64,
+    nonce: u32,
+    state: u64,
+) -> (AccountId, Account) {
+    let (id, mut account) = account(pk);
+    account.balance = Balance::from_u64(balance);
+    account.nonce = Nonce::from_u32(nonce);
+    let mut zkapp = ZkAppAccount::default();
+    zkapp.app_state[0] = Fp::from(state);
+    account.zkapp = Some(Box::new(zkapp));
+    (id, account)
+}
+
+/// Precondition that the first app state field is `state`, that the
+/// balance is in `balance` and the nonce in `nonce`.
+fn precondition(balance: (u64, u64), nonce: (u32, u32), state: u64) -> zkapp_command::Account {
+    let mut precondition = zkapp_command::Account::accept();
+    precondition.balance = OrIgnore::Check(ClosedInterval {
+        lower: Balance::from_u64(balance.0),
+        upper: Balance::from_u64(balance.1),
+    });
+    precondition.nonce = OrIgnore::Check(ClosedInterval {
+        lower: Nonce::from_u32(nonce.0),
+        upper: Nonce::from_u32(nonce.1),
+    });
+    precondition.state[0] = OrIgnore::Check(Fp::from(state));
+    precondition
+}
+
+/// Account update of `zkapp` checking `precondition`.
+fn zkapp_update(
+    zkapp: &CompressedPubKey,
+    precondition: zkapp_command::Account,
+) -> CallForest<AccountUpdate> {
+    let account_update = AccountUpdate {
+        body: Body {
+            public_key: zkapp.clone(),
+            token_id: TokenId::default(),
+            update: Update::noop(),
+            balance_change: Signed {
+                magnitude: Amount::zero(),
+                sgn: Sgn::Pos,
+            },
+            increment_nonce: false,
+            events: Events::empty(),
+            actions: Actions::empty(),
+            call_data: Fp::zero(),
+            preconditions: Preconditions {
+                network: ZkAppPreconditions::accept(),
+                account: AccountPreconditions(precondition),
+                valid_while: Numeric::Ignore,
+            },
+            use_full_commitment: false,
+            implicit_account_creation_fee: false,
+            may_use_token: MayUseToken::No,
+            authorization_kind: AuthorizationKind::NoneGiven,
+        },
+        authorization: Control::NoneGiven,
+    };
+    let account_updates = CallForest(vec![WithStackHash {
+        elt: Tree {
+            account_update,
+            account_update_digest: MutableFp::new(Fp::zero()),
+            calls: CallForest::new(),
+        },
+        stack_hash: MutableFp::new(Fp::zero()),
+    }]);
+    account_updates.ensure_hashed();
+    account_updates
+}
+
+fn rejected_field(result: &ApplyResult) -> String {
+    let (_, accepted, rejected, _, _) = result;
+    assert!(accepted.is_empty());
+    match &rejected[..] {
+        [(_, diff::Error::AccountPreconditionUnsatisfied { mismatch })] => mismatch.field.clone(),
+        rejected => panic!("unexpected rejections: {rejected:?}"),
+    }
+}
+
+#[test]
+fn test_admission_rejects_nonce_preconditions_in_the_past() {
+    let (fee_payer, zkapp) = (gen_compressed(), gen_compressed());
+    let accounts = [account(&fee_payer), zkapp_account(&zkapp, 500, 5, 7)]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+    let mut pool = pool();
+
+    let result = apply(
+        &mut pool,
+        vec![zkapp_command(
+            &fee_payer,
+            0,
+            zkapp_update(&zkapp, precondition((0, 1000), (0, 4), 7)),
+        )],
+        &accounts,
+    );
+    assert_eq!(rejected_field(&result), "nonce");
+    let (_, _, rejected, _, _) = &result;
+    let error = rejected[0].1.to_string();
+    assert!(
+        error.contains("nonce precondition 0..4 but account nonce is 5"),
+        "{error}"
+    );
+    assert_eq!(pool.size(), 0);
+}
+
+#[test]
+fn test_admission_accepts_preconditions_pending_commands_can_satisfy() {
+    let (fee_payer, zkapp) = (gen_compressed(), gen_compressed());
+    let accounts = [account(&fee_payer), zkapp_account(&zkapp, 500, 0, 7)]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+    let mut pool = pool();
+
+    // Chained zkApp commands: the second one expects the state and balance
+    // the first one leaves, and a nonce still ahead of the ledger.
+    let (_, accepted, rejected, _, _) = apply(
+        &mut pool,
+        vec![
+            zkapp_command(
+                &fee_payer,
+                0,
+                zkapp_update(&zkapp, precondition((0, 1000), (0, 0), 7)),
+            ),
+            zkapp_command(
+                &fee_payer,
+                1,
+                zkapp_update(&zkapp, precondition((1000, 2000), (1, 1), 8)),
+            ),
+        ],
+        &accounts,
+    );
+    assert!(rejected.is_empty(), "{rejected:?}");
+    assert_eq!(accepted.len(), 2);
+    assert_eq!(pool.size(), 2);
+}
+
+#[test]
+fn test_new_best_tip_evicts_unsatisfiable_commands() {
+    let (fee_payer, zkapp) = (gen_compressed(), gen_compressed());
+    let (zkapp_id, zkapp_account_before) = zkapp_account(&zkapp, 500, 0, 7);
+    let mut accounts = [
+        account(&fee_payer),
+        (zkapp_id.clone(), zkapp_account_before),
+    ]
+    .into_iter()
+    .collect::<BTreeMap<_, _>>();
+    let mut pool = pool();
+
+    let (_, accepted, rejected, _, _) = apply(
+        &mut pool,
+        vec![
+            zkapp_command(
+                &fee_payer,
+                0,
+                zkapp_update(&zkapp, precondition((0, 1000), (0, 1), 7)),
+            ),
+            // Depends on the zkApp command, evicted along with it.
+            payment(&fee_payer, &gen_compressed(), 1),
+        ],
+        &accounts,
+    );
+    assert!(rejected.is_empty(), "{rejected:?}");
+    assert_eq!(accepted.len(), 2);
+    assert!(pool
+        .get_accounts_to_revalidate_on_new_best_tip()
+        .contains(&zkapp_id));
+
+    // The state changed, a command before it in the pool may change it
+    // back, nothing is evicted.
+    accounts.insert(zkapp_id.clone(), zkapp_account(&zkapp, 500, 1, 8).1);
+    let dropped = pool.on_new_best_tip(Slot::zero(), &accounts).unwrap();
+    assert!(dropped.is_empty());
+    assert_eq!(pool.size(), 2);
+
+    // The nonce of the zkApp went past the precondition.
+    accounts.insert(zkapp_id.clone(), zkapp_account(&zkapp, 500, 2, 7).1);
+    let dropped = pool.on_new_best_tip(Slot::zero(), &accounts).unwrap();
+    assert_eq!(dropped.len(), 2);
+    assert_eq!(pool.size(), 0);
+}
+
+#[test]
+fn test_block_evicts_unsatisfiable_commands() {
+    let (fee_payer, zkapp) = (gen_compressed(), gen_compressed());
+    let (zkapp_id, zkapp_account_before) = zkapp_account(&zkapp, 500, 0, 7);
+    let accounts = [
+        account(&fee_payer),
+        (zkapp_id.clone(), zkapp_account_before),
+    ]
+    .into_iter()
+    .collect::<BTreeMap<_, _>>();
+    let mut pool = pool();
+
+    let zkapp_cmd = zkapp_command(
+        &fee_payer,
+        0,
+        zkapp_update(&zkapp, precondition((0, 1000), (0, 0), 7)),
+    );
+    let (_, accepted, _, _, _) = apply(
+        &mut pool,
+        vec![zkapp_cmd.clone(), payment(&fee_payer, &gen_compressed(), 1)],
+        &accounts,
+    );
+    assert_eq!(accepted.len(), 2);
+
+    // A block included another command of the zkApp account, its nonce is
+    // now past the precondition. Its balance is out of the range too, which
+    // alone isn't a reason to evict.
+    let after_block = [zkapp_account(&zkapp, 2000, 1, 7)]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+    let diff = BestTipDiff {
+        new_commands: Vec::new(),
+        removed_commands: Vec::new(),
+        reorg_best_tip: false,
+    };
+    pool.handle_transition_frontier_diff(
+        Slot::zero(),
+        Slot::zero(),
+        &diff,
+        &BTreeSet::from([zkapp_id.clone()]),
+        &after_block,
+        &BTreeMap::new(),
+    )
+    .unwrap();
+    assert_eq!(pool.size(), 0);
+
+    // The eviction reasons, the payment is evicted for following the zkApp
+    // command.
+    let mut pool = common::pool();
+    apply(
+        &mut pool,
+        vec![zkapp_cmd.clone(), payment(&fee_payer, &gen_compressed(), 1)],
+        &accounts,
+    );
+    let evicted = pool.remove_unsatisfied_preconditions(&after_block).unwrap();
+    assert_eq!(evicted.len(), 2);
+    let zkapp_hash = hash_command(zkapp_cmd).hash;
+    for eviction in &evicted {
+        match &eviction.reason {
+            EvictionReason::AccountPreconditionUnsatisfied { mismatch } => {
+                assert_eq!(eviction.command.hash, zkapp_hash);
+                assert_eq!(mismatch.field, "nonce");
+                assert_eq!(mismatch.actual, "1");
+            }
+            EvictionReason::PredecessorPreconditionUnsatisfied { predecessor } => {
+                assert_eq!(predecessor, &zkapp_hash);
+                assert_ne!(eviction.command.hash, zkapp_hash);
+            }
+            reason => panic!("unexpected eviction reason: {reason}"),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+// Run this test with:
+// cargo test --package mina-tree --test test_transaction_pool_replace
+
+use std::collections::BTreeMap;
+
+use mina_signer::CompressedPubKey;
+use mina_tree::{
+    scan_state::{
+        currency::{Balance, Fee},
+        transaction_logic::valid,
+    },
+    transaction_pool::{diff, Config, TransactionPool},
+    *,
+};
+
+mod common;
+use common::*;
+
+const REPLACE_FEE: u64 = 1_000_000;
+
+fn payment_with_fee(
+    sender: &CompressedPubKey,
+    receiver: &CompressedPubKey,
+    nonce: u32,
+    fee: u64,
+) -> valid::UserCommand {
+    signed_command(sender, nonce, fee, None, payment_body(receiver))
+}
+
+fn pool() -> TransactionPool {
+    pool_with(Config {
+        replace_fee: Fee::from_u64(REPLACE_FEE),
+        ..config()
+    })
+}
+
+#[test]
+fn test_replace_requires_fee_increase() {
+    let (sender, receiver) = (gen_compressed(), gen_compressed());
+    let accounts = [account(&sender)].into_iter().collect::<BTreeMap<_, _>>();
+
+    let mut pool = pool();
+    let (_, accepted, _, _, _) = apply(
+        &mut pool,
+        vec![payment_with_fee(&sender, &receiver, 0, FEE)],
+        &accounts,
+    );
+    let original = accepted[0].hash.clone();
+
+    let low_fee = payment_with_fee(&sender, &receiver, 0, FEE + REPLACE_FEE - 1);
+    let (_, accepted, rejected, _, replacements) = apply(&mut pool, vec![low_fee], &accounts);
+    assert!(accepted.is_empty());
+    assert!(matches!(
+        rejected[..],
+        [(_, diff::Error::InsufficientReplaceFee)]
+    ));
+    assert!(replacements.is_empty());
+    assert_eq!(pool.size(), 1);
+
+    let high_fee = payment_with_fee(&sender, &receiver, 0, FEE + REPLACE_FEE);
+    let (_, accepted, rejected, dropped, replacements) =
+        apply(&mut pool, vec![high_fee], &accounts);
+    assert!(rejected.is_empty());
+    assert_eq!(accepted.len(), 1);
+    assert!(dropped.contains(&original));
+    assert_eq!(replacements.len(), 1);
+    assert_eq!(replacements[0].replaced.hash, original);
+    assert_eq!(replacements[0].replacement.hash, accepted[0].hash);
+    assert_eq!(replacements[0].dropped_dependents, 0);
+    assert_eq!(pool.size(), 1);
+}
+
+#[test]
+fn test_replace_pays_for_dropped_dependents() {
+    let (sender, receiver) = (gen_compressed(), gen_compressed());
+    // Enough for two payments, but not if the first one pays a higher fee.
+    let id = AccountId::new(sender.clone(), TokenId::default());
+    let account = Account::create_with(id.clone(), Balance::from_u64(2_025_000_000));
+    let accounts = [(id, account)].into_iter().collect::<BTreeMap<_, _>>();
+
+    let mut pool = pool();
+    apply(
+        &mut pool,
+        vec![
+            payment_with_fee(&sender, &receiver, 0, FEE),
+            payment_with_fee(&sender, &receiver, 1, FEE),
+        ],
+        &accounts,
+    );
+    assert_eq!(pool.size(), 2);
+
+    // The fee increase is entirely spent on the fee of the dropped payment.
+    let replacement = payment_with_fee(&sender, &receiver, 0, 2 * FEE);
+    let (_, _, rejected, _, _) = apply(&mut pool, vec![replacement], &accounts);
+    assert!(matches!(
+        rejected[..],
+        [(_, diff::Error::InsufficientReplaceFee)]
+    ));
+    assert_eq!(pool.size(), 2);
+
+    let replacement = payment_with_fee(&sender, &receiver, 0, 3 * FEE);
+    let (_, accepted, _, dropped, replacements) = apply(&mut pool, vec![replacement], &accounts);
+    assert_eq!(accepted.len(), 1);
+    assert_eq!(dropped.len(), 2);
+    assert_eq!(replacements.len(), 1);
+    assert_eq!(replacements[0].dropped_dependents, 1);
+    assert_eq!(pool.size(), 1);
+}
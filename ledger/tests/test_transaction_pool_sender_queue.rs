@@ -0,0 +1,128 @@
+// Run this test with:
+// cargo test --package mina-tree --test test_transaction_pool_sender_queue
+
+use std::collections::BTreeMap;
+
+use mina_signer::CompressedPubKey;
+use mina_tree::{
+    scan_state::{
+        currency::{Amount, Fee, Magnitude, Nonce, Slot},
+        transaction_logic::valid,
+    },
+    transaction_pool::{Config, SenderQueueCommandStatus, TransactionPool},
+    *,
+};
+
+mod common;
+use common::*;
+
+const SLOT_TX_END: u32 = 100;
+
+fn payment_until(
+    sender: &CompressedPubKey,
+    receiver: &CompressedPubKey,
+    nonce: u32,
+    valid_until: u32,
+) -> valid::UserCommand {
+    signed_command(
+        sender,
+        nonce,
+        FEE,
+        Some(valid_until),
+        payment_body(receiver),
+    )
+}
+
+fn statuses(
+    pool: &TransactionPool,
+    sender: &AccountId,
+    global_slot_since_genesis: u32,
+    current_global_slot: u32,
+) -> Vec<SenderQueueCommandStatus> {
+    pool.sender_queue(
+        sender,
+        Slot::from_u32(global_slot_since_genesis),
+        Slot::from_u32(current_global_slot),
+    )
+    .unwrap()
+    .commands
+    .into_iter()
+    .map(|command| command.status)
+    .collect()
+}
+
+#[test]
+fn test_sender_queue() {
+    let receiver = gen_compressed();
+    let (sender, other_sender) = (gen_compressed(), gen_compressed());
+    let accounts = [account(&sender), account(&other_sender)]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+    let sender_id = AccountId::new(sender.clone(), TokenId::default());
+
+    let mut pool = pool_with(Config {
+        slot_tx_end: Some(Slot::from_u32(SLOT_TX_END)),
+        ..config()
+    });
+    add(
+        &mut pool,
+        vec![
+            payment(&sender, &receiver, 0),
+            payment_until(&sender, &receiver, 1, 10),
+            payment(&other_sender, &receiver, 0),
+        ],
+        &accounts,
+    );
+
+    let queue = pool
+        .sender_queue(&sender_id, Slot::zero(), Slot::zero())
+        .unwrap();
+    assert_eq!(queue.sender, sender_id);
+    assert_eq!(
+        queue.currency_reserved,
+        Amount::from_u64(2 * (FEE + AMOUNT))
+    );
+    let nonces = queue.commands.iter().map(|command| command.nonce);
+    assert!(nonces.eq([Nonce::from_u32(0), Nonce::from_u32(1)]));
+    assert!(queue
+        .commands
+        .iter()
+        .all(|command| command.fee == Fee::from_u64(FEE)));
+    assert_eq!(queue.commands[0].valid_until, Slot::max());
+    assert_eq!(queue.commands[1].valid_until, Slot::from_u32(10));
+
+    assert_eq!(
+        statuses(&pool, &sender_id, 10, 10),
+        [
+            SenderQueueCommandStatus::Includable,
+            SenderQueueCommandStatus::WaitingForNonce(Nonce::from_u32(0)),
+        ]
+    );
+    assert_eq!(
+        statuses(&pool, &sender_id, 11, 11),
+        [
+            SenderQueueCommandStatus::Includable,
+            SenderQueueCommandStatus::Expired,
+        ]
+    );
+    assert_eq!(
+        statuses(&pool, &sender_id, SLOT_TX_END, SLOT_TX_END),
+        [
+            SenderQueueCommandStatus::AfterSlotTxEnd,
+            SenderQueueCommandStatus::AfterSlotTxEnd,
+        ]
+    );
+
+    let queues = pool.sender_queues(Slot::zero(), Slot::zero());
+    assert_eq!(queues.len(), 2);
+    let other_queue = queues
+        .iter()
+        .find(|queue| queue.sender.public_key == other_sender)
+        .unwrap();
+    assert_eq!(other_queue.commands.len(), 1);
+
+    let unknown = AccountId::new(gen_compressed(), TokenId::default());
+    assert!(pool
+        .sender_queue(&unknown, Slot::zero(), Slot::zero())
+        .is_none());
+}
@@ -0,0 +1,146 @@
+// Run this test with:
+// cargo test --package mina-tree --test test_zkapp_size
+
+use ark_ff::Zero;
+use mina_curves::pasta::Fp;
+use mina_signer::Signature;
+use mina_tree::{
+    scan_state::{
+        currency::{Amount, Fee, Magnitude, Nonce, Sgn, Signed, Slot},
+        transaction_logic::{
+            zkapp_command::{
+                Account, AccountPreconditions, AccountUpdate, Actions, AuthorizationKind, Body,
+                CallForest, Control, Event, Events, FeePayer, FeePayerBody, MayUseToken, Numeric,
+                Preconditions, Tree, Update, WithStackHash, ZkAppCommand, ZkAppPreconditions,
+            },
+            Memo,
+        },
+        ZkAppCommandLimits,
+    },
+    MutableFp, TokenId,
+};
+
+const LIMITS: ZkAppCommandLimits = ZkAppCommandLimits {
+    max_event_elements: 10,
+    max_action_elements: 10,
+};
+
+/// One event or action of `n` field elements, or none if `n` is 0
+fn events(n: usize) -> Vec<Event> {
+    if n == 0 {
+        return Vec::new();
+    }
+    vec![Event(vec![Fp::zero(); n])]
+}
+
+/// zkApp command with one account update per `(event elements, action
+/// elements)` pair
+fn create_zkapp_command(updates: &[(usize, usize)]) -> ZkAppCommand {
+    let pk = mina_signer::PubKey::from_address(
+        "B62qmnY6m4c6bdgSPnQGZriSaj9vuSjsfh6qkveGTsFX3yGA5ywRaja",
+    )
+    .unwrap()
+    .into_compressed();
+
+    let fee_payer = FeePayer {
+        body: FeePayerBody {
+            public_key: pk.clone(),
+            fee: Fee::from_u64(1_000_000),
+            valid_until: Some(Slot::max()),
+            nonce: Nonce::from_u32(0),
+        },
+        authorization: Signature::dummy(),
+    };
+
+    let trees = updates
+        .iter()
+        .map(|&(n_events, n_actions)| {
+            let account_update = AccountUpdate {
+                body: Body {
+                    public_key: pk.clone(),
+                    token_id: TokenId::default(),
+                    update: Update::noop(),
+                    balance_change: Signed {
+                        magnitude: Amount::zero(),
+                        sgn: Sgn::Pos,
+                    },
+                    increment_nonce: false,
+                    events: Events(events(n_events)),
+                    actions: Actions(events(n_actions)),
+                    call_data: Fp::zero(),
+                    preconditions: Preconditions {
+                        network: ZkAppPreconditions::accept(),
+                        account: AccountPreconditions(Account::accept()),
+                        valid_while: Numeric::Ignore,
+                    },
+                    use_full_commitment: false,
+                    implicit_account_creation_fee: false,
+                    may_use_token: MayUseToken::No,
+                    authorization_kind: AuthorizationKind::NoneGiven,
+                },
+                authorization: Control::NoneGiven,
+            };
+            WithStackHash {
+                elt: Tree {
+                    account_update,
+                    account_update_digest: MutableFp::new(Fp::zero()),
+                    calls: CallForest::new(),
+                },
+                stack_hash: MutableFp::new(Fp::zero()),
+            }
+        })
+        .collect();
+
+    let account_updates = CallForest(trees);
+    account_updates.ensure_hashed();
+
+    ZkAppCommand {
+        fee_payer,
+        account_updates,
+        memo: Memo::empty(),
+    }
+}
+
+#[test]
+fn test_default_limits() {
+    let limits = ZkAppCommandLimits::default();
+    assert_eq!(limits.max_event_elements, 100);
+    assert_eq!(limits.max_action_elements, 100);
+}
+
+#[test]
+fn test_within_limits() {
+    let cmd = create_zkapp_command(&[(5, 0), (5, 10)]);
+    assert_eq!(cmd.valid_size_with_limits(&LIMITS), Ok(()));
+}
+
+#[test]
+fn test_too_many_event_elements() {
+    let cmd = create_zkapp_command(&[(4, 0), (4, 0), (4, 0), (4, 0)]);
+    let err = cmd.valid_size_with_limits(&LIMITS).unwrap_err();
+    assert_eq!(
+        err,
+        "too many event elements (16, max 10), limit exceeded at account update 2"
+    );
+}
+
+#[test]
+fn test_too_many_action_elements() {
+    let cmd = create_zkapp_command(&[(0, 11)]);
+    let err = cmd.valid_size_with_limits(&LIMITS).unwrap_err();
+    assert_eq!(
+        err,
+        "too many action elements (11, max 10), limit exceeded at account update 0"
+    );
+}
+
+#[test]
+fn test_too_many_events_and_actions() {
+    let cmd = create_zkapp_command(&[(6, 0), (0, 6), (6, 6)]);
+    let err = cmd.valid_size_with_limits(&LIMITS).unwrap_err();
+    assert_eq!(
+        err,
+        "too many event elements (12, max 10), limit exceeded at account update 2;\
+         too many action elements (12, max 10), limit exceeded at account update 2"
+    );
+}
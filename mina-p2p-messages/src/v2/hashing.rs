@@ -156,6 +156,200 @@ impl BinProtRead for TransactionHash {
     }
 }
 
+impl TransactionHash {
+    /// Hash of the command serialized in `transaction_id`, as computed by
+    /// `Transaction_hash.hash_of_transaction_id` of the OCaml node: the
+    /// Base64 id of a signed command or zkApp command, or the Base58Check
+    /// id of a signed command of the legacy (pre-Berkeley) format.
+    pub fn from_transaction_id(transaction_id: &str) -> io::Result<Self> {
+        const SIGNED_COMMAND_V1_VERSION_BYTE: u8 = 0x13;
+        const ZKAPP_COMMAND_VERSION: u8 = 1;
+        const SIGNED_COMMAND_VERSION: u8 = 2;
+
+        if let Ok(bytes) = bs58::decode(transaction_id)
+            .with_check(Some(SIGNED_COMMAND_V1_VERSION_BYTE))
+            .into_vec()
+        {
+            return LegacySignedCommand::hash(&bytes[1..]);
+        }
+
+        use base64::{engine::general_purpose::STANDARD, Engine as _};
+        let bytes = STANDARD
+            .decode(transaction_id)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let invalid = |err: binprot::Error| io::Error::new(io::ErrorKind::InvalidData, err);
+        match bytes.split_first() {
+            Some((&ZKAPP_COMMAND_VERSION, mut bytes)) => {
+                generated::MinaBaseZkappCommandTStableV1WireStableV1::binprot_read(&mut bytes)
+                    .map_err(invalid)?
+                    .hash()
+            }
+            Some((&SIGNED_COMMAND_VERSION, mut bytes)) => {
+                generated::MinaBaseSignedCommandStableV2::binprot_read(&mut bytes)
+                    .map_err(invalid)?
+                    .hash()
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unknown transaction id version",
+            )),
+        }
+    }
+}
+
+/// Signed command of the legacy format, in which every versioned type is
+/// preceded by its version. Its hash is the one of the command without the
+/// versions, with the dummy signature.
+///
+/// Only payments are supported, the other commands of that format are
+/// rejected.
+struct LegacySignedCommand<'a> {
+    input: &'a [u8],
+    output: Vec<u8>,
+}
+
+impl LegacySignedCommand<'_> {
+    fn hash(input: &[u8]) -> io::Result<TransactionHash> {
+        use blake2::{
+            digest::{Update, VariableOutput},
+            Blake2bVar,
+        };
+
+        let mut command = LegacySignedCommand {
+            input,
+            output: Vec::new(),
+        };
+        command.signed_command()?;
+        if !command.input.is_empty() {
+            return Err(Self::invalid("trailing bytes"));
+        }
+
+        let mut hasher = Blake2bVar::new(32).expect("Invalid Blake2bVar output size");
+        hasher.update(&command.output);
+        let mut hash = [0; 32];
+        hasher
+            .finalize_variable(&mut hash)
+            .expect("Invalid buffer size"); // Never occur
+
+        Ok(TransactionHash(hash.into()))
+    }
+
+    fn signed_command(&mut self) -> io::Result<()> {
+        const PAYMENT: u8 = 0;
+
+        // Signed command, its payload, and the common part of the payload.
+        self.versions(6)?;
+        self.versions(2)?;
+        self.int()?; // fee
+        self.versions(3)?;
+        self.int()?; // fee token
+        self.public_key()?; // fee payer
+        self.versions(2)?;
+        self.int()?; // nonce
+        self.versions(2)?;
+        self.int()?; // valid until
+        self.versions(1)?;
+        self.string()?; // memo
+
+        self.versions(1)?;
+        // Variant of the body.
+        let body = self.byte()?;
+        if body != PAYMENT {
+            return Err(Self::invalid("only legacy payments are supported"));
+        }
+        self.output.push(body);
+        self.versions(2)?;
+        self.public_key()?; // source
+        self.public_key()?; // receiver
+        self.versions(3)?;
+        self.int()?; // token id
+        self.versions(2)?;
+        self.int()?; // amount
+
+        self.versions(1)?;
+        self.public_key()?; // signer
+
+        // The signature is replaced with the dummy one.
+        self.versions(2)?;
+        self.take(64)?;
+        generated::MinaBaseSignatureStableV1(BigInt::one(), BigInt::one())
+            .binprot_write(&mut self.output)
+    }
+
+    fn invalid(error: &str) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid legacy signed command: {error}"),
+        )
+    }
+
+    fn take(&mut self, len: usize) -> io::Result<&[u8]> {
+        if self.input.len() < len {
+            return Err(Self::invalid("unexpected end"));
+        }
+        let (bytes, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn copy(&mut self, len: usize) -> io::Result<()> {
+        let input = self.input;
+        self.take(len)?;
+        self.output.extend_from_slice(&input[..len]);
+        Ok(())
+    }
+
+    fn versions(&mut self, count: usize) -> io::Result<()> {
+        for _ in 0..count {
+            if self.byte()? != 1 {
+                return Err(Self::invalid("unexpected version"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies an integer, returns its value if it isn't negative.
+    fn int(&mut self) -> io::Result<Option<u64>> {
+        let code = *self
+            .input
+            .first()
+            .ok_or_else(|| Self::invalid("unexpected end"))?;
+        let (len, signed) = match code {
+            0xff => (1, true),
+            0xfe => (2, false),
+            0xfd => (4, false),
+            0xfc => (8, false),
+            code => {
+                self.copy(1)?;
+                return Ok(Some(code.into()));
+            }
+        };
+        self.copy(1)?;
+        let bytes = &self.input[..len.min(self.input.len())];
+        let mut value = [0; 8];
+        value[..bytes.len()].copy_from_slice(bytes);
+        self.copy(len)?;
+        Ok((!signed).then(|| u64::from_le_bytes(value)))
+    }
+
+    fn string(&mut self) -> io::Result<()> {
+        let len = self
+            .int()?
+            .ok_or_else(|| Self::invalid("negative string length"))?;
+        self.copy(len as usize)
+    }
+
+    fn public_key(&mut self) -> io::Result<()> {
+        self.versions(2)?;
+        self.copy(32)?; // x
+        self.copy(1) // is_odd
+    }
+}
+
 impl generated::MinaTransactionTransactionStableV2 {
     pub fn hash(&self) -> io::Result<TransactionHash> {
         match self {
@@ -839,6 +1033,7 @@ mod hash_tests {
 
     use crate::v2::{
         MinaBaseZkappCommandTStableV1WireStableV1, MinaStateProtocolStateValueStableV2,
+        TransactionHash,
     };
 
     #[test]
@@ -878,4 +1073,36 @@ mod hash_tests {
 
         assert_eq!(expected_hash, hash);
     }
+
+    /// Vectors of `Transaction_hash.hash_of_transaction_id` of the OCaml
+    /// node.
+    #[test]
+    fn test_hash_of_transaction_id() {
+        let legacy_payment = "BD421DxjdoLimeUh4RA4FEvHdDn6bfxyMVWiWUwbYzQkqhNUv8B5M4gCSREpu9mVueBYoHYWkwB8BMf6iS2jjV8FffvPGkuNeczBfY7YRwLuUGBRCQJ3ktFBrNuu4abqgkYhXmcS2xyzoSGxHbXkJRAokTwjQ9HP6TLSeXz9qa92nJaTeccMnkoZBmEitsZWWnTCMqDc6rhN4Z9UMpg4wzdPMwNJvLRuJBD14Dd5pR84KBoY9rrnv66rHPc4m2hH9QSEt4aEJC76BQ446pHN9ZLmyhrk28f5xZdBmYxp3hV13fJEJ3Gv1XqJMBqFxRhzCVGoKDbLAaNRb5F1u1WxTzJu5n4cMMDEYydGEpNirY2PKQqHkR8gEqjXRTkpZzP8G19qT";
+        let hash = TransactionHash::from_transaction_id(legacy_payment).unwrap();
+        assert_eq!(
+            hash.to_string(),
+            "5JuV53FPXad1QLC46z7wsou9JjjYP87qaUeryscZqLUMmLSg8j2n"
+        );
+
+        let signed_command = "Av0IlDV3VklWpVXVRQr7cidImXn8E9nqCAxPjuyUNZ2pu3pJJxkBAAD//yIAIKTVOZ2q1qG1KT11p6844pWJ3fQug1XGnzv2S3N73azIABXhN3d+nO04Y7YqBul1CY5CEq9o34KWvfcB8IWep3kkAf60JFZJVqVV1UUK+3InSJl5/BPZ6ggMT47slDWdqbt6SScZAQEAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+        let hash = TransactionHash::from_transaction_id(signed_command).unwrap();
+        assert_eq!(
+            hash.to_string(),
+            "5JvBt4173K3t7gQSpFoMGtbtZuYWPSg29cWad5pnnRd9BnAowoqY"
+        );
+
+        let bytes = include_bytes!("../../../tests/files/zkapps/with_sig_auth.bin");
+        let zkapp =
+            MinaBaseZkappCommandTStableV1WireStableV1::binprot_read(&mut bytes.as_slice()).unwrap();
+        let hash = TransactionHash::from_transaction_id(&zkapp.to_base64().unwrap()).unwrap();
+        assert_eq!(
+            hash.to_string(),
+            "5JvQ6xQeGgCTe2d4KpCsJ97yK61mNRZHixJxPbKTppY1qSGgtj6t"
+        );
+
+        assert!(TransactionHash::from_transaction_id("not an id").is_err());
+        let truncated = &legacy_payment[..legacy_payment.len() - 4];
+        assert!(TransactionHash::from_transaction_id(truncated).is_err());
+    }
 }
@@ -0,0 +1,104 @@
+#![cfg(not(target_family = "wasm"))]
+
+//! Property-based tests for Base58Check encoded hashes and addresses, which
+//! are parsed from RPCs, GraphQL requests and configuration files.
+
+use std::str::FromStr;
+
+use mina_p2p_messages::{
+    b58,
+    bigint::BigInt,
+    v2::{
+        DataHashLibStateHashStableV1, LedgerHash, MinaBaseLedgerHash0StableV1, NonZeroCurvePoint,
+        NonZeroCurvePointUncompressedStableV1, StateHash,
+    },
+};
+use proptest::prelude::*;
+
+fn state_hash() -> impl Strategy<Value = StateHash> {
+    any::<[u8; 32]>()
+        .prop_map(|bytes| DataHashLibStateHashStableV1(BigInt::from_bytes(bytes)).into())
+}
+
+fn ledger_hash() -> impl Strategy<Value = LedgerHash> {
+    any::<[u8; 32]>()
+        .prop_map(|bytes| MinaBaseLedgerHash0StableV1(BigInt::from_bytes(bytes)).into())
+}
+
+fn address() -> impl Strategy<Value = NonZeroCurvePoint> {
+    (any::<[u8; 32]>(), any::<bool>()).prop_map(|(x, is_odd)| {
+        NonZeroCurvePointUncompressedStableV1 {
+            x: BigInt::from_bytes(x),
+            is_odd,
+        }
+        .into()
+    })
+}
+
+/// Replaces a single character, keeping it in the Base58 alphabet.
+fn corrupt(s: &str, index: prop::sample::Index) -> String {
+    let mut bytes = s.as_bytes().to_vec();
+    let i = index.index(bytes.len());
+    bytes[i] = if bytes[i] == b'2' { b'3' } else { b'2' };
+    String::from_utf8(bytes).unwrap()
+}
+
+fn parse_all(s: &str) {
+    let _ = StateHash::from_str(s);
+    let _ = LedgerHash::from_str(s);
+    let _ = NonZeroCurvePoint::from_str(s);
+}
+
+proptest! {
+    #[test]
+    fn state_hash_roundtrip(hash in state_hash()) {
+        prop_assert_eq!(hash.to_string().parse::<StateHash>().ok(), Some(hash));
+    }
+
+    #[test]
+    fn ledger_hash_roundtrip(hash in ledger_hash()) {
+        prop_assert_eq!(hash.to_string().parse::<LedgerHash>().ok(), Some(hash));
+    }
+
+    #[test]
+    fn address_roundtrip(address in address()) {
+        let s = address.to_string();
+        prop_assert!(s.starts_with("B62"));
+        prop_assert_eq!(s.parse::<NonZeroCurvePoint>().ok(), Some(address));
+    }
+
+    #[test]
+    fn wrong_version_rejected(hash in state_hash()) {
+        prop_assert!(LedgerHash::from_str(&hash.to_string()).is_err());
+        prop_assert!(NonZeroCurvePoint::from_str(&hash.to_string()).is_err());
+    }
+
+    #[test]
+    fn corrupted_rejected(
+        hash in state_hash(),
+        address in address(),
+        index in any::<prop::sample::Index>(),
+    ) {
+        prop_assert!(StateHash::from_str(&corrupt(&hash.to_string(), index)).is_err());
+        prop_assert!(NonZeroCurvePoint::from_str(&corrupt(&address.to_string(), index)).is_err());
+    }
+
+    #[test]
+    fn arbitrary_strings(s in any::<String>()) {
+        parse_all(&s);
+    }
+
+    #[test]
+    fn arbitrary_payloads(
+        payload in prop::collection::vec(any::<u8>(), 0..80),
+        version in prop_oneof![
+            Just(mina_p2p_messages::b58version::STATE_HASH),
+            Just(mina_p2p_messages::b58version::LEDGER_HASH),
+            Just(mina_p2p_messages::b58version::NON_ZERO_CURVE_POINT_COMPRESSED),
+            any::<u8>(),
+        ],
+    ) {
+        // Valid checksums, so the payload gets to the binprot reader
+        parse_all(&b58::encode(&payload, version));
+    }
+}
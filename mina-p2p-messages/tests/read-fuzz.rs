@@ -42,3 +42,16 @@ fn snark_pool_diff() {
 fn tx_pool_diff() {
     fuzz(try_decode::<mina_p2p_messages::p2p::NetworkPoolTransactionPoolDiffVersionedStable>);
 }
+
+#[test]
+fn base58check() {
+    use std::str::FromStr;
+
+    fuzz(|buf: &[u8]| {
+        if let Ok(s) = std::str::from_utf8(buf) {
+            let _ = mina_p2p_messages::v2::StateHash::from_str(s);
+            let _ = mina_p2p_messages::v2::NonZeroCurvePoint::from_str(s);
+        }
+        true
+    });
+}
@@ -1,6 +1,11 @@
 use std::env;
 
-use super::Error;
+use aws_sdk_s3::{
+    primitives::ByteStream,
+    types::{CompletedMultipartUpload, CompletedPart},
+};
+
+use super::{upload_in_parts, Error, MULTIPART_PART_SIZE, MULTIPART_THRESHOLD};
 
 pub(crate) struct ArchiveAWSClient {
     client: aws_sdk_s3::Client,
@@ -23,10 +28,15 @@ impl ArchiveAWSClient {
     }
 
     pub async fn upload_block(&self, key: &str, data: &[u8]) -> Result<(), Error> {
+        let key = format!("{}/{}", self.bucket_path, key);
+        if data.len() > MULTIPART_THRESHOLD {
+            return self.upload_multipart(&key, data).await;
+        }
+
         self.client
             .put_object()
             .bucket(self.bucket_name.clone())
-            .key(format!("{}/{}", self.bucket_path, key))
+            .key(key)
             .body(data.to_vec().into())
             .send()
             .await
@@ -34,4 +44,69 @@ impl ArchiveAWSClient {
 
         Ok(())
     }
+
+    async fn upload_multipart(&self, key: &str, data: &[u8]) -> Result<(), Error> {
+        let upload = self
+            .client
+            .create_multipart_upload()
+            .bucket(self.bucket_name.clone())
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| Error::UploadError(e.to_string()))?;
+        let upload_id = upload
+            .upload_id()
+            .ok_or_else(|| Error::UploadError("no multipart upload id".to_owned()))?;
+
+        let result = self.upload_parts(key, upload_id, data).await;
+        if result.is_err() {
+            // Parts of an unfinished upload are billed until it is aborted.
+            let _ = self
+                .client
+                .abort_multipart_upload()
+                .bucket(self.bucket_name.clone())
+                .key(key)
+                .upload_id(upload_id)
+                .send()
+                .await;
+        }
+        result
+    }
+
+    async fn upload_parts(&self, key: &str, upload_id: &str, data: &[u8]) -> Result<(), Error> {
+        let parts = upload_in_parts(data, MULTIPART_PART_SIZE, |part| async move {
+            let uploaded = self
+                .client
+                .upload_part()
+                .bucket(self.bucket_name.clone())
+                .key(key)
+                .upload_id(upload_id)
+                .part_number(part.number)
+                .body(ByteStream::from(part.data.to_vec()))
+                .send()
+                .await
+                .map_err(|e| Error::UploadError(e.to_string()))?;
+            Ok(CompletedPart::builder()
+                .set_e_tag(uploaded.e_tag().map(ToOwned::to_owned))
+                .part_number(part.number)
+                .build())
+        })
+        .await?;
+
+        self.client
+            .complete_multipart_upload()
+            .bucket(self.bucket_name.clone())
+            .key(key)
+            .upload_id(upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(|e| Error::UploadError(e.to_string()))?;
+
+        Ok(())
+    }
 }
@@ -107,4 +107,18 @@ impl ArchiveStorageOptions {
     pub fn uses_aws_precomputed_storage(&self) -> bool {
         self.contains(ArchiveStorageOptions::AWS_PRECOMPUTED_STORAGE)
     }
+
+    /// Names of the enabled storages.
+    pub fn names(&self) -> Vec<String> {
+        [
+            (self.uses_archiver_process(), "archiver_process"),
+            (self.uses_local_precomputed_storage(), "local_precomputed"),
+            (self.uses_gcp_precomputed_storage(), "gcp_precomputed"),
+            (self.uses_aws_precomputed_storage(), "aws_precomputed"),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, name)| name.to_owned())
+        .collect()
+    }
 }
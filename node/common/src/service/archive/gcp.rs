@@ -1,8 +1,11 @@
-use gcs::http::objects::upload as gcs_upload;
+use gcs::http::{
+    objects::{upload as gcs_upload, Object},
+    resumable_upload_client::{ChunkSize, UploadStatus},
+};
 use google_cloud_auth::credentials::CredentialsFile as GcpCredentialsFile;
 use google_cloud_storage as gcs;
 
-use super::Error;
+use super::{upload_in_parts, Error, MULTIPART_PART_SIZE, MULTIPART_THRESHOLD};
 use std::env;
 
 pub(crate) struct ArchiveGCPClient {
@@ -35,6 +38,10 @@ impl ArchiveGCPClient {
     }
 
     pub async fn upload_block(&self, key: &str, data: &[u8]) -> Result<(), Error> {
+        if data.len() > MULTIPART_THRESHOLD {
+            return self.upload_resumable(key, data).await;
+        }
+
         let upload_type = gcs_upload::UploadType::Simple(gcs_upload::Media::new(key.to_string()));
 
         self.client
@@ -51,4 +58,45 @@ impl ArchiveGCPClient {
 
         Ok(())
     }
+
+    /// Uploads large blocks in chunks, so that a failure doesn't require
+    /// sending the whole block in a single request again.
+    async fn upload_resumable(&self, key: &str, data: &[u8]) -> Result<(), Error> {
+        let upload_type = gcs_upload::UploadType::Multipart(Box::new(Object {
+            name: key.to_string(),
+            ..Default::default()
+        }));
+        let uploader = self
+            .client
+            .prepare_resumable_upload(
+                &gcs_upload::UploadObjectRequest {
+                    bucket: self.bucket_name.clone(),
+                    ..Default::default()
+                },
+                &upload_type,
+            )
+            .await
+            .map_err(|e| Error::UploadError(format!("GCP upload failed: {}", e)))?;
+
+        let uploader = &uploader;
+        let total_size = data.len() as u64;
+        let statuses = upload_in_parts(data, MULTIPART_PART_SIZE, |part| async move {
+            uploader
+                .upload_multiple_chunk(
+                    part.data.to_vec(),
+                    &ChunkSize::new(part.first_byte, part.last_byte, Some(total_size)),
+                )
+                .await
+                .map_err(|e| Error::UploadError(format!("GCP upload failed: {}", e)))
+        })
+        .await?;
+
+        // Only the last chunk completes the upload.
+        match statuses.last() {
+            Some(UploadStatus::Ok(_)) => Ok(()),
+            _ => Err(Error::UploadError(
+                "GCP upload failed: incomplete resumable upload".to_owned(),
+            )),
+        }
+    }
 }
@@ -4,9 +4,19 @@ use node::{
     ledger::write::BlockApplyResult,
 };
 use std::{env, io::Write};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    time::Duration,
+    time::SystemTime,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::sync::Notify;
 
 use mina_core::NetworkConfig;
 use mina_p2p_messages::v2::PrecomputedBlock;
+use node::transition_frontier::archive::archive_service::ArchiveUploadStats;
 use std::net::SocketAddr;
 
 use super::NodeService;
@@ -17,6 +27,8 @@ pub mod aws;
 pub mod gcp;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod rpc;
+#[cfg(not(target_arch = "wasm32"))]
+mod upload_queue;
 
 pub mod config;
 
@@ -26,16 +38,45 @@ const ARCHIVE_SEND_RETRIES: u8 = 5;
 const MAX_EVENT_COUNT: u64 = 100;
 const RETRY_INTERVAL_MS: u64 = 1000;
 
+/// Precomputed blocks larger than this are uploaded in parts.
+#[cfg(not(target_arch = "wasm32"))]
+const MULTIPART_THRESHOLD: usize = 16 * 1024 * 1024;
+/// Size of the parts of large uploads. S3 requires at least 5 MiB, GCS a
+/// multiple of 256 KiB.
+#[cfg(not(target_arch = "wasm32"))]
+const MULTIPART_PART_SIZE: usize = 8 * 1024 * 1024;
+#[cfg(not(target_arch = "wasm32"))]
+const UPLOAD_RETRIES: u32 = 5;
+#[cfg(not(target_arch = "wasm32"))]
+const UPLOAD_BACKOFF_INITIAL: Duration = Duration::from_secs(1);
+#[cfg(not(target_arch = "wasm32"))]
+const UPLOAD_BACKOFF_MAX: Duration = Duration::from_secs(30);
+/// How often the uploads left in the queue are retried.
+#[cfg(not(target_arch = "wasm32"))]
+const UPLOAD_QUEUE_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+/// How often the quarantined uploads are queued again.
+#[cfg(not(target_arch = "wasm32"))]
+const UPLOAD_QUARANTINE_RETRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Uploads waiting for longer than this are reported as warnings.
+#[cfg(not(target_arch = "wasm32"))]
+const UPLOAD_LAG_WARNING: Duration = Duration::from_secs(10 * 60);
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("Environment variable {0} is not set")]
     EnvironmentVariableNotSet(String),
     #[error("Failed to upload block to AWS: {0}")]
     UploadError(String),
+    #[error("{0} client not initialized")]
+    ClientNotInitialized(&'static str),
+    #[error("Archive upload queue error: {0}")]
+    UploadQueue(#[from] std::io::Error),
 }
 
 pub struct ArchiveService {
     archive_sender: mpsc::UnboundedSender<BlockApplyResult>,
+    #[cfg(not(target_arch = "wasm32"))]
+    upload_stats: upload_queue::UploadStats,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -43,24 +84,70 @@ struct ArchiveServiceClients {
     archiver_address: Option<SocketAddr>,
     aws_client: Option<aws::ArchiveAWSClient>,
     gcp_client: Option<gcp::ArchiveGCPClient>,
+    aws_upload_queue: Option<upload_queue::UploadQueue>,
+    gcp_upload_queue: Option<upload_queue::UploadQueue>,
+    upload_stats: upload_queue::UploadStats,
+    /// Notified when a block is added to an upload queue.
+    upload_queued: Notify,
     local_path: Option<String>,
+    /// Directory the vrf evaluations of the blocks produced by this node
+    /// are written to, apart from the precomputed blocks.
+    vrf_evaluations_path: PathBuf,
+}
+
+/// Part of an upload split in parts, with the range of its bytes.
+#[cfg(not(target_arch = "wasm32"))]
+struct UploadPart<'a> {
+    /// Starts at 1.
+    number: i32,
+    first_byte: u64,
+    last_byte: u64,
+    data: &'a [u8],
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Copy)]
+enum CloudStorage {
+    Aws,
+    Gcp,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl CloudStorage {
+    const ALL: [Self; 2] = [Self::Aws, Self::Gcp];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Aws => "AWS",
+            Self::Gcp => "GCP",
+        }
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl ArchiveServiceClients {
-    async fn new(options: &ArchiveStorageOptions, work_dir: String) -> Result<Self, Error> {
-        let aws_client = if options.uses_aws_precomputed_storage() {
+    async fn new(
+        options: &ArchiveStorageOptions,
+        work_dir: String,
+        upload_stats: upload_queue::UploadStats,
+    ) -> Result<Self, Error> {
+        let upload_queue_dir = Path::new(&work_dir).join("archive-upload-queue");
+        let vrf_evaluations_path = Path::new(&work_dir).join("vrf-evaluations");
+
+        let (aws_client, aws_upload_queue) = if options.uses_aws_precomputed_storage() {
             let client = aws::ArchiveAWSClient::new().await?;
-            Some(client)
+            let queue = upload_queue::UploadQueue::open(upload_queue_dir.join("aws"))?;
+            (Some(client), Some(queue))
         } else {
-            None
+            (None, None)
         };
 
-        let gcp_client = if options.uses_gcp_precomputed_storage() {
+        let (gcp_client, gcp_upload_queue) = if options.uses_gcp_precomputed_storage() {
             let client = gcp::ArchiveGCPClient::new().await?;
-            Some(client)
+            let queue = upload_queue::UploadQueue::open(upload_queue_dir.join("gcp"))?;
+            (Some(client), Some(queue))
         } else {
-            None
+            (None, None)
         };
 
         let local_path = if options.uses_local_precomputed_storage() {
@@ -90,7 +177,12 @@ impl ArchiveServiceClients {
             archiver_address,
             aws_client,
             gcp_client,
+            aws_upload_queue,
+            gcp_upload_queue,
+            upload_stats,
+            upload_queued: Notify::new(),
             local_path,
+            vrf_evaluations_path,
         })
     }
 
@@ -109,6 +201,14 @@ impl ArchiveServiceClients {
             let state_hash = breadcrumb.block.hash();
 
             let key = format!("{network_name}-{height}-{state_hash}.json");
+            // Blocks produced by this node are archived with the vrf
+            // evaluation that won their slot, for it to be audited.
+            let vrf_evaluation = breadcrumb.vrf_evaluation.clone().map(|vrf_evaluation| {
+                (
+                    format!("{network_name}-{height}-{state_hash}.json"),
+                    vrf_evaluation,
+                )
+            });
 
             node::core::info!(
                 summary = "Uploading precomputed block to archive",
@@ -135,53 +235,274 @@ impl ArchiveServiceClients {
                     return;
                 }
             };
+            self.store_precomputed(&key, &data, options).await;
 
-            if options.uses_local_precomputed_storage() {
-                if let Some(path) = &self.local_path {
-                    let key_clone = key.clone();
-                    match write_to_local_storage(path, &key, &data) {
-                        Ok(_) => node::core::info!(
-                            summary = "Successfully wrote precomputed block to local storage",
-                            key = key_clone
-                        ),
-                        Err(e) => node::core::warn!(
-                            summary = "Failed to write precomputed block to local storage",
-                            key = key_clone,
-                            error = e.to_string()
-                        ),
-                    }
-                } else {
-                    node::core::warn!(summary = "Local precomputed storage path not set");
+            if let Some((name, vrf_evaluation)) = vrf_evaluation {
+                match serde_json::to_vec(&vrf_evaluation) {
+                    Ok(data) => self.store_vrf_evaluation(&name, &data),
+                    Err(e) => node::core::warn!(
+                        summary = "Failed to serialize vrf evaluation",
+                        error = e.to_string()
+                    ),
                 }
             }
+        }
+    }
 
-            if options.uses_gcp_precomputed_storage() {
-                if let Some(client) = &self.gcp_client {
-                    if let Err(e) = client.upload_block(&key, &data).await {
-                        node::core::warn!(
-                            summary = "Failed to upload precomputed block to GCP",
-                            error = e.to_string()
-                        );
-                    }
-                } else {
-                    node::core::warn!(summary = "GCP client not initialized");
+    /// Writes the vrf evaluation of a produced block to its own directory,
+    /// never to the storages of the precomputed blocks, so that it isn't
+    /// mistaken for a block by whatever reads them.
+    fn store_vrf_evaluation(&self, name: &str, data: &[u8]) {
+        let path = self.vrf_evaluations_path.to_string_lossy();
+        match write_to_local_storage(&path, name, data) {
+            Ok(_) => node::core::info!(
+                summary = "Successfully wrote vrf evaluation",
+                path = format!("{path}/{name}")
+            ),
+            Err(e) => node::core::warn!(
+                summary = "Failed to write vrf evaluation",
+                path = format!("{path}/{name}"),
+                error = e.to_string()
+            ),
+        }
+    }
+
+    async fn store_precomputed(&self, key: &str, data: &[u8], options: &ArchiveStorageOptions) {
+        if options.uses_local_precomputed_storage() {
+            if let Some(path) = &self.local_path {
+                match write_to_local_storage(path, key, data) {
+                    Ok(_) => node::core::info!(
+                        summary = "Successfully wrote precomputed block to local storage",
+                        key = key
+                    ),
+                    Err(e) => node::core::warn!(
+                        summary = "Failed to write precomputed block to local storage",
+                        key = key,
+                        error = e.to_string()
+                    ),
                 }
+            } else {
+                node::core::warn!(summary = "Local precomputed storage path not set");
             }
-            if options.uses_aws_precomputed_storage() {
-                if let Some(client) = &self.aws_client {
-                    if let Err(e) = client.upload_block(&key, &data).await {
+        }
+
+        if options.uses_gcp_precomputed_storage() {
+            self.enqueue_upload(CloudStorage::Gcp, key, data).await;
+        }
+        if options.uses_aws_precomputed_storage() {
+            self.enqueue_upload(CloudStorage::Aws, key, data).await;
+        }
+    }
+
+    fn upload_queue(&self, storage: CloudStorage) -> Option<&upload_queue::UploadQueue> {
+        match storage {
+            CloudStorage::Aws => self.aws_upload_queue.as_ref(),
+            CloudStorage::Gcp => self.gcp_upload_queue.as_ref(),
+        }
+    }
+
+    /// Adds the block to the upload queue, it is uploaded in the background
+    /// by [`Self::run_upload_queues`], and kept in the queue until it
+    /// succeeds, even after a restart. Receiving blocks doesn't wait for
+    /// uploads, which may be retried for a while during an outage.
+    async fn enqueue_upload(&self, storage: CloudStorage, key: &str, data: &[u8]) {
+        let Some(queue) = self.upload_queue(storage) else {
+            node::core::warn!(
+                summary = "Failed to upload precomputed block",
+                storage = storage.name(),
+                error = Error::ClientNotInitialized(storage.name()).to_string()
+            );
+            return;
+        };
+        match queue.push(key, data) {
+            Ok(()) => {
+                self.upload_stats.queued(storage.name(), SystemTime::now());
+                self.upload_queued.notify_one();
+            }
+            Err(e) => {
+                node::core::warn!(
+                    summary = "Failed to queue precomputed block, uploading it directly",
+                    storage = storage.name(),
+                    key = key,
+                    error = e.to_string()
+                );
+                if let Err(e) = self.upload(storage, key, data).await {
+                    node::core::warn!(
+                        summary = "Failed to upload precomputed block",
+                        storage = storage.name(),
+                        key = key,
+                        error = e.to_string()
+                    );
+                }
+            }
+        }
+    }
+
+    /// Uploads the blocks added to the queues, and retries the ones left
+    /// in them periodically. The first retry is immediate, uploading the
+    /// blocks left in the queues before a restart. Quarantined blocks are
+    /// retried less often.
+    async fn run_upload_queues(&self) {
+        let mut retry_interval = tokio::time::interval(UPLOAD_QUEUE_RETRY_INTERVAL);
+        let mut quarantine_interval = tokio::time::interval(UPLOAD_QUARANTINE_RETRY_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = self.upload_queued.notified() => {}
+                _ = retry_interval.tick() => {}
+                _ = quarantine_interval.tick() => self.release_quarantined(),
+            }
+            self.upload_pending().await;
+        }
+    }
+
+    fn release_quarantined(&self) {
+        for storage in CloudStorage::ALL {
+            let Some(queue) = self.upload_queue(storage) else {
+                continue;
+            };
+            if let Err(e) = queue.release_quarantined() {
+                node::core::warn!(
+                    summary = "Failed to queue quarantined blocks again",
+                    storage = storage.name(),
+                    error = e.to_string()
+                );
+            }
+        }
+    }
+
+    /// Uploads the blocks in the queues, oldest first, and records the
+    /// upload lag: for how long the oldest block has been waiting.
+    async fn upload_pending(&self) {
+        for storage in CloudStorage::ALL {
+            let Some(queue) = self.upload_queue(storage) else {
+                continue;
+            };
+            let pending = match queue.pending() {
+                Ok(pending) => pending,
+                Err(e) => {
+                    node::core::warn!(
+                        summary = "Failed to read the upload queue",
+                        storage = storage.name(),
+                        error = e.to_string()
+                    );
+                    continue;
+                }
+            };
+            let quarantined = || queue.quarantined().unwrap_or_default();
+            if pending.is_empty() {
+                self.upload_stats
+                    .processed(storage.name(), &pending, quarantined(), 0, vec![]);
+                continue;
+            }
+
+            let mut uploaded = 0;
+            let mut errors = Vec::new();
+            for upload in &pending {
+                let result = match queue.read(&upload.key) {
+                    Ok(data) => self.upload_with_retry(storage, &upload.key, &data).await,
+                    Err(e) => Err(e.into()),
+                };
+                if let Err(e) = result {
+                    // Set aside, so that a block the storage keeps rejecting
+                    // doesn't hold back the ones queued after it.
+                    node::core::warn!(
+                        summary = "Failed to upload queued precomputed block, quarantining it",
+                        storage = storage.name(),
+                        key = upload.key.clone(),
+                        error = e.to_string()
+                    );
+                    if let Err(e) = queue.quarantine(&upload.key) {
                         node::core::warn!(
-                            summary = "Failed to upload precomputed block to AWS",
+                            summary = "Failed to quarantine queued precomputed block",
+                            storage = storage.name(),
+                            key = upload.key.clone(),
                             error = e.to_string()
                         );
                     }
-                } else {
-                    node::core::warn!(summary = "AWS client not initialized");
+                    errors.push(e.to_string());
+                    continue;
+                }
+                uploaded += 1;
+                if let Err(e) = queue.remove(&upload.key) {
+                    node::core::warn!(
+                        summary = "Failed to remove uploaded block from the upload queue",
+                        storage = storage.name(),
+                        key = upload.key.clone(),
+                        error = e.to_string()
+                    );
+                }
+            }
+
+            let remaining = queue.pending().unwrap_or_default();
+            self.upload_stats.processed(
+                storage.name(),
+                &remaining,
+                quarantined(),
+                uploaded,
+                errors,
+            );
+            let lag = upload_queue::UploadQueue::lag(&remaining);
+            if lag > UPLOAD_LAG_WARNING {
+                node::core::warn!(
+                    summary = "Precomputed block uploads are lagging",
+                    storage = storage.name(),
+                    uploaded = uploaded,
+                    pending = remaining.len(),
+                    lag_secs = lag.as_secs()
+                );
+            } else {
+                node::core::info!(
+                    summary = "Uploaded queued precomputed blocks",
+                    storage = storage.name(),
+                    uploaded = uploaded,
+                    pending = remaining.len(),
+                    lag_secs = lag.as_secs()
+                );
+            }
+        }
+    }
+
+    async fn upload_with_retry(
+        &self,
+        storage: CloudStorage,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let mut backoff = UPLOAD_BACKOFF_INITIAL;
+        let mut attempt = 1;
+        loop {
+            match self.upload(storage, key, data).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt >= UPLOAD_RETRIES => return Err(e),
+                Err(e) => {
+                    node::core::warn!(
+                        summary = "Failed to upload precomputed block, retrying",
+                        storage = storage.name(),
+                        key = key,
+                        attempt = attempt,
+                        error = e.to_string()
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = backoff.saturating_mul(2).min(UPLOAD_BACKOFF_MAX);
+                    attempt += 1;
                 }
             }
         }
     }
 
+    async fn upload(&self, storage: CloudStorage, key: &str, data: &[u8]) -> Result<(), Error> {
+        match storage {
+            CloudStorage::Aws => match &self.aws_client {
+                Some(client) => client.upload_block(key, data).await,
+                None => Err(Error::ClientNotInitialized(storage.name())),
+            },
+            CloudStorage::Gcp => match &self.gcp_client {
+                Some(client) => client.upload_block(key, data).await,
+                None => Err(Error::ClientNotInitialized(storage.name())),
+            },
+        }
+    }
+
     async fn handle_archiver_process(breadcrumb: &BlockApplyResult, socket_addr: &SocketAddr) {
         let mut retries = ARCHIVE_SEND_RETRIES;
 
@@ -216,17 +537,14 @@ impl ArchiveServiceClients {
 }
 
 impl ArchiveService {
-    fn new(archive_sender: mpsc::UnboundedSender<BlockApplyResult>) -> Self {
-        Self { archive_sender }
-    }
-
     #[cfg(not(target_arch = "wasm32"))]
     async fn run(
         mut archive_receiver: mpsc::UnboundedReceiver<BlockApplyResult>,
         options: ArchiveStorageOptions,
         work_dir: String,
+        upload_stats: upload_queue::UploadStats,
     ) {
-        let clients = match ArchiveServiceClients::new(&options, work_dir).await {
+        let clients = match ArchiveServiceClients::new(&options, work_dir, upload_stats).await {
             Ok(clients) => clients,
             Err(e) => {
                 node::core::error!(
@@ -237,8 +555,17 @@ impl ArchiveService {
             }
         };
 
-        while let Some(breadcrumb) = archive_receiver.recv().await {
-            clients.send_block(breadcrumb, &options).await;
+        let receive_blocks = async {
+            while let Some(breadcrumb) = archive_receiver.recv().await {
+                clients.send_block(breadcrumb, &options).await;
+            }
+        };
+        // Uploads run concurrently with receiving blocks, so that retrying
+        // them during an outage doesn't hold back the archiver process and
+        // the local storage.
+        tokio::select! {
+            _ = receive_blocks => {}
+            _ = clients.run_upload_queues() => {}
         }
     }
 
@@ -256,12 +583,31 @@ impl ArchiveService {
         let (archive_sender, archive_receiver) = mpsc::unbounded_channel::<BlockApplyResult>();
 
         #[cfg(not(target_arch = "wasm32"))]
-        Self::start_native(archive_receiver, options, work_dir);
+        let upload_stats = upload_queue::UploadStats::default();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        Self::start_native(archive_receiver, options, work_dir, upload_stats.clone());
 
         #[cfg(target_arch = "wasm32")]
         Self::start_wasm(archive_receiver, options, work_dir);
 
-        Self::new(archive_sender)
+        Self {
+            archive_sender,
+            #[cfg(not(target_arch = "wasm32"))]
+            upload_stats,
+        }
+    }
+
+    /// Statistics of the uploads of precomputed blocks to each cloud
+    /// storage.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn upload_stats(&self) -> Vec<ArchiveUploadStats> {
+        self.upload_stats.get()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn upload_stats(&self) -> Vec<ArchiveUploadStats> {
+        Vec::new()
     }
 
     #[cfg(not(target_arch = "wasm32"))]
@@ -269,6 +615,7 @@ impl ArchiveService {
         archive_receiver: mpsc::UnboundedReceiver<BlockApplyResult>,
         options: ArchiveStorageOptions,
         work_dir: String,
+        upload_stats: upload_queue::UploadStats,
     ) {
         let runtime = tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -278,7 +625,7 @@ impl ArchiveService {
         thread::Builder::new()
             .name("mina_archive".to_owned())
             .spawn(move || {
-                runtime.block_on(Self::run(archive_receiver, options, work_dir));
+                runtime.block_on(Self::run(archive_receiver, options, work_dir, upload_stats));
             })
             .unwrap();
     }
@@ -309,6 +656,10 @@ impl node::transition_frontier::archive::archive_service::ArchiveService for Nod
             }
         }
     }
+
+    fn archive_upload_stats(&self) -> Option<Vec<ArchiveUploadStats>> {
+        self.archive.as_ref().map(ArchiveService::upload_stats)
+    }
 }
 
 // Note: Placeholder for the wasm implementation, if we decide to include an archive mode in the future
@@ -335,3 +686,88 @@ fn write_to_local_storage(base_path: &str, key: &str, data: &[u8]) -> Result<(),
 
     Ok(())
 }
+
+/// Uploads `data` in parts of `part_size` bytes, in order, stopping at the
+/// first part that fails. Returns the results of the parts.
+#[cfg(not(target_arch = "wasm32"))]
+async fn upload_in_parts<'a, T, F, Fut>(
+    data: &'a [u8],
+    part_size: usize,
+    mut upload_part: F,
+) -> Result<Vec<T>, Error>
+where
+    F: FnMut(UploadPart<'a>) -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let mut results = Vec::new();
+    let mut first_byte = 0;
+    for (number, data) in (1..).zip(data.chunks(part_size)) {
+        let last_byte = first_byte + data.len() as u64 - 1;
+        let part = UploadPart {
+            number,
+            first_byte,
+            last_byte,
+            data,
+        };
+        results.push(upload_part(part).await?);
+        first_byte = last_byte + 1;
+    }
+    Ok(results)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_upload_in_parts() {
+        let data = (0..10).collect::<Vec<u8>>();
+        let parts = upload_in_parts(&data, 4, |part| async move {
+            Ok((part.number, part.first_byte, part.last_byte, part.data))
+        })
+        .await
+        .unwrap();
+
+        let ranges = parts
+            .iter()
+            .map(|(number, first_byte, last_byte, _)| (*number, *first_byte, *last_byte))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [(1, 0, 3), (2, 4, 7), (3, 8, 9)]);
+        assert_eq!(
+            parts
+                .into_iter()
+                .flat_map(|part| part.3)
+                .copied()
+                .collect::<Vec<_>>(),
+            data
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_in_parts_stops_at_failed_part() {
+        let mut uploaded = Vec::new();
+        let result = upload_in_parts(&[0; 10], 4, |part| {
+            uploaded.push(part.number);
+            async move {
+                match part.number {
+                    2 => Err(Error::UploadError("connection reset".to_owned())),
+                    _ => Ok(()),
+                }
+            }
+        })
+        .await;
+
+        assert!(matches!(result, Err(Error::UploadError(_))));
+        assert_eq!(uploaded, [1, 2], "parts after the failed one are not sent");
+    }
+
+    #[tokio::test]
+    async fn test_upload_in_parts_single_part() {
+        let parts = upload_in_parts(&[0; 4], MULTIPART_PART_SIZE, |part| async move {
+            Ok((part.first_byte, part.last_byte))
+        })
+        .await
+        .unwrap();
+        assert_eq!(parts, [(0, 3)]);
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use node::transition_frontier::archive::archive_service::ArchiveUploadStats;
+
+/// Precomputed blocks waiting to be uploaded to a cloud storage, kept on
+/// disk so that uploads that failed, or were interrupted by a restart, are
+/// retried instead of leaving gaps in the bucket.
+///
+/// Each pending upload is a file named after its key. The file is removed
+/// once the upload succeeds. Uploads that keep failing are moved to a
+/// quarantine directory, so that they don't hold back the others, until
+/// they are queued again by [`UploadQueue::release_quarantined`].
+pub(crate) struct UploadQueue {
+    dir: PathBuf,
+}
+
+pub(crate) struct PendingUpload {
+    pub key: String,
+    pub queued_at: SystemTime,
+}
+
+const TMP_EXTENSION: &str = "tmp";
+const QUARANTINE_DIR: &str = "quarantine";
+
+impl UploadQueue {
+    pub fn open(dir: PathBuf) -> io::Result<Self> {
+        fs::create_dir_all(dir.join(QUARANTINE_DIR))?;
+        let queue = Self { dir };
+        queue.release_quarantined()?;
+        Ok(queue)
+    }
+
+    pub fn push(&self, key: &str, data: &[u8]) -> io::Result<()> {
+        // Written to a temporary file first, so that a crash doesn't leave
+        // a truncated block in the queue.
+        let path = self.dir.join(key);
+        let tmp_path = path.with_extension(TMP_EXTENSION);
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+        fs::rename(tmp_path, path)
+    }
+
+    pub fn read(&self, key: &str) -> io::Result<Vec<u8>> {
+        fs::read(self.dir.join(key))
+    }
+
+    pub fn remove(&self, key: &str) -> io::Result<()> {
+        match fs::remove_file(self.dir.join(key)) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        }
+    }
+
+    /// Sets the upload aside, out of the pending uploads.
+    pub fn quarantine(&self, key: &str) -> io::Result<()> {
+        fs::rename(self.dir.join(key), self.dir.join(QUARANTINE_DIR).join(key))
+    }
+
+    /// Moves the quarantined uploads back to the pending ones.
+    pub fn release_quarantined(&self) -> io::Result<()> {
+        for entry in fs::read_dir(self.dir.join(QUARANTINE_DIR))? {
+            let entry = entry?;
+            fs::rename(entry.path(), self.dir.join(entry.file_name()))?;
+        }
+        Ok(())
+    }
+
+    /// Number of uploads set aside by [`Self::quarantine`].
+    pub fn quarantined(&self) -> io::Result<usize> {
+        Ok(fs::read_dir(self.dir.join(QUARANTINE_DIR))?.count())
+    }
+
+    /// Pending uploads, oldest first.
+    pub fn pending(&self) -> io::Result<Vec<PendingUpload>> {
+        let mut pending = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if !entry.file_type()?.is_file()
+                || path.extension().is_some_and(|ext| ext == TMP_EXTENSION)
+            {
+                continue;
+            }
+            let Some(key) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            pending.push(PendingUpload {
+                key: key.to_owned(),
+                queued_at: entry.metadata()?.modified()?,
+            });
+        }
+        pending.sort_by_key(|upload| upload.queued_at);
+        Ok(pending)
+    }
+
+    /// For how long the oldest pending upload has been waiting.
+    pub fn lag(pending: &[PendingUpload]) -> Duration {
+        pending
+            .first()
+            .and_then(|upload| upload.queued_at.elapsed().ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Statistics of the upload queues, shared between the archive thread
+/// uploading the blocks and the node reading them.
+#[derive(Clone, Default)]
+pub(crate) struct UploadStats(Arc<Mutex<BTreeMap<&'static str, UploadQueueStats>>>);
+
+#[derive(Default)]
+struct UploadQueueStats {
+    pending: usize,
+    oldest_queued_at: Option<SystemTime>,
+    quarantined: usize,
+    uploaded: u64,
+    failed: u64,
+    last_error: Option<String>,
+}
+
+impl UploadStats {
+    fn update(&self, storage: &'static str, f: impl FnOnce(&mut UploadQueueStats)) {
+        let mut stats = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        f(stats.entry(storage).or_default());
+    }
+
+    /// A block was added to the queue of `storage`.
+    pub fn queued(&self, storage: &'static str, queued_at: SystemTime) {
+        self.update(storage, |stats| {
+            stats.pending += 1;
+            stats.oldest_queued_at.get_or_insert(queued_at);
+        });
+    }
+
+    /// The queue of `storage` was processed, `remaining` being the uploads
+    /// left in it, and `quarantined` the ones set aside after failing.
+    pub fn processed(
+        &self,
+        storage: &'static str,
+        remaining: &[PendingUpload],
+        quarantined: usize,
+        uploaded: u64,
+        errors: Vec<String>,
+    ) {
+        self.update(storage, |stats| {
+            stats.pending = remaining.len();
+            stats.oldest_queued_at = remaining.first().map(|upload| upload.queued_at);
+            stats.quarantined = quarantined;
+            stats.uploaded += uploaded;
+            stats.failed += errors.len() as u64;
+            if let Some(error) = errors.into_iter().last() {
+                stats.last_error = Some(error);
+            }
+        });
+    }
+
+    pub fn get(&self) -> Vec<ArchiveUploadStats> {
+        let stats = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        stats
+            .iter()
+            .map(|(storage, stats)| ArchiveUploadStats {
+                storage: storage.to_string(),
+                pending: stats.pending,
+                lag_secs: stats
+                    .oldest_queued_at
+                    .and_then(|queued_at| queued_at.elapsed().ok())
+                    .unwrap_or_default()
+                    .as_secs(),
+                quarantined: stats.quarantined,
+                uploaded: stats.uploaded,
+                failed: stats.failed,
+                last_error: stats.last_error.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_uploads_survive_reopening() {
+        let dir = std::env::temp_dir().join(format!(
+            "mina-archive-upload-queue-test-{}",
+            std::process::id()
+        ));
+        let queue = UploadQueue::open(dir.join("aws")).unwrap();
+        queue.push("devnet-1-3NKa.json", b"first").unwrap();
+        queue.push("devnet-2-3NKb.json", b"second").unwrap();
+        queue.remove("devnet-1-3NKa.json").unwrap();
+        // Removing twice isn't an error, the upload may be retried.
+        queue.remove("devnet-1-3NKa.json").unwrap();
+        drop(queue);
+
+        let queue = UploadQueue::open(dir.join("aws")).unwrap();
+        let pending = queue.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].key, "devnet-2-3NKb.json");
+        assert_eq!(queue.read(&pending[0].key).unwrap(), b"second");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_quarantined_uploads_are_released() {
+        let dir = std::env::temp_dir().join(format!(
+            "mina-archive-upload-queue-quarantine-test-{}",
+            std::process::id()
+        ));
+        let queue = UploadQueue::open(dir.join("aws")).unwrap();
+        queue.push("devnet-1-3NKa.json", b"first").unwrap();
+        queue.push("devnet-2-3NKb.json", b"second").unwrap();
+        queue.quarantine("devnet-1-3NKa.json").unwrap();
+
+        let pending = queue.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].key, "devnet-2-3NKb.json");
+        assert_eq!(queue.quarantined().unwrap(), 1);
+
+        queue.release_quarantined().unwrap();
+        assert_eq!(queue.pending().unwrap().len(), 2);
+        assert_eq!(queue.quarantined().unwrap(), 0);
+
+        // Quarantined uploads are also released when the queue is opened.
+        queue.quarantine("devnet-2-3NKb.json").unwrap();
+        drop(queue);
+        let queue = UploadQueue::open(dir.join("aws")).unwrap();
+        assert_eq!(queue.pending().unwrap().len(), 2);
+        assert_eq!(queue.read("devnet-2-3NKb.json").unwrap(), b"second");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_upload_stats() {
+        let stats = UploadStats::default();
+        let queued_at = SystemTime::now() - Duration::from_secs(120);
+        stats.queued("AWS", queued_at);
+        stats.queued("AWS", SystemTime::now());
+
+        let [aws] = &stats.get()[..] else {
+            panic!("expected the stats of one storage");
+        };
+        assert_eq!(aws.pending, 2);
+        assert!(aws.lag_secs >= 120, "lag of the oldest block");
+
+        // The storage is down, the oldest upload failed.
+        let remaining = vec![PendingUpload {
+            key: "devnet-1-3NKa.json".to_owned(),
+            queued_at,
+        }];
+        stats.processed("AWS", &remaining, 0, 1, vec!["timed out".to_owned()]);
+        let aws = &stats.get()[0];
+        assert_eq!((aws.pending, aws.uploaded, aws.failed), (1, 1, 1));
+        assert_eq!(aws.last_error.as_deref(), Some("timed out"));
+        assert!(aws.lag_secs >= 120);
+
+        stats.processed("AWS", &[], 1, 1, vec![]);
+        let aws = &stats.get()[0];
+        assert_eq!((aws.pending, aws.lag_secs, aws.uploaded), (0, 0, 2));
+        assert_eq!(aws.quarantined, 1);
+        assert_eq!(aws.last_error.as_deref(), Some("timed out"));
+    }
+}
@@ -23,6 +23,8 @@ use crate::EventSender;
 pub struct BlockProducerService {
     provers: Option<BlockProver>,
     keypair: AccountSecretKey,
+    /// Blocks get a dummy proof instead of being proven.
+    dummy_proofs: bool,
     vrf_evaluation_sender: mpsc::TrackedUnboundedSender<VrfEvaluatorInput>,
     prove_sender: mpsc::TrackedUnboundedSender<(
         BlockProver,
@@ -45,6 +47,7 @@ impl BlockProducerService {
         Self {
             provers,
             keypair,
+            dummy_proofs: false,
             vrf_evaluation_sender,
             prove_sender,
         }
@@ -54,6 +57,21 @@ impl BlockProducerService {
         event_sender: EventSender,
         keypair: AccountSecretKey,
         provers: Option<BlockProver>,
+    ) -> Self {
+        Self::spawn(event_sender, keypair, provers, false)
+    }
+
+    /// Block producer of a local development chain: it wins every slot and
+    /// attaches dummy proofs to its blocks instead of proving them.
+    pub fn start_dev_local(event_sender: EventSender, keypair: AccountSecretKey) -> Self {
+        Self::spawn(event_sender, keypair, None, true)
+    }
+
+    fn spawn(
+        event_sender: EventSender,
+        keypair: AccountSecretKey,
+        provers: Option<BlockProver>,
+        dev_local: bool,
     ) -> Self {
         let (vrf_evaluation_sender, vrf_evaluation_receiver) = mpsc::unbounded_channel();
         let (prove_sender, prove_receiver) = mpsc::unbounded_channel();
@@ -67,6 +85,7 @@ impl BlockProducerService {
                     event_sender_clone,
                     vrf_evaluation_receiver,
                     producer_keypair.into(),
+                    dev_local,
                 );
             })
             .unwrap();
@@ -77,7 +96,10 @@ impl BlockProducerService {
             .spawn(move || prover_loop(producer_keypair, event_sender, prove_receiver))
             .unwrap();
 
-        BlockProducerService::new(keypair, vrf_evaluation_sender, prove_sender, provers)
+        BlockProducerService {
+            dummy_proofs: dev_local,
+            ..BlockProducerService::new(keypair, vrf_evaluation_sender, prove_sender, provers)
+        }
     }
 
     pub fn keypair(&self) -> AccountSecretKey {
@@ -167,6 +189,17 @@ impl node::service::BlockProducerService for crate::NodeService {
         if self.replayer.is_some() {
             return;
         }
+        let dummy_proofs = self
+            .block_producer
+            .as_ref()
+            .is_some_and(|bp| bp.dummy_proofs);
+        if dummy_proofs {
+            let proof = ledger::dummy::dummy_blockchain_proof();
+            let _ = self
+                .event_sender()
+                .send(BlockProducerEvent::BlockProve(block_hash, Ok(proof)).into());
+            return;
+        }
         let provers = self.provers();
         let _ = self
             .block_producer
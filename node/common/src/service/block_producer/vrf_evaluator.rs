@@ -4,17 +4,17 @@ use node::{
         vrf_evaluator::{VrfEvaluationOutputWithHash, VrfEvaluatorInput},
         BlockProducerEvent, BlockProducerVrfEvaluatorEvent,
     },
-    core::channels::mpsc::{TrackedUnboundedReceiver, UnboundedSender},
-    event_source::Event,
+    core::channels::mpsc::TrackedUnboundedReceiver,
 };
 use vrf::{VrfEvaluationInput, VrfEvaluationOutput};
 
-use crate::NodeService;
+use crate::{EventSender, NodeService};
 
 pub fn vrf_evaluator(
-    event_sender: UnboundedSender<Event>,
+    event_sender: EventSender,
     mut vrf_evaluation_receiver: TrackedUnboundedReceiver<VrfEvaluatorInput>,
     keypair: Keypair,
+    win_every_slot: bool,
 ) {
     while let Some(vrf_evaluator_input) = vrf_evaluation_receiver.blocking_recv() {
         // let bytes = serde_json::to_string(&vrf_evaluator_input).unwrap();
@@ -42,6 +42,11 @@ pub fn vrf_evaluator(
                     total_currency: (*total_currency).into(),
                 };
 
+                if win_every_slot {
+                    let won_slot = vrf::evaluate_vrf_always_won(vrf_input).unwrap();
+                    return Some(VrfEvaluationOutput::SlotWon(won_slot));
+                }
+
                 let vrf_result = vrf::evaluate_vrf(vrf_input).unwrap();
 
                 // the first delegate that won the slot
@@ -1,7 +1,9 @@
-use ledger::proofs::provers::BlockProver;
+use ledger::{ondisk::IoFaults, proofs::provers::BlockProver};
+#[cfg(not(target_arch = "wasm32"))]
+use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
 use node::{
     account::AccountSecretKey,
-    core::channels::mpsc,
+    core::TimeSource,
     ledger::{LedgerCtx, LedgerManager},
     p2p::{
         identity::SecretKey as P2pSecretKey,
@@ -17,15 +19,22 @@ use sha3::{
     digest::{ExtendableOutput, Update},
     Shake256,
 };
+use std::path::PathBuf;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
 
 use crate::{
+    event_channel,
     rpc::{RpcSender, RpcService},
-    EventReceiver, EventSender, NodeService,
+    EventQueueConfig, EventReceiver, EventSender, NodeService,
 };
 
 use super::{
     archive::{config::ArchiveStorageOptions, ArchiveService},
     block_producer::BlockProducerService,
+    peer_list::PeerListService,
+    SnarkVerifyPools, SnarkVerifyPoolsConfig, TransitionFrontierEventCallback,
+    TransitionFrontierEventHook,
 };
 
 pub struct NodeServiceCommonBuilder {
@@ -35,11 +44,22 @@ pub struct NodeServiceCommonBuilder {
     /// `event_source` state machine defined in the `mina-node` crate.
     event_sender: EventSender,
     event_receiver: EventReceiver,
+    event_queue: EventQueueConfig,
+    snark_verify_pools: SnarkVerifyPoolsConfig,
     ledger_manager: Option<LedgerManager>,
     block_producer: Option<BlockProducerService>,
     archive: Option<ArchiveService>,
     p2p: Option<P2pServiceCtx>,
+    p2p_previous_sec_keys: Vec<P2pSecretKey>,
+    peer_list: Option<PeerListService>,
+    transition_frontier_event_hook: Option<TransitionFrontierEventHook>,
     gather_stats: bool,
+    sync_history_path: Option<PathBuf>,
+    zkapp_actions_events_path: Option<PathBuf>,
+    time_source: TimeSource,
+    snark_work_dummy_proofs: bool,
+    snarker_work_token: Option<String>,
+    bootstrap_token: Option<String>,
     rpc: RpcService,
 }
 
@@ -53,18 +73,30 @@ pub enum NodeServiceCommonBuildError {
 
 impl NodeServiceCommonBuilder {
     pub fn new(rng_seed: [u8; 32]) -> Self {
-        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        let event_queue = EventQueueConfig::default();
+        let (event_sender, event_receiver) = event_channel(event_queue);
         Self {
             rng_seed,
             rng: StdRng::from_seed(rng_seed),
             event_sender,
-            event_receiver: event_receiver.into(),
+            event_receiver,
+            event_queue,
+            snark_verify_pools: SnarkVerifyPoolsConfig::default(),
             ledger_manager: None,
             block_producer: None,
             archive: None,
             p2p: None,
+            p2p_previous_sec_keys: Vec::new(),
+            peer_list: None,
+            transition_frontier_event_hook: None,
             rpc: RpcService::new(),
             gather_stats: false,
+            sync_history_path: None,
+            zkapp_actions_events_path: None,
+            time_source: TimeSource::real(),
+            snark_work_dummy_proofs: false,
+            snarker_work_token: None,
+            bootstrap_token: None,
         }
     }
 
@@ -77,11 +109,28 @@ impl NodeServiceCommonBuilder {
     }
 
     pub fn ledger_init(&mut self) -> &mut Self {
+        self.ledger_init_with(LedgerCtx::default())
+    }
+
+    /// Same as [`Self::ledger_init`], with the genesis ledger stored in
+    /// `directory`, see [`LedgerCtx::set_ondisk_ledger`].
+    pub fn ledger_init_ondisk(&mut self, directory: PathBuf, io_faults: IoFaults) -> &mut Self {
         let mut ctx = LedgerCtx::default();
-        ctx.set_event_sender(self.event_sender.clone());
+        ctx.set_ondisk_ledger(directory, io_faults);
+        self.ledger_init_with(ctx)
+    }
+
+    fn ledger_init_with(&mut self, mut ctx: LedgerCtx) -> &mut Self {
+        let event_sender = self.event_sender.clone();
+        ctx.set_event_sender(move |event| {
+            let _ = event_sender.send(event);
+        });
         if self.archive.is_some() {
             ctx.set_archive_mode();
         };
+        if let Some(path) = self.zkapp_actions_events_path.clone() {
+            ctx.set_action_event_store(path);
+        }
         self.ledger_manager = Some(LedgerManager::spawn(ctx));
         self
     }
@@ -99,6 +148,16 @@ impl NodeServiceCommonBuilder {
         self
     }
 
+    /// Block producer of a local development chain, see
+    /// [`BlockProducerService::start_dev_local`].
+    pub fn block_producer_dev_local_init(&mut self, keypair: AccountSecretKey) -> &mut Self {
+        self.block_producer = Some(BlockProducerService::start_dev_local(
+            self.event_sender.clone(),
+            keypair,
+        ));
+        self
+    }
+
     pub fn archive_init(&mut self, options: ArchiveStorageOptions, work_dir: String) -> &mut Self {
         self.archive = Some(ArchiveService::start(options, work_dir));
         self
@@ -117,16 +176,103 @@ impl NodeServiceCommonBuilder {
         self
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn peer_list_init(
+        &mut self,
+        url: reqwest::Url,
+        interval: Option<Duration>,
+        peers: Vec<P2pConnectionOutgoingInitOpts>,
+    ) -> &mut Self {
+        self.peer_list = Some(PeerListService::start(
+            self.event_sender.clone(),
+            url,
+            interval,
+            peers,
+        ));
+        self
+    }
+
     pub fn gather_stats(&mut self) -> &mut Self {
         self.gather_stats = true;
         self
     }
 
+    /// Saves the sync history gathered with the stats to `path`, and loads
+    /// it back from there on start.
+    pub fn sync_history(&mut self, path: PathBuf) -> &mut Self {
+        self.sync_history_path = Some(path);
+        self
+    }
+
+    /// Keeps the actions and events of zkApp commands, saved to `path`.
+    /// Must be called before the ledger is initialized.
+    pub fn zkapp_actions_events(&mut self, path: PathBuf) -> &mut Self {
+        self.zkapp_actions_events_path = Some(path);
+        self
+    }
+
+    pub fn event_queue_config(&mut self, config: EventQueueConfig) -> &mut Self {
+        self.event_queue = config;
+        self
+    }
+
+    /// Source of the time of the node, which can go by faster than the
+    /// wall clock. Nodes meant to stay in sync must be given copies of the
+    /// same source, see [`TimeSource`].
+    pub fn time_source(&mut self, time_source: TimeSource) -> &mut Self {
+        self.time_source = time_source;
+        self
+    }
+
+    /// Makes the snark worker attach dummy proofs to its work, only valid
+    /// on chains whose blocks aren't verified, see
+    /// [`Self::block_producer_dev_local_init`].
+    pub fn snark_work_dummy_proofs(&mut self) -> &mut Self {
+        self.snark_work_dummy_proofs = true;
+        self
+    }
+
+    /// Token sent with the snark work submitted to other nodes.
+    pub fn snarker_work_token(&mut self, token: String) -> &mut Self {
+        self.snarker_work_token = Some(token);
+        self
+    }
+
+    /// Token sent when downloading ledger snapshots to bootstrap from.
+    pub fn bootstrap_token(&mut self, token: String) -> &mut Self {
+        self.bootstrap_token = Some(token);
+        self
+    }
+
+    /// Keys this node used before its p2p key was rotated. Webrtc peers
+    /// which still dial their peer ids are answered with them.
+    pub fn p2p_previous_sec_keys(&mut self, keys: Vec<P2pSecretKey>) -> &mut Self {
+        self.p2p_previous_sec_keys = keys;
+        self
+    }
+
+    pub fn snark_verify_pools_config(&mut self, config: SnarkVerifyPoolsConfig) -> &mut Self {
+        self.snark_verify_pools = config;
+        self
+    }
+
+    /// Calls `callback` on the state machine thread with every change of
+    /// the best tip and of the sync status, see
+    /// [`super::TransitionFrontierEvent`].
+    pub fn transition_frontier_event_hook(
+        &mut self,
+        callback: TransitionFrontierEventCallback,
+    ) -> &mut Self {
+        self.transition_frontier_event_hook = Some(TransitionFrontierEventHook::new(callback));
+        self
+    }
+
     pub fn build(self) -> Result<NodeService, NodeServiceCommonBuildError> {
         let ledger_manager = self
             .ledger_manager
             .ok_or(NodeServiceCommonBuildError::LedgerNotInit)?;
-        let p2p = self.p2p.ok_or(NodeServiceCommonBuildError::P2pNotInit)?;
+        let mut p2p = self.p2p.ok_or(NodeServiceCommonBuildError::P2pNotInit)?;
+        p2p.previous_sec_keys = self.p2p_previous_sec_keys;
 
         Ok(NodeService {
             rng_seed: self.rng_seed,
@@ -140,20 +286,30 @@ impl NodeServiceCommonBuilder {
                 .finalize_xof(),
             rng: self.rng,
             event_sender: self.event_sender.clone(),
-            event_receiver: self.event_receiver,
+            event_receiver: self.event_receiver.with_config(self.event_queue),
             snark_block_proof_verify: NodeService::snark_block_proof_verifier_spawn(
                 self.event_sender,
             ),
+            snark_verify_pools: SnarkVerifyPools::new(self.snark_verify_pools),
             ledger_manager,
             block_producer: self.block_producer,
             // initialized in state machine.
             snark_worker: None,
+            snark_work_dummy_proofs: self.snark_work_dummy_proofs,
+            snarker_work_token: self.snarker_work_token,
+            bootstrap_token: self.bootstrap_token,
             archive: self.archive,
             p2p,
-            stats: self.gather_stats.then(Stats::new),
+            peer_list: self.peer_list,
+            transition_frontier_event_hook: self.transition_frontier_event_hook,
+            stats: self.gather_stats.then(|| match self.sync_history_path {
+                Some(path) => Stats::with_sync_history(path),
+                None => Stats::new(),
+            }),
             rpc: self.rpc,
             recorder: Default::default(),
             replayer: None,
+            time_source: self.time_source,
             invariants_state: Default::default(),
         })
     }
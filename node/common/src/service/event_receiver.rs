@@ -1,49 +1,430 @@
-use node::{core::channels::mpsc, event_source::Event};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard},
+};
 
-pub type EventSender = mpsc::UnboundedSender<Event>;
+use node::{
+    core::channels::mpsc,
+    event_source::{Event, EventPriority, EventsDropped},
+    p2p::{service_impl::P2pEventSender, PeerId},
+};
+use serde::{Deserialize, Serialize};
+
+const PRIORITIES: [EventPriority; 4] = [
+    EventPriority::Critical,
+    EventPriority::Block,
+    EventPriority::Work,
+    EventPriority::Transaction,
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct EventQueueConfig {
+    /// Max number of events with priority lower than
+    /// [`EventPriority::Critical`] buffered in the queue. Once it is
+    /// reached, such events are replaced by a shed marker, which keeps the
+    /// peer connected. Critical events aren't counted, the ones coming from
+    /// peers are bounded by the protocols they belong to.
+    pub capacity: usize,
+    pub shed_policy: EventShedPolicy,
+}
+
+/// Which event of the lowest non-empty priority gets dropped on overload.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EventShedPolicy {
+    #[default]
+    DropOldest,
+    DropNewest,
+}
+
+impl FromStr for EventShedPolicy {
+    type Err = EventShedPolicyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "drop-oldest" => Self::DropOldest,
+            "drop-newest" => Self::DropNewest,
+            other => return Err(EventShedPolicyParseError(other.to_owned())),
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("invalid shed policy: {0}! expected one of: drop-oldest/drop-newest")]
+pub struct EventShedPolicyParseError(String);
+
+impl Default for EventQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 10_000,
+            shed_policy: EventShedPolicy::default(),
+        }
+    }
+}
+
+/// Creates the event queue, shared between the senders, which push events
+/// into it right away, and the receiver.
+pub fn event_channel(config: EventQueueConfig) -> (EventSender, EventReceiver) {
+    let queue = Arc::new(Mutex::new(EventQueue::new(config)));
+    // A single pending wake up is enough, as the receiver handles all the
+    // queued events once woken up.
+    let (notify_tx, notify_rx) = mpsc::channel(1);
+    let sender = EventSender {
+        queue: queue.clone(),
+        notify: notify_tx,
+    };
+    let receiver = EventReceiver {
+        queue,
+        notify: notify_rx,
+    };
+    (sender, receiver)
+}
+
+#[derive(Clone)]
+pub struct EventSender {
+    queue: Arc<Mutex<EventQueue>>,
+    notify: mpsc::Sender<()>,
+}
 
 pub struct EventReceiver {
-    rx: mpsc::UnboundedReceiver<Event>,
-    queue: Vec<Event>,
+    queue: Arc<Mutex<EventQueue>>,
+    notify: mpsc::Receiver<()>,
+}
+
+struct EventQueue {
+    config: EventQueueConfig,
+    /// Queued events, indexed by [`EventPriority`], in the order they were
+    /// received.
+    queues: [VecDeque<QueuedEvent>; 4],
+    /// Number of events popped from each queue, which is the index of its
+    /// front event.
+    heads: [u64; 4],
+    /// Queue and index of the events which can be shed, per priority, in
+    /// the order they were received. May still hold events which were
+    /// handled since, those are skipped.
+    sheddable: [VecDeque<(usize, u64)>; 4],
+    sheddable_len: usize,
+    /// Number of queued events of a WebRTC peer, per queue, for the peers
+    /// with events in a queue other than the critical one.
+    peers: BTreeMap<PeerId, [usize; 4]>,
+    dropped: EventsDropped,
+}
+
+struct QueuedEvent {
+    event: Event,
+    /// Set if counted in [`EventQueue::peers`].
+    peer_id: Option<PeerId>,
+    /// Priority of the event, as long as it can be shed.
+    sheddable: Option<EventPriority>,
+}
+
+fn lock(queue: &Mutex<EventQueue>) -> MutexGuard<'_, EventQueue> {
+    queue.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+impl EventSender {
+    /// Queues the event, shedding lower priority ones if the queue is full.
+    /// Fails if the [`EventReceiver`] was dropped.
+    pub fn send(&self, event: Event) -> Result<(), mpsc::SendError<Event>> {
+        if self.notify.is_disconnected() {
+            return Err(mpsc::SendError(event));
+        }
+        lock(&self.queue).push(event);
+        // Only fails if a wake up is already pending.
+        let _ = self.notify.try_send(());
+        Ok(())
+    }
+}
+
+impl P2pEventSender<Event> for EventSender {
+    fn send(&self, event: Event) -> Result<(), mpsc::SendError<Event>> {
+        EventSender::send(self, event)
+    }
 }
 
 impl EventReceiver {
+    pub fn with_config(self, config: EventQueueConfig) -> Self {
+        lock(&self.queue).config = config;
+        self
+    }
+
     pub fn is_empty(&self) -> bool {
         !self.has_next()
     }
 
     pub fn len(&self) -> usize {
-        self.rx.len() + self.queue.len()
+        lock(&self.queue).len()
     }
 
-    /// If `Err(())`, `mpsc::Sender` for this channel was dropped.
+    pub fn dropped_events(&self) -> EventsDropped {
+        lock(&self.queue).dropped
+    }
+
+    /// If `Err(())`, all the [`EventSender`]s of this queue were dropped.
     pub async fn wait_for_events(&mut self) -> Result<(), ()> {
-        if !self.queue.is_empty() {
-            return Ok(());
+        loop {
+            if self.has_next() {
+                return Ok(());
+            }
+            self.notify.recv().await.ok_or(())?;
         }
-        let next = self.rx.recv().await.ok_or(())?;
-        self.queue.push(next);
-        Ok(())
     }
 
     pub fn has_next(&self) -> bool {
-        !self.queue.is_empty() || !self.rx.is_empty()
+        lock(&self.queue).len() > 0
     }
 
+    /// Returns the next event with the highest priority. Events of a WebRTC
+    /// peer are queued behind its earlier events of lower priority, so they
+    /// are returned in the order they were received.
     pub fn try_next(&mut self) -> Option<Event> {
-        if !self.queue.is_empty() {
-            Some(self.queue.remove(0))
-        } else {
-            self.rx.try_recv().ok()
-        }
+        lock(&self.queue).pop()
     }
 }
 
-impl From<mpsc::UnboundedReceiver<Event>> for EventReceiver {
-    fn from(rx: mpsc::UnboundedReceiver<Event>) -> Self {
+impl EventQueue {
+    fn new(config: EventQueueConfig) -> Self {
         Self {
-            rx,
-            queue: Vec::with_capacity(1),
+            config,
+            queues: Default::default(),
+            heads: Default::default(),
+            sheddable: Default::default(),
+            sheddable_len: 0,
+            peers: Default::default(),
+            dropped: Default::default(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+
+    fn push(&mut self, event: Event) {
+        let priority = event.priority();
+        let peer_id = event.webrtc_peer_id();
+        let queue = peer_id
+            .and_then(|peer_id| self.peers.get(&peer_id))
+            .and_then(|counts| counts.iter().rposition(|count| *count > 0))
+            .map_or(priority as usize, |queue| queue.max(priority as usize));
+        let peer_id = peer_id.filter(|_| queue != EventPriority::Critical as usize);
+        if let Some(peer_id) = peer_id {
+            self.peers.entry(peer_id).or_default()[queue] += 1;
+        }
+
+        let sheddable = (priority != EventPriority::Critical).then_some(priority);
+        if sheddable.is_some() {
+            let index = self.heads[queue] + self.queues[queue].len() as u64;
+            self.sheddable[priority as usize].push_back((queue, index));
+            self.sheddable_len += 1;
+        }
+        self.queues[queue].push_back(QueuedEvent {
+            event,
+            peer_id,
+            sheddable,
+        });
+
+        while self.sheddable_len > self.config.capacity {
+            if !self.shed() {
+                break;
+            }
+        }
+    }
+
+    fn pop(&mut self) -> Option<Event> {
+        let queue = self.queues.iter().position(|queue| !queue.is_empty())?;
+        let queued = self.queues[queue].pop_front()?;
+        self.heads[queue] += 1;
+
+        if let Some(peer_id) = queued.peer_id {
+            if let Some(counts) = self.peers.get_mut(&peer_id) {
+                counts[queue] = counts[queue].saturating_sub(1);
+                if counts.iter().all(|count| *count == 0) {
+                    self.peers.remove(&peer_id);
+                }
+            }
+        }
+        if let Some(priority) = queued.sheddable {
+            self.sheddable_len -= 1;
+            let heads = self.heads;
+            let sheddable = &mut self.sheddable[priority as usize];
+            while sheddable
+                .front()
+                .is_some_and(|(queue, index)| *index < heads[*queue])
+            {
+                sheddable.pop_front();
+            }
+        }
+        Some(queued.event)
+    }
+
+    /// Replaces a queued event of the lowest priority by a shed marker, in
+    /// place, so that it is still handled in order. Returns `false` if
+    /// there is no event to shed.
+    fn shed(&mut self) -> bool {
+        for priority in PRIORITIES.into_iter().skip(1).rev() {
+            loop {
+                let sheddable = &mut self.sheddable[priority as usize];
+                let next = match self.config.shed_policy {
+                    EventShedPolicy::DropOldest => sheddable.pop_front(),
+                    EventShedPolicy::DropNewest => sheddable.pop_back(),
+                };
+                let Some((queue, index)) = next else {
+                    break;
+                };
+                let queued = index
+                    .checked_sub(self.heads[queue])
+                    .and_then(|index| self.queues[queue].get_mut(index as usize));
+                // Otherwise already handled.
+                let Some(queued) = queued else {
+                    continue;
+                };
+                if let Some(replacement) = queued.event.shed() {
+                    queued.event = replacement;
+                }
+                queued.sheddable = None;
+                self.sheddable_len -= 1;
+                self.dropped.increment(priority);
+                return true;
+            }
         }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_p2p_messages::v2::TransactionHash;
+    use node::{
+        core::transaction::TransactionInfo,
+        p2p::{
+            channels::{transaction::TransactionPropagationChannelMsg, ChannelId, ChannelMsg},
+            P2pChannelEvent, P2pEvent,
+        },
+    };
+
+    use super::*;
+
+    fn channel(capacity: usize, shed_policy: EventShedPolicy) -> (EventSender, EventReceiver) {
+        event_channel(EventQueueConfig {
+            capacity,
+            shed_policy,
+        })
+    }
+
+    fn received(peer_id: PeerId, msg: TransactionPropagationChannelMsg) -> Event {
+        let msg = ChannelMsg::TransactionPropagation(msg);
+        P2pEvent::Channel(P2pChannelEvent::Received(peer_id, Ok(msg))).into()
+    }
+
+    fn transaction(peer_id: PeerId, n: u8) -> Event {
+        let info = TransactionInfo {
+            hash: TransactionHash::from(&[n; 32]),
+            fee_payer: "B62qmnY6m4c6bdgSPnQGZriSaj9vuSjsfh6qkveGTsFX3yGA5ywRaja"
+                .parse()
+                .unwrap(),
+            fee: 1_000_000,
+            nonce: n.into(),
+        };
+        received(peer_id, TransactionPropagationChannelMsg::Transaction(info))
+    }
+
+    fn is_transaction(event: &Event, n: u8) -> bool {
+        matches!(
+            event,
+            Event::P2p(P2pEvent::Channel(P2pChannelEvent::Received(
+                _,
+                Ok(ChannelMsg::TransactionPropagation(
+                    TransactionPropagationChannelMsg::Transaction(info)
+                )),
+            ))) if info.nonce == n.into()
+        )
+    }
+
+    fn is_shed(event: &Event, peer: PeerId) -> bool {
+        matches!(
+            event,
+            Event::P2p(P2pEvent::Channel(P2pChannelEvent::Shed(
+                peer_id,
+                ChannelId::TransactionPropagation,
+                1,
+            ))) if *peer_id == peer
+        )
+    }
+
+    #[test]
+    fn sheds_payloads_without_disconnecting() {
+        let (tx, mut receiver) = channel(2, EventShedPolicy::DropOldest);
+        let peer_id = PeerId::from_bytes([1; 32]);
+
+        let will_send = TransactionPropagationChannelMsg::WillSend { count: 3 };
+        tx.send(received(peer_id, will_send)).unwrap();
+        for n in 0..3 {
+            tx.send(transaction(peer_id, n)).unwrap();
+        }
+        tx.send(Event::GenesisLoad(Err("test".to_owned()))).unwrap();
+
+        // Control messages are never shed, and the shed transaction is
+        // replaced in place, so the channel still gets all 3 of them.
+        let events = std::iter::from_fn(|| receiver.try_next()).collect::<Vec<_>>();
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[0].priority(), EventPriority::Critical);
+        assert!(matches!(events[1], Event::GenesisLoad(_)));
+        assert!(is_shed(&events[2], peer_id));
+        assert!(is_transaction(&events[3], 1));
+        assert!(is_transaction(&events[4], 2));
+
+        let dropped = receiver.dropped_events();
+        assert_eq!((dropped.block, dropped.transaction), (0, 1));
+        assert!(receiver.is_empty());
+    }
+
+    #[test]
+    fn drop_newest_sheds_latest() {
+        let (tx, mut receiver) = channel(1, EventShedPolicy::DropNewest);
+        let peer_id = PeerId::from_bytes([1; 32]);
+
+        for n in 0..3 {
+            tx.send(transaction(peer_id, n)).unwrap();
+        }
+
+        let events = std::iter::from_fn(|| receiver.try_next()).collect::<Vec<_>>();
+        assert_eq!(events.len(), 3);
+        assert!(is_transaction(&events[0], 0));
+        assert!(is_shed(&events[1], peer_id));
+        assert!(is_shed(&events[2], peer_id));
+        assert_eq!(receiver.dropped_events().transaction, 2);
+    }
+
+    #[test]
+    fn keeps_order_within_peer() {
+        let (tx, mut receiver) = channel(10, EventShedPolicy::DropOldest);
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let other = PeerId::from_bytes([2; 32]);
+
+        tx.send(transaction(peer_id, 0)).unwrap();
+        let closed = P2pChannelEvent::Closed(peer_id, ChannelId::TransactionPropagation);
+        tx.send(P2pEvent::Channel(closed).into()).unwrap();
+        let get_next = TransactionPropagationChannelMsg::GetNext { limit: 1 };
+        tx.send(received(other, get_next)).unwrap();
+
+        // Critical events jump ahead of gossip of other peers, but not of
+        // the gossip of their own peer received before them.
+        let events = std::iter::from_fn(|| receiver.try_next()).collect::<Vec<_>>();
+        assert!(matches!(
+            &events[..],
+            [
+                Event::P2p(P2pEvent::Channel(P2pChannelEvent::Received(p1, Ok(_)))),
+                Event::P2p(P2pEvent::Channel(P2pChannelEvent::Received(p2, Ok(_)))),
+                Event::P2p(P2pEvent::Channel(P2pChannelEvent::Closed(p3, _))),
+            ] if *p1 == other && *p2 == peer_id && *p3 == peer_id
+        ));
+    }
+
+    #[test]
+    fn send_fails_once_receiver_dropped() {
+        let (tx, receiver) = channel(10, EventShedPolicy::DropOldest);
+        tx.send(Event::GenesisLoad(Err("test".to_owned()))).unwrap();
+        drop(receiver);
+        assert!(tx.send(Event::GenesisLoad(Err("test".to_owned()))).is_err());
     }
 }
@@ -0,0 +1,217 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom, Write},
+    mem::{size_of, take},
+    sync::Arc,
+    time::Duration,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use anyhow::Context;
+#[cfg(not(target_arch = "wasm32"))]
+use ledger::{Account, BaseLedger, Mask};
+#[cfg(not(target_arch = "wasm32"))]
+use mina_p2p_messages::{
+    bigint::InvalidBigInt,
+    binprot::{BinProtRead, Nat0},
+    v2::{LedgerHash, MinaBaseAccountBinableArgStableV2},
+};
+use node::transition_frontier::sync::ledger::{
+    LedgerSnapshotEvent, LedgerSnapshotQuery, LedgerSnapshotSource,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use node::{
+    core::thread,
+    ledger::{ledger_depth, LedgerAccountIndex, LedgerAccountsImporter, LedgerAddress},
+    p2p::{channels::rpc::StagedLedgerAuxAndPendingCoinbases, identity::Signature},
+    transition_frontier::sync::ledger::{
+        LedgerSnapshotDigest, LedgerSnapshotImported, LEDGER_SNAPSHOT_SIGNATURE_HEADER,
+    },
+};
+
+use super::NodeService;
+
+/// Snapshots of mainnet-sized ledgers take a while to serve and download.
+#[cfg(not(target_arch = "wasm32"))]
+const LEDGER_SNAPSHOT_FETCH_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Height of the subtrees in which snapshot accounts are written into the
+/// scratch ledger, so that only that many accounts are decoded at once.
+#[cfg(not(target_arch = "wasm32"))]
+const LEDGER_SNAPSHOT_CHUNK_HEIGHT: usize = 12;
+
+impl node::service::TransitionFrontierSyncLedgerSnapshotService for NodeService {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn ledger_snapshot_fetch(&mut self, source: &LedgerSnapshotSource, query: LedgerSnapshotQuery) {
+        let event_sender = self.event_sender.clone();
+        let source = source.clone();
+        let token = self.bootstrap_token.clone();
+        let importer = self.ledger_manager.accounts_importer();
+        thread::Builder::new()
+            .name("mina_ledger_snapshot".to_owned())
+            .spawn(move || {
+                let url = source.url.trim_end_matches('/');
+                let fetch = |path: String| {
+                    let url = format!("{url}/bootstrap/{path}");
+                    fetch_ledger_snapshot(&url, token.as_deref(), &source, query.clone())
+                        .with_context(|| format!("fetching ledger snapshot {url}"))
+                };
+                let event = match query.clone() {
+                    LedgerSnapshotQuery::SnarkedLedger(ledger_hash) => {
+                        let result =
+                            fetch(format!("snarked-ledger/{ledger_hash}")).and_then(|file| {
+                                import_snarked_ledger(file, &importer, ledger_hash.clone())
+                            });
+                        LedgerSnapshotEvent::SnarkedLedger {
+                            ledger_hash,
+                            result: result.map_err(|err| format!("{err:#}")),
+                        }
+                    }
+                    LedgerSnapshotQuery::StagedLedgerParts(block_hash) => {
+                        let result =
+                            fetch(format!("staged-ledger-parts/{block_hash}")).and_then(|file| {
+                                let mut reader = BufReader::new(file);
+                                Ok(StagedLedgerAuxAndPendingCoinbases::binprot_read(
+                                    &mut reader,
+                                )?)
+                            });
+                        LedgerSnapshotEvent::StagedLedgerParts {
+                            block_hash,
+                            result: result.map(Arc::new).map_err(|err| format!("{err:#}")),
+                        }
+                    }
+                };
+                let _ = event_sender.send(event.into());
+            })
+            .unwrap();
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn ledger_snapshot_fetch(
+        &mut self,
+        _source: &LedgerSnapshotSource,
+        query: LedgerSnapshotQuery,
+    ) {
+        let error = "bootstrapping from a trusted node isn't supported".to_owned();
+        let event = match query {
+            LedgerSnapshotQuery::SnarkedLedger(ledger_hash) => LedgerSnapshotEvent::SnarkedLedger {
+                ledger_hash,
+                result: Err(error),
+            },
+            LedgerSnapshotQuery::StagedLedgerParts(block_hash) => {
+                LedgerSnapshotEvent::StagedLedgerParts {
+                    block_hash,
+                    result: Err(error),
+                }
+            }
+        };
+        let _ = self.event_sender.send(event.into());
+    }
+}
+
+/// Downloads a snapshot into a temporary file, and checks that it was
+/// signed by the node we bootstrap from. Returns the file positioned at
+/// the binprot encoded snapshot, after its length prefix.
+#[cfg(not(target_arch = "wasm32"))]
+fn fetch_ledger_snapshot(
+    url: &str,
+    token: Option<&str>,
+    source: &LedgerSnapshotSource,
+    query: LedgerSnapshotQuery,
+) -> anyhow::Result<File> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(LEDGER_SNAPSHOT_FETCH_TIMEOUT)
+        .build()?;
+    let mut request = client.get(url);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let mut response = request.send()?.error_for_status()?;
+
+    let signature: Signature = response
+        .headers()
+        .get(LEDGER_SNAPSHOT_SIGNATURE_HEADER)
+        .context("snapshot isn't signed")?
+        .to_str()?
+        .parse()
+        .context("invalid snapshot signature")?;
+
+    let mut file = tempfile::tempfile()?;
+    let mut digest = LedgerSnapshotDigest::new(query);
+    let mut buf = vec![0; 1 << 16];
+    loop {
+        let read = response.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        digest.update(&buf[..read]);
+        file.write_all(&buf[..read])?;
+    }
+
+    let public_key = source
+        .peer_id
+        .to_public_key()
+        .map_err(|err| anyhow::anyhow!("invalid bootstrap peer id: {err}"))?;
+    if !digest.verify(&public_key, &signature) {
+        anyhow::bail!("snapshot isn't signed by {}", source.peer_id);
+    }
+
+    file.seek(SeekFrom::Start(size_of::<u64>() as u64))?;
+    Ok(file)
+}
+
+/// Reads the accounts of a snarked ledger snapshot into a scratch ledger,
+/// a subtree at a time. The ledger being synced is replaced with it only
+/// if its root is `ledger_hash`, so a bad snapshot leaves it untouched.
+#[cfg(not(target_arch = "wasm32"))]
+fn import_snarked_ledger(
+    file: File,
+    importer: &LedgerAccountsImporter,
+    ledger_hash: LedgerHash,
+) -> anyhow::Result<LedgerSnapshotImported> {
+    let chunk_size: usize = 1 << LEDGER_SNAPSHOT_CHUNK_HEIGHT;
+    let chunk_depth = ledger_depth() - LEDGER_SNAPSHOT_CHUNK_HEIGHT;
+    let mut mask = Mask::create(ledger_depth());
+    let mut import = |index: u64, accounts: Vec<MinaBaseAccountBinableArgStableV2>| {
+        let address = LedgerAddress::from_index(LedgerAccountIndex(index), chunk_depth);
+        let accounts = accounts
+            .iter()
+            .map(|account| Ok(Box::new(account.try_into()?)))
+            .collect::<Result<Vec<Box<Account>>, InvalidBigInt>>()
+            .context("decoding accounts")?;
+        mask.set_all_accounts_rooted_at(address, &accounts)
+            .map_err(|_| anyhow::anyhow!("failed to set accounts"))
+    };
+
+    let mut reader = BufReader::new(file);
+    let Nat0(count) = Nat0::binprot_read(&mut reader).context("reading account count")?;
+    if count == 0 || count > 1 << ledger_depth() {
+        anyhow::bail!("snapshot has {count} accounts");
+    }
+    let mut chunk = Vec::with_capacity(chunk_size.min(count as usize));
+    for i in 0..count {
+        let account = MinaBaseAccountBinableArgStableV2::binprot_read(&mut reader)
+            .with_context(|| format!("reading account {i}"))?;
+        chunk.push(account);
+        if chunk.len() == chunk_size {
+            import(i / chunk_size as u64, take(&mut chunk))?;
+        }
+    }
+    if !chunk.is_empty() {
+        import(count / chunk_size as u64, chunk)?;
+    }
+
+    // A mismatch is reported by the state machine, which rejects the
+    // snapshot, so the ledger is only committed when its root matches.
+    let computed_hash = LedgerHash::from_fp(mask.merkle_root());
+    if computed_hash == ledger_hash {
+        importer
+            .snarked_ledger_import(ledger_hash, mask)
+            .map_err(anyhow::Error::msg)?;
+    }
+    Ok(LedgerSnapshotImported {
+        computed_hash,
+        count,
+    })
+}
@@ -3,12 +3,19 @@ pub use event_receiver::*;
 
 pub mod archive;
 pub mod block_producer;
+mod ledger_snapshot;
 pub mod p2p;
+pub mod peer_list;
+mod precomputed_block;
 pub mod record;
 pub mod replay;
 pub mod rpc;
 pub mod snark_worker;
+mod snark_worker_coordinator;
 mod snarks;
+pub use snarks::{SnarkVerifyPools, SnarkVerifyPoolsConfig};
+mod transition_frontier_events;
+pub use transition_frontier_events::*;
 
 mod builder;
 pub use builder::*;
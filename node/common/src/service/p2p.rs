@@ -16,10 +16,11 @@ use sha3::digest::XofReader;
 
 pub use node::p2p::{service::*, service_impl::*};
 
-use crate::NodeService;
+use crate::{EventSender, NodeService};
 
 impl webrtc::P2pServiceWebrtc for NodeService {
     type Event = Event;
+    type EventSender = EventSender;
 
     fn random_pick(
         &mut self,
@@ -28,7 +29,7 @@ impl webrtc::P2pServiceWebrtc for NodeService {
         list.choose(&mut self.rng).cloned()
     }
 
-    fn event_sender(&self) -> &mpsc::UnboundedSender<Self::Event> {
+    fn event_sender(&self) -> &Self::EventSender {
         self.event_sender()
     }
 
@@ -74,7 +75,8 @@ impl webrtc::P2pServiceWebrtc for NodeService {
         other_pub_key: &PublicKey,
         auth: ConnectionAuth,
     ) {
-        let encrypted = auth.encrypt(&self.p2p.sec_key, other_pub_key, &mut self.rng);
+        let sec_key = self.p2p.sec_key_for(&peer_id);
+        let encrypted = auth.encrypt(sec_key, other_pub_key, &mut self.rng);
         Self::auth_send(self, peer_id, other_pub_key, encrypted);
     }
 
@@ -83,7 +85,10 @@ impl webrtc::P2pServiceWebrtc for NodeService {
         other_pub_key: &PublicKey,
         auth: node::p2p::webrtc::ConnectionAuthEncrypted,
     ) -> Option<ConnectionAuth> {
-        auth.decrypt(&self.p2p.sec_key, other_pub_key)
+        auth.decrypt(
+            self.p2p.sec_key_for(&other_pub_key.peer_id()),
+            other_pub_key,
+        )
     }
 }
 
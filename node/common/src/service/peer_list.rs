@@ -0,0 +1,127 @@
+use std::sync::mpsc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    io::{BufRead, BufReader, Read},
+    sync::mpsc::RecvTimeoutError,
+    time::Duration,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use anyhow::Context;
+use node::rpc::RpcId;
+#[cfg(not(target_arch = "wasm32"))]
+use node::{
+    core::thread,
+    p2p::{
+        connection::outgoing::{P2pConnectionOutgoingInitOpts, P2pConnectionOutgoingPeerAddr},
+        peer::{P2pPeerListDiff, P2pPeerListEvent},
+    },
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use super::EventSender;
+use super::NodeService;
+
+/// Refetches the peer list from the url the node was started with, and
+/// sends the changes to the state machine.
+pub struct PeerListService {
+    refresh_sender: mpsc::Sender<Option<RpcId>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PeerListService {
+    /// `peers` is the list fetched when the node was started. Without
+    /// `interval` the list is only refetched when requested.
+    pub fn start(
+        event_sender: EventSender,
+        url: reqwest::Url,
+        interval: Option<Duration>,
+        peers: Vec<P2pConnectionOutgoingInitOpts>,
+    ) -> Self {
+        let (refresh_sender, refresh_receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("mina_peer_list".to_owned())
+            .spawn(move || Self::run(event_sender, refresh_receiver, url, interval, peers))
+            .unwrap();
+        Self { refresh_sender }
+    }
+
+    fn run(
+        event_sender: EventSender,
+        refresh_receiver: mpsc::Receiver<Option<RpcId>>,
+        url: reqwest::Url,
+        interval: Option<Duration>,
+        mut peers: Vec<P2pConnectionOutgoingInitOpts>,
+    ) {
+        loop {
+            let rpc_id = match interval {
+                Some(interval) => match refresh_receiver.recv_timeout(interval) {
+                    Ok(rpc_id) => rpc_id,
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                },
+                None => match refresh_receiver.recv() {
+                    Ok(rpc_id) => rpc_id,
+                    Err(_) => return,
+                },
+            };
+
+            // `/dnsaddr` entries are only read from the first fetch, see
+            // `NodeBuilder::initial_peers_from_url`.
+            let result = fetch_peer_list(url.clone())
+                .map(|addrs| P2pConnectionOutgoingPeerAddr::partition(addrs).0)
+                .map(|new_peers| {
+                    let diff = P2pPeerListDiff::new(&peers, &new_peers);
+                    peers = new_peers;
+                    diff
+                })
+                .map_err(|err| format!("{err:#}"));
+            let event = P2pPeerListEvent { rpc_id, result };
+            if event_sender.send(event.into()).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl node::service::P2pPeerListService for NodeService {
+    fn peer_list_refresh(&mut self, rpc_id: Option<RpcId>) -> Result<(), String> {
+        let Some(peer_list) = &self.peer_list else {
+            return Err("node isn't configured with a peer list url".to_owned());
+        };
+        peer_list
+            .refresh_sender
+            .send(rpc_id)
+            .map_err(|_| "peer list service isn't running".to_owned())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn fetch_peer_list(url: reqwest::Url) -> anyhow::Result<Vec<P2pConnectionOutgoingPeerAddr>> {
+    let response = reqwest::blocking::get(url.clone())
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("reading peer list url {url}"))?;
+    read_peer_list(response).with_context(|| format!("reading peer list url {url}"))
+}
+
+/// Parses a peer list with one peer address per line.
+///
+/// Entries that can't be parsed are skipped. Host names and `/dnsaddr`
+/// domains are resolved by the state machine.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_peer_list(read: impl Read) -> anyhow::Result<Vec<P2pConnectionOutgoingPeerAddr>> {
+    let mut peers = Vec::new();
+    let reader = BufReader::new(read);
+    for line in reader.lines() {
+        let line = line.context("reading line")?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        match trimmed.parse::<P2pConnectionOutgoingPeerAddr>() {
+            Ok(addr) => peers.push(addr),
+            Err(e) => mina_core::warn!("Peer address parse error: {:?}", e),
+        }
+    }
+    Ok(peers)
+}
@@ -0,0 +1,122 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::{sync::Arc, time::Duration};
+
+#[cfg(not(target_arch = "wasm32"))]
+use anyhow::Context;
+#[cfg(not(target_arch = "wasm32"))]
+use mina_core::NetworkConfig;
+#[cfg(not(target_arch = "wasm32"))]
+use mina_p2p_messages::v2;
+use mina_p2p_messages::v2::StateHash;
+#[cfg(not(target_arch = "wasm32"))]
+use node::core::{block::ArcBlockWithHash, thread};
+use node::transition_frontier::sync::PrecomputedBlockEvent;
+
+use super::NodeService;
+
+#[cfg(not(target_arch = "wasm32"))]
+const PRECOMPUTED_BLOCK_FETCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+impl node::service::TransitionFrontierSyncPrecomputedBlockService for NodeService {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn precomputed_block_fetch(&mut self, url: &str, height: u32, block_hash: StateHash) {
+        let event_sender = self.event_sender.clone();
+        let url = precomputed_block_url(url, height, &block_hash);
+        thread::Builder::new()
+            .name("mina_precomputed_block".to_owned())
+            .spawn(move || {
+                let result = url
+                    .and_then(|url| fetch_precomputed_block(&url))
+                    .map_err(|err| format!("{err:#}"));
+                let event = PrecomputedBlockEvent { block_hash, result };
+                let _ = event_sender.send(event.into());
+            })
+            .unwrap();
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn precomputed_block_fetch(&mut self, _url: &str, _height: u32, block_hash: StateHash) {
+        let event = PrecomputedBlockEvent {
+            block_hash,
+            result: Err("fetching precomputed blocks isn't supported".to_owned()),
+        };
+        let _ = self.event_sender.send(event.into());
+    }
+}
+
+/// Url of the block in the bucket, named as archive nodes upload it.
+///
+/// Public `gs://` and `s3://` buckets are accessed through their HTTPS
+/// endpoints.
+#[cfg(not(target_arch = "wasm32"))]
+fn precomputed_block_url(
+    bucket_url: &str,
+    height: u32,
+    block_hash: &StateHash,
+) -> anyhow::Result<String> {
+    let bucket_url = bucket_url.trim_end_matches('/');
+    let base = if let Some(path) = bucket_url.strip_prefix("gs://") {
+        format!("https://storage.googleapis.com/{path}")
+    } else if let Some(path) = bucket_url.strip_prefix("s3://") {
+        let (bucket, prefix) = path.split_once('/').unwrap_or((path, ""));
+        format!("https://{bucket}.s3.amazonaws.com/{prefix}")
+            .trim_end_matches('/')
+            .to_owned()
+    } else if bucket_url.starts_with("http://") || bucket_url.starts_with("https://") {
+        bucket_url.to_owned()
+    } else {
+        anyhow::bail!("unsupported precomputed blocks url {bucket_url}");
+    };
+    let network_name = NetworkConfig::global().name;
+    Ok(format!("{base}/{network_name}-{height}-{block_hash}.json"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn fetch_precomputed_block(url: &str) -> anyhow::Result<ArcBlockWithHash> {
+    let bytes = reqwest::blocking::Client::builder()
+        .timeout(PRECOMPUTED_BLOCK_FETCH_TIMEOUT)
+        .build()
+        .and_then(|client| client.get(url).send())
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.bytes())
+        .with_context(|| format!("fetching precomputed block {url}"))?;
+    // Blocks dumped by the OCaml node are wrapped with their version.
+    let block = serde_json::from_slice::<v2::PrecomputedBlockData>(&bytes)
+        .map(|block| block.data)
+        .or_else(|_| serde_json::from_slice::<v2::PrecomputedBlock>(&bytes))
+        .with_context(|| format!("parsing precomputed block {url}"))?;
+    ArcBlockWithHash::try_new(Arc::new(block.into()))
+        .map_err(|err| anyhow::anyhow!("hashing precomputed block {url}: {err:?}"))
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_precomputed_block_url() {
+        let hash =
+            StateHash::from_str("3NK56ZbCS31qb8SvCtCCYza4beRDtKgXA2JL6s3evKouG2KkKtiy").unwrap();
+        let network_name = NetworkConfig::global().name;
+        let url = |bucket_url| precomputed_block_url(bucket_url, 42, &hash).unwrap();
+        assert_eq!(
+            url("gs://mina_network_block_data/"),
+            format!("https://storage.googleapis.com/mina_network_block_data/{network_name}-42-{hash}.json")
+        );
+        assert_eq!(
+            url("s3://blocks/precomputed"),
+            format!("https://blocks.s3.amazonaws.com/precomputed/{network_name}-42-{hash}.json")
+        );
+        assert_eq!(
+            url("s3://blocks"),
+            format!("https://blocks.s3.amazonaws.com/{network_name}-42-{hash}.json")
+        );
+        assert_eq!(
+            url("http://localhost:8080/blocks"),
+            format!("http://localhost:8080/blocks/{network_name}-42-{hash}.json")
+        );
+        assert!(precomputed_block_url("ftp://blocks", 42, &hash).is_err());
+    }
+}
@@ -8,16 +8,29 @@ pub mod transaction_pool;
 pub mod transition_frontier;
 
 use node::rpc::{
-    RpcBestChainResponse, RpcBlockProducerStatsGetResponse, RpcConsensusConstantsGetResponse,
+    RpcArchiveUploadStatsGetResponse, RpcBestChainPageGetResponse, RpcBestChainResponse,
+    RpcBlockInjectResponse, RpcBlockProducerStatsGetResponse, RpcBlockSummaryGetResponse,
+    RpcConsensusConstantsGetResponse, RpcConsensusForkDecisionsGetResponse,
     RpcConsensusTimeGetResponse, RpcDiscoveryBoostrapStatsResponse,
-    RpcDiscoveryRoutingTableResponse, RpcGenesisBlockResponse, RpcGetBlockResponse,
-    RpcHealthCheckResponse, RpcHeartbeatGetResponse, RpcLedgerAccountDelegatorsGetResponse,
-    RpcLedgerAccountsResponse, RpcLedgerSlimAccountsResponse, RpcLedgerStatusGetResponse,
-    RpcMessageProgressResponse, RpcPeersGetResponse, RpcPooledUserCommandsResponse,
-    RpcPooledZkappCommandsResponse, RpcReadinessCheckResponse, RpcRequest,
-    RpcSnarkPoolCompletedJobsResponse, RpcSnarkPoolPendingJobsGetResponse, RpcStateGetError,
-    RpcStatusGetResponse, RpcTransactionInjectResponse, RpcTransactionPoolResponse,
-    RpcTransactionStatusGetResponse, RpcTransitionFrontierUserCommandsResponse,
+    RpcDiscoveryRoutingTableResponse, RpcEnabledFeaturesGetResponse, RpcGenesisBlockResponse,
+    RpcGetBlockResponse, RpcGossipPauseResponse, RpcHealthCheckResponse, RpcHeartbeatGetResponse,
+    RpcLedgerAccountDelegatorsGetResponse, RpcLedgerAccountsAtBlockGetResponse,
+    RpcLedgerAccountsPageGetResponse, RpcLedgerAccountsResponse, RpcLedgerSlimAccountsResponse,
+    RpcLedgerSnapshotGetResponse, RpcLedgerStatusGetResponse,
+    RpcLocallyGeneratedCommandsGetResponse, RpcMessageProgressResponse, RpcNodeOverviewGetResponse,
+    RpcPeerContributionStatsGetResponse, RpcPeerListRefreshResponse, RpcPeersGetResponse,
+    RpcPendingCoinbaseGetResponse, RpcPendingRequestsGetResponse,
+    RpcPooledUserCommandsPageGetResponse, RpcPooledUserCommandsResponse,
+    RpcPooledZkappCommandsPageGetResponse, RpcPooledZkappCommandsResponse,
+    RpcReadinessCheckResponse, RpcRequest, RpcSnarkPoolCompletedJobsResponse,
+    RpcSnarkPoolPendingJobsGetResponse, RpcSnarkStatsGetResponse,
+    RpcSnarkVerifierDigestsGetResponse, RpcSnarkVerifyDryRunResponse,
+    RpcSnarkVerifyHistoryGetResponse, RpcStateGetError, RpcStatusGetResponse,
+    RpcSyncDiagnosisGetResponse, RpcSyncHistoryGetResponse, RpcTokenSupplyGetResponse,
+    RpcTransactionInjectResponse, RpcTransactionPoolResponse,
+    RpcTransactionPoolSenderQueuesGetResponse, RpcTransactionStatusGetResponse,
+    RpcTransitionFrontierUserCommandsResponse, RpcZkappActionsEventsGetResponse,
+    RpcZkappEventsPageGetResponse,
 };
 use serde::{Deserialize, Serialize};
 
@@ -94,6 +107,23 @@ impl RpcService {
         let req = req.req;
         Event::Rpc(rpc_id, Box::new(req))
     }
+
+    /// Sends the response to the request `rpc_id`, whose sender expects
+    /// a response of type `T`.
+    fn respond<T: 'static + Send>(
+        &mut self,
+        rpc_id: RpcId,
+        response: T,
+    ) -> Result<(), RespondError> {
+        let entry = self.pending.remove(rpc_id);
+        let chan = entry.ok_or(RespondError::UnknownRpcId)?;
+        let chan = chan
+            .downcast::<oneshot::Sender<T>>()
+            .map_err(|_| RespondError::UnexpectedResponseType)?;
+        chan.send(response)
+            .map_err(|_| RespondError::RespondingFailed)?;
+        Ok(())
+    }
 }
 
 impl NodeService {
@@ -109,14 +139,7 @@ impl NodeService {
 macro_rules! rpc_service_impl {
     ($name:ident, $ty:ty) => {
         fn $name(&mut self, rpc_id: RpcId, response: $ty) -> Result<(), RespondError> {
-            let entry = self.rpc.pending.remove(rpc_id);
-            let chan = entry.ok_or(RespondError::UnknownRpcId)?;
-            let chan = chan
-                .downcast::<oneshot::Sender<$ty>>()
-                .map_err(|_| RespondError::UnexpectedResponseType)?;
-            chan.send(response)
-                .map_err(|_| RespondError::RespondingFailed)?;
-            Ok(())
+            self.rpc.respond(rpc_id, response)
         }
     };
 }
@@ -227,11 +250,20 @@ impl node::rpc_effectful::RpcService for NodeService {
     rpc_service_impl!(respond_heartbeat_get, RpcHeartbeatGetResponse);
 
     rpc_service_impl!(respond_sync_stats_get, RpcSyncStatsGetResponse);
+    rpc_service_impl!(respond_sync_history_get, RpcSyncHistoryGetResponse);
     rpc_service_impl!(respond_action_stats_get, RpcActionStatsGetResponse);
     rpc_service_impl!(
         respond_block_producer_stats_get,
         RpcBlockProducerStatsGetResponse
     );
+    rpc_service_impl!(
+        respond_peer_contribution_stats_get,
+        RpcPeerContributionStatsGetResponse
+    );
+    rpc_service_impl!(
+        respond_archive_upload_stats_get,
+        RpcArchiveUploadStatsGetResponse
+    );
     rpc_service_impl!(
         respond_message_progress_stats_get,
         RpcMessageProgressResponse
@@ -292,6 +324,10 @@ impl node::rpc_effectful::RpcService for NodeService {
         respond_snarker_job_spec,
         node::rpc::RpcSnarkerJobSpecResponse
     );
+    rpc_service_impl!(
+        respond_snarker_work_submit,
+        node::rpc::RpcSnarkerWorkSubmitResponse
+    );
     rpc_service_impl!(
         respond_snarker_workers,
         node::rpc::RpcSnarkerWorkersResponse
@@ -311,6 +347,10 @@ impl node::rpc_effectful::RpcService for NodeService {
         RpcDiscoveryBoostrapStatsResponse
     );
     rpc_service_impl!(respond_transaction_pool, RpcTransactionPoolResponse);
+    rpc_service_impl!(
+        respond_transaction_pool_sender_queues,
+        RpcTransactionPoolSenderQueuesGetResponse
+    );
     rpc_service_impl!(respond_ledger_slim_accounts, RpcLedgerSlimAccountsResponse);
     rpc_service_impl!(respond_ledger_accounts, RpcLedgerAccountsResponse);
     rpc_service_impl!(respond_transaction_inject, RpcTransactionInjectResponse);
@@ -319,17 +359,33 @@ impl node::rpc_effectful::RpcService for NodeService {
         RpcTransitionFrontierUserCommandsResponse
     );
     rpc_service_impl!(respond_best_chain, RpcBestChainResponse);
+    rpc_service_impl!(respond_best_chain_page_get, RpcBestChainPageGetResponse);
     rpc_service_impl!(
         respond_consensus_constants,
         RpcConsensusConstantsGetResponse
     );
     rpc_service_impl!(respond_transaction_status, RpcTransactionStatusGetResponse);
     rpc_service_impl!(respond_block_get, RpcGetBlockResponse);
+    rpc_service_impl!(respond_block_summary_get, RpcBlockSummaryGetResponse);
     rpc_service_impl!(respond_pooled_user_commands, RpcPooledUserCommandsResponse);
     rpc_service_impl!(
         respond_pooled_zkapp_commands,
         RpcPooledZkappCommandsResponse
     );
+    rpc_service_impl!(
+        respond_pooled_user_commands_page_get,
+        RpcPooledUserCommandsPageGetResponse
+    );
+    rpc_service_impl!(
+        respond_pooled_zkapp_commands_page_get,
+        RpcPooledZkappCommandsPageGetResponse
+    );
+    rpc_service_impl!(respond_zkapp_events_page_get, RpcZkappEventsPageGetResponse);
+    rpc_service_impl!(
+        respond_zkapp_actions_events_get,
+        RpcZkappActionsEventsGetResponse
+    );
+    rpc_service_impl!(respond_token_supply_get, RpcTokenSupplyGetResponse);
     rpc_service_impl!(respond_genesis_block, RpcGenesisBlockResponse);
     rpc_service_impl!(respond_consensus_time_get, RpcConsensusTimeGetResponse);
     rpc_service_impl!(respond_ledger_status_get, RpcLedgerStatusGetResponse);
@@ -337,11 +393,54 @@ impl node::rpc_effectful::RpcService for NodeService {
         respond_ledger_account_delegators_get,
         RpcLedgerAccountDelegatorsGetResponse
     );
+    rpc_service_impl!(respond_ledger_snapshot_get, RpcLedgerSnapshotGetResponse);
+    rpc_service_impl!(
+        respond_ledger_accounts_page_get,
+        RpcLedgerAccountsPageGetResponse
+    );
+    rpc_service_impl!(
+        respond_ledger_accounts_at_block_get,
+        RpcLedgerAccountsAtBlockGetResponse
+    );
+    rpc_service_impl!(respond_block_inject, RpcBlockInjectResponse);
+    rpc_service_impl!(
+        respond_consensus_fork_decisions_get,
+        RpcConsensusForkDecisionsGetResponse
+    );
+    rpc_service_impl!(respond_pending_coinbase_get, RpcPendingCoinbaseGetResponse);
+    rpc_service_impl!(respond_peer_list_refresh, RpcPeerListRefreshResponse);
+    rpc_service_impl!(respond_gossip_pause, RpcGossipPauseResponse);
+    rpc_service_impl!(respond_sync_diagnosis_get, RpcSyncDiagnosisGetResponse);
+    rpc_service_impl!(respond_node_overview_get, RpcNodeOverviewGetResponse);
+    rpc_service_impl!(
+        respond_locally_generated_commands_get,
+        RpcLocallyGeneratedCommandsGetResponse
+    );
+    rpc_service_impl!(respond_pending_requests_get, RpcPendingRequestsGetResponse);
+    rpc_service_impl!(respond_snark_stats_get, RpcSnarkStatsGetResponse);
+    rpc_service_impl!(
+        respond_snark_verifier_digests_get,
+        RpcSnarkVerifierDigestsGetResponse
+    );
+    rpc_service_impl!(
+        respond_snark_verify_history_get,
+        RpcSnarkVerifyHistoryGetResponse
+    );
+    rpc_service_impl!(respond_snark_verify_dry_run, RpcSnarkVerifyDryRunResponse);
+    rpc_service_impl!(respond_enabled_features_get, RpcEnabledFeaturesGetResponse);
+
+    fn drop_request(&mut self, rpc_id: RpcId) {
+        self.rpc.pending.remove(rpc_id);
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::strip_root_field;
+    use std::sync::Arc;
+
+    use mina_p2p_messages::{binprot::BinProtRead, v2};
+
+    use super::*;
 
     #[test]
     fn strip_root_field_test() {
@@ -358,4 +457,76 @@ mod tests {
             assert_eq!(actual, expected)
         }
     }
+
+    fn block() -> Arc<v2::MinaBlockBlockStableV2> {
+        let bytes = include_bytes!(
+            "../../../../tests/files/archive-breadcrumb/3NK56ZbCS31qb8SvCtCCYza4beRDtKgXA2JL6s3evKouG2KkKtiy.bin"
+        );
+        let diff = v2::ArchiveTransitionFrontierDiff::binprot_read(&mut bytes.as_slice()).unwrap();
+        Arc::new(diff.block().unwrap())
+    }
+
+    /// Sends `req` to the service, which receives it with its id.
+    async fn request<T>(
+        service: &mut RpcService,
+        req: RpcRequest,
+    ) -> (RpcId, RpcRequest, tokio::task::JoinHandle<Option<T>>)
+    where
+        T: 'static + Send + Serialize,
+    {
+        let sender = service.req_sender();
+        let response = tokio::spawn(async move { sender.oneshot_request::<T>(req).await });
+        let req = service.req_receiver().recv().await.unwrap();
+        let Event::Rpc(rpc_id, req) = service.process_request(req) else {
+            panic!("not an rpc event");
+        };
+        (rpc_id, *req, response)
+    }
+
+    #[tokio::test]
+    async fn block_inject_response() {
+        let mut service = RpcService::new();
+        let hash: v2::StateHash = "3NK56ZbCS31qb8SvCtCCYza4beRDtKgXA2JL6s3evKouG2KkKtiy"
+            .parse()
+            .unwrap();
+
+        let (rpc_id, req, response) =
+            request::<RpcBlockInjectResponse>(&mut service, RpcRequest::BlockInject(block())).await;
+        assert!(matches!(req, RpcRequest::BlockInject(injected) if injected == block()));
+        service
+            .respond::<RpcBlockInjectResponse>(rpc_id, Ok(hash.clone()))
+            .unwrap();
+        assert_eq!(response.await.unwrap().unwrap().unwrap(), hash);
+        // Responded already.
+        assert!(matches!(
+            service.respond::<RpcBlockInjectResponse>(rpc_id, Ok(hash)),
+            Err(RespondError::UnknownRpcId)
+        ));
+
+        let (rpc_id, _, response) =
+            request::<RpcBlockInjectResponse>(&mut service, RpcRequest::BlockInject(block())).await;
+        service
+            .respond::<RpcBlockInjectResponse>(
+                rpc_id,
+                Err(node::rpc::RpcBlockInjectError::AlreadyKnown),
+            )
+            .unwrap();
+        assert!(matches!(
+            response.await.unwrap(),
+            Some(Err(node::rpc::RpcBlockInjectError::AlreadyKnown))
+        ));
+    }
+
+    #[tokio::test]
+    async fn respond_with_unexpected_type() {
+        let mut service = RpcService::new();
+        let (rpc_id, _, response) =
+            request::<RpcBlockInjectResponse>(&mut service, RpcRequest::BlockInject(block())).await;
+        assert!(matches!(
+            service.respond::<RpcGenesisBlockResponse>(rpc_id, None),
+            Err(RespondError::UnexpectedResponseType)
+        ));
+        // The sender is dropped without a response.
+        assert!(response.await.unwrap().is_none());
+    }
 }
@@ -51,6 +51,15 @@ impl Stats {
         JsValue::from_serde(&res).unwrap_or_default()
     }
 
+    pub async fn sync_history(&self) -> JsValue {
+        let res = self
+            .sender
+            .oneshot_request::<RpcSyncHistoryGetResponse>(RpcRequest::SyncHistoryGet)
+            .await
+            .flatten();
+        JsValue::from_serde(&res).unwrap_or_default()
+    }
+
     pub async fn block_producer(&self) -> JsValue {
         let res = self
             .sender
@@ -59,4 +68,31 @@ impl Stats {
             .flatten();
         JsValue::from_serde(&res).unwrap_or_default()
     }
+
+    pub async fn snark(&self) -> JsValue {
+        let res = self
+            .sender
+            .oneshot_request::<RpcSnarkStatsGetResponse>(RpcRequest::SnarkStatsGet)
+            .await;
+        JsValue::from_serde(&res).unwrap_or_default()
+    }
+
+    pub async fn snark_history(&self) -> JsValue {
+        let res = self
+            .sender
+            .oneshot_request::<RpcSnarkVerifyHistoryGetResponse>(RpcRequest::SnarkVerifyHistoryGet)
+            .await;
+        JsValue::from_serde(&res).unwrap_or_default()
+    }
+
+    pub async fn peer_contributions(&self) -> JsValue {
+        let res = self
+            .sender
+            .oneshot_request::<RpcPeerContributionStatsGetResponse>(
+                RpcRequest::PeerContributionStatsGet,
+            )
+            .await
+            .flatten();
+        JsValue::from_serde(&res).unwrap_or_default()
+    }
 }
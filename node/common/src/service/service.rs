@@ -1,16 +1,18 @@
 use super::{
     archive::ArchiveService,
     block_producer::BlockProducerService,
+    event_channel,
     p2p::webrtc_with_libp2p::P2pServiceCtx,
+    peer_list::PeerListService,
     replay::ReplayerState,
     rpc::{RpcSender, RpcService},
     snark_worker::SnarkWorker,
-    snarks::SnarkBlockVerifyArgs,
-    EventReceiver, EventSender,
+    snarks::{SnarkBlockVerifyArgs, SnarkVerifyPools, SnarkVerifyPoolsConfig},
+    EventReceiver, EventSender, TransitionFrontierEventHook,
 };
 use crate::rpc::RpcReceiver;
 use node::{
-    core::{channels::mpsc, invariants::InvariantsState},
+    core::{channels::mpsc, invariants::InvariantsState, TimeSource},
     event_source::Event,
     ledger::LedgerManager,
     p2p::identity::SecretKey as P2pSecretKey,
@@ -43,12 +45,23 @@ pub struct NodeService {
 
     /// Channel for asynchronous block proof verification requests.
     pub snark_block_proof_verify: mpsc::TrackedUnboundedSender<SnarkBlockVerifyArgs>,
+    /// Thread pools verifying snark works and user commands.
+    pub snark_verify_pools: SnarkVerifyPools,
 
     /// Manages ledger operations, database access, and staged ledger state.
     pub ledger_manager: LedgerManager,
     /// SNARK proof worker for generating transaction proofs (enabled when node
     /// acts as SNARK worker).
     pub snark_worker: Option<SnarkWorker>,
+    /// The snark worker attaches dummy proofs to its work instead of
+    /// proving it, on local development chains.
+    pub snark_work_dummy_proofs: bool,
+    /// Token sent with the snark work submitted to other nodes, see
+    /// `SnarkerConfig::coordinators`.
+    pub snarker_work_token: Option<String>,
+    /// Token sent when downloading ledger snapshots from the node we
+    /// bootstrap from.
+    pub bootstrap_token: Option<String>,
     /// Block production service including VRF evaluation and block proving
     /// (enabled when node acts as block producer).
     pub block_producer: Option<BlockProducerService>,
@@ -57,6 +70,12 @@ pub struct NodeService {
     pub archive: Option<ArchiveService>,
     /// P2P networking context (WebRTC and optionally libp2p transports).
     pub p2p: P2pServiceCtx,
+    /// Refetches the peer list from the configured url (enabled when node
+    /// was started with a peer list url).
+    pub peer_list: Option<PeerListService>,
+    /// Called with the changes of the transition frontier (set by
+    /// applications embedding the node).
+    pub transition_frontier_event_hook: Option<TransitionFrontierEventHook>,
 
     /// Runtime statistics and metrics collection.
     pub stats: Option<Stats>,
@@ -67,6 +86,8 @@ pub struct NodeService {
     /// Replayer state for deterministic action replay (only set in replay
     /// mode).
     pub replayer: Option<ReplayerState>,
+    /// Source of the monotonic time of the state machine, unless replaying.
+    pub time_source: TimeSource,
     /// State for runtime invariant checking and validation.
     pub invariants_state: InvariantsState,
 }
@@ -131,14 +152,24 @@ impl NodeService {
                 .chain(b"static")
                 .finalize_xof(),
             rng: StdRng::from_seed(rng_seed),
-            event_sender: mpsc::unbounded_channel().0,
-            event_receiver: mpsc::unbounded_channel().1.into(),
+            event_sender: event_channel(Default::default()).0,
+            event_receiver: event_channel(Default::default()).1,
             snark_block_proof_verify: mpsc::unbounded_channel().0,
+            // Verifications are skipped when replaying.
+            snark_verify_pools: SnarkVerifyPools::new(SnarkVerifyPoolsConfig {
+                work_threads: 1,
+                user_command_threads: 1,
+            }),
             ledger_manager: LedgerManager::spawn(Default::default()),
             snark_worker: None,
+            snark_work_dummy_proofs: false,
+            snarker_work_token: None,
+            bootstrap_token: None,
             block_producer: None,
             archive: None,
             p2p: P2pServiceCtx::mocked(p2p_sec_key),
+            peer_list: None,
+            transition_frontier_event_hook: None,
             stats: Some(Stats::new()),
             rpc: RpcService::new(),
             recorder: Recorder::None,
@@ -148,6 +179,7 @@ impl NodeService {
                 expected_actions: Default::default(),
                 replay_dynamic_effects_lib: dynamic_effects_lib.unwrap_or_default(),
             }),
+            time_source: TimeSource::real(),
             invariants_state: Default::default(),
         }
     }
@@ -165,7 +197,10 @@ impl node::Service for NodeService {
     fn queues(&mut self) -> node::service::Queues {
         node::service::Queues {
             events: self.event_receiver.len(),
+            events_dropped: self.event_receiver.dropped_events(),
             snark_block_verify: self.snark_block_proof_verify.len(),
+            snark_work_verify: self.snark_verify_pools.work_pending(),
+            snark_user_command_verify: self.snark_verify_pools.user_command_pending(),
             ledger: self.ledger_manager.pending_calls(),
             vrf_evaluator: self
                 .block_producer
@@ -200,7 +235,7 @@ impl redux::TimeService for NodeService {
         self.replayer
             .as_ref()
             .map(|v| v.next_monotonic_time())
-            .unwrap_or_else(redux::Instant::now)
+            .unwrap_or_else(|| self.time_source.now())
     }
 }
 
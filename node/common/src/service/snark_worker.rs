@@ -5,7 +5,7 @@ use ledger::{
     },
     scan_state::scan_state::transaction_snark::SokMessage,
 };
-use mina_p2p_messages::v2;
+use mina_p2p_messages::{string::ByteString, v2};
 use mina_signer::CompressedPubKey;
 use node::{
     core::channels::mpsc,
@@ -49,10 +49,19 @@ impl node::service::ExternalSnarkWorkerService for NodeService {
         );
         self.snark_worker = Some(SnarkWorker { cmd_sender });
         let event_sender = self.event_sender().clone();
+        let dummy_proofs = self.snark_work_dummy_proofs;
 
         node::core::thread::Builder::new()
             .name("snark_worker".to_owned())
-            .spawn(move || worker_thread(cmd_receiver, event_sender, sok_message, work_verifier))
+            .spawn(move || {
+                worker_thread(
+                    cmd_receiver,
+                    event_sender,
+                    sok_message,
+                    work_verifier,
+                    dummy_proofs,
+                )
+            })
             .map(|_| ())
             .map_err(|err| ExternalSnarkWorkerError::Error(err.to_string()))
     }
@@ -113,10 +122,17 @@ fn worker_thread(
     event_sender: EventSender,
     sok_message: SokMessage,
     work_verifier: TransactionVerifier,
+    dummy_proofs: bool,
 ) {
     let _ = event_sender.send(ExternalSnarkWorkerEvent::Started.into());
-    let tx_prover = TransactionProver::make(Some(work_verifier.clone()));
-    let zkapp_prover = ZkappProver::make(Some(work_verifier));
+    // Making the provers takes a while, no need with dummy proofs.
+    let provers = (!dummy_proofs).then(|| {
+        (
+            TransactionProver::make(Some(work_verifier.clone())),
+            ZkappProver::make(Some(work_verifier)),
+        )
+    });
+    let sok_digest: ByteString = (&sok_message.digest()).into();
     while let Some(cmd) = cmd_receiver.blocking_recv() {
         match cmd {
             Cmd::Kill => {
@@ -129,7 +145,13 @@ fn worker_thread(
                 let _ = event_sender.send(ExternalSnarkWorkerEvent::WorkCancelled.into());
             }
             Cmd::Submit(spec) => {
-                let event = match prove_spec(&tx_prover, &zkapp_prover, *spec, &sok_message) {
+                let result = match &provers {
+                    Some((tx_prover, zkapp_prover)) => {
+                        prove_spec(tx_prover, zkapp_prover, *spec, &sok_message)
+                    }
+                    None => Ok(dummy_work(*spec, &sok_digest).into()),
+                };
+                let event = match result {
                     Err(err) => ExternalSnarkWorkerEvent::WorkError(err),
                     Ok(res) => ExternalSnarkWorkerEvent::WorkResult(res),
                 };
@@ -157,6 +179,40 @@ fn prove_spec(
     .map(Into::into)
 }
 
+/// Work for `spec` done by the snarker with `sok_digest`, with dummy
+/// proofs. Only accepted by nodes which don't verify snark work.
+pub fn dummy_work(
+    spec: SnarkWorkSpec,
+    sok_digest: &ByteString,
+) -> v2::TransactionSnarkWorkTStableV2Proofs {
+    let dummy_proof = |single| {
+        let statement = match single {
+            v2::SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Transition(v, _) => v.0,
+            v2::SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Merge(v) => v.0 .0,
+        };
+        v2::LedgerProofProdStableV2(v2::TransactionSnarkStableV2 {
+            statement: v2::MinaStateSnarkedLedgerStateWithSokStableV2 {
+                source: statement.source,
+                target: statement.target,
+                connecting_ledger_left: statement.connecting_ledger_left,
+                connecting_ledger_right: statement.connecting_ledger_right,
+                supply_increase: statement.supply_increase,
+                fee_excess: statement.fee_excess,
+                sok_digest: sok_digest.clone(),
+            },
+            proof: (*ledger::dummy::dummy_transaction_proof()).clone(),
+        })
+    };
+    match spec {
+        SnarkWorkSpec::One(single) => {
+            v2::TransactionSnarkWorkTStableV2Proofs::One(dummy_proof(single))
+        }
+        SnarkWorkSpec::Two((one, two)) => {
+            v2::TransactionSnarkWorkTStableV2Proofs::Two((dummy_proof(one), dummy_proof(two)))
+        }
+    }
+}
+
 fn invalid_bigint_err() -> ExternalSnarkWorkerWorkError {
     ExternalSnarkWorkerWorkError::WorkSpecError(SnarkWorkSpecError::InvalidBigInt)
 }
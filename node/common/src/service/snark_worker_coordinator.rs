@@ -0,0 +1,201 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use anyhow::Context;
+use mina_p2p_messages::v2::CurrencyFeeStableV1;
+#[cfg(not(target_arch = "wasm32"))]
+use mina_p2p_messages::v2::SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse;
+use node::{
+    core::snark::{Snark, SnarkJobId},
+    event_source::ExternalSnarkWorkerEvent,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use node::{
+    core::thread,
+    external_snark_worker::{ExternalSnarkWorkerCoordinatorOffer, SnarkWorkSpec},
+    rpc::{RpcSnarkPoolGetResponse, RpcSnarkerWorkSubmitResponse},
+};
+
+use super::NodeService;
+
+#[cfg(not(target_arch = "wasm32"))]
+const COORDINATOR_SUBMIT_TIMEOUT: Duration = Duration::from_secs(30);
+#[cfg(not(target_arch = "wasm32"))]
+const COORDINATOR_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+impl node::service::ExternalSnarkWorkerCoordinatorService for NodeService {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn coordinator_submit(&mut self, url: &str, snark: Snark) {
+        let event_sender = self.event_sender.clone();
+        let token = self.snarker_work_token.clone();
+        let url = url.to_owned();
+        thread::Builder::new()
+            .name("mina_snarker_coordinator".to_owned())
+            .spawn(move || {
+                let result =
+                    submit(&url, token.as_deref(), &snark).map_err(|err| format!("{err:#}"));
+                let event = ExternalSnarkWorkerEvent::CoordinatorSubmitResult {
+                    url,
+                    job_id: snark.job_id(),
+                    fee: snark.fee,
+                    result,
+                };
+                let _ = event_sender.send(event.into());
+            })
+            .unwrap();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn coordinator_jobs_fetch(&mut self, url: &str, fee: CurrencyFeeStableV1) {
+        let event_sender = self.event_sender.clone();
+        let token = self.snarker_work_token.clone();
+        let url = url.to_owned();
+        thread::Builder::new()
+            .name("mina_snarker_coordinator".to_owned())
+            .spawn(move || {
+                let result = fetch_jobs(&url, token.as_deref())
+                    .map(|jobs| ExternalSnarkWorkerCoordinatorOffer::best_of(&jobs, fee.as_u64()))
+                    .map_err(|err| format!("{err:#}"));
+                let event = ExternalSnarkWorkerEvent::CoordinatorJobsResult { url, result };
+                let _ = event_sender.send(event.into());
+            })
+            .unwrap();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn coordinator_job_spec_fetch(&mut self, url: &str, job_id: SnarkJobId) {
+        let event_sender = self.event_sender.clone();
+        let token = self.snarker_work_token.clone();
+        let url = url.to_owned();
+        thread::Builder::new()
+            .name("mina_snarker_coordinator".to_owned())
+            .spawn(move || {
+                let result = fetch_job_spec(&url, token.as_deref(), &job_id)
+                    .map(Box::new)
+                    .map_err(|err| format!("{err:#}"));
+                let event = ExternalSnarkWorkerEvent::CoordinatorJobSpecResult {
+                    url,
+                    job_id,
+                    result,
+                };
+                let _ = event_sender.send(event.into());
+            })
+            .unwrap();
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn coordinator_submit(&mut self, url: &str, snark: Snark) {
+        let event = ExternalSnarkWorkerEvent::CoordinatorSubmitResult {
+            url: url.to_owned(),
+            job_id: snark.job_id(),
+            fee: snark.fee,
+            result: Err("submitting work to other nodes isn't supported".to_owned()),
+        };
+        let _ = self.event_sender.send(event.into());
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn coordinator_jobs_fetch(&mut self, url: &str, _fee: CurrencyFeeStableV1) {
+        let event = ExternalSnarkWorkerEvent::CoordinatorJobsResult {
+            url: url.to_owned(),
+            result: Err("fetching jobs from other nodes isn't supported".to_owned()),
+        };
+        let _ = self.event_sender.send(event.into());
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn coordinator_job_spec_fetch(&mut self, url: &str, job_id: SnarkJobId) {
+        let event = ExternalSnarkWorkerEvent::CoordinatorJobSpecResult {
+            url: url.to_owned(),
+            job_id,
+            result: Err("fetching jobs from other nodes isn't supported".to_owned()),
+        };
+        let _ = self.event_sender.send(event.into());
+    }
+}
+
+/// Gets the jobs of the pool of the node from `/snark-pool/jobs`, oldest
+/// first.
+#[cfg(not(target_arch = "wasm32"))]
+fn fetch_jobs(url: &str, token: Option<&str>) -> anyhow::Result<RpcSnarkPoolGetResponse> {
+    let url = format!("{}/snark-pool/jobs", url.trim_end_matches('/'));
+    get(&url, token, &[])
+}
+
+/// Gets the spec of the job from `/snarker/job/spec` of the node, which
+/// answers with an error status once the job left its pool.
+#[cfg(not(target_arch = "wasm32"))]
+fn fetch_job_spec(
+    url: &str,
+    token: Option<&str>,
+    job_id: &SnarkJobId,
+) -> anyhow::Result<SnarkWorkSpec> {
+    let url = format!("{}/snarker/job/spec", url.trim_end_matches('/'));
+    let SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(spec) =
+        get(&url, token, &[("id", job_id.to_string())])?;
+    let (spec, _) = spec.with_context(|| format!("{url} has no spec for {job_id}"))?;
+    Ok(spec.instances)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn get<T: serde::de::DeserializeOwned>(
+    url: &str,
+    token: Option<&str>,
+    query: &[(&str, String)],
+) -> anyhow::Result<T> {
+    let response = reqwest::blocking::Client::builder()
+        .timeout(COORDINATOR_FETCH_TIMEOUT)
+        .build()
+        .and_then(|client| {
+            let mut request = client.get(url).query(query);
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+            request.send()
+        })
+        .with_context(|| format!("fetching {url}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("fetching {url}: unexpected status {status}");
+    }
+    response
+        .json()
+        .with_context(|| format!("parsing response of {url}"))
+}
+
+/// Posts the work to `/snarker/work` of the node. Works the node doesn't
+/// need or can't verify are answered with an error status, but still with
+/// a response.
+#[cfg(not(target_arch = "wasm32"))]
+fn submit(
+    url: &str,
+    token: Option<&str>,
+    snark: &Snark,
+) -> anyhow::Result<RpcSnarkerWorkSubmitResponse> {
+    let url = format!("{}/snarker/work", url.trim_end_matches('/'));
+    let response = reqwest::blocking::Client::builder()
+        .timeout(COORDINATOR_SUBMIT_TIMEOUT)
+        .build()
+        .and_then(|client| {
+            let mut request = client.post(&url).json(snark);
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+            request.send()
+        })
+        .with_context(|| format!("submitting work to {url}"))?;
+    let status = response.status();
+    if !matches!(
+        status,
+        reqwest::StatusCode::ACCEPTED
+            | reqwest::StatusCode::NOT_FOUND
+            | reqwest::StatusCode::CONFLICT
+            | reqwest::StatusCode::UNPROCESSABLE_ENTITY
+    ) {
+        anyhow::bail!("submitting work to {url}: unexpected status {status}");
+    }
+    response
+        .json()
+        .with_context(|| format!("parsing response of {url}"))
+}
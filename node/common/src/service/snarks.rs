@@ -1,26 +1,30 @@
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 
-use ledger::{
-    scan_state::{
-        scan_state::transaction_snark::{SokDigest, Statement},
-        transaction_logic::WithStatus,
-    },
-    transaction_pool::{TransactionError, TransactionPoolErrors},
+use ledger::scan_state::{
+    scan_state::transaction_snark::{SokDigest, Statement},
+    transaction_logic::WithStatus,
 };
 use mina_p2p_messages::{bigint::InvalidBigInt, v2};
 use node::{
     core::{
         channels::mpsc,
+        requests::RpcId,
         snark::{Snark, SnarkJobId},
         thread,
     },
     snark::{
         block_verify::{SnarkBlockVerifyError, SnarkBlockVerifyId, VerifiableBlockWithHash},
+        user_command_verify::SnarkUserCommandVerifyError,
+        verify_dry_run::SnarkVerifyDryRunInput,
         work_verify::{SnarkWorkVerifyError, SnarkWorkVerifyId},
-        BlockVerifier, SnarkEvent, TransactionVerifier, VerifierSRS,
+        BlockVerifier, SnarkEvent, SnarkVerifyMetrics, TransactionVerifier, VerifierSRS,
     },
 };
 use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::NodeService;
 
@@ -31,6 +35,200 @@ pub struct SnarkBlockVerifyArgs {
     pub verifier_index: BlockVerifier,
     pub verifier_srs: Arc<VerifierSRS>,
     pub block: VerifiableBlockWithHash,
+    pub submitted: redux::Instant,
+}
+
+/// Number of threads verifying the snark works and the user commands
+/// received from peers.
+///
+/// Each kind gets its own pool, so that a flood of transactions can't delay
+/// the verification of works, nor the other way around. Blocks are verified
+/// on their own thread.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnarkVerifyPoolsConfig {
+    pub work_threads: usize,
+    pub user_command_threads: usize,
+}
+
+impl Default for SnarkVerifyPoolsConfig {
+    fn default() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        #[cfg(target_arch = "wasm32")]
+        let threads = 1;
+
+        Self {
+            work_threads: (threads / 2).max(1),
+            user_command_threads: (threads / 4).max(1),
+        }
+    }
+}
+
+pub struct SnarkVerifyPools {
+    work: SnarkVerifyPool,
+    user_command: SnarkVerifyPool,
+    /// Threads verifying the zkApp proofs of a batch of user commands in
+    /// parallel, created once parallel verification is asked for.
+    zkapp_proofs: Option<Arc<rayon::ThreadPool>>,
+}
+
+impl SnarkVerifyPools {
+    pub fn new(config: SnarkVerifyPoolsConfig) -> Self {
+        Self {
+            work: SnarkVerifyPool::new("work_verifier", config.work_threads),
+            user_command: SnarkVerifyPool::new(
+                "user_command_verifier",
+                config.user_command_threads,
+            ),
+            zkapp_proofs: None,
+        }
+    }
+
+    /// Pool verifying up to `concurrency` zkApp proofs in parallel, `None`
+    /// to verify them one after another. The pool is recreated if the
+    /// concurrency changes.
+    fn zkapp_proofs(&mut self, concurrency: usize) -> Option<Arc<rayon::ThreadPool>> {
+        if cfg!(target_arch = "wasm32") || concurrency <= 1 {
+            return None;
+        }
+        match &self.zkapp_proofs {
+            Some(pool) if pool.current_num_threads() == concurrency => {}
+            _ => {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(concurrency)
+                    .thread_name(|i| format!("zkapp_proof_verifier_{i}"))
+                    .build();
+                match pool {
+                    Ok(pool) => self.zkapp_proofs = Some(Arc::new(pool)),
+                    Err(error) => {
+                        node::core::warn!(
+                            summary = "failed to spawn zkapp proof verifier threads",
+                            error = error.to_string(),
+                        );
+                        return None;
+                    }
+                }
+            }
+        }
+        self.zkapp_proofs.clone()
+    }
+
+    /// Number of works verifications queued or running.
+    pub fn work_pending(&self) -> usize {
+        self.work.pending()
+    }
+
+    /// Number of user commands verifications queued or running.
+    pub fn user_command_pending(&self) -> usize {
+        self.user_command.pending()
+    }
+}
+
+/// Thread pool running verifications in the order they were submitted.
+struct SnarkVerifyPool {
+    /// `None` in wasm, where verifications run on the global rayon pool.
+    pool: Option<rayon::ThreadPool>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl SnarkVerifyPool {
+    fn new(name: &'static str, threads: usize) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads.max(1))
+            .thread_name(move |i| format!("{name}_{i}"))
+            // The verification times out in the state machine, instead of
+            // aborting the node.
+            .panic_handler(move |panic| {
+                let error = panic
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                node::core::error!(
+                    summary = "verifier thread panicked",
+                    pool = name,
+                    error = error,
+                );
+            })
+            .build()
+            .unwrap_or_else(|err| panic!("failed to spawn {name} threads: {err}"));
+        #[cfg(target_arch = "wasm32")]
+        let _ = (name, threads);
+
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            pool: Some(pool),
+            #[cfg(target_arch = "wasm32")]
+            pool: None,
+            pending: Default::default(),
+        }
+    }
+
+    fn pending(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    fn spawn<F>(&self, f: F)
+    where
+        F: 'static + Send + FnOnce(),
+    {
+        let pending = PendingGuard::new(self.pending.clone());
+        let task = move || {
+            let _pending = pending;
+            f();
+        };
+        match &self.pool {
+            Some(pool) => pool.spawn_fifo(task),
+            None => rayon::spawn_fifo(task),
+        }
+    }
+}
+
+/// Counts a verification as pending until it is dropped, once it is done
+/// or if it panicked.
+struct PendingGuard(Arc<AtomicUsize>);
+
+impl PendingGuard {
+    fn new(pending: Arc<AtomicUsize>) -> Self {
+        pending.fetch_add(1, Ordering::Relaxed);
+        Self(pending)
+    }
+}
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Logs the outcome of a verification request. `trace_id` identifies the
+/// request across the state machine and the service logs.
+fn log_verify_done<T, E: std::fmt::Display>(
+    trace_id: String,
+    result: &Result<T, E>,
+    metrics: &SnarkVerifyMetrics,
+) {
+    let batch_size = metrics.batch_size;
+    let queue_wait = format!("{:?}", metrics.queue_wait);
+    let verify = format!("{:?}", metrics.verify_duration);
+    match result {
+        Ok(_) => node::core::debug!(
+            summary = "verification succeeded",
+            trace_id = trace_id,
+            batch_size = batch_size,
+            queue_wait = queue_wait,
+            verify = verify,
+        ),
+        Err(error) => node::core::info!(
+            summary = "verification failed",
+            trace_id = trace_id,
+            batch_size = batch_size,
+            queue_wait = queue_wait,
+            verify = verify,
+            error = error.to_string(),
+        ),
+    }
 }
 
 impl NodeService {
@@ -47,21 +245,23 @@ impl NodeService {
                         verifier_index,
                         verifier_srs,
                         block,
+                        submitted,
                     } = msg.0;
-                    eprintln!("verify({}) - start", block.hash_ref());
+                    node::core::debug!(
+                        summary = "block verification started",
+                        trace_id = format!("{req_id:?}"),
+                        block_hash = block.hash_ref().to_string(),
+                    );
                     let header = block.header_ref();
-                    let result = {
-                        if !ledger::proofs::verification::verify_block(
+                    let (result, metrics) = SnarkVerifyMetrics::measure(submitted, 1, || {
+                        ledger::proofs::verification::check_block(
                             header,
                             &verifier_index,
                             &verifier_srs,
-                        ) {
-                            Err(SnarkBlockVerifyError::VerificationFailed)
-                        } else {
-                            Ok(())
-                        }
-                    };
-                    eprintln!("verify({}) - end", block.hash_ref());
+                        )
+                        .map_err(SnarkBlockVerifyError::from)
+                    });
+                    log_verify_done(format!("{req_id:?}"), &result, &metrics);
 
                     let _ = event_sender.send(SnarkEvent::BlockVerify(req_id, result).into());
                 }
@@ -88,6 +288,7 @@ impl node::service::SnarkBlockVerifyService for NodeService {
             verifier_index,
             verifier_srs,
             block,
+            submitted: redux::Instant::now(),
         };
         let _ = self.snark_block_proof_verify.tracked_send(args);
     }
@@ -105,38 +306,54 @@ impl node::service::SnarkWorkVerifyService for NodeService {
             return;
         }
         let tx = self.event_sender().clone();
-        rayon::spawn_fifo(move || {
-            let result = (|| {
+        let submitted = redux::Instant::now();
+        self.snark_verify_pools.work.spawn(move || {
+            let batch_size = work.len();
+            let (result, metrics) = SnarkVerifyMetrics::measure(submitted, batch_size, || {
                 let conv = |proof: &v2::LedgerProofProdStableV2| -> Result<_, InvalidBigInt> {
                     Ok((
                         Statement::<SokDigest>::try_from(&proof.0.statement)?,
                         proof.proof.clone(),
                     ))
                 };
-                let Ok(works) = work
-                    .into_iter()
-                    .flat_map(|work| match &*work.proofs {
-                        v2::TransactionSnarkWorkTStableV2Proofs::One(v) => {
-                            [conv(v).map(Some), Ok(None)]
-                        }
-                        v2::TransactionSnarkWorkTStableV2Proofs::Two((v1, v2)) => {
-                            [conv(v1).map(Some), conv(v2).map(Some)]
+                let works = work
+                    .iter()
+                    .enumerate()
+                    .map(|(index, work)| {
+                        let invalid =
+                            |_: InvalidBigInt| SnarkWorkVerifyError::InvalidStatement { index };
+                        match &*work.proofs {
+                            v2::TransactionSnarkWorkTStableV2Proofs::One(v) => {
+                                Ok(vec![conv(v).map_err(invalid)?])
+                            }
+                            v2::TransactionSnarkWorkTStableV2Proofs::Two((v1, v2)) => {
+                                Ok(vec![conv(v1).map_err(invalid)?, conv(v2).map_err(invalid)?])
+                            }
                         }
                     })
-                    .collect::<Result<Vec<_>, _>>()
-                else {
-                    return Err(SnarkWorkVerifyError::VerificationFailed);
+                    .collect::<Result<Vec<_>, _>>()?;
+                let verify = |works: &[Vec<_>]| {
+                    ledger::proofs::verification::verify_transaction(
+                        works.iter().flatten().map(|(v1, v2)| (v1, v2)),
+                        &verifier_index,
+                        &verifier_srs,
+                    )
                 };
-                if !ledger::proofs::verification::verify_transaction(
-                    works.iter().flatten().map(|(v1, v2)| (v1, v2)),
-                    &verifier_index,
-                    &verifier_srs,
-                ) {
-                    Err(SnarkWorkVerifyError::VerificationFailed)
-                } else {
-                    Ok(())
+                if verify(&works) {
+                    return Ok(());
                 }
-            })();
+                // The proofs are verified together, so the invalid work is
+                // found by verifying the works one by one.
+                let index = match works.len() {
+                    1 => Some(0),
+                    len => (0..len).find(|&i| !verify(&works[i..=i])),
+                };
+                Err(match index {
+                    Some(index) => SnarkWorkVerifyError::InvalidProof { index },
+                    None => SnarkWorkVerifyError::VerificationFailed,
+                })
+            });
+            log_verify_done(format!("{req_id:?}"), &result, &metrics);
 
             let _ = tx.send(SnarkEvent::WorkVerify(req_id, result).into());
         });
@@ -148,42 +365,71 @@ impl node::service::SnarkUserCommandVerifyService for NodeService {
         &mut self,
         req_id: node::snark::user_command_verify::SnarkUserCommandVerifyId,
         commands: Vec<WithStatus<ledger::scan_state::transaction_logic::verifiable::UserCommand>>,
+        concurrency: usize,
     ) {
         if self.replayer.is_some() {
             return;
         }
 
         let tx = self.event_sender().clone();
-        rayon::spawn_fifo(move || {
-            let result = {
-                let (verified, invalid): (Vec<_>, Vec<_>) = ledger::verifier::Verifier
-                    .verify_commands(commands, None)
+        let submitted = redux::Instant::now();
+        let zkapp_proofs = self.snark_verify_pools.zkapp_proofs(concurrency);
+        self.snark_verify_pools.user_command.spawn(move || {
+            let batch_size = commands.len();
+            let (result, metrics) = SnarkVerifyMetrics::measure(submitted, batch_size, || {
+                ledger::verifier::Verifier
+                    .verify_commands_in(commands, None, zkapp_proofs.as_deref())
                     .into_iter()
-                    .partition(Result::is_ok);
-
-                let verified: Vec<_> = verified.into_iter().map(Result::unwrap).collect();
-                let invalid: Vec<_> = invalid.into_iter().map(Result::unwrap_err).collect();
-
-                if !invalid.is_empty() {
-                    let transaction_pool_errors = invalid
-                        .into_iter()
-                        .map(TransactionError::Verifier)
-                        .collect();
-                    Err(TransactionPoolErrors::BatchedErrors(
-                        transaction_pool_errors,
-                    ))
-                } else {
-                    Ok(verified)
-                }
-            };
+                    .enumerate()
+                    .map(|(index, result)| {
+                        result
+                            .map_err(|err| SnarkUserCommandVerifyError::from_verifier(index, &err))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            });
 
-            let result = result.map_err(|err| err.to_string());
+            log_verify_done(format!("{req_id:?}"), &result, &metrics);
 
             let _ = tx.send(SnarkEvent::UserCommandVerify(req_id, result).into());
         });
     }
 }
 
+impl node::service::SnarkVerifyDryRunService for NodeService {
+    fn verify_dry_run(
+        &mut self,
+        rpc_id: RpcId,
+        input: SnarkVerifyDryRunInput,
+        block_verifier_index: BlockVerifier,
+        work_verifier_index: TransactionVerifier,
+        verifier_srs: Arc<VerifierSRS>,
+    ) {
+        if self.replayer.is_some() {
+            return;
+        }
+        let tx = self.event_sender().clone();
+        // Dry runs are rare and may verify big batches, so they don't
+        // queue in, nor delay, the pools verifying what peers send.
+        let spawned = thread::Builder::new()
+            .name("snark_verify_dry_run".to_owned())
+            .spawn(move || {
+                let report = node::snark::verify_dry_run::verify_dry_run(
+                    &input,
+                    &block_verifier_index,
+                    &work_verifier_index,
+                    &verifier_srs,
+                );
+                let _ = tx.send(SnarkEvent::VerifyDryRun(rpc_id, report).into());
+            });
+        if let Err(error) = spawned {
+            node::core::warn!(
+                summary = "failed to spawn snark_verify_dry_run thread",
+                error = error.to_string(),
+            );
+        }
+    }
+}
+
 impl node::service::SnarkPoolService for NodeService {
     fn random_choose<'a>(
         &mut self,
@@ -196,3 +442,45 @@ impl node::service::SnarkPoolService for NodeService {
             .collect()
     }
 }
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    /// Waits for the pool to finish its verifications.
+    fn wait_done(pool: &SnarkVerifyPool) {
+        let start = Instant::now();
+        while pool.pending() > 0 {
+            assert!(start.elapsed() < Duration::from_secs(10), "timed out");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_pending_count() {
+        let pool = SnarkVerifyPool::new("test_verifier", 1);
+        let (tx, rx) = std::sync::mpsc::channel::<()>();
+        pool.spawn(move || {
+            let _ = rx.recv();
+        });
+        pool.spawn(|| {});
+        assert_eq!(pool.pending(), 2);
+        drop(tx);
+        wait_done(&pool);
+    }
+
+    #[test]
+    fn test_pending_count_after_panic() {
+        let pool = SnarkVerifyPool::new("test_verifier", 1);
+        pool.spawn(|| panic!("verifier bug"));
+        wait_done(&pool);
+
+        // The pool still verifies.
+        let (tx, rx) = std::sync::mpsc::channel();
+        pool.spawn(move || tx.send(()).unwrap());
+        rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        wait_done(&pool);
+    }
+}
@@ -0,0 +1,90 @@
+use node::{
+    core::block::ArcBlockWithHash, service::TransitionFrontierEventService,
+    transition_frontier::sync::TransitionFrontierSyncState,
+};
+
+use crate::NodeService;
+
+/// Change of the transition frontier, passed to the hook set with
+/// [`crate::NodeServiceCommonBuilder::transition_frontier_event_hook`].
+#[derive(Debug, Clone)]
+pub enum TransitionFrontierEvent {
+    /// Sync status changed, e.g. from `Idle` to `Synced`.
+    SyncStatus(String),
+    /// The best chain changed, with this new tip.
+    BestTip(ArcBlockWithHash),
+}
+
+pub type TransitionFrontierEventCallback = Box<dyn FnMut(TransitionFrontierEvent) + Send>;
+
+/// Calls the callback with the changes of the transition frontier, the sync
+/// status only when it differs from the last one.
+pub struct TransitionFrontierEventHook {
+    callback: TransitionFrontierEventCallback,
+    sync_status: Option<String>,
+}
+
+impl TransitionFrontierEventHook {
+    pub fn new(callback: TransitionFrontierEventCallback) -> Self {
+        Self {
+            callback,
+            sync_status: None,
+        }
+    }
+
+    fn best_tip_update(&mut self, best_tip: &ArcBlockWithHash) {
+        (self.callback)(TransitionFrontierEvent::BestTip(best_tip.clone()));
+    }
+
+    fn sync_state_update(&mut self, sync: &TransitionFrontierSyncState) {
+        let status = sync.to_string();
+        if self.sync_status.as_ref() != Some(&status) {
+            self.sync_status = Some(status.clone());
+            (self.callback)(TransitionFrontierEvent::SyncStatus(status));
+        }
+    }
+}
+
+impl TransitionFrontierEventService for NodeService {
+    fn best_tip_update(&mut self, best_tip: &ArcBlockWithHash) {
+        if let Some(hook) = self.transition_frontier_event_hook.as_mut() {
+            hook.best_tip_update(best_tip);
+        }
+    }
+
+    fn sync_state_update(&mut self, sync: &TransitionFrontierSyncState) {
+        if let Some(hook) = self.transition_frontier_event_hook.as_mut() {
+            hook.sync_state_update(sync);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn sync_status_reported_on_change() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let hook_events = events.clone();
+        let mut hook = TransitionFrontierEventHook::new(Box::new(move |event| {
+            if let TransitionFrontierEvent::SyncStatus(status) = event {
+                hook_events.lock().unwrap().push(status);
+            }
+        }));
+
+        let synced = |secs| TransitionFrontierSyncState::Synced {
+            time: redux::Timestamp::new(secs * 1_000_000_000),
+        };
+        hook.sync_state_update(&TransitionFrontierSyncState::Idle);
+        hook.sync_state_update(&TransitionFrontierSyncState::Idle);
+        hook.sync_state_update(&synced(1));
+        // Synced again on a new best tip, the status stays the same.
+        hook.sync_state_update(&synced(2));
+        hook.sync_state_update(&TransitionFrontierSyncState::Idle);
+
+        assert_eq!(*events.lock().unwrap(), ["Idle", "Synced", "Idle"]);
+    }
+}
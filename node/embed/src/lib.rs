@@ -0,0 +1,359 @@
+//! Embeds a verifying Mina node in other Rust applications
+//!
+//! Indexers, bridges and other applications which need verified chain data
+//! can run the node in-process instead of spawning the `mina` CLI and
+//! talking to it over GraphQL. The embedded node syncs and verifies the
+//! chain like any other node, but neither produces blocks nor snark work.
+//!
+//! # Overview
+//!
+//! - [`EmbeddedNodeBuilder`] - Network, genesis and p2p settings
+//! - [`EmbeddedNode`] - Handle of the running node, to query it, subscribe
+//!   to its [`NodeEvent`]s and stop it
+//!
+//! The node runs its state machine on a thread of its own, the handle can
+//! be used from any thread and its queries from any async runtime.
+//!
+//! # Example Usage
+//!
+//! ```no_run
+//! use mina_node_embed::{EmbeddedNodeBuilder, NodeEvent};
+//!
+//! let node = EmbeddedNodeBuilder::new("devnet")
+//!     .work_dir("/tmp/mina-embed")
+//!     .start()?;
+//!
+//! let mut events = node.subscribe();
+//! while let Ok(event) = events.blocking_recv() {
+//!     if let NodeEvent::BestTip(best_tip) = event {
+//!         println!("best tip: {} at height {}", best_tip.hash, best_tip.height);
+//!     }
+//! }
+//! node.stop();
+//! # Ok::<(), mina_node_embed::Error>(())
+//! ```
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread::JoinHandle,
+};
+
+use mina_node_native::{rpc::RpcSender, NodeBuilder, TransitionFrontierEvent};
+use node::{
+    core::channels::{broadcast, oneshot},
+    daemon_json::{Daemon, DaemonJson},
+    p2p::identity::SecretKey as P2pSecretKey,
+    rpc::{
+        RpcBlockSummaryGetResponse, RpcGetBlockResponse, RpcLedgerAccountsResponse, RpcRequest,
+        RpcStatusGetResponse,
+    },
+    transition_frontier::genesis::GenesisConfig,
+};
+
+pub use node::{
+    p2p::connection::outgoing::P2pConnectionOutgoingPeerAddr,
+    rpc::{
+        AccountQuery, GetBlockQuery, RpcNodeStatus, RpcNodeStatusTransitionFrontierBlockSummary,
+    },
+};
+
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("network configuration error: {0}")]
+    Network(String),
+    #[error("failed to read config file {path:?}: {error}")]
+    Config { path: PathBuf, error: String },
+    #[error("failed to build node: {0:#}")]
+    Build(anyhow::Error),
+    #[error("failed to spawn node thread: {0}")]
+    Spawn(std::io::Error),
+    #[error("node is stopped")]
+    Stopped,
+}
+
+/// Changes of the node's view of the chain, see [`EmbeddedNode::subscribe`].
+/// They are sent by the node's state machine as they happen, so that no
+/// best tip is missed.
+#[derive(Debug, Clone)]
+pub enum NodeEvent {
+    /// Sync status of the transition frontier changed, e.g. from
+    /// `Bootstrap` to `Synced`.
+    SyncStatus(String),
+    /// The node switched to a new best tip.
+    BestTip(RpcNodeStatusTransitionFrontierBlockSummary),
+}
+
+pub struct EmbeddedNodeBuilder {
+    network: String,
+    daemon_conf: Daemon,
+    genesis_config: Option<Arc<GenesisConfig>>,
+    work_dir: Option<PathBuf>,
+    rng_seed: Option<[u8; 32]>,
+    p2p_sec_key: Option<P2pSecretKey>,
+    libp2p_port: Option<u16>,
+    max_peers: Option<usize>,
+    initial_peers: Vec<P2pConnectionOutgoingPeerAddr>,
+}
+
+impl EmbeddedNodeBuilder {
+    /// Node of the network named `network`, `devnet` or `mainnet`, starting
+    /// from the devnet genesis unless set with [`Self::daemon_json`] or
+    /// [`Self::genesis_config`].
+    pub fn new(network: impl Into<String>) -> Self {
+        Self {
+            network: network.into(),
+            daemon_conf: Daemon::DEFAULT,
+            genesis_config: None,
+            work_dir: None,
+            rng_seed: None,
+            p2p_sec_key: None,
+            libp2p_port: None,
+            max_peers: None,
+            initial_peers: Vec::new(),
+        }
+    }
+
+    /// Start from the genesis and daemon settings of a `daemon.json`.
+    pub fn daemon_json(self, path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let config_error = |error: String| Error::Config {
+            path: path.to_owned(),
+            error,
+        };
+        let file = std::fs::File::open(path).map_err(|e| config_error(e.to_string()))?;
+        let config: DaemonJson = serde_json::from_reader(std::io::BufReader::new(file))
+            .map_err(|e| config_error(e.to_string()))?;
+
+        Ok(Self {
+            daemon_conf: config.daemon.clone().unwrap_or(Daemon::DEFAULT),
+            genesis_config: Some(Arc::new(GenesisConfig::DaemonJson(Box::new(config)))),
+            ..self
+        })
+    }
+
+    pub fn genesis_config(mut self, config: Arc<GenesisConfig>) -> Self {
+        self.genesis_config = Some(config);
+        self
+    }
+
+    /// Directory of the node's ledgers and debug files, required unless
+    /// the application already called [`mina_core::set_work_dir`]. The work
+    /// dir is global to the process, so only one node can be started per
+    /// process.
+    pub fn work_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.work_dir = Some(dir.into());
+        self
+    }
+
+    pub fn rng_seed(mut self, seed: [u8; 32]) -> Self {
+        self.rng_seed = Some(seed);
+        self
+    }
+
+    /// If not called, a random one is generated.
+    pub fn p2p_sec_key(mut self, key: P2pSecretKey) -> Self {
+        self.p2p_sec_key = Some(key);
+        self
+    }
+
+    /// Listen for incoming connections on `port`, outgoing connections only
+    /// if not called.
+    pub fn libp2p_port(mut self, port: u16) -> Self {
+        self.libp2p_port = Some(port);
+        self
+    }
+
+    pub fn max_peers(mut self, limit: usize) -> Self {
+        self.max_peers = Some(limit);
+        self
+    }
+
+    /// Peers to connect to first, instead of the default peers of the
+    /// network.
+    pub fn initial_peers(
+        mut self,
+        addrs: impl IntoIterator<Item = P2pConnectionOutgoingPeerAddr>,
+    ) -> Self {
+        self.initial_peers.extend(addrs);
+        self
+    }
+
+    fn build(self, events: broadcast::Sender<NodeEvent>) -> anyhow::Result<mina_node_native::Node> {
+        let genesis_config = self
+            .genesis_config
+            .unwrap_or_else(|| node::config::DEVNET_CONFIG.clone());
+        let mut builder = NodeBuilder::new(self.rng_seed, self.daemon_conf, genesis_config);
+
+        if let Some(key) = self.p2p_sec_key {
+            builder.p2p_sec_key(key);
+        }
+        if let Some(port) = self.libp2p_port {
+            builder.p2p_libp2p_port(port);
+        }
+        if let Some(limit) = self.max_peers {
+            builder.p2p_max_peers(limit);
+        }
+        builder.initial_peer_addrs(self.initial_peers);
+        builder.transition_frontier_event_hook(Box::new(move |event| {
+            // No receivers isn't an error, events are only for subscribers.
+            let _ = events.send(event.into());
+        }));
+
+        builder.build()
+    }
+
+    /// Starts the node on a thread of its own, once it is built.
+    pub fn start(self) -> Result<EmbeddedNode, Error> {
+        if let Err(error) = mina_core::NetworkConfig::init(&self.network) {
+            // Already initialized, e.g. by the application.
+            if mina_core::NetworkConfig::global().name != self.network {
+                return Err(Error::Network(error));
+            }
+        }
+        if let Some(work_dir) = self.work_dir.clone() {
+            mina_core::set_work_dir(work_dir);
+        }
+
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let (started, started_rx) = std::sync::mpsc::channel();
+
+        let thread_events = events.clone();
+        let thread = std::thread::Builder::new()
+            .name("mina-node".to_owned())
+            .stack_size(64 * 1024 * 1024)
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(error) => {
+                        let _ = started.send(Err(Error::Spawn(error)));
+                        return;
+                    }
+                };
+                let mut node = match self.build(thread_events) {
+                    Ok(node) => node,
+                    Err(error) => {
+                        let _ = started.send(Err(Error::Build(error)));
+                        return;
+                    }
+                };
+                let _ = started.send(Ok(node.rpc()));
+
+                runtime.block_on(async {
+                    tokio::select! {
+                        _ = node.run_forever() => {}
+                        _ = shutdown_rx => {}
+                    }
+                });
+            })
+            .map_err(Error::Spawn)?;
+
+        let rpc = started_rx.recv().map_err(|_| Error::Stopped)??;
+
+        Ok(EmbeddedNode {
+            rpc,
+            events,
+            shutdown: Some(shutdown),
+            thread: Some(thread),
+        })
+    }
+}
+
+impl From<TransitionFrontierEvent> for NodeEvent {
+    fn from(event: TransitionFrontierEvent) -> Self {
+        match event {
+            TransitionFrontierEvent::SyncStatus(status) => Self::SyncStatus(status),
+            TransitionFrontierEvent::BestTip(block) => {
+                Self::BestTip(RpcNodeStatusTransitionFrontierBlockSummary {
+                    hash: block.hash().clone(),
+                    height: block.height(),
+                    global_slot: block.global_slot(),
+                })
+            }
+        }
+    }
+}
+
+/// Handle of a node started with [`EmbeddedNodeBuilder::start`]. The node is
+/// stopped when the handle is dropped.
+pub struct EmbeddedNode {
+    rpc: RpcSender,
+    events: broadcast::Sender<NodeEvent>,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EmbeddedNode {
+    /// Events from now on. A receiver falling behind by more than 256
+    /// events misses the oldest ones, see [`broadcast::Receiver::recv`].
+    pub fn subscribe(&self) -> broadcast::Receiver<NodeEvent> {
+        self.events.subscribe()
+    }
+
+    pub async fn status(&self) -> Result<Option<RpcNodeStatus>, Error> {
+        self.request::<RpcStatusGetResponse>(RpcRequest::StatusGet)
+            .await
+    }
+
+    pub async fn best_tip(
+        &self,
+    ) -> Result<Option<RpcNodeStatusTransitionFrontierBlockSummary>, Error> {
+        Ok(self
+            .status()
+            .await?
+            .and_then(|status| status.transition_frontier.best_tip))
+    }
+
+    /// Block of the transition frontier, by hash or by height on the best
+    /// chain.
+    pub async fn block(&self, query: GetBlockQuery) -> Result<RpcGetBlockResponse, Error> {
+        self.request(RpcRequest::GetBlock(query)).await
+    }
+
+    /// Summary of a block of the best chain, or of a block which recently
+    /// left the transition frontier.
+    pub async fn block_summary(
+        &self,
+        query: GetBlockQuery,
+    ) -> Result<RpcBlockSummaryGetResponse, Error> {
+        self.request(RpcRequest::BlockSummaryGet(query)).await
+    }
+
+    /// Accounts of the best tip ledger.
+    pub async fn accounts(&self, query: AccountQuery) -> Result<RpcLedgerAccountsResponse, Error> {
+        self.request(RpcRequest::LedgerAccountsGet(query)).await
+    }
+
+    async fn request<T>(&self, req: RpcRequest) -> Result<T, Error>
+    where
+        T: 'static + Send + serde::Serialize,
+    {
+        self.rpc.oneshot_request(req).await.ok_or(Error::Stopped)
+    }
+
+    /// Stops the node's state machine and waits for its thread to exit.
+    pub fn stop(mut self) {
+        self.shutdown_and_join();
+    }
+
+    fn shutdown_and_join(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for EmbeddedNode {
+    fn drop(&mut self) {
+        self.shutdown_and_join();
+    }
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use mina_node_embed::{EmbeddedNodeBuilder, NodeEvent};
+use node::p2p::identity::SecretKey as P2pSecretKey;
+
+/// Genesis is injected once its ledger is loaded, without any peer.
+const GENESIS_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// The state machine reports that the node is synced to the genesis block,
+/// and the genesis block as the first best tip.
+#[tokio::test]
+async fn genesis_events() {
+    let work_dir = tempfile::tempdir().unwrap();
+    // A peer which isn't listening, so that the node doesn't connect to
+    // the default peers of the network.
+    let peer_id = P2pSecretKey::deterministic(1).public_key().peer_id();
+    let peer = format!("/ip4/127.0.0.1/tcp/1/p2p/{peer_id}")
+        .parse()
+        .unwrap();
+    let node = EmbeddedNodeBuilder::new("devnet")
+        .work_dir(work_dir.path())
+        .rng_seed([0; 32])
+        .initial_peers([peer])
+        .start()
+        .unwrap();
+    let mut events = node.subscribe();
+
+    let mut sync_statuses = Vec::new();
+    let best_tip = tokio::time::timeout(GENESIS_TIMEOUT, async {
+        loop {
+            match events.recv().await.unwrap() {
+                NodeEvent::SyncStatus(status) => sync_statuses.push(status),
+                NodeEvent::BestTip(best_tip) => return best_tip,
+            }
+        }
+    })
+    .await
+    .expect("no best tip");
+
+    assert_eq!(sync_statuses.last().map(String::as_str), Some("Synced"));
+    assert_eq!(best_tip.height, 1);
+    let status_best_tip = node.best_tip().await.unwrap().unwrap();
+    assert_eq!(status_best_tip.hash, best_tip.hash);
+    node.stop();
+}
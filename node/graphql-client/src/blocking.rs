@@ -0,0 +1,72 @@
+//! Blocking variant of [`crate::GraphQLClient`].
+//!
+//! Must not be used from within an async runtime.
+
+use serde::de::DeserializeOwned;
+
+use crate::{
+    config::with_auth, ClientConfig, Error, GraphQLOperation, GraphQLRequest, GraphQLResponse,
+};
+
+#[derive(Debug, Clone)]
+pub struct GraphQLClient {
+    config: ClientConfig,
+    client: reqwest::blocking::Client,
+}
+
+impl GraphQLClient {
+    pub fn new(config: ClientConfig) -> Result<Self, Error> {
+        let mut builder = reqwest::blocking::Client::builder()
+            .timeout(config.timeout)
+            .danger_accept_invalid_certs(config.accept_invalid_certs);
+        for certificate in config.certificates()? {
+            builder = builder.add_root_certificate(certificate);
+        }
+        let client = builder.build().map_err(Error::Config)?;
+
+        Ok(Self { config, client })
+    }
+
+    pub fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    pub fn execute<O: GraphQLOperation>(&self, operation: &O) -> Result<O::Data, Error> {
+        self.send(&GraphQLRequest::of(operation))?.into_data()
+    }
+
+    /// Sends an arbitrary query. GraphQL errors are returned as part of
+    /// the response instead of failing the request.
+    pub fn execute_raw(
+        &self,
+        query: &str,
+        variables: Option<serde_json::Value>,
+    ) -> Result<GraphQLResponse<serde_json::Value>, Error> {
+        self.send(&GraphQLRequest {
+            query: query.into(),
+            variables,
+        })
+    }
+
+    fn send<T: DeserializeOwned>(
+        &self,
+        request: &GraphQLRequest<'_>,
+    ) -> Result<GraphQLResponse<T>, Error> {
+        let endpoint = &self.config.endpoint;
+        let response = with_auth!(self.client.post(endpoint), &self.config.auth)
+            .json(request)
+            .send()
+            .map_err(|error| Error::Request {
+                endpoint: endpoint.clone(),
+                error,
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(Error::Status { status, body });
+        }
+
+        response.json().map_err(Error::Response)
+    }
+}
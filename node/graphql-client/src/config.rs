@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use crate::Error;
+
+pub const DEFAULT_ENDPOINT: &str = "http://localhost:3000/graphql";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Settings of a [`crate::GraphQLClient`] or
+/// [`crate::blocking::GraphQLClient`].
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Url of the GraphQL endpoint, including the `/graphql` path.
+    pub endpoint: String,
+    pub timeout: Duration,
+    pub auth: Option<Auth>,
+    /// PEM encoded certificates trusted in addition to the system ones,
+    /// for nodes behind a proxy with a self-signed certificate.
+    pub root_certificates: Vec<Vec<u8>>,
+    /// Accept any TLS certificate. Only meant for local testing.
+    pub accept_invalid_certs: bool,
+}
+
+/// Credentials sent with each request, for nodes behind an
+/// authenticating proxy.
+#[derive(Debug, Clone)]
+pub enum Auth {
+    Bearer(String),
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+}
+
+impl ClientConfig {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            timeout: DEFAULT_TIMEOUT,
+            auth: None,
+            root_certificates: Vec::new(),
+            accept_invalid_certs: false,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    pub fn with_root_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    pub fn with_accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
+
+    pub(crate) fn certificates(&self) -> Result<Vec<reqwest::Certificate>, Error> {
+        self.root_certificates
+            .iter()
+            .map(|pem| reqwest::Certificate::from_pem(pem).map_err(Error::Config))
+            .collect()
+    }
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_ENDPOINT)
+    }
+}
+
+/// Applies the same settings to async and blocking requests, which are
+/// built by different types.
+macro_rules! with_auth {
+    ($request:expr, $auth:expr) => {
+        match $auth {
+            None => $request,
+            Some($crate::Auth::Bearer(token)) => $request.bearer_auth(token),
+            Some($crate::Auth::Basic { username, password }) => {
+                $request.basic_auth(username, password.as_ref())
+            }
+        }
+    };
+}
+
+pub(crate) use with_auth;
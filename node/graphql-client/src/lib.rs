@@ -0,0 +1,73 @@
+//! Typed client for the GraphQL API of Mina nodes
+//!
+//! The client talks to the `/graphql` endpoint served by the Rust node,
+//! and to the compatible endpoint of the OCaml node. It is used by the
+//! `mina` CLI and by the testing framework, and can be used by external
+//! tooling instead of hand-written queries.
+//!
+//! # Overview
+//!
+//! - [`GraphQLClient`] - Async client, built on `reqwest`
+//! - [`blocking::GraphQLClient`] - Blocking client, for use outside of an
+//!   async runtime
+//! - [`ClientConfig`] - Endpoint, timeout, TLS and authentication settings
+//!   shared by both clients
+//! - [`queries`] and [`mutations`] - Operations with typed variables and
+//!   responses, implementing [`GraphQLOperation`]
+//!
+//! Queries which aren't covered by the typed operations can be sent with
+//! `execute_raw`.
+//!
+//! # Example Usage
+//!
+//! ```no_run
+//! use mina_graphql_client::{blocking::GraphQLClient, queries, ClientConfig};
+//!
+//! let client = GraphQLClient::new(ClientConfig::new("http://localhost:3000/graphql"))?;
+//! let account = client.execute(&queries::Account::new(
+//!     "B62qrPN5Y5yq8kGE3FbVKbGTdTAJNdtNtB5sNVpxyRwWGcDEhpMzc8g",
+//! ))?;
+//! # Ok::<(), mina_graphql_client::Error>(())
+//! ```
+
+pub mod blocking;
+pub mod mutations;
+pub mod queries;
+
+mod client;
+mod config;
+mod operation;
+
+pub use client::GraphQLClient;
+pub use config::{Auth, ClientConfig, DEFAULT_ENDPOINT};
+pub use operation::{GraphQLError, GraphQLOperation, GraphQLRequest, GraphQLResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("failed to connect to GraphQL endpoint {endpoint}: {error}")]
+    Request {
+        endpoint: String,
+        error: reqwest::Error,
+    },
+    #[error("GraphQL endpoint returned HTTP {status}: {body}")]
+    Status {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+    #[error("failed to parse GraphQL response: {0}")]
+    Response(reqwest::Error),
+    #[error("GraphQL errors: {}", format_errors(.0))]
+    GraphQL(Vec<GraphQLError>),
+    #[error("GraphQL response contains no data")]
+    NoData,
+    #[error("invalid client configuration: {0}")]
+    Config(reqwest::Error),
+}
+
+fn format_errors(errors: &[GraphQLError]) -> String {
+    errors
+        .iter()
+        .map(|error| error.message.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
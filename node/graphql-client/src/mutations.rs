@@ -0,0 +1,115 @@
+//! Mutations, which submit signed transactions to the node.
+//!
+//! Input types are named differently by the Rust and the OCaml node, so
+//! inputs are sent as literals of the document instead of variables.
+
+use std::borrow::Cow;
+
+use serde::Deserialize;
+
+use crate::GraphQLOperation;
+
+/// Submits a signed payment. Amounts and fees are in nanomina.
+#[derive(Debug, Clone)]
+pub struct SendPayment {
+    pub input: SendPaymentInput,
+    pub signature: UserCommandSignature,
+}
+
+#[derive(Debug, Clone)]
+pub struct SendPaymentInput {
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub fee: String,
+    pub memo: Option<String>,
+    /// Inferred from the sender's account by the node if not set.
+    pub nonce: Option<String>,
+    pub valid_until: Option<String>,
+}
+
+/// Signature as decimal field and scalar.
+#[derive(Debug, Clone)]
+pub struct UserCommandSignature {
+    pub field: String,
+    pub scalar: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SendPaymentData {
+    pub send_payment: SendPaymentPayload,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SendPaymentPayload {
+    pub payment: SendPaymentPayment,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SendPaymentPayment {
+    pub hash: String,
+    pub id: String,
+}
+
+impl GraphQLOperation for SendPayment {
+    type Data = SendPaymentData;
+
+    fn document(&self) -> Cow<'static, str> {
+        let input = &self.input;
+        let input = literal_object(&[
+            ("from", Some(&input.from)),
+            ("to", Some(&input.to)),
+            ("amount", Some(&input.amount)),
+            ("fee", Some(&input.fee)),
+            ("memo", input.memo.as_ref()),
+            ("nonce", input.nonce.as_ref()),
+            ("validUntil", input.valid_until.as_ref()),
+        ]);
+        let signature = literal_object(&[
+            ("field", Some(&self.signature.field)),
+            ("scalar", Some(&self.signature.scalar)),
+        ]);
+
+        format!(
+            r#"mutation SendPayment {{
+  sendPayment(input: {input}, signature: {signature}) {{
+    payment {{
+      hash
+      id
+    }}
+  }}
+}}"#
+        )
+        .into()
+    }
+}
+
+/// Input object literal with string fields. Fields set to `None` are
+/// omitted.
+fn literal_object(fields: &[(&str, Option<&String>)]) -> String {
+    let fields = fields
+        .iter()
+        .filter_map(|(name, value)| {
+            // JSON string escaping is valid in GraphQL string literals.
+            let value = serde_json::Value::from(value?.as_str());
+            Some(format!("{name}: {value}"))
+        })
+        .collect::<Vec<_>>();
+    format!("{{ {} }}", fields.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal_object_escapes_and_skips_missing_fields() {
+        let memo = "say \"hi\"".to_owned();
+        let fee = "10".to_owned();
+        assert_eq!(
+            literal_object(&[("memo", Some(&memo)), ("nonce", None), ("fee", Some(&fee))]),
+            r#"{ memo: "say \"hi\"", fee: "10" }"#
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use std::borrow::Cow;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::Error;
+
+/// Query or mutation with a typed response.
+pub trait GraphQLOperation {
+    /// Response data, deserialized from the `data` field.
+    type Data: DeserializeOwned;
+
+    /// GraphQL document sent to the node.
+    fn document(&self) -> Cow<'static, str>;
+
+    fn variables(&self) -> Option<serde_json::Value> {
+        None
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GraphQLRequest<'a> {
+    pub query: Cow<'a, str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variables: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GraphQLResponse<T> {
+    pub data: Option<T>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphQLError>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GraphQLError {
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<serde_json::Value>,
+}
+
+impl GraphQLRequest<'static> {
+    pub fn of<O: GraphQLOperation>(operation: &O) -> Self {
+        Self {
+            query: operation.document(),
+            variables: operation.variables(),
+        }
+    }
+}
+
+impl<T> GraphQLResponse<T> {
+    /// Data of a response without errors. Partial data returned along
+    /// with errors is discarded.
+    pub fn into_data(self) -> Result<T, Error> {
+        if !self.errors.is_empty() {
+            return Err(Error::GraphQL(self.errors));
+        }
+        self.data.ok_or(Error::NoData)
+    }
+}
@@ -0,0 +1,319 @@
+//! Queries supported by both the Rust and the OCaml node.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::GraphQLOperation;
+
+/// Sync status and network of the node, checked before sending
+/// transactions to it.
+#[derive(Debug, Clone, Default)]
+pub struct NodeStatus;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeStatusData {
+    /// One of `CONNECTING`, `LISTENING`, `OFFLINE`, `BOOTSTRAP`, `SYNCED`
+    /// or `CATCHUP`.
+    pub sync_status: String,
+    /// Network identifier, in the format `mina:<network_name>`.
+    #[serde(rename = "networkID")]
+    pub network_id: String,
+}
+
+impl GraphQLOperation for NodeStatus {
+    type Data = NodeStatusData;
+
+    fn document(&self) -> Cow<'static, str> {
+        r#"query NodeStatus {
+  syncStatus
+  networkID
+}"#
+        .into()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SyncStatus;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatusData {
+    pub sync_status: String,
+}
+
+impl GraphQLOperation for SyncStatus {
+    type Data = SyncStatusData;
+
+    fn document(&self) -> Cow<'static, str> {
+        "query SyncStatus { syncStatus }".into()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DaemonStatus;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonStatusData {
+    pub daemon_status: DaemonStatusDaemonStatus,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonStatusDaemonStatus {
+    pub chain_id: Option<String>,
+    pub state_hash: Option<String>,
+    pub blockchain_length: Option<u32>,
+}
+
+impl GraphQLOperation for DaemonStatus {
+    type Data = DaemonStatusData;
+
+    fn document(&self) -> Cow<'static, str> {
+        r#"query DaemonStatus {
+  daemonStatus {
+    chainId
+    stateHash
+    blockchainLength
+  }
+}"#
+        .into()
+    }
+}
+
+/// Account of the default token for a public key.
+#[derive(Debug, Clone)]
+pub struct Account {
+    pub public_key: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AccountData {
+    pub account: Option<AccountAccount>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountAccount {
+    pub balance: AccountBalance,
+    pub nonce: String,
+    pub delegate_account: Option<AccountDelegate>,
+}
+
+/// Balances in nanomina.
+#[derive(Deserialize, Debug, Clone)]
+pub struct AccountBalance {
+    pub total: String,
+    pub liquid: Option<String>,
+    pub locked: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDelegate {
+    pub public_key: String,
+}
+
+impl Account {
+    pub fn new(public_key: impl Into<String>) -> Self {
+        Self {
+            public_key: public_key.into(),
+        }
+    }
+}
+
+impl GraphQLOperation for Account {
+    type Data = AccountData;
+
+    fn document(&self) -> Cow<'static, str> {
+        r#"query Account($publicKey: String!) {
+  account(publicKey: $publicKey) {
+    balance {
+      total
+      liquid
+      locked
+    }
+    nonce
+    delegateAccount {
+      publicKey
+    }
+  }
+}"#
+        .into()
+    }
+
+    fn variables(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({ "publicKey": self.public_key }))
+    }
+}
+
+/// Current receipt chain hash of an account, committing to the commands it
+/// authorized, used to verify payment proofs.
+#[derive(Debug, Clone)]
+pub struct ReceiptChainHash {
+    pub public_key: String,
+    /// Token id, the default token when `None`.
+    pub token: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReceiptChainHashData {
+    pub account: Option<ReceiptChainHashAccount>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptChainHashAccount {
+    /// Base58Check encoded.
+    pub receipt_chain_hash: String,
+}
+
+impl ReceiptChainHash {
+    pub fn new(public_key: impl Into<String>, token: Option<String>) -> Self {
+        Self {
+            public_key: public_key.into(),
+            token,
+        }
+    }
+}
+
+impl GraphQLOperation for ReceiptChainHash {
+    type Data = ReceiptChainHashData;
+
+    fn document(&self) -> Cow<'static, str> {
+        r#"query ReceiptChainHash($publicKey: String!, $token: String) {
+  account(publicKey: $publicKey, token: $token) {
+    receiptChainHash
+  }
+}"#
+        .into()
+    }
+
+    fn variables(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "publicKey": self.public_key,
+            "token": self.token,
+        }))
+    }
+}
+
+/// Status of a payment, by its hash.
+#[derive(Debug, Clone)]
+pub struct TransactionStatus {
+    pub payment: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionStatusData {
+    /// One of `PENDING`, `INCLUDED` or `UNKNOWN`.
+    pub transaction_status: String,
+}
+
+impl TransactionStatus {
+    pub fn new(payment: impl Into<String>) -> Self {
+        Self {
+            payment: payment.into(),
+        }
+    }
+}
+
+impl GraphQLOperation for TransactionStatus {
+    type Data = TransactionStatusData;
+
+    fn document(&self) -> Cow<'static, str> {
+        r#"query TransactionStatus($payment: String!) {
+  transactionStatus(payment: $payment)
+}"#
+        .into()
+    }
+
+    fn variables(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({ "payment": self.payment }))
+    }
+}
+
+/// Payments and delegations in the transaction pool.
+#[derive(Debug, Clone, Default)]
+pub struct PooledUserCommands {
+    pub public_key: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PooledUserCommandsData {
+    pub pooled_user_commands: Vec<PooledUserCommand>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PooledUserCommand {
+    pub hash: String,
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub fee: String,
+    /// Returned as a string by the Rust node and as a number by the
+    /// OCaml node.
+    #[serde(deserialize_with = "string_or_number")]
+    pub nonce: String,
+}
+
+impl GraphQLOperation for PooledUserCommands {
+    type Data = PooledUserCommandsData;
+
+    fn document(&self) -> Cow<'static, str> {
+        r#"query PooledUserCommands($publicKey: String) {
+  pooledUserCommands(publicKey: $publicKey) {
+    hash
+    from
+    to
+    amount
+    fee
+    nonce
+  }
+}"#
+        .into()
+    }
+
+    fn variables(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({ "publicKey": self.public_key }))
+    }
+}
+
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber {
+        String(String),
+        Number(u64),
+    }
+
+    Ok(match StringOrNumber::deserialize(deserializer)? {
+        StringOrNumber::String(s) => s,
+        StringOrNumber::Number(n) => n.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pooled_nonce_accepts_string_and_number() {
+        let data: PooledUserCommandsData = serde_json::from_value(serde_json::json!({
+            "pooledUserCommands": [
+                { "hash": "a", "from": "b", "to": "c", "amount": "1", "fee": "2", "nonce": "3" },
+                { "hash": "a", "from": "b", "to": "c", "amount": "1", "fee": "2", "nonce": 4 },
+            ]
+        }))
+        .unwrap();
+        let nonces = data
+            .pooled_user_commands
+            .iter()
+            .map(|command| command.nonce.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(nonces, ["3", "4"]);
+    }
+}
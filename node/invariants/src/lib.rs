@@ -109,6 +109,7 @@ define_invariants_enum! {
     NoRecursion,
     P2pStatesAreConsistent,
     TransitionFrontierOnlySyncsToBetterBlocks,
+    TransitionFrontierPendingCoinbaseMatchesBestTip,
 }
 
 lazy_static::lazy_static! {
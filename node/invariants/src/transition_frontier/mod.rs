@@ -1,2 +1,5 @@
 mod only_syncs_to_better_blocks;
 pub use only_syncs_to_better_blocks::*;
+
+mod pending_coinbase_matches_best_tip;
+pub use pending_coinbase_matches_best_tip::*;
@@ -0,0 +1,96 @@
+use node::{ledger::LedgerService, ActionKind, ActionWithMeta, Service, Store};
+
+use crate::{Invariant, InvariantResult};
+
+/// Makes sure that pending coinbase collection of the best tip's staged
+/// ledger hashes to what is committed in the best tip, that its cached
+/// merkle tree hashes are consistent with the stacks, and that applying the
+/// best tip's diff to the collection of its parent gives the same root.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct TransitionFrontierPendingCoinbaseMatchesBestTip;
+
+impl Invariant for TransitionFrontierPendingCoinbaseMatchesBestTip {
+    type InternalState = ();
+    fn triggers(&self) -> &[ActionKind] {
+        &[ActionKind::TransitionFrontierSynced]
+    }
+
+    fn check<S: Service>(
+        self,
+        _: &mut Self::InternalState,
+        store: &Store<S>,
+        _action: &ActionWithMeta,
+    ) -> InvariantResult {
+        let Some(best_tip) = store.state().transition_frontier.best_tip() else {
+            return InvariantResult::Updated;
+        };
+        let staged_ledger_hashes = best_tip.staged_ledger_hashes();
+        let Some(pending_coinbase) = store
+            .service
+            .ledger_manager()
+            .pending_coinbase(staged_ledger_hashes)
+        else {
+            return InvariantResult::Updated;
+        };
+
+        if pending_coinbase.recomputed_merkle_root != pending_coinbase.merkle_root {
+            return InvariantResult::Violation(format!(
+                "pending coinbase merkle root doesn't match its stacks!\nblock({}): {}\nroot: {}\nrecomputed: {}",
+                best_tip.height(),
+                best_tip.hash(),
+                pending_coinbase.merkle_root,
+                pending_coinbase.recomputed_merkle_root,
+            ));
+        }
+        // The root recomputed from the applied blocks, independently of
+        // the collection stored for the best tip.
+        let best_chain = &store.state().transition_frontier.best_chain;
+        if let Some(pred_block) = best_chain.len().checked_sub(2).map(|i| &best_chain[i]) {
+            let replayed = match store
+                .service
+                .ledger_manager()
+                .pending_coinbase_replay(best_tip, pred_block)
+            {
+                Ok(replayed) => replayed,
+                Err(err) => {
+                    return InvariantResult::Violation(format!(
+                        "failed to replay the best tip on its parent!\nblock({}): {}\nerror: {err}",
+                        best_tip.height(),
+                        best_tip.hash(),
+                    ))
+                }
+            };
+            if replayed != pending_coinbase.merkle_root {
+                return InvariantResult::Violation(format!(
+                    "pending coinbase merkle root doesn't match the one replayed from the applied blocks!\nblock({}): {}\nroot: {}\nreplayed: {}",
+                    best_tip.height(),
+                    best_tip.hash(),
+                    pending_coinbase.merkle_root,
+                    replayed,
+                ));
+            }
+        }
+        if pending_coinbase.merkle_root != staged_ledger_hashes.pending_coinbase_hash {
+            return InvariantResult::Violation(format!(
+                "pending coinbase merkle root doesn't match the best tip!\nblock({}): {}\nexpected: {}\nfound: {}",
+                best_tip.height(),
+                best_tip.hash(),
+                staged_ledger_hashes.pending_coinbase_hash,
+                pending_coinbase.merkle_root,
+            ));
+        }
+        if pending_coinbase.pending_coinbase_aux
+            != staged_ledger_hashes.non_snark.pending_coinbase_aux
+        {
+            return InvariantResult::Violation(format!(
+                "pending coinbase aux hash doesn't match the best tip!\nblock({}): {}\nexpected: {}\nfound: {}",
+                best_tip.height(),
+                best_tip.hash(),
+                serde_json::to_string(&staged_ledger_hashes.non_snark.pending_coinbase_aux).unwrap(),
+                serde_json::to_string(&pending_coinbase.pending_coinbase_aux).unwrap(),
+            ));
+        }
+
+        InvariantResult::Ok
+    }
+}
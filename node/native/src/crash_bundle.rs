@@ -0,0 +1,128 @@
+//! Crash bundles, written to the work directory when the node panics, to be
+//! attached to reports. Along with the panic, they tell how the node was
+//! built and which of its optional subsystems it ran.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        OnceLock,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use node::{BuildEnv, EnabledFeatures};
+use serde::{Deserialize, Serialize};
+
+static CONTEXT: OnceLock<CrashBundleContext> = OnceLock::new();
+
+/// What the bundles include besides the panic, known once the node is built.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CrashBundleContext {
+    /// Directory the bundles are written to.
+    #[serde(skip)]
+    pub dir: PathBuf,
+    pub build: BuildEnv,
+    pub features: EnabledFeatures,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CrashBundle {
+    /// Milliseconds since the unix epoch.
+    pub time: u64,
+    pub thread: String,
+    pub location: String,
+    pub message: String,
+    pub backtrace: String,
+    #[serde(flatten)]
+    pub context: CrashBundleContext,
+}
+
+impl CrashBundleContext {
+    /// Sets the context of the bundles written by [`write`], only the first
+    /// call has an effect.
+    pub fn install(self) {
+        let _ = CONTEXT.set(self);
+    }
+}
+
+impl CrashBundle {
+    /// Writes the bundle to a new `crash-<time>-<n>.json` file in `dir`,
+    /// returns its path.
+    pub fn write_to(&self, dir: &Path) -> std::io::Result<PathBuf> {
+        // Other threads may panic in the same millisecond.
+        static COUNT: AtomicU32 = AtomicU32::new(0);
+        let n = COUNT.fetch_add(1, Ordering::Relaxed);
+
+        let path = dir.join(format!("crash-{}-{n}.json", self.time));
+        std::fs::create_dir_all(dir)?;
+        let file = std::fs::File::create_new(&path)?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(path)
+    }
+}
+
+/// Writes a bundle for a panic, called by the panic hook. Returns `None`
+/// if the node wasn't built yet, so that nothing tells where to write it.
+pub fn write(
+    thread: &str,
+    location: String,
+    message: &str,
+    backtrace: String,
+) -> Option<std::io::Result<PathBuf>> {
+    let context = CONTEXT.get()?;
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_millis() as u64);
+    let bundle = CrashBundle {
+        time,
+        thread: thread.to_owned(),
+        location,
+        message: message.to_owned(),
+        backtrace,
+        context: context.clone(),
+    };
+    Some(bundle.write_to(&context.dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_bundle_includes_features() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = CrashBundle {
+            time: 1_700_000_000_000,
+            thread: "main".to_owned(),
+            location: "node/src/lib.rs:1:1".to_owned(),
+            message: "oops".to_owned(),
+            backtrace: "0: main".to_owned(),
+            context: CrashBundleContext {
+                dir: dir.path().to_owned(),
+                build: BuildEnv::get(),
+                features: EnabledFeatures {
+                    compiled: vec!["p2p-webrtc".to_owned()],
+                    wasm: false,
+                    block_producer: true,
+                    snarker: false,
+                    archive: vec!["local".to_owned()],
+                },
+            },
+        };
+
+        let first = bundle.write_to(dir.path()).unwrap();
+        let second = bundle.write_to(dir.path()).unwrap();
+        assert_ne!(first, second, "bundles must not overwrite each other");
+
+        let json: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&first).unwrap()).unwrap();
+        assert_eq!(json["message"], "oops");
+        assert_eq!(json["thread"], "main");
+        assert_eq!(json["features"]["compiled"][0], "p2p-webrtc");
+        assert_eq!(json["features"]["block_producer"], true);
+        assert_eq!(json["features"]["archive"][0], "local");
+        assert_eq!(json["build"]["version"], BuildEnv::get().version);
+        assert!(json.get("dir").is_none());
+    }
+}
@@ -1,7 +1,7 @@
 use std::{collections::HashMap, sync::Arc};
 
-use dataloader::non_cached::Loader;
-use juniper::{graphql_object, FieldResult, GraphQLInputObject, GraphQLObject};
+use dataloader::cached::Loader;
+use juniper::{graphql_object, FieldResult, GraphQLInputObject, GraphQLObject, IntoFieldError};
 use ledger::{
     scan_state::currency::{Balance, Magnitude, Slot},
     Account, AccountId, FpExt, Timing,
@@ -17,8 +17,13 @@ use mina_p2p_messages::{
 use mina_signer::CompressedPubKey;
 use node::rpc::{AccountQuery, RpcRequest};
 
-use super::{Context, ConversionError};
+use super::{Context, ConversionError, Error};
 
+/// Loads accounts from the best tip ledger.
+///
+/// The accounts loaded while resolving a query are read together, with a
+/// single `location_of_account_batch` and `get_batch` on the ledger mask, and
+/// are cached for the lifetime of the request.
 pub(crate) type AccountLoader =
     Loader<AccountId, Result<GraphQLAccount, Arc<ConversionError>>, AccountBatcher>;
 
@@ -170,10 +175,11 @@ impl GraphQLAccount {
                 .await
                 .unwrap_or_default();
 
-            Ok(delegators
+            delegators
                 .into_iter()
                 .map(GraphQLAccount::try_from)
-                .collect::<Result<Vec<_>, _>>()?)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| Error::Conversion(e).into_field_error())
         } else {
             Ok(vec![])
         }
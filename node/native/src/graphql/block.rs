@@ -10,7 +10,7 @@ use mina_p2p_messages::v2::{
     MinaBaseStakeDelegationStableV2, TransactionSnarkWorkTStableV2,
 };
 use mina_signer::CompressedPubKey;
-use node::account::AccountPublicKey;
+use node::{account::AccountPublicKey, transition_frontier::BlockSummary};
 
 use super::{zkapp::GraphQLZkapp, Context, ConversionError};
 
@@ -89,6 +89,33 @@ impl GraphQLBlock {
     }
 }
 
+#[derive(GraphQLObject, Debug)]
+#[graphql(description = "Hashes and height of a block")]
+pub struct GraphQLBlockSummary {
+    pub state_hash: String,
+    pub previous_state_hash: String,
+    pub block_height: String,
+    pub global_slot_since_genesis: String,
+    /// Time of the block, in milliseconds since the Unix epoch
+    pub date: String,
+    pub snarked_ledger_hash: String,
+    pub staged_ledger_hash: String,
+}
+
+impl From<BlockSummary> for GraphQLBlockSummary {
+    fn from(summary: BlockSummary) -> Self {
+        Self {
+            state_hash: summary.hash.to_string(),
+            previous_state_hash: summary.pred_hash.to_string(),
+            block_height: summary.height.to_string(),
+            global_slot_since_genesis: summary.global_slot_since_genesis.to_string(),
+            date: (u64::from(summary.timestamp) / 1_000_000).to_string(),
+            snarked_ledger_hash: summary.snarked_ledger_hash.to_string(),
+            staged_ledger_hash: summary.staged_ledger_hash.to_string(),
+        }
+    }
+}
+
 #[derive(GraphQLObject, Debug)]
 pub struct GraphQLSnarkJob {
     pub fee: String,
@@ -1,17 +1,18 @@
-use juniper::GraphQLObject;
+use juniper::{GraphQLEnum, GraphQLObject, IntoFieldError};
 use mina_core::{
     consensus::{ConsensusConstants, ConsensusTime},
     constants::ConstraintConstants,
 };
 use node::{
+    p2p::{gossip::P2pGossipKind, peer::P2pPeerListDiff, webrtc::ConnectionStats},
     rpc::{
         ConsensusTimeQuery, PeerConnectionStatus, RpcConsensusTimeGetResponse,
         RpcNodeStatusNetworkInfo, RpcPeerInfo, RpcRequest,
     },
-    BuildEnv,
+    BuildEnv, EnabledFeatures,
 };
 
-use super::{Context, ConversionError, Error};
+use super::{block::GraphQLEpochData, Context, ConversionError, Error};
 
 #[derive(Clone, Debug, Copy)]
 pub(crate) struct GraphQLDaemonStatus;
@@ -26,7 +27,7 @@ impl GraphQLDaemonStatus {
             .rpc_sender
             .oneshot_request(RpcRequest::ConsensusConstantsGet)
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
         Ok(GraphQLConsensusConfiguration::from(consensus_constants))
     }
 
@@ -35,7 +36,7 @@ impl GraphQLDaemonStatus {
             .rpc_sender
             .oneshot_request(RpcRequest::PeersGet)
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
         let connected_peers = peers
             .iter()
@@ -54,7 +55,7 @@ impl GraphQLDaemonStatus {
             .rpc_sender
             .oneshot_request(RpcRequest::ConsensusTimeGet(ConsensusTimeQuery::Now))
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
         match consensus_time {
             Some(consensus_time) => Ok(GraphQLConsensusTime::from(consensus_time)),
@@ -73,7 +74,7 @@ impl GraphQLDaemonStatus {
             .rpc_sender
             .oneshot_request(RpcRequest::ConsensusTimeGet(ConsensusTimeQuery::BestTip))
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
         match consensus_time_res {
             Some(consensus_time) => Ok(GraphQLConsensusTime::from(consensus_time)),
@@ -84,6 +85,26 @@ impl GraphQLDaemonStatus {
         }
     }
 
+    /// Epoch data of the best tip for the current epoch, whose ledger is
+    /// used to select block producers.
+    async fn staking_epoch_data(
+        &self,
+        context: &Context,
+    ) -> juniper::FieldResult<Option<GraphQLEpochData>> {
+        let best_tip = context.get_or_fetch_best_tip().await;
+        Ok(best_tip.map(|best_tip| best_tip.consensus_state().staking_epoch_data.clone().into()))
+    }
+
+    /// Epoch data of the best tip for the next epoch. Its `epochLength`
+    /// counts the blocks of the current epoch up to the best tip.
+    async fn next_epoch_data(
+        &self,
+        context: &Context,
+    ) -> juniper::FieldResult<Option<GraphQLEpochData>> {
+        let best_tip = context.get_or_fetch_best_tip().await;
+        Ok(best_tip.map(|best_tip| best_tip.consensus_state().next_epoch_data.clone().into()))
+    }
+
     async fn consensus_mechanism(&self, _context: &Context) -> juniper::FieldResult<String> {
         Ok("proof_of_stake".to_string())
     }
@@ -255,6 +276,7 @@ pub struct GraphQLRpcPeerInfo {
     pub incoming: bool,
     pub is_libp2p: bool,
     pub time: String,
+    pub connection_stats: Option<GraphQLConnectionStats>,
 }
 
 impl From<&RpcPeerInfo> for GraphQLRpcPeerInfo {
@@ -273,6 +295,81 @@ impl From<&RpcPeerInfo> for GraphQLRpcPeerInfo {
             incoming: peer.incoming,
             is_libp2p: peer.is_libp2p,
             time: peer.time.to_string(),
+            connection_stats: peer
+                .connection_stats
+                .as_ref()
+                .map(GraphQLConnectionStats::from),
+        }
+    }
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct GraphQLConnectionStats {
+    pub rtt_ms: Option<f64>,
+    pub bytes_sent: String,
+    pub bytes_received: String,
+    pub requests_sent: String,
+    pub responses_received: String,
+    pub retransmissions_sent: Option<String>,
+    /// Share of the connectivity checks left unanswered, between 0 and 1
+    pub unanswered_requests_ratio: Option<f64>,
+}
+
+impl From<&ConnectionStats> for GraphQLConnectionStats {
+    fn from(stats: &ConnectionStats) -> Self {
+        Self {
+            rtt_ms: stats.rtt_ms,
+            bytes_sent: stats.bytes_sent.to_string(),
+            bytes_received: stats.bytes_received.to_string(),
+            requests_sent: stats.requests_sent.to_string(),
+            responses_received: stats.responses_received.to_string(),
+            retransmissions_sent: stats.retransmissions_sent.map(|n| n.to_string()),
+            unanswered_requests_ratio: stats.unanswered_requests_ratio(),
+        }
+    }
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct GraphQLPeerListRefresh {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub total: i32,
+}
+
+impl From<&P2pPeerListDiff> for GraphQLPeerListRefresh {
+    fn from(diff: &P2pPeerListDiff) -> Self {
+        Self {
+            added: diff.added.iter().map(ToString::to_string).collect(),
+            removed: diff.removed.iter().map(ToString::to_string).collect(),
+            total: diff.total.try_into().unwrap_or(i32::MAX),
+        }
+    }
+}
+
+#[derive(GraphQLEnum, Clone, Copy, Debug)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum GraphQLGossipKind {
+    BLOCK,
+    TRANSACTION,
+    SNARK,
+}
+
+impl From<GraphQLGossipKind> for P2pGossipKind {
+    fn from(kind: GraphQLGossipKind) -> Self {
+        match kind {
+            GraphQLGossipKind::BLOCK => Self::Block,
+            GraphQLGossipKind::TRANSACTION => Self::Transaction,
+            GraphQLGossipKind::SNARK => Self::Snark,
+        }
+    }
+}
+
+impl From<P2pGossipKind> for GraphQLGossipKind {
+    fn from(kind: P2pGossipKind) -> Self {
+        match kind {
+            P2pGossipKind::Block => Self::BLOCK,
+            P2pGossipKind::Transaction => Self::TRANSACTION,
+            P2pGossipKind::Snark => Self::SNARK,
         }
     }
 }
@@ -344,3 +441,29 @@ impl From<ConsensusTime> for GraphQLConsensusTime {
         }
     }
 }
+
+#[derive(GraphQLObject, Debug)]
+#[graphql(description = "Optional subsystems the node was compiled with and runs")]
+pub struct GraphQLEnabledFeatures {
+    /// Cargo features the node was built with, e.g. `p2p-webrtc`
+    pub compiled: Vec<String>,
+    /// Whether the node runs in the browser, built for webassembly
+    pub wasm: bool,
+    pub block_producer: bool,
+    pub snarker: bool,
+    /// Storages the applied blocks are archived to, empty if archiving is
+    /// disabled
+    pub archive: Vec<String>,
+}
+
+impl From<EnabledFeatures> for GraphQLEnabledFeatures {
+    fn from(features: EnabledFeatures) -> Self {
+        Self {
+            compiled: features.compiled,
+            wasm: features.wasm,
+            block_producer: features.block_producer,
+            snarker: features.snarker,
+            archive: features.archive,
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use juniper::{GraphQLInputObject, GraphQLObject};
+use ledger::{
+    scan_state::transaction_logic::zkapp_command::Event,
+    staged_ledger::action_event_store::ActionsEvents, AccountId, FpExt,
+};
+use mina_p2p_messages::v2::TokenIdKeyHash;
+use mina_signer::CompressedPubKey;
+use node::{account::AccountPublicKey, rpc::RpcZkappActionsEventsQuery};
+use std::str::FromStr;
+
+use super::ConversionError;
+
+/// Account whose actions or events are queried, like the input of the
+/// `events` and `actions` queries of the archive node API.
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(name = "EventFilterOptionsInput")]
+pub struct InputGraphQLEventFilterOptions {
+    /// Base58-encoded public key of the zkApp
+    pub address: String,
+    /// Base58-encoded token ID, the default token if not set
+    pub token_id: Option<String>,
+    /// First global slot since genesis to include
+    pub from: Option<i32>,
+    /// Last global slot since genesis to include
+    pub to: Option<i32>,
+}
+
+#[derive(GraphQLObject, Debug)]
+#[graphql(name = "BlockInfo")]
+pub struct GraphQLBlockInfo {
+    pub state_hash: String,
+    pub global_slot_since_genesis: i32,
+}
+
+/// One event, or one action, with the account update which emitted it.
+#[derive(GraphQLObject, Debug)]
+#[graphql(name = "ActionEventData")]
+pub struct GraphQLActionEventData {
+    /// Index of the zkApp command among the commands of the block
+    pub transaction_index: i32,
+    /// Index of the account update in the zkApp command
+    pub account_update_index: i32,
+    /// Field elements, as decimal strings
+    pub data: Vec<String>,
+}
+
+#[derive(GraphQLObject, Debug)]
+#[graphql(name = "EventOutput")]
+pub struct GraphQLEventOutput {
+    pub block_info: GraphQLBlockInfo,
+    pub event_data: Vec<GraphQLActionEventData>,
+}
+
+#[derive(GraphQLObject, Debug)]
+#[graphql(name = "ActionOutput")]
+pub struct GraphQLActionOutput {
+    pub block_info: GraphQLBlockInfo,
+    pub action_data: Vec<GraphQLActionEventData>,
+}
+
+impl TryFrom<InputGraphQLEventFilterOptions> for RpcZkappActionsEventsQuery {
+    type Error = ConversionError;
+
+    fn try_from(input: InputGraphQLEventFilterOptions) -> Result<Self, Self::Error> {
+        let public_key = AccountPublicKey::from_str(&input.address)?;
+        let token_id = match input.token_id {
+            Some(token_id) => TokenIdKeyHash::from_str(&token_id)?.into(),
+            None => Default::default(),
+        };
+        Ok(Self {
+            account_id: AccountId::new(
+                CompressedPubKey::try_from(public_key.as_ref())
+                    .map_err(|_| ConversionError::InvalidBigInt)?,
+                token_id,
+            ),
+            from_slot: input.from.map(u32::try_from).transpose()?,
+            to_slot: input.to.map(u32::try_from).transpose()?,
+        })
+    }
+}
+
+/// Events, or actions, of `entries` grouped by block, oldest first.
+pub fn by_block(
+    entries: Vec<ActionsEvents>,
+    data: impl Fn(&ActionsEvents) -> &[Event],
+) -> Vec<(GraphQLBlockInfo, Vec<GraphQLActionEventData>)> {
+    let mut blocks: Vec<(GraphQLBlockInfo, Vec<GraphQLActionEventData>)> = Vec::new();
+    for entry in &entries {
+        let state_hash = entry.state_hash.to_string();
+        let is_new_block = blocks
+            .last()
+            .is_none_or(|(block_info, _)| block_info.state_hash != state_hash);
+        if is_new_block {
+            blocks.push((
+                GraphQLBlockInfo {
+                    state_hash,
+                    global_slot_since_genesis: entry.global_slot.as_u32() as i32,
+                },
+                Vec::new(),
+            ));
+        }
+        let Some((_, block_data)) = blocks.last_mut() else {
+            continue;
+        };
+        block_data.extend(data(entry).iter().map(|event| GraphQLActionEventData {
+            transaction_index: entry.transaction_index as i32,
+            account_update_index: entry.account_update_index as i32,
+            data: event.0.iter().map(|f| f.to_decimal()).collect(),
+        }));
+    }
+    blocks.retain(|(_, data)| !data.is_empty());
+    blocks
+}
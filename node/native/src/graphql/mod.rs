@@ -1,15 +1,17 @@
 use account::{create_account_loader, AccountLoader, GraphQLAccount};
-use block::{GraphQLBlock, GraphQLSnarkJob, GraphQLUserCommands};
-use juniper::{graphql_value, EmptySubscription, FieldError, GraphQLEnum, RootNode};
-use ledger::{Account, AccountId};
+use block::{GraphQLBlock, GraphQLBlockSummary, GraphQLSnarkJob, GraphQLUserCommands};
+use juniper::{
+    graphql_value, EmptySubscription, FieldError, GraphQLEnum, IntoFieldError, RootNode,
+};
+use ledger::{staged_ledger::action_event_store::ActionsEvents, Account, AccountId};
 use mina_core::{
-    block::AppliedBlock, consensus::ConsensusConstants, constants::constraint_constants,
-    NetworkConfig,
+    block::AppliedBlock, consensus::ConsensusConstants, constants::constraint_constants, ErrorCode,
+    HasErrorCode, NetworkConfig,
 };
 use mina_node_common::rpc::RpcSender;
 use mina_p2p_messages::v2::{
     conv, LedgerHash, MinaBaseSignedCommandStableV2, MinaBaseUserCommandStableV2,
-    MinaBaseZkappCommandTStableV1WireStableV1, TokenIdKeyHash, TransactionHash,
+    MinaBaseZkappCommandTStableV1WireStableV1, StateHash, TokenIdKeyHash, TransactionHash,
 };
 use mina_signer::CompressedPubKey;
 use node::{
@@ -17,27 +19,32 @@ use node::{
     ledger::read::LedgerStatus,
     rpc::{
         AccountQuery, GetBlockQuery, PooledCommandsQuery, RpcBestChainResponse,
-        RpcGenesisBlockResponse, RpcGetBlockResponse, RpcLedgerAccountDelegatorsGetResponse,
-        RpcLedgerStatusGetResponse, RpcNodeStatus, RpcPooledUserCommandsResponse,
-        RpcPooledZkappCommandsResponse, RpcRequest, RpcSnarkPoolCompletedJobsResponse,
-        RpcSnarkPoolPendingJobsGetResponse, RpcSnarkerConfig, RpcStatusGetResponse,
-        RpcSyncStatsGetResponse, RpcTransactionInjectResponse, RpcTransactionStatusGetResponse,
-        SyncStatsQuery,
+        RpcBlockSummaryGetResponse, RpcEnabledFeaturesGetResponse, RpcGenesisBlockResponse,
+        RpcGetBlockResponse, RpcGossipPauseResponse, RpcLedgerAccountDelegatorsGetResponse,
+        RpcLedgerAccountsAtBlockGetResponse, RpcLedgerStatusGetResponse,
+        RpcLocallyGeneratedCommandsGetResponse, RpcNodeStatus, RpcPeerListRefreshResponse,
+        RpcPooledUserCommandsResponse, RpcPooledZkappCommandsResponse, RpcRequest,
+        RpcSnarkPoolCompletedJobsResponse, RpcSnarkPoolPendingJobsGetResponse,
+        RpcSnarkStatsGetResponse, RpcSnarkerConfig, RpcStatusGetResponse, RpcSyncStatsGetResponse,
+        RpcTokenSupplyGetResponse, RpcTransactionInjectResponse, RpcTransactionStatusGetResponse,
+        RpcZkappActionsEventsGetResponse, RpcZkappActionsEventsQuery, SyncStatsQuery,
+        RPC_PAGE_DEFAULT_LIMIT, RPC_PAGE_MAX_LIMIT,
     },
     stats::sync::SyncKind,
     BuildEnv,
 };
 use o1_utils::field_helpers::FieldHelpersError;
-use snark::{GraphQLPendingSnarkWork, GraphQLSnarkWorker};
+use snark::{GraphQLPendingSnarkWork, GraphQLSnarkStats, GraphQLSnarkWorker};
 use std::str::FromStr;
 use tokio::sync::OnceCell;
-use transaction::GraphQLTransactionStatus;
+use transaction::{GraphQLLocallyGeneratedCommand, GraphQLTransactionStatus};
 use warp::{Filter, Rejection, Reply};
 use zkapp::GraphQLZkapp;
 
 pub mod account;
 pub mod block;
 pub mod constants;
+pub mod events;
 pub mod snark;
 pub mod transaction;
 pub mod user_command;
@@ -54,6 +61,8 @@ pub enum Error {
     StateMachineEmptyResponse,
     #[error("Custom: {0}")]
     Custom(String),
+    #[error("Unavailable: {0}")]
+    Unavailable(String),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -92,6 +101,8 @@ pub enum ConversionError {
     FieldHelpers(#[from] FieldHelpersError),
     #[error("Failed to convert integer to i32")]
     Integer,
+    #[error(transparent)]
+    AuthorizationMismatch(#[from] zkapp::AuthorizationMismatch),
 }
 
 impl From<ConversionError> for Error {
@@ -100,6 +111,51 @@ impl From<ConversionError> for Error {
     }
 }
 
+impl HasErrorCode for Error {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Error::Conversion(ConversionError::AuthorizationMismatch(_)) => {
+                ErrorCode::InvalidTransaction
+            }
+            Error::Conversion(_) | Error::Custom(_) => ErrorCode::InvalidInput,
+            Error::StateMachineEmptyResponse => ErrorCode::ResponseChannelDropped,
+            Error::Unavailable(_) => ErrorCode::Unavailable,
+        }
+    }
+}
+
+/// Errors with a code in their extensions. Resolvers must convert them
+/// explicitly, `?` goes through juniper's `From<impl Display>` for
+/// [`FieldError`] and drops the extensions.
+impl IntoFieldError for Error {
+    fn into_field_error(self) -> FieldError {
+        let code = self.error_code();
+        let extensions = match &self {
+            Error::Conversion(ConversionError::AuthorizationMismatch(mismatch)) => {
+                let index = i32::try_from(mismatch.index).unwrap_or(i32::MAX);
+                graphql_value!({
+                    "code": code.as_str(),
+                    "numericCode": i32::from(code.code()),
+                    "accountUpdateIndex": index,
+                    "authorizationKind": mismatch.kind.to_string(),
+                    "authorization": mismatch.authorization.to_string()
+                })
+            }
+            _ => error_code_extensions(code),
+        };
+        FieldError::new(self, extensions)
+    }
+}
+
+/// Extensions of a GraphQL error, with its code from the [`ErrorCode`]
+/// catalog.
+fn error_code_extensions(code: ErrorCode) -> juniper::Value {
+    graphql_value!({
+        "code": code.as_str(),
+        "numericCode": i32::from(code.code())
+    })
+}
+
 /// Context for the GraphQL API
 ///
 /// This is used to share state between the GraphQL queries and mutations.
@@ -173,6 +229,44 @@ impl Context {
         self.account_loader.try_load(account_id).await.ok()?.ok()
     }
 
+    /// Accounts of the ledger at the block `state_hash`. Read without the
+    /// loader, which batches accounts of the best tip ledger only.
+    pub(crate) async fn fetch_accounts_at_block(
+        &self,
+        state_hash: StateHash,
+        account_query: AccountQuery,
+    ) -> Result<Vec<Account>, Error> {
+        let res: RpcLedgerAccountsAtBlockGetResponse = self
+            .rpc_sender
+            .oneshot_request(RpcRequest::LedgerAccountsAtBlockGet(
+                state_hash,
+                account_query,
+            ))
+            .await
+            .ok_or(Error::StateMachineEmptyResponse)?;
+        res.map_err(|err| Error::Custom(err.to_string()))
+    }
+
+    /// Actions and events of an account, fails if the node doesn't keep
+    /// them.
+    pub(crate) async fn fetch_actions_events(
+        &self,
+        input: events::InputGraphQLEventFilterOptions,
+    ) -> Result<Vec<ActionsEvents>, Error> {
+        let query = RpcZkappActionsEventsQuery::try_from(input)?;
+        let response: RpcZkappActionsEventsGetResponse = self
+            .rpc_sender
+            .oneshot_request(RpcRequest::ZkappActionsEventsGet(query))
+            .await
+            .ok_or(Error::StateMachineEmptyResponse)?;
+        response.ok_or_else(|| {
+            Error::Unavailable(
+                "zkApp actions and events aren't kept, see --archive-zkapp-actions-events"
+                    .to_owned(),
+            )
+        })
+    }
+
     pub async fn fetch_delegators(
         &self,
         ledger_hash: LedgerHash,
@@ -208,6 +302,8 @@ pub struct Query;
 ///
 /// ## Account Management
 /// - `account` - Retrieve account information for a public key
+/// - `accounts` - Retrieve all the accounts of a public key, one per token
+/// - `token_accounts` - Retrieve all the accounts of a token
 /// - `current_snark_worker` - Get information about the current SNARK worker
 ///
 /// ## Blockchain State
@@ -223,7 +319,11 @@ pub struct Query;
 ///   pool
 /// - `pooled_zkapp_commands` - Query pending zkApp commands in the transaction
 ///   pool
+/// - `locally_generated_commands` - Query the commands submitted to this node
+///   and rebroadcast by it
 /// - `transaction_status` - Check the status of a transaction
+/// - `transaction_hash` - Compute the hash of a transaction without
+///   submitting it
 ///
 /// ## SNARK Pool
 /// - `snark_pool` - Get completed SNARK jobs
@@ -231,6 +331,7 @@ pub struct Query;
 ///
 /// ## Network Information
 /// - `network_id` - Get the chain-agnostic network identifier
+/// - `initial_peers` - Get the peers the node first connected to
 /// - `version` - Get the node version (git commit hash)
 #[juniper::graphql_object(context = Context)]
 impl Query {
@@ -239,33 +340,210 @@ impl Query {
     /// # Arguments
     /// - `public_key`: Base58-encoded public key
     /// - `token`: Optional token ID (defaults to MINA token if not provided)
+    /// - `state_hash`: Optional state hash of the block to read the account
+    ///   at (defaults to the best tip). Fails if the block was pruned from the
+    ///   transition frontier
     ///
     /// # Returns
     /// Account information including balance, nonce, delegate, and other fields
     async fn account(
         public_key: String,
         token: Option<String>,
+        state_hash: Option<String>,
         context: &Context,
     ) -> juniper::FieldResult<account::GraphQLAccount> {
-        let public_key = AccountPublicKey::from_str(&public_key)?;
-        let req = match token {
-            None => AccountQuery::SinglePublicKey(public_key),
-            Some(token) => {
-                let token_id = TokenIdKeyHash::from_str(&token)?;
-                AccountQuery::PubKeyWithTokenId(public_key, token_id)
+        let public_key = AccountPublicKey::from_str(&public_key)
+            .map_err(|e| Error::Conversion(e.into()).into_field_error())?;
+        let token_id = match token {
+            None => TokenIdKeyHash::default(),
+            Some(token) => TokenIdKeyHash::from_str(&token)
+                .map_err(|e| Error::Conversion(e.into()).into_field_error())?,
+        };
+        let account_id = AccountId {
+            public_key: CompressedPubKey::try_from(public_key.as_ref()).map_err(|_| {
+                Error::Conversion(ConversionError::InvalidBigInt).into_field_error()
+            })?,
+            token_id: token_id.into(),
+        };
+
+        if let Some(state_hash) = state_hash {
+            let account = context
+                .fetch_accounts_at_block(
+                    state_hash
+                        .parse::<StateHash>()
+                        .map_err(|e| Error::Conversion(e.into()).into_field_error())?,
+                    AccountQuery::MultipleIds(vec![account_id]),
+                )
+                .await
+                .map_err(Error::into_field_error)?
+                .into_iter()
+                .next()
+                .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
+            return GraphQLAccount::try_from(account)
+                .map_err(|e| Error::Conversion(e).into_field_error());
+        }
+
+        // Through the loader, so that all the accounts of the query are read
+        // from the ledger at once.
+        context
+            .load_account(account_id)
+            .await
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())
+    }
+
+    /// Retrieve all the accounts of a public key, one for each token it
+    /// holds
+    ///
+    /// # Arguments
+    /// - `public_key`: Base58-encoded public key
+    /// - `state_hash`: Optional state hash of the block to read the accounts
+    ///   at (defaults to the best tip). Fails if the block was pruned from the
+    ///   transition frontier
+    async fn accounts(
+        public_key: String,
+        state_hash: Option<String>,
+        context: &Context,
+    ) -> juniper::FieldResult<Vec<account::GraphQLAccount>> {
+        let public_key = AccountPublicKey::from_str(&public_key)
+            .map_err(|e| Error::Conversion(e.into()).into_field_error())?;
+        let account_query = AccountQuery::SinglePublicKey(public_key);
+        let accounts: Vec<Account> = match state_hash {
+            Some(state_hash) => {
+                let state_hash = state_hash
+                    .parse::<StateHash>()
+                    .map_err(|e| Error::Conversion(e.into()).into_field_error())?;
+                context
+                    .fetch_accounts_at_block(state_hash, account_query)
+                    .await
+                    .map_err(Error::into_field_error)?
             }
+            None => context
+                .rpc_sender
+                .oneshot_request(RpcRequest::LedgerAccountsGet(account_query))
+                .await
+                .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?,
+        };
+
+        accounts
+            .into_iter()
+            .map(account::GraphQLAccount::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Error::Conversion(e).into_field_error())
+    }
+
+    /// Retrieve a page of the accounts of a token, which are its holders
+    ///
+    /// # Arguments
+    /// - `token`: Base58-encoded token ID
+    /// - `offset`: Number of accounts to skip, 0 if not set
+    /// - `limit`: Maximum number of accounts to return, 100 if not set and
+    ///   at most 1000
+    ///
+    /// # Returns
+    /// Accounts of the token in the best tip ledger, in the order of their
+    /// index
+    async fn token_accounts(
+        token: String,
+        offset: Option<i32>,
+        limit: Option<i32>,
+        context: &Context,
+    ) -> juniper::FieldResult<Vec<account::GraphQLAccount>> {
+        let token_id = TokenIdKeyHash::from_str(&token)
+            .map_err(|e| Error::Conversion(e.into()).into_field_error())?;
+        let offset = offset
+            .map(usize::try_from)
+            .transpose()
+            .map_err(|e| Error::Conversion(e.into()).into_field_error())?
+            .unwrap_or(0);
+        let limit = limit
+            .map(usize::try_from)
+            .transpose()
+            .map_err(|e| Error::Conversion(e.into()).into_field_error())?
+            .unwrap_or(RPC_PAGE_DEFAULT_LIMIT)
+            .min(RPC_PAGE_MAX_LIMIT);
+        let query = AccountQuery::Token {
+            token_id,
+            offset,
+            limit,
         };
         let accounts: Vec<Account> = context
             .rpc_sender
-            .oneshot_request(RpcRequest::LedgerAccountsGet(req))
+            .oneshot_request(RpcRequest::LedgerAccountsGet(query))
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
-        Ok(accounts
-            .first()
-            .cloned()
-            .ok_or(Error::StateMachineEmptyResponse)?
-            .try_into()?)
+        accounts
+            .into_iter()
+            .map(account::GraphQLAccount::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Error::Conversion(e).into_field_error())
+    }
+
+    /// Retrieve the supply of a token
+    ///
+    /// # Arguments
+    /// - `token`: Base58-encoded token ID
+    ///
+    /// # Returns
+    /// Sum of the balances of the accounts of the token in the best tip
+    /// ledger, in nanounits, or null if it doesn't fit in an amount
+    async fn token_supply(
+        token: String,
+        context: &Context,
+    ) -> juniper::FieldResult<Option<String>> {
+        let token_id = TokenIdKeyHash::from_str(&token)
+            .map_err(|e| Error::Conversion(e.into()).into_field_error())?;
+        let supply: RpcTokenSupplyGetResponse = context
+            .rpc_sender
+            .oneshot_request(RpcRequest::TokenSupplyGet(token_id))
+            .await
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
+
+        Ok(supply.map(|supply| supply.as_u64().to_string()))
+    }
+
+    /// Events emitted by the account updates of a zkApp in the best chain,
+    /// kept when the node runs with `--archive-zkapp-actions-events`
+    ///
+    /// # Returns
+    /// Events grouped by block, oldest first
+    async fn events(
+        input: events::InputGraphQLEventFilterOptions,
+        context: &Context,
+    ) -> juniper::FieldResult<Vec<events::GraphQLEventOutput>> {
+        let entries = context
+            .fetch_actions_events(input)
+            .await
+            .map_err(Error::into_field_error)?;
+        Ok(events::by_block(entries, |entry| entry.events.as_slice())
+            .into_iter()
+            .map(|(block_info, event_data)| events::GraphQLEventOutput {
+                block_info,
+                event_data,
+            })
+            .collect())
+    }
+
+    /// Actions dispatched by the account updates of a zkApp in the best
+    /// chain, kept when the node runs with `--archive-zkapp-actions-events`
+    ///
+    /// # Returns
+    /// Actions grouped by block, oldest first
+    async fn actions(
+        input: events::InputGraphQLEventFilterOptions,
+        context: &Context,
+    ) -> juniper::FieldResult<Vec<events::GraphQLActionOutput>> {
+        let entries = context
+            .fetch_actions_events(input)
+            .await
+            .map_err(Error::into_field_error)?;
+        Ok(events::by_block(entries, |entry| entry.actions.as_slice())
+            .into_iter()
+            .map(|(block_info, action_data)| events::GraphQLActionOutput {
+                block_info,
+                action_data,
+            })
+            .collect())
     }
 
     /// Get the current synchronization status of the node
@@ -277,7 +555,7 @@ impl Query {
             .rpc_sender
             .oneshot_request(RpcRequest::SyncStatsGet(SyncStatsQuery { limit: Some(1) }))
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
         if let Some(state) = state.as_ref().and_then(|s| s.first()) {
             if state.synced.is_some() {
@@ -308,12 +586,13 @@ impl Query {
             .rpc_sender
             .oneshot_request(RpcRequest::BestChain(max_length as u32))
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
-        Ok(best_chain
+        best_chain
             .into_iter()
             .map(|v| v.try_into())
-            .collect::<Result<Vec<_>, _>>()?)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Error::Conversion(e).into_field_error())
     }
 
     /// Get daemon status information including chain ID and configuration
@@ -337,13 +616,14 @@ impl Query {
             .rpc_sender
             .oneshot_request(RpcRequest::ConsensusConstantsGet)
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
         let constraint_constants = constraint_constants();
 
-        Ok(constants::GraphQLGenesisConstants::try_new(
+        constants::GraphQLGenesisConstants::try_new(
             constraint_constants.clone(),
             consensus_constants,
-        )?)
+        )
+        .map_err(|e| Error::Conversion(e).into_field_error())
     }
 
     /// Check the status of a transaction
@@ -352,42 +632,54 @@ impl Query {
     /// - `payment`: Base64-encoded signed command (mutually exclusive with
     ///   zkapp_transaction)
     /// - `zkapp_transaction`: Base64-encoded zkApp command (mutually exclusive with payment)
+    /// - `state_hash`: Optional state hash of the block to check the inclusion
+    ///   at (defaults to the best tip). Fails if the block was pruned from the
+    ///   transition frontier. The transaction pool is always checked at its
+    ///   current state
     ///
     /// # Returns
     /// Transaction status (PENDING, INCLUDED, or UNKNOWN)
     async fn transaction_status(
         payment: Option<String>,
         zkapp_transaction: Option<String>,
+        state_hash: Option<String>,
         context: &Context,
     ) -> juniper::FieldResult<GraphQLTransactionStatus> {
         if payment.is_some() && zkapp_transaction.is_some() {
             return Err(Error::Custom(
                 "Cannot provide both payment and zkapp transaction".to_string(),
             )
-            .into());
+            .into_field_error());
         }
 
         let tx = if let Some(payment) = payment {
-            MinaBaseUserCommandStableV2::SignedCommand(MinaBaseSignedCommandStableV2::from_base64(
-                &payment,
-            )?)
+            MinaBaseUserCommandStableV2::SignedCommand(
+                MinaBaseSignedCommandStableV2::from_base64(&payment)
+                    .map_err(|e| Error::Conversion(e.into()).into_field_error())?,
+            )
         } else if let Some(zkapp_transaction) = zkapp_transaction {
             MinaBaseUserCommandStableV2::ZkappCommand(
-                MinaBaseZkappCommandTStableV1WireStableV1::from_base64(&zkapp_transaction)?,
+                MinaBaseZkappCommandTStableV1WireStableV1::from_base64(&zkapp_transaction)
+                    .map_err(|e| Error::Conversion(e.into()).into_field_error())?,
             )
         } else {
             return Err(Error::Custom(
                 "Must provide either payment or zkapp transaction".to_string(),
             )
-            .into());
+            .into_field_error());
         };
+        let state_hash = state_hash
+            .map(|hash| hash.parse::<StateHash>())
+            .transpose()
+            .map_err(|e| Error::Conversion(e.into()).into_field_error())?;
         let res: RpcTransactionStatusGetResponse = context
             .rpc_sender
-            .oneshot_request(RpcRequest::TransactionStatusGet(tx))
+            .oneshot_request(RpcRequest::TransactionStatusGet(tx, state_hash))
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
-        Ok(GraphQLTransactionStatus::from(res))
+        let status = res.map_err(|err| Error::Custom(err.to_string()).into_field_error())?;
+        Ok(GraphQLTransactionStatus::from(status))
     }
 
     /// Retrieve a block with the given state hash or height from the transition frontier
@@ -403,16 +695,7 @@ impl Query {
         state_hash: Option<String>,
         context: &Context,
     ) -> juniper::FieldResult<GraphQLBlock> {
-        let query = match (height, state_hash) {
-            (Some(height), None) => GetBlockQuery::Height(height.try_into().unwrap_or(u32::MAX)),
-            (None, Some(state_hash)) => GetBlockQuery::Hash(state_hash.parse()?),
-            _ => {
-                return Err(Error::Custom(
-                    "Must provide exactly one of state hash, height".to_owned(),
-                )
-                .into());
-            }
-        };
+        let query = get_block_query(height, state_hash).map_err(Error::into_field_error)?;
 
         let res: Option<RpcGetBlockResponse> = context
             .rpc_sender
@@ -420,20 +703,43 @@ impl Query {
             .await;
 
         match res {
-            None => Err(Error::Custom("response channel dropped".to_owned()).into()),
+            None => Err(Error::StateMachineEmptyResponse.into_field_error()),
             Some(None) => match query {
                 GetBlockQuery::Hash(hash) => Err(Error::Custom(format!(
                     "Could not find block with hash: `{}` in transition frontier",
                     hash
                 ))
-                .into()),
+                .into_field_error()),
                 GetBlockQuery::Height(height) => Err(Error::Custom(format!(
                     "Could not find block with height: `{}` in transition frontier",
                     height
                 ))
-                .into()),
+                .into_field_error()),
             },
-            Some(Some(block)) => Ok(GraphQLBlock::try_from(block)?),
+            Some(Some(block)) => {
+                GraphQLBlock::try_from(block).map_err(|e| Error::Conversion(e).into_field_error())
+            }
+        }
+    }
+
+    /// Hashes and height of a block of the transition frontier, or of a
+    /// block finalized recently enough to still be in the root history of
+    /// the node
+    async fn block_summary(
+        height: Option<i32>,
+        state_hash: Option<String>,
+        context: &Context,
+    ) -> juniper::FieldResult<Option<GraphQLBlockSummary>> {
+        let query = get_block_query(height, state_hash).map_err(Error::into_field_error)?;
+
+        let res: Option<RpcBlockSummaryGetResponse> = context
+            .rpc_sender
+            .oneshot_request(RpcRequest::BlockSummaryGet(query))
+            .await;
+
+        match res {
+            None => Err(Error::StateMachineEmptyResponse.into_field_error()),
+            Some(summary) => Ok(summary.map(GraphQLBlockSummary::from)),
         }
     }
 
@@ -457,18 +763,19 @@ impl Query {
             hashes,
             ids,
             MinaBaseSignedCommandStableV2::from_base64,
-        )?;
+        )
+        .map_err(|e| Error::Conversion(e).into_field_error())?;
 
         let res: RpcPooledUserCommandsResponse = context
             .rpc_sender
             .oneshot_request(RpcRequest::PooledUserCommands(query))
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
-        Ok(res
-            .into_iter()
+        res.into_iter()
             .map(GraphQLUserCommands::try_from)
-            .collect::<Result<Vec<_>, _>>()?)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Error::Conversion(e).into_field_error())
     }
 
     /// Retrieve all the scheduled zkApp commands for a specified sender that
@@ -490,36 +797,57 @@ impl Query {
             hashes,
             ids,
             MinaBaseZkappCommandTStableV1WireStableV1::from_base64,
-        )?;
+        )
+        .map_err(|e| Error::Conversion(e).into_field_error())?;
 
         let res: RpcPooledZkappCommandsResponse = context
             .rpc_sender
             .oneshot_request(RpcRequest::PooledZkappCommands(query))
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
-        Ok(res
-            .into_iter()
+        res.into_iter()
             .map(GraphQLZkapp::try_from)
-            .collect::<Result<Vec<_>, _>>()?)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Error::Conversion(e).into_field_error())
+    }
+
+    /// Retrieve the commands submitted to this daemon, which it rebroadcasts
+    /// every 10 minutes until they are included in the best chain, for up to
+    /// 50 minutes. Included commands are kept for the same duration.
+    async fn locally_generated_commands(
+        context: &Context,
+    ) -> juniper::FieldResult<Vec<GraphQLLocallyGeneratedCommand>> {
+        let res: RpcLocallyGeneratedCommandsGetResponse = context
+            .rpc_sender
+            .oneshot_request(RpcRequest::LocallyGeneratedCommandsGet)
+            .await
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
+
+        res.into_iter()
+            .map(GraphQLLocallyGeneratedCommand::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Error::Conversion(e).into_field_error())
     }
 
     /// Retrieve the genesis block
     ///
     /// # Returns
-    /// The genesis block data
+    /// The genesis block data, including its hash, protocol state and the
+    /// genesis winner account
     async fn genesis_block(context: &Context) -> juniper::FieldResult<GraphQLBlock> {
         let block = context
             .rpc_sender
             .oneshot_request::<RpcGenesisBlockResponse>(RpcRequest::GenesisBlockGet)
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
-        Ok(GraphQLBlock::try_from(AppliedBlock {
+        GraphQLBlock::try_from(AppliedBlock {
             block,
             just_emitted_a_proof: false,
-        })?)
+        })
+        .map_err(|e| Error::Conversion(e).into_field_error())
     }
 
     /// Get completed SNARK jobs from the SNARK pool
@@ -531,7 +859,7 @@ impl Query {
             .rpc_sender
             .oneshot_request(RpcRequest::SnarkPoolCompletedJobsGet)
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
         Ok(jobs.iter().map(GraphQLSnarkJob::from).collect())
     }
@@ -547,12 +875,58 @@ impl Query {
             .rpc_sender
             .oneshot_request(RpcRequest::SnarkPoolPendingJobsGet)
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
-        Ok(jobs
-            .into_iter()
+        jobs.into_iter()
             .map(GraphQLPendingSnarkWork::try_from)
-            .collect::<Result<Vec<_>, _>>()?)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Error::Conversion(e).into_field_error())
+    }
+
+    /// Latencies of the block, snark work and user command proof
+    /// verifications since the node started
+    ///
+    /// # Returns
+    /// Histograms of the time spent queued, verifying and in total, per kind
+    /// of verification
+    async fn snark_stats(context: &Context) -> juniper::FieldResult<GraphQLSnarkStats> {
+        let stats: RpcSnarkStatsGetResponse = context
+            .rpc_sender
+            .oneshot_request(RpcRequest::SnarkStatsGet)
+            .await
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
+
+        Ok(stats.into())
+    }
+
+    /// Compute the hash of a signed payment, delegation or zkApp command
+    /// without submitting it
+    ///
+    /// # Arguments
+    /// - `payment`: Payment details, as for `sendPayment`, including the nonce
+    /// - `delegation`: Delegation details, as for `sendDelegation`, including
+    ///   the nonce
+    /// - `zkapp`: zkApp command, as for `sendZkapp`
+    /// - `signature`: Signature of the payment or delegation
+    /// - `transaction_id`: Serialized command, the Base64 id of a signed
+    ///   command or zkApp command, or the Base58Check id of a signed command
+    ///   of the legacy (pre-Berkeley) format
+    ///
+    /// # Returns
+    /// Base58Check encoded transaction hash, the same as returned once the
+    /// transaction is submitted
+    async fn transaction_hash(
+        payment: Option<user_command::InputGraphQLPayment>,
+        delegation: Option<user_command::InputGraphQLDelegation>,
+        zkapp: Option<zkapp::SendZkappInput>,
+        signature: Option<user_command::UserCommandSignature>,
+        transaction_id: Option<String>,
+        _context: &Context,
+    ) -> juniper::FieldResult<String> {
+        let hash =
+            user_command::transaction_hash(payment, delegation, zkapp, signature, transaction_id)
+                .map_err(Error::into_field_error)?;
+        Ok(hash.to_string())
     }
 
     /// The chain-agnostic identifier of the network
@@ -565,6 +939,19 @@ impl Query {
         Ok(res)
     }
 
+    /// List of peers that the node first used to connect to the network
+    ///
+    /// # Returns
+    /// Multiaddrs of the configured initial peers
+    async fn initial_peers(context: &Context) -> juniper::FieldResult<Vec<String>> {
+        let status = context
+            .get_or_fetch_status()
+            .await
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
+
+        Ok(status.network_info.initial_peers)
+    }
+
     /// The version of the node (git commit hash)
     ///
     /// # Returns
@@ -574,6 +961,23 @@ impl Query {
         Ok(res)
     }
 
+    /// Optional subsystems the node was compiled with and runs
+    ///
+    /// # Returns
+    /// Compiled cargo features, and whether the node produces blocks, runs a
+    /// snarker or archives blocks
+    async fn enabled_features(
+        context: &Context,
+    ) -> juniper::FieldResult<constants::GraphQLEnabledFeatures> {
+        let features: RpcEnabledFeaturesGetResponse = context
+            .rpc_sender
+            .oneshot_request(RpcRequest::EnabledFeaturesGet)
+            .await
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
+
+        Ok(features.into())
+    }
+
     /// Get information about the current SNARK worker if configured
     ///
     /// # Returns
@@ -586,7 +990,7 @@ impl Query {
             .rpc_sender
             .oneshot_request(RpcRequest::SnarkerConfig)
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
         let Some(config) = config else {
             return Ok(None);
@@ -594,7 +998,9 @@ impl Query {
 
         let account = context
             .load_account(AccountId {
-                public_key: CompressedPubKey::try_from(&config.public_key)?,
+                public_key: CompressedPubKey::try_from(&config.public_key).map_err(|_| {
+                    Error::Conversion(ConversionError::InvalidBigInt).into_field_error()
+                })?,
                 token_id: TokenIdKeyHash::default().into(),
             })
             .await;
@@ -612,13 +1018,13 @@ async fn inject_tx<R>(
     context: &Context,
 ) -> juniper::FieldResult<R>
 where
-    R: TryFrom<MinaBaseUserCommandStableV2>,
+    R: TryFrom<MinaBaseUserCommandStableV2, Error = ConversionError>,
 {
     let res: RpcTransactionInjectResponse = context
         .rpc_sender
         .oneshot_request(RpcRequest::TransactionInject(vec![cmd]))
         .await
-        .ok_or(Error::StateMachineEmptyResponse)?;
+        .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
 
     match res {
         RpcTransactionInjectResponse::Success(res) => {
@@ -626,17 +1032,20 @@ where
                 Some(cmd) => cmd.into(),
                 _ => unreachable!(),
             };
-            cmd.try_into().map_err(|_| {
-                FieldError::new(
-                    "Failed to convert transaction to the required type".to_string(),
-                    graphql_value!(null),
-                )
-            })
+            cmd.try_into()
+                .map_err(|e| Error::Conversion(e).into_field_error())
         }
         RpcTransactionInjectResponse::Rejected(rejected) => {
             let error_list = rejected
                 .into_iter()
-                .map(|(_, err)| graphql_value!({ "message": err.to_string() }))
+                .map(|(_, err)| {
+                    let code = err.error_code();
+                    graphql_value!({
+                        "message": err.to_string(),
+                        "code": code.as_str(),
+                        "numericCode": i32::from(code.code())
+                    })
+                })
                 .collect::<Vec<_>>();
 
             Err(FieldError::new(
@@ -645,9 +1054,16 @@ where
             ))
         }
         RpcTransactionInjectResponse::Failure(failure) => {
+            let code = ErrorCode::InvalidTransaction;
             let error_list = failure
                 .into_iter()
-                .map(|err| graphql_value!({ "message": err.to_string() }))
+                .map(|err| {
+                    graphql_value!({
+                        "message": err.to_string(),
+                        "code": code.as_str(),
+                        "numericCode": i32::from(code.code())
+                    })
+                })
                 .collect::<Vec<_>>();
 
             Err(FieldError::new(
@@ -669,6 +1085,11 @@ pub struct Mutation;
 /// - `send_zkapp` - Submit a zkApp transaction to the network
 /// - `send_payment` - Send a payment transaction
 /// - `send_delegation` - Send a delegation transaction
+///
+/// ## Network
+/// - `refresh_peers` - Refetch the peer list from the configured url
+/// - `set_gossip_paused` - Pause or resume the gossip of blocks, transactions
+///   or snarks
 #[juniper::graphql_object(context = Context)]
 impl Mutation {
     /// Submit a zkApp transaction to the network
@@ -682,7 +1103,9 @@ impl Mutation {
         input: zkapp::SendZkappInput,
         context: &Context,
     ) -> juniper::FieldResult<zkapp::GraphQLSendZkappResponse> {
-        inject_tx(input.try_into()?, context).await
+        let command = MinaBaseUserCommandStableV2::try_from(input)
+            .map_err(|e| Error::Conversion(e).into_field_error())?;
+        inject_tx(command, context).await
     }
 
     /// Send a payment transaction
@@ -699,26 +1122,18 @@ impl Mutation {
         context: &Context,
     ) -> juniper::FieldResult<user_command::GraphQLSendPaymentResponse> {
         // Grab the sender's account to get the infered nonce
-        let token_id = TokenIdKeyHash::default();
         let public_key = AccountPublicKey::from_str(&input.from)
-            .map_err(|e| Error::Conversion(ConversionError::Base58Check(e)))?;
+            .map_err(|e| Error::Conversion(e.into()).into_field_error())?;
 
-        let accounts: Vec<Account> = context
-            .rpc_sender
-            .oneshot_request(RpcRequest::LedgerAccountsGet(
-                AccountQuery::PubKeyWithTokenId(public_key, token_id),
-            ))
+        let sender = default_token_account(public_key, context)
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
-
-        let infered_nonce = accounts
-            .first()
-            .ok_or(Error::StateMachineEmptyResponse)?
-            .nonce;
+            .map_err(Error::into_field_error)?
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
+        let infered_nonce = sender.nonce;
 
         let command = input
             .create_user_command(infered_nonce, signature)
-            .map_err(Error::Conversion)?;
+            .map_err(|e| Error::Conversion(e).into_field_error())?;
 
         inject_tx(command, context).await
     }
@@ -736,35 +1151,93 @@ impl Mutation {
         signature: user_command::UserCommandSignature,
         context: &Context,
     ) -> juniper::FieldResult<user_command::GraphQLSendDelegationResponse> {
-        // Payment commands are always for the default (MINA) token
-        let token_id = TokenIdKeyHash::default();
-        let public_key = AccountPublicKey::from_str(&input.from)?;
+        let public_key = AccountPublicKey::from_str(&input.from)
+            .map_err(|e| Error::Conversion(e.into()).into_field_error())?;
 
         // Grab the sender's account to get the infered nonce
-        let accounts: Vec<Account> = context
-            .rpc_sender
-            .oneshot_request(RpcRequest::LedgerAccountsGet(
-                AccountQuery::PubKeyWithTokenId(public_key, token_id),
-            ))
+        let sender = default_token_account(public_key, context)
             .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
-
-        let infered_nonce = accounts
-            .first()
-            .ok_or(Error::StateMachineEmptyResponse)?
-            .nonce;
-        let command = input.create_user_command(infered_nonce, signature)?;
+            .map_err(Error::into_field_error)?
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
+        let infered_nonce = sender.nonce;
+        let command = input
+            .create_user_command(infered_nonce, signature)
+            .map_err(|e| Error::Conversion(e).into_field_error())?;
 
         inject_tx(command, context).await
     }
+
+    /// Refetch the peer list from the url the node was started with
+    ///
+    /// # Returns
+    /// Peers added to and removed from the list since it was last fetched
+    async fn refresh_peers(
+        context: &Context,
+    ) -> juniper::FieldResult<constants::GraphQLPeerListRefresh> {
+        let res: RpcPeerListRefreshResponse = context
+            .rpc_sender
+            .oneshot_request(RpcRequest::PeerListRefresh)
+            .await
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
+        let diff = res.map_err(|e| Error::Custom(e).into_field_error())?;
+        Ok((&diff).into())
+    }
+
+    /// Pause or resume taking part in the gossip of the given kind
+    ///
+    /// # Arguments
+    /// - `kind`: Kind of gossiped items
+    /// - `paused`: Whether to pause or resume the gossip
+    ///
+    /// # Returns
+    /// Gossip kinds that are paused after the change
+    async fn set_gossip_paused(
+        kind: constants::GraphQLGossipKind,
+        paused: bool,
+        context: &Context,
+    ) -> juniper::FieldResult<Vec<constants::GraphQLGossipKind>> {
+        let res: RpcGossipPauseResponse = context
+            .rpc_sender
+            .oneshot_request(RpcRequest::GossipPause {
+                kind: kind.into(),
+                paused,
+            })
+            .await
+            .ok_or_else(|| Error::StateMachineEmptyResponse.into_field_error())?;
+        let paused = res.map_err(|e| Error::Custom(e).into_field_error())?;
+        Ok(paused.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Account of the default (MINA) token, which payments and delegations are
+/// always for, in the best tip ledger.
+async fn default_token_account(
+    public_key: AccountPublicKey,
+    context: &Context,
+) -> Result<Option<Account>, Error> {
+    let accounts: Vec<Account> = context
+        .rpc_sender
+        .oneshot_request(RpcRequest::LedgerAccountsGet(
+            AccountQuery::PubKeyWithTokenId(public_key, TokenIdKeyHash::default()),
+        ))
+        .await
+        .ok_or(Error::StateMachineEmptyResponse)?;
+    Ok(accounts.into_iter().next())
+}
+
+pub type Schema = RootNode<'static, Query, Mutation, EmptySubscription<Context>>;
+
+/// Schema served at `/graphql`, also used to run queries without the HTTP
+/// server.
+pub fn schema() -> Schema {
+    RootNode::new(Query, Mutation, EmptySubscription::<Context>::new())
 }
 
 pub fn routes(
     rpc_sernder: RpcSender,
 ) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
     let state = warp::any().map(move || Context::new(rpc_sernder.clone()));
-    let schema = RootNode::new(Query, Mutation, EmptySubscription::<Context>::new());
-    let graphql_filter = juniper_warp::make_graphql_filter(schema, state.boxed());
+    let graphql_filter = juniper_warp::make_graphql_filter(schema(), state.boxed());
     let graphiql_filter = juniper_warp::graphiql_filter("/graphql", None);
     let playground_filter = juniper_warp::playground_filter("/graphql", None);
 
@@ -817,3 +1290,20 @@ where
         ids,
     })
 }
+
+fn get_block_query(
+    height: Option<i32>,
+    state_hash: Option<String>,
+) -> Result<GetBlockQuery, Error> {
+    match (height, state_hash) {
+        (Some(height), None) => Ok(GetBlockQuery::Height(height.try_into().unwrap_or(u32::MAX))),
+        (None, Some(state_hash)) => Ok(GetBlockQuery::Hash(
+            state_hash
+                .parse::<StateHash>()
+                .map_err(ConversionError::from)?,
+        )),
+        _ => Err(Error::Custom(
+            "Must provide exactly one of state hash, height".to_owned(),
+        )),
+    }
+}
@@ -4,7 +4,10 @@ use mina_p2p_messages::v2::{
     MinaBaseFeeExcessStableV1, MinaStateBlockchainStateValueStableV2SignedAmount,
     TransactionSnarkScanStateTransactionWithWitnessStableV2, TransactionSnarkStableV2,
 };
-use node::snark_pool::JobState;
+use node::{
+    snark::{SnarkLatencyHistogram, SnarkStats, SnarkVerifyLatencyStats, SNARK_LATENCY_BUCKETS_MS},
+    snark_pool::JobState,
+};
 
 use super::{account::GraphQLAccount, Context, ConversionError, GraphQLPublicKey};
 
@@ -172,3 +175,87 @@ impl GraphQLSnarkWorker {
         self.account.clone()
     }
 }
+
+#[derive(GraphQLObject, Debug)]
+#[graphql(description = "Latencies of the proof verifications since the node started")]
+pub struct GraphQLSnarkStats {
+    pub block_verify: GraphQLSnarkVerifyLatency,
+    pub work_verify: GraphQLSnarkVerifyLatency,
+    pub user_command_verify: GraphQLSnarkVerifyLatency,
+}
+
+#[derive(GraphQLObject, Debug)]
+pub struct GraphQLSnarkVerifyLatency {
+    pub success: String,
+    /// Verifications which failed, including the ones which timed out
+    pub error: String,
+    /// Time from being queued to being sent to the verifier
+    pub queue_wait: GraphQLLatencyHistogram,
+    /// Time from being sent to the verifier to finishing
+    pub verify: GraphQLLatencyHistogram,
+    /// Time from being queued to finishing
+    pub total: GraphQLLatencyHistogram,
+    /// Mean number of proofs verified together per verification
+    pub mean_batch_size: String,
+    /// Max number of proofs verified together
+    pub max_batch_size: String,
+}
+
+#[derive(GraphQLObject, Debug)]
+pub struct GraphQLLatencyHistogram {
+    pub count: String,
+    pub mean_ms: String,
+    pub max_ms: String,
+    pub buckets: Vec<GraphQLLatencyBucket>,
+}
+
+#[derive(GraphQLObject, Debug)]
+pub struct GraphQLLatencyBucket {
+    /// Upper bound of the bucket in milliseconds, missing for the last
+    /// bucket
+    pub le_ms: Option<i32>,
+    pub count: String,
+}
+
+impl From<SnarkStats> for GraphQLSnarkStats {
+    fn from(stats: SnarkStats) -> Self {
+        Self {
+            block_verify: (&stats.block_verify).into(),
+            work_verify: (&stats.work_verify).into(),
+            user_command_verify: (&stats.user_command_verify).into(),
+        }
+    }
+}
+
+impl From<&SnarkVerifyLatencyStats> for GraphQLSnarkVerifyLatency {
+    fn from(stats: &SnarkVerifyLatencyStats) -> Self {
+        Self {
+            success: stats.success.to_string(),
+            error: stats.error.to_string(),
+            queue_wait: (&stats.queue_wait).into(),
+            verify: (&stats.verify).into(),
+            total: (&stats.total).into(),
+            mean_batch_size: stats.mean_batch_size().to_string(),
+            max_batch_size: stats.batch_size.max.to_string(),
+        }
+    }
+}
+
+impl From<&SnarkLatencyHistogram> for GraphQLLatencyHistogram {
+    fn from(histogram: &SnarkLatencyHistogram) -> Self {
+        let bounds = SNARK_LATENCY_BUCKETS_MS.iter().map(|bound| Some(*bound));
+        Self {
+            count: histogram.count.to_string(),
+            mean_ms: histogram.mean_ms().to_string(),
+            max_ms: histogram.max_ms.to_string(),
+            buckets: bounds
+                .chain([None])
+                .zip(histogram.buckets)
+                .map(|(bound, count)| GraphQLLatencyBucket {
+                    le_ms: bound.map(|bound| bound.try_into().unwrap_or(i32::MAX)),
+                    count: count.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -1,5 +1,8 @@
-use juniper::GraphQLEnum;
-use node::rpc::TransactionStatus;
+use juniper::{GraphQLEnum, GraphQLObject};
+use mina_p2p_messages::v2::MinaBaseUserCommandStableV2;
+use node::rpc::{RpcLocallyGeneratedCommand, TransactionStatus};
+
+use super::{block::GraphQLUserCommands, zkapp::GraphQLZkapp, ConversionError};
 
 #[derive(Clone, Copy, Debug, GraphQLEnum)]
 #[allow(non_camel_case_types)]
@@ -18,3 +21,34 @@ impl From<TransactionStatus> for GraphQLTransactionStatus {
         }
     }
 }
+
+#[derive(GraphQLObject, Debug)]
+pub struct GraphQLLocallyGeneratedCommand {
+    pub hash: String,
+    /// Time the command was added to the pool, in milliseconds since the
+    /// Unix epoch
+    pub added_at: String,
+    /// Whether the command was included in the best chain
+    pub committed: bool,
+    pub user_command: Option<GraphQLUserCommands>,
+    pub zkapp_command: Option<GraphQLZkapp>,
+}
+
+impl TryFrom<RpcLocallyGeneratedCommand> for GraphQLLocallyGeneratedCommand {
+    type Error = ConversionError;
+
+    fn try_from(value: RpcLocallyGeneratedCommand) -> Result<Self, Self::Error> {
+        let (user_command, zkapp_command) = match value.command {
+            MinaBaseUserCommandStableV2::SignedCommand(cmd) => (Some(cmd.try_into()?), None),
+            MinaBaseUserCommandStableV2::ZkappCommand(cmd) => (None, Some(cmd.try_into()?)),
+        };
+
+        Ok(Self {
+            hash: value.hash.to_string(),
+            added_at: (u64::from(value.added_at) / 1_000_000).to_string(),
+            committed: value.committed,
+            user_command,
+            zkapp_command,
+        })
+    }
+}
@@ -13,7 +13,7 @@ use mina_signer::CompressedPubKey;
 use node::account::AccountPublicKey;
 use o1_utils::field_helpers::FieldHelpers;
 
-use super::zkapp::GraphQLFailureReason;
+use super::zkapp::{GraphQLFailureReason, SendZkappInput};
 
 #[derive(GraphQLInputObject, Debug)]
 pub struct InputGraphQLPayment {
@@ -212,32 +212,91 @@ impl TryFrom<v2::MinaBaseUserCommandStableV2> for GraphQLSendDelegationResponse
     }
 }
 
+/// Hash of the command given by exactly one of the inputs. Payments and
+/// delegations are built with the nonce of their input, which takes
+/// precedence over the infered one, so the sender's account isn't read.
+pub fn transaction_hash(
+    payment: Option<InputGraphQLPayment>,
+    delegation: Option<InputGraphQLDelegation>,
+    zkapp: Option<SendZkappInput>,
+    signature: Option<UserCommandSignature>,
+    transaction_id: Option<String>,
+) -> Result<v2::TransactionHash, super::Error> {
+    let missing_signature = || super::ConversionError::MissingField("signature".to_string());
+    let command = match (payment, delegation, zkapp, transaction_id) {
+        (Some(payment), None, None, None) => payment.build_user_command(
+            input_nonce(payment.nonce.as_deref())?,
+            signature.ok_or_else(missing_signature)?,
+        )?,
+        (None, Some(delegation), None, None) => delegation.build_user_command(
+            input_nonce(delegation.nonce.as_deref())?,
+            signature.ok_or_else(missing_signature)?,
+        )?,
+        (None, None, Some(zkapp), None) => zkapp.try_into()?,
+        (None, None, None, Some(transaction_id)) => {
+            return Ok(v2::TransactionHash::from_transaction_id(&transaction_id)
+                .map_err(super::ConversionError::Io)?);
+        }
+        _ => {
+            return Err(super::Error::Custom(
+                "exactly one of `payment`, `delegation`, `zkapp` or `transactionId` must be \
+                 provided"
+                    .to_string(),
+            ))
+        }
+    };
+    Ok(command.hash().map_err(super::ConversionError::Io)?)
+}
+
+/// Parses the nonce given in the input of a command, which is required to
+/// build the command without looking up the sender's account.
+pub fn input_nonce(nonce: Option<&str>) -> Result<Nonce, super::ConversionError> {
+    let nonce = nonce.ok_or_else(|| super::ConversionError::MissingField("nonce".to_string()))?;
+    Ok(Nonce::from_u32(
+        nonce
+            .parse::<u32>()
+            .map_err(|_| super::ConversionError::InvalidBigInt)?,
+    ))
+}
+
+/// Nonce of a new command: the one given in the input, which can't be
+/// greater than the one following `infered_nonce`, or that one.
+fn command_nonce(
+    nonce: Option<&str>,
+    infered_nonce: Nonce,
+) -> Result<Nonce, super::ConversionError> {
+    let infered_nonce = infered_nonce.incr();
+    let Some(nonce) = nonce else {
+        return Ok(infered_nonce);
+    };
+
+    let input_nonce = input_nonce(Some(nonce))?;
+    if input_nonce.is_zero() || input_nonce > infered_nonce {
+        Err(super::ConversionError::Custom(
+            "Provided nonce is zero or greater than infered nonce".to_string(),
+        ))
+    } else {
+        Ok(input_nonce)
+    }
+}
+
 impl InputGraphQLPayment {
     pub fn create_user_command(
         &self,
         infered_nonce: Nonce,
         signature: UserCommandSignature,
     ) -> Result<v2::MinaBaseUserCommandStableV2, super::ConversionError> {
-        let infered_nonce = infered_nonce.incr();
-
-        let nonce = if let Some(nonce) = &self.nonce {
-            let input_nonce = Nonce::from_u32(
-                nonce
-                    .parse::<u32>()
-                    .map_err(|_| super::ConversionError::InvalidBigInt)?,
-            );
-
-            if input_nonce.is_zero() || input_nonce > infered_nonce {
-                return Err(super::ConversionError::Custom(
-                    "Provided nonce is zero or greater than infered nonce".to_string(),
-                ));
-            } else {
-                input_nonce
-            }
-        } else {
-            infered_nonce
-        };
+        let nonce = command_nonce(self.nonce.as_deref(), infered_nonce)?;
+        self.build_user_command(nonce, signature)
+    }
 
+    /// Builds the command with `nonce`, without checking it against the
+    /// sender's account.
+    pub fn build_user_command(
+        &self,
+        nonce: Nonce,
+        signature: UserCommandSignature,
+    ) -> Result<v2::MinaBaseUserCommandStableV2, super::ConversionError> {
         let valid_until = if let Some(valid_until) = &self.valid_until {
             Some(Slot::from_u32(
                 valid_until
@@ -297,26 +356,17 @@ impl InputGraphQLDelegation {
         infered_nonce: Nonce,
         signature: UserCommandSignature,
     ) -> Result<v2::MinaBaseUserCommandStableV2, super::ConversionError> {
-        let infered_nonce = infered_nonce.incr();
-
-        let nonce = if let Some(nonce) = &self.nonce {
-            let input_nonce = Nonce::from_u32(
-                nonce
-                    .parse::<u32>()
-                    .map_err(|_| super::ConversionError::InvalidBigInt)?,
-            );
-
-            if input_nonce.is_zero() || input_nonce > infered_nonce {
-                return Err(super::ConversionError::Custom(
-                    "Provided nonce is zero or greater than infered nonce".to_string(),
-                ));
-            } else {
-                input_nonce
-            }
-        } else {
-            infered_nonce
-        };
+        let nonce = command_nonce(self.nonce.as_deref(), infered_nonce)?;
+        self.build_user_command(nonce, signature)
+    }
 
+    /// Builds the command with `nonce`, without checking it against the
+    /// sender's account.
+    pub fn build_user_command(
+        &self,
+        nonce: Nonce,
+        signature: UserCommandSignature,
+    ) -> Result<v2::MinaBaseUserCommandStableV2, super::ConversionError> {
         let valid_until = if let Some(valid_until) = &self.valid_until {
             Some(Slot::from_u32(
                 valid_until
@@ -366,3 +416,51 @@ impl InputGraphQLDelegation {
         Ok(v2::MinaBaseUserCommandStableV2::SignedCommand(sc.into()))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn payment(nonce: Option<&str>) -> InputGraphQLPayment {
+        let public_key = "B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV";
+        InputGraphQLPayment {
+            from: public_key.to_owned(),
+            to: public_key.to_owned(),
+            amount: "1000000000".to_owned(),
+            valid_until: None,
+            fee: "10000000".to_owned(),
+            memo: Some("hash me".to_owned()),
+            nonce: nonce.map(ToOwned::to_owned),
+        }
+    }
+
+    fn signature() -> Option<UserCommandSignature> {
+        Some(UserCommandSignature {
+            field: Some("1".to_owned()),
+            scalar: Some("1".to_owned()),
+            raw_signature: None,
+        })
+    }
+
+    #[test]
+    fn test_transaction_hash() {
+        // A first command, with nonce 0, is hashed as well.
+        let hash =
+            transaction_hash(Some(payment(Some("0"))), None, None, signature(), None).unwrap();
+        let command = payment(None)
+            .build_user_command(Nonce::zero(), signature().unwrap())
+            .unwrap();
+        let v2::MinaBaseUserCommandStableV2::SignedCommand(command) = command else {
+            unreachable!()
+        };
+        let transaction_id = command.to_base64().unwrap();
+        let hash_of_id = transaction_hash(None, None, None, None, Some(transaction_id)).unwrap();
+        assert_eq!(hash, hash_of_id);
+
+        let missing_nonce = transaction_hash(Some(payment(None)), None, None, signature(), None);
+        assert!(missing_nonce.is_err());
+        let missing_signature = transaction_hash(Some(payment(Some("0"))), None, None, None, None);
+        assert!(missing_signature.is_err());
+        assert!(transaction_hash(None, None, None, None, None).is_err());
+    }
+}
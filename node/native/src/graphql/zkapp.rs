@@ -148,6 +148,7 @@ impl TryFrom<MinaBaseUserCommandStableV2> for GraphQLSendZkappResponse {
 impl TryFrom<InputGraphQLZkappCommand> for MinaBaseUserCommandStableV2 {
     type Error = ConversionError;
     fn try_from(value: InputGraphQLZkappCommand) -> Result<Self, Self::Error> {
+        check_authorizations(&value.account_updates)?;
         Ok(MinaBaseUserCommandStableV2::ZkappCommand(
             MinaBaseZkappCommandTStableV1WireStableV1 {
                 fee_payer: value.fee_payer.try_into()?,
@@ -270,6 +271,76 @@ pub struct InputGraphQLAccountUpdate {
     pub authorization: InputGraphQLAuthorization,
 }
 
+/// Authorization given to an account update, or the one its
+/// `authorizationKind` says it has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorizationShape {
+    NoneGiven,
+    Signature,
+    Proof,
+    SignatureAndProof,
+}
+
+impl AuthorizationShape {
+    fn new(is_signed: bool, is_proved: bool) -> Self {
+        match (is_signed, is_proved) {
+            (false, false) => Self::NoneGiven,
+            (true, false) => Self::Signature,
+            (false, true) => Self::Proof,
+            (true, true) => Self::SignatureAndProof,
+        }
+    }
+}
+
+impl std::fmt::Display for AuthorizationShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::NoneGiven => "none given",
+            Self::Signature => "signature",
+            Self::Proof => "proof",
+            Self::SignatureAndProof => "both signature and proof",
+        })
+    }
+}
+
+/// Account update, at `index` in the list of account updates, whose
+/// authorization doesn't match its `authorizationKind`.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "account update {index}: authorization kind is {kind}, but authorization is {authorization}"
+)]
+pub struct AuthorizationMismatch {
+    pub index: usize,
+    pub kind: AuthorizationShape,
+    pub authorization: AuthorizationShape,
+}
+
+/// Checks that each account update has either a signature or a proof, as
+/// its `authorizationKind` says, before the command is decoded, so that
+/// the update at fault can be reported.
+pub fn check_authorizations(
+    updates: &[InputGraphQLAccountUpdate],
+) -> Result<(), AuthorizationMismatch> {
+    for (index, update) in updates.iter().enumerate() {
+        let InputGraphQLAuthorizationKind {
+            is_signed,
+            is_proved,
+            ..
+        } = update.body.authorization_kind;
+        let kind = AuthorizationShape::new(is_signed, is_proved);
+        let InputGraphQLAuthorization { proof, signature } = &update.authorization;
+        let authorization = AuthorizationShape::new(signature.is_some(), proof.is_some());
+        if kind != authorization || kind == AuthorizationShape::SignatureAndProof {
+            return Err(AuthorizationMismatch {
+                index,
+                kind,
+                authorization,
+            });
+        }
+    }
+    Ok(())
+}
+
 #[derive(GraphQLObject, Debug)]
 pub struct GraphQLAuthorization {
     pub proof: Option<String>,
@@ -1597,6 +1668,65 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_authorization_mismatch() {
+        let mut input = create_input_graphql_zkapp();
+        assert!(check_authorizations(&input.zkapp_command.account_updates).is_ok());
+
+        input.zkapp_command.account_updates[1].authorization.proof = Some(String::new());
+        let err = MinaBaseUserCommandStableV2::try_from(input.zkapp_command).unwrap_err();
+        let mismatch = match err {
+            ConversionError::AuthorizationMismatch(mismatch) => mismatch,
+            err => panic!("unexpected error: {err}"),
+        };
+        assert_eq!(mismatch.index, 1);
+        assert_eq!(mismatch.kind, AuthorizationShape::Signature);
+        assert_eq!(
+            mismatch.authorization,
+            AuthorizationShape::SignatureAndProof
+        );
+    }
+
+    /// Decodes a zkApp command as serialized by o1js, the way the GraphQL
+    /// server decodes the input of `sendZkapp`.
+    fn input_from_o1js_json(json: &str) -> InputGraphQLZkapp {
+        let value: juniper::InputValue = serde_json::from_str(json).unwrap();
+        juniper::FromInputValue::from_input_value(&value).unwrap()
+    }
+
+    fn zkapp_command_json(input: InputGraphQLZkapp) -> String {
+        let MinaBaseUserCommandStableV2::ZkappCommand(zkapp_cmd) =
+            input.zkapp_command.try_into().unwrap()
+        else {
+            unreachable!()
+        };
+        serde_json::to_string_pretty(&zkapp_cmd).unwrap()
+    }
+
+    /// The fixtures are serialized by o1js, see
+    /// `tests/files/zkapps/o1js/generate.mjs`.
+    #[test]
+    fn test_o1js_fixtures() {
+        let bytes = include_bytes!("../../../../tests/files/zkapps/valid_zkapp.bin");
+        let zkapp =
+            MinaBaseZkappCommandTStableV1WireStableV1::binprot_read(&mut bytes.as_slice()).unwrap();
+        let input = input_from_o1js_json(include_str!(
+            "../../../../tests/files/zkapps/o1js/valid_zkapp.json"
+        ));
+        assert_eq!(
+            zkapp_command_json(input),
+            serde_json::to_string_pretty(&zkapp).unwrap()
+        );
+
+        let input = input_from_o1js_json(include_str!(
+            "../../../../tests/files/zkapps/o1js/none_given.json"
+        ));
+        assert_eq!(
+            zkapp_command_json(input),
+            zkapp_command_json(create_input_graphql_zkapp_bug_1464())
+        );
+    }
+
     #[test]
     fn test_authorization_kind() {
         let kind = InputGraphQLAuthorizationKind {
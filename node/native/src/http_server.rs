@@ -1,7 +1,18 @@
-use std::{convert::Infallible, mem::size_of, str::FromStr};
+use std::{
+    convert::Infallible,
+    mem::size_of,
+    net::SocketAddr,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
-use mina_p2p_messages::binprot::BinProtWrite;
+use mina_p2p_messages::{
+    binprot::{BinProtRead, BinProtWrite},
+    v2,
+};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use subtle::ConstantTimeEq;
 use warp::{
     http::HeaderValue,
     hyper::{header::CONTENT_TYPE, Response, StatusCode},
@@ -9,7 +20,19 @@ use warp::{
     Filter, Rejection, Reply,
 };
 
-use node::{core::snark::SnarkJobId, rpc::*};
+use node::{
+    core::{
+        error_code::ErrorCodeBody,
+        snark::{Snark, SnarkJobId},
+        ErrorCode,
+    },
+    p2p::{gossip::P2pGossipKind, identity::SecretKey as P2pSecretKey},
+    rpc::*,
+    snark::verify_dry_run::SnarkVerifyDryRunInput,
+    transition_frontier::sync::ledger::{
+        LedgerSnapshot, LedgerSnapshotDigest, LedgerSnapshotQuery, LEDGER_SNAPSHOT_SIGNATURE_HEADER,
+    },
+};
 
 use mina_node_common::rpc::{
     RpcActionStatsGetResponse, RpcSender, RpcSnarkPoolGetResponse, RpcSnarkerJobCommitResponse,
@@ -25,11 +48,42 @@ macro_rules! compose_route {
     );
 }
 
-pub async fn run(port: u16, rpc_sender: RpcSender) {
+/// Work submissions let through per second, over all snarkers. Each one
+/// takes a verifier thread for a while.
+const SNARKER_WORK_SUBMIT_RATE: u32 = 10;
+
+#[derive(Debug, Clone, Default)]
+pub struct HttpServerOptions {
+    /// Token which snarkers must send as `Authorization: Bearer <token>` to
+    /// submit work to `/snarker/work`. Without it, only snarkers on this
+    /// host can submit work.
+    pub snarker_work_token: Option<String>,
+    /// Token which nodes must send as `Authorization: Bearer <token>` to
+    /// download the ledger snapshots under `/bootstrap`. Without it, only
+    /// nodes on this host can bootstrap from this one.
+    pub bootstrap_token: Option<String>,
+    /// Key with which ledger snapshots are signed, the node's p2p key.
+    /// Without it, snapshots aren't served.
+    pub snapshot_secret_key: Option<P2pSecretKey>,
+}
+
+pub async fn run(port: u16, rpc_sender: RpcSender, options: HttpServerOptions) {
     let build_env_get = warp::path!("build_env")
         .and(warp::get())
         .then(move || async { with_json_reply(&node::BuildEnv::get(), StatusCode::OK) });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    let features_get = warp::path!("features").and(warp::get()).then(move || {
+        let rpc_sender_clone = rpc_sender_clone.clone();
+        async move {
+            let result: Option<RpcEnabledFeaturesGetResponse> = rpc_sender_clone
+                .oneshot_request(RpcRequest::EnabledFeaturesGet)
+                .await;
+
+            with_json_reply(&result, StatusCode::OK)
+        }
+    });
+
     #[cfg(feature = "p2p-webrtc")]
     let signaling = {
         use node::p2p::{
@@ -144,7 +198,7 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
     async fn state_recover(reject: warp::Rejection) -> Result<impl warp::Reply, warp::Rejection> {
         if let Some(StateGetRejection(error)) = reject.find() {
             Ok(warp::reply::with_status(
-                warp::reply::json(error),
+                warp::reply::json(&ErrorCodeBody::new(error)),
                 StatusCode::BAD_REQUEST,
             ))
         } else {
@@ -270,6 +324,21 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
                 }
             });
 
+        let rpc_sender_clone = rpc_sender.clone();
+        let sync_history = warp::path!("stats" / "sync" / "history")
+            .and(warp::get())
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: RpcSyncHistoryGetResponse = rpc_sender_clone
+                        .oneshot_request(RpcRequest::SyncHistoryGet)
+                        .await
+                        .flatten();
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
         let rpc_sender_clone = rpc_sender.clone();
         let block_producer_stats = warp::path!("stats" / "block_producer")
             .and(warp::get())
@@ -285,7 +354,72 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
                 }
             });
 
-        action_stats.or(sync_stats).or(block_producer_stats)
+        let rpc_sender_clone = rpc_sender.clone();
+        let snark_stats = warp::path!("stats" / "snark")
+            .and(warp::get())
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: Option<RpcSnarkStatsGetResponse> = rpc_sender_clone
+                        .oneshot_request(RpcRequest::SnarkStatsGet)
+                        .await;
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
+        let rpc_sender_clone = rpc_sender.clone();
+        let snark_history = warp::path!("stats" / "snark" / "history")
+            .and(warp::get())
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: Option<RpcSnarkVerifyHistoryGetResponse> = rpc_sender_clone
+                        .oneshot_request(RpcRequest::SnarkVerifyHistoryGet)
+                        .await;
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
+        let rpc_sender_clone = rpc_sender.clone();
+        let peer_contribution_stats = warp::path!("stats" / "peer_contributions")
+            .and(warp::get())
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: RpcPeerContributionStatsGetResponse = rpc_sender_clone
+                        .oneshot_request(RpcRequest::PeerContributionStatsGet)
+                        .await
+                        .flatten();
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
+        let rpc_sender_clone = rpc_sender.clone();
+        let archive_upload_stats = warp::path!("stats" / "archive_upload")
+            .and(warp::get())
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: RpcArchiveUploadStatsGetResponse = rpc_sender_clone
+                        .oneshot_request(RpcRequest::ArchiveUploadStatsGet)
+                        .await
+                        .flatten();
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
+        action_stats
+            .or(sync_stats)
+            .or(sync_history)
+            .or(block_producer_stats)
+            .or(snark_stats)
+            .or(snark_history)
+            .or(peer_contribution_stats)
+            .or(archive_upload_stats)
     };
 
     let rpc_sender_clone = rpc_sender.clone();
@@ -334,6 +468,39 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
             }
         });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    let pending_coinbase_get = warp::path!("pending-coinbase" / ..)
+        .and(warp::get())
+        .and(
+            warp::path::param::<String>()
+                .map(Some)
+                .or_else(|_| async { Ok::<(Option<String>,), std::convert::Infallible>((None,)) }),
+        )
+        .and(warp::path::end())
+        .then(move |block_hash: Option<String>| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            let block_hash = block_hash.map(|hash| hash.parse()).transpose();
+            async move {
+                let Ok(block_hash) = block_hash else {
+                    return with_json_reply(
+                        &"invalid arg! Expected block hash",
+                        StatusCode::BAD_REQUEST,
+                    );
+                };
+                let res: Option<RpcPendingCoinbaseGetResponse> = rpc_sender_clone
+                    .oneshot_request(RpcRequest::PendingCoinbaseGet(block_hash))
+                    .await;
+                match res {
+                    None => with_json_reply(
+                        &"response channel dropped",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                    Some(None) => with_json_reply(&"block not found", StatusCode::NOT_FOUND),
+                    Some(Some(data)) => with_json_reply(&data, StatusCode::OK),
+                }
+            }
+        });
+
     let rpc_sender_clone = rpc_sender.clone();
     let snark_pool_jobs_get = warp::path!("snark-pool" / "jobs")
         .and(warp::get())
@@ -449,6 +616,35 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
         )
     };
 
+    // Work of snarkers which don't run this node, e.g. a worker fleet
+    // submitting to several nodes. The work is verified before being
+    // added to the pool.
+    let rpc_sender_clone = rpc_sender.clone();
+    let snarker_work_submit = warp::path!("snarker" / "work")
+        .and(warp::post())
+        .and(local_or_token(options.snarker_work_token.map(Arc::from)))
+        .and(rate_limited(SNARKER_WORK_SUBMIT_RATE))
+        .and(warp::filters::body::json())
+        .then(move |snark: Snark| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::SnarkerWorkSubmit { snark })
+                    .await
+                    .map_or_else(dropped_channel_response, |reply| {
+                        let status = match &reply {
+                            RpcSnarkerWorkSubmitResponse::Accepted => StatusCode::ACCEPTED,
+                            RpcSnarkerWorkSubmitResponse::JobNotFound => StatusCode::NOT_FOUND,
+                            RpcSnarkerWorkSubmitResponse::NotCheaper { .. } => StatusCode::CONFLICT,
+                            RpcSnarkerWorkSubmitResponse::Invalid { .. } => {
+                                StatusCode::UNPROCESSABLE_ENTITY
+                            }
+                        };
+                        with_json_reply(&reply, status)
+                    })
+            }
+        });
+
     let rpc_sender_clone = rpc_sender.clone();
     let snark_workers = warp::path!("snarker" / "workers")
         .and(warp::get())
@@ -533,10 +729,9 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
                     .payment(body)
                     .await
                 {
-                    Err(err) => with_status(
-                        warp::reply::json(&serde_json::json!({"error": err})),
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                    ),
+                    Err(err) => {
+                        with_json_reply(&ErrorCode::InvalidInput.body(err), StatusCode::BAD_REQUEST)
+                    }
                     Ok(res) => res.map_or_else(
                         dropped_channel_response,
                         |reply: node::rpc::RpcTransactionInjectResponse| {
@@ -547,6 +742,176 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
             }
         });
 
+    let block_inject = block_inject(rpc_sender.clone());
+
+    // Refetch the peer list from the url the node was started with.
+    let rpc_sender_clone = rpc_sender.clone();
+    let peers_refresh = warp::path!("admin" / "refresh-peers")
+        .and(warp::post())
+        .and(local_only())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let res: Option<RpcPeerListRefreshResponse> = rpc_sender_clone
+                    .oneshot_request(RpcRequest::PeerListRefresh)
+                    .await;
+                match res {
+                    None => with_json_reply(
+                        &"response channel dropped",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                    Some(Ok(diff)) => with_json_reply(&diff, StatusCode::OK),
+                    Some(Err(err)) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                }
+            }
+        });
+
+    // Pause or resume taking part in the gossip of blocks, transactions or snarks.
+    let rpc_sender_clone = rpc_sender.clone();
+    let gossip_pause = warp::path!("admin" / "gossip" / P2pGossipKind / String)
+        .and(warp::post())
+        .and(local_only())
+        .then(move |kind: P2pGossipKind, action: String| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let paused = match action.as_str() {
+                    "pause" => true,
+                    "resume" => false,
+                    _ => {
+                        return with_json_reply(
+                            &"expected `pause` or `resume`",
+                            StatusCode::NOT_FOUND,
+                        )
+                    }
+                };
+                let res: Option<RpcGossipPauseResponse> = rpc_sender_clone
+                    .oneshot_request(RpcRequest::GossipPause { kind, paused })
+                    .await;
+                match res {
+                    None => with_json_reply(
+                        &"response channel dropped",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                    Some(Ok(paused)) => with_json_reply(&paused, StatusCode::OK),
+                    Some(Err(err)) => with_json_reply(&err, StatusCode::SERVICE_UNAVAILABLE),
+                }
+            }
+        });
+
+    // Verify a block, snark works or a user command without acting on the
+    // result, reporting each check performed.
+    let rpc_sender_clone = rpc_sender.clone();
+    let snark_verify_dry_run = warp::path!("snark" / "verify" / "dry-run")
+        .and(warp::post())
+        .and(local_only())
+        .and(warp::filters::body::json())
+        .then(move |input: SnarkVerifyDryRunInput| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::SnarkVerifyDryRun(input))
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |report: RpcSnarkVerifyDryRunResponse| {
+                            with_json_reply(&report, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let snark_verifier_digests = warp::path!("snark" / "verifier" / "digests")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::SnarkVerifierDigestsGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |digests: RpcSnarkVerifierDigestsGetResponse| {
+                            with_json_reply(&digests, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let consensus_fork_decisions = warp::path!("consensus" / "fork-decisions")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::ConsensusForkDecisionsGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: RpcConsensusForkDecisionsGetResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let sync_diagnosis = warp::path!("sync" / "diagnosis")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::SyncDiagnosisGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: RpcSyncDiagnosisGetResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let node_overview = warp::path!("overview").and(warp::get()).then(move || {
+        let rpc_sender_clone = rpc_sender_clone.clone();
+        async move {
+            rpc_sender_clone
+                .oneshot_request(RpcRequest::NodeOverviewGet)
+                .await
+                .map_or_else(
+                    dropped_channel_response,
+                    |reply: RpcNodeOverviewGetResponse| with_json_reply(&reply, StatusCode::OK),
+                )
+        }
+    });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let pending_requests = warp::path!("requests" / "pending")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::PendingRequestsGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: RpcPendingRequestsGetResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    let bootstrap_snapshots = bootstrap_snapshots(
+        rpc_sender.clone(),
+        options.bootstrap_token.map(Arc::from),
+        options.snapshot_secret_key,
+    );
+
     let rpc_sender_clone = rpc_sender.clone();
     let transition_frontier_user_commands = warp::path("best-chain-user-commands")
         .and(warp::get())
@@ -583,6 +948,7 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
     let routes = signaling.or(state_get).or(state_post);
     let routes = compose_route!(
         build_env_get,
+        features_get,
         routes,
         status,
         make_heartbeat,
@@ -590,20 +956,38 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
         message_progress_get,
         stats,
         scan_state_summary_get,
+        pending_coinbase_get,
         snark_pool_jobs_get,
         snark_pool_job_get,
         snarker_config,
         snarker_job_commit,
         snarker_job_spec,
+        snarker_work_submit,
         snark_workers,
         transaction_pool,
+        transaction_pool_sender_queues(rpc_sender.clone()),
         accounts,
         transaction_post,
+        block_inject,
+        peers_refresh,
+        gossip_pause,
+        snark_verify_dry_run,
+        snark_verifier_digests,
+        consensus_fork_decisions,
+        sync_diagnosis,
+        node_overview,
+        pending_requests,
+        bootstrap_snapshots,
         transition_frontier_user_commands,
         healthcheck(rpc_sender.clone()),
         readiness(rpc_sender.clone()),
         discovery::routing_table(rpc_sender.clone()),
         discovery::bootstrap_stats(rpc_sender.clone()),
+        paginated::best_chain(rpc_sender.clone()),
+        paginated::accounts(rpc_sender.clone()),
+        paginated::pooled_user_commands(rpc_sender.clone()),
+        paginated::pooled_zkapp_commands(rpc_sender.clone()),
+        paginated::zkapp_events(rpc_sender.clone()),
         super::graphql::routes(rpc_sender),
     );
 
@@ -612,6 +996,149 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
     warp::serve(routes).run(([0, 0, 0, 0], port)).await;
 }
 
+/// Block injection for disaster recovery and testing. The block is
+/// handled the same way as if it was received from a peer, the response is
+/// sent once it is prevalidated, with the reason it was rejected otherwise.
+fn block_inject(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("admin" / "inject-block")
+        .and(warp::post())
+        .and(local_only())
+        .and(warp::header::optional::<String>("content-type"))
+        .and(warp::filters::body::bytes())
+        .then(move |content_type: Option<String>, body: bytes::Bytes| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                let block = match parse_precomputed_block(content_type.as_deref(), &body) {
+                    Ok(block) => block,
+                    Err(err) => return with_json_reply(&err, StatusCode::BAD_REQUEST),
+                };
+
+                let res: Option<RpcBlockInjectResponse> = rpc_sender
+                    .oneshot_request(RpcRequest::BlockInject(Arc::new(block)))
+                    .await;
+                match res {
+                    None => with_json_reply(
+                        &"response channel dropped",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                    Some(Ok(hash)) => with_json_reply(&hash, StatusCode::ACCEPTED),
+                    Some(Err(err)) => {
+                        with_json_reply(&ErrorCodeBody::new(&err), StatusCode::BAD_REQUEST)
+                    }
+                }
+            }
+        })
+}
+
+#[derive(Deserialize, Default)]
+struct SenderQueuesQueryParams {
+    public_key: Option<String>,
+}
+
+/// Pending commands of the transaction pool per fee payer, or only the ones
+/// of `?public_key=`.
+fn transaction_pool_sender_queues(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("transaction-pool" / "sender-queues")
+        .and(warp::get())
+        .and(optq::<SenderQueuesQueryParams>())
+        .then(move |query: SenderQueuesQueryParams| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                let public_key = match query
+                    .public_key
+                    .as_deref()
+                    .map(node::account::AccountPublicKey::from_str)
+                    .transpose()
+                {
+                    Ok(public_key) => public_key,
+                    Err(err) => {
+                        return with_json_reply(
+                            &ErrorCode::InvalidInput.body(format!("invalid public key: {err}")),
+                            StatusCode::BAD_REQUEST,
+                        )
+                    }
+                };
+                let res: Option<RpcTransactionPoolSenderQueuesGetResponse> = rpc_sender
+                    .oneshot_request(RpcRequest::TransactionPoolSenderQueuesGet(public_key))
+                    .await;
+                match res {
+                    None => with_json_reply(
+                        &"response channel dropped",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                    Some(reply) => with_json_reply(&reply, StatusCode::OK),
+                }
+            }
+        })
+}
+
+/// Ledger snapshots for other nodes to bootstrap from, see
+/// `--bootstrap-from`. They're big and take a while to read, so they're
+/// only served to nodes on this host or which send the `token`.
+fn bootstrap_snapshots(
+    rpc_sender: RpcSender,
+    token: Option<Arc<str>>,
+    secret_key: Option<P2pSecretKey>,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let snarked_ledger = warp::path!("bootstrap" / "snarked-ledger" / v2::LedgerHash)
+        .map(LedgerSnapshotQuery::SnarkedLedger);
+    let staged_ledger_parts = warp::path!("bootstrap" / "staged-ledger-parts" / v2::StateHash)
+        .map(LedgerSnapshotQuery::StagedLedgerParts);
+    snarked_ledger
+        .or(staged_ledger_parts)
+        .unify()
+        .and(warp::get())
+        .and(local_or_token(token))
+        .then(move |query: LedgerSnapshotQuery| {
+            let rpc_sender = rpc_sender.clone();
+            let secret_key = secret_key.clone();
+            async move {
+                let response = rpc_sender
+                    .oneshot_request(RpcRequest::LedgerSnapshotGet(query.clone()))
+                    .await;
+                ledger_snapshot_reply(query, response, secret_key)
+            }
+        })
+}
+
+/// Binprot encoded ledger snapshot, signed with the node's p2p key so that
+/// the node bootstrapping from it can check where it comes from.
+fn ledger_snapshot_reply(
+    query: LedgerSnapshotQuery,
+    response: Option<RpcLedgerSnapshotGetResponse>,
+    secret_key: Option<P2pSecretKey>,
+) -> warp::reply::Response {
+    let Some(mut secret_key) = secret_key else {
+        return JsonOrBinary::error("snapshots aren't served", StatusCode::NOT_FOUND)
+            .into_response();
+    };
+    let reply = match response {
+        None => JsonOrBinary::error(DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+        Some(None) => JsonOrBinary::error("snapshot not available", StatusCode::NOT_FOUND),
+        Some(Some(LedgerSnapshot::SnarkedLedger(accounts))) => JsonOrBinary::binary(accounts),
+        Some(Some(LedgerSnapshot::StagedLedgerParts(parts))) => {
+            JsonOrBinary::binary(Arc::unwrap_or_clone(parts))
+        }
+    };
+    let JsonOrBinary::Binary(body) = &reply else {
+        return reply.into_response();
+    };
+    let mut digest = LedgerSnapshotDigest::new(query);
+    digest.update(body);
+    let signature = digest.sign(&mut secret_key).to_string();
+
+    let mut response = reply.into_response();
+    response.headers_mut().insert(
+        LEDGER_SNAPSHOT_SIGNATURE_HEADER,
+        HeaderValue::from_str(&signature).expect("hex is a valid header value"),
+    );
+    response
+}
+
 fn healthcheck(
     rpc_sender: RpcSender,
 ) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
@@ -712,6 +1239,271 @@ mod discovery {
     }
 }
 
+/// Paginated variants of the best chain, accounts and transaction pool
+/// queries, and the events and actions of zkApps. Each page is returned with a `next_cursor`, which is passed back
+/// as the `cursor` query parameter to get the next page.
+mod paginated {
+    use std::{fmt::Display, str::FromStr};
+
+    use mina_node_common::rpc::RpcSender;
+    use node::{
+        account::AccountPublicKey,
+        core::{ErrorCode, HasErrorCode},
+        rpc::{
+            PooledCommandsQuery, RpcBestChainPageGetResponse, RpcCursor,
+            RpcLedgerAccountsPageGetResponse, RpcPageError, RpcPageQuery, RpcPageResult,
+            RpcPooledUserCommandsPageGetResponse, RpcPooledZkappCommandsPageGetResponse,
+            RpcRequest, RpcZkappEventsKind, RpcZkappEventsPageGetResponse, RpcZkappEventsQuery,
+        },
+    };
+    use serde::{Deserialize, Serialize};
+    use warp::{hyper::StatusCode, Filter};
+
+    use super::{optq, with_json_reply, with_rpc_sender, DroppedChannel};
+
+    #[derive(Deserialize, Default)]
+    struct PageQueryParams {
+        cursor: Option<String>,
+        limit: Option<usize>,
+        public_key: Option<String>,
+        /// `events` (default) or `actions`, for the zkApp events pages.
+        kind: Option<String>,
+    }
+
+    impl PageQueryParams {
+        fn page_query<K: FromStr>(&self) -> Result<RpcPageQuery<K>, RpcPageError> {
+            Ok(RpcPageQuery {
+                cursor: self
+                    .cursor
+                    .as_deref()
+                    .map(RpcCursor::from_str)
+                    .transpose()?,
+                limit: self.limit,
+            })
+        }
+
+        fn pooled_commands_query<ID>(&self) -> Result<PooledCommandsQuery<ID>, String> {
+            Ok(PooledCommandsQuery {
+                public_key: self
+                    .public_key
+                    .as_deref()
+                    .map(AccountPublicKey::from_str)
+                    .transpose()
+                    .map_err(|err| format!("invalid public key: {err}"))?,
+                hashes: None,
+                ids: None,
+            })
+        }
+
+        fn zkapp_events_query(&self) -> Result<RpcZkappEventsQuery, String> {
+            let public_key = self
+                .public_key
+                .as_deref()
+                .ok_or("missing public key")?
+                .parse::<AccountPublicKey>()
+                .map_err(|err| format!("invalid public key: {err}"))?;
+            let kind = match self.kind.as_deref() {
+                None | Some("events") => RpcZkappEventsKind::Events,
+                Some("actions") => RpcZkappEventsKind::Actions,
+                Some(kind) => return Err(format!("invalid kind: {kind}")),
+            };
+            Ok(RpcZkappEventsQuery { public_key, kind })
+        }
+    }
+
+    pub fn best_chain(
+        rpc_sender: RpcSender,
+    ) -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        warp::path!("paginated" / "best-chain")
+            .and(warp::get())
+            .and(optq::<PageQueryParams>())
+            .and(with_rpc_sender(rpc_sender))
+            .and_then(
+                |params: PageQueryParams, rpc_sender: RpcSender| async move {
+                    let query = match params.page_query() {
+                        Ok(query) => query,
+                        Err(err) => {
+                            return Ok(error_reply(
+                                ErrorCode::InvalidInput,
+                                err,
+                                StatusCode::BAD_REQUEST,
+                            ))
+                        }
+                    };
+                    let response: Option<RpcBestChainPageGetResponse> = rpc_sender
+                        .oneshot_request(RpcRequest::BestChainPageGet(query))
+                        .await;
+                    page_reply(response)
+                },
+            )
+    }
+
+    pub fn accounts(
+        rpc_sender: RpcSender,
+    ) -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        warp::path!("paginated" / "accounts")
+            .and(warp::get())
+            .and(optq::<PageQueryParams>())
+            .and(with_rpc_sender(rpc_sender))
+            .and_then(
+                |params: PageQueryParams, rpc_sender: RpcSender| async move {
+                    let query = match params.page_query() {
+                        Ok(query) => query,
+                        Err(err) => {
+                            return Ok(error_reply(
+                                ErrorCode::InvalidInput,
+                                err,
+                                StatusCode::BAD_REQUEST,
+                            ))
+                        }
+                    };
+                    let response: Option<RpcLedgerAccountsPageGetResponse> = rpc_sender
+                        .oneshot_request(RpcRequest::LedgerAccountsPageGet(query))
+                        .await;
+                    page_reply(response)
+                },
+            )
+    }
+
+    pub fn pooled_user_commands(
+        rpc_sender: RpcSender,
+    ) -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        warp::path!("paginated" / "pooled-user-commands")
+            .and(warp::get())
+            .and(optq::<PageQueryParams>())
+            .and(with_rpc_sender(rpc_sender))
+            .and_then(
+                |params: PageQueryParams, rpc_sender: RpcSender| async move {
+                    let query = match params.pooled_commands_query() {
+                        Ok(query) => query,
+                        Err(err) => {
+                            return Ok(error_reply(
+                                ErrorCode::InvalidInput,
+                                err,
+                                StatusCode::BAD_REQUEST,
+                            ))
+                        }
+                    };
+                    let page_query = match params.page_query() {
+                        Ok(query) => query,
+                        Err(err) => {
+                            return Ok(error_reply(
+                                ErrorCode::InvalidInput,
+                                err,
+                                StatusCode::BAD_REQUEST,
+                            ))
+                        }
+                    };
+                    let response: Option<RpcPooledUserCommandsPageGetResponse> = rpc_sender
+                        .oneshot_request(RpcRequest::PooledUserCommandsPageGet(query, page_query))
+                        .await;
+                    page_reply(response)
+                },
+            )
+    }
+
+    pub fn pooled_zkapp_commands(
+        rpc_sender: RpcSender,
+    ) -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        warp::path!("paginated" / "pooled-zkapp-commands")
+            .and(warp::get())
+            .and(optq::<PageQueryParams>())
+            .and(with_rpc_sender(rpc_sender))
+            .and_then(
+                |params: PageQueryParams, rpc_sender: RpcSender| async move {
+                    let query = match params.pooled_commands_query() {
+                        Ok(query) => query,
+                        Err(err) => {
+                            return Ok(error_reply(
+                                ErrorCode::InvalidInput,
+                                err,
+                                StatusCode::BAD_REQUEST,
+                            ))
+                        }
+                    };
+                    let page_query = match params.page_query() {
+                        Ok(query) => query,
+                        Err(err) => {
+                            return Ok(error_reply(
+                                ErrorCode::InvalidInput,
+                                err,
+                                StatusCode::BAD_REQUEST,
+                            ))
+                        }
+                    };
+                    let response: Option<RpcPooledZkappCommandsPageGetResponse> = rpc_sender
+                        .oneshot_request(RpcRequest::PooledZkappCommandsPageGet(query, page_query))
+                        .await;
+                    page_reply(response)
+                },
+            )
+    }
+
+    pub fn zkapp_events(
+        rpc_sender: RpcSender,
+    ) -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        warp::path!("paginated" / "zkapp-events")
+            .and(warp::get())
+            .and(optq::<PageQueryParams>())
+            .and(with_rpc_sender(rpc_sender))
+            .and_then(
+                |params: PageQueryParams, rpc_sender: RpcSender| async move {
+                    let query = match params.zkapp_events_query() {
+                        Ok(query) => query,
+                        Err(err) => {
+                            return Ok(error_reply(
+                                ErrorCode::InvalidInput,
+                                err,
+                                StatusCode::BAD_REQUEST,
+                            ))
+                        }
+                    };
+                    let page_query = match params.page_query() {
+                        Ok(query) => query,
+                        Err(err) => {
+                            return Ok(error_reply(
+                                ErrorCode::InvalidInput,
+                                err,
+                                StatusCode::BAD_REQUEST,
+                            ))
+                        }
+                    };
+                    let response: Option<RpcZkappEventsPageGetResponse> = rpc_sender
+                        .oneshot_request(RpcRequest::ZkappEventsPageGet(query, page_query))
+                        .await;
+                    page_reply(response)
+                },
+            )
+    }
+
+    /// A cursor whose anchor was pruned from the frontier can't be used
+    /// anymore, clients must restart from the first page.
+    fn page_reply<T: Serialize, K: Display>(
+        response: Option<RpcPageResult<T, K>>,
+    ) -> Result<warp::reply::WithStatus<warp::reply::Json>, warp::Rejection> {
+        match response {
+            None => Err(warp::reject::custom(DroppedChannel)),
+            Some(Ok(page)) => Ok(with_json_reply(&page, StatusCode::OK)),
+            Some(Err(err)) => {
+                let status = match &err {
+                    RpcPageError::NoBestTip => StatusCode::SERVICE_UNAVAILABLE,
+                    RpcPageError::AnchorNotFound(_) => StatusCode::GONE,
+                    RpcPageError::InvalidCursor(_) => StatusCode::BAD_REQUEST,
+                };
+                Ok(error_reply(err.error_code(), err, status))
+            }
+        }
+    }
+
+    fn error_reply(
+        code: ErrorCode,
+        err: impl Display,
+        status: StatusCode,
+    ) -> warp::reply::WithStatus<warp::reply::Json> {
+        with_json_reply(&code.body(err), status)
+    }
+}
+
 fn with_rpc_sender(
     rpc_sender: RpcSender,
 ) -> impl warp::Filter<Extract = (RpcSender,), Error = Infallible> + Clone {
@@ -725,12 +1517,163 @@ struct DroppedChannel;
 
 impl warp::reject::Reject for DroppedChannel {}
 
+#[derive(Debug)]
+struct NotLocal;
+
+impl warp::reject::Reject for NotLocal {}
+
+/// Rejects requests that don't come from the loopback interface.
+fn local_only() -> impl warp::Filter<Extract = (), Error = Rejection> + Clone {
+    warp::addr::remote()
+        .and_then(|addr: Option<SocketAddr>| async move {
+            match addr {
+                Some(addr) if addr.ip().is_loopback() => Ok(()),
+                _ => Err(warp::reject::custom(NotLocal)),
+            }
+        })
+        .untuple_one()
+}
+
+#[derive(Debug)]
+struct Unauthorized;
+
+impl warp::reject::Reject for Unauthorized {}
+
+#[derive(Debug)]
+struct TooManyRequests;
+
+impl warp::reject::Reject for TooManyRequests {}
+
+/// Rejects requests that neither come from the loopback interface nor carry
+/// the `token`, if one is set. A request which carries a wrong token is
+/// rejected even from the loopback interface.
+fn local_or_token(
+    token: Option<Arc<str>>,
+) -> impl warp::Filter<Extract = (), Error = Rejection> + Clone {
+    warp::addr::remote()
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(
+            move |addr: Option<SocketAddr>, authorization: Option<String>| {
+                let token = token.clone();
+                async move {
+                    let is_local = addr.is_some_and(|addr| addr.ip().is_loopback());
+                    match (token, authorization) {
+                        (Some(token), Some(authorization)) => {
+                            if bearer_token_matches(&token, &authorization) {
+                                Ok(())
+                            } else {
+                                Err(warp::reject::custom(Unauthorized))
+                            }
+                        }
+                        _ if is_local => Ok(()),
+                        (Some(_), None) => Err(warp::reject::custom(Unauthorized)),
+                        (None, _) => Err(warp::reject::custom(NotLocal)),
+                    }
+                }
+            },
+        )
+        .untuple_one()
+}
+
+/// Compares in constant time, so that response times don't tell how much
+/// of the token was guessed right.
+fn bearer_token_matches(token: &str, authorization: &str) -> bool {
+    authorization
+        .strip_prefix("Bearer ")
+        .is_some_and(|sent| sent.as_bytes().ct_eq(token.as_bytes()).into())
+}
+
+/// Lets at most `limit` requests through per second.
+#[derive(Debug)]
+struct RateLimit {
+    limit: u32,
+    window_start: Instant,
+    count: u32,
+}
+
+impl RateLimit {
+    fn new(limit: u32, now: Instant) -> Self {
+        Self {
+            limit,
+            window_start: now,
+            count: 0,
+        }
+    }
+
+    fn check(&mut self, now: Instant) -> bool {
+        if now.saturating_duration_since(self.window_start) >= Duration::from_secs(1) {
+            self.window_start = now;
+            self.count = 0;
+        }
+        if self.count >= self.limit {
+            return false;
+        }
+        self.count += 1;
+        true
+    }
+}
+
+/// Rejects requests over `limit` per second, counted over all clients.
+fn rate_limited(limit: u32) -> impl warp::Filter<Extract = (), Error = Rejection> + Clone {
+    let rate_limit = Arc::new(Mutex::new(RateLimit::new(limit, Instant::now())));
+    warp::any()
+        .and_then(move || {
+            let rate_limit = rate_limit.clone();
+            async move {
+                let allowed = rate_limit
+                    .lock()
+                    .expect("rate limit lock poisoned")
+                    .check(Instant::now());
+                if allowed {
+                    Ok(())
+                } else {
+                    Err(warp::reject::custom(TooManyRequests))
+                }
+            }
+        })
+        .untuple_one()
+}
+
+/// Parses a precomputed block, either binprot encoded (when the content type
+/// is `application/octet-stream`) or in the JSON format of the OCaml node.
+fn parse_precomputed_block(
+    content_type: Option<&str>,
+    body: &[u8],
+) -> Result<v2::MinaBlockBlockStableV2, String> {
+    let block = if content_type == Some("application/octet-stream") {
+        let mut body = body;
+        v2::PrecomputedBlock::binprot_read(&mut body).map_err(|err| err.to_string())?
+    } else {
+        // Blocks dumped by the OCaml node are wrapped with their version.
+        serde_json::from_slice::<v2::PrecomputedBlockData>(body)
+            .map(|block| block.data)
+            .or_else(|_| serde_json::from_slice::<v2::PrecomputedBlock>(body))
+            .map_err(|err| err.to_string())?
+    };
+    Ok(block.into())
+}
+
 async fn recover(rejection: warp::Rejection) -> Result<impl warp::Reply, warp::Rejection> {
     if let Some(DroppedChannel) = rejection.find() {
         Ok(warp::reply::with_status(
             warp::reply::json(&serde_json::json!({"error": DROPPED_CHANNEL})),
             StatusCode::INTERNAL_SERVER_ERROR,
         ))
+    } else if let Some(NotLocal) = rejection.find() {
+        Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "only accessible locally"})),
+            StatusCode::FORBIDDEN,
+        ))
+    } else if let Some(Unauthorized) = rejection.find() {
+        Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "missing or wrong token"})),
+            StatusCode::UNAUTHORIZED,
+        ))
+    } else if let Some(TooManyRequests) = rejection.find() {
+        Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "too many requests"})),
+            StatusCode::TOO_MANY_REQUESTS,
+        ))
     } else {
         Err(rejection)
     }
@@ -810,3 +1753,359 @@ impl Reply for JsonOrBinary {
 fn with_json_reply<T: Serialize>(reply: &T, status: StatusCode) -> WithStatus<Json> {
     with_status(json(reply), status)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_addr() -> SocketAddr {
+        ([127, 0, 0, 1], 40000).into()
+    }
+
+    fn remote_addr() -> SocketAddr {
+        ([10, 0, 0, 1], 40000).into()
+    }
+
+    #[tokio::test]
+    async fn snarker_work_without_token_is_local_only() {
+        let filter = local_or_token(None);
+
+        let local = warp::test::request().remote_addr(local_addr());
+        assert!(local.matches(&filter).await);
+
+        let rejection = warp::test::request()
+            .remote_addr(remote_addr())
+            .filter(&filter)
+            .await
+            .unwrap_err();
+        assert!(rejection.find::<NotLocal>().is_some());
+    }
+
+    #[tokio::test]
+    async fn snarker_work_with_token_needs_the_token() {
+        let filter = local_or_token(Some("secret".into()));
+
+        let authorized = warp::test::request()
+            .remote_addr(remote_addr())
+            .header("authorization", "Bearer secret");
+        assert!(authorized.matches(&filter).await);
+
+        for authorization in [None, Some("Bearer wrong"), Some("secret")] {
+            let mut request = warp::test::request().remote_addr(remote_addr());
+            if let Some(authorization) = authorization {
+                request = request.header("authorization", authorization);
+            }
+            let rejection = request.filter(&filter).await.unwrap_err();
+            assert!(
+                rejection.find::<Unauthorized>().is_some(),
+                "{authorization:?}"
+            );
+        }
+
+        let local_with_wrong_token = warp::test::request()
+            .remote_addr(local_addr())
+            .header("authorization", "Bearer wrong");
+        assert!(!local_with_wrong_token.matches(&filter).await);
+    }
+
+    #[test]
+    fn rate_limit_resets_every_second() {
+        let start = Instant::now();
+        let mut rate_limit = RateLimit::new(2, start);
+        assert!(rate_limit.check(start));
+        assert!(rate_limit.check(start + Duration::from_millis(10)));
+        assert!(!rate_limit.check(start + Duration::from_millis(20)));
+        assert!(rate_limit.check(start + Duration::from_secs(1)));
+    }
+
+    #[tokio::test]
+    async fn snarker_work_over_the_rate_is_rejected() {
+        let filter = rate_limited(1);
+        assert!(warp::test::request().matches(&filter).await);
+        let rejection = warp::test::request().filter(&filter).await.unwrap_err();
+        assert!(rejection.find::<TooManyRequests>().is_some());
+    }
+
+    fn snarked_ledger_query() -> LedgerSnapshotQuery {
+        LedgerSnapshotQuery::SnarkedLedger(
+            "jx5YAT36bv62M8mPcREYYfZWXaKqqMzDCP8wmc21uf4CfDKAHCr"
+                .parse()
+                .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn bootstrap_snapshots_need_the_token() {
+        // Nothing answers the requests, which get through as a dropped
+        // channel error.
+        let (rpc_tx, _) = node::core::channels::mpsc::channel(1);
+        let filter = bootstrap_snapshots(
+            RpcSender::new(rpc_tx),
+            Some("secret".into()),
+            Some(P2pSecretKey::deterministic(0)),
+        );
+        let paths = [
+            "/bootstrap/snarked-ledger/jx5YAT36bv62M8mPcREYYfZWXaKqqMzDCP8wmc21uf4CfDKAHCr",
+            "/bootstrap/staged-ledger-parts/3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ",
+        ];
+
+        for path in paths {
+            let rejection = warp::test::request()
+                .path(path)
+                .remote_addr(remote_addr())
+                .filter(&filter)
+                .await
+                .err()
+                .unwrap();
+            assert!(rejection.find::<Unauthorized>().is_some(), "{path}");
+
+            let response = warp::test::request()
+                .path(path)
+                .remote_addr(remote_addr())
+                .header("authorization", "Bearer secret")
+                .reply(&filter)
+                .await;
+            assert_eq!(
+                response.status(),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn ledger_snapshot_reply_is_signed() {
+        let secret_key = P2pSecretKey::deterministic(0);
+        let snapshot = Some(Some(LedgerSnapshot::SnarkedLedger(Vec::new())));
+        let response =
+            ledger_snapshot_reply(snarked_ledger_query(), snapshot, Some(secret_key.clone()));
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let signature = response.headers()[LEDGER_SNAPSHOT_SIGNATURE_HEADER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let body = JsonOrBinary::binary(Vec::<v2::MinaBaseAccountBinableArgStableV2>::new());
+        let JsonOrBinary::Binary(body) = body else {
+            panic!("snapshot isn't binary");
+        };
+        let mut digest = LedgerSnapshotDigest::new(snarked_ledger_query());
+        digest.update(&body);
+        assert!(digest.verify(&secret_key.public_key(), &signature));
+    }
+
+    #[test]
+    fn ledger_snapshot_reply_without_key_isnt_served() {
+        let snapshot = Some(Some(LedgerSnapshot::SnarkedLedger(Vec::new())));
+        let response = ledger_snapshot_reply(snarked_ledger_query(), snapshot, None);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(!response
+            .headers()
+            .contains_key(LEDGER_SNAPSHOT_SIGNATURE_HEADER));
+    }
+
+    fn precomputed_block() -> v2::PrecomputedBlock {
+        let bytes = include_bytes!(
+            "../../../tests/files/archive-breadcrumb/3NK56ZbCS31qb8SvCtCCYza4beRDtKgXA2JL6s3evKouG2KkKtiy.bin"
+        );
+        let diff = v2::ArchiveTransitionFrontierDiff::binprot_read(&mut bytes.as_slice()).unwrap();
+        let block = diff.block().unwrap();
+        v2::PrecomputedBlock {
+            scheduled_time: block
+                .header
+                .protocol_state
+                .body
+                .blockchain_state
+                .timestamp
+                .clone(),
+            protocol_state: block.header.protocol_state.clone(),
+            protocol_state_proof: v2::PrecomputedBlockProof(
+                (*block.header.protocol_state_proof).clone(),
+            ),
+            staged_ledger_diff: block.body.staged_ledger_diff.clone(),
+            delta_transition_chain_proof: block.header.delta_block_chain_proof.clone(),
+            protocol_version: block.header.current_protocol_version.clone(),
+            proposed_protocol_version: block.header.proposed_protocol_version_opt.clone(),
+            accounts_accessed: Default::default(),
+            accounts_created: Default::default(),
+            tokens_used: Default::default(),
+        }
+    }
+
+    #[test]
+    fn precomputed_block_formats() {
+        let precomputed = precomputed_block();
+        let expected = v2::MinaBlockBlockStableV2::from(precomputed.clone());
+
+        let json = serde_json::to_vec(&precomputed).unwrap();
+        assert_eq!(parse_precomputed_block(None, &json).unwrap(), expected);
+        let versioned = serde_json::to_vec(&precomputed.with_version(1)).unwrap();
+        assert_eq!(
+            parse_precomputed_block(Some("application/json"), &versioned).unwrap(),
+            expected
+        );
+
+        let mut binprot = Vec::new();
+        precomputed.binprot_write(&mut binprot).unwrap();
+        assert_eq!(
+            parse_precomputed_block(Some("application/octet-stream"), &binprot).unwrap(),
+            expected
+        );
+        // Binprot is only read with its content type.
+        assert!(parse_precomputed_block(None, &binprot).is_err());
+        assert!(parse_precomputed_block(None, b"{}").is_err());
+    }
+
+    #[tokio::test]
+    async fn block_inject_is_local_only() {
+        let (rpc_tx, _) = node::core::channels::mpsc::channel(1);
+        let json = serde_json::to_vec(&precomputed_block()).unwrap();
+        let rejection = warp::test::request()
+            .method("POST")
+            .path("/admin/inject-block")
+            .remote_addr(remote_addr())
+            .body(json)
+            .filter(&block_inject(RpcSender::new(rpc_tx)))
+            .await
+            .err()
+            .unwrap();
+        assert!(rejection.find::<NotLocal>().is_some());
+    }
+
+    #[tokio::test]
+    async fn block_inject_rejects_malformed_blocks() {
+        let (rpc_tx, _) = node::core::channels::mpsc::channel(1);
+        let response = warp::test::request()
+            .method("POST")
+            .path("/admin/inject-block")
+            .remote_addr(local_addr())
+            .body("not a block")
+            .reply(&block_inject(RpcSender::new(rpc_tx)))
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn block_inject_responds_with_the_node_result() {
+        let precomputed = precomputed_block();
+        let expected = v2::MinaBlockBlockStableV2::from(precomputed.clone());
+        let hash: v2::StateHash = "3NK56ZbCS31qb8SvCtCCYza4beRDtKgXA2JL6s3evKouG2KkKtiy"
+            .parse()
+            .unwrap();
+        let json = serde_json::to_vec(&precomputed).unwrap();
+
+        let responses = [Ok(hash.clone()), Err(RpcBlockInjectError::AlreadyKnown)];
+        for response in responses {
+            let (rpc_tx, mut rpc_rx) = node::core::channels::mpsc::channel(1);
+            let node = tokio::spawn({
+                let expected = expected.clone();
+                let response = response.clone();
+                async move {
+                    let request: mina_node_common::rpc::NodeRpcRequest =
+                        rpc_rx.recv().await.unwrap();
+                    let RpcRequest::BlockInject(block) = request.req else {
+                        panic!("unexpected request");
+                    };
+                    assert_eq!(*block, expected);
+                    let responder = request
+                        .responder
+                        .downcast::<node::core::channels::oneshot::Sender<RpcBlockInjectResponse>>()
+                        .unwrap();
+                    responder.send(response).unwrap();
+                }
+            });
+
+            let reply = warp::test::request()
+                .method("POST")
+                .path("/admin/inject-block")
+                .remote_addr(local_addr())
+                .header("content-type", "application/json")
+                .body(json.clone())
+                .reply(&block_inject(RpcSender::new(rpc_tx)))
+                .await;
+            node.await.unwrap();
+
+            match response {
+                Ok(hash) => {
+                    assert_eq!(reply.status(), StatusCode::ACCEPTED);
+                    let body: v2::StateHash = serde_json::from_slice(reply.body()).unwrap();
+                    assert_eq!(body, hash);
+                }
+                Err(err) => {
+                    assert_eq!(reply.status(), StatusCode::BAD_REQUEST);
+                    let body: ErrorCodeBody = serde_json::from_slice(reply.body()).unwrap();
+                    assert_eq!(body.error, err.to_string());
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn transaction_pool_sender_queues_forwards_the_public_key() {
+        const PUBLIC_KEY: &str = "B62qoTtn6hP2R1x5d4UQoJos9vjoNGxLhaQn5cSKneu25Q3wpmtPirT";
+        let public_key: node::account::AccountPublicKey = PUBLIC_KEY.parse().unwrap();
+        let sender = ledger::AccountId::new(
+            public_key.clone().try_into().unwrap(),
+            ledger::TokenId::default(),
+        );
+        let paths = [
+            ("/transaction-pool/sender-queues".to_owned(), None),
+            (
+                format!("/transaction-pool/sender-queues?public_key={PUBLIC_KEY}"),
+                Some(public_key),
+            ),
+        ];
+        for (path, expected) in paths {
+            let (rpc_tx, mut rpc_rx) = node::core::channels::mpsc::channel(1);
+            let node = tokio::spawn({
+                let sender = sender.clone();
+                async move {
+                    let request: mina_node_common::rpc::NodeRpcRequest =
+                        rpc_rx.recv().await.unwrap();
+                    let RpcRequest::TransactionPoolSenderQueuesGet(public_key) = request.req else {
+                        panic!("unexpected request");
+                    };
+                    assert_eq!(public_key, expected);
+                    let response = vec![ledger::transaction_pool::SenderQueue {
+                        sender,
+                        currency_reserved: ledger::scan_state::currency::Amount::from_u64(
+                            1_010_000_000,
+                        ),
+                        commands: vec![],
+                    }];
+                    let responder = request
+                        .responder
+                        .downcast::<node::core::channels::oneshot::Sender<
+                            RpcTransactionPoolSenderQueuesGetResponse,
+                        >>()
+                        .unwrap();
+                    responder.send(response).unwrap();
+                }
+            });
+
+            let reply = warp::test::request()
+                .path(&path)
+                .reply(&transaction_pool_sender_queues(RpcSender::new(rpc_tx)))
+                .await;
+            node.await.unwrap();
+
+            assert_eq!(reply.status(), StatusCode::OK, "{path}");
+            let body: RpcTransactionPoolSenderQueuesGetResponse =
+                serde_json::from_slice(reply.body()).unwrap();
+            assert_eq!(body.len(), 1, "{path}");
+            assert_eq!(body[0].sender, sender, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn transaction_pool_sender_queues_rejects_invalid_public_keys() {
+        // Nothing answers, the request must not reach the node.
+        let (rpc_tx, _rpc_rx) = node::core::channels::mpsc::channel(1);
+        let reply = warp::test::request()
+            .path("/transaction-pool/sender-queues?public_key=B62qinvalid")
+            .reply(&transaction_pool_sender_queues(RpcSender::new(rpc_tx)))
+            .await;
+        assert_eq!(reply.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -1,5 +1,6 @@
 pub use mina_node_common::*;
 
+pub mod crash_bundle;
 pub mod graphql;
 pub mod http_server;
 
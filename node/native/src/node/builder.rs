@@ -1,44 +1,70 @@
 use std::{
     fs::File,
-    io::{BufRead, BufReader, Read},
     net::IpAddr,
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
 
 use anyhow::Context;
-use ledger::proofs::provers::BlockProver;
-use mina_core::{consensus::ConsensusConstants, constants::constraint_constants};
-use mina_node_common::{archive::config::ArchiveStorageOptions, p2p::TaskSpawner};
+use ledger::{
+    proofs::provers::BlockProver,
+    scan_state::currency::Fee,
+    transaction_pool::{ZkappRelayConfig, DEFAULT_REPLACE_FEE},
+};
+use mina_core::{
+    consensus::ConsensusConstants, constants::constraint_constants, NetworkConfig, TimeSource,
+};
+use mina_node_common::{
+    archive::config::ArchiveStorageOptions,
+    p2p::TaskSpawner,
+    peer_list::{fetch_peer_list, read_peer_list},
+    EventQueueConfig, SnarkVerifyPoolsConfig, TransitionFrontierEventCallback,
+};
 use mina_p2p_messages::v2::{self, NonZeroCurvePoint};
 use node::{
     account::AccountSecretKey,
+    block_producer::BlockProducerWorkFeePolicy,
     daemon_json::Daemon,
     p2p::{
-        channels::ChannelId, connection::outgoing::P2pConnectionOutgoingInitOpts,
-        identity::SecretKey as P2pSecretKey, P2pLimits, P2pMeshsubConfig, P2pTimeouts,
+        channels::ChannelId,
+        connection::outgoing::{P2pConnectionOutgoingInitOpts, P2pConnectionOutgoingPeerAddr},
+        identity::SecretKey as P2pSecretKey,
+        P2pLimits, P2pMeshsubConfig, P2pTimeouts, PeerId,
     },
     service::Recorder,
-    snark::{get_srs, BlockVerifier, TransactionVerifier, VerifierSRS},
-    transition_frontier::{archive::archive_config::ArchiveConfig, genesis::GenesisConfig},
+    snark::{
+        get_srs, BlockVerifier, SnarkVerifyCacheConfig, TransactionVerifier, VerifierDigests,
+        VerifierSRS,
+    },
+    transition_frontier::{
+        archive::archive_config::ArchiveConfig, genesis::GenesisConfig,
+        sync::ledger::LedgerSnapshotSource, DEFAULT_ROOT_HISTORY_SIZE,
+    },
     BlockProducerConfig, GlobalConfig, LedgerConfig, P2pConfig, SnarkConfig, SnarkerConfig,
     SnarkerStrategy, TransitionFrontierConfig,
 };
 use rand::Rng;
 
-use crate::NodeServiceBuilder;
+use crate::{http_server::HttpServerOptions, NodeServiceBuilder};
 
 use super::Node;
 
 pub struct NodeBuilder {
     rng_seed: [u8; 32],
     custom_initial_time: Option<redux::Timestamp>,
+    time_source: Option<TimeSource>,
     genesis_config: Arc<GenesisConfig>,
     p2p: P2pConfig,
     p2p_sec_key: Option<P2pSecretKey>,
     p2p_is_seed: bool,
     p2p_is_started: bool,
+    p2p_is_disabled: bool,
+    peer_list: Option<(reqwest::Url, Vec<P2pConnectionOutgoingInitOpts>)>,
+    peer_list_refresh_interval: Option<Duration>,
+    tx_pool_max_lifetime: Option<Duration>,
+    tx_pool_zkapp_relay: ZkappRelayConfig,
+    tx_pool_replace_fee: Fee,
     block_producer: Option<BlockProducerConfig>,
     archive: Option<ArchiveConfig>,
     snarker: Option<SnarkerConfig>,
@@ -47,7 +73,17 @@ pub struct NodeBuilder {
     block_verifier_index: Option<BlockVerifier>,
     work_verifier_index: Option<TransactionVerifier>,
     http_port: Option<u16>,
+    snarker_work_token: Option<String>,
+    bootstrap_token: Option<String>,
     daemon_conf: Daemon,
+    allow_historical_blocks: bool,
+    bootstrap_from: Option<LedgerSnapshotSource>,
+    precomputed_blocks_url: Option<reqwest::Url>,
+    root_history_size: Option<usize>,
+    snark_verify_cache: SnarkVerifyCacheConfig,
+    snark_verify_concurrency: usize,
+    skip_verifier_digest_check: bool,
+    crash_bundle_dir: Option<PathBuf>,
 }
 
 impl NodeBuilder {
@@ -66,13 +102,16 @@ impl NodeBuilder {
         Self {
             rng_seed,
             custom_initial_time: None,
+            time_source: None,
             genesis_config,
             p2p: P2pConfig {
                 libp2p_port: None,
                 listen_port: None,
                 // Must be replaced with builder api.
                 identity_pub_key: P2pSecretKey::deterministic(0).public_key(),
+                previous_peer_ids: Vec::new(),
                 initial_peers: Vec::new(),
+                initial_dnsaddrs: Vec::new(),
                 external_addrs: Vec::new(),
                 enabled_channels: ChannelId::iter_all().collect(),
                 peer_discovery: true,
@@ -86,6 +125,12 @@ impl NodeBuilder {
             p2p_sec_key: None,
             p2p_is_seed: false,
             p2p_is_started: false,
+            p2p_is_disabled: false,
+            peer_list: None,
+            peer_list_refresh_interval: None,
+            tx_pool_max_lifetime: NetworkConfig::global().tx_pool_max_lifetime,
+            tx_pool_zkapp_relay: Default::default(),
+            tx_pool_replace_fee: DEFAULT_REPLACE_FEE,
             block_producer: None,
             archive: None,
             snarker: None,
@@ -94,7 +139,17 @@ impl NodeBuilder {
             block_verifier_index: None,
             work_verifier_index: None,
             http_port: None,
+            snarker_work_token: None,
+            bootstrap_token: None,
             daemon_conf,
+            allow_historical_blocks: false,
+            bootstrap_from: None,
+            precomputed_blocks_url: None,
+            root_history_size: None,
+            snark_verify_cache: Default::default(),
+            snark_verify_concurrency: 1,
+            skip_verifier_digest_check: false,
+            crash_bundle_dir: None,
         }
     }
 
@@ -111,6 +166,15 @@ impl NodeBuilder {
         self
     }
 
+    /// Keys this node used before its p2p key was rotated. Their peer ids
+    /// are recognized as this node while other peers still advertise them,
+    /// and webrtc peers dialing them are answered with the matching key.
+    pub fn p2p_previous_sec_keys(&mut self, keys: Vec<P2pSecretKey>) -> &mut Self {
+        self.p2p.previous_peer_ids = keys.iter().map(|key| key.public_key().peer_id()).collect();
+        self.service.p2p_previous_sec_keys(keys);
+        self
+    }
+
     pub fn p2p_libp2p_port(&mut self, port: u16) -> &mut Self {
         self.p2p.libp2p_port = Some(port);
         self
@@ -136,6 +200,20 @@ impl NodeBuilder {
         self
     }
 
+    /// Extend p2p initial peers from addresses given by the operator.
+    ///
+    /// `/dnsaddr` domains are resolved by the running node, and resolved
+    /// again when their records expire or their peers can't be reached.
+    pub fn initial_peer_addrs(
+        &mut self,
+        addrs: impl IntoIterator<Item = P2pConnectionOutgoingPeerAddr>,
+    ) -> &mut Self {
+        let (peers, dnsaddrs) = P2pConnectionOutgoingPeerAddr::partition(addrs);
+        self.p2p.initial_peers.extend(peers);
+        self.p2p.initial_dnsaddrs.extend(dnsaddrs);
+        self
+    }
+
     pub fn external_addrs(&mut self, v: impl Iterator<Item = IpAddr>) -> &mut Self {
         self.p2p.external_addrs.extend(v);
         self
@@ -143,41 +221,73 @@ impl NodeBuilder {
 
     /// Extend p2p initial peers from file.
     pub fn initial_peers_from_file(&mut self, path: impl AsRef<Path>) -> anyhow::Result<&mut Self> {
-        peers_from_reader(
-            &mut self.p2p.initial_peers,
-            File::open(&path).context(anyhow::anyhow!(
-                "opening peer list file {:?}",
-                path.as_ref()
-            ))?,
-        )
+        let addrs = read_peer_list(File::open(&path).context(anyhow::anyhow!(
+            "opening peer list file {:?}",
+            path.as_ref()
+        ))?)
         .context(anyhow::anyhow!(
             "reading peer list file {:?}",
             path.as_ref()
         ))?;
-
-        Ok(self)
+        Ok(self.initial_peer_addrs(addrs))
     }
 
     /// Extend p2p initial peers by opening the url.
+    ///
+    /// The list can later be refetched with an rpc, or periodically, see
+    /// [`Self::peer_list_refresh_interval`]. `/dnsaddr` entries are only
+    /// read from the first fetch, the node keeps resolving their domains.
     pub fn initial_peers_from_url(
         &mut self,
         url: impl reqwest::IntoUrl,
     ) -> anyhow::Result<&mut Self> {
         let url = url.into_url().context("failed to parse peers url")?;
-        peers_from_reader(
-            &mut self.p2p.initial_peers,
-            reqwest::blocking::get(url.clone())
-                .context(anyhow::anyhow!("reading peer list url {url}"))?,
-        )
-        .context(anyhow::anyhow!("reading peer list url {url}"))?;
+        let (peers, dnsaddrs) =
+            P2pConnectionOutgoingPeerAddr::partition(fetch_peer_list(url.clone())?);
+        self.p2p.initial_peers.extend(peers.iter().cloned());
+        self.p2p.initial_dnsaddrs.extend(dnsaddrs);
+        self.peer_list = Some((url, peers));
         Ok(self)
     }
 
+    /// Refetch the peer list from the url periodically. Peers added to the
+    /// list get dialed, peers removed from it are no longer dialed.
+    pub fn peer_list_refresh_interval(&mut self, interval: Duration) -> &mut Self {
+        self.peer_list_refresh_interval = Some(interval);
+        self
+    }
+
+    /// How long a command can stay in the transaction pool, unlimited if
+    /// `None`. Defaults to the one of the network.
+    pub fn tx_pool_max_lifetime(&mut self, lifetime: Option<Duration>) -> &mut Self {
+        self.tx_pool_max_lifetime = lifetime;
+        self
+    }
+
+    /// Whether zkApp commands received from the gossip network are relayed
+    /// when they can't be verified in time.
+    pub fn tx_pool_zkapp_relay(&mut self, config: ZkappRelayConfig) -> &mut Self {
+        self.tx_pool_zkapp_relay = config;
+        self
+    }
+
+    /// Fee increase required to replace a command of the transaction pool
+    /// with one from the same fee payer and with the same nonce.
+    pub fn tx_pool_replace_fee(&mut self, fee: Fee) -> &mut Self {
+        self.tx_pool_replace_fee = fee;
+        self
+    }
+
     pub fn p2p_max_peers(&mut self, limit: usize) -> &mut Self {
         self.p2p.limits = self.p2p.limits.with_max_peers(Some(limit));
         self
     }
 
+    pub fn p2p_snarks_per_message(&mut self, limit: usize) -> &mut Self {
+        self.p2p.limits = self.p2p.limits.with_snarks_per_message(Some(limit));
+        self
+    }
+
     /// Override default p2p task spawner.
     pub fn p2p_custom_task_spawner(
         &mut self,
@@ -200,12 +310,34 @@ impl NodeBuilder {
             pub_key: key.public_key().into(),
             custom_coinbase_receiver: None,
             proposed_protocol_version: None,
+            work_fee_policy: Default::default(),
         };
         self.block_producer = Some(config);
         self.service.block_producer_init(key, provers);
         self
     }
 
+    /// Run a local development chain: p2p is disabled, and the node produces
+    /// a block on every slot with `key`, with dummy proofs. It also does the
+    /// snark work its blocks need, for free and with dummy proofs too.
+    ///
+    /// The genesis config should give `key` its funds, see
+    /// [`GenesisConfig::dev_local`].
+    pub fn dev_local(&mut self, key: AccountSecretKey) -> &mut Self {
+        self.p2p_is_disabled = true;
+        self.block_producer = Some(BlockProducerConfig {
+            pub_key: key.public_key().into(),
+            custom_coinbase_receiver: None,
+            proposed_protocol_version: None,
+            work_fee_policy: Default::default(),
+        });
+        self.snarker(key.clone(), 0, SnarkerStrategy::Sequential);
+        self.service
+            .block_producer_dev_local_init(key)
+            .snark_work_dummy_proofs();
+        self
+    }
+
     /// Set up block producer using keys from file.
     pub fn block_producer_from_file(
         &mut self,
@@ -223,7 +355,7 @@ impl NodeBuilder {
     }
 
     pub fn archive(&mut self, options: ArchiveStorageOptions, work_dir: String) -> &mut Self {
-        self.archive = Some(ArchiveConfig::new(work_dir.clone()));
+        self.archive = Some(ArchiveConfig::new(work_dir.clone(), options.names()));
         self.service.archive_init(options, work_dir.clone());
         self
     }
@@ -242,6 +374,18 @@ impl NodeBuilder {
         Ok(self)
     }
 
+    /// Limit the fees spent on completed snark work by the block producer.
+    pub fn block_producer_work_fee_policy(
+        &mut self,
+        policy: BlockProducerWorkFeePolicy,
+    ) -> anyhow::Result<&mut Self> {
+        let bp = self.block_producer.as_mut().ok_or_else(|| {
+            anyhow::anyhow!("can't set work_fee_policy when block producer is not initialized.")
+        })?;
+        bp.work_fee_policy = policy;
+        Ok(self)
+    }
+
     pub fn custom_block_producer_config(
         &mut self,
         config: BlockProducerConfig,
@@ -265,11 +409,24 @@ impl NodeBuilder {
             )),
             strategy,
             auto_commit: true,
+            coordinators: Vec::new(),
         };
         self.snarker = Some(config);
         self
     }
 
+    /// Also submit the produced snark work to the http servers of these
+    /// nodes.
+    pub fn snarker_coordinators(&mut self, urls: Vec<reqwest::Url>) -> anyhow::Result<&mut Self> {
+        self.snarker
+            .as_mut()
+            .ok_or_else(|| {
+                anyhow::anyhow!("snarker not initialized! Call `snarker` function first.")
+            })?
+            .coordinators = urls.into_iter().map(String::from).collect();
+        Ok(self)
+    }
+
     /// Set verifier srs. If not set, default will be used.
     pub fn verifier_srs(&mut self, srs: Arc<VerifierSRS>) -> &mut Self {
         self.verifier_srs = Some(srs);
@@ -291,21 +448,174 @@ impl NodeBuilder {
         self
     }
 
+    /// Makes the time of the node go by faster than the wall clock. Unless
+    /// set with [`Self::custom_initial_time`], the initial time of the node
+    /// is taken from `time_source`, so that its timestamps are accelerated
+    /// too.
+    pub fn time_source(&mut self, time_source: TimeSource) -> &mut Self {
+        self.time_source = Some(time_source);
+        self.service.time_source(time_source);
+        self
+    }
+
+    /// Keeps the history of sync durations and chain growth across
+    /// restarts, in the file at `path`.
+    pub fn sync_history(&mut self, path: PathBuf) -> &mut Self {
+        self.service.sync_history(path);
+        self
+    }
+
+    /// Keeps the actions and events of zkApp commands, saved to the file
+    /// at `path`, see [`node::ledger::LedgerCtx::set_action_event_store`].
+    pub fn zkapp_actions_events(&mut self, path: PathBuf) -> &mut Self {
+        self.service.zkapp_actions_events(path);
+        self
+    }
+
+    /// Directory the crash bundles are written to when the node panics,
+    /// see [`crate::crash_bundle`].
+    pub fn crash_bundle_dir(&mut self, dir: PathBuf) -> &mut Self {
+        self.crash_bundle_dir = Some(dir);
+        self
+    }
+
+    pub fn event_queue_config(&mut self, config: EventQueueConfig) -> &mut Self {
+        self.service.event_queue_config(config);
+        self
+    }
+
+    pub fn snark_verify_pools_config(&mut self, config: SnarkVerifyPoolsConfig) -> &mut Self {
+        self.service.snark_verify_pools_config(config);
+        self
+    }
+
+    /// Calls `callback` with every change of the best tip and of the sync
+    /// status, from the thread running the state machine, see
+    /// [`mina_node_common::TransitionFrontierEvent`].
+    pub fn transition_frontier_event_hook(
+        &mut self,
+        callback: TransitionFrontierEventCallback,
+    ) -> &mut Self {
+        self.service.transition_frontier_event_hook(callback);
+        self
+    }
+
+    /// Capacity and eviction policy of the cache of block, transaction and
+    /// snark work verification results.
+    pub fn snark_verify_cache_config(&mut self, config: SnarkVerifyCacheConfig) -> &mut Self {
+        self.snark_verify_cache = config;
+        self
+    }
+
+    /// Max number of zkApp proofs of a batch of transactions verified in
+    /// parallel.
+    pub fn snark_verify_concurrency(&mut self, concurrency: usize) -> &mut Self {
+        self.snark_verify_concurrency = concurrency;
+        self
+    }
+
+    /// Start even if the SRS or the verifier indices don't match the
+    /// known-good ones of the network, see [`VerifierDigests::check`].
+    pub fn skip_verifier_digest_check(&mut self, skip: bool) -> &mut Self {
+        self.skip_verifier_digest_check = skip;
+        self
+    }
+
+    /// Accept blocks regardless of how late they are compared to the
+    /// current slot, for importing historical chains.
+    pub fn allow_historical_blocks(&mut self) -> &mut Self {
+        self.allow_historical_blocks = true;
+        self
+    }
+
+    /// Download the root ledgers from the trusted node's http server at
+    /// `url` instead of syncing them from peers. Snapshots are only used
+    /// if signed with the p2p key of `peer_id`.
+    pub fn bootstrap_from(&mut self, url: reqwest::Url, peer_id: PeerId) -> &mut Self {
+        self.bootstrap_from = Some(LedgerSnapshotSource {
+            url: url.into(),
+            peer_id,
+        });
+        self
+    }
+
+    /// Fetch the blocks that peers can't serve during catchup from the
+    /// precomputed blocks bucket at `url`. Supports `gs://` and `s3://`
+    /// public buckets, as well as plain `http(s)://` urls.
+    pub fn precomputed_blocks_url(&mut self, url: reqwest::Url) -> anyhow::Result<&mut Self> {
+        if !matches!(url.scheme(), "http" | "https" | "gs" | "s3") {
+            anyhow::bail!(
+                "unsupported precomputed blocks url scheme: {}",
+                url.scheme()
+            );
+        }
+        self.precomputed_blocks_url = Some(url);
+        Ok(self)
+    }
+
+    /// Number of blocks which left the transition frontier to keep a
+    /// summary of, to answer queries about recently finalized blocks.
+    pub fn root_history_size(&mut self, size: usize) -> &mut Self {
+        self.root_history_size = Some(size);
+        self
+    }
+
     pub fn record(&mut self, recorder: Recorder) -> &mut Self {
         self.service.record(recorder);
         self
     }
 
+    /// Token required from snarkers on other hosts to submit work to our
+    /// http server, and sent with the work we submit to other nodes. Must
+    /// be set before the http server is started.
+    pub fn snarker_work_token(&mut self, token: String) -> &mut Self {
+        if self.http_port.is_some() {
+            panic!("snarker work token must be set before starting the http server");
+        }
+        self.service.snarker_work_token(token.clone());
+        self.snarker_work_token = Some(token);
+        self
+    }
+
+    /// Token which nodes bootstrapping from this one must send, and which
+    /// is sent when bootstrapping from another node.
+    pub fn bootstrap_token(&mut self, token: String) -> &mut Self {
+        if self.http_port.is_some() {
+            panic!("bootstrap token must be set before starting the http server");
+        }
+        self.service.bootstrap_token(token.clone());
+        self.bootstrap_token = Some(token);
+        self
+    }
+
     pub fn http_server(&mut self, port: u16) -> &mut Self {
         self.http_port = Some(port);
-        self.service.http_server_init(port);
+        let options = HttpServerOptions {
+            snarker_work_token: self.snarker_work_token.clone(),
+            bootstrap_token: self.bootstrap_token.clone(),
+            snapshot_secret_key: self.p2p_sec_key.clone(),
+        };
+        self.service.http_server_init(port, options);
         self
     }
 
     pub fn build(mut self) -> anyhow::Result<Node> {
         let p2p_sec_key = self.p2p_sec_key.clone().unwrap_or_else(P2pSecretKey::rand);
         self.p2p_sec_key(p2p_sec_key.clone());
-        if self.p2p.initial_peers.is_empty() && !self.p2p_is_seed {
+        if self.p2p_is_disabled {
+            if !self.p2p.initial_peers.is_empty()
+                || !self.p2p.initial_dnsaddrs.is_empty()
+                || self.peer_list.is_some()
+            {
+                anyhow::bail!("initial peers can't be set when p2p is disabled");
+            }
+            // Neither listen for nor look for peers.
+            self.p2p.libp2p_port = None;
+            self.p2p.peer_discovery = false;
+        } else if self.p2p.initial_peers.is_empty()
+            && self.p2p.initial_dnsaddrs.is_empty()
+            && !self.p2p_is_seed
+        {
             self.p2p.initial_peers = default_peers();
         }
 
@@ -314,15 +624,25 @@ impl NodeBuilder {
             .initial_peers
             .into_iter()
             .filter(|opts| *opts.peer_id() != p2p_sec_key.public_key().peer_id())
-            .filter_map(|opts| match opts {
-                P2pConnectionOutgoingInitOpts::LibP2P(mut opts) => {
-                    opts.host = opts.host.resolve()?;
-                    Some(P2pConnectionOutgoingInitOpts::LibP2P(opts))
-                }
-                x => Some(x),
-            })
             .collect();
 
+        let network = NetworkConfig::global();
+        let circuits_constraint_constants = network.circuits_constraint_constants();
+        if constraint_constants().digest() != circuits_constraint_constants.digest() {
+            node::core::warn!(
+                node::core::log::system_time();
+                summary = "constraint constants overridden",
+                message = "constraint constants differ from the built-in ones of the network, \
+                           proofs only verify with verifier indices generated for them",
+                network = network.name,
+            );
+        }
+        // The built-in verifier indices are generated for the built-in
+        // constraint constants of the network.
+        let constraint_constants_digest = (self.block_verifier_index.is_none()
+            && self.work_verifier_index.is_none())
+        .then(|| circuits_constraint_constants.digest());
+
         let srs = self.verifier_srs.unwrap_or_else(get_srs);
         let block_verifier_index = self
             .block_verifier_index
@@ -338,12 +658,17 @@ impl NodeBuilder {
             .checked_sub(redux::Timestamp::ZERO)
             .unwrap_or_default();
 
+        self.daemon_conf
+            .zkapp_command_limits()
+            .init()
+            .map_err(anyhow::Error::msg)?;
+
         let protocol_constants = self.genesis_config.protocol_constants()?;
         let consensus_consts =
             ConsensusConstants::create(constraint_constants(), &protocol_constants);
 
         // build config
-        let node_config = node::Config {
+        let mut node_config = node::Config {
             global: GlobalConfig {
                 build: node::BuildEnv::get().into(),
                 snarker: self.snarker,
@@ -352,23 +677,80 @@ impl NodeBuilder {
                 client_port: self.http_port,
             },
             p2p: self.p2p,
-            ledger: LedgerConfig {},
+            ledger: LedgerConfig::default(),
             snark: SnarkConfig {
                 block_verifier_index,
-                block_verifier_srs: srs.clone(),
                 work_verifier_index,
-                work_verifier_srs: srs,
+                verifier_srs: srs,
+                timeouts: Default::default(),
+                verify_cache: self.snark_verify_cache,
+                verify_concurrency: self.snark_verify_concurrency,
+                constraint_constants_digest,
+            },
+            transition_frontier: TransitionFrontierConfig {
+                allow_historical_blocks: self.allow_historical_blocks,
+                bootstrap_from: self.bootstrap_from,
+                precomputed_blocks_url: self.precomputed_blocks_url.map(String::from),
+                root_history_size: self.root_history_size.unwrap_or(DEFAULT_ROOT_HISTORY_SIZE),
+                ..TransitionFrontierConfig::new(self.genesis_config)
             },
-            transition_frontier: TransitionFrontierConfig::new(self.genesis_config),
             block_producer: self.block_producer,
             archive: self.archive,
             tx_pool: ledger::transaction_pool::Config {
                 trust_system: (),
                 pool_max_size: self.daemon_conf.tx_pool_max_size(),
                 slot_tx_end: self.daemon_conf.slot_tx_end(),
+                max_lifetime: self.tx_pool_max_lifetime,
+                zkapp_relay: self.tx_pool_zkapp_relay,
+                replace_fee: self.tx_pool_replace_fee,
             },
         };
 
+        node_config
+            .snark
+            .check_constraint_constants(constraint_constants())?;
+
+        let verifier_digests = VerifierDigests::new(&node_config.snark, network);
+        node::core::info!(
+            node::core::log::system_time();
+            summary = "verifier digests",
+            srs = verifier_digests.srs.clone(),
+            block_verifier_index = verifier_digests.block_verifier_index.index.clone(),
+            work_verifier_index = verifier_digests.work_verifier_index.index.clone(),
+        );
+        match verifier_digests.check() {
+            Err(err) if self.skip_verifier_digest_check => {
+                node::core::warn!(
+                    node::core::log::system_time();
+                    summary = "verifier digest check skipped",
+                    error = err.to_string(),
+                );
+            }
+            result => result?,
+        }
+        node_config.snark.verifier_digests = Some(verifier_digests);
+
+        // Logged and included in the crash bundles, so that reports tell how
+        // the node was built and configured.
+        let features = node::EnabledFeatures::new(&node_config);
+        node::core::info!(
+            node::core::log::system_time();
+            summary = "enabled features",
+            compiled = features.compiled.join(","),
+            wasm = features.wasm,
+            block_producer = features.block_producer,
+            snarker = features.snarker,
+            archive = features.archive.join(","),
+        );
+        if let Some(dir) = self.crash_bundle_dir {
+            crate::crash_bundle::CrashBundleContext {
+                dir,
+                build: (*node_config.global.build).clone(),
+                features,
+            }
+            .install();
+        }
+
         // build service
         let mut service = self.service;
         service.ledger_init();
@@ -376,8 +758,17 @@ impl NodeBuilder {
         if !self.p2p_is_started {
             service.p2p_init(p2p_sec_key);
         }
+        if let Some((url, peers)) = self.peer_list {
+            service.peer_list_init(url, self.peer_list_refresh_interval, peers);
+        }
 
         let service = service.build()?;
+        // Taken once the service is built, as the accelerated time went
+        // by faster while building it.
+        let initial_time = match (self.custom_initial_time, self.time_source) {
+            (None, Some(time_source)) => time_source.global_now(),
+            _ => initial_time,
+        };
         let state = node::State::new(node_config, &consensus_consts, initial_time);
 
         Ok(Node::new(self.rng_seed, state, service, None))
@@ -391,31 +782,3 @@ fn default_peers() -> Vec<P2pConnectionOutgoingInitOpts> {
         .map(|s| s.parse().unwrap())
         .collect()
 }
-
-fn peers_from_reader(
-    peers: &mut Vec<P2pConnectionOutgoingInitOpts>,
-    read: impl Read,
-) -> anyhow::Result<()> {
-    let reader = BufReader::new(read);
-    for line in reader.lines() {
-        let line = line.context("reading line")?;
-        let trimmed = line.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-        match trimmed.parse::<P2pConnectionOutgoingInitOpts>() {
-            Ok(opts) => {
-                if let Some(opts) = opts.with_host_resolved() {
-                    peers.push(opts);
-                } else {
-                    mina_core::warn!(
-                        "Peer address name resolution failed, skipping: {:?}",
-                        trimmed
-                    );
-                }
-            }
-            Err(e) => mina_core::warn!("Peer address parse error: {:?}", e),
-        }
-    }
-    Ok(())
-}
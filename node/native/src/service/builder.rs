@@ -1,15 +1,24 @@
-use ledger::proofs::provers::BlockProver;
+use std::{path::PathBuf, time::Duration};
+
+use ledger::{ondisk::IoFaults, proofs::provers::BlockProver};
 pub use mina_node_common::NodeServiceCommonBuildError;
 use mina_node_common::{
-    archive::config::ArchiveStorageOptions, p2p::TaskSpawner, rpc::RpcSender, EventSender,
-    NodeServiceCommonBuilder,
+    archive::config::ArchiveStorageOptions, p2p::TaskSpawner, rpc::RpcSender, EventQueueConfig,
+    EventSender, NodeServiceCommonBuilder, SnarkVerifyPoolsConfig, TransitionFrontierEventCallback,
 };
 use node::{
-    account::AccountSecretKey, core::thread, p2p::identity::SecretKey as P2pSecretKey,
+    account::AccountSecretKey,
+    core::{thread, TimeSource},
+    p2p::{
+        connection::outgoing::P2pConnectionOutgoingInitOpts, identity::SecretKey as P2pSecretKey,
+    },
     service::Recorder,
 };
 
-use crate::{http_server, NodeService, P2pTaskSpawner};
+use crate::{
+    http_server::{self, HttpServerOptions},
+    NodeService, P2pTaskSpawner,
+};
 
 pub struct NodeServiceBuilder {
     common: NodeServiceCommonBuilder,
@@ -45,6 +54,11 @@ impl NodeServiceBuilder {
         self
     }
 
+    pub fn ledger_init_ondisk(&mut self, directory: PathBuf, io_faults: IoFaults) -> &mut Self {
+        self.common.ledger_init_ondisk(directory, io_faults);
+        self
+    }
+
     pub fn block_producer_init(
         &mut self,
         keypair: AccountSecretKey,
@@ -54,6 +68,16 @@ impl NodeServiceBuilder {
         self
     }
 
+    pub fn block_producer_dev_local_init(&mut self, keypair: AccountSecretKey) -> &mut Self {
+        self.common.block_producer_dev_local_init(keypair);
+        self
+    }
+
+    pub fn snark_work_dummy_proofs(&mut self) -> &mut Self {
+        self.common.snark_work_dummy_proofs();
+        self
+    }
+
     pub fn archive_init(&mut self, options: ArchiveStorageOptions, work_dir: String) -> &mut Self {
         self.common.archive_init(options, work_dir);
         self
@@ -73,17 +97,75 @@ impl NodeServiceBuilder {
         self
     }
 
+    pub fn p2p_previous_sec_keys(&mut self, keys: Vec<P2pSecretKey>) -> &mut Self {
+        self.common.p2p_previous_sec_keys(keys);
+        self
+    }
+
+    pub fn peer_list_init(
+        &mut self,
+        url: reqwest::Url,
+        interval: Option<Duration>,
+        peers: Vec<P2pConnectionOutgoingInitOpts>,
+    ) -> &mut Self {
+        self.common.peer_list_init(url, interval, peers);
+        self
+    }
+
     pub fn gather_stats(&mut self) -> &mut Self {
         self.common.gather_stats();
         self
     }
 
+    pub fn sync_history(&mut self, path: PathBuf) -> &mut Self {
+        self.common.sync_history(path);
+        self
+    }
+
+    pub fn zkapp_actions_events(&mut self, path: PathBuf) -> &mut Self {
+        self.common.zkapp_actions_events(path);
+        self
+    }
+
+    pub fn event_queue_config(&mut self, config: EventQueueConfig) -> &mut Self {
+        self.common.event_queue_config(config);
+        self
+    }
+
+    pub fn time_source(&mut self, time_source: TimeSource) -> &mut Self {
+        self.common.time_source(time_source);
+        self
+    }
+
+    pub fn snark_verify_pools_config(&mut self, config: SnarkVerifyPoolsConfig) -> &mut Self {
+        self.common.snark_verify_pools_config(config);
+        self
+    }
+
+    pub fn transition_frontier_event_hook(
+        &mut self,
+        callback: TransitionFrontierEventCallback,
+    ) -> &mut Self {
+        self.common.transition_frontier_event_hook(callback);
+        self
+    }
+
+    pub fn snarker_work_token(&mut self, token: String) -> &mut Self {
+        self.common.snarker_work_token(token);
+        self
+    }
+
+    pub fn bootstrap_token(&mut self, token: String) -> &mut Self {
+        self.common.bootstrap_token(token);
+        self
+    }
+
     pub fn record(&mut self, recorder: Recorder) -> &mut Self {
         self.recorder = recorder;
         self
     }
 
-    pub fn http_server_init(&mut self, port: u16) -> &mut Self {
+    pub fn http_server_init(&mut self, port: u16, options: HttpServerOptions) -> &mut Self {
         if let Some(cur_port) = self.http_server_port {
             panic!("trying to start http server on port `{port}`, when it's already running on port `{cur_port}`");
         }
@@ -95,7 +177,7 @@ impl NodeServiceBuilder {
             .unwrap();
         thread::Builder::new()
             .name("mina_http_server".to_owned())
-            .spawn(move || runtime.block_on(http_server::run(port, rpc_sender)))
+            .spawn(move || runtime.block_on(http_server::run(port, rpc_sender, options)))
             .unwrap();
         self
     }
@@ -1,13 +1,6 @@
-use std::io;
-use std::mem::size_of;
-use std::path::Path;
-use std::process::Stdio;
-use std::sync::Arc;
-
-use mina_p2p_messages::v2::{
-    CurrencyFeeStableV1, NonZeroCurvePoint, SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
-    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0, TransactionSnarkWorkTStableV2Proofs,
-};
+use std::{io, mem::size_of, path::Path, process::Stdio, sync::Arc};
+
+use mina_node_common::EventSender;
 use mina_p2p_messages::{
     binprot::{
         self,
@@ -15,17 +8,24 @@ use mina_p2p_messages::{
         BinProtRead, BinProtWrite,
     },
     string::CharString,
+    v2::{
+        CurrencyFeeStableV1, NonZeroCurvePoint, SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0, TransactionSnarkWorkTStableV2Proofs,
+    },
 };
 
-use node::core::channels::{mpsc, oneshot};
-use node::event_source::Event;
-use node::external_snark_worker::{
-    ExternalSnarkWorkerError, ExternalSnarkWorkerEvent, ExternalSnarkWorkerService,
-    ExternalSnarkWorkerWorkError, SnarkWorkSpec,
+use node::{
+    core::channels::{mpsc, oneshot},
+    external_snark_worker::{
+        ExternalSnarkWorkerError, ExternalSnarkWorkerEvent, ExternalSnarkWorkerService,
+        ExternalSnarkWorkerWorkError, SnarkWorkSpec,
+    },
 };
 
-use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
-use tokio::process::Command;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    process::Command,
+};
 
 use super::NodeService;
 
@@ -203,7 +203,7 @@ impl ExternalSnarkWorkerFacade {
     fn start(
         public_key: NonZeroCurvePoint,
         fee: CurrencyFeeStableV1,
-        event_sender: mpsc::UnboundedSender<Event>,
+        event_sender: EventSender,
     ) -> Result<Self, SnarkerError> {
         let path = std::env::var_os("MINA_EXE_PATH")
             .or_else(|| {
@@ -444,24 +444,30 @@ impl ExternalSnarkWorkerService for NodeService {
 mod tests {
     use std::time::Duration;
 
-    use mina_p2p_messages::binprot::BinProtRead;
-    use mina_p2p_messages::v2::{
-        CurrencyFeeStableV1, NonZeroCurvePoint, SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
-        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0,
+    use mina_node_common::{event_channel, tracing};
+    use mina_p2p_messages::{
+        binprot::BinProtRead,
+        v2::{
+            CurrencyFeeStableV1, NonZeroCurvePoint,
+            SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
+            SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0,
+        },
     };
-    use node::core::channels::mpsc;
-    use node::core::log::inner::Level;
     use node::{
+        core::log::inner::Level,
         event_source::Event,
         external_snark_worker::{ExternalSnarkWorkerEvent, SnarkWorkSpec},
     };
-    use mina_node_common::tracing;
 
     use super::ExternalSnarkWorkerFacade;
 
     macro_rules! expect_event {
         ($source:expr, $event:pat) => {
-            let result = $source.recv().await.expect("failed to receive an event");
+            $source
+                .wait_for_events()
+                .await
+                .expect("failed to receive an event");
+            let result = $source.try_next().expect("failed to receive an event");
             let Event::ExternalSnarkWorker(result) = result else {
                 panic!("unexpected event kind");
             };
@@ -473,7 +479,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_kill() {
-        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = event_channel(Default::default());
         let cmd_sender = ExternalSnarkWorkerFacade::start(
             NonZeroCurvePoint::default(),
             CurrencyFeeStableV1(
@@ -513,7 +519,7 @@ mod tests {
         let mut r = DATA;
         let (public_key, fee, instances) = read_input(&mut r);
 
-        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = event_channel(Default::default());
         let mut cmd_sender = ExternalSnarkWorkerFacade::start(public_key, fee, event_tx).unwrap();
 
         expect_event!(event_rx, ExternalSnarkWorkerEvent::Started);
@@ -531,7 +537,7 @@ mod tests {
         let mut r = DATA;
         let (public_key, fee, instances) = read_input(&mut r);
 
-        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = event_channel(Default::default());
         let mut cmd_sender = ExternalSnarkWorkerFacade::start(public_key, fee, event_tx).unwrap();
 
         expect_event!(event_rx, ExternalSnarkWorkerEvent::Started);
@@ -561,7 +567,7 @@ mod tests {
         let mut r = DATA;
         let (public_key, fee, instances) = read_input(&mut r);
 
-        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = event_channel(Default::default());
         let mut cmd_sender = ExternalSnarkWorkerFacade::start(public_key, fee, event_tx).unwrap();
 
         expect_event!(event_rx, ExternalSnarkWorkerEvent::Started);
@@ -0,0 +1,347 @@
+//! GraphQL parity tests against the OCaml node.
+//!
+//! Each directory in `fixtures/` is a small recorded chain, with the
+//! responses of the OCaml node to a set of queries at the tip of that chain:
+//!
+//! ```text
+//! fixtures/<name>/
+//!     fixture.json           {"network": "mainnet"}
+//!     config.json            daemon config of the chain, devnet if missing
+//!     blocks/*.json          precomputed blocks, named <network>-<height>-<hash>.json,
+//!                            none for a fixture of the genesis state
+//!     queries/<query>.graphql
+//!     queries/<query>.variables.json   optional
+//!     golden/<query>.json    response of the OCaml node
+//! ```
+//!
+//! The node is booted without networking, the blocks are injected over RPC,
+//! and each query must return a response identical to the golden one, once
+//! both are normalized to pretty printed JSON with sorted keys.
+//!
+//! Golden responses are recorded by pointing `MINA_GRAPHQL_RECORD_URL` to
+//! the GraphQL endpoint of an OCaml node synced to the last block of the
+//! fixture, which rewrites the `golden/` directory of every fixture. The
+//! queries of a genesis fixture must not depend on the best tip, so that
+//! any node of the network can record them.
+//!
+//! Missing fixtures or golden responses fail the test, so that the parity
+//! check can't be skipped silently. No fixture has been recorded from an
+//! OCaml node yet, so the test is ignored until the first one is committed.
+
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use juniper::http::GraphQLRequest;
+use mina_node_native::{graphql, rpc::RpcSender, NodeBuilder};
+use mina_p2p_messages::v2;
+use node::{
+    rpc::{RpcBlockInjectError, RpcBlockInjectResponse, RpcRequest, RpcStatusGetResponse},
+    transition_frontier::genesis::GenesisConfig,
+};
+use serde::Deserialize;
+
+const RECORD_URL_ENV: &str = "MINA_GRAPHQL_RECORD_URL";
+const BLOCK_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Deserialize)]
+struct FixtureConfig {
+    network: String,
+}
+
+struct Fixture {
+    name: String,
+    dir: PathBuf,
+    config: FixtureConfig,
+}
+
+struct FixtureQuery {
+    name: String,
+    query: String,
+    variables: Option<juniper::InputValue>,
+}
+
+#[test]
+#[ignore = "no fixture recorded from an OCaml node yet"]
+fn graphql_fixtures() {
+    let fixtures = fixtures().unwrap();
+    assert!(
+        !fixtures.is_empty(),
+        "no GraphQL fixtures found in tests/graphql/fixtures"
+    );
+
+    if let Ok(url) = std::env::var(RECORD_URL_ENV) {
+        for fixture in &fixtures {
+            record(fixture, &url).unwrap();
+        }
+        return;
+    }
+
+    // The network config and the work dir are global, so all fixtures
+    // must belong to the same network.
+    let network = &fixtures[0].config.network;
+    if let Some(fixture) = fixtures.iter().find(|f| &f.config.network != network) {
+        panic!(
+            "fixture {} is on {}, while other fixtures are on {network}",
+            fixture.name, fixture.config.network
+        );
+    }
+    mina_core::NetworkConfig::init(network).unwrap();
+    mina_core::set_work_dir(Path::new(env!("CARGO_TARGET_TMPDIR")).join("graphql-fixtures"));
+
+    let failures = fixtures
+        .into_iter()
+        .flat_map(|fixture| {
+            // The node needs a bigger stack than the test threads have.
+            std::thread::Builder::new()
+                .stack_size(64 * 1024 * 1024)
+                .spawn(move || run(&fixture).unwrap())
+                .unwrap()
+                .join()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        failures.is_empty(),
+        "responses differ from the OCaml node:\n{}",
+        failures.join("\n")
+    );
+}
+
+/// Boots a node from the blocks of the fixture and runs its queries,
+/// returning the queries whose responses differ from the golden ones.
+fn run(fixture: &Fixture) -> anyhow::Result<Vec<String>> {
+    let (daemon_conf, genesis_conf) = match File::open(fixture.dir.join("config.json")) {
+        Ok(reader) => {
+            let config: node::daemon_json::DaemonJson = serde_json::from_reader(reader)
+                .with_context(|| format!("config of fixture {}", fixture.name))?;
+            (
+                config
+                    .daemon
+                    .clone()
+                    .unwrap_or(node::daemon_json::Daemon::DEFAULT),
+                Arc::new(GenesisConfig::DaemonJson(Box::new(config))),
+            )
+        }
+        Err(_) => (
+            node::daemon_json::Daemon::DEFAULT,
+            node::config::DEVNET_CONFIG.clone(),
+        ),
+    };
+
+    let mut node_builder = NodeBuilder::new(None, daemon_conf, genesis_conf);
+    node_builder
+        .p2p_seed_node()
+        .p2p_no_discovery()
+        .p2p_max_peers(0)
+        .allow_historical_blocks();
+    let mut node = node_builder.build().context("node build failed!")?;
+    let rpc = node.rpc();
+
+    let blocks = fixture_blocks(&fixture.dir.join("blocks"))?;
+    let queries = fixture_queries(&fixture.dir)?;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        tokio::select! {
+            _ = node.run_forever() => unreachable!(),
+            res = check(fixture, &rpc, blocks, &queries) => res,
+        }
+    })
+}
+
+async fn check(
+    fixture: &Fixture,
+    rpc: &RpcSender,
+    blocks: Vec<(u32, v2::MinaBlockBlockStableV2)>,
+    queries: &[FixtureQuery],
+) -> anyhow::Result<Vec<String>> {
+    wait_for_best_tip(rpc, 0).await?;
+    for (height, block) in blocks {
+        let res: Option<RpcBlockInjectResponse> = rpc
+            .oneshot_request(RpcRequest::BlockInject(Arc::new(block)))
+            .await;
+        match res.context("response channel dropped")? {
+            Ok(_) | Err(RpcBlockInjectError::AlreadyKnown) => {}
+            Err(err) => anyhow::bail!("fixture {} block {height} rejected: {err}", fixture.name),
+        }
+        wait_for_best_tip(rpc, height).await?;
+    }
+
+    let schema = graphql::schema();
+    let context = graphql::Context::new(rpc.clone());
+    let actual_dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("graphql-fixtures")
+        .join(&fixture.name);
+    fs::create_dir_all(&actual_dir)?;
+
+    let mut failures = Vec::new();
+    for query in queries {
+        let request = GraphQLRequest::new(query.query.clone(), None, query.variables.clone());
+        let response = request.execute(&schema, &context).await;
+        let actual = normalize(&serde_json::to_vec(&response)?)?;
+
+        let golden_path = fixture
+            .dir
+            .join("golden")
+            .join(format!("{}.json", query.name));
+        let Ok(golden) = fs::read(&golden_path) else {
+            failures.push(format!(
+                "{}/{}: no golden response at {golden_path:?}, record it with {RECORD_URL_ENV}",
+                fixture.name, query.name
+            ));
+            continue;
+        };
+        if normalize(&golden)? != actual {
+            let actual_path = actual_dir.join(format!("{}.json", query.name));
+            fs::write(&actual_path, &actual)?;
+            failures.push(format!(
+                "{}/{}: see {golden_path:?} and {actual_path:?}",
+                fixture.name, query.name
+            ));
+        }
+    }
+    Ok(failures)
+}
+
+async fn wait_for_best_tip(rpc: &RpcSender, height: u32) -> anyhow::Result<()> {
+    let deadline = tokio::time::Instant::now() + BLOCK_TIMEOUT;
+    loop {
+        let status: Option<RpcStatusGetResponse> = rpc.oneshot_request(RpcRequest::StatusGet).await;
+        let reached = status
+            .flatten()
+            .and_then(|status| status.transition_frontier.best_tip)
+            .is_some_and(|best_tip| best_tip.height >= height);
+        if reached {
+            return Ok(());
+        }
+        if tokio::time::Instant::now() >= deadline {
+            anyhow::bail!("timed out waiting for the best tip to reach height {height}");
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+/// Fetches the golden responses of the fixture from an OCaml node.
+fn record(fixture: &Fixture, url: &str) -> anyhow::Result<()> {
+    let golden_dir = fixture.dir.join("golden");
+    fs::create_dir_all(&golden_dir)?;
+    let client = reqwest::blocking::Client::new();
+    for query in fixture_queries(&fixture.dir)? {
+        let response = client
+            .post(url)
+            .json(&GraphQLRequest::<juniper::DefaultScalarValue>::new(
+                query.query,
+                None,
+                query.variables,
+            ))
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.bytes())
+            .with_context(|| format!("{}/{}", fixture.name, query.name))?;
+        let golden_path = golden_dir.join(format!("{}.json", query.name));
+        fs::write(&golden_path, normalize(&response)?)?;
+        eprintln!("recorded {golden_path:?}");
+    }
+    Ok(())
+}
+
+/// Pretty printed JSON with sorted keys, so that responses can be compared
+/// byte for byte regardless of how each node formats them.
+fn normalize(json: &[u8]) -> anyhow::Result<String> {
+    let value: serde_json::Value = serde_json::from_slice(json)?;
+    Ok(serde_json::to_string_pretty(&value)? + "\n")
+}
+
+fn fixtures() -> anyhow::Result<Vec<Fixture>> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/graphql/fixtures");
+    let mut fixtures = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("reading {dir:?}"))? {
+        let dir = entry?.path();
+        let config_path = dir.join("fixture.json");
+        if !config_path.is_file() {
+            continue;
+        }
+        let config = serde_json::from_slice(&fs::read(&config_path)?)
+            .with_context(|| format!("parsing {config_path:?}"))?;
+        let name = dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .context("fixture name")?
+            .to_owned();
+        fixtures.push(Fixture { name, dir, config });
+    }
+    fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(fixtures)
+}
+
+/// Blocks of the fixture, sorted by height. A genesis fixture has none.
+fn fixture_blocks(dir: &Path) -> anyhow::Result<Vec<(u32, v2::MinaBlockBlockStableV2)>> {
+    let mut blocks = Vec::new();
+    if !dir.exists() {
+        return Ok(blocks);
+    }
+    for entry in fs::read_dir(dir).with_context(|| format!("reading {dir:?}"))? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let contents = fs::read(&path)?;
+        // Blocks dumped by the OCaml node are wrapped with their version.
+        let block: v2::MinaBlockBlockStableV2 =
+            serde_json::from_slice::<v2::PrecomputedBlockData>(&contents)
+                .map(|block| block.data)
+                .or_else(|_| serde_json::from_slice::<v2::PrecomputedBlock>(&contents))
+                .with_context(|| format!("parsing precomputed block {path:?}"))?
+                .into();
+        let height = block
+            .header
+            .protocol_state
+            .body
+            .consensus_state
+            .blockchain_length
+            .as_u32();
+        blocks.push((height, block));
+    }
+    blocks.sort_by_key(|(height, _)| *height);
+    Ok(blocks)
+}
+
+fn fixture_queries(dir: &Path) -> anyhow::Result<Vec<FixtureQuery>> {
+    let dir = dir.join("queries");
+    let mut queries = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("reading {dir:?}"))? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "graphql") {
+            continue;
+        }
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .context("query name")?
+            .to_owned();
+        let variables_path = dir.join(format!("{name}.variables.json"));
+        let variables = match fs::read(&variables_path) {
+            Ok(contents) => Some(
+                serde_json::from_slice(&contents)
+                    .with_context(|| format!("parsing {variables_path:?}"))?,
+            ),
+            Err(_) => None,
+        };
+        let query = fs::read_to_string(&path)?;
+        queries.push(FixtureQuery {
+            name,
+            query,
+            variables,
+        });
+    }
+    queries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(queries)
+}
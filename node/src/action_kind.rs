@@ -40,6 +40,7 @@ use crate::{
             streaming_rpc::P2pChannelsStreamingRpcAction,
             transaction::P2pChannelsTransactionAction,
             P2pChannelsAction, P2pChannelsEffectfulAction, P2pChannelsMessageReceivedAction,
+            P2pChannelsMessageShedAction,
         },
         connection::{
             incoming::P2pConnectionIncomingAction,
@@ -50,6 +51,7 @@ use crate::{
         },
         disconnection::P2pDisconnectionAction,
         disconnection_effectful::P2pDisconnectionEffectfulAction,
+        gossip::P2pGossipAction,
         identify::P2pIdentifyAction,
         network::{
             identify::{
@@ -84,6 +86,7 @@ use crate::{
         block_verify_effectful::SnarkBlockVerifyEffectfulAction,
         user_command_verify::SnarkUserCommandVerifyAction,
         user_command_verify_effectful::SnarkUserCommandVerifyEffectfulAction,
+        verify_dry_run_effectful::SnarkVerifyDryRunEffectfulAction,
         work_verify::SnarkWorkVerifyAction, work_verify_effectful::SnarkWorkVerifyEffectfulAction,
         SnarkAction,
     },
@@ -135,6 +138,8 @@ pub enum ActionKind {
     BlockProducerBlockProvePending,
     BlockProducerBlockProveSuccess,
     BlockProducerBlockUnprovenBuild,
+    BlockProducerCoinbaseReceiverCheckInit,
+    BlockProducerCoinbaseReceiverCheckSuccess,
     BlockProducerStagedLedgerDiffCreateInit,
     BlockProducerStagedLedgerDiffCreatePending,
     BlockProducerStagedLedgerDiffCreateSuccess,
@@ -169,6 +174,7 @@ pub enum ActionKind {
     BlockProducerVrfEvaluatorProcessSlotEvaluationSuccess,
     BlockProducerVrfEvaluatorSelectInitialSlot,
     BlockProducerVrfEvaluatorWaitForNextEvaluation,
+    BlockProducerVrfEvaluatorEffectfulDelegatorTableStats,
     BlockProducerVrfEvaluatorEffectfulEvaluateSlot,
     BlockProducerVrfEvaluatorEffectfulInitializeStats,
     BlockProducerVrfEvaluatorEffectfulSlotEvaluated,
@@ -178,6 +184,16 @@ pub enum ActionKind {
     EventSourceWaitForEvents,
     EventSourceWaitTimeout,
     ExternalSnarkWorkerCancelWork,
+    ExternalSnarkWorkerCoordinatorJobSpecFetchError,
+    ExternalSnarkWorkerCoordinatorJobSpecFetchInit,
+    ExternalSnarkWorkerCoordinatorJobSpecFetchSuccess,
+    ExternalSnarkWorkerCoordinatorJobsFetchError,
+    ExternalSnarkWorkerCoordinatorJobsFetchInit,
+    ExternalSnarkWorkerCoordinatorJobsFetchPending,
+    ExternalSnarkWorkerCoordinatorJobsFetchSuccess,
+    ExternalSnarkWorkerCoordinatorSubmit,
+    ExternalSnarkWorkerCoordinatorSubmitError,
+    ExternalSnarkWorkerCoordinatorSubmitSuccess,
     ExternalSnarkWorkerError,
     ExternalSnarkWorkerKill,
     ExternalSnarkWorkerKilled,
@@ -191,16 +207,21 @@ pub enum ActionKind {
     ExternalSnarkWorkerWorkResult,
     ExternalSnarkWorkerWorkTimeout,
     ExternalSnarkWorkerEffectfulCancelWork,
+    ExternalSnarkWorkerEffectfulCoordinatorJobSpecFetch,
+    ExternalSnarkWorkerEffectfulCoordinatorJobsFetch,
+    ExternalSnarkWorkerEffectfulCoordinatorSubmit,
     ExternalSnarkWorkerEffectfulKill,
     ExternalSnarkWorkerEffectfulStart,
     ExternalSnarkWorkerEffectfulSubmitWork,
     LedgerEffectfulReadInit,
+    LedgerEffectfulReadRetry,
     LedgerEffectfulWriteInit,
     LedgerReadFindTodos,
     LedgerReadInit,
     LedgerReadPending,
     LedgerReadPrune,
     LedgerReadSuccess,
+    LedgerReadTimeout,
     LedgerWriteInit,
     LedgerWritePending,
     LedgerWriteSuccess,
@@ -209,6 +230,7 @@ pub enum ActionKind {
     P2pCallbacksP2pChannelsRpcResponseReceived,
     P2pCallbacksP2pChannelsRpcTimeout,
     P2pCallbacksP2pChannelsStreamingRpcReady,
+    P2pCallbacksP2pChannelsStreamingRpcRequestReceived,
     P2pCallbacksP2pChannelsStreamingRpcResponseReceived,
     P2pCallbacksP2pChannelsStreamingRpcTimeout,
     P2pCallbacksP2pDisconnection,
@@ -228,6 +250,7 @@ pub enum ActionKind {
     P2pChannelsEffectfulSignalingExchangeAnswerEncryptAndSend,
     P2pChannelsEffectfulSignalingExchangeOfferDecrypt,
     P2pChannelsMessageReceived,
+    P2pChannelsMessageShed,
     P2pChannelsRpcInit,
     P2pChannelsRpcPending,
     P2pChannelsRpcReady,
@@ -264,6 +287,7 @@ pub enum ActionKind {
     P2pChannelsSignalingExchangeReady,
     P2pChannelsSignalingExchangeRequestReceived,
     P2pChannelsSignalingExchangeRequestSend,
+    P2pChannelsSnarkBatchReceived,
     P2pChannelsSnarkInit,
     P2pChannelsSnarkLibp2pBroadcast,
     P2pChannelsSnarkLibp2pReceived,
@@ -326,10 +350,14 @@ pub enum ActionKind {
     P2pConnectionOutgoingAnswerRecvError,
     P2pConnectionOutgoingAnswerRecvPending,
     P2pConnectionOutgoingAnswerRecvSuccess,
+    P2pConnectionOutgoingDnsaddrResolveError,
+    P2pConnectionOutgoingDnsaddrResolveInit,
+    P2pConnectionOutgoingDnsaddrResolveSuccess,
     P2pConnectionOutgoingError,
     P2pConnectionOutgoingFinalizeError,
     P2pConnectionOutgoingFinalizePending,
     P2pConnectionOutgoingFinalizeSuccess,
+    P2pConnectionOutgoingHostResolveSuccess,
     P2pConnectionOutgoingInit,
     P2pConnectionOutgoingOfferReady,
     P2pConnectionOutgoingOfferSdpCreateError,
@@ -346,6 +374,8 @@ pub enum ActionKind {
     P2pConnectionOutgoingEffectfulInit,
     P2pConnectionOutgoingEffectfulOfferSend,
     P2pConnectionOutgoingEffectfulRandomInit,
+    P2pConnectionOutgoingEffectfulResolveDnsaddr,
+    P2pConnectionOutgoingEffectfulResolveHost,
     P2pDisconnectionFailedCleanup,
     P2pDisconnectionFinish,
     P2pDisconnectionInit,
@@ -353,6 +383,8 @@ pub enum ActionKind {
     P2pDisconnectionRandomTry,
     P2pDisconnectionEffectfulInit,
     P2pEffectfulInitialize,
+    P2pGossipPause,
+    P2pGossipResume,
     P2pIdentifyNewRequest,
     P2pIdentifyUpdatePeerInformation,
     P2pInitializeInitialize,
@@ -463,6 +495,7 @@ pub enum ActionKind {
     P2pNetworkSchedulerEffectfulIncomingConnectionIsReady,
     P2pNetworkSchedulerEffectfulIncomingDataIsReady,
     P2pNetworkSchedulerEffectfulIncomingDidAccept,
+    P2pNetworkSchedulerEffectfulIncomingDidRefuse,
     P2pNetworkSchedulerEffectfulInterfaceDetected,
     P2pNetworkSchedulerEffectfulNoiseSelectDone,
     P2pNetworkSchedulerEffectfulOutgoingConnect,
@@ -484,32 +517,56 @@ pub enum ActionKind {
     P2pNetworkYamuxOutgoingFrame,
     P2pNetworkYamuxPingStream,
     P2pPeerBestTipUpdate,
+    P2pPeerConnectionStatsUpdate,
     P2pPeerDiscovered,
+    P2pPeerListed,
     P2pPeerReady,
     P2pPeerRemove,
+    P2pPeerUnlisted,
     RpcActionStatsGet,
+    RpcArchiveUploadStatsGet,
     RpcBestChain,
+    RpcBestChainPageGet,
     RpcBlockGet,
+    RpcBlockInject,
+    RpcBlockInjectPending,
+    RpcBlockInjectPrevalidated,
     RpcBlockProducerStatsGet,
+    RpcBlockSummaryGet,
     RpcConsensusConstantsGet,
+    RpcConsensusForkDecisionsGet,
     RpcConsensusTimeGet,
     RpcDiscoveryBoostrapStats,
     RpcDiscoveryRoutingTable,
+    RpcEnabledFeaturesGet,
     RpcFinish,
     RpcGenesisBlock,
     RpcGlobalStateGet,
+    RpcGossipPause,
     RpcHealthCheck,
     RpcHeartbeatGet,
     RpcLedgerAccountDelegatorsGetInit,
     RpcLedgerAccountDelegatorsGetPending,
     RpcLedgerAccountDelegatorsGetSuccess,
+    RpcLedgerAccountsAtBlockGetInit,
+    RpcLedgerAccountsAtBlockGetPending,
+    RpcLedgerAccountsAtBlockGetSuccess,
     RpcLedgerAccountsGetInit,
     RpcLedgerAccountsGetPending,
     RpcLedgerAccountsGetSuccess,
+    RpcLedgerAccountsPageGetInit,
+    RpcLedgerAccountsPageGetPending,
+    RpcLedgerAccountsPageGetSuccess,
+    RpcLedgerReadError,
+    RpcLedgerSnapshotGetInit,
+    RpcLedgerSnapshotGetPending,
+    RpcLedgerSnapshotGetSuccess,
     RpcLedgerStatusGetInit,
     RpcLedgerStatusGetPending,
     RpcLedgerStatusGetSuccess,
+    RpcLocallyGeneratedCommandsGet,
     RpcMessageProgressGet,
+    RpcNodeOverviewGet,
     RpcP2pConnectionIncomingAnswerReady,
     RpcP2pConnectionIncomingError,
     RpcP2pConnectionIncomingInit,
@@ -520,9 +577,18 @@ pub enum ActionKind {
     RpcP2pConnectionOutgoingInit,
     RpcP2pConnectionOutgoingPending,
     RpcP2pConnectionOutgoingSuccess,
+    RpcPeerContributionStatsGet,
+    RpcPeerListRefreshInit,
+    RpcPeerListRefreshSuccess,
     RpcPeersGet,
+    RpcPendingCoinbaseGetInit,
+    RpcPendingCoinbaseGetPending,
+    RpcPendingCoinbaseGetSuccess,
+    RpcPendingRequestsGet,
     RpcPooledUserCommands,
+    RpcPooledUserCommandsPageGet,
     RpcPooledZkappCommands,
+    RpcPooledZkappCommandsPageGet,
     RpcReadinessCheck,
     RpcScanStateSummaryGetInit,
     RpcScanStateSummaryGetPending,
@@ -532,67 +598,120 @@ pub enum ActionKind {
     RpcSnarkPoolCompletedJobsGet,
     RpcSnarkPoolJobGet,
     RpcSnarkPoolPendingJobsGet,
+    RpcSnarkStatsGet,
+    RpcSnarkVerifierDigestsGet,
+    RpcSnarkVerifyDryRunInit,
+    RpcSnarkVerifyDryRunSuccess,
+    RpcSnarkVerifyHistoryGet,
     RpcSnarkerConfigGet,
     RpcSnarkerJobCommit,
     RpcSnarkerJobSpec,
+    RpcSnarkerWorkSubmitInit,
+    RpcSnarkerWorkSubmitPending,
+    RpcSnarkerWorkSubmitSuccess,
     RpcSnarkerWorkersGet,
     RpcStatusGet,
+    RpcSyncDiagnosisGet,
+    RpcSyncDiagnosisSuccess,
+    RpcSyncHistoryGet,
     RpcSyncStatsGet,
+    RpcTokenSupplyGetInit,
+    RpcTokenSupplyGetPending,
+    RpcTokenSupplyGetSuccess,
     RpcTransactionInjectFailure,
     RpcTransactionInjectInit,
     RpcTransactionInjectPending,
     RpcTransactionInjectRejected,
     RpcTransactionInjectSuccess,
     RpcTransactionPool,
+    RpcTransactionPoolSenderQueues,
     RpcTransactionStatusGet,
     RpcTransitionFrontierUserCommandsGet,
+    RpcZkappActionsEventsGetInit,
+    RpcZkappActionsEventsGetPending,
+    RpcZkappActionsEventsGetSuccess,
+    RpcZkappEventsPageGet,
     RpcEffectfulActionStatsGet,
+    RpcEffectfulArchiveUploadStatsGet,
     RpcEffectfulBestChain,
+    RpcEffectfulBestChainPageGet,
     RpcEffectfulBlockGet,
+    RpcEffectfulBlockInject,
     RpcEffectfulBlockProducerStatsGet,
+    RpcEffectfulBlockSummaryGet,
     RpcEffectfulConsensusConstantsGet,
+    RpcEffectfulConsensusForkDecisionsGet,
     RpcEffectfulConsensusTimeGet,
     RpcEffectfulDiscoveryBoostrapStats,
     RpcEffectfulDiscoveryRoutingTable,
+    RpcEffectfulDrop,
+    RpcEffectfulEnabledFeaturesGet,
     RpcEffectfulGenesisBlock,
     RpcEffectfulGlobalStateGet,
+    RpcEffectfulGossipPause,
     RpcEffectfulHealthCheck,
     RpcEffectfulHeartbeatGet,
     RpcEffectfulLedgerAccountDelegatorsGetSuccess,
+    RpcEffectfulLedgerAccountsAtBlockGetSuccess,
     RpcEffectfulLedgerAccountsGetSuccess,
+    RpcEffectfulLedgerAccountsPageGetSuccess,
+    RpcEffectfulLedgerSnapshotGetSuccess,
     RpcEffectfulLedgerStatusGetSuccess,
+    RpcEffectfulLocallyGeneratedCommandsGet,
     RpcEffectfulMessageProgressGet,
+    RpcEffectfulNodeOverviewGet,
     RpcEffectfulP2pConnectionIncomingError,
     RpcEffectfulP2pConnectionIncomingRespond,
     RpcEffectfulP2pConnectionIncomingSuccess,
     RpcEffectfulP2pConnectionOutgoingError,
     RpcEffectfulP2pConnectionOutgoingSuccess,
+    RpcEffectfulPeerContributionStatsGet,
+    RpcEffectfulPeerListRefreshInit,
+    RpcEffectfulPeerListRefreshSuccess,
     RpcEffectfulPeersGet,
+    RpcEffectfulPendingCoinbaseGetSuccess,
+    RpcEffectfulPendingRequestsGet,
     RpcEffectfulPooledUserCommands,
+    RpcEffectfulPooledUserCommandsPageGet,
     RpcEffectfulPooledZkappCommands,
+    RpcEffectfulPooledZkappCommandsPageGet,
     RpcEffectfulReadinessCheck,
     RpcEffectfulScanStateSummaryGetSuccess,
     RpcEffectfulSnarkPoolAvailableJobsGet,
     RpcEffectfulSnarkPoolCompletedJobsGet,
     RpcEffectfulSnarkPoolJobGet,
     RpcEffectfulSnarkPoolPendingJobsGet,
+    RpcEffectfulSnarkStatsGet,
+    RpcEffectfulSnarkVerifierDigestsGet,
+    RpcEffectfulSnarkVerifyDryRunSuccess,
+    RpcEffectfulSnarkVerifyHistoryGet,
     RpcEffectfulSnarkerConfigGet,
     RpcEffectfulSnarkerJobCommit,
     RpcEffectfulSnarkerJobSpec,
+    RpcEffectfulSnarkerWorkSubmitSuccess,
     RpcEffectfulSnarkerWorkersGet,
     RpcEffectfulStatusGet,
+    RpcEffectfulSyncDiagnosisGet,
+    RpcEffectfulSyncHistoryGet,
     RpcEffectfulSyncStatsGet,
+    RpcEffectfulTokenSupplyGetSuccess,
     RpcEffectfulTransactionInjectFailure,
     RpcEffectfulTransactionInjectRejected,
     RpcEffectfulTransactionInjectSuccess,
     RpcEffectfulTransactionPool,
+    RpcEffectfulTransactionPoolSenderQueues,
     RpcEffectfulTransactionStatusGet,
     RpcEffectfulTransitionFrontierUserCommandsGet,
+    RpcEffectfulZkappActionsEventsGetSuccess,
+    RpcEffectfulZkappEventsPageGet,
     SnarkBlockVerifyError,
     SnarkBlockVerifyFinish,
     SnarkBlockVerifyInit,
+    SnarkBlockVerifyNext,
     SnarkBlockVerifyPending,
+    SnarkBlockVerifyPrioritiesUpdate,
     SnarkBlockVerifySuccess,
+    SnarkBlockVerifyTimeout,
     SnarkBlockVerifyEffectfulInit,
     SnarkPoolAutoCreateCommitment,
     SnarkPoolCheckTimeouts,
@@ -604,6 +723,9 @@ pub enum ActionKind {
     SnarkPoolP2pSend,
     SnarkPoolP2pSendAll,
     SnarkPoolWorkAdd,
+    SnarkPoolWorkSubmit,
+    SnarkPoolWorkSubmitVerifyError,
+    SnarkPoolWorkSubmitVerifySuccess,
     SnarkPoolCandidateInfoReceived,
     SnarkPoolCandidatePeerPrune,
     SnarkPoolCandidateWorkFetchAll,
@@ -621,12 +743,15 @@ pub enum ActionKind {
     SnarkUserCommandVerifyInit,
     SnarkUserCommandVerifyPending,
     SnarkUserCommandVerifySuccess,
+    SnarkUserCommandVerifyTimeout,
     SnarkUserCommandVerifyEffectfulInit,
+    SnarkVerifyDryRunEffectfulInit,
     SnarkWorkVerifyError,
     SnarkWorkVerifyFinish,
     SnarkWorkVerifyInit,
     SnarkWorkVerifyPending,
     SnarkWorkVerifySuccess,
+    SnarkWorkVerifyTimeout,
     SnarkWorkVerifyEffectfulInit,
     TransactionPoolApplyTransitionFrontierDiff,
     TransactionPoolApplyTransitionFrontierDiffWithAccounts,
@@ -635,13 +760,19 @@ pub enum ActionKind {
     TransactionPoolBestTipChanged,
     TransactionPoolBestTipChangedWithAccounts,
     TransactionPoolCollectTransactionsByFee,
+    TransactionPoolDiffApplied,
     TransactionPoolP2pSend,
     TransactionPoolP2pSendAll,
     TransactionPoolRebroadcast,
+    TransactionPoolRebroadcastLocallyGenerated,
+    TransactionPoolRemoveExpired,
+    TransactionPoolRemoveStale,
+    TransactionPoolSnarkVerifyError,
     TransactionPoolStartVerify,
     TransactionPoolStartVerifyWithAccounts,
     TransactionPoolVerifyError,
     TransactionPoolVerifySuccess,
+    TransactionPoolZkappRelayCheck,
     TransactionPoolCandidateFetchAll,
     TransactionPoolCandidateFetchError,
     TransactionPoolCandidateFetchInit,
@@ -657,6 +788,9 @@ pub enum ActionKind {
     TransactionPoolEffectfulFetchAccounts,
     TransitionFrontierGenesisInject,
     TransitionFrontierGenesisProvenInject,
+    TransitionFrontierLedgerHashesCheckInit,
+    TransitionFrontierLedgerHashesCheckMerkleRootsSuccess,
+    TransitionFrontierLedgerHashesCheckPeersSuccess,
     TransitionFrontierSyncFailed,
     TransitionFrontierSynced,
     TransitionFrontierCandidateBlockChainProofUpdate,
@@ -665,7 +799,9 @@ pub enum ActionKind {
     TransitionFrontierCandidateBlockReceived,
     TransitionFrontierCandidateBlockSnarkVerifyError,
     TransitionFrontierCandidateBlockSnarkVerifyPending,
+    TransitionFrontierCandidateBlockSnarkVerifyPrioritiesUpdate,
     TransitionFrontierCandidateBlockSnarkVerifySuccess,
+    TransitionFrontierCandidateForkDecision,
     TransitionFrontierCandidateP2pBestTipUpdate,
     TransitionFrontierCandidatePrune,
     TransitionFrontierCandidateTransitionFrontierSyncTargetUpdate,
@@ -679,6 +815,10 @@ pub enum ActionKind {
     TransitionFrontierGenesisEffectfulLedgerLoadInit,
     TransitionFrontierGenesisEffectfulProveInit,
     TransitionFrontierSyncBestTipUpdate,
+    TransitionFrontierSyncBlocksBucketFetchError,
+    TransitionFrontierSyncBlocksBucketFetchInit,
+    TransitionFrontierSyncBlocksBucketFetchSuccess,
+    TransitionFrontierSyncBlocksBucketQuery,
     TransitionFrontierSyncBlocksFetchSuccess,
     TransitionFrontierSyncBlocksNextApplyError,
     TransitionFrontierSyncBlocksNextApplyInit,
@@ -729,6 +869,10 @@ pub enum ActionKind {
     TransitionFrontierSyncLedgerSnarkedPeerQueryNumAccountsSuccess,
     TransitionFrontierSyncLedgerSnarkedPeersQuery,
     TransitionFrontierSyncLedgerSnarkedPending,
+    TransitionFrontierSyncLedgerSnarkedSnapshotAccepted,
+    TransitionFrontierSyncLedgerSnarkedSnapshotFetchError,
+    TransitionFrontierSyncLedgerSnarkedSnapshotFetchInit,
+    TransitionFrontierSyncLedgerSnarkedSnapshotReceived,
     TransitionFrontierSyncLedgerSnarkedSuccess,
     TransitionFrontierSyncLedgerStagedPartsFetchPending,
     TransitionFrontierSyncLedgerStagedPartsFetchSuccess,
@@ -738,6 +882,9 @@ pub enum ActionKind {
     TransitionFrontierSyncLedgerStagedPartsPeerFetchSuccess,
     TransitionFrontierSyncLedgerStagedPartsPeerInvalid,
     TransitionFrontierSyncLedgerStagedPartsPeerValid,
+    TransitionFrontierSyncLedgerStagedPartsSnapshotFetchError,
+    TransitionFrontierSyncLedgerStagedPartsSnapshotFetchInit,
+    TransitionFrontierSyncLedgerStagedPartsSnapshotFetchSuccess,
     TransitionFrontierSyncLedgerStagedReconstructEmpty,
     TransitionFrontierSyncLedgerStagedReconstructError,
     TransitionFrontierSyncLedgerStagedReconstructInit,
@@ -757,7 +904,7 @@ pub enum ActionKind {
 }
 
 impl ActionKind {
-    pub const COUNT: u16 = 628;
+    pub const COUNT: u16 = 772;
 }
 
 impl std::fmt::Display for ActionKind {
@@ -819,6 +966,7 @@ impl ActionKindGet for P2pAction {
             Self::Identify(a) => a.kind(),
             Self::Channels(a) => a.kind(),
             Self::Peer(a) => a.kind(),
+            Self::Gossip(a) => a.kind(),
             Self::Network(a) => a.kind(),
         }
     }
@@ -856,6 +1004,9 @@ impl ActionKindGet for P2pCallbacksAction {
             Self::P2pChannelsStreamingRpcResponseReceived { .. } => {
                 ActionKind::P2pCallbacksP2pChannelsStreamingRpcResponseReceived
             }
+            Self::P2pChannelsStreamingRpcRequestReceived { .. } => {
+                ActionKind::P2pCallbacksP2pChannelsStreamingRpcRequestReceived
+            }
             Self::P2pDisconnection { .. } => ActionKind::P2pCallbacksP2pDisconnection,
             Self::RpcRespondBestTip { .. } => ActionKind::P2pCallbacksRpcRespondBestTip,
             Self::P2pPubsubValidateMessage { .. } => {
@@ -879,6 +1030,7 @@ impl ActionKindGet for LedgerEffectfulAction {
         match self {
             Self::WriteInit { .. } => ActionKind::LedgerEffectfulWriteInit,
             Self::ReadInit { .. } => ActionKind::LedgerEffectfulReadInit,
+            Self::ReadRetry { .. } => ActionKind::LedgerEffectfulReadRetry,
         }
     }
 }
@@ -892,6 +1044,7 @@ impl ActionKindGet for SnarkAction {
             Self::WorkVerifyEffect(a) => a.kind(),
             Self::UserCommandVerify(a) => a.kind(),
             Self::UserCommandVerifyEffect(a) => a.kind(),
+            Self::VerifyDryRunEffect(a) => a.kind(),
         }
     }
 }
@@ -907,6 +1060,13 @@ impl ActionKindGet for TransitionFrontierAction {
             Self::GenesisProvenInject => ActionKind::TransitionFrontierGenesisProvenInject,
             Self::Synced { .. } => ActionKind::TransitionFrontierSynced,
             Self::SyncFailed { .. } => ActionKind::TransitionFrontierSyncFailed,
+            Self::LedgerHashesCheckInit => ActionKind::TransitionFrontierLedgerHashesCheckInit,
+            Self::LedgerHashesCheckPeersSuccess { .. } => {
+                ActionKind::TransitionFrontierLedgerHashesCheckPeersSuccess
+            }
+            Self::LedgerHashesCheckMerkleRootsSuccess { .. } => {
+                ActionKind::TransitionFrontierLedgerHashesCheckMerkleRootsSuccess
+            }
         }
     }
 }
@@ -921,6 +1081,9 @@ impl ActionKindGet for SnarkPoolAction {
             Self::CommitmentCreate { .. } => ActionKind::SnarkPoolCommitmentCreate,
             Self::CommitmentAdd { .. } => ActionKind::SnarkPoolCommitmentAdd,
             Self::WorkAdd { .. } => ActionKind::SnarkPoolWorkAdd,
+            Self::WorkSubmit { .. } => ActionKind::SnarkPoolWorkSubmit,
+            Self::WorkSubmitVerifySuccess { .. } => ActionKind::SnarkPoolWorkSubmitVerifySuccess,
+            Self::WorkSubmitVerifyError { .. } => ActionKind::SnarkPoolWorkSubmitVerifyError,
             Self::P2pSendAll => ActionKind::SnarkPoolP2pSendAll,
             Self::P2pSend { .. } => ActionKind::SnarkPoolP2pSend,
             Self::CheckTimeouts => ActionKind::SnarkPoolCheckTimeouts,
@@ -949,6 +1112,7 @@ impl ActionKindGet for TransactionPoolAction {
             }
             Self::VerifySuccess { .. } => ActionKind::TransactionPoolVerifySuccess,
             Self::VerifyError { .. } => ActionKind::TransactionPoolVerifyError,
+            Self::SnarkVerifyError { .. } => ActionKind::TransactionPoolSnarkVerifyError,
             Self::BestTipChanged { .. } => ActionKind::TransactionPoolBestTipChanged,
             Self::BestTipChangedWithAccounts { .. } => {
                 ActionKind::TransactionPoolBestTipChangedWithAccounts
@@ -963,7 +1127,14 @@ impl ActionKindGet for TransactionPoolAction {
             Self::ApplyTransitionFrontierDiffWithAccounts { .. } => {
                 ActionKind::TransactionPoolApplyTransitionFrontierDiffWithAccounts
             }
+            Self::DiffApplied { .. } => ActionKind::TransactionPoolDiffApplied,
             Self::Rebroadcast { .. } => ActionKind::TransactionPoolRebroadcast,
+            Self::RebroadcastLocallyGenerated => {
+                ActionKind::TransactionPoolRebroadcastLocallyGenerated
+            }
+            Self::RemoveStale => ActionKind::TransactionPoolRemoveStale,
+            Self::RemoveExpired => ActionKind::TransactionPoolRemoveExpired,
+            Self::ZkappRelayCheck => ActionKind::TransactionPoolZkappRelayCheck,
             Self::CollectTransactionsByFee => ActionKind::TransactionPoolCollectTransactionsByFee,
             Self::P2pSendAll => ActionKind::TransactionPoolP2pSendAll,
             Self::P2pSend { .. } => ActionKind::TransactionPoolP2pSend,
@@ -995,6 +1166,34 @@ impl ActionKindGet for ExternalSnarkWorkerAction {
             Self::WorkCancelled => ActionKind::ExternalSnarkWorkerWorkCancelled,
             Self::PruneWork => ActionKind::ExternalSnarkWorkerPruneWork,
             Self::Error { .. } => ActionKind::ExternalSnarkWorkerError,
+            Self::CoordinatorSubmit { .. } => ActionKind::ExternalSnarkWorkerCoordinatorSubmit,
+            Self::CoordinatorSubmitSuccess { .. } => {
+                ActionKind::ExternalSnarkWorkerCoordinatorSubmitSuccess
+            }
+            Self::CoordinatorSubmitError { .. } => {
+                ActionKind::ExternalSnarkWorkerCoordinatorSubmitError
+            }
+            Self::CoordinatorJobsFetchInit { .. } => {
+                ActionKind::ExternalSnarkWorkerCoordinatorJobsFetchInit
+            }
+            Self::CoordinatorJobsFetchPending { .. } => {
+                ActionKind::ExternalSnarkWorkerCoordinatorJobsFetchPending
+            }
+            Self::CoordinatorJobsFetchSuccess { .. } => {
+                ActionKind::ExternalSnarkWorkerCoordinatorJobsFetchSuccess
+            }
+            Self::CoordinatorJobsFetchError { .. } => {
+                ActionKind::ExternalSnarkWorkerCoordinatorJobsFetchError
+            }
+            Self::CoordinatorJobSpecFetchInit { .. } => {
+                ActionKind::ExternalSnarkWorkerCoordinatorJobSpecFetchInit
+            }
+            Self::CoordinatorJobSpecFetchSuccess { .. } => {
+                ActionKind::ExternalSnarkWorkerCoordinatorJobSpecFetchSuccess
+            }
+            Self::CoordinatorJobSpecFetchError { .. } => {
+                ActionKind::ExternalSnarkWorkerCoordinatorJobSpecFetchError
+            }
         }
     }
 }
@@ -1006,6 +1205,15 @@ impl ActionKindGet for ExternalSnarkWorkerEffectfulAction {
             Self::Kill => ActionKind::ExternalSnarkWorkerEffectfulKill,
             Self::SubmitWork { .. } => ActionKind::ExternalSnarkWorkerEffectfulSubmitWork,
             Self::CancelWork => ActionKind::ExternalSnarkWorkerEffectfulCancelWork,
+            Self::CoordinatorSubmit { .. } => {
+                ActionKind::ExternalSnarkWorkerEffectfulCoordinatorSubmit
+            }
+            Self::CoordinatorJobsFetch { .. } => {
+                ActionKind::ExternalSnarkWorkerEffectfulCoordinatorJobsFetch
+            }
+            Self::CoordinatorJobSpecFetch { .. } => {
+                ActionKind::ExternalSnarkWorkerEffectfulCoordinatorJobSpecFetch
+            }
         }
     }
 }
@@ -1038,6 +1246,10 @@ impl ActionKindGet for BlockProducerAction {
             Self::BlockProduced => ActionKind::BlockProducerBlockProduced,
             Self::BlockInject => ActionKind::BlockProducerBlockInject,
             Self::BlockInjected => ActionKind::BlockProducerBlockInjected,
+            Self::CoinbaseReceiverCheckInit => ActionKind::BlockProducerCoinbaseReceiverCheckInit,
+            Self::CoinbaseReceiverCheckSuccess { .. } => {
+                ActionKind::BlockProducerCoinbaseReceiverCheckSuccess
+            }
         }
     }
 }
@@ -1070,7 +1282,10 @@ impl ActionKindGet for RpcAction {
             Self::HeartbeatGet { .. } => ActionKind::RpcHeartbeatGet,
             Self::ActionStatsGet { .. } => ActionKind::RpcActionStatsGet,
             Self::SyncStatsGet { .. } => ActionKind::RpcSyncStatsGet,
+            Self::SyncHistoryGet { .. } => ActionKind::RpcSyncHistoryGet,
             Self::BlockProducerStatsGet { .. } => ActionKind::RpcBlockProducerStatsGet,
+            Self::PeerContributionStatsGet { .. } => ActionKind::RpcPeerContributionStatsGet,
+            Self::ArchiveUploadStatsGet { .. } => ActionKind::RpcArchiveUploadStatsGet,
             Self::MessageProgressGet { .. } => ActionKind::RpcMessageProgressGet,
             Self::PeersGet { .. } => ActionKind::RpcPeersGet,
             Self::P2pConnectionOutgoingInit { .. } => ActionKind::RpcP2pConnectionOutgoingInit,
@@ -1108,15 +1323,35 @@ impl ActionKindGet for RpcAction {
             Self::SnarkerConfigGet { .. } => ActionKind::RpcSnarkerConfigGet,
             Self::SnarkerJobCommit { .. } => ActionKind::RpcSnarkerJobCommit,
             Self::SnarkerJobSpec { .. } => ActionKind::RpcSnarkerJobSpec,
+            Self::SnarkerWorkSubmitInit { .. } => ActionKind::RpcSnarkerWorkSubmitInit,
+            Self::SnarkerWorkSubmitPending { .. } => ActionKind::RpcSnarkerWorkSubmitPending,
+            Self::SnarkerWorkSubmitSuccess { .. } => ActionKind::RpcSnarkerWorkSubmitSuccess,
             Self::SnarkerWorkersGet { .. } => ActionKind::RpcSnarkerWorkersGet,
             Self::HealthCheck { .. } => ActionKind::RpcHealthCheck,
             Self::ReadinessCheck { .. } => ActionKind::RpcReadinessCheck,
             Self::DiscoveryRoutingTable { .. } => ActionKind::RpcDiscoveryRoutingTable,
             Self::DiscoveryBoostrapStats { .. } => ActionKind::RpcDiscoveryBoostrapStats,
             Self::TransactionPool { .. } => ActionKind::RpcTransactionPool,
+            Self::TransactionPoolSenderQueues { .. } => ActionKind::RpcTransactionPoolSenderQueues,
             Self::LedgerAccountsGetInit { .. } => ActionKind::RpcLedgerAccountsGetInit,
             Self::LedgerAccountsGetPending { .. } => ActionKind::RpcLedgerAccountsGetPending,
             Self::LedgerAccountsGetSuccess { .. } => ActionKind::RpcLedgerAccountsGetSuccess,
+            Self::LedgerAccountsPageGetInit { .. } => ActionKind::RpcLedgerAccountsPageGetInit,
+            Self::LedgerAccountsPageGetPending { .. } => {
+                ActionKind::RpcLedgerAccountsPageGetPending
+            }
+            Self::LedgerAccountsPageGetSuccess { .. } => {
+                ActionKind::RpcLedgerAccountsPageGetSuccess
+            }
+            Self::LedgerAccountsAtBlockGetInit { .. } => {
+                ActionKind::RpcLedgerAccountsAtBlockGetInit
+            }
+            Self::LedgerAccountsAtBlockGetPending { .. } => {
+                ActionKind::RpcLedgerAccountsAtBlockGetPending
+            }
+            Self::LedgerAccountsAtBlockGetSuccess { .. } => {
+                ActionKind::RpcLedgerAccountsAtBlockGetSuccess
+            }
             Self::TransactionInjectInit { .. } => ActionKind::RpcTransactionInjectInit,
             Self::TransactionInjectPending { .. } => ActionKind::RpcTransactionInjectPending,
             Self::TransactionInjectSuccess { .. } => ActionKind::RpcTransactionInjectSuccess,
@@ -1126,9 +1361,11 @@ impl ActionKindGet for RpcAction {
                 ActionKind::RpcTransitionFrontierUserCommandsGet
             }
             Self::BestChain { .. } => ActionKind::RpcBestChain,
+            Self::BestChainPageGet { .. } => ActionKind::RpcBestChainPageGet,
             Self::ConsensusConstantsGet { .. } => ActionKind::RpcConsensusConstantsGet,
             Self::TransactionStatusGet { .. } => ActionKind::RpcTransactionStatusGet,
             Self::BlockGet { .. } => ActionKind::RpcBlockGet,
+            Self::BlockSummaryGet { .. } => ActionKind::RpcBlockSummaryGet,
             Self::ConsensusTimeGet { .. } => ActionKind::RpcConsensusTimeGet,
             Self::LedgerStatusGetInit { .. } => ActionKind::RpcLedgerStatusGetInit,
             Self::LedgerStatusGetPending { .. } => ActionKind::RpcLedgerStatusGetPending,
@@ -1142,9 +1379,47 @@ impl ActionKindGet for RpcAction {
             Self::LedgerAccountDelegatorsGetSuccess { .. } => {
                 ActionKind::RpcLedgerAccountDelegatorsGetSuccess
             }
+            Self::LedgerSnapshotGetInit { .. } => ActionKind::RpcLedgerSnapshotGetInit,
+            Self::LedgerSnapshotGetPending { .. } => ActionKind::RpcLedgerSnapshotGetPending,
+            Self::LedgerSnapshotGetSuccess { .. } => ActionKind::RpcLedgerSnapshotGetSuccess,
+            Self::BlockInject { .. } => ActionKind::RpcBlockInject,
+            Self::BlockInjectPending { .. } => ActionKind::RpcBlockInjectPending,
+            Self::BlockInjectPrevalidated { .. } => ActionKind::RpcBlockInjectPrevalidated,
+            Self::ConsensusForkDecisionsGet { .. } => ActionKind::RpcConsensusForkDecisionsGet,
+            Self::PendingCoinbaseGetInit { .. } => ActionKind::RpcPendingCoinbaseGetInit,
+            Self::PendingCoinbaseGetPending { .. } => ActionKind::RpcPendingCoinbaseGetPending,
+            Self::PendingCoinbaseGetSuccess { .. } => ActionKind::RpcPendingCoinbaseGetSuccess,
+            Self::PeerListRefreshInit { .. } => ActionKind::RpcPeerListRefreshInit,
+            Self::PeerListRefreshSuccess { .. } => ActionKind::RpcPeerListRefreshSuccess,
+            Self::GossipPause { .. } => ActionKind::RpcGossipPause,
+            Self::SyncDiagnosisGet { .. } => ActionKind::RpcSyncDiagnosisGet,
+            Self::SyncDiagnosisSuccess { .. } => ActionKind::RpcSyncDiagnosisSuccess,
+            Self::NodeOverviewGet { .. } => ActionKind::RpcNodeOverviewGet,
+            Self::LocallyGeneratedCommandsGet { .. } => ActionKind::RpcLocallyGeneratedCommandsGet,
+            Self::PendingRequestsGet { .. } => ActionKind::RpcPendingRequestsGet,
+            Self::SnarkStatsGet { .. } => ActionKind::RpcSnarkStatsGet,
+            Self::SnarkVerifierDigestsGet { .. } => ActionKind::RpcSnarkVerifierDigestsGet,
+            Self::SnarkVerifyHistoryGet { .. } => ActionKind::RpcSnarkVerifyHistoryGet,
+            Self::SnarkVerifyDryRunInit { .. } => ActionKind::RpcSnarkVerifyDryRunInit,
+            Self::SnarkVerifyDryRunSuccess { .. } => ActionKind::RpcSnarkVerifyDryRunSuccess,
+            Self::EnabledFeaturesGet { .. } => ActionKind::RpcEnabledFeaturesGet,
             Self::PooledUserCommands { .. } => ActionKind::RpcPooledUserCommands,
             Self::PooledZkappCommands { .. } => ActionKind::RpcPooledZkappCommands,
+            Self::PooledUserCommandsPageGet { .. } => ActionKind::RpcPooledUserCommandsPageGet,
+            Self::PooledZkappCommandsPageGet { .. } => ActionKind::RpcPooledZkappCommandsPageGet,
+            Self::ZkappEventsPageGet { .. } => ActionKind::RpcZkappEventsPageGet,
+            Self::ZkappActionsEventsGetInit { .. } => ActionKind::RpcZkappActionsEventsGetInit,
+            Self::ZkappActionsEventsGetPending { .. } => {
+                ActionKind::RpcZkappActionsEventsGetPending
+            }
+            Self::ZkappActionsEventsGetSuccess { .. } => {
+                ActionKind::RpcZkappActionsEventsGetSuccess
+            }
+            Self::TokenSupplyGetInit { .. } => ActionKind::RpcTokenSupplyGetInit,
+            Self::TokenSupplyGetPending { .. } => ActionKind::RpcTokenSupplyGetPending,
+            Self::TokenSupplyGetSuccess { .. } => ActionKind::RpcTokenSupplyGetSuccess,
             Self::GenesisBlock { .. } => ActionKind::RpcGenesisBlock,
+            Self::LedgerReadError { .. } => ActionKind::RpcLedgerReadError,
             Self::Finish { .. } => ActionKind::RpcFinish,
         }
     }
@@ -1158,7 +1433,12 @@ impl ActionKindGet for RpcEffectfulAction {
             Self::HeartbeatGet { .. } => ActionKind::RpcEffectfulHeartbeatGet,
             Self::ActionStatsGet { .. } => ActionKind::RpcEffectfulActionStatsGet,
             Self::SyncStatsGet { .. } => ActionKind::RpcEffectfulSyncStatsGet,
+            Self::SyncHistoryGet { .. } => ActionKind::RpcEffectfulSyncHistoryGet,
             Self::BlockProducerStatsGet { .. } => ActionKind::RpcEffectfulBlockProducerStatsGet,
+            Self::PeerContributionStatsGet { .. } => {
+                ActionKind::RpcEffectfulPeerContributionStatsGet
+            }
+            Self::ArchiveUploadStatsGet { .. } => ActionKind::RpcEffectfulArchiveUploadStatsGet,
             Self::MessageProgressGet { .. } => ActionKind::RpcEffectfulMessageProgressGet,
             Self::PeersGet { .. } => ActionKind::RpcEffectfulPeersGet,
             Self::P2pConnectionOutgoingError { .. } => {
@@ -1190,12 +1470,18 @@ impl ActionKindGet for RpcEffectfulAction {
             Self::SnarkerConfigGet { .. } => ActionKind::RpcEffectfulSnarkerConfigGet,
             Self::SnarkerJobCommit { .. } => ActionKind::RpcEffectfulSnarkerJobCommit,
             Self::SnarkerJobSpec { .. } => ActionKind::RpcEffectfulSnarkerJobSpec,
+            Self::SnarkerWorkSubmitSuccess { .. } => {
+                ActionKind::RpcEffectfulSnarkerWorkSubmitSuccess
+            }
             Self::SnarkerWorkersGet { .. } => ActionKind::RpcEffectfulSnarkerWorkersGet,
             Self::HealthCheck { .. } => ActionKind::RpcEffectfulHealthCheck,
             Self::ReadinessCheck { .. } => ActionKind::RpcEffectfulReadinessCheck,
             Self::DiscoveryRoutingTable { .. } => ActionKind::RpcEffectfulDiscoveryRoutingTable,
             Self::DiscoveryBoostrapStats { .. } => ActionKind::RpcEffectfulDiscoveryBoostrapStats,
             Self::TransactionPool { .. } => ActionKind::RpcEffectfulTransactionPool,
+            Self::TransactionPoolSenderQueues { .. } => {
+                ActionKind::RpcEffectfulTransactionPoolSenderQueues
+            }
             Self::LedgerAccountsGetSuccess { .. } => {
                 ActionKind::RpcEffectfulLedgerAccountsGetSuccess
             }
@@ -1212,17 +1498,63 @@ impl ActionKindGet for RpcEffectfulAction {
                 ActionKind::RpcEffectfulTransitionFrontierUserCommandsGet
             }
             Self::BestChain { .. } => ActionKind::RpcEffectfulBestChain,
+            Self::BestChainPageGet { .. } => ActionKind::RpcEffectfulBestChainPageGet,
             Self::ConsensusConstantsGet { .. } => ActionKind::RpcEffectfulConsensusConstantsGet,
             Self::TransactionStatusGet { .. } => ActionKind::RpcEffectfulTransactionStatusGet,
             Self::BlockGet { .. } => ActionKind::RpcEffectfulBlockGet,
+            Self::BlockSummaryGet { .. } => ActionKind::RpcEffectfulBlockSummaryGet,
             Self::PooledUserCommands { .. } => ActionKind::RpcEffectfulPooledUserCommands,
             Self::PooledZkappCommands { .. } => ActionKind::RpcEffectfulPooledZkappCommands,
+            Self::PooledUserCommandsPageGet { .. } => {
+                ActionKind::RpcEffectfulPooledUserCommandsPageGet
+            }
+            Self::PooledZkappCommandsPageGet { .. } => {
+                ActionKind::RpcEffectfulPooledZkappCommandsPageGet
+            }
+            Self::ZkappEventsPageGet { .. } => ActionKind::RpcEffectfulZkappEventsPageGet,
+            Self::ZkappActionsEventsGetSuccess { .. } => {
+                ActionKind::RpcEffectfulZkappActionsEventsGetSuccess
+            }
+            Self::TokenSupplyGetSuccess { .. } => ActionKind::RpcEffectfulTokenSupplyGetSuccess,
             Self::GenesisBlock { .. } => ActionKind::RpcEffectfulGenesisBlock,
             Self::ConsensusTimeGet { .. } => ActionKind::RpcEffectfulConsensusTimeGet,
             Self::LedgerStatusGetSuccess { .. } => ActionKind::RpcEffectfulLedgerStatusGetSuccess,
             Self::LedgerAccountDelegatorsGetSuccess { .. } => {
                 ActionKind::RpcEffectfulLedgerAccountDelegatorsGetSuccess
             }
+            Self::LedgerSnapshotGetSuccess { .. } => {
+                ActionKind::RpcEffectfulLedgerSnapshotGetSuccess
+            }
+            Self::LedgerAccountsPageGetSuccess { .. } => {
+                ActionKind::RpcEffectfulLedgerAccountsPageGetSuccess
+            }
+            Self::LedgerAccountsAtBlockGetSuccess { .. } => {
+                ActionKind::RpcEffectfulLedgerAccountsAtBlockGetSuccess
+            }
+            Self::BlockInject { .. } => ActionKind::RpcEffectfulBlockInject,
+            Self::ConsensusForkDecisionsGet { .. } => {
+                ActionKind::RpcEffectfulConsensusForkDecisionsGet
+            }
+            Self::PendingCoinbaseGetSuccess { .. } => {
+                ActionKind::RpcEffectfulPendingCoinbaseGetSuccess
+            }
+            Self::PeerListRefreshInit { .. } => ActionKind::RpcEffectfulPeerListRefreshInit,
+            Self::PeerListRefreshSuccess { .. } => ActionKind::RpcEffectfulPeerListRefreshSuccess,
+            Self::GossipPause { .. } => ActionKind::RpcEffectfulGossipPause,
+            Self::SyncDiagnosisGet { .. } => ActionKind::RpcEffectfulSyncDiagnosisGet,
+            Self::NodeOverviewGet { .. } => ActionKind::RpcEffectfulNodeOverviewGet,
+            Self::LocallyGeneratedCommandsGet { .. } => {
+                ActionKind::RpcEffectfulLocallyGeneratedCommandsGet
+            }
+            Self::PendingRequestsGet { .. } => ActionKind::RpcEffectfulPendingRequestsGet,
+            Self::SnarkStatsGet { .. } => ActionKind::RpcEffectfulSnarkStatsGet,
+            Self::SnarkVerifierDigestsGet { .. } => ActionKind::RpcEffectfulSnarkVerifierDigestsGet,
+            Self::SnarkVerifyHistoryGet { .. } => ActionKind::RpcEffectfulSnarkVerifyHistoryGet,
+            Self::SnarkVerifyDryRunSuccess { .. } => {
+                ActionKind::RpcEffectfulSnarkVerifyDryRunSuccess
+            }
+            Self::EnabledFeaturesGet { .. } => ActionKind::RpcEffectfulEnabledFeaturesGet,
+            Self::Drop { .. } => ActionKind::RpcEffectfulDrop,
         }
     }
 }
@@ -1298,10 +1630,20 @@ impl ActionKindGet for P2pIdentifyAction {
     }
 }
 
+impl ActionKindGet for P2pGossipAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Pause { .. } => ActionKind::P2pGossipPause,
+            Self::Resume { .. } => ActionKind::P2pGossipResume,
+        }
+    }
+}
+
 impl ActionKindGet for P2pChannelsAction {
     fn kind(&self) -> ActionKind {
         match self {
             Self::MessageReceived(a) => a.kind(),
+            Self::MessageShed(a) => a.kind(),
             Self::SignalingDiscovery(a) => a.kind(),
             Self::SignalingExchange(a) => a.kind(),
             Self::BestTip(a) => a.kind(),
@@ -1320,7 +1662,10 @@ impl ActionKindGet for P2pPeerAction {
             Self::Discovered { .. } => ActionKind::P2pPeerDiscovered,
             Self::Ready { .. } => ActionKind::P2pPeerReady,
             Self::BestTipUpdate { .. } => ActionKind::P2pPeerBestTipUpdate,
+            Self::ConnectionStatsUpdate { .. } => ActionKind::P2pPeerConnectionStatsUpdate,
             Self::Remove { .. } => ActionKind::P2pPeerRemove,
+            Self::Listed { .. } => ActionKind::P2pPeerListed,
+            Self::Unlisted { .. } => ActionKind::P2pPeerUnlisted,
         }
     }
 }
@@ -1408,6 +1753,7 @@ impl ActionKindGet for LedgerReadAction {
             Self::Init { .. } => ActionKind::LedgerReadInit,
             Self::Pending { .. } => ActionKind::LedgerReadPending,
             Self::Success { .. } => ActionKind::LedgerReadSuccess,
+            Self::Timeout { .. } => ActionKind::LedgerReadTimeout,
             Self::Prune { .. } => ActionKind::LedgerReadPrune,
         }
     }
@@ -1417,9 +1763,12 @@ impl ActionKindGet for SnarkBlockVerifyAction {
     fn kind(&self) -> ActionKind {
         match self {
             Self::Init { .. } => ActionKind::SnarkBlockVerifyInit,
+            Self::Next => ActionKind::SnarkBlockVerifyNext,
+            Self::PrioritiesUpdate { .. } => ActionKind::SnarkBlockVerifyPrioritiesUpdate,
             Self::Pending { .. } => ActionKind::SnarkBlockVerifyPending,
             Self::Error { .. } => ActionKind::SnarkBlockVerifyError,
             Self::Success { .. } => ActionKind::SnarkBlockVerifySuccess,
+            Self::Timeout { .. } => ActionKind::SnarkBlockVerifyTimeout,
             Self::Finish { .. } => ActionKind::SnarkBlockVerifyFinish,
         }
     }
@@ -1440,6 +1789,7 @@ impl ActionKindGet for SnarkWorkVerifyAction {
             Self::Pending { .. } => ActionKind::SnarkWorkVerifyPending,
             Self::Error { .. } => ActionKind::SnarkWorkVerifyError,
             Self::Success { .. } => ActionKind::SnarkWorkVerifySuccess,
+            Self::Timeout { .. } => ActionKind::SnarkWorkVerifyTimeout,
             Self::Finish { .. } => ActionKind::SnarkWorkVerifyFinish,
         }
     }
@@ -1460,6 +1810,7 @@ impl ActionKindGet for SnarkUserCommandVerifyAction {
             Self::Pending { .. } => ActionKind::SnarkUserCommandVerifyPending,
             Self::Error { .. } => ActionKind::SnarkUserCommandVerifyError,
             Self::Success { .. } => ActionKind::SnarkUserCommandVerifySuccess,
+            Self::Timeout { .. } => ActionKind::SnarkUserCommandVerifyTimeout,
             Self::Finish { .. } => ActionKind::SnarkUserCommandVerifyFinish,
         }
     }
@@ -1473,6 +1824,14 @@ impl ActionKindGet for SnarkUserCommandVerifyEffectfulAction {
     }
 }
 
+impl ActionKindGet for SnarkVerifyDryRunEffectfulAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Init { .. } => ActionKind::SnarkVerifyDryRunEffectfulInit,
+        }
+    }
+}
+
 impl ActionKindGet for TransitionFrontierGenesisAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -1519,12 +1878,16 @@ impl ActionKindGet for TransitionFrontierCandidateAction {
             Self::BlockSnarkVerifyPending { .. } => {
                 ActionKind::TransitionFrontierCandidateBlockSnarkVerifyPending
             }
+            Self::BlockSnarkVerifyPrioritiesUpdate => {
+                ActionKind::TransitionFrontierCandidateBlockSnarkVerifyPrioritiesUpdate
+            }
             Self::BlockSnarkVerifySuccess { .. } => {
                 ActionKind::TransitionFrontierCandidateBlockSnarkVerifySuccess
             }
             Self::BlockSnarkVerifyError { .. } => {
                 ActionKind::TransitionFrontierCandidateBlockSnarkVerifyError
             }
+            Self::ForkDecision { .. } => ActionKind::TransitionFrontierCandidateForkDecision,
             Self::TransitionFrontierSyncTargetUpdate => {
                 ActionKind::TransitionFrontierCandidateTransitionFrontierSyncTargetUpdate
             }
@@ -1566,6 +1929,16 @@ impl ActionKindGet for TransitionFrontierSyncAction {
             Self::BlocksPeerQuerySuccess { .. } => {
                 ActionKind::TransitionFrontierSyncBlocksPeerQuerySuccess
             }
+            Self::BlocksBucketQuery => ActionKind::TransitionFrontierSyncBlocksBucketQuery,
+            Self::BlocksBucketFetchInit { .. } => {
+                ActionKind::TransitionFrontierSyncBlocksBucketFetchInit
+            }
+            Self::BlocksBucketFetchError { .. } => {
+                ActionKind::TransitionFrontierSyncBlocksBucketFetchError
+            }
+            Self::BlocksBucketFetchSuccess { .. } => {
+                ActionKind::TransitionFrontierSyncBlocksBucketFetchSuccess
+            }
             Self::BlocksFetchSuccess { .. } => ActionKind::TransitionFrontierSyncBlocksFetchSuccess,
             Self::BlocksNextApplyInit => ActionKind::TransitionFrontierSyncBlocksNextApplyInit,
             Self::BlocksNextApplyPending { .. } => {
@@ -1686,6 +2059,9 @@ impl ActionKindGet for BlockProducerVrfEvaluatorEffectfulAction {
             Self::InitializeStats { .. } => {
                 ActionKind::BlockProducerVrfEvaluatorEffectfulInitializeStats
             }
+            Self::DelegatorTableStats { .. } => {
+                ActionKind::BlockProducerVrfEvaluatorEffectfulDelegatorTableStats
+            }
         }
     }
 }
@@ -1696,6 +2072,14 @@ impl ActionKindGet for P2pConnectionOutgoingAction {
             Self::RandomInit => ActionKind::P2pConnectionOutgoingRandomInit,
             Self::Init { .. } => ActionKind::P2pConnectionOutgoingInit,
             Self::Reconnect { .. } => ActionKind::P2pConnectionOutgoingReconnect,
+            Self::HostResolveSuccess { .. } => ActionKind::P2pConnectionOutgoingHostResolveSuccess,
+            Self::DnsaddrResolveInit { .. } => ActionKind::P2pConnectionOutgoingDnsaddrResolveInit,
+            Self::DnsaddrResolveSuccess { .. } => {
+                ActionKind::P2pConnectionOutgoingDnsaddrResolveSuccess
+            }
+            Self::DnsaddrResolveError { .. } => {
+                ActionKind::P2pConnectionOutgoingDnsaddrResolveError
+            }
             Self::OfferSdpCreatePending { .. } => {
                 ActionKind::P2pConnectionOutgoingOfferSdpCreatePending
             }
@@ -1755,6 +2139,12 @@ impl ActionKindGet for P2pChannelsMessageReceivedAction {
     }
 }
 
+impl ActionKindGet for P2pChannelsMessageShedAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pChannelsMessageShed
+    }
+}
+
 impl ActionKindGet for P2pChannelsSignalingDiscoveryAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -1858,6 +2248,7 @@ impl ActionKindGet for P2pChannelsSnarkAction {
             Self::RequestSend { .. } => ActionKind::P2pChannelsSnarkRequestSend,
             Self::PromiseReceived { .. } => ActionKind::P2pChannelsSnarkPromiseReceived,
             Self::Received { .. } => ActionKind::P2pChannelsSnarkReceived,
+            Self::BatchReceived { .. } => ActionKind::P2pChannelsSnarkBatchReceived,
             Self::RequestReceived { .. } => ActionKind::P2pChannelsSnarkRequestReceived,
             Self::ResponseSend { .. } => ActionKind::P2pChannelsSnarkResponseSend,
             Self::Libp2pReceived { .. } => ActionKind::P2pChannelsSnarkLibp2pReceived,
@@ -2092,6 +2483,8 @@ impl ActionKindGet for P2pConnectionOutgoingEffectfulAction {
         match self {
             Self::RandomInit { .. } => ActionKind::P2pConnectionOutgoingEffectfulRandomInit,
             Self::Init { .. } => ActionKind::P2pConnectionOutgoingEffectfulInit,
+            Self::ResolveHost { .. } => ActionKind::P2pConnectionOutgoingEffectfulResolveHost,
+            Self::ResolveDnsaddr { .. } => ActionKind::P2pConnectionOutgoingEffectfulResolveDnsaddr,
             Self::OfferSend { .. } => ActionKind::P2pConnectionOutgoingEffectfulOfferSend,
             Self::AnswerSet { .. } => ActionKind::P2pConnectionOutgoingEffectfulAnswerSet,
             Self::ConnectionAuthorizationEncryptAndSend { .. } => {
@@ -2130,6 +2523,9 @@ impl ActionKindGet for P2pNetworkSchedulerEffectfulAction {
             Self::IncomingDidAccept { .. } => {
                 ActionKind::P2pNetworkSchedulerEffectfulIncomingDidAccept
             }
+            Self::IncomingDidRefuse { .. } => {
+                ActionKind::P2pNetworkSchedulerEffectfulIncomingDidRefuse
+            }
             Self::OutgoingConnect { .. } => ActionKind::P2pNetworkSchedulerEffectfulOutgoingConnect,
             Self::OutgoingDidConnect { .. } => {
                 ActionKind::P2pNetworkSchedulerEffectfulOutgoingDidConnect
@@ -2346,6 +2742,18 @@ impl ActionKindGet for TransitionFrontierSyncLedgerSnarkedAction {
             Self::MerkleTreeSyncSuccess => {
                 ActionKind::TransitionFrontierSyncLedgerSnarkedMerkleTreeSyncSuccess
             }
+            Self::SnapshotFetchInit => {
+                ActionKind::TransitionFrontierSyncLedgerSnarkedSnapshotFetchInit
+            }
+            Self::SnapshotFetchError { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerSnarkedSnapshotFetchError
+            }
+            Self::SnapshotReceived { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerSnarkedSnapshotReceived
+            }
+            Self::SnapshotAccepted { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerSnarkedSnapshotAccepted
+            }
             Self::Success => ActionKind::TransitionFrontierSyncLedgerSnarkedSuccess,
         }
     }
@@ -2375,6 +2783,15 @@ impl ActionKindGet for TransitionFrontierSyncLedgerStagedAction {
             Self::PartsPeerValid { .. } => {
                 ActionKind::TransitionFrontierSyncLedgerStagedPartsPeerValid
             }
+            Self::PartsSnapshotFetchInit => {
+                ActionKind::TransitionFrontierSyncLedgerStagedPartsSnapshotFetchInit
+            }
+            Self::PartsSnapshotFetchError { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerStagedPartsSnapshotFetchError
+            }
+            Self::PartsSnapshotFetchSuccess { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerStagedPartsSnapshotFetchSuccess
+            }
             Self::PartsFetchSuccess { .. } => {
                 ActionKind::TransitionFrontierSyncLedgerStagedPartsFetchSuccess
             }
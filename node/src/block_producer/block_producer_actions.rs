@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
-use ledger::scan_state::transaction_logic::valid;
+use ledger::{scan_state::transaction_logic::valid, Account};
 use mina_core::{block::ArcBlockWithHash, ActionEvent};
-use mina_p2p_messages::v2::MinaBaseProofStableV2;
+use mina_p2p_messages::v2::{LedgerHash, MinaBaseProofStableV2};
 use serde::{Deserialize, Serialize};
 
 use crate::block_producer_effectful::StagedLedgerDiffCreateOutput;
@@ -66,6 +66,13 @@ pub enum BlockProducerAction {
     #[action_event(level = trace)]
     BlockInject,
     BlockInjected,
+    /// Reads the custom coinbase receiver account, to check that it can
+    /// receive the coinbase, see [`super::BlockProducerCoinbaseReceiverCheck`].
+    CoinbaseReceiverCheckInit,
+    CoinbaseReceiverCheckSuccess {
+        ledger_hash: LedgerHash,
+        account: Option<Box<Account>>,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for BlockProducerAction {
@@ -216,6 +223,23 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
             BlockProducerAction::BlockInjected => state.block_producer.with(false, |this| {
                 matches!(this.current, BlockProducerCurrentState::Produced { .. })
             }),
+            BlockProducerAction::CoinbaseReceiverCheckInit => {
+                state.block_producer.with(false, |this| {
+                    let Some(best_tip) = state.transition_frontier.best_tip() else {
+                        return false;
+                    };
+                    let epoch = best_tip.consensus_state().epoch_count.as_u32();
+                    this.coinbase_receiver_check_is_due(epoch, time)
+                        && state.ledger.read.is_total_cost_under_limit()
+                })
+            }
+            BlockProducerAction::CoinbaseReceiverCheckSuccess { ledger_hash, .. } => {
+                state.block_producer.with(false, |this| {
+                    this.coinbase_receiver_check.as_ref().is_some_and(|check| {
+                        check.can_receive.is_none() && &check.ledger_hash == ledger_hash
+                    })
+                })
+            }
             BlockProducerAction::WonSlotDiscard { reason } => {
                 let current_reason = state.block_producer.with(None, |bp| {
                     let best_tip = state.transition_frontier.best_tip()?;
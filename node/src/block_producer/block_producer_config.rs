@@ -6,6 +6,21 @@ pub struct BlockProducerConfig {
     pub pub_key: NonZeroCurvePoint,
     pub custom_coinbase_receiver: Option<NonZeroCurvePoint>,
     pub proposed_protocol_version: Option<ProtocolVersionStableV2>,
+    #[serde(default)]
+    pub work_fee_policy: BlockProducerWorkFeePolicy,
+}
+
+/// Limits on the fees paid for completed snark work included in the
+/// produced blocks.
+///
+/// Works are bought in the order required by the scan state, so once a
+/// work is rejected by the policy, no more works are included in the block.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BlockProducerWorkFeePolicy {
+    /// Max fee paid for a single work, in nanomina.
+    pub max_fee_per_work: Option<u64>,
+    /// Max sum of the fees paid for the works in a block, in nanomina.
+    pub max_fee_per_block: Option<u64>,
 }
 
 impl BlockProducerConfig {
@@ -14,6 +29,7 @@ impl BlockProducerConfig {
             pub_key,
             custom_coinbase_receiver: None,
             proposed_protocol_version: None,
+            work_fee_policy: Default::default(),
         }
     }
 
@@ -23,3 +39,32 @@ impl BlockProducerConfig {
             .unwrap_or(&self.pub_key)
     }
 }
+
+impl BlockProducerWorkFeePolicy {
+    /// Whether a work with the `fee` can be bought, when `spent` was already
+    /// spent on the other works of the block.
+    pub fn allows(&self, spent: u64, fee: u64) -> bool {
+        self.max_fee_per_work.is_none_or(|max| fee <= max)
+            && self
+                .max_fee_per_block
+                .is_none_or(|max| spent.saturating_add(fee) <= max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn work_fee_policy_limits() {
+        let policy = BlockProducerWorkFeePolicy {
+            max_fee_per_work: Some(10),
+            max_fee_per_block: Some(25),
+        };
+        assert!(policy.allows(0, 10));
+        assert!(!policy.allows(0, 11));
+        assert!(policy.allows(15, 10));
+        assert!(!policy.allows(20, 10));
+        assert!(BlockProducerWorkFeePolicy::default().allows(u64::MAX, u64::MAX));
+    }
+}
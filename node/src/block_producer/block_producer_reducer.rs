@@ -13,8 +13,9 @@ use p2p::P2pNetworkPubsubAction;
 use redux::{callback, Dispatcher, Timestamp};
 
 use crate::{
-    transition_frontier::sync::TransitionFrontierSyncAction, Action, BlockProducerEffectfulAction,
-    State, Substate, TransactionPoolAction,
+    ledger::read::{LedgerReadAction, LedgerReadInitCallback, LedgerReadRequest},
+    transition_frontier::sync::TransitionFrontierSyncAction,
+    Action, BlockProducerEffectfulAction, State, Substate, TransactionPoolAction,
 };
 
 use super::{
@@ -215,7 +216,11 @@ impl BlockProducerEnabled {
                 };
 
                 let dispatcher = state_context.into_dispatcher();
-                dispatcher.push(BlockProducerEffectfulAction::StagedLedgerDiffCreateSuccess);
+                dispatcher.push(
+                    BlockProducerEffectfulAction::StagedLedgerDiffCreateSuccess {
+                        work_limited_by_policy: output.work_limited_by_policy,
+                    },
+                );
             }
             BlockProducerAction::BlockUnprovenBuild => {
                 state.reduce_block_unproved_build(consensus_constants, meta.time());
@@ -358,7 +363,9 @@ impl BlockProducerEnabled {
                     ..
                 } = &mut state.current
                 {
-                    state.injected_blocks.insert(block.hash().clone());
+                    state
+                        .injected_blocks
+                        .insert(block.hash().clone(), won_slot.vrf_evaluation());
                     state.current = BlockProducerCurrentState::Injected {
                         time: meta.time(),
                         won_slot: won_slot.clone(),
@@ -376,6 +383,44 @@ impl BlockProducerEnabled {
 
                 dispatcher.push(BlockProducerAction::WonSlotSearch);
             }
+            BlockProducerAction::CoinbaseReceiverCheckInit => {
+                let Some(best_tip) = best_chain.last() else {
+                    return;
+                };
+                let ledger_hash = best_tip.merkle_root_hash().clone();
+                let Some(receiver) = state.coinbase_receiver_check_start(
+                    best_tip.consensus_state().epoch_count.as_u32(),
+                    ledger_hash.clone(),
+                    meta.time(),
+                ) else {
+                    return;
+                };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::CoinbaseReceiverAccount(ledger_hash, receiver),
+                    callback: LedgerReadInitCallback::None,
+                });
+            }
+            BlockProducerAction::CoinbaseReceiverCheckSuccess { account, .. } => {
+                let Some(can_receive) = state.coinbase_receiver_check_finish(account.as_deref())
+                else {
+                    return;
+                };
+
+                if let Some(account) = account.as_ref().filter(|_| !can_receive) {
+                    mina_core::error!(meta.time();
+                        kind = "CoinbaseReceiverCannotReceive",
+                        summary = format!(
+                            "coinbase receiver {} can't receive the coinbase, \
+                             the rewards of the produced blocks will be burned",
+                            account.public_key.into_address()
+                        ),
+                        access = account.permissions.access.to_string(),
+                        receive = account.permissions.receive.to_string(),
+                    );
+                }
+            }
         }
     }
 
@@ -753,6 +798,8 @@ impl BlockProducerEnabled {
             next_epoch_data: Box::new(best_tip.consensus_state().next_epoch_data.clone()),
         });
 
+        dispatcher.push(BlockProducerAction::CoinbaseReceiverCheckInit);
+
         if let Some(reason) = state
             .block_producer
             .with(None, |bp| bp.current.won_slot_should_discard(best_tip))
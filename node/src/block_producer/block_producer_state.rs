@@ -1,6 +1,6 @@
-use std::{collections::BTreeSet, sync::Arc, time::Duration};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
-use ledger::scan_state::transaction_logic::valid;
+use ledger::{scan_state::transaction_logic::valid, Account};
 use mina_core::{
     block::{AppliedBlock, ArcBlockWithHash},
     consensus::consensus_take,
@@ -11,8 +11,8 @@ use serde::{Deserialize, Serialize};
 use crate::account::AccountPublicKey;
 
 use super::{
-    vrf_evaluator::BlockProducerVrfEvaluatorState, BlockProducerConfig, BlockProducerWonSlot,
-    BlockWithoutProof,
+    vrf_evaluator::BlockProducerVrfEvaluatorState, BlockProducerConfig, BlockProducerVrfEvaluation,
+    BlockProducerWonSlot, BlockWithoutProof,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -24,8 +24,28 @@ pub struct BlockProducerEnabled {
     pub vrf_evaluator: BlockProducerVrfEvaluatorState,
     pub current: BlockProducerCurrentState,
     /// Blocks that were injected into transition frontier, but hasn't
-    /// become our best tip yet.
-    pub injected_blocks: BTreeSet<v2::StateHash>,
+    /// become our best tip yet, with the vrf evaluation which won their
+    /// slot.
+    pub injected_blocks: BTreeMap<v2::StateHash, BlockProducerVrfEvaluation>,
+    /// Last check of the custom coinbase receiver's permissions.
+    #[serde(default)]
+    pub coinbase_receiver_check: Option<BlockProducerCoinbaseReceiverCheck>,
+}
+
+/// Check that the custom coinbase receiver is allowed to receive the
+/// coinbase. If it isn't, e.g. a zkApp account whose `receive` permission
+/// isn't `None`, the rewards of the produced blocks are burned.
+///
+/// The account is checked in the best tip ledger at startup, then once
+/// per epoch.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerCoinbaseReceiverCheck {
+    pub time: redux::Timestamp,
+    /// Epoch of the best tip at the time of the check.
+    pub epoch: u32,
+    pub ledger_hash: v2::LedgerHash,
+    /// `None` while the account is being read.
+    pub can_receive: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -149,6 +169,7 @@ impl BlockProducerState {
             vrf_evaluator: BlockProducerVrfEvaluatorState::new(now),
             current: BlockProducerCurrentState::Idle { time: now },
             injected_blocks: Default::default(),
+            coinbase_receiver_check: None,
         }))
     }
 
@@ -182,7 +203,8 @@ impl BlockProducerState {
     /// Checks if the block was produced by us recently.
     pub fn is_produced_by_me(&self, block: &ArcBlockWithHash) -> bool {
         self.with(false, |this| {
-            block.producer() == &this.config.pub_key && this.injected_blocks.contains(block.hash())
+            block.producer() == &this.config.pub_key
+                && this.injected_blocks.contains_key(block.hash())
         })
     }
 
@@ -211,6 +233,16 @@ impl BlockProducerState {
         self.with(None, |this| this.current.produced_block_with_chain())
     }
 
+    /// Vrf evaluation which won the slot of the block, if it's a block we
+    /// produced and injected. Kept until the block becomes our best tip, as
+    /// the search for the next won slot replaces the current one meanwhile.
+    pub fn injected_block_vrf_evaluation(
+        &self,
+        hash: &v2::StateHash,
+    ) -> Option<BlockProducerVrfEvaluation> {
+        self.with(None, |this| this.injected_blocks.get(hash).cloned())
+    }
+
     pub fn vrf_evaluator(&self) -> Option<&BlockProducerVrfEvaluatorState> {
         self.with(None, |this| Some(&this.vrf_evaluator))
     }
@@ -231,6 +263,54 @@ impl BlockProducerState {
     }
 }
 
+/// Time after which a check whose account wasn't read yet is started
+/// again, e.g. if the ledger read was dropped.
+const COINBASE_RECEIVER_CHECK_TIMEOUT: Duration = Duration::from_secs(60);
+
+impl BlockProducerEnabled {
+    /// Whether the custom coinbase receiver should be checked with a best
+    /// tip of `epoch`.
+    pub fn coinbase_receiver_check_is_due(&self, epoch: u32, now: redux::Timestamp) -> bool {
+        self.config.custom_coinbase_receiver.is_some()
+            && self.coinbase_receiver_check.as_ref().is_none_or(|check| {
+                check.epoch < epoch
+                    || (check.can_receive.is_none()
+                        && now
+                            .checked_sub(check.time)
+                            .is_some_and(|d| d >= COINBASE_RECEIVER_CHECK_TIMEOUT))
+            })
+    }
+
+    /// Starts checking the custom coinbase receiver in the ledger of a best
+    /// tip of `epoch`, returns the account to read.
+    pub fn coinbase_receiver_check_start(
+        &mut self,
+        epoch: u32,
+        ledger_hash: v2::LedgerHash,
+        now: redux::Timestamp,
+    ) -> Option<AccountPublicKey> {
+        let receiver = self.config.custom_coinbase_receiver.clone()?;
+        self.coinbase_receiver_check = Some(BlockProducerCoinbaseReceiverCheck {
+            time: now,
+            epoch,
+            ledger_hash,
+            can_receive: None,
+        });
+        Some(receiver.into())
+    }
+
+    /// Completes the check with the account read, `None` if it doesn't
+    /// exist. Returns whether the account can receive the coinbase.
+    pub fn coinbase_receiver_check_finish(&mut self, account: Option<&Account>) -> Option<bool> {
+        let check = self.coinbase_receiver_check.as_mut()?;
+        // A missing account is created by the coinbase, with the default
+        // permissions.
+        let can_receive = account.is_none_or(|account| account.has_permission_to_receive());
+        check.can_receive = Some(can_receive);
+        Some(can_receive)
+    }
+}
+
 impl BlockProducerCurrentState {
     pub fn won_slot_should_search(&self) -> bool {
         match self {
@@ -412,3 +492,155 @@ impl Default for BlockProducerCurrentState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use ledger::AccountIndex;
+    use mina_curves::pasta::Fp;
+
+    use super::*;
+
+    fn vrf_evaluation(global_slot: u32) -> BlockProducerVrfEvaluation {
+        let pub_key =
+            AccountPublicKey::from_str("B62qnLVz8wM7MfJsuYbjFf4UWbwrUBEL5ZdawExxxFhnGXB6siqokyM")
+                .unwrap();
+        BlockProducerVrfEvaluation {
+            global_slot,
+            delegator: (pub_key.into(), AccountIndex(0)),
+            staking_ledger_hash: v2::LedgerHash::zero(),
+            vrf_output: global_slot.to_string(),
+            vrf_output_fractional: 0.1,
+            threshold: Some(0.2),
+            stake_fraction: Some(0.5),
+        }
+    }
+
+    #[test]
+    fn test_injected_block_vrf_evaluation_outlives_current_slot() {
+        let pub_key =
+            AccountPublicKey::from_str("B62qnLVz8wM7MfJsuYbjFf4UWbwrUBEL5ZdawExxxFhnGXB6siqokyM")
+                .unwrap();
+        let mut state = BlockProducerState::new(
+            redux::Timestamp::ZERO,
+            Some(BlockProducerConfig::new(pub_key.into())),
+        );
+        let (first, second) = (
+            v2::StateHash::from_fp(Fp::from(1)),
+            v2::StateHash::from_fp(Fp::from(2)),
+        );
+        let this = state.as_mut().unwrap();
+        this.injected_blocks
+            .insert(first.clone(), vrf_evaluation(10));
+        this.injected_blocks
+            .insert(second.clone(), vrf_evaluation(11));
+        // The search for the next won slot already replaced the one of the
+        // injected blocks.
+        assert!(matches!(
+            this.current,
+            BlockProducerCurrentState::Idle { .. }
+        ));
+
+        assert_eq!(
+            state.injected_block_vrf_evaluation(&first),
+            Some(vrf_evaluation(10))
+        );
+        assert_eq!(
+            state.injected_block_vrf_evaluation(&second),
+            Some(vrf_evaluation(11))
+        );
+        assert_eq!(
+            state.injected_block_vrf_evaluation(&v2::StateHash::from_fp(Fp::from(3))),
+            None
+        );
+    }
+
+    fn with_coinbase_receiver() -> BlockProducerEnabled {
+        let pub_key =
+            AccountPublicKey::from_str("B62qnLVz8wM7MfJsuYbjFf4UWbwrUBEL5ZdawExxxFhnGXB6siqokyM")
+                .unwrap();
+        let receiver =
+            AccountPublicKey::from_str("B62qrztYfPinaKqpXaYGY6QJ3SSW2NNKs7SajBLF1iFNXW9BoALN2Aq")
+                .unwrap();
+        let mut config = BlockProducerConfig::new(pub_key.into());
+        config.custom_coinbase_receiver = Some(receiver.into());
+        let mut state = BlockProducerState::new(redux::Timestamp::ZERO, Some(config));
+        state.as_mut().unwrap().clone()
+    }
+
+    fn secs(secs: u64) -> redux::Timestamp {
+        redux::Timestamp::ZERO + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_coinbase_receiver_check_is_due_at_startup_and_every_epoch() {
+        let pub_key =
+            AccountPublicKey::from_str("B62qnLVz8wM7MfJsuYbjFf4UWbwrUBEL5ZdawExxxFhnGXB6siqokyM")
+                .unwrap();
+        let mut state = BlockProducerState::new(
+            redux::Timestamp::ZERO,
+            Some(BlockProducerConfig::new(pub_key.into())),
+        );
+        let this = state.as_mut().unwrap();
+        assert!(!this.coinbase_receiver_check_is_due(0, secs(0)));
+        assert_eq!(
+            this.coinbase_receiver_check_start(0, v2::LedgerHash::zero(), secs(0)),
+            None
+        );
+
+        let mut this = with_coinbase_receiver();
+        assert!(this.coinbase_receiver_check_is_due(3, secs(0)));
+        let receiver = this
+            .coinbase_receiver_check_start(3, v2::LedgerHash::zero(), secs(0))
+            .unwrap();
+        assert_eq!(
+            receiver.to_string(),
+            "B62qrztYfPinaKqpXaYGY6QJ3SSW2NNKs7SajBLF1iFNXW9BoALN2Aq"
+        );
+        assert_eq!(this.coinbase_receiver_check_finish(None), Some(true));
+
+        assert!(!this.coinbase_receiver_check_is_due(3, secs(3600)));
+        assert!(this.coinbase_receiver_check_is_due(4, secs(1)));
+    }
+
+    #[test]
+    fn test_coinbase_receiver_check_is_retried_on_timeout() {
+        let mut this = with_coinbase_receiver();
+        this.coinbase_receiver_check_start(0, v2::LedgerHash::zero(), secs(10))
+            .unwrap();
+        assert!(!this.coinbase_receiver_check_is_due(0, secs(10)));
+        assert!(!this.coinbase_receiver_check_is_due(0, secs(69)));
+        assert!(this.coinbase_receiver_check_is_due(0, secs(70)));
+
+        this.coinbase_receiver_check_start(0, v2::LedgerHash::zero(), secs(70))
+            .unwrap();
+        let check = this.coinbase_receiver_check.as_ref().unwrap();
+        assert_eq!(check.time, secs(70));
+        assert_eq!(check.can_receive, None);
+    }
+
+    #[test]
+    fn test_coinbase_receiver_without_receive_permission_cannot_receive() {
+        let mut this = with_coinbase_receiver();
+        this.coinbase_receiver_check_start(0, v2::LedgerHash::zero(), secs(0))
+            .unwrap();
+        let mut account = Account::create();
+        assert_eq!(
+            this.coinbase_receiver_check_finish(Some(&account)),
+            Some(true)
+        );
+
+        account.permissions.receive = ledger::AuthRequired::Proof;
+        assert_eq!(
+            this.coinbase_receiver_check_finish(Some(&account)),
+            Some(false)
+        );
+        assert_eq!(
+            this.coinbase_receiver_check.as_ref().unwrap().can_receive,
+            Some(false)
+        );
+        // A completed check isn't retried.
+        assert!(!this.coinbase_receiver_check_is_due(0, secs(3600)));
+    }
+}
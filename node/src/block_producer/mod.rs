@@ -32,10 +32,29 @@ pub struct BlockProducerWonSlot {
     pub global_slot: v2::ConsensusGlobalSlotStableV1,
     pub vrf_output: Box<VrfOutput>,
     pub value_with_threshold: Option<(f64, f64)>,
+    pub stake_fraction: Option<f64>,
     // Staking ledger which was used during vrf evaluation.
     pub staking_ledger_hash: v2::LedgerHash,
 }
 
+/// Evaluation of the VRF which won the slot of a block we produced.
+///
+/// It's enough, together with the staking ledger, to check that the
+/// producer had the right to produce the block, without its private key:
+/// `vrf_output` is the truncated output included in the consensus state of
+/// the block, and the slot is won if its fractional value is below the
+/// threshold computed from the stake fraction.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct BlockProducerVrfEvaluation {
+    pub global_slot: u32,
+    pub delegator: (v2::NonZeroCurvePoint, AccountIndex),
+    pub staking_ledger_hash: v2::LedgerHash,
+    pub vrf_output: String,
+    pub vrf_output_fractional: f64,
+    pub threshold: Option<f64>,
+    pub stake_fraction: Option<f64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BlockWithoutProof {
     pub protocol_state: v2::MinaStateProtocolStateValueStableV2,
@@ -74,10 +93,23 @@ impl BlockProducerWonSlot {
             global_slot,
             vrf_output: won_slot.vrf_output.clone(),
             value_with_threshold: won_slot.value_with_threshold,
+            stake_fraction: won_slot.stake_fraction,
             staking_ledger_hash: staking_ledger_hash.clone(),
         }
     }
 
+    pub fn vrf_evaluation(&self) -> BlockProducerVrfEvaluation {
+        BlockProducerVrfEvaluation {
+            global_slot: self.global_slot(),
+            delegator: self.delegator.clone(),
+            staking_ledger_hash: self.staking_ledger_hash.clone(),
+            vrf_output: self.vrf_output.to_string(),
+            vrf_output_fractional: self.vrf_output.fractional(),
+            threshold: self.value_with_threshold.map(|(_, threshold)| threshold),
+            stake_fraction: self.stake_fraction,
+        }
+    }
+
     fn calculate_slot_time(genesis_timestamp: redux::Timestamp, slot: u32) -> redux::Timestamp {
         let per_block_ns = constraint_constants()
             .block_window_duration_ms
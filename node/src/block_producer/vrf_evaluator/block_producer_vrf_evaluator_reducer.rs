@@ -462,6 +462,13 @@ impl BlockProducerVrfEvaluatorState {
                     epoch: epoch_number,
                     initial_slot,
                 });
+                dispatcher.push(
+                    BlockProducerVrfEvaluatorEffectfulAction::DelegatorTableStats {
+                        epoch: epoch_number,
+                        staking_ledger_hash: staking_epoch_data.ledger.clone(),
+                        delegator_table: staking_epoch_data.delegator_table.clone(),
+                    },
+                );
                 if let Some(initial_slot) = state
                     .block_producer
                     .vrf_evaluator()
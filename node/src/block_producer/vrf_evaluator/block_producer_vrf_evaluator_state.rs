@@ -924,6 +924,7 @@ mod test {
                 global_slot: slot,
                 account_index: AccountIndex(0),
                 value_with_threshold: None,
+                stake_fraction: None,
             };
             (
                 slot,
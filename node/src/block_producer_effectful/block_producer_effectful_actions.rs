@@ -13,7 +13,9 @@ pub enum BlockProducerEffectfulAction {
         reason: BlockProducerWonSlotDiscardReason,
     },
     StagedLedgerDiffCreateInit,
-    StagedLedgerDiffCreateSuccess,
+    StagedLedgerDiffCreateSuccess {
+        work_limited_by_policy: bool,
+    },
     BlockUnprovenBuild,
     BlockProveInit,
     BlockProveSuccess,
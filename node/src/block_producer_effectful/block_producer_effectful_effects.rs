@@ -37,16 +37,11 @@ pub fn block_producer_effects<S: crate::Service>(
                     .staged_ledger_diff_create_start(meta.time());
             }
             let state = store.state.get();
-            let Some((won_slot, pred_block, producer, coinbase_receiver)) = None.or_else(|| {
+            let Some((won_slot, pred_block, config)) = None.or_else(|| {
                 let pred_block = state.block_producer.current_parent_chain()?.last()?;
                 let won_slot = state.block_producer.current_won_slot()?;
                 let config = state.block_producer.config()?;
-                Some((
-                    won_slot,
-                    pred_block,
-                    &config.pub_key,
-                    config.coinbase_receiver(),
-                ))
+                Some((won_slot, pred_block, config))
             }) else {
                 return;
             };
@@ -77,12 +72,13 @@ pub fn block_producer_effects<S: crate::Service>(
                     global_slot_since_genesis: won_slot
                         .global_slot_since_genesis(pred_block.global_slot_diff()),
                     is_new_epoch,
-                    producer: producer.clone(),
+                    producer: config.pub_key.clone(),
                     delegator: won_slot.delegator.0.clone(),
-                    coinbase_receiver: coinbase_receiver.clone(),
+                    coinbase_receiver: config.coinbase_receiver().clone(),
                     completed_snarks,
                     supercharge_coinbase,
                     transactions_by_fee,
+                    work_fee_policy: config.work_fee_policy,
                 },
                 on_init: redux::callback!(
                     on_staged_ledger_diff_create_init(_request: LedgerWriteRequest) -> crate::Action {
@@ -91,11 +87,13 @@ pub fn block_producer_effects<S: crate::Service>(
                 ),
             });
         }
-        BlockProducerEffectfulAction::StagedLedgerDiffCreateSuccess => {
+        BlockProducerEffectfulAction::StagedLedgerDiffCreateSuccess {
+            work_limited_by_policy,
+        } => {
             if let Some(stats) = store.service.stats() {
                 stats
                     .block_producer()
-                    .staged_ledger_diff_create_end(meta.time());
+                    .staged_ledger_diff_create_end(meta.time(), work_limited_by_policy);
             }
             store.dispatch(BlockProducerAction::BlockUnprovenBuild);
         }
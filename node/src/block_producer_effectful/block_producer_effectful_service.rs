@@ -20,6 +20,9 @@ pub struct StagedLedgerDiffCreateOutput {
     pub pending_coinbase_update: MinaBasePendingCoinbaseUpdateStableV1,
     pub pending_coinbase_witness: MinaBasePendingCoinbaseWitnessStableV2,
     pub stake_proof_sparse_ledger: MinaBaseSparseLedgerBaseStableV2,
+    /// Whether some available completed work wasn't included, because of
+    /// the [`crate::block_producer::BlockProducerWorkFeePolicy`].
+    pub work_limited_by_policy: bool,
 }
 
 pub trait BlockProducerService {
@@ -1,12 +1,27 @@
-use crate::block_producer::vrf_evaluator::VrfEvaluatorInput;
+use std::sync::Arc;
+
+use crate::block_producer::vrf_evaluator::{DelegatorTable, VrfEvaluatorInput};
 use mina_core::ActionEvent;
+use mina_p2p_messages::v2;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
 pub enum BlockProducerVrfEvaluatorEffectfulAction {
-    EvaluateSlot { vrf_input: VrfEvaluatorInput },
-    SlotEvaluated { epoch: u32 },
-    InitializeStats { epoch: u32, initial_slot: u32 },
+    EvaluateSlot {
+        vrf_input: VrfEvaluatorInput,
+    },
+    SlotEvaluated {
+        epoch: u32,
+    },
+    InitializeStats {
+        epoch: u32,
+        initial_slot: u32,
+    },
+    DelegatorTableStats {
+        epoch: u32,
+        staking_ledger_hash: v2::LedgerHash,
+        delegator_table: Arc<DelegatorTable>,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for BlockProducerVrfEvaluatorEffectfulAction {
@@ -4,7 +4,7 @@ use redux::ActionMeta;
 use super::BlockProducerVrfEvaluatorEffectfulAction;
 
 impl BlockProducerVrfEvaluatorEffectfulAction {
-    pub fn effects<S: Service>(self, _: &ActionMeta, store: &mut Store<S>) {
+    pub fn effects<S: Service>(self, meta: &ActionMeta, store: &mut Store<S>) {
         match self {
             BlockProducerVrfEvaluatorEffectfulAction::EvaluateSlot { vrf_input } => {
                 store.service.evaluate(vrf_input);
@@ -33,6 +33,37 @@ impl BlockProducerVrfEvaluatorEffectfulAction {
                         .new_epoch_evaluation(epoch, remaining_slots);
                 }
             }
+            BlockProducerVrfEvaluatorEffectfulAction::DelegatorTableStats {
+                epoch,
+                staking_ledger_hash,
+                delegator_table,
+            } => {
+                let Some(stats) = store.service.stats() else {
+                    return;
+                };
+                let Some(change) = stats.block_producer().new_epoch_delegators(
+                    epoch,
+                    &staking_ledger_hash,
+                    &delegator_table,
+                ) else {
+                    return;
+                };
+                mina_core::info!(
+                    meta.time();
+                    kind = "BlockProducerDelegationChange",
+                    summary = format!(
+                        "epoch {epoch}: {} delegators gained, {} lost, net stake change {}",
+                        change.gained_delegators.len(),
+                        change.lost_delegators.len(),
+                        change.net_stake_change,
+                    ),
+                    epoch = epoch,
+                    staking_ledger_hash = staking_ledger_hash.to_string(),
+                    delegators = change.delegators,
+                    total_stake = change.total_stake,
+                    net_stake_change = change.net_stake_change,
+                );
+            }
         }
     }
 }
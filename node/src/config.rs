@@ -43,6 +43,12 @@ pub struct SnarkerConfig {
     pub fee: CurrencyFeeStableV1,
     pub strategy: SnarkerStrategy,
     pub auto_commit: bool,
+    /// Http servers of other nodes to fetch jobs from. The idle worker
+    /// works on the job paying best among theirs, and submits the work
+    /// only to the node which offered it. Jobs of our own pool are worked
+    /// on when none of them offers a job.
+    #[serde(default)]
+    pub coordinators: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
@@ -114,6 +120,54 @@ impl BuildEnv {
     }
 }
 
+/// Optional subsystems the node was compiled with and runs, telling what a
+/// node can do when troubleshooting it.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct EnabledFeatures {
+    /// Cargo features of the node crate the build enabled.
+    pub compiled: Vec<String>,
+    /// Whether the node runs in the browser, built for webassembly.
+    pub wasm: bool,
+    pub block_producer: bool,
+    pub snarker: bool,
+    /// Storages the applied blocks are archived to, empty if archiving is
+    /// disabled.
+    pub archive: Vec<String>,
+}
+
+impl EnabledFeatures {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            compiled: Self::compiled().into_iter().map(str::to_owned).collect(),
+            wasm: cfg!(target_arch = "wasm32"),
+            block_producer: config.block_producer.is_some(),
+            snarker: config.global.snarker.is_some(),
+            archive: config
+                .archive
+                .as_ref()
+                .map(|archive| archive.storages.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn compiled() -> Vec<&'static str> {
+        [
+            ("p2p-webrtc", cfg!(feature = "p2p-webrtc")),
+            ("p2p-libp2p", cfg!(feature = "p2p-libp2p")),
+            ("replay", cfg!(feature = "replay")),
+            ("fuzzing", cfg!(feature = "fuzzing")),
+            (
+                "serializable_callbacks",
+                cfg!(feature = "serializable_callbacks"),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(feature, _)| feature)
+        .collect()
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 #[error("invalid strategy: {0}! expected one of: seq/sequential/rand/random")]
 pub struct SnarkerStrategyParseError(String);
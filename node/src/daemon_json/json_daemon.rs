@@ -1,12 +1,17 @@
-use ledger::scan_state::currency::Slot;
+use ledger::scan_state::{currency::Slot, ZkAppCommandLimits};
 use serde::{Deserialize, Serialize};
 
+use super::json_proof::{parse_nanomina, ProofConfigError};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Daemon {
     txpool_max_size: Option<usize>,
     peer_list_url: Option<String>,
     slot_tx_end: Option<u32>,
     slot_chain_end: Option<u32>,
+    max_event_elements: Option<usize>,
+    max_action_elements: Option<usize>,
+    minimum_user_command_fee: Option<String>,
 }
 
 impl Daemon {
@@ -15,6 +20,9 @@ impl Daemon {
         peer_list_url: None,
         slot_tx_end: None,
         slot_chain_end: None,
+        max_event_elements: None,
+        max_action_elements: None,
+        minimum_user_command_fee: None,
     };
 
     pub fn tx_pool_max_size(&self) -> usize {
@@ -33,4 +41,25 @@ impl Daemon {
     pub fn slot_chain_end(&self) -> Option<Slot> {
         self.slot_chain_end.map(Slot::from_u32)
     }
+
+    pub fn zkapp_command_limits(&self) -> ZkAppCommandLimits {
+        let default = ZkAppCommandLimits::default();
+        ZkAppCommandLimits {
+            max_event_elements: self
+                .max_event_elements
+                .unwrap_or(default.max_event_elements),
+            max_action_elements: self
+                .max_action_elements
+                .unwrap_or(default.max_action_elements),
+        }
+    }
+
+    /// Minimum fee of user commands, in nanomina, overriding the one in the
+    /// constraint constants of the network.
+    pub fn minimum_user_command_fee(&self) -> Result<Option<u64>, ProofConfigError> {
+        self.minimum_user_command_fee
+            .as_deref()
+            .map(parse_nanomina)
+            .transpose()
+    }
 }
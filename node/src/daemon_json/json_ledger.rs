@@ -1,19 +1,19 @@
 use core::str::FromStr;
 use mina_curves::pasta::Fp;
-use mina_p2p_messages::binprot::BinProtWrite;
+use mina_p2p_messages::{binprot::BinProtWrite, v2};
 use multihash::{Blake2b256, Hasher};
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
 use std::fmt::{self, Display, Formatter};
 
 use ledger::{
     scan_state::currency::{Amount, Balance, Magnitude, Nonce, Slot, SlotSpan, TxnVersion},
-    AuthRequired, Permissions, ReceiptChainHash, SetVerificationKey, Timing, TokenId, TokenSymbol,
-    VotingFor, ZkAppAccount, ZkAppUri,
+    AuthRequired, FpExt, Permissions, ReceiptChainHash, SetVerificationKey, Timing, TokenId,
+    TokenSymbol, VerificationKey, VerificationKeyWire, VotingFor, ZkAppAccount, ZkAppUri,
 };
 use mina_node_account::{AccountPublicKey, AccountSecretKey};
 
-use crate::ledger::LEDGER_DEPTH;
+use crate::ledger::ledger_depth;
 
 type RawCurrency = String;
 type RawSlot = String;
@@ -92,7 +92,7 @@ pub fn build_ledger_name(
     balances: impl Iterator<Item = (usize, RawCurrency)>,
 ) -> String {
     let mut hash = Blake2b256::default();
-    hash.update(LEDGER_DEPTH.to_string().as_bytes());
+    hash.update(ledger_depth().to_string().as_bytes());
     hash.update(num_accounts.to_string().as_bytes());
     let balances = balances.fold(String::new(), |acc, (i, balance)| {
         format!("{} {} {}", acc, i, balance)
@@ -115,8 +115,17 @@ pub struct Account {
     pub(super) balance: RawCurrency,
     delegate: Option<String>,
     token_id: Option<String>,
+    #[serde(
+        default,
+        serialize_with = "bytes_string_option",
+        deserialize_with = "string_or_bytes_option"
+    )]
     token_symbol: Option<Vec<u8>>,
-    #[serde(default, deserialize_with = "string_or_u32_option")]
+    #[serde(
+        default,
+        serialize_with = "u32_string_option",
+        deserialize_with = "string_or_u32_option"
+    )]
     nonce: Option<u32>,
     receipt_chain_hash: Option<String>,
     voting_for: Option<String>,
@@ -267,6 +276,62 @@ impl Account {
     }
 }
 
+/// Formats nanomina the way `of_mina_string_exn` parses them
+fn mina_string(nanomina: u64) -> RawCurrency {
+    format!(
+        "{}.{:09}",
+        nanomina / 1_000_000_000,
+        nanomina % 1_000_000_000
+    )
+}
+
+impl From<&ledger::Account> for Account {
+    /// The inverse of [`Account::to_account`]. The delegate of an account
+    /// of the default token defaults to its public key, so an account
+    /// without delegate reads back delegating to itself.
+    fn from(account: &ledger::Account) -> Self {
+        let timing = match &account.timing {
+            Timing::Untimed => None,
+            Timing::Timed {
+                initial_minimum_balance,
+                cliff_time,
+                cliff_amount,
+                vesting_period,
+                vesting_increment,
+            } => Some(AccountTiming {
+                initial_minimum_balance: mina_string(initial_minimum_balance.as_u64()),
+                cliff_time: GlobalSlotSinceGenesis(cliff_time.as_u32()),
+                cliff_amount: mina_string(cliff_amount.as_u64()),
+                vesting_period: GlobalSlotSpan(vesting_period.as_u32()),
+                vesting_increment: mina_string(vesting_increment.as_u64()),
+            }),
+        };
+        let is_default_token = account.token_id.is_default();
+
+        Account {
+            pk: AccountPublicKey::from(account.public_key.clone()).to_string(),
+            sk: None,
+            balance: mina_string(account.balance.as_u64()),
+            delegate: account
+                .delegate
+                .clone()
+                .filter(|_| is_default_token)
+                .map(|delegate| AccountPublicKey::from(delegate).to_string()),
+            token_id: (!is_default_token).then(|| account.token_id.0.to_decimal()),
+            token_symbol: Some(account.token_symbol.as_bytes().to_vec())
+                .filter(|symbol| !symbol.is_empty()),
+            nonce: Some(account.nonce.as_u32()),
+            receipt_chain_hash: Some(
+                v2::ReceiptChainHash::from(account.receipt_chain_hash.clone()).to_string(),
+            ),
+            voting_for: Some(account.voting_for.to_base58check()),
+            timing,
+            permissions: Some(AccountPermissions::from(&account.permissions)),
+            zkapp: account.zkapp.as_deref().map(Zkapp::from),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountTiming {
     initial_minimum_balance: RawCurrency,
@@ -296,7 +361,7 @@ impl AccountTiming {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct SetVrfKeyPerm {
     auth: AuthRequired,
-    #[serde(deserialize_with = "string_or_u32")]
+    #[serde(serialize_with = "u32_string", deserialize_with = "string_or_u32")]
     txn_version: u32,
 }
 
@@ -331,6 +396,75 @@ where
     }
 }
 
+/// Numbers are strings in the OCaml JSON, as their type may not fit a
+/// JSON number.
+fn u32_string<S>(value: &u32, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&value.to_string())
+}
+
+fn u32_string_option<S>(value: &Option<u32>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(value) => u32_string(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Token symbols and zkApp URIs are strings in the OCaml JSON. Arrays of
+/// bytes, written by earlier versions of the export, are still read.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrBytes {
+    String(String),
+    Bytes(Vec<u8>),
+}
+
+impl From<StringOrBytes> for Vec<u8> {
+    fn from(value: StringOrBytes) -> Self {
+        match value {
+            StringOrBytes::String(s) => s.into_bytes(),
+            StringOrBytes::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+fn string_or_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    StringOrBytes::deserialize(deserializer).map(Into::into)
+}
+
+fn string_or_bytes_option<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let opt: Option<StringOrBytes> = Option::deserialize(deserializer)?;
+    Ok(opt.map(Into::into))
+}
+
+fn bytes_string<S>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&String::from_utf8_lossy(value))
+}
+
+fn bytes_string_option<S>(value: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(value) => bytes_string(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountPermissions {
     access: Option<AuthRequired>,
@@ -373,18 +507,40 @@ impl AccountPermissions {
     }
 }
 
+impl From<&Permissions<AuthRequired>> for AccountPermissions {
+    fn from(permissions: &Permissions<AuthRequired>) -> Self {
+        AccountPermissions {
+            access: Some(permissions.access),
+            edit_state: Some(permissions.edit_state),
+            send: Some(permissions.send),
+            receive: Some(permissions.receive),
+            set_delegate: Some(permissions.set_delegate),
+            set_permissions: Some(permissions.set_permissions),
+            set_verification_key: SetVrfKeyPerm {
+                auth: permissions.set_verification_key.auth,
+                txn_version: permissions.set_verification_key.txn_version.as_u32(),
+            },
+            set_zkapp_uri: Some(permissions.set_zkapp_uri),
+            edit_action_state: Some(permissions.edit_action_state),
+            set_token_symbol: Some(permissions.set_token_symbol),
+            increment_nonce: Some(permissions.increment_nonce),
+            set_voting_for: Some(permissions.set_voting_for),
+            set_timing: Some(permissions.set_timing),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Zkapp {
     app_state: Vec<String>,
-    // These verification keys currently don't appear in configs, but
-    // they could in the future. We ignore them for now, but should they
-    // appear, serialization is likely to crash and we'll have to update
-    // this type and relevant conversion functions.
-    verification_key: Option<()>,
+    /// Base64 of the binprot encoding, as in the OCaml node
+    verification_key: Option<String>,
+    #[serde(serialize_with = "u32_string", deserialize_with = "string_or_u32")]
     zkapp_version: u32,
     action_state: Vec<String>,
     last_action_slot: RawSlot,
     proved_state: bool,
+    #[serde(serialize_with = "bytes_string", deserialize_with = "string_or_bytes")]
     zkapp_uri: Vec<u8>,
 }
 
@@ -427,12 +583,21 @@ impl Zkapp {
             .parse::<u32>()
             .map(Slot::from_u32)
             .map_err(|_| AccountConfigError::MalformedSlot(self.last_action_slot.clone()))?;
-        if self.verification_key.is_some() {
-            return Err(AccountConfigError::VerificationKeyParsingNotSupported);
-        };
+        let verification_key = self
+            .verification_key
+            .as_ref()
+            .map(|vk| {
+                let malformed = || AccountConfigError::MalformedVerificationKey(vk.clone());
+                let vk = v2::MinaBaseVerificationKeyWireStableV1::from_base64(vk)
+                    .map_err(|_| malformed())?;
+                VerificationKey::try_from(&vk)
+                    .map(VerificationKeyWire::new)
+                    .map_err(|_| malformed())
+            })
+            .transpose()?;
         Ok(ZkAppAccount {
             app_state,
-            verification_key: None,
+            verification_key,
             zkapp_version: self.zkapp_version,
             action_state,
             last_action_slot,
@@ -443,6 +608,24 @@ impl Zkapp {
     }
 }
 
+impl From<&ZkAppAccount> for Zkapp {
+    fn from(zkapp: &ZkAppAccount) -> Self {
+        Zkapp {
+            app_state: zkapp.app_state.iter().map(FpExt::to_decimal).collect(),
+            verification_key: zkapp.verification_key.as_ref().map(|vk| {
+                v2::MinaBaseVerificationKeyWireStableV1::from(vk.vk())
+                    .to_base64()
+                    .expect("failed to write verification key")
+            }),
+            zkapp_version: zkapp.zkapp_version,
+            action_state: zkapp.action_state.iter().map(FpExt::to_decimal).collect(),
+            last_action_slot: zkapp.last_action_slot.as_u32().to_string(),
+            proved_state: zkapp.proved_state,
+            zkapp_uri: zkapp.zkapp_uri.to_vec(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum AccountConfigError {
     MalformedCurrencyValue(String),
@@ -453,14 +636,23 @@ pub enum AccountConfigError {
     MalformedSlot(String),
     MalformedFp(String),
     ZkAppStateTooLong(Vec<String>),
-    VerificationKeyParsingNotSupported,
+    MalformedVerificationKey(String),
     DelegateSetOnNonDefaultTokenAccount,
     InvalidBigInt,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone)]
 struct GlobalSlotSinceGenesis(u32);
 
+impl Serialize for GlobalSlotSinceGenesis {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0.to_string())
+    }
+}
+
 impl<'de> Deserialize<'de> for GlobalSlotSinceGenesis {
     fn deserialize<D>(deserializer: D) -> Result<GlobalSlotSinceGenesis, D::Error>
     where
@@ -501,9 +693,18 @@ impl<'de> Deserialize<'de> for GlobalSlotSinceGenesis {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone)]
 struct GlobalSlotSpan(u32);
 
+impl Serialize for GlobalSlotSpan {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0.to_string())
+    }
+}
+
 impl<'de> Deserialize<'de> for GlobalSlotSpan {
     fn deserialize<D>(deserializer: D) -> Result<GlobalSlotSpan, D::Error>
     where
@@ -563,8 +764,8 @@ impl Display for AccountConfigError {
             Self::ZkAppStateTooLong(app_state) => {
                 write!(f, "zkapp app state too long ('{:?}')", app_state)
             }
-            Self::VerificationKeyParsingNotSupported => {
-                write!(f, "verification key parsing not supported yet!")
+            Self::MalformedVerificationKey(vk) => {
+                write!(f, "malformed verification key ('{}')", vk)
             }
             Self::DelegateSetOnNonDefaultTokenAccount => {
                 write!(f, "delegate set on non-default token account")
@@ -0,0 +1,184 @@
+use std::fmt::{self, Display, Formatter};
+
+use mina_core::constants::ConstraintConstants;
+use serde::{Deserialize, Serialize};
+
+type RawCurrency = String;
+
+/// The `proof` section of daemon.json, describing the constraint constants.
+///
+/// All the constants, including the coinbase amount, supercharged coinbase
+/// factor and account creation fee, are compiled into the transaction and
+/// blockchain circuits. Since we can't generate the circuits ourselves and
+/// the verifier indices are those of the selected network, the values must
+/// match the constants of that network.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Proof {
+    level: Option<String>,
+    sub_windows_per_window: Option<u64>,
+    ledger_depth: Option<u64>,
+    work_delay: Option<u64>,
+    block_window_duration_ms: Option<u64>,
+    transaction_capacity: Option<TransactionCapacity>,
+    coinbase_amount: Option<RawCurrency>,
+    supercharged_coinbase_factor: Option<u64>,
+    account_creation_fee: Option<RawCurrency>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TransactionCapacity {
+    #[serde(rename = "2_to_the")]
+    TwoToThe(u64),
+    #[serde(rename = "txns_per_second_x10")]
+    TxnsPerSecondX10(u64),
+}
+
+impl Proof {
+    /// Returns the constants of this config, which must be those of `base`.
+    pub fn constraint_constants(
+        &self,
+        base: &ConstraintConstants,
+    ) -> Result<ConstraintConstants, ProofConfigError> {
+        if let Some(level) = self.level.as_deref() {
+            if level != "full" {
+                return Err(ProofConfigError::UnsupportedProofLevel(level.to_owned()));
+            }
+        }
+
+        check_unchanged(
+            "sub_windows_per_window",
+            base.sub_windows_per_window,
+            self.sub_windows_per_window,
+        )?;
+        check_unchanged("ledger_depth", base.ledger_depth, self.ledger_depth)?;
+        check_unchanged("work_delay", base.work_delay, self.work_delay)?;
+        check_unchanged(
+            "block_window_duration_ms",
+            base.block_window_duration_ms,
+            self.block_window_duration_ms,
+        )?;
+        match &self.transaction_capacity {
+            None => {}
+            Some(TransactionCapacity::TwoToThe(log_2)) => check_unchanged(
+                "transaction_capacity.2_to_the",
+                base.transaction_capacity_log_2,
+                Some(*log_2),
+            )?,
+            Some(TransactionCapacity::TxnsPerSecondX10(_)) => {
+                return Err(ProofConfigError::UnsupportedOverride {
+                    name: "transaction_capacity.txns_per_second_x10",
+                    expected: format!("2_to_the: {}", base.transaction_capacity_log_2),
+                    found: "txns_per_second_x10".to_owned(),
+                })
+            }
+        }
+
+        check_unchanged_amount(
+            "coinbase_amount",
+            base.coinbase_amount,
+            &self.coinbase_amount,
+        )?;
+        check_unchanged(
+            "supercharged_coinbase_factor",
+            base.supercharged_coinbase_factor,
+            self.supercharged_coinbase_factor,
+        )?;
+        check_unchanged_amount(
+            "account_creation_fee",
+            base.account_creation_fee,
+            &self.account_creation_fee,
+        )?;
+
+        Ok(base.clone())
+    }
+}
+
+fn check_unchanged(
+    name: &'static str,
+    expected: u64,
+    found: Option<u64>,
+) -> Result<(), ProofConfigError> {
+    match found {
+        Some(found) if found != expected => Err(ProofConfigError::UnsupportedOverride {
+            name,
+            expected: expected.to_string(),
+            found: found.to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn check_unchanged_amount(
+    name: &'static str,
+    expected: u64,
+    found: &Option<RawCurrency>,
+) -> Result<(), ProofConfigError> {
+    let Some(raw) = found else {
+        return Ok(());
+    };
+    let found = parse_nanomina(raw)?;
+    if found != expected {
+        return Err(ProofConfigError::UnsupportedOverride {
+            name,
+            expected: format!("{expected} nanomina"),
+            found: format!("{found} nanomina"),
+        });
+    }
+    Ok(())
+}
+
+/// Parses a currency value formatted in MINA (e.g. `"720"` or `"0.5"`) into nanomina.
+///
+/// Same format as `of_mina_string_exn`, but returns an error instead of panicking.
+pub(super) fn parse_nanomina(raw: &str) -> Result<u64, ProofConfigError> {
+    const PRECISION: usize = 9;
+    let malformed = || ProofConfigError::MalformedCurrencyValue(raw.to_owned());
+
+    let (whole, decimal) = raw.split_once('.').unwrap_or((raw, ""));
+    if whole.is_empty()
+        || decimal.len() > PRECISION
+        || !whole
+            .chars()
+            .chain(decimal.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(malformed());
+    }
+    format!("{whole}{decimal:0<width$}", width = PRECISION)
+        .parse::<u64>()
+        .map_err(|_| malformed())
+}
+
+#[derive(Debug, Clone)]
+pub enum ProofConfigError {
+    MalformedCurrencyValue(String),
+    UnsupportedProofLevel(String),
+    UnsupportedOverride {
+        name: &'static str,
+        expected: String,
+        found: String,
+    },
+}
+
+impl Display for ProofConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Proof configuration error encountered in JSON config: ")?;
+        match self {
+            Self::MalformedCurrencyValue(c) => write!(f, "malformed currency value ('{}')", c),
+            Self::UnsupportedProofLevel(level) => {
+                write!(f, "unsupported proof level ('{}'), only 'full' is supported", level)
+            }
+            Self::UnsupportedOverride {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "overriding '{}' is not supported, as it changes the circuits (network value: {}, found: {})",
+                name, expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProofConfigError {}
@@ -3,12 +3,14 @@ use serde::{Deserialize, Serialize};
 mod json_daemon;
 mod json_genesis;
 mod json_ledger;
+mod json_proof;
 pub use json_daemon::Daemon;
 pub use json_genesis::Genesis;
 pub use json_ledger::{
     build_ledger_name, Account, AccountConfigError, AccountPermissions, AccountTiming, Ledger,
     Zkapp,
 };
+pub use json_proof::{Proof, ProofConfigError, TransactionCapacity};
 
 /// This type represents a JSON object loaded from daemon.json
 /// file. It does not describe its full structure, as it's not
@@ -25,6 +27,7 @@ pub struct DaemonJson {
     pub daemon: Option<Daemon>,
     pub ledger: Option<Ledger>,
     pub genesis: Option<Genesis>,
+    pub proof: Option<Proof>,
     pub epoch_data: Option<Epochs>,
 }
 
@@ -61,11 +64,14 @@ impl EpochData {
 #[cfg(test)]
 mod test {
 
-    use ledger::{scan_state::currency::Balance, Timing};
+    use ledger::{
+        scan_state::{currency::Balance, ZkAppCommandLimits},
+        Timing,
+    };
     use mina_node_account::AccountPublicKey;
     use std::str::FromStr;
 
-    use crate::daemon_json::DaemonJson;
+    use crate::daemon_json::{Daemon, DaemonJson, Proof, ProofConfigError};
 
     #[test]
     fn test_daemon_json_read() {
@@ -109,5 +115,78 @@ mod test {
         assert_eq!(daemon.peer_list_url(), None);
         assert_eq!(daemon.slot_tx_end(), None);
         assert_eq!(daemon.slot_chain_end(), None);
+        assert_eq!(daemon.zkapp_command_limits(), ZkAppCommandLimits::default());
+    }
+
+    #[test]
+    fn test_daemon_json_minimum_user_command_fee() {
+        let test_file = std::fs::File::open("testing/data/daemon.json").unwrap();
+        let daemon_json: DaemonJson = serde_json::from_reader(test_file).unwrap();
+        let daemon = daemon_json.daemon.unwrap();
+        assert_eq!(daemon.minimum_user_command_fee().unwrap(), None);
+
+        let daemon: Daemon = serde_json::from_value(serde_json::json!({
+            "minimum_user_command_fee": "0.002"
+        }))
+        .unwrap();
+        assert_eq!(daemon.minimum_user_command_fee().unwrap(), Some(2_000_000));
+
+        let daemon: Daemon = serde_json::from_value(serde_json::json!({
+            "minimum_user_command_fee": "0.00x"
+        }))
+        .unwrap();
+        assert!(daemon.minimum_user_command_fee().is_err());
+    }
+
+    #[test]
+    fn test_daemon_json_proof_constants() {
+        let base = &mina_core::network::devnet::CONSTRAINT_CONSTANTS;
+        let test_file = std::fs::File::open("testing/data/daemon.json").unwrap();
+        let daemon_json: DaemonJson = serde_json::from_reader(test_file).unwrap();
+        let constants = daemon_json
+            .proof
+            .unwrap()
+            .constraint_constants(base)
+            .unwrap();
+        assert_eq!(constants.coinbase_amount, 720_000_000_000);
+        assert_eq!(constants.account_creation_fee, 1_000_000_000);
+        assert_eq!(constants.supercharged_coinbase_factor, 1);
+
+        // Compiled into the circuits, like the other constants.
+        for (name, value) in [
+            ("coinbase_amount", serde_json::json!("0.5")),
+            ("supercharged_coinbase_factor", serde_json::json!(2)),
+            ("account_creation_fee", serde_json::json!("0.001")),
+        ] {
+            let proof: Proof = serde_json::from_value(serde_json::json!({ name: value })).unwrap();
+            assert!(
+                matches!(
+                    proof.constraint_constants(base),
+                    Err(ProofConfigError::UnsupportedOverride { name: found, .. }) if found == name
+                ),
+                "{name}"
+            );
+        }
+        // The same value in another format isn't an override.
+        let proof: Proof =
+            serde_json::from_value(serde_json::json!({ "account_creation_fee": "1.000" })).unwrap();
+        assert!(proof.constraint_constants(base).is_ok());
+
+        let proof: Proof =
+            serde_json::from_value(serde_json::json!({ "ledger_depth": 20 })).unwrap();
+        assert!(matches!(
+            proof.constraint_constants(base),
+            Err(ProofConfigError::UnsupportedOverride {
+                name: "ledger_depth",
+                ..
+            })
+        ));
+
+        let proof: Proof =
+            serde_json::from_value(serde_json::json!({ "account_creation_fee": "1.x" })).unwrap();
+        assert!(matches!(
+            proof.constraint_constants(base),
+            Err(ProofConfigError::MalformedCurrencyValue(_))
+        ));
     }
 }
@@ -12,10 +12,16 @@ use crate::{
     p2p::node_p2p_effects,
     p2p_ready,
     rpc_effectful::rpc_effects,
-    snark::snark_effects,
+    snark::{
+        block_verify::SnarkBlockVerifyAction, snark_effects,
+        user_command_verify::SnarkUserCommandVerifyAction, work_verify::SnarkWorkVerifyAction,
+    },
     snark_pool::{candidate::SnarkPoolCandidateAction, snark_pool_effects, SnarkPoolAction},
     transaction_pool::candidate::TransactionPoolCandidateAction,
-    transition_frontier::{genesis::TransitionFrontierGenesisAction, transition_frontier_effects},
+    transition_frontier::{
+        genesis::TransitionFrontierGenesisAction, sync::TransitionFrontierSyncAction,
+        transition_frontier_effects, TransitionFrontierAction,
+    },
     Action, ActionWithMeta, ExternalSnarkWorkerAction, Service, Store, TransactionPoolAction,
 };
 
@@ -27,7 +33,9 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
     let (action, meta) = action.split();
 
     if let Some(stats) = store.service.stats() {
-        stats.new_action(action.kind(), meta.clone());
+        stats
+            .new_action(action.kind(), meta.clone())
+            .peer_contribution(meta.time(), &action);
     }
 
     logger_effects(store, meta.clone().with_action(&action));
@@ -48,6 +56,10 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
             }
 
             store.dispatch(TransactionPoolAction::P2pSendAll);
+            store.dispatch(TransactionPoolAction::RebroadcastLocallyGenerated);
+            store.dispatch(TransactionPoolAction::RemoveStale);
+            store.dispatch(TransactionPoolAction::RemoveExpired);
+            store.dispatch(TransactionPoolAction::ZkappRelayCheck);
             store.dispatch(TransactionPoolCandidateAction::FetchAll);
             store.dispatch(TransactionPoolCandidateAction::VerifyNext);
 
@@ -59,10 +71,17 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
 
             store.dispatch(ExternalSnarkWorkerAction::StartTimeout { now: meta.time() });
             store.dispatch(ExternalSnarkWorkerAction::WorkTimeout { now: meta.time() });
+            store
+                .dispatch(ExternalSnarkWorkerAction::CoordinatorJobsFetchInit { now: meta.time() });
 
             store.dispatch(BlockProducerAction::WonSlotProduceInit);
             store.dispatch(BlockProducerAction::BlockInject);
+            store.dispatch(BlockProducerAction::CoinbaseReceiverCheckInit);
             store.dispatch(LedgerReadAction::FindTodos);
+            check_request_timeouts(store, meta.time());
+
+            store.dispatch(TransitionFrontierSyncAction::BlocksBucketQuery);
+            store.dispatch(TransitionFrontierAction::LedgerHashesCheckInit);
         }
         Action::EventSource(action) => {
             event_source_effects(store, meta.with_action(action));
@@ -74,6 +93,8 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
             action.effects(store);
         }
         Action::TransitionFrontier(action) => {
+            let sync = &store.state.get().transition_frontier.sync;
+            store.service.sync_state_update(sync);
             transition_frontier_effects(store, meta.with_action(action));
         }
         Action::P2pEffectful(action) => {
@@ -108,6 +129,45 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
     }
 }
 
+/// Retries or fails the verifications and ledger reads which the services
+/// didn't answer in time.
+fn check_request_timeouts<S: Service>(store: &mut Store<S>, now: redux::Timestamp) {
+    let state = store.state();
+    let block_verify = state
+        .snark
+        .block_verify
+        .timed_out_jobs(now)
+        .collect::<Vec<_>>();
+    let work_verify = state
+        .snark
+        .work_verify
+        .timed_out_jobs(now)
+        .collect::<Vec<_>>();
+    let user_command_verify = state
+        .snark
+        .user_command_verify
+        .timed_out_jobs(now)
+        .collect::<Vec<_>>();
+    let ledger_read = state
+        .ledger
+        .read
+        .timed_out_requests(now)
+        .collect::<Vec<_>>();
+
+    for req_id in block_verify {
+        store.dispatch(SnarkBlockVerifyAction::Timeout { req_id });
+    }
+    for req_id in work_verify {
+        store.dispatch(SnarkWorkVerifyAction::Timeout { req_id });
+    }
+    for req_id in user_command_verify {
+        store.dispatch(SnarkUserCommandVerifyAction::Timeout { req_id });
+    }
+    for id in ledger_read {
+        store.dispatch(LedgerReadAction::Timeout { id });
+    }
+}
+
 fn p2p_request_best_tip_if_needed<S: Service>(store: &mut Store<S>) {
     // TODO(binier): refactor
     let state = store.state();
@@ -158,12 +218,24 @@ fn request_best_tip<S: Service>(store: &mut Store<S>, consensus_best_tip_hash: O
     }
 }
 
+/// Requests transaction infos (hash, fee payer, fee and nonce) from peers.
+///
+/// Peers send the infos of their whole pool, starting with the oldest
+/// transaction, so this also syncs the pool with newly connected peers. The
+/// transactions themselves are then fetched highest fee first, see
+/// [`TransactionPoolCandidateAction::FetchAll`].
+///
+/// Nothing is requested while syncing, as the transactions couldn't be
+/// verified against our best tip anyway.
 fn p2p_request_transactions_if_needed<S: Service>(store: &mut Store<S>) {
     use p2p::channels::transaction::P2pChannelsTransactionAction;
 
     const MAX_PEER_PENDING_TXS: usize = 32;
 
     let state = store.state();
+    if state.transition_frontier.sync.is_pending() {
+        return;
+    }
     let p2p = p2p_ready!(
         state.p2p,
         "p2p_request_transactions_if_needed",
@@ -1,12 +1,21 @@
 use serde::{Deserialize, Serialize};
 
+use crate::p2p::{
+    channels::{
+        best_tip::BestTipPropagationChannelMsg, snark::SnarkPropagationChannelMsg,
+        snark_job_commitment::SnarkJobCommitmentPropagationChannelMsg,
+        transaction::TransactionPropagationChannelMsg, ChannelMsg,
+    },
+    P2pChannelEvent, PeerId,
+};
 pub use crate::{
     block_producer::BlockProducerEvent,
     external_snark_worker_effectful::ExternalSnarkWorkerEvent,
     ledger::LedgerEvent,
-    p2p::{P2pConnectionEvent, P2pEvent},
+    p2p::{peer::P2pPeerListEvent, P2pConnectionEvent, P2pEvent},
     rpc::{RpcId, RpcRequest},
     snark::SnarkEvent,
+    transition_frontier::sync::{ledger::LedgerSnapshotEvent, PrecomputedBlockEvent},
 };
 
 use crate::transition_frontier::genesis::GenesisConfigLoaded;
@@ -19,10 +28,141 @@ pub enum Event {
     Rpc(RpcId, Box<RpcRequest>),
     ExternalSnarkWorker(ExternalSnarkWorkerEvent),
     BlockProducerEvent(BlockProducerEvent),
+    PeerList(P2pPeerListEvent),
+    LedgerSnapshot(LedgerSnapshotEvent),
+    PrecomputedBlock(PrecomputedBlockEvent),
 
     GenesisLoad(Result<GenesisConfigLoaded, String>),
 }
 
+/// Priority of an event in the service's event queue, highest first.
+///
+/// Only the payload of WebRTC propagation channels (blocks, snarks,
+/// commitments and transactions) has lower priority than
+/// [`EventPriority::Critical`], based on the channel it was received on.
+/// Those channels are pull based, so such a message can be shed under load
+/// and replaced by a [`P2pChannelEvent::Shed`], which lets the channel move
+/// on without its content.
+///
+/// Data of libp2p connections is critical: it's encrypted and multiplexed,
+/// so the service can't tell which stream or gossipsub topic it belongs to,
+/// and dropping it would break the connection along with its rpcs. It's
+/// read from a socket only once the previous read was handled, so a
+/// connection never has more than one read queued.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EventPriority {
+    /// Consensus/VRF, service responses, rpcs, connection events and
+    /// control messages of channels. Never dropped.
+    Critical,
+    Block,
+    Work,
+    Transaction,
+}
+
+/// Number of events dropped from the event queue, per priority.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub struct EventsDropped {
+    pub block: u64,
+    pub work: u64,
+    pub transaction: u64,
+}
+
+impl EventsDropped {
+    pub fn increment(&mut self, priority: EventPriority) {
+        let counter = match priority {
+            EventPriority::Critical => return,
+            EventPriority::Block => &mut self.block,
+            EventPriority::Work => &mut self.work,
+            EventPriority::Transaction => &mut self.transaction,
+        };
+        *counter = counter.saturating_add(1);
+    }
+
+    pub fn total(&self) -> u64 {
+        self.block
+            .saturating_add(self.work)
+            .saturating_add(self.transaction)
+    }
+}
+
+impl Event {
+    pub fn priority(&self) -> EventPriority {
+        let Self::P2p(P2pEvent::Channel(P2pChannelEvent::Received(_, Ok(msg)))) = self else {
+            return EventPriority::Critical;
+        };
+        match msg {
+            ChannelMsg::BestTipPropagation(BestTipPropagationChannelMsg::BestTip(_)) => {
+                EventPriority::Block
+            }
+            ChannelMsg::SnarkPropagation(
+                SnarkPropagationChannelMsg::Snark(_) | SnarkPropagationChannelMsg::Snarks(_),
+            )
+            | ChannelMsg::SnarkJobCommitmentPropagation(
+                SnarkJobCommitmentPropagationChannelMsg::Commitment(_),
+            ) => EventPriority::Work,
+            ChannelMsg::TransactionPropagation(TransactionPropagationChannelMsg::Transaction(
+                _,
+            )) => EventPriority::Transaction,
+            _ => EventPriority::Critical,
+        }
+    }
+
+    /// Peer of the WebRTC connection the event belongs to. Events of a peer
+    /// are handled in the order they were received, regardless of their
+    /// priority.
+    pub fn webrtc_peer_id(&self) -> Option<PeerId> {
+        let Self::P2p(event) = self else {
+            return None;
+        };
+        match event {
+            P2pEvent::Connection(event) => match event {
+                P2pConnectionEvent::OfferSdpReady(peer_id, _)
+                | P2pConnectionEvent::AnswerSdpReady(peer_id, _)
+                | P2pConnectionEvent::AnswerReceived(peer_id, _)
+                | P2pConnectionEvent::Finalized(peer_id, _)
+                | P2pConnectionEvent::Closed(peer_id)
+                | P2pConnectionEvent::Stats(peer_id, _) => Some(*peer_id),
+                P2pConnectionEvent::HostResolved(..) | P2pConnectionEvent::DnsaddrResolved(..) => {
+                    None
+                }
+            },
+            P2pEvent::Channel(event) => match event {
+                P2pChannelEvent::Opened(peer_id, ..)
+                | P2pChannelEvent::Sent(peer_id, ..)
+                | P2pChannelEvent::Received(peer_id, _)
+                | P2pChannelEvent::Shed(peer_id, ..)
+                | P2pChannelEvent::Closed(peer_id, _) => Some(*peer_id),
+            },
+            P2pEvent::MioEvent(_) => None,
+        }
+    }
+
+    /// Replacement for a shed event, or `None` for events that are never
+    /// shed.
+    ///
+    /// Propagation channels are request/response based, so silently
+    /// dropping a message would leave the channel waiting for it forever,
+    /// and disconnecting the peer would punish it for our own load. The
+    /// replacement tells the channel how many items the message carried,
+    /// so that it can request the next ones.
+    pub fn shed(&self) -> Option<Event> {
+        if self.priority() == EventPriority::Critical {
+            return None;
+        }
+        let Self::P2p(P2pEvent::Channel(P2pChannelEvent::Received(peer_id, Ok(msg)))) = self else {
+            return None;
+        };
+        let count = match msg {
+            ChannelMsg::SnarkPropagation(SnarkPropagationChannelMsg::Snarks(snarks)) => {
+                snarks.len()
+            }
+            _ => 1,
+        };
+        let event = P2pChannelEvent::Shed(*peer_id, msg.channel_id(), count);
+        Some(P2pEvent::Channel(event).into())
+    }
+}
+
 impl std::fmt::Display for Event {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -37,7 +177,10 @@ impl std::fmt::Display for Event {
                     RpcRequest::HeartbeatGet => write!(f, "HeartbeatGet"),
                     RpcRequest::ActionStatsGet(query) => write!(f, "ActionStatsGet, {query:?}"),
                     RpcRequest::SyncStatsGet(query) => write!(f, "SyncStatsGet, {query:?}"),
+                    RpcRequest::SyncHistoryGet => write!(f, "SyncHistoryGet"),
                     RpcRequest::BlockProducerStatsGet => write!(f, "BlockProducerStatsGet"),
+                    RpcRequest::PeerContributionStatsGet => write!(f, "PeerContributionStatsGet"),
+                    RpcRequest::ArchiveUploadStatsGet => write!(f, "ArchiveUploadStatsGet"),
                     RpcRequest::PeersGet => write!(f, "PeersGet"),
                     RpcRequest::MessageProgressGet => write!(f, "MessageProgressGet"),
                     RpcRequest::P2pConnectionOutgoing(opts) => {
@@ -60,31 +203,75 @@ impl std::fmt::Display for Event {
                         write!(f, "SnarkerJobCommit, {job_id}")
                     }
                     RpcRequest::SnarkerJobSpec { job_id } => write!(f, "SnarkerJobSpec, {job_id}"),
+                    RpcRequest::SnarkerWorkSubmit { snark } => {
+                        write!(f, "SnarkerWorkSubmit, {}", snark.job_id())
+                    }
                     RpcRequest::SnarkerWorkers => write!(f, "SnarkerWorkers"),
                     RpcRequest::HealthCheck => write!(f, "HealthCheck"),
                     RpcRequest::ReadinessCheck => write!(f, "ReadinessCheck"),
                     RpcRequest::DiscoveryRoutingTable => write!(f, "DiscoveryRoutingTable"),
                     RpcRequest::DiscoveryBoostrapStats => write!(f, "DiscoveryBoostrapStats"),
                     RpcRequest::TransactionPoolGet => write!(f, "TransactionPool"),
+                    RpcRequest::TransactionPoolSenderQueuesGet(public_key) => {
+                        write!(f, "TransactionPoolSenderQueuesGet, {public_key:?}")
+                    }
                     RpcRequest::LedgerAccountsGet(account_query) => {
                         write!(f, "LedgerAccountsGet, {account_query:?}")
                     }
+                    RpcRequest::LedgerAccountsPageGet(..) => write!(f, "LedgerAccountsPageGet"),
+                    RpcRequest::LedgerAccountsAtBlockGet(..) => {
+                        write!(f, "LedgerAccountsAtBlockGet")
+                    }
                     RpcRequest::TransactionInject(..) => write!(f, "TransactionInject"),
                     RpcRequest::TransitionFrontierUserCommandsGet => {
                         write!(f, "TransitionFrontierUserCommandsGet")
                     }
                     RpcRequest::BestChain(..) => write!(f, "BestChain"),
+                    RpcRequest::BestChainPageGet(..) => write!(f, "BestChainPageGet"),
                     RpcRequest::ConsensusConstantsGet => write!(f, "ConsensusConstantsGet"),
                     RpcRequest::TransactionStatusGet(..) => write!(f, "TransactionStatusGet"),
                     RpcRequest::GetBlock(..) => write!(f, "GetBlock"),
+                    RpcRequest::BlockSummaryGet(..) => write!(f, "BlockSummaryGet"),
                     RpcRequest::PooledUserCommands(..) => write!(f, "PooledUserCommands"),
                     RpcRequest::PooledZkappCommands(..) => write!(f, "PooledZkappCommands"),
+                    RpcRequest::PooledUserCommandsPageGet(..) => {
+                        write!(f, "PooledUserCommandsPageGet")
+                    }
+                    RpcRequest::PooledZkappCommandsPageGet(..) => {
+                        write!(f, "PooledZkappCommandsPageGet")
+                    }
+                    RpcRequest::ZkappEventsPageGet(..) => write!(f, "ZkappEventsPageGet"),
+                    RpcRequest::ZkappActionsEventsGet(..) => write!(f, "ZkappActionsEventsGet"),
+                    RpcRequest::TokenSupplyGet(..) => write!(f, "TokenSupplyGet"),
                     RpcRequest::GenesisBlockGet => write!(f, "GenesisBlock"),
                     RpcRequest::ConsensusTimeGet(..) => write!(f, "ConsensusTimeGet"),
                     RpcRequest::LedgerStatusGet(..) => write!(f, "LedgerStatusGet"),
                     RpcRequest::LedgerAccountDelegatorsGet(..) => {
                         write!(f, "LedgerAccountDelegatorsGet")
                     }
+                    RpcRequest::LedgerSnapshotGet(query) => {
+                        write!(f, "LedgerSnapshotGet, {query}")
+                    }
+                    RpcRequest::BlockInject(..) => write!(f, "BlockInject"),
+                    RpcRequest::ConsensusForkDecisionsGet => {
+                        write!(f, "ConsensusForkDecisionsGet")
+                    }
+                    RpcRequest::PendingCoinbaseGet(..) => write!(f, "PendingCoinbaseGet"),
+                    RpcRequest::PeerListRefresh => write!(f, "PeerListRefresh"),
+                    RpcRequest::GossipPause { kind, paused } => {
+                        write!(f, "GossipPause, {kind}, {paused}")
+                    }
+                    RpcRequest::SyncDiagnosisGet => write!(f, "SyncDiagnosisGet"),
+                    RpcRequest::NodeOverviewGet => write!(f, "NodeOverviewGet"),
+                    RpcRequest::LocallyGeneratedCommandsGet => {
+                        write!(f, "LocallyGeneratedCommandsGet")
+                    }
+                    RpcRequest::PendingRequestsGet => write!(f, "PendingRequestsGet"),
+                    RpcRequest::SnarkStatsGet => write!(f, "SnarkStatsGet"),
+                    RpcRequest::SnarkVerifierDigestsGet => write!(f, "SnarkVerifierDigestsGet"),
+                    RpcRequest::SnarkVerifyHistoryGet => write!(f, "SnarkVerifyHistoryGet"),
+                    RpcRequest::SnarkVerifyDryRun(..) => write!(f, "SnarkVerifyDryRun"),
+                    RpcRequest::EnabledFeaturesGet => write!(f, "EnabledFeaturesGet"),
                 }
             }
             Self::ExternalSnarkWorker(event) => {
@@ -97,9 +284,43 @@ impl std::fmt::Display for Event {
                     ExternalSnarkWorkerEvent::WorkError(_) => write!(f, "WorkError"),
                     ExternalSnarkWorkerEvent::WorkCancelled => write!(f, "WorkCancelled"),
                     ExternalSnarkWorkerEvent::Error(_) => write!(f, "Error"),
+                    ExternalSnarkWorkerEvent::CoordinatorSubmitResult {
+                        url,
+                        job_id,
+                        result,
+                        ..
+                    } => {
+                        write!(f, "CoordinatorSubmitResult, {url}, {job_id}, ")?;
+                        match result {
+                            Ok(response) => write!(f, "{response:?}"),
+                            Err(_) => write!(f, "Err"),
+                        }
+                    }
+                    ExternalSnarkWorkerEvent::CoordinatorJobsResult { url, result } => {
+                        write!(f, "CoordinatorJobsResult, {url}, ")?;
+                        match result {
+                            Ok(Some(offer)) => write!(f, "{}", offer.job_id),
+                            Ok(None) => write!(f, "None"),
+                            Err(_) => write!(f, "Err"),
+                        }
+                    }
+                    ExternalSnarkWorkerEvent::CoordinatorJobSpecResult {
+                        url,
+                        job_id,
+                        result,
+                    } => {
+                        write!(f, "CoordinatorJobSpecResult, {url}, {job_id}, ")?;
+                        match result {
+                            Ok(_) => write!(f, "Ok"),
+                            Err(_) => write!(f, "Err"),
+                        }
+                    }
                 }
             }
             Self::BlockProducerEvent(event) => event.fmt(f),
+            Self::PeerList(event) => event.fmt(f),
+            Self::LedgerSnapshot(event) => event.fmt(f),
+            Self::PrecomputedBlock(event) => event.fmt(f),
             Self::GenesisLoad(res) => {
                 write!(f, "GenesisLoad, ")?;
                 match res {
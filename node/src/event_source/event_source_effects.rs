@@ -7,7 +7,7 @@ use p2p::channels::{
     streaming_rpc::P2pChannelsStreamingRpcAction,
     transaction::P2pChannelsTransactionAction,
 };
-use snark::user_command_verify::{SnarkUserCommandVerifyAction, SnarkUserCommandVerifyError};
+use snark::user_command_verify::SnarkUserCommandVerifyAction;
 
 #[cfg(feature = "p2p-libp2p")]
 use crate::p2p::{MioEvent, P2pNetworkSchedulerAction};
@@ -23,18 +23,29 @@ use crate::{
         channels::{
             best_tip::P2pChannelsBestTipAction, rpc::P2pChannelsRpcAction,
             snark_job_commitment::P2pChannelsSnarkJobCommitmentAction, ChannelId,
-            P2pChannelsMessageReceivedAction,
+            P2pChannelsMessageReceivedAction, P2pChannelsMessageShedAction,
         },
         connection::{
-            incoming::P2pConnectionIncomingAction, outgoing::P2pConnectionOutgoingAction,
+            incoming::P2pConnectionIncomingAction,
+            outgoing::{P2pConnectionOutgoingAction, P2pConnectionOutgoingError},
             P2pConnectionErrorResponse, P2pConnectionResponse,
         },
         disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
+        peer::{P2pPeerAction, P2pPeerListEvent},
         P2pChannelEvent,
     },
     rpc::{RpcAction, RpcRequest},
     snark::{block_verify::SnarkBlockVerifyAction, work_verify::SnarkWorkVerifyAction, SnarkEvent},
-    transition_frontier::genesis::TransitionFrontierGenesisAction,
+    transition_frontier::{
+        genesis::TransitionFrontierGenesisAction,
+        sync::{
+            ledger::{
+                snarked::TransitionFrontierSyncLedgerSnarkedAction,
+                staged::TransitionFrontierSyncLedgerStagedAction, LedgerSnapshotEvent,
+            },
+            TransitionFrontierSyncAction,
+        },
+    },
     BlockProducerAction, ExternalSnarkWorkerAction, Service, Store,
 };
 
@@ -128,6 +139,35 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                     }
                 },
                 P2pEvent::Connection(e) => match e {
+                    P2pConnectionEvent::HostResolved(peer_id, res) => match res {
+                        Err(error) => {
+                            store.dispatch(P2pConnectionOutgoingAction::Error {
+                                peer_id,
+                                error: P2pConnectionOutgoingError::HostResolveError(error),
+                            });
+                        }
+                        Ok(ip) => {
+                            store.dispatch(P2pConnectionOutgoingAction::HostResolveSuccess {
+                                peer_id,
+                                ip,
+                            });
+                        }
+                    },
+                    P2pConnectionEvent::DnsaddrResolved(addr, res) => match res {
+                        Err(error) => {
+                            store.dispatch(P2pConnectionOutgoingAction::DnsaddrResolveError {
+                                addr,
+                                error,
+                            });
+                        }
+                        Ok((peers, ttl)) => {
+                            store.dispatch(P2pConnectionOutgoingAction::DnsaddrResolveSuccess {
+                                addr,
+                                peers,
+                                ttl,
+                            });
+                        }
+                    },
                     P2pConnectionEvent::OfferSdpReady(peer_id, res) => match res {
                         Err(error) => {
                             store.dispatch(P2pConnectionOutgoingAction::OfferSdpCreateError {
@@ -209,6 +249,9 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                         store.dispatch(P2pDisconnectionAction::PeerClosed { peer_id });
                         store.dispatch(P2pDisconnectionAction::Finish { peer_id });
                     }
+                    P2pConnectionEvent::Stats(peer_id, stats) => {
+                        store.dispatch(P2pPeerAction::ConnectionStatsUpdate { peer_id, stats });
+                    }
                 },
                 P2pEvent::Channel(e) => match e {
                     P2pChannelEvent::Opened(peer_id, chan_id, res) => match res {
@@ -267,6 +310,13 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                             });
                         }
                     },
+                    P2pChannelEvent::Shed(peer_id, channel_id, count) => {
+                        store.dispatch(P2pChannelsMessageShedAction {
+                            peer_id,
+                            channel_id,
+                            count,
+                        });
+                    }
                     P2pChannelEvent::Closed(peer_id, chan_id) => {
                         let reason = P2pDisconnectionReason::P2pChannelClosed(chan_id);
                         store.dispatch(P2pDisconnectionAction::Init { peer_id, reason });
@@ -298,15 +348,16 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                         store.dispatch(SnarkWorkVerifyAction::Success { req_id });
                     }
                 },
-                SnarkEvent::UserCommandVerify(req_id, result) => {
-                    if let Ok(commands) = result {
+                SnarkEvent::UserCommandVerify(req_id, result) => match result {
+                    Err(error) => {
+                        store.dispatch(SnarkUserCommandVerifyAction::Error { req_id, error });
+                    }
+                    Ok(commands) => {
                         store.dispatch(SnarkUserCommandVerifyAction::Success { req_id, commands });
-                    } else {
-                        store.dispatch(SnarkUserCommandVerifyAction::Error {
-                            req_id,
-                            error: SnarkUserCommandVerifyError::VerificationFailed,
-                        });
                     }
+                },
+                SnarkEvent::VerifyDryRun(rpc_id, report) => {
+                    store.dispatch(RpcAction::SnarkVerifyDryRunSuccess { rpc_id, report });
                 }
             },
             Event::Rpc(rpc_id, e) => match *e {
@@ -325,9 +376,18 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::SyncStatsGet(query) => {
                     store.dispatch(RpcAction::SyncStatsGet { rpc_id, query });
                 }
+                RpcRequest::SyncHistoryGet => {
+                    store.dispatch(RpcAction::SyncHistoryGet { rpc_id });
+                }
                 RpcRequest::BlockProducerStatsGet => {
                     store.dispatch(RpcAction::BlockProducerStatsGet { rpc_id });
                 }
+                RpcRequest::PeerContributionStatsGet => {
+                    store.dispatch(RpcAction::PeerContributionStatsGet { rpc_id });
+                }
+                RpcRequest::ArchiveUploadStatsGet => {
+                    store.dispatch(RpcAction::ArchiveUploadStatsGet { rpc_id });
+                }
                 RpcRequest::PeersGet => {
                     store.dispatch(RpcAction::PeersGet { rpc_id });
                 }
@@ -364,6 +424,9 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::SnarkerJobSpec { job_id } => {
                     store.dispatch(RpcAction::SnarkerJobSpec { rpc_id, job_id });
                 }
+                RpcRequest::SnarkerWorkSubmit { snark } => {
+                    store.dispatch(RpcAction::SnarkerWorkSubmitInit { rpc_id, snark });
+                }
                 RpcRequest::SnarkerWorkers => {
                     store.dispatch(RpcAction::SnarkerWorkersGet { rpc_id });
                 }
@@ -382,12 +445,25 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::TransactionPoolGet => {
                     store.dispatch(RpcAction::TransactionPool { rpc_id });
                 }
+                RpcRequest::TransactionPoolSenderQueuesGet(public_key) => {
+                    store.dispatch(RpcAction::TransactionPoolSenderQueues { rpc_id, public_key });
+                }
                 RpcRequest::LedgerAccountsGet(account_query) => {
                     store.dispatch(RpcAction::LedgerAccountsGetInit {
                         rpc_id,
                         account_query,
                     });
                 }
+                RpcRequest::LedgerAccountsPageGet(query) => {
+                    store.dispatch(RpcAction::LedgerAccountsPageGetInit { rpc_id, query });
+                }
+                RpcRequest::LedgerAccountsAtBlockGet(block_hash, account_query) => {
+                    store.dispatch(RpcAction::LedgerAccountsAtBlockGetInit {
+                        rpc_id,
+                        block_hash,
+                        account_query,
+                    });
+                }
                 RpcRequest::TransactionInject(commands) => {
                     store.dispatch(RpcAction::TransactionInjectInit { rpc_id, commands });
                 }
@@ -397,21 +473,58 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::BestChain(max_length) => {
                     store.dispatch(RpcAction::BestChain { rpc_id, max_length });
                 }
+                RpcRequest::BestChainPageGet(query) => {
+                    store.dispatch(RpcAction::BestChainPageGet { rpc_id, query });
+                }
                 RpcRequest::ConsensusConstantsGet => {
                     store.dispatch(RpcAction::ConsensusConstantsGet { rpc_id });
                 }
-                RpcRequest::TransactionStatusGet(tx) => {
-                    store.dispatch(RpcAction::TransactionStatusGet { rpc_id, tx });
+                RpcRequest::TransactionStatusGet(tx, block_hash) => {
+                    store.dispatch(RpcAction::TransactionStatusGet {
+                        rpc_id,
+                        tx,
+                        block_hash,
+                    });
                 }
                 RpcRequest::GetBlock(query) => {
                     store.dispatch(RpcAction::BlockGet { rpc_id, query });
                 }
+                RpcRequest::BlockSummaryGet(query) => {
+                    store.dispatch(RpcAction::BlockSummaryGet { rpc_id, query });
+                }
                 RpcRequest::PooledUserCommands(query) => {
                     store.dispatch(RpcAction::PooledUserCommands { rpc_id, query });
                 }
                 RpcRequest::PooledZkappCommands(query) => {
                     store.dispatch(RpcAction::PooledZkappCommands { rpc_id, query });
                 }
+                RpcRequest::PooledUserCommandsPageGet(query, page_query) => {
+                    store.dispatch(RpcAction::PooledUserCommandsPageGet {
+                        rpc_id,
+                        query,
+                        page_query,
+                    });
+                }
+                RpcRequest::PooledZkappCommandsPageGet(query, page_query) => {
+                    store.dispatch(RpcAction::PooledZkappCommandsPageGet {
+                        rpc_id,
+                        query,
+                        page_query,
+                    });
+                }
+                RpcRequest::ZkappActionsEventsGet(query) => {
+                    store.dispatch(RpcAction::ZkappActionsEventsGetInit { rpc_id, query });
+                }
+                RpcRequest::TokenSupplyGet(token_id) => {
+                    store.dispatch(RpcAction::TokenSupplyGetInit { rpc_id, token_id });
+                }
+                RpcRequest::ZkappEventsPageGet(query, page_query) => {
+                    store.dispatch(RpcAction::ZkappEventsPageGet {
+                        rpc_id,
+                        query,
+                        page_query,
+                    });
+                }
                 RpcRequest::ConsensusTimeGet(query) => {
                     store.dispatch(RpcAction::ConsensusTimeGet { rpc_id, query });
                 }
@@ -431,6 +544,55 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                         account_id,
                     });
                 }
+                RpcRequest::LedgerSnapshotGet(query) => {
+                    store.dispatch(RpcAction::LedgerSnapshotGetInit { rpc_id, query });
+                }
+                RpcRequest::BlockInject(block) => {
+                    store.dispatch(RpcAction::BlockInject { rpc_id, block });
+                }
+                RpcRequest::ConsensusForkDecisionsGet => {
+                    store.dispatch(RpcAction::ConsensusForkDecisionsGet { rpc_id });
+                }
+                RpcRequest::PendingCoinbaseGet(block_hash) => {
+                    store.dispatch(RpcAction::PendingCoinbaseGetInit { rpc_id, block_hash });
+                }
+                RpcRequest::PeerListRefresh => {
+                    store.dispatch(RpcAction::PeerListRefreshInit { rpc_id });
+                }
+                RpcRequest::GossipPause { kind, paused } => {
+                    store.dispatch(RpcAction::GossipPause {
+                        rpc_id,
+                        kind,
+                        paused,
+                    });
+                }
+                RpcRequest::SyncDiagnosisGet => {
+                    store.dispatch(RpcAction::SyncDiagnosisGet { rpc_id });
+                }
+                RpcRequest::NodeOverviewGet => {
+                    store.dispatch(RpcAction::NodeOverviewGet { rpc_id });
+                }
+                RpcRequest::LocallyGeneratedCommandsGet => {
+                    store.dispatch(RpcAction::LocallyGeneratedCommandsGet { rpc_id });
+                }
+                RpcRequest::PendingRequestsGet => {
+                    store.dispatch(RpcAction::PendingRequestsGet { rpc_id });
+                }
+                RpcRequest::SnarkStatsGet => {
+                    store.dispatch(RpcAction::SnarkStatsGet { rpc_id });
+                }
+                RpcRequest::SnarkVerifierDigestsGet => {
+                    store.dispatch(RpcAction::SnarkVerifierDigestsGet { rpc_id });
+                }
+                RpcRequest::SnarkVerifyHistoryGet => {
+                    store.dispatch(RpcAction::SnarkVerifyHistoryGet { rpc_id });
+                }
+                RpcRequest::SnarkVerifyDryRun(input) => {
+                    store.dispatch(RpcAction::SnarkVerifyDryRunInit { rpc_id, input });
+                }
+                RpcRequest::EnabledFeaturesGet => {
+                    store.dispatch(RpcAction::EnabledFeaturesGet { rpc_id });
+                }
             },
             Event::ExternalSnarkWorker(e) => match e {
                 ExternalSnarkWorkerEvent::Started => {
@@ -454,6 +616,62 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                         permanent: false,
                     });
                 }
+                ExternalSnarkWorkerEvent::CoordinatorSubmitResult {
+                    url,
+                    job_id,
+                    fee,
+                    result,
+                } => match result {
+                    Ok(response) => {
+                        store.dispatch(ExternalSnarkWorkerAction::CoordinatorSubmitSuccess {
+                            url,
+                            job_id,
+                            fee,
+                            response,
+                        });
+                    }
+                    Err(error) => {
+                        store.dispatch(ExternalSnarkWorkerAction::CoordinatorSubmitError {
+                            url,
+                            job_id,
+                            error,
+                        });
+                    }
+                },
+                ExternalSnarkWorkerEvent::CoordinatorJobsResult { url, result } => match result {
+                    Ok(offer) => {
+                        store.dispatch(ExternalSnarkWorkerAction::CoordinatorJobsFetchSuccess {
+                            url,
+                            offer,
+                        });
+                    }
+                    Err(error) => {
+                        store.dispatch(ExternalSnarkWorkerAction::CoordinatorJobsFetchError {
+                            url,
+                            error,
+                        });
+                    }
+                },
+                ExternalSnarkWorkerEvent::CoordinatorJobSpecResult {
+                    url,
+                    job_id,
+                    result,
+                } => match result {
+                    Ok(spec) => {
+                        store.dispatch(ExternalSnarkWorkerAction::CoordinatorJobSpecFetchSuccess {
+                            url,
+                            job_id,
+                            spec,
+                        });
+                    }
+                    Err(error) => {
+                        store.dispatch(ExternalSnarkWorkerAction::CoordinatorJobSpecFetchError {
+                            url,
+                            job_id,
+                            error,
+                        });
+                    }
+                },
             },
             Event::BlockProducerEvent(e) => match e {
                 BlockProducerEvent::VrfEvaluator(vrf_e) => match vrf_e {
@@ -486,6 +704,94 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                     }
                 },
             },
+            Event::PeerList(P2pPeerListEvent { rpc_id, result }) => {
+                match &result {
+                    Ok(diff) => {
+                        for opts in &diff.added {
+                            store.dispatch(P2pPeerAction::Listed {
+                                peer_id: *opts.peer_id(),
+                                dial_opts: opts.clone(),
+                            });
+                        }
+                        for peer_id in &diff.removed {
+                            store.dispatch(P2pPeerAction::Unlisted { peer_id: *peer_id });
+                        }
+                    }
+                    Err(error) => {
+                        mina_core::log::warn!(
+                            meta.time();
+                            kind = "PeerListRefresh",
+                            summary = "failed to fetch peer list",
+                            error = error,
+                        );
+                    }
+                }
+                if let Some(rpc_id) = rpc_id {
+                    store.dispatch(RpcAction::PeerListRefreshSuccess {
+                        rpc_id,
+                        response: result,
+                    });
+                }
+            }
+            Event::LedgerSnapshot(event) => match event {
+                LedgerSnapshotEvent::SnarkedLedger {
+                    ledger_hash,
+                    result,
+                } => match result {
+                    Ok(imported) => {
+                        store.dispatch(
+                            TransitionFrontierSyncLedgerSnarkedAction::SnapshotReceived {
+                                ledger_hash,
+                                computed_hash: imported.computed_hash,
+                                count: imported.count,
+                            },
+                        );
+                    }
+                    Err(error) => {
+                        store.dispatch(
+                            TransitionFrontierSyncLedgerSnarkedAction::SnapshotFetchError {
+                                ledger_hash,
+                                error,
+                            },
+                        );
+                    }
+                },
+                LedgerSnapshotEvent::StagedLedgerParts { block_hash, result } => match result {
+                    Ok(parts) => {
+                        store.dispatch(
+                            TransitionFrontierSyncLedgerStagedAction::PartsSnapshotFetchSuccess {
+                                block_hash,
+                                parts,
+                            },
+                        );
+                    }
+                    Err(error) => {
+                        store.dispatch(
+                            TransitionFrontierSyncLedgerStagedAction::PartsSnapshotFetchError {
+                                block_hash,
+                                error,
+                            },
+                        );
+                    }
+                },
+            },
+            Event::PrecomputedBlock(event) => {
+                let hash = event.block_hash.clone();
+                match event.verified_block() {
+                    Ok(block) => {
+                        store.dispatch(TransitionFrontierSyncAction::BlocksBucketFetchSuccess {
+                            hash,
+                            block,
+                        });
+                    }
+                    Err(error) => {
+                        store.dispatch(TransitionFrontierSyncAction::BlocksBucketFetchError {
+                            hash,
+                            error,
+                        });
+                    }
+                }
+            }
             Event::GenesisLoad(res) => match res {
                 Err(err) => todo!("error while trying to load genesis config/ledger. - {err}"),
                 Ok(data) => {
@@ -1,14 +1,18 @@
 use std::time::Duration;
 
-use mina_core::{snark::SnarkJobId, ActionEvent};
+use mina_core::{
+    snark::{Snark, SnarkJobId},
+    ActionEvent,
+};
+use mina_p2p_messages::v2::CurrencyFeeStableV1;
 use redux::{EnablingCondition, Timestamp};
 use serde::{Deserialize, Serialize};
 
-use crate::{snark_pool::JobSummary, State};
+use crate::{rpc::RpcSnarkerWorkSubmitResponse, snark_pool::JobSummary, State};
 
 use super::{
-    ExternalSnarkWorkerError, ExternalSnarkWorkerState, ExternalSnarkWorkerWorkError,
-    SnarkWorkResult,
+    ExternalSnarkWorkerCoordinatorOffer, ExternalSnarkWorkerError, ExternalSnarkWorkerState,
+    ExternalSnarkWorkerWorkError, SnarkWorkResult, SnarkWorkSpec,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, ActionEvent)]
@@ -41,6 +45,58 @@ pub enum ExternalSnarkWorkerAction {
 
     PruneWork,
 
+    /// Sends the work we produced to the node at `url`, see
+    /// [`crate::SnarkerConfig::coordinators`].
+    CoordinatorSubmit {
+        url: String,
+        snark: Snark,
+    },
+    CoordinatorSubmitSuccess {
+        url: String,
+        job_id: SnarkJobId,
+        fee: CurrencyFeeStableV1,
+        response: RpcSnarkerWorkSubmitResponse,
+    },
+    #[action_event(level = warn)]
+    CoordinatorSubmitError {
+        url: String,
+        job_id: SnarkJobId,
+        error: String,
+    },
+
+    /// Fetches the jobs of the coordinators, to work on the one paying
+    /// best before jobs of our own pool.
+    CoordinatorJobsFetchInit {
+        now: Timestamp,
+    },
+    CoordinatorJobsFetchPending {
+        url: String,
+    },
+    CoordinatorJobsFetchSuccess {
+        url: String,
+        offer: Option<ExternalSnarkWorkerCoordinatorOffer>,
+    },
+    #[action_event(level = warn)]
+    CoordinatorJobsFetchError {
+        url: String,
+        error: String,
+    },
+    CoordinatorJobSpecFetchInit {
+        url: String,
+        job_id: SnarkJobId,
+    },
+    CoordinatorJobSpecFetchSuccess {
+        url: String,
+        job_id: SnarkJobId,
+        spec: Box<SnarkWorkSpec>,
+    },
+    #[action_event(level = warn)]
+    CoordinatorJobSpecFetchError {
+        url: String,
+        job_id: SnarkJobId,
+        error: String,
+    },
+
     Error {
         error: ExternalSnarkWorkerError,
         permanent: bool,
@@ -130,6 +186,68 @@ impl EnablingCondition<State> for ExternalSnarkWorkerAction {
                         | ExternalSnarkWorkerState::Cancelled(..)
                 )
             }
+            ExternalSnarkWorkerAction::CoordinatorSubmit { url, .. } => state
+                .config
+                .snarker
+                .as_ref()
+                .is_some_and(|config| config.coordinators.contains(url)),
+            ExternalSnarkWorkerAction::CoordinatorSubmitSuccess { url, .. }
+            | ExternalSnarkWorkerAction::CoordinatorSubmitError { url, .. } => state
+                .external_snark_worker
+                .0
+                .coordinators
+                .get(url)
+                .is_some_and(|stats| stats.pending > 0),
+            ExternalSnarkWorkerAction::CoordinatorJobsFetchInit { now } => {
+                let jobs = &state.external_snark_worker.0.coordinator_jobs;
+                state
+                    .config
+                    .snarker
+                    .as_ref()
+                    .is_some_and(|config| !config.coordinators.is_empty())
+                    && state.external_snark_worker.is_idle()
+                    && !jobs.is_fetching()
+                    && jobs.is_fetch_due(*now)
+            }
+            ExternalSnarkWorkerAction::CoordinatorJobsFetchPending { url } => {
+                state
+                    .config
+                    .snarker
+                    .as_ref()
+                    .is_some_and(|config| config.coordinators.contains(url))
+                    && !state
+                        .external_snark_worker
+                        .0
+                        .coordinator_jobs
+                        .pending
+                        .contains(url)
+            }
+            ExternalSnarkWorkerAction::CoordinatorJobsFetchSuccess { url, .. }
+            | ExternalSnarkWorkerAction::CoordinatorJobsFetchError { url, .. } => state
+                .external_snark_worker
+                .0
+                .coordinator_jobs
+                .pending
+                .contains(url),
+            ExternalSnarkWorkerAction::CoordinatorJobSpecFetchInit { url, job_id } => {
+                let jobs = &state.external_snark_worker.0.coordinator_jobs;
+                state.external_snark_worker.is_idle()
+                    && jobs.spec_pending.is_none()
+                    && jobs
+                        .offers
+                        .get(url)
+                        .is_some_and(|offer| &offer.job_id == job_id)
+            }
+            ExternalSnarkWorkerAction::CoordinatorJobSpecFetchSuccess { url, job_id, .. }
+            | ExternalSnarkWorkerAction::CoordinatorJobSpecFetchError { url, job_id, .. } => state
+                .external_snark_worker
+                .0
+                .coordinator_jobs
+                .spec_pending
+                .as_ref()
+                .is_some_and(|(pending_url, pending_job_id)| {
+                    pending_url == url && pending_job_id == job_id
+                }),
             ExternalSnarkWorkerAction::Error { .. } => true,
         }
     }
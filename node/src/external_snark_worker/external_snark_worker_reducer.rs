@@ -9,7 +9,7 @@ use super::{
 };
 use crate::{
     external_snark_worker_effectful::ExternalSnarkWorkerEffectfulAction, p2p_ready,
-    SnarkPoolAction, Substate,
+    snark_pool::JobSummary, SnarkPoolAction, Substate,
 };
 
 impl ExternalSnarkWorkers {
@@ -110,12 +110,12 @@ impl ExternalSnarkWorker {
                 worker_state.state =
                     ExternalSnarkWorkerState::WorkReady(job_id.clone(), result.clone());
                 worker_state.update_timestamp(meta.time());
+                let issuer = worker_state.issuer.clone();
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let Some(config) = &state.config.snarker else {
                     return;
                 };
-                let p2p = p2p_ready!(state.p2p, meta.time());
                 let snarker = config.public_key.clone().into();
                 let fee = config.fee.clone();
                 let snark = Snark {
@@ -123,13 +123,19 @@ impl ExternalSnarkWorker {
                     fee,
                     proofs: result.clone(),
                 };
-                let sender = p2p.my_id();
-                // Directly add snark to the snark pool as it's produced by us.
-                dispatcher.push(SnarkPoolAction::WorkAdd {
-                    snark,
-                    sender,
-                    is_sender_local: true,
-                });
+                if let Some(url) = issuer {
+                    // The job isn't in our pool, only its coordinator needs the work.
+                    dispatcher.push(ExternalSnarkWorkerAction::CoordinatorSubmit { url, snark });
+                } else {
+                    let p2p = p2p_ready!(state.p2p, meta.time());
+                    let sender = p2p.my_id();
+                    // Directly add snark to the snark pool as it's produced by us.
+                    dispatcher.push(SnarkPoolAction::WorkAdd {
+                        snark,
+                        sender,
+                        is_sender_local: true,
+                    });
+                }
                 dispatcher.push(ExternalSnarkWorkerAction::PruneWork);
             }
             ExternalSnarkWorkerAction::WorkError { error } => {
@@ -169,9 +175,149 @@ impl ExternalSnarkWorker {
             }
             ExternalSnarkWorkerAction::PruneWork => {
                 worker_state.state = ExternalSnarkWorkerState::Idle;
+                worker_state.issuer = None;
+                worker_state.update_timestamp(meta.time());
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(SnarkPoolAction::AutoCreateCommitment);
+            }
+            ExternalSnarkWorkerAction::CoordinatorSubmit { url, snark } => {
+                let stats = worker_state.coordinators.entry(url.clone()).or_default();
+                stats.submitted += 1;
+                stats.pending += 1;
+                stats.last_submit = Some(meta.time());
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(ExternalSnarkWorkerEffectfulAction::CoordinatorSubmit {
+                    url: url.clone(),
+                    snark: snark.clone(),
+                });
+            }
+            ExternalSnarkWorkerAction::CoordinatorSubmitSuccess {
+                url, fee, response, ..
+            } => {
+                let Some(stats) = worker_state.coordinators.get_mut(url) else {
+                    return;
+                };
+                stats.record_response(fee.as_u64(), response);
+            }
+            ExternalSnarkWorkerAction::CoordinatorSubmitError { url, error, .. } => {
+                let Some(stats) = worker_state.coordinators.get_mut(url) else {
+                    return;
+                };
+                stats.pending = stats.pending.saturating_sub(1);
+                stats.failed += 1;
+                stats.last_error = Some(error.clone());
+            }
+            ExternalSnarkWorkerAction::CoordinatorJobsFetchInit { .. } => {
+                let jobs = &mut worker_state.coordinator_jobs;
+                jobs.last_fetch = Some(meta.time());
+                jobs.offers.clear();
+                jobs.none_offered = false;
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let Some(config) = &state.config.snarker else {
+                    return;
+                };
+                for url in &config.coordinators {
+                    dispatcher.push(ExternalSnarkWorkerAction::CoordinatorJobsFetchPending {
+                        url: url.clone(),
+                    });
+                }
+            }
+            ExternalSnarkWorkerAction::CoordinatorJobsFetchPending { url } => {
+                worker_state.coordinator_jobs.pending.insert(url.clone());
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let Some(config) = &state.config.snarker else {
+                    return;
+                };
+                dispatcher.push(ExternalSnarkWorkerEffectfulAction::CoordinatorJobsFetch {
+                    url: url.clone(),
+                    fee: config.fee.clone(),
+                });
+            }
+            ExternalSnarkWorkerAction::CoordinatorJobsFetchSuccess { url, offer } => {
+                let jobs = &mut worker_state.coordinator_jobs;
+                jobs.pending.remove(url);
+                if let Some(offer) = offer {
+                    jobs.offers.insert(url.clone(), offer.clone());
+                }
+
+                Self::choose_coordinator_job(state_context);
+            }
+            ExternalSnarkWorkerAction::CoordinatorJobsFetchError { url, error } => {
+                worker_state.coordinator_jobs.pending.remove(url);
+                let stats = worker_state.coordinators.entry(url.clone()).or_default();
+                stats.failed += 1;
+                stats.last_error = Some(error.clone());
+
+                Self::choose_coordinator_job(state_context);
+            }
+            ExternalSnarkWorkerAction::CoordinatorJobSpecFetchInit { url, job_id } => {
+                worker_state.coordinator_jobs.spec_pending = Some((url.clone(), job_id.clone()));
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(
+                    ExternalSnarkWorkerEffectfulAction::CoordinatorJobSpecFetch {
+                        url: url.clone(),
+                        job_id: job_id.clone(),
+                    },
+                );
+            }
+            ExternalSnarkWorkerAction::CoordinatorJobSpecFetchSuccess { url, job_id, spec } => {
+                worker_state.coordinator_jobs.spec_pending = None;
+                if !matches!(worker_state.state, ExternalSnarkWorkerState::Idle) {
+                    return;
+                }
+                worker_state.state =
+                    ExternalSnarkWorkerState::Working(job_id.clone(), JobSummary::from(&**spec));
+                worker_state.issuer = Some(url.clone());
                 worker_state.update_timestamp(meta.time());
 
                 let dispatcher = state_context.into_dispatcher();
+                dispatcher
+                    .push(ExternalSnarkWorkerEffectfulAction::SubmitWork { spec: spec.clone() });
+            }
+            ExternalSnarkWorkerAction::CoordinatorJobSpecFetchError { url, error, .. } => {
+                let jobs = &mut worker_state.coordinator_jobs;
+                jobs.spec_pending = None;
+                jobs.none_offered = true;
+                let stats = worker_state.coordinators.entry(url.clone()).or_default();
+                stats.failed += 1;
+                stats.last_error = Some(error.clone());
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(SnarkPoolAction::AutoCreateCommitment);
+            }
+        }
+    }
+
+    /// Once all the coordinators answered, fetches the spec of the best job
+    /// they offered, or falls back to jobs of our own pool.
+    fn choose_coordinator_job(mut state_context: Substate<ExternalSnarkWorker>) {
+        let Ok(worker_state) = state_context.get_substate_mut() else {
+            return;
+        };
+        let jobs = &mut worker_state.coordinator_jobs;
+        if !jobs.pending.is_empty() {
+            return;
+        }
+        jobs.none_offered = jobs.offers.is_empty();
+
+        let (dispatcher, state) = state_context.into_dispatcher_and_state();
+        let Some(config) = &state.config.snarker else {
+            return;
+        };
+        let jobs = &state.external_snark_worker.0.coordinator_jobs;
+        match jobs.best_offer(&config.coordinators) {
+            Some((url, offer)) => {
+                dispatcher.push(ExternalSnarkWorkerAction::CoordinatorJobSpecFetchInit {
+                    url: url.clone(),
+                    job_id: offer.job_id.clone(),
+                });
+            }
+            None => {
                 dispatcher.push(SnarkPoolAction::AutoCreateCommitment);
             }
         }
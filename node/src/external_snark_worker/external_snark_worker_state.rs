@@ -1,7 +1,17 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
+
+use mina_core::snark::SnarkJobId;
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
-use crate::snark_pool::JobSummary;
+use crate::{
+    rpc::{RpcSnarkPoolJobSummary, RpcSnarkerWorkSubmitResponse},
+    snark_pool::JobSummary,
+};
 
 use super::{ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkId, SnarkWorkResult};
 
@@ -12,6 +22,142 @@ pub struct ExternalSnarkWorkers(pub(crate) ExternalSnarkWorker);
 pub struct ExternalSnarkWorker {
     pub(crate) state: ExternalSnarkWorkerState,
     pub(crate) timestamp: Timestamp,
+    /// Submissions of our work to other nodes, by node url, see
+    /// [`crate::SnarkerConfig::coordinators`].
+    #[serde(default)]
+    pub(crate) coordinators: BTreeMap<String, ExternalSnarkWorkerCoordinatorStats>,
+    /// Jobs offered by the coordinators to the idle worker.
+    #[serde(default)]
+    pub(crate) coordinator_jobs: ExternalSnarkWorkerCoordinatorJobs,
+    /// Coordinator which issued the job being worked on, `None` for jobs
+    /// of our own pool. The work is only submitted to it.
+    #[serde(default)]
+    pub(crate) issuer: Option<String>,
+}
+
+/// Interval between fetches of the jobs of the coordinators, while the
+/// worker is idle or works on jobs of our own pool.
+pub const COORDINATOR_JOBS_FETCH_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExternalSnarkWorkerCoordinatorJobs {
+    pub last_fetch: Option<Timestamp>,
+    /// Coordinators whose jobs are being fetched.
+    pub pending: BTreeSet<String>,
+    /// Best job of each coordinator which answered.
+    pub offers: BTreeMap<String, ExternalSnarkWorkerCoordinatorOffer>,
+    /// Job whose spec is being fetched, with its coordinator.
+    pub spec_pending: Option<(String, SnarkJobId)>,
+    /// Whether no coordinator offered a job at the last fetch, in which
+    /// case jobs of our own pool are worked on until the next one.
+    pub none_offered: bool,
+}
+
+/// Job of the pool of a coordinator, which pays for our work.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalSnarkWorkerCoordinatorOffer {
+    pub job_id: SnarkJobId,
+    /// Fee of the work the coordinator has for the job, `None` if it has
+    /// none. Our work replaces it as it is cheaper.
+    pub competing_fee: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExternalSnarkWorkerCoordinatorStats {
+    /// Works sent to the node, including the pending ones.
+    pub submitted: u64,
+    pub pending: u64,
+    /// Works the node added to its pool, which pay our fee once included
+    /// in a block.
+    pub accepted: u64,
+    /// Works the node didn't need, as its pool doesn't have the job or
+    /// already has a cheaper work for it.
+    pub rejected: u64,
+    /// Works the node failed to verify.
+    #[serde(default)]
+    pub invalid: u64,
+    /// Submissions which failed, e.g. because the node is unreachable.
+    pub failed: u64,
+    /// Sum of the fees of the accepted works, in nanomina.
+    pub accepted_fees: u64,
+    pub last_submit: Option<Timestamp>,
+    pub last_error: Option<String>,
+}
+
+impl ExternalSnarkWorkerCoordinatorJobs {
+    pub fn is_fetching(&self) -> bool {
+        !self.pending.is_empty() || self.spec_pending.is_some()
+    }
+
+    pub fn is_fetch_due(&self, now: Timestamp) -> bool {
+        self.last_fetch.is_none_or(|last_fetch| {
+            now.checked_sub(last_fetch)
+                .is_some_and(|d| d >= COORDINATOR_JOBS_FETCH_INTERVAL)
+        })
+    }
+
+    /// Best of the offers, in the order of `coordinators` when they are
+    /// as good.
+    pub fn best_offer<'a>(
+        &'a self,
+        coordinators: &'a [String],
+    ) -> Option<(&'a String, &'a ExternalSnarkWorkerCoordinatorOffer)> {
+        coordinators
+            .iter()
+            .filter_map(|url| Some((url, self.offers.get(url)?)))
+            .min_by_key(|(_, offer)| offer.rank())
+    }
+}
+
+impl ExternalSnarkWorkerCoordinatorOffer {
+    /// Best job of the pool of a coordinator for our work with `fee`.
+    ///
+    /// Jobs without a work pay for sure, else the coordinator keeps our
+    /// work only if it is cheaper than the one it has, so jobs whose work
+    /// is the most expensive are the least likely to be taken by another
+    /// snarker first. Jobs committed to by another snarker are skipped,
+    /// and the oldest of equally good jobs is chosen.
+    pub fn best_of(jobs: &[RpcSnarkPoolJobSummary], fee: u64) -> Option<Self> {
+        jobs.iter()
+            .filter(|job| job.commitment.is_none())
+            .filter_map(|job| {
+                let competing_fee = job.snark.as_ref().map(|snark| snark.fee.as_u64());
+                competing_fee
+                    .is_none_or(|competing_fee| competing_fee > fee)
+                    .then(|| Self {
+                        job_id: job.id.clone(),
+                        competing_fee,
+                    })
+            })
+            .min_by_key(Self::rank)
+    }
+
+    /// Lower is better.
+    fn rank(&self) -> Reverse<u64> {
+        Reverse(self.competing_fee.unwrap_or(u64::MAX))
+    }
+}
+
+impl ExternalSnarkWorkerCoordinatorStats {
+    /// Accounts the answer of the node to a pending submission. The node
+    /// only answers `Accepted` once it verified the work.
+    pub fn record_response(&mut self, fee: u64, response: &RpcSnarkerWorkSubmitResponse) {
+        self.pending = self.pending.saturating_sub(1);
+        match response {
+            RpcSnarkerWorkSubmitResponse::Accepted => {
+                self.accepted += 1;
+                self.accepted_fees = self.accepted_fees.saturating_add(fee);
+            }
+            RpcSnarkerWorkSubmitResponse::JobNotFound
+            | RpcSnarkerWorkSubmitResponse::NotCheaper { .. } => {
+                self.rejected += 1;
+            }
+            RpcSnarkerWorkSubmitResponse::Invalid { error } => {
+                self.invalid += 1;
+                self.last_error = Some(error.clone());
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +182,9 @@ impl ExternalSnarkWorkers {
         ExternalSnarkWorkers(ExternalSnarkWorker {
             state: ExternalSnarkWorkerState::None,
             timestamp: now,
+            coordinators: Default::default(),
+            coordinator_jobs: Default::default(),
+            issuer: None,
         })
     }
 
@@ -55,10 +204,167 @@ impl ExternalSnarkWorkers {
         }
     }
 
+    /// Job of our own pool the worker is working on, `None` if it works
+    /// for a coordinator.
     pub fn working_job_id(&self) -> Option<&SnarkWorkId> {
         match &self.0.state {
-            ExternalSnarkWorkerState::Working(job_id, _) => Some(job_id),
+            ExternalSnarkWorkerState::Working(job_id, _) if self.0.issuer.is_none() => Some(job_id),
             _ => None,
         }
     }
+
+    /// Whether the worker should fetch the jobs of the coordinators before
+    /// working on a job of our own pool. Once they offered none, it works
+    /// on our own jobs until the next fetch is due.
+    pub fn wants_coordinator_jobs(&self, coordinators: &[String], now: Timestamp) -> bool {
+        let jobs = &self.0.coordinator_jobs;
+        !coordinators.is_empty() && (!jobs.none_offered || jobs.is_fetch_due(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_core::snark::SnarkJobCommitment;
+    use mina_curves::pasta::Fp;
+    use mina_p2p_messages::v2::{CurrencyFeeStableV1, LedgerHash, NonZeroCurvePoint};
+
+    use super::*;
+    use crate::{p2p::PeerId, rpc::RpcSnarkPoolJobSnarkWork, snark_pool::JobCommitment};
+
+    const FEE: u64 = 10_000_000;
+
+    fn job_id(n: u64) -> SnarkJobId {
+        let hash = |n: u64| LedgerHash::from_fp(Fp::from(n));
+        let (source, target) = (hash(n), hash(n + 1));
+        format!("{source}_{source}-{target}_{target}")
+            .parse()
+            .unwrap()
+    }
+
+    /// Job `n` of a coordinator's pool, with a work for `fee` if set.
+    fn job(n: u64, fee: Option<u64>) -> RpcSnarkPoolJobSummary {
+        RpcSnarkPoolJobSummary {
+            time: Timestamp::ZERO,
+            id: job_id(n),
+            commitment: None,
+            snark: fee.map(|fee| RpcSnarkPoolJobSnarkWork {
+                snarker: NonZeroCurvePoint::default(),
+                fee: CurrencyFeeStableV1(fee.into()),
+                received_t: Timestamp::ZERO,
+                sender: PeerId::from_bytes([1; 32]),
+            }),
+        }
+    }
+
+    fn offer(n: u64, competing_fee: Option<u64>) -> ExternalSnarkWorkerCoordinatorOffer {
+        ExternalSnarkWorkerCoordinatorOffer {
+            job_id: job_id(n),
+            competing_fee,
+        }
+    }
+
+    #[test]
+    fn offer_is_the_oldest_job_without_work() {
+        let jobs = [job(0, Some(2 * FEE)), job(1, None), job(2, None)];
+        assert_eq!(
+            ExternalSnarkWorkerCoordinatorOffer::best_of(&jobs, FEE),
+            Some(offer(1, None))
+        );
+    }
+
+    #[test]
+    fn offer_is_the_job_with_the_most_expensive_work() {
+        let jobs = [
+            job(0, Some(2 * FEE)),
+            job(1, Some(FEE)),
+            job(2, Some(3 * FEE)),
+        ];
+        assert_eq!(
+            ExternalSnarkWorkerCoordinatorOffer::best_of(&jobs, FEE),
+            Some(offer(2, Some(3 * FEE)))
+        );
+    }
+
+    #[test]
+    fn no_offer_for_jobs_with_cheaper_work_or_committed() {
+        let mut committed = job(1, None);
+        committed.commitment = Some(JobCommitment {
+            commitment: SnarkJobCommitment::new(
+                0,
+                job_id(1),
+                CurrencyFeeStableV1(FEE.into()),
+                NonZeroCurvePoint::default(),
+            ),
+            received_t: Timestamp::ZERO,
+            sender: PeerId::from_bytes([1; 32]),
+        });
+        let jobs = [job(0, Some(FEE)), committed];
+        assert_eq!(
+            ExternalSnarkWorkerCoordinatorOffer::best_of(&jobs, FEE),
+            None
+        );
+    }
+
+    #[test]
+    fn best_offer_is_chosen_among_coordinators() {
+        let coordinators = [
+            "http://a".to_owned(),
+            "http://b".to_owned(),
+            "http://c".to_owned(),
+        ];
+        let mut jobs = ExternalSnarkWorkerCoordinatorJobs::default();
+        assert_eq!(jobs.best_offer(&coordinators), None);
+
+        jobs.offers
+            .insert(coordinators[0].clone(), offer(0, Some(2 * FEE)));
+        jobs.offers.insert(coordinators[2].clone(), offer(2, None));
+        jobs.offers.insert(coordinators[1].clone(), offer(1, None));
+        let (url, best) = jobs.best_offer(&coordinators).unwrap();
+        assert_eq!(url, &coordinators[1]);
+        assert_eq!(best, &offer(1, None));
+    }
+
+    fn pending_stats() -> ExternalSnarkWorkerCoordinatorStats {
+        ExternalSnarkWorkerCoordinatorStats {
+            submitted: 1,
+            pending: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn accepted_work_counts_its_fee() {
+        let mut stats = pending_stats();
+        stats.record_response(1_000, &RpcSnarkerWorkSubmitResponse::Accepted);
+        assert_eq!(stats.pending, 0);
+        assert_eq!(stats.accepted, 1);
+        assert_eq!(stats.accepted_fees, 1_000);
+    }
+
+    #[test]
+    fn rejected_work_counts_no_fee() {
+        let mut stats = pending_stats();
+        stats.record_response(1_000, &RpcSnarkerWorkSubmitResponse::JobNotFound);
+        assert_eq!(stats.pending, 0);
+        assert_eq!(stats.accepted, 0);
+        assert_eq!(stats.rejected, 1);
+        assert_eq!(stats.accepted_fees, 0);
+    }
+
+    #[test]
+    fn invalid_work_counts_no_fee() {
+        let mut stats = pending_stats();
+        let response = RpcSnarkerWorkSubmitResponse::Invalid {
+            error: "work 0 has an invalid proof".to_owned(),
+        };
+        stats.record_response(1_000, &response);
+        assert_eq!(stats.pending, 0);
+        assert_eq!(stats.accepted, 0);
+        assert_eq!(stats.invalid, 1);
+        assert_eq!(stats.accepted_fees, 0);
+        assert_eq!(
+            stats.last_error.as_deref(),
+            Some("work 0 has an invalid proof")
+        );
+    }
 }
@@ -2,13 +2,46 @@ use std::sync::Arc;
 
 use mina_core::snark::SnarkJobId;
 use mina_p2p_messages::v2::{
+    MinaBaseUserCommandStableV2, MinaTransactionTransactionStableV2,
     SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances,
-    TransactionSnarkWorkTStableV2Proofs,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single, TransactionSnarkWorkTStableV2Proofs,
 };
 
+use crate::snark_pool::JobSummary;
+
 pub type SnarkWorkId = SnarkJobId;
 
 /// TODO use more slim type `OneOrTwo`...
 pub type SnarkWorkSpec = SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances;
 
 pub type SnarkWorkResult = Arc<TransactionSnarkWorkTStableV2Proofs>;
+
+/// Summary of a job fetched from a coordinator, whose pool we don't have.
+impl From<&SnarkWorkSpec> for JobSummary {
+    fn from(spec: &SnarkWorkSpec) -> Self {
+        use SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single as Single;
+
+        let account_updates = |single: &Single| match single {
+            Single::Transition(_, witness) => match &witness.transaction {
+                MinaTransactionTransactionStableV2::Command(command) => match command.as_ref() {
+                    MinaBaseUserCommandStableV2::ZkappCommand(zkapp) => zkapp.account_updates.len(),
+                    MinaBaseUserCommandStableV2::SignedCommand(_) => 1,
+                },
+                _ => 1,
+            },
+            Single::Merge(_) => 1,
+        };
+        let (first, account_updates) = match spec {
+            SnarkWorkSpec::One(single) => (single, account_updates(single)),
+            SnarkWorkSpec::Two((single1, single2)) => (
+                single1,
+                account_updates(single1).saturating_add(account_updates(single2)),
+            ),
+        };
+
+        match first {
+            Single::Transition(..) => JobSummary::Tx(account_updates),
+            Single::Merge(_) => JobSummary::Merge(account_updates),
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use mina_core::ActionEvent;
+use mina_core::{
+    snark::{Snark, SnarkJobId},
+    ActionEvent,
+};
 use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
 use redux::EnablingCondition;
 use serde::{Deserialize, Serialize};
@@ -16,6 +19,18 @@ pub enum ExternalSnarkWorkerEffectfulAction {
         spec: Box<SnarkWorkSpec>,
     },
     CancelWork,
+    CoordinatorSubmit {
+        url: String,
+        snark: Snark,
+    },
+    CoordinatorJobsFetch {
+        url: String,
+        fee: CurrencyFeeStableV1,
+    },
+    CoordinatorJobSpecFetch {
+        url: String,
+        job_id: SnarkJobId,
+    },
 }
 
 impl EnablingCondition<State> for ExternalSnarkWorkerEffectfulAction {
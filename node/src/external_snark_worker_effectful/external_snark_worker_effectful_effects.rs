@@ -38,5 +38,14 @@ pub fn external_snark_worker_effectful_effects<S: crate::Service>(
                 });
             }
         }
+        ExternalSnarkWorkerEffectfulAction::CoordinatorSubmit { url, snark } => {
+            store.service().coordinator_submit(&url, snark);
+        }
+        ExternalSnarkWorkerEffectfulAction::CoordinatorJobsFetch { url, fee } => {
+            store.service().coordinator_jobs_fetch(&url, fee);
+        }
+        ExternalSnarkWorkerEffectfulAction::CoordinatorJobSpecFetch { url, job_id } => {
+            store.service().coordinator_job_spec_fetch(&url, job_id);
+        }
     }
 }
@@ -1,9 +1,14 @@
+use mina_core::snark::{Snark, SnarkJobId};
 use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
 use serde::{Deserialize, Serialize};
 use snark::TransactionVerifier;
 
-use crate::external_snark_worker::{
-    ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkResult, SnarkWorkSpec,
+use crate::{
+    external_snark_worker::{
+        ExternalSnarkWorkerCoordinatorOffer, ExternalSnarkWorkerError,
+        ExternalSnarkWorkerWorkError, SnarkWorkResult, SnarkWorkSpec,
+    },
+    rpc::RpcSnarkerWorkSubmitResponse,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone, derive_more::From)]
@@ -14,6 +19,21 @@ pub enum ExternalSnarkWorkerEvent {
     WorkError(ExternalSnarkWorkerWorkError),
     WorkCancelled,
     Error(ExternalSnarkWorkerError),
+    CoordinatorSubmitResult {
+        url: String,
+        job_id: SnarkJobId,
+        fee: CurrencyFeeStableV1,
+        result: Result<RpcSnarkerWorkSubmitResponse, String>,
+    },
+    CoordinatorJobsResult {
+        url: String,
+        result: Result<Option<ExternalSnarkWorkerCoordinatorOffer>, String>,
+    },
+    CoordinatorJobSpecResult {
+        url: String,
+        job_id: SnarkJobId,
+        result: Result<Box<SnarkWorkSpec>, String>,
+    },
 }
 
 pub trait ExternalSnarkWorkerService {
@@ -34,3 +54,19 @@ pub trait ExternalSnarkWorkerService {
     /// Kills external process.
     fn kill(&mut self) -> Result<(), ExternalSnarkWorkerError>;
 }
+
+pub trait ExternalSnarkWorkerCoordinatorService: redux::Service {
+    /// Submits the work to the http server of the node at `url`. Result
+    /// is sent as [`ExternalSnarkWorkerEvent::CoordinatorSubmitResult`].
+    fn coordinator_submit(&mut self, url: &str, snark: Snark);
+
+    /// Fetches the jobs of the pool of the node at `url`, and picks the
+    /// best one for our work with `fee`, see
+    /// [`ExternalSnarkWorkerCoordinatorOffer::best_of`]. Result is sent as
+    /// [`ExternalSnarkWorkerEvent::CoordinatorJobsResult`].
+    fn coordinator_jobs_fetch(&mut self, url: &str, fee: CurrencyFeeStableV1);
+
+    /// Fetches the spec of the job of the node at `url`. Result is sent as
+    /// [`ExternalSnarkWorkerEvent::CoordinatorJobSpecResult`].
+    fn coordinator_job_spec_fetch(&mut self, url: &str, job_id: SnarkJobId);
+}
@@ -0,0 +1,338 @@
+//! Snapshots of ledgers in the genesis ledger format of `daemon.json`, as
+//! read by the OCaml node and by [`crate::daemon_json`], to start a fork
+//! from the ledger of a given block or to audit it.
+
+use std::path::Path;
+
+use ledger::{BaseLedger, Mask};
+use mina_p2p_messages::v2;
+
+use crate::daemon_json::{self, AccountConfigError, DaemonJson};
+
+use super::ledger_depth;
+
+#[derive(Debug, thiserror::Error)]
+pub enum LedgerSnapshotError {
+    #[error("no ledger in snapshot")]
+    NoLedger,
+    #[error("declared and computed ledger hashes don't match: {expected} != {computed}")]
+    LedgerHashMismatch {
+        expected: String,
+        computed: v2::LedgerHash,
+    },
+    #[error("unsupported ledger depth: {0}")]
+    UnsupportedDepth(usize),
+    #[error("{num_accounts} accounts don't fit a ledger of depth {depth}")]
+    TooManyAccounts { num_accounts: usize, depth: usize },
+    #[error("account error: {0}")]
+    Account(#[from] AccountConfigError),
+    #[error("error (de)serializing snapshot: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+fn ledger_hash(mask: &mut Mask) -> v2::LedgerHash {
+    v2::MinaBaseLedgerHash0StableV1(mask.merkle_root().into()).into()
+}
+
+/// Dumps the accounts of `mask`, in order of their index, along with the
+/// hash of the ledger.
+pub fn export_ledger(mask: &mut Mask) -> daemon_json::Ledger {
+    let accounts = mask
+        .to_list()
+        .iter()
+        .map(daemon_json::Account::from)
+        .collect::<Vec<_>>();
+
+    daemon_json::Ledger {
+        num_accounts: Some(accounts.len()),
+        accounts: Some(accounts),
+        balances: None,
+        hash: Some(ledger_hash(mask).to_string()),
+        s3_data_hash: None,
+        name: None,
+        // Already the first account of an exported ledger
+        add_genesis_winner: Some(false),
+    }
+}
+
+/// Builds a ledger from a snapshot, like the genesis ledger is built from
+/// `daemon.json`, and checks its hash when the snapshot has one.
+pub fn import_ledger(ledger: &daemon_json::Ledger) -> Result<Mask, LedgerSnapshotError> {
+    import_ledger_with_depth(ledger, ledger_depth())
+}
+
+/// Same as [`import_ledger`], for a snapshot of a ledger of `depth`.
+pub fn import_ledger_with_depth(
+    ledger: &daemon_json::Ledger,
+    depth: usize,
+) -> Result<Mask, LedgerSnapshotError> {
+    let mut mask = empty_ledger(depth)?;
+
+    for account in ledger.accounts_with_genesis_winner() {
+        let account = account.to_account()?;
+        mask.get_or_create_account(account.id(), account)
+            .map_err(|_| LedgerSnapshotError::TooManyAccounts {
+                num_accounts: ledger.accounts_with_genesis_winner().count(),
+                depth,
+            })?;
+    }
+
+    if let Some(expected) = ledger.hash.as_ref() {
+        let computed = ledger_hash(&mut mask);
+        if expected != &computed.to_string() {
+            return Err(LedgerSnapshotError::LedgerHashMismatch {
+                expected: expected.clone(),
+                computed,
+            });
+        }
+    }
+
+    Ok(mask)
+}
+
+fn empty_ledger(depth: usize) -> Result<Mask, LedgerSnapshotError> {
+    if !(1..=ledger::MAX_LEDGER_DEPTH).contains(&depth) {
+        return Err(LedgerSnapshotError::UnsupportedDepth(depth));
+    }
+    let db = ledger::Database::create_with_token_owners(depth as u8);
+    Ok(Mask::new_root(db))
+}
+
+/// Moves the accounts of a snapshot of a ledger of `from_depth` to a ledger
+/// of `to_depth`, at the same indices, for custom networks changing their
+/// ledger depth.
+///
+/// The hash of the snapshot is checked against the ledger of `from_depth`,
+/// and the returned snapshot, which has the hash of the ledger of
+/// `to_depth`, is checked to import back to the same accounts.
+pub fn migrate_ledger(
+    ledger: &daemon_json::Ledger,
+    from_depth: usize,
+    to_depth: usize,
+) -> Result<daemon_json::Ledger, LedgerSnapshotError> {
+    let mut source = import_ledger_with_depth(ledger, from_depth)?;
+    let accounts = source.to_list();
+
+    let mut target = empty_ledger(to_depth)?;
+    for account in &accounts {
+        target
+            .get_or_create_account(account.id(), account.clone())
+            .map_err(|_| LedgerSnapshotError::TooManyAccounts {
+                num_accounts: accounts.len(),
+                depth: to_depth,
+            })?;
+    }
+
+    let migrated = export_ledger(&mut target);
+    let mut imported = import_ledger_with_depth(&migrated, to_depth)?;
+    if imported.to_list() != accounts {
+        // The migrated snapshot has the hash of these accounts, so this is
+        // only reachable if accounts don't survive an export
+        return Err(LedgerSnapshotError::LedgerHashMismatch {
+            expected: ledger_hash(&mut target).to_string(),
+            computed: ledger_hash(&mut imported),
+        });
+    }
+
+    Ok(migrated)
+}
+
+/// Writes a snapshot of `mask` to `path`, as a `daemon.json` holding only
+/// the ledger.
+pub fn write_snapshot(mask: &mut Mask, path: impl AsRef<Path>) -> Result<(), LedgerSnapshotError> {
+    let config = DaemonJson {
+        daemon: None,
+        ledger: Some(export_ledger(mask)),
+        genesis: None,
+        proof: None,
+        epoch_data: None,
+    };
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    serde_json::to_writer_pretty(file, &config)?;
+    Ok(())
+}
+
+/// Reads the ledger of a `daemon.json` written by [`write_snapshot`], or by
+/// the OCaml node.
+pub fn read_snapshot(path: impl AsRef<Path>) -> Result<Mask, LedgerSnapshotError> {
+    let file = std::io::BufReader::new(std::fs::File::open(path)?);
+    let config: DaemonJson = serde_json::from_reader(file)?;
+    let ledger = config.ledger.ok_or(LedgerSnapshotError::NoLedger)?;
+    import_ledger(&ledger)
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::{Account, TokenId};
+
+    use super::*;
+
+    fn snapshot_account(index: usize) -> Account {
+        let mut account = Account::rand();
+        // Only accounts of the default token have a delegate, defaulting
+        // to themselves
+        if index % 2 == 0 {
+            account.token_id = TokenId::default();
+            account.delegate = account.delegate.or(Some(account.public_key.clone()));
+        } else {
+            account.delegate = None;
+        }
+        account
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let db = ledger::Database::create_with_token_owners(ledger_depth() as u8);
+        let mut mask = Mask::new_root(db);
+        for index in 0..20 {
+            let account = snapshot_account(index);
+            mask.get_or_create_account(account.id(), account).unwrap();
+        }
+
+        let path =
+            std::env::temp_dir().join(format!("ledger-snapshot-{}.json", ledger::next_uuid()));
+        write_snapshot(&mut mask, &path).unwrap();
+        let mut imported = read_snapshot(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(ledger_hash(&mut imported), ledger_hash(&mut mask));
+        assert_eq!(imported.to_list(), mask.to_list());
+    }
+
+    #[test]
+    fn test_migrate_ledger() {
+        let mut mask = empty_ledger(ledger_depth()).unwrap();
+        for index in 0..20 {
+            let account = snapshot_account(index);
+            mask.get_or_create_account(account.id(), account).unwrap();
+        }
+        let ledger = export_ledger(&mut mask);
+
+        for depth in [5, 40] {
+            let migrated = migrate_ledger(&ledger, ledger_depth(), depth).unwrap();
+            assert_ne!(migrated.hash, ledger.hash);
+
+            let mut imported = import_ledger_with_depth(&migrated, depth).unwrap();
+            assert_eq!(imported.to_list(), mask.to_list());
+
+            // Back to the original depth and hash
+            let back = migrate_ledger(&migrated, depth, ledger_depth()).unwrap();
+            assert_eq!(back.hash, ledger.hash);
+        }
+
+        assert!(matches!(
+            migrate_ledger(&ledger, ledger_depth(), 4),
+            Err(LedgerSnapshotError::TooManyAccounts { .. })
+        ));
+        assert!(matches!(
+            migrate_ledger(&ledger, 34, 40),
+            Err(LedgerSnapshotError::LedgerHashMismatch { .. })
+        ));
+    }
+
+    /// The devnet genesis ledger, along with its hash, as configured for the
+    /// OCaml node.
+    #[test]
+    fn test_import_ocaml_ledger() {
+        let file = std::fs::File::open("testing/data/daemon.json").unwrap();
+        let config: DaemonJson = serde_json::from_reader(file).unwrap();
+        let ledger = config.ledger.unwrap();
+        let expected = ledger.hash.clone().unwrap();
+
+        let mut mask = import_ledger(&ledger).unwrap();
+        assert_eq!(ledger_hash(&mut mask).to_string(), expected);
+
+        let exported = export_ledger(&mut mask);
+        assert_eq!(exported.hash, Some(expected));
+        let mut imported = import_ledger(&exported).unwrap();
+        assert_eq!(imported.to_list(), mask.to_list());
+    }
+
+    /// Numbers, token symbols and zkApp URIs are strings, as in the JSON
+    /// layout of the OCaml runtime config.
+    #[test]
+    fn test_ocaml_account_layout() {
+        let json = serde_json::json!({
+            "pk": "B62qnLVz8wM7MfJsuYbjFf4UWbwrUBEL5ZdawExxxFhnGXB6siqokyM",
+            "balance": "1000.000000000",
+            "delegate": "B62qnLVz8wM7MfJsuYbjFf4UWbwrUBEL5ZdawExxxFhnGXB6siqokyM",
+            "token_symbol": "MINA",
+            "nonce": "7",
+            "permissions": {
+                "access": "none",
+                "edit_state": "proof",
+                "send": "signature",
+                "receive": "none",
+                "set_delegate": "signature",
+                "set_permissions": "signature",
+                "set_verification_key": { "auth": "signature", "txn_version": "3" },
+                "set_zkapp_uri": "signature",
+                "edit_action_state": "proof",
+                "set_token_symbol": "signature",
+                "increment_nonce": "signature",
+                "set_voting_for": "signature",
+                "set_timing": "signature"
+            },
+            "zkapp": {
+                "app_state": ["1", "0", "0", "0", "0", "0", "0", "0"],
+                "verification_key": null,
+                "zkapp_version": "1",
+                "action_state": ["0", "0", "0", "0", "0"],
+                "last_action_slot": "0",
+                "proved_state": false,
+                "zkapp_uri": "https://example.com"
+            }
+        });
+        let config: daemon_json::Account = serde_json::from_value(json.clone()).unwrap();
+        let account = config.to_account().unwrap();
+        assert_eq!(account.token_symbol.as_bytes(), b"MINA");
+        assert_eq!(account.nonce.as_u32(), 7);
+        assert_eq!(
+            account
+                .permissions
+                .set_verification_key
+                .txn_version
+                .as_u32(),
+            3
+        );
+        let zkapp = account.zkapp.as_ref().unwrap();
+        assert_eq!(zkapp.zkapp_version, 1);
+        assert_eq!(
+            zkapp.zkapp_uri,
+            ledger::ZkAppUri::from(b"https://example.com".to_vec())
+        );
+
+        let exported = serde_json::to_value(daemon_json::Account::from(&account)).unwrap();
+        for pointer in [
+            "/token_symbol",
+            "/nonce",
+            "/permissions/set_verification_key/txn_version",
+            "/zkapp/zkapp_version",
+            "/zkapp/zkapp_uri",
+        ] {
+            assert_eq!(
+                exported.pointer(pointer),
+                json.pointer(pointer),
+                "{pointer}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_snapshot_hash_mismatch() {
+        let db = ledger::Database::create_with_token_owners(ledger_depth() as u8);
+        let mut mask = Mask::new_root(db);
+        let account = snapshot_account(0);
+        mask.get_or_create_account(account.id(), account).unwrap();
+
+        let mut ledger = export_ledger(&mut mask);
+        ledger.accounts.as_mut().unwrap().pop();
+
+        assert!(matches!(
+            import_ledger(&ledger),
+            Err(LedgerSnapshotError::LedgerHashMismatch { .. })
+        ));
+    }
+}
@@ -1,4 +1,20 @@
+use std::time::Duration;
+
+use mina_core::requests::RequestRetryPolicy;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct LedgerConfig {}
+pub struct LedgerConfig {
+    /// Timeout of the reads from the ledger service, `None` to wait for the
+    /// service forever.
+    #[serde(default)]
+    pub read_timeout: Option<RequestRetryPolicy>,
+}
+
+impl Default for LedgerConfig {
+    fn default() -> Self {
+        Self {
+            read_timeout: Some(RequestRetryPolicy::new(Duration::from_secs(120), 1)),
+        }
+    }
+}
@@ -1,20 +1,30 @@
 use super::{
-    read::{LedgerReadId, LedgerReadRequest, LedgerReadResponse, LedgerStatus},
+    read::{
+        LedgerReadId, LedgerReadRequest, LedgerReadResponse, LedgerReadSnapshotRequest,
+        LedgerStatus,
+    },
     write::{LedgerWriteRequest, LedgerWriteResponse},
     LedgerCtx, LedgerService,
 };
 use crate::{
-    account::AccountPublicKey, ledger::LedgerAddress, rpc::AccountQuery,
-    transition_frontier::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedService,
+    account::AccountPublicKey,
+    ledger::LedgerAddress,
+    rpc::RpcPendingCoinbase,
+    transition_frontier::sync::ledger::{
+        snarked::TransitionFrontierSyncLedgerSnarkedService, LedgerSnapshot,
+    },
 };
 use ledger::{
     staged_ledger::staged_ledger::{SkipVerification, StagedLedger},
     Account, AccountId, Mask,
 };
-use mina_core::{channels::mpsc, thread};
+use mina_core::{block::AppliedBlock, channels::mpsc, thread};
 use mina_p2p_messages::v2::{self, LedgerHash, MinaBaseAccountBinableArgStableV2};
 use mina_signer::CompressedPubKey;
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Mutex},
+};
 
 /// The type enumerating different requests that can be made to the
 /// service. Each specific constructor has a specific response
@@ -31,6 +41,10 @@ pub(super) enum LedgerRequest {
         parent: LedgerAddress,
         accounts: Vec<MinaBaseAccountBinableArgStableV2>,
     }, // expected response: LedgerHash
+    SnarkedLedgerImport {
+        snarked_ledger_hash: LedgerHash,
+        mask: Mask,
+    }, // expected response: SnarkedLedgerImported
     AccountsGet {
         ledger_hash: LedgerHash,
         account_ids: Vec<AccountId>,
@@ -54,6 +68,13 @@ pub(super) enum LedgerRequest {
     GetMask {
         ledger_hash: LedgerHash,
     }, // expected response: LedgerMask
+    PendingCoinbaseGet {
+        staged_ledger_hash: v2::MinaBaseStagedLedgerHashStableV1,
+    }, // expected response: PendingCoinbase
+    PendingCoinbaseReplay {
+        block: AppliedBlock,
+        pred_block: AppliedBlock,
+    }, // expected response: PendingCoinbaseReplayed
     InsertGenesisLedger {
         mask: Mask,
     },
@@ -69,6 +90,7 @@ pub enum LedgerResponse {
     Read(LedgerReadId, LedgerReadResponse),
     ChildHashes(Option<(LedgerHash, LedgerHash)>),
     AccountsSet(Result<LedgerHash, String>),
+    SnarkedLedgerImported(Result<(), String>),
     AccountsGet(Result<Vec<Account>, String>),
     LedgerMask(Option<(Mask, bool)>),
     #[allow(clippy::type_complexity)]
@@ -76,6 +98,8 @@ pub enum LedgerResponse {
         Option<BTreeMap<AccountPublicKey, Vec<(ledger::AccountIndex, AccountPublicKey, u64)>>>,
     ),
     SnarkedLedgerContentsCopied(Result<bool, String>),
+    PendingCoinbase(Option<RpcPendingCoinbase>),
+    PendingCoinbaseReplayed(Result<v2::PendingCoinbaseHash, String>),
     Success, // operation was performed and result stored; nothing to return.
 }
 
@@ -134,6 +158,7 @@ impl LedgerRequest {
                     completed_snarks,
                     supercharge_coinbase,
                     transactions_by_fee,
+                    work_fee_policy,
                 } => {
                     let pred_block_hash = pred_block.hash().clone();
                     let global_slot_since_genesis = global_slot.clone();
@@ -147,6 +172,7 @@ impl LedgerRequest {
                         completed_snarks,
                         supercharge_coinbase,
                         transactions_by_fee,
+                        work_fee_policy,
                     );
                     LedgerWriteResponse::StagedLedgerDiffCreate {
                         pred_block_hash,
@@ -207,6 +233,16 @@ impl LedgerRequest {
 
                         LedgerReadResponse::DelegatorTable(res)
                     }
+                    LedgerReadRequest::CoinbaseReceiverAccount(ledger_hash, public_key) => {
+                        let res = CompressedPubKey::try_from(public_key)
+                            .ok()
+                            .and_then(|public_key| {
+                                let id = AccountId::new_with_default_token(public_key);
+                                ledger_ctx.get_accounts(ledger_hash, vec![id]).pop()
+                            })
+                            .map(Box::new);
+                        LedgerReadResponse::CoinbaseReceiverAccount(res)
+                    }
                     LedgerReadRequest::GetNumAccounts(ledger_hash) => {
                         let res = ledger_ctx.get_num_accounts(ledger_hash);
                         LedgerReadResponse::GetNumAccounts(res)
@@ -235,24 +271,27 @@ impl LedgerRequest {
                         LedgerReadResponse::GetAccounts(res, rpc_id)
                     }
                     LedgerReadRequest::AccountsForRpc(rpc_id, ledger_hash, account_query) => {
-                        let res = match &account_query {
-                            AccountQuery::All => ledger_ctx.get_accounts_for_rpc(ledger_hash, None),
-                            AccountQuery::SinglePublicKey(public_key) => ledger_ctx
-                                .get_accounts_for_rpc(ledger_hash, Some(public_key.clone())),
-                            AccountQuery::PubKeyWithTokenId(public_key, token_id_key_hash) => {
-                                let id = AccountId {
-                                    public_key: public_key.clone().try_into().unwrap(),
-                                    token_id: token_id_key_hash.clone().into(),
-                                };
-                                ledger_ctx.get_accounts(ledger_hash, vec![id])
-                            }
-                            AccountQuery::MultipleIds(ids) => {
-                                ledger_ctx.get_accounts(ledger_hash, ids.clone())
-                            }
-                        };
-
+                        let res = ledger_ctx.get_accounts_for_query(ledger_hash, &account_query);
                         LedgerReadResponse::AccountsForRpc(rpc_id, res, account_query)
                     }
+                    LedgerReadRequest::AccountsPageForRpc(rpc_id, anchor, ledger_hash, query) => {
+                        let res =
+                            ledger_ctx.get_accounts_page_for_rpc(&anchor, ledger_hash, &query);
+                        LedgerReadResponse::AccountsPageForRpc(rpc_id, res)
+                    }
+                    LedgerReadRequest::AccountsAtBlockForRpc(
+                        rpc_id,
+                        block_hash,
+                        ledger_hash,
+                        account_query,
+                    ) => {
+                        let res = ledger_ctx.get_accounts_at_block_for_rpc(
+                            &block_hash,
+                            ledger_hash,
+                            &account_query,
+                        );
+                        LedgerReadResponse::AccountsAtBlockForRpc(rpc_id, res)
+                    }
                     LedgerReadRequest::GetLedgerStatus(rpc_id, ledger_hash) => {
                         let res = ledger_ctx.get_num_accounts(ledger_hash).map(
                             |(num_accounts, ledger_hash)| LedgerStatus {
@@ -267,6 +306,35 @@ impl LedgerRequest {
                         let res = ledger_ctx.get_account_delegators(&ledger_hash, &account_id);
                         LedgerReadResponse::GetAccountDelegators(rpc_id, res)
                     }
+                    LedgerReadRequest::GetZkappActionsEvents(rpc_id, query) => {
+                        let res = ledger_ctx.actions_events(&query);
+                        LedgerReadResponse::GetZkappActionsEvents(rpc_id, res)
+                    }
+                    LedgerReadRequest::GetTokenSupply(rpc_id, ledger_hash, token_id) => {
+                        let res = ledger_ctx.get_token_supply(&ledger_hash, &token_id.into());
+                        LedgerReadResponse::GetTokenSupply(rpc_id, res)
+                    }
+                    LedgerReadRequest::GetPendingCoinbase(rpc_id, staged_ledger_hash) => {
+                        let res = ledger_ctx.pending_coinbase(&staged_ledger_hash);
+                        LedgerReadResponse::GetPendingCoinbase(rpc_id, res)
+                    }
+                    LedgerReadRequest::MerkleRoots(ledger_hashes) => {
+                        LedgerReadResponse::MerkleRoots(ledger_ctx.merkle_roots(ledger_hashes))
+                    }
+                    LedgerReadRequest::GetLedgerSnapshot(rpc_id, request) => {
+                        let res = match request {
+                            LedgerReadSnapshotRequest::SnarkedLedger(ledger_hash) => ledger_ctx
+                                .get_child_accounts(ledger_hash, LedgerAddress::root())
+                                .map(LedgerSnapshot::SnarkedLedger),
+                            LedgerReadSnapshotRequest::StagedLedgerParts(data) => ledger_ctx
+                                .staged_ledger_aux_and_pending_coinbase(
+                                    &data.ledger_hash,
+                                    data.protocol_states,
+                                )
+                                .map(LedgerSnapshot::StagedLedgerParts),
+                        };
+                        LedgerReadResponse::GetLedgerSnapshot(rpc_id, res)
+                    }
                 },
             ),
             LedgerRequest::AccountsSet {
@@ -318,6 +386,13 @@ impl LedgerRequest {
             LedgerRequest::GetMask { ledger_hash } => {
                 LedgerResponse::LedgerMask(ledger_ctx.mask(&ledger_hash))
             }
+            LedgerRequest::PendingCoinbaseGet { staged_ledger_hash } => {
+                LedgerResponse::PendingCoinbase(ledger_ctx.pending_coinbase(&staged_ledger_hash))
+            }
+            LedgerRequest::PendingCoinbaseReplay { block, pred_block } => {
+                let res = ledger_ctx.pending_coinbase_replay(&block, &pred_block);
+                LedgerResponse::PendingCoinbaseReplayed(res)
+            }
             LedgerRequest::GetProducersWithDelegates {
                 ledger_hash,
                 filter,
@@ -345,6 +420,12 @@ impl LedgerRequest {
                     result,
                 })
             }
+            LedgerRequest::SnarkedLedgerImport {
+                snarked_ledger_hash,
+                mask,
+            } => LedgerResponse::SnarkedLedgerImported(
+                ledger_ctx.snarked_ledger_import(snarked_ledger_hash, mask),
+            ),
             LedgerRequest::AccountsGet {
                 ledger_hash,
                 account_ids,
@@ -363,6 +444,8 @@ struct LedgerRequestWithChan {
 
 pub struct LedgerManager {
     caller: LedgerCaller,
+    /// Reads sent to the ledger thread and not answered yet.
+    reads_in_flight: Arc<Mutex<BTreeSet<LedgerReadId>>>,
     join_handle: thread::JoinHandle<LedgerCtx>,
 }
 
@@ -374,11 +457,20 @@ impl LedgerManager {
         let (sender, mut receiver) = mpsc::tracked_unbounded_channel();
         let caller = LedgerCaller(sender);
         let ledger_caller = caller.clone();
+        let reads_in_flight = Arc::new(Mutex::new(BTreeSet::new()));
+        let reads_answered = reads_in_flight.clone();
 
         let ledger_manager_loop = move || {
             while let Some(msg) = receiver.blocking_recv() {
                 let LedgerRequestWithChan { request, responder } = msg.0;
+                let read_id = match &request {
+                    LedgerRequest::Read(id, _) => Some(*id),
+                    _ => None,
+                };
                 let response = request.handle(&mut ledger_ctx, &ledger_caller, responder.is_some());
+                if let Some(id) = read_id {
+                    reads_answered.lock().unwrap().remove(&id);
+                }
                 match (response, responder) {
                     (LedgerResponse::Write(resp), None) => {
                         ledger_ctx.send_write_response(resp);
@@ -408,14 +500,26 @@ impl LedgerManager {
             .expect("Failed: ledger manager");
         LedgerManager {
             caller,
+            reads_in_flight,
             join_handle,
         }
     }
 
+    /// Marks the read `id` as in flight. Returns `false` if it already is,
+    /// e.g. when a read which timed out is sent again while the ledger
+    /// thread is still busy with it, so that it isn't done twice.
+    pub(super) fn read_start(&self, id: LedgerReadId) -> bool {
+        self.reads_in_flight.lock().unwrap().insert(id)
+    }
+
     pub fn pending_calls(&self) -> usize {
         self.caller.0.len()
     }
 
+    pub fn accounts_importer(&self) -> LedgerAccountsImporter {
+        LedgerAccountsImporter(self.caller.clone())
+    }
+
     pub(super) fn call(&self, request: LedgerRequest) {
         self.caller.call(request)
     }
@@ -459,6 +563,34 @@ impl LedgerManager {
         }
     }
 
+    pub fn pending_coinbase(
+        &self,
+        staged_ledger_hash: &v2::MinaBaseStagedLedgerHashStableV1,
+    ) -> Option<RpcPendingCoinbase> {
+        match self.call_sync(LedgerRequest::PendingCoinbaseGet {
+            staged_ledger_hash: staged_ledger_hash.clone(),
+        }) {
+            Ok(LedgerResponse::PendingCoinbase(res)) => res,
+            _ => panic!("pending_coinbase failed"),
+        }
+    }
+
+    /// Pending coinbase merkle root of `block`, recomputed from the diff of
+    /// the block applied to the staged ledger of `pred_block`.
+    pub fn pending_coinbase_replay(
+        &self,
+        block: &AppliedBlock,
+        pred_block: &AppliedBlock,
+    ) -> Result<v2::PendingCoinbaseHash, String> {
+        match self.call_sync(LedgerRequest::PendingCoinbaseReplay {
+            block: block.clone(),
+            pred_block: pred_block.clone(),
+        }) {
+            Ok(LedgerResponse::PendingCoinbaseReplayed(res)) => res,
+            _ => panic!("pending_coinbase_replay failed"),
+        }
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn producers_with_delegates(
         &self,
@@ -575,7 +707,28 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerSnarkedService for T {
         parent: &LedgerAddress,
         accounts: Vec<MinaBaseAccountBinableArgStableV2>,
     ) -> Result<LedgerHash, String> {
-        self.ledger_manager()
+        self.ledger_manager().accounts_importer().accounts_set(
+            snarked_ledger_hash,
+            parent,
+            accounts,
+        )
+    }
+}
+
+/// Writes accounts into a snarked ledger being synced, from threads other
+/// than the state machine's, e.g. once a ledger snapshot is downloaded.
+#[derive(Clone)]
+pub struct LedgerAccountsImporter(LedgerCaller);
+
+impl LedgerAccountsImporter {
+    /// Sets the accounts of the subtree at `parent` and returns its hash.
+    pub fn accounts_set(
+        &self,
+        snarked_ledger_hash: LedgerHash,
+        parent: &LedgerAddress,
+        accounts: Vec<MinaBaseAccountBinableArgStableV2>,
+    ) -> Result<LedgerHash, String> {
+        self.0
             .call_sync(LedgerRequest::AccountsSet {
                 snarked_ledger_hash,
                 parent: parent.clone(),
@@ -590,4 +743,26 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerSnarkedService for T {
                 }
             })
     }
+
+    /// Replaces the snarked ledger being synced with `mask`, if its root
+    /// is `snarked_ledger_hash`.
+    pub fn snarked_ledger_import(
+        &self,
+        snarked_ledger_hash: LedgerHash,
+        mask: Mask,
+    ) -> Result<(), String> {
+        self.0
+            .call_sync(LedgerRequest::SnarkedLedgerImport {
+                snarked_ledger_hash,
+                mask,
+            })
+            .map_err(|_| "snarked_ledger_import responder dropped".to_owned())
+            .and_then(|res| {
+                if let LedgerResponse::SnarkedLedgerImported(res) = res {
+                    res
+                } else {
+                    Err(format_response_error("snarked_ledger_import", res))
+                }
+            })
+    }
 }
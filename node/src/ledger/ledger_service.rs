@@ -1,11 +1,12 @@
 use super::{
-    ledger_empty_hash_at_depth,
+    ledger_depth, ledger_empty_hash_at_depth,
     read::{LedgerReadId, LedgerReadRequest, LedgerReadResponse},
     write::{CommitResult, LedgerWriteRequest, LedgerWriteResponse, LedgersToKeep},
-    LedgerAddress, LedgerEvent, LEDGER_DEPTH,
+    LedgerAddress, LedgerEvent,
 };
 use crate::{
     account::AccountPublicKey,
+    block_producer::BlockProducerWorkFeePolicy,
     block_producer_effectful::StagedLedgerDiffCreateOutput,
     ledger::{
         ledger_manager::{LedgerManager, LedgerRequest},
@@ -13,8 +14,11 @@ use crate::{
     },
     p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases,
     rpc::{
+        AccountQuery, RpcBlockPrunedError, RpcLedgerAccountsAtBlockGetResponse, RpcPage,
+        RpcPageError, RpcPageQuery, RpcPageResult, RpcPendingCoinbase, RpcPendingCoinbaseStack,
         RpcScanStateSummaryBlockTransaction, RpcScanStateSummaryScanStateJob,
         RpcScanStateSummaryScanStateJobKind, RpcSnarkPoolJobSnarkWorkDone,
+        RpcZkappActionsEventsQuery, RPC_PAGE_MAX_LIMIT,
     },
     transition_frontier::{
         genesis::empty_pending_coinbase_hash,
@@ -25,8 +29,9 @@ use crate::{
     },
 };
 use ledger::{
+    ondisk::IoFaults,
     scan_state::{
-        currency::Slot,
+        currency::{Amount, Slot},
         scan_state::{AvailableJobMessage, JobValueBase, JobValueMerge, JobValueWithIndex, Pass},
         transaction_logic::{
             local_state::LocalState,
@@ -39,12 +44,14 @@ use ledger::{
     },
     sparse_ledger::SparseLedger,
     staged_ledger::{
-        diff::Diff,
+        action_event_store::{ActionEventStore, ActionsEvents},
+        block_diff::{apply_block_diff, validate_block_diff, BlockDiffError},
         staged_ledger::{SkipVerification, StagedLedger},
         validate_block::block_body_hash,
     },
     verifier::Verifier,
-    Account, AccountId, AccountIndex, BaseLedger, Database, Mask, TokenId, UnregisterBehavior,
+    Account, AccountId, AccountIndex, Address, BaseLedger, Database, Mask, TokenId,
+    UnregisterBehavior,
 };
 use mina_core::{
     block::{AppliedBlock, ArcBlockWithHash},
@@ -69,8 +76,9 @@ use mina_p2p_messages::{
 };
 use mina_signer::CompressedPubKey;
 use std::{
+    cell::Cell,
     collections::{BTreeMap, BTreeSet},
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
@@ -78,6 +86,36 @@ fn merkle_root(mask: &mut Mask) -> LedgerHash {
     MinaBaseLedgerHash0StableV1(mask.merkle_root().into()).into()
 }
 
+/// Copies the accounts of `mask` into a new ledger stored in a
+/// subdirectory of `directory` named after its hash.
+fn ondisk_ledger(mask: &mut Mask, directory: &Path, io_faults: IoFaults) -> std::io::Result<Mask> {
+    let depth = mask.depth();
+    let directory = directory.join(merkle_root(mask).to_string());
+    let mut db = Database::create_ondisk(depth, directory)?;
+    db.set_io_faults(io_faults);
+    if mask.has_token_owners() {
+        db.set_token_owners();
+    }
+    let accounts = mask
+        .to_list()
+        .into_iter()
+        .enumerate()
+        .map(|(index, account)| {
+            let addr = Address::from_index(AccountIndex(index as u64), depth as usize);
+            (addr, Box::new(account))
+        })
+        .collect::<Vec<_>>();
+    db.set_batch_with_hashes(&accounts);
+
+    let mut ondisk = Mask::new_root(db);
+    assert_eq!(
+        merkle_root(&mut ondisk),
+        merkle_root(mask),
+        "on-disk genesis ledger hash mismatch"
+    );
+    Ok(ondisk)
+}
+
 fn error_to_string(e: InvalidBigInt) -> String {
     format!("{:?}", e)
 }
@@ -177,7 +215,13 @@ pub struct LedgerCtx {
     sync: LedgerSyncState,
     /// Returns more data on block application necessary for archive node
     archive_mode: bool,
-    event_sender: Option<mina_core::channels::mpsc::UnboundedSender<crate::event_source::Event>>,
+    /// Directory the accounts of the genesis ledgers are stored in, see
+    /// [`LedgerCtx::set_ondisk_ledger`].
+    ondisk_ledger: Option<(PathBuf, IoFaults)>,
+    /// Actions and events of the zkApp commands of applied blocks, with the
+    /// file they are saved to, see [`LedgerCtx::set_action_event_store`].
+    action_event_store: Option<(PathBuf, ActionEventStore)>,
+    event_sender: Option<Box<dyn Fn(crate::event_source::Event) + Send>>,
 }
 
 #[derive(Default)]
@@ -225,18 +269,39 @@ impl LedgerCtx {
         self.archive_mode = true;
     }
 
+    /// Stores the accounts of the genesis ledgers, which are the roots of
+    /// the other ledgers, in `directory` instead of in memory. `io_faults` are
+    /// injected into its I/O, to test the node on slow disks.
+    pub fn set_ondisk_ledger(&mut self, directory: PathBuf, io_faults: IoFaults) {
+        self.ondisk_ledger = Some((directory, io_faults));
+    }
+
+    /// Keeps the actions and events of the zkApp commands of applied blocks,
+    /// to serve them over RPC. They are saved to `path` on each commit and
+    /// read back from it.
+    pub fn set_action_event_store(&mut self, path: PathBuf) {
+        let store = ActionEventStore::load(&path, ActionEventStore::DEFAULT_HISTORY_SLOTS)
+            .unwrap_or_else(|error| {
+                mina_core::warn!(mina_core::log::system_time();
+                    kind = "LedgerService::set_action_event_store",
+                    summary = format!("starting from an empty store, failed to read {path:?}: {error}"));
+                ActionEventStore::default()
+            });
+        self.action_event_store = Some((path, store));
+    }
+
     // TODO(tizoc): Only used for the current workaround to make staged ledger
     // reconstruction async, can be removed when the ledger services are made async
     pub fn set_event_sender(
         &mut self,
-        event_sender: mina_core::channels::mpsc::UnboundedSender<crate::event_source::Event>,
+        event_sender: impl Fn(crate::event_source::Event) + Send + 'static,
     ) {
-        self.event_sender = Some(event_sender);
+        self.event_sender = Some(Box::new(event_sender));
     }
 
     pub(super) fn send_event(&self, event: LedgerEvent) {
-        if let Some(tx) = self.event_sender.as_ref() {
-            let _ = tx.send(event.into());
+        if let Some(send) = self.event_sender.as_ref() {
+            send(event.into());
         }
     }
 
@@ -249,6 +314,10 @@ impl LedgerCtx {
     }
 
     pub fn insert_genesis_ledger(&mut self, mut mask: Mask) {
+        if let Some((directory, io_faults)) = &self.ondisk_ledger {
+            mask = ondisk_ledger(&mut mask, directory, io_faults.clone())
+                .expect("failed to store genesis ledger on disk");
+        }
         let merkle_root_hash = merkle_root(&mut mask);
         let staged_ledger =
             StagedLedger::create_exn(constraint_constants().clone(), mask.copy()).unwrap();
@@ -293,6 +362,91 @@ impl LedgerCtx {
         }
     }
 
+    pub fn get_accounts_for_query(
+        &mut self,
+        ledger_hash: LedgerHash,
+        query: &AccountQuery,
+    ) -> Vec<Account> {
+        match query {
+            AccountQuery::All => self.get_accounts_for_rpc(ledger_hash, None),
+            AccountQuery::SinglePublicKey(public_key) => {
+                self.get_accounts_for_rpc(ledger_hash, Some(public_key.clone()))
+            }
+            AccountQuery::PubKeyWithTokenId(public_key, token_id_key_hash) => {
+                let id = AccountId {
+                    public_key: public_key.clone().try_into().unwrap(),
+                    token_id: token_id_key_hash.clone().into(),
+                };
+                self.get_accounts(ledger_hash, vec![id])
+            }
+            AccountQuery::MultipleIds(ids) => self.get_accounts(ledger_hash, ids.clone()),
+            AccountQuery::Token {
+                token_id,
+                offset,
+                limit,
+            } => self.get_token_accounts_for_rpc(
+                ledger_hash,
+                token_id.clone().into(),
+                *offset,
+                (*limit).min(RPC_PAGE_MAX_LIMIT),
+            ),
+        }
+    }
+
+    /// Accounts of the ledger at the block `block_hash`, or an error if
+    /// the mask of that block was dropped when it was pruned from the
+    /// transition frontier.
+    pub fn get_accounts_at_block_for_rpc(
+        &mut self,
+        block_hash: &StateHash,
+        ledger_hash: LedgerHash,
+        query: &AccountQuery,
+    ) -> RpcLedgerAccountsAtBlockGetResponse {
+        if self.mask(&ledger_hash).is_none() {
+            return Err(RpcBlockPrunedError(block_hash.clone()));
+        }
+        Ok(self.get_accounts_for_query(ledger_hash, query))
+    }
+
+    /// Page of the accounts of `token_id`, for token explorers.
+    pub fn get_token_accounts_for_rpc(
+        &self,
+        ledger_hash: LedgerHash,
+        token_id: TokenId,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<Account> {
+        self.mask(&ledger_hash)
+            .map(|(mask, _)| mask.accounts_for_token(&token_id, offset, limit))
+            .unwrap_or_default()
+    }
+
+    /// Sum of the balances of the accounts of `token_id`, `None` if the
+    /// ledger isn't there or if the sum overflows.
+    pub fn get_token_supply(&self, ledger_hash: &LedgerHash, token_id: &TokenId) -> Option<Amount> {
+        let (mask, _) = self.mask(ledger_hash)?;
+        mask.token_supply(token_id)
+    }
+
+    /// Page of the accounts of the ledger at the `anchor` block, in the
+    /// order of their index.
+    pub fn get_accounts_page_for_rpc(
+        &self,
+        anchor: &StateHash,
+        ledger_hash: LedgerHash,
+        query: &RpcPageQuery,
+    ) -> RpcPageResult<Account> {
+        let (mask, _) = self
+            .mask(&ledger_hash)
+            .ok_or_else(|| RpcPageError::AnchorNotFound(anchor.clone()))?;
+
+        let accounts = (query.offset()..mask.num_accounts())
+            .map_while(|index| mask.get_at_index(AccountIndex(index as u64)))
+            .map(|account| *account);
+
+        Ok(RpcPage::from_offset(anchor, accounts, query))
+    }
+
     // TODO(tizoc): explain when `is_synced` is `true` and when it is `false`. Also use something else than a boolean.
     /// Returns a tuple of `(mask, is_synced)` for a [Mask] with the specified `hash` if it exists or `None` otherwise.
     pub fn mask(&self, hash: &LedgerHash) -> Option<(Mask, bool)> {
@@ -369,9 +523,9 @@ impl LedgerCtx {
                 snarked_ledger_hash
             ))?;
 
-        // Our ledger is lazy when it comes to hashing, but retrieving the
-        // merkle root hash forces all pending hashes to be computed.
-        let _force_hashing = origin.merkle_root();
+        // Our ledger is lazy when it comes to hashing, compute all the
+        // hashes at once, in parallel, now that all the accounts are there.
+        let _force_hashing = origin.recompute_hashes_parallel();
 
         Ok(())
     }
@@ -607,6 +761,27 @@ impl LedgerCtx {
         Ok(computed_hash)
     }
 
+    /// Replaces the snarked ledger being synced with `mask`, a ledger built
+    /// apart from it, e.g. from a snapshot. The sync ledger is left
+    /// untouched unless the root of `mask` is `snarked_ledger_hash`.
+    pub fn snarked_ledger_import(
+        &mut self,
+        snarked_ledger_hash: LedgerHash,
+        mut mask: Mask,
+    ) -> Result<(), String> {
+        if !self.sync.snarked_ledgers.contains_key(&snarked_ledger_hash) {
+            return Err(format!("Missing sync snarked ledger {snarked_ledger_hash}"));
+        }
+        let computed_hash = merkle_root(&mut mask);
+        if computed_hash != snarked_ledger_hash {
+            return Err(format!(
+                "Imported ledger hashes to {computed_hash}, expected {snarked_ledger_hash}"
+            ));
+        }
+        self.sync.snarked_ledgers.insert(snarked_ledger_hash, mask);
+        Ok(())
+    }
+
     pub fn staged_ledger_reconstruct<F>(
         &mut self,
         snarked_ledger_hash: LedgerHash,
@@ -680,63 +855,44 @@ impl LedgerCtx {
             })?
             .clone();
 
-        let global_slot = block.global_slot_since_genesis();
-        let prev_protocol_state = &pred_block.header().protocol_state;
-        let prev_state_view = protocol_state_view(prev_protocol_state).map_err(error_to_string)?;
-
         let consensus_state = &block.header().protocol_state.body.consensus_state;
         let coinbase_receiver: CompressedPubKey = (&consensus_state.coinbase_receiver)
             .try_into()
             .map_err(error_to_string)?;
-        let supercharge_coinbase = consensus_state.supercharge_coinbase;
-
-        let diff: Diff = (&block.body().staged_ledger_diff)
-            .try_into()
-            .map_err(error_to_string)?;
 
-        let prev_protocol_state: ledger::proofs::block::ProtocolState =
-            prev_protocol_state.try_into()?;
-
-        let result = staged_ledger
-            .apply(
-                skip_verification,
-                constraint_constants(),
-                Slot::from_u32(global_slot),
-                diff,
-                (),
-                &Verifier,
-                &prev_state_view,
-                prev_protocol_state.hashes(),
-                coinbase_receiver.clone(),
-                supercharge_coinbase,
-            )
-            .map_err(|err| format!("{err:?}"))?;
+        let result = match validate_block_diff(
+            &mut staged_ledger,
+            constraint_constants(),
+            &Verifier,
+            skip_verification,
+            &block.block,
+            &pred_block.header().protocol_state,
+        ) {
+            Ok(result) => result,
+            Err(err @ BlockDiffError::HashMismatch { .. }) => {
+                let staged_ledger = self
+                    .staged_ledger_mut(pred_block.staged_ledger_hashes())
+                    .unwrap(); // We already know the ledger exists, see the same call a few lines above
+
+                match dump_application_to_file(staged_ledger, block.clone(), pred_block) {
+                    Ok(filename) => mina_core::info!(
+                        mina_core::log::system_time();
+                        kind = "LedgerService::dump - Failed application",
+                        summary = format!("StagedLedger and block saved to: {filename:?}")
+                    ),
+                    Err(e) => mina_core::error!(
+                        mina_core::log::system_time();
+                        kind = "LedgerService::dump - Failed application",
+                        summary = format!("Failed to save block application to file: {e:?}")
+                    ),
+                }
+                return Err(format!("{err:?}"));
+            }
+            Err(err) => return Err(format!("{err:?}")),
+        };
         let just_emitted_a_proof = result.ledger_proof.is_some();
         let ledger_hashes = MinaBaseStagedLedgerHashStableV1::from(&result.hash_after_applying);
 
-        // TODO(binier): return error if not matching.
-        let expected_ledger_hashes = block.staged_ledger_hashes();
-        if &ledger_hashes != expected_ledger_hashes {
-            let staged_ledger = self
-                .staged_ledger_mut(pred_block.staged_ledger_hashes())
-                .unwrap(); // We already know the ledger exists, see the same call a few lines above
-
-            match dump_application_to_file(staged_ledger, block.clone(), pred_block) {
-                Ok(filename) => mina_core::info!(
-                    mina_core::log::system_time();
-                    kind = "LedgerService::dump - Failed application",
-                    summary = format!("StagedLedger and block saved to: {filename:?}")
-                ),
-                Err(e) => mina_core::error!(
-                    mina_core::log::system_time();
-                    kind = "LedgerService::dump - Failed application",
-                    summary = format!("Failed to save block application to file: {e:?}")
-                ),
-            }
-
-            panic!("staged ledger hash mismatch. found: {ledger_hashes:#?}, expected: {expected_ledger_hashes:#?}");
-        }
-
         let archive_data = if self.archive_mode {
             let senders = block
                 .body()
@@ -855,6 +1011,19 @@ impl LedgerCtx {
             None
         };
 
+        if let Some((_, store)) = &mut self.action_event_store {
+            let commands = block
+                .body()
+                .tranasctions_with_status()
+                .filter_map(|(tx, status)| Some((UserCommand::try_from(tx).ok()?, status.into())));
+            store.add_block(
+                block.hash().clone(),
+                block.pred_hash().clone(),
+                Slot::from_u32(block.global_slot_since_genesis()),
+                commands,
+            );
+        }
+
         self.sync
             .staged_ledgers
             .insert(Arc::new(ledger_hashes), staged_ledger);
@@ -865,6 +1034,7 @@ impl LedgerCtx {
             block,
             just_emitted_a_proof,
             archive_data,
+            vrf_evaluation: None,
         })
     }
 
@@ -884,6 +1054,17 @@ impl LedgerCtx {
             new_root_next_epoch_ledger = new_root.next_epoch_ledger_hash().to_string(),
             new_root_snarked_ledger = new_root.snarked_ledger_hash().to_string(),
         );
+        if let Some((path, store)) = &mut self.action_event_store {
+            store.commit(
+                Slot::from_u32(new_root.global_slot_since_genesis()),
+                new_best_tip.hash(),
+            );
+            if let Err(error) = store.save(path) {
+                mina_core::warn!(mina_core::log::system_time();
+                    kind = "LedgerService::commit",
+                    summary = format!("failed to save zkApp actions and events to {path:?}: {error}"));
+            }
+        }
         self.recreate_snarked_ledger(
             &root_snarked_ledger_updates,
             &needed_protocol_states,
@@ -1025,6 +1206,33 @@ impl LedgerCtx {
         }
     }
 
+    /// Actions and events of the account on the best chain, `None` if they
+    /// aren't kept, see [`LedgerCtx::set_action_event_store`].
+    pub fn actions_events(&self, query: &RpcZkappActionsEventsQuery) -> Option<Vec<ActionsEvents>> {
+        let (_, store) = self.action_event_store.as_ref()?;
+        let from = Slot::from_u32(query.from_slot.unwrap_or(0));
+        let to = Slot::from_u32(query.to_slot.unwrap_or(u32::MAX));
+        Some(store.get(&query.account_id, from..=to))
+    }
+
+    /// Merkle roots of the ledgers stored under `ledger_hashes`, which
+    /// differ from them only if a ledger got corrupted.
+    pub fn merkle_roots(
+        &self,
+        ledger_hashes: Vec<LedgerHash>,
+    ) -> Vec<(LedgerHash, Option<LedgerHash>)> {
+        ledger_hashes
+            .into_iter()
+            .map(|ledger_hash| {
+                let root = self
+                    .mask(&ledger_hash)
+                    .filter(|(_, is_synced)| *is_synced)
+                    .map(|(mut mask, _)| merkle_root(&mut mask));
+                (ledger_hash, root)
+            })
+            .collect()
+    }
+
     pub fn get_num_accounts(
         &mut self,
         ledger_hash: v2::LedgerHash,
@@ -1036,7 +1244,7 @@ impl LedgerCtx {
 
         let num_accounts = mask.num_accounts() as u64;
         let first_node_addr = ledger::Address::first(
-            LEDGER_DEPTH.saturating_sub(super::tree_height_for_num_accounts(num_accounts)),
+            ledger_depth().saturating_sub(super::tree_height_for_num_accounts(num_accounts)),
         );
         let hash = LedgerHash::from_fp(mask.get_hash(first_node_addr)?);
         Some((num_accounts, hash))
@@ -1146,6 +1354,7 @@ impl LedgerCtx {
         completed_snarks: BTreeMap<SnarkJobId, Snark>,
         supercharge_coinbase: bool,
         transactions_by_fee: Vec<valid::UserCommand>,
+        work_fee_policy: BlockProducerWorkFeePolicy,
     ) -> Result<StagedLedgerDiffCreateOutput, String> {
         let mut staged_ledger = self
             .staged_ledger_mut(pred_block.staged_ledger_hashes())
@@ -1165,6 +1374,9 @@ impl LedgerCtx {
         let protocol_state_view =
             protocol_state_view(&pred_block.header().protocol_state).map_err(error_to_string)?;
 
+        let work_fee_spent = Cell::new(0u64);
+        let work_limited_by_policy = Cell::new(false);
+
         // TODO(binier): include `invalid_txns` in output.
         let (pre_diff, _invalid_txns) = staged_ledger
             .create_diff(
@@ -1177,10 +1389,15 @@ impl LedgerCtx {
                 transactions_by_fee,
                 |stmt| {
                     let job_id = SnarkJobId::from(stmt);
-                    match completed_snarks.get(&job_id) {
-                        Some(snark) => snark.try_into().ok(),
-                        None => None,
+                    let snark = completed_snarks.get(&job_id)?;
+                    let fee = snark.fee.as_u64();
+                    if !work_fee_policy.allows(work_fee_spent.get(), fee) {
+                        work_limited_by_policy.set(true);
+                        return None;
                     }
+                    let work = snark.try_into().ok()?;
+                    work_fee_spent.set(work_fee_spent.get().saturating_add(fee));
+                    Some(work)
                 },
                 supercharge_coinbase,
             )
@@ -1236,6 +1453,7 @@ impl LedgerCtx {
             stake_proof_sparse_ledger: self
                 .stake_proof_sparse_ledger(staking_ledger_hash, &producer, &delegator)
                 .map_err(error_to_string)?,
+            work_limited_by_policy: work_limited_by_policy.get(),
         })
     }
 
@@ -1392,6 +1610,71 @@ impl LedgerCtx {
             })
             .collect()
     }
+
+    pub fn pending_coinbase(
+        &self,
+        staged_ledger_hash: &MinaBaseStagedLedgerHashStableV1,
+    ) -> Option<RpcPendingCoinbase> {
+        use ledger::scan_state::conv::to_pending_coinbase_hash;
+
+        let mut collection = self
+            .staged_ledgers
+            .get(staged_ledger_hash)?
+            .pending_coinbase_collection()
+            .clone();
+        Some(RpcPendingCoinbase {
+            merkle_root: to_pending_coinbase_hash(&collection.merkle_root()),
+            recomputed_merkle_root: to_pending_coinbase_hash(&collection.recompute_merkle_root()),
+            pending_coinbase_aux: mina_p2p_messages::string::ByteString::from(
+                collection.hash_extra().0.as_slice(),
+            )
+            .into(),
+            stacks: collection
+                .stacks()
+                .map(|(id, stack)| RpcPendingCoinbaseStack {
+                    id: (&id).into(),
+                    stack: stack.into(),
+                })
+                .collect(),
+            latest_stack: (&collection.latest_stack(false)).into(),
+        })
+    }
+
+    /// Pending coinbase merkle root of `block`, recomputed by applying its
+    /// staged ledger diff to the staged ledger of `pred_block`, instead of
+    /// reading it from the staged ledger stored for `block`.
+    pub fn pending_coinbase_replay(
+        &self,
+        block: &AppliedBlock,
+        pred_block: &AppliedBlock,
+    ) -> Result<v2::PendingCoinbaseHash, String> {
+        use ledger::scan_state::conv::to_pending_coinbase_hash;
+
+        let mut staged_ledger = self
+            .staged_ledgers
+            .get(pred_block.staged_ledger_hashes())
+            .ok_or_else(|| {
+                format!(
+                    "parent staged ledger missing: {:#?}",
+                    pred_block.staged_ledger_hashes()
+                )
+            })?
+            .clone();
+        // Applying the diff creates child masks, the parent staged ledger
+        // is left untouched.
+        let result = apply_block_diff(
+            &mut staged_ledger,
+            constraint_constants(),
+            &Verifier,
+            Some(SkipVerification::All),
+            block.block(),
+            &pred_block.header().protocol_state,
+        )
+        .map_err(|err| format!("{err:?}"))?;
+        Ok(to_pending_coinbase_hash(
+            &result.hash_after_applying.pending_coinbase_hash,
+        ))
+    }
 }
 
 impl LedgerSyncState {
@@ -1415,7 +1698,7 @@ impl LedgerSyncState {
     fn snarked_ledger_mut(&mut self, hash: LedgerHash) -> Result<&mut Mask, InvalidBigInt> {
         let hash_fp = hash.to_field()?;
         Ok(self.snarked_ledgers.entry(hash.clone()).or_insert_with(|| {
-            let mut ledger = Mask::create(LEDGER_DEPTH);
+            let mut ledger = Mask::create(ledger_depth());
             ledger.set_cached_hash_unchecked(&LedgerAddress::root(), hash_fp);
             ledger
         }))
@@ -1497,6 +1780,9 @@ pub trait LedgerService: redux::Service {
     }
 
     fn read_init(&mut self, id: LedgerReadId, request: LedgerReadRequest) {
+        if !self.ledger_manager().read_start(id) {
+            return;
+        }
         let request = LedgerRequest::Read(id, request);
         if self.force_sync_calls() {
             let _ = self.ledger_manager().call_sync(request);
@@ -1656,4 +1942,31 @@ mod tests {
             assert_eq!(hash.to_string(), expected_hash);
         });
     }
+
+    #[test]
+    fn test_snarked_ledger_import_keeps_sync_ledger_on_mismatch() {
+        let mut ctx = LedgerCtx::default();
+        let mut expected = Mask::create(ledger_depth());
+        let account = Account::create_with(
+            AccountId::new(ledger::gen_compressed(), Default::default()),
+            ledger::scan_state::currency::Balance::from_u64(1_000_000_000),
+        );
+        expected
+            .set_all_accounts_rooted_at(LedgerAddress::root(), &[Box::new(account)])
+            .unwrap();
+        let hash = merkle_root(&mut expected);
+        ctx.sync.snarked_ledger_mut(hash.clone()).unwrap();
+
+        // An empty ledger doesn't hash to `hash`, so it isn't committed.
+        assert!(ctx
+            .snarked_ledger_import(hash.clone(), Mask::create(ledger_depth()))
+            .is_err());
+        let synced = ctx.pending_sync_snarked_ledger_mask(&hash).unwrap();
+        assert_eq!(synced.num_accounts(), 0);
+
+        ctx.snarked_ledger_import(hash.clone(), expected).unwrap();
+        let mut synced = ctx.pending_sync_snarked_ledger_mask(&hash).unwrap();
+        assert_eq!(synced.num_accounts(), 1);
+        assert_eq!(merkle_root(&mut synced), hash);
+    }
 }
@@ -9,7 +9,10 @@ pub struct LedgerState {
 }
 
 impl LedgerState {
-    pub fn new(_config: LedgerConfig) -> Self {
-        Self::default()
+    pub fn new(config: LedgerConfig) -> Self {
+        Self {
+            read: LedgerReadState::new(config.read_timeout),
+            ..Default::default()
+        }
     }
 }
@@ -1,3 +1,4 @@
+pub mod export;
 pub mod read;
 pub mod write;
 
@@ -22,24 +23,28 @@ pub use ledger_service::*;
 pub mod ledger_manager;
 
 pub use ledger::{AccountIndex as LedgerAccountIndex, Address as LedgerAddress};
-pub use ledger_manager::LedgerManager;
+pub use ledger_manager::{LedgerAccountsImporter, LedgerManager};
 
 use ledger::TreeVersion;
 use mina_p2p_messages::v2;
 
-// FIXME(tizoc): both networks use the same value, but this will break if that changes
-pub const LEDGER_DEPTH: usize =
-    crate::core::network::mainnet::CONSTRAINT_CONSTANTS.ledger_depth as usize;
+/// Depth of the ledgers of the network the node runs on.
+pub fn ledger_depth() -> usize {
+    crate::core::constants::constraint_constants().ledger_depth as usize
+}
 
 lazy_static::lazy_static! {
-    /// Array size needs to be changed when the tree's depth change
-    static ref LEDGER_HASH_EMPTIES: [v2::LedgerHash; LEDGER_DEPTH + 1] = {
+    /// Empty hashes at each depth, up to [`ledger_depth`].
+    static ref LEDGER_HASH_EMPTIES: Vec<v2::LedgerHash> = {
         use ledger::TreeVersion;
 
-        std::array::from_fn(|i| {
-            let hash = ledger::V2::empty_hash_at_height(LEDGER_DEPTH.saturating_sub(i));
-            v2::MinaBaseLedgerHash0StableV1(hash.into()).into()
-        })
+        let ledger_depth = ledger_depth();
+        (0..=ledger_depth)
+            .map(|i| {
+                let hash = ledger::V2::empty_hash_at_height(ledger_depth.saturating_sub(i));
+                v2::MinaBaseLedgerHash0StableV1(hash.into()).into()
+            })
+            .collect()
     };
 }
 
@@ -48,17 +53,18 @@ pub fn ledger_empty_hash_at_depth(depth: usize) -> v2::LedgerHash {
 }
 
 /// Given the hash of the subtree containing all accounts of height `subtree_height`
-/// compute the hash of a tree of size `LEDGER_DEPTH` if all other nodes were
+/// compute the hash of a tree of size [`ledger_depth`] if all other nodes were
 /// empty.
 pub fn complete_height_tree_with_empties(
     content_hash: &v2::LedgerHash,
     subtree_height: usize,
 ) -> Result<v2::LedgerHash, InvalidBigInt> {
-    assert!(LEDGER_DEPTH >= subtree_height);
+    let ledger_depth = ledger_depth();
+    assert!(ledger_depth >= subtree_height);
     let content_hash = content_hash.0.to_field()?;
 
-    let computed_hash = (subtree_height..LEDGER_DEPTH).fold(content_hash, |prev_hash, height| {
-        let depth = LEDGER_DEPTH.saturating_sub(height);
+    let computed_hash = (subtree_height..ledger_depth).fold(content_hash, |prev_hash, height| {
+        let depth = ledger_depth.saturating_sub(height);
         let empty_right = ledger_empty_hash_at_depth(depth).0.to_field().unwrap(); // We know empties are valid
         ledger::V2::hash_node(height, prev_hash, empty_right)
     });
@@ -78,7 +84,7 @@ pub fn tree_height_for_num_accounts(num_accounts: u64) -> usize {
 }
 
 /// Given the hash of the subtree containing `num_accounts` accounts
-/// compute the hash of a tree of size `LEDGER_DEPTH` if all other nodes were
+/// compute the hash of a tree of size [`ledger_depth`] if all other nodes were
 /// empty.
 ///
 /// NOTE: For out of range sizes, en empty tree hash is returned.
@@ -94,7 +100,7 @@ pub fn complete_num_accounts_tree_with_empties(
     let subtree_height = tree_height_for_num_accounts(num_accounts);
 
     // This would not be a valid number of accounts because it doesn't fit the tree
-    if subtree_height > LEDGER_DEPTH {
+    if subtree_height > ledger_depth() {
         Ok(ledger_empty_hash_at_depth(0))
     } else {
         complete_height_tree_with_empties(contents_hash, subtree_height)
@@ -106,7 +112,7 @@ pub fn hash_node_at_depth(
     left: mina_curves::pasta::Fp,
     right: mina_curves::pasta::Fp,
 ) -> mina_curves::pasta::Fp {
-    let height = LEDGER_DEPTH.saturating_sub(depth).saturating_sub(1);
+    let height = ledger_depth().saturating_sub(depth).saturating_sub(1);
     ledger::V2::hash_node(height, left, right)
 }
 
@@ -22,13 +22,18 @@ pub enum LedgerReadAction {
         id: LedgerReadId,
         response: LedgerReadResponse,
     },
+    /// The service didn't answer in time, the request is sent again or
+    /// dropped.
+    Timeout {
+        id: LedgerReadId,
+    },
     Prune {
         id: LedgerReadId,
     },
 }
 
 impl redux::EnablingCondition<crate::State> for LedgerReadAction {
-    fn is_enabled(&self, state: &crate::State, _time: redux::Timestamp) -> bool {
+    fn is_enabled(&self, state: &crate::State, time: redux::Timestamp) -> bool {
         match self {
             LedgerReadAction::FindTodos => state.ledger.read.is_total_cost_under_limit(),
             LedgerReadAction::Init { .. } => state.ledger.read.is_total_cost_under_limit(),
@@ -43,6 +48,7 @@ impl redux::EnablingCondition<crate::State> for LedgerReadAction {
                 }
                 _ => false,
             },
+            LedgerReadAction::Timeout { id } => state.ledger.read.is_timed_out(*id, time),
             LedgerReadAction::Prune { id } => matches!(
                 state.ledger.read.get(*id),
                 Some(LedgerReadRequestState::Success { .. })
@@ -10,14 +10,16 @@ use p2p::{
 use redux::Dispatcher;
 
 use crate::{
-    block_producer::vrf_evaluator::BlockProducerVrfEvaluatorAction,
-    ledger_effectful::LedgerEffectfulAction, Action, RpcAction, State, Substate,
+    block_producer::{vrf_evaluator::BlockProducerVrfEvaluatorAction, BlockProducerAction},
+    ledger_effectful::LedgerEffectfulAction,
+    transition_frontier::TransitionFrontierAction,
+    Action, RpcAction, State, Substate,
 };
 
 use super::{
     LedgerAddress, LedgerReadAction, LedgerReadActionWithMetaRef, LedgerReadIdType,
     LedgerReadInitCallback, LedgerReadRequest, LedgerReadResponse,
-    LedgerReadStagedLedgerAuxAndPendingCoinbases, LedgerReadState,
+    LedgerReadStagedLedgerAuxAndPendingCoinbases, LedgerReadState, LedgerReadTimeout,
 };
 
 impl LedgerReadState {
@@ -55,6 +57,29 @@ impl LedgerReadState {
                 Self::propagate_read_response(dispatcher, state, *id, response.clone());
                 dispatcher.push(LedgerReadAction::Prune { id: *id });
             }
+            LedgerReadAction::Timeout { id } => match state.on_timeout(*id, meta.time()) {
+                None => {}
+                Some(LedgerReadTimeout::Retry { request, retries }) => {
+                    mina_core::warn!(meta.time();
+                        kind = "LedgerReadTimeout",
+                        summary = format!("ledger read {id} timed out, retrying"),
+                        request = format!("{:?}", request.kind()),
+                        retries = retries,
+                    );
+                    let dispatcher = state_context.into_dispatcher();
+                    dispatcher.push(LedgerEffectfulAction::ReadRetry { request, id: *id });
+                }
+                Some(LedgerReadTimeout::GiveUp { request, retries }) => {
+                    mina_core::error!(meta.time();
+                        kind = "LedgerReadTimeout",
+                        summary = format!("ledger read {id} timed out, giving up"),
+                        request = format!("{:?}", request.kind()),
+                        retries = retries,
+                    );
+                    let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                    Self::propagate_read_error(dispatcher, state, request);
+                }
+            },
             LedgerReadAction::Prune { id } => {
                 state.remove(*id);
             }
@@ -105,6 +130,16 @@ impl LedgerReadState {
                 }
             }
             (_, LedgerReadResponse::DelegatorTable(..)) => unreachable!(),
+            (
+                LedgerReadRequest::CoinbaseReceiverAccount(ledger_hash, _),
+                LedgerReadResponse::CoinbaseReceiverAccount(account),
+            ) => {
+                dispatcher.push(BlockProducerAction::CoinbaseReceiverCheckSuccess {
+                    ledger_hash: ledger_hash.clone(),
+                    account,
+                });
+            }
+            (_, LedgerReadResponse::CoinbaseReceiverAccount(..)) => unreachable!(),
             (req, LedgerReadResponse::GetNumAccounts(resp)) => {
                 for (peer_id, id, _) in find_peers_with_ledger_rpc(state, req) {
                     dispatcher.push(P2pChannelsRpcAction::ResponseSend {
@@ -199,6 +234,12 @@ impl LedgerReadState {
                     account_query,
                 });
             }
+            (_, LedgerReadResponse::AccountsPageForRpc(rpc_id, response)) => {
+                dispatcher.push(RpcAction::LedgerAccountsPageGetSuccess { rpc_id, response });
+            }
+            (_, LedgerReadResponse::AccountsAtBlockForRpc(rpc_id, response)) => {
+                dispatcher.push(RpcAction::LedgerAccountsAtBlockGetSuccess { rpc_id, response });
+            }
             (_, LedgerReadResponse::GetLedgerStatus(rpc_id, resp)) => {
                 dispatcher.push(RpcAction::LedgerStatusGetSuccess {
                     rpc_id,
@@ -211,6 +252,99 @@ impl LedgerReadState {
                     response: resp.clone(),
                 });
             }
+            (_, LedgerReadResponse::GetZkappActionsEvents(rpc_id, response)) => {
+                dispatcher.push(RpcAction::ZkappActionsEventsGetSuccess { rpc_id, response });
+            }
+            (_, LedgerReadResponse::GetTokenSupply(rpc_id, response)) => {
+                dispatcher.push(RpcAction::TokenSupplyGetSuccess { rpc_id, response });
+            }
+            (_, LedgerReadResponse::GetPendingCoinbase(rpc_id, resp)) => {
+                dispatcher.push(RpcAction::PendingCoinbaseGetSuccess {
+                    rpc_id,
+                    response: resp,
+                });
+            }
+            (_, LedgerReadResponse::GetLedgerSnapshot(rpc_id, resp)) => {
+                dispatcher.push(RpcAction::LedgerSnapshotGetSuccess {
+                    rpc_id,
+                    response: resp.clone(),
+                });
+            }
+            (_, LedgerReadResponse::MerkleRoots(merkle_roots)) => {
+                dispatcher.push(
+                    TransitionFrontierAction::LedgerHashesCheckMerkleRootsSuccess { merkle_roots },
+                );
+            }
+        }
+    }
+
+    /// Tells the requester of a read which was given up that it won't be
+    /// answered.
+    fn propagate_read_error(
+        dispatcher: &mut Dispatcher<Action, State>,
+        state: &State,
+        request: LedgerReadRequest,
+    ) {
+        match &request {
+            // The block producer can't go on without them, so they are read
+            // again from scratch.
+            LedgerReadRequest::DelegatorTable(..)
+            | LedgerReadRequest::CoinbaseReceiverAccount(..) => {
+                dispatcher.push(LedgerReadAction::Init {
+                    request,
+                    callback: LedgerReadInitCallback::None,
+                });
+            }
+            LedgerReadRequest::GetNumAccounts(..)
+            | LedgerReadRequest::GetChildHashesAtAddr(..)
+            | LedgerReadRequest::GetChildAccountsAtAddr(..)
+            | LedgerReadRequest::GetStagedLedgerAuxAndPendingCoinbases(..) => {
+                // Peers get an empty response, and ask someone else.
+                for (peer_id, id, is_streaming) in find_peers_with_ledger_rpc(state, &request) {
+                    if is_streaming {
+                        dispatcher.push(P2pChannelsStreamingRpcAction::ResponseSendInit {
+                            peer_id,
+                            id,
+                            response: None,
+                        });
+                    } else {
+                        dispatcher.push(P2pChannelsRpcAction::ResponseSend {
+                            peer_id,
+                            id,
+                            response: None,
+                        });
+                    }
+                }
+            }
+            LedgerReadRequest::GetAccounts(_, _, rpc_id) => {
+                if let Some(rpc_id) = rpc_id {
+                    dispatcher.push(RpcAction::LedgerReadError { rpc_id: *rpc_id });
+                }
+            }
+            LedgerReadRequest::ScanStateSummary(ledger_hash) => {
+                for rpc_id in state
+                    .rpc
+                    .scan_state_summary_rpc_ids()
+                    .filter(|(_, hash, _)| *hash == ledger_hash)
+                    .map(|(id, ..)| id)
+                    .collect::<Vec<_>>()
+                {
+                    dispatcher.push(RpcAction::LedgerReadError { rpc_id });
+                }
+            }
+            LedgerReadRequest::AccountsForRpc(rpc_id, ..)
+            | LedgerReadRequest::AccountsPageForRpc(rpc_id, ..)
+            | LedgerReadRequest::AccountsAtBlockForRpc(rpc_id, ..)
+            | LedgerReadRequest::GetLedgerStatus(rpc_id, ..)
+            | LedgerReadRequest::GetAccountDelegators(rpc_id, ..)
+            | LedgerReadRequest::GetPendingCoinbase(rpc_id, ..)
+            | LedgerReadRequest::GetLedgerSnapshot(rpc_id, ..)
+            | LedgerReadRequest::GetZkappActionsEvents(rpc_id, ..)
+            | LedgerReadRequest::GetTokenSupply(rpc_id, ..) => {
+                dispatcher.push(RpcAction::LedgerReadError { rpc_id: *rpc_id });
+            }
+            // Checked again at the next interval.
+            LedgerReadRequest::MerkleRoots(..) => {}
         }
     }
 
@@ -218,6 +352,7 @@ impl LedgerReadState {
         // fetching delegator table, this is required because delegator table construction requires reading from ledger.
         // It could be that ledger read quota was reached when vrf tried to initiate that read, so we need to "retry" it if that's the case
         dispatcher.push(BlockProducerVrfEvaluatorAction::BeginDelegatorTableConstruction);
+        dispatcher.push(BlockProducerAction::CoinbaseReceiverCheckInit);
 
         // p2p rpcs
         let mut peers = state
@@ -265,6 +400,7 @@ impl LedgerReadState {
                         P2pStreamingRpcRequest::StagedLedgerParts(block_hash) => {
                             build_staged_ledger_parts_request(state, block_hash)?
                         }
+                        P2pStreamingRpcRequest::Block(_) => return None,
                     };
                     Some((id, ledger_request, true))
                 })
@@ -402,28 +538,7 @@ fn build_staged_ledger_parts_request(
     state: &crate::State,
     block_hash: &v2::StateHash,
 ) -> Option<LedgerReadRequest> {
-    let tf = &state.transition_frontier;
-    let ledger_hash = tf
-        .best_chain
-        .iter()
-        .find(|b| b.hash() == block_hash)
-        .map(|b| b.staged_ledger_hashes().clone())?;
-    let protocol_states = tf
-        .needed_protocol_states
-        .iter()
-        .map(|(hash, b)| (hash.clone(), b.clone()))
-        .chain(
-            tf.best_chain
-                .iter()
-                .take_while(|b| b.hash() != block_hash)
-                .map(|b| (b.hash().clone(), b.header().protocol_state.clone())),
-        )
-        .collect();
-
     Some(LedgerReadRequest::GetStagedLedgerAuxAndPendingCoinbases(
-        LedgerReadStagedLedgerAuxAndPendingCoinbases {
-            ledger_hash,
-            protocol_states,
-        },
+        LedgerReadStagedLedgerAuxAndPendingCoinbases::for_block(state, block_hash)?,
     ))
 }
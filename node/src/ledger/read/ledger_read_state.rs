@@ -1,4 +1,4 @@
-use mina_core::requests::{PendingRequests, RequestId, RequestIdType};
+use mina_core::requests::{PendingRequests, RequestId, RequestIdType, RequestRetryPolicy};
 use serde::{Deserialize, Serialize};
 
 use super::{LedgerReadRequest, LedgerReadResponse};
@@ -19,6 +19,8 @@ pub struct LedgerReadState {
     pending: PendingRequests<LedgerReadIdType, LedgerReadRequestState>,
     /// Total cost of currently pending requests.
     total_cost: usize,
+    #[serde(default)]
+    timeout: Option<RequestRetryPolicy>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -26,6 +28,9 @@ pub enum LedgerReadRequestState {
     Pending {
         time: redux::Timestamp,
         request: LedgerReadRequest,
+        /// Times the request was sent again after timing out.
+        #[serde(default)]
+        retries: u8,
     },
     Success {
         time: redux::Timestamp,
@@ -35,6 +40,17 @@ pub enum LedgerReadRequestState {
 }
 
 impl LedgerReadState {
+    pub fn new(timeout: Option<RequestRetryPolicy>) -> Self {
+        Self {
+            timeout,
+            ..Default::default()
+        }
+    }
+
+    pub fn timeout(&self) -> Option<RequestRetryPolicy> {
+        self.timeout
+    }
+
     pub fn contains(&self, id: LedgerReadId) -> bool {
         self.pending.contains(id)
     }
@@ -57,8 +73,11 @@ impl LedgerReadState {
 
     pub fn add(&mut self, time: redux::Timestamp, request: LedgerReadRequest) -> LedgerReadId {
         self.total_cost = self.total_cost.saturating_add(request.cost());
-        self.pending
-            .add(LedgerReadRequestState::Pending { time, request })
+        self.pending.add(LedgerReadRequestState::Pending {
+            time,
+            request,
+            retries: 0,
+        })
     }
 
     pub fn remove(&mut self, id: LedgerReadId) -> Option<LedgerReadRequestState> {
@@ -95,10 +114,81 @@ impl LedgerReadState {
         &self,
     ) -> impl Iterator<Item = (LedgerReadId, &LedgerReadRequest, redux::Timestamp)> {
         self.pending.iter().filter_map(|(id, s)| match s {
-            LedgerReadRequestState::Pending { time, request } => Some((id, request, *time)),
+            LedgerReadRequestState::Pending { time, request, .. } => Some((id, request, *time)),
             _ => None,
         })
     }
+
+    pub fn is_timed_out(&self, id: LedgerReadId, now: redux::Timestamp) -> bool {
+        let Some(timeout) = &self.timeout else {
+            return false;
+        };
+        match self.pending.get(id) {
+            Some(LedgerReadRequestState::Pending { time, .. }) => timeout.is_timed_out(*time, now),
+            _ => false,
+        }
+    }
+
+    pub fn timed_out_requests(
+        &self,
+        now: redux::Timestamp,
+    ) -> impl Iterator<Item = LedgerReadId> + '_ {
+        self.pending
+            .iter()
+            .map(|(id, _)| id)
+            .filter(move |id| self.is_timed_out(*id, now))
+    }
+
+    /// Handles the timeout of the request `id`: restarts its timeout if it
+    /// can be sent again, removes it otherwise. `None` if it didn't time
+    /// out.
+    pub fn on_timeout(
+        &mut self,
+        id: LedgerReadId,
+        now: redux::Timestamp,
+    ) -> Option<LedgerReadTimeout> {
+        let timeout = self.timeout?;
+        if !self.is_timed_out(id, now) {
+            return None;
+        }
+        let Some(LedgerReadRequestState::Pending {
+            time,
+            request,
+            retries,
+        }) = self.pending.get_mut(id)
+        else {
+            return None;
+        };
+
+        if timeout.can_retry(*retries) {
+            *time = now;
+            *retries += 1;
+            return Some(LedgerReadTimeout::Retry {
+                request: request.clone(),
+                retries: *retries,
+            });
+        }
+        let retries = *retries;
+        let request = self.remove(id)?.request().clone();
+        Some(LedgerReadTimeout::GiveUp { request, retries })
+    }
+}
+
+/// What is done with a request which timed out, see
+/// [`LedgerReadState::on_timeout`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum LedgerReadTimeout {
+    /// The request is sent again, the service ignores it if the previous
+    /// read with the same id is still in flight.
+    Retry {
+        request: LedgerReadRequest,
+        retries: u8,
+    },
+    /// The request is dropped, its requester must be told.
+    GiveUp {
+        request: LedgerReadRequest,
+        retries: u8,
+    },
 }
 
 impl LedgerReadRequestState {
@@ -108,3 +198,69 @@ impl LedgerReadRequestState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use mina_p2p_messages::v2;
+
+    use super::*;
+
+    fn time(secs: u64) -> redux::Timestamp {
+        redux::Timestamp::ZERO + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_timed_out_read_is_retried_then_given_up() {
+        let mut state =
+            LedgerReadState::new(Some(RequestRetryPolicy::new(Duration::from_secs(10), 1)));
+        let request = LedgerReadRequest::GetNumAccounts(v2::LedgerHash::zero());
+        let id = state.add(time(0), request.clone());
+        assert_eq!(state.total_cost, 1);
+
+        assert_eq!(state.on_timeout(id, time(9)), None);
+        assert_eq!(
+            state.on_timeout(id, time(10)),
+            Some(LedgerReadTimeout::Retry {
+                request: request.clone(),
+                retries: 1
+            })
+        );
+        // The timeout restarted with the retry.
+        assert_eq!(state.on_timeout(id, time(19)), None);
+        assert!(state.has_same_request(&request));
+
+        assert_eq!(
+            state.on_timeout(id, time(20)),
+            Some(LedgerReadTimeout::GiveUp {
+                request: request.clone(),
+                retries: 1
+            })
+        );
+        assert!(!state.contains(id));
+        assert!(!state.has_same_request(&request));
+        assert_eq!(state.total_cost, 0);
+        assert_eq!(state.on_timeout(id, time(30)), None);
+    }
+
+    #[test]
+    fn test_answered_read_does_not_time_out() {
+        let mut state =
+            LedgerReadState::new(Some(RequestRetryPolicy::new(Duration::from_secs(10), 1)));
+        let id = state.add(
+            time(0),
+            LedgerReadRequest::GetNumAccounts(v2::LedgerHash::zero()),
+        );
+        state.add_response(id, time(5), LedgerReadResponse::GetNumAccounts(None));
+        assert_eq!(state.on_timeout(id, time(10)), None);
+        assert!(state.contains(id));
+
+        let mut state = LedgerReadState::new(None);
+        let id = state.add(
+            time(0),
+            LedgerReadRequest::GetNumAccounts(v2::LedgerHash::zero()),
+        );
+        assert_eq!(state.on_timeout(id, time(3600)), None);
+    }
+}
@@ -23,12 +23,18 @@ use crate::{
     block_producer::vrf_evaluator::DelegatorTable,
     ledger::LedgerAddress,
     p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases,
-    rpc::{AccountQuery, RpcScanStateSummaryScanStateJob},
+    rpc::{
+        AccountQuery, RpcLedgerAccountsAtBlockGetResponse, RpcPageQuery, RpcPageResult,
+        RpcPendingCoinbase, RpcScanStateSummaryScanStateJob, RpcTokenSupplyGetResponse,
+        RpcZkappActionsEventsGetResponse, RpcZkappActionsEventsQuery,
+    },
+    transition_frontier::sync::ledger::LedgerSnapshot,
 };
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
 pub enum LedgerReadKind {
     DelegatorTable,
+    CoinbaseReceiverAccount,
     GetNumAccounts,
     GetAccounts,
     GetChildHashesAtAddr,
@@ -36,14 +42,24 @@ pub enum LedgerReadKind {
     GetStagedLedgerAuxAndPendingCoinbases,
     ScanStateSummary,
     AccountsForRpc,
+    AccountsPageForRpc,
+    AccountsAtBlockForRpc,
     GetLedgerStatus,
     GetAccountDelegators,
+    GetPendingCoinbase,
+    GetLedgerSnapshot,
+    GetZkappActionsEvents,
+    GetTokenSupply,
+    MerkleRoots,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub enum LedgerReadRequest {
     /// Delegator table requested by vrf state machine.
     DelegatorTable(v2::LedgerHash, AccountPublicKey),
+    /// Custom coinbase receiver account, whose permissions are checked by
+    /// the block producer.
+    CoinbaseReceiverAccount(v2::LedgerHash, AccountPublicKey),
     // p2p rpcs
     GetNumAccounts(v2::LedgerHash),
     GetAccounts(v2::LedgerHash, Vec<AccountId>, Option<RpcId>),
@@ -53,8 +69,26 @@ pub enum LedgerReadRequest {
     // rpcs
     ScanStateSummary(v2::MinaBaseStagedLedgerHashStableV1),
     AccountsForRpc(RpcId, v2::LedgerHash, AccountQuery),
+    /// Accounts of the ledger at the anchor block of the query.
+    AccountsPageForRpc(RpcId, v2::StateHash, v2::LedgerHash, RpcPageQuery),
+    /// Accounts of the ledger at the given block, which may have been
+    /// pruned since the request was made.
+    AccountsAtBlockForRpc(RpcId, v2::StateHash, v2::LedgerHash, AccountQuery),
     GetLedgerStatus(RpcId, v2::LedgerHash),
     GetAccountDelegators(RpcId, v2::LedgerHash, AccountId),
+    GetPendingCoinbase(RpcId, v2::MinaBaseStagedLedgerHashStableV1),
+    GetLedgerSnapshot(RpcId, LedgerReadSnapshotRequest),
+    GetZkappActionsEvents(RpcId, RpcZkappActionsEventsQuery),
+    GetTokenSupply(RpcId, v2::LedgerHash, v2::TokenIdKeyHash),
+    /// Merkle roots of the ledgers stored under these hashes, compared
+    /// with them by the transition frontier.
+    MerkleRoots(Vec<v2::LedgerHash>),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum LedgerReadSnapshotRequest {
+    SnarkedLedger(v2::LedgerHash),
+    StagedLedgerParts(LedgerReadStagedLedgerAuxAndPendingCoinbases),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -67,6 +101,8 @@ pub struct LedgerStatus {
 pub enum LedgerReadResponse {
     /// Delegator table requested by vrf state machine.
     DelegatorTable(Option<DelegatorTable>),
+    /// Custom coinbase receiver account, if it exists.
+    CoinbaseReceiverAccount(Option<Box<Account>>),
     // p2p rpcs
     GetNumAccounts(Option<(u64, v2::LedgerHash)>),
     GetAccounts(Vec<Account>, Option<RpcId>),
@@ -76,8 +112,17 @@ pub enum LedgerReadResponse {
     // rpcs
     ScanStateSummary(Result<Vec<Vec<RpcScanStateSummaryScanStateJob>>, String>),
     AccountsForRpc(RpcId, Vec<Account>, AccountQuery),
+    AccountsPageForRpc(RpcId, RpcPageResult<Account>),
+    AccountsAtBlockForRpc(RpcId, RpcLedgerAccountsAtBlockGetResponse),
     GetLedgerStatus(RpcId, Option<LedgerStatus>),
     GetAccountDelegators(RpcId, Option<Vec<Account>>),
+    GetPendingCoinbase(RpcId, Option<RpcPendingCoinbase>),
+    GetLedgerSnapshot(RpcId, Option<LedgerSnapshot>),
+    GetZkappActionsEvents(RpcId, RpcZkappActionsEventsGetResponse),
+    GetTokenSupply(RpcId, RpcTokenSupplyGetResponse),
+    /// Each requested hash with the merkle root of its ledger, `None` if
+    /// the ledger isn't there.
+    MerkleRoots(Vec<(v2::LedgerHash, Option<v2::LedgerHash>)>),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -90,6 +135,7 @@ impl LedgerReadRequest {
     pub fn kind(&self) -> LedgerReadKind {
         match self {
             Self::DelegatorTable(..) => LedgerReadKind::DelegatorTable,
+            Self::CoinbaseReceiverAccount(..) => LedgerReadKind::CoinbaseReceiverAccount,
             Self::GetNumAccounts(..) => LedgerReadKind::GetNumAccounts,
             Self::GetAccounts(..) => LedgerReadKind::GetAccounts,
             Self::GetChildAccountsAtAddr(..) => LedgerReadKind::GetChildAccountsAtAddr,
@@ -99,18 +145,26 @@ impl LedgerReadRequest {
             }
             Self::ScanStateSummary(..) => LedgerReadKind::ScanStateSummary,
             Self::AccountsForRpc(..) => LedgerReadKind::AccountsForRpc,
+            Self::AccountsPageForRpc(..) => LedgerReadKind::AccountsPageForRpc,
+            Self::AccountsAtBlockForRpc(..) => LedgerReadKind::AccountsAtBlockForRpc,
             Self::GetLedgerStatus(..) => LedgerReadKind::GetLedgerStatus,
             Self::GetAccountDelegators(..) => LedgerReadKind::GetAccountDelegators,
+            Self::GetLedgerSnapshot(..) => LedgerReadKind::GetLedgerSnapshot,
+            Self::GetPendingCoinbase(..) => LedgerReadKind::GetPendingCoinbase,
+            Self::GetZkappActionsEvents(..) => LedgerReadKind::GetZkappActionsEvents,
+            Self::GetTokenSupply(..) => LedgerReadKind::GetTokenSupply,
+            Self::MerkleRoots(..) => LedgerReadKind::MerkleRoots,
         }
     }
 
     pub fn cost(&self) -> usize {
         let cost = match self {
             Self::DelegatorTable(..) => 100,
+            Self::CoinbaseReceiverAccount(..) => 1,
             Self::GetNumAccounts(..) => 1,
             Self::GetAccounts(..) => 10, // Not sure if 10 is a good number here
             Self::GetChildAccountsAtAddr(_, addr) => {
-                let height_diff = super::LEDGER_DEPTH.saturating_sub(addr.length());
+                let height_diff = super::ledger_depth().saturating_sub(addr.length());
                 let max_accounts_count = 2_u32.pow(height_diff as u32);
                 (max_accounts_count / 4) as usize
             }
@@ -119,8 +173,16 @@ impl LedgerReadRequest {
             Self::ScanStateSummary(..) => 100,
             // TODO(adonagy): not sure
             Self::AccountsForRpc(..) => 10,
+            Self::AccountsPageForRpc(.., query) => query.limit().div_ceil(100),
+            Self::AccountsAtBlockForRpc(..) => 10,
             Self::GetLedgerStatus(..) => 1,
             Self::GetAccountDelegators(..) => 10,
+            Self::GetPendingCoinbase(..) => 1,
+            Self::GetLedgerSnapshot(..) => 100,
+            Self::GetZkappActionsEvents(..) => 10,
+            // Folds the whole ledger.
+            Self::GetTokenSupply(..) => 100,
+            Self::MerkleRoots(hashes) => hashes.len(),
         };
         cost.max(1)
     }
@@ -130,6 +192,7 @@ impl LedgerReadResponse {
     pub fn kind(&self) -> LedgerReadKind {
         match self {
             Self::DelegatorTable(..) => LedgerReadKind::DelegatorTable,
+            Self::CoinbaseReceiverAccount(..) => LedgerReadKind::CoinbaseReceiverAccount,
             Self::GetNumAccounts(..) => LedgerReadKind::GetNumAccounts,
             Self::GetAccounts(..) => LedgerReadKind::GetAccounts,
             Self::GetChildAccountsAtAddr(..) => LedgerReadKind::GetChildAccountsAtAddr,
@@ -139,12 +202,48 @@ impl LedgerReadResponse {
             }
             Self::ScanStateSummary(..) => LedgerReadKind::ScanStateSummary,
             Self::AccountsForRpc(..) => LedgerReadKind::AccountsForRpc,
+            Self::AccountsPageForRpc(..) => LedgerReadKind::AccountsPageForRpc,
+            Self::AccountsAtBlockForRpc(..) => LedgerReadKind::AccountsAtBlockForRpc,
             Self::GetLedgerStatus(..) => LedgerReadKind::GetLedgerStatus,
             Self::GetAccountDelegators(..) => LedgerReadKind::GetAccountDelegators,
+            Self::GetLedgerSnapshot(..) => LedgerReadKind::GetLedgerSnapshot,
+            Self::GetPendingCoinbase(..) => LedgerReadKind::GetPendingCoinbase,
+            Self::GetZkappActionsEvents(..) => LedgerReadKind::GetZkappActionsEvents,
+            Self::GetTokenSupply(..) => LedgerReadKind::GetTokenSupply,
+            Self::MerkleRoots(..) => LedgerReadKind::MerkleRoots,
         }
     }
 }
 
+impl LedgerReadStagedLedgerAuxAndPendingCoinbases {
+    /// Request for the staged ledger parts at the block, if it is in the
+    /// best chain.
+    pub fn for_block(state: &crate::State, block_hash: &v2::StateHash) -> Option<Self> {
+        let tf = &state.transition_frontier;
+        let ledger_hash = tf
+            .best_chain
+            .iter()
+            .find(|b| b.hash() == block_hash)
+            .map(|b| b.staged_ledger_hashes().clone())?;
+        let protocol_states = tf
+            .needed_protocol_states
+            .iter()
+            .map(|(hash, b)| (hash.clone(), b.clone()))
+            .chain(
+                tf.best_chain
+                    .iter()
+                    .take_while(|b| b.hash() != block_hash)
+                    .map(|b| (b.hash().clone(), b.header().protocol_state.clone())),
+            )
+            .collect();
+
+        Some(Self {
+            ledger_hash,
+            protocol_states,
+        })
+    }
+}
+
 impl PartialEq for LedgerReadStagedLedgerAuxAndPendingCoinbases {
     fn eq(&self, other: &Self) -> bool {
         self.ledger_hash == other.ledger_hash
@@ -173,5 +272,29 @@ pub enum LedgerReadInitCallback {
         callback: Callback<RequestId<RpcIdType>>,
         args: RequestId<RpcIdType>,
     },
+    RpcZkappActionsEventsGetPending {
+        callback: Callback<RequestId<RpcIdType>>,
+        args: RequestId<RpcIdType>,
+    },
+    RpcTokenSupplyGetPending {
+        callback: Callback<RequestId<RpcIdType>>,
+        args: RequestId<RpcIdType>,
+    },
+    RpcPendingCoinbaseGetPending {
+        callback: Callback<RequestId<RpcIdType>>,
+        args: RequestId<RpcIdType>,
+    },
+    RpcLedgerSnapshotGetPending {
+        callback: Callback<RequestId<RpcIdType>>,
+        args: RequestId<RpcIdType>,
+    },
+    RpcLedgerAccountsPageGetPending {
+        callback: Callback<RequestId<RpcIdType>>,
+        args: RequestId<RpcIdType>,
+    },
+    RpcLedgerAccountsAtBlockGetPending {
+        callback: Callback<RequestId<RpcIdType>>,
+        args: RequestId<RpcIdType>,
+    },
     None,
 }
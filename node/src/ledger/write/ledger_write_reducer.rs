@@ -143,7 +143,9 @@ impl LedgerWriteState {
                     dispatcher
                         .push(TransitionFrontierSyncAction::BlocksNextApplyError { hash, error });
                 }
-                Ok(result) => {
+                Ok(mut result) => {
+                    result.vrf_evaluation =
+                        state.block_producer.injected_block_vrf_evaluation(&hash);
                     dispatcher.push(TransitionFrontierSyncAction::BlocksSendToArchive {
                         hash: hash.clone(),
                         data: result.clone(),
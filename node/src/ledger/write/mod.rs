@@ -18,6 +18,7 @@ use mina_p2p_messages::v2::{self, StateBodyHash};
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    block_producer::{BlockProducerVrfEvaluation, BlockProducerWorkFeePolicy},
     block_producer_effectful::StagedLedgerDiffCreateOutput,
     core::{
         block::ArcBlockWithHash,
@@ -53,6 +54,7 @@ pub enum LedgerWriteRequest {
         completed_snarks: BTreeMap<SnarkJobId, Snark>,
         supercharge_coinbase: bool,
         transactions_by_fee: Vec<valid::UserCommand>,
+        work_fee_policy: BlockProducerWorkFeePolicy,
     },
     BlockApply {
         block: ArcBlockWithHash,
@@ -94,6 +96,10 @@ pub struct BlockApplyResult {
     pub block: ArcBlockWithHash,
     pub just_emitted_a_proof: bool,
     pub archive_data: Option<BlockApplyResultArchive>,
+    /// Set for blocks produced by this node, to be archived alongside
+    /// the block.
+    #[serde(default)]
+    pub vrf_evaluation: Option<BlockProducerVrfEvaluation>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -17,6 +17,11 @@ pub enum LedgerEffectfulAction {
         callback: LedgerReadInitCallback,
         id: RequestId<LedgerReadIdType>,
     },
+    /// Sends again a read request which timed out.
+    ReadRetry {
+        request: LedgerReadRequest,
+        id: RequestId<LedgerReadIdType>,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for LedgerEffectfulAction {
@@ -49,8 +49,29 @@ pub fn ledger_effectful_effects<S>(
                 LedgerReadInitCallback::RpcLedgerAccountDelegatorsGetPending { callback, args } => {
                     store.dispatch_callback(callback, args);
                 }
+                LedgerReadInitCallback::RpcZkappActionsEventsGetPending { callback, args } => {
+                    store.dispatch_callback(callback, args);
+                }
+                LedgerReadInitCallback::RpcTokenSupplyGetPending { callback, args } => {
+                    store.dispatch_callback(callback, args);
+                }
+                LedgerReadInitCallback::RpcPendingCoinbaseGetPending { callback, args } => {
+                    store.dispatch_callback(callback, args);
+                }
+                LedgerReadInitCallback::RpcLedgerSnapshotGetPending { callback, args } => {
+                    store.dispatch_callback(callback, args);
+                }
+                LedgerReadInitCallback::RpcLedgerAccountsPageGetPending { callback, args } => {
+                    store.dispatch_callback(callback, args);
+                }
+                LedgerReadInitCallback::RpcLedgerAccountsAtBlockGetPending { callback, args } => {
+                    store.dispatch_callback(callback, args);
+                }
                 LedgerReadInitCallback::None => {}
             }
         }
+        LedgerEffectfulAction::ReadRetry { request, id } => {
+            store.service.read_init(id, request);
+        }
     }
 }
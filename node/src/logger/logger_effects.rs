@@ -75,6 +75,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
             P2pAction::Identify(action) => action.action_event(&context),
             P2pAction::Channels(action) => match action {
                 P2pChannelsAction::MessageReceived(action) => action.action_event(&context),
+                P2pChannelsAction::MessageShed(action) => action.action_event(&context),
                 P2pChannelsAction::SignalingDiscovery(action) => action.action_event(&context),
                 P2pChannelsAction::SignalingExchange(action) => action.action_event(&context),
                 P2pChannelsAction::BestTip(action) => action.action_event(&context),
@@ -85,6 +86,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 P2pChannelsAction::StreamingRpc(action) => action.action_event(&context),
             },
             P2pAction::Peer(action) => action.action_event(&context),
+            P2pAction::Gossip(action) => action.action_event(&context),
             P2pAction::Network(action) => match action {
                 P2pNetworkAction::Scheduler(action) => match action {
                     // MioErrors in scheduler are logged using debug instead of warn, to prevent spam
@@ -2,7 +2,7 @@ use mina_core::ActionEvent;
 use p2p::{
     channels::{
         rpc::{P2pRpcId, P2pRpcRequest, P2pRpcResponse},
-        streaming_rpc::P2pStreamingRpcResponseFull,
+        streaming_rpc::{P2pStreamingRpcRequest, P2pStreamingRpcResponseFull},
     },
     P2pNetworkPubsubMessageCacheId, PeerId,
 };
@@ -39,6 +39,11 @@ pub enum P2pCallbacksAction {
         id: P2pRpcId,
         response: Option<P2pStreamingRpcResponseFull>,
     },
+    P2pChannelsStreamingRpcRequestReceived {
+        peer_id: PeerId,
+        id: P2pRpcId,
+        request: Box<P2pStreamingRpcRequest>,
+    },
 
     P2pDisconnection {
         peer_id: PeerId,
@@ -61,6 +66,7 @@ impl redux::EnablingCondition<crate::State> for P2pCallbacksAction {
             P2pCallbacksAction::P2pChannelsStreamingRpcReady => true,
             P2pCallbacksAction::P2pChannelsStreamingRpcTimeout { .. } => true,
             P2pCallbacksAction::P2pChannelsStreamingRpcResponseReceived { .. } => true,
+            P2pCallbacksAction::P2pChannelsStreamingRpcRequestReceived { .. } => true,
             P2pCallbacksAction::P2pDisconnection { .. } => true,
             // TODO: what if we don't have best tip?
             P2pCallbacksAction::RpcRespondBestTip { .. } => {
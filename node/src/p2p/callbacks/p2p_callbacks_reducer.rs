@@ -12,7 +12,9 @@ use p2p::{
     channels::{
         best_tip::P2pChannelsBestTipAction,
         rpc::{BestTipWithProof, P2pChannelsRpcAction, P2pRpcRequest, P2pRpcResponse},
-        streaming_rpc::P2pStreamingRpcResponseFull,
+        streaming_rpc::{
+            P2pChannelsStreamingRpcAction, P2pStreamingRpcRequest, P2pStreamingRpcResponseFull,
+        },
     },
     disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
     P2pNetworkPubsubAction, PeerId,
@@ -155,6 +157,7 @@ impl crate::State {
             }
             P2pCallbacksAction::P2pChannelsStreamingRpcReady => {
                 dispatcher.push(TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit);
+                dispatcher.push(TransitionFrontierSyncAction::BlocksPeersQuery);
             }
             P2pCallbacksAction::P2pChannelsStreamingRpcTimeout { peer_id, id } => {
                 let peer_id = *peer_id;
@@ -175,6 +178,11 @@ impl crate::State {
                         error: PeerStagedLedgerPartsFetchError::Timeout,
                     },
                 );
+                dispatcher.push(TransitionFrontierSyncAction::BlocksPeerQueryError {
+                    peer_id,
+                    rpc_id,
+                    error: PeerBlockFetchError::Timeout,
+                });
                 dispatcher.push(P2pDisconnectionAction::Init {
                     peer_id,
                     reason: P2pDisconnectionReason::TransitionFrontierStreamingRpcTimeout(rpc_kind),
@@ -197,6 +205,11 @@ impl crate::State {
                                 error: PeerStagedLedgerPartsFetchError::DataUnavailable,
                             },
                         );
+                        dispatcher.push(TransitionFrontierSyncAction::BlocksPeerQueryError {
+                            peer_id,
+                            rpc_id,
+                            error: PeerBlockFetchError::DataUnavailable,
+                        });
                     }
                     Some(P2pStreamingRpcResponseFull::StagedLedgerParts(parts)) => {
                         dispatcher.push(
@@ -207,8 +220,47 @@ impl crate::State {
                             },
                         );
                     }
+                    Some(P2pStreamingRpcResponseFull::Block(block)) => {
+                        let Ok(block) = BlockWithHash::try_new(block.clone()) else {
+                            mina_core::error!(meta.time(); "P2pStreamingRpcResponseFull::Block: invalid block");
+                            return;
+                        };
+                        dispatcher.push(TransitionFrontierSyncAction::BlocksPeerQuerySuccess {
+                            peer_id,
+                            rpc_id,
+                            response: block,
+                        });
+                    }
                 }
                 dispatcher.push(TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit);
+                dispatcher.push(TransitionFrontierSyncAction::BlocksPeersQuery);
+            }
+            P2pCallbacksAction::P2pChannelsStreamingRpcRequestReceived {
+                peer_id,
+                id,
+                request,
+            } => {
+                match &**request {
+                    P2pStreamingRpcRequest::StagedLedgerParts(..) => {
+                        // async ledger request will be triggered
+                        // by `LedgerReadAction::FindTodos`.
+                    }
+                    P2pStreamingRpcRequest::Block(hash) => {
+                        let response = state
+                            .transition_frontier
+                            .best_chain
+                            .iter()
+                            .rev()
+                            .find(|b| b.hash() == hash)
+                            .map(|b| b.block().clone())
+                            .map(P2pStreamingRpcResponseFull::Block);
+                        dispatcher.push(P2pChannelsStreamingRpcAction::ResponseSendInit {
+                            peer_id: *peer_id,
+                            id: *id,
+                            response,
+                        });
+                    }
+                }
             }
             P2pCallbacksAction::P2pDisconnection { peer_id } => {
                 let peer_id = *peer_id;
@@ -469,11 +521,7 @@ impl crate::State {
             }
             P2pRpcRequest::InitialPeers => {
                 let p2p = p2p_ready!(state.p2p, meta.time());
-                let peers = p2p
-                    .peers
-                    .iter()
-                    .filter_map(|(_, v)| v.dial_opts.clone())
-                    .collect();
+                let peers = p2p.initial_peers_for(&peer_id).into_iter().collect();
                 let response = Some(Box::new(P2pRpcResponse::InitialPeers(peers)));
 
                 dispatcher.push(P2pChannelsRpcAction::ResponseSend {
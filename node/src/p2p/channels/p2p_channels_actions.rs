@@ -5,3 +5,9 @@ impl redux::EnablingCondition<crate::State> for P2pChannelsMessageReceivedAction
         state.p2p.is_enabled(self, time)
     }
 }
+
+impl redux::EnablingCondition<crate::State> for P2pChannelsMessageShedAction {
+    fn is_enabled(&self, state: &crate::State, time: redux::Timestamp) -> bool {
+        state.p2p.is_enabled(self, time)
+    }
+}
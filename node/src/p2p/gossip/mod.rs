@@ -0,0 +1,3 @@
+pub use ::p2p::gossip::*;
+
+mod p2p_gossip_actions;
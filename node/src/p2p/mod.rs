@@ -7,6 +7,7 @@ use p2p::{
 pub mod channels;
 pub mod connection;
 pub mod disconnection;
+pub mod gossip;
 pub mod network;
 pub mod peer;
 
@@ -100,6 +101,7 @@ impl_into_global_action!(network::kad::P2pNetworkKademliaAction);
 impl_into_global_action!(network::pubsub::P2pNetworkPubsubAction);
 
 impl_into_global_action!(channels::P2pChannelsMessageReceivedAction);
+impl_into_global_action!(channels::P2pChannelsMessageShedAction);
 impl_into_global_action!(channels::signaling::discovery::P2pChannelsSignalingDiscoveryAction);
 impl_into_global_action!(channels::signaling::exchange::P2pChannelsSignalingExchangeAction);
 impl_into_global_action!(channels::best_tip::P2pChannelsBestTipAction);
@@ -114,6 +116,7 @@ impl_into_global_action!(p2p::P2pNetworkKadRequestAction);
 impl_into_global_action!(p2p::P2pNetworkKadBootstrapAction);
 impl_into_global_action!(p2p::P2pNetworkYamuxAction);
 impl_into_global_action!(p2p::peer::P2pPeerAction);
+impl_into_global_action!(p2p::gossip::P2pGossipAction);
 impl_into_global_action!(p2p::network::identify::stream::P2pNetworkIdentifyStreamAction);
 impl_into_global_action!(p2p::identify::P2pIdentifyAction);
 impl_into_global_action!(p2p::P2pNetworkSelectAction);
@@ -1,3 +1,6 @@
 pub use ::p2p::peer::*;
 
 mod p2p_peer_actions;
+
+mod p2p_peer_list_service;
+pub use p2p_peer_list_service::*;
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    p2p::{connection::outgoing::P2pConnectionOutgoingInitOpts, PeerId},
+    rpc::RpcId,
+};
+
+pub trait P2pPeerListService: redux::Service {
+    /// Fetch the peer list from the configured url, outside of the
+    /// periodic refresh. Result is sent as a [`P2pPeerListEvent`].
+    ///
+    /// Returns an error if the node isn't configured with a peer list url.
+    fn peer_list_refresh(&mut self, rpc_id: Option<RpcId>) -> Result<(), String>;
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pPeerListEvent {
+    /// Set if the refresh was requested with an rpc.
+    pub rpc_id: Option<RpcId>,
+    pub result: Result<P2pPeerListDiff, String>,
+}
+
+/// Changes in the peer list compared to the previous fetch.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct P2pPeerListDiff {
+    pub added: Vec<P2pConnectionOutgoingInitOpts>,
+    pub removed: Vec<PeerId>,
+    /// Number of peers in the fetched list.
+    pub total: usize,
+}
+
+impl P2pPeerListDiff {
+    /// Entries whose address changed are reported as added, so that the
+    /// new address replaces the old one.
+    pub fn new(
+        prev: &[P2pConnectionOutgoingInitOpts],
+        new: &[P2pConnectionOutgoingInitOpts],
+    ) -> Self {
+        let added = new
+            .iter()
+            .filter(|opts| !prev.contains(opts))
+            .cloned()
+            .collect();
+        let removed = prev
+            .iter()
+            .map(|opts| *opts.peer_id())
+            .filter(|peer_id| new.iter().all(|opts| opts.peer_id() != peer_id))
+            .collect();
+        Self {
+            added,
+            removed,
+            total: new.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl std::fmt::Display for P2pPeerListEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PeerList, ")?;
+        if let Some(rpc_id) = self.rpc_id {
+            write!(f, "{rpc_id}, ")?;
+        }
+        match &self.result {
+            Ok(diff) => write!(
+                f,
+                "Ok, added: {}, removed: {}",
+                diff.added.len(),
+                diff.removed.len()
+            ),
+            Err(_) => write!(f, "Err"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::p2p::{connection::outgoing::P2pConnectionOutgoingInitLibp2pOpts, webrtc::Host};
+
+    use super::*;
+
+    fn opts(id: u8, port: u16) -> P2pConnectionOutgoingInitOpts {
+        P2pConnectionOutgoingInitOpts::LibP2P(P2pConnectionOutgoingInitLibp2pOpts {
+            peer_id: PeerId::from_bytes([id; 32]),
+            host: Host::Ipv4([127, 0, 0, 1].into()),
+            port,
+        })
+    }
+
+    #[test]
+    fn peer_list_diff() {
+        let prev = [opts(1, 8302), opts(2, 8302), opts(3, 8302)];
+        let new = [opts(1, 8302), opts(3, 8303), opts(4, 8302)];
+        let diff = P2pPeerListDiff::new(&prev, &new);
+
+        assert_eq!(diff.added, [opts(3, 8303), opts(4, 8302)]);
+        assert_eq!(diff.removed, [PeerId::from_bytes([2; 32])]);
+        assert_eq!(diff.total, 3);
+        assert!(P2pPeerListDiff::new(&new, &new).is_empty());
+    }
+}
@@ -1,30 +1,36 @@
 mod rpc_state;
-use std::{collections::BTreeMap, str::FromStr};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
 
 use ledger::{
     scan_state::{
         currency::{Amount, Balance, Fee, Nonce, Slot},
         transaction_logic::{signed_command, signed_command::SignedCommandPayload, valid, Memo},
     },
-    transaction_pool::{diff, ValidCommandWithHash},
+    staged_ledger::action_event_store::ActionsEvents,
+    transaction_pool::{diff, SenderQueue, ValidCommandWithHash},
     Account, AccountId,
 };
 use mina_core::{
-    block::{AppliedBlock, ArcBlockWithHash},
-    consensus::{ConsensusConstants, ConsensusTime},
+    block::{prevalidate::BlockPrevalidationError, AppliedBlock, ArcBlock, ArcBlockWithHash},
+    consensus::{ConsensusConstants, ConsensusForkComparison, ConsensusTime},
 };
 use mina_node_account::AccountPublicKey;
 use mina_p2p_messages::{
     bigint::{BigInt, InvalidBigInt},
     v2::{
-        LedgerHash, MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseSignedCommandStableV2,
-        MinaBaseTransactionStatusStableV2, MinaBaseUserCommandStableV2,
-        MinaBaseZkappCommandTStableV1WireStableV1, MinaTransactionTransactionStableV2,
-        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse, StateHash, TransactionHash,
-        TransactionSnarkWorkTStableV2,
+        LedgerHash, MinaBasePendingCoinbaseStackIdStableV1,
+        MinaBasePendingCoinbaseStackVersionedStableV1, MinaBaseSignedCommandPayloadBodyStableV2,
+        MinaBaseSignedCommandStableV2, MinaBaseTransactionStatusStableV2,
+        MinaBaseUserCommandStableV2, MinaBaseZkappCommandTStableV1WireStableV1,
+        MinaTransactionTransactionStableV2, PendingCoinbaseHash,
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse, StagedLedgerHashPendingCoinbaseAux,
+        StateHash, TokenIdKeyHash, TransactionHash, TransactionSnarkWorkTStableV2,
     },
 };
-use p2p::bootstrap::P2pNetworkKadBootstrapStats;
+use p2p::{bootstrap::P2pNetworkKadBootstrapStats, webrtc::ConnectionStats};
 pub use rpc_state::*;
 
 mod rpc_actions;
@@ -38,17 +44,31 @@ mod rpc_impls;
 mod heartbeat;
 pub use heartbeat::{NodeHeartbeat, ProducedBlockInfo, SignedNodeHeartbeat};
 
+mod sync_diagnosis;
+pub use sync_diagnosis::*;
+
+mod pending_requests;
+pub use pending_requests::*;
+
+mod pagination;
+pub use pagination::*;
+
+mod zkapp_events;
+pub use zkapp_events::*;
+
 pub use mina_core::requests::{RpcId, RpcIdType};
 
 use ledger::scan_state::scan_state::{transaction_snark::OneOrTwo, AvailableJobMessage};
-use mina_core::snark::SnarkJobId;
+use mina_core::snark::{Snark, SnarkJobId};
 use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    config::EnabledFeatures,
     external_snark_worker::{
-        ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkSpecError,
+        ExternalSnarkWorkerCoordinatorStats, ExternalSnarkWorkerError,
+        ExternalSnarkWorkerWorkError, SnarkWorkSpecError,
     },
     ledger::{
         read::{LedgerReadId, LedgerReadKind, LedgerStatus},
@@ -58,16 +78,30 @@ use crate::{
         connection::{
             incoming::P2pConnectionIncomingInitOpts, outgoing::P2pConnectionOutgoingInitOpts,
         },
+        gossip::P2pGossipKind,
+        peer::P2pPeerListDiff,
         PeerId,
     },
     service::Queues,
-    snark_pool::{JobCommitment, JobState, JobSummary},
+    snark::{
+        verify_dry_run::{SnarkVerifyDryRunInput, SnarkVerifyDryRunReport},
+        SnarkStats, SnarkVerifyRecord, VerifierDigests,
+    },
+    snark_pool::{JobCommitment, JobState, JobSummary, SnarkPoolState},
     stats::{
         actions::{ActionStatsForBlock, ActionStatsSnapshot},
         block_producer::{
-            BlockProductionAttempt, BlockProductionAttemptWonSlot, VrfEvaluatorStats,
+            BlockProductionAttempt, BlockProductionAttemptWonSlot, EpochDelegationChange,
+            VrfEvaluatorStats,
         },
+        peer_contribution::PeerContribution,
         sync::SyncStatsSnapshot,
+        sync_history::SyncHistory,
+    },
+    transition_frontier::{
+        archive::archive_service::ArchiveUploadStats,
+        sync::ledger::{LedgerSnapshot, LedgerSnapshotQuery},
+        BlockSummary, LedgerHashDivergence,
     },
 };
 
@@ -78,7 +112,10 @@ pub enum RpcRequest {
     HeartbeatGet,
     ActionStatsGet(ActionStatsQuery),
     SyncStatsGet(SyncStatsQuery),
+    SyncHistoryGet,
     BlockProducerStatsGet,
+    PeerContributionStatsGet,
+    ArchiveUploadStatsGet,
     MessageProgressGet,
     PeersGet,
     P2pConnectionOutgoing(P2pConnectionOutgoingInitOpts),
@@ -91,25 +128,51 @@ pub enum RpcRequest {
     SnarkerConfig,
     SnarkerJobCommit { job_id: SnarkJobId },
     SnarkerJobSpec { job_id: SnarkJobId },
+    SnarkerWorkSubmit { snark: Snark },
     SnarkerWorkers,
     HealthCheck,
     ReadinessCheck,
     DiscoveryRoutingTable,
     DiscoveryBoostrapStats,
     TransactionPoolGet,
+    TransactionPoolSenderQueuesGet(Option<AccountPublicKey>),
     LedgerAccountsGet(AccountQuery),
+    LedgerAccountsPageGet(RpcPageQuery),
+    LedgerAccountsAtBlockGet(StateHash, AccountQuery),
     TransactionInject(Vec<MinaBaseUserCommandStableV2>),
     TransitionFrontierUserCommandsGet,
     BestChain(MaxLength),
+    BestChainPageGet(RpcPageQuery),
     ConsensusConstantsGet,
-    TransactionStatusGet(MinaBaseUserCommandStableV2),
+    TransactionStatusGet(MinaBaseUserCommandStableV2, Option<StateHash>),
     GetBlock(GetBlockQuery),
+    BlockSummaryGet(GetBlockQuery),
     PooledUserCommands(PooledUserCommandsQuery),
     PooledZkappCommands(PooledZkappsCommandsQuery),
+    PooledUserCommandsPageGet(PooledUserCommandsQuery, RpcPageQuery<TransactionHash>),
+    PooledZkappCommandsPageGet(PooledZkappsCommandsQuery, RpcPageQuery<TransactionHash>),
+    ZkappEventsPageGet(RpcZkappEventsQuery, RpcPageQuery),
+    ZkappActionsEventsGet(RpcZkappActionsEventsQuery),
+    TokenSupplyGet(TokenIdKeyHash),
     GenesisBlockGet,
     ConsensusTimeGet(ConsensusTimeQuery),
     LedgerStatusGet(LedgerHash),
     LedgerAccountDelegatorsGet(LedgerHash, AccountId),
+    LedgerSnapshotGet(LedgerSnapshotQuery),
+    BlockInject(ArcBlock),
+    ConsensusForkDecisionsGet,
+    PendingCoinbaseGet(Option<StateHash>),
+    PeerListRefresh,
+    GossipPause { kind: P2pGossipKind, paused: bool },
+    SyncDiagnosisGet,
+    NodeOverviewGet,
+    LocallyGeneratedCommandsGet,
+    PendingRequestsGet,
+    SnarkStatsGet,
+    SnarkVerifyHistoryGet,
+    SnarkVerifierDigestsGet,
+    SnarkVerifyDryRun(SnarkVerifyDryRunInput),
+    EnabledFeaturesGet,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -209,6 +272,9 @@ pub struct RpcPeerInfo {
     pub incoming: bool,
     pub is_libp2p: bool,
     pub time: u64,
+    /// Quality of the WebRTC connection, when the peer is connected over
+    /// WebRTC.
+    pub connection_stats: Option<ConnectionStats>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -283,7 +349,28 @@ pub enum RpcScanStateSummaryScanStateJobStatus {
     Done,
 }
 
-#[derive(Serialize, Debug, Clone)]
+/// Pending coinbase collection of a staged ledger.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcPendingCoinbase {
+    /// Merkle root of the collection, as included in the staged ledger hash.
+    pub merkle_root: PendingCoinbaseHash,
+    /// Merkle root computed again from the stacks, ignoring cached hashes.
+    pub recomputed_merkle_root: PendingCoinbaseHash,
+    pub pending_coinbase_aux: StagedLedgerHashPendingCoinbaseAux,
+    /// Stacks which weren't popped yet, oldest first.
+    pub stacks: Vec<RpcPendingCoinbaseStack>,
+    /// Stack which the coinbase of the next block is added to.
+    pub latest_stack: MinaBasePendingCoinbaseStackVersionedStableV1,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcPendingCoinbaseStack {
+    pub id: MinaBasePendingCoinbaseStackIdStableV1,
+    #[serde(flatten)]
+    pub stack: MinaBasePendingCoinbaseStackVersionedStableV1,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcSnarkPoolJobSummary {
     pub time: Timestamp,
     pub id: SnarkJobId,
@@ -331,6 +418,35 @@ pub enum RpcSnarkerJobSpecResponse {
     JobNotFound,
 }
 
+/// Response to a work submitted by a snarker which doesn't run this node.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind")]
+pub enum RpcSnarkerWorkSubmitResponse {
+    /// The work was verified and added to the pool.
+    Accepted,
+    /// The job isn't in the pool, e.g. it was already included in a block.
+    JobNotFound,
+    /// The pool already has a work for the job with a fee at most `fee`.
+    NotCheaper { fee: CurrencyFeeStableV1 },
+    /// The proofs of the work didn't verify, or the verifier failed.
+    Invalid { error: String },
+}
+
+impl RpcSnarkerWorkSubmitResponse {
+    /// Why the pool doesn't need the work, if it doesn't.
+    pub fn rejection(pool: &SnarkPoolState, snark: &Snark) -> Option<Self> {
+        let Some(job) = pool.get(&snark.job_id()) else {
+            return Some(Self::JobNotFound);
+        };
+        match &job.snark {
+            Some(cur) if &cur.work >= snark => Some(Self::NotCheaper {
+                fee: cur.work.fee.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcMessageProgressResponse {
     pub messages_stats: BTreeMap<PeerId, MessagesStats>,
@@ -382,7 +498,13 @@ pub type RpcStatusGetResponse = Option<RpcNodeStatus>;
 pub type RpcHeartbeatGetResponse = Option<SignedNodeHeartbeat>;
 pub type RpcActionStatsGetResponse = Option<ActionStatsResponse>;
 pub type RpcSyncStatsGetResponse = Option<Vec<SyncStatsSnapshot>>;
+/// Sync durations and chain growth, kept across restarts.
+pub type RpcSyncHistoryGetResponse = Option<SyncHistory>;
 pub type RpcBlockProducerStatsGetResponse = Option<RpcBlockProducerStats>;
+/// Peers with the most accepted blocks, transactions and snark works first.
+pub type RpcPeerContributionStatsGetResponse = Option<Vec<PeerContribution>>;
+/// `None` if the node doesn't archive blocks.
+pub type RpcArchiveUploadStatsGetResponse = Option<Vec<ArchiveUploadStats>>;
 pub type RpcPeersGetResponse = Vec<RpcPeerInfo>;
 pub type RpcP2pConnectionOutgoingResponse = Result<(), String>;
 pub type RpcScanStateSummaryGetResponse = Result<RpcScanStateSummary, String>;
@@ -392,18 +514,74 @@ pub type RpcSnarkPoolPendingJobsGetResponse = Vec<JobState>;
 pub type RpcSnarkPoolJobGetResponse = Option<RpcSnarkPoolJobFull>;
 pub type RpcSnarkerConfigGetResponse = Option<RpcSnarkerConfig>;
 pub type RpcTransactionPoolResponse = Vec<ValidCommandWithHash>;
+pub type RpcTransactionPoolSenderQueuesGetResponse = Vec<SenderQueue>;
 pub type RpcLedgerSlimAccountsResponse = Vec<AccountSlim>;
 pub type RpcLedgerAccountsResponse = Vec<Account>;
+pub type RpcLedgerAccountsPageGetResponse = RpcPageResult<Account>;
+pub type RpcLedgerAccountsAtBlockGetResponse = Result<Vec<Account>, RpcBlockPrunedError>;
 pub type RpcTransitionFrontierUserCommandsResponse = Vec<MinaBaseUserCommandStableV2>;
 pub type RpcBestChainResponse = Vec<AppliedBlock>;
+/// Blocks of the best chain, newest first.
+pub type RpcBestChainPageGetResponse = RpcPageResult<AppliedBlock>;
 pub type RpcConsensusConstantsGetResponse = ConsensusConstants;
-pub type RpcTransactionStatusGetResponse = TransactionStatus;
+pub type RpcTransactionStatusGetResponse = Result<TransactionStatus, RpcBlockPrunedError>;
 pub type RpcPooledUserCommandsResponse = Vec<MinaBaseSignedCommandStableV2>;
 pub type RpcPooledZkappCommandsResponse = Vec<MinaBaseZkappCommandTStableV1WireStableV1>;
+pub type RpcPooledUserCommandsPageGetResponse =
+    RpcPageResult<MinaBaseSignedCommandStableV2, TransactionHash>;
+pub type RpcPooledZkappCommandsPageGetResponse =
+    RpcPageResult<MinaBaseZkappCommandTStableV1WireStableV1, TransactionHash>;
+/// Events or actions of the best chain, newest blocks first.
+pub type RpcZkappEventsPageGetResponse = RpcPageResult<RpcZkappEvents>;
+/// Oldest first, `None` if the node doesn't keep zkApp actions and events.
+pub type RpcZkappActionsEventsGetResponse = Option<Vec<ActionsEvents>>;
+/// Sum of the balances of the accounts of the token in the best tip
+/// ledger, `None` if it overflows.
+pub type RpcTokenSupplyGetResponse = Option<Amount>;
 pub type RpcGenesisBlockResponse = Option<ArcBlockWithHash>;
 pub type RpcConsensusTimeGetResponse = Option<ConsensusTime>;
 pub type RpcLedgerStatusGetResponse = Option<LedgerStatus>;
 pub type RpcLedgerAccountDelegatorsGetResponse = Option<Vec<Account>>;
+/// `None` if the ledger or the block isn't available.
+pub type RpcLedgerSnapshotGetResponse = Option<LedgerSnapshot>;
+pub type RpcBlockInjectResponse = Result<StateHash, RpcBlockInjectError>;
+/// Most recent fork decisions, newest first.
+pub type RpcConsensusForkDecisionsGetResponse = Vec<ConsensusForkComparison>;
+pub type RpcPendingCoinbaseGetResponse = Option<RpcPendingCoinbase>;
+/// Changes applied from the refetched peer list.
+pub type RpcPeerListRefreshResponse = Result<P2pPeerListDiff, String>;
+/// Gossip kinds that are paused after the change.
+pub type RpcGossipPauseResponse = Result<BTreeSet<P2pGossipKind>, String>;
+
+pub type RpcSyncDiagnosisGetResponse = RpcSyncDiagnosis;
+pub type RpcNodeOverviewGetResponse = RpcNodeOverview;
+pub type RpcLocallyGeneratedCommandsGetResponse = Vec<RpcLocallyGeneratedCommand>;
+/// Requests pending in all the subsystems, oldest first.
+pub type RpcPendingRequestsGetResponse = Vec<RpcPendingRequest>;
+pub type RpcSnarkStatsGetResponse = SnarkStats;
+/// Recently completed verifications, the most recently finished first.
+pub type RpcSnarkVerifyHistoryGetResponse = Vec<SnarkVerifyRecord>;
+/// `None` if the node didn't compute the digests, e.g. in the browser.
+pub type RpcSnarkVerifierDigestsGetResponse = Option<VerifierDigests>;
+pub type RpcSnarkVerifyDryRunResponse = SnarkVerifyDryRunReport;
+pub type RpcEnabledFeaturesGetResponse = EnabledFeatures;
+
+#[derive(Serialize, Deserialize, Debug, Clone, thiserror::Error)]
+pub enum RpcBlockInjectError {
+    #[error("invalid block: {0}")]
+    InvalidBlock(String),
+    #[error("block is already known")]
+    AlreadyKnown,
+    #[error("block failed prevalidation: {0:?}")]
+    Prevalidation(BlockPrevalidationError),
+}
+
+/// The block a query was anchored to isn't in the best chain anymore,
+/// either because it was pruned from the transition frontier or because
+/// of a reorg. The query must be retried with a more recent block.
+#[derive(Serialize, Deserialize, Debug, Clone, thiserror::Error)]
+#[error("block {0} is not in the best chain anymore")]
+pub struct RpcBlockPrunedError(pub StateHash);
 
 #[derive(Serialize, Deserialize, Debug, Clone, strum_macros::Display)]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
@@ -501,6 +679,18 @@ pub enum RpcTransactionInjectResponse {
 //     }
 // }
 
+/// Command submitted to this node, which it rebroadcasts until it is included
+/// in the best chain or expires.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcLocallyGeneratedCommand {
+    pub hash: TransactionHash,
+    pub command: MinaBaseUserCommandStableV2,
+    /// When the command was added to the pool, or last resubmitted.
+    pub added_at: Timestamp,
+    /// Included in the best chain, so no longer rebroadcast.
+    pub committed: bool,
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct AccountSlim {
     pub public_key: AccountPublicKey,
@@ -541,6 +731,8 @@ pub struct RpcNodeStatusNetworkInfo {
     pub external_ip: Option<String>,
     pub client_port: Option<u16>,
     pub libp2p_port: Option<u16>,
+    /// Peers the node was configured to connect to first, as multiaddrs.
+    pub initial_peers: Vec<String>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -550,7 +742,7 @@ pub struct RpcNodeStatusLedger {
     pub pending_reads: Vec<(LedgerReadId, LedgerReadKind, redux::Timestamp)>,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcNodeStatusResources {
     pub p2p_malloc_size: usize,
     pub transition_frontier: serde_json::Value,
@@ -591,6 +783,60 @@ pub struct RpcNodeStatusSnarkPool {
     pub snarks: usize,
 }
 
+/// Summary of the node shown by the dashboard, so that it can be
+/// refreshed with a single request instead of polling the status, peers,
+/// sync and block producer endpoints separately.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcNodeOverview {
+    pub time: redux::Timestamp,
+    /// Global slot of the current time, not of the best tip.
+    pub current_global_slot: Option<u32>,
+    pub chain_id: Option<String>,
+    pub synced: bool,
+    pub sync: RpcNodeStatusTransitionFrontierSync,
+    pub best_tip: Option<RpcNodeOverviewBestTip>,
+    /// Latest blocks of the best chain, newest first, the best tip included.
+    pub recent_blocks: Vec<RpcNodeOverviewBestTip>,
+    /// Our ledger hashes which differ from the ones reported by most of
+    /// the peers, see
+    /// [`crate::transition_frontier::TransitionFrontierLedgerHashesCheck`].
+    #[serde(default)]
+    pub ledger_hash_divergences: Vec<LedgerHashDivergence>,
+    pub peers: RpcNodeOverviewPeers,
+    pub resources_status: RpcNodeStatusResources,
+    pub transaction_pool: RpcNodeStatusTransactionPool,
+    pub snark_pool: RpcNodeStatusSnarkPool,
+    /// `None` if the node isn't a block producer.
+    pub block_producer: Option<RpcNodeOverviewBlockProducer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcNodeOverviewBestTip {
+    pub hash: StateHash,
+    pub height: u32,
+    pub global_slot: u32,
+    pub timestamp: redux::Timestamp,
+    pub producer: AccountPublicKey,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct RpcNodeOverviewPeers {
+    pub connected: usize,
+    pub connecting: usize,
+    pub disconnected: usize,
+    /// Highest best tip among the connected peers.
+    pub best_tip_height: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcNodeOverviewBlockProducer {
+    pub public_key: AccountPublicKey,
+    pub current_attempt: Option<BlockProductionAttempt>,
+    /// Won slots of the current and next epoch, which the node hasn't
+    /// attempted to produce a block for yet.
+    pub future_won_slots: Vec<BlockProductionAttemptWonSlot>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcBlockProducerStats {
     pub current_time: redux::Timestamp,
@@ -603,6 +849,8 @@ pub struct RpcBlockProducerStats {
     pub future_won_slots: Vec<BlockProductionAttemptWonSlot>,
     pub current_epoch_vrf_stats: Option<VrfEvaluatorStats>,
     pub vrf_stats: BTreeMap<u32, VrfEvaluatorStats>,
+    /// Changes of the delegators of the producer, per evaluated epoch.
+    pub delegation_changes: BTreeMap<u32, EpochDelegationChange>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -616,6 +864,8 @@ pub struct RpcSnarkWorker {
     pub time: Option<Timestamp>,
     pub id: Option<String>,
     pub status: RpcSnarkWorkerStatus,
+    /// Submissions of the work to other nodes, by node url.
+    pub coordinators: BTreeMap<String, ExternalSnarkWorkerCoordinatorStats>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -687,6 +937,9 @@ pub enum GetBlockQuery {
 }
 
 pub type RpcGetBlockResponse = Option<AppliedBlock>;
+/// Summary of a block of the best chain, or of a block which left the
+/// transition frontier and is still in its root history.
+pub type RpcBlockSummaryGetResponse = Option<BlockSummary>;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PooledCommandsQuery<ID> {
@@ -0,0 +1,249 @@
+//! Cursors for reading large results one page at a time.
+//!
+//! A cursor is anchored to the best tip at the time the first page was
+//! requested. Following pages are read from the state of that block
+//! instead of the current best tip, so that items aren't skipped or
+//! returned twice when the best tip advances between two requests.
+//!
+//! Cursors are serialized as strings, which clients should pass back
+//! as they are, without making assumptions about their format.
+
+use std::{fmt, str::FromStr};
+
+use mina_core::block::AppliedBlock;
+use mina_p2p_messages::v2::{StateHash, TransactionHash};
+use serde::{Deserialize, Serialize};
+
+use crate::State;
+
+/// Number of items of a page, when the query doesn't set it.
+pub const RPC_PAGE_DEFAULT_LIMIT: usize = 100;
+pub const RPC_PAGE_MAX_LIMIT: usize = 1000;
+
+/// Position of the last item returned, in the results read from the
+/// `anchor` block.
+///
+/// `K` is an offset for results which are fully determined by the anchor
+/// block, such as its ledger or its chain. Results from the transaction
+/// pool, which only has a current state, are sorted by hash and use the
+/// hash of the last command instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcCursor<K = usize> {
+    pub anchor: StateHash,
+    pub after: K,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RpcPageQuery<K = usize> {
+    /// Cursor returned with the previous page, `None` for the first page.
+    pub cursor: Option<RpcCursor<K>>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcPage<T, K = usize> {
+    pub items: Vec<T>,
+    /// Cursor of the next page, `None` if this is the last one.
+    pub next_cursor: Option<RpcCursor<K>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, thiserror::Error)]
+pub enum RpcPageError {
+    #[error("no best tip to anchor the cursor to")]
+    NoBestTip,
+    /// The anchor block was pruned from the transition frontier, or
+    /// isn't part of the best chain anymore. Pagination must be restarted
+    /// from the first page.
+    #[error("cursor anchor {0} is not in the best chain anymore")]
+    AnchorNotFound(StateHash),
+    #[error("invalid cursor: {0}")]
+    InvalidCursor(String),
+}
+
+pub type RpcPageResult<T, K = usize> = Result<RpcPage<T, K>, RpcPageError>;
+
+impl<K> RpcPageQuery<K> {
+    pub fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(RPC_PAGE_DEFAULT_LIMIT)
+            .clamp(1, RPC_PAGE_MAX_LIMIT)
+    }
+
+    /// Block the page is read from: the anchor of the cursor, or the best
+    /// tip for the first page.
+    pub fn anchor<'a>(&self, state: &'a State) -> Result<&'a AppliedBlock, RpcPageError> {
+        let best_chain = &state.transition_frontier.best_chain;
+        match &self.cursor {
+            None => best_chain.last().ok_or(RpcPageError::NoBestTip),
+            Some(cursor) => best_chain
+                .iter()
+                .rev()
+                .find(|block| block.hash() == &cursor.anchor)
+                .ok_or_else(|| RpcPageError::AnchorNotFound(cursor.anchor.clone())),
+        }
+    }
+}
+
+impl RpcPageQuery {
+    pub fn offset(&self) -> usize {
+        self.cursor.as_ref().map_or(0, |cursor| cursor.after)
+    }
+}
+
+impl<T> RpcPage<T> {
+    /// Page of `items`, which follow the offset of the cursor in results
+    /// that must be the same for every page read from the `anchor` block.
+    pub fn from_offset(
+        anchor: &StateHash,
+        items: impl IntoIterator<Item = T>,
+        query: &RpcPageQuery,
+    ) -> Self {
+        let offset = query.offset();
+        let mut items = items.into_iter();
+        let page = items.by_ref().take(query.limit()).collect::<Vec<_>>();
+        let next_cursor = items.next().map(|_| RpcCursor {
+            anchor: anchor.clone(),
+            after: offset + page.len(),
+        });
+
+        Self {
+            items: page,
+            next_cursor,
+        }
+    }
+}
+
+impl<T> RpcPage<T, TransactionHash> {
+    /// Page of pooled `items`, ordered by their hash.
+    ///
+    /// Commands added to the pool since the first page are returned only
+    /// if their hash comes after the cursor, but commands which stay in
+    /// the pool are returned exactly once.
+    pub fn after_hash(
+        anchor: &StateHash,
+        mut items: Vec<(TransactionHash, T)>,
+        query: &RpcPageQuery<TransactionHash>,
+    ) -> Self {
+        items.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let start = match &query.cursor {
+            None => 0,
+            Some(cursor) => items.partition_point(|(hash, _)| hash <= &cursor.after),
+        };
+        let mut items = items.into_iter().skip(start);
+        let page = items.by_ref().take(query.limit()).collect::<Vec<_>>();
+        let next_cursor = match (items.next(), page.last()) {
+            (Some(_), Some((after, _))) => Some(RpcCursor {
+                anchor: anchor.clone(),
+                after: after.clone(),
+            }),
+            _ => None,
+        };
+
+        Self {
+            items: page.into_iter().map(|(_, item)| item).collect(),
+            next_cursor,
+        }
+    }
+}
+
+impl<K: fmt::Display> fmt::Display for RpcCursor<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.anchor, self.after)
+    }
+}
+
+impl<K: FromStr> FromStr for RpcCursor<K> {
+    type Err = RpcPageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RpcPageError::InvalidCursor(s.to_owned());
+        let (anchor, after) = s.split_once('.').ok_or_else(invalid)?;
+
+        Ok(Self {
+            anchor: anchor.parse().map_err(|_| invalid())?,
+            after: after.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl<K: fmt::Display> Serialize for RpcCursor<K> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, K: FromStr> Deserialize<'de> for RpcCursor<K> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anchor() -> StateHash {
+        "3NKxUSAJE3wqJkrtBhMYhwzrMq3B5sKjPJQRyXz1YrPWA7761opD"
+            .parse()
+            .unwrap()
+    }
+
+    fn hash(byte: u8) -> TransactionHash {
+        TransactionHash::from(&[byte; 32])
+    }
+
+    #[test]
+    fn offset_pages_cover_items_once() {
+        let items = (0..25).collect::<Vec<_>>();
+        let mut query = RpcPageQuery {
+            cursor: None,
+            limit: Some(10),
+        };
+        let mut read = vec![];
+        loop {
+            let rest = items.iter().skip(query.offset()).copied();
+            let page = RpcPage::from_offset(&anchor(), rest, &query);
+            read.extend(page.items);
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(read, items);
+    }
+
+    #[test]
+    fn hash_pages_are_stable_when_pool_changes() {
+        let query = RpcPageQuery {
+            cursor: None,
+            limit: Some(2),
+        };
+        let pool = [3, 1, 5, 4].map(|b| (hash(b), b)).to_vec();
+        let page = RpcPage::after_hash(&anchor(), pool, &query);
+        assert_eq!(page.items, vec![1, 3]);
+
+        // 1 was included in a block, 2 was added before the cursor
+        let query = RpcPageQuery {
+            cursor: page.next_cursor,
+            limit: Some(2),
+        };
+        let pool = [2, 3, 5, 4].map(|b| (hash(b), b)).to_vec();
+        let page = RpcPage::after_hash(&anchor(), pool, &query);
+        assert_eq!(page.items, vec![4, 5]);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn cursor_string_roundtrip() {
+        let cursor = RpcCursor {
+            anchor: anchor(),
+            after: 42,
+        };
+        let s = serde_json::to_string(&cursor).unwrap();
+        assert_eq!(serde_json::from_str::<RpcCursor>(&s).unwrap(), cursor);
+        assert!("42".parse::<RpcCursor>().is_err());
+    }
+}
@@ -0,0 +1,242 @@
+//! Lists the requests the node is waiting on.
+//!
+//! Every effectful request tracked in the state, such as proof verifications,
+//! ledger reads and writes and RPCs sent to peers, is reported with its age
+//! and the last action it went through, so that a request that never completes
+//! shows up instead of silently stalling the subsystem waiting on it.
+
+use std::{fmt, time::Duration};
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ledger::{read::LedgerReadRequestState, write::LedgerWriteState},
+    p2p::{
+        channels::{
+            rpc::{P2pChannelsRpcState, P2pRpcLocalState},
+            streaming_rpc::{P2pChannelsStreamingRpcState, P2pStreamingRpcLocalState},
+        },
+        PeerId,
+    },
+    snark::{
+        block_verify::SnarkBlockVerifyStatus, user_command_verify::SnarkUserCommandVerifyStatus,
+        work_verify::SnarkWorkVerifyStatus,
+    },
+    ActionKind, State,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcPendingRequest {
+    pub subsystem: RpcPendingRequestSubsystem,
+    pub id: String,
+    /// Kind of the request, e.g. `LedgerQuery` for a p2p RPC.
+    pub kind: String,
+    /// Last action the request went through, derived from its state.
+    pub action: ActionKind,
+    /// Peer the request was sent to, for p2p RPCs.
+    pub peer: Option<PeerId>,
+    pub since: Timestamp,
+    pub age: Duration,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcPendingRequestSubsystem {
+    SnarkBlockVerify,
+    SnarkWorkVerify,
+    SnarkUserCommandVerify,
+    LedgerRead,
+    LedgerWrite,
+    P2pRpc,
+    P2pStreamingRpc,
+}
+
+impl RpcPendingRequest {
+    /// Requests pending in all the subsystems, oldest first.
+    pub fn collect(state: &State, now: Timestamp) -> Vec<Self> {
+        let mut requests = Vec::new();
+        let mut push = |subsystem, id: String, kind: String, action, peer, since| {
+            requests.push(Self {
+                subsystem,
+                id,
+                kind,
+                action,
+                peer,
+                since,
+                age: now.checked_sub(since).unwrap_or_default(),
+            })
+        };
+
+        for (id, status) in state.snark.block_verify.jobs.iter() {
+            let (time, block, action) = match status {
+                SnarkBlockVerifyStatus::Init { time, block, .. } => {
+                    (time, block, ActionKind::SnarkBlockVerifyInit)
+                }
+                SnarkBlockVerifyStatus::Pending { time, block, .. } => {
+                    (time, block, ActionKind::SnarkBlockVerifyPending)
+                }
+                _ => continue,
+            };
+            push(
+                RpcPendingRequestSubsystem::SnarkBlockVerify,
+                id.to_string(),
+                format!("Block {}", block.hash_ref()),
+                action,
+                None,
+                *time,
+            );
+        }
+        for (id, status) in state.snark.work_verify.jobs.iter() {
+            let (time, batch, action) = match status {
+                SnarkWorkVerifyStatus::Init { time, batch, .. } => {
+                    (time, batch, ActionKind::SnarkWorkVerifyInit)
+                }
+                SnarkWorkVerifyStatus::Pending { time, batch, .. } => {
+                    (time, batch, ActionKind::SnarkWorkVerifyPending)
+                }
+                _ => continue,
+            };
+            push(
+                RpcPendingRequestSubsystem::SnarkWorkVerify,
+                id.to_string(),
+                format!("{} snarks", batch.len()),
+                action,
+                None,
+                *time,
+            );
+        }
+        for (id, status) in state.snark.user_command_verify.jobs.iter() {
+            let (time, commands, action) = match status {
+                SnarkUserCommandVerifyStatus::Init { time, commands, .. } => {
+                    (time, commands, ActionKind::SnarkUserCommandVerifyInit)
+                }
+                SnarkUserCommandVerifyStatus::Pending { time, commands, .. } => {
+                    (time, commands, ActionKind::SnarkUserCommandVerifyPending)
+                }
+                _ => continue,
+            };
+            push(
+                RpcPendingRequestSubsystem::SnarkUserCommandVerify,
+                id.to_string(),
+                format!("{} commands", commands.len()),
+                action,
+                None,
+                *time,
+            );
+        }
+
+        for (id, request, time) in state.ledger.read.pending_requests() {
+            // Timed out requests are sent again, still pending.
+            let action = match state.ledger.read.get(id) {
+                Some(LedgerReadRequestState::Pending { retries, .. }) if *retries > 0 => {
+                    ActionKind::LedgerReadTimeout
+                }
+                _ => ActionKind::LedgerReadPending,
+            };
+            push(
+                RpcPendingRequestSubsystem::LedgerRead,
+                id.to_string(),
+                format!("{:?}", request.kind()),
+                action,
+                None,
+                time,
+            );
+        }
+        // There is at most one ledger write at a time, so it has no id.
+        let ledger_write = match &state.ledger.write {
+            LedgerWriteState::Init { time, request } => {
+                Some((time, request, ActionKind::LedgerWriteInit))
+            }
+            LedgerWriteState::Pending { time, request } => {
+                Some((time, request, ActionKind::LedgerWritePending))
+            }
+            _ => None,
+        };
+        if let Some((time, request, action)) = ledger_write {
+            push(
+                RpcPendingRequestSubsystem::LedgerWrite,
+                "0".to_owned(),
+                format!("{:?}", request.kind()),
+                action,
+                None,
+                *time,
+            );
+        }
+
+        for (peer_id, peer) in state.p2p.ready_peers_iter() {
+            if let P2pChannelsRpcState::Ready {
+                local: P2pRpcLocalState::Requested { time, id, request },
+                ..
+            } = &peer.channels.rpc
+            {
+                push(
+                    RpcPendingRequestSubsystem::P2pRpc,
+                    id.to_string(),
+                    format!("{:?}", request.kind()),
+                    ActionKind::P2pChannelsRpcRequestSend,
+                    Some(*peer_id),
+                    *time,
+                );
+            }
+            if let P2pChannelsStreamingRpcState::Ready {
+                local:
+                    P2pStreamingRpcLocalState::Requested {
+                        time,
+                        id,
+                        request,
+                        progress,
+                    },
+                ..
+            } = &peer.channels.streaming_rpc
+            {
+                let action = if progress.last_updated() <= *time {
+                    ActionKind::P2pChannelsStreamingRpcRequestSend
+                } else if progress.is_part_pending() {
+                    ActionKind::P2pChannelsStreamingRpcResponseNextPartGet
+                } else {
+                    ActionKind::P2pChannelsStreamingRpcResponsePartReceived
+                };
+                push(
+                    RpcPendingRequestSubsystem::P2pStreamingRpc,
+                    id.to_string(),
+                    format!("{:?}", request.kind()),
+                    action,
+                    Some(*peer_id),
+                    *time,
+                );
+            }
+        }
+
+        requests.sort_by_key(|request| request.since);
+        requests
+    }
+}
+
+impl fmt::Display for RpcPendingRequestSubsystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SnarkBlockVerify => write!(f, "block verification"),
+            Self::SnarkWorkVerify => write!(f, "snark work verification"),
+            Self::SnarkUserCommandVerify => write!(f, "user command verification"),
+            Self::LedgerRead => write!(f, "ledger read"),
+            Self::LedgerWrite => write!(f, "ledger write"),
+            Self::P2pRpc => write!(f, "p2p rpc"),
+            Self::P2pStreamingRpc => write!(f, "p2p streaming rpc"),
+        }
+    }
+}
+
+impl fmt::Display for RpcPendingRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} request {} ({}, last action {:?}",
+            self.subsystem, self.id, self.kind, self.action
+        )?;
+        if let Some(peer) = &self.peer {
+            write!(f, ", to {peer}")?;
+        }
+        write!(f, ") pending for {}s", self.age.as_secs())
+    }
+}
@@ -2,26 +2,41 @@ use ledger::{
     transaction_pool::{diff, ValidCommandWithHash},
     Account, AccountId,
 };
-use mina_core::{block::AppliedBlock, snark::SnarkJobId, ActionEvent};
+use mina_core::{
+    block::{prevalidate::BlockPrevalidationError, AppliedBlock, ArcBlock, ArcBlockWithHash},
+    snark::{Snark, SnarkJobId},
+    ActionEvent,
+};
 use mina_node_account::AccountPublicKey;
-use mina_p2p_messages::v2::{LedgerHash, MinaBaseUserCommandStableV2, TokenIdKeyHash};
+use mina_p2p_messages::v2::{
+    LedgerHash, MinaBaseUserCommandStableV2, StateHash, TokenIdKeyHash, TransactionHash,
+};
 use p2p::PeerId;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     external_snark_worker::SnarkWorkId,
-    p2p::connection::{
-        incoming::P2pConnectionIncomingInitOpts,
-        outgoing::{P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts},
-        P2pConnectionResponse,
-    },
+    p2p::{
+        connection::{
+            incoming::P2pConnectionIncomingInitOpts,
+            outgoing::{P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts},
+            P2pConnectionResponse,
+        },
+        gossip::P2pGossipKind,
+    },
+    snark::verify_dry_run::SnarkVerifyDryRunInput,
+    transition_frontier::sync::ledger::LedgerSnapshotQuery,
 };
 
 use super::{
     ActionStatsQuery, ConsensusTimeQuery, GetBlockQuery, PooledUserCommandsQuery,
     PooledZkappsCommandsQuery, RpcId, RpcLedgerAccountDelegatorsGetResponse,
-    RpcLedgerStatusGetResponse, RpcScanStateSummaryGetQuery, RpcScanStateSummaryScanStateJob,
-    SyncStatsQuery,
+    RpcLedgerAccountsAtBlockGetResponse, RpcLedgerAccountsPageGetResponse,
+    RpcLedgerSnapshotGetResponse, RpcLedgerStatusGetResponse, RpcPageQuery,
+    RpcPeerListRefreshResponse, RpcPendingCoinbaseGetResponse, RpcScanStateSummaryGetQuery,
+    RpcScanStateSummaryScanStateJob, RpcSnarkVerifyDryRunResponse, RpcSnarkerWorkSubmitResponse,
+    RpcSyncDiagnosis, RpcTokenSupplyGetResponse, RpcZkappActionsEventsGetResponse,
+    RpcZkappActionsEventsQuery, RpcZkappEventsQuery, SyncStatsQuery,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
@@ -46,9 +61,18 @@ pub enum RpcAction {
         rpc_id: RpcId,
         query: SyncStatsQuery,
     },
+    SyncHistoryGet {
+        rpc_id: RpcId,
+    },
     BlockProducerStatsGet {
         rpc_id: RpcId,
     },
+    PeerContributionStatsGet {
+        rpc_id: RpcId,
+    },
+    ArchiveUploadStatsGet {
+        rpc_id: RpcId,
+    },
 
     MessageProgressGet {
         rpc_id: RpcId,
@@ -137,6 +161,19 @@ pub enum RpcAction {
         rpc_id: RpcId,
         job_id: SnarkJobId,
     },
+    #[action_event(level = info)]
+    SnarkerWorkSubmitInit {
+        rpc_id: RpcId,
+        snark: Snark,
+    },
+    SnarkerWorkSubmitPending {
+        rpc_id: RpcId,
+    },
+    #[action_event(level = info)]
+    SnarkerWorkSubmitSuccess {
+        rpc_id: RpcId,
+        response: RpcSnarkerWorkSubmitResponse,
+    },
 
     SnarkerWorkersGet {
         rpc_id: RpcId,
@@ -159,6 +196,10 @@ pub enum RpcAction {
     TransactionPool {
         rpc_id: RpcId,
     },
+    TransactionPoolSenderQueues {
+        rpc_id: RpcId,
+        public_key: Option<AccountPublicKey>,
+    },
     #[action_event(level = info)]
     LedgerAccountsGetInit {
         rpc_id: RpcId,
@@ -175,6 +216,35 @@ pub enum RpcAction {
         account_query: AccountQuery,
     },
     #[action_event(level = info)]
+    LedgerAccountsPageGetInit {
+        rpc_id: RpcId,
+        query: RpcPageQuery,
+    },
+    #[action_event(level = info)]
+    LedgerAccountsPageGetPending {
+        rpc_id: RpcId,
+    },
+    #[action_event(level = info)]
+    LedgerAccountsPageGetSuccess {
+        rpc_id: RpcId,
+        response: RpcLedgerAccountsPageGetResponse,
+    },
+    #[action_event(level = info)]
+    LedgerAccountsAtBlockGetInit {
+        rpc_id: RpcId,
+        block_hash: StateHash,
+        account_query: AccountQuery,
+    },
+    #[action_event(level = info)]
+    LedgerAccountsAtBlockGetPending {
+        rpc_id: RpcId,
+    },
+    #[action_event(level = info)]
+    LedgerAccountsAtBlockGetSuccess {
+        rpc_id: RpcId,
+        response: RpcLedgerAccountsAtBlockGetResponse,
+    },
+    #[action_event(level = info)]
     TransactionInjectInit {
         rpc_id: RpcId,
         commands: Vec<MinaBaseUserCommandStableV2>,
@@ -207,6 +277,10 @@ pub enum RpcAction {
         rpc_id: RpcId,
         max_length: u32,
     },
+    BestChainPageGet {
+        rpc_id: RpcId,
+        query: RpcPageQuery,
+    },
     ConsensusConstantsGet {
         rpc_id: RpcId,
     },
@@ -214,12 +288,17 @@ pub enum RpcAction {
     TransactionStatusGet {
         rpc_id: RpcId,
         tx: MinaBaseUserCommandStableV2,
+        block_hash: Option<StateHash>,
     },
 
     BlockGet {
         rpc_id: RpcId,
         query: GetBlockQuery,
     },
+    BlockSummaryGet {
+        rpc_id: RpcId,
+        query: GetBlockQuery,
+    },
     ConsensusTimeGet {
         rpc_id: RpcId,
         query: ConsensusTimeQuery,
@@ -250,6 +329,104 @@ pub enum RpcAction {
         rpc_id: RpcId,
         response: RpcLedgerAccountDelegatorsGetResponse,
     },
+    #[action_event(level = info)]
+    LedgerSnapshotGetInit {
+        rpc_id: RpcId,
+        query: LedgerSnapshotQuery,
+    },
+    #[action_event(level = info)]
+    LedgerSnapshotGetPending {
+        rpc_id: RpcId,
+    },
+    #[action_event(level = info)]
+    LedgerSnapshotGetSuccess {
+        rpc_id: RpcId,
+        response: RpcLedgerSnapshotGetResponse,
+    },
+    /// Handles the block as received from a peer, responds once it was
+    /// prevalidated, see [`RpcAction::BlockInjectPrevalidated`].
+    #[action_event(level = info)]
+    BlockInject {
+        rpc_id: RpcId,
+        block: ArcBlock,
+    },
+    BlockInjectPending {
+        rpc_id: RpcId,
+        block: ArcBlockWithHash,
+    },
+    /// The candidate injected with [`RpcAction::BlockInject`] was
+    /// prevalidated, or rejected with the `error`.
+    BlockInjectPrevalidated {
+        hash: StateHash,
+        error: Option<BlockPrevalidationError>,
+    },
+    ConsensusForkDecisionsGet {
+        rpc_id: RpcId,
+    },
+    PendingCoinbaseGetInit {
+        rpc_id: RpcId,
+        block_hash: Option<StateHash>,
+    },
+    PendingCoinbaseGetPending {
+        rpc_id: RpcId,
+    },
+    PendingCoinbaseGetSuccess {
+        rpc_id: RpcId,
+        response: RpcPendingCoinbaseGetResponse,
+    },
+    #[action_event(level = info)]
+    PeerListRefreshInit {
+        rpc_id: RpcId,
+    },
+    #[action_event(level = info)]
+    PeerListRefreshSuccess {
+        rpc_id: RpcId,
+        response: RpcPeerListRefreshResponse,
+    },
+    #[action_event(level = info)]
+    GossipPause {
+        rpc_id: RpcId,
+        kind: P2pGossipKind,
+        paused: bool,
+    },
+    SyncDiagnosisGet {
+        rpc_id: RpcId,
+    },
+    SyncDiagnosisSuccess {
+        rpc_id: RpcId,
+        diagnosis: RpcSyncDiagnosis,
+    },
+    NodeOverviewGet {
+        rpc_id: RpcId,
+    },
+    LocallyGeneratedCommandsGet {
+        rpc_id: RpcId,
+    },
+    PendingRequestsGet {
+        rpc_id: RpcId,
+    },
+    SnarkStatsGet {
+        rpc_id: RpcId,
+    },
+    SnarkVerifierDigestsGet {
+        rpc_id: RpcId,
+    },
+    SnarkVerifyHistoryGet {
+        rpc_id: RpcId,
+    },
+    #[action_event(level = info)]
+    SnarkVerifyDryRunInit {
+        rpc_id: RpcId,
+        input: SnarkVerifyDryRunInput,
+    },
+    #[action_event(level = info)]
+    SnarkVerifyDryRunSuccess {
+        rpc_id: RpcId,
+        report: RpcSnarkVerifyDryRunResponse,
+    },
+    EnabledFeaturesGet {
+        rpc_id: RpcId,
+    },
 
     PooledUserCommands {
         rpc_id: RpcId,
@@ -259,9 +436,59 @@ pub enum RpcAction {
         rpc_id: RpcId,
         query: PooledZkappsCommandsQuery,
     },
+    PooledUserCommandsPageGet {
+        rpc_id: RpcId,
+        query: PooledUserCommandsQuery,
+        page_query: RpcPageQuery<TransactionHash>,
+    },
+    PooledZkappCommandsPageGet {
+        rpc_id: RpcId,
+        query: PooledZkappsCommandsQuery,
+        page_query: RpcPageQuery<TransactionHash>,
+    },
+    ZkappEventsPageGet {
+        rpc_id: RpcId,
+        query: RpcZkappEventsQuery,
+        page_query: RpcPageQuery,
+    },
+    #[action_event(level = info)]
+    ZkappActionsEventsGetInit {
+        rpc_id: RpcId,
+        query: RpcZkappActionsEventsQuery,
+    },
+    #[action_event(level = info)]
+    ZkappActionsEventsGetPending {
+        rpc_id: RpcId,
+    },
+    #[action_event(level = info)]
+    ZkappActionsEventsGetSuccess {
+        rpc_id: RpcId,
+        response: RpcZkappActionsEventsGetResponse,
+    },
+    #[action_event(level = info)]
+    TokenSupplyGetInit {
+        rpc_id: RpcId,
+        token_id: TokenIdKeyHash,
+    },
+    #[action_event(level = info)]
+    TokenSupplyGetPending {
+        rpc_id: RpcId,
+    },
+    #[action_event(level = info)]
+    TokenSupplyGetSuccess {
+        rpc_id: RpcId,
+        response: RpcTokenSupplyGetResponse,
+    },
     GenesisBlock {
         rpc_id: RpcId,
     },
+    /// The ledger read answering the request was given up, see
+    /// [`crate::ledger::read::LedgerReadTimeout::GiveUp`]. The request is
+    /// dropped without a response.
+    #[action_event(level = warn)]
+    LedgerReadError {
+        rpc_id: RpcId,
+    },
 
     Finish {
         rpc_id: RpcId,
@@ -274,6 +501,13 @@ pub enum AccountQuery {
     SinglePublicKey(AccountPublicKey),
     MultipleIds(Vec<AccountId>),
     PubKeyWithTokenId(AccountPublicKey, TokenIdKeyHash),
+    /// At most `limit` accounts of a token, after the first `offset` of
+    /// them in the order of their index.
+    Token {
+        token_id: TokenIdKeyHash,
+        offset: usize,
+        limit: usize,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for RpcAction {
@@ -284,7 +518,10 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
             RpcAction::HeartbeatGet { .. } => true,
             RpcAction::ActionStatsGet { .. } => true,
             RpcAction::SyncStatsGet { .. } => true,
+            RpcAction::SyncHistoryGet { .. } => true,
             RpcAction::BlockProducerStatsGet { .. } => true,
+            RpcAction::PeerContributionStatsGet { .. } => true,
+            RpcAction::ArchiveUploadStatsGet { .. } => true,
             RpcAction::MessageProgressGet { .. } => true,
             RpcAction::PeersGet { .. } => true,
             RpcAction::P2pConnectionOutgoingInit { rpc_id, .. } => {
@@ -352,17 +589,57 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
             RpcAction::SnarkerConfigGet { .. } => true,
             RpcAction::SnarkerJobCommit { .. } => true,
             RpcAction::SnarkerJobSpec { .. } => true,
+            RpcAction::SnarkerWorkSubmitInit { rpc_id, .. } => {
+                !state.rpc.requests.contains_key(rpc_id)
+            }
+            RpcAction::SnarkerWorkSubmitPending { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::SnarkerWorkSubmitSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_pending()),
             RpcAction::SnarkerWorkersGet { .. } => true,
             RpcAction::HealthCheck { .. } => true,
             RpcAction::ReadinessCheck { .. } => true,
             RpcAction::DiscoveryRoutingTable { .. } => true,
             RpcAction::DiscoveryBoostrapStats { .. } => true,
             RpcAction::TransactionPool { .. } => true,
+            RpcAction::TransactionPoolSenderQueues { .. } => true,
             RpcAction::ConsensusConstantsGet { .. } => true,
             RpcAction::BestChain { .. } => state.transition_frontier.best_tip().is_some(),
+            RpcAction::BestChainPageGet { .. } => true,
             RpcAction::TransactionStatusGet { .. } => true,
             RpcAction::PooledUserCommands { .. } => true,
             RpcAction::PooledZkappCommands { .. } => true,
+            RpcAction::PooledUserCommandsPageGet { .. } => true,
+            RpcAction::PooledZkappCommandsPageGet { .. } => true,
+            RpcAction::ZkappEventsPageGet { .. } => true,
+            RpcAction::ZkappActionsEventsGetInit { .. } => true,
+            RpcAction::ZkappActionsEventsGetPending { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::ZkappActionsEventsGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_pending()),
+            RpcAction::TokenSupplyGetInit { .. } => state.transition_frontier.best_tip().is_some(),
+            RpcAction::TokenSupplyGetPending { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::TokenSupplyGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_pending()),
             RpcAction::GenesisBlock { .. } => true,
             RpcAction::LedgerAccountsGetInit { .. } => {
                 state.transition_frontier.best_tip().is_some()
@@ -377,6 +654,32 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
                 .requests
                 .get(rpc_id)
                 .is_some_and(|v| v.status.is_pending()),
+            RpcAction::LedgerAccountsPageGetInit { rpc_id, .. } => {
+                !state.rpc.requests.contains_key(rpc_id)
+            }
+            RpcAction::LedgerAccountsPageGetPending { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::LedgerAccountsPageGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_pending()),
+            RpcAction::LedgerAccountsAtBlockGetInit { rpc_id, .. } => {
+                !state.rpc.requests.contains_key(rpc_id)
+            }
+            RpcAction::LedgerAccountsAtBlockGetPending { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::LedgerAccountsAtBlockGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_pending()),
 
             RpcAction::TransactionInjectInit { .. } => true,
             RpcAction::TransactionInjectPending { rpc_id } => state
@@ -401,6 +704,7 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
                 .is_some_and(|v| v.status.is_pending()),
             RpcAction::TransitionFrontierUserCommandsGet { .. } => true,
             RpcAction::BlockGet { .. } => true,
+            RpcAction::BlockSummaryGet { .. } => true,
             RpcAction::ConsensusTimeGet { .. } => true,
             RpcAction::LedgerStatusGetInit { .. } => state.transition_frontier.best_tip().is_some(),
             RpcAction::LedgerStatusGetPending { rpc_id } => state
@@ -426,6 +730,65 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
                 .requests
                 .get(rpc_id)
                 .is_some_and(|v| v.status.is_pending()),
+            RpcAction::LedgerSnapshotGetInit { .. } => true,
+            RpcAction::LedgerSnapshotGetPending { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::LedgerSnapshotGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_pending()),
+            RpcAction::BlockInject { .. } => true,
+            RpcAction::BlockInjectPending { rpc_id, .. } => {
+                !state.rpc.requests.contains_key(rpc_id)
+            }
+            RpcAction::BlockInjectPrevalidated { hash, .. } => {
+                state.rpc.block_inject_rpc_ids(hash).next().is_some()
+            }
+            RpcAction::ConsensusForkDecisionsGet { .. } => true,
+            RpcAction::PendingCoinbaseGetInit { .. } => true,
+            RpcAction::PendingCoinbaseGetPending { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::PendingCoinbaseGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_pending()),
+            RpcAction::PeerListRefreshInit { rpc_id } => !state.rpc.requests.contains_key(rpc_id),
+            RpcAction::PeerListRefreshSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::GossipPause { .. } => true,
+            RpcAction::SyncDiagnosisGet { .. } => true,
+            RpcAction::SyncDiagnosisSuccess { .. } => true,
+            RpcAction::NodeOverviewGet { .. } => true,
+            RpcAction::LocallyGeneratedCommandsGet { .. } => true,
+            RpcAction::PendingRequestsGet { .. } => true,
+            RpcAction::SnarkStatsGet { .. } => true,
+            RpcAction::SnarkVerifierDigestsGet { .. } => true,
+            RpcAction::SnarkVerifyHistoryGet { .. } => true,
+            RpcAction::SnarkVerifyDryRunInit { rpc_id, .. } => {
+                !state.rpc.requests.contains_key(rpc_id)
+            }
+            RpcAction::SnarkVerifyDryRunSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::EnabledFeaturesGet { .. } => true,
+            RpcAction::LedgerReadError { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| !v.status.is_finished()),
             RpcAction::Finish { rpc_id } => state
                 .rpc
                 .requests
@@ -1,6 +1,10 @@
+use mina_core::{ErrorCode, HasErrorCode};
+
 use crate::external_snark_worker::{ExternalSnarkWorker, ExternalSnarkWorkerState};
 
-use super::{RpcSnarkWorker, RpcSnarkWorkerStatus};
+use super::{
+    RpcBlockInjectError, RpcPageError, RpcSnarkWorker, RpcSnarkWorkerStatus, RpcStateGetError,
+};
 
 impl From<ExternalSnarkWorker> for RpcSnarkWorker {
     fn from(source: ExternalSnarkWorker) -> Self {
@@ -8,6 +12,7 @@ impl From<ExternalSnarkWorker> for RpcSnarkWorker {
             time: Some(source.timestamp),
             id: Some("single".into()),
             status: source.state.into(),
+            coordinators: source.coordinators,
         }
     }
 }
@@ -40,3 +45,31 @@ impl From<ExternalSnarkWorkerState> for RpcSnarkWorkerStatus {
         }
     }
 }
+
+impl HasErrorCode for RpcStateGetError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::FilterError(_) => ErrorCode::InvalidFilter,
+        }
+    }
+}
+
+impl HasErrorCode for RpcBlockInjectError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidBlock(_) => ErrorCode::InvalidBlock,
+            Self::AlreadyKnown => ErrorCode::BlockAlreadyKnown,
+            Self::Prevalidation(_) => ErrorCode::BlockPrevalidationFailed,
+        }
+    }
+}
+
+impl HasErrorCode for RpcPageError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::NoBestTip => ErrorCode::Unavailable,
+            Self::AnchorNotFound(_) => ErrorCode::CursorExpired,
+            Self::InvalidCursor(_) => ErrorCode::InvalidInput,
+        }
+    }
+}
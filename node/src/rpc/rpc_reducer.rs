@@ -1,33 +1,47 @@
 use ledger::scan_state::transaction_logic::valid;
 use mina_core::{
-    block::AppliedBlock,
+    block::{AppliedBlock, ArcBlockWithHash},
     bug_condition,
     requests::{RequestId, RpcId, RpcIdType},
     transaction::{TransactionPoolMessageSource, TransactionWithHash},
 };
 use mina_p2p_messages::v2::{
     MinaBaseSignedCommandStableV2, MinaBaseZkappCommandTStableV1WireStableV1, NonZeroCurvePoint,
-    TransactionSnarkWorkTStableV2,
+    TransactionHash, TransactionSnarkWorkTStableV2,
 };
 use p2p::{
     connection::{incoming::P2pConnectionIncomingAction, outgoing::P2pConnectionOutgoingAction},
+    gossip::P2pGossipAction,
     webrtc::P2pConnectionResponse,
     PeerId,
 };
 use redux::ActionWithMeta;
 
 use crate::{
-    ledger::read::{LedgerReadAction, LedgerReadInitCallback, LedgerReadRequest},
+    ledger::read::{
+        LedgerReadAction, LedgerReadInitCallback, LedgerReadRequest, LedgerReadSnapshotRequest,
+        LedgerReadStagedLedgerAuxAndPendingCoinbases,
+    },
     p2p_ready,
-    rpc::{GetBlockQuery, PooledCommandsQuery},
+    rpc::{
+        GetBlockQuery, PooledCommandsQuery, PooledUserCommandsQuery, PooledZkappsCommandsQuery,
+        RpcPage,
+    },
     rpc_effectful::RpcEffectfulAction,
-    TransactionPoolAction,
+    snark::verify_dry_run::SnarkVerifyDryRunEffectfulAction,
+    transaction_pool::TransactionPoolState,
+    transition_frontier::{
+        candidate::TransitionFrontierCandidateAction, sync::ledger::LedgerSnapshotQuery,
+        BlockSummary,
+    },
+    SnarkPoolAction, TransactionPoolAction,
 };
 
 use super::{
-    ConsensusTimeQuery, PeerConnectionStatus, RpcAction, RpcPeerInfo, RpcRequest,
-    RpcRequestExtraData, RpcRequestState, RpcRequestStatus, RpcScanStateSummaryGetQuery,
-    RpcSnarkerConfig, RpcState,
+    ConsensusTimeQuery, PeerConnectionStatus, RpcAction, RpcBlockInjectError, RpcBlockPrunedError,
+    RpcLocallyGeneratedCommand, RpcPeerInfo, RpcPendingRequest, RpcRequest, RpcRequestExtraData,
+    RpcRequestState, RpcRequestStatus, RpcScanStateSummaryGetQuery, RpcSnarkerConfig,
+    RpcSnarkerWorkSubmitResponse, RpcState, RpcSyncDiagnosis,
 };
 
 impl RpcState {
@@ -67,10 +81,22 @@ impl RpcState {
                     query: *query,
                 });
             }
+            RpcAction::SyncHistoryGet { rpc_id } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::SyncHistoryGet { rpc_id: *rpc_id });
+            }
             RpcAction::BlockProducerStatsGet { rpc_id } => {
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(RpcEffectfulAction::BlockProducerStatsGet { rpc_id: *rpc_id });
             }
+            RpcAction::PeerContributionStatsGet { rpc_id } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::PeerContributionStatsGet { rpc_id: *rpc_id });
+            }
+            RpcAction::ArchiveUploadStatsGet { rpc_id } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::ArchiveUploadStatsGet { rpc_id: *rpc_id });
+            }
             RpcAction::MessageProgressGet { rpc_id } => {
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(RpcEffectfulAction::MessageProgressGet { rpc_id: *rpc_id });
@@ -392,6 +418,53 @@ impl RpcState {
                     job_id: job_id.clone(),
                 });
             }
+            RpcAction::SnarkerWorkSubmitInit { rpc_id, snark } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::SnarkerWorkSubmit {
+                        snark: snark.clone(),
+                    },
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                dispatcher.push(RpcAction::SnarkerWorkSubmitPending { rpc_id: *rpc_id });
+                // Work the pool doesn't need isn't verified, so that it
+                // costs us nothing.
+                match RpcSnarkerWorkSubmitResponse::rejection(&state.snark_pool, snark) {
+                    Some(response) => {
+                        dispatcher.push(RpcAction::SnarkerWorkSubmitSuccess {
+                            rpc_id: *rpc_id,
+                            response,
+                        });
+                    }
+                    None => {
+                        dispatcher.push(SnarkPoolAction::WorkSubmit {
+                            rpc_id: *rpc_id,
+                            snark: snark.clone(),
+                        });
+                    }
+                }
+            }
+            RpcAction::SnarkerWorkSubmitPending { rpc_id } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+            }
+            RpcAction::SnarkerWorkSubmitSuccess { rpc_id, response } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::SnarkerWorkSubmitSuccess {
+                    rpc_id: *rpc_id,
+                    response: response.clone(),
+                });
+            }
             RpcAction::SnarkerWorkersGet { rpc_id } => {
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let snark_worker = state.external_snark_worker.0.clone();
@@ -463,6 +536,18 @@ impl RpcState {
                     response,
                 });
             }
+            RpcAction::LedgerReadError { rpc_id } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Error {
+                    time: meta.time(),
+                    error: "ledger read timed out".to_owned(),
+                };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::Drop { rpc_id: *rpc_id });
+            }
             RpcAction::Finish { rpc_id } => {
                 state.requests.remove(rpc_id);
             }
@@ -474,6 +559,21 @@ impl RpcState {
                     response,
                 });
             }
+            RpcAction::TransactionPoolSenderQueues { rpc_id, public_key } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                // The pool is empty until there is a best tip.
+                let response = TransactionPoolState::global_slots(state)
+                    .map(|global_slots| {
+                        state
+                            .transaction_pool
+                            .sender_queues(public_key.as_ref(), global_slots)
+                    })
+                    .unwrap_or_default();
+                dispatcher.push(RpcEffectfulAction::TransactionPoolSenderQueues {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
             RpcAction::LedgerAccountsGetInit {
                 rpc_id,
                 account_query,
@@ -531,6 +631,127 @@ impl RpcState {
                     accounts: accounts.clone(),
                 });
             }
+            RpcAction::LedgerAccountsPageGetInit { rpc_id, query } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::LedgerAccountsPageGet(query.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let anchor = match query.anchor(state) {
+                    Ok(anchor) => anchor,
+                    Err(error) => {
+                        dispatcher
+                            .push(RpcAction::LedgerAccountsPageGetPending { rpc_id: *rpc_id });
+                        dispatcher.push(RpcAction::LedgerAccountsPageGetSuccess {
+                            rpc_id: *rpc_id,
+                            response: Err(error),
+                        });
+                        return;
+                    }
+                };
+
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::AccountsPageForRpc(
+                        *rpc_id,
+                        anchor.hash().clone(),
+                        anchor.merkle_root_hash().clone(),
+                        query.clone(),
+                    ),
+                    callback: LedgerReadInitCallback::RpcLedgerAccountsPageGetPending {
+                        callback: redux::callback!(
+                            on_ledger_read_init_rpc_accounts_page_get_init(rpc_id: RequestId<RpcIdType>) -> crate::Action{
+                                RpcAction::LedgerAccountsPageGetPending { rpc_id }
+                            }
+                        ),
+                        args: *rpc_id,
+                    },
+                })
+            }
+            RpcAction::LedgerAccountsPageGetPending { rpc_id } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+            }
+            RpcAction::LedgerAccountsPageGetSuccess { rpc_id, response } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::LedgerAccountsPageGetSuccess {
+                    rpc_id: *rpc_id,
+                    response: response.clone(),
+                });
+            }
+            RpcAction::LedgerAccountsAtBlockGetInit {
+                rpc_id,
+                block_hash,
+                account_query,
+            } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::LedgerAccountsAtBlockGet(
+                        block_hash.clone(),
+                        account_query.clone(),
+                    ),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let Some(block) = state
+                    .transition_frontier
+                    .best_chain_up_to(block_hash)
+                    .and_then(|chain| chain.last())
+                else {
+                    dispatcher.push(RpcAction::LedgerAccountsAtBlockGetPending { rpc_id: *rpc_id });
+                    dispatcher.push(RpcAction::LedgerAccountsAtBlockGetSuccess {
+                        rpc_id: *rpc_id,
+                        response: Err(RpcBlockPrunedError(block_hash.clone())),
+                    });
+                    return;
+                };
+
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::AccountsAtBlockForRpc(
+                        *rpc_id,
+                        block.hash().clone(),
+                        block.merkle_root_hash().clone(),
+                        account_query.clone(),
+                    ),
+                    callback: LedgerReadInitCallback::RpcLedgerAccountsAtBlockGetPending {
+                        callback: redux::callback!(
+                            on_ledger_read_init_rpc_accounts_at_block_get_init(rpc_id: RequestId<RpcIdType>) -> crate::Action{
+                                RpcAction::LedgerAccountsAtBlockGetPending { rpc_id }
+                            }
+                        ),
+                        args: *rpc_id,
+                    },
+                })
+            }
+            RpcAction::LedgerAccountsAtBlockGetPending { rpc_id } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+            }
+            RpcAction::LedgerAccountsAtBlockGetSuccess { rpc_id, response } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::LedgerAccountsAtBlockGetSuccess {
+                    rpc_id: *rpc_id,
+                    response: response.clone(),
+                });
+            }
             RpcAction::TransactionInjectInit { rpc_id, commands } => {
                 let rpc_state = RpcRequestState {
                     req: RpcRequest::TransactionInject(commands.clone()),
@@ -638,6 +859,27 @@ impl RpcState {
                     best_chain,
                 });
             }
+            RpcAction::BestChainPageGet { rpc_id, query } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = query.anchor(state).map(|anchor| {
+                    // Newest first, so that the offsets of the anchor's
+                    // ancestors don't change when the root is moved.
+                    let chain = state
+                        .transition_frontier
+                        .best_chain
+                        .iter()
+                        .rev()
+                        .skip_while(|block| block.hash() != anchor.hash())
+                        .skip(query.offset())
+                        .cloned();
+                    RpcPage::from_offset(anchor.hash(), chain, query)
+                });
+
+                dispatcher.push(RpcEffectfulAction::BestChainPageGet {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
             RpcAction::ConsensusConstantsGet { rpc_id } => {
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let response = state.config.consensus_constants.clone();
@@ -646,11 +888,16 @@ impl RpcState {
                     response,
                 });
             }
-            RpcAction::TransactionStatusGet { rpc_id, tx } => {
+            RpcAction::TransactionStatusGet {
+                rpc_id,
+                tx,
+                block_hash,
+            } => {
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(RpcEffectfulAction::TransactionStatusGet {
                     rpc_id: *rpc_id,
                     tx: tx.clone(),
+                    block_hash: block_hash.clone(),
                 });
             }
             RpcAction::BlockGet { rpc_id, query } => {
@@ -673,6 +920,30 @@ impl RpcState {
                     block,
                 });
             }
+            RpcAction::BlockSummaryGet { rpc_id, query } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let frontier = &state.transition_frontier;
+
+                let summary = match query {
+                    GetBlockQuery::Hash(hash) => frontier
+                        .best_chain
+                        .iter()
+                        .find(|block| block.hash() == hash)
+                        .map(BlockSummary::from)
+                        .or_else(|| frontier.root_history.get_by_hash(hash).cloned()),
+                    GetBlockQuery::Height(height) => frontier
+                        .best_chain
+                        .iter()
+                        .find(|block| block.height() == *height)
+                        .map(BlockSummary::from)
+                        .or_else(|| frontier.root_history.get_by_height(*height).cloned()),
+                };
+
+                dispatcher.push(RpcEffectfulAction::BlockSummaryGet {
+                    rpc_id: *rpc_id,
+                    summary,
+                });
+            }
             RpcAction::P2pConnectionIncomingAnswerReady {
                 rpc_id,
                 answer,
@@ -688,44 +959,32 @@ impl RpcState {
             }
             RpcAction::PooledUserCommands { rpc_id, query } => {
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
-
-                let PooledCommandsQuery {
-                    public_key,
-                    hashes,
-                    ids,
-                } = query;
-
-                let all_transactions = state.transaction_pool.get_all_transactions();
-
-                let mut user_commands: Vec<_> = all_transactions
-                    .into_iter()
-                    .filter_map(|tx| match tx.data {
-                        valid::UserCommand::SignedCommand(signed_command) => Some((
-                            tx.hash,
-                            MinaBaseSignedCommandStableV2::from(*signed_command),
-                        )),
-                        valid::UserCommand::ZkAppCommand(_) => None,
-                    })
-                    .collect();
-
-                if let Some(pk) = public_key {
-                    let pk = NonZeroCurvePoint::from(pk.clone());
-                    user_commands.retain(|(_, tx)| tx.signer == pk)
-                }
-
-                if let Some(hashes) = hashes {
-                    user_commands.retain(|(hash, _)| hashes.contains(hash))
-                }
-
-                if let Some(ids) = ids {
-                    user_commands.retain(|(_, tx)| ids.contains(tx))
-                }
+                let user_commands = pooled_user_commands(state, query);
 
                 dispatcher.push(RpcEffectfulAction::PooledUserCommands {
                     rpc_id: *rpc_id,
                     user_commands: user_commands.into_iter().map(|(_, tx)| tx).collect(),
                 });
             }
+            RpcAction::PooledUserCommandsPageGet {
+                rpc_id,
+                query,
+                page_query,
+            } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = page_query.anchor(state).map(|anchor| {
+                    RpcPage::after_hash(
+                        anchor.hash(),
+                        pooled_user_commands(state, query),
+                        page_query,
+                    )
+                });
+
+                dispatcher.push(RpcEffectfulAction::PooledUserCommandsPageGet {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
             RpcAction::GenesisBlock { rpc_id } => {
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let genesis_block = state.genesis_block();
@@ -736,42 +995,140 @@ impl RpcState {
             }
             RpcAction::PooledZkappCommands { rpc_id, query } => {
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let zkapp_commands = pooled_zkapp_commands(state, query);
 
-                let PooledCommandsQuery {
-                    public_key,
-                    hashes,
-                    ids,
-                } = query;
+                dispatcher.push(RpcEffectfulAction::PooledZkappCommands {
+                    rpc_id: *rpc_id,
+                    zkapp_commands: zkapp_commands.into_iter().map(|(_, tx)| tx).collect(),
+                });
+            }
+            RpcAction::PooledZkappCommandsPageGet {
+                rpc_id,
+                query,
+                page_query,
+            } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = page_query.anchor(state).map(|anchor| {
+                    RpcPage::after_hash(
+                        anchor.hash(),
+                        pooled_zkapp_commands(state, query),
+                        page_query,
+                    )
+                });
 
-                let all_transactions = state.transaction_pool.get_all_transactions();
+                dispatcher.push(RpcEffectfulAction::PooledZkappCommandsPageGet {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::ZkappEventsPageGet {
+                rpc_id,
+                query,
+                page_query,
+            } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = page_query.anchor(state).map(|anchor| {
+                    // Newest blocks first, like the best chain pages.
+                    let events = state
+                        .transition_frontier
+                        .best_chain
+                        .iter()
+                        .rev()
+                        .skip_while(|block| block.hash() != anchor.hash())
+                        .flat_map(|block| query.block_events(block))
+                        .skip(page_query.offset());
+                    RpcPage::from_offset(anchor.hash(), events, page_query)
+                });
 
-                let mut zkapp_commands: Vec<_> = all_transactions
-                    .into_iter()
-                    .filter_map(|tx| match tx.data {
-                        valid::UserCommand::SignedCommand(_) => None,
-                        valid::UserCommand::ZkAppCommand(zkapp) => Some((
-                            tx.hash,
-                            MinaBaseZkappCommandTStableV1WireStableV1::from(&zkapp.zkapp_command),
-                        )),
-                    })
-                    .collect();
+                dispatcher.push(RpcEffectfulAction::ZkappEventsPageGet {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::ZkappActionsEventsGetInit { rpc_id, query } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::ZkappActionsEventsGet(query.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
 
-                if let Some(pk) = public_key {
-                    let pk = NonZeroCurvePoint::from(pk.clone());
-                    zkapp_commands.retain(|(_, tx)| tx.fee_payer.body.public_key == pk);
-                }
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::GetZkappActionsEvents(*rpc_id, query.clone()),
+                    callback: LedgerReadInitCallback::RpcZkappActionsEventsGetPending {
+                        callback: redux::callback!(
+                            on_ledger_read_init_rpc_zkapp_actions_events_get_init(rpc_id: RequestId<RpcIdType>) -> crate::Action {
+                                RpcAction::ZkappActionsEventsGetPending { rpc_id }
+                            }
+                        ),
+                        args: *rpc_id,
+                    },
+                })
+            }
+            RpcAction::ZkappActionsEventsGetPending { rpc_id } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+            }
+            RpcAction::ZkappActionsEventsGetSuccess { rpc_id, response } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
 
-                if let Some(hashes) = hashes {
-                    zkapp_commands.retain(|(hash, _)| hashes.contains(hash));
-                }
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::ZkappActionsEventsGetSuccess {
+                    rpc_id: *rpc_id,
+                    response: response.clone(),
+                });
+            }
+            RpcAction::TokenSupplyGetInit { rpc_id, token_id } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::TokenSupplyGet(token_id.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
 
-                if let Some(ids) = ids {
-                    zkapp_commands.retain(|(_, tx)| ids.contains(tx));
-                }
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let Some(best_tip) = state.transition_frontier.best_tip() else {
+                    return;
+                };
 
-                dispatcher.push(RpcEffectfulAction::PooledZkappCommands {
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::GetTokenSupply(
+                        *rpc_id,
+                        best_tip.merkle_root_hash().clone(),
+                        token_id.clone(),
+                    ),
+                    callback: LedgerReadInitCallback::RpcTokenSupplyGetPending {
+                        callback: redux::callback!(
+                            on_ledger_read_init_rpc_token_supply_get_init(rpc_id: RequestId<RpcIdType>) -> crate::Action {
+                                RpcAction::TokenSupplyGetPending { rpc_id }
+                            }
+                        ),
+                        args: *rpc_id,
+                    },
+                })
+            }
+            RpcAction::TokenSupplyGetPending { rpc_id } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+            }
+            RpcAction::TokenSupplyGetSuccess { rpc_id, response } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::TokenSupplyGetSuccess {
                     rpc_id: *rpc_id,
-                    zkapp_commands: zkapp_commands.into_iter().map(|(_, tx)| tx).collect(),
+                    response: *response,
                 });
             }
             RpcAction::ConsensusTimeGet { rpc_id, query } => {
@@ -881,16 +1238,445 @@ impl RpcState {
                     response: response.clone(),
                 });
             }
+            RpcAction::LedgerSnapshotGetInit { rpc_id, query } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::LedgerSnapshotGet(query.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let request = match query {
+                    LedgerSnapshotQuery::SnarkedLedger(ledger_hash) => {
+                        LedgerReadSnapshotRequest::SnarkedLedger(ledger_hash.clone())
+                    }
+                    LedgerSnapshotQuery::StagedLedgerParts(block_hash) => {
+                        match LedgerReadStagedLedgerAuxAndPendingCoinbases::for_block(
+                            state, block_hash,
+                        ) {
+                            Some(request) => LedgerReadSnapshotRequest::StagedLedgerParts(request),
+                            None => {
+                                dispatcher
+                                    .push(RpcAction::LedgerSnapshotGetPending { rpc_id: *rpc_id });
+                                dispatcher.push(RpcAction::LedgerSnapshotGetSuccess {
+                                    rpc_id: *rpc_id,
+                                    response: None,
+                                });
+                                return;
+                            }
+                        }
+                    }
+                };
+
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::GetLedgerSnapshot(*rpc_id, request),
+                    callback: LedgerReadInitCallback::RpcLedgerSnapshotGetPending {
+                        callback: redux::callback!(
+                            on_ledger_read_init_rpc_ledger_snapshot_get(rpc_id: RequestId<RpcIdType>) -> crate::Action{
+                                RpcAction::LedgerSnapshotGetPending { rpc_id }
+                            }
+                        ),
+                        args: *rpc_id,
+                    },
+                })
+            }
+            RpcAction::LedgerSnapshotGetPending { rpc_id } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+            }
+            RpcAction::LedgerSnapshotGetSuccess { rpc_id, response } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::LedgerSnapshotGetSuccess {
+                    rpc_id: *rpc_id,
+                    response: response.clone(),
+                });
+            }
+            RpcAction::BlockInject { rpc_id, block } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let block = ArcBlockWithHash::try_new(block.clone())
+                    .map_err(|err| RpcBlockInjectError::InvalidBlock(err.to_string()))
+                    .and_then(|block| {
+                        // `BlockReceived` would be filtered out otherwise.
+                        if block.is_genesis()
+                            || state.transition_frontier.candidates.contains(block.hash())
+                        {
+                            return Err(RpcBlockInjectError::AlreadyKnown);
+                        }
+                        Ok(block)
+                    });
+
+                match block {
+                    // Prevalidated the same way as a block received from
+                    // a peer, the response is sent with the result.
+                    Ok(block) => {
+                        dispatcher.push(RpcAction::BlockInjectPending {
+                            rpc_id: *rpc_id,
+                            block: block.clone(),
+                        });
+                        dispatcher.push(TransitionFrontierCandidateAction::BlockReceived {
+                            block,
+                            chain_proof: None,
+                        });
+                    }
+                    Err(error) => {
+                        dispatcher.push(RpcEffectfulAction::BlockInject {
+                            rpc_id: *rpc_id,
+                            response: Err(error),
+                        });
+                    }
+                }
+            }
+            RpcAction::BlockInjectPending { rpc_id, block } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::BlockInject(block.block.clone()),
+                    status: RpcRequestStatus::Pending { time: meta.time() },
+                    data: RpcRequestExtraData::BlockHash(block.hash().clone()),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+            }
+            RpcAction::BlockInjectPrevalidated { hash, error } => {
+                let rpc_ids = state.block_inject_rpc_ids(hash).collect::<Vec<_>>();
+                let response = match error {
+                    None => Ok(hash.clone()),
+                    Some(error) => Err(RpcBlockInjectError::Prevalidation(error.clone())),
+                };
+                for rpc_id in &rpc_ids {
+                    let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                        continue;
+                    };
+                    rpc.status = match &response {
+                        Ok(_) => RpcRequestStatus::Success { time: meta.time() },
+                        Err(error) => RpcRequestStatus::Error {
+                            time: meta.time(),
+                            error: error.to_string(),
+                        },
+                    };
+                }
+
+                let dispatcher = state_context.into_dispatcher();
+                for rpc_id in rpc_ids {
+                    dispatcher.push(RpcEffectfulAction::BlockInject {
+                        rpc_id,
+                        response: response.clone(),
+                    });
+                }
+            }
+            RpcAction::ConsensusForkDecisionsGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = state
+                    .transition_frontier
+                    .candidates
+                    .fork_decisions()
+                    .rev()
+                    .cloned()
+                    .collect();
+                dispatcher.push(RpcEffectfulAction::ConsensusForkDecisionsGet {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::PendingCoinbaseGetInit { rpc_id, block_hash } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::PendingCoinbaseGet(block_hash.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let transition_frontier = &state.transition_frontier;
+                let block = match block_hash {
+                    None => transition_frontier.best_tip_breadcrumb(),
+                    Some(hash) => transition_frontier
+                        .best_chain
+                        .iter()
+                        .rev()
+                        .find(|b| b.hash() == hash),
+                };
+                let Some(block) = block else {
+                    dispatcher.push(RpcAction::PendingCoinbaseGetPending { rpc_id: *rpc_id });
+                    dispatcher.push(RpcAction::PendingCoinbaseGetSuccess {
+                        rpc_id: *rpc_id,
+                        response: None,
+                    });
+                    return;
+                };
+
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::GetPendingCoinbase(*rpc_id, block.staged_ledger_hashes().clone()),
+                    callback: LedgerReadInitCallback::RpcPendingCoinbaseGetPending {
+                        callback: redux::callback!(
+                            on_ledger_read_init_rpc_pending_coinbase_get_pending(rpc_id: RequestId<RpcIdType>) -> crate::Action{
+                                RpcAction::PendingCoinbaseGetPending { rpc_id }
+                            }
+                        ),
+                        args: *rpc_id,
+                    },
+                })
+            }
+            RpcAction::PendingCoinbaseGetPending { rpc_id } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+            }
+            RpcAction::PendingCoinbaseGetSuccess { rpc_id, response } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::PendingCoinbaseGetSuccess {
+                    rpc_id: *rpc_id,
+                    response: response.clone(),
+                });
+            }
+            RpcAction::PeerListRefreshInit { rpc_id } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::PeerListRefresh,
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::PeerListRefreshInit { rpc_id: *rpc_id });
+            }
+            RpcAction::PeerListRefreshSuccess { rpc_id, response } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::PeerListRefreshSuccess {
+                    rpc_id: *rpc_id,
+                    response: response.clone(),
+                });
+            }
+            RpcAction::GossipPause {
+                rpc_id,
+                kind,
+                paused,
+            } => {
+                let dispatcher = state_context.into_dispatcher();
+                if *paused {
+                    dispatcher.push(P2pGossipAction::Pause { kind: *kind });
+                } else {
+                    dispatcher.push(P2pGossipAction::Resume { kind: *kind });
+                }
+                dispatcher.push(RpcEffectfulAction::GossipPause { rpc_id: *rpc_id });
+            }
+            RpcAction::SyncDiagnosisGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                match &state.rpc.last_sync_diagnosis {
+                    Some(diagnosis) if diagnosis.is_fresh(meta.time()) => {
+                        dispatcher.push(RpcEffectfulAction::SyncDiagnosisGet {
+                            rpc_id: *rpc_id,
+                            diagnosis: diagnosis.clone(),
+                        });
+                    }
+                    _ => {
+                        let diagnosis = RpcSyncDiagnosis::new(state, meta.time());
+                        dispatcher.push(RpcAction::SyncDiagnosisSuccess {
+                            rpc_id: *rpc_id,
+                            diagnosis,
+                        });
+                    }
+                }
+            }
+            RpcAction::SyncDiagnosisSuccess { rpc_id, diagnosis } => {
+                state.last_sync_diagnosis = Some(diagnosis.clone());
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::SyncDiagnosisGet {
+                    rpc_id: *rpc_id,
+                    diagnosis: diagnosis.clone(),
+                });
+            }
+            RpcAction::NodeOverviewGet { rpc_id } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::NodeOverviewGet { rpc_id: *rpc_id });
+            }
+            RpcAction::LocallyGeneratedCommandsGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = state
+                    .transaction_pool
+                    .locally_generated()
+                    .map(|(cmd, added_at, committed)| RpcLocallyGeneratedCommand {
+                        hash: cmd.hash.clone(),
+                        command: cmd.data.clone().into(),
+                        added_at,
+                        committed,
+                    })
+                    .collect();
+                dispatcher.push(RpcEffectfulAction::LocallyGeneratedCommandsGet {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::PendingRequestsGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = RpcPendingRequest::collect(state, meta.time());
+                dispatcher.push(RpcEffectfulAction::PendingRequestsGet {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::SnarkStatsGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                dispatcher.push(RpcEffectfulAction::SnarkStatsGet {
+                    rpc_id: *rpc_id,
+                    response: state.snark.stats(),
+                });
+            }
+            RpcAction::SnarkVerifierDigestsGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                dispatcher.push(RpcEffectfulAction::SnarkVerifierDigestsGet {
+                    rpc_id: *rpc_id,
+                    response: state.snark.verifier_digests.clone(),
+                });
+            }
+            RpcAction::SnarkVerifyHistoryGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                dispatcher.push(RpcEffectfulAction::SnarkVerifyHistoryGet {
+                    rpc_id: *rpc_id,
+                    response: state.snark.history(),
+                });
+            }
+            RpcAction::SnarkVerifyDryRunInit { rpc_id, input } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::SnarkVerifyDryRun(input.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                dispatcher.push(SnarkVerifyDryRunEffectfulAction::Init {
+                    rpc_id: *rpc_id,
+                    input: input.clone(),
+                    block_verifier_index: state.snark.block_verify.verifier_index.clone(),
+                    work_verifier_index: state.snark.work_verify.verifier_index.clone(),
+                    verifier_srs: state.snark.work_verify.verifier_srs.clone(),
+                });
+            }
+            RpcAction::SnarkVerifyDryRunSuccess { rpc_id, report } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::SnarkVerifyDryRunSuccess {
+                    rpc_id: *rpc_id,
+                    report: report.clone(),
+                });
+            }
+            RpcAction::EnabledFeaturesGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                dispatcher.push(RpcEffectfulAction::EnabledFeaturesGet {
+                    rpc_id: *rpc_id,
+                    response: state.features.clone(),
+                });
+            }
         }
     }
 }
 
+fn pooled_user_commands(
+    state: &crate::State,
+    query: &PooledUserCommandsQuery,
+) -> Vec<(TransactionHash, MinaBaseSignedCommandStableV2)> {
+    let PooledCommandsQuery {
+        public_key,
+        hashes,
+        ids,
+    } = query;
+
+    let all_transactions = state.transaction_pool.get_all_transactions();
+
+    let mut user_commands: Vec<_> = all_transactions
+        .into_iter()
+        .filter_map(|tx| match tx.data {
+            valid::UserCommand::SignedCommand(signed_command) => Some((
+                tx.hash,
+                MinaBaseSignedCommandStableV2::from(*signed_command),
+            )),
+            valid::UserCommand::ZkAppCommand(_) => None,
+        })
+        .collect();
+
+    if let Some(pk) = public_key {
+        let pk = NonZeroCurvePoint::from(pk.clone());
+        user_commands.retain(|(_, tx)| tx.signer == pk)
+    }
+
+    if let Some(hashes) = hashes {
+        user_commands.retain(|(hash, _)| hashes.contains(hash))
+    }
+
+    if let Some(ids) = ids {
+        user_commands.retain(|(_, tx)| ids.contains(tx))
+    }
+
+    user_commands
+}
+
+fn pooled_zkapp_commands(
+    state: &crate::State,
+    query: &PooledZkappsCommandsQuery,
+) -> Vec<(TransactionHash, MinaBaseZkappCommandTStableV1WireStableV1)> {
+    let PooledCommandsQuery {
+        public_key,
+        hashes,
+        ids,
+    } = query;
+
+    let all_transactions = state.transaction_pool.get_all_transactions();
+
+    let mut zkapp_commands: Vec<_> = all_transactions
+        .into_iter()
+        .filter_map(|tx| match tx.data {
+            valid::UserCommand::SignedCommand(_) => None,
+            valid::UserCommand::ZkAppCommand(zkapp) => Some((
+                tx.hash,
+                MinaBaseZkappCommandTStableV1WireStableV1::from(&zkapp.zkapp_command),
+            )),
+        })
+        .collect();
+
+    if let Some(pk) = public_key {
+        let pk = NonZeroCurvePoint::from(pk.clone());
+        zkapp_commands.retain(|(_, tx)| tx.fee_payer.body.public_key == pk);
+    }
+
+    if let Some(hashes) = hashes {
+        zkapp_commands.retain(|(hash, _)| hashes.contains(hash));
+    }
+
+    if let Some(ids) = ids {
+        zkapp_commands.retain(|(_, tx)| ids.contains(tx));
+    }
+
+    zkapp_commands
+}
+
 pub fn collect_rpc_peers_info(state: &crate::State) -> Vec<RpcPeerInfo> {
     state.p2p.ready().map_or_else(Vec::new, |p2p| {
         p2p.peers
             .iter()
             .map(|(peer_id, state)| {
-                let best_tip = state.status.as_ready().and_then(|r| r.best_tip.as_ref());
+                let ready = state.status.as_ready();
+                let best_tip = ready.and_then(|r| r.best_tip.as_ref());
                 let (connection_status, time, incoming, connecting_details) = match &state.status {
                     p2p::P2pPeerStatus::Connecting(c) => match c {
                         p2p::connection::P2pConnectionState::Outgoing(o) => (
@@ -937,6 +1723,7 @@ pub fn collect_rpc_peers_info(state: &crate::State) -> Vec<RpcPeerInfo> {
                     best_tip_global_slot: best_tip.map(|bt| bt.global_slot_since_genesis()),
                     best_tip_timestamp: best_tip.map(|bt| bt.timestamp().into()),
                     time,
+                    connection_stats: ready.and_then(|r| r.connection_stats.clone()),
                 }
             })
             .collect()
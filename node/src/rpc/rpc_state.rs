@@ -4,7 +4,7 @@ use mina_core::block::AppliedBlock;
 use mina_p2p_messages::v2;
 use serde::{Deserialize, Serialize};
 
-use super::{AccountQuery, RpcId, RpcRequest};
+use super::{AccountQuery, RpcId, RpcRequest, RpcSyncDiagnosis};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcRequestState {
@@ -35,6 +35,8 @@ pub enum RpcRequestStatus {
 pub enum RpcRequestExtraData {
     None,
     FullBlockOpt(Option<AppliedBlock>),
+    /// Hash of the block of a [`RpcRequest::BlockInject`].
+    BlockHash(v2::StateHash),
 }
 
 impl RpcRequestStatus {
@@ -54,6 +56,10 @@ impl RpcRequestStatus {
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct RpcState {
     pub requests: BTreeMap<RpcId, RpcRequestState>,
+    /// Last computed sync diagnosis, reused to answer requests arriving
+    /// within [`super::SYNC_DIAGNOSIS_MIN_INTERVAL`].
+    #[serde(default)]
+    pub last_sync_diagnosis: Option<RpcSyncDiagnosis>,
 }
 
 impl RpcState {
@@ -82,6 +88,20 @@ impl RpcState {
             })
     }
 
+    /// Injections of the block `hash` waiting for its prevalidation.
+    pub fn block_inject_rpc_ids<'a>(
+        &'a self,
+        hash: &'a v2::StateHash,
+    ) -> impl Iterator<Item = RpcId> + 'a {
+        self.requests
+            .iter()
+            .filter(move |(_, req)| {
+                req.status.is_pending()
+                    && matches!(&req.data, RpcRequestExtraData::BlockHash(h) if h == hash)
+            })
+            .map(|(id, _)| *id)
+    }
+
     pub fn accounts_request_rpc_ids(
         &self,
     ) -> impl Iterator<Item = (RpcId, AccountQuery, &RpcRequestStatus)> + '_ {
@@ -0,0 +1,630 @@
+//! Explains why the node isn't synced.
+//!
+//! The diagnosis is computed from the transition frontier sync state, the
+//! ready peers and the snark verifier queues. Each detected problem is a
+//! [`RpcSyncIssue`], which serializes to a tagged object and displays as
+//! a sentence meant for node operators.
+
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use mina_core::block::ArcBlockWithHash;
+use mina_p2p_messages::v2::StateHash;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    p2p::PeerId,
+    transition_frontier::sync::{
+        ledger::{
+            snarked::{
+                PeerLedgerQueryError, PeerRpcState as LedgerPeerRpcState,
+                TransitionFrontierSyncLedgerSnarkedState as Snarked,
+            },
+            staged::{
+                PeerStagedLedgerPartsFetchError, PeerStagedLedgerPartsFetchState,
+                TransitionFrontierSyncLedgerStagedState as Staged,
+            },
+        },
+        PeerBlockFetchError, PeerRpcState as BlockPeerRpcState, TransitionFrontierSyncBlockState,
+        TransitionFrontierSyncState,
+    },
+    State,
+};
+
+/// Requests received within this interval of the last diagnosis are
+/// answered with it instead of inspecting the state again.
+pub const SYNC_DIAGNOSIS_MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of pending verification jobs of a single kind above which the
+/// verifier is reported as a bottleneck.
+const VERIFICATION_BACKLOG_THRESHOLD: usize = 16;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcSyncDiagnosis {
+    pub time: Timestamp,
+    /// Current state of the sync state machine, e.g. `RootLedgerPending`.
+    pub sync_state: String,
+    pub synced: bool,
+    pub best_tip: Option<RpcSyncDiagnosisBlock>,
+    /// Best tip the node is syncing to, if a sync is in progress.
+    pub sync_target: Option<RpcSyncDiagnosisBlock>,
+    pub ready_peers: usize,
+    pub issues: Vec<RpcSyncIssue>,
+    /// Human readable explanation, one issue per line.
+    pub summary: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcSyncDiagnosisBlock {
+    pub height: u32,
+    pub global_slot: u32,
+    pub hash: StateHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RpcSyncIssue {
+    /// There are no peers with an established connection.
+    NoPeers,
+    /// None of the ready peers knows a better best tip than ours.
+    PeersBehind {
+        peers: usize,
+        best_peer_height: Option<u32>,
+        our_height: Option<u32>,
+    },
+    /// Queries to a peer that failed while syncing.
+    PeerQueryErrors {
+        peer_id: PeerId,
+        query: RpcSyncQuery,
+        error: RpcSyncPeerError,
+        count: usize,
+    },
+    BlockApplyError {
+        height: u32,
+        hash: StateHash,
+        error: String,
+    },
+    StagedLedgerReconstructError {
+        error: String,
+    },
+    /// Too many proofs are waiting to be verified.
+    VerificationBacklog {
+        blocks: usize,
+        work: usize,
+        user_commands: usize,
+    },
+}
+
+/// Number of proofs waiting to be verified, of each kind.
+struct VerifyJobs {
+    blocks: usize,
+    work: usize,
+    user_commands: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcSyncQuery {
+    LedgerNumAccounts,
+    LedgerAddress,
+    StagedLedgerParts,
+    Block,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcSyncPeerError {
+    Timeout,
+    Disconnected,
+    DataUnavailable,
+}
+
+impl RpcSyncDiagnosis {
+    pub fn new(state: &State, time: Timestamp) -> Self {
+        let peer_heights = state
+            .p2p
+            .ready_peers_iter()
+            .map(|(_, peer)| peer.best_tip.as_ref().map(|block| block.height()))
+            .collect::<Vec<_>>();
+        let verify_jobs = VerifyJobs {
+            blocks: state.snark.block_verify.jobs.len(),
+            work: state.snark.work_verify.jobs.len(),
+            user_commands: state.snark.user_command_verify.jobs.len(),
+        };
+        Self::diagnose(
+            time,
+            &state.transition_frontier.sync,
+            state.transition_frontier.best_tip(),
+            &peer_heights,
+            verify_jobs,
+        )
+    }
+
+    /// `peer_heights` are the heights of the best tips of the ready peers,
+    /// `None` for the ones which didn't send theirs yet.
+    fn diagnose(
+        time: Timestamp,
+        sync: &TransitionFrontierSyncState,
+        best_tip: Option<&ArcBlockWithHash>,
+        peer_heights: &[Option<u32>],
+        verify_jobs: VerifyJobs,
+    ) -> Self {
+        let best_tip = best_tip.map(|block| RpcSyncDiagnosisBlock {
+            height: block.height(),
+            global_slot: block.global_slot(),
+            hash: block.hash().clone(),
+        });
+        let sync_target = sync.best_tip().map(|block| RpcSyncDiagnosisBlock {
+            height: block.height(),
+            global_slot: block.global_slot(),
+            hash: block.hash().clone(),
+        });
+        let ready_peers = peer_heights.len();
+
+        let mut issues = Vec::new();
+
+        if ready_peers == 0 {
+            issues.push(RpcSyncIssue::NoPeers);
+        } else if !sync.is_synced() && sync_target.is_none() {
+            let our_height = best_tip.as_ref().map(|block| block.height);
+            let best_peer_height = peer_heights.iter().flatten().copied().max();
+            if best_peer_height <= our_height {
+                issues.push(RpcSyncIssue::PeersBehind {
+                    peers: ready_peers,
+                    best_peer_height,
+                    our_height,
+                });
+            }
+        }
+
+        let mut query_errors = BTreeMap::new();
+        let mut add_query_error =
+            |peer_id: &PeerId, query: RpcSyncQuery, error: RpcSyncPeerError| {
+                *query_errors.entry((*peer_id, query, error)).or_insert(0) += 1;
+            };
+
+        if let Some(ledger) = sync.ledger() {
+            if let Some(snarked) = ledger.snarked() {
+                let attempts = match snarked {
+                    Snarked::NumAccountsPending {
+                        pending_num_accounts,
+                        ..
+                    } => vec![(
+                        RpcSyncQuery::LedgerNumAccounts,
+                        &pending_num_accounts.attempts,
+                    )],
+                    Snarked::MerkleTreeSyncPending {
+                        pending_addresses, ..
+                    } => pending_addresses
+                        .values()
+                        .map(|pending| (RpcSyncQuery::LedgerAddress, &pending.attempts))
+                        .collect(),
+                    _ => vec![],
+                };
+                for (query, attempts) in attempts {
+                    for (peer_id, attempt) in attempts {
+                        if let LedgerPeerRpcState::Error { error, .. } = attempt {
+                            add_query_error(peer_id, query, error.into());
+                        }
+                    }
+                }
+            }
+
+            if let Some(staged) = ledger.staged() {
+                match staged {
+                    Staged::PartsFetchPending { attempts, .. } => {
+                        for (peer_id, attempt) in attempts {
+                            if let PeerStagedLedgerPartsFetchState::Error { error, .. } = attempt {
+                                add_query_error(
+                                    peer_id,
+                                    RpcSyncQuery::StagedLedgerParts,
+                                    error.into(),
+                                );
+                            }
+                        }
+                    }
+                    Staged::ReconstructError { error, .. } => {
+                        issues.push(RpcSyncIssue::StagedLedgerReconstructError {
+                            error: error.clone(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for block_state in sync.blocks_iter() {
+            match block_state {
+                TransitionFrontierSyncBlockState::FetchPending { attempts, .. } => {
+                    for (peer_id, attempt) in attempts {
+                        if let BlockPeerRpcState::Error { error, .. } = attempt {
+                            add_query_error(peer_id, RpcSyncQuery::Block, error.into());
+                        }
+                    }
+                }
+                TransitionFrontierSyncBlockState::ApplyError { block, error, .. } => {
+                    issues.push(RpcSyncIssue::BlockApplyError {
+                        height: block.height(),
+                        hash: block.hash().clone(),
+                        error: error.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        issues.extend(
+            query_errors
+                .into_iter()
+                .map(
+                    |((peer_id, query, error), count)| RpcSyncIssue::PeerQueryErrors {
+                        peer_id,
+                        query,
+                        error,
+                        count,
+                    },
+                ),
+        );
+
+        let VerifyJobs {
+            blocks,
+            work,
+            user_commands,
+        } = verify_jobs;
+        if [blocks, work, user_commands]
+            .into_iter()
+            .any(|len| len > VERIFICATION_BACKLOG_THRESHOLD)
+        {
+            issues.push(RpcSyncIssue::VerificationBacklog {
+                blocks,
+                work,
+                user_commands,
+            });
+        }
+
+        let synced = sync.is_synced();
+        let summary = if issues.is_empty() {
+            if synced {
+                "synced".to_owned()
+            } else {
+                format!("syncing ({sync}), no issues detected")
+            }
+        } else {
+            issues
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        Self {
+            time,
+            sync_state: sync.to_string(),
+            synced,
+            best_tip,
+            sync_target,
+            ready_peers,
+            issues,
+            summary,
+        }
+    }
+
+    /// Whether this diagnosis can still be used to answer a request
+    /// received at `now`.
+    pub fn is_fresh(&self, now: Timestamp) -> bool {
+        now.checked_sub(self.time)
+            .is_some_and(|elapsed| elapsed < SYNC_DIAGNOSIS_MIN_INTERVAL)
+    }
+}
+
+impl fmt::Display for RpcSyncIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoPeers => write!(f, "no peers: not connected to any peer"),
+            Self::PeersBehind {
+                peers,
+                best_peer_height: Some(best_peer_height),
+                our_height: Some(our_height),
+            } => write!(
+                f,
+                "peers behind: best tip of {peers} peers is at height {best_peer_height}, ours is at {our_height}"
+            ),
+            Self::PeersBehind { peers, .. } => write!(
+                f,
+                "peers behind: none of {peers} peers has sent a better best tip"
+            ),
+            Self::PeerQueryErrors {
+                peer_id,
+                query,
+                error,
+                count,
+            } => write!(f, "{query} queries to peer {peer_id} failed: {error} ({count}x)"),
+            Self::BlockApplyError {
+                height,
+                hash,
+                error,
+            } => write!(f, "failed to apply block {height} ({hash}): {error}"),
+            Self::StagedLedgerReconstructError { error } => {
+                write!(f, "failed to reconstruct staged ledger: {error}")
+            }
+            Self::VerificationBacklog {
+                blocks,
+                work,
+                user_commands,
+            } => write!(
+                f,
+                "verification backlog: {blocks} blocks, {work} snark works and {user_commands} user commands pending verification"
+            ),
+        }
+    }
+}
+
+impl fmt::Display for RpcSyncQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LedgerNumAccounts => write!(f, "ledger num accounts"),
+            Self::LedgerAddress => write!(f, "ledger address"),
+            Self::StagedLedgerParts => write!(f, "staged ledger parts"),
+            Self::Block => write!(f, "block"),
+        }
+    }
+}
+
+impl fmt::Display for RpcSyncPeerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout => write!(f, "timeout"),
+            Self::Disconnected => write!(f, "disconnected"),
+            Self::DataUnavailable => write!(f, "data unavailable"),
+        }
+    }
+}
+
+impl From<&PeerLedgerQueryError> for RpcSyncPeerError {
+    fn from(value: &PeerLedgerQueryError) -> Self {
+        match value {
+            PeerLedgerQueryError::Timeout => Self::Timeout,
+            PeerLedgerQueryError::Disconnected => Self::Disconnected,
+            PeerLedgerQueryError::DataUnavailable => Self::DataUnavailable,
+        }
+    }
+}
+
+impl From<&PeerStagedLedgerPartsFetchError> for RpcSyncPeerError {
+    fn from(value: &PeerStagedLedgerPartsFetchError) -> Self {
+        match value {
+            PeerStagedLedgerPartsFetchError::Timeout => Self::Timeout,
+            PeerStagedLedgerPartsFetchError::Disconnected => Self::Disconnected,
+            PeerStagedLedgerPartsFetchError::DataUnavailable => Self::DataUnavailable,
+        }
+    }
+}
+
+impl From<&PeerBlockFetchError> for RpcSyncPeerError {
+    fn from(value: &PeerBlockFetchError) -> Self {
+        match value {
+            PeerBlockFetchError::Timeout => Self::Timeout,
+            PeerBlockFetchError::Disconnected => Self::Disconnected,
+            PeerBlockFetchError::DataUnavailable => Self::DataUnavailable,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_p2p_messages::{binprot::BinProtRead, v2};
+
+    use super::*;
+
+    fn time(secs: u64) -> Timestamp {
+        Timestamp::ZERO + Duration::from_secs(secs)
+    }
+
+    fn peer_id(n: u8) -> PeerId {
+        PeerId::from_bytes([n; 32])
+    }
+
+    fn block() -> ArcBlockWithHash {
+        let bytes = include_bytes!(
+            "../../../tests/files/archive-breadcrumb/3NK56ZbCS31qb8SvCtCCYza4beRDtKgXA2JL6s3evKouG2KkKtiy.bin"
+        );
+        let diff = v2::ArchiveTransitionFrontierDiff::binprot_read(&mut bytes.as_slice()).unwrap();
+        ArcBlockWithHash::try_new(diff.block().unwrap().into()).unwrap()
+    }
+
+    fn no_verify_jobs() -> VerifyJobs {
+        VerifyJobs {
+            blocks: 0,
+            work: 0,
+            user_commands: 0,
+        }
+    }
+
+    fn block_fetch_errors(
+        n: u64,
+        errors: impl IntoIterator<Item = (PeerId, PeerBlockFetchError)>,
+    ) -> TransitionFrontierSyncBlockState {
+        TransitionFrontierSyncBlockState::FetchPending {
+            time: time(0),
+            block_hash: StateHash::from_fp(mina_curves::pasta::Fp::from(n)),
+            attempts: errors
+                .into_iter()
+                .map(|(peer_id, error)| {
+                    let attempt = BlockPeerRpcState::Error {
+                        time: time(1),
+                        rpc_id: n,
+                        error,
+                    };
+                    (peer_id, attempt)
+                })
+                .collect(),
+            bucket: None,
+        }
+    }
+
+    #[test]
+    fn test_no_peers() {
+        let sync = TransitionFrontierSyncState::Idle;
+        let diagnosis = RpcSyncDiagnosis::diagnose(time(0), &sync, None, &[], no_verify_jobs());
+        assert!(!diagnosis.synced);
+        assert_eq!(diagnosis.ready_peers, 0);
+        assert!(matches!(diagnosis.issues[..], [RpcSyncIssue::NoPeers]));
+        assert_eq!(diagnosis.summary, "no peers: not connected to any peer");
+        assert_eq!(
+            serde_json::to_value(&diagnosis.issues).unwrap(),
+            serde_json::json!([{ "kind": "no_peers" }])
+        );
+    }
+
+    #[test]
+    fn test_peers_behind() {
+        let best_tip = block();
+        let height = best_tip.height();
+        let sync = TransitionFrontierSyncState::Idle;
+        let diagnose = |peer_heights: &[Option<u32>]| {
+            RpcSyncDiagnosis::diagnose(
+                time(0),
+                &sync,
+                Some(&best_tip),
+                peer_heights,
+                no_verify_jobs(),
+            )
+        };
+
+        let diagnosis = diagnose(&[Some(height - 1), None]);
+        assert!(matches!(
+            diagnosis.issues[..],
+            [RpcSyncIssue::PeersBehind {
+                peers: 2,
+                best_peer_height: Some(best_peer_height),
+                our_height: Some(our_height),
+            }] if best_peer_height == height - 1 && our_height == height
+        ));
+        assert_eq!(
+            diagnosis.summary,
+            format!(
+                "peers behind: best tip of 2 peers is at height {}, ours is at {height}",
+                height - 1
+            )
+        );
+
+        let diagnosis = diagnose(&[None]);
+        assert_eq!(
+            diagnosis.summary,
+            "peers behind: none of 1 peers has sent a better best tip"
+        );
+
+        let diagnosis = diagnose(&[Some(height + 1), None]);
+        assert!(diagnosis.issues.is_empty());
+        assert_eq!(diagnosis.summary, "syncing (Idle), no issues detected");
+
+        let synced = TransitionFrontierSyncState::Synced { time: time(0) };
+        let diagnosis = RpcSyncDiagnosis::diagnose(
+            time(0),
+            &synced,
+            Some(&best_tip),
+            &[Some(height)],
+            no_verify_jobs(),
+        );
+        assert!(diagnosis.synced);
+        assert!(diagnosis.issues.is_empty());
+        assert_eq!(diagnosis.summary, "synced");
+    }
+
+    #[test]
+    fn test_block_errors() {
+        let best_tip = block();
+        let sync = TransitionFrontierSyncState::BlocksPending {
+            time: time(0),
+            chain: vec![
+                block_fetch_errors(
+                    1,
+                    [
+                        (peer_id(1), PeerBlockFetchError::Timeout),
+                        (peer_id(2), PeerBlockFetchError::DataUnavailable),
+                    ],
+                ),
+                block_fetch_errors(2, [(peer_id(1), PeerBlockFetchError::Timeout)]),
+                TransitionFrontierSyncBlockState::ApplyError {
+                    time: time(2),
+                    block: best_tip.clone(),
+                    error: "invalid staged ledger hash".to_owned(),
+                },
+            ],
+            root_snarked_ledger_updates: Default::default(),
+            needed_protocol_states: Default::default(),
+        };
+        let diagnosis = RpcSyncDiagnosis::diagnose(
+            time(3),
+            &sync,
+            None,
+            &[Some(best_tip.height())],
+            no_verify_jobs(),
+        );
+        assert_eq!(diagnosis.sync_state, "BlocksPending");
+        assert_eq!(
+            diagnosis.sync_target.as_ref().map(|block| &block.hash),
+            Some(best_tip.hash())
+        );
+
+        let lines = diagnosis.summary.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                format!(
+                    "failed to apply block {} ({}): invalid staged ledger hash",
+                    best_tip.height(),
+                    best_tip.hash()
+                ),
+                format!("block queries to peer {} failed: timeout (2x)", peer_id(1)),
+                format!(
+                    "block queries to peer {} failed: data unavailable (1x)",
+                    peer_id(2)
+                ),
+            ]
+        );
+        let issues = serde_json::to_value(&diagnosis.issues).unwrap();
+        assert_eq!(issues[1]["kind"], "peer_query_errors");
+        assert_eq!(issues[1]["query"], "block");
+        assert_eq!(issues[1]["error"], "timeout");
+        assert_eq!(issues[1]["count"], 2);
+    }
+
+    #[test]
+    fn test_verification_backlog() {
+        let sync = TransitionFrontierSyncState::Synced { time: time(0) };
+        let diagnose =
+            |verify_jobs| RpcSyncDiagnosis::diagnose(time(0), &sync, None, &[None], verify_jobs);
+
+        let diagnosis = diagnose(VerifyJobs {
+            blocks: VERIFICATION_BACKLOG_THRESHOLD,
+            work: VERIFICATION_BACKLOG_THRESHOLD,
+            user_commands: 0,
+        });
+        assert!(diagnosis.issues.is_empty());
+
+        let diagnosis = diagnose(VerifyJobs {
+            blocks: 1,
+            work: VERIFICATION_BACKLOG_THRESHOLD + 1,
+            user_commands: 2,
+        });
+        assert!(matches!(
+            diagnosis.issues[..],
+            [RpcSyncIssue::VerificationBacklog {
+                blocks: 1,
+                work: 17,
+                user_commands: 2,
+            }]
+        ));
+    }
+
+    #[test]
+    fn test_diagnosis_is_reused_for_a_second() {
+        let sync = TransitionFrontierSyncState::Idle;
+        let diagnosis = RpcSyncDiagnosis::diagnose(time(10), &sync, None, &[], no_verify_jobs());
+        assert!(diagnosis.is_fresh(time(10)));
+        assert!(diagnosis.is_fresh(time(10) + Duration::from_millis(999)));
+        assert!(!diagnosis.is_fresh(time(11)));
+        assert!(!diagnosis.is_fresh(time(9)), "clock went back");
+    }
+}
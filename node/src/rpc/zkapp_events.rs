@@ -0,0 +1,203 @@
+//! Events and actions emitted by zkApps in the best chain, read one page
+//! at a time with the cursors of [`super::pagination`], or beyond the
+//! frontier from the store kept by the ledger service.
+
+use ledger::AccountId;
+use mina_core::block::AppliedBlock;
+use mina_node_account::AccountPublicKey;
+use mina_p2p_messages::v2::{
+    MinaBaseAccountUpdateBodyEventsStableV1, MinaBaseAccountUpdateTStableV1,
+    MinaBaseTransactionStatusStableV2, MinaBaseUserCommandStableV2,
+    MinaBaseZkappCommandTStableV1WireStableV1,
+    MinaBaseZkappCommandTStableV1WireStableV1AccountUpdatesAA, NonZeroCurvePoint, StateHash,
+    TransactionHash,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcZkappEventsKind {
+    Events,
+    Actions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RpcZkappEventsQuery {
+    /// Account whose account updates emitted the events.
+    pub public_key: AccountPublicKey,
+    pub kind: RpcZkappEventsKind,
+}
+
+/// Events, or actions, emitted by one account update of a zkApp command
+/// applied in the best chain.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcZkappEvents {
+    pub block_height: u32,
+    pub block_hash: StateHash,
+    pub transaction_hash: Option<TransactionHash>,
+    /// Field elements of each event, or of each action.
+    pub data: MinaBaseAccountUpdateBodyEventsStableV1,
+}
+
+/// Events and actions of an account kept by the ledger service, see
+/// [`crate::ledger::LedgerCtx::set_action_event_store`], emitted within a
+/// range of global slots since genesis.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RpcZkappActionsEventsQuery {
+    pub account_id: AccountId,
+    pub from_slot: Option<u32>,
+    pub to_slot: Option<u32>,
+}
+
+impl RpcZkappEventsQuery {
+    /// Events of the commands of `block`, in the order they were applied.
+    /// Commands which failed didn't emit their events.
+    pub fn block_events<'a>(
+        &'a self,
+        block: &'a AppliedBlock,
+    ) -> impl 'a + Iterator<Item = RpcZkappEvents> {
+        let public_key = NonZeroCurvePoint::from(self.public_key.clone());
+        block
+            .commands_iter()
+            .filter(|command| matches!(command.status, MinaBaseTransactionStatusStableV2::Applied))
+            .filter_map(|command| match &command.data {
+                MinaBaseUserCommandStableV2::ZkappCommand(zkapp) => Some(zkapp),
+                MinaBaseUserCommandStableV2::SignedCommand(_) => None,
+            })
+            .flat_map(move |zkapp| {
+                let events = self.command_events(zkapp, &public_key);
+                // Only commands which emitted events are hashed.
+                let transaction_hash = if events.is_empty() {
+                    None
+                } else {
+                    MinaBaseUserCommandStableV2::ZkappCommand(zkapp.clone())
+                        .hash()
+                        .ok()
+                };
+                events.into_iter().map(move |data| RpcZkappEvents {
+                    block_height: block.height(),
+                    block_hash: block.hash().clone(),
+                    transaction_hash: transaction_hash.clone(),
+                    data,
+                })
+            })
+    }
+
+    /// Events of the account updates of `public_key`, in call order: each
+    /// account update comes before the ones it calls.
+    fn command_events(
+        &self,
+        zkapp: &MinaBaseZkappCommandTStableV1WireStableV1,
+        public_key: &NonZeroCurvePoint,
+    ) -> Vec<MinaBaseAccountUpdateBodyEventsStableV1> {
+        fn visit(
+            tree: &MinaBaseZkappCommandTStableV1WireStableV1AccountUpdatesAA,
+            f: &mut impl FnMut(&MinaBaseAccountUpdateTStableV1),
+        ) {
+            f(&tree.account_update);
+            for call in tree.calls.iter() {
+                visit(&call.elt, f);
+            }
+        }
+
+        let mut events = Vec::new();
+        for tree in zkapp.account_updates.iter() {
+            visit(&tree.elt, &mut |account_update| {
+                let body = &account_update.body;
+                if &body.public_key != public_key {
+                    return;
+                }
+                let data = match self.kind {
+                    RpcZkappEventsKind::Events => &body.events,
+                    RpcZkappEventsKind::Actions => &body.actions,
+                };
+                if !data.0.is_empty() {
+                    events.push(data.clone());
+                }
+            });
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_p2p_messages::{
+        bigint::BigInt, binprot::BinProtRead,
+        v2::MinaBaseZkappCommandTStableV1WireStableV1AccountUpdatesAACallsA,
+    };
+
+    use super::*;
+
+    fn events(values: &[u64]) -> MinaBaseAccountUpdateBodyEventsStableV1 {
+        let field = |value| {
+            let mut bytes = [0; 32];
+            bytes[..8].copy_from_slice(&u64::to_le_bytes(value));
+            BigInt::from_bytes(bytes)
+        };
+        MinaBaseAccountUpdateBodyEventsStableV1(
+            values
+                .iter()
+                .map(|value| [field(*value)].into_iter().collect())
+                .collect(),
+        )
+    }
+
+    /// zkApp command whose first account update, of the fee payer, calls
+    /// the second one, of the zkApp.
+    fn zkapp_command() -> MinaBaseZkappCommandTStableV1WireStableV1 {
+        let bytes = include_bytes!("../../../tests/files/zkapps/valid_zkapp.bin");
+        let mut zkapp =
+            MinaBaseZkappCommandTStableV1WireStableV1::binprot_read(&mut bytes.as_slice()).unwrap();
+        let mut account_updates = zkapp.account_updates.iter().cloned();
+        let mut caller = account_updates.next().unwrap();
+        let mut callee = account_updates.next().unwrap();
+
+        caller.elt.account_update.body.events = events(&[1]);
+        callee.elt.account_update.body.events = events(&[2, 3]);
+        callee.elt.account_update.body.actions = events(&[4]);
+        let mut second_call = callee.elt.clone();
+        second_call.account_update.body.events = events(&[5]);
+        caller.elt.calls = [callee.elt, second_call]
+            .into_iter()
+            .map(
+                |elt| MinaBaseZkappCommandTStableV1WireStableV1AccountUpdatesAACallsA {
+                    elt: Box::new(elt),
+                    stack_hash: (),
+                },
+            )
+            .collect();
+        zkapp.account_updates = [caller].into_iter().collect();
+        zkapp
+    }
+
+    fn query(public_key: &NonZeroCurvePoint, kind: RpcZkappEventsKind) -> RpcZkappEventsQuery {
+        RpcZkappEventsQuery {
+            public_key: public_key.clone().into(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_events_of_nested_account_updates() {
+        let zkapp = zkapp_command();
+        let caller = zkapp.account_updates.iter().next().unwrap();
+        let fee_payer = caller.elt.account_update.body.public_key.clone();
+        let zkapp_account = caller.elt.calls.iter().next().unwrap();
+        let zkapp_account = zkapp_account.elt.account_update.body.public_key.clone();
+        assert_ne!(fee_payer, zkapp_account);
+
+        let query = self::query(&zkapp_account, RpcZkappEventsKind::Events);
+        let emitted = query.command_events(&zkapp, &zkapp_account);
+        assert_eq!(emitted, [events(&[2, 3]), events(&[5])]);
+
+        let query = self::query(&zkapp_account, RpcZkappEventsKind::Actions);
+        let actions = query.command_events(&zkapp, &zkapp_account);
+        // The second call has the actions of the first one.
+        assert_eq!(actions, [events(&[4]), events(&[4])]);
+
+        let query = self::query(&fee_payer, RpcZkappEventsKind::Events);
+        assert_eq!(query.command_events(&zkapp, &fee_payer), [events(&[1])]);
+        let query = self::query(&fee_payer, RpcZkappEventsKind::Actions);
+        assert!(query.command_events(&zkapp, &fee_payer).is_empty());
+    }
+}
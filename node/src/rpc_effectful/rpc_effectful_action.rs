@@ -2,13 +2,25 @@ use crate::{
     external_snark_worker::{ExternalSnarkWorker, SnarkWorkId},
     p2p::connection::P2pConnectionResponse,
     rpc::{
-        discovery::RpcDiscoveryRoutingTable, AccountQuery, ActionStatsQuery, RpcBestChainResponse,
-        RpcConsensusTimeGetResponse, RpcGenesisBlockResponse, RpcGetBlockResponse,
-        RpcLedgerAccountDelegatorsGetResponse, RpcLedgerStatusGetResponse, RpcPeerInfo,
-        RpcPooledUserCommandsResponse, RpcPooledZkappCommandsResponse,
+        discovery::RpcDiscoveryRoutingTable, AccountQuery, ActionStatsQuery,
+        RpcBestChainPageGetResponse, RpcBestChainResponse, RpcBlockInjectResponse,
+        RpcBlockSummaryGetResponse, RpcConsensusForkDecisionsGetResponse,
+        RpcConsensusTimeGetResponse, RpcEnabledFeaturesGetResponse, RpcGenesisBlockResponse,
+        RpcGetBlockResponse, RpcLedgerAccountDelegatorsGetResponse,
+        RpcLedgerAccountsAtBlockGetResponse, RpcLedgerAccountsPageGetResponse,
+        RpcLedgerSnapshotGetResponse, RpcLedgerStatusGetResponse,
+        RpcLocallyGeneratedCommandsGetResponse, RpcPeerInfo, RpcPeerListRefreshResponse,
+        RpcPendingCoinbaseGetResponse, RpcPendingRequestsGetResponse,
+        RpcPooledUserCommandsPageGetResponse, RpcPooledUserCommandsResponse,
+        RpcPooledZkappCommandsPageGetResponse, RpcPooledZkappCommandsResponse,
         RpcScanStateSummaryScanStateJob, RpcSnarkPoolCompletedJobsResponse,
-        RpcSnarkPoolPendingJobsGetResponse, RpcSnarkerConfig, RpcTransactionInjectFailure,
-        RpcTransactionInjectRejected, RpcTransactionInjectSuccess, SyncStatsQuery,
+        RpcSnarkPoolPendingJobsGetResponse, RpcSnarkStatsGetResponse,
+        RpcSnarkVerifierDigestsGetResponse, RpcSnarkVerifyDryRunResponse,
+        RpcSnarkVerifyHistoryGetResponse, RpcSnarkerConfig, RpcSnarkerWorkSubmitResponse,
+        RpcSyncDiagnosis, RpcTokenSupplyGetResponse, RpcTransactionInjectFailure,
+        RpcTransactionInjectRejected, RpcTransactionInjectSuccess,
+        RpcTransactionPoolSenderQueuesGetResponse, RpcZkappActionsEventsGetResponse,
+        RpcZkappEventsPageGetResponse, SyncStatsQuery,
     },
 };
 use ledger::{
@@ -40,9 +52,18 @@ pub enum RpcEffectfulAction {
         rpc_id: RpcId,
         query: SyncStatsQuery,
     },
+    SyncHistoryGet {
+        rpc_id: RpcId,
+    },
     BlockProducerStatsGet {
         rpc_id: RpcId,
     },
+    PeerContributionStatsGet {
+        rpc_id: RpcId,
+    },
+    ArchiveUploadStatsGet {
+        rpc_id: RpcId,
+    },
 
     MessageProgressGet {
         rpc_id: RpcId,
@@ -100,6 +121,10 @@ pub enum RpcEffectfulAction {
         rpc_id: RpcId,
         job_id: SnarkJobId,
     },
+    SnarkerWorkSubmitSuccess {
+        rpc_id: RpcId,
+        response: RpcSnarkerWorkSubmitResponse,
+    },
     SnarkerWorkersGet {
         rpc_id: RpcId,
         snark_worker: ExternalSnarkWorker,
@@ -123,6 +148,10 @@ pub enum RpcEffectfulAction {
         rpc_id: RpcId,
         response: Vec<WithHash<UserCommand, v2::TransactionHash>>,
     },
+    TransactionPoolSenderQueues {
+        rpc_id: RpcId,
+        response: RpcTransactionPoolSenderQueuesGetResponse,
+    },
     LedgerAccountsGetSuccess {
         rpc_id: RpcId,
         accounts: Vec<Account>,
@@ -148,6 +177,10 @@ pub enum RpcEffectfulAction {
         rpc_id: RpcId,
         best_chain: RpcBestChainResponse,
     },
+    BestChainPageGet {
+        rpc_id: RpcId,
+        response: RpcBestChainPageGetResponse,
+    },
     ConsensusConstantsGet {
         rpc_id: RpcId,
         response: ConsensusConstants,
@@ -155,11 +188,16 @@ pub enum RpcEffectfulAction {
     TransactionStatusGet {
         rpc_id: RpcId,
         tx: MinaBaseUserCommandStableV2,
+        block_hash: Option<v2::StateHash>,
     },
     BlockGet {
         rpc_id: RpcId,
         block: RpcGetBlockResponse,
     },
+    BlockSummaryGet {
+        rpc_id: RpcId,
+        summary: RpcBlockSummaryGetResponse,
+    },
     PooledUserCommands {
         rpc_id: RpcId,
         user_commands: RpcPooledUserCommandsResponse,
@@ -168,6 +206,26 @@ pub enum RpcEffectfulAction {
         rpc_id: RpcId,
         zkapp_commands: RpcPooledZkappCommandsResponse,
     },
+    PooledUserCommandsPageGet {
+        rpc_id: RpcId,
+        response: RpcPooledUserCommandsPageGetResponse,
+    },
+    PooledZkappCommandsPageGet {
+        rpc_id: RpcId,
+        response: RpcPooledZkappCommandsPageGetResponse,
+    },
+    ZkappEventsPageGet {
+        rpc_id: RpcId,
+        response: RpcZkappEventsPageGetResponse,
+    },
+    ZkappActionsEventsGetSuccess {
+        rpc_id: RpcId,
+        response: RpcZkappActionsEventsGetResponse,
+    },
+    TokenSupplyGetSuccess {
+        rpc_id: RpcId,
+        response: RpcTokenSupplyGetResponse,
+    },
     GenesisBlock {
         rpc_id: RpcId,
         genesis_block: RpcGenesisBlockResponse,
@@ -184,6 +242,81 @@ pub enum RpcEffectfulAction {
         rpc_id: RpcId,
         response: RpcLedgerAccountDelegatorsGetResponse,
     },
+    LedgerSnapshotGetSuccess {
+        rpc_id: RpcId,
+        response: RpcLedgerSnapshotGetResponse,
+    },
+    LedgerAccountsPageGetSuccess {
+        rpc_id: RpcId,
+        response: RpcLedgerAccountsPageGetResponse,
+    },
+    LedgerAccountsAtBlockGetSuccess {
+        rpc_id: RpcId,
+        response: RpcLedgerAccountsAtBlockGetResponse,
+    },
+    BlockInject {
+        rpc_id: RpcId,
+        response: RpcBlockInjectResponse,
+    },
+    ConsensusForkDecisionsGet {
+        rpc_id: RpcId,
+        response: RpcConsensusForkDecisionsGetResponse,
+    },
+    PendingCoinbaseGetSuccess {
+        rpc_id: RpcId,
+        response: RpcPendingCoinbaseGetResponse,
+    },
+    PeerListRefreshInit {
+        rpc_id: RpcId,
+    },
+    PeerListRefreshSuccess {
+        rpc_id: RpcId,
+        response: RpcPeerListRefreshResponse,
+    },
+    GossipPause {
+        rpc_id: RpcId,
+    },
+    SyncDiagnosisGet {
+        rpc_id: RpcId,
+        diagnosis: RpcSyncDiagnosis,
+    },
+    NodeOverviewGet {
+        rpc_id: RpcId,
+    },
+    LocallyGeneratedCommandsGet {
+        rpc_id: RpcId,
+        response: RpcLocallyGeneratedCommandsGetResponse,
+    },
+    PendingRequestsGet {
+        rpc_id: RpcId,
+        response: RpcPendingRequestsGetResponse,
+    },
+    SnarkStatsGet {
+        rpc_id: RpcId,
+        response: RpcSnarkStatsGetResponse,
+    },
+    SnarkVerifierDigestsGet {
+        rpc_id: RpcId,
+        response: RpcSnarkVerifierDigestsGetResponse,
+    },
+    SnarkVerifyHistoryGet {
+        rpc_id: RpcId,
+        response: RpcSnarkVerifyHistoryGetResponse,
+    },
+    SnarkVerifyDryRunSuccess {
+        rpc_id: RpcId,
+        report: RpcSnarkVerifyDryRunResponse,
+    },
+    EnabledFeaturesGet {
+        rpc_id: RpcId,
+        response: RpcEnabledFeaturesGetResponse,
+    },
+    /// Drops the request without a response, see [`RpcService::drop_request`].
+    ///
+    /// [`RpcService::drop_request`]: super::RpcService::drop_request
+    Drop {
+        rpc_id: RpcId,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for RpcEffectfulAction {
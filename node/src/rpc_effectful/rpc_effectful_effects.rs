@@ -8,13 +8,15 @@ use super::{super::rpc, RpcEffectfulAction};
 use crate::{
     block_producer::BlockProducerWonSlot,
     external_snark_worker::available_job_to_snark_worker_spec,
-    p2p::connection::P2pConnectionResponse,
+    p2p::{connection::P2pConnectionResponse, P2pPeerStatus},
     p2p_ready,
     rpc::{
         AccountQuery, AccountSlim, ActionStatsQuery, ActionStatsResponse, CurrentMessageProgress,
         MessagesStats, NodeHeartbeat, ProducedBlockInfo, RootLedgerSyncProgress,
-        RootStagedLedgerSyncProgress, RpcAction, RpcBlockProducerStats, RpcMessageProgressResponse,
-        RpcNodeStatus, RpcNodeStatusLedger, RpcNodeStatusNetworkInfo, RpcNodeStatusResources,
+        RootStagedLedgerSyncProgress, RpcAction, RpcBlockProducerStats, RpcBlockPrunedError,
+        RpcMessageProgressResponse, RpcNodeOverview, RpcNodeOverviewBestTip,
+        RpcNodeOverviewBlockProducer, RpcNodeOverviewPeers, RpcNodeStatus, RpcNodeStatusLedger,
+        RpcNodeStatusNetworkInfo, RpcNodeStatusResources, RpcNodeStatusSnarkPool,
         RpcNodeStatusTransactionPool, RpcNodeStatusTransitionFrontier,
         RpcNodeStatusTransitionFrontierBlockSummary, RpcNodeStatusTransitionFrontierSync,
         RpcRequestExtraData, RpcScanStateSummary, RpcScanStateSummaryBlock,
@@ -24,6 +26,7 @@ use crate::{
         RpcTransactionInjectResponse, TransactionStatus,
     },
     snark_pool::SnarkPoolAction,
+    stats::block_producer::{BlockProductionAttempt, BlockProductionAttemptWonSlot},
     transition_frontier::sync::{
         ledger::TransitionFrontierSyncLedgerState, TransitionFrontierSyncState,
     },
@@ -126,18 +129,19 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 .map(|s| s.collect_sync_stats(query.limit));
             let _ = store.service.respond_sync_stats_get(rpc_id, resp);
         }
+        RpcEffectfulAction::SyncHistoryGet { rpc_id } => {
+            let resp = store.service.stats().map(|s| s.collect_sync_history());
+            let _ = store.service.respond_sync_history_get(rpc_id, resp);
+        }
         RpcEffectfulAction::BlockProducerStatsGet { rpc_id } => {
             let mut create_response = || {
                 let state = store.state.get();
                 let best_tip = state.transition_frontier.best_tip()?;
                 let public_key = state.block_producer.config()?.pub_key.clone();
-                let won_slots = &state.block_producer.vrf_evaluator()?.won_slots;
 
                 let stats = store.service.stats()?;
                 let attempts = stats.block_producer().collect_attempts();
-                let future_slot = attempts.last().map_or(0, |v| {
-                    v.won_slot.global_slot.checked_add(1).expect("overflow")
-                });
+                let future_won_slots = future_won_slots(state, &attempts)?;
 
                 let cur_global_slot = state.cur_global_slot();
                 let current_epoch = state.current_epoch();
@@ -151,6 +155,7 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 let current_epoch_vrf_stats = current_epoch
                     .and_then(|epoch| stats.block_producer().vrf_evaluator.get(&epoch).cloned());
                 let vrf_stats = stats.block_producer().vrf_evaluator.clone();
+                let delegation_changes = stats.block_producer().delegation.clone();
 
                 Some(RpcBlockProducerStats {
                     current_time: meta.time(),
@@ -158,21 +163,13 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                     current_epoch,
                     current_epoch_vrf_stats,
                     vrf_stats,
+                    delegation_changes,
                     epoch_start,
                     epoch_end: epoch_start
                         .map(|slot| slot.checked_add(slots_per_epoch).expect("overflow")),
                     public_key: public_key.into(),
                     attempts,
-                    future_won_slots: won_slots
-                        .range(future_slot..)
-                        .map(|(_, won_slot)| {
-                            let won_slot = BlockProducerWonSlot::from_vrf_won_slot(
-                                won_slot,
-                                best_tip.genesis_timestamp(),
-                            );
-                            (&won_slot).into()
-                        })
-                        .collect(),
+                    future_won_slots,
                 })
             };
             let response = create_response();
@@ -180,6 +177,19 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 .service
                 .respond_block_producer_stats_get(rpc_id, response);
         }
+        RpcEffectfulAction::PeerContributionStatsGet { rpc_id } => {
+            let resp = store
+                .service
+                .stats()
+                .map(|s| s.collect_peer_contribution_stats());
+            let _ = store
+                .service
+                .respond_peer_contribution_stats_get(rpc_id, resp);
+        }
+        RpcEffectfulAction::ArchiveUploadStatsGet { rpc_id } => {
+            let resp = store.service.archive_upload_stats();
+            let _ = store.service.respond_archive_upload_stats_get(rpc_id, resp);
+        }
         RpcEffectfulAction::MessageProgressGet { rpc_id } => {
             // TODO: move to stats
             let p2p = p2p_ready!(store.state().p2p, meta.time());
@@ -274,7 +284,9 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                                 .and_then(|peer_id| store.state().p2p.get_ready_peer(peer_id))
                                 .map(|peer| {
                                     match peer.channels.streaming_rpc.pending_local_rpc_progress() {
-                                        None => unknown_staged_progress(),
+                                        None | Some(P2pStreamingRpcReceiveProgress::Block(_)) => {
+                                            unknown_staged_progress()
+                                        }
                                         Some(
                                             P2pStreamingRpcReceiveProgress::StagedLedgerParts(
                                                 progress,
@@ -533,12 +545,15 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 job.job.clone(),
                 &store.state().transition_frontier,
             );
-            // TODO(binier): maybe don't require snarker to be enabled here.
-            let Some(config) = store.state.get().config.snarker.as_ref() else {
-                return;
+            // Snarkers fetching jobs from this node, which doesn't snark
+            // itself, put their own key and fee in the work they submit.
+            let (public_key, fee) = match store.state.get().config.snarker.as_ref() {
+                Some(config) => (config.public_key.clone().into(), config.fee.clone()),
+                None => (
+                    v2::NonZeroCurvePoint::default(),
+                    v2::CurrencyFeeStableV1(0u64.into()),
+                ),
             };
-            let public_key = config.public_key.clone().into();
-            let fee = config.fee.clone();
             let input = match input {
                     Ok(instances) => RpcSnarkerJobSpecResponse::Ok(
                         mina_p2p_messages::v2::SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(Some((
@@ -555,6 +570,15 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
             // TODO: handle potential errors
             let _ = store.service().respond_snarker_job_spec(rpc_id, input);
         }
+        RpcEffectfulAction::SnarkerWorkSubmitSuccess { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_snarker_work_submit(rpc_id, response),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
         RpcEffectfulAction::SnarkerWorkersGet {
             rpc_id,
             snark_worker,
@@ -634,6 +658,14 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 meta.time()
             )
         }
+        RpcEffectfulAction::TransactionPoolSenderQueues { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_transaction_pool_sender_queues(rpc_id, response),
+                meta.time()
+            )
+        }
         RpcEffectfulAction::LedgerAccountsGetSuccess {
             rpc_id,
             accounts,
@@ -689,7 +721,7 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                         meta.time()
                     )
                 }
-                AccountQuery::MultipleIds(..) => {
+                AccountQuery::MultipleIds(..) | AccountQuery::Token { .. } => {
                     respond_or_log!(
                         store.service().respond_ledger_accounts(rpc_id, accounts),
                         meta.time()
@@ -738,6 +770,14 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 meta.time()
             )
         }
+        RpcEffectfulAction::BestChainPageGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_best_chain_page_get(rpc_id, response),
+                meta.time()
+            )
+        }
         RpcEffectfulAction::ConsensusConstantsGet { rpc_id, response } => {
             respond_or_log!(
                 store
@@ -746,8 +786,29 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 meta.time()
             )
         }
-        RpcEffectfulAction::TransactionStatusGet { rpc_id, tx } => {
-            // Check if the transaction is in the pool, if it is, return PENDING
+        RpcEffectfulAction::TransactionStatusGet {
+            rpc_id,
+            tx,
+            block_hash,
+        } => {
+            // Check that the block the status is read at is still in the best chain
+            if let Some(block_hash) = &block_hash {
+                let transition_frontier = &store.state().transition_frontier;
+                if transition_frontier.best_chain_up_to(block_hash).is_none() {
+                    let error = RpcBlockPrunedError(block_hash.clone());
+                    respond_or_log!(
+                        store
+                            .service()
+                            .respond_transaction_status(rpc_id, Err(error)),
+                        meta.time()
+                    );
+                    return;
+                }
+            }
+
+            // Check if the transaction is in the pool, if it is, return PENDING.
+            // The pool has no history, so this is checked at the current state
+            // even if the status is read at an older block.
             let tx_hash = tx.hash().ok();
 
             let in_tx_pool = store
@@ -761,7 +822,7 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 respond_or_log!(
                     store
                         .service()
-                        .respond_transaction_status(rpc_id, TransactionStatus::Pending),
+                        .respond_transaction_status(rpc_id, Ok(TransactionStatus::Pending)),
                     meta.time()
                 );
                 return;
@@ -771,17 +832,18 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 store
                     .state()
                     .transition_frontier
-                    .contains_transaction(&hash)
+                    .contains_transaction(&hash, block_hash.as_ref())
             } else {
                 false
             };
 
-            // Check whether the transaction is in the transition frontier, if it is, return INCLUDED
+            // Check whether the transaction is in the transition frontier, up to
+            // the block the status is read at, if it is, return INCLUDED
             if in_transition_frontier {
                 respond_or_log!(
                     store
                         .service()
-                        .respond_transaction_status(rpc_id, TransactionStatus::Included),
+                        .respond_transaction_status(rpc_id, Ok(TransactionStatus::Included)),
                     meta.time()
                 )
             // Otherwise, return UNKNOWN
@@ -789,7 +851,7 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 respond_or_log!(
                     store
                         .service()
-                        .respond_transaction_status(rpc_id, TransactionStatus::Unknown),
+                        .respond_transaction_status(rpc_id, Ok(TransactionStatus::Unknown)),
                     meta.time()
                 )
             }
@@ -800,6 +862,12 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 meta.time()
             )
         }
+        RpcEffectfulAction::BlockSummaryGet { rpc_id, summary } => {
+            respond_or_log!(
+                store.service().respond_block_summary_get(rpc_id, summary),
+                meta.time()
+            )
+        }
 
         RpcEffectfulAction::PooledUserCommands {
             rpc_id,
@@ -824,6 +892,44 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 meta.time()
             )
         }
+        RpcEffectfulAction::PooledUserCommandsPageGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_pooled_user_commands_page_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::PooledZkappCommandsPageGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_pooled_zkapp_commands_page_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::ZkappEventsPageGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_zkapp_events_page_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::ZkappActionsEventsGetSuccess { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_zkapp_actions_events_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::TokenSupplyGetSuccess { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_token_supply_get(rpc_id, response),
+                meta.time()
+            )
+        }
         RpcEffectfulAction::GenesisBlock {
             rpc_id,
             genesis_block,
@@ -859,6 +965,152 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 meta.time()
             )
         }
+        RpcEffectfulAction::LedgerSnapshotGetSuccess { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_ledger_snapshot_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::LedgerAccountsPageGetSuccess { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_ledger_accounts_page_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::LedgerAccountsAtBlockGetSuccess { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_ledger_accounts_at_block_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::BlockInject { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_block_inject(rpc_id, response),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
+        RpcEffectfulAction::ConsensusForkDecisionsGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_consensus_fork_decisions_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::PendingCoinbaseGetSuccess { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_pending_coinbase_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::PeerListRefreshInit { rpc_id } => {
+            if let Err(error) = store.service().peer_list_refresh(Some(rpc_id)) {
+                store.dispatch(RpcAction::PeerListRefreshSuccess {
+                    rpc_id,
+                    response: Err(error),
+                });
+            }
+        }
+        RpcEffectfulAction::PeerListRefreshSuccess { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_peer_list_refresh(rpc_id, response),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
+        RpcEffectfulAction::GossipPause { rpc_id } => {
+            let response = store
+                .state()
+                .p2p
+                .ready()
+                .map(|p2p| p2p.paused_gossip.clone())
+                .ok_or_else(|| "p2p isn't initialized".to_owned());
+            respond_or_log!(
+                store.service().respond_gossip_pause(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::SyncDiagnosisGet { rpc_id, diagnosis } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_sync_diagnosis_get(rpc_id, diagnosis),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::NodeOverviewGet { rpc_id } => {
+            let overview = compute_node_overview(store, meta.time());
+            respond_or_log!(
+                store.service().respond_node_overview_get(rpc_id, overview),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::LocallyGeneratedCommandsGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_locally_generated_commands_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::PendingRequestsGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_pending_requests_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::SnarkStatsGet { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_snark_stats_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::SnarkVerifierDigestsGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_snark_verifier_digests_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::SnarkVerifyHistoryGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_snark_verify_history_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::SnarkVerifyDryRunSuccess { rpc_id, report } => {
+            respond_or_log!(
+                store.service().respond_snark_verify_dry_run(rpc_id, report),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
+        RpcEffectfulAction::EnabledFeaturesGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_enabled_features_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::Drop { rpc_id } => {
+            store.service.drop_request(rpc_id);
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
     }
 }
 
@@ -894,6 +1146,13 @@ fn compute_node_status<S: Service>(store: &mut Store<S>) -> RpcNodeStatus {
             .map(|addr| addr.to_string()),
         client_port: state.config.client_port,
         libp2p_port: state.p2p.config().libp2p_port,
+        initial_peers: state
+            .p2p
+            .config()
+            .initial_peers
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
     };
 
     let block_producer = state
@@ -934,39 +1193,167 @@ fn compute_node_status<S: Service>(store: &mut Store<S>) -> RpcNodeStatus {
                 .collect(),
         },
         peers: rpc::collect_rpc_peers_info(state),
-        snark_pool: state
-            .snark_pool
-            .jobs_iter()
-            .fold(Default::default(), |mut acc, job| {
-                if job.snark.is_some() {
-                    acc.snarks = acc.snarks.saturating_add(1);
-                }
-                acc.total_jobs = acc.total_jobs.saturating_add(1);
-                acc
-            }),
-        transaction_pool: RpcNodeStatusTransactionPool {
-            transactions: state.transaction_pool.size(),
-            transactions_for_propagation: state.transaction_pool.for_propagation_size(),
-            transaction_candidates: state.transaction_pool.candidates.transactions_count(),
-        },
+        snark_pool: node_status_snark_pool(state),
+        transaction_pool: node_status_transaction_pool(state),
         current_block_production_attempt,
         previous_block_production_attempt,
-        resources_status: RpcNodeStatusResources {
-            p2p_malloc_size: {
-                let mut set = BTreeSet::new();
-                let fun = move |ptr: *const c_void| !set.insert(ptr.addr());
-                let mut ops = MallocSizeOfOps::new(None, Some(Box::new(fun)));
-                size_of_val(&state.p2p).saturating_add(state.p2p.size_of(&mut ops))
-            },
-            transition_frontier: state.transition_frontier.resources_usage(),
-            snark_pool: state.snark_pool.resources_usage(),
-        },
+        resources_status: node_status_resources(state),
         service_queues: store.service.queues(),
         network_info,
     };
     status
 }
 
+/// Number of the latest blocks of the best chain included in the overview.
+const OVERVIEW_RECENT_BLOCKS: usize = 10;
+
+fn compute_node_overview<S: Service>(
+    store: &mut Store<S>,
+    time: redux::Timestamp,
+) -> RpcNodeOverview {
+    let state = store.state.get();
+    let sync = &state.transition_frontier.sync;
+
+    let block_summary = |block: &ArcBlockWithHash| RpcNodeOverviewBestTip {
+        hash: block.hash().clone(),
+        height: block.height(),
+        global_slot: block.global_slot(),
+        timestamp: block.timestamp(),
+        producer: block.producer().clone().into(),
+    };
+    let best_tip = state.transition_frontier.best_tip().map(block_summary);
+    let recent_blocks = state
+        .transition_frontier
+        .best_chain
+        .iter()
+        .rev()
+        .take(OVERVIEW_RECENT_BLOCKS)
+        .map(|block| block_summary(block))
+        .collect();
+
+    let peers = state
+        .p2p
+        .ready()
+        .into_iter()
+        .flat_map(|p2p| p2p.peers.values())
+        .fold(RpcNodeOverviewPeers::default(), |mut peers, peer| {
+            match &peer.status {
+                P2pPeerStatus::Ready(ready) => {
+                    peers.connected = peers.connected.saturating_add(1);
+                    let height = ready.best_tip.as_ref().map(|block| block.height());
+                    peers.best_tip_height = peers.best_tip_height.max(height);
+                }
+                P2pPeerStatus::Connecting(_) => {
+                    peers.connecting = peers.connecting.saturating_add(1);
+                }
+                P2pPeerStatus::Disconnecting { .. } | P2pPeerStatus::Disconnected { .. } => {
+                    peers.disconnected = peers.disconnected.saturating_add(1);
+                }
+            }
+            peers
+        });
+
+    let block_producer = state.block_producer.config().map(|config| {
+        let attempts = store
+            .service
+            .stats()
+            .map_or_else(Vec::new, |stats| stats.block_producer().collect_attempts());
+        RpcNodeOverviewBlockProducer {
+            public_key: config.pub_key.clone().into(),
+            future_won_slots: future_won_slots(state, &attempts).unwrap_or_default(),
+            current_attempt: attempts.into_iter().last(),
+        }
+    });
+
+    RpcNodeOverview {
+        time,
+        current_global_slot: state.cur_global_slot(),
+        chain_id: state.p2p.ready().map(|p2p| p2p.chain_id.to_hex()),
+        synced: sync.is_synced(),
+        sync: RpcNodeStatusTransitionFrontierSync {
+            time: sync.time(),
+            status: sync.to_string(),
+            phase: sync.sync_phase().to_string(),
+            target: sync
+                .best_tip()
+                .map(|block| RpcNodeStatusTransitionFrontierBlockSummary {
+                    hash: block.hash().clone(),
+                    height: block.height(),
+                    global_slot: block.global_slot(),
+                }),
+        },
+        best_tip,
+        recent_blocks,
+        ledger_hash_divergences: state
+            .transition_frontier
+            .ledger_hashes_check
+            .divergences
+            .clone(),
+        peers,
+        resources_status: node_status_resources(state),
+        transaction_pool: node_status_transaction_pool(state),
+        snark_pool: node_status_snark_pool(state),
+        block_producer,
+    }
+}
+
+fn node_status_snark_pool(state: &crate::State) -> RpcNodeStatusSnarkPool {
+    state
+        .snark_pool
+        .jobs_iter()
+        .fold(Default::default(), |mut acc, job| {
+            if job.snark.is_some() {
+                acc.snarks = acc.snarks.saturating_add(1);
+            }
+            acc.total_jobs = acc.total_jobs.saturating_add(1);
+            acc
+        })
+}
+
+fn node_status_transaction_pool(state: &crate::State) -> RpcNodeStatusTransactionPool {
+    RpcNodeStatusTransactionPool {
+        transactions: state.transaction_pool.size(),
+        transactions_for_propagation: state.transaction_pool.for_propagation_size(),
+        transaction_candidates: state.transaction_pool.candidates.transactions_count(),
+    }
+}
+
+fn node_status_resources(state: &crate::State) -> RpcNodeStatusResources {
+    RpcNodeStatusResources {
+        p2p_malloc_size: {
+            let mut set = BTreeSet::new();
+            let fun = move |ptr: *const c_void| !set.insert(ptr.addr());
+            let mut ops = MallocSizeOfOps::new(None, Some(Box::new(fun)));
+            size_of_val(&state.p2p).saturating_add(state.p2p.size_of(&mut ops))
+        },
+        transition_frontier: state.transition_frontier.resources_usage(),
+        snark_pool: state.snark_pool.resources_usage(),
+    }
+}
+
+/// Won slots after the last block production attempt, `None` if the
+/// node isn't a block producer or doesn't have a best tip yet.
+fn future_won_slots(
+    state: &crate::State,
+    attempts: &[BlockProductionAttempt],
+) -> Option<Vec<BlockProductionAttemptWonSlot>> {
+    let genesis_timestamp = state.transition_frontier.best_tip()?.genesis_timestamp();
+    let won_slots = &state.block_producer.vrf_evaluator()?.won_slots;
+    let future_slot = attempts.last().map_or(0, |v| {
+        v.won_slot.global_slot.checked_add(1).expect("overflow")
+    });
+
+    Some(
+        won_slots
+            .range(future_slot..)
+            .map(|(_, won_slot)| {
+                let won_slot = BlockProducerWonSlot::from_vrf_won_slot(won_slot, genesis_timestamp);
+                (&won_slot).into()
+            })
+            .collect(),
+    )
+}
+
 fn make_produced_block_info(
     block: Option<ArcBlockWithHash>,
 ) -> std::io::Result<Option<ProducedBlockInfo>> {
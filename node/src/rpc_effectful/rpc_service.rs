@@ -1,20 +1,32 @@
 use crate::{
     p2p::connection::P2pConnectionResponse,
     rpc::{
-        RpcActionStatsGetResponse, RpcBestChainResponse, RpcBlockProducerStatsGetResponse,
+        RpcActionStatsGetResponse, RpcArchiveUploadStatsGetResponse, RpcBestChainPageGetResponse,
+        RpcBestChainResponse, RpcBlockInjectResponse, RpcBlockProducerStatsGetResponse,
+        RpcBlockSummaryGetResponse, RpcConsensusForkDecisionsGetResponse,
         RpcConsensusTimeGetResponse, RpcDiscoveryBoostrapStatsResponse,
-        RpcDiscoveryRoutingTableResponse, RpcGenesisBlockResponse, RpcGetBlockResponse,
-        RpcHealthCheckResponse, RpcHeartbeatGetResponse, RpcId,
-        RpcLedgerAccountDelegatorsGetResponse, RpcLedgerAccountsResponse,
-        RpcLedgerSlimAccountsResponse, RpcLedgerStatusGetResponse, RpcMessageProgressResponse,
-        RpcP2pConnectionOutgoingResponse, RpcPeersGetResponse, RpcPooledUserCommandsResponse,
-        RpcPooledZkappCommandsResponse, RpcReadinessCheckResponse, RpcScanStateSummaryGetResponse,
+        RpcDiscoveryRoutingTableResponse, RpcEnabledFeaturesGetResponse, RpcGenesisBlockResponse,
+        RpcGetBlockResponse, RpcGossipPauseResponse, RpcHealthCheckResponse,
+        RpcHeartbeatGetResponse, RpcId, RpcLedgerAccountDelegatorsGetResponse,
+        RpcLedgerAccountsAtBlockGetResponse, RpcLedgerAccountsPageGetResponse,
+        RpcLedgerAccountsResponse, RpcLedgerSlimAccountsResponse, RpcLedgerSnapshotGetResponse,
+        RpcLedgerStatusGetResponse, RpcLocallyGeneratedCommandsGetResponse,
+        RpcMessageProgressResponse, RpcNodeOverviewGetResponse, RpcP2pConnectionOutgoingResponse,
+        RpcPeerContributionStatsGetResponse, RpcPeerListRefreshResponse, RpcPeersGetResponse,
+        RpcPendingCoinbaseGetResponse, RpcPendingRequestsGetResponse,
+        RpcPooledUserCommandsPageGetResponse, RpcPooledUserCommandsResponse,
+        RpcPooledZkappCommandsPageGetResponse, RpcPooledZkappCommandsResponse,
+        RpcReadinessCheckResponse, RpcScanStateSummaryGetResponse,
         RpcSnarkPoolCompletedJobsResponse, RpcSnarkPoolGetResponse, RpcSnarkPoolJobGetResponse,
-        RpcSnarkPoolPendingJobsGetResponse, RpcSnarkerConfigGetResponse,
-        RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse, RpcSnarkerWorkersResponse,
-        RpcStatusGetResponse, RpcSyncStatsGetResponse, RpcTransactionInjectResponse,
-        RpcTransactionPoolResponse, RpcTransactionStatusGetResponse,
-        RpcTransitionFrontierUserCommandsResponse,
+        RpcSnarkPoolPendingJobsGetResponse, RpcSnarkStatsGetResponse,
+        RpcSnarkVerifierDigestsGetResponse, RpcSnarkVerifyDryRunResponse,
+        RpcSnarkVerifyHistoryGetResponse, RpcSnarkerConfigGetResponse, RpcSnarkerJobCommitResponse,
+        RpcSnarkerJobSpecResponse, RpcSnarkerWorkSubmitResponse, RpcSnarkerWorkersResponse,
+        RpcStatusGetResponse, RpcSyncDiagnosisGetResponse, RpcSyncHistoryGetResponse,
+        RpcSyncStatsGetResponse, RpcTokenSupplyGetResponse, RpcTransactionInjectResponse,
+        RpcTransactionPoolResponse, RpcTransactionPoolSenderQueuesGetResponse,
+        RpcTransactionStatusGetResponse, RpcTransitionFrontierUserCommandsResponse,
+        RpcZkappActionsEventsGetResponse, RpcZkappEventsPageGetResponse,
     },
     State,
 };
@@ -72,11 +84,26 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcSyncStatsGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_sync_history_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSyncHistoryGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_block_producer_stats_get(
         &mut self,
         rpc_id: RpcId,
         response: RpcBlockProducerStatsGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_peer_contribution_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcPeerContributionStatsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_archive_upload_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcArchiveUploadStatsGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_message_progress_stats_get(
         &mut self,
         rpc_id: RpcId,
@@ -142,6 +169,11 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcSnarkerJobSpecResponse,
     ) -> Result<(), RespondError>;
+    fn respond_snarker_work_submit(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkerWorkSubmitResponse,
+    ) -> Result<(), RespondError>;
     fn respond_snarker_workers(
         &mut self,
         rpc_id: RpcId,
@@ -172,6 +204,11 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcTransactionPoolResponse,
     ) -> Result<(), RespondError>;
+    fn respond_transaction_pool_sender_queues(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcTransactionPoolSenderQueuesGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_ledger_slim_accounts(
         &mut self,
         rpc_id: RpcId,
@@ -197,6 +234,11 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcBestChainResponse,
     ) -> Result<(), RespondError>;
+    fn respond_best_chain_page_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBestChainPageGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_consensus_constants(
         &mut self,
         rpc_id: RpcId,
@@ -212,6 +254,11 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcGetBlockResponse,
     ) -> Result<(), RespondError>;
+    fn respond_block_summary_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockSummaryGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_pooled_user_commands(
         &mut self,
         rpc_id: RpcId,
@@ -222,6 +269,31 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcPooledZkappCommandsResponse,
     ) -> Result<(), RespondError>;
+    fn respond_pooled_user_commands_page_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcPooledUserCommandsPageGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_pooled_zkapp_commands_page_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcPooledZkappCommandsPageGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_zkapp_events_page_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcZkappEventsPageGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_zkapp_actions_events_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcZkappActionsEventsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_token_supply_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcTokenSupplyGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_genesis_block(
         &mut self,
         rpc_id: RpcId,
@@ -242,4 +314,92 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcLedgerAccountDelegatorsGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_ledger_snapshot_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLedgerSnapshotGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_ledger_accounts_page_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLedgerAccountsPageGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_ledger_accounts_at_block_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLedgerAccountsAtBlockGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_block_inject(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockInjectResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_consensus_fork_decisions_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcConsensusForkDecisionsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_pending_coinbase_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcPendingCoinbaseGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_peer_list_refresh(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcPeerListRefreshResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_gossip_pause(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcGossipPauseResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_sync_diagnosis_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSyncDiagnosisGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_node_overview_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcNodeOverviewGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_locally_generated_commands_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLocallyGeneratedCommandsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_pending_requests_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcPendingRequestsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_snark_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkStatsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_snark_verifier_digests_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkVerifierDigestsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_snark_verify_history_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkVerifyHistoryGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_snark_verify_dry_run(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkVerifyDryRunResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_enabled_features_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcEnabledFeaturesGetResponse,
+    ) -> Result<(), RespondError>;
+    /// Drops the responder of the request, so that its sender gets an
+    /// error instead of waiting forever for a response.
+    fn drop_request(&mut self, rpc_id: RpcId);
 }
@@ -3,20 +3,30 @@ pub use crate::{
         vrf_evaluator_effectful::BlockProducerVrfEvaluatorService, BlockProducerService,
     },
     event_source::EventSourceService,
-    external_snark_worker_effectful::ExternalSnarkWorkerService,
+    external_snark_worker_effectful::{
+        ExternalSnarkWorkerCoordinatorService, ExternalSnarkWorkerService,
+    },
     ledger::LedgerService,
-    p2p::service::*,
+    p2p::{peer::P2pPeerListService, service::*},
     recorder::Recorder,
     rpc_effectful::RpcService,
     snark::{
         block_verify_effectful::SnarkBlockVerifyService,
+        verify_dry_run_effectful::SnarkVerifyDryRunService,
         work_verify_effectful::SnarkWorkVerifyService,
     },
     snark_pool::SnarkPoolService,
     transition_frontier::{
         archive::archive_service::ArchiveService,
         genesis_effectful::TransitionFrontierGenesisService,
-        sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedService,
+        sync::{
+            ledger::{
+                snarked::TransitionFrontierSyncLedgerSnarkedService,
+                TransitionFrontierSyncLedgerSnapshotService,
+            },
+            TransitionFrontierSyncPrecomputedBlockService,
+        },
+        TransitionFrontierEventService,
     },
 };
 pub use redux::TimeService;
@@ -30,16 +40,22 @@ pub trait Service:
     + SnarkBlockVerifyService
     + SnarkWorkVerifyService
     + P2pService
+    + P2pPeerListService
     + LedgerService
     + TransitionFrontierGenesisService
     + TransitionFrontierSyncLedgerSnarkedService
+    + TransitionFrontierSyncLedgerSnapshotService
+    + TransitionFrontierSyncPrecomputedBlockService
     + SnarkPoolService
     + SnarkUserCommandVerifyService
+    + SnarkVerifyDryRunService
     + BlockProducerVrfEvaluatorService
     + BlockProducerService
     + ExternalSnarkWorkerService
+    + ExternalSnarkWorkerCoordinatorService
     + RpcService
     + ArchiveService
+    + TransitionFrontierEventService
 {
     fn queues(&mut self) -> Queues;
     fn stats(&mut self) -> Option<&mut Stats>;
@@ -50,7 +66,10 @@ pub trait Service:
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct Queues {
     pub events: usize,
+    pub events_dropped: crate::event_source::EventsDropped,
     pub snark_block_verify: usize,
+    pub snark_work_verify: usize,
+    pub snark_user_command_verify: usize,
     pub ledger: usize,
     pub vrf_evaluator: Option<usize>,
     pub block_prover: Option<usize>,
@@ -18,5 +18,8 @@ pub fn snark_effects<S: Service>(store: &mut Store<S>, action: SnarkActionWithMe
         SnarkAction::UserCommandVerifyEffect(a) => {
             a.effects(&meta, store);
         }
+        SnarkAction::VerifyDryRunEffect(a) => {
+            a.effects(&meta, store);
+        }
     }
 }
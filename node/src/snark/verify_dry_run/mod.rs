@@ -0,0 +1,3 @@
+pub use ::snark::verify_dry_run_effectful::*;
+
+mod snark_verify_dry_run_actions;
@@ -0,0 +1,7 @@
+use super::*;
+
+impl From<SnarkVerifyDryRunEffectfulAction> for crate::Action {
+    fn from(value: SnarkVerifyDryRunEffectfulAction) -> Self {
+        Self::Snark(value.into())
+    }
+}
@@ -7,8 +7,8 @@ use mina_core::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    p2p::{channels::rpc::P2pRpcId, PeerId},
-    snark::work_verify::SnarkWorkVerifyId,
+    p2p::{channels::rpc::P2pRpcId, gossip::P2pGossipKind, PeerId},
+    snark::work_verify::{SnarkWorkVerifyError, SnarkWorkVerifyId},
 };
 
 use super::SnarkPoolCandidateState;
@@ -53,6 +53,7 @@ pub enum SnarkPoolCandidateAction {
         peer_id: PeerId,
         verify_id: SnarkWorkVerifyId,
         batch: Vec<SnarkJobId>,
+        error: SnarkWorkVerifyError,
     },
     WorkVerifySuccess {
         peer_id: PeerId,
@@ -68,14 +69,17 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolCandidateAction {
     fn is_enabled(&self, state: &crate::State, _time: redux::Timestamp) -> bool {
         match self {
             SnarkPoolCandidateAction::InfoReceived { peer_id, info } => {
-                state.snark_pool.contains(&info.job_id)
+                !state.p2p.is_gossip_paused(P2pGossipKind::Snark)
+                    && state.snark_pool.contains(&info.job_id)
                     && state
                         .snark_pool
                         .candidates
                         .get(*peer_id, &info.job_id)
                         .is_none_or(|v| info > v)
             }
-            SnarkPoolCandidateAction::WorkFetchAll => state.p2p.ready().is_some(),
+            SnarkPoolCandidateAction::WorkFetchAll => {
+                state.p2p.ready().is_some() && !state.p2p.is_gossip_paused(P2pGossipKind::Snark)
+            }
             SnarkPoolCandidateAction::WorkFetchInit { peer_id, job_id } => {
                 let is_peer_available = state
                     .p2p
@@ -118,6 +122,7 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolCandidateAction {
             SnarkPoolCandidateAction::WorkVerifyNext => {
                 state.snark.work_verify.jobs.is_empty()
                     && state.transition_frontier.sync.is_synced()
+                    && !state.p2p.is_gossip_paused(P2pGossipKind::Snark)
             }
             SnarkPoolCandidateAction::WorkVerifyPending {
                 peer_id, job_ids, ..
@@ -7,7 +7,10 @@ use p2p::{
     disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
     BroadcastMessageId, P2pNetworkPubsubAction, PeerId,
 };
-use snark::{work_verify::SnarkWorkVerifyAction, work_verify_effectful::SnarkWorkVerifyId};
+use snark::{
+    work_verify::SnarkWorkVerifyAction,
+    work_verify_effectful::{SnarkWorkVerifyError, SnarkWorkVerifyId},
+};
 
 use super::{
     SnarkPoolCandidateAction, SnarkPoolCandidateActionWithMetaRef, SnarkPoolCandidatesState,
@@ -124,11 +127,12 @@ impl SnarkPoolCandidatesState {
                             }
                         }),
                     on_error: redux::callback!(
-                        on_snark_pool_candidate_work_verify_error((req_id: SnarkWorkVerifyId, sender: String, batch: Vec<SnarkJobId>)) -> crate::Action {
+                        on_snark_pool_candidate_work_verify_error((req_id: SnarkWorkVerifyId, sender: String, batch: Vec<SnarkJobId>, error: SnarkWorkVerifyError)) -> crate::Action {
                             SnarkPoolCandidateAction::WorkVerifyError {
                                 peer_id: sender.parse().unwrap(),
                                 verify_id: req_id,
-                                batch
+                                batch,
+                                error
                             }
                         }),
                 });
@@ -149,31 +153,51 @@ impl SnarkPoolCandidatesState {
                 peer_id,
                 verify_id,
                 batch,
+                error,
             } => {
                 state.verify_result(meta.time(), peer_id, *verify_id, Err(()));
 
-                // TODO(binier): blacklist peer
                 let dispatcher = state_context.into_dispatcher();
                 let peer_id = *peer_id;
+                // The verifier failed, so the works may be valid and the
+                // peer isn't at fault.
+                if !error.is_deterministic() {
+                    for snark_job_id in batch {
+                        dispatcher.push(P2pNetworkPubsubAction::IgnoreMessage {
+                            message_id: Some(BroadcastMessageId::Snark {
+                                job_id: snark_job_id.clone(),
+                            }),
+                            reason: format!("Snark work not verified: {error}"),
+                        });
+                    }
+                    return;
+                }
+
+                // TODO(binier): blacklist peer
                 dispatcher.push(P2pDisconnectionAction::Init {
                     peer_id,
                     reason: P2pDisconnectionReason::SnarkPoolVerifyError,
                 });
 
-                // TODO: This is not correct. We are rejecting all snark messages, but the fact that the batch
-                // failed to verify means that there is at least one invalid snark in the batch, not that all of them
-                // are invalid.
-                // Instead, what should happen here is that we split the batch in two and try to verify the two batches
-                // again. Repeating until batches don't fail to verify anymore, or each batch is of size 1.
-                // It may also be worth capping the batch sizes to 10.
-                for snark_job_id in batch {
-                    dispatcher.push(P2pNetworkPubsubAction::RejectMessage {
-                        message_id: Some(BroadcastMessageId::Snark {
-                            job_id: snark_job_id.clone(),
-                        }),
-                        peer_id: None,
-                        reason: "Snark work verification failed".to_string(),
+                // When the invalid work is known, the others of the batch
+                // aren't known to be invalid, so they are only ignored.
+                let invalid_index = error.work_index();
+                for (index, snark_job_id) in batch.iter().enumerate() {
+                    let message_id = Some(BroadcastMessageId::Snark {
+                        job_id: snark_job_id.clone(),
                     });
+                    if invalid_index.is_none_or(|invalid| invalid == index) {
+                        dispatcher.push(P2pNetworkPubsubAction::RejectMessage {
+                            message_id,
+                            peer_id: None,
+                            reason: format!("Snark work verification failed: {error}"),
+                        });
+                    } else {
+                        dispatcher.push(P2pNetworkPubsubAction::IgnoreMessage {
+                            message_id,
+                            reason: "Snark work verified with an invalid one".to_string(),
+                        });
+                    }
                 }
             }
             SnarkPoolCandidateAction::WorkVerifySuccess {
@@ -2,12 +2,13 @@ use std::sync::Arc;
 
 use ledger::scan_state::scan_state::{transaction_snark::OneOrTwo, AvailableJobMessage};
 use mina_core::{
+    requests::RpcId,
     snark::{Snark, SnarkJobCommitment, SnarkJobId},
     ActionEvent,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::p2p::PeerId;
+use crate::{p2p::PeerId, snark::work_verify::SnarkWorkVerifyError};
 
 use super::{candidate::SnarkPoolCandidateAction, SnarkWork};
 
@@ -39,6 +40,23 @@ pub enum SnarkPoolAction {
         sender: PeerId,
         is_sender_local: bool,
     },
+    /// Work submitted over rpc by a snarker which doesn't run this node,
+    /// which is verified before being added to the pool.
+    #[action_event(level = info)]
+    WorkSubmit {
+        rpc_id: RpcId,
+        snark: Snark,
+    },
+    WorkSubmitVerifySuccess {
+        rpc_id: RpcId,
+        batch: Vec<Snark>,
+    },
+    #[action_event(level = warn, fields(display(error)))]
+    WorkSubmitVerifyError {
+        rpc_id: RpcId,
+        job_ids: Vec<SnarkJobId>,
+        error: SnarkWorkVerifyError,
+    },
     #[action_event(level = trace)]
     P2pSendAll,
     #[action_event(level = trace)]
@@ -69,13 +87,21 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolAction {
                     Some(cur) => commitment > &cur.commitment,
                     None => true,
                 }),
-            SnarkPoolAction::WorkAdd { snark, .. } => state
-                .snark_pool
-                .get(&snark.job_id())
-                .is_some_and(|s| match s.snark.as_ref() {
-                    Some(cur) => snark > &cur.work,
-                    None => true,
-                }),
+            SnarkPoolAction::WorkAdd { snark, .. } | SnarkPoolAction::WorkSubmit { snark, .. } => {
+                state
+                    .snark_pool
+                    .get(&snark.job_id())
+                    .is_some_and(|s| match s.snark.as_ref() {
+                        Some(cur) => snark > &cur.work,
+                        None => true,
+                    })
+            }
+            SnarkPoolAction::WorkSubmitVerifySuccess { rpc_id, .. }
+            | SnarkPoolAction::WorkSubmitVerifyError { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_pending()),
             SnarkPoolAction::P2pSend { peer_id } => state
                 .p2p
                 .get_ready_peer(peer_id)
@@ -1,7 +1,12 @@
 use std::collections::BTreeMap;
 
-use crate::{snark_pool::JobCommitment, ExternalSnarkWorkerAction, SnarkerStrategy};
-use mina_core::snark::{SnarkJobCommitment, SnarkJobId};
+use crate::{
+    rpc::RpcSnarkerWorkSubmitResponse,
+    snark::work_verify::{SnarkWorkVerifyAction, SnarkWorkVerifyError, SnarkWorkVerifyId},
+    snark_pool::JobCommitment,
+    ExternalSnarkWorkerAction, RpcAction, SnarkerStrategy,
+};
+use mina_core::snark::{Snark, SnarkJobCommitment, SnarkJobId};
 use p2p::channels::{
     snark::P2pChannelsSnarkAction, snark_job_commitment::P2pChannelsSnarkJobCommitmentAction,
 };
@@ -84,6 +89,18 @@ impl SnarkPoolState {
                     return;
                 };
                 let available_workers = global_state.external_snark_worker.available();
+                if available_workers > 0
+                    && global_state
+                        .external_snark_worker
+                        .wants_coordinator_jobs(&snarker_config.coordinators, meta.time())
+                {
+                    // Jobs of the coordinators pay the worker, ours are
+                    // only worked on once they offer none.
+                    dispatcher.push(ExternalSnarkWorkerAction::CoordinatorJobsFetchInit {
+                        now: meta.time(),
+                    });
+                    return;
+                }
 
                 if available_workers > 0 {
                     let jobs = global_state
@@ -207,6 +224,69 @@ impl SnarkPoolState {
                     is_local: *is_sender_local,
                 });
             }
+            SnarkPoolAction::WorkSubmit { rpc_id, snark } => {
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let req_id = global_state.snark.work_verify.next_req_id();
+                dispatcher.push(SnarkWorkVerifyAction::Init {
+                    req_id,
+                    batch: vec![snark.clone()],
+                    sender: rpc_id.to_string(),
+                    on_success: redux::callback!(
+                    on_snark_pool_work_submit_verify_success(
+                        (_req_id: SnarkWorkVerifyId, sender: String, batch: Vec<Snark>)
+                    ) -> crate::Action {
+                        SnarkPoolAction::WorkSubmitVerifySuccess {
+                            rpc_id: sender.parse().unwrap(),
+                            batch
+                        }
+                    }),
+                    on_error: redux::callback!(
+                    on_snark_pool_work_submit_verify_error(
+                        (_req_id: SnarkWorkVerifyId, sender: String, job_ids: Vec<SnarkJobId>, error: SnarkWorkVerifyError)
+                    ) -> crate::Action {
+                        SnarkPoolAction::WorkSubmitVerifyError {
+                            rpc_id: sender.parse().unwrap(),
+                            job_ids,
+                            error
+                        }
+                    }),
+                });
+            }
+            SnarkPoolAction::WorkSubmitVerifySuccess { rpc_id, batch } => {
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let sender = global_state.p2p.my_id();
+                let mut response = RpcSnarkerWorkSubmitResponse::Accepted;
+                for snark in batch {
+                    // The pool may have got a cheaper work while this one
+                    // was verified.
+                    if let Some(rejection) =
+                        RpcSnarkerWorkSubmitResponse::rejection(&global_state.snark_pool, snark)
+                    {
+                        response = rejection;
+                        continue;
+                    }
+                    // We didn't produce the work, so it isn't gossiped as
+                    // ours, peers get it from our pool like any other work.
+                    dispatcher.push(SnarkPoolAction::WorkAdd {
+                        snark: snark.clone(),
+                        sender,
+                        is_sender_local: false,
+                    });
+                }
+                dispatcher.push(RpcAction::SnarkerWorkSubmitSuccess {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            SnarkPoolAction::WorkSubmitVerifyError { rpc_id, error, .. } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcAction::SnarkerWorkSubmitSuccess {
+                    rpc_id: *rpc_id,
+                    response: RpcSnarkerWorkSubmitResponse::Invalid {
+                        error: error.to_string(),
+                    },
+                });
+            }
             SnarkPoolAction::P2pSendAll { .. } => {
                 let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
                 for peer_id in global_state.p2p.ready_peers() {
@@ -23,9 +23,10 @@ use p2p::{
     bootstrap::P2pNetworkKadBootstrapState,
     channels::{
         rpc::{P2pRpcId, P2pRpcRequest, P2pRpcResponse},
-        streaming_rpc::P2pStreamingRpcResponseFull,
+        streaming_rpc::{P2pStreamingRpcRequest, P2pStreamingRpcResponseFull},
     },
     connection::{outgoing::P2pConnectionOutgoingError, P2pConnectionResponse},
+    gossip::P2pGossipKind,
     network::identify::P2pNetworkIdentifyState,
     P2pCallbacks, P2pConfig, P2pNetworkSchedulerState, P2pPeerState, P2pPeerStatusReady, PeerId,
 };
@@ -38,7 +39,7 @@ use snark::{
 
 use crate::{
     block_producer::vrf_evaluator::BlockProducerVrfEvaluatorState,
-    config::GlobalConfig,
+    config::{EnabledFeatures, GlobalConfig},
     external_snark_worker::{ExternalSnarkWorker, ExternalSnarkWorkers},
     ledger::{read::LedgerReadState, write::LedgerWriteState},
     p2p::callbacks::P2pCallbacksAction,
@@ -88,6 +89,9 @@ pub struct State {
 
     pub watched_accounts: WatchedAccountsState,
 
+    #[serde(default)]
+    pub features: EnabledFeatures,
+
     // TODO(binier): include action kind in `last_action`.
     last_action: ActionMeta,
     applied_actions_count: u64,
@@ -274,6 +278,7 @@ pub type Substate<'a, S> = mina_core::Substate<'a, crate::Action, State, S>;
 
 impl State {
     pub fn new(config: Config, constants: &ConsensusConstants, now: Timestamp) -> Self {
+        let features = EnabledFeatures::new(&config);
         Self {
             p2p: P2p::Pending(config.p2p),
             ledger: LedgerState::new(config.ledger),
@@ -290,6 +295,7 @@ impl State {
 
             watched_accounts: WatchedAccountsState::new(),
 
+            features,
             config: config.global,
             last_action: ActionMeta::zero_custom(now),
             applied_actions_count: 0,
@@ -611,6 +617,11 @@ impl P2p {
                     P2pCallbacksAction::P2pChannelsStreamingRpcResponseReceived { peer_id, id, response }
                 }
             )),
+            on_p2p_channels_streaming_rpc_request_received: Some(redux::callback!(
+                on_p2p_channels_streaming_rpc_request_received((peer_id: PeerId, id: P2pRpcId, request: Box<P2pStreamingRpcRequest>)) -> crate::Action {
+                    P2pCallbacksAction::P2pChannelsStreamingRpcRequestReceived { peer_id, id, request }
+                }
+            )),
             on_p2p_channels_streaming_rpc_timeout: Some(redux::callback!(
                 on_p2p_channels_streaming_rpc_timeout((peer_id: PeerId, id: P2pRpcId)) -> crate::Action {
                     P2pCallbacksAction::P2pChannelsStreamingRpcTimeout { peer_id, id }
@@ -679,6 +690,10 @@ impl P2p {
     pub fn ready_peers_iter(&self) -> ReadyPeersIter<'_> {
         ReadyPeersIter::new(self)
     }
+
+    pub fn is_gossip_paused(&self, kind: P2pGossipKind) -> bool {
+        self.ready().is_some_and(|p2p| p2p.is_gossip_paused(kind))
+    }
 }
 
 #[derive(Debug, Clone)]
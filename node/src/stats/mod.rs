@@ -10,12 +10,26 @@ pub mod sync {
 }
 use sync::{SyncStats, SyncStatsSnapshot, SyncingLedger};
 
+mod stats_sync_history;
+pub mod sync_history {
+    pub use super::stats_sync_history::*;
+}
+use sync_history::{SyncHistory, SyncHistoryStats};
+
 mod stats_block_producer;
 pub mod block_producer {
     pub use super::stats_block_producer::*;
 }
 use block_producer::BlockProducerStats;
 
+mod stats_peer_contribution;
+pub mod peer_contribution {
+    pub use super::stats_peer_contribution::*;
+}
+use peer_contribution::{PeerContribution, PeerContributionStats};
+
+use std::path::PathBuf;
+
 use mina_core::block::{AppliedBlock, ArcBlockWithHash};
 use redux::{ActionMeta, ActionWithMeta, Timestamp};
 
@@ -24,7 +38,7 @@ use crate::{
         ledger::{staged::PeerStagedLedgerPartsFetchError, SyncLedgerTargetKind},
         TransitionFrontierSyncBlockState,
     },
-    ActionKind,
+    Action, ActionKind,
 };
 
 pub type ActionKindWithMeta = ActionWithMeta<ActionKind>;
@@ -33,7 +47,9 @@ pub struct Stats {
     last_action: ActionKindWithMeta,
     action_stats: ActionStats,
     sync_stats: SyncStats,
+    sync_history_stats: SyncHistoryStats,
     block_producer_stats: BlockProducerStats,
+    peer_contribution_stats: PeerContributionStats,
 }
 
 impl Stats {
@@ -42,7 +58,17 @@ impl Stats {
             last_action: ActionMeta::ZERO.with_action(ActionKind::None),
             action_stats: Default::default(),
             sync_stats: Default::default(),
+            sync_history_stats: Default::default(),
             block_producer_stats: Default::default(),
+            peer_contribution_stats: Default::default(),
+        }
+    }
+
+    /// Stats whose sync history is saved to and loaded from `path`.
+    pub fn with_sync_history(path: PathBuf) -> Self {
+        Self {
+            sync_history_stats: SyncHistoryStats::load(path),
+            ..Self::new()
         }
     }
 
@@ -50,6 +76,11 @@ impl Stats {
         &mut self.block_producer_stats
     }
 
+    pub fn peer_contribution(&mut self, time: Timestamp, action: &Action) -> &mut Self {
+        self.peer_contribution_stats.add(time, action);
+        self
+    }
+
     pub fn new_sync_target(
         &mut self,
         time: Timestamp,
@@ -57,6 +88,9 @@ impl Stats {
         root_block: &ArcBlockWithHash,
     ) -> &mut Self {
         self.sync_stats.new_target(time, best_tip, root_block);
+        if let Some(kind) = self.sync_stats.current_kind() {
+            self.sync_history_stats.sync_started(kind, time);
+        }
         self
     }
 
@@ -87,11 +121,15 @@ impl Stats {
         self.action_stats
             .new_best_tip(time, best_tip.height(), best_tip.hash().clone());
         self.sync_stats.synced(time);
+        self.sync_history_stats.synced(time, best_tip.height());
+        self.sync_history_stats
+            .best_tip_update(time, best_tip.height());
         self.block_producer_stats.new_best_chain(time, chain);
         self
     }
 
     pub fn new_action(&mut self, kind: ActionKind, meta: ActionMeta) -> &mut Self {
+        self.sync_history_stats.node_started(meta.time());
         let action = meta.with_action(kind);
         self.action_stats.add(&action, &self.last_action);
         self.last_action = action;
@@ -113,6 +151,14 @@ impl Stats {
         self.sync_stats.collect_stats(limit)
     }
 
+    pub fn collect_sync_history(&self) -> SyncHistory {
+        self.sync_history_stats.history()
+    }
+
+    pub fn collect_peer_contribution_stats(&self) -> Vec<PeerContribution> {
+        self.peer_contribution_stats.leaderboard()
+    }
+
     pub fn get_sync_time(&self) -> Option<Timestamp> {
         self.sync_stats
             .collect_stats(Some(1))
@@ -6,7 +6,11 @@ use mina_p2p_messages::v2;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    block_producer::{BlockProducerWonSlot, BlockProducerWonSlotDiscardReason, BlockWithoutProof},
+    account::AccountPublicKey,
+    block_producer::{
+        vrf_evaluator::DelegatorTable, BlockProducerWonSlot, BlockProducerWonSlotDiscardReason,
+        BlockWithoutProof,
+    },
     core::block::BlockHash,
 };
 
@@ -16,6 +20,13 @@ const MAX_HISTORY: usize = 2048;
 pub struct BlockProducerStats {
     pub(super) attempts: VecDeque<BlockProductionAttempt>,
     pub vrf_evaluator: BTreeMap<u32, VrfEvaluatorStats>,
+    /// Changes of the stake delegated to the producer, per evaluated epoch.
+    #[serde(default)]
+    pub delegation: BTreeMap<u32, EpochDelegationChange>,
+    /// Stake of each delegator in the staking ledger of the latest evaluated
+    /// epoch, to compare the next one against.
+    #[serde(skip)]
+    last_delegators: Option<(u32, BTreeMap<AccountPublicKey, u64>)>,
     pub last_produced_block: Option<ArcBlockWithHash>,
 }
 
@@ -24,6 +35,10 @@ pub struct BlockProductionAttempt {
     pub won_slot: BlockProductionAttemptWonSlot,
     pub block: Option<ProducedBlock>,
     pub times: BlockProductionTimes,
+    /// Whether some available completed work wasn't bought, because of the
+    /// block producer's work fee policy.
+    #[serde(default)]
+    pub work_limited_by_policy: bool,
     #[serde(flatten)]
     pub status: BlockProductionStatus,
 }
@@ -35,6 +50,10 @@ pub struct BlockProductionAttemptWonSlot {
     pub epoch: u32,
     pub delegator: (v2::NonZeroCurvePoint, AccountIndex),
     pub value_with_threshold: Option<(f64, f64)>,
+    /// Truncated vrf output, as included in the produced block.
+    pub vrf_output: String,
+    pub stake_fraction: Option<f64>,
+    pub staking_ledger_hash: v2::LedgerHash,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -83,6 +102,9 @@ pub struct ProducedBlock {
     pub coinbase: u64,
     pub fees: u64,
     pub snark_fees: u64,
+    /// Highest fee paid for a single completed work.
+    #[serde(default)]
+    pub snark_fee_max: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -98,6 +120,28 @@ pub struct VrfEvaluatorStats {
     pub evaluated_slots: u32,
 }
 
+/// Delegators of the producer in the staking ledger of an epoch, compared to
+/// the staking ledger of the previously evaluated epoch.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EpochDelegationChange {
+    pub staking_ledger_hash: v2::LedgerHash,
+    /// Epoch compared against, `None` for the first epoch evaluated since the
+    /// node started, in which case there are no gained or lost delegators.
+    pub previous_epoch: Option<u32>,
+    pub delegators: usize,
+    pub total_stake: u64,
+    pub gained_delegators: Vec<DelegatorStake>,
+    /// Delegators which left, with their stake in the previous epoch.
+    pub lost_delegators: Vec<DelegatorStake>,
+    pub net_stake_change: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DelegatorStake {
+    pub public_key: AccountPublicKey,
+    pub stake: u64,
+}
+
 impl Default for VrfEvaluatorStats {
     fn default() -> Self {
         Self {
@@ -206,6 +250,7 @@ impl BlockProducerStats {
                 committed: None,
                 discarded: None,
             },
+            work_limited_by_policy: false,
             status: BlockProductionStatus::Scheduled,
         });
     }
@@ -224,13 +269,18 @@ impl BlockProducerStats {
         );
     }
 
-    pub fn staged_ledger_diff_create_end(&mut self, time: redux::Timestamp) {
+    pub fn staged_ledger_diff_create_end(
+        &mut self,
+        time: redux::Timestamp,
+        work_limited_by_policy: bool,
+    ) {
         self.update(
             "staged_ledger_diff_create_end",
             move |attempt| match attempt.status {
                 BlockProductionStatus::StagedLedgerDiffCreatePending => {
                     attempt.status = BlockProductionStatus::StagedLedgerDiffCreateSuccess;
                     attempt.times.staged_ledger_diff_create_end = Some(time);
+                    attempt.work_limited_by_policy = work_limited_by_policy;
                     true
                 }
                 _ => false,
@@ -359,6 +409,75 @@ impl BlockProducerStats {
         );
     }
 
+    /// Records the delegators of the producer in the staking ledger of
+    /// `epoch`, returning how they changed since the previous evaluated epoch.
+    ///
+    /// Returns `None` if that staking ledger was already recorded, e.g. when
+    /// the evaluation of the epoch is restarted.
+    pub fn new_epoch_delegators(
+        &mut self,
+        epoch: u32,
+        staking_ledger_hash: &v2::LedgerHash,
+        delegator_table: &DelegatorTable,
+    ) -> Option<&EpochDelegationChange> {
+        if self
+            .delegation
+            .get(&epoch)
+            .is_some_and(|change| &change.staking_ledger_hash == staking_ledger_hash)
+        {
+            return None;
+        }
+
+        // A delegator may own several accounts.
+        let mut delegators = BTreeMap::<AccountPublicKey, u64>::new();
+        for (public_key, stake) in delegator_table.values() {
+            let total = delegators.entry(public_key.clone()).or_default();
+            *total = total.checked_add(*stake).expect("overflow");
+        }
+        let total_stake = delegators.values().sum::<u64>();
+
+        let previous = self
+            .last_delegators
+            .as_ref()
+            .filter(|(previous_epoch, _)| *previous_epoch < epoch);
+        let change = match previous {
+            Some((previous_epoch, previous)) => {
+                let previous_total_stake = previous.values().sum::<u64>();
+                EpochDelegationChange {
+                    staking_ledger_hash: staking_ledger_hash.clone(),
+                    previous_epoch: Some(*previous_epoch),
+                    delegators: delegators.len(),
+                    total_stake,
+                    gained_delegators: delegators_missing_from(&delegators, previous),
+                    lost_delegators: delegators_missing_from(previous, &delegators),
+                    // Stakes are bounded by the total currency, which fits in an `i64`.
+                    net_stake_change: (total_stake as i64)
+                        .checked_sub(previous_total_stake as i64)
+                        .expect("overflow"),
+                }
+            }
+            None => EpochDelegationChange {
+                staking_ledger_hash: staking_ledger_hash.clone(),
+                previous_epoch: None,
+                delegators: delegators.len(),
+                total_stake,
+                gained_delegators: vec![],
+                lost_delegators: vec![],
+                net_stake_change: 0,
+            },
+        };
+
+        if self
+            .last_delegators
+            .as_ref()
+            .is_none_or(|(last_epoch, _)| *last_epoch <= epoch)
+        {
+            self.last_delegators = Some((epoch, delegators));
+        }
+        self.delegation.insert(epoch, change);
+        self.delegation.get(&epoch)
+    }
+
     pub fn increment_slot_evaluated(&mut self, epoch: u32) {
         self.vrf_evaluator
             .entry(epoch)
@@ -370,6 +489,21 @@ impl BlockProducerStats {
     }
 }
 
+/// Delegators of `delegators` which aren't in `other`.
+fn delegators_missing_from(
+    delegators: &BTreeMap<AccountPublicKey, u64>,
+    other: &BTreeMap<AccountPublicKey, u64>,
+) -> Vec<DelegatorStake> {
+    delegators
+        .iter()
+        .filter(|(public_key, _)| !other.contains_key(public_key))
+        .map(|(public_key, stake)| DelegatorStake {
+            public_key: public_key.clone(),
+            stake: *stake,
+        })
+        .collect()
+}
+
 impl From<&BlockProducerWonSlot> for BlockProductionAttemptWonSlot {
     fn from(won_slot: &BlockProducerWonSlot) -> Self {
         Self {
@@ -378,6 +512,9 @@ impl From<&BlockProducerWonSlot> for BlockProductionAttemptWonSlot {
             epoch: won_slot.epoch(),
             delegator: won_slot.delegator.clone(),
             value_with_threshold: won_slot.value_with_threshold,
+            vrf_output: won_slot.vrf_output.to_string(),
+            stake_fraction: won_slot.stake_fraction,
+            staking_ledger_hash: won_slot.staking_ledger_hash.clone(),
         }
     }
 }
@@ -401,6 +538,12 @@ impl From<(&BlockHash, &BlockWithoutProof)> for ProducedBlock {
             },
             fees: block.body.fees_sum(),
             snark_fees: block.body.snark_fees_sum(),
+            snark_fee_max: block
+                .body
+                .completed_works_iter()
+                .map(|work| work.fee.as_u64())
+                .max()
+                .unwrap_or(0),
         }
     }
 }
@@ -428,3 +571,57 @@ impl From<&BlockWithoutProof> for ProducedBlockTransactions {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::account::AccountSecretKey;
+
+    use super::*;
+
+    fn delegator(i: u64) -> AccountPublicKey {
+        AccountSecretKey::deterministic(i).public_key()
+    }
+
+    fn table(stakes: &[(u64, u64)]) -> DelegatorTable {
+        stakes
+            .iter()
+            .enumerate()
+            .map(|(index, (i, stake))| (AccountIndex(index as u64), (delegator(*i), *stake)))
+            .collect()
+    }
+
+    #[test]
+    fn delegation_change_between_epochs() {
+        let ledger_hash =
+            v2::LedgerHash::from_str("jxTAZfKKDxoX4vtt68pQCWooXoVLjnfBpusaMwewrcZxsL3uWp6")
+                .unwrap();
+        let mut stats = BlockProducerStats::default();
+
+        let change = stats
+            .new_epoch_delegators(1, &ledger_hash, &table(&[(0, 100), (1, 50)]))
+            .unwrap();
+        assert_eq!(change.previous_epoch, None);
+        assert_eq!(change.total_stake, 150);
+        assert!(change.gained_delegators.is_empty());
+        // Recorded already.
+        assert!(stats
+            .new_epoch_delegators(1, &ledger_hash, &table(&[(0, 100), (1, 50)]))
+            .is_none());
+
+        // Delegator 0 has a second account in the next ledger.
+        let change = stats
+            .new_epoch_delegators(2, &ledger_hash, &table(&[(0, 100), (0, 20), (2, 10)]))
+            .unwrap();
+        assert_eq!(change.previous_epoch, Some(1));
+        assert_eq!(change.delegators, 2);
+        assert_eq!(change.total_stake, 130);
+        assert_eq!(change.net_stake_change, -20);
+        assert_eq!(change.gained_delegators.len(), 1);
+        assert_eq!(change.gained_delegators[0].public_key, delegator(2));
+        assert_eq!(change.lost_delegators.len(), 1);
+        assert_eq!(change.lost_delegators[0].public_key, delegator(1));
+        assert_eq!(change.lost_delegators[0].stake, 50);
+    }
+}
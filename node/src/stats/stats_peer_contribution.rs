@@ -0,0 +1,367 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use mina_core::{block::BlockHash, snark::SnarkJobId, transaction::TransactionHash};
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    p2p::{P2pAction, P2pPeerAction, PeerId},
+    snark_pool::{candidate::SnarkPoolCandidateAction, SnarkPoolAction},
+    transaction_pool::{candidate::TransactionPoolCandidateAction, TransactionPoolAction},
+    transition_frontier::{candidate::TransitionFrontierCandidateAction, TransitionFrontierAction},
+    Action,
+};
+
+/// Max number of peers to keep contributions for. The peers which haven't
+/// delivered anything for the longest time are forgotten first.
+const MAX_PEERS: usize = 1000;
+
+/// Max number of items for which the first peer to deliver them is kept,
+/// per kind of item, until we accept or reject them.
+const MAX_FIRST_DELIVERIES: usize = 10_000;
+
+/// Blocks, transactions and snark works delivered by each peer, and how
+/// many of them were accepted or rejected.
+///
+/// An item is attributed to the first peer which delivered it, so peers
+/// relaying what others already sent us don't get credited for it. Snark
+/// works are identified by their job, the same way as in the snark pool.
+#[derive(Default)]
+pub struct PeerContributionStats {
+    peers: BTreeMap<PeerId, PeerContribution>,
+    blocks: FirstDeliveries<BlockHash>,
+    transactions: FirstDeliveries<TransactionHash>,
+    snarks: FirstDeliveries<SnarkJobId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerContribution {
+    pub peer_id: PeerId,
+    pub blocks: PeerContributionCounts,
+    pub transactions: PeerContributionCounts,
+    pub snarks: PeerContributionCounts,
+    pub last_contribution: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PeerContributionCounts {
+    /// Items this peer was the first to deliver.
+    pub delivered: u64,
+    pub accepted: u64,
+    pub rejected: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PeerContributionKind {
+    Block,
+    Transaction,
+    Snark,
+}
+
+enum PeerContributionUpdate {
+    Delivered,
+    Accepted,
+    Rejected,
+}
+
+struct FirstDeliveries<K> {
+    /// Peer which delivered the item first, and whether the item has been
+    /// accepted or rejected already.
+    peers: BTreeMap<K, (PeerId, bool)>,
+    order: VecDeque<K>,
+}
+
+impl PeerContributionStats {
+    /// Updates the contributions from actions which deliver an item or
+    /// decide on it.
+    pub fn add(&mut self, time: Timestamp, action: &Action) {
+        match action {
+            Action::P2p(P2pAction::Peer(P2pPeerAction::BestTipUpdate { peer_id, best_tip })) => {
+                if self.blocks.insert(best_tip.hash().clone(), *peer_id) {
+                    self.update(
+                        time,
+                        *peer_id,
+                        PeerContributionKind::Block,
+                        PeerContributionUpdate::Delivered,
+                    );
+                }
+            }
+            Action::TransitionFrontier(TransitionFrontierAction::Candidate(action)) => {
+                let (hash, is_accepted) = match action {
+                    TransitionFrontierCandidateAction::BlockSnarkVerifySuccess { hash } => {
+                        (hash, true)
+                    }
+                    // The verifier failed, the block isn't known to be
+                    // invalid.
+                    TransitionFrontierCandidateAction::BlockSnarkVerifyError { error, .. }
+                        if !error.is_deterministic() =>
+                    {
+                        return
+                    }
+                    TransitionFrontierCandidateAction::BlockPrevalidateError { hash, .. }
+                    | TransitionFrontierCandidateAction::BlockSnarkVerifyError { hash, .. } => {
+                        (hash, false)
+                    }
+                    _ => return,
+                };
+                if let Some(peer_id) = self.blocks.decide(hash) {
+                    self.decided(time, peer_id, PeerContributionKind::Block, is_accepted);
+                }
+            }
+            Action::TransactionPool(TransactionPoolAction::Candidate(action)) => {
+                let (peer_id, transactions) = match action {
+                    TransactionPoolCandidateAction::FetchSuccess {
+                        peer_id,
+                        transaction,
+                    } => (peer_id, std::slice::from_ref(transaction)),
+                    TransactionPoolCandidateAction::Libp2pTransactionsReceived {
+                        peer_id,
+                        transactions,
+                        ..
+                    } => (peer_id, transactions.as_slice()),
+                    _ => return,
+                };
+                for transaction in transactions {
+                    if self
+                        .transactions
+                        .insert(transaction.hash().clone(), *peer_id)
+                    {
+                        self.update(
+                            time,
+                            *peer_id,
+                            PeerContributionKind::Transaction,
+                            PeerContributionUpdate::Delivered,
+                        );
+                    }
+                }
+            }
+            Action::TransactionPool(TransactionPoolAction::DiffApplied { accepted, rejected }) => {
+                let accepted = accepted.iter().map(|hash| (hash, true));
+                let rejected = rejected.iter().map(|hash| (hash, false));
+                for (hash, is_accepted) in accepted.chain(rejected) {
+                    if let Some(peer_id) = self.transactions.decide(hash) {
+                        self.decided(
+                            time,
+                            peer_id,
+                            PeerContributionKind::Transaction,
+                            is_accepted,
+                        );
+                    }
+                }
+            }
+            Action::SnarkPool(SnarkPoolAction::Candidate(action)) => match action {
+                SnarkPoolCandidateAction::WorkFetchSuccess { peer_id, work } => {
+                    if self.snarks.insert(work.job_id(), *peer_id) {
+                        self.update(
+                            time,
+                            *peer_id,
+                            PeerContributionKind::Snark,
+                            PeerContributionUpdate::Delivered,
+                        );
+                    }
+                }
+                SnarkPoolCandidateAction::WorkVerifySuccess { batch, .. } => {
+                    for work in batch {
+                        if let Some(peer_id) = self.snarks.decide(&work.job_id()) {
+                            self.decided(time, peer_id, PeerContributionKind::Snark, true);
+                        }
+                    }
+                }
+                // The verifier failed, the works aren't known to be invalid.
+                SnarkPoolCandidateAction::WorkVerifyError { batch, error, .. }
+                    if error.is_deterministic() =>
+                {
+                    for job_id in batch {
+                        if let Some(peer_id) = self.snarks.decide(job_id) {
+                            self.decided(time, peer_id, PeerContributionKind::Snark, false);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    /// Contributions of all the known peers, the ones with the most accepted
+    /// items first.
+    pub fn leaderboard(&self) -> Vec<PeerContribution> {
+        let mut peers = self.peers.values().cloned().collect::<Vec<_>>();
+        peers.sort_by_key(|peer| {
+            (
+                std::cmp::Reverse(peer.total().accepted),
+                peer.total().rejected,
+            )
+        });
+        peers
+    }
+
+    fn decided(
+        &mut self,
+        time: Timestamp,
+        peer_id: PeerId,
+        kind: PeerContributionKind,
+        is_accepted: bool,
+    ) {
+        let update = if is_accepted {
+            PeerContributionUpdate::Accepted
+        } else {
+            PeerContributionUpdate::Rejected
+        };
+        self.update(time, peer_id, kind, update);
+    }
+
+    fn update(
+        &mut self,
+        time: Timestamp,
+        peer_id: PeerId,
+        kind: PeerContributionKind,
+        update: PeerContributionUpdate,
+    ) {
+        if !self.peers.contains_key(&peer_id) && self.peers.len() >= MAX_PEERS {
+            let oldest = self
+                .peers
+                .values()
+                .min_by_key(|peer| peer.last_contribution)
+                .map(|peer| peer.peer_id);
+            if let Some(oldest) = oldest {
+                self.peers.remove(&oldest);
+            }
+        }
+        let peer = self
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerContribution {
+                peer_id,
+                blocks: Default::default(),
+                transactions: Default::default(),
+                snarks: Default::default(),
+                last_contribution: time,
+            });
+        peer.last_contribution = time;
+
+        let counts = match kind {
+            PeerContributionKind::Block => &mut peer.blocks,
+            PeerContributionKind::Transaction => &mut peer.transactions,
+            PeerContributionKind::Snark => &mut peer.snarks,
+        };
+        match update {
+            PeerContributionUpdate::Delivered => {
+                counts.delivered = counts.delivered.saturating_add(1);
+            }
+            PeerContributionUpdate::Accepted => {
+                counts.accepted = counts.accepted.saturating_add(1);
+            }
+            PeerContributionUpdate::Rejected => {
+                counts.rejected = counts.rejected.saturating_add(1);
+            }
+        }
+    }
+}
+
+impl PeerContribution {
+    /// Counts summed over all kinds of items.
+    pub fn total(&self) -> PeerContributionCounts {
+        [self.blocks, self.transactions, self.snarks]
+            .into_iter()
+            .fold(PeerContributionCounts::default(), |total, counts| {
+                PeerContributionCounts {
+                    delivered: total.delivered.saturating_add(counts.delivered),
+                    accepted: total.accepted.saturating_add(counts.accepted),
+                    rejected: total.rejected.saturating_add(counts.rejected),
+                }
+            })
+    }
+}
+
+impl<K: Ord + Clone> FirstDeliveries<K> {
+    /// Returns `false` if another peer already delivered the item.
+    fn insert(&mut self, key: K, peer_id: PeerId) -> bool {
+        if self.peers.contains_key(&key) {
+            return false;
+        }
+        if self.order.len() >= MAX_FIRST_DELIVERIES {
+            if let Some(oldest) = self.order.pop_front() {
+                self.peers.remove(&oldest);
+            }
+        }
+        self.order.push_back(key.clone());
+        self.peers.insert(key, (peer_id, false));
+        true
+    }
+
+    /// Peer which delivered the item, the first time the item is decided
+    /// on. The item is kept until evicted, so that it isn't credited to the
+    /// next peer delivering it again.
+    fn decide(&mut self, key: &K) -> Option<PeerId> {
+        let (peer_id, is_decided) = self.peers.get_mut(key)?;
+        if std::mem::replace(is_decided, true) {
+            return None;
+        }
+        Some(*peer_id)
+    }
+}
+
+impl<K> Default for FirstDeliveries<K> {
+    fn default() -> Self {
+        Self {
+            peers: Default::default(),
+            order: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(n: u8) -> PeerId {
+        PeerId::from_bytes([n; 32])
+    }
+
+    #[test]
+    fn first_delivery_is_credited_once() {
+        let mut deliveries = FirstDeliveries::<u32>::default();
+        assert!(deliveries.insert(1, peer(1)));
+        assert!(!deliveries.insert(1, peer(2)));
+        assert_eq!(deliveries.decide(&1), Some(peer(1)));
+        // Decided already, and still known after that.
+        assert_eq!(deliveries.decide(&1), None);
+        assert!(!deliveries.insert(1, peer(2)));
+        assert_eq!(deliveries.decide(&2), None);
+    }
+
+    #[test]
+    fn leaderboard_by_accepted() {
+        let mut stats = PeerContributionStats::default();
+        let time = Timestamp::ZERO;
+        stats.update(
+            time,
+            peer(1),
+            PeerContributionKind::Block,
+            PeerContributionUpdate::Delivered,
+        );
+        stats.decided(time, peer(1), PeerContributionKind::Block, true);
+        for _ in 0..3 {
+            stats.decided(time, peer(2), PeerContributionKind::Snark, true);
+        }
+        stats.decided(time, peer(2), PeerContributionKind::Transaction, false);
+        for _ in 0..2 {
+            stats.decided(time, peer(3), PeerContributionKind::Transaction, false);
+        }
+
+        let leaderboard = stats.leaderboard();
+        let order = leaderboard.iter().map(|p| p.peer_id).collect::<Vec<_>>();
+        assert_eq!(order, [peer(2), peer(1), peer(3)]);
+        assert_eq!(
+            leaderboard[1].blocks,
+            PeerContributionCounts {
+                delivered: 1,
+                accepted: 1,
+                rejected: 0,
+            }
+        );
+        assert_eq!(leaderboard[0].total().accepted, 3);
+        assert_eq!(leaderboard[0].total().rejected, 1);
+    }
+}
@@ -358,6 +358,11 @@ impl SyncStats {
         self
     }
 
+    /// Kind of the latest sync.
+    pub fn current_kind(&self) -> Option<SyncKind> {
+        self.snapshots.back().map(|s| s.kind.clone())
+    }
+
     pub fn collect_stats(&self, limit: Option<usize>) -> Vec<SyncStatsSnapshot> {
         let limit = limit.unwrap_or(usize::MAX);
         self.snapshots.iter().rev().take(limit).cloned().collect()
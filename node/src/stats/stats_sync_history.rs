@@ -0,0 +1,242 @@
+use std::{collections::VecDeque, path::PathBuf, time::Duration};
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use super::sync::SyncKind;
+use crate::BuildEnv;
+
+/// Max number of completed syncs to keep.
+const MAX_SYNCS: usize = 1000;
+
+/// Max number of hours of chain growth to keep, 30 days.
+const MAX_HOURS: usize = 30 * 24;
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+/// Durations of the syncs of the node and growth of its best chain.
+///
+/// Unlike the other stats, these are saved to disk whenever they change and
+/// loaded back on start, so that operators can compare how fast different
+/// versions of the node sync on their hardware.
+#[derive(Default)]
+pub struct SyncHistoryStats {
+    path: Option<PathBuf>,
+    /// Time of the first action after the node started.
+    node_started: Option<Timestamp>,
+    /// Sync in progress, and when it started.
+    syncing: Option<(SyncKind, Timestamp)>,
+    /// Whether the node got synced since it started.
+    synced_once: bool,
+    best_tip_height: Option<u32>,
+    history: SyncHistory,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SyncHistory {
+    /// Completed syncs, the most recent last.
+    pub syncs: VecDeque<SyncHistoryEntry>,
+    /// Blocks applied to the best chain each hour, the most recent last.
+    pub chain_growth: VecDeque<ChainGrowthEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SyncHistoryEntry {
+    pub kind: SyncKind,
+    /// Version of the node which did the sync.
+    pub version: String,
+    /// When the best tip to sync to was received.
+    pub started: Timestamp,
+    pub synced: Timestamp,
+    pub duration_ms: u64,
+    /// Time from the start of the node until it got synced, for the first
+    /// sync after each start only.
+    pub time_to_synced_ms: Option<u64>,
+    pub best_tip_height: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainGrowthEntry {
+    /// Start of the hour.
+    pub hour: Timestamp,
+    pub blocks_applied: u32,
+    /// Height of the best tip at the end of the hour, or now for the
+    /// current hour.
+    pub best_tip_height: u32,
+}
+
+impl SyncHistoryStats {
+    /// Loads the history saved at `path`, which is then updated whenever the
+    /// history changes.
+    pub fn load(path: PathBuf) -> Self {
+        let history = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|error| {
+                mina_core::warn!(
+                    mina_core::log::system_time();
+                    kind = "SyncHistory",
+                    summary = format!("ignoring invalid sync history at {path:?}: {error}")
+                );
+                SyncHistory::default()
+            }),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => SyncHistory::default(),
+            Err(error) => {
+                mina_core::warn!(
+                    mina_core::log::system_time();
+                    kind = "SyncHistory",
+                    summary = format!("failed to read sync history at {path:?}: {error}")
+                );
+                SyncHistory::default()
+            }
+        };
+
+        Self {
+            path: Some(path),
+            history,
+            ..Default::default()
+        }
+    }
+
+    pub fn node_started(&mut self, time: Timestamp) {
+        self.node_started.get_or_insert(time);
+    }
+
+    /// Sync to a new target started. Targets changing before the node got
+    /// synced are part of the same sync.
+    pub fn sync_started(&mut self, kind: SyncKind, time: Timestamp) {
+        self.syncing.get_or_insert((kind, time));
+    }
+
+    pub fn synced(&mut self, time: Timestamp, best_tip_height: u32) {
+        let Some((kind, started)) = self.syncing.take() else {
+            return;
+        };
+        let time_to_synced = match self.synced_once {
+            true => None,
+            false => self.node_started.and_then(|t| time.checked_sub(t)),
+        };
+        self.synced_once = true;
+
+        if self.history.syncs.len() >= MAX_SYNCS {
+            self.history.syncs.pop_front();
+        }
+        self.history.syncs.push_back(SyncHistoryEntry {
+            kind,
+            version: BuildEnv::get().version,
+            started,
+            synced: time,
+            duration_ms: as_millis(time.checked_sub(started).unwrap_or_default()),
+            time_to_synced_ms: time_to_synced.map(as_millis),
+            best_tip_height,
+        });
+        self.save();
+    }
+
+    /// Best chain changed, counts the blocks it grew by for the current hour.
+    pub fn best_tip_update(&mut self, time: Timestamp, best_tip_height: u32) {
+        let blocks_applied = self
+            .best_tip_height
+            .map_or(0, |height| best_tip_height.saturating_sub(height));
+        self.best_tip_height = Some(best_tip_height);
+
+        let hour_nanos = HOUR.as_nanos() as u64;
+        let hour = Timestamp::new(u64::from(time) / hour_nanos * hour_nanos);
+
+        match self.history.chain_growth.back_mut() {
+            Some(entry) if entry.hour == hour => {
+                entry.blocks_applied = entry.blocks_applied.saturating_add(blocks_applied);
+                entry.best_tip_height = best_tip_height;
+            }
+            _ => {
+                if self.history.chain_growth.len() >= MAX_HOURS {
+                    self.history.chain_growth.pop_front();
+                }
+                self.history.chain_growth.push_back(ChainGrowthEntry {
+                    hour,
+                    blocks_applied,
+                    best_tip_height,
+                });
+            }
+        }
+        self.save();
+    }
+
+    pub fn history(&self) -> SyncHistory {
+        self.history.clone()
+    }
+
+    /// Writes the history to a temporary file first, so that it isn't lost
+    /// if the node gets killed while saving it.
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let tmp_path = path.with_extension("json.tmp");
+        let result = serde_json::to_vec(&self.history)
+            .map_err(std::io::Error::from)
+            .and_then(|bytes| std::fs::write(&tmp_path, bytes))
+            .and_then(|_| std::fs::rename(&tmp_path, path));
+
+        if let Err(error) = result {
+            mina_core::warn!(
+                mina_core::log::system_time();
+                kind = "SyncHistory",
+                summary = format!("failed to save sync history at {path:?}: {error}")
+            );
+        }
+    }
+}
+
+fn as_millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: u64) -> Timestamp {
+        Timestamp::new(secs * 1_000_000_000)
+    }
+
+    #[test]
+    fn sync_durations() {
+        let mut stats = SyncHistoryStats::default();
+        stats.node_started(at(100));
+        stats.sync_started(SyncKind::Bootstrap, at(110));
+        // Target changed while syncing.
+        stats.sync_started(SyncKind::Catchup, at(120));
+        stats.synced(at(200), 10);
+        // Already synced.
+        stats.synced(at(210), 11);
+        stats.sync_started(SyncKind::Catchup, at(300));
+        stats.synced(at(303), 12);
+
+        let history = stats.history();
+        assert_eq!(history.syncs.len(), 2);
+        assert!(matches!(history.syncs[0].kind, SyncKind::Bootstrap));
+        assert_eq!(history.syncs[0].duration_ms, 90_000);
+        assert_eq!(history.syncs[0].time_to_synced_ms, Some(100_000));
+        assert!(matches!(history.syncs[1].kind, SyncKind::Catchup));
+        assert_eq!(history.syncs[1].duration_ms, 3_000);
+        assert_eq!(history.syncs[1].time_to_synced_ms, None);
+    }
+
+    #[test]
+    fn chain_growth_per_hour() {
+        let mut stats = SyncHistoryStats::default();
+        let hour = HOUR.as_secs();
+        stats.best_tip_update(at(hour + 10), 100);
+        stats.best_tip_update(at(hour + 20), 102);
+        // Reorg to a shorter chain.
+        stats.best_tip_update(at(hour + 30), 101);
+        stats.best_tip_update(at(2 * hour + 10), 105);
+
+        let growth = stats.history().chain_growth;
+        assert_eq!(growth.len(), 2);
+        assert_eq!(growth[0].hour, at(hour));
+        assert_eq!(growth[0].blocks_applied, 2);
+        assert_eq!(growth[0].best_tip_height, 101);
+        assert_eq!(growth[1].hour, at(2 * hour));
+        assert_eq!(growth[1].blocks_applied, 4);
+    }
+}
@@ -7,7 +7,7 @@ use mina_core::{
 use p2p::P2pNetworkPubsubMessageCacheId;
 use serde::{Deserialize, Serialize};
 
-use crate::p2p::{channels::rpc::P2pRpcId, PeerId};
+use crate::p2p::{channels::rpc::P2pRpcId, gossip::P2pGossipKind, PeerId};
 
 use super::TransactionPoolCandidateState;
 
@@ -73,13 +73,17 @@ impl redux::EnablingCondition<crate::State> for TransactionPoolCandidateAction {
     fn is_enabled(&self, state: &crate::State, _time: redux::Timestamp) -> bool {
         match self {
             TransactionPoolCandidateAction::InfoReceived { peer_id, info } => {
-                !state.transaction_pool.contains(&info.hash)
+                !state.p2p.is_gossip_paused(P2pGossipKind::Transaction)
+                    && !state.transaction_pool.contains(&info.hash)
                     && !state
                         .transaction_pool
                         .candidates
                         .peer_contains(*peer_id, &info.hash)
             }
-            TransactionPoolCandidateAction::FetchAll => state.p2p.ready().is_some(),
+            TransactionPoolCandidateAction::FetchAll => {
+                state.p2p.ready().is_some()
+                    && !state.p2p.is_gossip_paused(P2pGossipKind::Transaction)
+            }
             TransactionPoolCandidateAction::FetchInit { peer_id, hash } => {
                 let is_peer_available = state
                     .p2p
@@ -115,8 +119,9 @@ impl redux::EnablingCondition<crate::State> for TransactionPoolCandidateAction {
             TransactionPoolCandidateAction::Libp2pTransactionsReceived { .. } => true,
             TransactionPoolCandidateAction::VerifyNext => {
                 // Don't continue if we are producing a block, or we never synced yet
-                // or if the ledger service is busy.
+                // or if the ledger service is busy or transaction gossip is paused.
                 !state.block_producer.is_producing()
+                    && !state.p2p.is_gossip_paused(P2pGossipKind::Transaction)
                     && state
                         .transition_frontier
                         .best_tip()
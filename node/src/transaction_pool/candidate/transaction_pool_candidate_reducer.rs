@@ -29,15 +29,14 @@ impl TransactionPoolCandidatesState {
             TransactionPoolCandidateAction::FetchAll => {
                 let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
                 let p2p = p2p_ready!(global_state.p2p, meta.time());
-                let peers = p2p.ready_peers_iter().map(|(id, _)| *id);
-                let get_order = |_hash: &_| {
-                    // TODO(binier)
-                    0
-                };
+                let peers = p2p
+                    .ready_peers_iter()
+                    .filter(|(_, peer)| peer.channels.rpc.can_send_request())
+                    .map(|(id, _)| *id);
                 let list = global_state
                     .transaction_pool
                     .candidates
-                    .peers_next_transactions_to_fetch(peers, get_order);
+                    .peers_next_transactions_to_fetch(peers);
 
                 for (peer_id, hash) in list {
                     dispatcher.push(TransactionPoolCandidateAction::FetchInit { peer_id, hash });
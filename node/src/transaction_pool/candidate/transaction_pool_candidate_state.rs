@@ -140,14 +140,15 @@ impl TransactionPoolCandidatesState {
         self.by_peer.entry(peer_id).or_default().insert(hash, state);
     }
 
-    pub fn peers_next_transactions_to_fetch<I, F>(
-        &self,
-        peers: I,
-        get_order: F,
-    ) -> Vec<(PeerId, TransactionHash)>
+    /// Transactions to fetch next, highest fee first.
+    ///
+    /// At most one transaction per peer, since the rpc channel can only have
+    /// one pending request, and each transaction only from one peer. Skips
+    /// transactions which are already being fetched or were received from
+    /// another peer.
+    pub fn peers_next_transactions_to_fetch<I>(&self, peers: I) -> Vec<(PeerId, TransactionHash)>
     where
         I: IntoIterator<Item = PeerId>,
-        F: Copy + Fn(&TransactionHash) -> usize,
     {
         let mut needs_fetching = peers
             .into_iter()
@@ -155,26 +156,38 @@ impl TransactionPoolCandidatesState {
             .flat_map(|(peer_id, transactions)| {
                 transactions
                     .iter()
-                    .filter(|(_, state)| {
+                    .filter(|(hash, state)| {
                         matches!(state, TransactionPoolCandidateState::InfoReceived { .. })
+                            && !self.is_fetching_or_received(hash)
                     })
-                    .map(move |(hash, state)| (get_order(hash), state.fee(), peer_id, hash))
+                    .map(move |(hash, state)| (state.fee(), peer_id, hash))
             })
             .collect::<Vec<_>>();
-        needs_fetching
-            .sort_by(|(ord1, fee1, ..), (ord2, fee2, ..)| ord1.cmp(ord2).then(fee1.cmp(fee2)));
+        needs_fetching.sort_by(|(fee1, ..), (fee2, ..)| fee2.cmp(fee1));
+
+        let mut busy_peers = BTreeSet::new();
+        let mut hashes = BTreeSet::new();
+        let mut list = Vec::new();
+        for (_, peer_id, hash) in needs_fetching {
+            if busy_peers.contains(&peer_id) || !hashes.insert(hash) {
+                continue;
+            }
+            busy_peers.insert(peer_id);
+            list.push((peer_id, hash.clone()));
+        }
+        list
+    }
 
-        needs_fetching
-            .into_iter()
-            .scan(None, |last_ord, (ord, _, peer_id, hash)| {
-                if *last_ord == Some(ord) {
-                    return Some(None);
-                }
-                *last_ord = Some(ord);
-                Some(Some((peer_id, hash.clone())))
+    /// Whether the transaction is being fetched from, or was received from
+    /// any peer.
+    fn is_fetching_or_received(&self, hash: &TransactionHash) -> bool {
+        self.by_hash.get(hash).is_some_and(|peers| {
+            peers.iter().any(|peer_id| {
+                self.get(*peer_id, hash).is_some_and(|state| {
+                    !matches!(state, TransactionPoolCandidateState::InfoReceived { .. })
+                })
             })
-            .flatten()
-            .collect()
+        })
     }
 
     pub fn fetch_pending(
@@ -460,3 +473,59 @@ impl TransactionPoolCandidateState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(n: u8) -> PeerId {
+        PeerId::from_bytes([n; 32])
+    }
+
+    fn info(n: u8, fee: u64) -> TransactionInfo {
+        TransactionInfo {
+            hash: TransactionHash::from(&[n; 32]),
+            fee_payer: "B62qmnY6m4c6bdgSPnQGZriSaj9vuSjsfh6qkveGTsFX3yGA5ywRaja"
+                .parse()
+                .unwrap(),
+            fee,
+            nonce: n.into(),
+        }
+    }
+
+    #[test]
+    fn fetches_highest_fee_first() {
+        let time = Timestamp::ZERO;
+        let mut state = TransactionPoolCandidatesState::new();
+        state.info_received(time, peer(1), info(1, 10));
+        state.info_received(time, peer(1), info(2, 30));
+        state.info_received(time, peer(2), info(2, 30));
+        state.info_received(time, peer(2), info(3, 20));
+        state.info_received(time, peer(3), info(4, 5));
+
+        let list = state.peers_next_transactions_to_fetch([peer(1), peer(2), peer(3)]);
+        // One transaction per peer, and the transaction with hash 2 only
+        // from one of the two peers having it
+        assert_eq!(
+            list,
+            vec![
+                (peer(1), info(2, 30).hash),
+                (peer(2), info(3, 20).hash),
+                (peer(3), info(4, 5).hash),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_transactions_already_fetching() {
+        let time = Timestamp::ZERO;
+        let mut state = TransactionPoolCandidatesState::new();
+        state.info_received(time, peer(1), info(1, 30));
+        state.info_received(time, peer(2), info(1, 30));
+        state.info_received(time, peer(2), info(2, 10));
+        state.fetch_pending(time, &peer(1), &info(1, 30).hash, 0);
+
+        let list = state.peers_next_transactions_to_fetch([peer(1), peer(2)]);
+        assert_eq!(list, vec![(peer(2), info(2, 10).hash)]);
+    }
+}
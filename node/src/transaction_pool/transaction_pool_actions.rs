@@ -1,7 +1,7 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use ledger::{
-    scan_state::transaction_logic::valid,
+    scan_state::{currency::Slot, transaction_logic::valid},
     transaction_pool::{
         diff::{self, BestTipDiff, DiffVerified},
         ValidCommandWithHash,
@@ -9,7 +9,7 @@ use ledger::{
     Account, AccountId,
 };
 use mina_core::{
-    transaction::{TransactionPoolMessageSource, TransactionWithHash},
+    transaction::{TransactionHash, TransactionPoolMessageSource, TransactionWithHash},
     ActionEvent,
 };
 use mina_p2p_messages::{
@@ -18,6 +18,7 @@ use mina_p2p_messages::{
 };
 use redux::Callback;
 use serde::{Deserialize, Serialize};
+use snark::user_command_verify::SnarkUserCommandVerifyError;
 
 use super::{candidate::TransactionPoolCandidateAction, PendingId};
 
@@ -45,8 +46,18 @@ pub enum TransactionPoolAction {
     VerifyError {
         errors: Vec<String>,
     },
+    /// The commands failed verification, or the verifier failed.
+    #[action_event(level = warn, fields(display(error)))]
+    SnarkVerifyError {
+        error: SnarkUserCommandVerifyError,
+        from_source: TransactionPoolMessageSource,
+    },
     BestTipChanged {
         best_tip_hash: v2::LedgerHash,
+        /// The new best tip doesn't extend the previous one. The entire pool
+        /// is revalidated, otherwise only the commands whose accounts are
+        /// referenced by the applied blocks are, by `ApplyTransitionFrontierDiff`.
+        is_reorg: bool,
     },
     BestTipChangedWithAccounts {
         accounts: BTreeMap<AccountId, Account>,
@@ -68,13 +79,39 @@ pub enum TransactionPoolAction {
         accounts: BTreeMap<AccountId, Account>,
         pending_id: PendingId,
     },
-    /// Rebroadcast locally generated pool items every 10 minutes. Do so for 50
-    /// minutes - at most 5 rebroadcasts - before giving up.
+    /// Commands of a diff received from peers, accepted or rejected by the
+    /// pool. Only reported, so that the outcome can be attributed to the
+    /// peer which delivered them in the stats.
+    #[action_event(level = debug)]
+    DiffApplied {
+        accepted: Vec<TransactionHash>,
+        rejected: Vec<TransactionHash>,
+    },
+    /// Broadcast the commands of a diff that didn't come from the gossip
+    /// network, once it has been applied to the pool.
     Rebroadcast {
         accepted: Vec<ValidCommandWithHash>,
         rejected: Vec<(ValidCommandWithHash, diff::Error)>,
         is_local: bool,
     },
+    /// Rebroadcast locally generated pool items every 10 minutes. Do so for 50
+    /// minutes - at most 5 rebroadcasts - or until they are included in the
+    /// best chain, before giving up.
+    #[action_event(level = debug)]
+    RebroadcastLocallyGenerated,
+    /// Remove the commands which have been in the pool for longer than its
+    /// `max_lifetime`, checked every minute.
+    #[action_event(level = trace)]
+    RemoveStale,
+    /// Remove the commands whose `valid_until` slot has passed, along with
+    /// the commands depending on them, whenever the global slot advances.
+    /// Otherwise they would stay in the pool until a new best tip.
+    #[action_event(level = trace)]
+    RemoveExpired,
+    /// Apply the zkApp relay policy to the gossip messages whose commands
+    /// weren't verified in time, see [`ledger::transaction_pool::ZkappRelayPolicy`].
+    #[action_event(level = trace)]
+    ZkappRelayCheck,
     CollectTransactionsByFee,
     #[action_event(level = trace)]
     P2pSendAll,
@@ -120,9 +157,26 @@ impl redux::EnablingCondition<crate::State> for TransactionPoolAction {
                         last_index,
                     )
                 }),
+            TransactionPoolAction::DiffApplied { accepted, rejected } => {
+                !(accepted.is_empty() && rejected.is_empty())
+            }
             TransactionPoolAction::Rebroadcast {
                 accepted, rejected, ..
             } => !(accepted.is_empty() && rejected.is_empty()),
+            TransactionPoolAction::RebroadcastLocallyGenerated => state
+                .transaction_pool
+                .should_rebroadcast_locally_generated(time),
+            TransactionPoolAction::RemoveStale => state.transaction_pool.should_remove_stale(time),
+            TransactionPoolAction::RemoveExpired => {
+                state.cur_global_slot_since_genesis().is_some_and(|slot| {
+                    state
+                        .transaction_pool
+                        .should_remove_expired(Slot::from_u32(slot))
+                })
+            }
+            TransactionPoolAction::ZkappRelayCheck => {
+                state.transaction_pool.should_check_zkapp_relay(time)
+            }
             _ => true,
         }
     }
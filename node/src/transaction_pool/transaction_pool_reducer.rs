@@ -2,7 +2,7 @@ use ledger::{
     scan_state::transaction_logic::{valid, GenericCommand, UserCommand},
     transaction_pool::{
         diff::{self, DiffVerified},
-        transaction_hash, ApplyDecision, TransactionPoolErrors,
+        transaction_hash, ApplyDecision, Eviction, TransactionPoolErrors, ZkappRelayPolicy,
     },
     Account, AccountId,
 };
@@ -12,17 +12,21 @@ use mina_core::{
     transaction::{Transaction, TransactionPoolMessageSource, TransactionWithHash},
 };
 use p2p::{
-    channels::transaction::P2pChannelsTransactionAction, BroadcastMessageId, P2pNetworkPubsubAction,
+    channels::transaction::P2pChannelsTransactionAction, BroadcastMessageId,
+    P2pNetworkPubsubAction, P2pNetworkPubsubMessageCacheId,
+};
+use redux::{callback, Dispatcher, Timestamp};
+use snark::user_command_verify::{
+    SnarkUserCommandVerifyAction, SnarkUserCommandVerifyError, SnarkUserCommandVerifyId,
 };
-use redux::callback;
-use snark::user_command_verify::{SnarkUserCommandVerifyAction, SnarkUserCommandVerifyId};
 use std::collections::{BTreeMap, BTreeSet};
 
-use crate::{BlockProducerAction, RpcAction};
+use crate::{Action, BlockProducerAction, RpcAction, State};
 
 use super::{
     PendingId, TransactionPoolAction, TransactionPoolActionWithMetaRef,
-    TransactionPoolEffectfulAction, TransactionPoolState, TransactionState,
+    TransactionPoolEffectfulAction, TransactionPoolState, TransactionState, ZkappRelayStatus,
+    ZKAPP_RELAY_MAX_AGE,
 };
 
 impl TransactionPoolState {
@@ -76,6 +80,16 @@ impl TransactionPoolState {
                     .iter()
                     .flat_map(UserCommand::accounts_referenced)
                     .collect::<BTreeSet<_>>();
+                if let TransactionPoolMessageSource::Pubsub { id } = from_source {
+                    let has_zkapp = commands
+                        .iter()
+                        .any(|cmd| matches!(cmd, UserCommand::ZkAppCommand(_)));
+                    if has_zkapp {
+                        substate
+                            .zkapp_relay
+                            .insert(*id, (meta.time(), ZkappRelayStatus::Pending));
+                    }
+                }
                 let best_tip_hash = substate.best_tip_hash.clone().unwrap();
                 let pending_id = substate.make_action_pending(action);
 
@@ -114,7 +128,7 @@ impl TransactionPoolState {
 
                 match substate
                     .pool
-                    .prevalidate(diff)
+                    .prevalidate(diff, constraint_constants())
                     .and_then(|diff| substate.pool.convert_diff_to_verifiable(diff, accounts))
                 {
                     Ok(verifiable) => {
@@ -137,14 +151,44 @@ impl TransactionPoolState {
                             ),
                             on_error: callback!(
                                 on_snark_user_command_verify_error(
-                                    (req_id: SnarkUserCommandVerifyId, errors: Vec<String>)
+                                    (req_id: SnarkUserCommandVerifyId, error: SnarkUserCommandVerifyError, from_source: TransactionPoolMessageSource)
                                 ) -> crate::Action {
-                                    TransactionPoolAction::VerifyError { errors }
+                                    TransactionPoolAction::SnarkVerifyError { error, from_source }
                                 }
                             )
                         });
                     }
                     Err(e) => {
+                        let relay_status = match from_source {
+                            TransactionPoolMessageSource::Pubsub { id } => {
+                                substate.zkapp_relay.remove(id).map(|(_, status)| status)
+                            }
+                            _ => None,
+                        };
+                        let relay_policy = substate.pool.config.zkapp_relay.policy;
+                        if let (
+                            TransactionPoolErrors::LoadingVK(error),
+                            TransactionPoolMessageSource::Pubsub { id },
+                            Some(ZkappRelayStatus::Pending),
+                        ) = (&e, from_source, relay_status)
+                        {
+                            // The verification key might be available later,
+                            // so the commands aren't known to be invalid.
+                            if relay_policy != ZkappRelayPolicy::Delay {
+                                let dispatcher = state.into_dispatcher();
+                                dispatcher.push(TransactionPoolAction::VerifyError {
+                                    errors: vec![error.clone()],
+                                });
+                                Self::zkapp_relay_unverified(
+                                    dispatcher,
+                                    meta.time(),
+                                    relay_policy,
+                                    *id,
+                                    "verification key not available",
+                                );
+                                return;
+                            }
+                        }
                         let dispatch_errors = |errors: Vec<String>| {
                             let dispatcher = state.into_dispatcher();
                             dispatcher.push(TransactionPoolAction::VerifyError {
@@ -206,10 +250,60 @@ impl TransactionPoolState {
             TransactionPoolAction::VerifyError { .. } => {
                 // just logging the errors
             }
-            TransactionPoolAction::BestTipChanged { best_tip_hash } => {
-                let account_ids = substate.pool.get_accounts_to_revalidate_on_new_best_tip();
+            TransactionPoolAction::SnarkVerifyError { error, from_source } => {
+                let relay_status = match from_source {
+                    TransactionPoolMessageSource::Pubsub { id } => {
+                        substate.zkapp_relay.remove(id).map(|(_, status)| status)
+                    }
+                    _ => None,
+                };
+
+                let dispatcher = state.into_dispatcher();
+                match from_source {
+                    TransactionPoolMessageSource::Rpc { id } => {
+                        dispatcher.push(RpcAction::TransactionInjectFailure {
+                            rpc_id: *id,
+                            errors: vec![error.to_string()],
+                        });
+                    }
+                    TransactionPoolMessageSource::Pubsub { id } => {
+                        let message_id = Some(BroadcastMessageId::MessageId { message_id: *id });
+                        if error.is_deterministic() {
+                            dispatcher.push(P2pNetworkPubsubAction::RejectMessage {
+                                message_id,
+                                peer_id: None,
+                                reason: format!("Transaction diff rejected: {error}"),
+                            });
+                        } else if relay_status
+                            .is_none_or(|status| status == ZkappRelayStatus::Pending)
+                        {
+                            // The verifier failed, so the commands may be
+                            // valid and the sender isn't at fault.
+                            dispatcher.push(P2pNetworkPubsubAction::IgnoreMessage {
+                                message_id,
+                                reason: format!("Transaction diff not verified: {error}"),
+                            });
+                        }
+                    }
+                    TransactionPoolMessageSource::None => {}
+                }
+            }
+            TransactionPoolAction::BestTipChanged {
+                best_tip_hash,
+                is_reorg,
+            } => {
+                let is_first_best_tip = substate.best_tip_hash.is_none();
                 substate.best_tip_hash = Some(best_tip_hash.clone());
 
+                // When the best tip is extended, only accounts referenced by
+                // the new blocks can have a lower balance or another nonce,
+                // and these are revalidated when the diff is applied.
+                if !is_reorg && !is_first_best_tip {
+                    return;
+                }
+
+                let account_ids = substate.pool.get_accounts_to_revalidate_on_new_best_tip();
+
                 let dispatcher = state.into_dispatcher();
                 dispatcher.push(TransactionPoolEffectfulAction::FetchAccounts {
                     account_ids,
@@ -269,6 +363,12 @@ impl TransactionPoolState {
                     panic!()
                 };
                 let is_sender_local = from_source.is_sender_local();
+                let relay_status = match from_source {
+                    TransactionPoolMessageSource::Pubsub { id } => {
+                        substate.zkapp_relay.remove(&id).map(|(_, status)| status)
+                    }
+                    _ => None,
+                };
 
                 // Note(adonagy): Action for rebroadcast, in his action we can use forget_check
                 let (was_accepted, accepted, rejected) = match substate.pool.unsafe_apply(
@@ -279,10 +379,24 @@ impl TransactionPoolState {
                     accounts,
                     is_sender_local,
                 ) {
-                    Ok((ApplyDecision::Accept, accepted, rejected, dropped)) => {
+                    Ok((ApplyDecision::Accept, accepted, rejected, dropped, replacements)) => {
                         for hash in dropped {
                             substate.dpool.remove(&hash);
                         }
+                        for replacement in &replacements {
+                            let replaced = replacement.replaced.data.forget_check();
+                            let fee = replacement.replacement.data.forget_check().fee();
+                            crate::core::info!(
+                                meta.time();
+                                kind = "TransactionPoolCommandReplaced",
+                                summary = format!("replaced command {} with {}", replacement.replaced.hash, replacement.replacement.hash),
+                                fee_payer = replaced.fee_payer().public_key.into_address(),
+                                nonce = replaced.applicable_at_nonce().as_u32(),
+                                replaced_fee = replaced.fee().as_u64(),
+                                fee = fee.as_u64(),
+                                dropped_dependents = replacement.dropped_dependents,
+                            );
+                        }
                         for tx in &accepted {
                             substate.dpool.insert(TransactionState {
                                 time: meta.time(),
@@ -292,7 +406,7 @@ impl TransactionPoolState {
 
                         (true, accepted, rejected)
                     }
-                    Ok((ApplyDecision::Reject, accepted, rejected, _)) => {
+                    Ok((ApplyDecision::Reject, accepted, rejected, _, _)) => {
                         (false, accepted, rejected)
                     }
                     Err(e) => {
@@ -303,6 +417,13 @@ impl TransactionPoolState {
 
                 let dispatcher = state.into_dispatcher();
 
+                if !is_sender_local {
+                    dispatcher.push(TransactionPoolAction::DiffApplied {
+                        accepted: accepted.iter().map(|cmd| cmd.hash.clone()).collect(),
+                        rejected: rejected.iter().map(|(cmd, _)| cmd.hash.clone()).collect(),
+                    });
+                }
+
                 // TODO: use callbacks
                 match (was_accepted, from_source) {
                     (true, TransactionPoolMessageSource::Rpc { id }) => {
@@ -321,9 +442,13 @@ impl TransactionPoolState {
                         }
                     }
                     (true, TransactionPoolMessageSource::Pubsub { id }) => {
-                        dispatcher.push(P2pNetworkPubsubAction::BroadcastValidatedMessage {
-                            message_id: BroadcastMessageId::MessageId { message_id: id },
-                        });
+                        // Messages dropped or forwarded already by the zkApp
+                        // relay policy aren't relayed again.
+                        if relay_status.is_none_or(|status| status == ZkappRelayStatus::Pending) {
+                            dispatcher.push(P2pNetworkPubsubAction::BroadcastValidatedMessage {
+                                message_id: BroadcastMessageId::MessageId { message_id: id },
+                            });
+                        }
                     }
                     (false, TransactionPoolMessageSource::Rpc { id }) => {
                         dispatcher.push(RpcAction::TransactionInjectRejected {
@@ -437,6 +562,96 @@ impl TransactionPoolState {
                     });
                 }
             }
+            TransactionPoolAction::DiffApplied { .. } => {}
+            TransactionPoolAction::RebroadcastLocallyGenerated => {
+                let rebroadcastable = substate.rebroadcast_locally_generated(meta.time());
+
+                let dispatcher = state.into_dispatcher();
+
+                for cmd in rebroadcastable {
+                    dispatcher.push(P2pChannelsTransactionAction::Libp2pBroadcast {
+                        transaction: Box::new((&cmd).into()),
+                        nonce: 0,
+                        is_local: true,
+                    });
+                }
+            }
+            TransactionPoolAction::ZkappRelayCheck => {
+                let now = meta.time();
+                let policy = substate.pool.config.zkapp_relay.policy;
+                substate.zkapp_relay.retain(|_, (time, _)| {
+                    now.checked_sub(*time)
+                        .is_none_or(|dur| dur < ZKAPP_RELAY_MAX_AGE)
+                });
+                let expired = substate.zkapp_relay_expired(now).collect::<Vec<_>>();
+                let status = match policy {
+                    ZkappRelayPolicy::Drop => ZkappRelayStatus::Dropped,
+                    _ => ZkappRelayStatus::ForwardedUnverified,
+                };
+                for message_id in &expired {
+                    if let Some((_, relay_status)) = substate.zkapp_relay.get_mut(message_id) {
+                        *relay_status = status;
+                    }
+                }
+
+                let dispatcher = state.into_dispatcher();
+                for message_id in expired {
+                    Self::zkapp_relay_unverified(
+                        dispatcher,
+                        now,
+                        policy,
+                        message_id,
+                        "verification took too long",
+                    );
+                }
+            }
+            TransactionPoolAction::RemoveStale => {
+                substate.last_stale_check = meta.time();
+
+                match substate.pool.remove_stale(meta.time()) {
+                    Err(e) => bug_condition!("transaction pool::remove_stale failed: {:?}", e),
+                    Ok(dropped) => {
+                        if dropped.is_empty() {
+                            return;
+                        }
+                        let hashes = dropped
+                            .iter()
+                            .map(|tx| {
+                                substate.dpool.remove(&tx.hash);
+                                tx.hash.to_string()
+                            })
+                            .collect::<Vec<_>>();
+                        crate::core::info!(
+                            meta.time();
+                            kind = "TransactionPoolRemoveStale",
+                            summary = format!("removed {} stale commands from the pool", hashes.len()),
+                            hashes = format!("{hashes:?}"),
+                        );
+                    }
+                }
+            }
+            TransactionPoolAction::RemoveExpired => {
+                substate.last_expiry_check = Some(global_slot_from_genesis);
+
+                match substate.pool.remove_expired(global_slot_from_genesis) {
+                    Err(e) => bug_condition!("transaction pool::remove_expired failed: {:?}", e),
+                    Ok(evicted) => {
+                        for Eviction { command, reason } in evicted {
+                            substate.dpool.remove(&command.hash);
+                            let cmd = command.data.forget_check();
+                            crate::core::info!(
+                                meta.time();
+                                kind = "TransactionPoolCommandEvicted",
+                                summary = format!("evicted command {}: {reason}", command.hash),
+                                fee_payer = cmd.fee_payer().public_key.into_address(),
+                                nonce = cmd.applicable_at_nonce().as_u32(),
+                                reason = reason.to_string(),
+                                global_slot_since_genesis = global_slot_from_genesis.as_u32(),
+                            );
+                        }
+                    }
+                }
+            }
             TransactionPoolAction::CollectTransactionsByFee => {
                 let transaction_capacity =
                     2u64.pow(constraint_constants().transaction_capacity_log_2 as u32);
@@ -487,4 +702,35 @@ impl TransactionPoolState {
             }
         }
     }
+
+    /// Applies the relay policy to a gossip message whose zkApp commands
+    /// couldn't be verified in time.
+    fn zkapp_relay_unverified(
+        dispatcher: &mut Dispatcher<Action, State>,
+        time: Timestamp,
+        policy: ZkappRelayPolicy,
+        message_id: P2pNetworkPubsubMessageCacheId,
+        reason: &str,
+    ) {
+        let broadcast_id = BroadcastMessageId::MessageId { message_id };
+        match policy {
+            ZkappRelayPolicy::Drop => {
+                dispatcher.push(P2pNetworkPubsubAction::IgnoreMessage {
+                    message_id: Some(broadcast_id),
+                    reason: format!("zkApp commands not verified: {reason}"),
+                });
+            }
+            ZkappRelayPolicy::Delay => {}
+            ZkappRelayPolicy::ForwardUnverified => {
+                crate::core::warn!(
+                    time;
+                    kind = "TransactionPoolZkappForwardedUnverified",
+                    summary = format!("relaying message {message_id:?} with unverified zkApp commands: {reason}"),
+                );
+                dispatcher.push(P2pNetworkPubsubAction::BroadcastValidatedMessage {
+                    message_id: broadcast_id,
+                });
+            }
+        }
+    }
 }
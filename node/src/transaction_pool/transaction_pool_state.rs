@@ -3,18 +3,39 @@ use ledger::{
         currency::{Amount, Nonce, Slot},
         transaction_logic::valid::UserCommand,
     },
-    transaction_pool::{Config, ValidCommandWithHash},
-    AccountId,
+    transaction_pool::{
+        Config, SenderQueue, ValidCommandWithHash, ZkappRelayConfig, ZkappRelayPolicy,
+    },
+    AccountId, TokenId,
 };
 use mina_core::{consensus::ConsensusConstants, distributed_pool::DistributedPool};
 use mina_p2p_messages::v2::{self, TransactionHash};
+use mina_signer::CompressedPubKey;
+use p2p::P2pNetworkPubsubMessageCacheId;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
 
 use super::{candidate::TransactionPoolCandidatesState, TransactionPoolAction};
+use crate::account::AccountPublicKey;
 
 pub(super) type PendingId = u32;
 
+/// Interval between rebroadcasts of the locally generated commands.
+pub const LOCALLY_GENERATED_REBROADCAST_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Locally generated commands are rebroadcast until they are included in the
+/// best chain, or for this long after being added to the pool.
+pub const LOCALLY_GENERATED_REBROADCAST_TIMEOUT: Duration = Duration::from_secs(50 * 60);
+/// Interval between checks for commands staying in the pool for longer than
+/// its `max_lifetime`.
+pub const STALE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Gossip messages are kept for this long by the pubsub message cache, so
+/// the relay status of the ones whose commands are never verified is
+/// forgotten after that.
+pub const ZKAPP_RELAY_MAX_AGE: Duration = Duration::from_secs(5 * 60);
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TransactionPoolState {
     pub candidates: TransactionPoolCandidatesState,
@@ -24,6 +45,17 @@ pub struct TransactionPoolState {
     pub(super) pending_actions: BTreeMap<PendingId, TransactionPoolAction>,
     pub(super) pending_id: PendingId,
     pub(super) best_tip_hash: Option<v2::LedgerHash>,
+    pub(super) last_rebroadcast: redux::Timestamp,
+    pub(super) last_stale_check: redux::Timestamp,
+    /// Global slot since genesis at which expired commands were last
+    /// removed, see [`TransactionPoolAction::RemoveExpired`].
+    #[serde(default)]
+    pub(super) last_expiry_check: Option<Slot>,
+    /// Gossip messages with zkApp commands being verified, with the time
+    /// their verification started, see [`ZkappRelayPolicy`].
+    #[serde(default)]
+    pub(super) zkapp_relay:
+        BTreeMap<P2pNetworkPubsubMessageCacheId, (redux::Timestamp, ZkappRelayStatus)>,
     /// For debug only
     #[serde(skip)]
     pub(super) file: Option<std::fs::File>,
@@ -35,6 +67,17 @@ pub struct TransactionState {
     pub hash: TransactionHash,
 }
 
+/// Relay status of a gossip message with zkApp commands, until its commands
+/// are verified.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZkappRelayStatus {
+    Pending,
+    /// Not relayed, as the commands weren't verified in time.
+    Dropped,
+    /// Relayed before the commands were verified.
+    ForwardedUnverified,
+}
+
 impl AsRef<TransactionHash> for TransactionState {
     fn as_ref(&self) -> &TransactionHash {
         &self.hash
@@ -50,6 +93,10 @@ impl Clone for TransactionPoolState {
             pending_actions: self.pending_actions.clone(),
             pending_id: self.pending_id,
             best_tip_hash: self.best_tip_hash.clone(),
+            last_rebroadcast: self.last_rebroadcast,
+            last_stale_check: self.last_stale_check,
+            last_expiry_check: self.last_expiry_check,
+            zkapp_relay: self.zkapp_relay.clone(),
             file: None,
         }
     }
@@ -64,6 +111,10 @@ impl TransactionPoolState {
             pending_actions: Default::default(),
             pending_id: 0,
             best_tip_hash: None,
+            last_rebroadcast: redux::Timestamp::ZERO,
+            last_stale_check: redux::Timestamp::ZERO,
+            last_expiry_check: None,
+            zkapp_relay: Default::default(),
             file: None,
         }
     }
@@ -100,6 +151,100 @@ impl TransactionPoolState {
         self.pool.get_pending_amount_and_nonce()
     }
 
+    /// Pending commands of the fee payer `public_key`, or of all of them,
+    /// with whether they can be included in a block at `global_slots`.
+    pub fn sender_queues(
+        &self,
+        public_key: Option<&AccountPublicKey>,
+        (current_global_slot, global_slot_since_genesis): (Slot, Slot),
+    ) -> Vec<SenderQueue> {
+        let Some(public_key) = public_key else {
+            return self
+                .pool
+                .sender_queues(global_slot_since_genesis, current_global_slot);
+        };
+        // Fee payers always pay with the default token.
+        CompressedPubKey::try_from(public_key.clone())
+            .ok()
+            .map(|public_key| AccountId::new(public_key, TokenId::default()))
+            .and_then(|sender| {
+                self.pool
+                    .sender_queue(&sender, global_slot_since_genesis, current_global_slot)
+            })
+            .into_iter()
+            .collect()
+    }
+
+    /// Commands submitted to this node, with the time they were added to the
+    /// pool and whether they were included in the best chain.
+    pub fn locally_generated(
+        &self,
+    ) -> impl Iterator<Item = (&ValidCommandWithHash, redux::Timestamp, bool)> {
+        self.pool.locally_generated()
+    }
+
+    pub fn should_rebroadcast_locally_generated(&self, now: redux::Timestamp) -> bool {
+        now.checked_sub(self.last_rebroadcast)
+            .is_some_and(|dur| dur >= LOCALLY_GENERATED_REBROADCAST_INTERVAL)
+    }
+
+    /// Forgets the locally generated commands added to the pool more than
+    /// [`LOCALLY_GENERATED_REBROADCAST_TIMEOUT`] ago, and returns the
+    /// uncommitted ones left to rebroadcast.
+    pub(super) fn rebroadcast_locally_generated(
+        &mut self,
+        now: redux::Timestamp,
+    ) -> Vec<UserCommand> {
+        self.last_rebroadcast = now;
+        self.pool
+            .get_rebroadcastable(|time| {
+                now.checked_sub(*time)
+                    .is_some_and(|dur| dur > LOCALLY_GENERATED_REBROADCAST_TIMEOUT)
+            })
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Gossip messages whose zkApp commands weren't verified within the
+    /// relay budget, and to which the relay policy wasn't applied yet.
+    pub fn zkapp_relay_expired(
+        &self,
+        now: redux::Timestamp,
+    ) -> impl Iterator<Item = P2pNetworkPubsubMessageCacheId> + '_ {
+        let ZkappRelayConfig { policy, budget } = self.pool.config.zkapp_relay;
+        self.zkapp_relay
+            .iter()
+            .filter(move |(_, (time, status))| {
+                policy != ZkappRelayPolicy::Delay
+                    && *status == ZkappRelayStatus::Pending
+                    && now.checked_sub(*time).is_some_and(|dur| dur >= budget)
+            })
+            .map(|(message_id, _)| *message_id)
+    }
+
+    pub fn should_check_zkapp_relay(&self, now: redux::Timestamp) -> bool {
+        self.zkapp_relay_expired(now).next().is_some()
+            || self.zkapp_relay.values().any(|(time, _)| {
+                now.checked_sub(*time)
+                    .is_some_and(|dur| dur >= ZKAPP_RELAY_MAX_AGE)
+            })
+    }
+
+    pub fn should_remove_stale(&self, now: redux::Timestamp) -> bool {
+        self.pool.config.max_lifetime.is_some()
+            && now
+                .checked_sub(self.last_stale_check)
+                .is_some_and(|dur| dur >= STALE_CHECK_INTERVAL)
+    }
+
+    /// Whether the global slot since genesis advanced since expired
+    /// commands were last removed.
+    pub fn should_remove_expired(&self, global_slot_since_genesis: Slot) -> bool {
+        self.last_expiry_check
+            .is_none_or(|slot| slot < global_slot_since_genesis)
+    }
+
     fn next_pending_id(&mut self) -> PendingId {
         let id = self.pending_id;
         self.pending_id = self.pending_id.wrapping_add(1);
@@ -123,7 +268,7 @@ impl TransactionPoolState {
         }
     }
 
-    pub(super) fn global_slots(state: &crate::State) -> Option<(Slot, Slot)> {
+    pub(crate) fn global_slots(state: &crate::State) -> Option<(Slot, Slot)> {
         Some((
             Slot::from_u32(state.cur_global_slot()?),
             Slot::from_u32(state.cur_global_slot_since_genesis()?),
@@ -135,8 +280,122 @@ impl TransactionPoolState {
 mod tests {
     use super::{super::TransactionPoolActionWithMeta, *};
     use crate::State;
+    use ledger::{
+        gen_compressed,
+        scan_state::{
+            currency::{Balance, Fee},
+            transaction_logic::{
+                signed_command::{self, PaymentPayload, SignedCommand, SignedCommandPayload},
+                valid, Memo,
+            },
+        },
+        transaction_pool::{
+            diff::DiffVerified, transaction_hash::hash_command, DEFAULT_REPLACE_FEE,
+        },
+        Account, TokenId,
+    };
+    use mina_core::constants::{constraint_constants, PROTOCOL_CONSTANTS};
+    use mina_signer::Signature;
     use redux::Dispatcher;
 
+    fn pool_state() -> TransactionPoolState {
+        let config = Config {
+            trust_system: (),
+            pool_max_size: 3000,
+            slot_tx_end: None,
+            max_lifetime: None,
+            zkapp_relay: Default::default(),
+            replace_fee: DEFAULT_REPLACE_FEE,
+        };
+        let constants = ConsensusConstants::create(constraint_constants(), &PROTOCOL_CONSTANTS);
+        TransactionPoolState::new(config, &constants)
+    }
+
+    /// Adds a payment to the pool, as submitted to this node at `time`.
+    /// Returns its fee payer.
+    fn add_local_payment(
+        state: &mut TransactionPoolState,
+        time: redux::Timestamp,
+    ) -> CompressedPubKey {
+        let sender = gen_compressed();
+        let payload = SignedCommandPayload::create(
+            Fee::from_u64(10_000_000),
+            sender.clone(),
+            Nonce::zero(),
+            None,
+            Memo::empty(),
+            signed_command::Body::Payment(PaymentPayload {
+                receiver_pk: gen_compressed(),
+                amount: Amount::from_u64(1_000_000_000),
+            }),
+        );
+        let command = valid::UserCommand::SignedCommand(Box::new(SignedCommand {
+            payload,
+            signer: sender.clone(),
+            signature: Signature::dummy(),
+        }));
+        let id = AccountId::new(sender.clone(), TokenId::default());
+        let account = Account::create_with(id.clone(), Balance::from_u64(1_000_000_000_000));
+        let diff = DiffVerified {
+            list: vec![hash_command(command)],
+        };
+        let (_, accepted, rejected, _, _) = state
+            .pool
+            .unsafe_apply(
+                time,
+                Slot::zero(),
+                Slot::zero(),
+                &diff,
+                &[(id, account)].into_iter().collect(),
+                true,
+            )
+            .unwrap();
+        assert_eq!((accepted.len(), rejected.len()), (1, 0));
+        sender
+    }
+
+    #[test]
+    fn test_rebroadcast_locally_generated() {
+        let minutes = |m| redux::Timestamp::ZERO + Duration::from_secs(m * 60);
+        let mut state = pool_state();
+        add_local_payment(&mut state, minutes(0));
+        assert_eq!(state.locally_generated().count(), 1);
+
+        assert!(state.should_rebroadcast_locally_generated(minutes(10)));
+        assert_eq!(state.rebroadcast_locally_generated(minutes(10)).len(), 1);
+        assert!(!state.should_rebroadcast_locally_generated(minutes(15)));
+
+        // Rebroadcast until the timeout, then forgotten.
+        assert!(state.should_rebroadcast_locally_generated(minutes(50)));
+        assert_eq!(state.rebroadcast_locally_generated(minutes(50)).len(), 1);
+        assert!(state.rebroadcast_locally_generated(minutes(60)).is_empty());
+        assert_eq!(state.locally_generated().count(), 0);
+        // The command itself stays in the pool.
+        assert_eq!(state.size(), 1);
+    }
+
+    #[test]
+    fn test_sender_queues() {
+        let mut state = pool_state();
+        let sender = add_local_payment(&mut state, redux::Timestamp::ZERO);
+        add_local_payment(&mut state, redux::Timestamp::ZERO);
+        let global_slots = (Slot::zero(), Slot::zero());
+
+        assert_eq!(state.sender_queues(None, global_slots).len(), 2);
+
+        let public_key = AccountPublicKey::from(sender.clone());
+        let queues = state.sender_queues(Some(&public_key), global_slots);
+        assert_eq!(queues.len(), 1);
+        assert_eq!(queues[0].sender.public_key, sender);
+        assert_eq!(
+            queues[0].commands[0].status,
+            ledger::transaction_pool::SenderQueueCommandStatus::Includable
+        );
+
+        let unknown = AccountPublicKey::from(gen_compressed());
+        assert!(state.sender_queues(Some(&unknown), global_slots).is_empty());
+    }
+
     #[allow(unused)]
     #[test]
     fn test_replay_pool() {
@@ -6,10 +6,16 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ArchiveConfig {
     pub address: String,
+    /// Names of the storages the blocks are archived to.
+    #[serde(default)]
+    pub storages: Vec<String>,
 }
 
 impl ArchiveConfig {
-    pub fn new(work_dir: String) -> Self {
-        Self { address: work_dir }
+    pub fn new(work_dir: String, storages: Vec<String>) -> Self {
+        Self {
+            address: work_dir,
+            storages,
+        }
     }
 }
@@ -1,5 +1,30 @@
+use serde::{Deserialize, Serialize};
+
 use crate::ledger::write::BlockApplyResult;
 
 pub trait ArchiveService: redux::Service {
     fn send_to_archive(&mut self, data: BlockApplyResult);
+
+    /// Statistics of the uploads of precomputed blocks to each cloud
+    /// storage, `None` if the node doesn't archive blocks.
+    fn archive_upload_stats(&self) -> Option<Vec<ArchiveUploadStats>>;
+}
+
+/// Uploads of precomputed blocks to a cloud storage.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArchiveUploadStats {
+    pub storage: String,
+    /// Blocks waiting in the upload queue.
+    pub pending: usize,
+    /// For how long the oldest pending block has been waiting, in seconds.
+    /// Grows during an outage of the storage.
+    pub lag_secs: u64,
+    /// Blocks whose upload failed after being retried, set aside so that
+    /// they don't hold back the others. They are retried every hour.
+    pub quarantined: usize,
+    /// Blocks uploaded since the node started.
+    pub uploaded: u64,
+    /// Uploads that failed after being retried, since the node started.
+    pub failed: u64,
+    pub last_error: Option<String>,
 }
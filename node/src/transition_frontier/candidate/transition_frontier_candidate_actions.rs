@@ -1,7 +1,7 @@
 use mina_core::{
     action_event,
     block::{prevalidate::BlockPrevalidationError, ArcBlockWithHash},
-    consensus::consensus_take,
+    consensus::{consensus_take, ConsensusForkComparison},
     ActionEvent,
 };
 use mina_p2p_messages::v2::StateHash;
@@ -42,6 +42,10 @@ pub enum TransitionFrontierCandidateAction {
         req_id: SnarkBlockVerifyId,
         hash: StateHash,
     },
+    /// Recomputes the verify priorities of the candidates waiting to be
+    /// verified once the best tip changed, see
+    /// [`SnarkBlockVerifyPriority`](snark::block_verify::SnarkBlockVerifyPriority).
+    BlockSnarkVerifyPrioritiesUpdate,
     #[action_event(level = info)]
     BlockSnarkVerifySuccess {
         hash: StateHash,
@@ -51,6 +55,22 @@ pub enum TransitionFrontierCandidateAction {
         hash: StateHash,
         error: SnarkBlockVerifyError,
     },
+    /// Verified candidate compared with the current best tip.
+    #[action_event(level = info, fields(
+        tip_hash = display(&comparison.tip_hash),
+        candidate_hash = display(&comparison.candidate_hash),
+        take = comparison.take,
+        reason = debug(&comparison.reason),
+        tip_length = comparison.tip_length,
+        candidate_length = comparison.candidate_length,
+        tip_density = debug(&comparison.tip_density),
+        candidate_density = debug(&comparison.candidate_density),
+        tip_vrf_hash = display(&comparison.tip_vrf_hash),
+        candidate_vrf_hash = display(&comparison.candidate_vrf_hash),
+    ))]
+    ForkDecision {
+        comparison: ConsensusForkComparison,
+    },
     TransitionFrontierSyncTargetUpdate,
     Prune,
 }
@@ -80,6 +100,12 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierCandidateActio
                     .is_some_and(|block| block.status.is_prevalidated())
                     && state.snark.block_verify.jobs.contains(*req_id)
             }
+            TransitionFrontierCandidateAction::BlockSnarkVerifyPrioritiesUpdate => state
+                .transition_frontier
+                .candidates
+                .snark_verify_pending()
+                .next()
+                .is_some(),
             TransitionFrontierCandidateAction::BlockSnarkVerifySuccess { hash } => state
                 .transition_frontier
                 .candidates
@@ -90,6 +116,7 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierCandidateActio
                 .candidates
                 .get(hash)
                 .is_some_and(|block| block.status.is_snark_verify_pending()),
+            TransitionFrontierCandidateAction::ForkDecision { .. } => true,
             TransitionFrontierCandidateAction::TransitionFrontierSyncTargetUpdate => {
                 let Some(best_candidate) =
                     state.transition_frontier.candidates.best_verified_block()
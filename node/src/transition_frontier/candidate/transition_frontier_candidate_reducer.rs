@@ -1,8 +1,11 @@
 use mina_core::{
     block::{ArcBlockWithHash, BlockHash},
     bug_condition,
+    consensus::{consensus_compare, consensus_take},
+};
+use snark::block_verify::{
+    SnarkBlockVerifyAction, SnarkBlockVerifyError, SnarkBlockVerifyId, SnarkBlockVerifyPriority,
 };
-use snark::block_verify::{SnarkBlockVerifyAction, SnarkBlockVerifyError, SnarkBlockVerifyId};
 
 use crate::{
     transition_frontier::sync::{
@@ -12,7 +15,7 @@ use crate::{
         },
         TransitionFrontierSyncAction,
     },
-    WatchedAccountsAction,
+    RpcAction, WatchedAccountsAction,
 };
 
 use super::{
@@ -69,6 +72,12 @@ impl TransitionFrontierCandidatesState {
             }
             TransitionFrontierCandidateAction::BlockPrevalidateError { hash, error } => {
                 state.invalidate(hash, error.is_forever_invalid());
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcAction::BlockInjectPrevalidated {
+                    hash: hash.clone(),
+                    error: Some(error.clone()),
+                });
             }
             TransitionFrontierCandidateAction::BlockPrevalidateSuccess { hash } => {
                 state.update_status(hash, |_| TransitionFrontierCandidateStatus::Prevalidated);
@@ -78,9 +87,15 @@ impl TransitionFrontierCandidatesState {
                 };
 
                 // Dispatch
-                let dispatcher = state_context.into_dispatcher();
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                dispatcher.push(RpcAction::BlockInjectPrevalidated {
+                    hash: hash.clone(),
+                    error: None,
+                });
+                let priority = block_verify_priority(global_state, &block);
                 dispatcher.push(SnarkBlockVerifyAction::Init {
                     block: block.into(),
+                    priority,
                     on_init: redux::callback!(
                         on_received_block_snark_verify_init((hash: BlockHash, req_id: SnarkBlockVerifyId)) -> crate::Action {
                             TransitionFrontierCandidateAction::BlockSnarkVerifyPending { hash, req_id }
@@ -110,8 +125,20 @@ impl TransitionFrontierCandidatesState {
                     }
                 });
             }
-            TransitionFrontierCandidateAction::BlockSnarkVerifyError { hash, .. } => {
-                state.invalidate(hash, true);
+            TransitionFrontierCandidateAction::BlockSnarkVerifyPrioritiesUpdate => {
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let priorities = global_state
+                    .transition_frontier
+                    .candidates
+                    .snark_verify_pending()
+                    .map(|(req_id, block)| (req_id, block_verify_priority(global_state, block)))
+                    .collect();
+                dispatcher.push(SnarkBlockVerifyAction::PrioritiesUpdate { priorities });
+            }
+            TransitionFrontierCandidateAction::BlockSnarkVerifyError { hash, error } => {
+                // When the verifier failed, the block may be valid and is
+                // verified again if received again.
+                state.invalidate(hash, error.is_deterministic());
             }
             TransitionFrontierCandidateAction::BlockSnarkVerifySuccess { hash } => {
                 state.update_status(hash, |_| {
@@ -120,6 +147,29 @@ impl TransitionFrontierCandidatesState {
 
                 // Dispatch
                 let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let tip = global_state
+                    .transition_frontier
+                    .sync
+                    .best_tip()
+                    .or_else(|| global_state.transition_frontier.best_tip());
+                let candidate = global_state
+                    .transition_frontier
+                    .candidates
+                    .get(hash)
+                    .map(|s| &s.block);
+                if let (Some(tip), Some(candidate)) = (tip, candidate) {
+                    if tip.hash() != candidate.hash() {
+                        dispatcher.push(TransitionFrontierCandidateAction::ForkDecision {
+                            comparison: consensus_compare(
+                                tip.consensus_state(),
+                                candidate.consensus_state(),
+                                tip.hash(),
+                                candidate.hash(),
+                            ),
+                        });
+                    }
+                }
+
                 let Some(block) = global_state
                     .transition_frontier
                     .candidates
@@ -140,6 +190,9 @@ impl TransitionFrontierCandidatesState {
                 dispatcher
                     .push(TransitionFrontierCandidateAction::TransitionFrontierSyncTargetUpdate);
             }
+            TransitionFrontierCandidateAction::ForkDecision { comparison } => {
+                state.add_fork_decision(comparison.clone());
+            }
             TransitionFrontierCandidateAction::TransitionFrontierSyncTargetUpdate => {
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let Some(best_tip) = state.transition_frontier.candidates.best_verified_block()
@@ -171,6 +224,10 @@ impl TransitionFrontierCandidatesState {
                     blocks_inbetween,
                     on_success: None,
                 });
+                // Candidates extending the previous best tip may now be on
+                // a side chain, and the other way around.
+                dispatcher
+                    .push(TransitionFrontierCandidateAction::BlockSnarkVerifyPrioritiesUpdate);
             }
             TransitionFrontierCandidateAction::Prune => {
                 state.prune();
@@ -179,6 +236,34 @@ impl TransitionFrontierCandidatesState {
     }
 }
 
+/// Blocks which would become our best tip are verified first, so that we
+/// don't wait for side chain blocks to be verified before switching to a
+/// better chain. Recomputed for the queued blocks whenever the best tip
+/// changes.
+fn block_verify_priority(
+    state: &crate::State,
+    block: &ArcBlockWithHash,
+) -> SnarkBlockVerifyPriority {
+    let tip = state
+        .transition_frontier
+        .sync
+        .best_tip()
+        .or_else(|| state.transition_frontier.best_tip());
+    let is_best_tip_candidate = tip.is_none_or(|tip| {
+        consensus_take(
+            tip.consensus_state(),
+            block.consensus_state(),
+            tip.hash(),
+            block.hash(),
+        )
+    });
+    if is_best_tip_candidate {
+        SnarkBlockVerifyPriority::High
+    } else {
+        SnarkBlockVerifyPriority::Low
+    }
+}
+
 /// Decide if the time-reception check should be done for this block or not.
 ///
 /// The check is skipped if the block's global_slot is greater than the
@@ -187,7 +272,13 @@ impl TransitionFrontierCandidatesState {
 /// Ideally we would differentiate between requested blocks and blocks
 /// received from gossip, but this difference doesn't really exist
 /// in the WebRTC transport, hence this heuristic.
+///
+/// Always allowed when importing historical blocks.
 pub fn allow_block_too_late(state: &crate::State, block: &ArcBlockWithHash) -> bool {
+    if state.transition_frontier.config.allow_historical_blocks {
+        return true;
+    }
+
     let (has_greater_blobal_slot, diff_with_best_tip) = state
         .transition_frontier
         .best_tip()
@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
 use mina_p2p_messages::v2::StateHash;
 use serde::{Deserialize, Serialize};
@@ -6,7 +6,8 @@ use serde::{Deserialize, Serialize};
 use mina_core::{
     block::ArcBlockWithHash,
     consensus::{
-        consensus_take, ConsensusLongRangeForkDecisionReason, ConsensusShortRangeForkDecisionReason,
+        consensus_take, ConsensusForkComparison, ConsensusLongRangeForkDecisionReason,
+        ConsensusShortRangeForkDecisionReason,
     },
 };
 
@@ -14,6 +15,9 @@ use crate::{
     snark::block_verify::SnarkBlockVerifyId, transition_frontier::TransitionFrontierState,
 };
 
+/// Number of the most recent fork decisions kept in the state.
+const FORK_DECISIONS_HISTORY_LEN: usize = 128;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ConsensusShortRangeForkDecision {
     TakeNoBestTip,
@@ -131,6 +135,10 @@ pub struct TransitionFrontierCandidatesState {
     /// consume less memory while still preventing us from triggering
     /// revalidation for an invalid block if we receive it on p2p again.
     invalid: BTreeMap<StateHash, u32>,
+    /// Most recent comparisons between the best tip and verified
+    /// candidates, oldest first.
+    #[serde(default)]
+    fork_decisions: VecDeque<ConsensusForkComparison>,
 }
 
 impl TransitionFrontierCandidatesState {
@@ -229,6 +237,17 @@ impl TransitionFrontierCandidatesState {
         });
     }
 
+    pub(super) fn add_fork_decision(&mut self, comparison: ConsensusForkComparison) {
+        if self.fork_decisions.len() >= FORK_DECISIONS_HISTORY_LEN {
+            self.fork_decisions.pop_front();
+        }
+        self.fork_decisions.push_back(comparison);
+    }
+
+    pub fn fork_decisions(&self) -> impl DoubleEndedIterator<Item = &ConsensusForkComparison> {
+        self.fork_decisions.iter()
+    }
+
     pub(super) fn best(&self) -> Option<&TransitionFrontierCandidateState> {
         self.ordered.last()
     }
@@ -240,6 +259,19 @@ impl TransitionFrontierCandidatesState {
             .find(|s| s.status.is_snark_verify_success())
     }
 
+    /// Candidates waiting for their snark verification, with the id of
+    /// their verify request.
+    pub fn snark_verify_pending(
+        &self,
+    ) -> impl Iterator<Item = (SnarkBlockVerifyId, &ArcBlockWithHash)> {
+        self.ordered.iter().filter_map(|s| match &s.status {
+            TransitionFrontierCandidateStatus::SnarkVerifyPending { req_id, .. } => {
+                Some((*req_id, &s.block))
+            }
+            _ => None,
+        })
+    }
+
     pub fn is_chain_proof_needed(&self, hash: &StateHash) -> bool {
         self.get(hash).is_some_and(|s| s.chain_proof.is_none())
     }
@@ -119,6 +119,23 @@ impl GenesisConfig {
         }
     }
 
+    /// Genesis of a local development chain starting at `timestamp_ms`, in
+    /// which [`Self::dev_local_key`] holds the stake and
+    /// [`Self::DEV_LOCAL_BALANCE`] mina.
+    pub fn dev_local(timestamp_ms: u64) -> Self {
+        Self::BalancesDelegateTable {
+            table: vec![(Self::DEV_LOCAL_BALANCE, vec![])],
+            constants: Self::default_constants(timestamp_ms),
+        }
+    }
+
+    pub const DEV_LOCAL_BALANCE: u64 = 1_000_000_000;
+
+    /// Funded account of [`Self::dev_local`], also used to produce its blocks.
+    pub fn dev_local_key() -> AccountSecretKey {
+        AccountSecretKey::deterministic(0)
+    }
+
     // This is a stub for the moment until PR #420 is merged, which implements this for
     // real. In case of conflict, delete this stub and put the real implementation here.
     pub fn protocol_constants(&self) -> Result<ProtocolConstants, time::error::Parse> {
@@ -492,8 +509,19 @@ impl GenesisConfig {
         Self::build_ledger_from_accounts(accounts)
     }
 
+    /// Builds the ledger and hashes it, hashing each level of the tree in
+    /// parallel.
     fn build_ledger_from_accounts(
         accounts: impl IntoIterator<Item = Result<ledger::Account, InvalidBigInt>>,
+    ) -> Result<(ledger::Mask, v2::CurrencyAmountStableV1), InvalidBigInt> {
+        let (mut mask, total_currency) = Self::insert_accounts(accounts)?;
+        mask.recompute_hashes_parallel();
+
+        Ok((mask, total_currency))
+    }
+
+    fn insert_accounts(
+        accounts: impl IntoIterator<Item = Result<ledger::Account, InvalidBigInt>>,
     ) -> Result<(ledger::Mask, v2::CurrencyAmountStableV1), InvalidBigInt> {
         let db =
             ledger::Database::create_with_token_owners(constraint_constants().ledger_depth as u8);
@@ -518,7 +546,7 @@ impl GenesisConfig {
         accounts: impl IntoIterator<Item = Result<ledger::Account, InvalidBigInt>>,
         hashes: Vec<(u64, Fp)>,
     ) -> Result<(ledger::Mask, v2::CurrencyAmountStableV1), InvalidBigInt> {
-        let (mask, total_currency) = Self::build_ledger_from_accounts(accounts)?;
+        let (mask, total_currency) = Self::insert_accounts(accounts)?;
 
         // Must happen after the accounts have been set to avoid
         // cache invalidations.
@@ -552,14 +580,22 @@ fn create_genesis_producer_stake_proof(
     (&sparse_ledger).into()
 }
 
+/// Ledger cached on disk, in the `ledgers` directory of the cache, so that
+/// it is loaded without hashing it again.
 #[derive(Debug, BinProtRead, BinProtWrite)]
-struct LedgerAccountsWithHash {
-    ledger_hash: LedgerHash,
-    accounts: Vec<MinaBaseAccountBinableArgStableV2>,
-    hashes: Vec<(u64, LedgerHash)>,
+pub struct LedgerAccountsWithHash {
+    pub ledger_hash: LedgerHash,
+    /// Accounts ordered by their index in the ledger.
+    pub accounts: Vec<MinaBaseAccountBinableArgStableV2>,
+    /// Inner hashes of the ledger, by their index in the Merkle tree.
+    pub hashes: Vec<(u64, LedgerHash)>,
 }
 
 impl LedgerAccountsWithHash {
+    pub fn read<R: Read>(mut reader: R) -> Result<Self, binprot::Error> {
+        Self::binprot_read(&mut reader)
+    }
+
     fn cache(&self) -> Result<(), std::io::Error> {
         let cache_dir = mina_cache_path("ledgers").unwrap();
         let cache_file = cache_dir.join(format!("{}.bin", self.ledger_hash));
@@ -10,6 +10,12 @@ pub use transition_frontier_config::*;
 mod transition_frontier_state;
 pub use transition_frontier_state::*;
 
+mod transition_frontier_root_history;
+pub use transition_frontier_root_history::*;
+
+mod transition_frontier_ledger_hashes_check;
+pub use transition_frontier_ledger_hashes_check::*;
+
 mod transition_frontier_actions;
 pub use transition_frontier_actions::*;
 
@@ -17,3 +23,6 @@ mod transition_frontier_reducer;
 
 mod transition_frontier_effects;
 pub use transition_frontier_effects::*;
+
+mod transition_frontier_event_service;
+pub use transition_frontier_event_service::*;
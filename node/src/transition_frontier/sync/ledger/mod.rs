@@ -12,6 +12,9 @@ mod transition_frontier_sync_ledger_reducer;
 mod transition_frontier_sync_ledger_effects;
 pub use transition_frontier_sync_ledger_effects::*;
 
+mod transition_frontier_sync_ledger_snapshot_service;
+pub use transition_frontier_sync_ledger_snapshot_service::*;
+
 use mina_core::block::ArcBlockWithHash;
 use mina_p2p_messages::v2::{LedgerHash, MinaBaseStagedLedgerHashStableV1, StateHash};
 use serde::{Deserialize, Serialize};
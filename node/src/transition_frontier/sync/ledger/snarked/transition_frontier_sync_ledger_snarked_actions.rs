@@ -3,7 +3,7 @@ use mina_p2p_messages::v2::{LedgerHash, MinaBaseAccountBinableArgStableV2};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    ledger::{LedgerAddress, LEDGER_DEPTH},
+    ledger::{ledger_depth, LedgerAddress},
     p2p::{channels::rpc::P2pRpcId, PeerId},
     transition_frontier::sync::ledger::TransitionFrontierSyncLedgerState,
 };
@@ -29,6 +29,26 @@ pub enum TransitionFrontierSyncLedgerSnarkedAction {
     Pending,
     PeersQuery,
 
+    // For downloading the ledger from the node we bootstrap from
+    SnapshotFetchInit,
+    #[action_event(level = warn, fields(ledger_hash = display(ledger_hash), error))]
+    SnapshotFetchError {
+        ledger_hash: LedgerHash,
+        error: String,
+    },
+    /// Accounts of the snapshot were written into the ledger being synced.
+    #[action_event(level = info, fields(ledger_hash = display(ledger_hash), computed_hash = display(computed_hash), count))]
+    SnapshotReceived {
+        ledger_hash: LedgerHash,
+        computed_hash: LedgerHash,
+        count: u64,
+    },
+    #[action_event(level = info, fields(ledger_hash = display(ledger_hash), count))]
+    SnapshotAccepted {
+        ledger_hash: LedgerHash,
+        count: u64,
+    },
+
     // For NumAccounts query
     PeerQueryNumAccountsInit {
         peer_id: PeerId,
@@ -162,6 +182,35 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
                 peers_available && sync_next_available
             }
 
+            // snapshot
+            TransitionFrontierSyncLedgerSnarkedAction::SnapshotFetchInit => {
+                state.transition_frontier.config.bootstrap_from.is_some()
+                    && state
+                        .transition_frontier
+                        .sync
+                        .ledger()
+                        .and_then(|s| s.snarked()?.num_accounts_pending())
+                        .is_some_and(|pending| pending.attempts.is_empty())
+            }
+            TransitionFrontierSyncLedgerSnarkedAction::SnapshotFetchError {
+                ledger_hash, ..
+            }
+            | TransitionFrontierSyncLedgerSnarkedAction::SnapshotReceived { ledger_hash, .. }
+            | TransitionFrontierSyncLedgerSnarkedAction::SnapshotAccepted { ledger_hash, .. } => {
+                state
+                    .transition_frontier
+                    .sync
+                    .ledger()
+                    .and_then(|s| s.snarked())
+                    .is_some_and(|s| match s {
+                        TransitionFrontierSyncLedgerSnarkedState::SnapshotFetchPending {
+                            target,
+                            ..
+                        } => &target.snarked_ledger_hash == ledger_hash,
+                        _ => false,
+                    })
+            }
+
             // num accounts
             TransitionFrontierSyncLedgerSnarkedAction::PeerQueryNumAccountsInit { peer_id } => None
                 .or_else(|| {
@@ -402,7 +451,7 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
                 sender,
                 ..
             } => {
-                address.length() < LEDGER_DEPTH - ACCOUNT_SUBTREE_HEIGHT
+                address.length() < ledger_depth() - ACCOUNT_SUBTREE_HEIGHT
                     && state
                         .transition_frontier
                         .sync
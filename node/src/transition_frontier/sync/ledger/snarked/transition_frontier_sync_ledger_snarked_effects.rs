@@ -1,6 +1,12 @@
 use redux::ActionMeta;
 
-use crate::{ledger::hash_node_at_depth, Store};
+use crate::{
+    ledger::hash_node_at_depth,
+    transition_frontier::sync::ledger::{
+        LedgerSnapshotQuery, TransitionFrontierSyncLedgerSnapshotService,
+    },
+    Store,
+};
 
 use super::{
     TransitionFrontierSyncLedgerSnarkedAction, TransitionFrontierSyncLedgerSnarkedService,
@@ -9,12 +15,66 @@ use super::{
 impl TransitionFrontierSyncLedgerSnarkedAction {
     pub fn effects<S>(&self, _: &ActionMeta, store: &mut Store<S>)
     where
-        S: redux::Service + TransitionFrontierSyncLedgerSnarkedService,
+        S: redux::Service
+            + TransitionFrontierSyncLedgerSnarkedService
+            + TransitionFrontierSyncLedgerSnapshotService,
     {
         match self {
             TransitionFrontierSyncLedgerSnarkedAction::Pending => {}
             TransitionFrontierSyncLedgerSnarkedAction::PeersQuery => {}
 
+            TransitionFrontierSyncLedgerSnarkedAction::SnapshotFetchInit => {
+                let Some((source, ledger_hash)) = None.or_else(|| {
+                    let state = store.state();
+                    let source = state.transition_frontier.config.bootstrap_from.clone()?;
+                    let snarked_ledger = state.transition_frontier.sync.ledger()?.snarked()?;
+                    Some((source, snarked_ledger.ledger_hash().clone()))
+                }) else {
+                    return;
+                };
+                store.service.ledger_snapshot_fetch(
+                    &source,
+                    LedgerSnapshotQuery::SnarkedLedger(ledger_hash),
+                );
+            }
+            TransitionFrontierSyncLedgerSnarkedAction::SnapshotFetchError { .. } => {}
+            TransitionFrontierSyncLedgerSnarkedAction::SnapshotReceived {
+                ledger_hash,
+                computed_hash,
+                count,
+            } => {
+                // The snapshot is signed by the node we bootstrap from, but
+                // we still only accept it if the accounts hash to the
+                // ledger hash that the best tip commits to.
+                if computed_hash != ledger_hash {
+                    store.dispatch(
+                        TransitionFrontierSyncLedgerSnarkedAction::SnapshotFetchError {
+                            ledger_hash: ledger_hash.clone(),
+                            error: format!("snapshot accounts hash to {computed_hash}"),
+                        },
+                    );
+                    return;
+                }
+
+                if let Err(error) = store.service.compute_snarked_ledger_hashes(ledger_hash) {
+                    store.dispatch(
+                        TransitionFrontierSyncLedgerSnarkedAction::SnapshotFetchError {
+                            ledger_hash: ledger_hash.clone(),
+                            error,
+                        },
+                    );
+                    return;
+                }
+
+                store.dispatch(
+                    TransitionFrontierSyncLedgerSnarkedAction::SnapshotAccepted {
+                        ledger_hash: ledger_hash.clone(),
+                        count: *count,
+                    },
+                );
+            }
+            TransitionFrontierSyncLedgerSnarkedAction::SnapshotAccepted { .. } => {}
+
             TransitionFrontierSyncLedgerSnarkedAction::PeerQueryNumAccountsInit { .. } => {}
             TransitionFrontierSyncLedgerSnarkedAction::PeerQueryNumAccountsPending { .. } => {}
             TransitionFrontierSyncLedgerSnarkedAction::PeerQueryNumAccountsRetry { .. } => {}
@@ -10,7 +10,7 @@ use rand::prelude::*;
 
 use crate::{
     ledger::{
-        ledger_empty_hash_at_depth, tree_height_for_num_accounts, LedgerAddress, LEDGER_DEPTH,
+        ledger_depth, ledger_empty_hash_at_depth, tree_height_for_num_accounts, LedgerAddress,
     },
     Action, State,
 };
@@ -37,10 +37,50 @@ impl TransitionFrontierSyncLedgerSnarkedState {
             TransitionFrontierSyncLedgerSnarkedAction::Pending => {
                 // handled in parent reducer. TODO(refactor): should have a callback instead?
 
+                // Dispatch
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                if !dispatcher.push_if_enabled(
+                    TransitionFrontierSyncLedgerSnarkedAction::SnapshotFetchInit,
+                    global_state,
+                    meta.time(),
+                ) {
+                    dispatcher.push(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
+                }
+            }
+            TransitionFrontierSyncLedgerSnarkedAction::SnapshotFetchInit => {
+                let Self::NumAccountsPending { target, .. } = state else {
+                    return;
+                };
+                *state = Self::SnapshotFetchPending {
+                    time: meta.time(),
+                    target: target.clone(),
+                };
+            }
+            TransitionFrontierSyncLedgerSnarkedAction::SnapshotFetchError { .. } => {
+                let Self::SnapshotFetchPending { target, .. } = state else {
+                    return;
+                };
+                // Fall back to syncing the ledger from peers.
+                *state = Self::pending(meta.time(), target.clone());
+
                 // Dispatch
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
             }
+            TransitionFrontierSyncLedgerSnarkedAction::SnapshotReceived { .. } => {}
+            TransitionFrontierSyncLedgerSnarkedAction::SnapshotAccepted { .. } => {
+                let Self::SnapshotFetchPending { target, .. } = state else {
+                    return;
+                };
+                *state = Self::MerkleTreeSyncSuccess {
+                    time: meta.time(),
+                    target: target.clone(),
+                };
+
+                // Dispatch
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(TransitionFrontierSyncLedgerSnarkedAction::Success);
+            }
             TransitionFrontierSyncLedgerSnarkedAction::PeersQuery => {
                 let mut retry_addresses: Vec<_> = state.sync_address_retry_iter().collect();
                 let mut addresses: Vec<_> = state.sync_address_query_iter().collect();
@@ -323,7 +363,7 @@ impl TransitionFrontierSyncLedgerSnarkedState {
 
                 // We know at which node to begin querying, so we skip all the intermediary depths
                 let first_node_address = ledger::Address::first(
-                    LEDGER_DEPTH.saturating_sub(tree_height_for_num_accounts(*num_accounts)),
+                    ledger_depth().saturating_sub(tree_height_for_num_accounts(*num_accounts)),
                 );
                 let expected_hash = contents_hash.clone();
                 let first_query = (first_node_address, expected_hash);
@@ -676,7 +716,7 @@ fn peer_query_address_init(
         return;
     };
 
-    let query = if address.length() >= LEDGER_DEPTH - ACCOUNT_SUBTREE_HEIGHT {
+    let query = if address.length() >= ledger_depth() - ACCOUNT_SUBTREE_HEIGHT {
         MinaLedgerSyncLedgerQueryStableV1::WhatContents(address.clone().into())
     } else {
         MinaLedgerSyncLedgerQueryStableV1::WhatChildHashes(address.clone().into())
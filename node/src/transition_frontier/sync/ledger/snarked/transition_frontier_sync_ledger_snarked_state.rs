@@ -19,6 +19,12 @@ static SYNC_QUERY_EMPTY: BTreeMap<LedgerAddress, LedgerHash> = BTreeMap::new();
 #[serde_with::serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum TransitionFrontierSyncLedgerSnarkedState {
+    /// Downloading the whole ledger from the trusted node the node was
+    /// configured to bootstrap from.
+    SnapshotFetchPending {
+        time: Timestamp,
+        target: SyncLedgerTarget,
+    },
     NumAccountsPending {
         time: Timestamp,
         target: SyncLedgerTarget,
@@ -132,7 +138,8 @@ impl TransitionFrontierSyncLedgerSnarkedState {
 
     pub fn is_pending(&self) -> bool {
         match self {
-            Self::NumAccountsPending { .. }
+            Self::SnapshotFetchPending { .. }
+            | Self::NumAccountsPending { .. }
             | Self::MerkleTreeSyncPending { .. }
             | Self::NumAccountsSuccess { .. }
             | Self::MerkleTreeSyncSuccess { .. } => true,
@@ -142,7 +149,8 @@ impl TransitionFrontierSyncLedgerSnarkedState {
 
     pub fn target(&self) -> &SyncLedgerTarget {
         match self {
-            Self::NumAccountsPending { target, .. }
+            Self::SnapshotFetchPending { target, .. }
+            | Self::NumAccountsPending { target, .. }
             | Self::MerkleTreeSyncPending { target, .. }
             | Self::NumAccountsSuccess { target, .. }
             | Self::MerkleTreeSyncSuccess { target, .. }
@@ -218,7 +226,9 @@ impl TransitionFrontierSyncLedgerSnarkedState {
 
     pub fn estimation(&self) -> Option<LedgerSyncProgress> {
         match self {
-            Self::NumAccountsPending { .. } | Self::NumAccountsSuccess { .. } => None,
+            Self::SnapshotFetchPending { .. }
+            | Self::NumAccountsPending { .. }
+            | Self::NumAccountsSuccess { .. } => None,
             Self::MerkleTreeSyncPending {
                 total_accounts_expected,
                 synced_accounts_count,
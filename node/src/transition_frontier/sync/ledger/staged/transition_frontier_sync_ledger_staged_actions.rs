@@ -26,6 +26,16 @@ pub type TransitionFrontierSyncLedgerStagedActionWithMetaRef<'a> =
 #[action_event(level = info)]
 pub enum TransitionFrontierSyncLedgerStagedAction {
     PartsFetchPending,
+    PartsSnapshotFetchInit,
+    #[action_event(level = warn, fields(block_hash = display(block_hash), error))]
+    PartsSnapshotFetchError {
+        block_hash: v2::StateHash,
+        error: String,
+    },
+    PartsSnapshotFetchSuccess {
+        block_hash: v2::StateHash,
+        parts: Arc<StagedLedgerAuxAndPendingCoinbases>,
+    },
     PartsPeerFetchInit,
     PartsPeerFetchPending {
         peer_id: PeerId,
@@ -78,6 +88,34 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerStag
                     }
                     _ => false,
                 }),
+            TransitionFrontierSyncLedgerStagedAction::PartsSnapshotFetchInit => {
+                state.transition_frontier.config.bootstrap_from.is_some()
+                    && state
+                        .transition_frontier
+                        .sync
+                        .ledger()
+                        .and_then(|s| s.staged()?.fetch_attempts())
+                        .is_some_and(|attempts| attempts.is_empty())
+            }
+            TransitionFrontierSyncLedgerStagedAction::PartsSnapshotFetchError {
+                block_hash,
+                ..
+            }
+            | TransitionFrontierSyncLedgerStagedAction::PartsSnapshotFetchSuccess {
+                block_hash,
+                ..
+            } => state
+                .transition_frontier
+                .sync
+                .ledger()
+                .and_then(|s| s.staged())
+                .is_some_and(|s| match s {
+                    TransitionFrontierSyncLedgerStagedState::PartsSnapshotFetchPending {
+                        target,
+                        ..
+                    } => &target.staged.block_hash == block_hash,
+                    _ => false,
+                }),
             TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit => state
                 .transition_frontier
                 .sync
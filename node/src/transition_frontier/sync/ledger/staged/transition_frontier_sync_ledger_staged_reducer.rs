@@ -30,9 +30,68 @@ impl TransitionFrontierSyncLedgerStagedState {
         match action {
             TransitionFrontierSyncLedgerStagedAction::PartsFetchPending => {
                 // handled in parent. TODO(refactor) check this
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                if !dispatcher.push_if_enabled(
+                    TransitionFrontierSyncLedgerStagedAction::PartsSnapshotFetchInit,
+                    global_state,
+                    meta.time(),
+                ) {
+                    dispatcher.push(TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit);
+                }
+            }
+            TransitionFrontierSyncLedgerStagedAction::PartsSnapshotFetchInit => {
+                let Self::PartsFetchPending { target, .. } = state else {
+                    return;
+                };
+                *state = Self::PartsSnapshotFetchPending {
+                    time: meta.time(),
+                    target: target.clone(),
+                };
+            }
+            TransitionFrontierSyncLedgerStagedAction::PartsSnapshotFetchError { .. } => {
+                let Self::PartsSnapshotFetchPending { target, .. } = state else {
+                    return;
+                };
+                // Fall back to fetching the parts from peers.
+                *state = Self::pending(meta.time(), target.clone());
+
+                // Dispatch
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit);
             }
+            TransitionFrontierSyncLedgerStagedAction::PartsSnapshotFetchSuccess {
+                block_hash,
+                parts,
+            } => {
+                let Self::PartsSnapshotFetchPending { target, .. } = state else {
+                    return;
+                };
+
+                let expected_hash = &target.staged.hashes;
+                match StagedLedgerAuxAndPendingCoinbasesValidated::validate(parts, expected_hash) {
+                    StagedLedgerAuxAndPendingCoinbasesValidated::Valid(parts) => {
+                        *state = Self::PartsFetchSuccess {
+                            time: meta.time(),
+                            target: target.clone(),
+                            parts,
+                        };
+
+                        // Dispatch
+                        let dispatcher = state_context.into_dispatcher();
+                        dispatcher.push(TransitionFrontierSyncLedgerStagedAction::ReconstructInit);
+                    }
+                    StagedLedgerAuxAndPendingCoinbasesValidated::Invalid(_) => {
+                        // Dispatch
+                        let dispatcher = state_context.into_dispatcher();
+                        dispatcher.push(
+                            TransitionFrontierSyncLedgerStagedAction::PartsSnapshotFetchError {
+                                block_hash: block_hash.clone(),
+                                error: "snapshot doesn't match the staged ledger hash".to_owned(),
+                            },
+                        );
+                    }
+                }
+            }
             TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit => {
                 let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
                 let Some((p2p, target_best_tip, staged_ledger, fetch_attempts)) =
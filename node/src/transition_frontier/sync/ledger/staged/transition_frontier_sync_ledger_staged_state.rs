@@ -17,6 +17,12 @@ use super::{
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum TransitionFrontierSyncLedgerStagedState {
+    /// Downloading pieces required to reconstruct staged ledger from the
+    /// trusted node the node was configured to bootstrap from.
+    PartsSnapshotFetchPending {
+        time: Timestamp,
+        target: SyncLedgerTargetWithStaged,
+    },
     /// Fetching pieces required to reconstruct staged ledger from
     /// snarked ledger.
     PartsFetchPending {
@@ -93,6 +99,7 @@ impl TransitionFrontierSyncLedgerStagedState {
 
     pub fn target(&self) -> &SyncLedgerTargetWithStaged {
         match self {
+            Self::PartsSnapshotFetchPending { target, .. } => target,
             Self::PartsFetchPending { target, .. } => target,
             Self::PartsFetchSuccess { target, .. } => target,
             Self::ReconstructEmpty { target, .. } => target,
@@ -104,7 +111,10 @@ impl TransitionFrontierSyncLedgerStagedState {
     }
 
     pub fn is_parts_fetched(&self) -> bool {
-        !matches!(self, Self::PartsFetchPending { .. })
+        !matches!(
+            self,
+            Self::PartsSnapshotFetchPending { .. } | Self::PartsFetchPending { .. }
+        )
     }
 
     pub fn target_with_parts(
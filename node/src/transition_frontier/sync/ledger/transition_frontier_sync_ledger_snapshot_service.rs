@@ -0,0 +1,217 @@
+use std::sync::Arc;
+
+use blake2::{
+    digest::{Update, VariableOutput},
+    Blake2bVar,
+};
+use mina_p2p_messages::v2::{LedgerHash, StateHash};
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::{
+    channels::rpc::StagedLedgerAuxAndPendingCoinbases,
+    identity::{PublicKey, SecretKey, Signature},
+    PeerId,
+};
+
+/// Header in which the serving node sends its signature of the snapshot,
+/// see [`LedgerSnapshotDigest`].
+pub const LEDGER_SNAPSHOT_SIGNATURE_HEADER: &str = "x-mina-snapshot-signature";
+
+pub trait TransitionFrontierSyncLedgerSnapshotService: redux::Service {
+    /// Download a ledger snapshot from the trusted node at `source`, which
+    /// the node was configured to bootstrap from. Result is sent as a
+    /// [`LedgerSnapshotEvent`].
+    ///
+    /// Snarked ledger accounts are written into the ledger being synced
+    /// as they are read, once the snapshot's signature is verified, so
+    /// the event only carries the hash they add up to.
+    fn ledger_snapshot_fetch(&mut self, source: &LedgerSnapshotSource, query: LedgerSnapshotQuery);
+}
+
+/// Trusted node to bootstrap from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LedgerSnapshotSource {
+    /// Url of the node's http server.
+    pub url: String,
+    /// Peer id of the node, whose key must have signed the snapshots.
+    pub peer_id: PeerId,
+}
+
+/// Snapshot served by a node to let other nodes bootstrap from it
+/// instead of syncing ledgers from peers.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum LedgerSnapshotQuery {
+    /// All accounts of the snarked ledger.
+    SnarkedLedger(LedgerHash),
+    /// Scan state and pending coinbases of the staged ledger at the block.
+    StagedLedgerParts(StateHash),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum LedgerSnapshot {
+    /// Accounts ordered by their index in the ledger.
+    SnarkedLedger(Vec<mina_p2p_messages::v2::MinaBaseAccountBinableArgStableV2>),
+    StagedLedgerParts(Arc<StagedLedgerAuxAndPendingCoinbases>),
+}
+
+/// Snapshots are only read if signed by the trusted node, but that's not
+/// enough to trust them. Snarked ledger accounts are accepted only if they
+/// hash to the ledger hash of the sync target, and staged ledger parts
+/// only if they match its staged ledger hash.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum LedgerSnapshotEvent {
+    SnarkedLedger {
+        ledger_hash: LedgerHash,
+        result: Result<LedgerSnapshotImported, String>,
+    },
+    StagedLedgerParts {
+        block_hash: StateHash,
+        result: Result<Arc<StagedLedgerAuxAndPendingCoinbases>, String>,
+    },
+}
+
+/// Snarked ledger accounts written into the ledger being synced.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LedgerSnapshotImported {
+    /// Hash of the ledger with the accounts written.
+    pub computed_hash: LedgerHash,
+    pub count: u64,
+}
+
+/// Digest of a served snapshot, fed with the response body as it's
+/// written or read. The node serving snapshots signs it, so that the
+/// downloaded snapshot can be checked against the key of the node it was
+/// meant to come from.
+pub struct LedgerSnapshotDigest {
+    query: LedgerSnapshotQuery,
+    hasher: Blake2bVar,
+}
+
+impl LedgerSnapshotDigest {
+    const DOMAIN: &'static [u8] = b"mina-ledger-snapshot:";
+
+    pub fn new(query: LedgerSnapshotQuery) -> Self {
+        Self {
+            query,
+            hasher: Blake2bVar::new(32).expect("Invalid Blake2bVar output size"),
+        }
+    }
+
+    pub fn update(&mut self, body: &[u8]) {
+        self.hasher.update(body);
+    }
+
+    /// Signed message, which binds the digest of the body to the query,
+    /// so that a signed snapshot can't be served for another ledger.
+    fn message(self) -> Vec<u8> {
+        let mut digest = [0u8; 32];
+        self.hasher
+            .finalize_variable(&mut digest)
+            .expect("Invalid Blake2bVar output size");
+        [
+            Self::DOMAIN,
+            self.query.to_string().as_bytes(),
+            b":",
+            &digest,
+        ]
+        .concat()
+    }
+
+    pub fn sign(self, secret_key: &mut SecretKey) -> Signature {
+        secret_key.sign(&self.message())
+    }
+
+    pub fn verify(self, public_key: &PublicKey, signature: &Signature) -> bool {
+        public_key.verify(&self.message(), signature)
+    }
+}
+
+impl std::fmt::Display for LedgerSnapshotQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SnarkedLedger(ledger_hash) => write!(f, "SnarkedLedger, {ledger_hash}"),
+            Self::StagedLedgerParts(block_hash) => write!(f, "StagedLedgerParts, {block_hash}"),
+        }
+    }
+}
+
+impl std::fmt::Display for LedgerSnapshotEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LedgerSnapshot, ")?;
+        let is_ok = match self {
+            Self::SnarkedLedger {
+                ledger_hash,
+                result,
+            } => {
+                write!(f, "SnarkedLedger, {ledger_hash}, ")?;
+                result.is_ok()
+            }
+            Self::StagedLedgerParts { block_hash, result } => {
+                write!(f, "StagedLedgerParts, {block_hash}, ")?;
+                result.is_ok()
+            }
+        };
+        if is_ok {
+            write!(f, "Ok")
+        } else {
+            write!(f, "Err")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snarked_query() -> LedgerSnapshotQuery {
+        LedgerSnapshotQuery::SnarkedLedger(
+            "jx5YAT36bv62M8mPcREYYfZWXaKqqMzDCP8wmc21uf4CfDKAHCr"
+                .parse()
+                .unwrap(),
+        )
+    }
+
+    fn signed(secret_key: &mut SecretKey, body: &[u8]) -> Signature {
+        let mut digest = LedgerSnapshotDigest::new(snarked_query());
+        digest.update(body);
+        digest.sign(secret_key)
+    }
+
+    /// Digest of `chunks` of the body, as read from the response.
+    fn read(query: LedgerSnapshotQuery, chunks: &[&[u8]]) -> LedgerSnapshotDigest {
+        let mut digest = LedgerSnapshotDigest::new(query);
+        chunks.iter().for_each(|chunk| digest.update(chunk));
+        digest
+    }
+
+    #[test]
+    fn snapshot_signature_is_verified_with_the_serving_key() {
+        let mut secret_key = SecretKey::deterministic(0);
+        let signature = signed(&mut secret_key, b"accounts");
+
+        let digest = read(snarked_query(), &[b"acc", b"ounts"]);
+        assert!(digest.verify(&secret_key.public_key(), &signature));
+
+        let other_key = SecretKey::deterministic(1).public_key();
+        let digest = read(snarked_query(), &[b"accounts"]);
+        assert!(!digest.verify(&other_key, &signature));
+    }
+
+    #[test]
+    fn snapshot_signature_covers_body_and_query() {
+        let mut secret_key = SecretKey::deterministic(0);
+        let public_key = secret_key.public_key();
+        let signature = signed(&mut secret_key, b"accounts");
+
+        let tampered = read(snarked_query(), &[b"accountz"]);
+        assert!(!tampered.verify(&public_key, &signature));
+
+        let other_query = LedgerSnapshotQuery::SnarkedLedger(
+            "jxo5pSyt16XGwA9UeuAdiFDzrwFH3smbNTJF7fxq98w1y9Jem2m"
+                .parse()
+                .unwrap(),
+        );
+        let replayed = read(other_query, &[b"accounts"]);
+        assert!(!replayed.verify(&public_key, &signature));
+    }
+}
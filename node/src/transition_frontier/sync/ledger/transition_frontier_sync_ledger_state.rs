@@ -83,7 +83,8 @@ impl TransitionFrontierSyncLedgerState {
     pub fn update_target(&mut self, time: Timestamp, new_target: SyncLedgerTarget) {
         match self {
             Self::Snarked(
-                TransitionFrontierSyncLedgerSnarkedState::NumAccountsPending { target, .. }
+                TransitionFrontierSyncLedgerSnarkedState::SnapshotFetchPending { target, .. }
+                | TransitionFrontierSyncLedgerSnarkedState::NumAccountsPending { target, .. }
                 | TransitionFrontierSyncLedgerSnarkedState::MerkleTreeSyncPending { target, .. },
             ) => {
                 if target.snarked_ledger_hash == new_target.snarked_ledger_hash {
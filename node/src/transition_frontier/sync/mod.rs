@@ -10,7 +10,12 @@ mod transition_frontier_sync_reducer;
 
 mod transition_frontier_sync_effects;
 
-use mina_core::block::ArcBlockWithHash;
+mod transition_frontier_sync_precomputed_block_service;
+pub use transition_frontier_sync_precomputed_block_service::*;
+
+use std::time::Duration;
+
+use mina_core::{block::ArcBlockWithHash, ErrorCode, HasErrorCode};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -26,7 +31,37 @@ pub enum SyncError {
     BlockApplyFailed(ArcBlockWithHash, String),
 }
 
+impl HasErrorCode for PeerBlockFetchError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::Timeout => ErrorCode::PeerTimeout,
+            Self::Disconnected => ErrorCode::PeerDisconnected,
+            Self::DataUnavailable => ErrorCode::PeerDataUnavailable,
+        }
+    }
+}
+
+impl HasErrorCode for SyncError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::BlockApplyFailed(..) => ErrorCode::BlockApplyFailed,
+        }
+    }
+}
+
 /// How close to the best tip we have to be for the full
 /// verification of proofs contained in the block
 /// body (zkApp txns and completed works) to be enabled.
 const CATCHUP_BLOCK_VERIFY_TAIL_LENGTH: usize = 5;
+
+/// For how long a block is left to peers, before fetching it from the
+/// precomputed blocks bucket, if no peer could serve it.
+const BUCKET_FETCH_PEERS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delay before fetching a block from the precomputed blocks bucket
+/// again, after the previous fetch failed.
+const BUCKET_FETCH_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Maximum number of blocks fetched from the precomputed blocks bucket
+/// at the same time.
+const BUCKET_FETCH_MAX_PENDING: usize = 4;
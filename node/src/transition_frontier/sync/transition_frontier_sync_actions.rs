@@ -92,6 +92,21 @@ pub enum TransitionFrontierSyncAction {
         rpc_id: P2pRpcId,
         response: ArcBlockWithHash,
     },
+    /// Fetch blocks that peers can't serve from the precomputed blocks
+    /// bucket, if one is configured.
+    BlocksBucketQuery,
+    BlocksBucketFetchInit {
+        hash: StateHash,
+    },
+    #[action_event(level = warn, fields(hash = display(hash), error))]
+    BlocksBucketFetchError {
+        hash: StateHash,
+        error: String,
+    },
+    BlocksBucketFetchSuccess {
+        hash: StateHash,
+        block: ArcBlockWithHash,
+    },
     BlocksFetchSuccess {
         hash: StateHash,
     },
@@ -290,7 +305,10 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncAction {
                         let peer_best_tip = p.best_tip.as_ref()?;
                         Some(p).filter(|_| sync_best_tip.hash == peer_best_tip.hash)
                     })
-                    .is_some_and(|p| p.channels.rpc.can_send_request());
+                    .is_some_and(|p| {
+                        p.channels.rpc.can_send_request()
+                            || p.channels.streaming_rpc.can_send_request()
+                    });
 
                 check_next_hash && check_peer_available
             }
@@ -310,7 +328,10 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncAction {
                         let peer_best_tip = p.best_tip.as_ref()?;
                         Some(p).filter(|_| sync_best_tip.hash == peer_best_tip.hash)
                     })
-                    .is_some_and(|p| p.channels.rpc.can_send_request());
+                    .is_some_and(|p| {
+                        p.channels.rpc.can_send_request()
+                            || p.channels.streaming_rpc.can_send_request()
+                    });
 
                 check_next_hash && check_peer_available
             }
@@ -336,6 +357,43 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncAction {
                 .block_state(&response.hash)
                 .filter(|s| s.is_fetch_pending_from_peer(peer_id, *rpc_id))
                 .is_some_and(|s| s.block_hash() == &response.hash),
+            TransitionFrontierSyncAction::BlocksBucketQuery => {
+                state
+                    .transition_frontier
+                    .config
+                    .precomputed_blocks_url
+                    .is_some()
+                    && state
+                        .transition_frontier
+                        .sync
+                        .blocks_bucket_fetch_next(time)
+                        .is_some()
+            }
+            TransitionFrontierSyncAction::BlocksBucketFetchInit { hash } => {
+                state
+                    .transition_frontier
+                    .config
+                    .precomputed_blocks_url
+                    .is_some()
+                    && state
+                        .transition_frontier
+                        .sync
+                        .blocks_bucket_fetch_next(time)
+                        .is_some_and(|expected| &expected == hash)
+            }
+            TransitionFrontierSyncAction::BlocksBucketFetchError { hash, .. } => state
+                .transition_frontier
+                .sync
+                .block_state(hash)
+                .is_some_and(|s| s.is_bucket_fetch_pending()),
+            TransitionFrontierSyncAction::BlocksBucketFetchSuccess { hash, block } => {
+                &block.hash == hash
+                    && state
+                        .transition_frontier
+                        .sync
+                        .block_state(hash)
+                        .is_some_and(|s| s.is_bucket_fetch_pending())
+            }
             TransitionFrontierSyncAction::BlocksFetchSuccess { hash } => state
                 .transition_frontier
                 .sync
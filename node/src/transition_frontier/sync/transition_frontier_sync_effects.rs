@@ -1,7 +1,10 @@
 use mina_core::block::{AppliedBlock, ArcBlockWithHash};
-use mina_p2p_messages::v2::LedgerHash;
+use mina_p2p_messages::v2::{LedgerHash, StateHash};
 use p2p::{
-    channels::rpc::{P2pChannelsRpcAction, P2pRpcId},
+    channels::{
+        rpc::{P2pChannelsRpcAction, P2pRpcId},
+        streaming_rpc::{P2pChannelsStreamingRpcAction, P2pStreamingRpcRequest},
+    },
     P2pNetworkPubsubAction, PeerId,
 };
 use redux::ActionMeta;
@@ -10,7 +13,9 @@ use crate::{
     ledger::write::{LedgerWriteAction, LedgerWriteRequest, LedgersToKeep},
     p2p::channels::rpc::P2pRpcRequest,
     p2p_ready,
-    service::TransitionFrontierSyncLedgerSnarkedService,
+    service::{
+        TransitionFrontierSyncLedgerSnarkedService, TransitionFrontierSyncPrecomputedBlockService,
+    },
     Service, Store, TransitionFrontierAction,
 };
 
@@ -75,7 +80,9 @@ impl TransitionFrontierSyncAction {
                 // if root snarked ledger stayed same but root block changed
                 // while reconstructing staged ledger.
                 store.dispatch(TransitionFrontierSyncLedgerStagedAction::PartsFetchPending);
-                store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
+                if !store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::SnapshotFetchInit) {
+                    store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
+                }
                 // if we don't need to sync root staged ledger.
                 store.dispatch(TransitionFrontierSyncAction::BlocksPeersQuery);
                 // if we already have a block ready to be applied.
@@ -136,7 +143,10 @@ impl TransitionFrontierSyncAction {
                 // TODO(binier): make sure they have the ledger we want to query.
                 let mut peer_ids = p2p
                     .ready_peers_iter()
-                    .filter(|(_, p)| p.channels.rpc.can_send_request())
+                    .filter(|(_, p)| {
+                        p.channels.rpc.can_send_request()
+                            || (p.block_streaming && p.channels.streaming_rpc.can_send_request())
+                    })
                     .map(|(id, p)| (*id, p.connected_since))
                     .collect::<Vec<_>>();
                 peer_ids.sort_by(|(_, t1), (_, t2)| t2.cmp(t1));
@@ -173,6 +183,9 @@ impl TransitionFrontierSyncAction {
                 }
             }
             TransitionFrontierSyncAction::BlocksPeerQueryInit { hash, peer_id } => {
+                if blocks_peer_query_streaming(store, peer_id, hash) {
+                    return;
+                }
                 let p2p = p2p_ready!(store.state().p2p, meta.time());
                 let Some(rpc_id) = p2p
                     .get_ready_peer(peer_id)
@@ -202,6 +215,9 @@ impl TransitionFrontierSyncAction {
                 });
             }
             TransitionFrontierSyncAction::BlocksPeerQueryRetry { hash, peer_id } => {
+                if blocks_peer_query_streaming(store, peer_id, hash) {
+                    return;
+                }
                 let p2p = p2p_ready!(store.state().p2p, meta.time());
                 let Some(rpc_id) = p2p
                     .get_ready_peer(peer_id)
@@ -233,6 +249,7 @@ impl TransitionFrontierSyncAction {
             TransitionFrontierSyncAction::BlocksPeerQueryPending { .. } => {}
             TransitionFrontierSyncAction::BlocksPeerQueryError { .. } => {
                 store.dispatch(TransitionFrontierSyncAction::BlocksPeersQuery);
+                store.dispatch(TransitionFrontierSyncAction::BlocksBucketQuery);
             }
             TransitionFrontierSyncAction::BlocksPeerQuerySuccess { response, .. } => {
                 store.dispatch(TransitionFrontierSyncAction::BlocksPeersQuery);
@@ -240,6 +257,47 @@ impl TransitionFrontierSyncAction {
                     hash: response.hash.clone(),
                 });
             }
+            TransitionFrontierSyncAction::BlocksBucketQuery => {
+                while let Some(hash) = store
+                    .state()
+                    .transition_frontier
+                    .sync
+                    .blocks_bucket_fetch_next(meta.time())
+                {
+                    if !store.dispatch(TransitionFrontierSyncAction::BlocksBucketFetchInit { hash })
+                    {
+                        break;
+                    }
+                }
+            }
+            TransitionFrontierSyncAction::BlocksBucketFetchInit { hash } => {
+                let state = store.state();
+                let Some(url) = state
+                    .transition_frontier
+                    .config
+                    .precomputed_blocks_url
+                    .clone()
+                else {
+                    return;
+                };
+                // Blocks are stored under their height in the bucket.
+                let Some(height) = state.transition_frontier.sync.block_height(hash) else {
+                    store.dispatch(TransitionFrontierSyncAction::BlocksBucketFetchError {
+                        hash: hash.clone(),
+                        error: "unknown block height".to_owned(),
+                    });
+                    return;
+                };
+                store
+                    .service
+                    .precomputed_block_fetch(&url, height, hash.clone());
+            }
+            TransitionFrontierSyncAction::BlocksBucketFetchError { .. } => {}
+            TransitionFrontierSyncAction::BlocksBucketFetchSuccess { hash, .. } => {
+                store.dispatch(TransitionFrontierSyncAction::BlocksFetchSuccess {
+                    hash: hash.clone(),
+                });
+            }
             TransitionFrontierSyncAction::BlocksFetchSuccess { .. } => {
                 let _ = store;
                 store.dispatch(TransitionFrontierSyncAction::BlocksNextApplyInit {});
@@ -419,6 +477,47 @@ fn sync_best_tip(state: &crate::State) -> ArcBlockWithHash {
     state.transition_frontier.sync.best_tip().unwrap().clone()
 }
 
+/// Requests the block through the streaming RPC if the peer announced it
+/// serves blocks over it, so that its body is checked chunk by chunk while
+/// it's received. Returns whether the request was sent, otherwise the block
+/// is requested with [`P2pRpcRequest::Block`].
+fn blocks_peer_query_streaming<S: Service>(
+    store: &mut Store<S>,
+    peer_id: &PeerId,
+    hash: &StateHash,
+) -> bool {
+    let Some(rpc_id) = store
+        .state()
+        .p2p
+        .ready()
+        .and_then(|p2p| p2p.get_ready_peer(peer_id))
+        .filter(|peer| peer.block_streaming && peer.channels.streaming_rpc.can_send_request())
+        .map(|peer| peer.channels.next_local_rpc_id())
+    else {
+        return false;
+    };
+
+    store.dispatch(P2pChannelsStreamingRpcAction::RequestSend {
+        peer_id: *peer_id,
+        id: rpc_id,
+        request: Box::new(P2pStreamingRpcRequest::Block(hash.clone())),
+        on_init: Some(redux::callback!(
+            on_send_p2p_block_streaming_rpc_request(
+                (peer_id: PeerId, rpc_id: P2pRpcId, request: P2pStreamingRpcRequest)
+            ) -> crate::Action {
+                let P2pStreamingRpcRequest::Block(hash) = request else {
+                    unreachable!()
+                };
+                TransitionFrontierSyncAction::BlocksPeerQueryPending {
+                    hash,
+                    peer_id,
+                    rpc_id,
+                }
+            }
+        )),
+    })
+}
+
 /// For snarked ledger sync targets, copy the previous snarked ledger if required
 fn maybe_copy_ledgers_for_sync<S>(
     store: &mut Store<S>,
@@ -0,0 +1,39 @@
+use mina_core::block::ArcBlockWithHash;
+use mina_p2p_messages::v2::StateHash;
+use serde::{Deserialize, Serialize};
+
+pub trait TransitionFrontierSyncPrecomputedBlockService: redux::Service {
+    /// Download the precomputed block at `height` with hash `block_hash`
+    /// from the bucket at `url`, where blocks are stored as archive nodes
+    /// upload them. Result is sent as a [`PrecomputedBlockEvent`].
+    fn precomputed_block_fetch(&mut self, url: &str, height: u32, block_hash: StateHash);
+}
+
+/// Blocks from the bucket aren't trusted. They are accepted only if
+/// their hash matches the hash of the chain received from peers.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PrecomputedBlockEvent {
+    pub block_hash: StateHash,
+    pub result: Result<ArcBlockWithHash, String>,
+}
+
+impl PrecomputedBlockEvent {
+    /// Fetched block, if its hash is the one of the requested block.
+    pub fn verified_block(self) -> Result<ArcBlockWithHash, String> {
+        let block = self.result?;
+        if block.hash != self.block_hash {
+            return Err(format!("block hash mismatch, got {}", block.hash));
+        }
+        Ok(block)
+    }
+}
+
+impl std::fmt::Display for PrecomputedBlockEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PrecomputedBlock, {}, ", self.block_hash)?;
+        match &self.result {
+            Ok(_) => write!(f, "Ok"),
+            Err(_) => write!(f, "Err"),
+        }
+    }
+}
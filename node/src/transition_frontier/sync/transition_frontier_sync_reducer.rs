@@ -8,9 +8,10 @@ use super::{
         snarked::TransitionFrontierSyncLedgerSnarkedState, SyncLedgerTarget, SyncLedgerTargetKind,
         TransitionFrontierSyncLedgerState,
     },
-    PeerRpcState, TransitionFrontierRootSnarkedLedgerUpdates, TransitionFrontierSyncAction,
-    TransitionFrontierSyncActionWithMetaRef, TransitionFrontierSyncBlockState,
-    TransitionFrontierSyncLedgerPending, TransitionFrontierSyncState,
+    BucketFetchState, PeerRpcState, TransitionFrontierRootSnarkedLedgerUpdates,
+    TransitionFrontierSyncAction, TransitionFrontierSyncActionWithMetaRef,
+    TransitionFrontierSyncBlockState, TransitionFrontierSyncLedgerPending,
+    TransitionFrontierSyncState,
 };
 
 impl TransitionFrontierSyncState {
@@ -183,6 +184,7 @@ impl TransitionFrontierSyncState {
                                         time: meta.time(),
                                         block_hash: hash.clone(),
                                         attempts: Default::default(),
+                                        bucket: None,
                                     }
                                 }
                             })
@@ -244,6 +246,7 @@ impl TransitionFrontierSyncState {
                                     time: meta.time(),
                                     block_hash: hash.clone(),
                                     attempts: Default::default(),
+                                    bucket: None,
                                 },
                             })
                             .collect::<Vec<_>>();
@@ -491,6 +494,7 @@ impl TransitionFrontierSyncState {
                             time: meta.time(),
                             block_hash,
                             attempts: Default::default(),
+                            bucket: None,
                         }
                     }
                 }));
@@ -577,6 +581,40 @@ impl TransitionFrontierSyncState {
                     block: response.clone(),
                 };
             }
+            TransitionFrontierSyncAction::BlocksBucketQuery => {}
+            TransitionFrontierSyncAction::BlocksBucketFetchInit { hash } => {
+                let Some(bucket) = state
+                    .block_state_mut(hash)
+                    .and_then(|s| s.bucket_fetch_mut())
+                else {
+                    return;
+                };
+                *bucket = Some(BucketFetchState::Pending { time: meta.time() });
+            }
+            TransitionFrontierSyncAction::BlocksBucketFetchError { hash, error } => {
+                let Some(bucket) = state
+                    .block_state_mut(hash)
+                    .and_then(|s| s.bucket_fetch_mut())
+                else {
+                    return;
+                };
+                *bucket = Some(BucketFetchState::Error {
+                    time: meta.time(),
+                    error: error.clone(),
+                });
+            }
+            TransitionFrontierSyncAction::BlocksBucketFetchSuccess { hash, block } => {
+                let Some(bucket) = state
+                    .block_state_mut(hash)
+                    .and_then(|s| s.bucket_fetch_mut())
+                else {
+                    return;
+                };
+                *bucket = Some(BucketFetchState::Success {
+                    time: meta.time(),
+                    block: block.clone(),
+                });
+            }
             TransitionFrontierSyncAction::BlocksFetchSuccess { hash } => {
                 let Some(block_state) = state.block_state_mut(hash) else {
                     return;
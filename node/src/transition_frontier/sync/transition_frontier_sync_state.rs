@@ -10,7 +10,8 @@ use crate::p2p::{channels::rpc::P2pRpcId, PeerId};
 
 use super::{
     ledger::{SyncLedgerTarget, SyncLedgerTargetKind, TransitionFrontierSyncLedgerState},
-    PeerBlockFetchError,
+    PeerBlockFetchError, BUCKET_FETCH_MAX_PENDING, BUCKET_FETCH_PEERS_TIMEOUT,
+    BUCKET_FETCH_RETRY_INTERVAL,
 };
 
 #[derive(Serialize, Deserialize, Display, Debug, Clone)]
@@ -114,6 +115,10 @@ pub enum TransitionFrontierSyncBlockState {
         time: Timestamp,
         block_hash: StateHash,
         attempts: BTreeMap<PeerId, PeerRpcState>,
+        /// Fetch from the precomputed blocks bucket, if peers couldn't
+        /// serve the block.
+        #[serde(default)]
+        bucket: Option<BucketFetchState>,
     },
     FetchSuccess {
         time: Timestamp,
@@ -154,6 +159,21 @@ pub enum PeerRpcState {
     },
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum BucketFetchState {
+    Pending {
+        time: Timestamp,
+    },
+    Error {
+        time: Timestamp,
+        error: String,
+    },
+    Success {
+        time: Timestamp,
+        block: ArcBlockWithHash,
+    },
+}
+
 #[derive(Serialize, Deserialize, Display, Debug, Clone)]
 pub enum SyncPhase {
     Bootstrap,
@@ -299,6 +319,35 @@ impl TransitionFrontierSyncState {
         })
     }
 
+    /// Next block to fetch from the precomputed blocks bucket, see
+    /// [`TransitionFrontierSyncBlockState::is_bucket_fetch_needed`].
+    pub fn blocks_bucket_fetch_next(&self, now: Timestamp) -> Option<StateHash> {
+        if self.blocks_bucket_fetch_pending_count() >= BUCKET_FETCH_MAX_PENDING {
+            return None;
+        }
+        self.blocks_iter()
+            .find(|s| s.is_bucket_fetch_needed(now))
+            .map(|s| s.block_hash().clone())
+    }
+
+    pub fn blocks_bucket_fetch_pending_count(&self) -> usize {
+        self.blocks_iter()
+            .filter(|s| s.is_bucket_fetch_pending())
+            .count()
+    }
+
+    /// Height of a block of the chain being synced. Known from its
+    /// position in the chain, even if the block isn't fetched yet.
+    pub fn block_height(&self, hash: &StateHash) -> Option<u32> {
+        let Self::BlocksPending { chain, .. } = self else {
+            return None;
+        };
+        let best_tip = chain.last()?.block()?;
+        let index = chain.iter().position(|s| s.block_hash() == hash)?;
+        let distance = u32::try_from(chain.len() - 1 - index).ok()?;
+        best_tip.height().checked_sub(distance)
+    }
+
     pub fn block_state(&self, hash: &StateHash) -> Option<&TransitionFrontierSyncBlockState> {
         self.blocks_iter().find(|s| s.block_hash() == hash)
     }
@@ -457,6 +506,54 @@ impl TransitionFrontierSyncBlockState {
             .filter(|_| !attempts.is_empty() && attempts.iter().all(|(_, s)| s.is_error()))
     }
 
+    /// Whether the block should be fetched from the precomputed blocks
+    /// bucket, because all the peers asked for it failed to serve it, or
+    /// peers, retries included, didn't serve it for a while.
+    pub fn is_bucket_fetch_needed(&self, now: Timestamp) -> bool {
+        let Self::FetchPending {
+            time,
+            attempts,
+            bucket,
+            ..
+        } = self
+        else {
+            return false;
+        };
+        let elapsed = |since: Timestamp| now.checked_sub(since).unwrap_or_default();
+        let peers_failed = if attempts.values().any(PeerRpcState::is_success) {
+            false
+        } else if !attempts.is_empty() && attempts.values().all(PeerRpcState::is_error) {
+            true
+        } else {
+            elapsed(*time) >= BUCKET_FETCH_PEERS_TIMEOUT
+        };
+        let bucket_ready = match bucket {
+            None => true,
+            Some(BucketFetchState::Error { time, .. }) => {
+                elapsed(*time) >= BUCKET_FETCH_RETRY_INTERVAL
+            }
+            Some(_) => false,
+        };
+        peers_failed && bucket_ready
+    }
+
+    pub fn is_bucket_fetch_pending(&self) -> bool {
+        matches!(
+            self,
+            Self::FetchPending {
+                bucket: Some(BucketFetchState::Pending { .. }),
+                ..
+            }
+        )
+    }
+
+    pub fn bucket_fetch_mut(&mut self) -> Option<&mut Option<BucketFetchState>> {
+        match self {
+            Self::FetchPending { bucket, .. } => Some(bucket),
+            _ => None,
+        }
+    }
+
     pub fn fetch_pending_from_peer_rpc_id(&self, peer_id: &PeerId) -> Option<P2pRpcId> {
         let Self::FetchPending { attempts, .. } = self else {
             return None;
@@ -496,10 +593,19 @@ impl TransitionFrontierSyncBlockState {
     }
 
     pub fn fetch_pending_fetched_block(&self) -> Option<&ArcBlockWithHash> {
-        let Self::FetchPending { attempts, .. } = self else {
+        let Self::FetchPending {
+            attempts, bucket, ..
+        } = self
+        else {
             return None;
         };
-        attempts.iter().find_map(|(_, s)| s.success_block())
+        attempts
+            .iter()
+            .find_map(|(_, s)| s.success_block())
+            .or(match bucket {
+                Some(BucketFetchState::Success { block, .. }) => Some(block),
+                _ => None,
+            })
     }
 }
 
@@ -597,3 +703,217 @@ impl TransitionFrontierRootSnarkedLedgerUpdates {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use mina_p2p_messages::binprot::BinProtRead;
+
+    use super::*;
+    use crate::transition_frontier::sync::PrecomputedBlockEvent;
+
+    fn time(secs: u64) -> Timestamp {
+        Timestamp::ZERO + Duration::from_secs(secs)
+    }
+
+    fn hash(n: u64) -> StateHash {
+        StateHash::from_fp(mina_curves::pasta::Fp::from(n))
+    }
+
+    fn peer_id(n: u8) -> PeerId {
+        PeerId::from_bytes([n; 32])
+    }
+
+    fn block() -> ArcBlockWithHash {
+        let bytes = include_bytes!(
+            "../../../../tests/files/archive-breadcrumb/3NK56ZbCS31qb8SvCtCCYza4beRDtKgXA2JL6s3evKouG2KkKtiy.bin"
+        );
+        let diff = v2::ArchiveTransitionFrontierDiff::binprot_read(&mut bytes.as_slice()).unwrap();
+        ArcBlockWithHash::try_new(diff.block().unwrap().into()).unwrap()
+    }
+
+    fn fetch_pending(
+        block_hash: StateHash,
+        attempts: impl IntoIterator<Item = (PeerId, PeerRpcState)>,
+        bucket: Option<BucketFetchState>,
+    ) -> TransitionFrontierSyncBlockState {
+        TransitionFrontierSyncBlockState::FetchPending {
+            time: time(0),
+            block_hash,
+            attempts: attempts.into_iter().collect(),
+            bucket,
+        }
+    }
+
+    fn peer_error(rpc_id: P2pRpcId) -> PeerRpcState {
+        PeerRpcState::Error {
+            time: time(1),
+            rpc_id,
+            error: PeerBlockFetchError::DataUnavailable,
+        }
+    }
+
+    /// Chain of `pending` blocks to fetch, up to the fetched best tip.
+    fn sync_state(
+        pending: impl IntoIterator<Item = TransitionFrontierSyncBlockState>,
+        best_tip: ArcBlockWithHash,
+    ) -> TransitionFrontierSyncState {
+        let mut chain = pending.into_iter().collect::<Vec<_>>();
+        chain.push(TransitionFrontierSyncBlockState::FetchSuccess {
+            time: time(0),
+            block: best_tip,
+        });
+        TransitionFrontierSyncState::BlocksPending {
+            time: time(0),
+            chain,
+            root_snarked_ledger_updates: Default::default(),
+            needed_protocol_states: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_bucket_fetch_once_peers_failed() {
+        let failed = fetch_pending(hash(1), [(peer_id(1), peer_error(1))], None);
+        assert!(failed.is_bucket_fetch_needed(time(1)));
+
+        // Another peer is still asked for the block.
+        let retried = fetch_pending(
+            hash(1),
+            [
+                (peer_id(1), peer_error(1)),
+                (
+                    peer_id(2),
+                    PeerRpcState::Pending {
+                        time: time(1),
+                        rpc_id: 2,
+                    },
+                ),
+            ],
+            None,
+        );
+        assert!(!retried.is_bucket_fetch_needed(time(1)));
+        // But not for ever.
+        assert!(retried.is_bucket_fetch_needed(time(0) + BUCKET_FETCH_PEERS_TIMEOUT));
+
+        let served = fetch_pending(
+            hash(1),
+            [
+                (peer_id(1), peer_error(1)),
+                (
+                    peer_id(2),
+                    PeerRpcState::Success {
+                        time: time(1),
+                        block: block(),
+                    },
+                ),
+            ],
+            None,
+        );
+        assert!(!served.is_bucket_fetch_needed(time(0) + BUCKET_FETCH_PEERS_TIMEOUT));
+    }
+
+    #[test]
+    fn test_bucket_fetch_without_peers() {
+        let state = fetch_pending(hash(1), [], None);
+        assert!(!state.is_bucket_fetch_needed(time(1)));
+        assert!(state.is_bucket_fetch_needed(time(0) + BUCKET_FETCH_PEERS_TIMEOUT));
+    }
+
+    #[test]
+    fn test_bucket_fetch_retry() {
+        let pending = fetch_pending(
+            hash(1),
+            [(peer_id(1), peer_error(1))],
+            Some(BucketFetchState::Pending { time: time(1) }),
+        );
+        assert!(pending.is_bucket_fetch_pending());
+        assert!(!pending.is_bucket_fetch_needed(time(1000)));
+
+        let failed = fetch_pending(
+            hash(1),
+            [(peer_id(1), peer_error(1))],
+            Some(BucketFetchState::Error {
+                time: time(2),
+                error: "block hash mismatch".to_owned(),
+            }),
+        );
+        assert!(!failed.is_bucket_fetch_needed(time(3)));
+        assert!(failed.is_bucket_fetch_needed(time(2) + BUCKET_FETCH_RETRY_INTERVAL));
+    }
+
+    #[test]
+    fn test_bucket_fetch_next() {
+        let best_tip = block();
+        let failed = |n| fetch_pending(hash(n), [(peer_id(1), peer_error(n))], None);
+        let state = sync_state((1..=6).map(failed), best_tip.clone());
+        assert_eq!(state.blocks_bucket_fetch_next(time(1)), Some(hash(1)));
+        // Heights are known from the best tip.
+        assert_eq!(state.block_height(&hash(6)), Some(best_tip.height() - 1));
+        assert_eq!(state.block_height(&hash(1)), Some(best_tip.height() - 6));
+        assert_eq!(state.block_height(&hash(7)), None);
+
+        let bucket_pending = |n| {
+            fetch_pending(
+                hash(n),
+                [(peer_id(1), peer_error(n))],
+                Some(BucketFetchState::Pending { time: time(1) }),
+            )
+        };
+        let state = sync_state(
+            (1..=BUCKET_FETCH_MAX_PENDING as u64)
+                .map(bucket_pending)
+                .chain([failed(10)]),
+            best_tip.clone(),
+        );
+        assert_eq!(
+            state.blocks_bucket_fetch_pending_count(),
+            BUCKET_FETCH_MAX_PENDING
+        );
+        assert_eq!(state.blocks_bucket_fetch_next(time(1)), None);
+
+        let state = sync_state([bucket_pending(1), failed(2)], best_tip);
+        assert_eq!(state.blocks_bucket_fetch_next(time(1)), Some(hash(2)));
+    }
+
+    #[test]
+    fn test_bucket_block_is_fetched() {
+        let block = block();
+        let state = fetch_pending(
+            block.hash.clone(),
+            [(peer_id(1), peer_error(1))],
+            Some(BucketFetchState::Success {
+                time: time(2),
+                block: block.clone(),
+            }),
+        );
+        assert_eq!(
+            state.fetch_pending_fetched_block().map(|b| &b.hash),
+            Some(&block.hash)
+        );
+        assert!(!state.is_bucket_fetch_needed(time(1000)));
+    }
+
+    #[test]
+    fn test_bucket_block_hash_mismatch() {
+        let block = block();
+        let event = PrecomputedBlockEvent {
+            block_hash: block.hash.clone(),
+            result: Ok(block.clone()),
+        };
+        assert_eq!(event.verified_block().unwrap().hash, block.hash);
+
+        let event = PrecomputedBlockEvent {
+            block_hash: hash(1),
+            result: Ok(block.clone()),
+        };
+        let error = event.verified_block().unwrap_err();
+        assert_eq!(error, format!("block hash mismatch, got {}", block.hash));
+
+        let event = PrecomputedBlockEvent {
+            block_hash: hash(1),
+            result: Err("404 Not Found".to_owned()),
+        };
+        assert_eq!(event.verified_block().unwrap_err(), "404 Not Found");
+    }
+}
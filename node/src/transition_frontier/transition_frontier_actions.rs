@@ -1,7 +1,7 @@
 use std::{collections::BTreeSet, sync::Arc};
 
 use mina_core::{block::ArcBlockWithHash, ActionEvent};
-use mina_p2p_messages::v2::StateHash;
+use mina_p2p_messages::v2::{LedgerHash, StateHash};
 use serde::{Deserialize, Serialize};
 
 use super::{
@@ -9,6 +9,7 @@ use super::{
     genesis::TransitionFrontierGenesisAction,
     genesis_effectful::TransitionFrontierGenesisEffectfulAction,
     sync::{SyncError, TransitionFrontierSyncAction, TransitionFrontierSyncState},
+    LedgerHashDivergence,
 };
 
 pub type TransitionFrontierActionWithMeta = redux::ActionWithMeta<TransitionFrontierAction>;
@@ -41,6 +42,16 @@ pub enum TransitionFrontierAction {
         best_tip: ArcBlockWithHash,
         error: SyncError,
     },
+    /// Compare our epoch ledger hashes with the best tips of peers, and
+    /// read the merkle roots of the best tip ledgers.
+    LedgerHashesCheckInit,
+    LedgerHashesCheckPeersSuccess {
+        divergences: Vec<LedgerHashDivergence>,
+    },
+    /// Merkle roots of the ledgers stored under the best tip hashes.
+    LedgerHashesCheckMerkleRootsSuccess {
+        merkle_roots: Vec<(LedgerHash, Option<LedgerHash>)>,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for TransitionFrontierAction {
@@ -80,6 +91,15 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierAction {
                             .is_some_and(|s| s.is_apply_error()),
                     }
             }
+            TransitionFrontierAction::LedgerHashesCheckInit
+            | TransitionFrontierAction::LedgerHashesCheckPeersSuccess { .. } => {
+                state.transition_frontier.sync.is_synced()
+                    && state.transition_frontier.best_tip().is_some()
+                    && state.transition_frontier.ledger_hashes_check.is_due(time)
+            }
+            TransitionFrontierAction::LedgerHashesCheckMerkleRootsSuccess { .. } => {
+                state.transition_frontier.best_tip().is_some()
+            }
         }
     }
 }
@@ -2,15 +2,45 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
-use super::genesis::TransitionFrontierGenesisConfig;
+use super::{genesis::TransitionFrontierGenesisConfig, sync::ledger::LedgerSnapshotSource};
+
+/// Default number of blocks kept in the root history, `k` blocks on the
+/// networks currently running.
+pub const DEFAULT_ROOT_HISTORY_SIZE: usize = 290;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransitionFrontierConfig {
     pub genesis: Arc<TransitionFrontierGenesisConfig>,
+    /// Skip the check for blocks received too late. Used when importing
+    /// historical blocks, which are all late compared to the current slot.
+    #[serde(default)]
+    pub allow_historical_blocks: bool,
+    /// Trusted node to download the ledgers needed for bootstrapping
+    /// from, instead of syncing them from peers.
+    #[serde(default)]
+    pub bootstrap_from: Option<LedgerSnapshotSource>,
+    /// Url of a bucket with precomputed blocks, as uploaded by archive
+    /// nodes, to fetch the blocks that peers can't serve during catchup.
+    #[serde(default)]
+    pub precomputed_blocks_url: Option<String>,
+    /// Number of blocks which left the frontier to keep a summary of,
+    /// see [`super::TransitionFrontierRootHistory`].
+    #[serde(default = "default_root_history_size")]
+    pub root_history_size: usize,
+}
+
+fn default_root_history_size() -> usize {
+    DEFAULT_ROOT_HISTORY_SIZE
 }
 
 impl TransitionFrontierConfig {
     pub fn new(genesis: Arc<TransitionFrontierGenesisConfig>) -> Self {
-        TransitionFrontierConfig { genesis }
+        TransitionFrontierConfig {
+            genesis,
+            allow_historical_blocks: false,
+            bootstrap_from: None,
+            precomputed_blocks_url: None,
+            root_history_size: DEFAULT_ROOT_HISTORY_SIZE,
+        }
     }
 }
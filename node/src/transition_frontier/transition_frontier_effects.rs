@@ -3,8 +3,9 @@ use redux::Timestamp;
 
 use crate::{
     block_producer::BlockProducerAction,
-    ledger::LEDGER_DEPTH,
+    ledger::ledger_depth,
     p2p::{channels::best_tip::P2pChannelsBestTipAction, P2pNetworkPubsubAction},
+    service::TransitionFrontierSyncLedgerSnapshotService,
     snark_pool::{SnarkPoolAction, SnarkWork},
     stats::sync::SyncingLedger,
     Store, TransactionPoolAction,
@@ -19,7 +20,7 @@ use super::{
             staged::TransitionFrontierSyncLedgerStagedAction,
             transition_frontier_sync_ledger_init_effects,
             transition_frontier_sync_ledger_snarked_success_effects,
-            transition_frontier_sync_ledger_staged_success_effects,
+            transition_frontier_sync_ledger_staged_success_effects, LedgerSnapshotQuery,
             TransitionFrontierSyncLedgerAction,
         },
         TransitionFrontierSyncAction, TransitionFrontierSyncState,
@@ -185,6 +186,10 @@ pub fn transition_frontier_effects<S: crate::Service>(
                 }
                 TransitionFrontierSyncAction::BlocksPeerQueryError { .. } => {}
                 TransitionFrontierSyncAction::BlocksPeerQuerySuccess { .. } => {}
+                TransitionFrontierSyncAction::BlocksBucketQuery => {}
+                TransitionFrontierSyncAction::BlocksBucketFetchInit { .. } => {}
+                TransitionFrontierSyncAction::BlocksBucketFetchError { .. } => {}
+                TransitionFrontierSyncAction::BlocksBucketFetchSuccess { .. } => {}
                 TransitionFrontierSyncAction::BlocksFetchSuccess { ref hash } => {
                     if let Some(stats) = store.service.stats() {
                         if let Some(state) =
@@ -283,6 +288,9 @@ pub fn transition_frontier_effects<S: crate::Service>(
         TransitionFrontierAction::SyncFailed { .. } => {
             // TODO(SEC): disconnect/blacklist peers that caused this.
         }
+        TransitionFrontierAction::LedgerHashesCheckInit
+        | TransitionFrontierAction::LedgerHashesCheckPeersSuccess { .. }
+        | TransitionFrontierAction::LedgerHashesCheckMerkleRootsSuccess { .. } => {}
     }
 }
 
@@ -302,6 +310,7 @@ fn synced_effects<S: crate::Service>(
     if let Some(stats) = store.service.stats() {
         stats.new_best_chain(meta.time(), best_chain);
     }
+    store.service.best_tip_update(&best_tip.block);
 
     let chain_diff = chain_diff.clone();
 
@@ -328,11 +337,13 @@ fn synced_effects<S: crate::Service>(
 
     let best_tip_hash = best_tip.merkle_root_hash().clone();
     store.dispatch(TransitionFrontierCandidateAction::Prune);
+    store.dispatch(TransitionFrontierCandidateAction::BlockSnarkVerifyPrioritiesUpdate);
     store.dispatch(BlockProducerAction::BestTipUpdate {
         best_tip: best_tip.block.clone(),
     });
     store.dispatch(TransactionPoolAction::BestTipChanged {
         best_tip_hash: best_tip_hash.clone(),
+        is_reorg: chain_diff.as_ref().is_some_and(|diff| diff.reorg_best_tip),
     });
     if let Some(diff) = chain_diff {
         store.dispatch(TransactionPoolAction::ApplyTransitionFrontierDiff {
@@ -371,7 +382,7 @@ fn handle_transition_frontier_sync_ledger_action<S: crate::Service>(
                             .sync
                             .ledger_target_kind()
                         {
-                            if address.length() < LEDGER_DEPTH - ACCOUNT_SUBTREE_HEIGHT {
+                            if address.length() < ledger_depth() - ACCOUNT_SUBTREE_HEIGHT {
                                 stats.syncing_ledger(
                                     kind,
                                     SyncingLedger::FetchHashes { start, end },
@@ -441,6 +452,20 @@ fn handle_transition_frontier_sync_ledger_action<S: crate::Service>(
                         }
                     }
                 }
+                TransitionFrontierSyncLedgerStagedAction::PartsSnapshotFetchInit => {
+                    let Some((source, block_hash)) = None.or_else(|| {
+                        let state = store.state();
+                        let source = state.transition_frontier.config.bootstrap_from.clone()?;
+                        let staged = state.transition_frontier.sync.ledger()?.staged()?;
+                        Some((source, staged.target().staged.block_hash.clone()))
+                    }) else {
+                        return;
+                    };
+                    store.service.ledger_snapshot_fetch(
+                        &source,
+                        LedgerSnapshotQuery::StagedLedgerParts(block_hash),
+                    );
+                }
                 TransitionFrontierSyncLedgerStagedAction::PartsFetchSuccess { .. } => {
                     if let Some(stats) = store.service.stats() {
                         let (start, end) = (Timestamp::ZERO, Some(meta.time()));
@@ -0,0 +1,15 @@
+use mina_core::block::ArcBlockWithHash;
+
+use super::sync::TransitionFrontierSyncState;
+
+/// Hooks into the changes of the transition frontier, for applications
+/// embedding the node.
+pub trait TransitionFrontierEventService: redux::Service {
+    /// The best chain changed, `best_tip` being its new tip. Called for
+    /// every update, so that no best tip is missed.
+    fn best_tip_update(&mut self, best_tip: &ArcBlockWithHash);
+
+    /// Called after every action of the transition frontier, `sync` may be
+    /// the same as the last time.
+    fn sync_state_update(&mut self, sync: &TransitionFrontierSyncState);
+}
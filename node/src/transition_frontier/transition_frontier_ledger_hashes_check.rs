@@ -0,0 +1,314 @@
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use mina_core::block::ArcBlockWithHash;
+use mina_p2p_messages::v2::LedgerHash;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Interval between two checks of our ledger hashes.
+pub const LEDGER_HASHES_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Minimum number of peers to compare a hash with, before flagging it.
+const LEDGER_HASHES_CHECK_MIN_PEERS: usize = 3;
+
+/// Check of the ledgers we store for our best tip.
+///
+/// The merkle roots of the staking epoch, next epoch and staged ledgers
+/// must be the hashes in the best tip header, and peers in the same epoch
+/// as us must have the same epoch ledger hashes. A mismatch points to a
+/// corrupted state, which would otherwise only show up later as a fork.
+///
+/// The staged ledger isn't compared with peers, short forks make it differ
+/// legitimately and peers on the same tip share the header we check it
+/// against.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransitionFrontierLedgerHashesCheck {
+    pub time: Option<Timestamp>,
+    pub divergences: Vec<LedgerHashDivergence>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerHashKind {
+    StakingEpoch,
+    NextEpoch,
+    Staged,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LedgerHashDivergence {
+    pub ledger: LedgerHashKind,
+    pub ours: LedgerHash,
+    /// Hash we should have according to the reference.
+    pub expected: LedgerHash,
+    pub reference: LedgerHashReference,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerHashReference {
+    /// `ours` is the merkle root of the stored ledger, `expected` the hash
+    /// in the best tip header.
+    BestTip,
+    /// `ours` is the hash in the best tip header, `expected` the one
+    /// reported by most of the peers which disagree with us.
+    Peers {
+        agreeing_peers: usize,
+        disagreeing_peers: usize,
+    },
+}
+
+/// Ledger hashes of a best tip, with what is needed to know if they are
+/// comparable.
+#[derive(Debug, Clone)]
+pub struct LedgerHashesReport {
+    pub epoch: u32,
+    pub staking_epoch: LedgerHash,
+    pub next_epoch: LedgerHash,
+    pub staged: LedgerHash,
+}
+
+impl From<&ArcBlockWithHash> for LedgerHashesReport {
+    fn from(block: &ArcBlockWithHash) -> Self {
+        Self {
+            epoch: block.consensus_state().epoch_count.as_u32(),
+            staking_epoch: block.staking_epoch_ledger_hash().clone(),
+            next_epoch: block.next_epoch_ledger_hash().clone(),
+            staged: block.merkle_root_hash().clone(),
+        }
+    }
+}
+
+impl LedgerHashesReport {
+    pub fn ledgers(&self) -> [(LedgerHashKind, &LedgerHash); 3] {
+        [
+            (LedgerHashKind::StakingEpoch, &self.staking_epoch),
+            (LedgerHashKind::NextEpoch, &self.next_epoch),
+            (LedgerHashKind::Staged, &self.staged),
+        ]
+    }
+}
+
+impl LedgerHashDivergence {
+    pub fn is_from_peers(&self) -> bool {
+        matches!(self.reference, LedgerHashReference::Peers { .. })
+    }
+}
+
+impl TransitionFrontierLedgerHashesCheck {
+    pub fn is_due(&self, now: Timestamp) -> bool {
+        self.time.is_none_or(|time| {
+            now.checked_sub(time)
+                .is_some_and(|elapsed| elapsed >= LEDGER_HASHES_CHECK_INTERVAL)
+        })
+    }
+
+    /// Epoch ledger hashes which diverge from the ones reported by a
+    /// strict majority of the peers in the same epoch.
+    pub fn compare<'a>(
+        ours: &LedgerHashesReport,
+        peers: impl IntoIterator<Item = &'a LedgerHashesReport>,
+    ) -> Vec<LedgerHashDivergence> {
+        let peers = peers
+            .into_iter()
+            .filter(|p| p.epoch == ours.epoch)
+            .collect::<Vec<_>>();
+
+        [
+            (
+                LedgerHashKind::StakingEpoch,
+                &ours.staking_epoch,
+                peers.iter().map(|p| &p.staking_epoch).collect::<Vec<_>>(),
+            ),
+            (
+                LedgerHashKind::NextEpoch,
+                &ours.next_epoch,
+                peers.iter().map(|p| &p.next_epoch).collect(),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(ledger, ours, reported)| majority_divergence(ledger, ours, &reported))
+        .collect()
+    }
+
+    /// Ledgers of the best tip whose merkle root isn't the hash they are
+    /// stored under. Ledgers which aren't stored (anymore) are skipped.
+    pub fn compare_merkle_roots(
+        best_tip: &LedgerHashesReport,
+        merkle_roots: &[(LedgerHash, Option<LedgerHash>)],
+    ) -> Vec<LedgerHashDivergence> {
+        best_tip
+            .ledgers()
+            .into_iter()
+            .filter_map(|(ledger, expected)| {
+                let root = merkle_roots
+                    .iter()
+                    .find(|(hash, _)| hash == expected)
+                    .and_then(|(_, root)| root.as_ref())?;
+                (root != expected).then(|| LedgerHashDivergence {
+                    ledger,
+                    ours: root.clone(),
+                    expected: expected.clone(),
+                    reference: LedgerHashReference::BestTip,
+                })
+            })
+            .collect()
+    }
+}
+
+fn majority_divergence(
+    ledger: LedgerHashKind,
+    ours: &LedgerHash,
+    reported: &[&LedgerHash],
+) -> Option<LedgerHashDivergence> {
+    if reported.len() < LEDGER_HASHES_CHECK_MIN_PEERS {
+        return None;
+    }
+    let agreeing_peers = reported.iter().filter(|hash| **hash == ours).count();
+    let disagreeing_peers = reported.len() - agreeing_peers;
+    if disagreeing_peers * 2 <= reported.len() {
+        return None;
+    }
+
+    let mut counts = BTreeMap::<&LedgerHash, usize>::new();
+    for hash in reported.iter().filter(|hash| **hash != ours) {
+        *counts.entry(hash).or_default() += 1;
+    }
+    let (expected, _) = counts.into_iter().max_by_key(|(_, count)| *count)?;
+    Some(LedgerHashDivergence {
+        ledger,
+        ours: ours.clone(),
+        expected: expected.clone(),
+        reference: LedgerHashReference::Peers {
+            agreeing_peers,
+            disagreeing_peers,
+        },
+    })
+}
+
+impl fmt::Display for LedgerHashKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StakingEpoch => write!(f, "staking epoch ledger"),
+            Self::NextEpoch => write!(f, "next epoch ledger"),
+            Self::Staged => write!(f, "best tip staged ledger"),
+        }
+    }
+}
+
+impl fmt::Display for LedgerHashDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reference {
+            LedgerHashReference::BestTip => write!(
+                f,
+                "{} root {} doesn't match hash {} of the best tip",
+                self.ledger, self.ours, self.expected
+            ),
+            LedgerHashReference::Peers {
+                agreeing_peers,
+                disagreeing_peers,
+            } => write!(
+                f,
+                "{} hash {} differs from {} reported by {disagreeing_peers} peers \
+                 ({agreeing_peers} agree)",
+                self.ledger, self.ours, self.expected
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(n: u8) -> LedgerHash {
+        LedgerHash::from_fp(mina_curves::pasta::Fp::from(n as u64))
+    }
+
+    fn report(epoch: u32, staking: u8, next: u8, staged: u8) -> LedgerHashesReport {
+        LedgerHashesReport {
+            epoch,
+            staking_epoch: hash(staking),
+            next_epoch: hash(next),
+            staged: hash(staged),
+        }
+    }
+
+    #[test]
+    fn test_divergence_needs_majority() {
+        let ours = report(1, 1, 2, 3);
+        let peers = [report(1, 1, 2, 3), report(1, 9, 2, 3), report(1, 9, 2, 3)];
+        let divergences = TransitionFrontierLedgerHashesCheck::compare(&ours, &peers);
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].ledger, LedgerHashKind::StakingEpoch);
+        assert_eq!(divergences[0].expected, hash(9));
+        assert_eq!(
+            divergences[0].reference,
+            LedgerHashReference::Peers {
+                agreeing_peers: 1,
+                disagreeing_peers: 2
+            }
+        );
+
+        // Half of the peers isn't a majority.
+        let peers = [
+            report(1, 1, 2, 3),
+            report(1, 1, 2, 3),
+            report(1, 9, 2, 3),
+            report(1, 9, 2, 3),
+        ];
+        assert!(TransitionFrontierLedgerHashesCheck::compare(&ours, &peers).is_empty());
+    }
+
+    #[test]
+    fn test_only_comparable_peers() {
+        let ours = report(1, 1, 2, 3);
+        // Peers in another epoch aren't compared.
+        let peers = [report(2, 2, 5, 7), report(2, 2, 5, 7), report(2, 2, 5, 7)];
+        assert!(TransitionFrontierLedgerHashesCheck::compare(&ours, &peers).is_empty());
+
+        // Not enough peers to compare with.
+        let peers = [report(1, 9, 9, 9), report(1, 9, 9, 9)];
+        assert!(TransitionFrontierLedgerHashesCheck::compare(&ours, &peers).is_empty());
+
+        // Staged ledgers of peers on short forks differ legitimately.
+        let peers = [
+            report(2, 1, 2, 3),
+            report(1, 1, 8, 7),
+            report(1, 1, 8, 7),
+            report(1, 1, 2, 7),
+        ];
+        let divergences = TransitionFrontierLedgerHashesCheck::compare(&ours, &peers);
+        let kinds = divergences.iter().map(|d| d.ledger).collect::<Vec<_>>();
+        assert_eq!(kinds, [LedgerHashKind::NextEpoch]);
+    }
+
+    #[test]
+    fn test_merkle_roots_checked_against_best_tip() {
+        let best_tip = report(1, 1, 2, 3);
+        let merkle_roots = [
+            (hash(1), Some(hash(1))),
+            (hash(2), None),
+            (hash(3), Some(hash(4))),
+        ];
+        let divergences =
+            TransitionFrontierLedgerHashesCheck::compare_merkle_roots(&best_tip, &merkle_roots);
+        assert_eq!(
+            divergences,
+            [LedgerHashDivergence {
+                ledger: LedgerHashKind::Staged,
+                ours: hash(4),
+                expected: hash(3),
+                reference: LedgerHashReference::BestTip,
+            }]
+        );
+
+        // Roots read for a previous best tip aren't compared.
+        let best_tip = report(1, 1, 2, 5);
+        assert!(TransitionFrontierLedgerHashesCheck::compare_merkle_roots(
+            &best_tip,
+            &merkle_roots
+        )
+        .is_empty());
+    }
+}
@@ -1,8 +1,12 @@
 use super::{
     sync::{SyncError, TransitionFrontierSyncState},
-    TransitionFrontierAction, TransitionFrontierActionWithMetaRef, TransitionFrontierState,
+    LedgerHashDivergence, LedgerHashesReport, TransitionFrontierAction,
+    TransitionFrontierActionWithMetaRef, TransitionFrontierLedgerHashesCheck,
+    TransitionFrontierState,
 };
+use crate::ledger::read::{LedgerReadAction, LedgerReadInitCallback, LedgerReadRequest};
 use mina_core::block::AppliedBlock;
+use redux::Timestamp;
 
 impl TransitionFrontierState {
     pub fn reducer(
@@ -111,6 +115,17 @@ impl TransitionFrontierState {
                         > tip.height()
                 });
                 state.chain_diff = state.maybe_make_chain_diff(&new_chain);
+
+                // Blocks before the new root in the old chain left the frontier
+                let new_root_index = new_chain
+                    .first()
+                    .and_then(|root| state.best_chain.iter().position(|b| b == root));
+                if let Some(index) = new_root_index {
+                    state.root_history.extend(
+                        state.best_chain.get(..index).unwrap_or_default(),
+                        state.config.root_history_size,
+                    );
+                }
                 state.best_chain = new_chain;
                 state.sync = TransitionFrontierSyncState::Synced { time: meta.time() };
             }
@@ -122,6 +137,93 @@ impl TransitionFrontierState {
                 }
                 state.sync = TransitionFrontierSyncState::Synced { time: meta.time() };
             }
+            TransitionFrontierAction::LedgerHashesCheckInit => {
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let Some(best_tip) = global_state.transition_frontier.best_tip() else {
+                    return;
+                };
+                let best_tip = LedgerHashesReport::from(best_tip);
+                let peers = global_state
+                    .p2p
+                    .ready()
+                    .into_iter()
+                    .flat_map(|p2p| p2p.ready_peers_iter())
+                    .filter_map(|(_, peer)| peer.best_tip.as_ref())
+                    .map(LedgerHashesReport::from)
+                    .collect::<Vec<_>>();
+                let divergences = TransitionFrontierLedgerHashesCheck::compare(&best_tip, &peers);
+                dispatcher
+                    .push(TransitionFrontierAction::LedgerHashesCheckPeersSuccess { divergences });
+                let ledger_hashes = best_tip
+                    .ledgers()
+                    .into_iter()
+                    .map(|(_, hash)| hash.clone())
+                    .collect();
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::MerkleRoots(ledger_hashes),
+                    callback: LedgerReadInitCallback::None,
+                });
+            }
+            TransitionFrontierAction::LedgerHashesCheckPeersSuccess { divergences } => {
+                let check = &mut state.ledger_hashes_check;
+                check.time = Some(meta.time());
+                update_divergences(check, meta.time(), true, divergences);
+            }
+            TransitionFrontierAction::LedgerHashesCheckMerkleRootsSuccess { merkle_roots } => {
+                let Some(best_tip) = state.best_tip() else {
+                    return;
+                };
+                let divergences = TransitionFrontierLedgerHashesCheck::compare_merkle_roots(
+                    &best_tip.into(),
+                    merkle_roots,
+                );
+                let check = &mut state.ledger_hashes_check;
+                update_divergences(check, meta.time(), false, &divergences);
+            }
+        }
+    }
+}
+
+/// Replaces the divergences found by the same comparison, logging the new
+/// and the resolved ones.
+fn update_divergences(
+    check: &mut TransitionFrontierLedgerHashesCheck,
+    time: Timestamp,
+    from_peers: bool,
+    divergences: &[LedgerHashDivergence],
+) {
+    for divergence in divergences {
+        // Peer counts change between checks, only a divergence with
+        // different hashes is logged again.
+        let is_new = !check.divergences.iter().any(|d| {
+            d.is_from_peers() == from_peers
+                && d.ledger == divergence.ledger
+                && d.ours == divergence.ours
+                && d.expected == divergence.expected
+        });
+        if is_new {
+            mina_core::warn!(time;
+                kind = "LedgerHashDivergence",
+                summary = divergence.to_string(),
+                ledger = divergence.ledger.to_string(),
+                ours = divergence.ours.to_string(),
+                expected = divergence.expected.to_string());
         }
     }
+    for resolved in check.divergences.iter().filter(|d| {
+        d.is_from_peers() == from_peers && !divergences.iter().any(|new| new.ledger == d.ledger)
+    }) {
+        let summary = if from_peers {
+            format!("{} hash agrees with peers again", resolved.ledger)
+        } else {
+            format!("{} root matches the best tip again", resolved.ledger)
+        };
+        mina_core::info!(time;
+            kind = "LedgerHashDivergenceResolved",
+            summary = summary);
+    }
+    check
+        .divergences
+        .retain(|d| d.is_from_peers() != from_peers);
+    check.divergences.extend_from_slice(divergences);
 }
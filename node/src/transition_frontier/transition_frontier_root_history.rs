@@ -0,0 +1,77 @@
+use std::collections::VecDeque;
+
+use mina_core::block::AppliedBlock;
+use mina_p2p_messages::v2::{LedgerHash, StateHash};
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Hashes and heights of a block, enough to answer queries about it once
+/// the block itself is dropped.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BlockSummary {
+    pub hash: StateHash,
+    pub pred_hash: StateHash,
+    pub height: u32,
+    pub global_slot_since_genesis: u32,
+    pub timestamp: Timestamp,
+    pub snarked_ledger_hash: LedgerHash,
+    pub staged_ledger_hash: LedgerHash,
+}
+
+impl From<&AppliedBlock> for BlockSummary {
+    fn from(block: &AppliedBlock) -> Self {
+        Self {
+            hash: block.hash().clone(),
+            pred_hash: block.pred_hash().clone(),
+            height: block.height(),
+            global_slot_since_genesis: block.global_slot_since_genesis(),
+            timestamp: block.timestamp(),
+            snarked_ledger_hash: block.snarked_ledger_hash().clone(),
+            staged_ledger_hash: block.merkle_root_hash().clone(),
+        }
+    }
+}
+
+/// Summaries of the blocks which were the root of the transition frontier
+/// before it moved past them, oldest first.
+///
+/// Lets the node answer queries about recently finalized blocks (common
+/// queries of explorers) without an archive.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransitionFrontierRootHistory {
+    blocks: VecDeque<BlockSummary>,
+}
+
+impl TransitionFrontierRootHistory {
+    /// Adds the blocks which left the frontier, keeping at most `max_size`
+    /// summaries.
+    pub fn extend<'a>(
+        &mut self,
+        blocks: impl IntoIterator<Item = &'a AppliedBlock>,
+        max_size: usize,
+    ) {
+        self.blocks
+            .extend(blocks.into_iter().map(BlockSummary::from));
+        let excess = self.blocks.len().saturating_sub(max_size);
+        self.blocks.drain(..excess);
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    pub fn get_by_hash(&self, hash: &StateHash) -> Option<&BlockSummary> {
+        self.blocks.iter().rev().find(|block| &block.hash == hash)
+    }
+
+    pub fn get_by_height(&self, height: u32) -> Option<&BlockSummary> {
+        self.blocks
+            .iter()
+            .rev()
+            .find(|block| block.height == height)
+    }
+}
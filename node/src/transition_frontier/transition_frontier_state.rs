@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 
 use super::{
     candidate::TransitionFrontierCandidatesState, genesis::TransitionFrontierGenesisState,
-    sync::TransitionFrontierSyncState, TransitionFrontierConfig,
+    sync::TransitionFrontierSyncState, TransitionFrontierConfig, TransitionFrontierRootHistory,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -26,6 +26,9 @@ pub struct TransitionFrontierState {
     /// Needed protocol states for applying transactions in the root
     /// scan state that we don't have in the `best_chain` list.
     pub needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    /// Summaries of the blocks that were behind the root of `best_chain`
+    #[serde(default)]
+    pub root_history: TransitionFrontierRootHistory,
     pub candidates: TransitionFrontierCandidatesState,
     /// Transition frontier synchronization state
     pub sync: TransitionFrontierSyncState,
@@ -37,6 +40,9 @@ pub struct TransitionFrontierState {
     pub chain_diff: Option<BestTipDiff>,
     /// Archive mode enabled
     pub archive_enabled: bool,
+    /// Last comparison of our ledger hashes with the ones of peers
+    #[serde(default)]
+    pub ledger_hashes_check: TransitionFrontierLedgerHashesCheck,
 }
 
 impl TransitionFrontierState {
@@ -47,10 +53,12 @@ impl TransitionFrontierState {
             candidates: TransitionFrontierCandidatesState::new(),
             best_chain: Vec::with_capacity(290),
             needed_protocol_states: Default::default(),
+            root_history: Default::default(),
             sync: TransitionFrontierSyncState::Idle,
             blacklist: Default::default(),
             chain_diff: None,
             archive_enabled,
+            ledger_hashes_check: Default::default(),
         }
     }
 
@@ -70,10 +78,27 @@ impl TransitionFrontierState {
         self.best_chain.first()
     }
 
+    /// Blocks of the best chain, from the root up to and including the
+    /// block `hash`, or `None` if that block isn't in the best chain.
+    pub fn best_chain_up_to(&self, hash: &StateHash) -> Option<&[AppliedBlock]> {
+        let index = self
+            .best_chain
+            .iter()
+            .rposition(|block| block.hash() == hash)?;
+        Some(&self.best_chain[..=index])
+    }
+
+    /// Whether the transaction is included in the best chain, or only in
+    /// its blocks up to `up_to` if it's set.
+    ///
     /// FIXME
     /// Note(adonagy): This can be expensive, keep a map with all the tx hashis in the best chain
-    pub fn contains_transaction(&self, hash: &TransactionHash) -> bool {
-        self.best_chain.iter().any(|block| {
+    pub fn contains_transaction(&self, hash: &TransactionHash, up_to: Option<&StateHash>) -> bool {
+        let chain = match up_to {
+            None => &self.best_chain[..],
+            Some(up_to) => self.best_chain_up_to(up_to).unwrap_or_default(),
+        };
+        chain.iter().any(|block| {
             block
                 .body()
                 .transactions()
@@ -110,6 +135,9 @@ impl TransitionFrontierState {
 
     /// Create a diff between the old best chain and the new one
     /// This is used to update the transaction pool
+    ///
+    /// Returns `None` if the new chain extends the old one with blocks
+    /// without commands.
     pub fn maybe_make_chain_diff(&self, new_chain: &[AppliedBlock]) -> Option<BestTipDiff> {
         let old_chain = self.best_chain.as_slice();
         let new_root = new_chain.first();
@@ -193,15 +221,18 @@ impl TransitionFrontierState {
 
         let removed_commands = collect(diff_old_chain);
         let new_commands = collect(diff_new_chain);
+        // Blocks of the old chain were dropped, the new best tip doesn't
+        // just extend it.
+        let reorg_best_tip = !diff_old_chain.is_empty();
 
-        if removed_commands.is_empty() && new_commands.is_empty() {
+        if removed_commands.is_empty() && new_commands.is_empty() && !reorg_best_tip {
             return None;
         }
 
         Some(BestTipDiff {
             new_commands,
             removed_commands,
-            reorg_best_tip: false, // TODO: Unused for now
+            reorg_best_tip,
         })
     }
 
@@ -211,6 +242,7 @@ impl TransitionFrontierState {
             "needed_protocol_states_size": self
                 .needed_protocol_states
                 .len(),
+            "root_history_size": self.root_history.len(),
             "blacklist_size": self.blacklist.len(),
             "diff_tx_size": self
                 .chain_diff
@@ -0,0 +1,173 @@
+//! Artifact bundles of failed scenario runs.
+//!
+//! When a scenario fails, the cluster writes everything needed to
+//! investigate the failure into a timestamped directory:
+//!
+//! - `scenario.json` - scenario up to and including the failing step
+//! - `error.txt` - error or panic message
+//! - `rust_node_<i>/state.json` - state of each Rust node
+//! - `rust_node_<i>/recorder/` - recorder files of each Rust node
+//! - `ocaml_node_<i>/` - logs of each OCaml node
+//! - `debugger/` - connections and messages captured by the network
+//!   debugger, if it is used
+//!
+//! Bundles are written to `target/scenario-artifacts`, unless
+//! `MINA_SCENARIO_ARTIFACTS_DIR` is set.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use node::core::log::{system_time, warn};
+use serde::Serialize;
+
+use super::{Cluster, ClusterScenarioRun};
+use crate::scenario::{Scenario, ScenarioId};
+
+const ARTIFACTS_DIR_ENV: &str = "MINA_SCENARIO_ARTIFACTS_DIR";
+const DEFAULT_ARTIFACTS_DIR: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../target/scenario-artifacts"
+);
+
+impl Cluster {
+    /// Writes the artifact bundle of a failed run and returns its
+    /// directory.
+    ///
+    /// `scenario` holds the steps executed so far. If it isn't passed, the
+    /// scenario replayed by the cluster is exported up to the step it
+    /// failed on.
+    pub fn export_artifacts(
+        &self,
+        scenario: Option<&Scenario>,
+        error: &str,
+    ) -> anyhow::Result<PathBuf> {
+        let replayed;
+        let scenario = match scenario {
+            Some(scenario) => scenario,
+            None => {
+                replayed = self
+                    .scenario
+                    .executed_scenario()
+                    .context("cluster has no scenario")?;
+                &replayed
+            }
+        };
+
+        let dir = artifacts_dir(&scenario.info.id);
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create directory '{}'", dir.display()))?;
+        write_json(&dir.join("scenario.json"), scenario)?;
+        fs::write(dir.join("error.txt"), error)?;
+
+        for (node_id, node) in self.nodes_iter() {
+            let node_dir = dir.join(format!("rust_node_{}", node_id.index()));
+            fs::create_dir_all(&node_dir)?;
+            write_json(&node_dir.join("state.json"), node.state())?;
+
+            let recorder_dir = node.work_dir().path().join("recorder");
+            if recorder_dir.exists() {
+                copy_dir(&recorder_dir, &node_dir.join("recorder"))?;
+            }
+        }
+
+        for (node_id, node) in self.ocaml_nodes_iter() {
+            let node_dir = dir.join(format!("ocaml_node_{}", node_id.index()));
+            fs::create_dir_all(&node_dir)?;
+            copy_logs(&node.config_dir(), &node_dir)?;
+        }
+
+        if let Some(debugger) = self.debugger() {
+            let debugger_dir = dir.join("debugger");
+            fs::create_dir_all(&debugger_dir)?;
+            let connections = debugger.connections_raw(0).collect::<Vec<_>>();
+            write_json(&debugger_dir.join("connections.json"), &connections)?;
+            let messages = debugger.messages(0, "").collect::<Vec<_>>();
+            write_json(&debugger_dir.join("messages.json"), &messages)?;
+        }
+
+        Ok(dir)
+    }
+
+    /// Exports the artifact bundle of a failed run and prints its location.
+    /// Errors are only logged, so that they don't hide the original
+    /// failure.
+    pub fn export_failure_artifacts(&self, scenario: Option<&Scenario>, error: &str) {
+        match self.export_artifacts(scenario, error) {
+            Ok(dir) => eprintln!("scenario artifacts written to: {}", dir.display()),
+            Err(err) => {
+                warn!(system_time(); "failed to write scenario artifacts: {err:#}");
+            }
+        }
+    }
+}
+
+impl ClusterScenarioRun {
+    /// Replayed scenario up to and including the next step.
+    fn executed_scenario(&self) -> Option<Scenario> {
+        let Some((scenario_i, step_i)) = self.peek_i() else {
+            return self.target_scenario().cloned();
+        };
+        let scenario = &self.chain[scenario_i];
+        Some(Scenario {
+            info: scenario.info.clone(),
+            steps: scenario.steps[..=step_i].to_vec(),
+        })
+    }
+}
+
+fn artifacts_dir(scenario_id: &ScenarioId) -> PathBuf {
+    let base = std::env::var_os(ARTIFACTS_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_ARTIFACTS_DIR));
+    let now = time::OffsetDateTime::now_utc();
+    base.join(format!(
+        "{scenario_id}-{:04}{:02}{:02}-{:02}{:02}{:02}",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second(),
+    ))
+}
+
+fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> anyhow::Result<()> {
+    let file =
+        fs::File::create(path).with_context(|| format!("failed to create '{}'", path.display()))?;
+    serde_json::to_writer_pretty(std::io::BufWriter::new(file), value)
+        .with_context(|| format!("failed to write '{}'", path.display()))
+}
+
+fn copy_dir(from: &Path, to: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            copy_dir(&path, &to.join(entry.file_name()))?;
+        } else {
+            fs::copy(&path, to.join(entry.file_name()))
+                .with_context(|| format!("failed to copy '{}'", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Copies log files of an OCaml node, skipping the ledgers and keys which
+/// are also stored in its config directory.
+fn copy_logs(config_dir: &Path, to: &Path) -> anyhow::Result<()> {
+    let Ok(entries) = fs::read_dir(config_dir) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let entry = entry?;
+        let is_log = entry.file_name().to_string_lossy().contains(".log");
+        if is_log && entry.file_type()?.is_file() {
+            fs::copy(entry.path(), to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
@@ -14,12 +14,24 @@ pub struct ClusterConfig {
     use_debugger: bool,
     #[serde(default)]
     ocaml_node_executable: Option<OcamlNodeExecutable>,
+    #[serde(default)]
+    time_acceleration: Option<u32>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+/// How block and snark work proofs are produced and verified by the Rust
+/// nodes of the cluster.
+///
+/// Staged ledger diffs are created and applied the same way for every kind,
+/// only the proving and the proof verification are affected.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub enum ProofKind {
+    /// Blocks get a dummy proof, and block and work proofs are accepted
+    /// without verification. Block provers are not loaded.
     Dummy,
+    /// Block circuits are checked against the witness but no proof is
+    /// created, blocks get a dummy proof. Proofs are not verified.
     ConstraintsChecked,
+    /// Proofs are created and verified.
     Full,
 }
 
@@ -31,6 +43,8 @@ impl Default for ProofKind {
 }
 
 impl ClusterConfig {
+    pub const DEFAULT_PORT_RANGE: (u16, u16) = (11_000, 49_151);
+
     pub fn new(ocaml_node_executable: Option<OcamlNodeExecutable>) -> anyhow::Result<Self> {
         Ok(Self {
             port_range: None,
@@ -39,6 +53,7 @@ impl ClusterConfig {
             is_replay: false,
             use_debugger: false,
             ocaml_node_executable,
+            time_acceleration: None,
         })
     }
 
@@ -61,10 +76,19 @@ impl ClusterConfig {
     }
 
     pub fn port_range(&self) -> std::ops::RangeInclusive<u16> {
-        let range = self.port_range.unwrap_or((11_000, 49_151));
+        let range = self.port_range.unwrap_or(Self::DEFAULT_PORT_RANGE);
         (range.0)..=(range.1)
     }
 
+    pub fn has_port_range(&self) -> bool {
+        self.port_range.is_some()
+    }
+
+    pub fn set_port_range(&mut self, start: u16, end: u16) -> &mut Self {
+        self.port_range = Some((start, end));
+        self
+    }
+
     pub fn set_all_rust_to_rust_use_webrtc(&mut self) -> &mut Self {
         assert!(cfg!(feature = "p2p-webrtc"));
         self.all_rust_to_rust_use_webrtc = true;
@@ -84,6 +108,19 @@ impl ClusterConfig {
         self.proof_kind
     }
 
+    /// Makes the time of the Rust nodes go by `speed` times faster than the
+    /// wall clock, when advanced with [`RunCfgAdvanceTime::Real`].
+    ///
+    /// [`RunCfgAdvanceTime::Real`]: crate::scenarios::RunCfgAdvanceTime::Real
+    pub fn set_time_acceleration(&mut self, speed: u32) -> &mut Self {
+        self.time_acceleration = Some(speed);
+        self
+    }
+
+    pub fn time_acceleration(&self) -> u32 {
+        self.time_acceleration.unwrap_or(1)
+    }
+
     pub fn set_ocaml_node_executable(&mut self, executable: OcamlNodeExecutable) -> &mut Self {
         self.ocaml_node_executable = Some(executable);
         self
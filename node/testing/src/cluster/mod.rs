@@ -12,6 +12,7 @@
 //! - Port allocation and resource management
 //! - Scenario execution and state tracking
 //! - Network debugger integration
+//! - Artifact bundles of failed runs
 //!
 //! # Node Addition Methods
 //!
@@ -35,6 +36,8 @@ pub use config::{ClusterConfig, ProofKind};
 
 mod p2p_task_spawner;
 
+mod artifacts;
+
 mod node_id;
 use mina_core::channels::Aborter;
 pub use node_id::{ClusterNodeId, ClusterOcamlNodeId};
@@ -51,7 +54,7 @@ use std::{
 
 use libp2p::futures::{stream::FuturesUnordered, StreamExt};
 
-use ledger::proofs::provers::BlockProver;
+use ledger::{ondisk::IoFaults, proofs::provers::BlockProver};
 use mina_node_invariants::{InvariantResult, Invariants};
 use mina_node_native::{http_server, NodeServiceBuilder};
 use node::{
@@ -62,7 +65,7 @@ use node::{
         invariants::InvariantsState,
         log::{info, system_time, warn},
         requests::RpcId,
-        thread,
+        thread, TimeSource,
     },
     event_source::Event,
     p2p::{
@@ -189,6 +192,9 @@ pub struct Cluster {
     ocaml_nodes: Vec<Option<OcamlNode>>,
     /// Genesis timestamp for deterministic time progression
     initial_time: Option<redux::Timestamp>,
+    /// Scales the real time elapsed by the time the runner advances the
+    /// nodes by, the nodes themselves only see the advanced time.
+    time_source: TimeSource,
 
     /// Counter for generating unique RPC request IDs
     rpc_counter: usize,
@@ -244,6 +250,7 @@ impl Cluster {
             nodes: Vec::new(),
             ocaml_nodes: Vec::new(),
             initial_time: None,
+            time_source: TimeSource::accelerated(config.time_acceleration()),
 
             rpc_counter: 0,
             ocaml_libp2p_keypair_i: 0,
@@ -279,6 +286,10 @@ impl Cluster {
         self.initial_time
     }
 
+    pub fn time_source(&self) -> TimeSource {
+        self.time_source
+    }
+
     /// Add a new Rust implementation node to the cluster.
     ///
     /// Creates and configures a Rust Mina node with the specified testing
@@ -429,13 +440,17 @@ impl Cluster {
             ConsensusConstants::create(constraint_constants(), &protocol_constants);
 
         let config = Config {
-            ledger: LedgerConfig {},
+            ledger: LedgerConfig::default(),
             snark: SnarkConfig {
                 // TODO(binier): use cache
                 block_verifier_index: self.block_verifier_index.clone(),
-                block_verifier_srs: self.verifier_srs.clone(),
                 work_verifier_index: self.work_verifier_index.clone(),
-                work_verifier_srs: self.verifier_srs.clone(),
+                verifier_srs: self.verifier_srs.clone(),
+                timeouts: Default::default(),
+                verify_cache: Default::default(),
+                verify_concurrency: 1,
+                constraint_constants_digest: None,
+                verifier_digests: None,
             },
             global: GlobalConfig {
                 build: BuildEnv::get().into(),
@@ -448,7 +463,9 @@ impl Cluster {
                 libp2p_port: Some(libp2p_port),
                 listen_port: Some(http_port),
                 identity_pub_key: p2p_sec_key.public_key(),
+                previous_peer_ids: Vec::new(),
                 initial_peers,
+                initial_dnsaddrs: Vec::new(),
                 external_addrs: vec![],
                 enabled_channels: ChannelId::iter_all().collect(),
                 peer_discovery: testing_config.peer_discovery,
@@ -469,12 +486,31 @@ impl Cluster {
                 trust_system: (),
                 pool_max_size: 3000,
                 slot_tx_end: None,
+                max_lifetime: None,
+                zkapp_relay: Default::default(),
+                replace_fee: ledger::transaction_pool::DEFAULT_REPLACE_FEE,
             },
         };
 
         let mut service_builder = NodeServiceBuilder::new(rng_seed);
+        match testing_config.slow_disk {
+            None => service_builder.ledger_init(),
+            Some(slow_disk) => {
+                info!(
+                    system_time();
+                    "Rust node {} stores its ledgers on a slow disk: {:?}",
+                    node_id.index(),
+                    slow_disk
+                );
+                let io_faults = IoFaults {
+                    write_latency: slow_disk.write_latency,
+                    sync_latency: slow_disk.sync_latency,
+                    sync_failure_probability: slow_disk.sync_failure_probability,
+                };
+                service_builder.ledger_init_ondisk(work_dir.path().join("ledger"), io_faults)
+            }
+        };
         service_builder
-            .ledger_init()
             .p2p_init_with_custom_task_spawner(
                 p2p_sec_key.clone(),
                 p2p_task_spawner::P2pTaskSpawner::new(shutdown_listener.clone()),
@@ -489,8 +525,15 @@ impl Cluster {
 
         if let Some(keypair) = block_producer_sec_key {
             info!(system_time(); "Initializing block producer for Rust node {}", node_id.index());
-            let provers = BlockProver::make(None, None);
-            service_builder.block_producer_init(keypair, Some(provers));
+            // Provers are only needed to check constraints or to create
+            // real proofs, making them takes a while.
+            let provers = match self.config.proof_kind() {
+                ProofKind::Dummy => None,
+                ProofKind::ConstraintsChecked | ProofKind::Full => {
+                    Some(BlockProver::make(None, None))
+                }
+            };
+            service_builder.block_producer_init(keypair, provers);
         }
 
         let real_service = service_builder
@@ -512,7 +555,7 @@ impl Cluster {
                 let task = async {
                     tokio::select! {
                         _ = shutdown.wait() => {}
-                        _ = http_server::run(http_port, rpc_sender) => {}
+                        _ = http_server::run(http_port, rpc_sender, Default::default()) => {}
                     }
                 };
                 local_set.block_on(&runtime, task);
@@ -929,7 +972,13 @@ impl Cluster {
             Some(v) => v,
             None => return Ok(false),
         };
-        let dispatched = self.exec_step(step.clone()).await?;
+        let dispatched = match self.exec_step(step.clone()).await {
+            Ok(dispatched) => dispatched,
+            Err(err) => {
+                self.export_failure_artifacts(None, &format!("{err:#}"));
+                return Err(err);
+            }
+        };
 
         if dispatched {
             self.scenario.advance();
@@ -1093,6 +1142,14 @@ impl Cluster {
                 node.advance_time(by_nanos);
                 true
             }
+            #[cfg(feature = "byzantine")]
+            ScenarioStep::Byzantine { node_id, behavior } => {
+                let node = self
+                    .nodes
+                    .get_mut(node_id.index())
+                    .ok_or_else(|| anyhow::anyhow!("node {node_id:?} not found"))?;
+                node.byzantine_broadcast(behavior)
+            }
             ScenarioStep::Ocaml { node_id, step } => {
                 let node = self.ocaml_nodes.get_mut(node_id.index());
                 let node =
@@ -6,6 +6,7 @@ use std::{path::PathBuf, time::Duration};
 use ledger::BaseLedger;
 use node::{
     account::{AccountPublicKey, AccountSecretKey},
+    core::TimeSource,
     event_source::Event,
     ledger::LedgerService,
     ActionKind, State,
@@ -106,6 +107,10 @@ impl<'a> ClusterRunner<'a> {
         self.cluster.set_initial_time(initial_time)
     }
 
+    pub fn time_source(&self) -> TimeSource {
+        self.cluster.time_source()
+    }
+
     pub fn get_account_sec_key(&self, pub_key: &AccountPublicKey) -> Option<&AccountSecretKey> {
         self.cluster.get_account_sec_key(pub_key)
     }
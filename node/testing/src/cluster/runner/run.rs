@@ -28,7 +28,8 @@ pub enum RunCfgAdvanceTime {
     /// Set the range of time in milliseconds, with which time will be
     /// advanced during `run` function execution.
     Rand(std::ops::RangeInclusive<u64>),
-    /// Advance time so that node's time matches the real time.
+    /// Advance time so that node's time matches the real time, sped up by
+    /// the time acceleration of the cluster.
     #[default]
     Real,
 }
@@ -129,8 +130,9 @@ impl super::ClusterRunner<'_> {
                             let latest: &mut redux::Timestamp =
                                 self.latest_advance_time.get_or_insert(now);
                             let latest = std::mem::replace(latest, now);
-                            now.checked_sub(latest)
-                                .map_or(0, |dur| dur.as_nanos() as u64)
+                            now.checked_sub(latest).map_or(0, |dur| {
+                                self.cluster.time_source().scale(dur).as_nanos() as u64
+                            })
                         }
                     };
                     self.exec_step(ScenarioStep::AdvanceTime { by_nanos })
@@ -25,7 +25,7 @@
 use clap::Parser;
 
 use mina_node_testing::{
-    cluster::{Cluster, ClusterConfig},
+    cluster::{Cluster, ClusterConfig, ProofKind},
     exit_with_error,
     scenario::Scenario,
     scenarios::Scenarios,
@@ -60,6 +60,10 @@ pub struct CommandServer {
     pub port: u16,
     #[arg(long, short)]
     pub ssl_port: Option<u16>,
+    /// Token required in the `Authorization: Bearer <token>` header of
+    /// API requests. The API is unauthenticated if not set.
+    #[arg(long, env = "MINA_TESTING_SERVER_TOKEN")]
+    pub token: Option<String>,
 }
 
 #[derive(Debug, clap::Args)]
@@ -72,6 +76,13 @@ pub struct CommandScenariosGenerate {
     pub webrtc: bool,
     #[arg(long, short = 'o', default_value = "stdout", value_enum)]
     pub output: OutputFormat,
+    /// How block proofs are created and verified by the Rust nodes.
+    #[arg(long, value_enum)]
+    pub proof_kind: Option<ProofKind>,
+    /// Make the time of the Rust nodes go by this many times faster than
+    /// the wall clock, where scenarios follow the real time.
+    #[arg(long)]
+    pub time_acceleration: Option<u32>,
 }
 
 #[derive(Debug, Clone, clap::ValueEnum)]
@@ -88,6 +99,9 @@ pub struct CommandScenariosRun {
     /// Must match filename in `res/scenarios` (without an extension).
     #[arg(long, short)]
     pub name: String,
+    /// How block proofs are created and verified by the Rust nodes.
+    #[arg(long, value_enum)]
+    pub proof_kind: Option<ProofKind>,
 }
 
 #[derive(Debug, clap::Args)]
@@ -113,7 +127,7 @@ impl Command {
 
         match self {
             Self::Server(args) => {
-                server(rt, args.host, args.port, args.ssl_port);
+                server(rt, args.host, args.port, args.ssl_port, args.token);
                 Ok(())
             }
             Self::ScenariosGenerate(cmd) => {
@@ -128,6 +142,12 @@ impl Command {
                         if cmd.webrtc {
                             config.set_all_rust_to_rust_use_webrtc();
                         }
+                        if let Some(proof_kind) = cmd.proof_kind {
+                            config.set_proof_kind(proof_kind);
+                        }
+                        if let Some(speed) = cmd.time_acceleration {
+                            config.set_time_acceleration(speed);
+                        }
                         Ok((scenario, config))
                     };
                     let fut = async move {
@@ -179,6 +199,9 @@ impl Command {
                     anyhow::anyhow!("failed to create cluster configuration: {err}")
                 })?;
                 config.set_replay();
+                if let Some(proof_kind) = cmd.proof_kind {
+                    config.set_proof_kind(proof_kind);
+                }
 
                 let id = cmd.name.parse()?;
                 let fut = async move {
@@ -2,7 +2,11 @@ mod config;
 pub use config::*;
 
 mod rust;
+#[cfg(feature = "byzantine")]
+pub use rust::ByzantineBehavior;
 pub use rust::{Node, NonDeterministicEvent};
 
 mod ocaml;
-pub use ocaml::{OcamlNode, OcamlStep};
+pub use ocaml::{
+    OcamlAccount, OcamlNode, OcamlNodeGraphQL, OcamlPayment, OcamlStep, OcamlSyncStatus,
+};
@@ -0,0 +1,280 @@
+//! Typed GraphQL access to OCaml nodes.
+//!
+//! Lets scenarios query and drive OCaml nodes through their GraphQL API
+//! instead of waiting on logs.
+
+use std::{borrow::Cow, str::FromStr};
+
+use ledger::scan_state::{
+    currency::{Amount, Fee, Nonce, Slot},
+    transaction_logic::{
+        signed_command::{Body, Common, PaymentPayload, SignedCommandPayload},
+        transaction_union_payload::TransactionUnionPayload,
+        Memo,
+    },
+};
+use mina_core::{network::NetworkId, ChainId, NetworkConfig};
+use mina_graphql_client::{
+    mutations::{SendPayment, SendPaymentInput, UserCommandSignature},
+    queries, ClientConfig, GraphQLClient, GraphQLOperation,
+};
+use mina_p2p_messages::{bigint::BigInt, v2::StateHash};
+use mina_signer::{Keypair, Signer};
+use node::{
+    account::{AccountPublicKey, AccountSecretKey},
+    p2p::PeerId,
+};
+use serde::Deserialize;
+
+/// Longest memo the OCaml node accepts, in bytes. Longer ones are rejected
+/// rather than truncated.
+const MEMO_MAX_LENGTH: usize = 32;
+
+#[derive(Debug, Clone)]
+pub struct OcamlNodeGraphQL {
+    client: GraphQLClient,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OcamlSyncStatus {
+    Connecting,
+    Listening,
+    Offline,
+    Bootstrap,
+    Synced,
+    Catchup,
+}
+
+#[derive(Debug, Clone)]
+pub struct OcamlAccount {
+    /// Total balance in nanomina.
+    pub balance: u64,
+    pub nonce: u32,
+    pub delegate: Option<AccountPublicKey>,
+}
+
+/// Payment signed by the testing framework before it is sent, so the
+/// sender's key doesn't need to be in the OCaml node's wallet.
+#[derive(Debug, Clone)]
+pub struct OcamlPayment {
+    pub from: AccountSecretKey,
+    pub to: AccountPublicKey,
+    /// Amount in nanomina.
+    pub amount: u64,
+    /// Fee in nanomina.
+    pub fee: u64,
+    /// Queried from the sender's account if not set.
+    pub nonce: Option<u32>,
+    pub memo: Option<String>,
+}
+
+impl OcamlNodeGraphQL {
+    pub fn new(graphql_port: u16) -> anyhow::Result<Self> {
+        let endpoint = format!("http://127.0.0.1:{graphql_port}/graphql");
+        let client = GraphQLClient::new(ClientConfig::new(endpoint))?;
+        Ok(Self { client })
+    }
+
+    /// Underlying client, for operations without a helper.
+    pub fn client(&self) -> &GraphQLClient {
+        &self.client
+    }
+
+    pub async fn sync_status(&self) -> anyhow::Result<OcamlSyncStatus> {
+        self.client
+            .execute(&queries::SyncStatus)
+            .await?
+            .sync_status
+            .parse()
+    }
+
+    /// Best tip hash, if the node is synced.
+    pub async fn synced_best_tip(&self) -> anyhow::Result<Option<StateHash>> {
+        if self.sync_status().await? != OcamlSyncStatus::Synced {
+            return Ok(None);
+        }
+        let status = self.client.execute(&queries::DaemonStatus).await?;
+        match status.daemon_status.state_hash {
+            Some(state_hash) => Ok(Some(state_hash.parse()?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn chain_id(&self) -> anyhow::Result<ChainId> {
+        let status = self.client.execute(&queries::DaemonStatus).await?;
+        let chain_id = status
+            .daemon_status
+            .chain_id
+            .ok_or_else(|| anyhow::anyhow!("empty chain_id response"))?;
+        ChainId::from_hex(&chain_id).map_err(|e| anyhow::anyhow!("invalid chain_id: {}", e))
+    }
+
+    pub async fn account(
+        &self,
+        public_key: &AccountPublicKey,
+    ) -> anyhow::Result<Option<OcamlAccount>> {
+        let Some(account) = self
+            .client
+            .execute(&queries::Account::new(public_key.to_string()))
+            .await?
+            .account
+        else {
+            return Ok(None);
+        };
+        Ok(Some(OcamlAccount {
+            balance: account.balance.total.parse()?,
+            nonce: account.nonce.parse()?,
+            delegate: account
+                .delegate_account
+                .map(|delegate| delegate.public_key.parse())
+                .transpose()?,
+        }))
+    }
+
+    /// Signs and sends the payment, returning its hash.
+    pub async fn send_payment(&self, payment: OcamlPayment) -> anyhow::Result<String> {
+        let from = payment.from.public_key();
+        let nonce = match payment.nonce {
+            Some(nonce) => nonce,
+            None => {
+                self.account(&from)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("sender account {from} not found"))?
+                    .nonce
+            }
+        };
+        let memo = payment.memo.unwrap_or_default();
+        let signed_memo = payment_memo(&memo)?;
+
+        let payload = SignedCommandPayload {
+            common: Common {
+                fee: Fee::from_u64(payment.fee),
+                fee_payer_pk: payment.from.public_key_compressed(),
+                nonce: Nonce::from_u32(nonce),
+                valid_until: Slot::max(),
+                memo: signed_memo,
+            },
+            body: Body::Payment(PaymentPayload {
+                receiver_pk: payment
+                    .to
+                    .clone()
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("invalid receiver {}", payment.to))?,
+                amount: Amount::from_u64(payment.amount),
+            }),
+        };
+        let network_id = match NetworkConfig::global().network_id {
+            NetworkId::MAINNET => mina_signer::NetworkId::MAINNET,
+            NetworkId::TESTNET => mina_signer::NetworkId::TESTNET,
+        };
+        let mut signer = mina_signer::create_legacy(network_id);
+        let keypair: Keypair = payment.from.into();
+        let signature = signer.sign(
+            &keypair,
+            &TransactionUnionPayload::of_user_command_payload(&payload),
+            true,
+        );
+
+        let mutation = SendPayment {
+            input: SendPaymentInput {
+                from: from.to_string(),
+                to: payment.to.to_string(),
+                amount: payment.amount.to_string(),
+                fee: payment.fee.to_string(),
+                memo: (!memo.is_empty()).then_some(memo),
+                nonce: Some(nonce.to_string()),
+                valid_until: Some(Slot::max().as_u32().to_string()),
+            },
+            signature: UserCommandSignature {
+                field: BigInt::from(signature.rx).to_decimal(),
+                scalar: BigInt::from(signature.s).to_decimal(),
+            },
+        };
+        Ok(self
+            .client
+            .execute(&mutation)
+            .await?
+            .send_payment
+            .payment
+            .hash)
+    }
+
+    /// Status of a payment, either `PENDING`, `INCLUDED` or `UNKNOWN`.
+    pub async fn transaction_status(&self, hash: &str) -> anyhow::Result<String> {
+        Ok(self
+            .client
+            .execute(&queries::TransactionStatus::new(hash))
+            .await?
+            .transaction_status)
+    }
+
+    /// Peers the node is connected to.
+    pub async fn peers(&self) -> anyhow::Result<Vec<PeerId>> {
+        self.client
+            .execute(&GetPeers)
+            .await?
+            .get_peers
+            .into_iter()
+            .map(|peer| {
+                let peer_id = peer.peer_id.parse::<libp2p::PeerId>()?;
+                Ok(peer_id.try_into()?)
+            })
+            .collect()
+    }
+}
+
+impl FromStr for OcamlSyncStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "CONNECTING" => Self::Connecting,
+            "LISTENING" => Self::Listening,
+            "OFFLINE" => Self::Offline,
+            "BOOTSTRAP" => Self::Bootstrap,
+            "SYNCED" => Self::Synced,
+            "CATCHUP" => Self::Catchup,
+            _ => anyhow::bail!("unknown sync status: {s}"),
+        })
+    }
+}
+
+/// Connected peers. Only supported by the OCaml node.
+struct GetPeers;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetPeersData {
+    get_peers: Vec<GetPeersPeer>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetPeersPeer {
+    peer_id: String,
+}
+
+impl GraphQLOperation for GetPeers {
+    type Data = GetPeersData;
+
+    fn document(&self) -> Cow<'static, str> {
+        r#"query GetPeers {
+  getPeers {
+    peerId
+  }
+}"#
+        .into()
+    }
+}
+
+/// Memo to sign, which must be the one the OCaml node reads from the
+/// request, or the signature won't match.
+fn payment_memo(memo: &str) -> anyhow::Result<Memo> {
+    if memo.len() > MEMO_MAX_LENGTH {
+        anyhow::bail!(
+            "memo {memo:?} is {} bytes long, the maximum is {MEMO_MAX_LENGTH}",
+            memo.len()
+        );
+    }
+    Memo::from_str(memo).map_err(|()| anyhow::anyhow!("invalid memo {memo:?}"))
+}
@@ -1,5 +1,9 @@
 mod config;
 pub use config::*;
+
+mod graphql;
+pub use graphql::{OcamlAccount, OcamlNodeGraphQL, OcamlPayment, OcamlSyncStatus};
+
 use mina_core::{thread, ChainId};
 use mina_p2p_messages::v2::StateHash;
 use node::{
@@ -179,6 +183,11 @@ impl OcamlNode {
         self.peer_id.try_into().unwrap()
     }
 
+    /// Directory passed as `--config-dir`, which also holds the node logs.
+    pub fn config_dir(&self) -> PathBuf {
+        self.temp_dir.path().join(".config")
+    }
+
     pub async fn exec(&mut self, step: OcamlStep) -> anyhow::Result<bool> {
         Ok(match step {
             OcamlStep::WaitReady { timeout } => {
@@ -300,53 +309,20 @@ impl OcamlNode {
         Ok(())
     }
 
+    /// Typed GraphQL client of the node.
+    pub fn graphql(&self) -> anyhow::Result<OcamlNodeGraphQL> {
+        OcamlNodeGraphQL::new(self.graphql_port)
+    }
+
     /// Queries graphql to get chain_id.
     pub async fn chain_id(&self) -> anyhow::Result<ChainId> {
-        let res = self
-            .grapql_query("query { daemonStatus { chainId } }")
-            .await?;
-        let chain_id = res["data"]["daemonStatus"]["chainId"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("empty chain_id response"))?;
-        ChainId::from_hex(chain_id).map_err(|e| anyhow::anyhow!("invalid chain_id: {}", e))
+        self.graphql()?.chain_id().await
     }
 
     /// Queries graphql to check if ocaml node is synced,
     /// returning it's best tip hash if yes.
     pub async fn synced_best_tip(&self) -> anyhow::Result<Option<StateHash>> {
-        let mut res = self
-            .grapql_query("query { daemonStatus { syncStatus, stateHash } }")
-            .await?;
-        let data = &mut res["data"]["daemonStatus"];
-        if data["syncStatus"].as_str() == Some("SYNCED") {
-            Ok(Some(serde_json::from_value(data["stateHash"].take())?))
-        } else {
-            Ok(None)
-        }
-    }
-
-    fn graphql_addr(&self) -> String {
-        format!("http://127.0.0.1:{}/graphql", self.graphql_port)
-    }
-
-    // TODO(binier): shouldn't be publically accessible.
-    //
-    // Only `exec` function should be exposed and instead of this, we
-    // should have a step to query graphql and assert response as a part
-    // of that step.
-    pub async fn grapql_query(&self, query: &str) -> anyhow::Result<serde_json::Value> {
-        let client = reqwest::Client::new();
-        let response = client
-            .post(self.graphql_addr())
-            .json(&{
-                serde_json::json!({
-                    "query": query
-                })
-            })
-            .send()
-            .await?;
-
-        Ok(response.json().await?)
+        self.graphql()?.synced_best_tip().await
     }
 
     async fn wait_for_p2p(&self, timeout: Duration) -> anyhow::Result<()> {
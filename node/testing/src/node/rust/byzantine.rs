@@ -0,0 +1,89 @@
+//! Invalid gossip emitted on purpose, to test how honest nodes react to
+//! byzantine peers.
+//!
+//! The messages are built from the state of the byzantine node itself, which
+//! is otherwise a normal node, and broadcast to its peers as if it was the
+//! author. Receiving nodes are expected to reject them, and may disconnect
+//! the peer with [`P2pDisconnectionReason::InvalidMessage`] or
+//! [`P2pDisconnectionReason::SnarkPoolVerifyError`], which bans it if bans
+//! are enabled, see [`P2pDisconnectionBansState`].
+//!
+//! [`P2pDisconnectionReason::InvalidMessage`]: node::p2p::disconnection::P2pDisconnectionReason::InvalidMessage
+//! [`P2pDisconnectionReason::SnarkPoolVerifyError`]: node::p2p::disconnection::P2pDisconnectionReason::SnarkPoolVerifyError
+//! [`P2pDisconnectionBansState`]: node::p2p::disconnection::P2pDisconnectionBansState
+
+use std::sync::Arc;
+
+use mina_p2p_messages::{gossip::GossipNetMessageV2, v2};
+use node::{core::snark::Snark, State};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByzantineBehavior {
+    /// Broadcast the best tip with its proof replaced by a dummy one.
+    ///
+    /// Receivers must not have the block yet, or they ignore it as a
+    /// duplicate, and must verify block proofs.
+    InvalidBlockProof,
+    /// Broadcast a block conflicting with the best tip: same parent, slot
+    /// and producer, but a different protocol state, so a different hash.
+    ///
+    /// The byzantine node doesn't have the key of the producer, so the block
+    /// keeps the proof of the best tip, which doesn't verify against the
+    /// changed protocol state. Receivers already having the best tip still
+    /// verify it, as its hash is new to them.
+    Equivocation,
+    /// Broadcast a snark from the pool with its fee lowered by one nanomina,
+    /// so that receivers prefer it over the original. The proofs commit to
+    /// the fee, so their verification fails.
+    WrongSnarkFee,
+    /// Broadcast a user command which is already included in the best tip.
+    ReplayedTransaction,
+}
+
+impl ByzantineBehavior {
+    /// Message to broadcast, or `None` if the node doesn't have what is
+    /// needed to build it yet (best tip, snark or command).
+    pub fn message(self, state: &State, nonce: u32) -> Option<GossipNetMessageV2> {
+        match self {
+            Self::InvalidBlockProof => {
+                let best_tip = state.transition_frontier.best_tip()?;
+                let mut block = (*best_tip.block).clone();
+                block.header.protocol_state_proof = node::core::dummy::dummy_blockchain_proof();
+                Some(GossipNetMessageV2::NewState(Arc::new(block)))
+            }
+            Self::Equivocation => {
+                let best_tip = state.transition_frontier.best_tip()?;
+                let mut block = (*best_tip.block).clone();
+                let timestamp = &mut block.header.protocol_state.body.blockchain_state.timestamp;
+                *timestamp = v2::BlockTimeTimeStableV1((timestamp.as_u64() + 1).into());
+                Some(GossipNetMessageV2::NewState(Arc::new(block)))
+            }
+            Self::WrongSnarkFee => {
+                let snark = state
+                    .snark_pool
+                    .completed_snarks_iter()
+                    .find(|snark| snark.fee.0.as_u64() > 0)?;
+                let snark = Snark {
+                    fee: v2::CurrencyFeeStableV1((snark.fee.0.as_u64() - 1).into()),
+                    ..snark.clone()
+                };
+                let message = Box::new((snark.statement(), (&snark).into()));
+                Some(GossipNetMessageV2::SnarkPoolDiff {
+                    message: v2::NetworkPoolSnarkPoolDiffVersionedStableV2::AddSolvedWork(message),
+                    nonce: nonce.into(),
+                })
+            }
+            Self::ReplayedTransaction => {
+                let best_tip = state.transition_frontier.best_tip()?;
+                let command = best_tip.commands_iter().next()?.data.clone();
+                Some(GossipNetMessageV2::TransactionPoolDiff {
+                    message: v2::NetworkPoolTransactionPoolDiffVersionedStableV2(
+                        std::iter::once(command).collect(),
+                    ),
+                    nonce: nonce.into(),
+                })
+            }
+        }
+    }
+}
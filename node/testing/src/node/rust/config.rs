@@ -1,4 +1,4 @@
-use std::{fs::File, path::Path, sync::Arc};
+use std::{fs::File, path::Path, sync::Arc, time::Duration};
 
 use node::{
     account::AccountSecretKey, config::DEVNET_CONFIG, p2p::P2pTimeouts,
@@ -39,6 +39,22 @@ pub struct RustNodeTestingConfig {
     #[serde(default)]
     pub recorder: Recorder,
     pub peer_discovery: bool,
+    /// Stores the ledgers on disk instead of in memory, with the disk made
+    /// slow by the given latencies.
+    #[serde(default)]
+    pub slow_disk: Option<SlowDisk>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub struct SlowDisk {
+    /// Latency added to each write to the ledger files
+    pub write_latency: Duration,
+    /// Latency added to each `fsync` of the ledger files
+    pub sync_latency: Duration,
+    /// Probability, between 0 and 1, of an `fsync` of the ledger files to
+    /// fail
+    #[serde(default)]
+    pub sync_failure_probability: f64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -68,6 +84,7 @@ impl RustNodeTestingConfig {
             libp2p_port: None,
             recorder: Default::default(),
             peer_discovery: true,
+            slow_disk: None,
         }
     }
 
@@ -84,6 +101,7 @@ impl RustNodeTestingConfig {
             libp2p_port: None,
             recorder: Default::default(),
             peer_discovery: true,
+            slow_disk: None,
         }
     }
 
@@ -129,4 +147,9 @@ impl RustNodeTestingConfig {
         self.peer_discovery = false;
         self
     }
+
+    pub fn with_slow_disk(mut self, slow_disk: SlowDisk) -> Self {
+        self.slow_disk = Some(slow_disk);
+        self
+    }
 }
@@ -4,6 +4,11 @@ pub use config::*;
 mod event;
 pub use event::*;
 
+#[cfg(feature = "byzantine")]
+mod byzantine;
+#[cfg(feature = "byzantine")]
+pub use byzantine::*;
+
 use node::{
     event_source::EventSourceAction,
     p2p::{
@@ -196,4 +201,22 @@ impl Node {
     pub fn p2p_disconnect(&mut self, peer_id: PeerId) -> bool {
         self.service_mut().disconnect(peer_id)
     }
+
+    /// Broadcasts an invalid message to the peers of the node, see
+    /// [`ByzantineBehavior`]. Returns `false` if the message can't be built
+    /// from the current state of the node.
+    #[cfg(feature = "byzantine")]
+    pub fn byzantine_broadcast(&mut self, behavior: ByzantineBehavior) -> bool {
+        use node::p2p::P2pNetworkPubsubAction;
+
+        let nonce = self
+            .state()
+            .p2p
+            .ready()
+            .map_or(0, |p2p| p2p.network.scheduler.broadcast_state.seq as u32);
+        let Some(message) = behavior.message(self.state(), nonce) else {
+            return false;
+        };
+        self.dispatch(P2pNetworkPubsubAction::Broadcast { message })
+    }
 }
@@ -53,6 +53,12 @@ pub enum ScenarioStep {
         node_id: ClusterOcamlNodeId,
         step: OcamlStep,
     },
+    /// Make the node broadcast an invalid message to its peers.
+    #[cfg(feature = "byzantine")]
+    Byzantine {
+        node_id: ClusterNodeId,
+        behavior: crate::node::ByzantineBehavior,
+    },
 }
 
 #[derive(Serialize, Deserialize, derive_more::From, Debug, Clone)]
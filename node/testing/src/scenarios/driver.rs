@@ -53,33 +53,6 @@ pub fn match_addr_with_port_and_peer_id(
     }
 }
 
-pub fn get_peers_iter(
-    data: &serde_json::Value,
-) -> Option<impl Iterator<Item = Option<(&str, i64, &str)>>> {
-    let iter = data
-        .as_object()?
-        .get("data")?
-        .get("getPeers")?
-        .as_array()?
-        .iter()
-        .map(|elt| {
-            let elt = elt.as_object()?;
-            let host = elt.get("host")?.as_str()?;
-            let port = elt.get("libp2pPort")?.as_i64()?;
-            let peer_id = elt.get("peerId")?.as_str()?;
-            Some((host, port, peer_id))
-        });
-    Some(iter)
-}
-
-pub const PEERS_QUERY: &str = r#"query {
-  getPeers {
-    host
-    libp2pPort
-    peerId
-  }
-}"#;
-
 pub fn connection_finalized_event(
     pred: impl Fn(ClusterNodeId, &PeerId) -> bool,
 ) -> impl Fn(ClusterNodeId, &Event, &State) -> bool {
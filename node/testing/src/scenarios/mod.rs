@@ -68,6 +68,9 @@ pub use driver::*;
 
 pub use crate::cluster::runner::*;
 
+use std::panic::AssertUnwindSafe;
+
+use libp2p::futures::FutureExt;
 use mina_core::log::{debug, system_time, warn};
 use strum_macros::{EnumIter, EnumString, IntoStaticStr};
 
@@ -76,6 +79,10 @@ use crate::{
     scenario::{Scenario, ScenarioId, ScenarioStep},
 };
 
+#[cfg(feature = "byzantine")]
+use self::multi_node::byzantine::{
+    MultiNodeByzantineEquivocation, MultiNodeByzantineWrongSnarkFee,
+};
 use self::{
     multi_node::{
         basic_connectivity_initial_joining::MultiNodeBasicConnectivityInitialJoining,
@@ -86,7 +93,9 @@ use self::{
             RustToOCamlViaSeed,
         },
         pubsub_advanced::MultiNodePubsubPropagateBlock,
+        slow_disk::MultiNodeSlowDisk,
         sync_4_block_producers::MultiNodeSync4BlockProducers,
+        time_acceleration::MultiNodeTimeAcceleration,
         vrf_correct_ledgers::MultiNodeVrfGetCorrectLedgers,
         vrf_correct_slots::MultiNodeVrfGetCorrectSlots,
         vrf_epoch_bounds_correct_ledgers::MultiNodeVrfEpochBoundsCorrectLedger,
@@ -111,11 +120,15 @@ use self::{
     record_replay::{
         block_production::RecordReplayBlockProduction, bootstrap::RecordReplayBootstrap,
     },
-    simulation::{small::SimulationSmall, small_forever_real_time::SimulationSmallForeverRealTime},
+    simulation::{
+        small::SimulationSmall, small_forever_real_time::SimulationSmallForeverRealTime,
+        soak::SimulationSoak,
+    },
     solo_node::{
         basic_connectivity_accept_incoming::SoloNodeBasicConnectivityAcceptIncoming,
         basic_connectivity_initial_joining::SoloNodeBasicConnectivityInitialJoining,
-        bootstrap::SoloNodeBootstrap, sync_root_snarked_ledger::SoloNodeSyncRootSnarkedLedger,
+        block_inject::SoloNodeBlockInject, bootstrap::SoloNodeBootstrap,
+        sync_root_snarked_ledger::SoloNodeSyncRootSnarkedLedger,
         sync_to_genesis::SoloNodeSyncToGenesis,
         sync_to_genesis_custom::SoloNodeSyncToGenesisCustom,
     },
@@ -130,6 +143,7 @@ pub enum Scenarios {
     SoloNodeSyncRootSnarkedLedger(SoloNodeSyncRootSnarkedLedger),
     SoloNodeBasicConnectivityInitialJoining(SoloNodeBasicConnectivityInitialJoining),
     SoloNodeBasicConnectivityAcceptIncoming(SoloNodeBasicConnectivityAcceptIncoming),
+    SoloNodeBlockInject(SoloNodeBlockInject),
     MultiNodeSync4BlockProducers(MultiNodeSync4BlockProducers),
     MultiNodeVrfGetCorrectLedgers(MultiNodeVrfGetCorrectLedgers),
     MultiNodeVrfGetCorrectSlots(MultiNodeVrfGetCorrectSlots),
@@ -139,10 +153,17 @@ pub enum Scenarios {
     MultiNodeBasicConnectivityPeerDiscovery(MultiNodeBasicConnectivityPeerDiscovery),
     SimulationSmall(SimulationSmall),
     SimulationSmallForeverRealTime(SimulationSmallForeverRealTime),
+    SimulationSoak(SimulationSoak),
     P2pReceiveMessage(P2pReceiveMessage),
     P2pSignaling(P2pSignaling),
     P2pConnectionDiscoveryRustNodeAsSeed(P2pConnectionDiscoveryRustNodeAsSeed),
     MultiNodePubsubPropagateBlock(MultiNodePubsubPropagateBlock),
+    #[cfg(feature = "byzantine")]
+    MultiNodeByzantineEquivocation(MultiNodeByzantineEquivocation),
+    #[cfg(feature = "byzantine")]
+    MultiNodeByzantineWrongSnarkFee(MultiNodeByzantineWrongSnarkFee),
+    MultiNodeSlowDisk(MultiNodeSlowDisk),
+    MultiNodeTimeAcceleration(MultiNodeTimeAcceleration),
     RecordReplayBootstrap(RecordReplayBootstrap),
     RecordReplayBlockProduction(RecordReplayBlockProduction),
 
@@ -186,6 +207,7 @@ impl Scenarios {
             Self::MultiNodeBasicConnectivityPeerDiscovery(_) => cfg!(feature = "p2p-webrtc"),
             Self::SimulationSmall(_) => true,
             Self::SimulationSmallForeverRealTime(_) => true,
+            Self::SimulationSoak(_) => true,
             Self::MultiNodePubsubPropagateBlock(_) => true, // in progress
             Self::P2pSignaling(_) => !cfg!(feature = "p2p-webrtc"),
             _ => false,
@@ -230,6 +252,7 @@ impl Scenarios {
             Self::SoloNodeBasicConnectivityAcceptIncoming(_) => {
                 SoloNodeBasicConnectivityAcceptIncoming::DOCS
             }
+            Self::SoloNodeBlockInject(_) => SoloNodeBlockInject::DOCS,
             Self::MultiNodeSync4BlockProducers(_) => MultiNodeSync4BlockProducers::DOCS,
             Self::MultiNodeVrfGetCorrectLedgers(_) => MultiNodeVrfGetCorrectLedgers::DOCS,
             Self::MultiNodeVrfGetCorrectSlots(_) => MultiNodeVrfGetCorrectSlots::DOCS,
@@ -245,12 +268,19 @@ impl Scenarios {
             }
             Self::SimulationSmall(_) => SimulationSmall::DOCS,
             Self::SimulationSmallForeverRealTime(_) => SimulationSmallForeverRealTime::DOCS,
+            Self::SimulationSoak(_) => SimulationSoak::DOCS,
             Self::P2pReceiveMessage(_) => P2pReceiveMessage::DOCS,
             Self::P2pSignaling(_) => P2pSignaling::DOCS,
             Self::P2pConnectionDiscoveryRustNodeAsSeed(_) => {
                 P2pConnectionDiscoveryRustNodeAsSeed::DOCS
             }
             Self::MultiNodePubsubPropagateBlock(_) => MultiNodePubsubPropagateBlock::DOCS,
+            #[cfg(feature = "byzantine")]
+            Self::MultiNodeByzantineEquivocation(_) => MultiNodeByzantineEquivocation::DOCS,
+            #[cfg(feature = "byzantine")]
+            Self::MultiNodeByzantineWrongSnarkFee(_) => MultiNodeByzantineWrongSnarkFee::DOCS,
+            Self::MultiNodeSlowDisk(_) => MultiNodeSlowDisk::DOCS,
+            Self::MultiNodeTimeAcceleration(_) => MultiNodeTimeAcceleration::DOCS,
             Self::RecordReplayBootstrap(_) => RecordReplayBootstrap::DOCS,
             Self::RecordReplayBlockProduction(_) => RecordReplayBlockProduction::DOCS,
 
@@ -283,6 +313,11 @@ impl Scenarios {
 
         match self {
             Self::P2pSignaling(v) => v.default_cluster_config(config),
+            #[cfg(feature = "byzantine")]
+            Self::MultiNodeByzantineEquivocation(v) => v.default_cluster_config(config),
+            #[cfg(feature = "byzantine")]
+            Self::MultiNodeByzantineWrongSnarkFee(v) => v.default_cluster_config(config),
+            Self::MultiNodeTimeAcceleration(v) => v.default_cluster_config(config),
             _ => Ok(config),
         }
     }
@@ -295,11 +330,31 @@ impl Scenarios {
         scenario
     }
 
-    async fn run<F>(self, cluster: &mut Cluster, add_step: F)
+    /// Runs the scenario. If it panics, artifacts of the run are exported
+    /// before the panic is resumed.
+    async fn run<F>(self, cluster: &mut Cluster, mut add_step: F)
     where
         F: Send + FnMut(&ScenarioStep),
     {
-        let runner = ClusterRunner::new(cluster, add_step);
+        let mut executed = self.blank_scenario();
+        let runner = ClusterRunner::new(cluster, |step: &ScenarioStep| {
+            executed.steps.push(step.clone());
+            add_step(step);
+        });
+        let result = AssertUnwindSafe(self.run_with(runner)).catch_unwind().await;
+
+        if let Err(panic) = result {
+            let message = panic
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("panic without a message");
+            cluster.export_failure_artifacts(Some(&executed), message);
+            std::panic::resume_unwind(panic);
+        }
+    }
+
+    async fn run_with(self, runner: ClusterRunner<'_>) {
         match self {
             Self::SoloNodeSyncToGenesis(v) => v.run(runner).await,
             Self::SoloNodeBootstrap(v) => v.run(runner).await,
@@ -307,6 +362,7 @@ impl Scenarios {
             Self::SoloNodeSyncRootSnarkedLedger(v) => v.run(runner).await,
             Self::SoloNodeBasicConnectivityInitialJoining(v) => v.run(runner).await,
             Self::SoloNodeBasicConnectivityAcceptIncoming(v) => v.run(runner).await,
+            Self::SoloNodeBlockInject(v) => v.run(runner).await,
             Self::MultiNodeSync4BlockProducers(v) => v.run(runner).await,
             Self::MultiNodeVrfGetCorrectLedgers(v) => v.run(runner).await,
             Self::MultiNodeVrfGetCorrectSlots(v) => v.run(runner).await,
@@ -316,10 +372,17 @@ impl Scenarios {
             Self::MultiNodeBasicConnectivityPeerDiscovery(v) => v.run(runner).await,
             Self::SimulationSmall(v) => v.run(runner).await,
             Self::SimulationSmallForeverRealTime(v) => v.run(runner).await,
+            Self::SimulationSoak(v) => v.run(runner).await,
             Self::P2pReceiveMessage(v) => v.run(runner).await,
             Self::P2pSignaling(v) => v.run(runner).await,
             Self::P2pConnectionDiscoveryRustNodeAsSeed(v) => v.run(runner).await,
             Self::MultiNodePubsubPropagateBlock(v) => v.run(runner).await,
+            #[cfg(feature = "byzantine")]
+            Self::MultiNodeByzantineEquivocation(v) => v.run(runner).await,
+            #[cfg(feature = "byzantine")]
+            Self::MultiNodeByzantineWrongSnarkFee(v) => v.run(runner).await,
+            Self::MultiNodeSlowDisk(v) => v.run(runner).await,
+            Self::MultiNodeTimeAcceleration(v) => v.run(runner).await,
             Self::RecordReplayBootstrap(v) => v.run(runner).await,
             Self::RecordReplayBlockProduction(v) => v.run(runner).await,
 
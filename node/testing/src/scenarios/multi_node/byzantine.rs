@@ -0,0 +1,244 @@
+use std::time::Duration;
+
+use mina_p2p_messages::{
+    gossip::GossipNetMessageV2,
+    v2::{BlockTimeTimeStableV1, PROTOCOL_CONSTANTS},
+};
+use node::{
+    core::block::BlockWithHash,
+    p2p::P2pTimeouts,
+    transition_frontier::genesis::{GenesisConfig, NonStakers},
+};
+
+use crate::{
+    cluster::{ClusterConfig, ClusterNodeId, ProofKind},
+    node::ByzantineBehavior,
+    scenario::ScenarioStep,
+    scenarios::{ClusterRunner, RunCfg, RunCfgAdvanceTime},
+    simulator::{Simulator, SimulatorConfig, SimulatorRunUntil},
+};
+
+/// A node broadcasts a block conflicting with the best tip.
+///
+/// 1. Create a seed node, 2 normal nodes and a block producer, and run until
+///    the blockchain length is 2.
+/// 2. Make the first normal node broadcast a block with the same parent,
+///    slot and producer as the best tip, but a different protocol state.
+/// 3. Check that for a minute no node switches to the block, as its proof
+///    doesn't verify.
+///
+/// A block failing verification is not a reason to ban its sender, which
+/// may have relayed it without verifying it, so the node is not expected to
+/// be disconnected.
+///
+/// Proofs are created and verified, see [`ProofKind::Full`].
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct MultiNodeByzantineEquivocation;
+
+impl MultiNodeByzantineEquivocation {
+    pub fn default_cluster_config(
+        self,
+        mut config: ClusterConfig,
+    ) -> Result<ClusterConfig, anyhow::Error> {
+        config.set_proof_kind(ProofKind::Full);
+        Ok(config)
+    }
+
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        simulator(0).setup_and_run(&mut runner).await;
+        let behavior = ByzantineBehavior::Equivocation;
+        let forged_block_hash =
+            match behavior.message(runner.node(byzantine_node()).unwrap().state(), 0) {
+                Some(GossipNetMessageV2::NewState(block)) => {
+                    BlockWithHash::try_new(block).unwrap().hash().clone()
+                }
+                _ => panic!("{behavior:?} message can't be built"),
+            };
+        broadcast(&mut runner, behavior).await;
+
+        run_for(&mut runner, Duration::from_secs(60), |runner| {
+            for (node_id, node) in runner.nodes_iter() {
+                let best_tip = node.state().transition_frontier.best_tip();
+                assert!(
+                    best_tip.is_none_or(|best_tip| best_tip.hash() != &forged_block_hash),
+                    "node {node_id} switched to the forged block"
+                );
+            }
+        })
+        .await;
+    }
+}
+
+/// A node broadcasts a snark with a forged fee.
+///
+/// 1. Create a seed node, 2 normal nodes, a snark worker and a block
+///    producer, and run until the blockchain length is 2.
+/// 2. Wait until the first normal node has a snark in its pool.
+/// 3. Make it broadcast the snark with a lower fee, which the proofs don't
+///    commit to.
+/// 4. Wait until the peers which received it fail to verify it, and
+///    disconnect and ban the node.
+/// 5. Check that the banned node doesn't get connected to them again.
+///
+/// Bans are enabled, see [`P2pTimeouts::misbehavior_ban`]. Proofs are
+/// created and verified, see [`ProofKind::Full`].
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct MultiNodeByzantineWrongSnarkFee;
+
+impl MultiNodeByzantineWrongSnarkFee {
+    pub fn default_cluster_config(
+        self,
+        mut config: ClusterConfig,
+    ) -> Result<ClusterConfig, anyhow::Error> {
+        config.set_proof_kind(ProofKind::Full);
+        Ok(config)
+    }
+
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        simulator(1)
+            .with_timeouts(P2pTimeouts {
+                misbehavior_ban: Some(Duration::from_secs(60 * 60)),
+                ..Default::default()
+            })
+            .setup_and_run(&mut runner)
+            .await;
+        run_until(&mut runner, Duration::from_secs(10 * 60), |runner| {
+            let state = runner.node(byzantine_node()).unwrap().state();
+            ByzantineBehavior::WrongSnarkFee.message(state, 0).is_some()
+        })
+        .await;
+        ban_byzantine_node(&mut runner, ByzantineBehavior::WrongSnarkFee).await;
+    }
+}
+
+/// The first normal node, see [`Simulator`].
+fn byzantine_node() -> ClusterNodeId {
+    ClusterNodeId::new_unchecked(1)
+}
+
+fn simulator(snark_workers: usize) -> Simulator {
+    let initial_time = redux::Timestamp::global_now();
+    let mut constants = PROTOCOL_CONSTANTS.clone();
+    constants.genesis_state_timestamp =
+        BlockTimeTimeStableV1((u64::from(initial_time) / 1_000_000).into());
+    let genesis_cfg = GenesisConfig::Counts {
+        whales: 1,
+        fish: 0,
+        non_stakers: NonStakers::None,
+        constants,
+    };
+    let config = SimulatorConfig {
+        genesis: genesis_cfg.into(),
+        seed_nodes: 1,
+        normal_nodes: 2,
+        snark_workers,
+        block_producers: 1,
+        advance_time: RunCfgAdvanceTime::Rand(1..=200),
+        run_until: SimulatorRunUntil::BlockchainLength(2),
+        run_until_timeout: Duration::from_secs(10 * 60),
+        recorder: Default::default(),
+    };
+    Simulator::new(initial_time, config)
+}
+
+/// Runs the cluster until `f` returns `true`, panics after `timeout`.
+async fn run_until<F>(runner: &mut ClusterRunner<'_>, timeout: Duration, mut f: F)
+where
+    F: FnMut(&ClusterRunner<'_>) -> bool,
+{
+    let start_t = redux::Instant::now();
+    while !f(runner) {
+        assert!(start_t.elapsed() < timeout, "timed out");
+        tokio::task::yield_now().await;
+        let cfg = RunCfg::default()
+            .advance_time(RunCfgAdvanceTime::Rand(1..=200))
+            .timeout(Duration::ZERO);
+        let _ = runner.run(cfg).await;
+    }
+}
+
+/// Makes the byzantine node broadcast its message, returns the nodes it was
+/// connected to.
+async fn broadcast(
+    runner: &mut ClusterRunner<'_>,
+    behavior: ByzantineBehavior,
+) -> Vec<ClusterNodeId> {
+    let byzantine_node = byzantine_node();
+    let byzantine_peer_id = runner.node(byzantine_node).unwrap().state().p2p.my_id();
+
+    let peers = runner
+        .nodes_iter()
+        .filter(|(_, node)| {
+            node.state()
+                .p2p
+                .ready()
+                .is_some_and(|p2p| p2p.get_ready_peer(&byzantine_peer_id).is_some())
+        })
+        .map(|(node_id, _)| node_id)
+        .collect::<Vec<_>>();
+    assert!(!peers.is_empty(), "byzantine node has no peers");
+
+    let broadcast = runner
+        .exec_step(ScenarioStep::Byzantine {
+            node_id: byzantine_node,
+            behavior,
+        })
+        .await
+        .unwrap();
+    assert!(broadcast, "{behavior:?} message can't be built");
+    peers
+}
+
+/// Runs the cluster for `duration` of the byzantine node's time, calling
+/// `check` in between.
+async fn run_for<F>(runner: &mut ClusterRunner<'_>, duration: Duration, mut check: F)
+where
+    F: FnMut(&ClusterRunner<'_>),
+{
+    let time = |runner: &ClusterRunner<'_>| runner.node(byzantine_node()).unwrap().state().time();
+    let start_time = time(runner);
+    run_until(runner, Duration::from_secs(5 * 60), |runner| {
+        check(runner);
+        time(runner) >= start_time + duration
+    })
+    .await;
+}
+
+/// Makes the byzantine node misbehave, and checks that the peers receiving
+/// its message ban it.
+async fn ban_byzantine_node(runner: &mut ClusterRunner<'_>, behavior: ByzantineBehavior) {
+    let byzantine_peer_id = runner.node(byzantine_node()).unwrap().state().p2p.my_id();
+    let peers = broadcast(runner, behavior).await;
+
+    // Not every peer is in the gossip mesh of the byzantine node, so only
+    // the ones that banned it are checked.
+    let banned_by = |runner: &ClusterRunner<'_>| {
+        peers
+            .iter()
+            .copied()
+            .filter(|node_id| {
+                let p2p = runner.node(*node_id).unwrap().state().p2p.ready();
+                p2p.is_some_and(|p2p| p2p.is_banned(&byzantine_peer_id))
+            })
+            .collect::<Vec<_>>()
+    };
+    run_until(runner, Duration::from_secs(2 * 60), |runner| {
+        !banned_by(runner).is_empty()
+    })
+    .await;
+    let banned_by = banned_by(runner);
+    eprintln!("byzantine node {byzantine_peer_id} banned by nodes: {banned_by:?}");
+
+    // The byzantine node keeps dialing its initial peers, which must refuse
+    // it while it is banned.
+    run_for(runner, Duration::from_secs(60), |runner| {
+        for node_id in &banned_by {
+            let p2p = runner.node(*node_id).unwrap().state().p2p.ready().unwrap();
+            assert!(
+                p2p.get_ready_peer(&byzantine_peer_id).is_none(),
+                "node {node_id} is connected to the banned node"
+            );
+        }
+    })
+    .await;
+}
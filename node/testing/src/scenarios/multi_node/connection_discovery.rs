@@ -4,7 +4,7 @@ use crate::{
     cluster::{ClusterNodeId, ClusterOcamlNodeId},
     node::{OcamlNodeTestingConfig, RustNodeTestingConfig},
     scenario::{ListenerNode, ScenarioStep},
-    scenarios::{get_peers_iter, ClusterRunner, RunCfg, PEERS_QUERY},
+    scenarios::{ClusterRunner, RunCfg},
 };
 use anyhow::Context;
 use node::{
@@ -343,21 +343,14 @@ async fn check_ocaml_peers<A>(
 where
     A: IntoIterator<Item = PeerId>,
 {
-    let data = runner
+    let peers = runner
         .ocaml_node(node_id)
         .expect("OCaml node not found")
-        .grapql_query(PEERS_QUERY)
+        .graphql()?
+        .peers()
         .await?;
 
-    let peers = get_peers_iter(&data)
-        .with_context(|| "Failed to convert graphql response")?
-        .flatten()
-        .map(|peer| peer.2.to_owned())
-        .collect::<Vec<_>>();
-
-    Ok(peer_ids
-        .into_iter()
-        .all(|peer_id| peers.contains(&peer_id.to_libp2p_string())))
+    Ok(peer_ids.into_iter().all(|peer_id| peers.contains(&peer_id)))
 }
 
 pub fn check_kademlia_entries<A>(
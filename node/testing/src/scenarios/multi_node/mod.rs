@@ -3,10 +3,14 @@ pub mod sync_4_block_producers;
 pub mod basic_connectivity_initial_joining;
 pub mod basic_connectivity_peer_discovery;
 
+#[cfg(feature = "byzantine")]
+pub mod byzantine;
 #[cfg(feature = "p2p-libp2p")]
 pub mod connection_discovery;
 #[cfg(feature = "p2p-libp2p")]
 pub mod pubsub_advanced;
+pub mod slow_disk;
+pub mod time_acceleration;
 pub mod vrf_correct_ledgers;
 pub mod vrf_correct_slots;
 pub mod vrf_epoch_bounds_correct_ledgers;
@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use mina_p2p_messages::v2::{BlockTimeTimeStableV1, PROTOCOL_CONSTANTS};
+use node::transition_frontier::genesis::{GenesisConfig, NonStakers};
+
+use crate::{
+    cluster::ClusterNodeId,
+    node::{RustNodeTestingConfig, SlowDisk},
+    scenarios::{ClusterRunner, RunCfg, RunCfgAdvanceTime},
+    simulator::{Simulator, SimulatorConfig, SimulatorRunUntil},
+};
+
+/// Latencies of the slow disk, high enough for ledger writes to take
+/// seconds, and some of its `fsync`s failing.
+const SLOW_DISK: SlowDisk = SlowDisk {
+    write_latency: Duration::from_millis(5),
+    sync_latency: Duration::from_millis(200),
+    sync_failure_probability: 0.1,
+};
+
+/// A node storing its ledgers on a slow disk joins the network.
+///
+/// 1. Create a seed node, a normal node and a block producer, and run until
+///    the blockchain length is 2.
+/// 2. Add a node storing its ledgers on disk, with latency added to each
+///    write and `fsync`, and some `fsync`s failing.
+/// 3. Wait until it is synced, checking that it keeps its peers while its
+///    ledgers are written.
+/// 4. Run until 2 more blocks are produced, and check that the slow node
+///    applied them, and that its best chain is the one of the block
+///    producer.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct MultiNodeSlowDisk;
+
+impl MultiNodeSlowDisk {
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        simulator().setup_and_run(&mut runner).await;
+
+        let seed_node = ClusterNodeId::new_unchecked(0);
+        let block_producer = runner
+            .nodes_iter()
+            .find(|(_, node)| node.config().block_producer.is_some())
+            .map(|(node_id, _)| node_id)
+            .unwrap();
+        let slow_node = runner.add_rust_node(RustNodeTestingConfig {
+            initial_peers: vec![seed_node.into()],
+            block_producer: None,
+            ..runner
+                .node(seed_node)
+                .unwrap()
+                .config()
+                .clone()
+                .with_slow_disk(SLOW_DISK)
+        });
+        eprintln!("slow disk node: {slow_node}");
+
+        // Once connected, the slow node must not lose all of its peers,
+        // which happens if writing its ledgers blocks it long enough for
+        // their requests to time out.
+        let mut connected = false;
+        run_until(&mut runner, Duration::from_secs(10 * 60), |runner| {
+            let state = runner.node(slow_node).unwrap().state();
+            let peers = state.p2p.ready().map_or(0, |p2p| p2p.ready_peers().len());
+            assert!(
+                !connected || peers > 0,
+                "slow disk node lost its peers at {:?}",
+                state.time()
+            );
+            connected |= peers > 0;
+            state.transition_frontier.sync.is_synced()
+        })
+        .await;
+        eprintln!("slow disk node synced");
+
+        let length = |runner: &ClusterRunner<'_>, node_id| {
+            let state = runner.node(node_id).unwrap().state();
+            state
+                .transition_frontier
+                .best_tip()
+                .map_or(0, |best_tip| best_tip.height())
+        };
+        let target_length = length(&runner, block_producer) + 2;
+        run_until(&mut runner, Duration::from_secs(10 * 60), |runner| {
+            length(runner, slow_node) >= target_length
+                && length(runner, block_producer) == length(runner, slow_node)
+        })
+        .await;
+
+        let best_chain = |node_id| {
+            let state = runner.node(node_id).unwrap().state();
+            let best_chain = &state.transition_frontier.best_chain;
+            best_chain
+                .iter()
+                .map(|block| (block.hash().clone(), block.staged_ledger_hashes().clone()))
+                .collect::<Vec<_>>()
+        };
+        let slow_node_chain = best_chain(slow_node);
+        let block_producer_chain = best_chain(block_producer);
+        assert_eq!(
+            slow_node_chain.last(),
+            block_producer_chain.last(),
+            "slow disk node is on another best tip"
+        );
+        assert!(
+            block_producer_chain.ends_with(&slow_node_chain),
+            "slow disk node has another best chain"
+        );
+    }
+}
+
+fn simulator() -> Simulator {
+    let initial_time = redux::Timestamp::global_now();
+    let mut constants = PROTOCOL_CONSTANTS.clone();
+    constants.genesis_state_timestamp =
+        BlockTimeTimeStableV1((u64::from(initial_time) / 1_000_000).into());
+    let genesis_cfg = GenesisConfig::Counts {
+        whales: 1,
+        fish: 0,
+        non_stakers: NonStakers::None,
+        constants,
+    };
+    let config = SimulatorConfig {
+        genesis: genesis_cfg.into(),
+        seed_nodes: 1,
+        normal_nodes: 1,
+        snark_workers: 0,
+        block_producers: 1,
+        advance_time: RunCfgAdvanceTime::Rand(1..=200),
+        run_until: SimulatorRunUntil::BlockchainLength(2),
+        run_until_timeout: Duration::from_secs(10 * 60),
+        recorder: Default::default(),
+    };
+    Simulator::new(initial_time, config)
+}
+
+/// Runs the cluster until `f` returns `true`, panics after `timeout`.
+async fn run_until<F>(runner: &mut ClusterRunner<'_>, timeout: Duration, mut f: F)
+where
+    F: FnMut(&ClusterRunner<'_>) -> bool,
+{
+    let start_t = redux::Instant::now();
+    while !f(runner) {
+        assert!(start_t.elapsed() < timeout, "timed out");
+        tokio::task::yield_now().await;
+        let cfg = RunCfg::default()
+            .advance_time(RunCfgAdvanceTime::Rand(1..=200))
+            .timeout(Duration::ZERO);
+        let _ = runner.run(cfg).await;
+    }
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use mina_p2p_messages::v2::{BlockTimeTimeStableV1, PROTOCOL_CONSTANTS};
+use node::transition_frontier::genesis::{GenesisConfig, NonStakers};
+
+use crate::{
+    cluster::ClusterConfig,
+    scenarios::{ClusterRunner, RunCfg, RunCfgAdvanceTime},
+    simulator::{Simulator, SimulatorConfig, SimulatorRunUntil},
+};
+
+/// Speed of the time of the cluster, a slot of 3 minutes goes by in 1.8
+/// seconds.
+const TIME_ACCELERATION: u32 = 100;
+
+/// Nodes of a cluster with accelerated time stay in sync and produce blocks
+/// at the accelerated slot pace.
+///
+/// 1. Create a seed node, a normal node and a block producer, with the time
+///    of the cluster going by 100 times faster than the wall clock, and run
+///    until the blockchain length is 2.
+/// 2. Run until 2 more blocks are produced, which takes about 4 seconds
+///    instead of 6 minutes.
+/// 3. Check that the time of the nodes went by 100 times faster than the
+///    wall clock, and that all nodes have the same time.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct MultiNodeTimeAcceleration;
+
+impl MultiNodeTimeAcceleration {
+    pub fn default_cluster_config(
+        self,
+        mut config: ClusterConfig,
+    ) -> anyhow::Result<ClusterConfig> {
+        config.set_time_acceleration(TIME_ACCELERATION);
+        Ok(config)
+    }
+
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        assert_eq!(runner.time_source().speed(), TIME_ACCELERATION);
+        simulator().setup_and_run(&mut runner).await;
+
+        let block_producer = runner
+            .nodes_iter()
+            .find(|(_, node)| node.config().block_producer.is_some())
+            .map(|(node_id, _)| node_id)
+            .unwrap();
+        let length = |runner: &ClusterRunner<'_>| {
+            let state = runner.node(block_producer).unwrap().state();
+            state
+                .transition_frontier
+                .best_tip()
+                .map_or(0, |best_tip| best_tip.height())
+        };
+        let node_time =
+            |runner: &ClusterRunner<'_>| runner.node(block_producer).unwrap().state().time();
+
+        let target_length = length(&runner) + 2;
+        let real_start = redux::Instant::now();
+        let node_start = node_time(&runner);
+        // Without acceleration, 2 slots take 6 minutes.
+        let timeout = Duration::from_secs(60);
+        while length(&runner) < target_length {
+            assert!(real_start.elapsed() < timeout, "timed out");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            let cfg = RunCfg::default()
+                .advance_time(RunCfgAdvanceTime::Real)
+                .timeout(Duration::ZERO);
+            let _ = runner.run(cfg).await;
+        }
+        let real_elapsed = real_start.elapsed();
+        let node_elapsed = node_time(&runner).checked_sub(node_start).unwrap();
+        eprintln!(
+            "{target_length} blocks, real time: {real_elapsed:?}, node time: {node_elapsed:?}"
+        );
+
+        // Some slack, the time is advanced after the nodes ran.
+        assert!(
+            node_elapsed >= real_elapsed * (TIME_ACCELERATION / 2),
+            "node time isn't accelerated: {node_elapsed:?} in {real_elapsed:?}"
+        );
+        assert!(
+            node_elapsed <= (real_elapsed + Duration::from_secs(1)) * TIME_ACCELERATION,
+            "node time is too fast: {node_elapsed:?} in {real_elapsed:?}"
+        );
+        for (node_id, node) in runner.nodes_iter() {
+            assert_eq!(
+                node.state().time(),
+                node_time(&runner),
+                "node {node_id} is out of sync"
+            );
+        }
+    }
+}
+
+fn simulator() -> Simulator {
+    let initial_time = redux::Timestamp::global_now();
+    let mut constants = PROTOCOL_CONSTANTS.clone();
+    constants.genesis_state_timestamp =
+        BlockTimeTimeStableV1((u64::from(initial_time) / 1_000_000).into());
+    let genesis_cfg = GenesisConfig::Counts {
+        whales: 1,
+        fish: 0,
+        non_stakers: NonStakers::None,
+        constants,
+    };
+    let config = SimulatorConfig {
+        genesis: genesis_cfg.into(),
+        seed_nodes: 1,
+        normal_nodes: 1,
+        snark_workers: 0,
+        block_producers: 1,
+        advance_time: RunCfgAdvanceTime::Real,
+        run_until: SimulatorRunUntil::BlockchainLength(2),
+        run_until_timeout: Duration::from_secs(60),
+        recorder: Default::default(),
+    };
+    Simulator::new(initial_time, config)
+}
@@ -47,6 +47,7 @@ impl MultiNodeVrfGetCorrectLedgers {
                     pub_key: sec_key.public_key().into(),
                     custom_coinbase_receiver: None,
                     proposed_protocol_version: None,
+                    work_fee_policy: Default::default(),
                 },
                 sec_key,
             }),
@@ -55,6 +56,7 @@ impl MultiNodeVrfGetCorrectLedgers {
             libp2p_port: None,
             recorder: Default::default(),
             peer_discovery: true,
+            slow_disk: None,
         });
 
         tokio::time::sleep(Duration::from_secs(2)).await;
@@ -50,6 +50,7 @@ impl MultiNodeVrfGetCorrectSlots {
                     pub_key: sec_key.public_key().into(),
                     custom_coinbase_receiver: None,
                     proposed_protocol_version: None,
+                    work_fee_policy: Default::default(),
                 },
                 sec_key,
             }),
@@ -58,6 +59,7 @@ impl MultiNodeVrfGetCorrectSlots {
             libp2p_port: None,
             recorder: Default::default(),
             peer_discovery: true,
+            slow_disk: None,
         });
 
         tokio::time::sleep(Duration::from_secs(2)).await;
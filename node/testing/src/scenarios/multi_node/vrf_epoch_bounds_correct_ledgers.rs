@@ -59,6 +59,7 @@ impl MultiNodeVrfEpochBoundsCorrectLedger {
             libp2p_port: None,
             recorder: Default::default(),
             peer_discovery: true,
+            slow_disk: None,
         };
 
         let producer_node = runner.add_rust_node(RustNodeTestingConfig {
@@ -67,6 +68,7 @@ impl MultiNodeVrfEpochBoundsCorrectLedger {
                     pub_key: sec_key.public_key().into(),
                     custom_coinbase_receiver: None,
                     proposed_protocol_version: None,
+                    work_fee_policy: Default::default(),
                 },
                 sec_key: sec_key.clone(),
             }),
@@ -81,6 +83,7 @@ impl MultiNodeVrfEpochBoundsCorrectLedger {
                 )),
                 strategy: SnarkerStrategy::Sequential,
                 auto_commit: true,
+                coordinators: Vec::new(),
             }),
             ..rust_config
         });
@@ -47,6 +47,7 @@ impl MultiNodeVrfEpochBoundsEvaluation {
             libp2p_port: None,
             recorder: Default::default(),
             peer_discovery: true,
+            slow_disk: None,
         };
 
         let producer_node = runner.add_rust_node(RustNodeTestingConfig {
@@ -55,6 +56,7 @@ impl MultiNodeVrfEpochBoundsEvaluation {
                     pub_key: sec_key.public_key().into(),
                     custom_coinbase_receiver: None,
                     proposed_protocol_version: None,
+                    work_fee_policy: Default::default(),
                 },
                 sec_key: sec_key.clone(),
             }),
@@ -69,6 +71,7 @@ impl MultiNodeVrfEpochBoundsEvaluation {
                 )),
                 strategy: SnarkerStrategy::Sequential,
                 auto_commit: true,
+                coordinators: Vec::new(),
             }),
             ..rust_config
         });
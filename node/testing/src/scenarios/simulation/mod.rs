@@ -1,2 +1,3 @@
 pub mod small;
 pub mod small_forever_real_time;
+pub mod soak;
@@ -0,0 +1,249 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use ledger::scan_state::{
+    currency::{Amount, Fee, Slot},
+    transaction_logic::{
+        signed_command::{Body, Common, PaymentPayload, SignedCommand, SignedCommandPayload},
+        transaction_union_payload::TransactionUnionPayload,
+        Memo, UserCommand,
+    },
+};
+use mina_p2p_messages::v2;
+use mina_signer::{Keypair, Signer};
+use node::{
+    account::AccountSecretKey,
+    core::NetworkConfig,
+    event_source::Event,
+    rpc::{RpcId, RpcPendingRequest, RpcRequest},
+    transition_frontier::genesis::{GenesisConfig, NonStakers},
+    State,
+};
+
+use crate::{
+    cluster::ClusterNodeId,
+    scenario::ScenarioStep,
+    scenarios::{ClusterRunner, RunCfgAdvanceTime},
+    simulator::{Simulator, SimulatorConfig, SimulatorRunUntil},
+};
+
+/// Number of epochs to run for, unless overridden by `MINA_SOAK_EPOCHS`.
+const DEFAULT_EPOCHS: u32 = 3;
+/// Slots after which the chain is considered stuck if no node's best tip
+/// got higher.
+const MAX_SLOTS_WITHOUT_BLOCK: u32 = 30;
+/// Age after which a pending request (verification, ledger service, p2p
+/// RPC) is considered stuck.
+const MAX_PENDING_REQUEST_AGE: Duration = Duration::from_secs(10 * 60);
+/// How many times its size at the end of the first epoch a node's state
+/// may grow to.
+const MAX_STATE_GROWTH: usize = 4;
+const PAYMENT_AMOUNT: u64 = 1_000_000_000;
+const PAYMENT_FEE: u64 = 10_000_000;
+
+/// Soak simulation, meant as a stability gate for nightly runs.
+///
+/// Run until `epoch_count >= MINA_SOAK_EPOCHS` (**3** by default), sending a
+/// payment between genesis accounts every slot, and fail if:
+///
+/// - no node's best tip gets higher for **30** slots.
+/// - a node has a request pending for more than **10** minutes.
+/// - a node's state grows to more than **4** times its size at the end of the
+///   first epoch.
+///
+/// - **whale** block producers: **1**.
+/// - **fish** block producers: **2**.
+/// - seed nodes (observers): **2**.
+/// - snark workers: **1**.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct SimulationSoak;
+
+impl SimulationSoak {
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        let epochs = std::env::var("MINA_SOAK_EPOCHS")
+            .ok()
+            .map(|epochs| epochs.parse().expect("invalid MINA_SOAK_EPOCHS"))
+            .unwrap_or(DEFAULT_EPOCHS);
+
+        let initial_time = redux::Timestamp::global_now();
+        let mut constants = v2::PROTOCOL_CONSTANTS.clone();
+        constants.genesis_state_timestamp =
+            v2::BlockTimeTimeStableV1((u64::from(initial_time) / 1_000_000).into());
+        let genesis_cfg = GenesisConfig::Counts {
+            whales: 1,
+            fish: 2,
+            non_stakers: NonStakers::Count(20),
+            constants,
+        };
+        let cfg = SimulatorConfig {
+            genesis: genesis_cfg.into(),
+            seed_nodes: 2,
+            normal_nodes: 0,
+            snark_workers: 1,
+            block_producers: 3,
+            advance_time: RunCfgAdvanceTime::Rand(10..=200),
+            run_until: SimulatorRunUntil::Epoch(epochs),
+            run_until_timeout: Duration::from_secs(u64::from(epochs) * 30 * 60),
+            recorder: Default::default(),
+        };
+        let mut simulator = Simulator::new(initial_time, cfg);
+        simulator.setup(&mut runner).await;
+
+        let mut checks = SoakChecks::new(&runner);
+        simulator
+            .run_with_hooks(
+                &mut runner,
+                || |_, _, _, _| false,
+                |runner, slot| checks.on_slot(runner, slot),
+            )
+            .await;
+    }
+}
+
+struct SoakChecks {
+    /// Genesis accounts taking turns sending payments to each other.
+    payers: Vec<AccountSecretKey>,
+    network_id: mina_signer::NetworkId,
+    rpc_counter: usize,
+    best_height: u32,
+    best_height_slot: Option<u32>,
+    /// Size of the serialized state of each node, at the end of the first
+    /// epoch.
+    state_sizes: BTreeMap<ClusterNodeId, usize>,
+}
+
+impl SoakChecks {
+    fn new(runner: &ClusterRunner<'_>) -> Self {
+        let payers = runner
+            .accounts_with_sec_keys(ClusterNodeId::new_unchecked(0))
+            .map(|(sec_key, _)| sec_key)
+            .collect::<Vec<_>>();
+        assert!(payers.len() >= 2, "not enough accounts to send payments");
+
+        let network_id = match NetworkConfig::global().network_id {
+            node::core::network::NetworkId::TESTNET => mina_signer::NetworkId::TESTNET,
+            node::core::network::NetworkId::MAINNET => mina_signer::NetworkId::MAINNET,
+        };
+
+        Self {
+            payers,
+            network_id,
+            rpc_counter: 0,
+            best_height: 0,
+            best_height_slot: None,
+            state_sizes: BTreeMap::new(),
+        }
+    }
+
+    fn on_slot(&mut self, runner: &ClusterRunner<'_>, slot: u32) -> Vec<ScenarioStep> {
+        self.check_block_production(runner, slot);
+        for (node_id, node) in runner.nodes_iter() {
+            self.check_pending_requests(node_id, node.state());
+            self.check_state_size(node_id, node.state());
+        }
+        self.payment(runner, slot).into_iter().collect()
+    }
+
+    fn check_block_production(&mut self, runner: &ClusterRunner<'_>, slot: u32) {
+        let best_height = runner
+            .nodes_iter()
+            .filter_map(|(_, node)| node.state().transition_frontier.best_tip())
+            .map(|best_tip| best_tip.height())
+            .max()
+            .unwrap_or_default();
+
+        let best_height_slot = *self.best_height_slot.get_or_insert(slot);
+        if best_height > self.best_height {
+            self.best_height = best_height;
+            self.best_height_slot = Some(slot);
+        } else {
+            assert!(
+                slot - best_height_slot <= MAX_SLOTS_WITHOUT_BLOCK,
+                "no block produced since slot {best_height_slot}, best tip height: {best_height}"
+            );
+        }
+    }
+
+    fn check_pending_requests(&self, node_id: ClusterNodeId, state: &State) {
+        let Some(oldest) = RpcPendingRequest::collect(state, state.time())
+            .into_iter()
+            .next()
+        else {
+            return;
+        };
+        assert!(
+            oldest.age <= MAX_PENDING_REQUEST_AGE,
+            "node_{node_id} has a stuck request: {oldest:?}"
+        );
+    }
+
+    fn check_state_size(&mut self, node_id: ClusterNodeId, state: &State) {
+        let Some(best_tip) = state.transition_frontier.best_tip() else {
+            return;
+        };
+        if best_tip
+            .header()
+            .protocol_state
+            .body
+            .consensus_state
+            .epoch_count
+            .as_u32()
+            == 0
+        {
+            return;
+        }
+
+        let size = postcard::to_stdvec(state)
+            .expect("failed to serialize state")
+            .len();
+        let initial_size = *self.state_sizes.entry(node_id).or_insert(size);
+        assert!(
+            size <= initial_size * MAX_STATE_GROWTH,
+            "node_{node_id} state grew from {initial_size} to {size} bytes"
+        );
+    }
+
+    /// Payment from the next payer to the one after it, sent to the first
+    /// seed node, with the nonce of the payer in the best tip ledger.
+    fn payment(&mut self, runner: &ClusterRunner<'_>, slot: u32) -> Option<ScenarioStep> {
+        let node_id = ClusterNodeId::new_unchecked(0);
+        let payer = &self.payers[slot as usize % self.payers.len()];
+        let receiver = &self.payers[(slot as usize + 1) % self.payers.len()];
+        let (_, account) = runner
+            .accounts_with_sec_keys(node_id)
+            .find(|(sec_key, _)| sec_key.public_key() == payer.public_key())?;
+
+        let payload = SignedCommandPayload {
+            common: Common {
+                fee: Fee::from_u64(PAYMENT_FEE),
+                fee_payer_pk: payer.public_key_compressed(),
+                nonce: account.nonce,
+                valid_until: Slot::max(),
+                memo: Memo::empty(),
+            },
+            body: Body::Payment(PaymentPayload {
+                receiver_pk: receiver.public_key_compressed(),
+                amount: Amount::from_u64(PAYMENT_AMOUNT),
+            }),
+        };
+        let payload_to_sign = TransactionUnionPayload::of_user_command_payload(&payload);
+        let keypair: Keypair = payer.clone().into();
+        let signature = mina_signer::create_legacy(self.network_id.clone()).sign(
+            &keypair,
+            &payload_to_sign,
+            true,
+        );
+        let command = UserCommand::SignedCommand(Box::new(SignedCommand {
+            payload,
+            signer: payer.public_key_compressed(),
+            signature,
+        }));
+
+        self.rpc_counter += 1;
+        let rpc_id = RpcId::new_unchecked(usize::MAX - 1, self.rpc_counter);
+        let request = RpcRequest::TransactionInject(vec![command.into()]);
+        Some(ScenarioStep::ManualEvent {
+            node_id,
+            event: Box::new(Event::Rpc(rpc_id, Box::new(request))),
+        })
+    }
+}
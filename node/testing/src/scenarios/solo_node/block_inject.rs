@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use mina_p2p_messages::v2;
+use node::{
+    core::block::{prevalidate::BlockPrevalidationError, ArcBlock},
+    rpc::{RpcBlockInjectError, RpcBlockInjectResponse, RpcRequest},
+    rpc_effectful::RpcEffectfulAction,
+    Action,
+};
+
+use crate::{
+    cluster::ClusterNodeId,
+    node::RustNodeTestingConfig,
+    scenarios::{ClusterRunner, RunCfg},
+};
+
+/// Inject blocks into a single Rust node and check the responses.
+///
+/// 1. Wait for the genesis block.
+/// 2. Inject a block from a future slot, it is rejected as received too early.
+/// 3. Inject it again, it is rejected the same way as it may become valid.
+/// 4. Inject a block of another chain, it is rejected for its genesis state.
+/// 5. Inject it again, it is known to be invalid.
+/// 6. Inject the genesis block, it is already known.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct SoloNodeBlockInject;
+
+impl SoloNodeBlockInject {
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        let node_id = runner.add_rust_node(
+            RustNodeTestingConfig::devnet_default().initial_time(redux::Timestamp::global_now()),
+        );
+
+        eprintln!("waiting for genesis block");
+        runner
+            .run(
+                RunCfg::default()
+                    .action_handler(|_, state, _, _| state.genesis_block().is_some()),
+            )
+            .await
+            .expect("genesis block not ready");
+
+        let state = runner.node(node_id).unwrap().state();
+        let genesis = state.genesis_block().unwrap();
+        // Time isn't advanced, so the current slot stays the same.
+        let cur_global_slot = state.cur_global_slot().unwrap();
+
+        let mut too_early = (*genesis.block).clone();
+        let consensus_state = &mut too_early.header.protocol_state.body.consensus_state;
+        consensus_state.blockchain_length = v2::UnsignedExtendedUInt32StableV1(2.into());
+        consensus_state.curr_global_slot_since_hard_fork.slot_number =
+            v2::MinaNumbersGlobalSlotSinceHardForkMStableV1::SinceHardFork(
+                (cur_global_slot + 1).into(),
+            );
+        let too_early = Arc::new(too_early);
+
+        for _ in 0..2 {
+            let response = block_inject(&mut runner, node_id, too_early.clone()).await;
+            assert!(
+                matches!(
+                    response,
+                    Err(RpcBlockInjectError::Prevalidation(
+                        BlockPrevalidationError::ReceivedTooEarly { .. }
+                    ))
+                ),
+                "{response:?}"
+            );
+        }
+
+        let mut other_chain = (*too_early).clone();
+        let body = &mut other_chain.header.protocol_state.body;
+        body.consensus_state
+            .curr_global_slot_since_hard_fork
+            .slot_number =
+            v2::MinaNumbersGlobalSlotSinceHardForkMStableV1::SinceHardFork(cur_global_slot.into());
+        body.genesis_state_hash = too_early.header.protocol_state.try_hash().unwrap();
+        let other_chain = Arc::new(other_chain);
+
+        let response = block_inject(&mut runner, node_id, other_chain.clone()).await;
+        assert!(
+            matches!(
+                response,
+                Err(RpcBlockInjectError::Prevalidation(
+                    BlockPrevalidationError::InvalidGenesisProtocolState
+                ))
+            ),
+            "{response:?}"
+        );
+        let response = block_inject(&mut runner, node_id, other_chain).await;
+        assert!(
+            matches!(response, Err(RpcBlockInjectError::AlreadyKnown)),
+            "{response:?}"
+        );
+
+        let response = block_inject(&mut runner, node_id, genesis.block.clone()).await;
+        assert!(
+            matches!(response, Err(RpcBlockInjectError::AlreadyKnown)),
+            "{response:?}"
+        );
+
+        let rpc_requests = &runner.node(node_id).unwrap().state().rpc.requests;
+        assert!(rpc_requests.is_empty(), "{rpc_requests:?}");
+    }
+}
+
+/// Sends the block the same way as the http server, and runs the node until
+/// it responds.
+async fn block_inject(
+    runner: &mut ClusterRunner<'_>,
+    node_id: ClusterNodeId,
+    block: ArcBlock,
+) -> RpcBlockInjectResponse {
+    let rpc_sender = runner.node(node_id).unwrap().service().rpc_sender();
+    let (response, run) = tokio::join!(
+        rpc_sender.oneshot_request(RpcRequest::BlockInject(block)),
+        runner.run(RunCfg::default().action_handler(|_, _, _, action| {
+            matches!(
+                action.action(),
+                Action::RpcEffectful(RpcEffectfulAction::BlockInject { .. })
+            )
+        })),
+    );
+    run.expect("no block inject response");
+    response.expect("response channel dropped")
+}
@@ -1,5 +1,6 @@
 pub mod basic_connectivity_accept_incoming;
 pub mod basic_connectivity_initial_joining;
+pub mod block_inject;
 pub mod bootstrap;
 pub mod sync_root_snarked_ledger;
 pub mod sync_to_genesis;
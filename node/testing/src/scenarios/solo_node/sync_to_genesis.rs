@@ -55,6 +55,7 @@ impl SoloNodeSyncToGenesis {
             libp2p_port: None,
             recorder: Default::default(),
             peer_discovery: true,
+            slow_disk: None,
         });
 
         runner
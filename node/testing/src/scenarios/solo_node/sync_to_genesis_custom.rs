@@ -71,6 +71,7 @@ impl SoloNodeSyncToGenesisCustom {
             libp2p_port: None,
             recorder: Default::default(),
             peer_discovery: true,
+            slow_disk: None,
         });
 
         runner
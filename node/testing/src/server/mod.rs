@@ -1,4 +1,47 @@
+//! HTTP API of the testing server, used by the frontend and by external
+//! test drivers to control clusters remotely.
+//!
+//! # Scenarios
+//!
+//! - `GET /scenarios` - list scenarios
+//! - `PUT /scenarios` - create a scenario
+//! - `GET /scenarios/:id` - get a scenario
+//! - `PUT /scenarios/:id/nodes` - add a node to a scenario
+//! - `PUT /scenarios/:id/steps` - add a step to a scenario
+//!
+//! # Clusters
+//!
+//! - `GET /clusters` - list cluster ids
+//! - `PUT /clusters/create` - create an empty cluster
+//! - `PUT /clusters/create/:scenario_id` - create a cluster for a scenario
+//! - `GET /clusters/:cluster_id` - get the scenario progress of a cluster
+//! - `POST /clusters/:cluster_id/run` - execute scenario steps
+//! - `POST /clusters/:cluster_id/run/auto` - execute scenario steps and
+//!   record pending events as new steps
+//! - `POST /clusters/:cluster_id/steps` - execute a single step
+//! - `GET /clusters/:cluster_id/steps/ws` - WebSocket executing one step
+//!   per message
+//! - `GET /clusters/:cluster_id/nodes` - list nodes
+//! - `PUT /clusters/:cluster_id/nodes` - add a Rust or OCaml node
+//! - `GET /clusters/:cluster_id/nodes/:node_id/state` - state of a Rust
+//!   node, optionally narrowed with a `pointer` JSON pointer
+//! - `GET /clusters/:cluster_id/nodes/events/pending` - pending events
+//! - `POST /clusters/:cluster_id/destroy` - destroy a cluster
+//!
+//! # Authentication
+//!
+//! If the server is started with a token, API requests must pass it in an
+//! `Authorization: Bearer <token>` header.
+//!
+//! # Isolation
+//!
+//! Each cluster is locked independently, so long running requests on one
+//! cluster don't block requests on others. Clusters created without an
+//! explicit port range get a disjoint range of their own.
+
+pub mod nodes;
 pub mod simulator;
+pub mod steps;
 pub mod webnode;
 
 use crate::{
@@ -16,9 +59,10 @@ use std::{
 };
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Request, State},
     http::{header, StatusCode},
     middleware,
+    response::IntoResponse,
     routing::{get, get_service, post, put},
     Json, Router,
 };
@@ -33,6 +77,7 @@ use node::{
 };
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
 use tokio::{
     net::TcpListener,
     runtime::Runtime,
@@ -40,7 +85,10 @@ use tokio::{
 };
 use tower_http::{cors::CorsLayer, services::ServeDir};
 
-pub fn server(rt: Runtime, host: Host, port: u16, ssl_port: Option<u16>) {
+/// Size of the port range assigned to each cluster created without one.
+const CLUSTER_PORT_RANGE_SIZE: u16 = 1000;
+
+pub fn server(rt: Runtime, host: Host, port: u16, ssl_port: Option<u16>, token: Option<String>) {
     let fe_dist_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .join("../../")
         .join("frontend/dist/frontend/");
@@ -58,11 +106,20 @@ pub fn server(rt: Runtime, host: Host, port: u16, ssl_port: Option<u16>) {
 
     let clusters_router = Router::new()
         .route("/", get(cluster_list))
+        .route("/create", put(cluster_create_empty))
         .route("/create/:scenario_id", put(cluster_create))
         .route("/:cluster_id", get(cluster_get))
         .nest("/:cluster_id/webnode", webnode::router())
         .route("/:cluster_id/run", post(cluster_run))
         .route("/:cluster_id/run/auto", post(cluster_run_auto))
+        .route("/:cluster_id/steps", post(steps::cluster_step_exec))
+        .route("/:cluster_id/steps/ws", get(steps::cluster_steps_ws))
+        .route("/:cluster_id/nodes", get(nodes::cluster_nodes_list))
+        .route("/:cluster_id/nodes", put(nodes::cluster_node_add))
+        .route(
+            "/:cluster_id/nodes/:node_id/state",
+            get(nodes::cluster_node_state),
+        )
         .route(
             "/:cluster_id/scenarios/reload",
             post(cluster_scenarios_reload),
@@ -97,10 +154,23 @@ pub fn server(rt: Runtime, host: Host, port: u16, ssl_port: Option<u16>) {
         resp
     });
 
+    let auth = middleware::from_fn(move |req: Request, next: middleware::Next| {
+        let token = token.clone();
+        async move {
+            match token {
+                Some(token) if !is_authorized(&req, &token) => {
+                    (StatusCode::UNAUTHORIZED, "invalid or missing token").into_response()
+                }
+                _ => next.run(req).await,
+            }
+        }
+    });
+
     let app = Router::new()
         .nest("/scenarios", scenarios_router)
         .nest("/clusters", clusters_router)
         .nest("/simulations", simulator::simulations_router())
+        .layer(auth)
         .fallback(get_service(ServeDir::new(&fe_dist_dir)).layer(coop_coep.clone()))
         .with_state(state)
         .layer(cors);
@@ -114,11 +184,24 @@ pub fn server(rt: Runtime, host: Host, port: u16, ssl_port: Option<u16>) {
     });
 }
 
+/// Compares the tokens in constant time, so that their content can't be
+/// guessed from the response times.
+fn is_authorized(req: &Request, token: &str) -> bool {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|value| value.as_bytes().ct_eq(token.as_bytes()).into())
+}
+
 pub struct AppStateInner {
     host: Host,
     ssl_port: Option<u16>,
     rng: StdRng,
     clusters: BTreeMap<u16, Arc<Mutex<Cluster>>>,
+    /// Ids of created and being created clusters, along with the port
+    /// range slot assigned to them, if any.
+    reserved: BTreeMap<u16, Option<u16>>,
     // TODO(binier): move inside cluster state
     locked_block_producer_keys: BTreeMap<u16, BTreeSet<AccountPublicKey>>,
 }
@@ -130,9 +213,42 @@ impl AppStateInner {
             ssl_port,
             rng: StdRng::seed_from_u64(0),
             clusters: Default::default(),
+            reserved: Default::default(),
             locked_block_producer_keys: Default::default(),
         }
     }
+
+    /// Reserves an id for a new cluster. If the config has no port range,
+    /// a range which isn't used by other clusters is assigned to it.
+    fn cluster_reserve(&mut self, config: &mut ClusterConfig) -> Result<u16, (StatusCode, String)> {
+        let slot = if config.has_port_range() {
+            None
+        } else {
+            let (start, end) = ClusterConfig::DEFAULT_PORT_RANGE;
+            let slots = (end - start + 1) / CLUSTER_PORT_RANGE_SIZE;
+            let used = self.reserved.values().flatten().collect::<BTreeSet<_>>();
+            let slot = (0..slots)
+                .find(|slot| !used.contains(slot))
+                .ok_or_else(|| {
+                    (
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        "no free port range for a new cluster".to_owned(),
+                    )
+                })?;
+            let start = start + slot * CLUSTER_PORT_RANGE_SIZE;
+            config.set_port_range(start, start + CLUSTER_PORT_RANGE_SIZE - 1);
+            Some(slot)
+        };
+
+        let id = loop {
+            let id = self.rng.gen();
+            if !self.reserved.contains_key(&id) {
+                break id;
+            }
+        };
+        self.reserved.insert(id, slot);
+        Ok(id)
+    }
 }
 
 #[derive(Clone)]
@@ -154,67 +270,55 @@ impl AppState {
         self.lock().await.cluster_mutex(cluster_id)
     }
 
+    /// Locks the cluster. The app state isn't locked while waiting for
+    /// it, so a busy cluster doesn't block requests to other clusters.
     pub async fn cluster(
         &self,
         cluster_id: u16,
     ) -> Result<OwnedMutexGuard<Cluster>, (StatusCode, String)> {
-        self.lock().await.cluster(cluster_id).await
+        Ok(self.cluster_mutex(cluster_id).await?.lock_owned().await)
     }
 
     pub async fn cluster_create(
         &self,
         scenario_id: ScenarioId,
-        config: ClusterConfig,
+        mut config: ClusterConfig,
     ) -> Result<(u16, OwnedMutexGuard<Cluster>), (StatusCode, String)> {
         let scenario = Scenario::load(&scenario_id)
             .await
             .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
 
+        let id = self.lock().await.cluster_reserve(&mut config)?;
         let mut cluster = Cluster::new(config);
-        cluster
-            .start(scenario)
-            .await
-            .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
-
-        let mut state = self.lock().await;
-        let id = loop {
-            let id = state.rng.gen();
-            if !state.clusters.contains_key(&id) {
-                break id;
-            }
-        };
-
-        let cluster = Arc::new(Mutex::new(cluster));
-        let cluster_guard = cluster.clone().try_lock_owned().unwrap();
-        state.clusters.insert(id, cluster);
+        if let Err(err) = cluster.start(scenario).await {
+            self.lock().await.reserved.remove(&id);
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string()));
+        }
 
-        Ok((id, cluster_guard))
+        Ok((id, self.cluster_insert(id, cluster).await))
     }
 
     pub async fn cluster_create_empty(
         &self,
-        config: ClusterConfig,
+        mut config: ClusterConfig,
     ) -> Result<(u16, OwnedMutexGuard<Cluster>), (StatusCode, String)> {
+        let id = self.lock().await.cluster_reserve(&mut config)?;
         let cluster = Cluster::new(config);
 
-        let mut state = self.lock().await;
-        let id = loop {
-            let id = state.rng.gen();
-            if !state.clusters.contains_key(&id) {
-                break id;
-            }
-        };
+        Ok((id, self.cluster_insert(id, cluster).await))
+    }
 
+    async fn cluster_insert(&self, id: u16, cluster: Cluster) -> OwnedMutexGuard<Cluster> {
         let cluster = Arc::new(Mutex::new(cluster));
         let cluster_guard = cluster.clone().try_lock_owned().unwrap();
-        state.clusters.insert(id, cluster);
-
-        Ok((id, cluster_guard))
+        self.lock().await.clusters.insert(id, cluster);
+        cluster_guard
     }
 
     pub async fn cluster_destroy(&self, cluster_id: u16) -> bool {
         let mut this = self.lock().await;
         this.locked_block_producer_keys.remove(&cluster_id);
+        this.reserved.remove(&cluster_id);
         this.clusters.remove(&cluster_id).is_some()
     }
 }
@@ -228,13 +332,6 @@ impl AppStateInner {
             )
         })
     }
-
-    pub async fn cluster(
-        &self,
-        cluster_id: u16,
-    ) -> Result<OwnedMutexGuard<Cluster>, (StatusCode, String)> {
-        Ok(self.cluster_mutex(cluster_id)?.lock_owned().await)
-    }
 }
 
 async fn scenario_list(
@@ -373,6 +470,21 @@ async fn cluster_create(
         .map(|(cluster_id, _)| Json(ClusterCreateResponse { cluster_id }))
 }
 
+async fn cluster_create_empty(
+    State(state): State<AppState>,
+    args: Option<Json<ClusterConfig>>,
+) -> Result<Json<ClusterCreateResponse>, (StatusCode, String)> {
+    let config = match args {
+        Some(Json(v)) => v,
+        None => ClusterConfig::new(None)
+            .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?,
+    };
+    state
+        .cluster_create_empty(config)
+        .await
+        .map(|(cluster_id, _)| Json(ClusterCreateResponse { cluster_id }))
+}
+
 #[derive(Deserialize, Default)]
 struct ClusterRunArgs {
     exec_until: Option<ClusterExecUntil>,
@@ -516,9 +628,10 @@ async fn cluster_seeds(
     State(state): State<AppState>,
     Path(cluster_id): Path<u16>,
 ) -> Result<String, (StatusCode, String)> {
-    let state = state.lock().await;
-    let host = state.host.clone();
-    let ssl_port = state.ssl_port;
+    let (host, ssl_port) = {
+        let state = state.lock().await;
+        (state.host.clone(), state.ssl_port)
+    };
     state.cluster(cluster_id).await.map(|cluster| {
         let list = cluster
             .nodes_iter()
@@ -660,3 +773,38 @@ async fn cluster_destroy(
     let existed = state.cluster_destroy(cluster_id).await;
     Json(ClusterDestroyResponse { existed })
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+
+    use super::*;
+
+    fn request(authorization: Option<&str>) -> Request {
+        let mut request = Request::builder().uri("/clusters");
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+        request.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_is_authorized() {
+        assert!(is_authorized(&request(Some("Bearer secret")), "secret"));
+
+        for authorization in [
+            None,
+            Some("Bearer wrong"),
+            Some("Bearer secre"),
+            Some("Bearer secret2"),
+            Some("Bearer "),
+            Some("secret"),
+            Some("bearer secret"),
+        ] {
+            assert!(
+                !is_authorized(&request(authorization), "secret"),
+                "{authorization:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+};
+use node::p2p::PeerId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cluster::{ClusterNodeId, ClusterOcamlNodeId},
+    node::NodeTestingConfig,
+    scenario::ScenarioStep,
+};
+
+use super::AppState;
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ClusterNodeInfo {
+    Rust {
+        node_id: ClusterNodeId,
+        peer_id: PeerId,
+        dial_addr: String,
+    },
+    Ocaml {
+        node_id: ClusterOcamlNodeId,
+        peer_id: PeerId,
+        dial_addr: String,
+    },
+}
+
+pub async fn cluster_nodes_list(
+    State(state): State<AppState>,
+    Path(cluster_id): Path<u16>,
+) -> Result<Json<Vec<ClusterNodeInfo>>, (StatusCode, String)> {
+    let cluster = state.cluster(cluster_id).await?;
+    let rust_nodes = cluster
+        .nodes_iter()
+        .map(|(node_id, node)| ClusterNodeInfo::Rust {
+            node_id,
+            peer_id: node.peer_id(),
+            dial_addr: node.dial_addr().to_string(),
+        });
+    let ocaml_nodes = cluster
+        .ocaml_nodes_iter()
+        .map(|(node_id, node)| ClusterNodeInfo::Ocaml {
+            node_id,
+            peer_id: node.peer_id(),
+            dial_addr: node.dial_addr().to_string(),
+        });
+
+    Ok(Json(rust_nodes.chain(ocaml_nodes).collect()))
+}
+
+/// Adds a node to the cluster. Same as executing the
+/// [`ScenarioStep::AddNode`] step, but returns the id of the new node.
+pub async fn cluster_node_add(
+    State(state): State<AppState>,
+    Path(cluster_id): Path<u16>,
+    Json(config): Json<NodeTestingConfig>,
+) -> Result<Json<ClusterNodeInfo>, (StatusCode, String)> {
+    let mut cluster = state.cluster(cluster_id).await?;
+    let is_rust = matches!(config, NodeTestingConfig::Rust(_));
+    cluster
+        .exec_step(ScenarioStep::AddNode {
+            config: Box::new(config),
+        })
+        .await
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let info = if is_rust {
+        cluster
+            .nodes_iter()
+            .last()
+            .map(|(node_id, node)| ClusterNodeInfo::Rust {
+                node_id,
+                peer_id: node.peer_id(),
+                dial_addr: node.dial_addr().to_string(),
+            })
+    } else {
+        cluster
+            .ocaml_nodes_iter()
+            .last()
+            .map(|(node_id, node)| ClusterNodeInfo::Ocaml {
+                node_id,
+                peer_id: node.peer_id(),
+                dial_addr: node.dial_addr().to_string(),
+            })
+    };
+    info.map(Json).ok_or_else(|| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "added node not found".to_owned(),
+        )
+    })
+}
+
+#[derive(Deserialize)]
+pub struct ClusterNodeStateQuery {
+    /// JSON pointer (RFC 6901) selecting a part of the state, for example
+    /// `/transition_frontier/best_chain`.
+    pointer: Option<String>,
+}
+
+pub async fn cluster_node_state(
+    State(state): State<AppState>,
+    Path((cluster_id, node_id)): Path<(u16, ClusterNodeId)>,
+    Query(query): Query<ClusterNodeStateQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let cluster = state.cluster(cluster_id).await?;
+    let node = cluster.node(node_id).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("node {node_id:?} not found"),
+        )
+    })?;
+    let mut value = serde_json::to_value(node.state())
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    if let Some(pointer) = query.pointer {
+        value = value
+            .pointer_mut(&pointer)
+            .map(std::mem::take)
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("no value at pointer '{pointer}'"),
+                )
+            })?;
+    }
+    Ok(Json(value))
+}
@@ -0,0 +1,82 @@
+use std::sync::{Arc, Weak};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Json, Path, State,
+    },
+    http::StatusCode,
+    response::Response,
+};
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+use crate::{cluster::Cluster, scenario::ScenarioStep};
+
+use super::AppState;
+
+#[derive(Serialize)]
+pub struct ClusterStepExecResponse {
+    /// Whether the step was dispatched. Steps waiting for an event aren't
+    /// dispatched if the event doesn't happen.
+    dispatched: bool,
+}
+
+pub async fn cluster_step_exec(
+    State(state): State<AppState>,
+    Path(cluster_id): Path<u16>,
+    Json(step): Json<ScenarioStep>,
+) -> Result<Json<ClusterStepExecResponse>, (StatusCode, String)> {
+    let mut cluster = state.cluster(cluster_id).await?;
+    cluster
+        .exec_step(step)
+        .await
+        .map(|dispatched| Json(ClusterStepExecResponse { dispatched }))
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))
+}
+
+/// Executes a step for each JSON text message received. Each step is
+/// answered with `{"Ok":{"dispatched":bool}}` or `{"Err":"<error>"}`.
+///
+/// The cluster is only locked while a step is executed, so other requests
+/// can be made to it in between.
+pub async fn cluster_steps_ws(
+    State(state): State<AppState>,
+    Path(cluster_id): Path<u16>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, (StatusCode, String)> {
+    let cluster = Arc::downgrade(&state.cluster_mutex(cluster_id).await?);
+    Ok(ws.on_upgrade(move |socket| steps_ws(socket, cluster)))
+}
+
+async fn steps_ws(mut socket: WebSocket, cluster: Weak<Mutex<Cluster>>) {
+    while let Some(Ok(msg)) = socket.recv().await {
+        let step = match msg {
+            Message::Text(text) => serde_json::from_str::<ScenarioStep>(&text),
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let Some(cluster) = cluster.upgrade() else {
+            let _ = send(&mut socket, Err("cluster destroyed".to_owned())).await;
+            break;
+        };
+        let res = match step {
+            Ok(step) => cluster.lock().await.exec_step(step).await,
+            Err(err) => Err(anyhow::anyhow!("invalid step: {err}")),
+        };
+        let res = res
+            .map(|dispatched| ClusterStepExecResponse { dispatched })
+            .map_err(|err| err.to_string());
+        if send(&mut socket, res).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn send(
+    socket: &mut WebSocket,
+    res: Result<ClusterStepExecResponse, String>,
+) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(&res).map_err(axum::Error::new)?;
+    socket.send(Message::Text(text)).await
+}
@@ -38,9 +38,9 @@ async fn webnode_lock_random_bp(
     Path(cluster_id): Path<u16>,
 ) -> Result<Redirect, (StatusCode, String)> {
     use base64::{engine::general_purpose::URL_SAFE, Engine as _};
+    let mut cluster = state.cluster(cluster_id).await?;
     let mut state_guard = state.lock().await;
     let state = &mut *state_guard;
-    let mut cluster = state.cluster(cluster_id).await?;
     let runner = ClusterRunner::new(&mut cluster, |_| {});
     let locked_keys = state
         .locked_block_producer_keys
@@ -7,7 +7,6 @@ use std::{
 };
 
 use ledger::{
-    dummy::dummy_transaction_proof,
     proofs::transaction::ProofError,
     scan_state::{
         scan_state::transaction_snark::SokMessage,
@@ -15,16 +14,13 @@ use ledger::{
     },
     Mask,
 };
-use mina_core::channels::Aborter;
-use mina_node_native::NodeService;
+use mina_core::{block::ArcBlockWithHash, channels::Aborter};
+use mina_node_native::{rpc::RpcSender, snark_worker, EventSender, NodeService};
 use mina_p2p_messages::{
     string::ByteString,
     v2::{
-        CurrencyFeeStableV1, LedgerHash, LedgerProofProdStableV2, MinaBaseProofStableV2,
-        MinaStateSnarkedLedgerStateWithSokStableV2, NonZeroCurvePoint,
-        ProverExtendBlockchainInputStableV2,
-        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single, StateHash,
-        TransactionSnarkStableV2, TransactionSnarkWorkTStableV2Proofs,
+        CurrencyFeeStableV1, LedgerHash, MinaBaseProofStableV2, NonZeroCurvePoint,
+        ProverExtendBlockchainInputStableV2, StateHash,
     },
 };
 use node::{
@@ -37,7 +33,9 @@ use node::{
     },
     event_source::Event,
     external_snark_worker::SnarkWorkSpec,
-    external_snark_worker_effectful::{ExternalSnarkWorkerEvent, ExternalSnarkWorkerService},
+    external_snark_worker_effectful::{
+        ExternalSnarkWorkerCoordinatorService, ExternalSnarkWorkerEvent, ExternalSnarkWorkerService,
+    },
     ledger::write::BlockApplyResult,
     p2p::{
         connection::outgoing::P2pConnectionOutgoingInitOpts,
@@ -48,19 +46,31 @@ use node::{
         webrtc, P2pCryptoService, PeerId,
     },
     recorder::Recorder,
+    rpc::RpcId,
     service::{
-        BlockProducerService, BlockProducerVrfEvaluatorService, TransitionFrontierGenesisService,
+        BlockProducerService, BlockProducerVrfEvaluatorService, P2pPeerListService,
+        TransitionFrontierGenesisService, TransitionFrontierSyncLedgerSnapshotService,
+        TransitionFrontierSyncPrecomputedBlockService,
     },
     snark::{
         block_verify::{SnarkBlockVerifyId, SnarkBlockVerifyService, VerifiableBlockWithHash},
         user_command_verify::SnarkUserCommandVerifyId,
         user_command_verify_effectful::SnarkUserCommandVerifyService,
+        verify_dry_run::{SnarkVerifyDryRunInput, SnarkVerifyDryRunService},
         work_verify::{SnarkWorkVerifyId, SnarkWorkVerifyService},
         BlockVerifier, SnarkEvent, TransactionVerifier, VerifierSRS,
     },
     snark_pool::SnarkPoolService,
     stats::Stats,
-    transition_frontier::{archive::archive_service::ArchiveService, genesis::GenesisConfig},
+    transition_frontier::{
+        archive::archive_service::{ArchiveService, ArchiveUploadStats},
+        genesis::GenesisConfig,
+        sync::{
+            ledger::{LedgerSnapshotQuery, LedgerSnapshotSource},
+            TransitionFrontierSyncState,
+        },
+        TransitionFrontierEventService,
+    },
     ActionWithMeta, State,
 };
 use redux::Instant;
@@ -224,6 +234,11 @@ impl NodeTestingService {
         self.dyn_effects.take()
     }
 
+    /// Sends RPC requests to the node, as the http server does.
+    pub fn rpc_sender(&self) -> RpcSender {
+        self.real.rpc_sender()
+    }
+
     pub fn set_snarker_sok_digest(&mut self, digest: ByteString) {
         self.snarker_sok_digest = Some(digest);
     }
@@ -364,6 +379,7 @@ impl TransitionFrontierGenesisService for NodeTestingService {
 
 impl P2pServiceWebrtc for NodeTestingService {
     type Event = Event;
+    type EventSender = EventSender;
 
     fn random_pick(
         &mut self,
@@ -372,7 +388,7 @@ impl P2pServiceWebrtc for NodeTestingService {
         self.real.random_pick(list)
     }
 
-    fn event_sender(&self) -> &mpsc::UnboundedSender<Event> {
+    fn event_sender(&self) -> &EventSender {
         P2pServiceWebrtc::event_sender(&self.real)
     }
 
@@ -469,8 +485,9 @@ impl SnarkUserCommandVerifyService for NodeTestingService {
         &mut self,
         req_id: SnarkUserCommandVerifyId,
         commands: Vec<WithStatus<verifiable::UserCommand>>,
+        concurrency: usize,
     ) {
-        SnarkUserCommandVerifyService::verify_init(&mut self.real, req_id, commands)
+        SnarkUserCommandVerifyService::verify_init(&mut self.real, req_id, commands, concurrency)
     }
 }
 
@@ -500,6 +517,26 @@ impl SnarkWorkVerifyService for NodeTestingService {
     }
 }
 
+impl SnarkVerifyDryRunService for NodeTestingService {
+    fn verify_dry_run(
+        &mut self,
+        rpc_id: RpcId,
+        input: SnarkVerifyDryRunInput,
+        block_verifier_index: BlockVerifier,
+        work_verifier_index: TransactionVerifier,
+        verifier_srs: Arc<VerifierSRS>,
+    ) {
+        SnarkVerifyDryRunService::verify_dry_run(
+            &mut self.real,
+            rpc_id,
+            input,
+            block_verifier_index,
+            work_verifier_index,
+            verifier_srs,
+        )
+    }
+}
+
 impl SnarkPoolService for NodeTestingService {
     fn random_choose<'a>(
         &mut self,
@@ -520,6 +557,38 @@ impl ArchiveService for NodeTestingService {
     fn send_to_archive(&mut self, data: BlockApplyResult) {
         self.real.send_to_archive(data);
     }
+
+    fn archive_upload_stats(&self) -> Option<Vec<ArchiveUploadStats>> {
+        self.real.archive_upload_stats()
+    }
+}
+
+impl TransitionFrontierEventService for NodeTestingService {
+    fn best_tip_update(&mut self, best_tip: &ArcBlockWithHash) {
+        self.real.best_tip_update(best_tip);
+    }
+
+    fn sync_state_update(&mut self, sync: &TransitionFrontierSyncState) {
+        self.real.sync_state_update(sync);
+    }
+}
+
+impl P2pPeerListService for NodeTestingService {
+    fn peer_list_refresh(&mut self, rpc_id: Option<RpcId>) -> Result<(), String> {
+        self.real.peer_list_refresh(rpc_id)
+    }
+}
+
+impl TransitionFrontierSyncLedgerSnapshotService for NodeTestingService {
+    fn ledger_snapshot_fetch(&mut self, source: &LedgerSnapshotSource, query: LedgerSnapshotQuery) {
+        self.real.ledger_snapshot_fetch(source, query)
+    }
+}
+
+impl TransitionFrontierSyncPrecomputedBlockService for NodeTestingService {
+    fn precomputed_block_fetch(&mut self, url: &str, height: u32, block_hash: StateHash) {
+        self.real.precomputed_block_fetch(url, height, block_hash)
+    }
 }
 
 use std::cell::RefCell;
@@ -639,32 +708,7 @@ impl ExternalSnarkWorkerService for NodeTestingService {
         spec: SnarkWorkSpec,
     ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         let sok_digest = self.snarker_sok_digest.clone().unwrap();
-        let make_dummy_proof = |spec| {
-            let statement = match spec {
-                SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Transition(v, _) => v.0,
-                SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Merge(v) => v.0 .0,
-            };
-
-            LedgerProofProdStableV2(TransactionSnarkStableV2 {
-                statement: MinaStateSnarkedLedgerStateWithSokStableV2 {
-                    source: statement.source,
-                    target: statement.target,
-                    connecting_ledger_left: statement.connecting_ledger_left,
-                    connecting_ledger_right: statement.connecting_ledger_right,
-                    supply_increase: statement.supply_increase,
-                    fee_excess: statement.fee_excess,
-                    sok_digest: sok_digest.clone(),
-                },
-                proof: (*dummy_transaction_proof()).clone(),
-            })
-        };
-        let res = match spec {
-            SnarkWorkSpec::One(v) => TransactionSnarkWorkTStableV2Proofs::One(make_dummy_proof(v)),
-            SnarkWorkSpec::Two((v1, v2)) => TransactionSnarkWorkTStableV2Proofs::Two((
-                make_dummy_proof(v1),
-                make_dummy_proof(v2),
-            )),
-        };
+        let res = snark_worker::dummy_work(spec, &sok_digest);
         let _ = self
             .real
             .event_sender()
@@ -692,6 +736,20 @@ impl ExternalSnarkWorkerService for NodeTestingService {
     }
 }
 
+impl ExternalSnarkWorkerCoordinatorService for NodeTestingService {
+    fn coordinator_submit(&mut self, url: &str, snark: Snark) {
+        self.real.coordinator_submit(url, snark)
+    }
+
+    fn coordinator_jobs_fetch(&mut self, url: &str, fee: CurrencyFeeStableV1) {
+        self.real.coordinator_jobs_fetch(url, fee)
+    }
+
+    fn coordinator_job_spec_fetch(&mut self, url: &str, job_id: SnarkJobId) {
+        self.real.coordinator_job_spec_fetch(url, job_id)
+    }
+}
+
 impl node::core::invariants::InvariantService for NodeTestingService {
     type ClusterInvariantsState<'a> = std::sync::MutexGuard<'a, InvariantsState>;
 
@@ -17,10 +17,22 @@ impl RpcService for super::NodeTestingService {
     to_real!(respond_status_get, node::rpc::RpcStatusGetResponse);
     to_real!(respond_heartbeat_get, node::rpc::RpcHeartbeatGetResponse);
     to_real!(respond_sync_stats_get, node::rpc::RpcSyncStatsGetResponse);
+    to_real!(
+        respond_sync_history_get,
+        node::rpc::RpcSyncHistoryGetResponse
+    );
     to_real!(
         respond_block_producer_stats_get,
         node::rpc::RpcBlockProducerStatsGetResponse
     );
+    to_real!(
+        respond_peer_contribution_stats_get,
+        node::rpc::RpcPeerContributionStatsGetResponse
+    );
+    to_real!(
+        respond_archive_upload_stats_get,
+        node::rpc::RpcArchiveUploadStatsGetResponse
+    );
 
     to_real!(
         respond_action_stats_get,
@@ -66,6 +78,10 @@ impl RpcService for super::NodeTestingService {
         respond_snarker_job_spec,
         node::rpc::RpcSnarkerJobSpecResponse,
     );
+    to_real!(
+        respond_snarker_work_submit,
+        node::rpc::RpcSnarkerWorkSubmitResponse,
+    );
     to_real!(
         respond_snarker_workers,
         node::rpc::RpcSnarkerWorkersResponse,
@@ -91,6 +107,10 @@ impl RpcService for super::NodeTestingService {
         respond_transaction_pool,
         node::rpc::RpcTransactionPoolResponse
     );
+    to_real!(
+        respond_transaction_pool_sender_queues,
+        node::rpc::RpcTransactionPoolSenderQueuesGetResponse
+    );
     to_real!(
         respond_ledger_slim_accounts,
         node::rpc::RpcLedgerSlimAccountsResponse
@@ -108,6 +128,10 @@ impl RpcService for super::NodeTestingService {
         node::rpc::RpcTransitionFrontierUserCommandsResponse,
     );
     to_real!(respond_best_chain, node::rpc::RpcBestChainResponse,);
+    to_real!(
+        respond_best_chain_page_get,
+        node::rpc::RpcBestChainPageGetResponse,
+    );
     to_real!(
         respond_consensus_constants,
         node::rpc::RpcConsensusConstantsGetResponse,
@@ -117,6 +141,10 @@ impl RpcService for super::NodeTestingService {
         node::rpc::RpcTransactionStatusGetResponse,
     );
     to_real!(respond_block_get, node::rpc::RpcGetBlockResponse,);
+    to_real!(
+        respond_block_summary_get,
+        node::rpc::RpcBlockSummaryGetResponse,
+    );
     to_real!(
         respond_pooled_user_commands,
         node::rpc::RpcPooledUserCommandsResponse,
@@ -125,6 +153,26 @@ impl RpcService for super::NodeTestingService {
         respond_pooled_zkapp_commands,
         node::rpc::RpcPooledZkappCommandsResponse,
     );
+    to_real!(
+        respond_pooled_user_commands_page_get,
+        node::rpc::RpcPooledUserCommandsPageGetResponse,
+    );
+    to_real!(
+        respond_pooled_zkapp_commands_page_get,
+        node::rpc::RpcPooledZkappCommandsPageGetResponse,
+    );
+    to_real!(
+        respond_zkapp_events_page_get,
+        node::rpc::RpcZkappEventsPageGetResponse,
+    );
+    to_real!(
+        respond_zkapp_actions_events_get,
+        node::rpc::RpcZkappActionsEventsGetResponse,
+    );
+    to_real!(
+        respond_token_supply_get,
+        node::rpc::RpcTokenSupplyGetResponse,
+    );
     to_real!(respond_genesis_block, node::rpc::RpcGenesisBlockResponse,);
     to_real!(
         respond_consensus_time_get,
@@ -138,4 +186,67 @@ impl RpcService for super::NodeTestingService {
         respond_ledger_account_delegators_get,
         node::rpc::RpcLedgerAccountDelegatorsGetResponse,
     );
+    to_real!(
+        respond_ledger_snapshot_get,
+        node::rpc::RpcLedgerSnapshotGetResponse,
+    );
+    to_real!(
+        respond_ledger_accounts_page_get,
+        node::rpc::RpcLedgerAccountsPageGetResponse,
+    );
+    to_real!(
+        respond_ledger_accounts_at_block_get,
+        node::rpc::RpcLedgerAccountsAtBlockGetResponse,
+    );
+    to_real!(respond_block_inject, node::rpc::RpcBlockInjectResponse,);
+    to_real!(
+        respond_consensus_fork_decisions_get,
+        node::rpc::RpcConsensusForkDecisionsGetResponse,
+    );
+    to_real!(
+        respond_pending_coinbase_get,
+        node::rpc::RpcPendingCoinbaseGetResponse,
+    );
+    to_real!(
+        respond_peer_list_refresh,
+        node::rpc::RpcPeerListRefreshResponse,
+    );
+    to_real!(respond_gossip_pause, node::rpc::RpcGossipPauseResponse,);
+    to_real!(
+        respond_sync_diagnosis_get,
+        node::rpc::RpcSyncDiagnosisGetResponse,
+    );
+    to_real!(
+        respond_node_overview_get,
+        node::rpc::RpcNodeOverviewGetResponse,
+    );
+    to_real!(
+        respond_locally_generated_commands_get,
+        node::rpc::RpcLocallyGeneratedCommandsGetResponse,
+    );
+    to_real!(
+        respond_pending_requests_get,
+        node::rpc::RpcPendingRequestsGetResponse,
+    );
+    to_real!(respond_snark_stats_get, node::rpc::RpcSnarkStatsGetResponse,);
+    to_real!(
+        respond_snark_verifier_digests_get,
+        node::rpc::RpcSnarkVerifierDigestsGetResponse,
+    );
+    to_real!(
+        respond_snark_verify_history_get,
+        node::rpc::RpcSnarkVerifyHistoryGetResponse,
+    );
+    to_real!(
+        respond_snark_verify_dry_run,
+        node::rpc::RpcSnarkVerifyDryRunResponse,
+    );
+    to_real!(
+        respond_enabled_features_get,
+        node::rpc::RpcEnabledFeaturesGetResponse,
+    );
+
+    fn drop_request(&mut self, rpc_id: RpcId) {
+        self.real.drop_request(rpc_id)
+    }
 }
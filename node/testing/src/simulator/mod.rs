@@ -8,13 +8,14 @@ use mina_p2p_messages::v2::{
 use std::{collections::BTreeSet, time::Duration};
 
 use node::{
-    ActionKind, ActionWithMeta, BlockProducerConfig, SnarkerConfig, SnarkerStrategy, State,
+    p2p::P2pTimeouts, ActionKind, ActionWithMeta, BlockProducerConfig, SnarkerConfig,
+    SnarkerStrategy, State,
 };
 
 use crate::{
     cluster::ClusterNodeId,
     node::{Node, RustNodeBlockProducerTestingConfig, RustNodeTestingConfig},
-    scenario::ListenerNode,
+    scenario::{ListenerNode, ScenarioStep},
     scenarios::{ClusterRunner, RunCfg},
     service::NodeTestingService,
 };
@@ -22,6 +23,7 @@ use crate::{
 pub struct Simulator {
     initial_time: redux::Timestamp,
     config: SimulatorConfig,
+    timeouts: P2pTimeouts,
     start_t: Option<redux::Instant>,
 }
 
@@ -30,10 +32,17 @@ impl Simulator {
         Self {
             initial_time,
             config,
+            timeouts: Default::default(),
             start_t: None,
         }
     }
 
+    /// Sets the p2p timeouts of the nodes, e.g. to enable bans.
+    pub fn with_timeouts(mut self, timeouts: P2pTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
     fn initial_time(&self) -> redux::Timestamp {
         self.initial_time
     }
@@ -47,10 +56,11 @@ impl Simulator {
             peer_id: Default::default(),
             block_producer: None,
             snark_worker: None,
-            timeouts: Default::default(),
+            timeouts: self.timeouts.clone(),
             libp2p_port: None,
             recorder: self.config.recorder.clone(),
             peer_discovery: true,
+            slow_disk: None,
         }
     }
 
@@ -167,6 +177,7 @@ impl Simulator {
                     )),
                     strategy: SnarkerStrategy::Sequential,
                     auto_commit: true,
+                    coordinators: Vec::new(),
                 }),
                 ..node_config.clone()
             };
@@ -210,6 +221,7 @@ impl Simulator {
                         pub_key: sec_key.public_key().into(),
                         custom_coinbase_receiver: None,
                         proposed_protocol_version: None,
+                        work_fee_policy: Default::default(),
                     },
                     sec_key,
                 }),
@@ -252,14 +264,33 @@ impl Simulator {
     }
 
     pub async fn run_with_listener<AL, ALF>(
+        &mut self,
+        runner: &mut ClusterRunner<'_>,
+        listener: ALF,
+    ) where
+        ALF: FnMut() -> AL,
+        AL: 'static
+            + Send
+            + FnMut(ClusterNodeId, &State, &NodeTestingService, &ActionWithMeta) -> bool,
+    {
+        self.run_with_hooks(runner, listener, |_, _| Vec::new())
+            .await;
+    }
+
+    /// Like [`Self::run_with_listener`], but also calls `on_slot` once the
+    /// first node reaches a new global slot, and executes the steps it
+    /// returns, e.g. to inject transactions or to check the nodes.
+    pub async fn run_with_hooks<AL, ALF, S>(
         &mut self,
         runner: &mut ClusterRunner<'_>,
         mut listener: ALF,
+        mut on_slot: S,
     ) where
         ALF: FnMut() -> AL,
         AL: 'static
             + Send
             + FnMut(ClusterNodeId, &State, &NodeTestingService, &ActionWithMeta) -> bool,
+        S: FnMut(&ClusterRunner<'_>, u32) -> Vec<ScenarioStep>,
     {
         let run_until = self.config.run_until.clone();
         let advance_time = self.config.advance_time.clone();
@@ -323,6 +354,13 @@ impl Simulator {
                         return;
                     }
                 }
+
+                for step in on_slot(runner, last_printed_slot) {
+                    runner
+                        .exec_step(step)
+                        .await
+                        .expect("failed to execute simulator step");
+                }
             }
         }
 
@@ -19,7 +19,7 @@ macro_rules! scenario_test {
         async fn $name() {
             use mina_node_testing::{
                 cluster::Cluster,
-                scenarios::{ClusterRunner, Scenarios},
+                scenarios::Scenarios,
                 setup_without_rt, wait_for_other_tests,
             };
             use std::io::Write;
@@ -65,8 +65,7 @@ macro_rules! scenario_test {
                 config.set_all_rust_to_rust_use_webrtc();
             }
             let mut cluster = Cluster::new(config);
-            let runner = ClusterRunner::new(&mut cluster, |_| {});
-            scenario.run(runner).await;
+            Scenarios::from(scenario).run_only(&mut cluster).await;
 
             if let Some(summary) = std::env::var_os("GITHUB_STEP_SUMMARY") {
                 let _ = std::fs::File::options()
@@ -0,0 +1,15 @@
+mod common;
+
+#[cfg(feature = "byzantine")]
+scenario_test!(
+    equivocation,
+    mina_node_testing::scenarios::multi_node::byzantine::MultiNodeByzantineEquivocation,
+    mina_node_testing::scenarios::multi_node::byzantine::MultiNodeByzantineEquivocation
+);
+
+#[cfg(feature = "byzantine")]
+scenario_test!(
+    wrong_snark_fee,
+    mina_node_testing::scenarios::multi_node::byzantine::MultiNodeByzantineWrongSnarkFee,
+    mina_node_testing::scenarios::multi_node::byzantine::MultiNodeByzantineWrongSnarkFee
+);
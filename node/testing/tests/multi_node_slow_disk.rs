@@ -0,0 +1,7 @@
+mod common;
+
+scenario_test!(
+    slow_disk,
+    mina_node_testing::scenarios::multi_node::slow_disk::MultiNodeSlowDisk,
+    mina_node_testing::scenarios::multi_node::slow_disk::MultiNodeSlowDisk
+);
@@ -0,0 +1,7 @@
+mod common;
+
+scenario_test!(
+    time_acceleration,
+    mina_node_testing::scenarios::multi_node::time_acceleration::MultiNodeTimeAcceleration,
+    mina_node_testing::scenarios::multi_node::time_acceleration::MultiNodeTimeAcceleration
+);
@@ -2,7 +2,8 @@
 use mina_node_testing::scenarios::solo_node::basic_connectivity_accept_incoming::SoloNodeBasicConnectivityAcceptIncoming;
 use mina_node_testing::scenarios::solo_node::{
     basic_connectivity_initial_joining::SoloNodeBasicConnectivityInitialJoining,
-    bootstrap::SoloNodeBootstrap, sync_root_snarked_ledger::SoloNodeSyncRootSnarkedLedger,
+    block_inject::SoloNodeBlockInject, bootstrap::SoloNodeBootstrap,
+    sync_root_snarked_ledger::SoloNodeSyncRootSnarkedLedger,
 };
 
 mod common;
@@ -21,6 +22,8 @@ scenario_test!(
     SoloNodeBasicConnectivityInitialJoining
 );
 
+scenario_test!(block_inject, SoloNodeBlockInject, SoloNodeBlockInject);
+
 scenario_test!(
     #[ignore = "investigate failure"]
     sync_root_snarked_ledger,
@@ -126,6 +126,7 @@ impl NodeBuilder {
             pub_key: key.public_key().into(),
             custom_coinbase_receiver: None,
             proposed_protocol_version: None,
+            work_fee_policy: Default::default(),
         };
         self.block_producer = Some(config);
         self.service.block_producer_init(key, provers);
@@ -169,6 +170,7 @@ impl NodeBuilder {
             )),
             strategy,
             auto_commit: true,
+            coordinators: Vec::new(),
         };
         self.snarker = Some(config);
         self
@@ -234,7 +236,9 @@ impl NodeBuilder {
                 libp2p_port: None,
                 listen_port: None,
                 identity_pub_key: p2p_sec_key.public_key(),
+                previous_peer_ids: Vec::new(),
                 initial_peers,
+                initial_dnsaddrs: Vec::new(),
                 external_addrs: vec![],
                 enabled_channels: ChannelId::iter_all().collect(),
                 peer_discovery: !self.p2p_no_discovery,
@@ -245,12 +249,16 @@ impl NodeBuilder {
                 timeouts: P2pTimeouts::default(),
                 limits: P2pLimits::default().with_max_peers(Some(100)),
             },
-            ledger: LedgerConfig {},
+            ledger: LedgerConfig::default(),
             snark: SnarkConfig {
                 block_verifier_index,
-                block_verifier_srs: srs.clone(),
                 work_verifier_index,
-                work_verifier_srs: srs,
+                verifier_srs: srs,
+                timeouts: Default::default(),
+                verify_cache: Default::default(),
+                verify_concurrency: 1,
+                constraint_constants_digest: None,
+                verifier_digests: None,
             },
             transition_frontier,
             block_producer: self.block_producer,
@@ -258,6 +266,9 @@ impl NodeBuilder {
                 trust_system: (),
                 pool_max_size: node::daemon_json::Daemon::DEFAULT.tx_pool_max_size(),
                 slot_tx_end: node::daemon_json::Daemon::DEFAULT.slot_tx_end(),
+                max_lifetime: node::core::NetworkConfig::global().tx_pool_max_lifetime,
+                zkapp_relay: Default::default(),
+                replace_fee: ledger::transaction_pool::DEFAULT_REPLACE_FEE,
             },
             archive: None,
         };
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     channels::{best_tip::P2pChannelsBestTipState, P2pChannelsAction},
+    gossip::P2pGossipKind,
     P2pState, PeerId,
 };
 
@@ -75,16 +76,19 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsBestTipAction {
                     )
                 })
             }
-            P2pChannelsBestTipAction::RequestSend { peer_id } => state
-                .get_ready_peer(peer_id)
-                .is_some_and(|p| match &p.channels.best_tip {
-                    P2pChannelsBestTipState::Ready { local, .. } => matches!(
-                        local,
-                        BestTipPropagationState::WaitingForRequest { .. }
-                            | BestTipPropagationState::Responded { .. },
-                    ),
-                    _ => false,
-                }),
+            P2pChannelsBestTipAction::RequestSend { peer_id } => {
+                !state.is_gossip_paused(P2pGossipKind::Block)
+                    && state
+                        .get_ready_peer(peer_id)
+                        .is_some_and(|p| match &p.channels.best_tip {
+                            P2pChannelsBestTipState::Ready { local, .. } => matches!(
+                                local,
+                                BestTipPropagationState::WaitingForRequest { .. }
+                                    | BestTipPropagationState::Responded { .. },
+                            ),
+                            _ => false,
+                        })
+            }
             P2pChannelsBestTipAction::Received { peer_id, .. } => {
                 // TODO(binier): use consensus to enforce that peer doesn't send
                 // us inferior block than it has in the past.
@@ -82,7 +82,10 @@ impl ChannelId {
         }
     }
 
-    pub fn max_msg_size(self) -> usize {
+    /// Max size of a message received on the channel. Snark messages are
+    /// only allowed to be big enough for a batch if the peer negotiated
+    /// batching, see [`crate::P2pPeerStatusReady::snark_batching`].
+    pub fn max_msg_size(self, snark_batching: bool) -> usize {
         match self {
             // TODO(binier): measure signaling message sizes
             Self::SignalingDiscovery => 16 * 1024, // 16KB
@@ -91,10 +94,11 @@ impl ChannelId {
             // propagation to just propagating consensus state with block hash.
             Self::BestTipPropagation => 32 * 1024 * 1024, // 32MB
             Self::TransactionPropagation => 1024,         // 1KB - just transaction info.
-            Self::SnarkPropagation => 1024,               // 1KB - just snark info.
-            Self::SnarkJobCommitmentPropagation => 2 * 1024, // 2KB,
-            Self::Rpc => 256 * 1024 * 1024,               // 256MB,
-            Self::StreamingRpc => 16 * 1024 * 1024,       // 16MB,
+            Self::SnarkPropagation if snark_batching => 256 * 1024, // 256KB - up to 255 snark infos.
+            Self::SnarkPropagation => 1024,                         // 1KB - just snark info.
+            Self::SnarkJobCommitmentPropagation => 2 * 1024,        // 2KB,
+            Self::Rpc => 256 * 1024 * 1024,                         // 256MB,
+            Self::StreamingRpc => 16 * 1024 * 1024,                 // 16MB,
         }
     }
 
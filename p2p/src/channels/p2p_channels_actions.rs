@@ -25,6 +25,7 @@ use super::{
 #[derive(Serialize, Deserialize, Debug, Clone, mina_core::ActionEvent)]
 pub enum P2pChannelsAction {
     MessageReceived(P2pChannelsMessageReceivedAction),
+    MessageShed(P2pChannelsMessageShedAction),
     SignalingDiscovery(P2pChannelsSignalingDiscoveryAction),
     SignalingExchange(P2pChannelsSignalingExchangeAction),
     BestTip(P2pChannelsBestTipAction),
@@ -73,6 +74,7 @@ impl P2pChannelsAction {
     pub fn peer_id(&self) -> Option<&PeerId> {
         match self {
             Self::MessageReceived(v) => Some(&v.peer_id),
+            Self::MessageShed(v) => Some(&v.peer_id),
             Self::SignalingDiscovery(v) => Some(v.peer_id()),
             Self::SignalingExchange(v) => Some(v.peer_id()),
             Self::BestTip(v) => Some(v.peer_id()),
@@ -89,6 +91,7 @@ impl redux::EnablingCondition<crate::P2pState> for P2pChannelsAction {
     fn is_enabled(&self, state: &crate::P2pState, time: redux::Timestamp) -> bool {
         match self {
             P2pChannelsAction::MessageReceived(a) => a.is_enabled(state, time),
+            P2pChannelsAction::MessageShed(a) => a.is_enabled(state, time),
             P2pChannelsAction::SignalingDiscovery(a) => a.is_enabled(state, time),
             P2pChannelsAction::SignalingExchange(a) => a.is_enabled(state, time),
             P2pChannelsAction::Transaction(a) => a.is_enabled(state, time),
@@ -134,3 +137,43 @@ impl ActionEvent for P2pChannelsMessageReceivedAction {
     {
     }
 }
+
+/// Messages of a propagation channel that the service dropped under load,
+/// see [`crate::P2pChannelEvent::Shed`]. The channel moves on as if they were
+/// received, without their content, so the peer stays connected.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pChannelsMessageShedAction {
+    pub peer_id: PeerId,
+    pub channel_id: ChannelId,
+    /// Number of items the messages carried.
+    pub count: usize,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsMessageShedAction {
+    fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
+        state
+            .get_ready_peer(&self.peer_id)
+            .is_some_and(|p| p.channels.is_channel_ready(self.channel_id))
+    }
+}
+
+impl From<P2pChannelsMessageShedAction> for crate::P2pAction {
+    fn from(a: P2pChannelsMessageShedAction) -> Self {
+        Self::Channels(P2pChannelsAction::MessageShed(a))
+    }
+}
+
+impl ActionEvent for P2pChannelsMessageShedAction {
+    fn action_event<T>(&self, context: &T)
+    where
+        T: mina_core::log::EventContext,
+    {
+        mina_core::action_debug!(
+            context,
+            kind = "P2pChannelsMessageShed",
+            peer_id = display(self.peer_id),
+            channel_id = debug(self.channel_id),
+            count = self.count
+        );
+    }
+}
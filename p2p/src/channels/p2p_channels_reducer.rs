@@ -1,5 +1,8 @@
 use super::{
-    best_tip::{BestTipPropagationChannelMsg, P2pChannelsBestTipAction, P2pChannelsBestTipState},
+    best_tip::{
+        BestTipPropagationChannelMsg, BestTipPropagationState, P2pChannelsBestTipAction,
+        P2pChannelsBestTipState,
+    },
     rpc::{P2pChannelsRpcAction, P2pChannelsRpcState, RpcChannelMsg},
     signaling::{
         discovery::{
@@ -22,13 +25,14 @@ use super::{
     transaction::{
         P2pChannelsTransactionAction, P2pChannelsTransactionState, TransactionPropagationChannelMsg,
     },
-    ChannelMsg, P2pChannelsAction, P2pChannelsMessageReceivedAction, P2pChannelsState,
+    ChannelId, ChannelMsg, P2pChannelsAction, P2pChannelsMessageReceivedAction,
+    P2pChannelsMessageShedAction, P2pChannelsState,
 };
 use crate::{
     disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
     P2pState,
 };
-use mina_core::{block::BlockWithHash, error, Substate};
+use mina_core::{block::BlockWithHash, bug_condition, error, Substate};
 use redux::{ActionWithMeta, Dispatcher};
 
 impl P2pChannelsState {
@@ -47,6 +51,9 @@ impl P2pChannelsState {
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 Self::dispatch_message(meta.with_action(action), dispatcher, state)
             }
+            P2pChannelsAction::MessageShed(action) => {
+                Self::message_shed(state_context, meta.with_action(action))
+            }
             P2pChannelsAction::SignalingDiscovery(action) => {
                 P2pChannelsSignalingDiscoveryState::reducer(state_context, meta.with_action(action))
             }
@@ -194,6 +201,9 @@ impl P2pChannelsState {
                     }
                     .into(),
                 ),
+                SnarkPropagationChannelMsg::Snarks(snarks) => {
+                    is_enabled(P2pChannelsSnarkAction::BatchReceived { peer_id, snarks }.into())
+                }
             },
             ChannelMsg::SnarkJobCommitmentPropagation(msg) => match msg {
                 SnarkJobCommitmentPropagationChannelMsg::GetNext { limit } => is_enabled(
@@ -273,4 +283,83 @@ impl P2pChannelsState {
 
         Ok(())
     }
+
+    /// Moves the propagation channel on as if the shed messages were
+    /// received, so that the next ones get requested, but drops nothing
+    /// else: their content was never seen.
+    fn message_shed<Action, State>(
+        mut state_context: Substate<Action, State, P2pState>,
+        action: ActionWithMeta<P2pChannelsMessageShedAction>,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let (action, meta) = action.split();
+        let P2pChannelsMessageShedAction {
+            peer_id,
+            channel_id,
+            count,
+        } = action;
+        let time = meta.time();
+
+        let p2p_state = state_context.get_substate_mut()?;
+        let Some(peer) = p2p_state.get_ready_peer_mut(&peer_id) else {
+            bug_condition!(
+                "Invalid state for `P2pChannelsMessageShedAction`, peer {peer_id} not ready"
+            );
+            return Ok(());
+        };
+        let channels = &mut peer.channels;
+        let was_expected = match channel_id {
+            ChannelId::BestTipPropagation => match &mut channels.best_tip {
+                P2pChannelsBestTipState::Ready { local, .. }
+                    if matches!(local, BestTipPropagationState::Requested { .. }) =>
+                {
+                    *local = BestTipPropagationState::Responded { time };
+                    true
+                }
+                _ => false,
+            },
+            ChannelId::TransactionPropagation => match &mut channels.transaction {
+                P2pChannelsTransactionState::Ready { local, .. } => local.on_received(count, time),
+                _ => false,
+            },
+            ChannelId::SnarkPropagation => match &mut channels.snark {
+                P2pChannelsSnarkState::Ready { local, .. } => local.on_received(count, time),
+                _ => false,
+            },
+            ChannelId::SnarkJobCommitmentPropagation => match &mut channels.snark_job_commitment {
+                P2pChannelsSnarkJobCommitmentState::Ready { local, .. } => {
+                    local.on_received(count, time)
+                }
+                _ => false,
+            },
+            ChannelId::SignalingDiscovery
+            | ChannelId::SignalingExchange
+            | ChannelId::Rpc
+            | ChannelId::StreamingRpc => false,
+        };
+
+        let dispatcher = state_context.into_dispatcher();
+        if !was_expected {
+            let reason = P2pDisconnectionReason::P2pChannelMsgUnexpected(channel_id);
+            dispatcher.push(P2pDisconnectionAction::Init { peer_id, reason });
+            return Ok(());
+        }
+        // Same follow-ups as for received messages.
+        match channel_id {
+            ChannelId::BestTipPropagation => {
+                dispatcher.push(P2pChannelsBestTipAction::RequestSend { peer_id });
+            }
+            ChannelId::SnarkJobCommitmentPropagation => {
+                dispatcher.push(P2pChannelsSnarkJobCommitmentAction::RequestSend {
+                    peer_id,
+                    limit: super::snark_job_commitment::LIMIT,
+                });
+            }
+            _ => {}
+        }
+        Ok(())
+    }
 }
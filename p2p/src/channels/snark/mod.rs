@@ -10,6 +10,8 @@ use binprot_derive::{BinProtRead, BinProtWrite};
 use mina_core::snark::SnarkInfo;
 use serde::{Deserialize, Serialize};
 
+use crate::Limit;
+
 #[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
 pub enum SnarkPropagationChannelMsg {
     /// Request next snarks upto the `limit`.
@@ -26,4 +28,121 @@ pub enum SnarkPropagationChannelMsg {
     WillSend { count: u8 },
     /// Snark.
     Snark(SnarkInfo),
+    /// Multiple snarks in a single message, each counting towards the
+    /// amount promised by `WillSend`.
+    ///
+    /// - Peers running older versions can't decode it, so it is only sent
+    ///   to peers which negotiated it, see
+    ///   [`crate::P2pPeerStatusReady::snark_batching`], and when
+    ///   [`crate::P2pLimits::snarks_per_message`] is raised above 1.
+    /// - Must not be empty, nor contain more snarks than still promised.
+    Snarks(Vec<SnarkInfo>),
+}
+
+impl SnarkPropagationChannelMsg {
+    /// Messages delivering `snarks` promised by `WillSend`. They are sent
+    /// in batches of up to `snarks_per_message` if the peer negotiated
+    /// batching, one by one otherwise.
+    pub fn response(
+        snarks: &[SnarkInfo],
+        snarks_per_message: Limit<usize>,
+        snark_batching: bool,
+    ) -> impl Iterator<Item = Self> + '_ {
+        let batch_size = match snarks_per_message {
+            _ if !snark_batching => 1,
+            Limit::Some(limit) => limit.max(1),
+            Limit::Unlimited => snarks.len().max(1),
+        };
+        snarks.chunks(batch_size).map(|batch| match batch {
+            [snark] => Self::Snark(snark.clone()),
+            batch => Self::Snarks(batch.to_vec()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_p2p_messages::v2::{
+        CurrencyFeeStableV1, LedgerHash, NonZeroCurvePoint, NonZeroCurvePointUncompressedStableV1,
+    };
+
+    use crate::channels::{ChannelId, ChannelMsg};
+
+    use super::*;
+
+    pub(super) fn snark(fee: u64) -> SnarkInfo {
+        let hash = LedgerHash::zero();
+        SnarkInfo {
+            job_id: format!("{hash}_{hash}-{hash}_{hash}").parse().unwrap(),
+            fee: CurrencyFeeStableV1(fee.into()),
+            prover: NonZeroCurvePoint::from(NonZeroCurvePointUncompressedStableV1::default()),
+        }
+    }
+
+    fn encode(msg: SnarkPropagationChannelMsg) -> Vec<u8> {
+        let mut bytes = vec![];
+        ChannelMsg::SnarkPropagation(msg)
+            .encode(&mut bytes)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_snarks_msg_round_trip() {
+        let snarks = (0..3).map(snark).collect::<Vec<_>>();
+        let bytes = encode(SnarkPropagationChannelMsg::Snarks(snarks.clone()));
+
+        let decoded = ChannelMsg::decode(&mut bytes.as_slice(), ChannelId::SnarkPropagation);
+        let Ok(ChannelMsg::SnarkPropagation(SnarkPropagationChannelMsg::Snarks(decoded))) = decoded
+        else {
+            panic!("unexpected message: {decoded:?}");
+        };
+        assert_eq!(decoded.len(), snarks.len());
+        for (decoded, snark) in decoded.iter().zip(&snarks) {
+            assert_eq!(decoded.job_id, snark.job_id);
+            assert_eq!(decoded.fee, snark.fee);
+            assert_eq!(decoded.prover, snark.prover);
+        }
+        assert_eq!(
+            encode(SnarkPropagationChannelMsg::Snarks(decoded)),
+            bytes,
+            "re-encoding must give the same bytes"
+        );
+    }
+
+    #[test]
+    fn test_snarks_msg_size_limits() {
+        let id = ChannelId::SnarkPropagation;
+        let single = encode(SnarkPropagationChannelMsg::Snark(snark(u64::MAX)));
+        assert!(single.len() <= id.max_msg_size(false));
+
+        let batch = (0..u8::MAX as u64).map(|_| snark(u64::MAX)).collect();
+        let batch = encode(SnarkPropagationChannelMsg::Snarks(batch));
+        assert!(batch.len() <= id.max_msg_size(true));
+        assert!(batch.len() > id.max_msg_size(false));
+    }
+
+    #[test]
+    fn test_response_batches() {
+        let snarks = (0..5).map(snark).collect::<Vec<_>>();
+        let sizes = |snarks_per_message, snark_batching| {
+            SnarkPropagationChannelMsg::response(&snarks, snarks_per_message, snark_batching)
+                .map(|msg| match msg {
+                    SnarkPropagationChannelMsg::Snark(_) => 1,
+                    SnarkPropagationChannelMsg::Snarks(batch) => {
+                        assert!(batch.len() > 1);
+                        batch.len()
+                    }
+                    msg => panic!("unexpected message: {msg:?}"),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(sizes(Limit::Some(2), true), [2, 2, 1]);
+        assert_eq!(sizes(Limit::Unlimited, true), [5]);
+        assert_eq!(sizes(Limit::Some(1), true), [1; 5]);
+        // Peers which didn't negotiate batching only get single snarks.
+        assert_eq!(sizes(Limit::Some(2), false), [1; 5]);
+        assert_eq!(sizes(Limit::Unlimited, false), [1; 5]);
+    }
 }
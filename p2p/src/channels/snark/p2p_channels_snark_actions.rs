@@ -1,7 +1,7 @@
 use mina_core::{snark::Snark, ActionEvent};
 use serde::{Deserialize, Serialize};
 
-use crate::{channels::P2pChannelsAction, P2pState, PeerId};
+use crate::{channels::P2pChannelsAction, gossip::P2pGossipKind, P2pState, PeerId};
 
 use super::{P2pChannelsSnarkState, SnarkInfo, SnarkPropagationState};
 
@@ -33,6 +33,13 @@ pub enum P2pChannelsSnarkAction {
         peer_id: PeerId,
         snark: Box<SnarkInfo>,
     },
+    /// Batch of snarks received in a single message. Each of them is passed
+    /// on separately, the same way as with [`Self::Received`].
+    #[action_event(level = debug, fields(display(peer_id), snarks = snarks.len()))]
+    BatchReceived {
+        peer_id: PeerId,
+        snarks: Vec<SnarkInfo>,
+    },
     #[action_event(level = debug, fields(display(peer_id), limit))]
     RequestReceived {
         peer_id: PeerId,
@@ -69,6 +76,7 @@ impl P2pChannelsSnarkAction {
             | Self::RequestSend { peer_id, .. }
             | Self::PromiseReceived { peer_id, .. }
             | Self::Received { peer_id, .. }
+            | Self::BatchReceived { peer_id, .. }
             | Self::RequestReceived { peer_id, .. }
             | Self::ResponseSend { peer_id, .. } => Some(peer_id),
             Self::Libp2pReceived { peer_id, .. } => Some(peer_id),
@@ -92,16 +100,17 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsSnarkAction {
                 })
             }
             P2pChannelsSnarkAction::RequestSend { peer_id, .. } => {
-                state.get_ready_peer(peer_id).is_some_and(|p| {
-                    matches!(
-                        &p.channels.snark,
-                        P2pChannelsSnarkState::Ready {
-                            local: SnarkPropagationState::WaitingForRequest { .. }
-                                | SnarkPropagationState::Responded { .. },
-                            ..
-                        }
-                    )
-                })
+                !state.is_gossip_paused(P2pGossipKind::Snark)
+                    && state.get_ready_peer(peer_id).is_some_and(|p| {
+                        matches!(
+                            &p.channels.snark,
+                            P2pChannelsSnarkState::Ready {
+                                local: SnarkPropagationState::WaitingForRequest { .. }
+                                    | SnarkPropagationState::Responded { .. },
+                                ..
+                            }
+                        )
+                    })
             }
             P2pChannelsSnarkAction::PromiseReceived {
                 peer_id,
@@ -127,6 +136,21 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsSnarkAction {
                     )
                 })
             }
+            P2pChannelsSnarkAction::BatchReceived { peer_id, snarks } => {
+                !snarks.is_empty() && state.get_ready_peer(peer_id).is_some_and(|p| {
+                    p.snark_batching && matches!(
+                        &p.channels.snark,
+                        P2pChannelsSnarkState::Ready {
+                            local: SnarkPropagationState::Responding {
+                                promised_count,
+                                current_count,
+                                ..
+                            },
+                            ..
+                        } if snarks.len() <= promised_count.saturating_sub(*current_count) as usize
+                    )
+                })
+            }
             P2pChannelsSnarkAction::RequestReceived { peer_id, limit } => {
                 *limit > 0
                     && state.get_ready_peer(peer_id).is_some_and(|p| {
@@ -194,3 +218,108 @@ impl From<P2pChannelsSnarkAction> for crate::P2pAction {
         Self::Channels(P2pChannelsAction::Snark(action))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use redux::EnablingCondition;
+
+    use crate::{
+        channels::ChannelId, identity::SecretKey, P2pCallbacks, P2pConfig, P2pLimits,
+        P2pMeshsubConfig, P2pPeerState, P2pPeerStatus, P2pPeerStatusReady, P2pTimeouts,
+    };
+
+    use super::*;
+
+    /// State with a ready peer which promised to send `promised_count`
+    /// snarks, and already sent `current_count` of them.
+    fn state(snark_batching: bool, promised_count: u8, current_count: u8) -> (P2pState, PeerId) {
+        let config = P2pConfig {
+            libp2p_port: None,
+            listen_port: None,
+            identity_pub_key: SecretKey::deterministic(0).public_key(),
+            previous_peer_ids: Vec::new(),
+            initial_peers: Vec::new(),
+            initial_dnsaddrs: Vec::new(),
+            external_addrs: Vec::new(),
+            enabled_channels: Default::default(),
+            timeouts: P2pTimeouts::default(),
+            limits: P2pLimits::default(),
+            peer_discovery: false,
+            meshsub: P2pMeshsubConfig::default(),
+        };
+        let mut state = P2pState::new(config, P2pCallbacks::default(), &mina_core::DEVNET_CHAIN_ID);
+
+        let time = redux::Timestamp::ZERO;
+        let peer_id = SecretKey::deterministic(1).public_key().peer_id();
+        let mut ready =
+            P2pPeerStatusReady::new(false, time, &BTreeSet::from([ChannelId::SnarkPropagation]));
+        ready.snark_batching = snark_batching;
+        ready.channels.snark = P2pChannelsSnarkState::Ready {
+            time,
+            local: SnarkPropagationState::Responding {
+                time,
+                requested_limit: promised_count,
+                promised_count,
+                current_count,
+            },
+            remote: SnarkPropagationState::WaitingForRequest { time },
+            next_send_index: 0,
+        };
+        state.peers.insert(
+            peer_id,
+            P2pPeerState {
+                is_libp2p: false,
+                dial_opts: None,
+                status: P2pPeerStatus::Ready(ready),
+                identify: None,
+            },
+        );
+        (state, peer_id)
+    }
+
+    fn batch_received(peer_id: PeerId, count: u64) -> P2pChannelsSnarkAction {
+        P2pChannelsSnarkAction::BatchReceived {
+            peer_id,
+            snarks: (0..count).map(super::super::tests::snark).collect(),
+        }
+    }
+
+    #[test]
+    fn test_batch_received_within_promise() {
+        let time = redux::Timestamp::ZERO;
+        let (state, peer_id) = state(true, 5, 2);
+
+        assert!(batch_received(peer_id, 1).is_enabled(&state, time));
+        assert!(batch_received(peer_id, 3).is_enabled(&state, time));
+        assert!(!batch_received(peer_id, 4).is_enabled(&state, time));
+        assert!(!batch_received(peer_id, 0).is_enabled(&state, time));
+
+        let (state, peer_id) = state_with_promise_fulfilled();
+        assert!(!batch_received(peer_id, 1).is_enabled(&state, time));
+    }
+
+    #[test]
+    fn test_batch_received_requires_negotiated_batching() {
+        let time = redux::Timestamp::ZERO;
+        let (state, peer_id) = state(false, 5, 0);
+
+        assert!(!batch_received(peer_id, 2).is_enabled(&state, time));
+        let single = P2pChannelsSnarkAction::Received {
+            peer_id,
+            snark: Box::new(super::super::tests::snark(0)),
+        };
+        assert!(single.is_enabled(&state, time));
+    }
+
+    fn state_with_promise_fulfilled() -> (P2pState, PeerId) {
+        let (mut state, peer_id) = state(true, 2, 0);
+        let snark = &mut state.get_ready_peer_mut(&peer_id).unwrap().channels.snark;
+        let P2pChannelsSnarkState::Ready { local, .. } = snark else {
+            unreachable!()
+        };
+        assert!(local.on_received(2, redux::Timestamp::ZERO));
+        (state, peer_id)
+    }
+}
@@ -117,33 +117,49 @@ impl P2pChannelsSnarkState {
                     );
                     return Ok(());
                 };
-                let SnarkPropagationState::Responding {
-                    promised_count,
-                    current_count,
-                    ..
-                } = local
-                else {
+                if !local.on_received(1, meta.time()) {
                     bug_condition!(
                         "Invalid state for `P2pChannelsSnarkAction::Received`, state: {:?}",
                         state
                     );
                     return Ok(());
-                };
+                }
 
-                *current_count += 1;
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
 
-                if current_count >= promised_count {
-                    *local = SnarkPropagationState::Responded {
-                        time: meta.time(),
-                        count: *current_count,
-                    };
+                if let Some(callback) = &p2p_state.callbacks.on_p2p_channels_snark_received {
+                    dispatcher.push_callback(callback.clone(), (peer_id, snark));
+                }
+
+                Ok(())
+            }
+            P2pChannelsSnarkAction::BatchReceived { peer_id, snarks } => {
+                let state = state.inspect_err(|error| bug_condition!("{}", error))?;
+                let Self::Ready { local, .. } = state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsSnarkAction::BatchReceived`, state: {:?}",
+                        state
+                    );
+                    return Ok(());
+                };
+                if !local.on_received(snarks.len(), meta.time()) {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsSnarkAction::BatchReceived`, state: {:?}",
+                        state
+                    );
+                    return Ok(());
                 }
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let p2p_state: &P2pState = state.substate()?;
 
+                // Snarks are validated one by one, invalid ones don't affect
+                // the others from the same batch.
                 if let Some(callback) = &p2p_state.callbacks.on_p2p_channels_snark_received {
-                    dispatcher.push_callback(callback.clone(), (peer_id, snark));
+                    for snark in snarks {
+                        dispatcher.push_callback(callback.clone(), (peer_id, Box::new(snark)));
+                    }
                 }
 
                 Ok(())
@@ -194,18 +210,29 @@ impl P2pChannelsSnarkState {
                     count,
                 };
 
-                let dispatcher = state_context.into_dispatcher();
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+                // Batches are only sent to peers which announced they accept
+                // them, older versions can't decode them.
+                let snark_batching = p2p_state
+                    .get_ready_peer(&peer_id)
+                    .is_some_and(|peer| peer.snark_batching);
+
                 dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
                     peer_id,
                     msg_id: MsgId::first(),
                     msg: SnarkPropagationChannelMsg::WillSend { count }.into(),
                 });
 
-                for snark in snarks {
+                for msg in SnarkPropagationChannelMsg::response(
+                    &snarks,
+                    p2p_state.config.limits.snarks_per_message(),
+                    snark_batching,
+                ) {
                     dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
                         peer_id,
                         msg_id: MsgId::first(),
-                        msg: SnarkPropagationChannelMsg::Snark(snark).into(),
+                        msg: msg.into(),
                     });
                 }
                 Ok(())
@@ -42,6 +42,30 @@ pub enum SnarkPropagationState {
     },
 }
 
+impl SnarkPropagationState {
+    /// Counts `count` received snarks towards the amount promised by the
+    /// peer, and moves to [`Self::Responded`] once it is delivered. Returns
+    /// `false` if no snarks were promised.
+    pub fn on_received(&mut self, count: usize, time: redux::Timestamp) -> bool {
+        let Self::Responding {
+            promised_count,
+            current_count,
+            ..
+        } = self
+        else {
+            return false;
+        };
+        *current_count = current_count.saturating_add(count.try_into().unwrap_or(u8::MAX));
+        if current_count >= promised_count {
+            *self = Self::Responded {
+                time,
+                count: *current_count,
+            };
+        }
+        true
+    }
+}
+
 impl P2pChannelsSnarkState {
     pub fn is_ready(&self) -> bool {
         matches!(self, Self::Ready { .. })
@@ -74,3 +98,35 @@ impl P2pChannelsSnarkState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_on_received_counts_towards_promise() {
+        let time = redux::Timestamp::ZERO;
+        let mut state = SnarkPropagationState::Responding {
+            time,
+            requested_limit: 8,
+            promised_count: 5,
+            current_count: 0,
+        };
+
+        assert!(state.on_received(1, time));
+        assert!(state.on_received(3, time));
+        assert!(matches!(
+            state,
+            SnarkPropagationState::Responding {
+                current_count: 4,
+                ..
+            }
+        ));
+        assert!(state.on_received(1, time));
+        assert!(matches!(
+            state,
+            SnarkPropagationState::Responded { count: 5, .. }
+        ));
+        assert!(!state.on_received(1, time), "nothing is promised anymore");
+    }
+}
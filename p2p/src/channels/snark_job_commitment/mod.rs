@@ -5,6 +5,7 @@ mod p2p_channels_snark_job_commitment_actions;
 pub use p2p_channels_snark_job_commitment_actions::*;
 
 mod p2p_channels_snark_job_commitment_reducer;
+pub(crate) use p2p_channels_snark_job_commitment_reducer::LIMIT;
 
 use binprot_derive::{BinProtRead, BinProtWrite};
 use mina_core::snark::SnarkJobCommitment;
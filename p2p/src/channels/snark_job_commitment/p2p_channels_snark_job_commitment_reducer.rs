@@ -11,7 +11,7 @@ use super::{
     SnarkJobCommitmentPropagationChannelMsg, SnarkJobCommitmentPropagationState,
 };
 
-const LIMIT: u8 = 16;
+pub(crate) const LIMIT: u8 = 16;
 
 impl P2pChannelsSnarkJobCommitmentState {
     /// Substate is accessed
@@ -124,26 +124,12 @@ impl P2pChannelsSnarkJobCommitmentState {
                     );
                     return Ok(());
                 };
-                let SnarkJobCommitmentPropagationState::Responding {
-                    promised_count,
-                    current_count,
-                    ..
-                } = local
-                else {
+                if !local.on_received(1, meta.time()) {
                     bug_condition!(
                         "Invalid state for `P2pChannelsSnarkJobCommitmentAction::Received`, state: {:?}",
                         snark_job_state
                     );
                     return Ok(());
-                };
-
-                *current_count += 1;
-
-                if current_count >= promised_count {
-                    *local = SnarkJobCommitmentPropagationState::Responded {
-                        time: meta.time(),
-                        count: *current_count,
-                    };
                 }
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
@@ -42,6 +42,30 @@ pub enum SnarkJobCommitmentPropagationState {
     },
 }
 
+impl SnarkJobCommitmentPropagationState {
+    /// Counts `count` received commitments towards the amount promised by the
+    /// peer, and moves to [`Self::Responded`] once it is delivered. Returns
+    /// `false` if no commitments were promised.
+    pub fn on_received(&mut self, count: usize, time: redux::Timestamp) -> bool {
+        let Self::Responding {
+            promised_count,
+            current_count,
+            ..
+        } = self
+        else {
+            return false;
+        };
+        *current_count = current_count.saturating_add(count.try_into().unwrap_or(u8::MAX));
+        if current_count >= promised_count {
+            *self = Self::Responded {
+                time,
+                count: *current_count,
+            };
+        }
+        true
+    }
+}
+
 impl P2pChannelsSnarkJobCommitmentState {
     pub fn is_ready(&self) -> bool {
         matches!(self, Self::Ready { .. })
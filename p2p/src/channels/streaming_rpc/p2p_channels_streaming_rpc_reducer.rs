@@ -3,10 +3,12 @@ use redux::ActionWithMeta;
 
 use crate::{
     channels::{ChannelId, ChannelMsg, MsgId, P2pChannelsEffectfulAction},
+    disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
     P2pState,
 };
 
 use super::{
+    block::{BlockReceiveProgress, BlockSendData, BlockSendProgress},
     staged_ledger_parts::{StagedLedgerPartsReceiveProgress, StagedLedgerPartsSendProgress},
     P2pChannelsStreamingRpcAction, P2pChannelsStreamingRpcState, P2pStreamingRpcLocalState,
     P2pStreamingRpcRemoteState, P2pStreamingRpcRequest, P2pStreamingRpcResponseFull,
@@ -95,6 +97,12 @@ impl P2pChannelsStreamingRpcState {
                                 time: meta.time(),
                             })
                         }
+                        P2pStreamingRpcRequest::Block(hash) => {
+                            Into::into(BlockReceiveProgress::HeaderPending {
+                                time: meta.time(),
+                                hash: hash.clone(),
+                            })
+                        }
                     },
                 };
 
@@ -157,6 +165,7 @@ impl P2pChannelsStreamingRpcState {
                     );
                     return Ok(());
                 };
+                let kind = response.kind();
                 if !progress.update(meta.time(), response) {
                     bug_condition!("progress response mismatch! {progress:?}");
                 }
@@ -167,6 +176,18 @@ impl P2pChannelsStreamingRpcState {
                     return Ok(());
                 };
 
+                if let Some(error) = peer.channels.streaming_rpc.local_error() {
+                    // Abort the transfer as soon as the peer sends invalid
+                    // data, instead of receiving the rest of it.
+                    dispatcher.push(P2pDisconnectionAction::Init {
+                        peer_id,
+                        reason: P2pDisconnectionReason::StreamingRpcResponseInvalid(
+                            kind,
+                            error.to_owned(),
+                        ),
+                    });
+                    return Ok(());
+                }
                 if let Some(response) = peer.channels.streaming_rpc.local_done_response() {
                     dispatcher.push(P2pChannelsStreamingRpcAction::ResponseReceived {
                         peer_id,
@@ -223,14 +244,31 @@ impl P2pChannelsStreamingRpcState {
                     );
                     return Ok(());
                 };
+                let progress = match &*request {
+                    // async ledger request will be triggered by `LedgerReadAction::FindTodos`.
+                    P2pStreamingRpcRequest::StagedLedgerParts(_) => {
+                        StagedLedgerPartsSendProgress::LedgerGetIdle { time: meta.time() }.into()
+                    }
+                    P2pStreamingRpcRequest::Block(_) => {
+                        BlockSendProgress::BlockGetPending { time: meta.time() }.into()
+                    }
+                };
                 *remote = P2pStreamingRpcRemoteState::Requested {
                     time: meta.time(),
                     id,
-                    request,
-                    progress: StagedLedgerPartsSendProgress::LedgerGetIdle { time: meta.time() }
-                        .into(),
+                    request: request.clone(),
+                    progress,
                 };
-                // async ledger request will be triggered by `LedgerReadAction::FindTodos`.
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+
+                if let Some(callback) = &p2p_state
+                    .callbacks
+                    .on_p2p_channels_streaming_rpc_request_received
+                {
+                    dispatcher.push_callback(callback.clone(), (peer_id, id, request));
+                }
                 Ok(())
             }
             P2pChannelsStreamingRpcAction::ResponsePending { .. } => {
@@ -251,6 +289,9 @@ impl P2pChannelsStreamingRpcState {
                             StagedLedgerPartsSendProgress::LedgerGetPending { time: meta.time() }
                                 .into();
                     }
+                    P2pStreamingRpcRequest::Block(_) => {
+                        *progress = BlockSendProgress::BlockGetPending { time: meta.time() }.into();
+                    }
                 }
                 Ok(())
             }
@@ -269,6 +310,7 @@ impl P2pChannelsStreamingRpcState {
                     );
                     return Ok(());
                 };
+                let mut has_response = response.is_some();
                 match (&**request, &response) {
                     (_, Some(P2pStreamingRpcResponseFull::StagedLedgerParts(data))) => {
                         *progress = StagedLedgerPartsSendProgress::LedgerGetSuccess {
@@ -280,11 +322,30 @@ impl P2pChannelsStreamingRpcState {
                     (P2pStreamingRpcRequest::StagedLedgerParts(_), None) => {
                         *progress =
                             StagedLedgerPartsSendProgress::Success { time: meta.time() }.into();
-                    } // _ => todo!("unexpected response send call: {response:?}"),
+                    }
+                    (_, Some(P2pStreamingRpcResponseFull::Block(block))) => {
+                        match BlockSendData::new(block.clone()) {
+                            Ok(data) => {
+                                *progress = BlockSendProgress::BlockGetSuccess {
+                                    time: meta.time(),
+                                    data,
+                                }
+                                .into();
+                            }
+                            Err(error) => {
+                                bug_condition!("failed to split block body into chunks: {error}");
+                                *progress = BlockSendProgress::Success { time: meta.time() }.into();
+                                has_response = false;
+                            }
+                        }
+                    }
+                    (P2pStreamingRpcRequest::Block(_), None) => {
+                        *progress = BlockSendProgress::Success { time: meta.time() }.into();
+                    }
                 }
 
                 let dispatcher = state_context.into_dispatcher();
-                if response.is_none() {
+                if !has_response {
                     let msg = StreamingRpcChannelMsg::Response(id, None).into();
                     dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
                         peer_id,
@@ -388,6 +449,27 @@ impl P2pChannelsStreamingRpcState {
                             }
                         }
                     }
+                    P2pStreamingRpcSendProgress::Block(progress) => {
+                        let (data, chunk_index) = match progress {
+                            BlockSendProgress::BlockGetSuccess { data, .. } => (data.clone(), 0),
+                            BlockSendProgress::BodyChunksSending {
+                                data, chunk_index, ..
+                            } => (data.clone(), *chunk_index + 1),
+                            progress => {
+                                bug_condition!("unexpected state during `P2pStreamingRpcSendProgress::Block`: {progress:?}");
+                                return Ok(());
+                            }
+                        };
+                        *progress = if chunk_index >= data.body_chunks.len() {
+                            BlockSendProgress::Success { time: meta.time() }
+                        } else {
+                            BlockSendProgress::BodyChunksSending {
+                                time: meta.time(),
+                                data,
+                                chunk_index,
+                            }
+                        };
+                    }
                 }
 
                 let dispatcher = state_context.into_dispatcher();
@@ -3,9 +3,9 @@ use serde::{Deserialize, Serialize};
 use crate::P2pTimeouts;
 
 use super::{
-    staged_ledger_parts::StagedLedgerPartsReceiveProgress, P2pStreamingRpcId, P2pStreamingRpcKind,
-    P2pStreamingRpcReceiveProgress, P2pStreamingRpcRequest, P2pStreamingRpcResponse,
-    P2pStreamingRpcResponseFull, P2pStreamingRpcSendProgress,
+    block::BlockReceiveProgress, staged_ledger_parts::StagedLedgerPartsReceiveProgress,
+    P2pStreamingRpcId, P2pStreamingRpcKind, P2pStreamingRpcReceiveProgress, P2pStreamingRpcRequest,
+    P2pStreamingRpcResponse, P2pStreamingRpcResponseFull, P2pStreamingRpcSendProgress,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -158,10 +158,26 @@ impl P2pChannelsStreamingRpcState {
                     },
                 ..
             } => Some(data.clone().into()),
+            Self::Ready {
+                local:
+                    P2pStreamingRpcLocalState::Requested {
+                        progress:
+                            P2pStreamingRpcReceiveProgress::Block(BlockReceiveProgress::Success {
+                                data,
+                                ..
+                            }),
+                        ..
+                    },
+                ..
+            } => Some(data.clone().into()),
             _ => None,
         }
     }
 
+    pub(super) fn local_error(&self) -> Option<&str> {
+        self.pending_local_rpc_progress()?.error()
+    }
+
     pub fn local_responded_request(&self) -> Option<(P2pStreamingRpcId, &P2pStreamingRpcRequest)> {
         match self {
             Self::Ready {
@@ -0,0 +1,331 @@
+use std::sync::Arc;
+
+use binprot_derive::{BinProtRead, BinProtWrite};
+use derive_more::From;
+use mina_core::block::{
+    bitswap_block::{self, BitswapBlockError, BodyBlocksReader},
+    ArcBlock,
+};
+use mina_p2p_messages::{string::ByteString, v2};
+use serde::{Deserialize, Serialize};
+
+pub type BlockResponseFull = ArcBlock;
+
+/// Max size of the body of a block received from a peer. The transfer is
+/// aborted as soon as more is received.
+pub const BLOCK_BODY_MAX_SIZE: usize = 64 * 1024 * 1024;
+
+/// The header is sent first, then the body as its bitswap blocks, so that
+/// each chunk of the body is checked against the body reference of the
+/// header as soon as it's received.
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, From, Debug, Clone)]
+pub enum BlockResponse {
+    Header(Box<v2::MinaBlockHeaderStableV2>),
+    BodyChunk(ByteString),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockSendData {
+    pub block: BlockResponseFull,
+    pub body_chunks: Arc<Vec<ByteString>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum BlockSendProgress {
+    BlockGetPending {
+        time: redux::Timestamp,
+    },
+    BlockGetSuccess {
+        time: redux::Timestamp,
+        data: BlockSendData,
+    },
+    BodyChunksSending {
+        time: redux::Timestamp,
+        data: BlockSendData,
+        chunk_index: usize,
+    },
+    Success {
+        time: redux::Timestamp,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum BlockReceiveProgress {
+    HeaderPending {
+        time: redux::Timestamp,
+        hash: v2::StateHash,
+    },
+    BodyChunksPending {
+        time: redux::Timestamp,
+        header: Box<v2::MinaBlockHeaderStableV2>,
+        body: BodyBlocksReader,
+        is_next_chunk_requested: bool,
+    },
+    Success {
+        time: redux::Timestamp,
+        data: BlockResponseFull,
+    },
+    /// The peer sent a header or a body chunk which doesn't match the
+    /// requested block.
+    Invalid {
+        time: redux::Timestamp,
+        error: String,
+    },
+}
+
+impl BlockSendData {
+    pub fn new(block: BlockResponseFull) -> Result<Self, BitswapBlockError> {
+        let body_chunks = bitswap_block::body_blocks(&block.body.staged_ledger_diff)?
+            .into_iter()
+            .map(ByteString::from)
+            .collect();
+        Ok(Self {
+            block,
+            body_chunks: Arc::new(body_chunks),
+        })
+    }
+}
+
+impl BlockSendProgress {
+    pub fn next_msg(&self) -> Option<BlockResponse> {
+        match self {
+            Self::BlockGetSuccess { data, .. } => Some(Box::new(data.block.header.clone()).into()),
+            Self::BodyChunksSending {
+                data, chunk_index, ..
+            } => data.body_chunks.get(*chunk_index).cloned().map(Into::into),
+            _ => None,
+        }
+    }
+}
+
+impl Default for BlockSendProgress {
+    fn default() -> Self {
+        Self::BlockGetPending {
+            time: redux::Timestamp::ZERO,
+        }
+    }
+}
+
+impl BlockReceiveProgress {
+    pub fn last_updated(&self) -> redux::Timestamp {
+        match self {
+            Self::HeaderPending { time, .. }
+            | Self::BodyChunksPending { time, .. }
+            | Self::Success { time, .. }
+            | Self::Invalid { time, .. } => *time,
+        }
+    }
+
+    pub fn update(&mut self, time: redux::Timestamp, resp: BlockResponse) -> bool {
+        match (std::mem::take(self), resp) {
+            (Self::HeaderPending { hash, .. }, BlockResponse::Header(header)) => {
+                *self = match body_reader(&hash, &header) {
+                    Ok(body) => Self::BodyChunksPending {
+                        time,
+                        header,
+                        body,
+                        is_next_chunk_requested: false,
+                    },
+                    Err(error) => Self::Invalid { time, error },
+                };
+                true
+            }
+            (
+                Self::BodyChunksPending {
+                    header, mut body, ..
+                },
+                BlockResponse::BodyChunk(chunk),
+            ) => {
+                *self = match body.push(&chunk) {
+                    Err(error) => Self::Invalid {
+                        time,
+                        error: error.to_string(),
+                    },
+                    Ok(()) if !body.is_done() => Self::BodyChunksPending {
+                        time,
+                        header,
+                        body,
+                        is_next_chunk_requested: false,
+                    },
+                    Ok(()) => match body.finish() {
+                        Err(error) => Self::Invalid {
+                            time,
+                            error: error.to_string(),
+                        },
+                        Ok(staged_ledger_diff) => Self::Success {
+                            time,
+                            data: Arc::new(v2::MinaBlockBlockStableV2 {
+                                header: *header,
+                                body: v2::StagedLedgerDiffBodyStableV1 { staged_ledger_diff },
+                            }),
+                        },
+                    },
+                };
+                true
+            }
+            (old_state, _) => {
+                *self = old_state;
+                false
+            }
+        }
+    }
+
+    pub fn is_part_pending(&self) -> bool {
+        match self {
+            Self::HeaderPending { .. } => true,
+            Self::BodyChunksPending {
+                is_next_chunk_requested,
+                ..
+            } => *is_next_chunk_requested,
+            Self::Success { .. } | Self::Invalid { .. } => false,
+        }
+    }
+
+    pub fn set_next_pending(&mut self, time: redux::Timestamp) -> bool {
+        match self {
+            Self::BodyChunksPending {
+                time: t,
+                is_next_chunk_requested,
+                ..
+            } => {
+                *t = time;
+                *is_next_chunk_requested = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            Self::Invalid { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Checks that the header is the one of the requested block, and returns
+/// the reader of the body it references.
+fn body_reader(
+    hash: &v2::StateHash,
+    header: &v2::MinaBlockHeaderStableV2,
+) -> Result<BodyBlocksReader, String> {
+    let header_hash = header
+        .try_hash()
+        .map_err(|_| "header hash can't be computed".to_owned())?;
+    if &header_hash != hash {
+        return Err(format!(
+            "header hash mismatch, expected: {hash}, got: {header_hash}"
+        ));
+    }
+    let body_reference = &header.protocol_state.body.blockchain_state.body_reference;
+    BodyBlocksReader::new(body_reference, BLOCK_BODY_MAX_SIZE).map_err(|error| error.to_string())
+}
+
+impl Default for BlockReceiveProgress {
+    fn default() -> Self {
+        Self::HeaderPending {
+            time: redux::Timestamp::ZERO,
+            hash: v2::StateHash::zero(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channels::streaming_rpc::{
+        P2pChannelsStreamingRpcState, P2pStreamingRpcLocalState, P2pStreamingRpcReceiveProgress,
+        P2pStreamingRpcRemoteState, P2pStreamingRpcRequest,
+    };
+
+    use super::*;
+
+    fn block() -> BlockResponseFull {
+        let json: serde_json::Value = serde_json::from_str(include_str!(
+            "../../../../tests/files/rpc/block_response.json"
+        ))
+        .unwrap();
+        Arc::new(serde_json::from_value(json["Block"].clone()).unwrap())
+    }
+
+    /// Streaming RPC channel of a peer which was asked for the block `hash`
+    /// and sent `responses` so far.
+    fn channel(hash: v2::StateHash, responses: Vec<BlockResponse>) -> P2pChannelsStreamingRpcState {
+        let time = redux::Timestamp::ZERO;
+        let mut progress = BlockReceiveProgress::HeaderPending {
+            time,
+            hash: hash.clone(),
+        };
+        for response in responses {
+            assert!(progress.update(time, response), "unexpected response");
+        }
+        P2pChannelsStreamingRpcState::Ready {
+            time,
+            local: P2pStreamingRpcLocalState::Requested {
+                time,
+                id: 0,
+                request: Box::new(P2pStreamingRpcRequest::Block(hash)),
+                progress: P2pStreamingRpcReceiveProgress::Block(progress),
+            },
+            remote: P2pStreamingRpcRemoteState::WaitingForRequest { time },
+            remote_last_responded: time,
+        }
+    }
+
+    #[test]
+    fn test_block_header_mismatch_aborts() {
+        let block = block();
+        let other_hash = block.header.protocol_state.previous_state_hash.clone();
+
+        let channel = channel(other_hash, vec![Box::new(block.header.clone()).into()]);
+        let error = channel.local_error().expect("transfer must be aborted");
+        assert!(error.contains("header hash mismatch"), "{error}");
+        assert!(channel.local_done_response().is_none());
+    }
+
+    #[test]
+    fn test_block_body_chunk_mismatch_aborts() {
+        let block = block();
+        let hash = block.header.try_hash().unwrap();
+        let data = BlockSendData::new(block.clone()).unwrap();
+        let header = BlockResponse::Header(Box::new(block.header.clone()));
+
+        let channel = self::channel(hash.clone(), vec![header.clone()]);
+        assert_eq!(channel.local_error(), None);
+
+        let mut chunk = data.body_chunks[0].to_vec();
+        *chunk.last_mut().unwrap() ^= 1;
+        let channel = self::channel(
+            hash.clone(),
+            vec![header.clone(), BlockResponse::BodyChunk(chunk.into())],
+        );
+        assert!(channel.local_error().is_some(), "transfer must be aborted");
+        assert!(channel.local_done_response().is_none());
+
+        // Nothing is accepted once the transfer is aborted.
+        let mut progress = BlockReceiveProgress::Invalid {
+            time: redux::Timestamp::ZERO,
+            error: String::new(),
+        };
+        assert!(!progress.update(redux::Timestamp::ZERO, header));
+    }
+
+    #[test]
+    fn test_block_chunks_received() {
+        let block = block();
+        let hash = block.header.try_hash().unwrap();
+        let data = BlockSendData::new(block.clone()).unwrap();
+        let responses = std::iter::once(Box::new(block.header.clone()).into())
+            .chain(
+                data.body_chunks
+                    .iter()
+                    .cloned()
+                    .map(BlockResponse::BodyChunk),
+            )
+            .collect();
+
+        let channel = channel(hash, responses);
+        assert_eq!(channel.local_error(), None);
+        assert!(channel.local_done_response().is_some());
+    }
+}
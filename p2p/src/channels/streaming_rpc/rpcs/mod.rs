@@ -1,3 +1,6 @@
+pub mod block;
+use block::{BlockReceiveProgress, BlockResponse, BlockResponseFull, BlockSendProgress};
+
 pub mod staged_ledger_parts;
 use staged_ledger_parts::{
     StagedLedgerPartsReceiveProgress, StagedLedgerPartsResponse, StagedLedgerPartsResponseFull,
@@ -16,38 +19,46 @@ use crate::P2pTimeouts;
 #[derive(Serialize, Deserialize, Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
 pub enum P2pStreamingRpcKind {
     StagedLedgerParts,
+    Block,
 }
 
 #[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub enum P2pStreamingRpcRequest {
     StagedLedgerParts(v2::StateHash),
+    Block(v2::StateHash),
 }
 
 #[derive(Serialize, Deserialize, From, Debug, Clone)]
 pub enum P2pStreamingRpcResponseFull {
     StagedLedgerParts(StagedLedgerPartsResponseFull),
+    Block(BlockResponseFull),
 }
 
 #[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, From, Debug, Clone)]
 pub enum P2pStreamingRpcResponse {
     StagedLedgerParts(StagedLedgerPartsResponse),
+    Block(BlockResponse),
 }
 
 #[derive(Serialize, Deserialize, From, Debug, Clone)]
 pub enum P2pStreamingRpcSendProgress {
     StagedLedgerParts(StagedLedgerPartsSendProgress),
+    Block(BlockSendProgress),
 }
 
 #[derive(Serialize, Deserialize, From, Debug, Clone)]
 pub enum P2pStreamingRpcReceiveProgress {
     StagedLedgerParts(StagedLedgerPartsReceiveProgress),
+    Block(BlockReceiveProgress),
 }
 
 impl P2pStreamingRpcKind {
-    pub fn timeout(self, _config: &P2pTimeouts) -> Option<Duration> {
+    pub fn timeout(self, config: &P2pTimeouts) -> Option<Duration> {
         match self {
             // TODO(binier): use config
             Self::StagedLedgerParts => Some(Duration::from_secs(30)),
+            // Timeout of each part, so of each body chunk.
+            Self::Block => config.block,
         }
     }
 }
@@ -56,6 +67,7 @@ impl P2pStreamingRpcRequest {
     pub fn kind(&self) -> P2pStreamingRpcKind {
         match self {
             Self::StagedLedgerParts(_) => P2pStreamingRpcKind::StagedLedgerParts,
+            Self::Block(_) => P2pStreamingRpcKind::Block,
         }
     }
 }
@@ -70,7 +82,9 @@ impl std::fmt::Display for P2pStreamingRpcRequest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self.kind())?;
         match self {
-            Self::StagedLedgerParts(block_hash) => write!(f, ", {block_hash}"),
+            Self::StagedLedgerParts(block_hash) | Self::Block(block_hash) => {
+                write!(f, ", {block_hash}")
+            }
         }
     }
 }
@@ -79,6 +93,7 @@ impl P2pStreamingRpcResponseFull {
     pub fn kind(&self) -> P2pStreamingRpcKind {
         match self {
             Self::StagedLedgerParts(_) => P2pStreamingRpcKind::StagedLedgerParts,
+            Self::Block(_) => P2pStreamingRpcKind::Block,
         }
     }
 }
@@ -87,6 +102,7 @@ impl P2pStreamingRpcResponse {
     pub fn kind(&self) -> P2pStreamingRpcKind {
         match self {
             Self::StagedLedgerParts(_) => P2pStreamingRpcKind::StagedLedgerParts,
+            Self::Block(_) => P2pStreamingRpcKind::Block,
         }
     }
 }
@@ -95,6 +111,7 @@ impl P2pStreamingRpcSendProgress {
     pub fn kind(&self) -> P2pStreamingRpcKind {
         match self {
             Self::StagedLedgerParts(_) => P2pStreamingRpcKind::StagedLedgerParts,
+            Self::Block(_) => P2pStreamingRpcKind::Block,
         }
     }
 
@@ -103,6 +120,8 @@ impl P2pStreamingRpcSendProgress {
             Self::StagedLedgerParts(v) => {
                 matches!(v, StagedLedgerPartsSendProgress::LedgerGetIdle { .. })
             }
+            // The block is looked up as soon as it's requested.
+            Self::Block(_) => false,
         }
     }
 
@@ -111,12 +130,14 @@ impl P2pStreamingRpcSendProgress {
             Self::StagedLedgerParts(v) => {
                 matches!(v, StagedLedgerPartsSendProgress::LedgerGetPending { .. })
             }
+            Self::Block(v) => matches!(v, BlockSendProgress::BlockGetPending { .. }),
         }
     }
 
     pub fn next_msg(&self) -> Option<P2pStreamingRpcResponse> {
         match self {
             Self::StagedLedgerParts(v) => v.next_msg().map(Into::into),
+            Self::Block(v) => v.next_msg().map(Into::into),
         }
     }
 
@@ -125,6 +146,7 @@ impl P2pStreamingRpcSendProgress {
             Self::StagedLedgerParts(s) => {
                 matches!(s, StagedLedgerPartsSendProgress::Success { .. })
             }
+            Self::Block(s) => matches!(s, BlockSendProgress::Success { .. }),
         }
     }
 }
@@ -133,6 +155,7 @@ impl P2pStreamingRpcReceiveProgress {
     pub fn kind(&self) -> P2pStreamingRpcKind {
         match self {
             Self::StagedLedgerParts(_) => P2pStreamingRpcKind::StagedLedgerParts,
+            Self::Block(_) => P2pStreamingRpcKind::Block,
         }
     }
 
@@ -141,12 +164,14 @@ impl P2pStreamingRpcReceiveProgress {
             Self::StagedLedgerParts(s) => {
                 matches!(s, StagedLedgerPartsReceiveProgress::Success { .. })
             }
+            Self::Block(s) => matches!(s, BlockReceiveProgress::Success { .. }),
         }
     }
 
     pub fn last_updated(&self) -> redux::Timestamp {
         match self {
             Self::StagedLedgerParts(s) => s.last_updated(),
+            Self::Block(s) => s.last_updated(),
         }
     }
 
@@ -156,19 +181,33 @@ impl P2pStreamingRpcReceiveProgress {
                 Self::StagedLedgerParts(progress),
                 P2pStreamingRpcResponse::StagedLedgerParts(resp),
             ) => progress.update(time, resp),
-            // _ => false,
+            (Self::Block(progress), P2pStreamingRpcResponse::Block(resp)) => {
+                progress.update(time, resp)
+            }
+            _ => false,
         }
     }
 
     pub fn is_part_pending(&self) -> bool {
         match self {
             Self::StagedLedgerParts(progress) => progress.is_part_pending(),
+            Self::Block(progress) => progress.is_part_pending(),
         }
     }
 
     pub fn set_next_pending(&mut self, time: redux::Timestamp) -> bool {
         match self {
             Self::StagedLedgerParts(progress) => progress.set_next_pending(time),
+            Self::Block(progress) => progress.set_next_pending(time),
+        }
+    }
+
+    /// Error if the peer sent a part which doesn't match the request, in
+    /// which case the transfer can't go on.
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            Self::StagedLedgerParts(_) => None,
+            Self::Block(progress) => progress.error(),
         }
     }
 }
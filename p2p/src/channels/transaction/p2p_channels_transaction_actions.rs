@@ -1,7 +1,7 @@
 use mina_core::{p2p::P2pNetworkPubsubMessageCacheId, transaction::Transaction, ActionEvent};
 use serde::{Deserialize, Serialize};
 
-use crate::{channels::P2pChannelsAction, P2pState, PeerId};
+use crate::{channels::P2pChannelsAction, gossip::P2pGossipKind, P2pState, PeerId};
 
 use super::{P2pChannelsTransactionState, TransactionInfo, TransactionPropagationState};
 
@@ -101,16 +101,17 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionAction {
                 })
             }
             P2pChannelsTransactionAction::RequestSend { peer_id, .. } => {
-                state.get_ready_peer(peer_id).is_some_and(|p| {
-                    matches!(
-                        &p.channels.transaction,
-                        P2pChannelsTransactionState::Ready {
-                            local: TransactionPropagationState::WaitingForRequest { .. }
-                                | TransactionPropagationState::Responded { .. },
-                            ..
-                        }
-                    )
-                })
+                !state.is_gossip_paused(P2pGossipKind::Transaction)
+                    && state.get_ready_peer(peer_id).is_some_and(|p| {
+                        matches!(
+                            &p.channels.transaction,
+                            P2pChannelsTransactionState::Ready {
+                                local: TransactionPropagationState::WaitingForRequest { .. }
+                                    | TransactionPropagationState::Responded { .. },
+                                ..
+                            }
+                        )
+                    })
             }
             P2pChannelsTransactionAction::PromiseReceived {
                 peer_id,
@@ -121,22 +121,8 @@ impl P2pChannelsTransactionState {
                     );
                     return Ok(());
                 };
-                let TransactionPropagationState::Responding {
-                    promised_count,
-                    current_count,
-                    ..
-                } = local
-                else {
+                if !local.on_received(1, meta.time()) {
                     return Ok(());
-                };
-
-                *current_count += 1;
-
-                if current_count >= promised_count {
-                    *local = TransactionPropagationState::Responded {
-                        time: meta.time(),
-                        count: *current_count,
-                    };
                 }
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
@@ -42,6 +42,30 @@ pub enum TransactionPropagationState {
     },
 }
 
+impl TransactionPropagationState {
+    /// Counts `count` received transactions towards the amount promised by the
+    /// peer, and moves to [`Self::Responded`] once it is delivered. Returns
+    /// `false` if no transactions were promised.
+    pub fn on_received(&mut self, count: usize, time: redux::Timestamp) -> bool {
+        let Self::Responding {
+            promised_count,
+            current_count,
+            ..
+        } = self
+        else {
+            return false;
+        };
+        *current_count = current_count.saturating_add(count.try_into().unwrap_or(u8::MAX));
+        if current_count >= promised_count {
+            *self = Self::Responded {
+                time,
+                count: *current_count,
+            };
+        }
+        true
+    }
+}
+
 impl P2pChannelsTransactionState {
     pub fn is_ready(&self) -> bool {
         matches!(self, Self::Ready { .. })
@@ -74,3 +98,29 @@ impl P2pChannelsTransactionState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_on_received_counts_shed_transactions() {
+        let time = redux::Timestamp::ZERO;
+        let mut state = TransactionPropagationState::Responding {
+            time,
+            requested_limit: 8,
+            promised_count: 3,
+            current_count: 0,
+        };
+
+        // A shed transaction counts towards the promise the same way as a
+        // received one, so the next ones can be requested.
+        assert!(state.on_received(1, time));
+        assert!(state.on_received(2, time));
+        assert!(matches!(
+            state,
+            TransactionPropagationState::Responded { count: 3, .. }
+        ));
+        assert!(!state.on_received(1, time), "nothing is promised anymore");
+    }
+}
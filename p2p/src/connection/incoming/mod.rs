@@ -43,14 +43,18 @@ impl P2pState {
 
         let my_peer_id = self.my_id();
 
-        if offer.target_peer_id != my_peer_id {
+        if !self.is_my_id(&offer.target_peer_id) {
             return Err(RejectionReason::TargetPeerIdNotMe);
         }
 
-        if peer_id == my_peer_id {
+        if self.is_my_id(&peer_id) {
             return Err(RejectionReason::ConnectingToSelf);
         }
 
+        if self.is_banned(&peer_id) {
+            return Err(RejectionReason::Banned);
+        }
+
         if self.is_peer_connected_or_connecting(&peer_id) {
             // Both nodes trying to connect to each other at the same time.
             // Choose connection arbitrarily based on peer id.
@@ -68,10 +72,14 @@ impl P2pState {
     }
 
     pub fn libp2p_incoming_accept(&self, peer_id: PeerId) -> Result<(), RejectionReason> {
-        if peer_id == self.my_id() {
+        if self.is_my_id(&peer_id) {
             return Err(RejectionReason::ConnectingToSelf);
         }
 
+        if self.is_banned(&peer_id) {
+            return Err(RejectionReason::Banned);
+        }
+
         if self.already_has_max_ready_peers() {
             return Err(RejectionReason::PeerCapacityFull);
         }
@@ -79,3 +87,104 @@ impl P2pState {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        disconnection::P2pDisconnectionReason, identity::SecretKey, webrtc::Host, P2pCallbacks,
+        P2pConfig, P2pLimits, P2pMeshsubConfig, P2pTimeouts,
+    };
+
+    use super::*;
+
+    fn state(sec_key: &SecretKey, previous: &SecretKey) -> P2pState {
+        let config = P2pConfig {
+            libp2p_port: None,
+            listen_port: None,
+            identity_pub_key: sec_key.public_key(),
+            previous_peer_ids: vec![previous.public_key().peer_id()],
+            initial_peers: Vec::new(),
+            initial_dnsaddrs: Vec::new(),
+            external_addrs: Vec::new(),
+            enabled_channels: Default::default(),
+            timeouts: P2pTimeouts::default(),
+            limits: P2pLimits::default(),
+            peer_discovery: false,
+            meshsub: P2pMeshsubConfig::default(),
+        };
+        P2pState::new(config, P2pCallbacks::default(), &mina_core::DEVNET_CHAIN_ID)
+    }
+
+    fn offer(from: &SecretKey, target_peer_id: PeerId) -> webrtc::Offer {
+        webrtc::Offer {
+            sdp: String::new(),
+            chain_id: mina_core::DEVNET_CHAIN_ID,
+            identity_pub_key: from.public_key(),
+            target_peer_id,
+            host: Host::Ipv4([127, 0, 0, 1].into()),
+            listen_port: None,
+            snark_batching: true,
+            block_streaming: true,
+        }
+    }
+
+    #[test]
+    fn test_incoming_accept_previous_peer_id() {
+        let sec_key = SecretKey::deterministic(0);
+        let previous = SecretKey::deterministic(1);
+        let state = state(&sec_key, &previous);
+        let other = SecretKey::deterministic(2);
+        let other_id = other.public_key().peer_id();
+
+        let to_current = offer(&other, sec_key.public_key().peer_id());
+        assert_eq!(state.incoming_accept(other_id, &to_current), Ok(()));
+        let to_previous = offer(&other, previous.public_key().peer_id());
+        assert_eq!(state.incoming_accept(other_id, &to_previous), Ok(()));
+
+        let unknown = SecretKey::deterministic(3).public_key().peer_id();
+        assert_eq!(
+            state.incoming_accept(other_id, &offer(&other, unknown)),
+            Err(RejectionReason::TargetPeerIdNotMe)
+        );
+    }
+
+    #[test]
+    fn test_incoming_accept_rejects_own_previous_key() {
+        let sec_key = SecretKey::deterministic(0);
+        let previous = SecretKey::deterministic(1);
+        let state = state(&sec_key, &previous);
+
+        let from_previous = offer(&previous, sec_key.public_key().peer_id());
+        assert_eq!(
+            state.incoming_accept(previous.public_key().peer_id(), &from_previous),
+            Err(RejectionReason::ConnectingToSelf)
+        );
+    }
+
+    #[test]
+    fn test_incoming_accept_rejects_banned_peer() {
+        let sec_key = SecretKey::deterministic(0);
+        let previous = SecretKey::deterministic(1);
+        let mut state = state(&sec_key, &previous);
+        let other = SecretKey::deterministic(2);
+        let other_id = other.public_key().peer_id();
+        let to_me = offer(&other, sec_key.public_key().peer_id());
+        state.config.timeouts.misbehavior_ban = Some(std::time::Duration::from_secs(60));
+
+        let banned = state.bans.on_disconnect(
+            other_id,
+            &P2pDisconnectionReason::InvalidMessage,
+            &state.config.timeouts,
+            redux::Timestamp::ZERO,
+        );
+        assert!(banned);
+        assert_eq!(
+            state.incoming_accept(other_id, &to_me),
+            Err(RejectionReason::Banned)
+        );
+        assert_eq!(
+            state.libp2p_incoming_accept(other_id),
+            Err(RejectionReason::Banned)
+        );
+    }
+}
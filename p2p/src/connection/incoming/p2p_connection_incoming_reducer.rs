@@ -111,13 +111,14 @@ impl P2pConnectionIncomingState {
                 let state = p2p_state.incoming_peer_connection_mut(&peer_id).ok_or(
                     "Missing state for `P2pConnectionIncomingAction::AnswerSdpCreateSuccess`",
                 )?;
-                if let Self::AnswerSdpCreatePending {
+                let dialed_peer_id = if let Self::AnswerSdpCreatePending {
                     signaling,
                     offer,
                     rpc_id,
                     ..
                 } = state
                 {
+                    let dialed_peer_id = offer.target_peer_id;
                     *state = Self::AnswerSdpCreateSuccess {
                         time: meta.time(),
                         signaling: *signaling,
@@ -125,20 +126,32 @@ impl P2pConnectionIncomingState {
                         sdp: sdp.clone(),
                         rpc_id: rpc_id.take(),
                     };
+                    dialed_peer_id
                 } else {
                     bug_condition!(
                         "Invalid state for `P2pConnectionIncomingAction::AnswerSdpCreateSuccess`: {:?}",
                         state
                     );
                     return Ok(());
-                }
+                };
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let p2p_state: &P2pState = state.substate()?;
+                // Peers which still dial one of our previous peer ids are
+                // answered as it, for the grace period after a key rotation.
+                let identity_pub_key = if dialed_peer_id == p2p_state.my_id() {
+                    p2p_state.config.identity_pub_key.clone()
+                } else {
+                    dialed_peer_id
+                        .to_public_key()
+                        .map_err(|err| format!("invalid dialed peer id: {err}"))?
+                };
                 let answer = Box::new(crate::webrtc::Answer {
                     sdp,
-                    identity_pub_key: p2p_state.config.identity_pub_key.clone(),
+                    identity_pub_key,
                     target_peer_id: peer_id,
+                    snark_batching: true,
+                    block_streaming: true,
                 });
                 dispatcher.push(P2pConnectionIncomingAction::AnswerReady { peer_id, answer });
                 Ok(())
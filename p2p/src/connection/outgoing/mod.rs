@@ -6,9 +6,12 @@ pub use p2p_connection_outgoing_actions::*;
 
 mod p2p_connection_outgoing_reducer;
 
+mod p2p_connection_outgoing_dnsaddr_state;
+pub use p2p_connection_outgoing_dnsaddr_state::*;
+
 #[cfg(feature = "p2p-libp2p")]
 use std::net::SocketAddr;
-use std::{fmt, net::IpAddr, str::FromStr};
+use std::{fmt, str::FromStr};
 
 use binprot_derive::{BinProtRead, BinProtWrite};
 use multiaddr::{Multiaddr, Protocol};
@@ -59,20 +62,12 @@ impl P2pConnectionOutgoingInitLibp2pOpts {
     /// If the current host is local and there is a better host among the `addrs`,
     /// replace the current one with the better one.
     pub fn update_host_if_needed<'a>(&mut self, mut addrs: impl Iterator<Item = &'a Multiaddr>) {
-        fn is_local(ip: impl Into<IpAddr>) -> bool {
-            match ip.into() {
-                IpAddr::V4(ip) => ip.is_loopback() || ip.is_private(),
-                IpAddr::V6(ip) => ip.is_loopback(),
-            }
+        fn is_local(ip: impl Into<Host>) -> bool {
+            ip.into().is_local()
         }
 
         // if current dial opts is not good enough
-        let update = match &self.host {
-            Host::Domain(_) => false,
-            Host::Ipv4(ip) => is_local(*ip),
-            Host::Ipv6(ip) => is_local(*ip),
-        };
-        if update {
+        if self.host.is_local() {
             // if new options is better
             let new = addrs.find_map(|x| {
                 x.iter().find_map(|x| match x {
@@ -208,6 +203,20 @@ impl P2pConnectionOutgoingInitOpts {
         }
     }
 
+    /// Whether the peer is dialed at an address that isn't reachable from
+    /// the internet, see [`Host::is_local`].
+    pub fn is_local(&self) -> bool {
+        match self {
+            Self::LibP2P(opts) => opts.host.is_local(),
+            Self::WebRTC { signaling, .. } => match signaling {
+                webrtc::SignalingMethod::Http(info)
+                | webrtc::SignalingMethod::Https(info)
+                | webrtc::SignalingMethod::HttpsProxy(_, info) => info.host.is_local(),
+                webrtc::SignalingMethod::P2p { .. } => false,
+            },
+        }
+    }
+
     pub fn webrtc_p2p_relay_peer_id(&self) -> Option<PeerId> {
         match self {
             Self::WebRTC { signaling, .. } => signaling.p2p_relay_peer_id(),
@@ -375,8 +384,9 @@ impl FromStr for P2pConnectionOutgoingInitOpts {
             ));
         }
 
-        let id_end_index = s[1..]
-            .find('/')
+        let id_end_index = s
+            .get(1..)
+            .and_then(|s| s.find('/'))
             .map(|i| i + 1)
             .filter(|i| s.len() > *i)
             .ok_or(P2pConnectionOutgoingInitOptsParseError::NotEnoughArgs)?;
@@ -413,6 +423,161 @@ impl<'de> Deserialize<'de> for P2pConnectionOutgoingInitOpts {
     }
 }
 
+/// Address of a peer as given by the operator: either the address of a single
+/// peer, or a `/dnsaddr` domain listing the addresses of the peers.
+#[derive(Debug, Eq, PartialEq, Clone, derive_more::From)]
+pub enum P2pConnectionOutgoingPeerAddr {
+    Opts(P2pConnectionOutgoingInitOpts),
+    Dnsaddr(P2pDnsaddr),
+}
+
+impl P2pConnectionOutgoingPeerAddr {
+    /// Splits the addresses into the peers to dial and the `/dnsaddr`
+    /// domains, which are resolved by the state machine, see
+    /// [`P2pDnsaddrState`].
+    pub fn partition(
+        addrs: impl IntoIterator<Item = Self>,
+    ) -> (Vec<P2pConnectionOutgoingInitOpts>, Vec<P2pDnsaddr>) {
+        let mut peers = Vec::new();
+        let mut dnsaddrs = Vec::new();
+        for addr in addrs {
+            match addr {
+                Self::Opts(opts) => peers.push(opts),
+                Self::Dnsaddr(dnsaddr) => dnsaddrs.push(dnsaddr),
+            }
+        }
+        (peers, dnsaddrs)
+    }
+}
+
+impl fmt::Display for P2pConnectionOutgoingPeerAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Opts(opts) => opts.fmt(f),
+            Self::Dnsaddr(dnsaddr) => dnsaddr.fmt(f),
+        }
+    }
+}
+
+impl FromStr for P2pConnectionOutgoingPeerAddr {
+    type Err = P2pConnectionOutgoingInitOptsParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("/dnsaddr/") {
+            s.parse().map(Self::Dnsaddr)
+        } else {
+            s.parse().map(Self::Opts)
+        }
+    }
+}
+
+impl Serialize for P2pConnectionOutgoingPeerAddr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for P2pConnectionOutgoingPeerAddr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s: String = Deserialize::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A `/dnsaddr/<domain>[/p2p/<peer_id>]` multiaddr. The `TXT` records of
+/// `_dnsaddr.<domain>` list the addresses of the peers, only the one with
+/// `peer_id` is used if it is set.
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct P2pDnsaddr {
+    pub domain: String,
+    pub peer_id: Option<PeerId>,
+}
+
+impl fmt::Display for P2pDnsaddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "/dnsaddr/{}", self.domain)?;
+        match self.peer_id.map(libp2p_identity::PeerId::try_from) {
+            Some(Ok(peer_id)) => write!(f, "/p2p/{peer_id}"),
+            Some(Err(_)) => write!(f, "/p2p/*INVALID PEER ID*"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FromStr for P2pDnsaddr {
+    type Err = P2pConnectionOutgoingInitOptsParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let maddr = multiaddr::Multiaddr::from_str(s)
+            .map_err(|e| P2pConnectionOutgoingInitOptsParseError::Other(e.to_string()))?;
+        let mut iter = maddr.iter();
+        let domain = match iter.next() {
+            Some(Protocol::Dnsaddr(domain)) => match Host::from_str(&domain) {
+                Ok(Host::Domain(domain)) => domain,
+                _ => {
+                    return Err(P2pConnectionOutgoingInitOptsParseError::Other(format!(
+                        "invalid dnsaddr domain `{domain}`"
+                    )))
+                }
+            },
+            _ => {
+                return Err(P2pConnectionOutgoingInitOptsParseError::Other(
+                    "expected dnsaddr multiaddr".to_owned(),
+                ))
+            }
+        };
+        let peer_id = match iter.next() {
+            Some(Protocol::P2p(hash)) => Some(
+                libp2p_identity::PeerId::from_multihash(hash.into())
+                    .ok()
+                    .and_then(|peer_id| peer_id.try_into().ok())
+                    .ok_or_else(|| {
+                        P2pConnectionOutgoingInitOptsParseError::PeerIdParseError(
+                            "invalid peer_id multihash".to_owned(),
+                        )
+                    })?,
+            ),
+            None => None,
+            Some(_) => {
+                return Err(P2pConnectionOutgoingInitOptsParseError::Other(
+                    "unexpected part in dnsaddr multiaddr! expected peer_id".to_owned(),
+                ))
+            }
+        };
+        if iter.next().is_some() {
+            return Err(P2pConnectionOutgoingInitOptsParseError::Other(
+                "unexpected part in dnsaddr multiaddr after peer_id".to_owned(),
+            ));
+        }
+        Ok(Self { domain, peer_id })
+    }
+}
+
+impl Serialize for P2pDnsaddr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for P2pDnsaddr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s: String = Deserialize::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 impl TryFrom<P2pConnectionOutgoingInitLibp2pOpts> for multiaddr::Multiaddr {
     type Error = libp2p_identity::DecodingError;
 
@@ -459,8 +624,19 @@ impl TryFrom<&multiaddr::Multiaddr> for P2pConnectionOutgoingInitLibp2pOpts {
         Ok(P2pConnectionOutgoingInitLibp2pOpts {
             host: match iter.next() {
                 Some(Protocol::Ip4(v)) => Host::Ipv4(v),
+                Some(Protocol::Ip6(v)) => Host::Ipv6(v),
+                // Normalizes the name, non-ASCII labels are punycoded.
                 Some(Protocol::Dns(v) | Protocol::Dns4(v) | Protocol::Dns6(v)) => {
-                    Host::Domain(v.to_string())
+                    Host::from_str(&v).map_err(|err| {
+                        P2pConnectionOutgoingInitOptsParseError::Other(format!(
+                            "invalid host `{v}` in multiaddr: {err}"
+                        ))
+                    })?
+                }
+                Some(Protocol::Dnsaddr(_)) => {
+                    return Err(P2pConnectionOutgoingInitOptsParseError::Other(
+                        "dnsaddr multiaddr lists peers, it must be resolved first".to_string(),
+                    ));
                 }
                 Some(_) => {
                     return Err(P2pConnectionOutgoingInitOptsParseError::Other(
@@ -527,3 +703,82 @@ mod measurement {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PEER_ID: &str = "12D3KooWD8jSyPFXNdAcMBHyHjRBcK1AW9t3xvnpfCFSRKMweVKi";
+
+    #[test]
+    fn test_parse_dns_multiaddr_punycode() {
+        let opts = format!("/dns4/Bücher.example/tcp/8302/p2p/{PEER_ID}")
+            .parse::<P2pConnectionOutgoingInitOpts>()
+            .unwrap();
+        let P2pConnectionOutgoingInitOpts::LibP2P(opts) = opts else {
+            panic!("expected libp2p opts");
+        };
+        assert_eq!(opts.host, Host::Domain("xn--bcher-kva.example".to_owned()));
+        assert_eq!(opts.port, 8302);
+    }
+
+    #[test]
+    fn test_parse_dnsaddr() {
+        let addr = "/dnsaddr/Seeds.Example.com"
+            .parse::<P2pConnectionOutgoingPeerAddr>()
+            .unwrap();
+        assert_eq!(
+            addr,
+            P2pConnectionOutgoingPeerAddr::Dnsaddr(P2pDnsaddr {
+                domain: "seeds.example.com".to_owned(),
+                peer_id: None,
+            })
+        );
+        assert_eq!(addr.to_string(), "/dnsaddr/seeds.example.com");
+
+        let s = format!("/dnsaddr/seeds.example.com/p2p/{PEER_ID}");
+        let addr = s.parse::<P2pConnectionOutgoingPeerAddr>().unwrap();
+        assert!(matches!(
+            addr,
+            P2pConnectionOutgoingPeerAddr::Dnsaddr(P2pDnsaddr {
+                peer_id: Some(_),
+                ..
+            })
+        ));
+        assert_eq!(addr.to_string(), s);
+
+        assert!(s.parse::<P2pConnectionOutgoingInitOpts>().is_err());
+        assert!("/dnsaddr/seeds.example.com/tcp/8302"
+            .parse::<P2pConnectionOutgoingPeerAddr>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_peer_addr_opts() {
+        let s = format!("/ip4/1.2.3.4/tcp/8302/p2p/{PEER_ID}");
+        let addr = s.parse::<P2pConnectionOutgoingPeerAddr>().unwrap();
+        assert_eq!(
+            addr,
+            P2pConnectionOutgoingPeerAddr::Opts(s.parse().unwrap())
+        );
+        assert_eq!(addr.to_string(), s);
+    }
+
+    #[test]
+    fn test_partition_peer_addrs() {
+        let opts = format!("/ip4/1.2.3.4/tcp/8302/p2p/{PEER_ID}");
+        let addrs = [opts.as_str(), "/dnsaddr/seeds.example.com"]
+            .map(|s| s.parse::<P2pConnectionOutgoingPeerAddr>().unwrap());
+
+        let (peers, dnsaddrs) = P2pConnectionOutgoingPeerAddr::partition(addrs);
+        assert_eq!(peers, [opts.parse().unwrap()]);
+        assert_eq!(dnsaddrs, ["/dnsaddr/seeds.example.com".parse().unwrap()]);
+
+        let json = serde_json::to_string(&dnsaddrs).unwrap();
+        assert_eq!(json, r#"["/dnsaddr/seeds.example.com"]"#);
+        assert_eq!(
+            serde_json::from_str::<Vec<P2pDnsaddr>>(&json).unwrap(),
+            dnsaddrs
+        );
+    }
+}
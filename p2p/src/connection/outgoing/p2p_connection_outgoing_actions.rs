@@ -1,3 +1,5 @@
+use std::{net::IpAddr, time::Duration};
+
 use mina_core::ActionEvent;
 use redux::Callback;
 use serde::{Deserialize, Serialize};
@@ -6,7 +8,7 @@ use mina_core::requests::RpcId;
 
 use crate::{connection::P2pConnectionErrorResponse, webrtc, P2pState, PeerId};
 
-use super::{P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts};
+use super::{P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts, P2pDnsaddr};
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
 #[action_event(fields(display(opts), display(peer_id), display(error)))]
@@ -26,6 +28,30 @@ pub enum P2pConnectionOutgoingAction {
         opts: P2pConnectionOutgoingInitOpts,
         rpc_id: Option<RpcId>,
     },
+    /// The host of the peer is resolved, connect to `ip`.
+    HostResolveSuccess {
+        peer_id: PeerId,
+        ip: IpAddr,
+    },
+    /// Resolve the `/dnsaddr` domain given by the operator, see
+    /// [`super::P2pDnsaddrState`].
+    #[action_event(level = debug, fields(display(addr)))]
+    DnsaddrResolveInit {
+        addr: P2pDnsaddr,
+    },
+    /// The `/dnsaddr` domain is resolved to `peers`, its records are valid
+    /// for `ttl`.
+    #[action_event(level = info, fields(display(addr), peers = peers.len()))]
+    DnsaddrResolveSuccess {
+        addr: P2pDnsaddr,
+        peers: Vec<P2pConnectionOutgoingInitOpts>,
+        ttl: Duration,
+    },
+    #[action_event(level = warn, fields(display(addr), error))]
+    DnsaddrResolveError {
+        addr: P2pDnsaddr,
+        error: String,
+    },
     #[action_event(level = trace)]
     OfferSdpCreatePending {
         peer_id: PeerId,
@@ -94,7 +120,8 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionOutgoingAction {
             P2pConnectionOutgoingAction::RandomInit =>  !state.already_has_min_peers() && state.disconnected_peers().next().is_some(),
             P2pConnectionOutgoingAction::Init { opts, .. } => {
                 !state.already_has_min_peers() &&
-                &state.my_id() != opts.peer_id() &&
+                !state.is_my_id(opts.peer_id()) &&
+                !state.is_banned(opts.peer_id()) &&
                 state
                     .peers
                     .get(opts.peer_id())
@@ -102,10 +129,31 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionOutgoingAction {
             }
             P2pConnectionOutgoingAction::Reconnect { opts, .. } => {
                 !state.already_has_min_peers()
+                    && !state.is_banned(opts.peer_id())
                     && state.peers.get(opts.peer_id()).is_some_and( |peer| {
                         peer.can_reconnect(time, &state.config.timeouts)
                     })
             }
+            P2pConnectionOutgoingAction::HostResolveSuccess { peer_id, .. } => state
+                .peers
+                .get(peer_id)
+                .and_then(|peer| peer.status.as_connecting()?.as_outgoing())
+                .is_some_and(|s| {
+                    matches!(
+                        s,
+                        P2pConnectionOutgoingState::Init {
+                            opts: P2pConnectionOutgoingInitOpts::LibP2P(opts),
+                            ..
+                        } if matches!(opts.host, webrtc::Host::Domain(_))
+                    )
+                }),
+            P2pConnectionOutgoingAction::DnsaddrResolveInit { addr } => state
+                .dnsaddr(addr)
+                .is_some_and(|dnsaddr| !dnsaddr.is_pending() || dnsaddr.should_resolve(time, false)),
+            P2pConnectionOutgoingAction::DnsaddrResolveSuccess { addr, .. }
+            | P2pConnectionOutgoingAction::DnsaddrResolveError { addr, .. } => {
+                state.dnsaddr(addr).is_some_and(|dnsaddr| dnsaddr.is_pending())
+            }
             P2pConnectionOutgoingAction::OfferSdpCreatePending { peer_id } => state
                 .peers
                 .get(peer_id)
@@ -185,6 +233,9 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionOutgoingAction {
                 .get(peer_id)
                 .is_some_and( |peer| match &peer.status {
                     P2pPeerStatus::Connecting(P2pConnectionState::Outgoing(s)) => match error {
+                        P2pConnectionOutgoingError::HostResolveError(_) => {
+                            matches!(s, P2pConnectionOutgoingState::Init { .. })
+                        }
                         P2pConnectionOutgoingError::SdpCreateError(_) => {
                             matches!(s, P2pConnectionOutgoingState::OfferSdpCreatePending { .. })
                         }
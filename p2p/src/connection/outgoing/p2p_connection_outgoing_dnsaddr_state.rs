@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::PeerId;
+
+use super::P2pDnsaddr;
+
+/// Delay before resolving a domain again after its resolution failed, or
+/// after connecting to one of its peers failed.
+pub const DNSADDR_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+/// Resolution is started again if its result didn't come in this long.
+pub const DNSADDR_RESOLVE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Resolution of a `/dnsaddr` domain given by the operator.
+///
+/// The peers listed by the domain are added to the initial peers. The
+/// domain is resolved again once its records expire, after the resolution
+/// failed, and after connecting to one of its peers failed, as the peer may
+/// have moved. Peers no longer listed are removed from the initial peers.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pDnsaddrState {
+    pub addr: P2pDnsaddr,
+    /// Peers listed by the last successful resolution.
+    pub peers: Vec<PeerId>,
+    pub status: P2pDnsaddrStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum P2pDnsaddrStatus {
+    Idle,
+    Pending {
+        time: Timestamp,
+    },
+    Resolved {
+        time: Timestamp,
+        /// Time the records expire at, clamped by the resolver.
+        expires_at: Timestamp,
+    },
+    Error {
+        time: Timestamp,
+        error: String,
+    },
+}
+
+impl P2pDnsaddrState {
+    pub fn new(addr: P2pDnsaddr) -> Self {
+        Self {
+            addr,
+            peers: Vec::new(),
+            status: P2pDnsaddrStatus::Idle,
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        matches!(self.status, P2pDnsaddrStatus::Pending { .. })
+    }
+
+    /// Whether the domain needs to be resolved (again) at `now`.
+    /// `peer_failed` tells if connecting to one of its peers failed.
+    pub fn should_resolve(&self, now: Timestamp, peer_failed: bool) -> bool {
+        match &self.status {
+            P2pDnsaddrStatus::Idle => true,
+            P2pDnsaddrStatus::Pending { time } => now >= *time + DNSADDR_RESOLVE_TIMEOUT,
+            P2pDnsaddrStatus::Resolved { time, expires_at } => {
+                now >= *expires_at || (peer_failed && now >= *time + DNSADDR_RETRY_INTERVAL)
+            }
+            P2pDnsaddrStatus::Error { time, .. } => now >= *time + DNSADDR_RETRY_INTERVAL,
+        }
+    }
+
+    /// Whether the cached records must be bypassed, as the previous
+    /// resolution is the reason to resolve again.
+    pub fn needs_refresh(&self) -> bool {
+        !matches!(self.status, P2pDnsaddrStatus::Idle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(secs: u64) -> Timestamp {
+        Timestamp::ZERO + Duration::from_secs(secs)
+    }
+
+    fn state(status: P2pDnsaddrStatus) -> P2pDnsaddrState {
+        P2pDnsaddrState {
+            status,
+            ..P2pDnsaddrState::new("/dnsaddr/seeds.example.com".parse().unwrap())
+        }
+    }
+
+    #[test]
+    fn test_resolve_once_records_expire() {
+        let state = state(P2pDnsaddrStatus::Resolved {
+            time: time(0),
+            expires_at: time(300),
+        });
+        assert!(!state.should_resolve(time(299), false));
+        assert!(state.should_resolve(time(300), false));
+        assert!(state.needs_refresh());
+    }
+
+    #[test]
+    fn test_resolve_again_on_peer_failure() {
+        let state = state(P2pDnsaddrStatus::Resolved {
+            time: time(0),
+            expires_at: time(300),
+        });
+        assert!(!state.should_resolve(time(10), true), "too soon");
+        assert!(state.should_resolve(time(30), true));
+        assert!(!state.should_resolve(time(30), false));
+    }
+
+    #[test]
+    fn test_retry_on_error() {
+        let state = state(P2pDnsaddrStatus::Error {
+            time: time(0),
+            error: "timed out".to_owned(),
+        });
+        assert!(!state.should_resolve(time(29), false));
+        assert!(state.should_resolve(time(30), false));
+    }
+
+    #[test]
+    fn test_pending_resolution() {
+        assert!(
+            P2pDnsaddrState::new("/dnsaddr/seeds.example.com".parse().unwrap())
+                .should_resolve(time(0), false)
+        );
+
+        let state = state(P2pDnsaddrStatus::Pending { time: time(0) });
+        assert!(!state.should_resolve(time(59), true));
+        assert!(state.should_resolve(time(60), false), "result was lost");
+    }
+}
@@ -1,6 +1,7 @@
+#[cfg(feature = "p2p-libp2p")]
 use std::net::SocketAddr;
 
-use mina_core::{bug_condition, warn, Substate};
+use mina_core::{bug_condition, Substate};
 use redux::ActionWithMeta;
 
 use crate::{
@@ -15,10 +16,14 @@ use crate::{
     P2pPeerStatus, P2pState,
 };
 
+#[cfg(feature = "p2p-libp2p")]
 use super::{
     libp2p_opts::P2pConnectionOutgoingInitLibp2pOptsTryToSocketAddrError,
+    P2pConnectionOutgoingInitLibp2pOpts,
+};
+use super::{
     P2pConnectionOutgoingAction, P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts,
-    P2pConnectionOutgoingState,
+    P2pConnectionOutgoingState, P2pDnsaddrStatus,
 };
 
 impl P2pConnectionOutgoingState {
@@ -73,22 +78,7 @@ impl P2pConnectionOutgoingState {
 
                 #[cfg(feature = "p2p-libp2p")]
                 if let P2pConnectionOutgoingInitOpts::LibP2P(libp2p_opts) = &opts {
-                    match SocketAddr::try_from(libp2p_opts) {
-                        Ok(addr) => {
-                            dispatcher.push(P2pNetworkSchedulerAction::OutgoingConnect { addr });
-                        }
-                        Err(
-                            P2pConnectionOutgoingInitLibp2pOptsTryToSocketAddrError::Unresolved(
-                                _name,
-                            ),
-                        ) => {
-                            // TODO: initiate name resolution
-                            warn!(meta.time(); "name resolution needed to connect to {}", opts);
-                        }
-                    }
-                    dispatcher.push(P2pConnectionOutgoingAction::FinalizePending {
-                        peer_id: libp2p_opts.peer_id,
-                    });
+                    Self::libp2p_connect(dispatcher, libp2p_opts, false);
                     return Ok(());
                 }
 
@@ -101,6 +91,14 @@ impl P2pConnectionOutgoingState {
                     .get_mut(opts.peer_id())
                     .ok_or("Missing peer state for: `P2pConnectionOutgoingAction::Reconnect`")?;
 
+                // The previous attempt failed, the peer may have moved to
+                // another address.
+                #[cfg(feature = "p2p-libp2p")]
+                let refresh = matches!(
+                    peer_state.status,
+                    P2pPeerStatus::Connecting(P2pConnectionState::Outgoing(Self::Error { .. }))
+                );
+
                 peer_state.status =
                     P2pPeerStatus::Connecting(P2pConnectionState::Outgoing(Self::Init {
                         time,
@@ -113,28 +111,101 @@ impl P2pConnectionOutgoingState {
 
                 #[cfg(feature = "p2p-libp2p")]
                 if let P2pConnectionOutgoingInitOpts::LibP2P(libp2p_opts) = &opts {
-                    match SocketAddr::try_from(libp2p_opts) {
-                        Ok(addr) => {
-                            dispatcher.push(P2pNetworkSchedulerAction::OutgoingConnect { addr });
-                        }
-                        Err(
-                            P2pConnectionOutgoingInitLibp2pOptsTryToSocketAddrError::Unresolved(
-                                _name,
-                            ),
-                        ) => {
-                            // TODO: initiate name resolution
-                            warn!(meta.time(); "name resolution needed to connect to {}", opts);
-                        }
-                    }
-                    dispatcher.push(P2pConnectionOutgoingAction::FinalizePending {
-                        peer_id: *opts.peer_id(),
-                    });
+                    Self::libp2p_connect(dispatcher, libp2p_opts, refresh);
                     return Ok(());
                 }
 
                 dispatcher.push(P2pConnectionOutgoingEffectfulAction::Init { opts, rpc_id });
                 Ok(())
             }
+            P2pConnectionOutgoingAction::HostResolveSuccess { peer_id, ip } => {
+                let state = p2p_state
+                    .outgoing_peer_connection_mut(&peer_id)
+                    .ok_or("Missing connection state for: `P2pConnectionOutgoingAction::HostResolveSuccess`")?;
+
+                let Self::Init {
+                    opts: P2pConnectionOutgoingInitOpts::LibP2P(libp2p_opts),
+                    ..
+                } = state
+                else {
+                    bug_condition!(
+                        "Invalid state for `P2pConnectionOutgoingAction::HostResolveSuccess`: {:?}",
+                        state
+                    );
+                    return Ok(());
+                };
+                // Keep the resolved address in the connection state, so that
+                // the connection is matched with the peer. The dial options of
+                // the peer keep the name, to resolve it again on reconnection.
+                libp2p_opts.host = ip.into();
+
+                #[cfg(feature = "p2p-libp2p")]
+                {
+                    let libp2p_opts = libp2p_opts.clone();
+                    Self::libp2p_connect(state_context.into_dispatcher(), &libp2p_opts, false);
+                }
+                Ok(())
+            }
+            P2pConnectionOutgoingAction::DnsaddrResolveInit { addr } => {
+                let dnsaddr = p2p_state.dnsaddr_mut(&addr).ok_or(
+                    "Missing state for: `P2pConnectionOutgoingAction::DnsaddrResolveInit`",
+                )?;
+                let refresh = dnsaddr.needs_refresh();
+                dnsaddr.status = P2pDnsaddrStatus::Pending { time };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher
+                    .push(P2pConnectionOutgoingEffectfulAction::ResolveDnsaddr { addr, refresh });
+                Ok(())
+            }
+            P2pConnectionOutgoingAction::DnsaddrResolveSuccess { addr, peers, ttl } => {
+                let listed = peers.iter().map(|opts| *opts.peer_id()).collect::<Vec<_>>();
+                // Peers also listed by another domain stay initial peers.
+                let unlisted = p2p_state
+                    .dnsaddrs
+                    .iter()
+                    .filter(|dnsaddr| dnsaddr.addr == addr)
+                    .flat_map(|dnsaddr| &dnsaddr.peers)
+                    .filter(|peer_id| {
+                        !listed.contains(peer_id)
+                            && !p2p_state
+                                .dnsaddrs
+                                .iter()
+                                .any(|other| other.addr != addr && other.peers.contains(peer_id))
+                    })
+                    .copied()
+                    .collect::<Vec<_>>();
+
+                let dnsaddr = p2p_state.dnsaddr_mut(&addr).ok_or(
+                    "Missing state for: `P2pConnectionOutgoingAction::DnsaddrResolveSuccess`",
+                )?;
+                dnsaddr.peers = listed;
+                dnsaddr.status = P2pDnsaddrStatus::Resolved {
+                    time,
+                    expires_at: time + ttl,
+                };
+
+                let dispatcher = state_context.into_dispatcher();
+                for peer_id in unlisted {
+                    dispatcher.push(P2pPeerAction::Unlisted { peer_id });
+                }
+                for dial_opts in peers {
+                    dispatcher.push(P2pPeerAction::Listed {
+                        peer_id: *dial_opts.peer_id(),
+                        dial_opts,
+                    });
+                }
+                Ok(())
+            }
+            P2pConnectionOutgoingAction::DnsaddrResolveError { addr, error } => {
+                let dnsaddr = p2p_state.dnsaddr_mut(&addr).ok_or(
+                    "Missing state for: `P2pConnectionOutgoingAction::DnsaddrResolveError`",
+                )?;
+                // Peers listed by the previous resolution are kept until the
+                // domain resolves again.
+                dnsaddr.status = P2pDnsaddrStatus::Error { time, error };
+                Ok(())
+            }
             P2pConnectionOutgoingAction::OfferSdpCreatePending { peer_id, .. } => {
                 let state = p2p_state
                     .outgoing_peer_connection_mut(&peer_id)
@@ -200,6 +271,8 @@ impl P2pConnectionOutgoingState {
                     // TODO(vlad9486): put real address
                     host: Host::Ipv4([127, 0, 0, 1].into()),
                     listen_port: p2p_state.config.listen_port,
+                    snark_batching: true,
+                    block_streaming: true,
                 });
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(P2pConnectionOutgoingAction::OfferReady { peer_id, offer });
@@ -592,4 +665,31 @@ impl P2pConnectionOutgoingState {
             }
         }
     }
+
+    /// Connects to the libp2p peer, or resolves its host first.
+    #[cfg(feature = "p2p-libp2p")]
+    fn libp2p_connect<Action, State>(
+        dispatcher: &mut redux::Dispatcher<Action, State>,
+        opts: &P2pConnectionOutgoingInitLibp2pOpts,
+        refresh: bool,
+    ) where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        match SocketAddr::try_from(opts) {
+            Ok(addr) => {
+                dispatcher.push(P2pNetworkSchedulerAction::OutgoingConnect { addr });
+                dispatcher.push(P2pConnectionOutgoingAction::FinalizePending {
+                    peer_id: opts.peer_id,
+                });
+            }
+            Err(P2pConnectionOutgoingInitLibp2pOptsTryToSocketAddrError::Unresolved(host)) => {
+                dispatcher.push(P2pConnectionOutgoingEffectfulAction::ResolveHost {
+                    peer_id: opts.peer_id,
+                    host,
+                    refresh,
+                });
+            }
+        }
+    }
 }
@@ -140,6 +140,23 @@ impl P2pConnectionOutgoingState {
         }
     }
 
+    /// Options used to connect to the peer, for libp2p peers the host is the
+    /// resolved IP address once resolution succeeded.
+    pub fn opts(&self) -> Option<&P2pConnectionOutgoingInitOpts> {
+        match self {
+            Self::Init { opts, .. } => Some(opts),
+            Self::OfferSdpCreatePending { opts, .. } => Some(opts),
+            Self::OfferSdpCreateSuccess { opts, .. } => Some(opts),
+            Self::OfferReady { opts, .. } => Some(opts),
+            Self::OfferSendSuccess { opts, .. } => Some(opts),
+            Self::AnswerRecvPending { opts, .. } => Some(opts),
+            Self::AnswerRecvSuccess { opts, .. } => Some(opts),
+            Self::FinalizePending { opts, .. } => Some(opts),
+            Self::FinalizeSuccess { opts, .. } => Some(opts),
+            Self::Error { .. } | Self::Success { .. } => None,
+        }
+    }
+
     pub fn is_timed_out(&self, now: Timestamp, timeouts: &P2pTimeouts) -> bool {
         !matches!(self, Self::Error { .. })
             && now
@@ -151,6 +168,8 @@ impl P2pConnectionOutgoingState {
 
 #[derive(Serialize, Deserialize, Debug, Clone, thiserror::Error, MallocSizeOf)]
 pub enum P2pConnectionOutgoingError {
+    #[error("error resolving host: {0}")]
+    HostResolveError(String),
     #[error("error creating SDP: {0}")]
     SdpCreateError(String),
     #[error("rejected: {0}")]
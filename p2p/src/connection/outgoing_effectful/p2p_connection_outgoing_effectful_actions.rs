@@ -4,7 +4,10 @@ use serde::{Deserialize, Serialize};
 use mina_core::requests::RpcId;
 
 use crate::{
-    connection::{outgoing::P2pConnectionOutgoingInitOpts, P2pConnectionEffectfulAction},
+    connection::{
+        outgoing::{P2pConnectionOutgoingInitOpts, P2pDnsaddr},
+        P2pConnectionEffectfulAction,
+    },
     identity::PublicKey,
     webrtc::{self, ConnectionAuth, SignalingMethod},
     P2pState, PeerId,
@@ -24,6 +27,16 @@ pub enum P2pConnectionOutgoingEffectfulAction {
         opts: P2pConnectionOutgoingInitOpts,
         rpc_id: Option<RpcId>,
     },
+    /// Resolve the host of a libp2p peer, bypassing the cached addresses if
+    /// `refresh` is set.
+    ResolveHost {
+        peer_id: PeerId,
+        host: String,
+        refresh: bool,
+    },
+    /// Resolve the peers listed by the `/dnsaddr` domain, bypassing the
+    /// cached records if `refresh` is set.
+    ResolveDnsaddr { addr: P2pDnsaddr, refresh: bool },
     OfferSend {
         peer_id: PeerId,
         offer: Box<webrtc::Offer>,
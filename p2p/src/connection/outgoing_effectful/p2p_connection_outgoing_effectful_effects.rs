@@ -34,6 +34,16 @@ impl P2pConnectionOutgoingEffectfulAction {
                 store.service().outgoing_init(opts);
                 store.dispatch(P2pConnectionOutgoingAction::OfferSdpCreatePending { peer_id });
             }
+            P2pConnectionOutgoingEffectfulAction::ResolveHost {
+                peer_id,
+                host,
+                refresh,
+            } => {
+                store.service().resolve_host(peer_id, host, refresh);
+            }
+            P2pConnectionOutgoingEffectfulAction::ResolveDnsaddr { addr, refresh } => {
+                store.service().resolve_dnsaddr(addr, refresh);
+            }
             P2pConnectionOutgoingEffectfulAction::OfferSend {
                 peer_id,
                 offer,
@@ -2,7 +2,7 @@ use std::collections::BTreeSet;
 
 use crate::{identity::PublicKey, webrtc, PeerId};
 
-use super::outgoing::P2pConnectionOutgoingInitOpts;
+use super::outgoing::{P2pConnectionOutgoingInitOpts, P2pDnsaddr};
 
 pub trait P2pConnectionService: redux::Service {
     fn connections(&self) -> BTreeSet<PeerId>;
@@ -16,6 +16,14 @@ pub trait P2pConnectionService: redux::Service {
     /// which will be received in the state machine as an event.
     fn outgoing_init(&mut self, opts: P2pConnectionOutgoingInitOpts);
 
+    /// Resolves the host of a libp2p peer, the result will be received in
+    /// the state machine as an event.
+    fn resolve_host(&mut self, peer_id: PeerId, host: String, refresh: bool);
+
+    /// Resolves the peers listed by the `/dnsaddr` domain, the result will
+    /// be received in the state machine as an event.
+    fn resolve_dnsaddr(&mut self, addr: P2pDnsaddr, refresh: bool);
+
     /// Initiates an incoming connection and creates an answer sdp,
     /// which will be received in the state machine as an event.
     fn incoming_init(&mut self, peer_id: PeerId, offer: webrtc::Offer);
@@ -90,4 +90,33 @@ impl P2pConnectionState {
             P2pConnectionState::Incoming(i) => i.time(),
         }
     }
+
+    /// Whether the remote peer accepts batched snark messages, as announced
+    /// in its offer or answer. Always `false` for libp2p connections.
+    pub fn remote_snark_batching(&self) -> bool {
+        match self {
+            Self::Outgoing(P2pConnectionOutgoingState::Success { answer, .. }) => {
+                answer.as_ref().is_some_and(|answer| answer.snark_batching)
+            }
+            Self::Incoming(P2pConnectionIncomingState::Success { offer, .. }) => {
+                offer.snark_batching
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the remote peer serves blocks over the streaming RPC, as
+    /// announced in its offer or answer. Always `false` for libp2p
+    /// connections.
+    pub fn remote_block_streaming(&self) -> bool {
+        match self {
+            Self::Outgoing(P2pConnectionOutgoingState::Success { answer, .. }) => {
+                answer.as_ref().is_some_and(|answer| answer.block_streaming)
+            }
+            Self::Incoming(P2pConnectionIncomingState::Success { offer, .. }) => {
+                offer.block_streaming
+            }
+            _ => false,
+        }
+    }
 }
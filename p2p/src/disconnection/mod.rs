@@ -6,6 +6,9 @@ pub use p2p_disconnection_actions::*;
 
 mod p2p_disconnection_reducer;
 
+mod p2p_disconnection_bans_state;
+pub use p2p_disconnection_bans_state::*;
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -31,6 +34,8 @@ pub enum P2pDisconnectionReason {
     TransitionFrontierRpcTimeout(P2pRpcKind),
     #[error("transition frontier streaming RPC({0:?}) timeout")]
     TransitionFrontierStreamingRpcTimeout(P2pStreamingRpcKind),
+    #[error("streaming RPC({0:?}) response is invalid: {1}")]
+    StreamingRpcResponseInvalid(P2pStreamingRpcKind, String),
     #[error("received num accounts rejected")]
     TransitionFrontierSyncLedgerSnarkedNumAccountsRejected,
     #[error("failed to verify snark pool diff")]
@@ -44,3 +49,29 @@ pub enum P2pDisconnectionReason {
     #[error("invalid pubsub message")]
     InvalidMessage,
 }
+
+impl P2pDisconnectionReason {
+    /// How much the disconnection adds to the misbehavior score of the
+    /// peer, see [`P2pDisconnectionBansState`]. `0` if the peer didn't
+    /// misbehave.
+    pub fn misbehavior_score(&self) -> u32 {
+        match self {
+            Self::InvalidMessage | Self::SnarkPoolVerifyError => MISBEHAVIOR_BAN_SCORE,
+            Self::StreamingRpcResponseInvalid(..)
+            | Self::TransitionFrontierSyncLedgerSnarkedNumAccountsRejected => {
+                MISBEHAVIOR_BAN_SCORE / 2
+            }
+            Self::P2pChannelMsgUnexpected(_) => MISBEHAVIOR_BAN_SCORE / 4,
+            Self::FreeUpSpace
+            | Self::P2pChannelSendFailed(_)
+            | Self::P2pChannelReceiveFailed(_)
+            | Self::P2pChannelClosed(_)
+            | Self::Libp2pIncomingRejected(_)
+            | Self::TransitionFrontierRpcTimeout(_)
+            | Self::TransitionFrontierStreamingRpcTimeout(_)
+            | Self::DuplicateConnection
+            | Self::Timeout
+            | Self::Unsupported => 0,
+        }
+    }
+}
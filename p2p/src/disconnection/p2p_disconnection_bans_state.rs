@@ -0,0 +1,225 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{P2pTimeouts, PeerId};
+
+use super::P2pDisconnectionReason;
+
+/// Score at which a misbehaving peer is banned.
+pub const MISBEHAVIOR_BAN_SCORE: u32 = 100;
+/// Time in which a misbehavior score decays by [`MISBEHAVIOR_BAN_SCORE`],
+/// so that occasional misbehaviors of a long-lived peer don't add up to a
+/// ban.
+pub const MISBEHAVIOR_SCORE_DECAY: Duration = Duration::from_secs(60 * 60);
+
+/// Peers disconnected for misbehaving, see
+/// [`P2pDisconnectionReason::misbehavior_score`].
+///
+/// Banning is disabled unless [`P2pTimeouts::misbehavior_ban`] is set. Then
+/// each misbehavior adds to the score of the peer, which decays linearly
+/// over [`MISBEHAVIOR_SCORE_DECAY`]. Once the score reaches
+/// [`MISBEHAVIOR_BAN_SCORE`], the peer is banned for
+/// [`P2pTimeouts::misbehavior_ban`]: it is neither dialed nor accepted.
+/// The score is reset by the ban.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct P2pDisconnectionBansState {
+    scores: BTreeMap<PeerId, P2pMisbehaviorScore>,
+    /// Banned peers, with the time until which they are.
+    banned: BTreeMap<PeerId, Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+struct P2pMisbehaviorScore {
+    score: u32,
+    /// Time of the last misbehavior, the score decays from it.
+    time: Timestamp,
+}
+
+impl P2pMisbehaviorScore {
+    fn at(&self, now: Timestamp) -> u32 {
+        let elapsed = now.checked_sub(self.time).unwrap_or_default();
+        let decayed = elapsed.as_millis() * u128::from(MISBEHAVIOR_BAN_SCORE)
+            / MISBEHAVIOR_SCORE_DECAY.as_millis();
+        self.score
+            .saturating_sub(decayed.try_into().unwrap_or(u32::MAX))
+    }
+}
+
+impl P2pDisconnectionBansState {
+    /// Registers the disconnection of `peer_id`. Returns `true` if the peer
+    /// got banned by it.
+    pub fn on_disconnect(
+        &mut self,
+        peer_id: PeerId,
+        reason: &P2pDisconnectionReason,
+        timeouts: &P2pTimeouts,
+        now: Timestamp,
+    ) -> bool {
+        let Some(ban) = timeouts.misbehavior_ban else {
+            return false;
+        };
+        let added = reason.misbehavior_score();
+        if added == 0 {
+            return false;
+        }
+        let score = self.score(&peer_id, now).saturating_add(added);
+        if score < MISBEHAVIOR_BAN_SCORE {
+            self.scores
+                .insert(peer_id, P2pMisbehaviorScore { score, time: now });
+            return false;
+        }
+        self.scores.remove(&peer_id);
+        self.banned.insert(peer_id, now + ban);
+        true
+    }
+
+    /// Whether `peer_id` is banned. Expired bans are only removed by
+    /// [`Self::prune`], which is called periodically.
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.banned.contains_key(peer_id)
+    }
+
+    /// Misbehavior score of `peer_id` at `now`, once decayed.
+    pub fn score(&self, peer_id: &PeerId, now: Timestamp) -> u32 {
+        self.scores.get(peer_id).map_or(0, |score| score.at(now))
+    }
+
+    /// Banned peers, with the time until which they are.
+    pub fn banned(&self) -> impl Iterator<Item = (&PeerId, &Timestamp)> {
+        self.banned.iter()
+    }
+
+    /// Removes the expired bans, and the scores which decayed to zero.
+    pub fn prune(&mut self, now: Timestamp) {
+        self.banned.retain(|_, until| *until > now);
+        self.scores.retain(|_, score| score.at(now) > 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(secs: u64) -> Timestamp {
+        Timestamp::ZERO + Duration::from_secs(secs)
+    }
+
+    fn timeouts() -> P2pTimeouts {
+        P2pTimeouts {
+            misbehavior_ban: Some(Duration::from_secs(60)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bans_are_disabled_by_default() {
+        let mut state = P2pDisconnectionBansState::default();
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let timeouts = P2pTimeouts {
+            misbehavior_ban: None,
+            ..Default::default()
+        };
+
+        let reason = P2pDisconnectionReason::InvalidMessage;
+        assert!(!state.on_disconnect(peer_id, &reason, &timeouts, time(0)));
+        assert!(!state.is_banned(&peer_id));
+        assert_eq!(state.score(&peer_id, time(0)), 0);
+        assert!(P2pTimeouts::default().misbehavior_ban.is_none());
+    }
+
+    #[test]
+    fn test_invalid_message_bans() {
+        let mut state = P2pDisconnectionBansState::default();
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let other = PeerId::from_bytes([2; 32]);
+
+        let reason = P2pDisconnectionReason::InvalidMessage;
+        assert!(state.on_disconnect(peer_id, &reason, &timeouts(), time(0)));
+        assert!(state.is_banned(&peer_id));
+        assert!(!state.is_banned(&other));
+        assert_eq!(state.score(&peer_id, time(0)), 0);
+
+        state.prune(time(30));
+        assert!(state.is_banned(&peer_id));
+        state.prune(time(60));
+        assert!(!state.is_banned(&peer_id));
+    }
+
+    #[test]
+    fn test_misbehaviors_add_up() {
+        let mut state = P2pDisconnectionBansState::default();
+        let peer_id = PeerId::from_bytes([1; 32]);
+
+        let reason = P2pDisconnectionReason::P2pChannelMsgUnexpected(
+            crate::channels::ChannelId::BestTipPropagation,
+        );
+        let score = reason.misbehavior_score();
+        assert!(score > 0 && score < MISBEHAVIOR_BAN_SCORE);
+        let mut disconnections = 0;
+        while !state.on_disconnect(peer_id, &reason, &timeouts(), time(0)) {
+            disconnections += 1;
+            assert_eq!(state.score(&peer_id, time(0)), score * disconnections);
+        }
+        assert_eq!(disconnections + 1, MISBEHAVIOR_BAN_SCORE.div_ceil(score));
+        assert!(state.is_banned(&peer_id));
+    }
+
+    #[test]
+    fn test_other_disconnections_are_not_scored() {
+        let mut state = P2pDisconnectionBansState::default();
+        let peer_id = PeerId::from_bytes([1; 32]);
+
+        for reason in [
+            P2pDisconnectionReason::FreeUpSpace,
+            P2pDisconnectionReason::Timeout,
+            P2pDisconnectionReason::DuplicateConnection,
+        ] {
+            assert!(!state.on_disconnect(peer_id, &reason, &timeouts(), time(0)));
+        }
+        assert_eq!(state.score(&peer_id, time(0)), 0);
+        assert!(!state.is_banned(&peer_id));
+    }
+
+    #[test]
+    fn test_scores_decay() {
+        let mut state = P2pDisconnectionBansState::default();
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let reason = P2pDisconnectionReason::P2pChannelMsgUnexpected(
+            crate::channels::ChannelId::BestTipPropagation,
+        );
+        let score = reason.misbehavior_score();
+        let decay_secs = MISBEHAVIOR_SCORE_DECAY.as_secs();
+
+        assert!(!state.on_disconnect(peer_id, &reason, &timeouts(), time(0)));
+        assert_eq!(state.score(&peer_id, time(0)), score);
+        let decayed = time(decay_secs * u64::from(score) / 100);
+        assert!(state.score(&peer_id, time(1)) < score);
+        assert_eq!(state.score(&peer_id, decayed), 0);
+
+        // Occasional misbehaviors, each once the previous one decayed, never
+        // add up to a ban.
+        let interval = decay_secs * u64::from(score) / 100;
+        for i in 1..=2 * MISBEHAVIOR_BAN_SCORE.div_ceil(score) {
+            let now = time(u64::from(i) * interval);
+            assert!(!state.on_disconnect(peer_id, &reason, &timeouts(), now));
+            assert_eq!(state.score(&peer_id, now), score);
+        }
+    }
+
+    #[test]
+    fn test_prune_decayed_scores() {
+        let mut state = P2pDisconnectionBansState::default();
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let reason = P2pDisconnectionReason::P2pChannelMsgUnexpected(
+            crate::channels::ChannelId::BestTipPropagation,
+        );
+        assert!(!state.on_disconnect(peer_id, &reason, &timeouts(), time(0)));
+
+        state.prune(time(1));
+        assert_eq!(state.scores.len(), 1);
+        state.prune(time(MISBEHAVIOR_SCORE_DECAY.as_secs()));
+        assert!(state.scores.is_empty());
+    }
+}
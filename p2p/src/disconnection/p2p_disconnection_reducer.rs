@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use mina_core::{bug_condition, pseudo_rng, Substate};
+use mina_core::{bug_condition, pseudo_rng, warn, Substate};
 use rand::prelude::*;
 use redux::ActionWithMeta;
 
@@ -29,6 +29,7 @@ impl P2pDisconnectedState {
         match action {
             P2pDisconnectionAction::RandomTry => {
                 p2p_state.last_random_disconnection_try = meta.time();
+                p2p_state.bans.prune(meta.time());
                 if p2p_state.config.limits.max_stable_peers()
                     >= p2p_state.ready_peers_iter().count()
                 {
@@ -52,6 +53,14 @@ impl P2pDisconnectedState {
                 Ok(())
             }
             P2pDisconnectionAction::Init { peer_id, reason } => {
+                if p2p_state.bans.on_disconnect(
+                    peer_id,
+                    &reason,
+                    &p2p_state.config.timeouts,
+                    meta.time(),
+                ) {
+                    warn!(meta.time(); summary = "banned misbehaving peer", peer_id = display(peer_id), reason = display(&reason));
+                }
                 let Some(peer) = p2p_state.peers.get_mut(&peer_id) else {
                     bug_condition!("Invalid state for: `P2pDisconnectionAction::Init`");
                     return Ok(());
@@ -0,0 +1,7 @@
+mod p2p_gossip_state;
+pub use self::p2p_gossip_state::*;
+
+mod p2p_gossip_actions;
+pub use self::p2p_gossip_actions::*;
+
+mod p2p_gossip_reducer;
@@ -0,0 +1,24 @@
+use mina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::P2pState;
+
+use super::P2pGossipKind;
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(level = info, fields(display(kind)))]
+pub enum P2pGossipAction {
+    /// Stop accepting and requesting gossiped items of the given kind.
+    Pause { kind: P2pGossipKind },
+    /// Resume participation in the gossip of the given kind.
+    Resume { kind: P2pGossipKind },
+}
+
+impl redux::EnablingCondition<P2pState> for P2pGossipAction {
+    fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
+        match self {
+            P2pGossipAction::Pause { kind } => !state.is_gossip_paused(*kind),
+            P2pGossipAction::Resume { kind } => state.is_gossip_paused(*kind),
+        }
+    }
+}
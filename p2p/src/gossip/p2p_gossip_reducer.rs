@@ -0,0 +1,42 @@
+use mina_core::Substate;
+use redux::ActionWithMeta;
+
+use crate::{channels::best_tip::P2pChannelsBestTipAction, P2pState};
+
+use super::{P2pGossipAction, P2pGossipKind};
+
+impl P2pState {
+    pub fn gossip_reducer<Action, State>(
+        mut state_context: Substate<Action, State, P2pState>,
+        action: ActionWithMeta<P2pGossipAction>,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let (action, _meta) = action.split();
+        let p2p_state = state_context.get_substate_mut()?;
+
+        match action {
+            P2pGossipAction::Pause { kind } => {
+                p2p_state.paused_gossip.insert(kind);
+                Ok(())
+            }
+            P2pGossipAction::Resume { kind } => {
+                p2p_state.paused_gossip.remove(&kind);
+
+                // Transactions and snarks are requested periodically, best
+                // tips only after the previous one was received, so the
+                // requests skipped while paused have to be sent now.
+                if kind == P2pGossipKind::Block {
+                    let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                    let p2p_state: &P2pState = state.substate()?;
+                    for peer_id in p2p_state.ready_peers() {
+                        dispatcher.push(P2pChannelsBestTipAction::RequestSend { peer_id });
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
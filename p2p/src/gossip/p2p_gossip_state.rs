@@ -0,0 +1,48 @@
+use std::{fmt, str::FromStr};
+
+use mina_p2p_messages::gossip::GossipNetMessageV2;
+use serde::{Deserialize, Serialize};
+
+/// Kind of gossiped items, participation in which can be paused at runtime.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum P2pGossipKind {
+    Block,
+    Transaction,
+    Snark,
+}
+
+impl P2pGossipKind {
+    pub const ALL: [Self; 3] = [Self::Block, Self::Transaction, Self::Snark];
+
+    pub fn of_message(message: &GossipNetMessageV2) -> Self {
+        match message {
+            GossipNetMessageV2::NewState(_) => Self::Block,
+            GossipNetMessageV2::TransactionPoolDiff { .. } => Self::Transaction,
+            GossipNetMessageV2::SnarkPoolDiff { .. } => Self::Snark,
+        }
+    }
+}
+
+impl fmt::Display for P2pGossipKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Block => write!(f, "block"),
+            Self::Transaction => write!(f, "transaction"),
+            Self::Snark => write!(f, "snark"),
+        }
+    }
+}
+
+impl FromStr for P2pGossipKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.to_string() == s)
+            .ok_or_else(|| {
+                format!("unknown gossip kind `{s}`, expected block, transaction or snark")
+            })
+    }
+}
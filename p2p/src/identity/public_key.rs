@@ -10,6 +10,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::PeerId;
 
+use super::Signature;
+
 #[derive(Eq, PartialEq, Clone)]
 pub struct PublicKey(pub(super) Ed25519PublicKey);
 
@@ -31,6 +33,10 @@ impl PublicKey {
     pub fn to_x25519(&self) -> x25519_dalek::PublicKey {
         self.0.to_montgomery().to_bytes().into()
     }
+
+    pub fn verify(&self, data: &[u8], signature: &Signature) -> bool {
+        self.0.verify_strict(data, &signature.0).is_ok()
+    }
 }
 
 impl fmt::Display for PublicKey {
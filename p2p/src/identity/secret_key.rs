@@ -92,12 +92,35 @@ impl SecretKey {
         Ok(Self::from_bytes(key_bytes))
     }
 
+    /// Writes the key in the libp2p keypair file format of the OCaml node,
+    /// so that it can be loaded with [`Self::from_encrypted_file`] by either
+    /// node. Fails if the file already exists.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn to_encrypted_file(
         &self,
-        _password: &str,
-        _path: impl AsRef<Path>,
+        password: &str,
+        path: impl AsRef<Path>,
     ) -> Result<(), EncryptionError> {
-        todo!()
+        // Protobuf encoded libp2p keys: key type ed25519, followed by the key
+        // data (secret key bytes followed by public key bytes for the secret
+        // key).
+        let public_key = self.public_key().to_bytes();
+        let secret_key_proto =
+            Zeroizing::new([&[0x08, 0x01, 0x12, 0x40][..], &self.to_bytes(), &public_key].concat());
+        let public_key_proto = [&[0x08, 0x01, 0x12, 0x20][..], &public_key].concat();
+
+        let engine = base64::engine::general_purpose::STANDARD;
+        let keypair_string = Zeroizing::new(format!(
+            "{},{},{}",
+            engine.encode(&*secret_key_proto),
+            engine.encode(public_key_proto),
+            self.public_key().peer_id().to_libp2p_string(),
+        ));
+        let encrypted = Self::try_encrypt_unprefixed(keypair_string.as_bytes(), password)?;
+
+        let f = std::fs::File::create_new(path)?;
+        serde_json::to_writer(f, &encrypted)?;
+        Ok(())
     }
 }
 
@@ -300,4 +323,21 @@ mod tests {
         let peer_id = decrypted.public_key().peer_id().to_libp2p_string();
         assert_eq!(expected_peer_id, peer_id);
     }
+
+    #[test]
+    fn test_libp2p_key_encrypt_decrypt() {
+        let password = "not-very-secure-pass";
+
+        let new_key = SecretKey::rand();
+        let tmp_path = std::env::temp_dir().join(format!("{}-libp2p-key", new_key.public_key()));
+
+        new_key
+            .to_encrypted_file(password, &tmp_path)
+            .expect("Failed to encrypt secret key");
+        let decrypted = SecretKey::from_encrypted_file(&tmp_path, password);
+        let _ = std::fs::remove_file(&tmp_path);
+
+        let decrypted = decrypted.expect("Failed to decrypt secret key file");
+        assert_eq!(new_key.to_bytes(), decrypted.to_bytes());
+    }
 }
@@ -7,6 +7,7 @@ pub mod channels;
 pub mod connection;
 pub mod disconnection;
 pub mod disconnection_effectful;
+pub mod gossip;
 pub mod identity;
 use bootstrap::P2pNetworkKadBootstrapState;
 use channels::{
@@ -29,6 +30,7 @@ use connection::{
 };
 use disconnection::P2pDisconnectionAction;
 use disconnection_effectful::P2pDisconnectionEffectfulAction;
+use gossip::P2pGossipAction;
 use identify::P2pIdentifyAction;
 pub use identity::PeerId;
 use mina_core::SubstateAccess;
@@ -124,6 +126,7 @@ pub trait P2pActionTrait<State>:
     + From<P2pNetworkSchedulerAction>
     + From<P2pNetworkIdentifyStreamAction>
     + From<P2pIdentifyAction>
+    + From<P2pGossipAction>
     + From<P2pNetworkIdentifyStreamEffectfulAction>
     + From<P2pNetworkSelectAction>
     + From<P2pNetworkPnetAction>
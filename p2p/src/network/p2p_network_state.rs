@@ -46,6 +46,7 @@ impl P2pNetworkState {
                 discovery_state,
                 rpc_incoming_streams: Default::default(),
                 rpc_outgoing_streams: Default::default(),
+                incoming_throttle: Default::default(),
             },
         }
     }
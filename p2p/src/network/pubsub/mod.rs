@@ -7,7 +7,13 @@ pub use self::p2p_network_pubsub_actions::P2pNetworkPubsubAction;
 
 mod p2p_network_pubsub_state;
 pub use self::p2p_network_pubsub_state::{
-    P2pNetworkPubsubClientState, P2pNetworkPubsubClientTopicState, P2pNetworkPubsubState,
+    P2pNetworkPubsubClientState, P2pNetworkPubsubClientTopicState,
+    P2pNetworkPubsubIncompatiblePeer, P2pNetworkPubsubIncompatibleVersions, P2pNetworkPubsubState,
+};
+
+mod p2p_network_pubsub_version;
+pub use self::p2p_network_pubsub_version::{
+    decode_gossip_message, P2pNetworkPubsubDecodeError, P2pNetworkPubsubIncompatibleVersion,
 };
 
 #[cfg(feature = "p2p-libp2p")]
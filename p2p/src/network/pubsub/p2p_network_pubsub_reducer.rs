@@ -1,30 +1,30 @@
 use std::{collections::btree_map::Entry, time::Duration};
 
-use binprot::BinProtRead;
 use mina_core::{
     block::BlockWithHash, bug_condition, fuzz_maybe, fuzzed_maybe, snark::Snark, Substate,
 };
 use mina_p2p_messages::{
-    gossip::{self, GossipNetMessageV2},
-    v2::NetworkPoolSnarkPoolDiffVersionedStableV2,
+    gossip::GossipNetMessageV2, v2::NetworkPoolSnarkPoolDiffVersionedStableV2,
 };
 use redux::{Dispatcher, Timestamp};
 
 use crate::{
     channels::{snark::P2pChannelsSnarkAction, transaction::P2pChannelsTransactionAction},
     disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
+    gossip::P2pGossipKind,
     peer::P2pPeerAction,
     Data, P2pConfig, P2pNetworkYamuxAction, P2pState, PeerId,
 };
 
 use super::{
+    decode_gossip_message,
     p2p_network_pubsub_state::{
         source_from_message, P2pNetworkPubsubClientMeshAddingState,
         P2pNetworkPubsubMessageCacheMessage,
     },
     pb::{self, Message},
-    P2pNetworkPubsubAction, P2pNetworkPubsubClientState, P2pNetworkPubsubEffectfulAction,
-    P2pNetworkPubsubMessageCacheId, P2pNetworkPubsubState, TOPIC,
+    P2pNetworkPubsubAction, P2pNetworkPubsubClientState, P2pNetworkPubsubDecodeError,
+    P2pNetworkPubsubEffectfulAction, P2pNetworkPubsubMessageCacheId, P2pNetworkPubsubState, TOPIC,
 };
 
 const MAX_MESSAGE_KEEP_DURATION: Duration = Duration::from_secs(300);
@@ -199,6 +199,13 @@ impl P2pNetworkPubsubState {
                 // Check result later to ensure we always dispatch the cleanup action
                 let reduce_incoming_result =
                     pubsub_state.reduce_incoming_message(&message, seen_limit);
+                if let Err(P2pNetworkPubsubDecodeError::IncompatibleVersion(version)) =
+                    &reduce_incoming_result
+                {
+                    pubsub_state
+                        .incompatible_versions
+                        .add(peer_id, version.clone(), time);
+                }
 
                 let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
                 let p2p_state: &P2pState = global_state.substate()?;
@@ -206,7 +213,17 @@ impl P2pNetworkPubsubState {
 
                 dispatcher.push(P2pNetworkPubsubAction::IncomingMessageCleanup { peer_id });
 
-                let message_content = reduce_incoming_result?;
+                let message_content = match reduce_incoming_result {
+                    Ok(message_content) => message_content,
+                    Err(P2pNetworkPubsubDecodeError::IncompatibleVersion(version)) => {
+                        dispatcher.push(P2pNetworkPubsubAction::IgnoreMessage {
+                            message_id: None,
+                            reason: version.to_string(),
+                        });
+                        return Ok(());
+                    }
+                    Err(error) => return Err(error.to_string()),
+                };
 
                 for (topic_id, map) in &state.topics {
                     let mesh_size = map.values().filter(|s| s.on_mesh()).count();
@@ -224,6 +241,14 @@ impl P2pNetworkPubsubState {
 
                 // This happens if message was already seen
                 if let Some(message_content) = message_content {
+                    let kind = P2pGossipKind::of_message(&message_content);
+                    if p2p_state.is_gossip_paused(kind) {
+                        dispatcher.push(P2pNetworkPubsubAction::IgnoreMessage {
+                            message_id: None,
+                            reason: format!("Gossip of kind {kind} is paused"),
+                        });
+                        return Ok(());
+                    }
                     dispatcher.push(P2pNetworkPubsubAction::HandleIncomingMessage {
                         message,
                         message_content,
@@ -767,14 +792,15 @@ impl P2pNetworkPubsubState {
     ///
     /// * `Ok(Some(GossipNetMessageV2))` - Successfully processed and deserialized message
     /// * `Ok(None)` - Message was a duplicate (already seen)
-    /// * `Err(String)` - Error during processing (invalid message format or deserialization failure)
+    /// * `Err(P2pNetworkPubsubDecodeError)` - Error during processing (invalid message format,
+    ///   deserialization failure or incompatible protocol version)
     ///
     #[inline(never)]
     fn reduce_incoming_message(
         &mut self,
         message: &Message,
         seen_limit: usize,
-    ) -> Result<Option<GossipNetMessageV2>, String> {
+    ) -> Result<Option<GossipNetMessageV2>, P2pNetworkPubsubDecodeError> {
         let Some(signature) = &message.signature else {
             bug_condition!("Validation failed: missing signature");
             return Ok(None);
@@ -792,14 +818,10 @@ impl P2pNetworkPubsubState {
         }
 
         match &message.data {
-            Some(data) if data.len() > 8 => {
-                let mut slice = &data[8..];
-                Ok(Some(
-                    gossip::GossipNetMessageV2::binprot_read(&mut slice)
-                        .map_err(|e| format!("Invalid `GossipNetMessageV2` message, error: {e}"))?,
-                ))
-            }
-            _ => Err("Invalid message".to_owned()),
+            Some(data) if data.len() > 8 => Ok(Some(decode_gossip_message(&data[8..])?)),
+            _ => Err(P2pNetworkPubsubDecodeError::Invalid(
+                "Invalid message".to_owned(),
+            )),
         }
     }
 
@@ -1,4 +1,4 @@
-use super::{pb, BroadcastMessageId};
+use super::{pb, BroadcastMessageId, P2pNetworkPubsubIncompatibleVersion};
 use crate::{token::BroadcastAlgorithm, ConnectionAddr, PeerId, StreamId};
 
 use libp2p_identity::ParseError;
@@ -19,6 +19,11 @@ use malloc_size_of_derive::MallocSizeOf;
 
 pub const IWANT_TIMEOUT_DURATION: Duration = Duration::from_secs(5);
 
+/// Max number of peers for which messages of incompatible versions are
+/// counted. The peers which sent one the longest time ago are forgotten
+/// first.
+const INCOMPATIBLE_VERSIONS_MAX_PEERS: usize = 100;
+
 /// State of the P2P Network PubSub system.
 ///
 /// This struct maintains information about connected peers, message sequencing,
@@ -62,6 +67,27 @@ pub struct P2pNetworkPubsubState {
 
     /// `iwant` requests, tracking the number of times peers have expressed interest in specific messages.
     pub iwant: VecDeque<P2pNetworkPubsubIwantRequestCount>,
+
+    /// Messages received from protocol versions this node can't handle.
+    #[serde(default)]
+    #[ignore_malloc_size_of = "bounded"]
+    pub incompatible_versions: P2pNetworkPubsubIncompatibleVersions,
+}
+
+/// Messages of incompatible protocol versions, which are ignored without
+/// disconnecting the peers that sent them, as they may just have upgraded
+/// for a hard fork earlier than us.
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNetworkPubsubIncompatibleVersions {
+    pub total: u64,
+    pub by_peer: BTreeMap<PeerId, P2pNetworkPubsubIncompatiblePeer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNetworkPubsubIncompatiblePeer {
+    pub count: u64,
+    pub last_version: P2pNetworkPubsubIncompatibleVersion,
+    pub last_time: Timestamp,
 }
 
 #[derive(Default, Serialize, Deserialize, Debug, Clone, MallocSizeOf)]
@@ -71,6 +97,40 @@ pub struct P2pNetworkPubsubIwantRequestCount {
     pub count: Vec<Timestamp>,
 }
 
+impl P2pNetworkPubsubIncompatibleVersions {
+    pub fn add(
+        &mut self,
+        peer_id: PeerId,
+        version: P2pNetworkPubsubIncompatibleVersion,
+        time: Timestamp,
+    ) {
+        self.total = self.total.saturating_add(1);
+        if !self.by_peer.contains_key(&peer_id)
+            && self.by_peer.len() >= INCOMPATIBLE_VERSIONS_MAX_PEERS
+        {
+            let oldest = self
+                .by_peer
+                .iter()
+                .min_by_key(|(_, peer)| peer.last_time)
+                .map(|(peer_id, _)| *peer_id);
+            if let Some(oldest) = oldest {
+                self.by_peer.remove(&oldest);
+            }
+        }
+        let peer =
+            self.by_peer
+                .entry(peer_id)
+                .or_insert_with(|| P2pNetworkPubsubIncompatiblePeer {
+                    count: 0,
+                    last_version: version.clone(),
+                    last_time: time,
+                });
+        peer.count = peer.count.saturating_add(1);
+        peer.last_version = version;
+        peer.last_time = time;
+    }
+}
+
 impl P2pNetworkPubsubState {
     pub fn prune_peer_state(&mut self, peer_id: &PeerId) {
         self.clients.remove(peer_id);
@@ -0,0 +1,195 @@
+use binprot::BinProtRead;
+use mina_core::constants::is_protocol_version_compatible;
+use mina_p2p_messages::{gossip::GossipNetMessageV2, list::List, v2};
+use serde::{Deserialize, Serialize};
+
+/// Binprot tags of the [`GossipNetMessageV2`] variants.
+const NEW_STATE_TAG: u8 = 0;
+const SNARK_POOL_DIFF_TAG: u8 = 1;
+const TRANSACTION_POOL_DIFF_TAG: u8 = 2;
+
+#[derive(Debug, thiserror::Error)]
+pub enum P2pNetworkPubsubDecodeError {
+    #[error("Invalid `GossipNetMessageV2` message, error: {0}")]
+    Invalid(String),
+    /// Kind of message this node doesn't know. It is as invalid as any
+    /// other message which doesn't decode, as nothing tells it apart from
+    /// garbage.
+    #[error("Invalid `GossipNetMessageV2` message, unknown kind {0}")]
+    UnknownKind(u8),
+    #[error(transparent)]
+    IncompatibleVersion(#[from] P2pNetworkPubsubIncompatibleVersion),
+}
+
+/// Gossip message from a protocol version this node can't handle, e.g.
+/// sent by a peer which already upgraded for a hard fork.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum P2pNetworkPubsubIncompatibleVersion {
+    /// Block of another protocol version, which can't be applied on top of
+    /// our chain.
+    #[error("incompatible version: block of protocol version {transaction}.{network}.{patch}")]
+    Protocol {
+        transaction: u64,
+        network: u64,
+        patch: u64,
+    },
+}
+
+/// Decodes a gossip message, telling apart messages of an incompatible
+/// protocol version from invalid ones.
+pub fn decode_gossip_message(
+    data: &[u8],
+) -> Result<GossipNetMessageV2, P2pNetworkPubsubDecodeError> {
+    let mut slice = data;
+    match GossipNetMessageV2::binprot_read(&mut slice) {
+        Ok(message) => {
+            if let GossipNetMessageV2::NewState(block) = &message {
+                check_protocol_version(&block.header.current_protocol_version)?;
+            }
+            Ok(message)
+        }
+        Err(error) => match data.split_first() {
+            Some((&NEW_STATE_TAG, block)) => match peek_incompatible_version(block) {
+                Some(version) => Err(version.into()),
+                None => Err(P2pNetworkPubsubDecodeError::Invalid(error.to_string())),
+            },
+            Some((&SNARK_POOL_DIFF_TAG | &TRANSACTION_POOL_DIFF_TAG, _)) | None => {
+                Err(P2pNetworkPubsubDecodeError::Invalid(error.to_string()))
+            }
+            Some((&tag, _)) => Err(P2pNetworkPubsubDecodeError::UnknownKind(tag)),
+        },
+    }
+}
+
+fn check_protocol_version(
+    version: &v2::ProtocolVersionStableV2,
+) -> Result<(), P2pNetworkPubsubIncompatibleVersion> {
+    if is_protocol_version_compatible(version) {
+        return Ok(());
+    }
+    Err(P2pNetworkPubsubIncompatibleVersion::Protocol {
+        transaction: version.transaction.as_u64(),
+        network: version.network.as_u64(),
+        patch: version.patch.as_u64(),
+    })
+}
+
+/// Version of a block which doesn't decode as a whole. Only the start of
+/// its header up to the protocol version is read, so a later version
+/// changing anything after it is still recognized.
+fn peek_incompatible_version(mut block: &[u8]) -> Option<P2pNetworkPubsubIncompatibleVersion> {
+    v2::MinaStateProtocolStateValueStableV2::binprot_read(&mut block).ok()?;
+    v2::MinaBaseProofStableV2::binprot_read(&mut block).ok()?;
+    <(v2::StateHash, List<v2::StateBodyHash>)>::binprot_read(&mut block).ok()?;
+    let version = v2::ProtocolVersionStableV2::binprot_read(&mut block).ok()?;
+    check_protocol_version(&version).err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use binprot::BinProtWrite;
+    use mina_core::constants::PROTOCOL_VERSION;
+
+    const NEW_STATE: &[u8] =
+        include_bytes!("../../../../mina-p2p-messages/tests/files/v2/gossip/new_state.bin");
+
+    /// The block of [`NEW_STATE`] with its protocol version set to
+    /// `version`, encoded.
+    fn new_state(version: v2::ProtocolVersionStableV2) -> Vec<u8> {
+        let mut slice = NEW_STATE;
+        let GossipNetMessageV2::NewState(mut block) =
+            GossipNetMessageV2::binprot_read(&mut slice).unwrap()
+        else {
+            panic!("not a block");
+        };
+        Arc::make_mut(&mut block).header.current_protocol_version = version;
+        let mut data = Vec::new();
+        GossipNetMessageV2::NewState(block)
+            .binprot_write(&mut data)
+            .unwrap();
+        data
+    }
+
+    fn next_transaction_version() -> v2::ProtocolVersionStableV2 {
+        v2::ProtocolVersionStableV2 {
+            transaction: (PROTOCOL_VERSION.transaction.as_u64() + 1).into(),
+            ..PROTOCOL_VERSION
+        }
+    }
+
+    #[test]
+    fn unknown_kind_is_invalid() {
+        for data in [&[3][..], &[7, 1, 2, 3][..], &[u8::MAX][..]] {
+            let result = decode_gossip_message(data);
+            assert!(
+                matches!(
+                    result,
+                    Err(P2pNetworkPubsubDecodeError::UnknownKind(tag)) if tag == data[0]
+                ),
+                "{result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn block_of_compatible_version_is_decoded() {
+        let version = v2::ProtocolVersionStableV2 {
+            patch: (PROTOCOL_VERSION.patch.as_u64() + 1).into(),
+            ..PROTOCOL_VERSION
+        };
+        let result = decode_gossip_message(&new_state(version));
+        assert!(
+            matches!(result, Ok(GossipNetMessageV2::NewState(_))),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn block_of_incompatible_version_is_incompatible() {
+        let expected = P2pNetworkPubsubIncompatibleVersion::Protocol {
+            transaction: PROTOCOL_VERSION.transaction.as_u64() + 1,
+            network: PROTOCOL_VERSION.network.as_u64(),
+            patch: PROTOCOL_VERSION.patch.as_u64(),
+        };
+        let data = new_state(next_transaction_version());
+        let result = decode_gossip_message(&data);
+        assert!(
+            matches!(
+                &result,
+                Err(P2pNetworkPubsubDecodeError::IncompatibleVersion(version)) if version == &expected
+            ),
+            "{result:?}"
+        );
+
+        // A later version may change the block after its protocol version,
+        // so that it doesn't decode.
+        let truncated = &data[..data.len() - 1];
+        let result = decode_gossip_message(truncated);
+        assert!(
+            matches!(
+                &result,
+                Err(P2pNetworkPubsubDecodeError::IncompatibleVersion(version)) if version == &expected
+            ),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn truncated_message_is_invalid() {
+        for data in [
+            &[][..],
+            &[NEW_STATE_TAG][..],
+            &[TRANSACTION_POOL_DIFF_TAG, 1][..],
+        ] {
+            let result = decode_gossip_message(data);
+            assert!(matches!(
+                result,
+                Err(P2pNetworkPubsubDecodeError::Invalid(_))
+            ));
+        }
+    }
+}
@@ -4,5 +4,8 @@ pub use self::p2p_network_scheduler_actions::*;
 mod p2p_network_scheduler_state;
 pub use self::p2p_network_scheduler_state::*;
 
+mod p2p_network_incoming_throttle_state;
+pub use self::p2p_network_incoming_throttle_state::*;
+
 #[cfg(feature = "p2p-libp2p")]
 mod p2p_network_scheduler_reducer;
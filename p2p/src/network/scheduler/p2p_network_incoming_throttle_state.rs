@@ -0,0 +1,185 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    net::IpAddr,
+};
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{P2pLimits, P2pTimeouts};
+
+/// Protection of the accept path against connection floods.
+///
+/// Counts the connections accepted from each IP address within a window,
+/// addresses exceeding the limit are greylisted, so that their connections
+/// are refused right after being accepted, before any handshake work.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct P2pNetworkIncomingThrottleState {
+    /// Times of the connections accepted from each address, within the
+    /// rate window, oldest first.
+    attempts: BTreeMap<IpAddr, VecDeque<Timestamp>>,
+    /// Addresses that are refused, with the time until which they are.
+    greylist: BTreeMap<IpAddr, Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum P2pNetworkIncomingThrottleReason {
+    #[error("address is greylisted")]
+    Greylisted,
+    #[error("too many connections from the address")]
+    RateExceeded,
+    #[error("too many handshakes in progress")]
+    TooManyHandshakes,
+}
+
+impl P2pNetworkIncomingThrottleState {
+    /// Registers a connection accepted from `ip`. Returns the reason to
+    /// refuse it, if it must be.
+    ///
+    /// `handshakes` is the number of incoming connections which haven't
+    /// completed the handshake yet.
+    pub fn on_accept(
+        &mut self,
+        ip: IpAddr,
+        handshakes: usize,
+        limits: &P2pLimits,
+        timeouts: &P2pTimeouts,
+        now: Timestamp,
+    ) -> Result<(), P2pNetworkIncomingThrottleReason> {
+        self.prune(timeouts, now);
+
+        if !ip.is_loopback() {
+            if self.greylist.contains_key(&ip) {
+                return Err(P2pNetworkIncomingThrottleReason::Greylisted);
+            }
+
+            if timeouts.incoming_connection_rate_window.is_some() {
+                let attempts = self.attempts.entry(ip).or_default();
+                attempts.push_back(now);
+                if attempts.len() > limits.max_incoming_per_ip() {
+                    self.attempts.remove(&ip);
+                    if let Some(greylist) = timeouts.incoming_connection_greylist {
+                        self.greylist.insert(ip, now + greylist);
+                    }
+                    return Err(P2pNetworkIncomingThrottleReason::RateExceeded);
+                }
+            }
+        }
+
+        if handshakes >= limits.max_incoming_handshakes() {
+            return Err(P2pNetworkIncomingThrottleReason::TooManyHandshakes);
+        }
+        Ok(())
+    }
+
+    pub fn is_greylisted(&self, ip: &IpAddr, now: Timestamp) -> bool {
+        self.greylist.get(ip).is_some_and(|until| *until > now)
+    }
+
+    /// Greylisted addresses, with the time until which they are.
+    pub fn greylist(&self) -> impl Iterator<Item = (&IpAddr, &Timestamp)> {
+        self.greylist.iter()
+    }
+
+    fn prune(&mut self, timeouts: &P2pTimeouts, now: Timestamp) {
+        self.greylist.retain(|_, until| *until > now);
+
+        let Some(window) = timeouts.incoming_connection_rate_window else {
+            self.attempts.clear();
+            return;
+        };
+        self.attempts.retain(|_, attempts| {
+            while attempts
+                .front()
+                .is_some_and(|time| now.checked_sub(*time).is_none_or(|age| age >= window))
+            {
+                attempts.pop_front();
+            }
+            !attempts.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::Ipv4Addr, time::Duration};
+
+    use super::*;
+
+    fn time(secs: u64) -> Timestamp {
+        Timestamp::ZERO + Duration::from_secs(secs)
+    }
+
+    fn config() -> (P2pLimits, P2pTimeouts) {
+        let limits = P2pLimits::default()
+            .with_max_incoming_per_ip(2)
+            .with_max_incoming_handshakes(3);
+        let timeouts = P2pTimeouts {
+            incoming_connection_rate_window: Some(Duration::from_secs(10)),
+            incoming_connection_greylist: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+        (limits, timeouts)
+    }
+
+    #[test]
+    fn test_rate_exceeded_is_greylisted() {
+        let (limits, timeouts) = config();
+        let mut state = P2pNetworkIncomingThrottleState::default();
+        let ip = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1));
+        let other = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 2));
+
+        assert_eq!(state.on_accept(ip, 0, &limits, &timeouts, time(0)), Ok(()));
+        assert_eq!(state.on_accept(ip, 0, &limits, &timeouts, time(1)), Ok(()));
+        assert_eq!(
+            state.on_accept(ip, 0, &limits, &timeouts, time(2)),
+            Err(P2pNetworkIncomingThrottleReason::RateExceeded)
+        );
+        assert!(state.is_greylisted(&ip, time(2)));
+        assert_eq!(
+            state.on_accept(ip, 0, &limits, &timeouts, time(30)),
+            Err(P2pNetworkIncomingThrottleReason::Greylisted)
+        );
+        assert_eq!(
+            state.on_accept(other, 0, &limits, &timeouts, time(30)),
+            Ok(())
+        );
+
+        // The greylist expired.
+        assert_eq!(state.on_accept(ip, 0, &limits, &timeouts, time(62)), Ok(()));
+        assert!(!state.is_greylisted(&ip, time(62)));
+    }
+
+    #[test]
+    fn test_rate_window() {
+        let (limits, timeouts) = config();
+        let mut state = P2pNetworkIncomingThrottleState::default();
+        let ip = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1));
+
+        for secs in [0, 5, 10, 15, 20, 25] {
+            assert_eq!(
+                state.on_accept(ip, 0, &limits, &timeouts, time(secs)),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn test_handshakes_and_loopback() {
+        let (limits, timeouts) = config();
+        let mut state = P2pNetworkIncomingThrottleState::default();
+        let ip = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1));
+
+        assert_eq!(
+            state.on_accept(ip, 3, &limits, &timeouts, time(0)),
+            Err(P2pNetworkIncomingThrottleReason::TooManyHandshakes)
+        );
+
+        for _ in 0..10 {
+            assert_eq!(
+                state.on_accept(Ipv4Addr::LOCALHOST.into(), 0, &limits, &timeouts, time(0)),
+                Ok(())
+            );
+        }
+    }
+}
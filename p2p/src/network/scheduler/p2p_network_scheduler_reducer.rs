@@ -80,6 +80,33 @@ impl P2pNetworkSchedulerState {
                 Ok(())
             }
             P2pNetworkSchedulerAction::IncomingDidAccept { addr, result } => {
+                let mut state_context =
+                    Substate::<Action, State, P2pState>::from_compatible_substate(state_context);
+                let p2p_state = state_context.get_substate_mut()?;
+                let scheduler_state = &mut p2p_state.network.scheduler;
+
+                if let (Some(addr), Ok(())) = (addr, &result) {
+                    let handshakes = scheduler_state
+                        .connections
+                        .values()
+                        .filter(|conn| conn.incoming && conn.mux.is_none() && conn.closed.is_none())
+                        .count();
+                    if let Err(reason) = scheduler_state.incoming_throttle.on_accept(
+                        addr.sock_addr.ip(),
+                        handshakes,
+                        &p2p_state.config.limits,
+                        &p2p_state.config.timeouts,
+                        meta.time(),
+                    ) {
+                        let dispatcher = state_context.into_dispatcher();
+                        dispatcher.push(P2pNetworkSchedulerEffectfulAction::IncomingDidRefuse {
+                            addr,
+                            reason,
+                        });
+                        return Ok(());
+                    }
+                }
+
                 if let Some(addr) = addr {
                     scheduler_state.connections.insert(
                         addr,
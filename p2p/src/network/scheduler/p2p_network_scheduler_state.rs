@@ -61,6 +61,8 @@ pub struct P2pNetworkSchedulerState {
     pub discovery_state: Option<P2pNetworkKadState>,
     pub rpc_incoming_streams: StreamState<P2pNetworkRpcState>,
     pub rpc_outgoing_streams: StreamState<P2pNetworkRpcState>,
+    #[serde(default)]
+    pub incoming_throttle: P2pNetworkIncomingThrottleState,
 }
 
 impl P2pNetworkSchedulerState {
@@ -3,7 +3,9 @@ use std::net::{IpAddr, SocketAddr};
 use mina_core::ActionEvent;
 use serde::{Deserialize, Serialize};
 
-use crate::{ConnectionAddr, P2pNetworkConnectionCloseReason, P2pState};
+use crate::{
+    ConnectionAddr, P2pNetworkConnectionCloseReason, P2pNetworkIncomingThrottleReason, P2pState,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
 #[action_event(fields(display(ip), display(listener), display(addr), debug(result), select_kind = debug(kind), display(error)))]
@@ -21,6 +23,12 @@ pub enum P2pNetworkSchedulerEffectfulAction {
         addr: ConnectionAddr,
         result: Result<(), String>,
     },
+    /// Accepted incoming connection is refused by the flood protection.
+    #[action_event(level = debug, fields(display(addr), display(reason)))]
+    IncomingDidRefuse {
+        addr: ConnectionAddr,
+        reason: P2pNetworkIncomingThrottleReason,
+    },
     /// Initialize outgoing connection.
     OutgoingConnect {
         addr: SocketAddr,
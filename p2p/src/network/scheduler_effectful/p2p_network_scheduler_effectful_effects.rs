@@ -35,6 +35,9 @@ impl P2pNetworkSchedulerEffectfulAction {
                     incoming: true,
                 });
             }
+            P2pNetworkSchedulerEffectfulAction::IncomingDidRefuse { addr, .. } => {
+                store.service().send_mio_cmd(MioCmd::Disconnect(addr));
+            }
             P2pNetworkSchedulerEffectfulAction::OutgoingConnect { addr } => {
                 store.service().send_mio_cmd(MioCmd::Connect(addr));
             }
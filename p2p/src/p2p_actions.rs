@@ -9,8 +9,8 @@ use crate::{
 
 use super::{
     channels::P2pChannelsAction, connection::P2pConnectionAction,
-    disconnection::P2pDisconnectionAction, identify::P2pIdentifyAction, network::P2pNetworkAction,
-    peer::P2pPeerAction, P2pState,
+    disconnection::P2pDisconnectionAction, gossip::P2pGossipAction, identify::P2pIdentifyAction,
+    network::P2pNetworkAction, peer::P2pPeerAction, P2pState,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone, derive_more::From, ActionEvent)]
@@ -21,6 +21,7 @@ pub enum P2pAction {
     Identify(P2pIdentifyAction),
     Channels(P2pChannelsAction),
     Peer(P2pPeerAction),
+    Gossip(P2pGossipAction),
     Network(P2pNetworkAction),
 }
 
@@ -57,6 +58,7 @@ impl redux::EnablingCondition<crate::P2pState> for P2pAction {
             P2pAction::Channels(a) => a.is_enabled(state, time),
             P2pAction::Peer(a) => a.is_enabled(state, time),
             P2pAction::Identify(a) => a.is_enabled(state, time),
+            P2pAction::Gossip(a) => a.is_enabled(state, time),
             P2pAction::Network(a) => a.is_enabled(state, time),
         }
     }
@@ -3,7 +3,10 @@ use std::{collections::BTreeSet, net::IpAddr, time::Duration};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    channels::ChannelId, connection::outgoing::P2pConnectionOutgoingInitOpts, identity::PublicKey,
+    channels::ChannelId,
+    connection::outgoing::{P2pConnectionOutgoingInitOpts, P2pDnsaddr},
+    identity::PublicKey,
+    PeerId,
 };
 
 pub const DEVNET_SEEDS: &[&str] = &[
@@ -20,8 +23,18 @@ pub struct P2pConfig {
     pub listen_port: Option<u16>,
     /// The public key used for authentication all p2p communication.
     pub identity_pub_key: PublicKey,
+    /// Peer ids this node used before its key was rotated. Peers may still
+    /// advertise them for a while, they are recognized as this node so
+    /// that they aren't dialed or stored as other peers, and webrtc offers
+    /// targeting them are accepted.
+    #[serde(default)]
+    pub previous_peer_ids: Vec<PeerId>,
     /// A list addresses of seed nodes.
     pub initial_peers: Vec<P2pConnectionOutgoingInitOpts>,
+    /// `/dnsaddr` domains listing seed nodes. They are resolved by the
+    /// state machine, and their peers added to [`Self::initial_peers`].
+    #[serde(default)]
+    pub initial_dnsaddrs: Vec<P2pDnsaddr>,
     /// External addresses
     pub external_addrs: Vec<IpAddr>,
 
@@ -78,6 +91,16 @@ pub struct P2pTimeouts {
     pub kademlia_initial_bootstrap: Option<Duration>,
     pub select: Option<Duration>,
     pub pnet: Option<Duration>,
+    /// Window in which incoming connections from the same address are
+    /// counted against [`P2pLimits::max_incoming_per_ip`].
+    pub incoming_connection_rate_window: Option<Duration>,
+    /// For how long an address exceeding the incoming connection rate is
+    /// refused.
+    pub incoming_connection_greylist: Option<Duration>,
+    /// For how long a misbehaving peer is banned, see
+    /// [`crate::disconnection::P2pDisconnectionBansState`]. Never if `None`,
+    /// the default.
+    pub misbehavior_ban: Option<Duration>,
 }
 
 fn from_env_or(name: &str, default: Option<Duration>) -> Option<Duration> {
@@ -134,6 +157,15 @@ impl Default for P2pTimeouts {
             ),
             select: from_env_or("SELECT_TIMEOUT", Some(Duration::from_secs(5))),
             pnet: from_env_or("PNET_TIMEOUT", Some(Duration::from_secs(2))),
+            incoming_connection_rate_window: from_env_or(
+                "INCOMING_CONNECTION_RATE_WINDOW",
+                Some(Duration::from_secs(60)),
+            ),
+            incoming_connection_greylist: from_env_or(
+                "INCOMING_CONNECTION_GREYLIST",
+                Some(Duration::from_secs(10 * 60)),
+            ),
+            misbehavior_ban: from_env_or("MISBEHAVIOR_BAN", None),
         }
     }
 }
@@ -253,6 +285,9 @@ pub struct P2pLimits {
     max_streams: Limit<usize>,
     yamux_message_size: Limit<usize>,
     yamux_pending_outgoing_per_peer: Limit<usize>,
+    max_incoming_handshakes: Limit<usize>,
+    max_incoming_per_ip: Limit<usize>,
+    snarks_per_message: Limit<usize>,
 
     identify_message: Limit<usize>,
     kademlia_request: Limit<usize>,
@@ -327,6 +362,31 @@ impl P2pLimits {
         with_yamux_pending_outgoing_per_peer
     );
 
+    limit!(
+        /// Maximum number of incoming connections that haven't completed the
+        /// handshake yet. Connections above it are refused.
+        max_incoming_handshakes,
+        /// Sets the maximum number of concurrent incoming handshakes.
+        with_max_incoming_handshakes
+    );
+    limit!(
+        /// Maximum number of connections accepted from one IP address within
+        /// [`P2pTimeouts::incoming_connection_rate_window`]. Addresses
+        /// exceeding it are greylisted. Loopback addresses aren't limited.
+        max_incoming_per_ip,
+        /// Sets the maximum number of incoming connections from one IP
+        /// address within the rate window.
+        with_max_incoming_per_ip
+    );
+    limit!(
+        /// Maximum number of snarks sent to a WebRTC peer in one message.
+        /// Batches can't be decoded by peers running older versions, so
+        /// only raise it above 1 once they've upgraded.
+        snarks_per_message,
+        /// Sets the maximum number of snarks sent in one message.
+        with_snarks_per_message
+    );
+
     limit!(
         /// Minimum number of peers.
         min_peers(&self): self.max_peers.map(|v| (v / 2).max(3).min(v))
@@ -393,6 +453,9 @@ impl Default for P2pLimits {
         let max_streams = Limit::Some(10);
         // 256 MiB
         let yamux_message_size = Limit::Some(0x10000000);
+        let max_incoming_handshakes = Limit::Some(50);
+        let max_incoming_per_ip = Limit::Some(30);
+        let snarks_per_message = Limit::Some(1);
 
         let identify_message = Limit::Some(0x1000);
         let kademlia_request = Limit::Some(50);
@@ -413,6 +476,9 @@ impl Default for P2pLimits {
             max_streams,
             yamux_message_size,
             yamux_pending_outgoing_per_peer: rpc_get_staged_ledger,
+            max_incoming_handshakes,
+            max_incoming_per_ip,
+            snarks_per_message,
 
             identify_message,
             kademlia_request,
@@ -1,6 +1,7 @@
 use std::{
     fmt,
     net::{IpAddr, SocketAddr},
+    time::Duration,
 };
 
 use derive_more::From;
@@ -15,8 +16,11 @@ use crate::{
         transaction::TransactionPropagationChannelMsg,
         ChannelId, ChannelMsg, MsgId,
     },
-    connection::P2pConnectionResponse,
-    webrtc::ConnectionAuthEncrypted,
+    connection::{
+        outgoing::{P2pConnectionOutgoingInitOpts, P2pDnsaddr},
+        P2pConnectionResponse,
+    },
+    webrtc::{ConnectionAuthEncrypted, ConnectionStats},
     ConnectionAddr, PeerId,
 };
 
@@ -63,11 +67,21 @@ pub enum MioEvent {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum P2pConnectionEvent {
+    /// The host of a libp2p peer is resolved.
+    HostResolved(PeerId, Result<IpAddr, String>),
+    /// The `/dnsaddr` domain is resolved to the listed peers, and the time
+    /// its records are valid for.
+    DnsaddrResolved(
+        P2pDnsaddr,
+        Result<(Vec<P2pConnectionOutgoingInitOpts>, Duration), String>,
+    ),
     OfferSdpReady(PeerId, Result<String, String>),
     AnswerSdpReady(PeerId, Result<String, String>),
     AnswerReceived(PeerId, P2pConnectionResponse),
     Finalized(PeerId, Result<ConnectionAuthEncrypted, String>),
     Closed(PeerId),
+    /// Periodic quality metrics of a ready WebRTC connection.
+    Stats(PeerId, ConnectionStats),
 }
 
 #[derive(Serialize, Deserialize, From, Debug, Clone)]
@@ -75,6 +89,10 @@ pub enum P2pChannelEvent {
     Opened(PeerId, ChannelId, Result<(), String>),
     Sent(PeerId, ChannelId, MsgId, Result<(), String>),
     Received(PeerId, Result<ChannelMsg, String>),
+    /// Messages received on a propagation channel which the service dropped
+    /// without handling them, as it was overloaded. Holds the number of
+    /// items (transactions, snarks, ...) they carried.
+    Shed(PeerId, ChannelId, usize),
     Closed(PeerId, ChannelId),
 }
 
@@ -100,6 +118,12 @@ impl fmt::Display for P2pConnectionEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Connection, ")?;
         match self {
+            Self::HostResolved(peer_id, res) => {
+                write!(f, "HostResolved, {peer_id}, {}", res_kind(res))
+            }
+            Self::DnsaddrResolved(addr, res) => {
+                write!(f, "DnsaddrResolved, {addr}, {}", res_kind(res))
+            }
             Self::OfferSdpReady(peer_id, res) => {
                 write!(f, "OfferSdpReady, {peer_id}, {}", res_kind(res))
             }
@@ -122,6 +146,7 @@ impl fmt::Display for P2pConnectionEvent {
             },
             Self::Finalized(peer_id, res) => write!(f, "Finalized, {peer_id}, {}", res_kind(res)),
             Self::Closed(peer_id) => write!(f, "Closed, {peer_id}"),
+            Self::Stats(peer_id, _) => write!(f, "Stats, {peer_id}"),
         }
     }
 }
@@ -142,6 +167,9 @@ impl fmt::Display for P2pChannelEvent {
             Self::Closed(peer_id, chan_id) => {
                 write!(f, "Closed, {peer_id}, {chan_id:?}")
             }
+            Self::Shed(peer_id, chan_id, count) => {
+                write!(f, "Shed, {peer_id}, {chan_id:?}, {count}")
+            }
             Self::Sent(peer_id, chan_id, msg_id, res) => {
                 write!(
                     f,
@@ -220,6 +248,9 @@ impl fmt::Display for P2pChannelEvent {
                             snark.prover,
                             snark.job_id
                         ),
+                        SnarkPropagationChannelMsg::Snarks(snarks) => {
+                            write!(f, "Snarks, count: {}", snarks.len())
+                        }
                     },
                     ChannelMsg::SnarkJobCommitmentPropagation(v) => match v {
                         SnarkJobCommitmentPropagationChannelMsg::GetNext { limit } => {
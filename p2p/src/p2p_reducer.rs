@@ -48,6 +48,9 @@ impl P2pState {
             P2pAction::Channels(action) => {
                 P2pChannelsState::reducer(state_context, meta.with_action(action))
             }
+            P2pAction::Gossip(action) => {
+                P2pState::gossip_reducer(state_context, meta.with_action(action))
+            }
             P2pAction::Identify(_action) => {
                 #[cfg(feature = "p2p-libp2p")]
                 Self::identify_reducer(state_context, meta.with_action(_action))?;
@@ -84,6 +87,7 @@ impl P2pState {
         dispatcher.push(P2pConnectionOutgoingAction::RandomInit);
         dispatcher.push(P2pDisconnectionAction::RandomTry);
 
+        state.p2p_resolve_dnsaddrs(dispatcher, time);
         state.p2p_connect_initial_peers(dispatcher);
         state.p2p_try_reconnect_disconnected_peers(dispatcher, time)?;
         state.p2p_discovery(dispatcher, time)?;
@@ -156,6 +160,24 @@ impl P2pState {
         Ok(())
     }
 
+    fn p2p_resolve_dnsaddrs<State, Action>(
+        &self,
+        dispatcher: &mut Dispatcher<Action, State>,
+        time: Timestamp,
+    ) where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        self.dnsaddrs
+            .iter()
+            .filter(|dnsaddr| dnsaddr.should_resolve(time, self.dnsaddr_peer_failed(dnsaddr)))
+            .for_each(|dnsaddr| {
+                dispatcher.push(P2pConnectionOutgoingAction::DnsaddrResolveInit {
+                    addr: dnsaddr.addr.clone(),
+                });
+            });
+    }
+
     fn p2p_connect_initial_peers<State, Action>(&self, dispatcher: &mut Dispatcher<Action, State>)
     where
         State: crate::P2pStateTrait,
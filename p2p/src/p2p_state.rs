@@ -21,32 +21,50 @@ use crate::{
     bootstrap::P2pNetworkKadBootstrapState,
     channels::{
         rpc::{P2pRpcId, P2pRpcRequest, P2pRpcResponse},
-        streaming_rpc::{P2pStreamingRpcId, P2pStreamingRpcResponseFull},
+        streaming_rpc::{P2pStreamingRpcId, P2pStreamingRpcRequest, P2pStreamingRpcResponseFull},
         ChannelId, P2pChannelsState,
     },
     connection::{
         incoming::P2pConnectionIncomingState,
         outgoing::{
-            P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts, P2pConnectionOutgoingState,
+            P2pConnectionOutgoingError, P2pConnectionOutgoingInitLibp2pOpts,
+            P2pConnectionOutgoingInitOpts, P2pConnectionOutgoingState, P2pDnsaddr, P2pDnsaddrState,
+            P2pDnsaddrStatus,
         },
         P2pConnectionResponse, P2pConnectionState,
     },
+    disconnection::P2pDisconnectionBansState,
+    gossip::P2pGossipKind,
     is_time_passed,
     network::{
         identify::{P2pNetworkIdentify, P2pNetworkIdentifyState},
         P2pNetworkState,
     },
+    webrtc::{ConnectionStats, Host},
     Limit, P2pConfig, P2pLimits, P2pNetworkKadState, P2pNetworkPubsubMessageCacheId,
     P2pNetworkPubsubState, P2pNetworkSchedulerState, P2pTimeouts, PeerId,
 };
 use mina_p2p_messages::v2;
 
+/// Max number of peers sent in response to `get_some_initial_peers`.
+pub const INITIAL_PEERS_RESPONSE_MAX: usize = 64;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct P2pState {
     pub chain_id: ChainId,
     pub config: P2pConfig,
     pub network: P2pNetworkState,
     pub peers: BTreeMap<PeerId, P2pPeerState>,
+    /// Gossip kinds the node currently doesn't take part in.
+    #[serde(default)]
+    pub paused_gossip: BTreeSet<P2pGossipKind>,
+    /// Misbehavior scores and bans of the peers.
+    #[serde(default)]
+    pub bans: P2pDisconnectionBansState,
+    /// Resolution of the `/dnsaddr` domains of
+    /// [`P2pConfig::initial_dnsaddrs`].
+    #[serde(default)]
+    pub dnsaddrs: Vec<P2pDnsaddrState>,
 
     pub last_random_disconnection_try: redux::Timestamp,
 
@@ -95,6 +113,13 @@ impl P2pState {
             Vec::new()
         };
 
+        let dnsaddrs = config
+            .initial_dnsaddrs
+            .iter()
+            .cloned()
+            .map(P2pDnsaddrState::new)
+            .collect();
+
         let network = P2pNetworkState::new(
             config.identity_pub_key.clone(),
             addrs,
@@ -107,6 +132,9 @@ impl P2pState {
             config,
             network,
             peers: Default::default(),
+            paused_gossip: Default::default(),
+            bans: Default::default(),
+            dnsaddrs,
 
             last_random_disconnection_try: redux::Timestamp::ZERO,
 
@@ -118,6 +146,21 @@ impl P2pState {
         self.config.identity_pub_key.peer_id()
     }
 
+    /// Whether `peer_id` is banned for misbehaving, see
+    /// [`P2pDisconnectionBansState`].
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.bans.is_banned(peer_id)
+    }
+
+    /// Whether `peer_id` is this node's current or a previous peer id.
+    pub fn is_my_id(&self, peer_id: &PeerId) -> bool {
+        *peer_id == self.my_id() || self.config.previous_peer_ids.contains(peer_id)
+    }
+
+    pub fn is_gossip_paused(&self, kind: P2pGossipKind) -> bool {
+        self.paused_gossip.contains(&kind)
+    }
+
     pub fn peer_connection_rpc_id(&self, peer_id: &PeerId) -> Option<RpcId> {
         self.peers.get(peer_id)?.connection_rpc_id()
     }
@@ -134,6 +177,35 @@ impl P2pState {
         self.peers.get_mut(peer_id)?.status.as_ready_mut()
     }
 
+    pub fn dnsaddr(&self, addr: &P2pDnsaddr) -> Option<&P2pDnsaddrState> {
+        self.dnsaddrs.iter().find(|dnsaddr| dnsaddr.addr == *addr)
+    }
+
+    pub fn dnsaddr_mut(&mut self, addr: &P2pDnsaddr) -> Option<&mut P2pDnsaddrState> {
+        self.dnsaddrs
+            .iter_mut()
+            .find(|dnsaddr| dnsaddr.addr == *addr)
+    }
+
+    /// Whether connecting to one of the peers listed by `dnsaddr` failed
+    /// since it was resolved.
+    pub fn dnsaddr_peer_failed(&self, dnsaddr: &P2pDnsaddrState) -> bool {
+        let P2pDnsaddrStatus::Resolved {
+            time: resolved_at, ..
+        } = dnsaddr.status
+        else {
+            return false;
+        };
+        dnsaddr.peers.iter().any(|peer_id| {
+            self.peers
+                .get(peer_id)
+                .and_then(|peer| peer.status.as_connecting()?.as_outgoing())
+                .is_some_and(|s| {
+                    matches!(s, P2pConnectionOutgoingState::Error { time, .. } if *time >= resolved_at)
+                })
+        })
+    }
+
     pub fn any_ready_peers(&self) -> bool {
         self.peers
             .iter()
@@ -141,13 +213,16 @@ impl P2pState {
     }
 
     pub fn disconnected_peers(&self) -> impl '_ + Iterator<Item = P2pConnectionOutgoingInitOpts> {
-        self.peers.iter().filter_map(|(_, state)| {
+        self.peers.iter().filter_map(|(peer_id, state)| {
             if let P2pPeerState {
                 status: P2pPeerStatus::Disconnected { .. },
                 dial_opts: Some(opts),
                 ..
             } = state
             {
+                if self.is_banned(peer_id) {
+                    return None;
+                }
                 Some(opts.clone())
             } else {
                 None
@@ -168,6 +243,42 @@ impl P2pState {
             .filter(|(_, p)| p.channels.rpc.can_send_request())
     }
 
+    /// Addresses to share with `requester` in response to its
+    /// `get_some_initial_peers` request.
+    ///
+    /// Ready peers come first, as they are known to be reachable, followed by
+    /// the disconnected peers we didn't fail to connect to. A libp2p
+    /// requester can only dial libp2p addresses, so the others are left out
+    /// before the response is limited to [`INITIAL_PEERS_RESPONSE_MAX`].
+    /// Loopback and private addresses are only shared with a requester
+    /// connected from such an address, which may be on the same network.
+    pub fn initial_peers_for(&self, requester_id: &PeerId) -> Vec<P2pConnectionOutgoingInitOpts> {
+        let requester = self.peers.get(requester_id);
+        let libp2p_only = requester.is_some_and(|requester| requester.is_libp2p);
+        let include_local = requester
+            .and_then(|requester| requester.dial_opts.as_ref())
+            .is_some_and(|opts| opts.is_local());
+        let (ready, others): (Vec<_>, Vec<_>) = self
+            .peers
+            .iter()
+            .filter(|(peer_id, _)| *peer_id != requester_id)
+            .filter(|(_, peer)| {
+                matches!(
+                    peer.status,
+                    P2pPeerStatus::Ready(_) | P2pPeerStatus::Disconnected { .. }
+                )
+            })
+            .partition(|(_, peer)| peer.status.as_ready().is_some());
+
+        ready
+            .into_iter()
+            .chain(others)
+            .filter_map(|(peer_id, peer)| peer.advertised_dial_opts(*peer_id, include_local))
+            .filter(|opts| !libp2p_only || matches!(opts, P2pConnectionOutgoingInitOpts::LibP2P(_)))
+            .take(INITIAL_PEERS_RESPONSE_MAX)
+            .collect()
+    }
+
     pub fn ready_peers(&self) -> Vec<PeerId> {
         self.peers
             .iter()
@@ -294,14 +405,24 @@ impl P2pState {
             incoming: false,
         } = conn_id
         {
-            self.peers
-                .iter()
-                .find(|(_, peer_state)| match &peer_state.dial_opts {
-                    Some(P2pConnectionOutgoingInitOpts::LibP2P(libp2p_opts)) => {
-                        libp2p_opts.matches_socket_addr(sock_addr)
-                    }
-                    _ => false,
-                })
+            self.peers.iter().find(|(_, peer_state)| {
+                // The dial options keep the name of the host, the
+                // connection state has its resolved address.
+                let connecting_opts = peer_state
+                    .status
+                    .as_connecting()
+                    .and_then(|s| s.as_outgoing())
+                    .and_then(|s| s.opts());
+                [peer_state.dial_opts.as_ref(), connecting_opts]
+                    .into_iter()
+                    .flatten()
+                    .any(|opts| match opts {
+                        P2pConnectionOutgoingInitOpts::LibP2P(libp2p_opts) => {
+                            libp2p_opts.matches_socket_addr(sock_addr)
+                        }
+                        _ => false,
+                    })
+            })
         } else {
             None
         };
@@ -366,6 +487,40 @@ impl P2pPeerState {
         }
     }
 
+    /// Address other nodes can use to connect to this peer.
+    ///
+    /// If the peer connected to us, `dial_opts` has the port of that
+    /// connection, so the listen address the peer advertised with identify
+    /// is used instead, preferring a public one. Loopback and private
+    /// addresses, see [`Host::is_local`], are left out unless
+    /// `include_local` is set.
+    pub fn advertised_dial_opts(
+        &self,
+        peer_id: PeerId,
+        include_local: bool,
+    ) -> Option<P2pConnectionOutgoingInitOpts> {
+        let listen_addr = self.identify.as_ref().and_then(|identify| {
+            let mut addrs = identify
+                .listen_addrs
+                .iter()
+                .filter_map(|addr| libp2p_listen_addr(peer_id, addr))
+                .filter(|opts| include_local || !opts.host.is_local());
+            let first = addrs.next()?;
+            Some(
+                std::iter::once(first.clone())
+                    .chain(addrs)
+                    .find(|opts| !opts.host.is_local())
+                    .unwrap_or(first),
+            )
+        });
+        let dial_opts = match (listen_addr, &self.status) {
+            (Some(opts), _) => Some(P2pConnectionOutgoingInitOpts::LibP2P(opts)),
+            (None, P2pPeerStatus::Ready(ready)) if ready.is_incoming && self.is_libp2p => None,
+            (None, _) => self.dial_opts.clone(),
+        };
+        dial_opts.filter(|opts| include_local || !opts.is_local())
+    }
+
     /// Returns true if the peer can be reconnected, that is:
     /// - it has available dial options
     /// - it is never been connected yet or enough time is passed since its connection failure or disconnection.
@@ -402,6 +557,31 @@ impl P2pPeerState {
     }
 }
 
+fn libp2p_listen_addr(
+    peer_id: PeerId,
+    addr: &multiaddr::Multiaddr,
+) -> Option<P2pConnectionOutgoingInitLibp2pOpts> {
+    use multiaddr::Protocol;
+
+    let mut iter = addr.iter();
+    let host = match iter.next()? {
+        Protocol::Ip4(ip) => Host::Ipv4(ip),
+        Protocol::Ip6(ip) => Host::Ipv6(ip),
+        Protocol::Dns(name) | Protocol::Dns4(name) | Protocol::Dns6(name) => {
+            Host::Domain(name.into_owned())
+        }
+        _ => return None,
+    };
+    let Protocol::Tcp(port) = iter.next()? else {
+        return None;
+    };
+    Some(P2pConnectionOutgoingInitLibp2pOpts {
+        peer_id,
+        host,
+        port,
+    })
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, MallocSizeOf)]
 #[serde(tag = "state")]
 pub enum P2pPeerStatus {
@@ -480,6 +660,17 @@ pub struct P2pPeerStatusReady {
     pub connected_since: redux::Timestamp,
     pub channels: P2pChannelsState,
     pub best_tip: Option<ArcBlockWithHash>,
+    /// Latest stats of the WebRTC connection, `None` for libp2p peers.
+    #[serde(default)]
+    pub connection_stats: Option<ConnectionStats>,
+    /// Whether the peer announced it accepts batched snark messages when
+    /// connecting, see [`crate::webrtc::Offer::snark_batching`].
+    #[serde(default)]
+    pub snark_batching: bool,
+    /// Whether the peer announced it serves blocks over the streaming RPC
+    /// when connecting, see [`crate::webrtc::Offer::block_streaming`].
+    #[serde(default)]
+    pub block_streaming: bool,
 }
 
 impl P2pPeerStatusReady {
@@ -493,6 +684,9 @@ impl P2pPeerStatusReady {
             connected_since: time,
             channels: P2pChannelsState::new(enabled_channels),
             best_tip: None,
+            connection_stats: None,
+            snark_batching: false,
+            block_streaming: false,
         }
     }
 
@@ -575,6 +769,9 @@ pub struct P2pCallbacks {
     /// Callback for [`crate::channels::streaming_rpc::P2pChannelsStreamingRpcAction::ResponseReceived`]
     pub on_p2p_channels_streaming_rpc_response_received:
         OptionalCallback<(PeerId, P2pRpcId, Option<P2pStreamingRpcResponseFull>)>,
+    /// Callback for [`crate::channels::streaming_rpc::P2pChannelsStreamingRpcAction::RequestReceived`]
+    pub on_p2p_channels_streaming_rpc_request_received:
+        OptionalCallback<(PeerId, P2pRpcId, Box<P2pStreamingRpcRequest>)>,
 
     /// Callback for received pubsub message
     pub on_p2p_pubsub_message_received: OptionalCallback<P2pNetworkPubsubMessageCacheId>,
@@ -654,3 +851,231 @@ mod measurement {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        webrtc::{HttpSignalingInfo, SignalingMethod},
+        P2pMeshsubConfig,
+    };
+
+    use super::*;
+
+    #[test]
+    fn advertised_dial_opts_prefers_public_listen_addr() {
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let dial_opts =
+            P2pConnectionOutgoingInitOpts::LibP2P(P2pConnectionOutgoingInitLibp2pOpts {
+                peer_id,
+                host: Host::Ipv4([1, 2, 3, 4].into()),
+                port: 54321,
+            });
+        let mut peer = P2pPeerState {
+            is_libp2p: true,
+            dial_opts: Some(dial_opts.clone()),
+            status: P2pPeerStatus::Disconnected {
+                time: Timestamp::ZERO,
+            },
+            identify: None,
+        };
+        assert_eq!(peer.advertised_dial_opts(peer_id, false), Some(dial_opts));
+
+        peer.identify = Some(P2pNetworkIdentify {
+            protocol_version: None,
+            agent_version: None,
+            public_key: None,
+            listen_addrs: vec![
+                "/ip4/127.0.0.1/tcp/8302".parse().unwrap(),
+                "/ip4/1.2.3.4/tcp/8302".parse().unwrap(),
+            ],
+            observed_addr: None,
+            protocols: vec![],
+        });
+        let expected = P2pConnectionOutgoingInitOpts::LibP2P(P2pConnectionOutgoingInitLibp2pOpts {
+            peer_id,
+            host: Host::Ipv4([1, 2, 3, 4].into()),
+            port: 8302,
+        });
+        assert_eq!(peer.advertised_dial_opts(peer_id, false), Some(expected));
+    }
+
+    #[test]
+    fn advertised_dial_opts_leaves_out_local_addrs() {
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let listen_addrs = [
+            "/ip4/127.0.0.1/tcp/8302",
+            "/ip4/10.0.0.1/tcp/8302",
+            "/ip4/169.254.1.1/tcp/8302",
+            "/ip4/100.64.1.1/tcp/8302",
+            "/ip6/fd00::1/tcp/8302",
+            "/ip6/fe80::1/tcp/8302",
+        ];
+        let mut peer = P2pPeerState {
+            is_libp2p: true,
+            dial_opts: Some(P2pConnectionOutgoingInitOpts::LibP2P(
+                P2pConnectionOutgoingInitLibp2pOpts {
+                    peer_id,
+                    host: Host::Ipv4([192, 168, 1, 1].into()),
+                    port: 54321,
+                },
+            )),
+            status: P2pPeerStatus::Disconnected {
+                time: Timestamp::ZERO,
+            },
+            identify: Some(P2pNetworkIdentify {
+                protocol_version: None,
+                agent_version: None,
+                public_key: None,
+                listen_addrs: listen_addrs
+                    .iter()
+                    .map(|addr| addr.parse().unwrap())
+                    .collect(),
+                observed_addr: None,
+                protocols: vec![],
+            }),
+        };
+        assert_eq!(peer.advertised_dial_opts(peer_id, false), None);
+
+        let local = P2pConnectionOutgoingInitOpts::LibP2P(P2pConnectionOutgoingInitLibp2pOpts {
+            peer_id,
+            host: Host::Ipv4([127, 0, 0, 1].into()),
+            port: 8302,
+        });
+        assert_eq!(peer.advertised_dial_opts(peer_id, true), Some(local));
+
+        // Without identify, the address we know the peer at is left out too.
+        peer.identify = None;
+        assert_eq!(peer.advertised_dial_opts(peer_id, false), None);
+        assert_eq!(
+            peer.advertised_dial_opts(peer_id, true),
+            peer.dial_opts.clone()
+        );
+    }
+
+    fn config() -> P2pConfig {
+        P2pConfig {
+            libp2p_port: None,
+            listen_port: None,
+            identity_pub_key: crate::identity::SecretKey::deterministic(0).public_key(),
+            previous_peer_ids: Vec::new(),
+            initial_peers: Vec::new(),
+            initial_dnsaddrs: Vec::new(),
+            external_addrs: Vec::new(),
+            enabled_channels: Default::default(),
+            timeouts: P2pTimeouts::default(),
+            limits: P2pLimits::default(),
+            peer_discovery: false,
+            meshsub: P2pMeshsubConfig::default(),
+        }
+    }
+
+    #[test]
+    fn initial_peers_for_libp2p_requester_skips_webrtc_peers() {
+        let mut state = P2pState::new(
+            config(),
+            P2pCallbacks::default(),
+            &mina_core::DEVNET_CHAIN_ID,
+        );
+        let disconnected = |is_libp2p, dial_opts| P2pPeerState {
+            is_libp2p,
+            dial_opts: Some(dial_opts),
+            status: P2pPeerStatus::Disconnected {
+                time: Timestamp::ZERO,
+            },
+            identify: None,
+        };
+
+        // More WebRTC peers than fit in a response, coming before the
+        // libp2p ones.
+        for i in 1..=(INITIAL_PEERS_RESPONSE_MAX as u8 + 6) {
+            let peer_id = PeerId::from_bytes([i; 32]);
+            let dial_opts = P2pConnectionOutgoingInitOpts::WebRTC {
+                peer_id,
+                signaling: SignalingMethod::Http(HttpSignalingInfo {
+                    host: Host::Ipv4([1, 2, 3, i].into()),
+                    port: 3000,
+                }),
+            };
+            state.peers.insert(peer_id, disconnected(false, dial_opts));
+        }
+        let libp2p_peers = (200..203)
+            .map(|i| {
+                let peer_id = PeerId::from_bytes([i; 32]);
+                let dial_opts =
+                    P2pConnectionOutgoingInitOpts::LibP2P(P2pConnectionOutgoingInitLibp2pOpts {
+                        peer_id,
+                        host: Host::Ipv4([1, 2, 3, i].into()),
+                        port: 8302,
+                    });
+                state
+                    .peers
+                    .insert(peer_id, disconnected(true, dial_opts.clone()));
+                dial_opts
+            })
+            .collect::<Vec<_>>();
+
+        let libp2p_requester = *libp2p_peers[0].peer_id();
+        assert_eq!(
+            state.initial_peers_for(&libp2p_requester),
+            libp2p_peers[1..]
+        );
+
+        let webrtc_requester = PeerId::from_bytes([1; 32]);
+        let peers = state.initial_peers_for(&webrtc_requester);
+        assert_eq!(peers.len(), INITIAL_PEERS_RESPONSE_MAX);
+        assert!(peers
+            .iter()
+            .all(|opts| matches!(opts, P2pConnectionOutgoingInitOpts::WebRTC { .. })));
+    }
+
+    #[test]
+    fn dnsaddr_peer_failed_after_resolution() {
+        let dnsaddr: P2pDnsaddr = "/dnsaddr/seeds.example.com".parse().unwrap();
+        let config = P2pConfig {
+            initial_dnsaddrs: vec![dnsaddr.clone()],
+            ..config()
+        };
+        let mut state = P2pState::new(config, P2pCallbacks::default(), &mina_core::DEVNET_CHAIN_ID);
+        let time = |secs| Timestamp::ZERO + Duration::from_secs(secs);
+
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let dial_opts =
+            P2pConnectionOutgoingInitOpts::LibP2P(P2pConnectionOutgoingInitLibp2pOpts {
+                peer_id,
+                host: Host::Ipv4([1, 2, 3, 4].into()),
+                port: 8302,
+            });
+        let failed_at = |time| P2pPeerState {
+            is_libp2p: true,
+            dial_opts: Some(dial_opts.clone()),
+            status: P2pPeerStatus::Connecting(P2pConnectionState::Outgoing(
+                P2pConnectionOutgoingState::Error {
+                    time,
+                    error: P2pConnectionOutgoingError::RemoteInternalError,
+                    rpc_id: None,
+                },
+            )),
+            identify: None,
+        };
+        state.peers.insert(peer_id, failed_at(time(5)));
+
+        let dnsaddr_state = state.dnsaddr_mut(&dnsaddr).unwrap();
+        assert!(matches!(dnsaddr_state.status, P2pDnsaddrStatus::Idle));
+        dnsaddr_state.peers = vec![peer_id];
+        dnsaddr_state.status = P2pDnsaddrStatus::Resolved {
+            time: time(10),
+            expires_at: time(300),
+        };
+        let dnsaddr_state = state.dnsaddr(&dnsaddr).unwrap();
+        assert!(
+            !state.dnsaddr_peer_failed(dnsaddr_state),
+            "failed before the resolution"
+        );
+
+        state.peers.insert(peer_id, failed_at(time(20)));
+        let dnsaddr_state = state.dnsaddr(&dnsaddr).unwrap();
+        assert!(state.dnsaddr_peer_failed(dnsaddr_state));
+        assert!(!dnsaddr_state.should_resolve(time(39), true));
+        assert!(dnsaddr_state.should_resolve(time(40), true));
+    }
+}
@@ -1,7 +1,10 @@
 use mina_core::{block::ArcBlockWithHash, ActionEvent};
 use serde::{Deserialize, Serialize};
 
-use crate::{connection::outgoing::P2pConnectionOutgoingInitOpts, P2pState, PeerId};
+use crate::{
+    connection::outgoing::P2pConnectionOutgoingInitOpts, gossip::P2pGossipKind,
+    webrtc::ConnectionStats, P2pState, PeerId,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
 #[action_event(level = debug, fields(display(peer_id), debug(dial_opts), best_tip = display(&best_tip.hash), incoming))]
@@ -19,8 +22,21 @@ pub enum P2pPeerAction {
         peer_id: PeerId,
         best_tip: ArcBlockWithHash,
     },
+    /// Stats of the peer's WebRTC connection are updated.
+    #[action_event(level = trace)]
+    ConnectionStatsUpdate {
+        peer_id: PeerId,
+        stats: ConnectionStats,
+    },
     /// Remove peer from state
     Remove { peer_id: PeerId },
+    /// Peer was added to the peer list fetched from the configured url.
+    Listed {
+        peer_id: PeerId,
+        dial_opts: P2pConnectionOutgoingInitOpts,
+    },
+    /// Peer was removed from the peer list fetched from the configured url.
+    Unlisted { peer_id: PeerId },
 }
 
 impl P2pPeerAction {
@@ -29,7 +45,10 @@ impl P2pPeerAction {
             Self::Discovered { peer_id, .. } => peer_id,
             Self::Ready { peer_id, .. } => peer_id,
             Self::BestTipUpdate { peer_id, .. } => peer_id,
+            Self::ConnectionStatsUpdate { peer_id, .. } => peer_id,
             Self::Remove { peer_id } => peer_id,
+            Self::Listed { peer_id, .. } => peer_id,
+            Self::Unlisted { peer_id } => peer_id,
         }
     }
 }
@@ -38,7 +57,7 @@ impl redux::EnablingCondition<P2pState> for P2pPeerAction {
     fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
         match self {
             P2pPeerAction::Discovered { peer_id, .. } => {
-                peer_id != &state.my_id()
+                !state.is_my_id(peer_id)
                     && state
                         .peers
                         .get(peer_id)
@@ -52,12 +71,24 @@ impl redux::EnablingCondition<P2pState> for P2pPeerAction {
             P2pPeerAction::BestTipUpdate { peer_id, .. } => {
                 // TODO: don't enable if block inferior than existing peer's
                 // best tip.
+                !state.is_gossip_paused(P2pGossipKind::Block)
+                    && state.get_ready_peer(peer_id).is_some()
+            }
+            P2pPeerAction::ConnectionStatsUpdate { peer_id, .. } => {
                 state.get_ready_peer(peer_id).is_some()
             }
             P2pPeerAction::Remove { peer_id } => {
                 state.peers.len() > state.config.limits.min_peers_in_state()
                     && state.peers.contains_key(peer_id)
             }
+            P2pPeerAction::Listed { peer_id, dial_opts } => {
+                !state.is_my_id(peer_id) && !state.config.initial_peers.contains(dial_opts)
+            }
+            P2pPeerAction::Unlisted { peer_id } => state
+                .config
+                .initial_peers
+                .iter()
+                .any(|opts| opts.peer_id() == peer_id),
         }
     }
 }
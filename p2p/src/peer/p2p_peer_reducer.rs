@@ -42,11 +42,19 @@ impl P2pPeerState {
                 let Some(peer) = p2p_state.peers.get_mut(&peer_id) else {
                     return Ok(());
                 };
-                peer.status = P2pPeerStatus::Ready(P2pPeerStatusReady::new(
+                let connection = peer.status.as_connecting();
+                let snark_batching =
+                    connection.is_some_and(|connection| connection.remote_snark_batching());
+                let block_streaming =
+                    connection.is_some_and(|connection| connection.remote_block_streaming());
+                let mut ready = P2pPeerStatusReady::new(
                     incoming,
                     meta.time(),
                     &p2p_state.config.enabled_channels,
-                ));
+                );
+                ready.snark_batching = snark_batching;
+                ready.block_streaming = block_streaming;
+                peer.status = P2pPeerStatus::Ready(ready);
 
                 if !peer.is_libp2p {
                     let (dispatcher, state) = state_context.into_dispatcher_and_state();
@@ -71,6 +79,16 @@ impl P2pPeerState {
                 }
                 Ok(())
             }
+            P2pPeerAction::ConnectionStatsUpdate { peer_id, stats } => {
+                let Some(peer) = p2p_state.get_ready_peer_mut(&peer_id) else {
+                    bug_condition!(
+                        "Peer state not found for `P2pPeerAction::ConnectionStatsUpdate`"
+                    );
+                    return Ok(());
+                };
+                peer.connection_stats = Some(stats);
+                Ok(())
+            }
             P2pPeerAction::Remove { peer_id } => {
                 if p2p_state.peers.remove(&peer_id).is_none() {
                     bug_condition!(
@@ -80,6 +98,37 @@ impl P2pPeerState {
 
                 Ok(())
             }
+            P2pPeerAction::Listed { peer_id, dial_opts } => {
+                // Initial peers that aren't in the state yet get dialed on
+                // the next timeout check.
+                let initial_peers = &mut p2p_state.config.initial_peers;
+                initial_peers.retain(|opts| *opts.peer_id() != peer_id);
+                initial_peers.push(dial_opts.clone());
+
+                if let Some(peer) = p2p_state.peers.get_mut(&peer_id) {
+                    if !peer.status.is_connected_or_connecting() {
+                        peer.dial_opts = Some(dial_opts);
+                    }
+                }
+                Ok(())
+            }
+            P2pPeerAction::Unlisted { peer_id } => {
+                p2p_state
+                    .config
+                    .initial_peers
+                    .retain(|opts| *opts.peer_id() != peer_id);
+
+                // Connections to the peer are kept, the list only decides
+                // which peers get dialed.
+                if p2p_state
+                    .peers
+                    .get(&peer_id)
+                    .is_some_and(|peer| !peer.status.is_connected_or_connecting())
+                {
+                    p2p_state.peers.remove(&peer_id);
+                }
+                Ok(())
+            }
         }
     }
 }
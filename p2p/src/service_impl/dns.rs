@@ -0,0 +1,248 @@
+//! Name resolution of the hosts of peers.
+//!
+//! Seed lists are often published as DNS names only, either as `/dns4`,
+//! `/dns6` or `/dns` multiaddrs or as a `/dnsaddr` domain, whose `TXT`
+//! records list the multiaddrs of the peers (see the
+//! [dnsaddr spec](https://github.com/multiformats/multiaddr/blob/master/protocols/DNSADDR.md)).
+//!
+//! Resolved records are cached for their TTL (clamped to
+//! [`MIN_TTL`]..[`MAX_TTL`]), so that reconnecting to a peer doesn't query
+//! the DNS server every time. A failed connection to a resolved address
+//! should [`forget_host`], so that the next attempt resolves the name again,
+//! in case the peer moved.
+//!
+//! All functions block the calling thread until the DNS server responds.
+
+use std::{
+    collections::BTreeMap,
+    net::IpAddr,
+    str::FromStr,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use multiaddr::{Multiaddr, Protocol};
+use trust_dns_resolver::{
+    config::{ResolverConfig, ResolverOpts},
+    Resolver,
+};
+
+use crate::{connection::outgoing::P2pConnectionOutgoingInitOpts, PeerId};
+
+/// Records are kept at least this long, even if their TTL is shorter.
+pub const MIN_TTL: Duration = Duration::from_secs(30);
+/// Records are resolved again after this long, even if their TTL is longer.
+pub const MAX_TTL: Duration = Duration::from_secs(60 * 60);
+/// Maximum depth of `/dnsaddr` records pointing to other `/dnsaddr` records.
+const MAX_DNSADDR_DEPTH: usize = 4;
+
+#[derive(Default)]
+struct Cache {
+    hosts: BTreeMap<String, (Instant, Vec<IpAddr>)>,
+    dnsaddrs: BTreeMap<String, (Instant, Vec<Multiaddr>)>,
+}
+
+fn resolver() -> Result<&'static Resolver, String> {
+    static RESOLVER: OnceLock<Result<Resolver, String>> = OnceLock::new();
+    RESOLVER
+        .get_or_init(|| {
+            Resolver::from_system_conf()
+                .or_else(|_| Resolver::new(ResolverConfig::default(), ResolverOpts::default()))
+                .map_err(|err| format!("failed to create DNS resolver: {err}"))
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+fn cache() -> &'static Mutex<Cache> {
+    static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// Cached value of `name` if it is still valid, with the time until which
+/// it is.
+fn cached<T: Clone>(entries: &BTreeMap<String, (Instant, T)>, name: &str) -> Option<(T, Instant)> {
+    entries
+        .get(name)
+        .filter(|(valid_until, _)| *valid_until > Instant::now())
+        .map(|(valid_until, value)| (value.clone(), *valid_until))
+}
+
+fn clamp_valid_until(valid_until: Instant) -> Instant {
+    let now = Instant::now();
+    valid_until.clamp(now + MIN_TTL, now + MAX_TTL)
+}
+
+/// Resolves `name` to its IP addresses, IPv4 ones first.
+pub fn resolve_host(name: &str) -> Result<Vec<IpAddr>, String> {
+    if let Ok(ip) = IpAddr::from_str(name) {
+        return Ok(vec![ip]);
+    }
+    if let Some((ips, _)) = cached(&cache().lock().expect("poisoned").hosts, name) {
+        return Ok(ips);
+    }
+
+    let lookup = resolver()?
+        .lookup_ip(name)
+        .map_err(|err| format!("{name}, {err}"))?;
+    let mut ips = lookup.iter().collect::<Vec<_>>();
+    if ips.is_empty() {
+        return Err(format!("{name}, no addresses"));
+    }
+    ips.sort_by_key(IpAddr::is_ipv6);
+
+    cache().lock().expect("poisoned").hosts.insert(
+        name.to_owned(),
+        (clamp_valid_until(lookup.valid_until()), ips.clone()),
+    );
+    Ok(ips)
+}
+
+/// Drops the cached addresses of `name` (and of the `/dnsaddr` domain
+/// `name`), so that they are resolved again.
+pub fn forget_host(name: &str) {
+    let mut cache = cache().lock().expect("poisoned");
+    cache.hosts.remove(name);
+    cache.dnsaddrs.remove(name);
+}
+
+/// Resolves the multiaddrs listed in the `_dnsaddr.<domain>` `TXT` records,
+/// following nested `/dnsaddr` records. Returns them with the time until
+/// which all the records they come from are valid.
+pub fn resolve_dnsaddr(domain: &str) -> Result<(Vec<Multiaddr>, Instant), String> {
+    resolve_dnsaddr_with_depth(domain, 0)
+}
+
+fn resolve_dnsaddr_with_depth(
+    domain: &str,
+    depth: usize,
+) -> Result<(Vec<Multiaddr>, Instant), String> {
+    if depth > MAX_DNSADDR_DEPTH {
+        return Err(format!("{domain}, too many nested dnsaddr records"));
+    }
+    let (maddrs, mut valid_until) =
+        match cached(&cache().lock().expect("poisoned").dnsaddrs, domain) {
+            Some(cached) => cached,
+            None => {
+                let name = format!("_dnsaddr.{domain}");
+                let lookup = resolver()?
+                    .txt_lookup(name.as_str())
+                    .map_err(|err| format!("{name}, {err}"))?;
+                let maddrs = lookup
+                    .iter()
+                    .filter_map(|txt| {
+                        let txt = txt
+                            .txt_data()
+                            .iter()
+                            .map(|data| String::from_utf8_lossy(data))
+                            .collect::<String>();
+                        txt.strip_prefix("dnsaddr=")?.parse::<Multiaddr>().ok()
+                    })
+                    .collect::<Vec<_>>();
+
+                let valid_until = clamp_valid_until(lookup.valid_until());
+                cache()
+                    .lock()
+                    .expect("poisoned")
+                    .dnsaddrs
+                    .insert(domain.to_owned(), (valid_until, maddrs.clone()));
+                (maddrs, valid_until)
+            }
+        };
+
+    let mut result = Vec::with_capacity(maddrs.len());
+    for maddr in maddrs {
+        match maddr.iter().next() {
+            Some(Protocol::Dnsaddr(nested)) => {
+                // A peer id at the end of the nested record is checked by the
+                // caller, against the peer id of the resolved multiaddrs.
+                match resolve_dnsaddr_with_depth(&nested, depth + 1) {
+                    Ok((nested, nested_valid_until)) => {
+                        result.extend(nested);
+                        valid_until = valid_until.min(nested_valid_until);
+                    }
+                    Err(err) => {
+                        mina_core::warn!(
+                            mina_core::log::system_time();
+                            "failed to resolve nested dnsaddr {maddr}: {err}"
+                        );
+                    }
+                }
+            }
+            _ => result.push(maddr),
+        }
+    }
+    Ok((result, valid_until))
+}
+
+/// Resolves the peers listed by the `/dnsaddr` `domain`, keeping only the one
+/// with `peer_id` if set. Records that aren't valid peer addresses are
+/// skipped. Returns them with the time the records are valid for.
+pub fn resolve_dnsaddr_peers(
+    domain: &str,
+    peer_id: Option<PeerId>,
+) -> Result<(Vec<P2pConnectionOutgoingInitOpts>, Duration), String> {
+    let (maddrs, valid_until) = resolve_dnsaddr(domain)?;
+    let peers = dnsaddr_peers(&maddrs, peer_id);
+    if peers.is_empty() {
+        return Err(format!("{domain}, no peer addresses in dnsaddr records"));
+    }
+    Ok((peers, valid_until.saturating_duration_since(Instant::now())))
+}
+
+fn dnsaddr_peers(
+    maddrs: &[Multiaddr],
+    peer_id: Option<PeerId>,
+) -> Vec<P2pConnectionOutgoingInitOpts> {
+    maddrs
+        .iter()
+        .filter_map(|maddr| P2pConnectionOutgoingInitOpts::try_from(maddr).ok())
+        .filter(|opts| peer_id.is_none_or(|peer_id| *opts.peer_id() == peer_id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PEER_ID: &str = "12D3KooWD8jSyPFXNdAcMBHyHjRBcK1AW9t3xvnpfCFSRKMweVKi";
+    const OTHER_PEER_ID: &str = "12D3KooWAdgYL6hv18M3iDBdaK1dRygPivSfAfBNDzie6YqydVbs";
+
+    #[test]
+    fn test_dnsaddr_peers() {
+        let maddrs = [
+            format!("/ip4/1.2.3.4/tcp/8302/p2p/{PEER_ID}"),
+            format!("/dns4/seed.example.com/tcp/8302/p2p/{OTHER_PEER_ID}"),
+            // Not a peer address, skipped.
+            "/ip4/1.2.3.4/tcp/8302".to_owned(),
+        ]
+        .map(|s| s.parse::<Multiaddr>().unwrap());
+
+        let peers = dnsaddr_peers(&maddrs, None);
+        assert_eq!(peers.len(), 2);
+
+        let first = P2pConnectionOutgoingInitOpts::try_from(&maddrs[0]).unwrap();
+        let peers = dnsaddr_peers(&maddrs, Some(*first.peer_id()));
+        assert_eq!(peers, [first]);
+    }
+
+    #[test]
+    fn test_cached_entries_expire() {
+        let mut entries = BTreeMap::new();
+        let valid_until = Instant::now() + MIN_TTL;
+        entries.insert("valid".to_owned(), (valid_until, 1));
+        entries.insert("expired".to_owned(), (Instant::now(), 2));
+
+        assert_eq!(cached(&entries, "valid"), Some((1, valid_until)));
+        assert_eq!(cached(&entries, "expired"), None);
+        assert_eq!(cached(&entries, "unknown"), None);
+    }
+
+    #[test]
+    fn test_ttl_is_clamped() {
+        let now = Instant::now();
+        assert!(clamp_valid_until(now) >= now + MIN_TTL);
+        let valid_until = clamp_valid_until(now + MAX_TTL * 2);
+        assert!(valid_until <= Instant::now() + MAX_TTL);
+    }
+}
@@ -1,4 +1,6 @@
 #[cfg(feature = "p2p-libp2p")]
+pub mod dns;
+#[cfg(feature = "p2p-libp2p")]
 pub mod mio;
 #[cfg(feature = "p2p-webrtc")]
 pub mod webrtc;
@@ -6,6 +8,19 @@ pub mod webrtc_with_libp2p;
 
 use std::future::Future;
 
+use mina_core::channels::mpsc;
+
+/// Sends the events of the p2p service to the state machine.
+pub trait P2pEventSender<E>: Clone + Send + Sync + 'static {
+    fn send(&self, event: E) -> Result<(), mpsc::SendError<E>>;
+}
+
+impl<E: Send + 'static> P2pEventSender<E> for mpsc::UnboundedSender<E> {
+    fn send(&self, event: E) -> Result<(), mpsc::SendError<E>> {
+        mpsc::UnboundedSender::send(self, event)
+    }
+}
+
 pub trait TaskSpawner: Send + Clone {
     fn spawn_main<F>(&self, name: &str, fut: F)
     where
@@ -25,7 +40,7 @@ pub mod webrtc {
         webrtc, P2pEvent, PeerId,
     };
 
-    use super::TaskSpawner;
+    use super::{P2pEventSender, TaskSpawner};
 
     pub struct P2pServiceCtx {
         pub cmd_sender: mpsc::UnboundedSender<Cmd>,
@@ -39,13 +54,14 @@ pub mod webrtc {
     #[allow(unused_variables)]
     pub trait P2pServiceWebrtc: redux::Service {
         type Event: From<P2pEvent> + Send + Sync + 'static;
+        type EventSender: P2pEventSender<Self::Event>;
 
         fn random_pick(
             &mut self,
             list: &[P2pConnectionOutgoingInitOpts],
         ) -> Option<P2pConnectionOutgoingInitOpts>;
 
-        fn event_sender(&self) -> &mpsc::UnboundedSender<Self::Event>;
+        fn event_sender(&self) -> &Self::EventSender;
 
         fn cmd_sender(&self) -> &mpsc::TrackedUnboundedSender<Cmd>;
 
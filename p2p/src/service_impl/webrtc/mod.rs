@@ -52,7 +52,7 @@ mod imports {
 use imports::*;
 pub use imports::{webrtc_signal_send, RTCSignalingError};
 
-use super::TaskSpawner;
+use super::{P2pEventSender, TaskSpawner};
 
 /// 16KB.
 const CHUNK_SIZE: usize = 16 * 1024;
@@ -100,6 +100,9 @@ pub enum PeerConnectionKind {
 pub struct PeerState {
     pub cmd_sender: mpsc::TrackedUnboundedSender<PeerCmd>,
     pub abort: Aborter,
+    /// Our peer id which the peer dialed, for incoming connections. It's a
+    /// previous one if the peer still knows us by it.
+    pub dialed_peer_id: Option<PeerId>,
 }
 
 #[derive(thiserror::Error, derive_more::From, Debug)]
@@ -204,6 +207,9 @@ impl Drop for RTCConnection {
     }
 }
 
+/// How often the stats of a ready connection are reported.
+const CONNECTION_STATS_INTERVAL: Duration = Duration::from_secs(10);
+
 async fn sleep(dur: Duration) {
     #[cfg(not(target_arch = "wasm32"))]
     let fut = tokio::time::sleep(dur);
@@ -236,6 +242,11 @@ async fn peer_start(
         mut cmd_receiver,
     } = args;
     let is_outgoing = matches!(kind, PeerConnectionKind::Outgoing);
+    // Whether the peer accepts batched snark messages, and so may send them.
+    let mut snark_batching = match &kind {
+        PeerConnectionKind::Incoming(offer) => offer.snark_batching,
+        PeerConnectionKind::Outgoing => false,
+    };
 
     let config = RTCConfig {
         ice_servers: Default::default(),
@@ -308,7 +319,10 @@ async fn peer_start(
             }
             Err(Error::ChannelClosed)
         };
-        answer_fut.await.and_then(|v| Ok(v.try_into()?))
+        answer_fut.await.and_then(|v| {
+            snark_batching = v.snark_batching;
+            Ok(v.try_into()?)
+        })
     } else {
         pc.answer_create().await.map_err(Error::from)
     };
@@ -423,7 +437,15 @@ async fn peer_start(
 
     let _ = main_channel.close().await;
 
-    peer_loop(peer_id, event_sender, cmd_receiver, pc, abort).await
+    peer_loop(
+        peer_id,
+        snark_batching,
+        event_sender,
+        cmd_receiver,
+        pc,
+        abort,
+    )
+    .await
 }
 
 struct Channel {
@@ -490,6 +512,7 @@ impl Channels {
 #[allow(unused_mut)]
 async fn peer_loop(
     peer_id: PeerId,
+    snark_batching: bool,
     event_sender: Arc<dyn Fn(P2pEvent) -> Option<()> + Send + Sync + 'static>,
     mut cmd_receiver: mpsc::TrackedUnboundedReceiver<PeerCmd>,
     mut pc: RTCConnection,
@@ -502,6 +525,8 @@ async fn peer_loop(
     let (internal_cmd_sender, mut internal_cmd_receiver) =
         mpsc::unbounded_channel::<PeerCmdInternal>();
 
+    let mut stats_timeout = Box::pin(sleep(CONNECTION_STATS_INTERVAL));
+
     while matches!(pc.connection_state(), RTCConnectionState::Connected) {
         let (cmd, _tracker) = tokio::select! {
             cmd = cmd_receiver.recv() => match cmd {
@@ -512,6 +537,13 @@ async fn peer_loop(
                 None => return,
                 Some(cmd) => (PeerCmdAll::Internal(cmd), None),
             },
+            _ = &mut stats_timeout => {
+                stats_timeout = Box::pin(sleep(CONNECTION_STATS_INTERVAL));
+                if let Some(stats) = pc.stats().await {
+                    let _ = event_sender(P2pConnectionEvent::Stats(peer_id, stats).into());
+                }
+                continue;
+            }
         };
         match cmd {
             PeerCmdAll::External(
@@ -609,6 +641,7 @@ async fn peer_loop(
                 if let Some(mut chan) = chan {
                     fn process_msg(
                         chan_id: ChannelId,
+                        snark_batching: bool,
                         buf: &mut Vec<u8>,
                         len: &mut u32,
                         msg: &mut &[u8],
@@ -622,11 +655,11 @@ async fn peer_loop(
                                 );
                                 *msg = &msg[4..];
                                 let len = *len as usize;
-                                if len > chan_id.max_msg_size() {
+                                let limit = chan_id.max_msg_size(snark_batching);
+                                if len > limit {
                                     return Err(format!(
                                         "ChannelMsgLenOverLimit; len: {}, limit: {}",
-                                        len,
-                                        chan_id.max_msg_size()
+                                        len, limit
                                     ));
                                 }
                                 len
@@ -656,7 +689,13 @@ async fn peer_loop(
 
                     chan.on_message(move |mut data| {
                         while !data.is_empty() {
-                            let res = match process_msg(chan_id, &mut buf, &mut len, &mut data) {
+                            let res = match process_msg(
+                                chan_id,
+                                snark_batching,
+                                &mut buf,
+                                &mut len,
+                                &mut data,
+                            ) {
                                 Ok(None) => continue,
                                 Ok(Some(msg)) => Ok(msg),
                                 Err(err) => Err(err),
@@ -723,13 +762,14 @@ async fn peer_loop(
 
 pub trait P2pServiceWebrtc: redux::Service {
     type Event: From<P2pEvent> + Send + Sync + 'static;
+    type EventSender: P2pEventSender<Self::Event>;
 
     fn random_pick(
         &mut self,
         list: &[P2pConnectionOutgoingInitOpts],
     ) -> Option<P2pConnectionOutgoingInitOpts>;
 
-    fn event_sender(&self) -> &mpsc::UnboundedSender<Self::Event>;
+    fn event_sender(&self) -> &Self::EventSender;
 
     fn cmd_sender(&self) -> &mpsc::TrackedUnboundedSender<Cmd>;
 
@@ -802,6 +842,7 @@ pub trait P2pServiceWebrtc: redux::Service {
             PeerState {
                 cmd_sender: peer_cmd_sender,
                 abort: aborter,
+                dialed_peer_id: None,
             },
         );
         let event_sender = self.event_sender().clone();
@@ -828,6 +869,7 @@ pub trait P2pServiceWebrtc: redux::Service {
             PeerState {
                 cmd_sender: peer_cmd_sender,
                 abort: aborter,
+                dialed_peer_id: Some(offer.target_peer_id),
             },
         );
         let event_sender = self.event_sender().clone();
@@ -15,3 +15,34 @@ export function webrtcCleanup() {
 export function schedulePeriodicWebrtcCleanup() {
   setInterval(webrtcCleanup, 60 * 1000);
 }
+
+// Stats of the candidate pair selected by the ICE transport, or `null` if
+// the connection has none yet.
+export async function webrtcConnectionStats(pc) {
+  const report = await pc.getStats();
+  let pair = null;
+  report.forEach((stat) => {
+    if (stat.type === "transport" && stat.selectedCandidatePairId) {
+      pair = report.get(stat.selectedCandidatePairId) ?? pair;
+    }
+  });
+  if (!pair) {
+    // Firefox doesn't report `selectedCandidatePairId`.
+    report.forEach((stat) => {
+      if (stat.type === "candidate-pair" && stat.nominated && stat.state === "succeeded") {
+        pair = stat;
+      }
+    });
+  }
+  if (!pair) {
+    return null;
+  }
+  return {
+    rtt_ms: pair.currentRoundTripTime !== undefined ? pair.currentRoundTripTime * 1000 : null,
+    bytes_sent: pair.bytesSent ?? 0,
+    bytes_received: pair.bytesReceived ?? 0,
+    requests_sent: pair.requestsSent ?? 0,
+    responses_received: pair.responsesReceived ?? 0,
+    retransmissions_sent: null,
+  };
+}
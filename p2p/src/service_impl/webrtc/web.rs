@@ -17,7 +17,7 @@ use mina_core::channels::oneshot;
 
 use crate::{
     connection::P2pConnectionResponse,
-    webrtc::{Answer, Offer},
+    webrtc::{Answer, ConnectionStats, Offer},
 };
 
 use super::{OnConnectionStateChangeHdlrFn, RTCChannelConfig, RTCConfig};
@@ -26,6 +26,9 @@ use super::{OnConnectionStateChangeHdlrFn, RTCChannelConfig, RTCConfig};
 extern "C" {
     #[wasm_bindgen(js_name = schedulePeriodicWebrtcCleanup)]
     fn schedule_periodic_webrtc_cleanup();
+
+    #[wasm_bindgen(js_name = webrtcConnectionStats)]
+    fn webrtc_connection_stats(pc: &RtcPeerConnection) -> js_sys::Promise;
 }
 
 pub type Result<T> = std::result::Result<T, JsValue>;
@@ -148,6 +151,14 @@ impl RTCConnection {
         callback.forget();
     }
 
+    /// Stats of the selected ICE candidate pair, if any.
+    pub async fn stats(&self) -> Option<ConnectionStats> {
+        let stats = JsFuture::from(webrtc_connection_stats(&self.0))
+            .await
+            .ok()?;
+        stats.into_serde().ok().flatten()
+    }
+
     pub async fn close(&self) {
         self.0.close();
     }
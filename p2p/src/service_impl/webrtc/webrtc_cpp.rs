@@ -10,7 +10,7 @@ use tokio::task::spawn_local;
 
 use crate::{
     connection::P2pConnectionResponse,
-    webrtc::{Answer, Offer},
+    webrtc::{Answer, ConnectionStats, Offer},
 };
 
 use super::{OnConnectionStateChangeHdlrFn, RTCChannelConfig, RTCConfig};
@@ -147,6 +147,11 @@ impl RTCConnection {
             }
         });
     }
+
+    /// Connection stats aren't exposed by the `datachannel` bindings.
+    pub async fn stats(&self) -> Option<ConnectionStats> {
+        None
+    }
 }
 
 impl PeerConnectionHandler for RTCConnectionHandlers {
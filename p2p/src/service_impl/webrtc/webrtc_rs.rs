@@ -12,11 +12,12 @@ use webrtc::{
         policy::ice_transport_policy::RTCIceTransportPolicy,
         sdp::session_description::RTCSessionDescription, RTCPeerConnection,
     },
+    stats::StatsReportType,
 };
 
 use crate::{
     connection::P2pConnectionResponse,
-    webrtc::{Answer, Offer},
+    webrtc::{Answer, ConnectionStats, Offer},
 };
 
 use super::{OnConnectionStateChangeHdlrFn, RTCChannelConfig, RTCConfig};
@@ -125,6 +126,26 @@ impl RTCConnection {
         self.0.on_peer_connection_state_change(handler)
     }
 
+    /// Stats of the nominated ICE candidate pair, if any.
+    pub async fn stats(&self) -> Option<ConnectionStats> {
+        self.0
+            .get_stats()
+            .await
+            .reports
+            .into_values()
+            .find_map(|report| match report {
+                StatsReportType::CandidatePair(pair) if pair.nominated => Some(ConnectionStats {
+                    rtt_ms: Some(pair.current_round_trip_time * 1000.0),
+                    bytes_sent: pair.bytes_sent,
+                    bytes_received: pair.bytes_received,
+                    requests_sent: pair.requests_sent,
+                    responses_received: pair.responses_received,
+                    retransmissions_sent: Some(pair.retransmissions_sent),
+                }),
+                _ => None,
+            })
+    }
+
     pub async fn close(self) {
         if let Err(error) = self.0.close().await {
             mina_core::warn!(
@@ -2,11 +2,14 @@ use std::collections::BTreeSet;
 
 use crate::{
     channels::{ChannelId, ChannelMsg, MsgId, P2pChannelsService},
-    connection::{outgoing::P2pConnectionOutgoingInitOpts, P2pConnectionService},
+    connection::{
+        outgoing::{P2pConnectionOutgoingInitOpts, P2pDnsaddr},
+        P2pConnectionService,
+    },
     disconnection_effectful::P2pDisconnectionService,
     identity::{PublicKey, SecretKey},
     webrtc::{ConnectionAuth, ConnectionAuthEncrypted},
-    P2pChannelEvent, P2pEvent, PeerId,
+    P2pChannelEvent, P2pConnectionEvent, P2pEvent, PeerId,
 };
 
 #[cfg(feature = "p2p-libp2p")]
@@ -14,10 +17,13 @@ use super::mio::MioService;
 #[cfg(feature = "p2p-libp2p")]
 use crate::{P2pMioService, P2pNetworkService, P2pNetworkServiceError};
 
-use super::{webrtc::P2pServiceWebrtc, TaskSpawner};
+use super::{webrtc::P2pServiceWebrtc, P2pEventSender, TaskSpawner};
 
 pub struct P2pServiceCtx {
     pub sec_key: SecretKey,
+    /// Keys used before the current one was rotated in, see
+    /// [`crate::P2pConfig::previous_peer_ids`].
+    pub previous_sec_keys: Vec<SecretKey>,
     pub webrtc: super::webrtc::P2pServiceCtx,
     #[cfg(feature = "p2p-libp2p")]
     pub mio: MioService,
@@ -32,6 +38,7 @@ pub trait P2pServiceWebrtcWithLibp2p: P2pServiceWebrtc {
     fn init<S: TaskSpawner>(sec_key: SecretKey, spawner: S, rng_seed: [u8; 32]) -> P2pServiceCtx {
         P2pServiceCtx {
             sec_key: sec_key.clone(),
+            previous_sec_keys: Vec::new(),
             #[cfg(feature = "p2p-libp2p")]
             mio: MioService::pending(sec_key.clone().try_into().expect("valid keypair")),
             webrtc: <Self as P2pServiceWebrtc>::init(sec_key, spawner, rng_seed),
@@ -111,6 +118,57 @@ impl<T: P2pServiceWebrtcWithLibp2p> P2pConnectionService for T {
         }
     }
 
+    #[cfg(not(feature = "p2p-libp2p"))]
+    fn resolve_host(&mut self, _peer_id: PeerId, _host: String, _refresh: bool) {}
+
+    #[cfg(feature = "p2p-libp2p")]
+    fn resolve_host(&mut self, peer_id: PeerId, host: String, refresh: bool) {
+        use super::dns;
+
+        spawn_resolver(
+            self,
+            move || {
+                if refresh {
+                    dns::forget_host(&host);
+                }
+                let result = dns::resolve_host(&host)
+                    .map(|ips| ips[0])
+                    .map_err(|err| format!("failed to resolve {err}"));
+                P2pConnectionEvent::HostResolved(peer_id, result)
+            },
+            |error| P2pConnectionEvent::HostResolved(peer_id, Err(error)),
+        );
+    }
+
+    #[cfg(not(feature = "p2p-libp2p"))]
+    fn resolve_dnsaddr(&mut self, addr: P2pDnsaddr, _refresh: bool) {
+        let error = "dnsaddr peer addresses are only supported with libp2p".to_owned();
+        self.event_sender()
+            .send(
+                P2pEvent::Connection(P2pConnectionEvent::DnsaddrResolved(addr, Err(error))).into(),
+            )
+            .unwrap_or_default();
+    }
+
+    #[cfg(feature = "p2p-libp2p")]
+    fn resolve_dnsaddr(&mut self, addr: P2pDnsaddr, refresh: bool) {
+        use super::dns;
+
+        let error_addr = addr.clone();
+        spawn_resolver(
+            self,
+            move || {
+                if refresh {
+                    dns::forget_host(&addr.domain);
+                }
+                let result = dns::resolve_dnsaddr_peers(&addr.domain, addr.peer_id)
+                    .map_err(|err| format!("failed to resolve {err}"));
+                P2pConnectionEvent::DnsaddrResolved(addr, result)
+            },
+            |error| P2pConnectionEvent::DnsaddrResolved(error_addr, Err(error)),
+        );
+    }
+
     fn incoming_init(&mut self, peer_id: PeerId, offer: crate::webrtc::Offer) {
         P2pServiceWebrtc::incoming_init(self, peer_id, offer)
     }
@@ -211,10 +269,32 @@ where
 }
 
 impl P2pServiceCtx {
+    /// Key to authenticate the webrtc connection with `peer_id` with. Peers
+    /// which dialed one of our previous peer ids get answered with its key.
+    #[cfg(feature = "p2p-webrtc")]
+    pub fn sec_key_for(&self, peer_id: &PeerId) -> &SecretKey {
+        self.webrtc
+            .peers
+            .get(peer_id)
+            .and_then(|peer| peer.dialed_peer_id)
+            .and_then(|dialed| {
+                self.previous_sec_keys
+                    .iter()
+                    .find(|key| key.public_key().peer_id() == dialed)
+            })
+            .unwrap_or(&self.sec_key)
+    }
+
+    #[cfg(not(feature = "p2p-webrtc"))]
+    pub fn sec_key_for(&self, _peer_id: &PeerId) -> &SecretKey {
+        &self.sec_key
+    }
+
     pub fn mocked(sec_key: SecretKey) -> Self {
         use mina_core::channels::mpsc;
         Self {
             sec_key: sec_key.clone(),
+            previous_sec_keys: Vec::new(),
             #[cfg(feature = "p2p-libp2p")]
             mio: super::mio::MioService::mocked(sec_key.try_into().expect("valid keypair")),
             webrtc: super::webrtc::P2pServiceCtx {
@@ -224,3 +304,26 @@ impl P2pServiceCtx {
         }
     }
 }
+
+/// Runs `resolve` on its own thread, as DNS lookups block, and sends the
+/// event it returns.
+#[cfg(feature = "p2p-libp2p")]
+fn spawn_resolver<T: P2pServiceWebrtcWithLibp2p>(
+    service: &T,
+    resolve: impl 'static + Send + FnOnce() -> P2pConnectionEvent,
+    on_spawn_error: impl FnOnce(String) -> P2pConnectionEvent,
+) {
+    let event_sender = service.event_sender().clone();
+    let spawn_result = std::thread::Builder::new()
+        .name("p2p-dns".to_owned())
+        .spawn(move || {
+            let _ = event_sender.send(P2pEvent::Connection(resolve()).into());
+        });
+    if let Err(err) = spawn_result {
+        let error = format!("failed to spawn resolver thread: {err}");
+        service
+            .event_sender()
+            .send(P2pEvent::Connection(on_spawn_error(error)).into())
+            .unwrap_or_default();
+    }
+}
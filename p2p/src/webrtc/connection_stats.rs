@@ -0,0 +1,54 @@
+//! Quality metrics of a WebRTC connection, as reported by the WebRTC stack of
+//! the node (`RTCPeerConnection.getStats()` in browsers).
+//!
+//! They are collected periodically for every ready WebRTC peer and exposed in
+//! the peers RPC, to tell slow or lossy connections apart from healthy ones.
+
+use serde::{Deserialize, Serialize};
+
+/// Metrics of the selected ICE candidate pair of a connection.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ConnectionStats {
+    /// Most recent round trip time, in milliseconds.
+    pub rtt_ms: Option<f64>,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// STUN connectivity checks sent on the candidate pair.
+    pub requests_sent: u64,
+    /// Answers received to the connectivity checks. Checks left unanswered
+    /// are a sign of packet loss.
+    pub responses_received: u64,
+    /// Retransmitted connectivity checks, if reported by the WebRTC stack.
+    pub retransmissions_sent: Option<u64>,
+}
+
+impl ConnectionStats {
+    /// Share of the connectivity checks that were not answered, between 0
+    /// and 1.
+    pub fn unanswered_requests_ratio(&self) -> Option<f64> {
+        if self.requests_sent == 0 {
+            return None;
+        }
+        let unanswered = self.requests_sent.saturating_sub(self.responses_received);
+        Some(unanswered as f64 / self.requests_sent as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unanswered_requests_ratio() {
+        let stats = |requests_sent, responses_received| ConnectionStats {
+            requests_sent,
+            responses_received,
+            ..Default::default()
+        };
+        assert_eq!(stats(0, 0).unanswered_requests_ratio(), None);
+        assert_eq!(stats(10, 10).unanswered_requests_ratio(), Some(0.0));
+        assert_eq!(stats(10, 8).unanswered_requests_ratio(), Some(0.2));
+        // duplicated answers
+        assert_eq!(stats(10, 12).unanswered_requests_ratio(), Some(0.0));
+    }
+}
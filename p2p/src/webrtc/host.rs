@@ -55,6 +55,36 @@ impl Host {
             Some(self)
         }
     }
+
+    /// Whether the host is an address that isn't reachable from the
+    /// internet: loopback, unspecified, private, link-local, shared (CGNAT)
+    /// or IPv6 unique local. Domains are assumed to be public.
+    pub fn is_local(&self) -> bool {
+        match self {
+            Self::Domain(_) => false,
+            Self::Ipv4(ip) => is_local_ipv4(ip),
+            Self::Ipv6(ip) => {
+                let [first, ..] = ip.segments();
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    // Unique local, fc00::/7
+                    || first & 0xfe00 == 0xfc00
+                    // Link-local, fe80::/10
+                    || first & 0xffc0 == 0xfe80
+                    || ip.to_ipv4_mapped().is_some_and(|ip| is_local_ipv4(&ip))
+            }
+        }
+    }
+}
+
+fn is_local_ipv4(ip: &Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_private()
+        || ip.is_link_local()
+        // Shared address space of carrier-grade NATs, 100.64.0.0/10
+        || (first == 100 && second & 0xc0 == 64)
 }
 
 impl<'a> From<&'a Host> for multiaddr::Protocol<'a> {
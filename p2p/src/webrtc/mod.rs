@@ -22,3 +22,6 @@ pub use signaling_method::{HttpSignalingInfo, SignalingMethod, SignalingMethodPa
 
 mod connection_auth;
 pub use connection_auth::{ConnectionAuth, ConnectionAuthEncrypted};
+
+mod connection_stats;
+pub use connection_stats::ConnectionStats;
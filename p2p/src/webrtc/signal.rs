@@ -96,6 +96,18 @@ pub struct Offer {
     /// Port number of the signaling server of the offerer.
     /// Optional port for signaling server connections.
     pub listen_port: Option<u16>,
+
+    /// Whether the offerer accepts batched snark propagation messages, see
+    /// [`crate::channels::snark::SnarkPropagationChannelMsg::Snarks`].
+    /// Missing in offers of older versions, which can't decode them.
+    #[serde(default)]
+    pub snark_batching: bool,
+
+    /// Whether the offerer serves blocks over the streaming RPC, see
+    /// [`crate::channels::streaming_rpc::P2pStreamingRpcRequest::Block`].
+    /// Missing in offers of older versions, which don't know the request.
+    #[serde(default)]
+    pub block_streaming: bool,
 }
 
 /// WebRTC connection answer responding to an offer.
@@ -135,6 +147,16 @@ pub struct Answer {
     /// Peer ID of the original offerer that this answer is responding to.
     /// Ensures the answer reaches the correct peer that initiated the connection.
     pub target_peer_id: PeerId,
+
+    /// Whether the answering peer accepts batched snark propagation
+    /// messages. Missing in answers of older versions.
+    #[serde(default)]
+    pub snark_batching: bool,
+
+    /// Whether the answering peer serves blocks over the streaming RPC.
+    /// Missing in answers of older versions.
+    #[serde(default)]
+    pub block_streaming: bool,
 }
 
 /// Union type for WebRTC signaling messages.
@@ -218,6 +240,13 @@ pub enum RejectionReason {
     /// Not considered a "bad" rejection.
     #[error("self connection detected")]
     ConnectingToSelf,
+
+    /// The offering peer misbehaved and is banned for a while.
+    ///
+    /// Considered a "bad" rejection, the offering peer is expected to have
+    /// sent invalid messages.
+    #[error("peer is banned")]
+    Banned,
 }
 
 /// Response to a WebRTC connection offer.
@@ -360,6 +389,7 @@ impl RejectionReason {
     /// - [`PeerIdAndPublicKeyMismatch`] - Identity verification failure
     /// - [`TargetPeerIdNotMe`] - Targeting error or discovery issue
     /// - [`AlreadyConnected`] - Connection management issue
+    /// - [`Banned`] - Misbehavior of the offering peer
     ///
     /// # Normal Rejection Reasons
     ///
@@ -370,6 +400,7 @@ impl RejectionReason {
     /// [`PeerIdAndPublicKeyMismatch`]: RejectionReason::PeerIdAndPublicKeyMismatch
     /// [`TargetPeerIdNotMe`]: RejectionReason::TargetPeerIdNotMe
     /// [`AlreadyConnected`]: RejectionReason::AlreadyConnected
+    /// [`Banned`]: RejectionReason::Banned
     /// [`ChainIdMismatch`]: RejectionReason::ChainIdMismatch
     /// [`PeerCapacityFull`]: RejectionReason::PeerCapacityFull
     /// [`ConnectingToSelf`]: RejectionReason::ConnectingToSelf
@@ -381,6 +412,7 @@ impl RejectionReason {
             Self::PeerCapacityFull => false,
             Self::AlreadyConnected => true,
             Self::ConnectingToSelf => false,
+            Self::Banned => true,
         }
     }
 }
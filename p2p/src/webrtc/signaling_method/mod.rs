@@ -285,6 +285,13 @@ pub enum SignalingMethodParseError {
     /// 16-bit unsigned integer port number.
     #[error("port parse error: {0}")]
     PortParseError(String),
+
+    /// Failed to parse the relay peer ID for P2P relay methods.
+    ///
+    /// This occurs when the peer ID component is not a valid Base58Check
+    /// encoded peer ID.
+    #[error("peer id parse error: {0}")]
+    PeerIdParseError(String),
 }
 
 impl FromStr for SignalingMethod {
@@ -324,13 +331,15 @@ impl FromStr for SignalingMethod {
     /// - Unknown method types
     /// - Invalid numeric values (ports, cluster IDs)
     /// - Invalid host formats
+    /// - Invalid relay peer IDs
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s.is_empty() {
             return Err(SignalingMethodParseError::NotEnoughArgs);
         }
 
-        let method_end_index = s[1..]
-            .find('/')
+        let method_end_index = s
+            .get(1..)
+            .and_then(|s| s.find('/'))
             .map(|i| i + 1)
             .filter(|i| s.len() > *i)
             .ok_or(SignalingMethodParseError::NotEnoughArgs)?;
@@ -352,6 +361,15 @@ impl FromStr for SignalingMethod {
                     .or(Err(SignalingMethodParseError::InvalidClusterId))?;
                 Ok(Self::HttpsProxy(cluster_id, rest.parse()?))
             }
+            "p2p" => {
+                let relay_peer_id = rest
+                    .split('/')
+                    .find(|v| !v.trim().is_empty())
+                    .ok_or(SignalingMethodParseError::NotEnoughArgs)?
+                    .parse::<PeerId>()
+                    .map_err(|err| SignalingMethodParseError::PeerIdParseError(err.to_string()))?;
+                Ok(Self::P2p { relay_peer_id })
+            }
             method => Err(SignalingMethodParseError::UnknownSignalingMethod(
                 method.to_owned(),
             )),
@@ -648,6 +666,53 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_from_str_valid_p2p() {
+        let peer_id: PeerId = "2bEgBrPTzL8wov2D4Kz34WVLCxR4uCarsBmHYXWKQA5wvBQzd9H"
+            .parse()
+            .unwrap();
+        let method: SignalingMethod = format!("/p2p/{peer_id}").parse().unwrap();
+        assert_eq!(
+            method,
+            SignalingMethod::P2p {
+                relay_peer_id: peer_id
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_str_p2p_invalid_peer_id() {
+        let result: Result<SignalingMethod, _> = "/p2p/not-a-peer-id".parse();
+        assert!(matches!(
+            result.unwrap_err(),
+            SignalingMethodParseError::PeerIdParseError(_)
+        ));
+    }
+
+    #[test]
+    fn test_from_str_multibyte_prefix() {
+        // Must not slice inside the first character
+        let result: Result<SignalingMethod, _> = "é".parse();
+        assert!(matches!(
+            result.unwrap_err(),
+            SignalingMethodParseError::NotEnoughArgs
+        ));
+    }
+
+    #[test]
+    fn test_roundtrip_p2p() {
+        let original = SignalingMethod::P2p {
+            relay_peer_id: "2bEgBrPTzL8wov2D4Kz34WVLCxR4uCarsBmHYXWKQA5wvBQzd9H"
+                .parse()
+                .unwrap(),
+        };
+
+        let serialized = original.to_string();
+        let deserialized: SignalingMethod = serialized.parse().unwrap();
+
+        assert_eq!(original, deserialized);
+    }
+
     #[test]
     fn test_roundtrip_http() {
         let original = SignalingMethod::Http(HttpSignalingInfo {
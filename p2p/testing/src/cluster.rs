@@ -351,7 +351,9 @@ impl Cluster {
             libp2p_port: Some(libp2p_port),
             listen_port: Some(listen_port),
             identity_pub_key: secret_key.public_key(),
+            previous_peer_ids: Vec::new(),
             initial_peers,
+            initial_dnsaddrs: Vec::new(),
             external_addrs: vec![],
             enabled_channels: p2p::channels::ChannelId::for_libp2p().collect(),
             peer_discovery: config.discovery,
@@ -26,7 +26,7 @@ use p2p::{
     },
     connection::{
         incoming_effectful::P2pConnectionIncomingEffectfulAction,
-        outgoing::P2pConnectionOutgoingAction,
+        outgoing::{P2pConnectionOutgoingAction, P2pConnectionOutgoingError},
         outgoing_effectful::P2pConnectionOutgoingEffectfulAction,
     },
     disconnection::P2pDisconnectionAction,
@@ -37,10 +37,10 @@ use p2p::{
         P2pNetworkIdentifyStreamAction,
     },
     peer::P2pPeerAction,
-    MioEvent, P2pAction, P2pEffectfulAction, P2pEvent, P2pNetworkKadBootstrapAction,
-    P2pNetworkKadEffectfulAction, P2pNetworkKadRequestAction, P2pNetworkKademliaAction,
-    P2pNetworkKademliaStreamAction, P2pNetworkSchedulerAction, P2pNetworkYamuxAction, P2pState,
-    P2pStateTrait, PeerId,
+    MioEvent, P2pAction, P2pConnectionEvent, P2pEffectfulAction, P2pEvent,
+    P2pNetworkKadBootstrapAction, P2pNetworkKadEffectfulAction, P2pNetworkKadRequestAction,
+    P2pNetworkKademliaAction, P2pNetworkKademliaStreamAction, P2pNetworkSchedulerAction,
+    P2pNetworkYamuxAction, P2pState, P2pStateTrait, PeerId,
 };
 use redux::{ActionMeta, EnablingCondition, SubStore};
 
@@ -255,6 +255,29 @@ pub(super) fn event_effect(store: &mut crate::redux::Store, event: P2pEvent) ->
                 SubStore::dispatch(store, P2pNetworkSchedulerAction::Prune { addr })
             }
         },
+        P2pEvent::Connection(P2pConnectionEvent::HostResolved(peer_id, result)) => match result {
+            Ok(ip) => SubStore::dispatch(
+                store,
+                P2pConnectionOutgoingAction::HostResolveSuccess { peer_id, ip },
+            ),
+            Err(error) => SubStore::dispatch(
+                store,
+                P2pConnectionOutgoingAction::Error {
+                    peer_id,
+                    error: P2pConnectionOutgoingError::HostResolveError(error),
+                },
+            ),
+        },
+        P2pEvent::Connection(P2pConnectionEvent::DnsaddrResolved(addr, result)) => match result {
+            Ok((peers, ttl)) => SubStore::dispatch(
+                store,
+                P2pConnectionOutgoingAction::DnsaddrResolveSuccess { addr, peers, ttl },
+            ),
+            Err(error) => SubStore::dispatch(
+                store,
+                P2pConnectionOutgoingAction::DnsaddrResolveError { addr, error },
+            ),
+        },
         _ => false,
     }
 }
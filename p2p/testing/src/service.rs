@@ -82,6 +82,7 @@ impl P2pServiceWebrtcWithLibp2p for ClusterService {
 
 impl P2pServiceWebrtc for ClusterService {
     type Event = P2pEvent;
+    type EventSender = mpsc::UnboundedSender<P2pEvent>;
 
     fn random_pick(
         &mut self,
@@ -90,7 +91,7 @@ impl P2pServiceWebrtc for ClusterService {
         list.choose(&mut self.rng).cloned()
     }
 
-    fn event_sender(&self) -> &mpsc::UnboundedSender<Self::Event> {
+    fn event_sender(&self) -> &Self::EventSender {
         &self.event_sender
     }
 
@@ -0,0 +1,54 @@
+#![allow(unexpected_cfgs)]
+#![cfg(all(test, fuzzing))]
+
+//! Fuzz targets for the parsers covered by `parsers.rs`. Failing inputs
+//! should be added to `files/parsers-corpus`.
+
+use std::str::FromStr;
+
+use p2p::{
+    connection::outgoing::P2pConnectionOutgoingInitOpts,
+    webrtc::{Host, SignalingMethod},
+    PeerId,
+};
+
+fn try_parse<T>(buf: &[u8]) -> bool
+where
+    T: FromStr,
+{
+    if let Ok(s) = std::str::from_utf8(buf) {
+        let _ = T::from_str(s);
+    }
+    true
+}
+
+fn fuzz<F>(f: F)
+where
+    F: Fn(&[u8]) -> bool + 'static,
+{
+    let result = fuzzcheck::fuzz_test(f)
+        .default_options()
+        .stop_after_first_test_failure(true)
+        .launch();
+    assert!(!result.found_test_failure);
+}
+
+#[test]
+fn signaling_method() {
+    fuzz(try_parse::<SignalingMethod>);
+}
+
+#[test]
+fn connection_opts() {
+    fuzz(try_parse::<P2pConnectionOutgoingInitOpts>);
+}
+
+#[test]
+fn peer_id() {
+    fuzz(try_parse::<PeerId>);
+}
+
+#[test]
+fn host() {
+    fuzz(try_parse::<Host>);
+}
@@ -0,0 +1,171 @@
+//! Property-based tests for the parsers of peer addresses and signaling
+//! methods, which parse untrusted input from the CLI, RPCs and peers.
+//!
+//! Inputs stored in `files/parsers-corpus` are replayed by [`corpus`].
+//! Failing inputs found by the fuzz targets in `parsers-fuzz.rs` should be
+//! added there.
+
+use std::{
+    fs,
+    net::{Ipv4Addr, Ipv6Addr},
+    path::Path,
+    str::FromStr,
+};
+
+use p2p::{
+    connection::outgoing::{P2pConnectionOutgoingInitLibp2pOpts, P2pConnectionOutgoingInitOpts},
+    identity::SecretKey,
+    multiaddr::Multiaddr,
+    webrtc::{Host, HttpSignalingInfo, SignalingMethod},
+    PeerId,
+};
+use proptest::prelude::*;
+
+/// Peer ids of valid ed25519 keys, so they can be converted to libp2p ones.
+fn peer_id() -> impl Strategy<Value = PeerId> {
+    any::<[u8; 32]>().prop_map(|bytes| SecretKey::from_bytes(bytes).public_key().peer_id())
+}
+
+fn host() -> impl Strategy<Value = Host> {
+    prop_oneof![
+        "[a-z][a-z0-9]{0,15}(\\.[a-z][a-z0-9]{0,15}){0,3}".prop_map(Host::Domain),
+        any::<[u8; 4]>().prop_map(|octets| Host::Ipv4(Ipv4Addr::from(octets))),
+        any::<[u16; 8]>().prop_map(|segments| Host::Ipv6(Ipv6Addr::from(segments))),
+    ]
+}
+
+fn http_signaling_info() -> impl Strategy<Value = HttpSignalingInfo> {
+    (host(), any::<u16>()).prop_map(|(host, port)| HttpSignalingInfo { host, port })
+}
+
+fn signaling_method() -> impl Strategy<Value = SignalingMethod> {
+    prop_oneof![
+        http_signaling_info().prop_map(SignalingMethod::Http),
+        http_signaling_info().prop_map(SignalingMethod::Https),
+        (any::<u16>(), http_signaling_info())
+            .prop_map(|(cluster_id, info)| SignalingMethod::HttpsProxy(cluster_id, info)),
+        peer_id().prop_map(|relay_peer_id| SignalingMethod::P2p { relay_peer_id }),
+    ]
+}
+
+fn libp2p_opts() -> impl Strategy<Value = P2pConnectionOutgoingInitLibp2pOpts> {
+    (peer_id(), host(), any::<u16>()).prop_map(|(peer_id, host, port)| {
+        P2pConnectionOutgoingInitLibp2pOpts {
+            peer_id,
+            host,
+            port,
+        }
+    })
+}
+
+fn connection_opts() -> impl Strategy<Value = P2pConnectionOutgoingInitOpts> {
+    prop_oneof![
+        (peer_id(), signaling_method()).prop_map(|(peer_id, signaling)| {
+            P2pConnectionOutgoingInitOpts::WebRTC { peer_id, signaling }
+        }),
+        libp2p_opts().prop_map(P2pConnectionOutgoingInitOpts::LibP2P),
+    ]
+}
+
+/// Strings made of path segments the parsers look for, mixed with arbitrary
+/// ones, so that inputs get past the first checks more often than random
+/// strings do.
+fn address_like() -> impl Strategy<Value = String> {
+    let segment = prop_oneof![
+        Just(String::new()),
+        Just("http".to_owned()),
+        Just("https".to_owned()),
+        Just("https_proxy".to_owned()),
+        Just("p2p".to_owned()),
+        Just("ip4".to_owned()),
+        Just("ip6".to_owned()),
+        Just("dns4".to_owned()),
+        Just("tcp".to_owned()),
+        "[0-9]{1,6}",
+        "[a-zA-Z0-9.:\\[\\]%-]{1,16}",
+        any::<String>(),
+    ];
+    prop::collection::vec(segment, 0..8).prop_map(|segments| format!("/{}", segments.join("/")))
+}
+
+/// Runs the input through every parser. Must never panic.
+fn parse_all(s: &str) {
+    let _ = PeerId::from_str(s);
+    let _ = Host::from_str(s);
+    let _ = HttpSignalingInfo::from_str(s);
+    let _ = Multiaddr::from_str(s);
+    let _ = P2pConnectionOutgoingInitOpts::from_str(s);
+
+    // Whatever is accepted must be printed in a form that parses back to the
+    // same value.
+    if let Ok(method) = SignalingMethod::from_str(s) {
+        assert_eq!(
+            method.to_string().parse::<SignalingMethod>().ok(),
+            Some(method)
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn peer_id_roundtrip(bytes in any::<[u8; 32]>()) {
+        let peer_id = PeerId::from_bytes(bytes);
+        prop_assert_eq!(peer_id.to_string().parse::<PeerId>().ok(), Some(peer_id));
+    }
+
+    #[test]
+    fn peer_id_rejects_corrupted(peer_id in peer_id(), index in any::<prop::sample::Index>()) {
+        let mut s = peer_id.to_string().into_bytes();
+        let i = index.index(s.len());
+        s[i] = if s[i] == b'2' { b'3' } else { b'2' };
+        prop_assert!(PeerId::from_str(std::str::from_utf8(&s).unwrap()).is_err());
+    }
+
+    #[test]
+    fn host_roundtrip(host in host()) {
+        prop_assert_eq!(host.to_string().parse::<Host>().ok(), Some(host));
+    }
+
+    #[test]
+    fn signaling_method_roundtrip(method in signaling_method()) {
+        prop_assert_eq!(method.to_string().parse::<SignalingMethod>().ok(), Some(method));
+    }
+
+    #[test]
+    fn connection_opts_roundtrip(opts in connection_opts()) {
+        prop_assert_eq!(
+            opts.to_string().parse::<P2pConnectionOutgoingInitOpts>().ok(),
+            Some(opts)
+        );
+    }
+
+    #[test]
+    fn libp2p_opts_multiaddr_roundtrip(opts in libp2p_opts()) {
+        let maddr = opts.to_maddr().expect("valid peer id");
+        prop_assert_eq!(
+            P2pConnectionOutgoingInitLibp2pOpts::try_from(&maddr).ok(),
+            Some(opts)
+        );
+    }
+
+    #[test]
+    fn arbitrary_strings(s in any::<String>()) {
+        parse_all(&s);
+    }
+
+    #[test]
+    fn address_like_strings(s in address_like()) {
+        parse_all(&s);
+    }
+}
+
+#[test]
+fn corpus() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/files/parsers-corpus");
+    for entry in fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        let input = fs::read(&path).unwrap();
+        eprintln!("{}", path.display());
+        parse_all(&String::from_utf8_lossy(&input));
+    }
+}
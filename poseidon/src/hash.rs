@@ -251,6 +251,8 @@ impl LazyParam {
 }
 
 pub mod params {
+    use std::{collections::BTreeMap, sync::Mutex};
+
     use once_cell::sync::Lazy;
 
     use super::*;
@@ -361,6 +363,9 @@ pub mod params {
         ARRAY[height]
     }
 
+    /// Params of the merkle tree node at `height`. Heights above the 35
+    /// of the built-in ledgers, for ledgers of custom networks, get their
+    /// params created on first use.
     pub fn get_merkle_param_for_height(height: usize) -> &'static LazyParam {
         static ARRAY: [&Lazy<Box<LazyParam>>; 36] = [
             &MINA_MERKLE_TREE_0,
@@ -401,7 +406,32 @@ pub mod params {
             &MINA_MERKLE_TREE_35,
         ];
 
-        ARRAY[height]
+        match ARRAY.get(height) {
+            Some(param) => param,
+            None => get_extra_merkle_param_for_height(height),
+        }
+    }
+
+    fn get_extra_merkle_param_for_height(height: usize) -> &'static LazyParam {
+        static EXTRA: Lazy<Mutex<BTreeMap<usize, &'static LazyParam>>> =
+            Lazy::new(Default::default);
+
+        let mut extra = EXTRA.lock().unwrap();
+        *extra.entry(height).or_insert_with(|| {
+            // Leaked on purpose, like the built-in params they live until
+            // the process exits.
+            let string: &'static str =
+                Box::leak(format!("MinaMklTree{height:03}").into_boxed_str());
+            let mut sponge = Sponge::<Fp>::default();
+            sponge.absorb(&[param_to_field(string)]);
+            let last_squeezed = sponge.squeeze();
+            Box::leak(Box::new(LazyParam {
+                sponge_state: sponge.sponge_state,
+                state: sponge.state,
+                last_squeezed,
+                string,
+            }))
+        })
     }
 
     macro_rules! impl_params_noinput {
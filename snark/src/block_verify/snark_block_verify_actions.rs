@@ -1,7 +1,9 @@
 use mina_core::{block::BlockHash, ActionEvent, SubstateAccess};
 use serde::{Deserialize, Serialize};
 
-use super::{SnarkBlockVerifyError, SnarkBlockVerifyId, VerifiableBlockWithHash};
+use super::{
+    SnarkBlockVerifyError, SnarkBlockVerifyId, SnarkBlockVerifyPriority, VerifiableBlockWithHash,
+};
 
 pub type SnarkBlockVerifyActionWithMeta = redux::ActionWithMeta<SnarkBlockVerifyAction>;
 pub type SnarkBlockVerifyActionWithMetaRef<'a> = redux::ActionWithMeta<&'a SnarkBlockVerifyAction>;
@@ -10,11 +12,20 @@ pub type SnarkBlockVerifyActionWithMetaRef<'a> = redux::ActionWithMeta<&'a Snark
 pub enum SnarkBlockVerifyAction {
     Init {
         block: VerifiableBlockWithHash,
+        priority: SnarkBlockVerifyPriority,
 
         on_init: redux::Callback<(BlockHash, SnarkBlockVerifyId)>,
         on_success: redux::Callback<BlockHash>,
         on_error: redux::Callback<(BlockHash, SnarkBlockVerifyError)>,
     },
+    /// Sends the queued block with the highest priority to the verifier.
+    #[action_event(level = trace)]
+    Next,
+    /// Priorities of the queued blocks recomputed after the best tip
+    /// changed, reordering them.
+    PrioritiesUpdate {
+        priorities: Vec<(SnarkBlockVerifyId, SnarkBlockVerifyPriority)>,
+    },
     Pending {
         req_id: SnarkBlockVerifyId,
     },
@@ -25,15 +36,25 @@ pub enum SnarkBlockVerifyAction {
     Success {
         req_id: SnarkBlockVerifyId,
     },
+    /// The verifier didn't answer in time, the request is sent again or
+    /// fails with [`SnarkBlockVerifyError::Timeout`].
+    #[action_event(level = warn, fields(display(req_id)))]
+    Timeout {
+        req_id: SnarkBlockVerifyId,
+    },
     Finish {
         req_id: SnarkBlockVerifyId,
     },
 }
 
 impl redux::EnablingCondition<crate::SnarkState> for SnarkBlockVerifyAction {
-    fn is_enabled(&self, state: &crate::SnarkState, _time: redux::Timestamp) -> bool {
+    fn is_enabled(&self, state: &crate::SnarkState, time: redux::Timestamp) -> bool {
         match self {
             SnarkBlockVerifyAction::Init { .. } => true,
+            SnarkBlockVerifyAction::Next => state.block_verify.next_to_verify().is_some(),
+            SnarkBlockVerifyAction::PrioritiesUpdate { priorities } => {
+                state.block_verify.priorities_changed(priorities)
+            }
             SnarkBlockVerifyAction::Pending { req_id } => state
                 .block_verify
                 .jobs
@@ -49,6 +70,9 @@ impl redux::EnablingCondition<crate::SnarkState> for SnarkBlockVerifyAction {
                 .jobs
                 .get(*req_id)
                 .is_some_and(|v| v.is_pending()),
+            SnarkBlockVerifyAction::Timeout { req_id } => {
+                state.block_verify.is_timed_out(*req_id, time)
+            }
             SnarkBlockVerifyAction::Finish { req_id } => state
                 .block_verify
                 .jobs
@@ -1,11 +1,14 @@
 use mina_core::{Substate, SubstateAccess};
 use redux::EnablingCondition;
 
-use crate::block_verify_effectful::SnarkBlockVerifyEffectfulAction;
+use crate::{
+    block_verify_effectful::SnarkBlockVerifyEffectfulAction, SnarkVerifyCacheKey,
+    SnarkVerifyJobKind, SnarkVerifyTiming,
+};
 
 use super::{
-    SnarkBlockVerifyAction, SnarkBlockVerifyActionWithMetaRef, SnarkBlockVerifyState,
-    SnarkBlockVerifyStatus,
+    SnarkBlockVerifyAction, SnarkBlockVerifyActionWithMetaRef, SnarkBlockVerifyError,
+    SnarkBlockVerifyState, SnarkBlockVerifyStatus,
 };
 
 pub fn reducer<State, Action>(
@@ -27,6 +30,7 @@ pub fn reducer<State, Action>(
     match action {
         SnarkBlockVerifyAction::Init {
             block,
+            priority,
             on_init,
             on_success,
             on_error,
@@ -34,18 +38,51 @@ pub fn reducer<State, Action>(
             let req_id = state.jobs.add(SnarkBlockVerifyStatus::Init {
                 time: meta.time(),
                 block: block.clone(),
+                priority: *priority,
                 on_success: on_success.clone(),
                 on_error: on_error.clone(),
             });
 
+            // Dispatch
+            let dispatcher = state_context.into_dispatcher();
+            dispatcher.push_callback(on_init.clone(), (block.hash_ref().clone(), req_id));
+            dispatcher.push(SnarkBlockVerifyAction::Next);
+        }
+        SnarkBlockVerifyAction::PrioritiesUpdate { priorities } => {
+            state.set_priorities(priorities);
+        }
+        SnarkBlockVerifyAction::Next => {
+            let Some(req_id) = state.next_to_verify() else {
+                return;
+            };
+            let Some(job) = state.jobs.get(req_id) else {
+                return;
+            };
+            let block = job.block().clone();
+
+            // Blocks received again, e.g. during catchup, aren't sent to
+            // the verifier when their result is cached.
+            let cached = state
+                .cache
+                .get(&SnarkVerifyCacheKey::block(&block))
+                .cloned();
+            if let Some(result) = cached {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(SnarkBlockVerifyAction::Pending { req_id });
+                match result {
+                    Ok(()) => dispatcher.push(SnarkBlockVerifyAction::Success { req_id }),
+                    Err(error) => dispatcher.push(SnarkBlockVerifyAction::Error { req_id, error }),
+                }
+                return;
+            }
+
             // Dispatch
             let verifier_index = state.verifier_index.clone();
             let verifier_srs = state.verifier_srs.clone();
             let dispatcher = state_context.into_dispatcher();
-            dispatcher.push_callback(on_init.clone(), (block.hash_ref().clone(), req_id));
             dispatcher.push(SnarkBlockVerifyEffectfulAction::Init {
                 req_id,
-                block: block.clone(),
+                block,
                 verifier_index,
                 verifier_srs,
             });
@@ -55,15 +92,18 @@ pub fn reducer<State, Action>(
             if let Some(req) = state.jobs.get_mut(*req_id) {
                 *req = match req {
                     SnarkBlockVerifyStatus::Init {
+                        time,
                         block,
                         on_success,
                         on_error,
                         ..
                     } => SnarkBlockVerifyStatus::Pending {
                         time: meta.time(),
+                        timing: SnarkVerifyTiming::queued(*time).started(meta.time()),
                         block: block.clone(),
                         on_success: on_success.clone(),
                         on_error: on_error.clone(),
+                        retries: 0,
                     },
                     _ => return,
                 };
@@ -72,13 +112,26 @@ pub fn reducer<State, Action>(
         SnarkBlockVerifyAction::Error { req_id, error, .. } => {
             let callback_and_arg = state.jobs.get_mut(*req_id).and_then(|req| {
                 if let SnarkBlockVerifyStatus::Pending {
-                    block, on_error, ..
+                    block,
+                    on_error,
+                    timing,
+                    ..
                 } = req
                 {
                     let callback = on_error.clone();
                     let block_hash = block.hash_ref().clone();
+                    if error.is_deterministic() {
+                        let key = SnarkVerifyCacheKey::block(block);
+                        state.cache.insert(key, Err(error.clone()));
+                    }
+                    let timing = timing.finished(meta.time());
+                    state.stats.add(&timing, false, 1);
+                    state
+                        .history
+                        .push(SnarkVerifyJobKind::Block, &timing, false, 1);
                     *req = SnarkBlockVerifyStatus::Error {
                         time: meta.time(),
+                        timing,
                         block: block.clone(),
                         error: error.clone(),
                     };
@@ -101,13 +154,25 @@ pub fn reducer<State, Action>(
         SnarkBlockVerifyAction::Success { req_id, .. } => {
             let callback_and_arg = state.jobs.get_mut(*req_id).and_then(|req| {
                 if let SnarkBlockVerifyStatus::Pending {
-                    block, on_success, ..
+                    block,
+                    on_success,
+                    timing,
+                    ..
                 } = req
                 {
                     let callback = on_success.clone();
                     let block_hash = block.hash_ref().clone();
+                    state
+                        .cache
+                        .insert(SnarkVerifyCacheKey::block(block), Ok(()));
+                    let timing = timing.finished(meta.time());
+                    state.stats.add(&timing, true, 1);
+                    state
+                        .history
+                        .push(SnarkVerifyJobKind::Block, &timing, true, 1);
                     *req = SnarkBlockVerifyStatus::Success {
                         time: meta.time(),
+                        timing,
                         block: block.clone(),
                     };
                     Some((callback, block_hash))
@@ -125,8 +190,48 @@ pub fn reducer<State, Action>(
 
             dispatcher.push(SnarkBlockVerifyAction::Finish { req_id: *req_id });
         }
+        SnarkBlockVerifyAction::Timeout { req_id } => {
+            let Some(timeout) = state.timeout else {
+                return;
+            };
+            let Some(SnarkBlockVerifyStatus::Pending {
+                time,
+                block,
+                retries,
+                ..
+            }) = state.jobs.get_mut(*req_id)
+            else {
+                return;
+            };
+
+            if !timeout.can_retry(*retries) {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(SnarkBlockVerifyAction::Error {
+                    req_id: *req_id,
+                    error: SnarkBlockVerifyError::Timeout,
+                });
+                return;
+            }
+            *time = meta.time();
+            *retries += 1;
+            let block = block.clone();
+
+            // Dispatch
+            let verifier_index = state.verifier_index.clone();
+            let verifier_srs = state.verifier_srs.clone();
+            let dispatcher = state_context.into_dispatcher();
+            dispatcher.push(SnarkBlockVerifyEffectfulAction::Init {
+                req_id: *req_id,
+                block,
+                verifier_index,
+                verifier_srs,
+            });
+        }
         SnarkBlockVerifyAction::Finish { req_id, .. } => {
             state.jobs.remove(*req_id);
+
+            let dispatcher = state_context.into_dispatcher();
+            dispatcher.push(SnarkBlockVerifyAction::Next);
         }
     }
 }
@@ -1,34 +1,144 @@
-use std::sync::Arc;
+use std::{cmp::Reverse, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 
-use mina_core::{block::BlockHash, requests::PendingRequests};
+use mina_core::{
+    block::BlockHash,
+    requests::{PendingRequests, RequestRetryPolicy},
+};
 
-use crate::{BlockVerifier, VerifierSRS};
+use crate::{
+    BlockVerifier, SnarkVerifyCache, SnarkVerifyCacheConfig, SnarkVerifyHistory,
+    SnarkVerifyLatencyStats, SnarkVerifyTiming, VerifierSRS,
+};
 
 use super::{
     SnarkBlockVerifyError, SnarkBlockVerifyId, SnarkBlockVerifyIdType, VerifiableBlockWithHash,
 };
 
+/// Max number of blocks sent to the verifier at once. The verifier
+/// verifies one block at a time, so more requests would only be queued in
+/// the service, where they can't be reordered by priority.
+pub const SNARK_BLOCK_VERIFY_MAX_PENDING: usize = 1;
+
+/// Order in which queued blocks are verified, higher priority first, and
+/// in the order they were received for the same priority.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SnarkBlockVerifyPriority {
+    /// Blocks which wouldn't become our best tip, e.g. on a side chain.
+    #[default]
+    Low,
+    /// Blocks which would become our best tip once verified.
+    High,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SnarkBlockVerifyState {
     pub verifier_index: BlockVerifier,
     pub verifier_srs: Arc<VerifierSRS>,
     pub jobs: PendingRequests<SnarkBlockVerifyIdType, SnarkBlockVerifyStatus>,
+    #[serde(default)]
+    pub timeout: Option<RequestRetryPolicy>,
+    #[serde(default)]
+    pub cache: SnarkVerifyCache<Result<(), SnarkBlockVerifyError>>,
+    #[serde(default)]
+    pub stats: SnarkVerifyLatencyStats,
+    #[serde(default)]
+    pub history: SnarkVerifyHistory,
 }
 
 impl SnarkBlockVerifyState {
-    pub fn new(verifier_index: BlockVerifier, verifier_srs: Arc<VerifierSRS>) -> Self {
+    pub fn new(
+        verifier_index: BlockVerifier,
+        verifier_srs: Arc<VerifierSRS>,
+        timeout: Option<RequestRetryPolicy>,
+        cache: SnarkVerifyCacheConfig,
+    ) -> Self {
         Self {
             verifier_index,
             verifier_srs,
             jobs: Default::default(),
+            timeout,
+            cache: SnarkVerifyCache::new(cache),
+            stats: Default::default(),
+            history: Default::default(),
         }
     }
 
     pub fn next_req_id(&self) -> SnarkBlockVerifyId {
         self.jobs.next_req_id()
     }
+
+    /// Queued request to send to the verifier next, if the verifier can
+    /// take more requests.
+    pub fn next_to_verify(&self) -> Option<SnarkBlockVerifyId> {
+        let pending = self.jobs.iter().filter(|(_, job)| job.is_pending()).count();
+        if pending >= SNARK_BLOCK_VERIFY_MAX_PENDING {
+            return None;
+        }
+        highest_priority(
+            self.jobs
+                .iter()
+                .filter_map(|(req_id, job)| Some((req_id, job.priority()?))),
+        )
+    }
+
+    /// Whether `priorities` would change the priority of a queued request.
+    pub fn priorities_changed(
+        &self,
+        priorities: &[(SnarkBlockVerifyId, SnarkBlockVerifyPriority)],
+    ) -> bool {
+        priorities.iter().any(|(req_id, priority)| {
+            self.jobs
+                .get(*req_id)
+                .and_then(SnarkBlockVerifyStatus::priority)
+                .is_some_and(|current| current != *priority)
+        })
+    }
+
+    /// Updates the priorities of the queued requests, e.g. once the best
+    /// tip changed. Requests already sent to the verifier are left as is.
+    pub fn set_priorities(
+        &mut self,
+        priorities: &[(SnarkBlockVerifyId, SnarkBlockVerifyPriority)],
+    ) {
+        for (req_id, new_priority) in priorities {
+            if let Some(SnarkBlockVerifyStatus::Init { priority, .. }) = self.jobs.get_mut(*req_id)
+            {
+                *priority = *new_priority;
+            }
+        }
+    }
+
+    pub fn is_timed_out(&self, req_id: SnarkBlockVerifyId, now: redux::Timestamp) -> bool {
+        let Some(timeout) = &self.timeout else {
+            return false;
+        };
+        match self.jobs.get(req_id) {
+            Some(SnarkBlockVerifyStatus::Pending { time, .. }) => timeout.is_timed_out(*time, now),
+            _ => false,
+        }
+    }
+
+    pub fn timed_out_jobs(
+        &self,
+        now: redux::Timestamp,
+    ) -> impl Iterator<Item = SnarkBlockVerifyId> + '_ {
+        self.jobs
+            .iter()
+            .map(|(req_id, _)| req_id)
+            .filter(move |req_id| self.is_timed_out(*req_id, now))
+    }
+}
+
+/// Queued request with the highest priority, the oldest one among the
+/// requests with the same priority.
+fn highest_priority<Id: Copy + Ord>(
+    queued: impl Iterator<Item = (Id, SnarkBlockVerifyPriority)>,
+) -> Option<Id> {
+    queued
+        .max_by_key(|(req_id, priority)| (*priority, Reverse(*req_id)))
+        .map(|(req_id, _)| req_id)
 }
 
 impl std::fmt::Debug for SnarkBlockVerifyState {
@@ -38,31 +148,48 @@ impl std::fmt::Debug for SnarkBlockVerifyState {
             .field("verifier_index", &"<content too big>")
             .field("verifier_srs", &"<content too big>")
             .field("jobs", &self.jobs)
+            .field("timeout", &self.timeout)
+            .field("cache", &self.cache)
+            .field("stats", &self.stats)
+            .field("history", &self.history.iter().count())
             .finish()
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum SnarkBlockVerifyStatus {
+    /// Queued until the verifier can take it, see
+    /// [`SnarkBlockVerifyState::next_to_verify`].
     Init {
         time: redux::Timestamp,
         block: VerifiableBlockWithHash,
+        #[serde(default)]
+        priority: SnarkBlockVerifyPriority,
         on_success: redux::Callback<BlockHash>,
         on_error: redux::Callback<(BlockHash, SnarkBlockVerifyError)>,
     },
     Pending {
         time: redux::Timestamp,
+        #[serde(default)]
+        timing: SnarkVerifyTiming,
         block: VerifiableBlockWithHash,
         on_success: redux::Callback<BlockHash>,
         on_error: redux::Callback<(BlockHash, SnarkBlockVerifyError)>,
+        /// Times the request was sent again after timing out.
+        #[serde(default)]
+        retries: u8,
     },
     Error {
         time: redux::Timestamp,
+        #[serde(default)]
+        timing: SnarkVerifyTiming,
         block: VerifiableBlockWithHash,
         error: SnarkBlockVerifyError,
     },
     Success {
         time: redux::Timestamp,
+        #[serde(default)]
+        timing: SnarkVerifyTiming,
         block: VerifiableBlockWithHash,
     },
 }
@@ -80,6 +207,23 @@ impl SnarkBlockVerifyStatus {
         matches!(self, Self::Error { .. } | Self::Success { .. })
     }
 
+    /// Priority of a queued request, `None` once sent to the verifier.
+    pub fn priority(&self) -> Option<SnarkBlockVerifyPriority> {
+        match self {
+            Self::Init { priority, .. } => Some(*priority),
+            _ => None,
+        }
+    }
+
+    pub fn timing(&self) -> SnarkVerifyTiming {
+        match self {
+            Self::Init { time, .. } => SnarkVerifyTiming::queued(*time),
+            Self::Pending { timing, .. } => *timing,
+            Self::Error { timing, .. } => *timing,
+            Self::Success { timing, .. } => *timing,
+        }
+    }
+
     pub fn block(&self) -> &VerifiableBlockWithHash {
         match self {
             Self::Init { block, .. } => block,
@@ -89,3 +233,39 @@ impl SnarkBlockVerifyStatus {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SnarkBlockVerifyPriority::{High, Low};
+
+    /// Order in which the queued requests are sent to the verifier.
+    fn order(mut queued: Vec<(u64, SnarkBlockVerifyPriority)>) -> Vec<u64> {
+        let mut order = Vec::new();
+        while let Some(next) = highest_priority(queued.iter().copied()) {
+            order.push(next);
+            queued.retain(|(req_id, _)| *req_id != next);
+        }
+        order
+    }
+
+    #[test]
+    fn test_best_tip_candidates_first() {
+        assert_eq!(
+            order(vec![(1, Low), (2, High), (3, Low), (4, High)]),
+            [2, 4, 1, 3]
+        );
+        assert_eq!(order(vec![(1, Low), (2, Low), (3, Low)]), [1, 2, 3]);
+        assert_eq!(order(vec![]), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_order_follows_best_tip_change() {
+        // Blocks 1 and 2 extended the best tip when they were received.
+        assert_eq!(order(vec![(1, High), (2, High), (3, Low)]), [1, 2, 3]);
+
+        // A better chain was found, which block 3 extends, while blocks 1
+        // and 2 are now on a side chain.
+        assert_eq!(order(vec![(1, Low), (2, Low), (3, High)]), [3, 1, 2]);
+    }
+}
@@ -11,6 +11,7 @@ mod snark_block_verify_effects;
 mod snark_block_verify_service;
 pub use snark_block_verify_service::*;
 
+use mina_core::{ErrorCode, HasErrorCode};
 use serde::{Deserialize, Serialize};
 
 pub struct SnarkBlockVerifyIdType;
@@ -22,9 +23,56 @@ impl mina_core::requests::RequestIdType for SnarkBlockVerifyIdType {
 
 pub type SnarkBlockVerifyId = mina_core::requests::RequestId<SnarkBlockVerifyIdType>;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, thiserror::Error)]
 pub enum SnarkBlockVerifyError {
+    /// The protocol state, whose hash is the statement of the proof, can't
+    /// be decoded.
+    #[error("invalid protocol state")]
+    InvalidProtocolState,
+    #[error("accumulator check failed")]
     AccumulatorCheckFailed,
+    #[error("verification failed")]
     VerificationFailed,
+    #[error("validator thread crashed")]
     ValidatorThreadCrashed,
+    #[error("verifier didn't answer in time")]
+    Timeout,
+}
+
+impl SnarkBlockVerifyError {
+    /// Whether the block itself is invalid, so verifying it again would
+    /// fail the same way. Otherwise the verifier failed, which says nothing
+    /// about the block.
+    pub fn is_deterministic(&self) -> bool {
+        match self {
+            Self::InvalidProtocolState
+            | Self::AccumulatorCheckFailed
+            | Self::VerificationFailed => true,
+            Self::ValidatorThreadCrashed | Self::Timeout => false,
+        }
+    }
+}
+
+impl From<ledger::proofs::verification::BlockProofError> for SnarkBlockVerifyError {
+    fn from(value: ledger::proofs::verification::BlockProofError) -> Self {
+        use ledger::proofs::verification::BlockProofError;
+
+        match value {
+            BlockProofError::InvalidProtocolState => Self::InvalidProtocolState,
+            BlockProofError::AccumulatorCheckFailed => Self::AccumulatorCheckFailed,
+            BlockProofError::VerificationFailed => Self::VerificationFailed,
+        }
+    }
+}
+
+impl HasErrorCode for SnarkBlockVerifyError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidProtocolState => ErrorCode::InvalidStatement,
+            Self::AccumulatorCheckFailed => ErrorCode::AccumulatorCheckFailed,
+            Self::VerificationFailed => ErrorCode::InvalidProof,
+            Self::ValidatorThreadCrashed => ErrorCode::VerifierCrashed,
+            Self::Timeout => ErrorCode::VerifierTimeout,
+        }
+    }
 }
@@ -41,6 +41,8 @@
 //! - [`block_verify_effectful`] - Block verification services
 //! - [`user_command_verify_effectful`] - Transaction verification services
 //! - [`work_verify_effectful`] - Work verification services
+//! - [`verify_dry_run_effectful`] - Verification dry runs, reporting each
+//!   check
 //!
 //! ## Configuration
 //!
@@ -72,6 +74,8 @@
 //! ## Performance Considerations
 //!
 //! - Verifier indices and SRS parameters are cached for reuse
+//! - A single SRS instance, from [`get_srs`], is shared by all the verifiers
+//!   and the provers of the process
 //! - Multiple verification operations can run concurrently
 //!
 //! For detailed API documentation, see the individual module documentation.
@@ -85,12 +89,13 @@ pub use ledger::proofs::{
     verifiers::{BlockVerifier, TransactionVerifier},
 };
 
-pub use merkle_path::calc_merkle_root_hash;
+pub use merkle_path::{calc_merkle_root_hash, MerklePathError, MerklePathVerifier, MerkleTreeKind};
 
 pub mod block_verify;
 pub mod block_verify_effectful;
 pub mod user_command_verify;
 pub mod user_command_verify_effectful;
+pub mod verify_dry_run_effectful;
 pub mod work_verify;
 pub mod work_verify_effectful;
 
@@ -106,6 +111,12 @@ pub use snark_config::*;
 mod snark_state;
 pub use snark_state::*;
 
+mod snark_verify_cache;
+pub use snark_verify_cache::*;
+
+mod snark_stats;
+pub use snark_stats::*;
+
 mod snark_reducer;
 
 pub type VerifierIndex = ledger::proofs::VerifierIndex<mina_curves::pasta::Fq>;
@@ -118,6 +129,7 @@ pub trait SnarkStore<GlobalState>:
 }
 impl<S, T: SubStore<S, SnarkState, SubAction = SnarkAction>> SnarkStore<S> for T {}
 
+/// Returns the SRS shared by the verifiers and the provers of the process.
 pub fn get_srs() -> std::sync::Arc<poly_commitment::ipa::SRS<Vesta>> {
     ledger::verifier::get_srs::<mina_curves::pasta::Fp>()
 }
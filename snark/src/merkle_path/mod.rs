@@ -8,12 +8,167 @@
 //! ledger state.
 //! This is commonly used in transaction verification (ensuring account exists).
 //! It uses the Poseidon hash function, as specified in the Mina protocol.
+//!
+//! [`MerklePathVerifier`] verifies paths of trees of any depth, given the
+//! hash of the leaf, the directions and the sibling hashes. It supports the
+//! ledger trees, e.g. the staking and next epoch ledgers, and the tree of
+//! pending coinbase stacks of the staged ledger. The other parts of the
+//! staged ledger hash, like the scan state hash, aren't Merkle trees.
 
+use ledger::{Direction, MerklePath};
+use mina_curves::pasta::Fp;
 use mina_p2p_messages::{
     bigint::{BigInt, InvalidBigInt},
     v2::MerkleTreeNode,
 };
-use poseidon::hash::params::get_merkle_param_for_height;
+use poseidon::hash::params::{get_coinbase_param_for_height, get_merkle_param_for_height};
+
+/// Kind of Merkle tree, which tells how its inner nodes are hashed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MerkleTreeKind {
+    /// Tree of accounts, e.g. the staking and next epoch ledgers.
+    Ledger,
+    /// Tree of the pending coinbase stacks.
+    PendingCoinbase,
+}
+
+impl MerkleTreeKind {
+    /// Max depth of the tree, the hash parameters being defined per height.
+    pub const fn max_depth(self) -> usize {
+        match self {
+            Self::Ledger => 36,
+            Self::PendingCoinbase => 6,
+        }
+    }
+
+    /// Hash of the inner node at `height` (0 being the parent of the
+    /// leaves) with the given children.
+    pub fn hash_node(self, height: usize, left: Fp, right: Fp) -> Fp {
+        let param = match self {
+            Self::Ledger => get_merkle_param_for_height(height),
+            Self::PendingCoinbase => get_coinbase_param_for_height(height),
+        };
+        poseidon::hash::hash_with_kimchi(param, &[left, right])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MerklePathError {
+    #[error("depth {depth} is larger than the max depth {max} of the tree")]
+    DepthTooLarge { depth: usize, max: usize },
+    #[error("path of depth {depth} has {directions} directions and {siblings} siblings")]
+    InvalidLength {
+        depth: usize,
+        directions: usize,
+        siblings: usize,
+    },
+    #[error("root hash of the path doesn't match the expected one")]
+    RootMismatch,
+}
+
+/// Verifies Merkle paths of a tree of a given kind and depth.
+///
+/// A path goes from the leaf up to the root: `directions[i]` tells if the
+/// node at height `i` is the left or the right child of its parent, and
+/// `siblings[i]` is the hash of its sibling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MerklePathVerifier {
+    kind: MerkleTreeKind,
+    depth: usize,
+}
+
+impl MerklePathVerifier {
+    pub fn new(kind: MerkleTreeKind, depth: usize) -> Result<Self, MerklePathError> {
+        let max = kind.max_depth();
+        if depth > max {
+            return Err(MerklePathError::DepthTooLarge { depth, max });
+        }
+        Ok(Self { kind, depth })
+    }
+
+    pub fn ledger(depth: usize) -> Result<Self, MerklePathError> {
+        Self::new(MerkleTreeKind::Ledger, depth)
+    }
+
+    pub fn pending_coinbase(depth: usize) -> Result<Self, MerklePathError> {
+        Self::new(MerkleTreeKind::PendingCoinbase, depth)
+    }
+
+    pub fn kind(&self) -> MerkleTreeKind {
+        self.kind
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Computes the root hash of the tree from the hash of a leaf and its
+    /// path.
+    pub fn root_hash(
+        &self,
+        leaf_hash: Fp,
+        directions: &[Direction],
+        siblings: &[Fp],
+    ) -> Result<Fp, MerklePathError> {
+        if directions.len() != self.depth || siblings.len() != self.depth {
+            return Err(MerklePathError::InvalidLength {
+                depth: self.depth,
+                directions: directions.len(),
+                siblings: siblings.len(),
+            });
+        }
+
+        let root_hash = directions.iter().zip(siblings).enumerate().fold(
+            leaf_hash,
+            |hash, (height, (direction, sibling))| match direction {
+                Direction::Left => self.kind.hash_node(height, hash, *sibling),
+                Direction::Right => self.kind.hash_node(height, *sibling, hash),
+            },
+        );
+        Ok(root_hash)
+    }
+
+    /// Same as [`Self::root_hash`], with a path as returned by the ledger.
+    pub fn root_hash_of_path(
+        &self,
+        leaf_hash: Fp,
+        path: &[MerklePath],
+    ) -> Result<Fp, MerklePathError> {
+        let (directions, siblings): (Vec<_>, Vec<_>) = path
+            .iter()
+            .map(|node| match node {
+                MerklePath::Left(right) => (Direction::Left, *right),
+                MerklePath::Right(left) => (Direction::Right, *left),
+            })
+            .unzip();
+        self.root_hash(leaf_hash, &directions, &siblings)
+    }
+
+    /// Checks that the leaf is in the tree with the expected root hash.
+    pub fn verify(
+        &self,
+        leaf_hash: Fp,
+        directions: &[Direction],
+        siblings: &[Fp],
+        expected_root_hash: Fp,
+    ) -> Result<(), MerklePathError> {
+        if self.root_hash(leaf_hash, directions, siblings)? != expected_root_hash {
+            return Err(MerklePathError::RootMismatch);
+        }
+        Ok(())
+    }
+
+    /// Directions of the path of the leaf at `index`, from the leaf up to
+    /// the root.
+    pub fn directions_of_index(&self, index: u64) -> Vec<Direction> {
+        (0..self.depth)
+            .map(|height| match (index >> height) & 1 {
+                0 => Direction::Left,
+                _ => Direction::Right,
+            })
+            .collect()
+    }
+}
 
 /// Computes the root hash of the merkle tree with an account and its merkle path
 ///
@@ -27,13 +182,14 @@ pub fn calc_merkle_root_hash(
     let mut child_hash = account.hash();
 
     for (height, path) in merkle_path.iter().enumerate() {
-        let hashes = match path {
-            MerkleTreeNode::Left(right) => [child_hash, right.to_field()?],
-            MerkleTreeNode::Right(left) => [left.to_field()?, child_hash],
+        child_hash = match path {
+            MerkleTreeNode::Left(right) => {
+                MerkleTreeKind::Ledger.hash_node(height, child_hash, right.to_field()?)
+            }
+            MerkleTreeNode::Right(left) => {
+                MerkleTreeKind::Ledger.hash_node(height, left.to_field()?, child_hash)
+            }
         };
-
-        let param = get_merkle_param_for_height(height);
-        child_hash = poseidon::hash::hash_with_kimchi(param, &hashes)
     }
 
     Ok(child_hash.into())
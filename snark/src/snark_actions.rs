@@ -4,6 +4,7 @@ use crate::{
     block_verify_effectful::SnarkBlockVerifyEffectfulAction,
     user_command_verify::SnarkUserCommandVerifyAction,
     user_command_verify_effectful::SnarkUserCommandVerifyEffectfulAction,
+    verify_dry_run_effectful::SnarkVerifyDryRunEffectfulAction,
     work_verify_effectful::SnarkWorkVerifyEffectfulAction,
 };
 
@@ -20,6 +21,7 @@ pub enum SnarkAction {
     WorkVerifyEffect(SnarkWorkVerifyEffectfulAction),
     UserCommandVerify(SnarkUserCommandVerifyAction),
     UserCommandVerifyEffect(SnarkUserCommandVerifyEffectfulAction),
+    VerifyDryRunEffect(SnarkVerifyDryRunEffectfulAction),
 }
 
 impl redux::EnablingCondition<crate::SnarkState> for SnarkAction {
@@ -31,6 +33,7 @@ impl redux::EnablingCondition<crate::SnarkState> for SnarkAction {
             SnarkAction::WorkVerifyEffect(a) => a.is_enabled(state, time),
             SnarkAction::UserCommandVerify(a) => a.is_enabled(state, time),
             SnarkAction::UserCommandVerifyEffect(a) => a.is_enabled(state, time),
+            SnarkAction::VerifyDryRunEffect(a) => a.is_enabled(state, time),
         }
     }
 }
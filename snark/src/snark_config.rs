@@ -1,22 +1,255 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
+use mina_core::{constants::ConstraintConstants, requests::RequestRetryPolicy, NetworkConfig};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SnarkConfig {
     pub block_verifier_index: crate::BlockVerifier,
-    pub block_verifier_srs: Arc<crate::VerifierSRS>,
     pub work_verifier_index: crate::TransactionVerifier,
-    pub work_verifier_srs: Arc<crate::VerifierSRS>,
+    /// SRS shared by all the verifiers. It should be the one returned by
+    /// [`crate::get_srs`], which the provers use as well.
+    pub verifier_srs: Arc<crate::VerifierSRS>,
+    #[serde(default)]
+    pub timeouts: SnarkTimeouts,
+    /// Cache of the verification results, see [`crate::SnarkVerifyCache`].
+    #[serde(default)]
+    pub verify_cache: crate::SnarkVerifyCacheConfig,
+    /// Max number of zkApp proofs of a batch of user commands verified in
+    /// parallel. `0` and `1` verify them one after another.
+    #[serde(default)]
+    pub verify_concurrency: usize,
+    /// Digest of the constraint constants the verifier indices are
+    /// generated for, `None` if unknown. See
+    /// [`SnarkConfig::check_constraint_constants`].
+    #[serde(default)]
+    pub constraint_constants_digest: Option<[u8; 32]>,
+    /// Digests of the verifier indices and the SRS, `None` if they weren't
+    /// computed. See [`VerifierDigests`].
+    #[serde(default)]
+    pub verifier_digests: Option<VerifierDigests>,
+}
+
+/// Digests of the parameters the node verifies proofs with, and of the
+/// known-good ones of the network they are checked against.
+///
+/// All digests are sha256, hex encoded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VerifierDigests {
+    pub network: String,
+    /// Digest of the points of the SRS, see
+    /// [`ledger::proofs::caching::srs_digest`].
+    pub srs: String,
+    /// Digest of the SRS created by the node, see [`crate::get_srs`]. The
+    /// SRS doesn't need a trusted setup and is deterministic, so that one is
+    /// known-good.
+    pub expected_srs: String,
+    pub block_verifier_index: VerifierIndexDigests,
+    pub work_verifier_index: VerifierIndexDigests,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VerifierIndexDigests {
+    /// Digest of the loaded verifier index, see
+    /// [`ledger::proofs::caching::verifier_index_digest`].
+    pub index: String,
+    /// Digest of the json the loaded index was built from, which its cache
+    /// file is keyed by. `None` for an index provided by the user.
+    pub source: Option<String>,
+    /// Known-good digest of the json for the network, see
+    /// [`mina_core::network::VerifierIndexDigests`].
+    pub expected_source: String,
+}
+
+impl VerifierDigests {
+    /// Computes the digests of the verifier indices and the SRS of `config`,
+    /// and the ones they should have.
+    ///
+    /// This serializes the indices and the SRS, so it takes a while.
+    pub fn new(config: &SnarkConfig, network: &NetworkConfig) -> Self {
+        let srs = ledger::proofs::caching::srs_digest(&config.verifier_srs);
+        let expected_srs = crate::get_srs();
+        let expected_srs = if Arc::ptr_eq(&expected_srs, &config.verifier_srs) {
+            srs
+        } else {
+            ledger::proofs::caching::srs_digest(&expected_srs)
+        };
+
+        let block = &config.block_verifier_index;
+        let work = &config.work_verifier_index;
+        let expected = &network.verifier_index_digests;
+        Self {
+            network: network.name.to_owned(),
+            srs: hex::encode(srs),
+            expected_srs: hex::encode(expected_srs),
+            block_verifier_index: VerifierIndexDigests {
+                index: hex::encode(block.digest()),
+                source: block.loaded_source_digest().map(hex::encode),
+                expected_source: expected.block_verifier.to_owned(),
+            },
+            work_verifier_index: VerifierIndexDigests {
+                index: hex::encode(work.digest()),
+                source: work.loaded_source_digest().map(hex::encode),
+                expected_source: expected.transaction_verifier.to_owned(),
+            },
+        }
+    }
+
+    /// Checks that the node verifies proofs with the known-good SRS, and
+    /// with verifier indices built from, or cached for, json matching the
+    /// known-good digests of the network.
+    pub fn check(&self) -> Result<(), SnarkConfigError> {
+        let mismatch = |parameter, expected: &String, actual: &String| {
+            Err(SnarkConfigError::VerifierDigestMismatch {
+                parameter,
+                expected: expected.clone(),
+                actual: actual.clone(),
+            })
+        };
+        if self.srs != self.expected_srs {
+            return mismatch("SRS", &self.expected_srs, &self.srs);
+        }
+        for (parameter, digests) in [
+            ("block verifier index", &self.block_verifier_index),
+            ("work verifier index", &self.work_verifier_index),
+        ] {
+            let source = digests
+                .source
+                .clone()
+                .unwrap_or_else(|| "unknown (custom index)".to_owned());
+            if source != digests.expected_source {
+                return mismatch(parameter, &digests.expected_source, &source);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SnarkConfigError {
+    #[error(
+        "verifier indices are generated for constraint constants with digest {indices}, \
+         but the node runs with constraint constants with digest {active}: proofs would \
+         fail to verify. Use the constraint constants of the network, or verifier indices \
+         generated for the custom ones"
+    )]
+    ConstraintConstantsMismatch { indices: String, active: String },
+    #[error(
+        "{parameter} has digest {actual}, but the known-good one is {expected}: the node \
+         wouldn't verify proofs like the rest of the network. Skip the check only if the \
+         parameters are expected to differ"
+    )]
+    VerifierDigestMismatch {
+        parameter: &'static str,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl SnarkConfig {
+    /// Checks that the verifier indices are generated for the constraint
+    /// constants the node runs with, as they fail to verify proofs created
+    /// with other constants.
+    pub fn check_constraint_constants(
+        &self,
+        constants: &ConstraintConstants,
+    ) -> Result<(), SnarkConfigError> {
+        let Some(indices) = self.constraint_constants_digest else {
+            return Ok(());
+        };
+        let active = constants.digest();
+        if indices != active {
+            return Err(SnarkConfigError::ConstraintConstantsMismatch {
+                indices: hex::encode(indices),
+                active: hex::encode(active),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Timeouts of the requests sent to the verifier service, `None` to wait
+/// for the verifier forever.
+///
+/// Verifications are queued by the service, so the timeouts leave room for
+/// the jobs sent before.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkTimeouts {
+    pub block_verify: Option<RequestRetryPolicy>,
+    pub work_verify: Option<RequestRetryPolicy>,
+    pub user_command_verify: Option<RequestRetryPolicy>,
+}
+
+impl Default for SnarkTimeouts {
+    fn default() -> Self {
+        Self {
+            block_verify: Some(RequestRetryPolicy::new(Duration::from_secs(120), 2)),
+            work_verify: Some(RequestRetryPolicy::new(Duration::from_secs(300), 2)),
+            user_command_verify: Some(RequestRetryPolicy::new(Duration::from_secs(120), 2)),
+        }
+    }
 }
 
 impl std::fmt::Debug for SnarkConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SnarkConfig")
             .field("block_verifier_index", &"<content too big>")
-            .field("block_verifier_srs", &"<content too big>")
             .field("work_verifier_index", &"<content too big>")
-            .field("work_verifier_srs", &"<content too big>")
+            .field("verifier_srs", &"<content too big>")
+            .field("timeouts", &self.timeouts)
+            .field("verify_cache", &self.verify_cache)
+            .field(
+                "constraint_constants_digest",
+                &self.constraint_constants_digest.map(hex::encode),
+            )
+            .field("verifier_digests", &self.verifier_digests)
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_digests(digest: &str) -> VerifierIndexDigests {
+        VerifierIndexDigests {
+            index: "ff".to_owned(),
+            source: Some(digest.to_owned()),
+            expected_source: digest.to_owned(),
+        }
+    }
+
+    fn digests() -> VerifierDigests {
+        VerifierDigests {
+            network: "devnet".to_owned(),
+            srs: "00".to_owned(),
+            expected_srs: "00".to_owned(),
+            block_verifier_index: index_digests("01"),
+            work_verifier_index: index_digests("02"),
+        }
+    }
+
+    fn mismatched_parameter(digests: &VerifierDigests) -> Option<&'static str> {
+        match digests.check() {
+            Err(SnarkConfigError::VerifierDigestMismatch { parameter, .. }) => Some(parameter),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_verifier_digests_check() {
+        assert!(digests().check().is_ok());
+
+        let mut srs = digests();
+        srs.srs = "ff".to_owned();
+        assert_eq!(mismatched_parameter(&srs), Some("SRS"));
+
+        let mut custom = digests();
+        custom.work_verifier_index.source = None;
+        assert_eq!(mismatched_parameter(&custom), Some("work verifier index"));
+
+        let mut source = digests();
+        source.block_verifier_index.source = Some("ff".to_owned());
+        assert_eq!(mismatched_parameter(&source), Some("block verifier index"));
+    }
+}
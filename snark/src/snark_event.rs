@@ -1,11 +1,17 @@
+use std::time::Duration;
+
 use ledger::scan_state::transaction_logic::valid;
+use mina_core::requests::RpcId;
 use serde::{Deserialize, Serialize};
 
 use super::{
     block_verify::{SnarkBlockVerifyError, SnarkBlockVerifyId},
     work_verify::{SnarkWorkVerifyError, SnarkWorkVerifyId},
 };
-use crate::user_command_verify::SnarkUserCommandVerifyId;
+use crate::{
+    user_command_verify::{SnarkUserCommandVerifyError, SnarkUserCommandVerifyId},
+    verify_dry_run_effectful::SnarkVerifyDryRunReport,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum SnarkEvent {
@@ -13,8 +19,41 @@ pub enum SnarkEvent {
     WorkVerify(SnarkWorkVerifyId, Result<(), SnarkWorkVerifyError>),
     UserCommandVerify(
         SnarkUserCommandVerifyId,
-        Result<Vec<valid::UserCommand>, String>,
+        Result<Vec<valid::UserCommand>, SnarkUserCommandVerifyError>,
     ),
+    VerifyDryRun(RpcId, SnarkVerifyDryRunReport),
+}
+
+/// Metrics of a verification request, measured and logged by the service.
+/// The latencies seen by the state machine are in [`crate::SnarkStats`].
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub struct SnarkVerifyMetrics {
+    /// Number of items (blocks, snarks or commands) verified together.
+    pub batch_size: usize,
+    /// Time between the request being submitted to the service and the
+    /// start of the verification.
+    pub queue_wait: Duration,
+    pub verify_duration: Duration,
+}
+
+impl SnarkVerifyMetrics {
+    /// Runs `verify`, measuring it along with the time passed since the
+    /// request was `submitted`.
+    pub fn measure<T>(
+        submitted: redux::Instant,
+        batch_size: usize,
+        verify: impl FnOnce() -> T,
+    ) -> (T, Self) {
+        let queue_wait = submitted.elapsed();
+        let start = redux::Instant::now();
+        let result = verify();
+        let metrics = Self {
+            batch_size,
+            queue_wait,
+            verify_duration: start.elapsed(),
+        };
+        (result, metrics)
+    }
 }
 
 fn res_kind<T, E>(res: &Result<T, E>) -> &'static str {
@@ -39,6 +78,9 @@ impl std::fmt::Display for SnarkEvent {
                 //let n_success = res.len() - n_failed;
                 write!(f, "UserCommandVerify, {id}, success={}", res.is_err())
             }
+            Self::VerifyDryRun(id, report) => {
+                write!(f, "VerifyDryRun, {id}, valid={}", report.is_valid())
+            }
         }
     }
 }
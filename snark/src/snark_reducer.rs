@@ -6,6 +6,7 @@ use crate::{
     block_verify_effectful::SnarkBlockVerifyEffectfulAction,
     user_command_verify::{SnarkUserCommandVerifyAction, SnarkUserCommandVerifyState},
     user_command_verify_effectful::SnarkUserCommandVerifyEffectfulAction,
+    verify_dry_run_effectful::SnarkVerifyDryRunEffectfulAction,
     work_verify::{SnarkWorkVerifyAction, SnarkWorkVerifyState},
     work_verify_effectful::SnarkWorkVerifyEffectfulAction,
 };
@@ -27,6 +28,7 @@ impl SnarkState {
             + From<SnarkWorkVerifyEffectfulAction>
             + From<SnarkUserCommandVerifyAction>
             + From<SnarkUserCommandVerifyEffectfulAction>
+            + From<SnarkVerifyDryRunEffectfulAction>
             + From<redux::AnyAction>
             + EnablingCondition<State>,
     {
@@ -47,6 +49,7 @@ impl SnarkState {
                 meta.with_action(a),
             ),
             SnarkAction::UserCommandVerifyEffect(_) => {}
+            SnarkAction::VerifyDryRunEffect(_) => {}
         }
     }
 }
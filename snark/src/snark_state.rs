@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{user_command_verify::SnarkUserCommandVerifyState, SnarkConfig};
+use crate::{
+    user_command_verify::SnarkUserCommandVerifyState, SnarkConfig, SnarkStats, SnarkVerifyRecord,
+    VerifierDigests,
+};
 
 use super::{block_verify::SnarkBlockVerifyState, work_verify::SnarkWorkVerifyState};
 
@@ -9,6 +12,9 @@ pub struct SnarkState {
     pub block_verify: SnarkBlockVerifyState,
     pub work_verify: SnarkWorkVerifyState,
     pub user_command_verify: SnarkUserCommandVerifyState,
+    /// See [`SnarkConfig::verifier_digests`].
+    #[serde(default)]
+    pub verifier_digests: Option<VerifierDigests>,
 }
 
 impl SnarkState {
@@ -16,16 +22,47 @@ impl SnarkState {
         Self {
             block_verify: SnarkBlockVerifyState::new(
                 config.block_verifier_index,
-                config.block_verifier_srs,
+                config.verifier_srs.clone(),
+                config.timeouts.block_verify,
+                config.verify_cache,
             ),
             work_verify: SnarkWorkVerifyState::new(
                 config.work_verifier_index.clone(),
-                config.work_verifier_srs.clone(),
+                config.verifier_srs.clone(),
+                config.timeouts.work_verify,
+                config.verify_cache,
             ),
             user_command_verify: SnarkUserCommandVerifyState::new(
                 config.work_verifier_index,
-                config.work_verifier_srs,
+                config.verifier_srs,
+                config.timeouts.user_command_verify,
+                config.verify_cache,
+                config.verify_concurrency,
             ),
+            verifier_digests: config.verifier_digests,
         }
     }
+
+    pub fn stats(&self) -> SnarkStats {
+        SnarkStats {
+            block_verify: self.block_verify.stats.clone(),
+            work_verify: self.work_verify.stats.clone(),
+            user_command_verify: self.user_command_verify.stats.clone(),
+        }
+    }
+
+    /// Recently completed verifications of all kinds, the most recently
+    /// finished first.
+    pub fn history(&self) -> Vec<SnarkVerifyRecord> {
+        let mut history = self
+            .block_verify
+            .history
+            .iter()
+            .chain(self.work_verify.history.iter())
+            .chain(self.user_command_verify.history.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        history.sort_by_key(|record| std::cmp::Reverse(record.finished_at));
+        history
+    }
 }
@@ -0,0 +1,285 @@
+use std::{collections::VecDeque, time::Duration};
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Upper bounds of the latency histogram buckets, in milliseconds. Latencies
+/// above the last bound are counted in an extra bucket.
+pub const SNARK_LATENCY_BUCKETS_MS: [u64; 10] =
+    [10, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000];
+
+/// Max number of completed verifications kept in the history, per kind of
+/// verification.
+pub const SNARK_VERIFY_HISTORY_LEN: usize = 1000;
+
+/// When a verification request was queued, sent to the verifier and
+/// finished. Requests whose result is cached finish as soon as they start.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnarkVerifyTiming {
+    pub queued_at: Timestamp,
+    pub started_at: Option<Timestamp>,
+    pub finished_at: Option<Timestamp>,
+}
+
+/// Latencies of the verifications, per kind of verification, since the node
+/// started.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SnarkStats {
+    pub block_verify: SnarkVerifyLatencyStats,
+    pub work_verify: SnarkVerifyLatencyStats,
+    pub user_command_verify: SnarkVerifyLatencyStats,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SnarkVerifyLatencyStats {
+    pub success: u64,
+    /// Requests which failed, including the ones which timed out.
+    pub error: u64,
+    /// Time from being queued to being sent to the verifier.
+    pub queue_wait: SnarkLatencyHistogram,
+    /// Time from being sent to the verifier to finishing, including the
+    /// retries after timeouts.
+    pub verify: SnarkLatencyHistogram,
+    /// Time from being queued to finishing.
+    pub total: SnarkLatencyHistogram,
+    /// Number of proofs verified together per request, e.g. the works of a
+    /// batch.
+    pub batch_size: SnarkBatchSizeStats,
+}
+
+/// Most recently completed verifications, oldest first.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SnarkVerifyHistory(VecDeque<SnarkVerifyRecord>);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkVerifyRecord {
+    pub kind: SnarkVerifyJobKind,
+    pub is_success: bool,
+    /// Number of proofs verified together, e.g. the works of a batch.
+    pub batch_size: usize,
+    pub finished_at: Timestamp,
+    pub queue_wait: Duration,
+    pub verify_duration: Duration,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SnarkVerifyJobKind {
+    Block,
+    Work,
+    UserCommand,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct SnarkLatencyHistogram {
+    pub count: u64,
+    pub sum_ms: u64,
+    pub max_ms: u64,
+    /// Number of latencies per bucket of [`SNARK_LATENCY_BUCKETS_MS`], and
+    /// above the last bound.
+    pub buckets: [u64; SNARK_LATENCY_BUCKETS_MS.len() + 1],
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct SnarkBatchSizeStats {
+    pub total: u64,
+    pub max: u64,
+}
+
+impl Default for SnarkVerifyTiming {
+    fn default() -> Self {
+        Self::queued(Timestamp::ZERO)
+    }
+}
+
+impl SnarkVerifyTiming {
+    pub fn queued(time: Timestamp) -> Self {
+        Self {
+            queued_at: time,
+            started_at: None,
+            finished_at: None,
+        }
+    }
+
+    pub fn started(self, time: Timestamp) -> Self {
+        Self {
+            started_at: Some(time),
+            ..self
+        }
+    }
+
+    pub fn finished(self, time: Timestamp) -> Self {
+        Self {
+            finished_at: Some(time),
+            ..self
+        }
+    }
+
+    pub fn queue_wait(&self) -> Option<Duration> {
+        self.started_at?.checked_sub(self.queued_at)
+    }
+
+    pub fn verify_duration(&self) -> Option<Duration> {
+        self.finished_at?.checked_sub(self.started_at?)
+    }
+
+    pub fn total(&self) -> Option<Duration> {
+        self.finished_at?.checked_sub(self.queued_at)
+    }
+}
+
+impl SnarkVerifyLatencyStats {
+    /// Adds the latencies of a finished request.
+    pub fn add(&mut self, timing: &SnarkVerifyTiming, is_success: bool, batch_size: usize) {
+        if is_success {
+            self.success = self.success.saturating_add(1);
+        } else {
+            self.error = self.error.saturating_add(1);
+        }
+        if let Some(latency) = timing.queue_wait() {
+            self.queue_wait.add(latency);
+        }
+        if let Some(latency) = timing.verify_duration() {
+            self.verify.add(latency);
+        }
+        if let Some(latency) = timing.total() {
+            self.total.add(latency);
+        }
+        let batch_size = batch_size as u64;
+        self.batch_size.total = self.batch_size.total.saturating_add(batch_size);
+        self.batch_size.max = self.batch_size.max.max(batch_size);
+    }
+
+    pub fn mean_batch_size(&self) -> u64 {
+        let count = self.success.saturating_add(self.error);
+        self.batch_size.total / count.max(1)
+    }
+}
+
+impl SnarkVerifyHistory {
+    /// Adds a finished request, dropping the oldest one once full.
+    pub fn push(
+        &mut self,
+        kind: SnarkVerifyJobKind,
+        timing: &SnarkVerifyTiming,
+        is_success: bool,
+        batch_size: usize,
+    ) {
+        let Some(finished_at) = timing.finished_at else {
+            return;
+        };
+        if self.0.len() >= SNARK_VERIFY_HISTORY_LEN {
+            self.0.pop_front();
+        }
+        self.0.push_back(SnarkVerifyRecord {
+            kind,
+            is_success,
+            batch_size,
+            finished_at,
+            queue_wait: timing.queue_wait().unwrap_or_default(),
+            verify_duration: timing.verify_duration().unwrap_or_default(),
+        });
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &SnarkVerifyRecord> {
+        self.0.iter()
+    }
+}
+
+impl SnarkLatencyHistogram {
+    pub fn add(&mut self, latency: Duration) {
+        let ms = latency.as_millis().try_into().unwrap_or(u64::MAX);
+        let bucket = SNARK_LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| ms <= *bound)
+            .unwrap_or(SNARK_LATENCY_BUCKETS_MS.len());
+        self.buckets[bucket] = self.buckets[bucket].saturating_add(1);
+        self.count = self.count.saturating_add(1);
+        self.sum_ms = self.sum_ms.saturating_add(ms);
+        self.max_ms = self.max_ms.max(ms);
+    }
+
+    pub fn mean_ms(&self) -> u64 {
+        self.sum_ms / self.count.max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(ms: u64) -> Timestamp {
+        Timestamp::ZERO + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn timing_latencies() {
+        let timing = SnarkVerifyTiming::queued(at(100));
+        assert_eq!(timing.queue_wait(), None);
+        let timing = timing.started(at(150)).finished(at(1150));
+        assert_eq!(timing.queue_wait(), Some(Duration::from_millis(50)));
+        assert_eq!(timing.verify_duration(), Some(Duration::from_millis(1000)));
+        assert_eq!(timing.total(), Some(Duration::from_millis(1050)));
+    }
+
+    #[test]
+    fn latency_stats_per_request() {
+        let mut stats = SnarkVerifyLatencyStats::default();
+        let timing = |verify_ms| {
+            SnarkVerifyTiming::queued(at(0))
+                .started(at(1))
+                .finished(at(1 + verify_ms))
+        };
+        stats.add(&timing(10), true, 2);
+        stats.add(&timing(30), false, 4);
+        // Cached, so finished without being sent to the verifier.
+        stats.add(&SnarkVerifyTiming::queued(at(0)).finished(at(0)), true, 1);
+
+        assert_eq!((stats.success, stats.error), (2, 1));
+        assert_eq!(stats.verify.count, 2);
+        assert_eq!(stats.verify.max_ms, 30);
+        assert_eq!(stats.total.count, 3);
+        assert_eq!(stats.batch_size, SnarkBatchSizeStats { total: 7, max: 4 });
+        assert_eq!(stats.mean_batch_size(), 2);
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut history = SnarkVerifyHistory::default();
+        let timing = |ms| {
+            SnarkVerifyTiming::queued(at(0))
+                .started(at(0))
+                .finished(at(ms))
+        };
+        for ms in 0..SNARK_VERIFY_HISTORY_LEN as u64 + 2 {
+            history.push(SnarkVerifyJobKind::Work, &timing(ms), true, 2);
+        }
+        // Not finished, so not recorded.
+        history.push(
+            SnarkVerifyJobKind::Work,
+            &SnarkVerifyTiming::queued(at(0)),
+            true,
+            2,
+        );
+
+        assert_eq!(history.iter().count(), SNARK_VERIFY_HISTORY_LEN);
+        let first = history.iter().next().unwrap();
+        assert_eq!(first.finished_at, at(2));
+        assert_eq!(first.verify_duration, Duration::from_millis(2));
+    }
+
+    #[test]
+    fn histogram_buckets() {
+        let mut histogram = SnarkLatencyHistogram::default();
+        for ms in [0, 10, 11, 999, 60_000] {
+            histogram.add(Duration::from_millis(ms));
+        }
+        assert_eq!(histogram.buckets[0], 2);
+        assert_eq!(histogram.buckets[1], 1);
+        assert_eq!(histogram.buckets[5], 1);
+        assert_eq!(histogram.buckets[SNARK_LATENCY_BUCKETS_MS.len()], 1);
+        assert_eq!(histogram.count, 5);
+        assert_eq!(histogram.max_ms, 60_000);
+        assert_eq!(histogram.mean_ms(), 61_020 / 5);
+    }
+}
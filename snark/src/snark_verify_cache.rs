@@ -0,0 +1,241 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use ledger::scan_state::transaction_logic::{verifiable, WithStatus};
+use mina_core::snark::Snark;
+use mina_p2p_messages::binprot::BinProtWrite;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::block_verify::VerifiableBlockWithHash;
+
+/// Results of verifications, keyed by a hash of the verified proofs, so
+/// that proofs received again, e.g. re-gossiped during catchup or when the
+/// pools are rebroadcast, aren't verified from scratch.
+///
+/// Only the results from the verifier are kept, not timeouts, which don't
+/// say anything about the proofs.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkVerifyCache<V> {
+    config: SnarkVerifyCacheConfig,
+    /// Entries by the sequence number they were inserted, or last used at
+    /// with [`SnarkVerifyCacheEviction::Lru`], oldest first.
+    entries: BTreeMap<u64, (SnarkVerifyCacheKey, V)>,
+    index: BTreeMap<SnarkVerifyCacheKey, u64>,
+    next_seq: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct SnarkVerifyCacheConfig {
+    /// Max number of results kept per kind of proof, `0` to disable the
+    /// cache.
+    pub capacity: usize,
+    pub eviction: SnarkVerifyCacheEviction,
+}
+
+/// Which result is evicted once the cache is full.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SnarkVerifyCacheEviction {
+    /// The least recently used one.
+    #[default]
+    Lru,
+    /// The oldest one, regardless of how often it's used.
+    Fifo,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("invalid cache eviction policy: {0}! expected one of: lru/fifo")]
+pub struct SnarkVerifyCacheEvictionParseError(String);
+
+/// Sha256 of the serialized proofs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SnarkVerifyCacheKey([u8; 32]);
+
+impl Default for SnarkVerifyCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            eviction: Default::default(),
+        }
+    }
+}
+
+impl FromStr for SnarkVerifyCacheEviction {
+    type Err = SnarkVerifyCacheEvictionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "lru" => Self::Lru,
+            "fifo" => Self::Fifo,
+            other => return Err(SnarkVerifyCacheEvictionParseError(other.to_owned())),
+        })
+    }
+}
+
+impl<V> Default for SnarkVerifyCache<V> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<V> SnarkVerifyCache<V> {
+    pub fn new(config: SnarkVerifyCacheConfig) -> Self {
+        Self {
+            config,
+            entries: Default::default(),
+            index: Default::default(),
+            next_seq: 0,
+        }
+    }
+
+    pub fn config(&self) -> &SnarkVerifyCacheConfig {
+        &self.config
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, key: &SnarkVerifyCacheKey) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// Cached result for the proofs, which counts as a use of it.
+    pub fn get(&mut self, key: &SnarkVerifyCacheKey) -> Option<&V> {
+        let seq = self.index.get(key).copied()?;
+        let seq = match self.config.eviction {
+            SnarkVerifyCacheEviction::Lru => {
+                let entry = self.entries.remove(&seq)?;
+                let seq = self.push(entry);
+                self.index.insert(*key, seq);
+                seq
+            }
+            SnarkVerifyCacheEviction::Fifo => seq,
+        };
+        self.entries.get(&seq).map(|(_, value)| value)
+    }
+
+    pub fn insert(&mut self, key: SnarkVerifyCacheKey, value: V) {
+        if self.config.capacity == 0 {
+            return;
+        }
+        if let Some(seq) = self.index.get(&key) {
+            self.entries.remove(seq);
+        }
+        while self.entries.len() >= self.config.capacity {
+            let Some((_, (oldest, _))) = self.entries.pop_first() else {
+                break;
+            };
+            self.index.remove(&oldest);
+        }
+        let seq = self.push((key, value));
+        self.index.insert(key, seq);
+    }
+
+    fn push(&mut self, entry: (SnarkVerifyCacheKey, V)) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.entries.insert(seq, entry);
+        seq
+    }
+}
+
+impl SnarkVerifyCacheKey {
+    /// Key of the block proof. The protocol state is committed to by the
+    /// block hash, which is included along with the proof.
+    pub fn block(block: &VerifiableBlockWithHash) -> Self {
+        let mut hasher = Sha256::new();
+        hash_binprot(&mut hasher, block.hash_ref());
+        hash_binprot(&mut hasher, &*block.header_ref().protocol_state_proof);
+        Self(hasher.finalize().into())
+    }
+
+    /// Key of the work, including the fee and the prover, as the proofs
+    /// commit to them through the sok digest.
+    pub fn work(work: &Snark) -> Self {
+        let mut hasher = Sha256::new();
+        hash_binprot(&mut hasher, work);
+        Self(hasher.finalize().into())
+    }
+
+    /// Key of a batch of user commands, which are verified together.
+    pub fn user_commands(commands: &[WithStatus<verifiable::UserCommand>]) -> Self {
+        let mut hasher = Sha256::new();
+        // Verifiable commands have no binprot encoding.
+        match serde_json::to_vec(commands) {
+            Ok(bytes) => hasher.update(bytes),
+            Err(_) => hasher.update(b"invalid"),
+        }
+        Self(hasher.finalize().into())
+    }
+}
+
+fn hash_binprot<T: BinProtWrite>(hasher: &mut Sha256, value: &T) {
+    let mut bytes = Vec::new();
+    // Writing to a vec can't fail.
+    let _ = value.binprot_write(&mut bytes);
+    hasher.update(bytes);
+}
+
+impl Serialize for SnarkVerifyCacheKey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for SnarkVerifyCacheKey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let mut bytes = [0; 32];
+        hex::decode_to_slice(s, &mut bytes).map_err(serde::de::Error::custom)?;
+        Ok(Self(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: u8) -> SnarkVerifyCacheKey {
+        SnarkVerifyCacheKey([n; 32])
+    }
+
+    fn cache(capacity: usize, eviction: SnarkVerifyCacheEviction) -> SnarkVerifyCache<u8> {
+        SnarkVerifyCache::new(SnarkVerifyCacheConfig { capacity, eviction })
+    }
+
+    #[test]
+    fn lru_evicts_least_recently_used() {
+        let mut cache = cache(2, SnarkVerifyCacheEviction::Lru);
+        cache.insert(key(1), 1);
+        cache.insert(key(2), 2);
+        assert_eq!(cache.get(&key(1)), Some(&1));
+        cache.insert(key(3), 3);
+        assert!(cache.contains(&key(1)));
+        assert!(!cache.contains(&key(2)));
+        assert!(cache.contains(&key(3)));
+    }
+
+    #[test]
+    fn fifo_evicts_oldest() {
+        let mut cache = cache(2, SnarkVerifyCacheEviction::Fifo);
+        cache.insert(key(1), 1);
+        cache.insert(key(2), 2);
+        assert_eq!(cache.get(&key(1)), Some(&1));
+        cache.insert(key(3), 3);
+        assert!(!cache.contains(&key(1)));
+        assert_eq!(cache.get(&key(1)), None);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn zero_capacity_disables_cache() {
+        let mut cache = cache(0, SnarkVerifyCacheEviction::Lru);
+        cache.insert(key(1), 1);
+        assert!(cache.is_empty());
+    }
+}
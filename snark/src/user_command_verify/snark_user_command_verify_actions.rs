@@ -16,6 +16,11 @@ pub(super) type OnSuccess = Callback<(
     Vec<valid::UserCommand>,
     TransactionPoolMessageSource,
 )>;
+pub(super) type OnError = Callback<(
+    SnarkUserCommandVerifyId,
+    SnarkUserCommandVerifyError,
+    TransactionPoolMessageSource,
+)>;
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
 #[action_event(level = trace, fields(display(req_id), display(error)))]
@@ -26,7 +31,7 @@ pub enum SnarkUserCommandVerifyAction {
         commands: Vec<WithStatus<verifiable::UserCommand>>,
         from_source: TransactionPoolMessageSource,
         on_success: OnSuccess,
-        on_error: Callback<(SnarkUserCommandVerifyId, Vec<String>)>,
+        on_error: OnError,
     },
     Pending {
         req_id: SnarkUserCommandVerifyId,
@@ -40,13 +45,19 @@ pub enum SnarkUserCommandVerifyAction {
         req_id: SnarkUserCommandVerifyId,
         commands: Vec<valid::UserCommand>,
     },
+    /// The verifier didn't answer in time, the request is sent again or
+    /// fails with [`SnarkUserCommandVerifyError::Timeout`].
+    #[action_event(level = warn)]
+    Timeout {
+        req_id: SnarkUserCommandVerifyId,
+    },
     Finish {
         req_id: SnarkUserCommandVerifyId,
     },
 }
 
 impl redux::EnablingCondition<crate::SnarkState> for SnarkUserCommandVerifyAction {
-    fn is_enabled(&self, state: &crate::SnarkState, _time: redux::Timestamp) -> bool {
+    fn is_enabled(&self, state: &crate::SnarkState, time: redux::Timestamp) -> bool {
         match self {
             SnarkUserCommandVerifyAction::Init {
                 req_id, commands, ..
@@ -66,6 +77,9 @@ impl redux::EnablingCondition<crate::SnarkState> for SnarkUserCommandVerifyActio
                 .jobs
                 .get(*req_id)
                 .is_some_and(|v| v.is_pending()),
+            SnarkUserCommandVerifyAction::Timeout { req_id } => {
+                state.user_command_verify.is_timed_out(*req_id, time)
+            }
             SnarkUserCommandVerifyAction::Finish { req_id } => state
                 .user_command_verify
                 .jobs
@@ -1,11 +1,14 @@
 use mina_core::{bug_condition, Substate, SubstateAccess};
 use redux::EnablingCondition;
 
-use crate::user_command_verify_effectful::SnarkUserCommandVerifyEffectfulAction;
+use crate::{
+    user_command_verify_effectful::SnarkUserCommandVerifyEffectfulAction, SnarkVerifyCacheKey,
+    SnarkVerifyJobKind, SnarkVerifyTiming,
+};
 
 use super::{
     SnarkUserCommandVerifyAction, SnarkUserCommandVerifyActionWithMetaRef,
-    SnarkUserCommandVerifyState, SnarkUserCommandVerifyStatus,
+    SnarkUserCommandVerifyError, SnarkUserCommandVerifyState, SnarkUserCommandVerifyStatus,
 };
 
 pub fn reducer<State, Action>(
@@ -37,11 +40,33 @@ pub fn reducer<State, Action>(
                 on_error: on_error.clone(),
             });
 
+            // Commands received again, e.g. when the pool is rebroadcast,
+            // aren't sent to the verifier when their result is cached.
+            let key = SnarkVerifyCacheKey::user_commands(commands);
+            if let Some(result) = substate.cache.get(&key).cloned() {
+                let dispatcher = state.into_dispatcher();
+                dispatcher.push(SnarkUserCommandVerifyAction::Pending { req_id: *req_id });
+                match result {
+                    Ok(commands) => dispatcher.push(SnarkUserCommandVerifyAction::Success {
+                        req_id: *req_id,
+                        commands,
+                    }),
+                    Err(error) => dispatcher.push(SnarkUserCommandVerifyAction::Error {
+                        req_id: *req_id,
+                        error,
+                    }),
+                }
+                return;
+            }
+
+            let concurrency = substate.verify_concurrency;
+
             // Dispatch
             let dispatcher = state.into_dispatcher();
             dispatcher.push(SnarkUserCommandVerifyEffectfulAction::Init {
                 req_id: *req_id,
                 commands: commands.clone(),
+                concurrency,
             });
             dispatcher.push(SnarkUserCommandVerifyAction::Pending { req_id: *req_id });
         }
@@ -53,11 +78,11 @@ pub fn reducer<State, Action>(
                 return;
             };
             let SnarkUserCommandVerifyStatus::Init {
+                time,
                 commands,
                 from_source,
                 on_success,
                 on_error,
-                ..
             } = req
             else {
                 bug_condition!("Unexpected state in SnarkUserCommandVerifyAction::Pending");
@@ -66,10 +91,12 @@ pub fn reducer<State, Action>(
 
             *req = SnarkUserCommandVerifyStatus::Pending {
                 time: meta.time(),
+                timing: SnarkVerifyTiming::queued(*time).started(meta.time()),
                 commands: std::mem::take(commands),
                 from_source: std::mem::take(from_source),
                 on_success: on_success.clone(),
                 on_error: on_error.clone(),
+                retries: 0,
             };
         }
         SnarkUserCommandVerifyAction::Error { req_id, error } => {
@@ -79,20 +106,42 @@ pub fn reducer<State, Action>(
                 bug_condition!("State for job not found in SnarkUserCommandVerifyAction::Error");
                 return;
             };
-            let SnarkUserCommandVerifyStatus::Pending { commands, .. } = req else {
+            let SnarkUserCommandVerifyStatus::Pending {
+                commands,
+                from_source,
+                on_error,
+                timing,
+                ..
+            } = req
+            else {
                 bug_condition!("Unexpected state in SnarkUserCommandVerifyAction::Error");
                 return;
             };
+            if error.is_deterministic() {
+                let key = SnarkVerifyCacheKey::user_commands(commands);
+                substate.cache.insert(key, Err(error.clone()));
+            }
+            let from_source = std::mem::take(from_source);
+            let on_error = on_error.clone();
 
+            let timing = timing.finished(meta.time());
+            substate.stats.add(&timing, false, commands.len());
+            substate.history.push(
+                SnarkVerifyJobKind::UserCommand,
+                &timing,
+                false,
+                commands.len(),
+            );
             *req = SnarkUserCommandVerifyStatus::Error {
                 time: meta.time(),
+                timing,
                 commands: std::mem::take(commands),
                 error: error.clone(),
             };
 
             // Dispatch
             let dispatcher = state.into_dispatcher();
-            // TODO: dispatch on error callback
+            dispatcher.push_callback(on_error, (*req_id, error.clone(), from_source));
             dispatcher.push(SnarkUserCommandVerifyAction::Finish { req_id: *req_id });
         }
         SnarkUserCommandVerifyAction::Success { req_id, commands } => {
@@ -102,22 +151,35 @@ pub fn reducer<State, Action>(
                 return;
             };
             let SnarkUserCommandVerifyStatus::Pending {
+                commands: verifiable_commands,
                 from_source,
                 on_success,
+                timing,
                 ..
             } = req
             else {
                 bug_condition!("Unexpected state in SnarkUserCommandVerifyAction::Success");
                 return;
             };
+            let key = SnarkVerifyCacheKey::user_commands(verifiable_commands);
+            substate.cache.insert(key, Ok(commands.clone()));
 
             let from_source = std::mem::take(from_source);
             let commands: Vec<ledger::scan_state::transaction_logic::valid::UserCommand> =
                 commands.clone();
             let on_success = on_success.clone();
 
+            let timing = timing.finished(meta.time());
+            substate.stats.add(&timing, true, verifiable_commands.len());
+            substate.history.push(
+                SnarkVerifyJobKind::UserCommand,
+                &timing,
+                true,
+                verifiable_commands.len(),
+            );
             *req = SnarkUserCommandVerifyStatus::Success {
                 time: meta.time(),
+                timing,
                 commands: commands.clone(), // std::mem::take(commands),
             };
 
@@ -126,6 +188,42 @@ pub fn reducer<State, Action>(
             dispatcher.push_callback(on_success, (*req_id, commands, from_source));
             dispatcher.push(SnarkUserCommandVerifyAction::Finish { req_id: *req_id });
         }
+        SnarkUserCommandVerifyAction::Timeout { req_id } => {
+            let substate = state.get_substate_mut().unwrap();
+            let Some(timeout) = substate.timeout else {
+                return;
+            };
+            let Some(SnarkUserCommandVerifyStatus::Pending {
+                time,
+                commands,
+                retries,
+                ..
+            }) = substate.jobs.get_mut(*req_id)
+            else {
+                return;
+            };
+
+            if !timeout.can_retry(*retries) {
+                let dispatcher = state.into_dispatcher();
+                dispatcher.push(SnarkUserCommandVerifyAction::Error {
+                    req_id: *req_id,
+                    error: SnarkUserCommandVerifyError::Timeout,
+                });
+                return;
+            }
+            *time = meta.time();
+            *retries += 1;
+            let commands = commands.clone();
+            let concurrency = substate.verify_concurrency;
+
+            // Dispatch
+            let dispatcher = state.into_dispatcher();
+            dispatcher.push(SnarkUserCommandVerifyEffectfulAction::Init {
+                req_id: *req_id,
+                commands,
+                concurrency,
+            });
+        }
         SnarkUserCommandVerifyAction::Finish { req_id } => {
             let substate = state.get_substate_mut().unwrap();
             substate.jobs.remove(*req_id);
@@ -1,12 +1,17 @@
 use std::sync::Arc;
 
 use ledger::scan_state::transaction_logic::{valid, verifiable, WithStatus};
-use redux::Callback;
 use serde::{Deserialize, Serialize};
 
-use mina_core::{requests::PendingRequests, transaction::TransactionPoolMessageSource};
+use mina_core::{
+    requests::{PendingRequests, RequestRetryPolicy},
+    transaction::TransactionPoolMessageSource,
+};
 
-use crate::{TransactionVerifier, VerifierSRS};
+use crate::{
+    SnarkVerifyCache, SnarkVerifyCacheConfig, SnarkVerifyHistory, SnarkVerifyLatencyStats,
+    SnarkVerifyTiming, TransactionVerifier, VerifierSRS,
+};
 
 use super::{SnarkUserCommandVerifyError, SnarkUserCommandVerifyId, SnarkUserCommandVerifyIdType};
 
@@ -15,20 +20,64 @@ pub struct SnarkUserCommandVerifyState {
     pub verifier_index: TransactionVerifier,
     pub verifier_srs: Arc<VerifierSRS>,
     pub jobs: PendingRequests<SnarkUserCommandVerifyIdType, SnarkUserCommandVerifyStatus>,
+    #[serde(default)]
+    pub timeout: Option<RequestRetryPolicy>,
+    #[serde(default)]
+    pub cache: SnarkVerifyCache<Result<Vec<valid::UserCommand>, SnarkUserCommandVerifyError>>,
+    #[serde(default)]
+    pub stats: SnarkVerifyLatencyStats,
+    #[serde(default)]
+    pub history: SnarkVerifyHistory,
+    /// See [`crate::SnarkConfig::verify_concurrency`].
+    #[serde(default)]
+    pub verify_concurrency: usize,
 }
 
 impl SnarkUserCommandVerifyState {
-    pub fn new(verifier_index: TransactionVerifier, verifier_srs: Arc<VerifierSRS>) -> Self {
+    pub fn new(
+        verifier_index: TransactionVerifier,
+        verifier_srs: Arc<VerifierSRS>,
+        timeout: Option<RequestRetryPolicy>,
+        cache: SnarkVerifyCacheConfig,
+        verify_concurrency: usize,
+    ) -> Self {
         Self {
             verifier_index,
             verifier_srs,
             jobs: Default::default(),
+            timeout,
+            cache: SnarkVerifyCache::new(cache),
+            stats: Default::default(),
+            history: Default::default(),
+            verify_concurrency,
         }
     }
 
     pub fn next_req_id(&self) -> SnarkUserCommandVerifyId {
         self.jobs.next_req_id()
     }
+
+    pub fn is_timed_out(&self, req_id: SnarkUserCommandVerifyId, now: redux::Timestamp) -> bool {
+        let Some(timeout) = &self.timeout else {
+            return false;
+        };
+        match self.jobs.get(req_id) {
+            Some(SnarkUserCommandVerifyStatus::Pending { time, .. }) => {
+                timeout.is_timed_out(*time, now)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn timed_out_jobs(
+        &self,
+        now: redux::Timestamp,
+    ) -> impl Iterator<Item = SnarkUserCommandVerifyId> + '_ {
+        self.jobs
+            .iter()
+            .map(|(req_id, _)| req_id)
+            .filter(move |req_id| self.is_timed_out(*req_id, now))
+    }
 }
 
 impl std::fmt::Debug for SnarkUserCommandVerifyState {
@@ -38,6 +87,11 @@ impl std::fmt::Debug for SnarkUserCommandVerifyState {
             .field("verifier_index", &"<content too big>")
             .field("verifier_srs", &"<content too big>")
             .field("jobs", &self.jobs)
+            .field("timeout", &self.timeout)
+            .field("cache", &self.cache)
+            .field("stats", &self.stats)
+            .field("history", &self.history.iter().count())
+            .field("verify_concurrency", &self.verify_concurrency)
             .finish()
     }
 }
@@ -49,22 +103,31 @@ pub enum SnarkUserCommandVerifyStatus {
         commands: Vec<WithStatus<verifiable::UserCommand>>,
         from_source: TransactionPoolMessageSource,
         on_success: super::OnSuccess,
-        on_error: Callback<(SnarkUserCommandVerifyId, Vec<String>)>,
+        on_error: super::OnError,
     },
     Pending {
         time: redux::Timestamp,
+        #[serde(default)]
+        timing: SnarkVerifyTiming,
         commands: Vec<WithStatus<verifiable::UserCommand>>,
         from_source: TransactionPoolMessageSource,
         on_success: super::OnSuccess,
-        on_error: Callback<(SnarkUserCommandVerifyId, Vec<String>)>,
+        on_error: super::OnError,
+        /// Times the request was sent again after timing out.
+        #[serde(default)]
+        retries: u8,
     },
     Error {
         time: redux::Timestamp,
+        #[serde(default)]
+        timing: SnarkVerifyTiming,
         commands: Vec<WithStatus<verifiable::UserCommand>>,
         error: SnarkUserCommandVerifyError,
     },
     Success {
         time: redux::Timestamp,
+        #[serde(default)]
+        timing: SnarkVerifyTiming,
         commands: Vec<valid::UserCommand>,
     },
 }
@@ -81,4 +144,13 @@ impl SnarkUserCommandVerifyStatus {
     pub fn is_finished(&self) -> bool {
         matches!(self, Self::Error { .. } | Self::Success { .. })
     }
+
+    pub fn timing(&self) -> SnarkVerifyTiming {
+        match self {
+            Self::Init { time, .. } => SnarkVerifyTiming::queued(*time),
+            Self::Pending { timing, .. } => *timing,
+            Self::Error { timing, .. } => *timing,
+            Self::Success { timing, .. } => *timing,
+        }
+    }
 }
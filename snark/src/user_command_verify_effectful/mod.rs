@@ -6,6 +6,7 @@ mod snark_user_command_verify_effects;
 mod snark_user_command_verify_service;
 pub use snark_user_command_verify_service::*;
 
+use mina_core::{ErrorCode, HasErrorCode};
 use serde::{Deserialize, Serialize};
 
 pub struct SnarkUserCommandVerifyIdType;
@@ -17,10 +18,80 @@ impl mina_core::requests::RequestIdType for SnarkUserCommandVerifyIdType {
 
 pub type SnarkUserCommandVerifyId = mina_core::requests::RequestId<SnarkUserCommandVerifyIdType>;
 
+/// Commands are identified by their `index` in the verified batch.
 #[derive(Serialize, Deserialize, Debug, Clone, thiserror::Error)]
 pub enum SnarkUserCommandVerifyError {
-    #[error("verification failed")]
-    VerificationFailed,
+    #[error("command {index} has an invalid signature")]
+    InvalidSignature { index: usize },
+    #[error("command {index} has an invalid proof")]
+    InvalidProof { index: usize },
+    /// Command which isn't well formed, e.g. with verification keys which
+    /// don't match its accounts.
+    #[error("command {index} is invalid: {reason}")]
+    InvalidCommand { index: usize, reason: String },
+    /// The zkApp proofs of the batch are verified together, so which
+    /// command has an invalid proof isn't known.
+    #[error("zkApp proofs verification failed")]
+    ZkappProofsFailed,
     #[error("validator thread crashed")]
     ValidatorThreadCrashed,
+    #[error("verifier didn't answer in time")]
+    Timeout,
+}
+
+impl SnarkUserCommandVerifyError {
+    /// Whether the commands are invalid, so verifying them again would fail
+    /// the same way. Otherwise the verifier failed, which says nothing
+    /// about the commands.
+    pub fn is_deterministic(&self) -> bool {
+        match self {
+            Self::InvalidSignature { .. }
+            | Self::InvalidProof { .. }
+            | Self::InvalidCommand { .. }
+            | Self::ZkappProofsFailed => true,
+            Self::ValidatorThreadCrashed | Self::Timeout => false,
+        }
+    }
+
+    /// Index of the invalid command in the batch, if known.
+    pub fn command_index(&self) -> Option<usize> {
+        match self {
+            Self::InvalidSignature { index }
+            | Self::InvalidProof { index }
+            | Self::InvalidCommand { index, .. } => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Error of the command at `index` of the batch.
+    pub fn from_verifier(index: usize, error: &ledger::verifier::VerifierError) -> Self {
+        use ledger::verifier::VerifierError;
+
+        match error {
+            VerifierError::ValidAssuming(_) => Self::ZkappProofsFailed,
+            VerifierError::InvalidSignature(_) => Self::InvalidSignature { index },
+            VerifierError::InvalidProof(_) => Self::InvalidProof { index },
+            VerifierError::InvalidKeys(_)
+            | VerifierError::MissingVerificationKey(_)
+            | VerifierError::UnexpectedVerificationKey(_)
+            | VerifierError::MismatchedVerificationKey(_)
+            | VerifierError::MismatchedAuthorizationKind(_) => Self::InvalidCommand {
+                index,
+                reason: error.to_string(),
+            },
+        }
+    }
+}
+
+impl HasErrorCode for SnarkUserCommandVerifyError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidProof { .. } | Self::ZkappProofsFailed => ErrorCode::InvalidProof,
+            Self::InvalidSignature { .. } | Self::InvalidCommand { .. } => {
+                ErrorCode::InvalidTransaction
+            }
+            Self::ValidatorThreadCrashed => ErrorCode::VerifierCrashed,
+            Self::Timeout => ErrorCode::VerifierTimeout,
+        }
+    }
 }
@@ -8,6 +8,7 @@ pub enum SnarkUserCommandVerifyEffectfulAction {
     Init {
         req_id: SnarkUserCommandVerifyId,
         commands: Vec<WithStatus<verifiable::UserCommand>>,
+        concurrency: usize,
     },
 }
 
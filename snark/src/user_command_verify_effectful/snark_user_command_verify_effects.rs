@@ -9,8 +9,12 @@ impl SnarkUserCommandVerifyEffectfulAction {
         Store::Service: SnarkUserCommandVerifyService,
     {
         match self {
-            SnarkUserCommandVerifyEffectfulAction::Init { req_id, commands } => {
-                store.service().verify_init(req_id, commands);
+            SnarkUserCommandVerifyEffectfulAction::Init {
+                req_id,
+                commands,
+                concurrency,
+            } => {
+                store.service().verify_init(req_id, commands, concurrency);
             }
         }
     }
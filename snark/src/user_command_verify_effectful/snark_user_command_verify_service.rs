@@ -3,9 +3,12 @@ use ledger::scan_state::transaction_logic::{verifiable, WithStatus};
 use super::SnarkUserCommandVerifyId;
 
 pub trait SnarkUserCommandVerifyService: redux::Service {
+    /// Verifies the commands, with up to `concurrency` zkApp proofs
+    /// verified in parallel.
     fn verify_init(
         &mut self,
         req_id: SnarkUserCommandVerifyId,
         commands: Vec<WithStatus<verifiable::UserCommand>>,
+        concurrency: usize,
     );
 }
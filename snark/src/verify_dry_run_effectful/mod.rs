@@ -0,0 +1,99 @@
+//! # Verification Dry Runs
+//!
+//! Runs the verification of a proof supplied through the RPC, e.g. a block
+//! or a zkApp command produced by another implementation, and reports the
+//! outcome of each check, to find out why it fails. Dry runs don't go
+//! through the verification state machines, so they change neither the
+//! consensus state nor the verification cache.
+
+mod snark_verify_dry_run_effectful_actions;
+pub use snark_verify_dry_run_effectful_actions::*;
+
+mod snark_verify_dry_run_effects;
+
+mod snark_verify_dry_run_service;
+pub use snark_verify_dry_run_service::*;
+
+mod snark_verify_dry_run;
+pub use snark_verify_dry_run::*;
+
+use std::time::Duration;
+
+use mina_core::{block::ArcBlock, snark::Snark};
+use mina_p2p_messages::v2::{MinaBaseUserCommandStableV2, MinaBaseVerificationKeyWireStableV1};
+use serde::{Deserialize, Serialize};
+
+use crate::SnarkVerifyJobKind;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum SnarkVerifyDryRunInput {
+    Block(ArcBlock),
+    Work(Vec<Snark>),
+    UserCommand {
+        command: MinaBaseUserCommandStableV2,
+        /// Verification keys of the accounts whose updates are authorized
+        /// by a proof, looked up by the hash in their authorization kind.
+        #[serde(default)]
+        verification_keys: Vec<MinaBaseVerificationKeyWireStableV1>,
+    },
+}
+
+impl SnarkVerifyDryRunInput {
+    pub fn kind(&self) -> SnarkVerifyJobKind {
+        match self {
+            Self::Block(_) => SnarkVerifyJobKind::Block,
+            Self::Work(_) => SnarkVerifyJobKind::Work,
+            Self::UserCommand { .. } => SnarkVerifyJobKind::UserCommand,
+        }
+    }
+}
+
+/// Checks performed by a dry run, in the order they ran. Checks which
+/// can't run because an earlier one failed, e.g. verifying a proof whose
+/// statement can't be decoded, aren't listed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkVerifyDryRunReport {
+    pub kind: SnarkVerifyJobKind,
+    pub checks: Vec<SnarkVerifyDryRunCheck>,
+    pub duration: Duration,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SnarkVerifyDryRunCheck {
+    pub name: String,
+    pub passed: bool,
+    pub error: Option<String>,
+}
+
+impl SnarkVerifyDryRunReport {
+    pub fn is_valid(&self) -> bool {
+        !self.checks.is_empty() && self.checks.iter().all(|check| check.passed)
+    }
+}
+
+impl SnarkVerifyDryRunCheck {
+    pub fn passed(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            passed: true,
+            error: None,
+        }
+    }
+
+    pub fn failed(name: impl Into<String>, error: impl ToString) -> Self {
+        Self {
+            name: name.into(),
+            passed: false,
+            error: Some(error.to_string()),
+        }
+    }
+
+    fn new(name: impl Into<String>, passed: bool, error: &str) -> Self {
+        if passed {
+            Self::passed(name)
+        } else {
+            Self::failed(name, error)
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use ledger::{
+    proofs::verification,
+    scan_state::{
+        scan_state::transaction_snark::{SokDigest, Statement},
+        transaction_logic::{TransactionStatus, UserCommand, WithStatus},
+    },
+    verifier::common::{check, CheckResult},
+    AccountId, VerificationKey, VerificationKeyWire,
+};
+use mina_curves::pasta::Fp;
+use mina_p2p_messages::v2::{
+    LedgerProofProdStableV2, MinaBaseUserCommandStableV2, MinaBaseVerificationKeyWireStableV1,
+    MinaBlockHeaderStableV2, TransactionSnarkWorkTStableV2Proofs,
+};
+
+use crate::{BlockVerifier, TransactionVerifier, VerifierSRS};
+
+use super::{SnarkVerifyDryRunCheck, SnarkVerifyDryRunInput, SnarkVerifyDryRunReport};
+
+/// Runs the checks the node runs when verifying the input, without
+/// stopping at the first failure whenever the remaining checks can run.
+pub fn verify_dry_run(
+    input: &SnarkVerifyDryRunInput,
+    block_verifier_index: &BlockVerifier,
+    work_verifier_index: &TransactionVerifier,
+    verifier_srs: &VerifierSRS,
+) -> SnarkVerifyDryRunReport {
+    let start = redux::Instant::now();
+    let checks = match input {
+        SnarkVerifyDryRunInput::Block(block) => {
+            block_checks(&block.header, block_verifier_index, verifier_srs)
+        }
+        SnarkVerifyDryRunInput::Work(works) => {
+            let proofs = works.iter().map(|work| match &*work.proofs {
+                TransactionSnarkWorkTStableV2Proofs::One(v) => vec![v],
+                TransactionSnarkWorkTStableV2Proofs::Two((v1, v2)) => vec![v1, v2],
+            });
+            proofs
+                .enumerate()
+                .flat_map(|(i, proofs)| {
+                    proofs.into_iter().enumerate().flat_map(move |(j, proof)| {
+                        let name = format!("work[{i}].proof[{j}]");
+                        work_proof_checks(name, proof, work_verifier_index, verifier_srs)
+                    })
+                })
+                .collect()
+        }
+        SnarkVerifyDryRunInput::UserCommand {
+            command,
+            verification_keys,
+        } => user_command_checks(command, verification_keys),
+    };
+    SnarkVerifyDryRunReport {
+        kind: input.kind(),
+        checks,
+        duration: start.elapsed(),
+    }
+}
+
+fn block_checks(
+    header: &MinaBlockHeaderStableV2,
+    verifier_index: &BlockVerifier,
+    srs: &VerifierSRS,
+) -> Vec<SnarkVerifyDryRunCheck> {
+    match verification::block_checks(header, verifier_index, srs) {
+        Err(error) => vec![SnarkVerifyDryRunCheck::failed("protocol_state", error)],
+        Ok(checks) => vec![
+            SnarkVerifyDryRunCheck::passed("protocol_state"),
+            SnarkVerifyDryRunCheck::new(
+                "accumulator_check",
+                checks.accumulator_check,
+                "accumulator check failed",
+            ),
+            SnarkVerifyDryRunCheck::new("proof", checks.verified, "invalid proof"),
+        ],
+    }
+}
+
+fn work_proof_checks(
+    name: String,
+    proof: &LedgerProofProdStableV2,
+    verifier_index: &TransactionVerifier,
+    srs: &VerifierSRS,
+) -> Vec<SnarkVerifyDryRunCheck> {
+    let statement_name = format!("{name}.statement");
+    match Statement::<SokDigest>::try_from(&proof.0.statement) {
+        Err(error) => vec![SnarkVerifyDryRunCheck::failed(statement_name, error)],
+        Ok(statement) => {
+            let verified =
+                verification::verify_transaction([(&statement, &proof.proof)], verifier_index, srs);
+            vec![
+                SnarkVerifyDryRunCheck::passed(statement_name),
+                SnarkVerifyDryRunCheck::new(name, verified, "invalid proof"),
+            ]
+        }
+    }
+}
+
+fn user_command_checks(
+    command: &MinaBaseUserCommandStableV2,
+    verification_keys: &[MinaBaseVerificationKeyWireStableV1],
+) -> Vec<SnarkVerifyDryRunCheck> {
+    let mut checks = Vec::new();
+
+    let command = match UserCommand::try_from(command) {
+        Ok(command) => command,
+        Err(error) => {
+            checks.push(SnarkVerifyDryRunCheck::failed("decode", error));
+            return checks;
+        }
+    };
+    checks.push(SnarkVerifyDryRunCheck::passed("decode"));
+
+    let verification_keys = match verification_keys
+        .iter()
+        .map(|vk| VerificationKey::try_from(vk).map(VerificationKeyWire::new))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(verification_keys) => verification_keys,
+        Err(error) => {
+            checks.push(SnarkVerifyDryRunCheck::failed("verification_keys", error));
+            return checks;
+        }
+    };
+    let find_vk = |vk_hash: Fp, _: &AccountId| {
+        verification_keys
+            .iter()
+            .find(|vk| vk.hash() == vk_hash)
+            .cloned()
+            .ok_or_else(|| format!("no verification key supplied with hash {vk_hash}"))
+    };
+    let status = TransactionStatus::Applied;
+    let command = match command.to_verifiable(&status, find_vk) {
+        Ok(command) => command,
+        Err(error) => {
+            checks.push(SnarkVerifyDryRunCheck::failed("verification_keys", error));
+            return checks;
+        }
+    };
+    checks.push(SnarkVerifyDryRunCheck::passed("verification_keys"));
+
+    let proofs = match check(WithStatus {
+        data: command,
+        status,
+    }) {
+        CheckResult::Valid(_) => Vec::new(),
+        CheckResult::ValidAssuming((_, proofs)) => proofs,
+        result => {
+            checks.push(SnarkVerifyDryRunCheck::failed(
+                "signatures",
+                format!("{result:?}"),
+            ));
+            return checks;
+        }
+    };
+    checks.push(SnarkVerifyDryRunCheck::passed("signatures"));
+
+    let srs = ledger::verifier::get_srs::<Fp>();
+    checks.extend(
+        proofs
+            .iter()
+            .enumerate()
+            .map(|(i, (vk, statement, proof))| {
+                let verified = verification::verify_zkapp(vk, statement, &(&**proof).into(), &srs);
+                SnarkVerifyDryRunCheck::new(format!("zkapp_proof[{i}]"), verified, "invalid proof")
+            }),
+    );
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_of_failed_checks_is_invalid() {
+        let report = |checks| SnarkVerifyDryRunReport {
+            kind: crate::SnarkVerifyJobKind::Work,
+            checks,
+            duration: Default::default(),
+        };
+        assert!(!report(vec![]).is_valid());
+        assert!(report(vec![SnarkVerifyDryRunCheck::passed("proof")]).is_valid());
+        assert!(!report(vec![
+            SnarkVerifyDryRunCheck::passed("statement"),
+            SnarkVerifyDryRunCheck::failed("proof", "invalid proof"),
+        ])
+        .is_valid());
+    }
+}
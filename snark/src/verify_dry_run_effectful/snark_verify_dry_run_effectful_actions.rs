@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use mina_core::requests::RpcId;
+use serde::{Deserialize, Serialize};
+
+use crate::{BlockVerifier, TransactionVerifier, VerifierSRS};
+
+use super::SnarkVerifyDryRunInput;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum SnarkVerifyDryRunEffectfulAction {
+    Init {
+        rpc_id: RpcId,
+        input: SnarkVerifyDryRunInput,
+        block_verifier_index: BlockVerifier,
+        work_verifier_index: TransactionVerifier,
+        verifier_srs: Arc<VerifierSRS>,
+    },
+}
+
+impl redux::EnablingCondition<crate::SnarkState> for SnarkVerifyDryRunEffectfulAction {
+    fn is_enabled(&self, _state: &crate::SnarkState, _time: redux::Timestamp) -> bool {
+        true
+    }
+}
@@ -0,0 +1,29 @@
+use redux::ActionMeta;
+
+use super::{SnarkVerifyDryRunEffectfulAction, SnarkVerifyDryRunService};
+
+impl SnarkVerifyDryRunEffectfulAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::SnarkStore<S>,
+        Store::Service: SnarkVerifyDryRunService,
+    {
+        match self {
+            Self::Init {
+                rpc_id,
+                input,
+                block_verifier_index,
+                work_verifier_index,
+                verifier_srs,
+            } => {
+                store.service().verify_dry_run(
+                    rpc_id,
+                    input,
+                    block_verifier_index,
+                    work_verifier_index,
+                    verifier_srs,
+                );
+            }
+        }
+    }
+}
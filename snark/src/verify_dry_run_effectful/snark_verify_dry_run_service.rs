@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use mina_core::requests::RpcId;
+
+use crate::{BlockVerifier, TransactionVerifier, VerifierSRS};
+
+use super::SnarkVerifyDryRunInput;
+
+pub trait SnarkVerifyDryRunService: redux::Service {
+    /// Runs [`super::verify_dry_run`] off the state machine thread, then
+    /// sends the report with [`crate::SnarkEvent::VerifyDryRun`].
+    fn verify_dry_run(
+        &mut self,
+        rpc_id: RpcId,
+        input: SnarkVerifyDryRunInput,
+        block_verifier_index: BlockVerifier,
+        work_verifier_index: TransactionVerifier,
+        verifier_srs: Arc<VerifierSRS>,
+    );
+}
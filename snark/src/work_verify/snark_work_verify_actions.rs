@@ -8,6 +8,14 @@ use super::{SnarkWorkVerifyError, SnarkWorkVerifyId};
 pub type SnarkWorkVerifyActionWithMeta = redux::ActionWithMeta<SnarkWorkVerifyAction>;
 pub type SnarkWorkVerifyActionWithMetaRef<'a> = redux::ActionWithMeta<&'a SnarkWorkVerifyAction>;
 
+// define this alias, or `build.rs` cannot parse the enum
+pub(super) type OnError = redux::Callback<(
+    SnarkWorkVerifyId,
+    String,
+    Vec<SnarkJobId>,
+    SnarkWorkVerifyError,
+)>;
+
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
 #[action_event(level = trace, fields(display(req_id), display(error)))]
 pub enum SnarkWorkVerifyAction {
@@ -17,7 +25,7 @@ pub enum SnarkWorkVerifyAction {
         batch: Vec<Snark>,
         sender: String,
         on_success: redux::Callback<(SnarkWorkVerifyId, String, Vec<Snark>)>,
-        on_error: redux::Callback<(SnarkWorkVerifyId, String, Vec<SnarkJobId>)>,
+        on_error: OnError,
     },
     Pending {
         req_id: SnarkWorkVerifyId,
@@ -30,13 +38,19 @@ pub enum SnarkWorkVerifyAction {
     Success {
         req_id: SnarkWorkVerifyId,
     },
+    /// The verifier didn't answer in time, the request is sent again or
+    /// fails with [`SnarkWorkVerifyError::Timeout`].
+    #[action_event(level = warn)]
+    Timeout {
+        req_id: SnarkWorkVerifyId,
+    },
     Finish {
         req_id: SnarkWorkVerifyId,
     },
 }
 
 impl redux::EnablingCondition<crate::SnarkState> for SnarkWorkVerifyAction {
-    fn is_enabled(&self, state: &crate::SnarkState, _time: redux::Timestamp) -> bool {
+    fn is_enabled(&self, state: &crate::SnarkState, time: redux::Timestamp) -> bool {
         match self {
             SnarkWorkVerifyAction::Init { req_id, batch, .. } => {
                 !batch.is_empty() && state.work_verify.jobs.next_req_id() == *req_id
@@ -52,6 +66,9 @@ impl redux::EnablingCondition<crate::SnarkState> for SnarkWorkVerifyAction {
                 .jobs
                 .get(*req_id)
                 .is_some_and(|v| v.is_pending()),
+            SnarkWorkVerifyAction::Timeout { req_id } => {
+                state.work_verify.is_timed_out(*req_id, time)
+            }
             SnarkWorkVerifyAction::Finish { req_id } => state
                 .work_verify
                 .jobs
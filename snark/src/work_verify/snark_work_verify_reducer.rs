@@ -1,11 +1,14 @@
 use mina_core::{bug_condition, snark::Snark, Substate, SubstateAccess};
 use redux::EnablingCondition;
 
-use crate::work_verify_effectful::SnarkWorkVerifyEffectfulAction;
+use crate::{
+    work_verify_effectful::SnarkWorkVerifyEffectfulAction, SnarkVerifyCacheKey, SnarkVerifyJobKind,
+    SnarkVerifyTiming,
+};
 
 use super::{
-    SnarkWorkVerifyAction, SnarkWorkVerifyActionWithMetaRef, SnarkWorkVerifyState,
-    SnarkWorkVerifyStatus,
+    SnarkWorkVerifyAction, SnarkWorkVerifyActionWithMetaRef, SnarkWorkVerifyError,
+    SnarkWorkVerifyState, SnarkWorkVerifyStatus,
 };
 
 pub fn reducer<State, Action>(
@@ -40,6 +43,34 @@ pub fn reducer<State, Action>(
                 on_success: on_success.clone(),
             });
 
+            // Works received again, e.g. when the pools are rebroadcast,
+            // aren't sent to the verifier when all their results are cached.
+            let keys = batch
+                .iter()
+                .map(SnarkVerifyCacheKey::work)
+                .collect::<Vec<_>>();
+            let cached = keys.iter().all(|key| state.cache.contains(key)).then(|| {
+                keys.iter()
+                    .enumerate()
+                    .filter_map(|(index, key)| {
+                        let result = state.cache.get(key).cloned()?;
+                        Some(result.map_err(|error| error.with_work_index(index)))
+                    })
+                    .collect::<Result<(), _>>()
+            });
+            if let Some(result) = cached {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(SnarkWorkVerifyAction::Pending { req_id: *req_id });
+                match result {
+                    Ok(()) => dispatcher.push(SnarkWorkVerifyAction::Success { req_id: *req_id }),
+                    Err(error) => dispatcher.push(SnarkWorkVerifyAction::Error {
+                        req_id: *req_id,
+                        error,
+                    }),
+                }
+                return;
+            }
+
             // Dispatch
             let verifier_index = state.verifier_index.clone();
             let verifier_srs = state.verifier_srs.clone();
@@ -56,17 +87,19 @@ pub fn reducer<State, Action>(
             if let Some(req) = state.jobs.get_mut(*req_id) {
                 *req = match req {
                     SnarkWorkVerifyStatus::Init {
+                        time,
                         batch,
                         sender,
                         on_error,
                         on_success,
-                        ..
                     } => SnarkWorkVerifyStatus::Pending {
                         time: meta.time(),
+                        timing: SnarkVerifyTiming::queued(*time).started(meta.time()),
                         batch: std::mem::take(batch),
                         sender: std::mem::take(sender),
                         on_error: on_error.clone(),
                         on_success: on_success.clone(),
+                        retries: 0,
                     },
                     _ => return,
                 };
@@ -84,6 +117,7 @@ pub fn reducer<State, Action>(
                 batch,
                 sender,
                 on_error,
+                timing,
                 ..
             } = req
             else {
@@ -96,15 +130,32 @@ pub fn reducer<State, Action>(
             let sender = std::mem::take(sender);
             let batch = std::mem::take(batch);
             let job_ids = batch.iter().map(Snark::job_id).collect();
+            // Only the invalid work is cached, the others of the batch may
+            // be valid.
+            let invalid_work = match (error.work_index(), batch.as_slice()) {
+                (Some(index), _) => batch.get(index),
+                (None, [work]) if error.is_deterministic() => Some(work),
+                _ => None,
+            };
+            if let Some(work) = invalid_work {
+                let key = SnarkVerifyCacheKey::work(work);
+                state.cache.insert(key, Err(error.clone()));
+            }
+            let timing = timing.finished(meta.time());
+            state.stats.add(&timing, false, batch.len());
+            state
+                .history
+                .push(SnarkVerifyJobKind::Work, &timing, false, batch.len());
             *req = SnarkWorkVerifyStatus::Error {
                 time: meta.time(),
+                timing,
                 batch,
                 sender: sender.clone(),
                 error: error.clone(),
             };
             // Dispatch
             let dispatcher = state_context.into_dispatcher();
-            dispatcher.push_callback(callback, (*req_id, sender, job_ids));
+            dispatcher.push_callback(callback, (*req_id, sender, job_ids, error.clone()));
             dispatcher.push(SnarkWorkVerifyAction::Finish { req_id: *req_id });
         }
         SnarkWorkVerifyAction::Success { req_id } => {
@@ -119,6 +170,7 @@ pub fn reducer<State, Action>(
                 batch,
                 sender,
                 on_success,
+                timing,
                 ..
             } = req
             else {
@@ -131,9 +183,18 @@ pub fn reducer<State, Action>(
             let callback = on_success.clone();
             let sender = std::mem::take(sender);
             let batch = std::mem::take(batch);
+            for work in &batch {
+                state.cache.insert(SnarkVerifyCacheKey::work(work), Ok(()));
+            }
 
+            let timing = timing.finished(meta.time());
+            state.stats.add(&timing, true, batch.len());
+            state
+                .history
+                .push(SnarkVerifyJobKind::Work, &timing, true, batch.len());
             *req = SnarkWorkVerifyStatus::Success {
                 time: meta.time(),
+                timing,
                 batch: batch.clone(),
                 sender: sender.clone(),
             };
@@ -143,6 +204,43 @@ pub fn reducer<State, Action>(
             dispatcher.push_callback(callback, (*req_id, sender, batch));
             dispatcher.push(SnarkWorkVerifyAction::Finish { req_id: *req_id });
         }
+        SnarkWorkVerifyAction::Timeout { req_id } => {
+            let Some(timeout) = state.timeout else {
+                return;
+            };
+            let Some(SnarkWorkVerifyStatus::Pending {
+                time,
+                batch,
+                retries,
+                ..
+            }) = state.jobs.get_mut(*req_id)
+            else {
+                return;
+            };
+
+            if !timeout.can_retry(*retries) {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(SnarkWorkVerifyAction::Error {
+                    req_id: *req_id,
+                    error: SnarkWorkVerifyError::Timeout,
+                });
+                return;
+            }
+            *time = meta.time();
+            *retries += 1;
+            let batch = batch.clone();
+
+            // Dispatch
+            let verifier_index = state.verifier_index.clone();
+            let verifier_srs = state.verifier_srs.clone();
+            let dispatcher = state_context.into_dispatcher();
+            dispatcher.push(SnarkWorkVerifyEffectfulAction::Init {
+                req_id: *req_id,
+                batch,
+                verifier_index,
+                verifier_srs,
+            });
+        }
         SnarkWorkVerifyAction::Finish { req_id } => {
             state.jobs.remove(*req_id);
         }
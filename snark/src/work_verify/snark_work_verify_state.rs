@@ -3,11 +3,14 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 
 use mina_core::{
-    requests::PendingRequests,
-    snark::{Snark, SnarkJobId},
+    requests::{PendingRequests, RequestRetryPolicy},
+    snark::Snark,
 };
 
-use crate::{TransactionVerifier, VerifierSRS};
+use crate::{
+    SnarkVerifyCache, SnarkVerifyCacheConfig, SnarkVerifyHistory, SnarkVerifyLatencyStats,
+    SnarkVerifyTiming, TransactionVerifier, VerifierSRS,
+};
 
 use super::{SnarkWorkVerifyError, SnarkWorkVerifyId, SnarkWorkVerifyIdType};
 
@@ -16,20 +19,57 @@ pub struct SnarkWorkVerifyState {
     pub verifier_index: TransactionVerifier,
     pub verifier_srs: Arc<VerifierSRS>,
     pub jobs: PendingRequests<SnarkWorkVerifyIdType, SnarkWorkVerifyStatus>,
+    #[serde(default)]
+    pub timeout: Option<RequestRetryPolicy>,
+    #[serde(default)]
+    pub cache: SnarkVerifyCache<Result<(), SnarkWorkVerifyError>>,
+    #[serde(default)]
+    pub stats: SnarkVerifyLatencyStats,
+    #[serde(default)]
+    pub history: SnarkVerifyHistory,
 }
 
 impl SnarkWorkVerifyState {
-    pub fn new(verifier_index: TransactionVerifier, verifier_srs: Arc<VerifierSRS>) -> Self {
+    pub fn new(
+        verifier_index: TransactionVerifier,
+        verifier_srs: Arc<VerifierSRS>,
+        timeout: Option<RequestRetryPolicy>,
+        cache: SnarkVerifyCacheConfig,
+    ) -> Self {
         Self {
             verifier_index,
             verifier_srs,
             jobs: Default::default(),
+            timeout,
+            cache: SnarkVerifyCache::new(cache),
+            stats: Default::default(),
+            history: Default::default(),
         }
     }
 
     pub fn next_req_id(&self) -> SnarkWorkVerifyId {
         self.jobs.next_req_id()
     }
+
+    pub fn is_timed_out(&self, req_id: SnarkWorkVerifyId, now: redux::Timestamp) -> bool {
+        let Some(timeout) = &self.timeout else {
+            return false;
+        };
+        match self.jobs.get(req_id) {
+            Some(SnarkWorkVerifyStatus::Pending { time, .. }) => timeout.is_timed_out(*time, now),
+            _ => false,
+        }
+    }
+
+    pub fn timed_out_jobs(
+        &self,
+        now: redux::Timestamp,
+    ) -> impl Iterator<Item = SnarkWorkVerifyId> + '_ {
+        self.jobs
+            .iter()
+            .map(|(req_id, _)| req_id)
+            .filter(move |req_id| self.is_timed_out(*req_id, now))
+    }
 }
 
 impl std::fmt::Debug for SnarkWorkVerifyState {
@@ -39,6 +79,10 @@ impl std::fmt::Debug for SnarkWorkVerifyState {
             .field("verifier_index", &"<content too big>")
             .field("verifier_srs", &"<content too big>")
             .field("jobs", &self.jobs)
+            .field("timeout", &self.timeout)
+            .field("cache", &self.cache)
+            .field("stats", &self.stats)
+            .field("history", &self.history.iter().count())
             .finish()
     }
 }
@@ -52,23 +96,32 @@ pub enum SnarkWorkVerifyStatus {
         // `PeerId` here.
         sender: String,
         on_success: redux::Callback<(SnarkWorkVerifyId, String, Vec<Snark>)>,
-        on_error: redux::Callback<(SnarkWorkVerifyId, String, Vec<SnarkJobId>)>,
+        on_error: super::OnError,
     },
     Pending {
         time: redux::Timestamp,
+        #[serde(default)]
+        timing: SnarkVerifyTiming,
         batch: Vec<Snark>,
         sender: String,
         on_success: redux::Callback<(SnarkWorkVerifyId, String, Vec<Snark>)>,
-        on_error: redux::Callback<(SnarkWorkVerifyId, String, Vec<SnarkJobId>)>,
+        on_error: super::OnError,
+        /// Times the request was sent again after timing out.
+        #[serde(default)]
+        retries: u8,
     },
     Error {
         time: redux::Timestamp,
+        #[serde(default)]
+        timing: SnarkVerifyTiming,
         batch: Vec<Snark>,
         sender: String,
         error: SnarkWorkVerifyError,
     },
     Success {
         time: redux::Timestamp,
+        #[serde(default)]
+        timing: SnarkVerifyTiming,
         batch: Vec<Snark>,
         sender: String,
     },
@@ -87,6 +140,15 @@ impl SnarkWorkVerifyStatus {
         matches!(self, Self::Error { .. } | Self::Success { .. })
     }
 
+    pub fn timing(&self) -> SnarkVerifyTiming {
+        match self {
+            Self::Init { time, .. } => SnarkVerifyTiming::queued(*time),
+            Self::Pending { timing, .. } => *timing,
+            Self::Error { timing, .. } => *timing,
+            Self::Success { timing, .. } => *timing,
+        }
+    }
+
     pub fn batch(&self) -> &[Snark] {
         match self {
             Self::Init { batch, .. } => batch,
@@ -6,6 +6,7 @@ mod snark_work_verify_effects;
 mod snark_work_verify_service;
 pub use snark_work_verify_service::*;
 
+use mina_core::{ErrorCode, HasErrorCode};
 use serde::{Deserialize, Serialize};
 
 pub struct SnarkWorkVerifyIdType;
@@ -17,10 +18,61 @@ impl mina_core::requests::RequestIdType for SnarkWorkVerifyIdType {
 
 pub type SnarkWorkVerifyId = mina_core::requests::RequestId<SnarkWorkVerifyIdType>;
 
+/// Works are identified by their `index` in the verified batch.
 #[derive(Serialize, Deserialize, Debug, Clone, thiserror::Error)]
 pub enum SnarkWorkVerifyError {
+    /// The statement of a proof of the work can't be decoded.
+    #[error("work {index} has an invalid statement")]
+    InvalidStatement { index: usize },
+    #[error("work {index} has an invalid proof")]
+    InvalidProof { index: usize },
+    /// The batch failed verification, but none of its works alone did.
     #[error("verification failed")]
     VerificationFailed,
     #[error("validator thread crashed")]
     ValidatorThreadCrashed,
+    #[error("verifier didn't answer in time")]
+    Timeout,
+}
+
+impl SnarkWorkVerifyError {
+    /// Whether the works are invalid, so verifying them again would fail
+    /// the same way. Otherwise the verifier failed, which says nothing
+    /// about the works.
+    pub fn is_deterministic(&self) -> bool {
+        match self {
+            Self::InvalidStatement { .. }
+            | Self::InvalidProof { .. }
+            | Self::VerificationFailed => true,
+            Self::ValidatorThreadCrashed | Self::Timeout => false,
+        }
+    }
+
+    /// Index of the invalid work in the batch, if known.
+    pub fn work_index(&self) -> Option<usize> {
+        match self {
+            Self::InvalidStatement { index } | Self::InvalidProof { index } => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Same error for the work at `index` of another batch.
+    pub fn with_work_index(self, index: usize) -> Self {
+        match self {
+            Self::InvalidStatement { .. } => Self::InvalidStatement { index },
+            Self::InvalidProof { .. } => Self::InvalidProof { index },
+            error => error,
+        }
+    }
+}
+
+impl HasErrorCode for SnarkWorkVerifyError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidStatement { .. } => ErrorCode::InvalidStatement,
+            Self::InvalidProof { .. } | Self::VerificationFailed => ErrorCode::InvalidProof,
+            Self::ValidatorThreadCrashed => ErrorCode::VerifierCrashed,
+            Self::Timeout => ErrorCode::VerifierTimeout,
+        }
+    }
 }
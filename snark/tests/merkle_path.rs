@@ -0,0 +1,118 @@
+// Run this test with:
+// cargo test --package snark --test merkle_path
+
+#![cfg(not(target_family = "wasm"))]
+
+use ledger::{
+    scan_state::pending_coinbase::{PendingCoinbase, Stack},
+    Account, AccountIndex, BaseLedger, Direction, Mask, MerklePath, ToInputs,
+};
+use mina_curves::pasta::Fp;
+use poseidon::hash::params::COINBASE_STACK;
+use proptest::prelude::*;
+use snark::{MerklePathError, MerklePathVerifier};
+
+/// Ledger of `depth` with `naccounts` random accounts.
+fn ledger_with_accounts(depth: usize, naccounts: u64) -> Mask {
+    let mut ledger = Mask::create(depth);
+    for _ in 0..naccounts {
+        let account = Account::rand();
+        ledger.get_or_create_account(account.id(), account).unwrap();
+    }
+    ledger
+}
+
+fn split_path(path: &[MerklePath]) -> (Vec<Direction>, Vec<Fp>) {
+    path.iter()
+        .map(|node| match node {
+            MerklePath::Left(right) => (Direction::Left, *right),
+            MerklePath::Right(left) => (Direction::Right, *left),
+        })
+        .unzip()
+}
+
+/// Depth of the ledger, number of accounts and index of an account.
+fn ledger_params() -> impl Strategy<Value = (usize, u64, u64)> {
+    (1usize..=10)
+        .prop_flat_map(|depth| (Just(depth), 1..=(1u64 << depth).min(16)))
+        .prop_flat_map(|(depth, naccounts)| (Just(depth), Just(naccounts), 0..naccounts))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn ledger_paths_verify((depth, naccounts, index) in ledger_params()) {
+        let mut ledger = ledger_with_accounts(depth, naccounts);
+        let root_hash = ledger.merkle_root();
+        let leaf_hash = ledger.get_at_index(AccountIndex(index)).unwrap().hash();
+        let path = ledger.merkle_path_at_index(AccountIndex(index));
+        let (directions, siblings) = split_path(&path);
+
+        let verifier = MerklePathVerifier::ledger(depth).unwrap();
+        prop_assert_eq!(&directions, &verifier.directions_of_index(index));
+        prop_assert_eq!(verifier.root_hash_of_path(leaf_hash, &path), Ok(root_hash));
+        prop_assert_eq!(
+            verifier.verify(leaf_hash, &directions, &siblings, root_hash),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn tampered_paths_fail(
+        (depth, naccounts, index) in ledger_params(),
+        height in any::<prop::sample::Index>(),
+    ) {
+        let mut ledger = ledger_with_accounts(depth, naccounts);
+        let root_hash = ledger.merkle_root();
+        let leaf_hash = ledger.get_at_index(AccountIndex(index)).unwrap().hash();
+        let (mut directions, siblings) =
+            split_path(&ledger.merkle_path_at_index(AccountIndex(index)));
+        let verifier = MerklePathVerifier::ledger(depth).unwrap();
+
+        prop_assert!(matches!(
+            verifier.root_hash(leaf_hash, &directions[1..], &siblings),
+            Err(MerklePathError::InvalidLength { .. })
+        ));
+
+        let height = height.index(depth);
+        directions[height] = match directions[height] {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        };
+        prop_assert_eq!(
+            verifier.verify(leaf_hash, &directions, &siblings, root_hash),
+            Err(MerklePathError::RootMismatch)
+        );
+    }
+}
+
+#[test]
+fn pending_coinbase_paths_verify() {
+    const DEPTH: usize = 5;
+    let root_hash = PendingCoinbase::create(DEPTH).merkle_root();
+    let verifier = MerklePathVerifier::pending_coinbase(DEPTH).unwrap();
+
+    // All the stacks are empty, so the siblings are the empty subtrees.
+    let mut siblings = vec![Stack::empty().hash_with_param(&COINBASE_STACK)];
+    for height in 0..DEPTH - 1 {
+        let hash = siblings[height];
+        siblings.push(verifier.kind().hash_node(height, hash, hash));
+    }
+    for index in [0, 7, (1 << DEPTH) - 1] {
+        let directions = verifier.directions_of_index(index);
+        assert_eq!(
+            verifier.verify(siblings[0], &directions, &siblings, root_hash),
+            Ok(())
+        );
+    }
+}
+
+#[test]
+fn depth_too_large() {
+    assert_eq!(
+        MerklePathVerifier::pending_coinbase(7),
+        Err(MerklePathError::DepthTooLarge { depth: 7, max: 6 })
+    );
+    assert!(MerklePathVerifier::ledger(35).is_ok());
+}
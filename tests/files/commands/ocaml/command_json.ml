@@ -0,0 +1,24 @@
+(* Prints a zkApp command fixture of tests/files/zkapps with the OCaml node's
+   `User_command.to_yojson`, as a golden of ledger/tests/test_command_json.rs.
+
+   Copy this directory to src/app/command_json in a checkout of the OCaml node
+   (MinaProtocol/mina) and run from the root of this repository with:
+     dune exec --root <mina> src/app/command_json/command_json.exe -- \
+       tests/files/zkapps/valid_zkapp.bin \
+       > tests/files/commands/ocaml/valid_zkapp.json
+
+   The signed command goldens are taken from precomputed blocks instead, see
+   generate.sh. *)
+
+open Core_kernel
+open Mina_base
+
+let () =
+  let path = (Sys.get_argv ()).(1) in
+  let buf = In_channel.read_all path |> Bigstring.of_string in
+  let wire =
+    Zkapp_command.Stable.Latest.Wire.Stable.Latest.bin_read_t buf
+      ~pos_ref:(ref 0)
+  in
+  User_command.Zkapp_command (Zkapp_command.of_wire wire)
+  |> User_command.to_yojson |> Yojson.Safe.to_string |> print_endline
@@ -0,0 +1,40 @@
+#!/usr/bin/env bash
+# Writes the JSON goldens of ledger/tests/test_command_json.rs, as serialized
+# by the OCaml node.
+#
+# zkApp commands are printed by command_json.ml from the binprot fixtures of
+# tests/files/zkapps. Signed commands are taken from precomputed blocks
+# written by the OCaml node, whose commands are `User_command.to_yojson`.
+#
+# Run from the root of this repository with:
+#   MINA=<checkout of the OCaml node> tests/files/commands/ocaml/generate.sh \
+#     <precomputed blocks with the payments>...
+
+set -euo pipefail
+
+DIR=tests/files/commands/ocaml
+if [ $# -eq 0 ]; then
+  echo "usage: generate.sh <precomputed block>..." >&2
+  exit 1
+fi
+
+rm -rf "$MINA/src/app/command_json"
+cp -r "$DIR" "$MINA/src/app/command_json"
+for fixture in valid_zkapp with_sig_auth with_proof_auth; do
+  dune exec --root "$MINA" src/app/command_json/command_json.exe -- \
+    "tests/files/zkapps/$fixture.bin" > "$DIR/$fixture.json"
+done
+
+# <golden> <fee payer> <nonce>
+signed_command() {
+  jq -c --arg pk "$2" --arg nonce "$3" '
+    .data.staged_ledger_diff.diff[] | select(. != null) | .commands[].data
+    | select(.[0] == "Signed_command"
+        and .[1].payload.common.fee_payer_pk == $pk
+        and .[1].payload.common.nonce == $nonce)' "${BLOCKS[@]}" > "$DIR/$1.json"
+  test -s "$DIR/$1.json"
+}
+
+BLOCKS=("$@")
+signed_command payment B62qoTtn6hP2R1x5d4UQoJos9vjoNGxLhaQn5cSKneu25Q3wpmtPirT 49
+signed_command payment_with_memo B62qnEdPB1V5YPEcGaETb19naLJV6sWdveCZEjSLhcVyrPcPWHkGGax 122581
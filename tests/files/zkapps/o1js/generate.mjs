@@ -0,0 +1,20 @@
+// Serializes the zkApp command fixtures of this directory with o1js, the
+// way `Mina.sendTransaction` serializes the input of the `sendZkapp`
+// mutation. Decoding and encoding them with o1js keeps them in the format
+// o1js sends, with every field present.
+//
+// Run from this directory with:
+//   npm install --no-save o1js@1.8.0
+//   node generate.mjs
+
+import { readFileSync, writeFileSync } from 'node:fs';
+import { Types } from 'o1js';
+
+const fixtures = ['valid_zkapp.json', 'none_given.json'];
+
+for (const fixture of fixtures) {
+  const { zkappCommand } = JSON.parse(readFileSync(fixture, 'utf8'));
+  const command = Types.ZkappCommand.fromJSON(zkappCommand);
+  const json = { zkappCommand: Types.ZkappCommand.toJSON(command) };
+  writeFileSync(fixture, JSON.stringify(json, null, 2) + '\n');
+}
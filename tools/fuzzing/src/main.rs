@@ -79,9 +79,7 @@ pub mod transaction_fuzzer {
 
         #[coverage(off)]
         pub fn update_rust(&mut self) -> bool {
-            let rust_cov_stats = Stats::from_file_counters(&self.file_counters);
-            let coverage_increased = self.rust.is_none()
-                || rust_cov_stats.has_coverage_increased(self.rust.as_ref().unwrap());
+            let coverage_increased = self.update_rust_stats();
 
             if coverage_increased {
                 let llvm_dump = self.cov.dump();
@@ -91,6 +89,17 @@ pub mod transaction_fuzzer {
                 report_rust.write_files("rust".to_string());
             }
 
+            coverage_increased
+        }
+
+        /// Like [`Self::update_rust`], without saving a coverage report, so
+        /// that it can be called after every input.
+        #[coverage(off)]
+        pub fn update_rust_stats(&mut self) -> bool {
+            let rust_cov_stats = Stats::from_file_counters(&self.file_counters);
+            let coverage_increased = self.rust.is_none()
+                || rust_cov_stats.has_coverage_increased(self.rust.as_ref().unwrap());
+
             self.rust = Some(rust_cov_stats);
             coverage_increased
         }
@@ -421,6 +430,76 @@ pub mod transaction_fuzzer {
     }
 }
 
+#[cfg(feature = "nightly")]
+pub mod sync_rpc_fuzzer {
+    pub mod context;
+    pub mod mutator;
+    pub mod targets;
+    use crate::transaction_fuzzer::CoverageStats;
+    use context::{SyncRpcFuzzerCtx, SyncRpcTarget};
+    use std::{
+        io::Write,
+        panic::{self, AssertUnwindSafe},
+        path::Path,
+    };
+
+    #[coverage(off)]
+    pub fn fuzz(target: SyncRpcTarget, seed: u64, corpus_path: &Path, fuzzcases_path: String) {
+        let mut cov_stats = CoverageStats::new();
+        let mut ctx = SyncRpcFuzzerCtx::new(target, seed, corpus_path, fuzzcases_path);
+
+        // Replaying the corpus catches regressions and sets the coverage
+        // that new inputs are compared against.
+        for input in ctx.corpus.clone() {
+            if panic::catch_unwind(AssertUnwindSafe(
+                #[coverage(off)]
+                || ctx.run(&input),
+            ))
+            .is_err()
+            {
+                println!("!!! PANIC detected on corpus input");
+                ctx.save_fuzzcase(&input);
+                std::process::exit(0);
+            }
+        }
+        cov_stats.update_rust();
+        cov_stats.print();
+
+        for iteration in 0.. {
+            print!("Iteration {}\r", iteration);
+            std::io::stdout().flush().unwrap();
+
+            let input = ctx.random_input();
+            match panic::catch_unwind(AssertUnwindSafe(
+                #[coverage(off)]
+                || ctx.run(&input),
+            )) {
+                Ok(_) => {
+                    if cov_stats.update_rust_stats() {
+                        ctx.add_to_corpus(input);
+                    }
+                }
+                Err(_) => {
+                    println!("!!! PANIC detected");
+                    ctx.save_fuzzcase(&input);
+                    std::process::exit(0);
+                }
+            }
+
+            if (iteration % 10000) == 0 {
+                cov_stats.print();
+            }
+        }
+    }
+
+    #[coverage(off)]
+    pub fn reproduce(target: SyncRpcTarget, corpus_path: &Path, fuzzcase: &String) {
+        let mut ctx = SyncRpcFuzzerCtx::new(target, 0, corpus_path, String::new());
+        let input = ctx.load_fuzzcase(fuzzcase);
+        println!("{:?}: {:?}", target, ctx.run(&input));
+    }
+}
+
 fn main() {
     #[cfg(feature = "nightly")]
     {
@@ -452,8 +531,42 @@ fn main() {
                     .default_value("true")
                     .value_parser(clap::value_parser!(bool)),
             )
+            .arg(
+                clap::Arg::new("sync-rpc")
+                    .long("sync-rpc")
+                    .value_name("TARGET")
+                    .help("Fuzz the validation of a sync RPC response instead of transactions")
+                    .value_parser(clap::value_parser!(sync_rpc_fuzzer::context::SyncRpcTarget)),
+            )
+            .arg(
+                clap::Arg::new("corpus")
+                    .long("corpus")
+                    .value_name("DIR")
+                    .default_value("corpus")
+                    .value_parser(clap::value_parser!(std::path::PathBuf)),
+            )
             .get_matches();
 
+        // Sync RPC targets don't compare with OCaml.
+        if let Some(target) = matches.get_one::<sync_rpc_fuzzer::context::SyncRpcTarget>("sync-rpc")
+        {
+            let corpus_path = matches.get_one::<std::path::PathBuf>("corpus").unwrap();
+            if let Some(fuzzcase) = matches.get_one::<String>("fuzzcase") {
+                println!("Reproducing fuzzcase from file: {}", fuzzcase);
+                sync_rpc_fuzzer::reproduce(*target, corpus_path, fuzzcase);
+            } else {
+                let seed = *matches.get_one::<u64>("seed").unwrap();
+                println!("Fuzzing [seed: {seed}] [sync rpc: {target:?}]...");
+                sync_rpc_fuzzer::fuzz(
+                    *target,
+                    seed,
+                    corpus_path,
+                    std::env::var("FUZZCASES_PATH").unwrap_or("/tmp/".to_string()),
+                );
+            }
+            return;
+        }
+
         let mut child = Command::new(
             std::env::var("OCAML_TRANSACTION_FUZZER_PATH").unwrap_or_else(
                 #[coverage(off)]
@@ -0,0 +1,244 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use ledger::{
+    dummy::for_tests::dummy_protocol_state,
+    scan_state::{pending_coinbase::PendingCoinbase, scan_state::ScanState},
+    staged_ledger::hash::StagedLedgerHash,
+    Account, BaseLedger, Database, Mask,
+};
+use mina_curves::pasta::Fp;
+use mina_p2p_messages::{
+    binprot::BinProtWrite,
+    list::List,
+    number::Number,
+    rpc::{
+        AnswerSyncLedgerQueryV2, GetStagedLedgerAuxAndPendingCoinbasesAtHashV2,
+        GetTransitionChainV2,
+    },
+    rpc_kernel::{NeedsLength, ResponsePayload, RpcMethod, RpcResult},
+    v2::{
+        LedgerHash, MinaBaseAccountBinableArgStableV2, MinaBaseStagedLedgerHashStableV1,
+        MinaLedgerSyncLedgerAnswerStableV2, StateHash,
+    },
+};
+use node::ledger::{ledger_depth, LedgerAddress, LedgerCtx};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use super::{mutator::mutate, targets};
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SyncRpcTarget {
+    AnswerSyncLedgerQuery,
+    StagedLedgerAuxAndPendingCoinbases,
+    TransitionChain,
+}
+
+impl SyncRpcTarget {
+    #[coverage(off)]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::AnswerSyncLedgerQuery => "answer-sync-ledger-query",
+            Self::StagedLedgerAuxAndPendingCoinbases => "staged-ledger-aux-and-pending-coinbases",
+            Self::TransitionChain => "transition-chain",
+        }
+    }
+}
+
+#[coverage(off)]
+fn encode<M: RpcMethod>(response: M::Response) -> Vec<u8>
+where
+    M::Response: BinProtWrite,
+{
+    let payload: ResponsePayload<M::Response> = RpcResult(Ok(NeedsLength(response)));
+    let mut bytes = vec![];
+    payload.binprot_write(&mut bytes).unwrap();
+    bytes
+}
+
+/// Fuzzing state of a sync RPC target.
+///
+/// The corpus holds one raw response per file, in a directory per target.
+/// It starts from valid responses, and inputs that increase the coverage
+/// are added to it.
+pub struct SyncRpcFuzzerCtx {
+    pub target: SyncRpcTarget,
+    pub rng: SmallRng,
+    pub corpus: Vec<Vec<u8>>,
+    corpus_path: PathBuf,
+    fuzzcases_path: String,
+    /// Ledger service state, with the snarked ledger being synced that
+    /// `answer-sync-ledger-query` inputs answer for.
+    ledger: LedgerCtx,
+    genesis_ledger_hash: LedgerHash,
+    snarked_ledger_hash: LedgerHash,
+    addr: LedgerAddress,
+    staged_ledger_hash: MinaBaseStagedLedgerHashStableV1,
+    requested: Vec<StateHash>,
+}
+
+impl SyncRpcFuzzerCtx {
+    #[coverage(off)]
+    pub fn new(
+        target: SyncRpcTarget,
+        seed: u64,
+        corpus_path: &Path,
+        fuzzcases_path: String,
+    ) -> Self {
+        let constraint_constants = mina_core::constants::constraint_constants();
+        let scan_state = ScanState::empty(constraint_constants);
+        let mut pending_coinbase =
+            PendingCoinbase::create(constraint_constants.pending_coinbase_depth);
+        let ledger_hash = Fp::from(3);
+        let staged_ledger_hash = (&StagedLedgerHash::of_aux_ledger_and_coinbase_hash(
+            scan_state.hash(),
+            ledger_hash,
+            &mut pending_coinbase,
+        ))
+            .into();
+
+        let mut ledger = LedgerCtx::default();
+        let mut genesis_ledger = Mask::new_root(Database::create(ledger_depth() as u8));
+        let genesis_ledger_hash = LedgerHash::from_fp(genesis_ledger.merkle_root());
+        ledger.insert_genesis_ledger(genesis_ledger);
+
+        let mut ctx = Self {
+            target,
+            rng: SmallRng::seed_from_u64(seed),
+            corpus: Vec::new(),
+            corpus_path: corpus_path.join(target.name()),
+            fuzzcases_path,
+            ledger,
+            genesis_ledger_hash,
+            snarked_ledger_hash: LedgerHash::from_fp(Fp::from(5)),
+            // Subtree of 4 accounts, like the `ContentsAre` answers the node
+            // requests.
+            addr: LedgerAddress::first(ledger_depth() - 2),
+            staged_ledger_hash,
+            requested: vec![StateHash::from_fp(Fp::from(1))],
+        };
+        ctx.load_corpus(|| match target {
+            SyncRpcTarget::AnswerSyncLedgerQuery => {
+                let hash = LedgerHash::from_fp(Fp::from(7));
+                let accounts = (0..4)
+                    .map(
+                        #[coverage(off)]
+                        |_| MinaBaseAccountBinableArgStableV2::from(&Account::rand()),
+                    )
+                    .collect::<List<_>>();
+                [
+                    MinaLedgerSyncLedgerAnswerStableV2::ChildHashesAre(hash.clone(), hash.clone()),
+                    MinaLedgerSyncLedgerAnswerStableV2::ContentsAre(accounts),
+                    MinaLedgerSyncLedgerAnswerStableV2::NumAccounts(Number(4), hash),
+                ]
+                .map(
+                    #[coverage(off)]
+                    |answer| encode::<AnswerSyncLedgerQueryV2>(RpcResult(Ok(answer))),
+                )
+                .into()
+            }
+            SyncRpcTarget::StagedLedgerAuxAndPendingCoinbases => vec![
+                encode::<GetStagedLedgerAuxAndPendingCoinbasesAtHashV2>(Some((
+                    (&scan_state).into(),
+                    ledger_hash.into(),
+                    (&pending_coinbase).into(),
+                    [dummy_protocol_state()].into_iter().collect(),
+                ))),
+                encode::<GetStagedLedgerAuxAndPendingCoinbasesAtHashV2>(None),
+            ],
+            SyncRpcTarget::TransitionChain => vec![
+                encode::<GetTransitionChainV2>(Some(List::new())),
+                encode::<GetTransitionChainV2>(None),
+            ],
+        });
+        ctx
+    }
+
+    /// Loads the corpus of the target, or creates it with the valid
+    /// responses returned by `seeds` if it doesn't exist yet.
+    #[coverage(off)]
+    fn load_corpus(&mut self, seeds: impl FnOnce() -> Vec<Vec<u8>>) {
+        if self.corpus_path.exists() {
+            println!("Loading corpus: {}", self.corpus_path.display());
+            for entry in fs::read_dir(&self.corpus_path).unwrap() {
+                self.corpus.push(fs::read(entry.unwrap().path()).unwrap());
+            }
+            return;
+        }
+
+        fs::create_dir_all(&self.corpus_path).unwrap();
+        for seed in seeds() {
+            if let Err(error) = self.run(&seed) {
+                panic!("seed must be valid: {error}");
+            }
+            self.add_to_corpus(seed);
+        }
+    }
+
+    #[coverage(off)]
+    pub fn add_to_corpus(&mut self, input: Vec<u8>) {
+        let filename = self.corpus_path.join(format!("{:x}", md5::compute(&input)));
+        fs::write(filename, &input).unwrap();
+        self.corpus.push(input);
+    }
+
+    /// Mutated input from the corpus.
+    #[coverage(off)]
+    pub fn random_input(&mut self) -> Vec<u8> {
+        let index = self.rng.gen_range(0..self.corpus.len());
+        mutate(&mut self.rng, &self.corpus[index])
+    }
+
+    /// Runs the target. Errors are the expected outcome for malformed input,
+    /// only panics are bugs.
+    #[coverage(off)]
+    pub fn run(&mut self, input: &[u8]) -> Result<String, targets::SyncRpcFuzzError> {
+        match self.target {
+            SyncRpcTarget::AnswerSyncLedgerQuery => {
+                // Each input answers for the same empty ledger.
+                self.ledger
+                    .copy_snarked_ledger_contents_for_sync(
+                        self.genesis_ledger_hash.clone(),
+                        self.snarked_ledger_hash.clone(),
+                        true,
+                    )
+                    .unwrap();
+                targets::answer_sync_ledger_query(
+                    &mut self.ledger,
+                    &self.snarked_ledger_hash,
+                    input,
+                    &self.addr,
+                )
+                .map(|outcome| format!("{outcome:?}"))
+            }
+            SyncRpcTarget::StagedLedgerAuxAndPendingCoinbases => {
+                targets::staged_ledger_aux_and_pending_coinbases(input, &self.staged_ledger_hash)
+                    .map(|outcome| format!("{outcome:?}"))
+            }
+            SyncRpcTarget::TransitionChain => targets::transition_chain(input, &self.requested)
+                .map(|hashes| format!("{hashes:?}")),
+        }
+    }
+
+    #[coverage(off)]
+    pub fn save_fuzzcase(&self, input: &[u8]) {
+        let filename = format!(
+            "{}{}-{:x}.fuzzcase",
+            self.fuzzcases_path,
+            self.target.name(),
+            md5::compute(input)
+        );
+
+        println!("Saving fuzzcase: {}", filename);
+        fs::write(filename, input).unwrap();
+    }
+
+    #[coverage(off)]
+    pub fn load_fuzzcase(&self, file_path: &String) -> Vec<u8> {
+        println!("Loading fuzzcase: {}", file_path);
+        fs::read(file_path).unwrap()
+    }
+}
@@ -0,0 +1,28 @@
+use rand::{rngs::SmallRng, Rng};
+
+/// Returns a copy of `input` with random bits flipped, truncated, or with
+/// random bytes inserted.
+#[coverage(off)]
+pub fn mutate(rng: &mut SmallRng, input: &[u8]) -> Vec<u8> {
+    let mut data = input.to_vec();
+    match rng.gen_range(0..3) {
+        0 if !data.is_empty() => {
+            for _ in 0..rng.gen_range(1..8) {
+                let index = rng.gen_range(0..data.len());
+                data[index] ^= 1 << rng.gen_range(0..8);
+            }
+        }
+        1 if !data.is_empty() => data.truncate(rng.gen_range(0..data.len())),
+        _ => {
+            let index = rng.gen_range(0..=data.len());
+            let extra: Vec<u8> = (0..rng.gen_range(1..64))
+                .map(
+                    #[coverage(off)]
+                    |_| rng.gen(),
+                )
+                .collect();
+            data.splice(index..index, extra);
+        }
+    }
+    data
+}
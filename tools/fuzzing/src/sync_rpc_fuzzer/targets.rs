@@ -0,0 +1,148 @@
+//! Fuzz targets for peer-provided data received as responses to sync RPCs.
+//!
+//! Each target decodes raw response bytes the same way the p2p layer does and
+//! passes the data to the functions the node validates it with. Malformed
+//! input must result in a [`SyncRpcFuzzError`], never in a panic.
+
+use std::sync::Arc;
+
+use mina_curves::pasta::Fp;
+use mina_p2p_messages::{
+    rpc::{
+        AnswerSyncLedgerQueryV2, GetStagedLedgerAuxAndPendingCoinbasesAtHashV2,
+        GetTransitionChainV2,
+    },
+    rpc_kernel::PayloadBinprotReader,
+    v2::{
+        self, LedgerHash, MinaBaseStagedLedgerHashStableV1, MinaLedgerSyncLedgerAnswerStableV2,
+        StateHash,
+    },
+};
+use node::{
+    ledger::{LedgerAddress, LedgerCtx},
+    p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases,
+    transition_frontier::sync::ledger::staged::StagedLedgerAuxAndPendingCoinbasesValidated,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SyncRpcFuzzError {
+    #[error("failed to decode response: {0}")]
+    Decode(String),
+    #[error("response rejected: {0}")]
+    Rejected(String),
+}
+
+fn rejected(error: impl ToString) -> SyncRpcFuzzError {
+    SyncRpcFuzzError::Rejected(error.to_string())
+}
+
+/// Outcome of a successfully validated ledger query answer.
+#[derive(Debug, PartialEq)]
+pub enum LedgerQueryOutcome {
+    ChildHashes(Fp, Fp),
+    /// Hash of the subtree at the queried address, after setting the accounts.
+    Contents(LedgerHash),
+    NumAccounts(u64, Fp),
+}
+
+/// Answer to `answer_sync_ledger_query`, for a query on the address `addr`
+/// of the snarked ledger `snarked_ledger_hash` being synced in `ledger`.
+///
+/// The accounts of the answer are set with [`LedgerCtx::accounts_set`], as
+/// the node does.
+pub fn answer_sync_ledger_query(
+    ledger: &mut LedgerCtx,
+    snarked_ledger_hash: &LedgerHash,
+    bytes: &[u8],
+    addr: &LedgerAddress,
+) -> Result<LedgerQueryOutcome, SyncRpcFuzzError> {
+    let mut bytes = bytes;
+    let answer = AnswerSyncLedgerQueryV2::response_payload(&mut bytes)
+        .map_err(|e| SyncRpcFuzzError::Decode(e.to_string()))?;
+    let answer = Result::from(answer).map_err(rejected)?;
+
+    match answer {
+        MinaLedgerSyncLedgerAnswerStableV2::ChildHashesAre(left, right) => {
+            Ok(LedgerQueryOutcome::ChildHashes(
+                left.to_field().map_err(rejected)?,
+                right.to_field().map_err(rejected)?,
+            ))
+        }
+        MinaLedgerSyncLedgerAnswerStableV2::ContentsAre(accounts) => {
+            let hash = ledger
+                .accounts_set(
+                    snarked_ledger_hash.clone(),
+                    addr,
+                    accounts.into_iter().collect(),
+                )
+                .map_err(rejected)?;
+            Ok(LedgerQueryOutcome::Contents(hash))
+        }
+        MinaLedgerSyncLedgerAnswerStableV2::NumAccounts(count, contents_hash) => {
+            let count = count.as_u64();
+            let depth = node::ledger::ledger_depth();
+            if count > 1u64 << depth {
+                return Err(rejected("more accounts than the ledger can hold"));
+            }
+            Ok(LedgerQueryOutcome::NumAccounts(
+                count,
+                contents_hash.to_field().map_err(rejected)?,
+            ))
+        }
+    }
+}
+
+/// Answer to `get_staged_ledger_aux_and_pending_coinbases_at_hash`, validated
+/// with [`StagedLedgerAuxAndPendingCoinbasesValidated::validate`] against the
+/// staged ledger hash of the block it was requested for.
+///
+/// Returns `Ok(None)` when the peer didn't have the data.
+pub fn staged_ledger_aux_and_pending_coinbases(
+    bytes: &[u8],
+    expected_hash: &MinaBaseStagedLedgerHashStableV1,
+) -> Result<Option<()>, SyncRpcFuzzError> {
+    let mut bytes = bytes;
+    let response = GetStagedLedgerAuxAndPendingCoinbasesAtHashV2::response_payload(&mut bytes)
+        .map_err(|e| SyncRpcFuzzError::Decode(e.to_string()))?;
+    let Some((scan_state, hash, pending_coinbase, needed_blocks)) = response else {
+        return Ok(None);
+    };
+    // Same conversion as the p2p layer's.
+    let parts = Arc::new(StagedLedgerAuxAndPendingCoinbases {
+        scan_state,
+        staged_ledger_hash: v2::MinaBaseLedgerHash0StableV1(hash).into(),
+        pending_coinbase,
+        needed_blocks,
+    });
+
+    match StagedLedgerAuxAndPendingCoinbasesValidated::validate(&parts, expected_hash) {
+        StagedLedgerAuxAndPendingCoinbasesValidated::Valid(_) => Ok(Some(())),
+        StagedLedgerAuxAndPendingCoinbasesValidated::Invalid(_) => {
+            Err(rejected("staged ledger hash mismatch"))
+        }
+    }
+}
+
+/// Answer to `get_transition_chain`, requested for the blocks `requested`.
+///
+/// Returns the hashes of the received blocks.
+pub fn transition_chain(
+    bytes: &[u8],
+    requested: &[StateHash],
+) -> Result<Vec<StateHash>, SyncRpcFuzzError> {
+    let mut bytes = bytes;
+    let response = GetTransitionChainV2::response_payload(&mut bytes)
+        .map_err(|e| SyncRpcFuzzError::Decode(e.to_string()))?;
+
+    response
+        .iter()
+        .flatten()
+        .map(|block| {
+            let hash = block.try_hash().map_err(rejected)?;
+            if !requested.contains(&hash) {
+                return Err(rejected(format!("unexpected block {hash}")));
+            }
+            Ok(hash)
+        })
+        .collect()
+}
@@ -564,7 +564,7 @@ impl FuzzerCtx {
             )
             .collect::<BTreeMap<_, _>>();
 
-        let rust_pool_result = self.pool.prevalidate(diff);
+        let rust_pool_result = self.pool.prevalidate(diff, &self.constraint_constants);
         let mismatch;
 
         if let Ok(diff) = rust_pool_result {
@@ -889,6 +889,9 @@ impl FuzzerCtxBuilder {
                 trust_system: (),
                 pool_max_size: 3000,
                 slot_tx_end: None,
+                max_lifetime: None,
+                zkapp_relay: Default::default(),
+                replace_fee: ledger::transaction_pool::DEFAULT_REPLACE_FEE,
             },
             &ConsensusConstants::create(&constraint_constants, &protocol_constants),
         );
@@ -4,7 +4,7 @@ use ledger::AccountIndex;
 use message::VrfMessage;
 use mina_node_account::AccountPublicKey;
 use mina_p2p_messages::v2::EpochSeed;
-use num::{rational::Ratio, BigInt, ToPrimitive};
+use num::{rational::Ratio, BigInt, ToPrimitive, Zero};
 use output::VrfOutput;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -71,6 +71,10 @@ pub struct VrfWonSlot {
     pub account_index: AccountIndex,
     pub vrf_output: Box<VrfOutput>,
     pub value_with_threshold: Option<(f64, f64)>,
+    /// Stake delegated to the winner account relative to the total
+    /// currency of the staking ledger, from which the threshold is derived.
+    #[serde(default)]
+    pub stake_fraction: Option<f64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -128,6 +132,23 @@ fn calculate_vrf(
 
 /// Evaluate vrf with a specific input. Used by the block producer
 pub fn evaluate_vrf(vrf_input: VrfEvaluationInput) -> VrfResult<VrfEvaluationOutput> {
+    let global_slot = vrf_input.global_slot;
+    let (won_slot, threshold_met) = evaluate_vrf_with_threshold(vrf_input)?;
+    if threshold_met {
+        Ok(VrfEvaluationOutput::SlotWon(won_slot))
+    } else {
+        Ok(VrfEvaluationOutput::SlotLost(global_slot))
+    }
+}
+
+/// Evaluates the vrf like [`evaluate_vrf`], but counts the slot as won
+/// whatever the stake of the delegator. Only meant for local development
+/// chains, where no other node checks the threshold.
+pub fn evaluate_vrf_always_won(vrf_input: VrfEvaluationInput) -> VrfResult<VrfWonSlot> {
+    evaluate_vrf_with_threshold(vrf_input).map(|(won_slot, _)| won_slot)
+}
+
+fn evaluate_vrf_with_threshold(vrf_input: VrfEvaluationInput) -> VrfResult<(VrfWonSlot, bool)> {
     let VrfEvaluationInput {
         producer_key,
         global_slot,
@@ -141,25 +162,29 @@ pub fn evaluate_vrf(vrf_input: VrfEvaluationInput) -> VrfResult<VrfEvaluationOut
     let vrf_output = calculate_vrf(&producer_key, epoch_seed, global_slot, &delegator_index)?;
 
     let value = vrf_output.truncated().into_bigint();
+    let stake_fraction = (!total_currency.is_zero())
+        .then(|| {
+            BigRational2048::new(delegated_stake.to_nlimbs(), total_currency.to_nlimbs()).to_f64()
+        })
+        .flatten();
     let threshold = Threshold::new(delegated_stake, total_currency);
+    let threshold_met = threshold.threshold_met(value);
 
-    if threshold.threshold_met(value) {
-        Ok(VrfEvaluationOutput::SlotWon(VrfWonSlot {
-            producer: producer_key.public.into(),
-            vrf_output: Box::new(vrf_output),
-            winner_account: account_pub_key,
-            global_slot,
-            account_index: delegator_index,
-            value_with_threshold: None.or_else(|| {
-                Some((
-                    self::threshold::get_fractional(value).to_f64()?,
-                    threshold.threshold_rational.to_f64()?,
-                ))
-            }),
-        }))
-    } else {
-        Ok(VrfEvaluationOutput::SlotLost(global_slot))
-    }
+    let won_slot = VrfWonSlot {
+        producer: producer_key.public.into(),
+        vrf_output: Box::new(vrf_output),
+        winner_account: account_pub_key,
+        global_slot,
+        account_index: delegator_index,
+        value_with_threshold: None.or_else(|| {
+            Some((
+                self::threshold::get_fractional(value).to_f64()?,
+                threshold.threshold_rational.to_f64()?,
+            ))
+        }),
+        stake_fraction,
+    };
+    Ok((won_slot, threshold_met))
 }
 
 pub fn keypair_from_bs58_string(str: &str) -> Keypair {
@@ -185,7 +210,7 @@ mod test {
 
     use crate::{genesis_vrf, keypair_from_bs58_string, VrfEvaluationInput, VrfEvaluationOutput};
 
-    use super::evaluate_vrf;
+    use super::{evaluate_vrf, evaluate_vrf_always_won};
 
     #[test]
     fn test_genesis_vrf() {
@@ -216,7 +241,12 @@ mod test {
         assert_eq!(
             evaluation_result,
             VrfEvaluationOutput::SlotLost(vrf_input.global_slot)
-        )
+        );
+
+        let won_slot = evaluate_vrf_always_won(vrf_input.clone()).expect("Failed to evaluate vrf");
+        assert_eq!(won_slot.global_slot, vrf_input.global_slot);
+        let (value, threshold) = won_slot.value_with_threshold.expect("value with threshold");
+        assert!(value > threshold);
     }
 
     #[test]
@@ -243,6 +273,8 @@ mod test {
                 won_slot.vrf_output.to_string()
             );
             assert_eq!(0.16978997004532187, won_slot.vrf_output.fractional());
+            let stake_fraction = won_slot.stake_fraction.expect("stake fraction");
+            assert!((stake_fraction - 1.0 / 6.0).abs() < 1e-9);
             assert_eq!(
                 "B62qrztYfPinaKqpXaYGY6QJ3SSW2NNKs7SajBLF1iFNXW9BoALN2Aq",
                 won_slot.producer.to_string()
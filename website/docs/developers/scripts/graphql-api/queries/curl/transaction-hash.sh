@@ -0,0 +1,31 @@
+#!/bin/bash
+# Usage: $0 [GRAPHQL_ENDPOINT]
+# GRAPHQL_ENDPOINT: GraphQL endpoint URL (default: http://mina-rust-plain-1.gcp.o1test.net/graphql)
+
+GRAPHQL_ENDPOINT="${1:-http://mina-rust-plain-1.gcp.o1test.net/graphql}"
+
+# Replace with your own node endpoint: http://localhost:3000/graphql
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+# Read the query and create JSON payload using jq for proper escaping
+QUERY=$(< "$SCRIPT_DIR/../query/transaction-hash.graphql")
+
+# Example variables - replace with actual values
+VARIABLES='{
+  "payment": {
+    "from": "B62qmGtQ7kn6zbw4tAYomBJJri1gZSThfQZJaMG6eR3tyNP3RiCcEQZ",
+    "to": "B62qrPN5Y5yq8kGE3FbVKbGTdTAJNdtNtB5sNVpxyRwWGcDEhpMzc8g",
+    "amount": "1000000000",
+    "fee": "10000000",
+    "memo": "Test payment",
+    "nonce": "1"
+  },
+  "signature": {
+    "field": "...",
+    "scalar": "..."
+  }
+}'
+
+JSON_PAYLOAD=$(echo '{}' | jq --arg query "$QUERY" --argjson variables "$VARIABLES" '.query = $query | .variables = $variables')
+curl -s -X POST "$GRAPHQL_ENDPOINT" \
+  -H "Content-Type: application/json" \
+  -d "$JSON_PAYLOAD"
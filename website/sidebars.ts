@@ -92,6 +92,7 @@ const sidebars: SidebarsConfig = {
       items: [
         'developers/graphql-api',
         'developers/archive-database-queries',
+        'developers/embedding-a-node',
       ],
     },
     {
@@ -104,6 +105,7 @@ const sidebars: SidebarsConfig = {
         'developers/wallet/generate',
         'developers/wallet/send',
         'developers/wallet/status',
+        'developers/wallet/verify-receipt',
       ],
     },
     {
@@ -145,6 +147,7 @@ const sidebars: SidebarsConfig = {
         'developers/testing/network-connectivity',
         'developers/testing/ocaml-node-tests',
         'developers/testing/replayer',
+        'developers/testing/cluster-api',
       ],
     },
     {